@@ -0,0 +1,156 @@
+//! Benchmark: Trace Replay
+//!
+//! Reads back a 5k-event NDJSON trace (the `nika trace replay` / `trace
+//! show` path) and counts heap allocations per replay with a counting
+//! global allocator, to keep event deserialization cheap.
+//!
+//! Run: cargo bench --bench trace_replay
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use nika::event::{read_trace_events, Event, EventKind};
+use nika::util::compat::{FileKind, VersionStamp};
+use serde_json::json;
+
+/// System allocator that counts allocations
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const EVENTS: usize = 5_000;
+
+/// Events of one infer task calling an MCP tool, repeated
+fn task_events(i: usize) -> [EventKind; 10] {
+    let task_id: Arc<str> = Arc::from(format!("task_{}", i));
+    [
+        EventKind::BindingResolved {
+            task_id: Arc::clone(&task_id),
+            alias: "draft".into(),
+            source_task: Some(Arc::from(format!("task_{}", i.saturating_sub(1)))),
+            path: "draft.summary".into(),
+            source_event_id: None,
+            used_default: false,
+            lazy: false,
+        },
+        EventKind::TaskStarted {
+            task_id: Arc::clone(&task_id),
+            verb: "infer".into(),
+            inputs: json!({ "draft": "short" }),
+        },
+        EventKind::ProviderCalled {
+            task_id: Arc::clone(&task_id),
+            provider: "claude".into(),
+            model: "claude-sonnet-4-20250514".into(),
+            prompt_len: 512,
+        },
+        EventKind::McpInvoke {
+            task_id: Arc::clone(&task_id),
+            call_id: format!("call-{}", i).into(),
+            mcp_server: "novanet".into(),
+            tool: Some("novanet_describe".into()),
+            resource: None,
+            params: None,
+        },
+        EventKind::McpConnected {
+            server_name: "novanet".into(),
+        },
+        EventKind::ProviderResponded {
+            task_id: Arc::clone(&task_id),
+            request_id: None,
+            input_tokens: 512,
+            output_tokens: 128,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            ttft_ms: Some(300),
+            finish_reason: "end_turn".into(),
+            cost_usd: 0.0,
+        },
+        EventKind::ConcurrencyAdjusted {
+            provider: "claude".into(),
+            previous: 4,
+            limit: 5,
+        },
+        EventKind::ModelRouted {
+            task_id: Arc::clone(&task_id),
+            tier: "cheap".into(),
+            model: "claude-haiku-4".into(),
+            reason: "default".into(),
+        },
+        EventKind::AgentComplete {
+            task_id: Arc::clone(&task_id),
+            turns: 2,
+            stop_reason: "natural".into(),
+        },
+        EventKind::TaskCompleted {
+            task_id,
+            output: Arc::new(json!("ok")),
+            duration_ms: 42,
+        },
+    ]
+}
+
+/// Write a current-schema trace of `EVENTS` events, returning its file
+fn write_trace() -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let header = json!({ "nika_trace": VersionStamp::current(FileKind::Trace) });
+    writeln!(file, "{}", header).unwrap();
+    let events = (0..).flat_map(task_events).take(EVENTS);
+    for (id, kind) in events.enumerate() {
+        let event = Event {
+            id: id as u64,
+            timestamp_ms: id as u64,
+            kind,
+        };
+        writeln!(file, "{}", serde_json::to_string(&event).unwrap()).unwrap();
+    }
+    file.flush().unwrap();
+    file
+}
+
+fn bench_trace_replay(c: &mut Criterion) {
+    let trace = write_trace();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let events = read_trace_events(trace.path()).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    assert_eq!(events.len(), EVENTS);
+    eprintln!(
+        "trace_replay: {} allocations for {} events ({:.1} per event)",
+        allocations,
+        EVENTS,
+        allocations as f64 / EVENTS as f64
+    );
+
+    let mut group = c.benchmark_group("trace_replay");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    group.bench_function("read_5k_events", |b| {
+        b.iter(|| black_box(read_trace_events(black_box(trace.path())).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_trace_replay);
+criterion_main!(benches);
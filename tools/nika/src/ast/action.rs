@@ -6,6 +6,19 @@
 //! - `FetchParams`: HTTP request
 //! - `InvokeParams`: MCP tool call / resource read (v0.2)
 //! - `AgentParams`: Agentic execution with tool calling (v0.2)
+//! - `ReduceParams`: Array aggregation (v0.7)
+//! - `ApproveParams`: Human-in-the-loop approval (v0.7)
+//! - `EmbedParams` / `RecallParams`: Embeddings and vector search (v0.7)
+//! - `RetrieveParams`: Chunk and rank local files for a query (v0.7)
+//! - `ValidateParams`: Schema and rule checks on a binding (v0.7)
+//! - `TranscribeParams`: Speech to text (v0.7)
+//! - `ImportParams` / `ExportParams`: CSV and XLSX files in and out (v0.7)
+//! - `RowsParams`: Filter, group, sort and limit row arrays (v0.7)
+//! - `ScriptParams`: Sandboxed Rhai snippets (v0.7)
+//! - `ChunkParams`: Split long text for `for_each` fan-out (v0.7)
+//! - `DedupeParams`: Drop near-duplicate array items (v0.7)
+//! - `DetectLangParams` / `TranslateParams`: Language detection and translation (v0.7)
+//! - `WsParams`: WebSocket message collection (v0.7)
 //!
 //! ## Shorthand Syntax (v0.5.1)
 //!
@@ -25,7 +38,12 @@
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Deserializer};
 
-use crate::ast::{AgentParams, InvokeParams};
+use crate::ast::{
+    AgentParams, ApproveParams, ChunkParams, ContainerRuntime, DedupeParams, DetectLangParams,
+    EmbedParams, ExecContainer, ExecSandbox, ExportParams, FetchGraphql, FetchPaginate, FetchRetry,
+    ImportParams, InvokeParams, RecallParams, ReduceParams, RetrieveParams, RowsParams,
+    ScriptParams, TranscribeParams, TranslateParams, ValidateParams, WsParams,
+};
 
 /// Infer action - one-shot LLM call
 ///
@@ -37,6 +55,41 @@ pub struct InferParams {
     pub provider: Option<String>,
     /// Override model for this task
     pub model: Option<String>,
+    /// Maximum tokens to generate (v0.7)
+    pub max_tokens: Option<u32>,
+    /// Race a second request and keep the first success (v0.7)
+    pub hedge: Option<HedgeSpec>,
+    /// Image paths or URLs sent with the prompt (v0.7)
+    pub images: Vec<String>,
+}
+
+/// Second request raced against an infer call (v0.7)
+///
+/// The first successful response wins and the other request is cancelled.
+/// Tokens spent on the cancelled request are still reported (estimated
+/// from what it streamed before being dropped).
+///
+/// ```yaml
+/// infer:
+///   prompt: "Summarize {{use.doc}}"
+///   model: claude-sonnet-4
+///   hedge:
+///     provider: openai
+///     model: gpt-4o
+///     after_ms: 800   # only if the first call is still running by then
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HedgeSpec {
+    /// Provider of the second request (default: the task's)
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model of the second request (default: the task's)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Delay before firing the second request (default: fire both at once)
+    #[serde(default)]
+    pub after_ms: Option<u64>,
 }
 
 impl<'de> Deserialize<'de> for InferParams {
@@ -54,6 +107,12 @@ impl<'de> Deserialize<'de> for InferParams {
                 provider: Option<String>,
                 #[serde(default)]
                 model: Option<String>,
+                #[serde(default)]
+                max_tokens: Option<u32>,
+                #[serde(default)]
+                hedge: Option<HedgeSpec>,
+                #[serde(default)]
+                images: Vec<String>,
             },
         }
 
@@ -62,15 +121,24 @@ impl<'de> Deserialize<'de> for InferParams {
                 prompt,
                 provider: None,
                 model: None,
+                max_tokens: None,
+                hedge: None,
+                images: Vec::new(),
             }),
             InferParamsHelper::Full {
                 prompt,
                 provider,
                 model,
+                max_tokens,
+                hedge,
+                images,
             } => Ok(InferParams {
                 prompt,
                 provider,
                 model,
+                max_tokens,
+                hedge,
+                images,
             }),
         }
     }
@@ -78,10 +146,20 @@ impl<'de> Deserialize<'de> for InferParams {
 
 /// Exec action - shell command
 ///
-/// Supports shorthand: `exec: "command"` or full form `exec: { command: "..." }`
+/// Supports shorthand: `exec: "command"` or full form `exec: { command: "..." }`.
+/// `cmd:` is accepted for `command:`, `image:` runs it in a container and
+/// `host:` runs it over SSH on a host from `.nika/config.toml`.
 #[derive(Debug, Clone)]
 pub struct ExecParams {
     pub command: String,
+    /// Timeout in seconds (default 60s, v0.7)
+    pub timeout: Option<u64>,
+    /// Working dir, env allowlist and resource limits (v0.7)
+    pub sandbox: Option<ExecSandbox>,
+    /// Run in a container instead of the host shell (v0.7)
+    pub container: Option<ExecContainer>,
+    /// Run over SSH on a `[hosts]` entry instead of locally (v0.7)
+    pub host: Option<String>,
 }
 
 impl<'de> Deserialize<'de> for ExecParams {
@@ -93,12 +171,67 @@ impl<'de> Deserialize<'de> for ExecParams {
         #[serde(untagged)]
         enum ExecParamsHelper {
             Short(String),
-            Full { command: String },
+            Full {
+                #[serde(alias = "cmd")]
+                command: String,
+                #[serde(default)]
+                timeout: Option<u64>,
+                #[serde(default)]
+                sandbox: Option<ExecSandbox>,
+                #[serde(default)]
+                image: Option<String>,
+                #[serde(default)]
+                runtime: Option<ContainerRuntime>,
+                #[serde(default)]
+                artifacts: Option<String>,
+                #[serde(default)]
+                host: Option<String>,
+            },
         }
 
         match ExecParamsHelper::deserialize(deserializer)? {
-            ExecParamsHelper::Short(command) => Ok(ExecParams { command }),
-            ExecParamsHelper::Full { command } => Ok(ExecParams { command }),
+            ExecParamsHelper::Short(command) => Ok(ExecParams {
+                command,
+                timeout: None,
+                sandbox: None,
+                container: None,
+                host: None,
+            }),
+            ExecParamsHelper::Full {
+                command,
+                timeout,
+                sandbox,
+                image,
+                runtime,
+                artifacts,
+                host,
+            } => {
+                if host.is_some() && (image.is_some() || sandbox.is_some()) {
+                    return Err(serde::de::Error::custom(
+                        "exec: host can't be combined with image or sandbox",
+                    ));
+                }
+                let container = match image {
+                    Some(image) => Some(ExecContainer {
+                        image,
+                        runtime,
+                        artifacts,
+                    }),
+                    None if runtime.is_some() || artifacts.is_some() => {
+                        return Err(serde::de::Error::custom(
+                            "exec: runtime and artifacts need an image",
+                        ));
+                    }
+                    None => None,
+                };
+                Ok(ExecParams {
+                    command,
+                    timeout,
+                    sandbox,
+                    container,
+                    host,
+                })
+            }
         }
     }
 }
@@ -106,19 +239,33 @@ impl<'de> Deserialize<'de> for ExecParams {
 /// Fetch action - HTTP request
 #[derive(Debug, Clone, Deserialize)]
 pub struct FetchParams {
+    /// Request URL (empty when `graphql` gives the endpoint)
+    #[serde(default)]
     pub url: String,
     #[serde(default = "default_method")]
     pub method: String,
     #[serde(default)]
     pub headers: FxHashMap<String, String>,
     pub body: Option<String>,
+    /// `[auth.<name>]` profile from the user config (v0.7)
+    #[serde(default)]
+    pub auth: Option<String>,
+    /// Retries on 429/5xx (default: 2 with backoff, v0.7)
+    #[serde(default)]
+    pub retry: Option<FetchRetry>,
+    /// Follow next-page links into one array output (v0.7)
+    #[serde(default)]
+    pub paginate: Option<FetchPaginate>,
+    /// GraphQL request, instead of `url`/`method`/`body` (v0.7)
+    #[serde(default)]
+    pub graphql: Option<FetchGraphql>,
 }
 
 fn default_method() -> String {
     "GET".to_string()
 }
 
-/// The 5 task action types (v0.2)
+/// The 21 task action types (v0.2, reduce/approve/embed/recall/retrieve/validate/transcribe/import/export/rows/script/chunk/dedupe/detect_lang/translate/ws: v0.7)
 ///
 /// Each variant corresponds to a YAML verb:
 /// - `infer:` - LLM inference (one-shot)
@@ -126,6 +273,22 @@ fn default_method() -> String {
 /// - `fetch:` - HTTP request
 /// - `invoke:` - MCP tool call or resource read
 /// - `agent:` - Agentic execution with tool calling loop
+/// - `reduce:` - Combine an array binding into one output (v0.7)
+/// - `approve:` - Wait for a human decision (v0.7)
+/// - `embed:` - Embed text, optionally into the vector store (v0.7)
+/// - `recall:` - Similarity search in the vector store (v0.7)
+/// - `retrieve:` - Rank chunks of local files against a query (v0.7)
+/// - `validate:` - Check a binding against a schema and rules (v0.7)
+/// - `transcribe:` - Speech to text (v0.7)
+/// - `import:` - Read a CSV or XLSX file into rows (v0.7)
+/// - `export:` - Write rows to a CSV or XLSX file (v0.7)
+/// - `rows:` - Filter, group, sort and limit an array of rows (v0.7)
+/// - `script:` - Run a sandboxed Rhai snippet over the bindings (v0.7)
+/// - `chunk:` - Split long text into overlapping chunks (v0.7)
+/// - `dedupe:` - Drop near-duplicate items of an array (v0.7)
+/// - `detect_lang:` - Detect the language of text offline (v0.7)
+/// - `translate:` - Translate text with a glossary (v0.7)
+/// - `ws:` - Collect messages from a WebSocket endpoint (v0.7)
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum TaskAction {
@@ -134,10 +297,51 @@ pub enum TaskAction {
     Fetch { fetch: FetchParams },
     Invoke { invoke: InvokeParams },
     Agent { agent: AgentParams },
+    Reduce { reduce: ReduceParams },
+    Approve { approve: ApproveParams },
+    Embed { embed: EmbedParams },
+    Recall { recall: RecallParams },
+    Retrieve { retrieve: RetrieveParams },
+    Validate { validate: ValidateParams },
+    Transcribe { transcribe: TranscribeParams },
+    Import { import: ImportParams },
+    Export { export: ExportParams },
+    Rows { rows: RowsParams },
+    Script { script: ScriptParams },
+    Chunk { chunk: ChunkParams },
+    Dedupe { dedupe: DedupeParams },
+    DetectLang { detect_lang: DetectLangParams },
+    Translate { translate: TranslateParams },
+    Ws { ws: WsParams },
 }
 
 impl TaskAction {
-    /// Get the verb name for this action (infer, exec, fetch, invoke, agent)
+    /// Every verb name, in declaration order
+    pub const VERBS: &'static [&'static str] = &[
+        "infer",
+        "exec",
+        "fetch",
+        "invoke",
+        "agent",
+        "reduce",
+        "approve",
+        "embed",
+        "recall",
+        "retrieve",
+        "validate",
+        "transcribe",
+        "import",
+        "export",
+        "rows",
+        "script",
+        "chunk",
+        "dedupe",
+        "detect_lang",
+        "translate",
+        "ws",
+    ];
+
+    /// Get the verb name for this action (infer, exec, ..., detect_lang, translate, ws)
     pub fn verb_name(&self) -> &'static str {
         match self {
             TaskAction::Infer { .. } => "infer",
@@ -145,6 +349,34 @@ impl TaskAction {
             TaskAction::Fetch { .. } => "fetch",
             TaskAction::Invoke { .. } => "invoke",
             TaskAction::Agent { .. } => "agent",
+            TaskAction::Reduce { .. } => "reduce",
+            TaskAction::Approve { .. } => "approve",
+            TaskAction::Embed { .. } => "embed",
+            TaskAction::Recall { .. } => "recall",
+            TaskAction::Retrieve { .. } => "retrieve",
+            TaskAction::Validate { .. } => "validate",
+            TaskAction::Transcribe { .. } => "transcribe",
+            TaskAction::Import { .. } => "import",
+            TaskAction::Export { .. } => "export",
+            TaskAction::Rows { .. } => "rows",
+            TaskAction::Script { .. } => "script",
+            TaskAction::Chunk { .. } => "chunk",
+            TaskAction::Dedupe { .. } => "dedupe",
+            TaskAction::DetectLang { .. } => "detect_lang",
+            TaskAction::Translate { .. } => "translate",
+            TaskAction::Ws { .. } => "ws",
+        }
+    }
+
+    /// Verbs with a `max_tokens:` generation limit (v0.7)
+    pub const TOKEN_LIMIT_VERBS: &'static [&'static str] = &["infer", "reduce"];
+
+    /// The `max_tokens:` of a verb in [`Self::TOKEN_LIMIT_VERBS`]
+    pub fn max_tokens_mut(&mut self) -> Option<&mut Option<u32>> {
+        match self {
+            TaskAction::Infer { infer } => Some(&mut infer.max_tokens),
+            TaskAction::Reduce { reduce } => Some(&mut reduce.max_tokens),
+            _ => None,
         }
     }
 }
@@ -193,6 +425,54 @@ infer:
         }
     }
 
+    #[test]
+    fn test_infer_params_hedge_deserialize() {
+        let yaml = r#"
+infer:
+  prompt: "Generate a headline"
+  model: claude-sonnet-4-20250514
+  hedge:
+    provider: openai
+    model: gpt-4o
+    after_ms: 800
+"#;
+        let action: TaskAction = serde_yaml::from_str(yaml).unwrap();
+        let TaskAction::Infer { infer } = action else {
+            panic!("Expected TaskAction::Infer");
+        };
+        let hedge = infer.hedge.expect("hedge");
+        assert_eq!(hedge.provider.as_deref(), Some("openai"));
+        assert_eq!(hedge.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(hedge.after_ms, Some(800));
+
+        // An empty hedge races the same provider and model
+        let action: TaskAction =
+            serde_yaml::from_str("infer:\n  prompt: hi\n  hedge: {}\n").unwrap();
+        assert!(matches!(
+            action,
+            TaskAction::Infer { infer } if infer.hedge.as_ref().is_some_and(|h| h.provider.is_none())
+        ));
+    }
+
+    #[test]
+    fn test_infer_params_images_deserialize() {
+        let yaml = r#"
+infer:
+  prompt: "What does this chart show?"
+  images:
+    - ./charts/q3.png
+    - "{{use.photo_url}}"
+"#;
+        let action: TaskAction = serde_yaml::from_str(yaml).unwrap();
+        let TaskAction::Infer { infer } = action else {
+            panic!("Expected TaskAction::Infer");
+        };
+        assert_eq!(infer.images, vec!["./charts/q3.png", "{{use.photo_url}}"]);
+
+        let action: TaskAction = serde_yaml::from_str("infer: hi").unwrap();
+        assert!(matches!(action, TaskAction::Infer { infer } if infer.images.is_empty()));
+    }
+
     #[test]
     fn test_infer_params_full_form_only_prompt() {
         let yaml = r#"
@@ -295,6 +575,43 @@ exec:
         }
     }
 
+    #[test]
+    fn test_exec_params_container_form() {
+        let yaml = r#"
+exec:
+  image: "python:3.12"
+  cmd: "python analyze.py"
+  runtime: podman
+"#;
+        let action: TaskAction = serde_yaml::from_str(yaml).unwrap();
+        let TaskAction::Exec { exec } = action else {
+            panic!("Expected TaskAction::Exec");
+        };
+        assert_eq!(exec.command, "python analyze.py");
+        let container = exec.container.unwrap();
+        assert_eq!(container.image, "python:3.12");
+        assert_eq!(container.runtime, Some(ContainerRuntime::Podman));
+        assert!(container.artifacts.is_none());
+
+        let no_image = "exec: { command: ls, artifacts: ./out }";
+        assert!(serde_yaml::from_str::<TaskAction>(no_image).is_err());
+    }
+
+    #[test]
+    fn test_exec_params_host_form() {
+        let yaml = r#"exec: { host: build-server, cmd: "make release" }"#;
+        let action: TaskAction = serde_yaml::from_str(yaml).unwrap();
+        let TaskAction::Exec { exec } = action else {
+            panic!("Expected TaskAction::Exec");
+        };
+        assert_eq!(exec.command, "make release");
+        assert_eq!(exec.host.as_deref(), Some("build-server"));
+        assert!(exec.container.is_none());
+
+        let both = "exec: { host: build-server, image: alpine, cmd: ls }";
+        assert!(serde_yaml::from_str::<TaskAction>(both).is_err());
+    }
+
     #[test]
     fn test_exec_params_complex_command() {
         let yaml = r#"
@@ -677,6 +994,9 @@ agent:
                 prompt: "test".to_string(),
                 provider: None,
                 model: None,
+                max_tokens: None,
+                hedge: None,
+                images: Vec::new(),
             },
         };
         assert_eq!(action.verb_name(), "infer");
@@ -687,6 +1007,10 @@ agent:
         let action = TaskAction::Exec {
             exec: ExecParams {
                 command: "echo test".to_string(),
+                timeout: None,
+                sandbox: None,
+                container: None,
+                host: None,
             },
         };
         assert_eq!(action.verb_name(), "exec");
@@ -700,6 +1024,10 @@ agent:
                 method: "GET".to_string(),
                 headers: FxHashMap::default(),
                 body: None,
+                auth: None,
+                retry: None,
+                paginate: None,
+                graphql: None,
             },
         };
         assert_eq!(action.verb_name(), "fetch");
@@ -888,6 +1216,9 @@ fetch:
                 prompt: "test".to_string(),
                 provider: Some("claude".to_string()),
                 model: Some("claude-sonnet-4-20250514".to_string()),
+                max_tokens: None,
+                hedge: None,
+                images: Vec::new(),
             },
         };
         let cloned = action.clone();
@@ -901,11 +1232,18 @@ fetch:
                 prompt: "test".to_string(),
                 provider: None,
                 model: None,
+                max_tokens: None,
+                hedge: None,
+                images: Vec::new(),
             },
         };
         let exec = TaskAction::Exec {
             exec: ExecParams {
                 command: "echo".to_string(),
+                timeout: None,
+                sandbox: None,
+                container: None,
+                host: None,
             },
         };
         let fetch = TaskAction::Fetch {
@@ -914,6 +1252,10 @@ fetch:
                 method: "GET".to_string(),
                 headers: FxHashMap::default(),
                 body: None,
+                auth: None,
+                retry: None,
+                paginate: None,
+                graphql: None,
             },
         };
 
@@ -37,6 +37,12 @@ const DEFAULT_DEPTH_LIMIT: u32 = 3;
 /// Maximum allowed depth limit (prevent deep recursion)
 const MAX_DEPTH_LIMIT: u32 = 10;
 
+/// Default number of tool calls of one turn run at once (v0.7)
+const DEFAULT_TOOL_CONCURRENCY: u32 = 4;
+
+/// Maximum allowed tool concurrency
+const MAX_TOOL_CONCURRENCY: u32 = 32;
+
 /// Parameters for the `agent:` verb
 ///
 /// Enables agentic execution with MCP tool access. The agent runs
@@ -59,6 +65,10 @@ pub struct AgentParams {
     #[serde(default)]
     pub model: Option<String>,
 
+    /// Image paths or URLs sent with the prompt (v0.7)
+    #[serde(default)]
+    pub images: Vec<String>,
+
     /// MCP servers the agent can access for tool calling
     #[serde(default)]
     pub mcp: Vec<String>,
@@ -103,6 +113,104 @@ pub struct AgentParams {
     /// Used by `spawn_agent` internal tool to prevent infinite recursion.
     #[serde(default)]
     pub depth_limit: Option<u32>,
+
+    /// Cache the system prompt and tool schemas (Claude only, v0.7)
+    ///
+    /// Marks a prompt cache breakpoint after the stable prefix, so later
+    /// turns read it back at a tenth of the input price. Cache reads and
+    /// writes are reported separately in `AgentTurn` metadata.
+    #[serde(default)]
+    pub prompt_cache: Option<bool>,
+
+    /// Give the agent the `web_search` tool (v0.7)
+    ///
+    /// The backend (Brave, Tavily or SearxNG) and its key come from
+    /// `[web_search]` in the global config or the environment.
+    #[serde(default)]
+    pub web_search: Option<bool>,
+
+    /// Give the agent the `read_url` tool (v0.7)
+    ///
+    /// Pages come back as Markdown without boilerplate; `domains` rules in
+    /// `.nika/policy.yaml` limit which hosts it may fetch.
+    #[serde(default)]
+    pub read_url: Option<bool>,
+
+    /// Give the agent the `run_background`, `tail_logs` and `kill` tools (v0.7)
+    ///
+    /// For dev servers and watchers: processes are tracked per run and
+    /// killed when the workflow ends.
+    #[serde(default)]
+    pub background: Option<bool>,
+
+    /// Give the agent the `remember` and `recall` tools (v0.7)
+    ///
+    /// Memories are kept across runs in the `[memory]` store, per project.
+    #[serde(default)]
+    pub memory: Option<bool>,
+
+    /// Give the agent the `spawn_subagent` tool (v0.7)
+    ///
+    /// Each call runs a fresh agent on its own prompt, a subset of this
+    /// agent's MCP tools and its own `max_turns`, and returns its final
+    /// answer as the tool result. Nesting stops at `depth_limit`.
+    #[serde(default)]
+    pub subagents: Option<bool>,
+
+    /// Token, cost and tool-call limits of the whole loop (v0.7)
+    #[serde(default)]
+    pub limits: Option<AgentLimits>,
+
+    /// Tool calls of one turn run at once (v0.7, default 4, 1 = sequential)
+    ///
+    /// Results are still returned to the model in the order it made the calls.
+    #[serde(default)]
+    pub tool_concurrency: Option<u32>,
+
+    /// Conversation the agent joins (v0.7, default `isolated`)
+    ///
+    /// `shared` agents continue one conversation for the whole run: each
+    /// sees the prompts and final answers of the shared agents before it.
+    /// Isolated agents start empty; outputs cross between the two only
+    /// through `use:` bindings.
+    #[serde(default)]
+    pub context: AgentContext,
+}
+
+/// Conversation context of an agent task (v0.7)
+///
+/// Run by `IsolatedAgentRunner` and `SharedAgentRunner` in the runtime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentContext {
+    /// Fresh conversation: the prompt and nothing else
+    #[default]
+    Isolated,
+    /// The run's shared conversation, one agent at a time
+    Shared,
+}
+
+/// Spending limits of an agent task (v0.7)
+///
+/// Once one is hit, the agent's next tool calls are not run: they return a
+/// notice asking the model to summarize and stop, so the task ends with a
+/// final answer instead of an error.
+///
+/// ```yaml
+/// limits: { max_tokens: 100000, max_cost_usd: 1.0, max_tool_calls: 50 }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentLimits {
+    /// Input and output tokens over all turns
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    /// Estimated cost in USD (models without a known price cost nothing)
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    /// Tool calls over all turns
+    #[serde(default)]
+    pub max_tool_calls: Option<u32>,
 }
 
 impl AgentParams {
@@ -124,6 +232,23 @@ impl AgentParams {
         self.token_budget.unwrap_or(u32::MAX)
     }
 
+    /// Token limit of the loop: `limits.max_tokens`, else `token_budget`
+    pub fn token_limit(&self) -> Option<u64> {
+        self.limits
+            .as_ref()
+            .and_then(|limits| limits.max_tokens)
+            .or(self.token_budget.map(u64::from))
+    }
+
+    /// Get effective tool concurrency (with default).
+    ///
+    /// Returns the configured `tool_concurrency` if set, otherwise returns
+    /// the default value of 4.
+    #[inline]
+    pub fn effective_tool_concurrency(&self) -> u32 {
+        self.tool_concurrency.unwrap_or(DEFAULT_TOOL_CONCURRENCY)
+    }
+
     /// Get effective thinking budget (with default).
     ///
     /// Returns the configured `thinking_budget` if set, otherwise returns
@@ -160,6 +285,8 @@ impl AgentParams {
     /// - `prompt` is empty
     /// - `max_turns` is 0 or exceeds 100
     /// - `token_budget` is 0
+    /// - a `limits` value is 0 (or not a positive cost)
+    /// - `tool_concurrency` is 0 or exceeds 32
     pub fn validate(&self) -> Result<(), String> {
         if self.prompt.is_empty() {
             return Err("Agent prompt cannot be empty".to_string());
@@ -180,6 +307,33 @@ impl AgentParams {
             }
         }
 
+        if let Some(limits) = &self.limits {
+            if limits.max_tokens == Some(0) {
+                return Err("limits.max_tokens must be > 0".to_string());
+            }
+            if limits
+                .max_cost_usd
+                .is_some_and(|cost| !cost.is_finite() || cost <= 0.0)
+            {
+                return Err("limits.max_cost_usd must be > 0".to_string());
+            }
+            if limits.max_tool_calls == Some(0) {
+                return Err("limits.max_tool_calls must be > 0".to_string());
+            }
+        }
+
+        if let Some(concurrency) = self.tool_concurrency {
+            if concurrency == 0 {
+                return Err("tool_concurrency must be > 0".to_string());
+            }
+            if concurrency > MAX_TOOL_CONCURRENCY {
+                return Err(format!(
+                    "tool_concurrency cannot exceed {}",
+                    MAX_TOOL_CONCURRENCY
+                ));
+            }
+        }
+
         // Extended thinking is only supported for Claude
         if self.extended_thinking == Some(true) {
             if let Some(ref provider) = self.provider {
@@ -192,6 +346,18 @@ impl AgentParams {
             }
         }
 
+        // Prompt caching is only supported for Claude
+        if self.prompt_cache == Some(true) {
+            if let Some(ref provider) = self.provider {
+                if provider != "claude" {
+                    return Err(format!(
+                        "prompt_cache only supported for claude provider, got '{}'",
+                        provider
+                    ));
+                }
+            }
+        }
+
         // Validate depth_limit (MVP 8 Phase 2)
         if let Some(depth) = self.depth_limit {
             if depth == 0 {
@@ -201,6 +367,9 @@ impl AgentParams {
                 return Err(format!("depth_limit cannot exceed {}", MAX_DEPTH_LIMIT));
             }
         }
+        if self.subagents == Some(true) && self.effective_depth_limit() < 2 {
+            return Err("subagents needs a depth_limit of at least 2".to_string());
+        }
 
         Ok(())
     }
@@ -341,6 +510,64 @@ token_budget: 100000
         assert!(params.validate().is_err());
     }
 
+    #[test]
+    fn parse_limits() {
+        let yaml = r#"
+prompt: "Test"
+token_budget: 5000
+limits: { max_tokens: 100000, max_cost_usd: 1.0, max_tool_calls: 50 }
+"#;
+        let params: AgentParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            params.limits,
+            Some(AgentLimits {
+                max_tokens: Some(100000),
+                max_cost_usd: Some(1.0),
+                max_tool_calls: Some(50),
+            })
+        );
+        // limits.max_tokens wins over token_budget
+        assert_eq!(params.token_limit(), Some(100000));
+        assert!(params.validate().is_ok());
+
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Test\"\ntoken_budget: 5000\n").unwrap();
+        assert_eq!(params.token_limit(), Some(5000));
+        assert!(
+            serde_yaml::from_str::<AgentParams>("prompt: x\nlimits: { max_turns: 3 }").is_err()
+        );
+    }
+
+    #[test]
+    fn validate_zero_limits() {
+        for limits in [
+            "{ max_tokens: 0 }",
+            "{ max_cost_usd: 0.0 }",
+            "{ max_tool_calls: 0 }",
+        ] {
+            let yaml = format!("prompt: \"Test\"\nlimits: {}\n", limits);
+            let params: AgentParams = serde_yaml::from_str(&yaml).unwrap();
+            assert!(params.validate().is_err(), "{}", limits);
+        }
+    }
+
+    #[test]
+    fn tool_concurrency_default_and_bounds() {
+        let params: AgentParams = serde_yaml::from_str("prompt: \"Test\"").unwrap();
+        assert_eq!(params.effective_tool_concurrency(), 4);
+
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Test\"\ntool_concurrency: 1").unwrap();
+        assert_eq!(params.effective_tool_concurrency(), 1);
+        assert!(params.validate().is_ok());
+
+        for concurrency in [0, 33] {
+            let yaml = format!("prompt: \"Test\"\ntool_concurrency: {}", concurrency);
+            let params: AgentParams = serde_yaml::from_str(&yaml).unwrap();
+            assert!(params.validate().is_err(), "{}", concurrency);
+        }
+    }
+
     // ========================================================================
     // System Prompt Tests
     // ========================================================================
@@ -471,4 +698,81 @@ thinking_budget: 8192
         let params = AgentParams::default();
         assert!(params.thinking_budget.is_none());
     }
+
+    // ========================================================================
+    // Prompt Caching Tests (v0.7)
+    // ========================================================================
+
+    #[test]
+    fn parse_and_validate_prompt_cache() {
+        let yaml = r#"
+prompt: "Test"
+prompt_cache: true
+"#;
+        let params: AgentParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(params.prompt_cache, Some(true));
+        assert!(params.validate().is_ok());
+
+        let openai = AgentParams {
+            provider: Some("openai".to_string()),
+            ..params
+        };
+        let err = openai.validate().unwrap_err();
+        assert!(err.contains("prompt_cache only supported for claude"));
+    }
+
+    #[test]
+    fn parse_web_search() {
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Find the release date\"\nweb_search: true\n").unwrap();
+        assert_eq!(params.web_search, Some(true));
+        assert!(params.validate().is_ok());
+    }
+
+    #[test]
+    fn parse_read_url() {
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Summarize the changelog\"\nread_url: true\n").unwrap();
+        assert_eq!(params.read_url, Some(true));
+        assert_eq!(AgentParams::default().read_url, None);
+    }
+
+    #[test]
+    fn parse_background() {
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Start the dev server\"\nbackground: true\n").unwrap();
+        assert_eq!(params.background, Some(true));
+        assert_eq!(AgentParams::default().background, None);
+    }
+
+    #[test]
+    fn parse_memory() {
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Triage new issues\"\nmemory: true\n").unwrap();
+        assert_eq!(params.memory, Some(true));
+        assert_eq!(AgentParams::default().memory, None);
+    }
+
+    #[test]
+    fn subagents_need_room_to_nest() {
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Audit each crate\"\nsubagents: true\n").unwrap();
+        assert_eq!(params.subagents, Some(true));
+        assert!(params.validate().is_ok());
+
+        let flat = AgentParams {
+            depth_limit: Some(1),
+            ..params
+        };
+        assert!(flat.validate().unwrap_err().contains("depth_limit"));
+    }
+
+    #[test]
+    fn parse_context() {
+        let params: AgentParams =
+            serde_yaml::from_str("prompt: \"Review the draft\"\ncontext: shared\n").unwrap();
+        assert_eq!(params.context, AgentContext::Shared);
+        assert_eq!(AgentParams::default().context, AgentContext::Isolated);
+        assert!(serde_yaml::from_str::<AgentParams>("prompt: x\ncontext: global\n").is_err());
+    }
 }
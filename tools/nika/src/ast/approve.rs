@@ -0,0 +1,79 @@
+//! Approve Action - human-in-the-loop gate (v0.7)
+//!
+//! The `approve:` verb pauses the task until a person approves or rejects
+//! it (CLI prompt or TUI). The output is a small JSON object, so downstream
+//! flows can branch on it with `when: "{{from.approved}}"`.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: review
+//!     use:
+//!       draft: write_post
+//!     approve:
+//!       prompt: "Publish this post?\n\n{{use.draft}}"
+//!       timeout: 600
+//!       default: reject
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of an approval gate
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    #[serde(alias = "approved", alias = "yes")]
+    Approve,
+    #[serde(alias = "rejected", alias = "no")]
+    Reject,
+}
+
+impl ApprovalDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::Reject => "reject",
+        }
+    }
+
+    pub fn is_approved(&self) -> bool {
+        matches!(self, Self::Approve)
+    }
+}
+
+/// Approve action parameters (v0.7)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApproveParams {
+    /// Question shown to the approver (supports `{{use.alias}}` templates)
+    pub prompt: String,
+    /// Seconds to wait before falling back to `default` (none = wait forever)
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    /// Decision used on timeout or when no one can be asked (non-interactive runs)
+    #[serde(default)]
+    pub default: Option<ApprovalDecision>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_minimal() {
+        let params: ApproveParams = serde_yaml::from_str("prompt: Ship it?").unwrap();
+        assert_eq!(params.prompt, "Ship it?");
+        assert!(params.timeout.is_none());
+        assert!(params.default.is_none());
+    }
+
+    #[test]
+    fn test_parse_timeout_and_default() {
+        let yaml = "prompt: Ship it?\ntimeout: 30\ndefault: rejected";
+        let params: ApproveParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(params.timeout, Some(30));
+        assert_eq!(params.default, Some(ApprovalDecision::Reject));
+        assert!(!ApprovalDecision::Reject.is_approved());
+        assert_eq!(ApprovalDecision::Approve.as_str(), "approve");
+    }
+}
@@ -0,0 +1,104 @@
+//! Chunk Action - split long text for `for_each` fan-out (v0.7)
+//!
+//! The `chunk:` verb splits a document into overlapping pieces that each
+//! fit a model's context, breaking at sentence or heading boundaries, and
+//! outputs them as a JSON array ready for `for_each:`.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: pieces
+//!     use:
+//!       doc: load_report
+//!     chunk:
+//!       source: $doc
+//!       by: headings       # tokens (default) | chars | sentences | headings
+//!       size: 800          # tokens per chunk (chars with by: chars)
+//!       overlap: 80
+//!     output:
+//!       format: json
+//!
+//!   - id: summarize
+//!     use:
+//!       pieces: pieces
+//!     for_each: "$pieces"
+//!     as: piece
+//!     infer: "Summarize: {{use.piece}}"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Default chunk size, in tokens (characters with `by: chars`)
+pub const DEFAULT_CHUNK_SIZE: usize = 500;
+
+/// Where chunks may break
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChunkBy {
+    /// Paragraph, line or word breaks; size and overlap in estimated
+    /// tokens (~4 characters each, default)
+    #[default]
+    Tokens,
+    /// Paragraph, line or word breaks; size and overlap in characters
+    Chars,
+    /// Between sentences; size and overlap in estimated tokens, overlap
+    /// repeating whole sentences
+    Sentences,
+    /// Before Markdown headings, then sentences; size and overlap in
+    /// estimated tokens
+    Headings,
+}
+
+/// Chunk action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChunkParams {
+    /// Text binding expression (`$alias`, `$task.field`, `{{use.alias}}`)
+    pub source: String,
+    /// Boundary to break at
+    #[serde(default)]
+    pub by: ChunkBy,
+    /// Maximum chunk size (default 500)
+    #[serde(default)]
+    pub size: Option<usize>,
+    /// How much of the previous chunk each chunk repeats (default 0)
+    #[serde(default)]
+    pub overlap: usize,
+}
+
+impl ChunkBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChunkBy::Tokens => "tokens",
+            ChunkBy::Chars => "chars",
+            ChunkBy::Sentences => "sentences",
+            ChunkBy::Headings => "headings",
+        }
+    }
+}
+
+impl ChunkParams {
+    pub fn size(&self) -> usize {
+        self.size.unwrap_or(DEFAULT_CHUNK_SIZE).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chunk() {
+        let chunk: ChunkParams =
+            serde_yaml::from_str("source: $doc\nby: headings\nsize: 800\noverlap: 80").unwrap();
+        assert_eq!(chunk.by, ChunkBy::Headings);
+        assert_eq!(chunk.size(), 800);
+        assert_eq!(chunk.overlap, 80);
+
+        let chunk: ChunkParams = serde_yaml::from_str("source: $doc").unwrap();
+        assert_eq!(chunk.by, ChunkBy::Tokens);
+        assert_eq!(chunk.size(), DEFAULT_CHUNK_SIZE);
+        assert!(serde_yaml::from_str::<ChunkParams>("source: $doc\nby: pages").is_err());
+    }
+}
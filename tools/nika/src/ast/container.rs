@@ -0,0 +1,88 @@
+//! Container settings for `exec:` (v0.7)
+//!
+//! Setting `image:` runs the command in a throwaway container instead of the
+//! host shell:
+//!
+//! ```yaml
+//! exec:
+//!   image: "python:3.12"
+//!   cmd: "python analyze.py"        # alias of command:
+//!   runtime: podman                 # docker | podman (default: first on PATH)
+//!   artifacts: ./out                # mounted at /artifacts (default .nika/artifacts)
+//! ```
+//!
+//! The working directory is mounted read-only at `/workspace`. Drivers live
+//! in `runtime::container`.
+
+use serde::{Deserialize, Serialize};
+
+/// Container engine CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+}
+
+/// Container for an `exec:` command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecContainer {
+    /// Image reference, e.g. `python:3.12`
+    pub image: String,
+    /// Engine to use; `None` picks docker, then podman
+    pub runtime: Option<ContainerRuntime>,
+    /// Host directory mounted read-write at `/artifacts`
+    pub artifacts: Option<String>,
+}
+
+impl ExecContainer {
+    pub fn new(image: impl Into<String>) -> Self {
+        Self {
+            image: image.into(),
+            runtime: None,
+            artifacts: None,
+        }
+    }
+
+    /// Check the settings, returning the first problem found
+    pub fn check(&self) -> Result<(), String> {
+        if self.image.trim().is_empty() || self.image.contains(char::is_whitespace) {
+            return Err(format!("'{}' is not an image reference", self.image));
+        }
+        if self
+            .artifacts
+            .as_deref()
+            .is_some_and(|a| a.trim().is_empty())
+        {
+            return Err("artifacts must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_container() {
+        assert!(ExecContainer::new("ghcr.io/acme/tool:1.2").check().is_ok());
+        assert!(ExecContainer::new("python 3").check().is_err());
+        let empty = ExecContainer {
+            artifacts: Some(" ".to_string()),
+            ..ExecContainer::new("alpine")
+        };
+        assert!(empty.check().unwrap_err().contains("artifacts"));
+        let runtime: ContainerRuntime = serde_yaml::from_str("podman").unwrap();
+        assert_eq!(runtime.as_str(), "podman");
+    }
+}
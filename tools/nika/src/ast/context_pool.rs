@@ -0,0 +1,130 @@
+//! Context pool - a token budget shared by a workflow's LLM tasks (v0.7)
+//!
+//! `agent:` and `infer:` tasks draw from one pool. Each holds a reservation
+//! while it runs; when it ends, the tokens it actually used are charged and
+//! the reservation is released:
+//!
+//! ```yaml
+//! context_pool:
+//!   tokens: 200000        # shared by every agent: and infer: task
+//!   reserve: 20000        # held per task while it runs (default 10% of tokens)
+//!   reservations:         # per-task overrides
+//!     research: 80000
+//!   on_exhausted: queue   # queue (default) | summarize | fail
+//! ```
+//!
+//! The pool itself lives in `runtime::context_pool`.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// What a task does when the pool can't cover its reservation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolExhausted {
+    /// Wait until running tasks release enough
+    #[default]
+    Queue,
+    /// Run with what is left as a hard limit: agents wrap up with a
+    /// summary, `infer:` output is capped
+    Summarize,
+    /// Fail the task
+    Fail,
+}
+
+impl PoolExhausted {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queue => "queue",
+            Self::Summarize => "summarize",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+/// Workflow-level `context_pool:` block
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContextPoolSpec {
+    /// Input and output tokens of all pooled tasks together
+    pub tokens: u64,
+    /// Reservation of tasks not listed in `reservations`
+    #[serde(default)]
+    pub reserve: Option<u64>,
+    /// Task ID -> reservation
+    #[serde(default)]
+    pub reservations: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub on_exhausted: PoolExhausted,
+}
+
+impl ContextPoolSpec {
+    /// Tokens held for `task_id` while it runs
+    pub fn reservation(&self, task_id: &str) -> u64 {
+        self.reservations
+            .get(task_id)
+            .copied()
+            .or(self.reserve)
+            .unwrap_or((self.tokens / 10).max(1))
+    }
+
+    /// Check sizes and that reservations name known tasks
+    pub fn check<'a>(&self, task_ids: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+        if self.tokens == 0 {
+            return Err("tokens must be greater than 0".to_string());
+        }
+        let task_ids: Vec<&str> = task_ids.into_iter().collect();
+        let reservations = self.reserve.iter().map(|r| ("reserve", *r));
+        let named = self.reservations.iter().map(|(id, r)| (id.as_str(), *r));
+        for (name, reservation) in reservations.chain(named) {
+            if reservation == 0 || reservation > self.tokens {
+                return Err(format!(
+                    "{}: reservation of {} must be between 1 and tokens ({})",
+                    name, reservation, self.tokens
+                ));
+            }
+        }
+        if let Some(id) = self
+            .reservations
+            .keys()
+            .find(|id| !task_ids.contains(&id.as_str()))
+        {
+            return Err(format!("reservations: unknown task '{}'", id));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(yaml: &str) -> ContextPoolSpec {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn reservation_falls_back_to_default_then_tenth() {
+        let pool = spec("tokens: 100000\nreservations: { research: 60000 }\n");
+        assert_eq!(pool.on_exhausted, PoolExhausted::Queue);
+        assert_eq!(pool.reservation("research"), 60000);
+        assert_eq!(pool.reservation("draft"), 10000);
+
+        let pool = spec("tokens: 100000\nreserve: 5000\non_exhausted: summarize\n");
+        assert_eq!(pool.reservation("draft"), 5000);
+        assert_eq!(pool.on_exhausted, PoolExhausted::Summarize);
+    }
+
+    #[test]
+    fn check_rejects_bad_sizes_and_unknown_tasks() {
+        let ids = || ["research", "draft"].into_iter();
+        assert!(spec("tokens: 1000\nreserve: 100\n").check(ids()).is_ok());
+        assert!(spec("tokens: 0\n").check(ids()).is_err());
+        assert!(spec("tokens: 1000\nreserve: 2000\n").check(ids()).is_err());
+        let err = spec("tokens: 1000\nreservations: { reserch: 100 }\n")
+            .check(ids())
+            .unwrap_err();
+        assert_eq!(err, "reservations: unknown task 'reserch'");
+    }
+}
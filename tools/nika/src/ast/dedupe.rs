@@ -0,0 +1,107 @@
+//! Dedupe Action - drop near-duplicate items (v0.7)
+//!
+//! `dedupe:` removes items of an array that are too similar to an earlier
+//! item, before expensive per-item work (`for_each:` + `infer:`). Similarity
+//! is estimated with MinHash over the item text (no provider calls), or
+//! measured as cosine similarity of embeddings.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: unique
+//!     use:
+//!       tickets: fetch_tickets
+//!     dedupe:
+//!       source: $tickets
+//!       field: body           # compare this field (default: the whole item)
+//!       by: minhash           # minhash (default) | embeddings
+//!       threshold: 0.8        # drop items at least this similar to a kept one
+//!     output:
+//!       format: json
+//!
+//!   - id: triage
+//!     use:
+//!       items: unique.kept
+//!     for_each: "$items"
+//!     as: ticket
+//!     infer: "Triage: {{use.ticket}}"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Default MinHash similarity (estimated Jaccard of 5-character shingles)
+pub const DEFAULT_MINHASH_THRESHOLD: f32 = 0.8;
+
+/// Default embeddings similarity (cosine)
+pub const DEFAULT_EMBEDDINGS_THRESHOLD: f32 = 0.92;
+
+/// How `dedupe:` measures similarity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupeBy {
+    /// Estimated Jaccard similarity of character shingles, computed locally
+    #[default]
+    Minhash,
+    /// Cosine similarity of embeddings from the provider
+    Embeddings,
+}
+
+/// Dedupe action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DedupeParams {
+    /// Array binding expression (`$alias`, `$task.field`, `{{use.alias}}`)
+    pub source: String,
+    /// Dot path of the text to compare in each item (default: whole item)
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub by: DedupeBy,
+    /// Similarity (0.0..=1.0) at or above which an item is a duplicate
+    #[serde(default)]
+    pub threshold: Option<f32>,
+    /// Override workflow provider (`by: embeddings`)
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Embedding model (default: the provider's)
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl DedupeBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DedupeBy::Minhash => "minhash",
+            DedupeBy::Embeddings => "embeddings",
+        }
+    }
+}
+
+impl DedupeParams {
+    pub fn threshold(&self) -> f32 {
+        self.threshold.unwrap_or(match self.by {
+            DedupeBy::Minhash => DEFAULT_MINHASH_THRESHOLD,
+            DedupeBy::Embeddings => DEFAULT_EMBEDDINGS_THRESHOLD,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dedupe() {
+        let dedupe: DedupeParams =
+            serde_yaml::from_str("source: $tickets\nfield: body\nby: embeddings").unwrap();
+        assert_eq!(dedupe.by, DedupeBy::Embeddings);
+        assert_eq!(dedupe.field.as_deref(), Some("body"));
+        assert_eq!(dedupe.threshold(), DEFAULT_EMBEDDINGS_THRESHOLD);
+
+        let dedupe: DedupeParams = serde_yaml::from_str("source: $t\nthreshold: 0.5").unwrap();
+        assert_eq!(dedupe.by, DedupeBy::Minhash);
+        assert_eq!(dedupe.threshold(), 0.5);
+        assert!(serde_yaml::from_str::<DedupeParams>("source: $t\nby: exact").is_err());
+    }
+}
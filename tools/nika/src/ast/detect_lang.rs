@@ -0,0 +1,58 @@
+//! Detect Lang Action - offline language detection (v0.7)
+//!
+//! `detect_lang:` identifies the language of a text (or of each text in an
+//! array) locally with trigram profiles, so localization pipelines can
+//! branch or pick a `translate:` source without a provider call.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: lang
+//!     use:
+//!       ticket: read_ticket
+//!     detect_lang:
+//!       source: $ticket
+//!       allow: [en, fr, de, es]   # optional: only consider these languages
+//!     output:
+//!       format: json
+//!
+//!   - id: to_english
+//!     use:
+//!       ticket: read_ticket
+//!       lang: lang.code
+//!     translate:
+//!       source: $ticket
+//!       from: "{{use.lang}}"
+//!       to: en
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Detect lang action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectLangParams {
+    /// Text or array binding expression (`$alias`, `$task.field`, `{{use.alias}}`)
+    pub source: String,
+    /// Only consider these languages (ISO 639-1 or 639-3 codes)
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_detect_lang() {
+        let detect: DetectLangParams =
+            serde_yaml::from_str("source: $text\nallow: [en, fra]").unwrap();
+        assert_eq!(detect.source, "$text");
+        assert_eq!(detect.allow, vec!["en", "fra"]);
+
+        let detect: DetectLangParams = serde_yaml::from_str("source: $text").unwrap();
+        assert!(detect.allow.is_empty());
+        assert!(serde_yaml::from_str::<DetectLangParams>("source: $t\nmin: 1").is_err());
+    }
+}
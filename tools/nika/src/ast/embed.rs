@@ -0,0 +1,114 @@
+//! Embed and Recall Actions - embeddings and vector memory (v0.7)
+//!
+//! `embed:` turns text into embedding vectors through a provider with an
+//! embeddings API, optionally storing them in a named collection of the
+//! project's vector store (`.nika/vectors/`). `recall:` embeds a query and
+//! returns the closest stored texts, ready to bind into a prompt.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: index
+//!     use:
+//!       pages: crawl
+//!     embed:
+//!       input: "{{use.pages}}"   # text, or a JSON array of texts / {id, text, metadata}
+//!       collection: docs
+//!       provider: openai
+//!
+//!   - id: lookup
+//!     recall:
+//!       query: "How do I rotate API keys?"
+//!       collection: docs
+//!       top_k: 3
+//!
+//!   - id: answer
+//!     use:
+//!       context: lookup
+//!     infer: "Answer from these notes: {{use.context}}"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Number of matches `recall:` returns unless `top_k` is set
+pub const DEFAULT_TOP_K: usize = 5;
+
+/// Embed action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmbedParams {
+    /// Text to embed (supports `{{use.alias}}`); a JSON array embeds each item
+    pub input: String,
+    /// Vector store collection to upsert into (none = only output the vectors)
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Override workflow provider (must support embeddings)
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Embedding model (default: the provider's, see `ProviderInfo::embedding_model`)
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Recall action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecallParams {
+    /// Search text (supports `{{use.alias}}`)
+    pub query: String,
+    /// Vector store collection to search
+    pub collection: String,
+    /// Maximum number of matches
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    /// Drop matches with a lower cosine similarity (-1.0..=1.0)
+    #[serde(default)]
+    pub min_score: Option<f32>,
+    /// Override the provider the collection was embedded with
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Override the model the collection was embedded with
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl RecallParams {
+    pub fn top_k(&self) -> usize {
+        self.top_k.unwrap_or(DEFAULT_TOP_K)
+    }
+}
+
+/// Collection names double as file names: letters, digits, `-` and `_`
+pub fn is_valid_collection(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_embed_and_recall() {
+        let embed: EmbedParams =
+            serde_yaml::from_str("input: \"{{use.doc}}\"\ncollection: docs").unwrap();
+        assert_eq!(embed.collection.as_deref(), Some("docs"));
+        assert!(embed.provider.is_none());
+
+        let recall: RecallParams =
+            serde_yaml::from_str("query: keys\ncollection: docs\nmin_score: 0.3").unwrap();
+        assert_eq!(recall.top_k(), DEFAULT_TOP_K);
+        assert_eq!(recall.min_score, Some(0.3));
+        assert!(serde_yaml::from_str::<RecallParams>("query: keys").is_err());
+    }
+
+    #[test]
+    fn test_collection_names() {
+        assert!(is_valid_collection("docs_v2-en"));
+        assert!(!is_valid_collection(""));
+        assert!(!is_valid_collection("../secrets"));
+    }
+}
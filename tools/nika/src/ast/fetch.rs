@@ -0,0 +1,172 @@
+//! Retry, pagination and GraphQL settings for `fetch:` (v0.7)
+//!
+//! ```yaml
+//! fetch:
+//!   url: "https://api.example.com/items"
+//!   auth: example                  # [auth.example] in ~/.config/nika/config.toml
+//!   retry: { max: 4, backoff_ms: 250 }
+//!   paginate:
+//!     next: "$.next_url"           # default: the Link header's rel="next"
+//!     items: "$.data"              # default: the page itself
+//!     limit: 10                    # max pages (default 10)
+//! ```
+//!
+//! A GraphQL request replaces `url`, `method` and `body`:
+//!
+//! ```yaml
+//! fetch:
+//!   graphql:
+//!     endpoint: "https://api.github.com/graphql"
+//!     query: |
+//!       query($owner: String!) { repositoryOwner(login: $owner) { url } }
+//!     variables: { owner: "{{use.owner}}" }
+//! ```
+//!
+//! Requests are retried on 429 and 5xx even without `retry:`; the loop and
+//! page walking live in `runtime::http`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::{graphql, jsonpath};
+
+/// Retry policy for 429 and 5xx responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FetchRetry {
+    /// Retries after the first attempt (0 disables)
+    #[serde(default = "default_max_retries")]
+    pub max: u32,
+    /// First backoff, doubled on each retry, when there is no `Retry-After`
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+}
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_backoff_ms() -> u64 {
+    500
+}
+
+impl Default for FetchRetry {
+    fn default() -> Self {
+        Self {
+            max: default_max_retries(),
+            backoff_ms: default_backoff_ms(),
+        }
+    }
+}
+
+/// Follow `next` links and concatenate the pages into one array
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FetchPaginate {
+    /// JSONPath to the next page URL in each response
+    #[serde(default)]
+    pub next: Option<String>,
+    /// JSONPath to the array of items in each response
+    #[serde(default)]
+    pub items: Option<String>,
+    /// Maximum number of pages to fetch
+    #[serde(default = "default_page_limit")]
+    pub limit: usize,
+}
+
+fn default_page_limit() -> usize {
+    10
+}
+
+impl FetchPaginate {
+    /// Check the settings, returning the first problem found
+    pub fn check(&self) -> Result<(), String> {
+        if self.limit == 0 {
+            return Err("limit must be at least 1".to_string());
+        }
+        for (field, path) in [("next", &self.next), ("items", &self.items)] {
+            if let Some(path) = path {
+                jsonpath::validate(path).map_err(|e| format!("{}: {}", field, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A GraphQL request, sent as `POST {"query", "variables"}`
+///
+/// The task output is the response's `data`; a response with `errors`
+/// fails the task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FetchGraphql {
+    /// GraphQL endpoint URL (supports templates)
+    pub endpoint: String,
+    /// Query or mutation document
+    pub query: String,
+    /// Variables object; string values support templates
+    #[serde(default)]
+    pub variables: Option<serde_json::Value>,
+}
+
+impl FetchGraphql {
+    /// Check the query syntax and variables, returning the first problem found
+    pub fn check(&self) -> Result<(), String> {
+        if self.endpoint.trim().is_empty() {
+            return Err("endpoint is empty".to_string());
+        }
+        graphql::validate(&self.query).map_err(|e| format!("query: {}", e))?;
+        if graphql::operation_kind(&self.query) == Some("subscription") {
+            return Err("subscriptions are not supported over HTTP".to_string());
+        }
+        match &self.variables {
+            None | Some(serde_json::Value::Object(_)) => Ok(()),
+            Some(_) => Err("variables must be a mapping".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_defaults_and_check() {
+        let paginate: FetchPaginate = serde_yaml::from_str("next: $.next_url").unwrap();
+        assert_eq!(paginate.limit, 10);
+        assert!(paginate.items.is_none());
+        assert!(paginate.check().is_ok());
+
+        let zero: FetchPaginate = serde_yaml::from_str("limit: 0").unwrap();
+        assert!(zero.check().unwrap_err().contains("limit"));
+        let bad: FetchPaginate = serde_yaml::from_str("items: \"$.[\"").unwrap();
+        assert!(bad.check().unwrap_err().starts_with("items"));
+
+        let retry: FetchRetry = serde_yaml::from_str("max: 0").unwrap();
+        assert_eq!(retry.backoff_ms, 500);
+    }
+
+    #[test]
+    fn test_graphql_check() {
+        let ok: FetchGraphql = serde_yaml::from_str(
+            "endpoint: https://api.example.com/graphql\nquery: \"query($id: ID!) { node(id: $id) { id } }\"\nvariables: { id: \"{{use.id}}\" }",
+        )
+        .unwrap();
+        assert!(ok.check().is_ok());
+
+        let broken = FetchGraphql {
+            query: "{ node(id: 1) { id }".to_string(),
+            ..ok.clone()
+        };
+        assert!(broken.check().unwrap_err().starts_with("query: "));
+        let sub = FetchGraphql {
+            query: "subscription { events { id } }".to_string(),
+            ..ok.clone()
+        };
+        assert!(sub.check().unwrap_err().contains("subscriptions"));
+        let vars = FetchGraphql {
+            variables: Some(serde_json::json!([1])),
+            ..ok
+        };
+        assert!(vars.check().is_err());
+    }
+}
@@ -0,0 +1,511 @@
+//! Workflow Formatter - canonical YAML for `nika fmt` (v0.7)
+//!
+//! Parses the workflow into the AST (so only valid workflows are formatted),
+//! then re-emits the document with:
+//!
+//! - Stable key order (workflow header, `mcp`, `tasks`, `flows`; task keys
+//!   `id`, `use`, iteration, verb, `output`)
+//! - Normalized `use:` entries (`task.path ?? default` strings, object form
+//!   only for lazy bindings) and verb shorthand (`infer: "..."`)
+//! - Two-space block indentation, literal blocks for multi-line strings
+//! - One aligned `{ source, target }` line per flow
+//!
+//! The file's header comment and comments directly above each task are
+//! kept; other comments are dropped.
+
+use serde_json::Value as JsonValue;
+use serde_yaml::{Mapping, Value};
+
+use crate::binding::{UseEntry, WiringSpec};
+use crate::error::NikaError;
+
+use super::Workflow;
+
+const WORKFLOW_KEYS: &[&str] = &[
+    "schema",
+    "workflow",
+    "provider",
+    "model",
+    "templates",
+    "triggers",
+];
+
+const TASK_KEYS: &[&str] = &[
+    "id",
+    "use",
+    "for_each",
+    "as",
+    "concurrency",
+    "fail_fast",
+    "decompose",
+    "infer",
+    "exec",
+    "fetch",
+    "invoke",
+    "agent",
+    "output",
+];
+
+/// Leading keys per verb block (remaining keys keep their order)
+const VERB_KEYS: &[(&str, &[&str])] = &[
+    ("infer", &["prompt", "provider", "model"]),
+    ("exec", &["command"]),
+    ("fetch", &["method", "url", "headers", "body"]),
+    ("invoke", &["mcp", "tool", "resource", "params"]),
+    ("agent", &["prompt", "system", "provider", "model", "mcp"]),
+];
+
+/// Verbs with a shorthand string form and the field it maps to
+const SHORTHAND: &[(&str, &str)] = &[("infer", "prompt"), ("exec", "command")];
+
+/// Format workflow YAML canonically
+pub fn format_workflow(yaml: &str) -> Result<String, NikaError> {
+    let workflow: Workflow = serde_yaml::from_str(yaml)?;
+    let doc: Value = serde_yaml::from_str(yaml)?;
+    let doc = doc.as_mapping().ok_or_else(|| NikaError::ParseError {
+        details: "workflow must be a YAML mapping".to_string(),
+    })?;
+
+    let mut out = String::new();
+    let header = header_comments(yaml);
+    if !header.is_empty() {
+        out.push_str(&header);
+        out.push('\n');
+    }
+
+    // Header keys, then unknown top-level keys in file order
+    let mut header_map = Mapping::new();
+    for key in WORKFLOW_KEYS {
+        if let Some(v) = doc.get(*key) {
+            header_map.insert(Value::from(*key), v.clone());
+        }
+    }
+    for (k, v) in doc {
+        let known = k
+            .as_str()
+            .is_some_and(|k| WORKFLOW_KEYS.contains(&k) || ["mcp", "tasks", "flows"].contains(&k));
+        if !known {
+            header_map.insert(k.clone(), v.clone());
+        }
+    }
+    emit_mapping(&mut out, &header_map, 0);
+
+    if let Some(mcp) = doc.get("mcp") {
+        out.push('\n');
+        let mut section = Mapping::new();
+        section.insert(Value::from("mcp"), mcp.clone());
+        emit_mapping(&mut out, &section, 0);
+    }
+
+    out.push_str("\ntasks:\n");
+    let comments = task_comments(yaml);
+    let tasks = doc.get("tasks").and_then(Value::as_sequence);
+    for (idx, (task, parsed)) in tasks.into_iter().flatten().zip(&workflow.tasks).enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        for line in comments.get(idx).into_iter().flatten() {
+            out.push_str(&format!("  {}\n", line));
+        }
+        let task = canonical_task(task, parsed.use_wiring.as_ref());
+        emit_seq_item(&mut out, &Value::Mapping(task), 2);
+    }
+
+    if !workflow.flows.is_empty() {
+        out.push_str("\nflows:\n");
+        let lines: Vec<(String, String)> = workflow
+            .flows
+            .iter()
+            .map(|flow| {
+                let mut target = format!("target: {}", endpoint(&flow.target.as_vec()));
+                if let Some(when) = &flow.when {
+                    target.push_str(&format!(", when: {}", scalar(&Value::from(when.as_str()))));
+                }
+                (
+                    format!("source: {},", endpoint(&flow.source.as_vec())),
+                    target,
+                )
+            })
+            .collect();
+        let width = lines.iter().map(|(s, _)| s.len()).max().unwrap_or(0);
+        for (source, target) in lines {
+            out.push_str(&format!("  - {{ {:<width$} {} }}\n", source, target));
+        }
+    }
+
+    Ok(out)
+}
+
+/// Reorder a task's keys and normalize `use:` and verb shorthand
+fn canonical_task(task: &Value, wiring: Option<&WiringSpec>) -> Mapping {
+    let Some(map) = task.as_mapping() else {
+        return Mapping::new();
+    };
+    let mut out = Mapping::new();
+
+    for key in TASK_KEYS {
+        let Some(value) = map.get(*key) else {
+            continue;
+        };
+        let value = match *key {
+            "use" => normalize_use(value, wiring),
+            verb => match VERB_KEYS.iter().find(|(v, _)| *v == verb) {
+                Some((_, order)) => normalize_verb(verb, value, order),
+                None => value.clone(),
+            },
+        };
+        out.insert(Value::from(*key), value);
+    }
+    for (k, v) in map {
+        if !k.as_str().is_some_and(|k| TASK_KEYS.contains(&k)) {
+            out.insert(k.clone(), v.clone());
+        }
+    }
+    out
+}
+
+fn normalize_use(value: &Value, wiring: Option<&WiringSpec>) -> Value {
+    let (Some(map), Some(wiring)) = (value.as_mapping(), wiring) else {
+        return value.clone();
+    };
+    let mut out = Mapping::new();
+    for alias in map.keys() {
+        let Some(entry) = alias.as_str().and_then(|a| wiring.get(a)) else {
+            continue;
+        };
+        out.insert(alias.clone(), use_entry(entry));
+    }
+    Value::Mapping(out)
+}
+
+fn use_entry(entry: &UseEntry) -> Value {
+    if !entry.lazy {
+        return Value::String(match &entry.default {
+            Some(default) => format!("{} ?? {}", entry.path, default),
+            None => entry.path.clone(),
+        });
+    }
+    let mut map = Mapping::new();
+    map.insert(Value::from("path"), Value::from(entry.path.as_str()));
+    map.insert(Value::from("lazy"), Value::Bool(true));
+    if let Some(default) = &entry.default {
+        let default = serde_yaml::to_value(default).unwrap_or(Value::Null);
+        map.insert(Value::from("default"), default);
+    }
+    Value::Mapping(map)
+}
+
+fn normalize_verb(verb: &str, value: &Value, order: &[&str]) -> Value {
+    let Some(map) = value.as_mapping() else {
+        return value.clone();
+    };
+
+    // `infer: { prompt: x }` → `infer: x`
+    if let Some((_, field)) = SHORTHAND.iter().find(|(v, _)| *v == verb) {
+        if map.len() == 1 {
+            if let Some(s @ Value::String(_)) = map.get(*field) {
+                return s.clone();
+            }
+        }
+    }
+
+    let mut out = Mapping::new();
+    for key in order {
+        if let Some(v) = map.get(*key) {
+            out.insert(Value::from(*key), v.clone());
+        }
+    }
+    for (k, v) in map {
+        if !out.contains_key(k) {
+            out.insert(k.clone(), v.clone());
+        }
+    }
+    Value::Mapping(out)
+}
+
+fn endpoint(ids: &[&str]) -> String {
+    match ids {
+        [single] => scalar(&Value::from(*single)),
+        many => format!(
+            "[{}]",
+            many.iter()
+                .map(|id| scalar(&Value::from(*id)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// COMMENTS
+// ═══════════════════════════════════════════════════════════════
+
+/// Comment block at the top of the file (up to the first key)
+fn header_comments(yaml: &str) -> String {
+    let mut lines: Vec<&str> = yaml
+        .lines()
+        .map(str::trim)
+        .take_while(|l| l.is_empty() || l.starts_with('#'))
+        .skip_while(|l| l.is_empty())
+        .collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.iter().map(|l| format!("{}\n", l)).collect()
+}
+
+/// Comment lines directly above each item of the `tasks:` list, by position
+fn task_comments(yaml: &str) -> Vec<Vec<String>> {
+    let mut result = Vec::new();
+    let mut pending = Vec::new();
+    let mut item_indent = None;
+    let mut in_tasks = false;
+
+    for line in yaml.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if indent == 0 && !trimmed.is_empty() && !trimmed.starts_with('#') {
+            in_tasks = trimmed.starts_with("tasks:");
+            pending.clear();
+            continue;
+        }
+        if !in_tasks {
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            pending.push(trimmed.to_string());
+        } else if trimmed.starts_with("- ") && *item_indent.get_or_insert(indent) == indent {
+            result.push(std::mem::take(&mut pending));
+        } else if !trimmed.is_empty() {
+            pending.clear();
+        }
+    }
+    result
+}
+
+// ═══════════════════════════════════════════════════════════════
+// EMITTER
+// ═══════════════════════════════════════════════════════════════
+
+fn emit_mapping(out: &mut String, map: &Mapping, indent: usize) {
+    for (key, value) in map {
+        out.push_str(&" ".repeat(indent));
+        emit_entry(out, key, value, indent);
+    }
+}
+
+/// `key: value` with the cursor already indented
+fn emit_entry(out: &mut String, key: &Value, value: &Value, indent: usize) {
+    out.push_str(&scalar(key));
+    out.push(':');
+    match value {
+        Value::Mapping(m) if !m.is_empty() => {
+            out.push('\n');
+            emit_mapping(out, m, indent + 2);
+        }
+        Value::Sequence(s) if !s.is_empty() => {
+            out.push('\n');
+            for item in s {
+                emit_seq_item(out, item, indent + 2);
+            }
+        }
+        Value::String(s) if s.contains('\n') && block_safe(s) => {
+            let chomp = if s.ends_with('\n') { "" } else { "-" };
+            out.push_str(&format!(" |{}\n", chomp));
+            for line in s.trim_end_matches('\n').lines() {
+                if !line.is_empty() {
+                    out.push_str(&" ".repeat(indent + 2));
+                    out.push_str(line);
+                }
+                out.push('\n');
+            }
+        }
+        other => {
+            out.push(' ');
+            out.push_str(&scalar(other));
+            out.push('\n');
+        }
+    }
+}
+
+fn emit_seq_item(out: &mut String, item: &Value, indent: usize) {
+    out.push_str(&" ".repeat(indent));
+    out.push_str("- ");
+    match item {
+        Value::Mapping(m) if !m.is_empty() => {
+            for (i, (key, value)) in m.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(&" ".repeat(indent + 2));
+                }
+                emit_entry(out, key, value, indent + 2);
+            }
+        }
+        other => {
+            out.push_str(&scalar(other));
+            out.push('\n');
+        }
+    }
+}
+
+/// Literal blocks can't start with indentation or end with extra blank lines
+fn block_safe(s: &str) -> bool {
+    !s.starts_with([' ', '\t']) && !s.ends_with("\n\n") && !s.contains('\r')
+}
+
+/// Inline scalar (or flow collection) as serde_yaml would quote it
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Mapping(m) if m.is_empty() => "{}".to_string(),
+        Value::Sequence(s) if s.is_empty() => "[]".to_string(),
+        Value::Sequence(s) => format!("[{}]", s.iter().map(scalar).collect::<Vec<_>>().join(", ")),
+        Value::Mapping(m) => format!(
+            "{{ {} }}",
+            m.iter()
+                .map(|(k, v)| format!("{}: {}", scalar(k), scalar(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::String(s) if s.contains('\n') => {
+            serde_json::to_string(&JsonValue::String(s.clone())).unwrap_or_default()
+        }
+        other => serde_yaml::to_string(other)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY: &str = r#"# Example workflow
+#
+# Fetches and summarizes.
+
+provider: mock
+schema: "nika/workflow@0.5"
+tasks:
+  # Grab the page
+  - exec: { command: "curl example.com" }
+    id: fetch
+  - id: summarize
+    infer:
+      model: gpt-4o
+      prompt: |
+        Summarize:
+        {{use.page}}
+    use:
+      page: { path: fetch }
+      lang: fetch.lang ?? "en"
+flows:
+  - source: fetch
+    target: summarize
+  - { target: [fetch], source: summarize_later_a }
+"#;
+
+    #[test]
+    fn test_canonical_output() {
+        let yaml = MESSY.replace("  - { target: [fetch], source: summarize_later_a }\n", "");
+        let formatted = format_workflow(&yaml).unwrap();
+        assert_eq!(
+            formatted,
+            r#"# Example workflow
+#
+# Fetches and summarizes.
+
+schema: nika/workflow@0.5
+provider: mock
+
+tasks:
+  # Grab the page
+  - id: fetch
+    exec: curl example.com
+
+  - id: summarize
+    use:
+      page: fetch
+      lang: fetch.lang ?? "en"
+    infer:
+      prompt: |
+        Summarize:
+        {{use.page}}
+      model: gpt-4o
+
+flows:
+  - { source: fetch, target: summarize }
+"#
+        );
+    }
+
+    #[test]
+    fn test_idempotent_and_equivalent() {
+        let yaml = MESSY.replace("summarize_later_a", "summarize");
+        let once = format_workflow(&yaml).unwrap();
+        assert_eq!(format_workflow(&once).unwrap(), once);
+
+        let before: Workflow = serde_yaml::from_str(&yaml).unwrap();
+        let after: Workflow = serde_yaml::from_str(&once).unwrap();
+        assert_eq!(before.tasks.len(), after.tasks.len());
+        assert_eq!(
+            before.tasks[1].use_wiring.as_ref().unwrap().get("lang"),
+            after.tasks[1].use_wiring.as_ref().unwrap().get("lang")
+        );
+    }
+
+    #[test]
+    fn test_flows_are_aligned() {
+        let yaml = MESSY.replace("summarize_later_a", "summarize");
+        let formatted = format_workflow(&yaml).unwrap();
+        assert!(formatted.contains(
+            "  - { source: fetch,     target: summarize }\n  - { source: summarize, target: fetch }\n"
+        ));
+    }
+
+    #[test]
+    fn test_flow_condition_is_kept() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: review
+    exec: "echo approved"
+  - id: publish
+    exec: "echo publish"
+flows:
+  - source: review
+    target: publish
+    when: "{{from.status}} == 'approved'"
+"#;
+        let formatted = format_workflow(yaml).unwrap();
+        assert!(formatted.contains(
+            "  - { source: review, target: publish, when: '{{from.status}} == ''approved''' }\n"
+        ));
+        let reparsed: Workflow = serde_yaml::from_str(&formatted).unwrap();
+        assert_eq!(
+            reparsed.flows[0].when.as_deref(),
+            Some("{{from.status}} == 'approved'")
+        );
+        assert_eq!(format_workflow(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_lazy_use_keeps_object_form() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: a
+    exec: "echo a"
+  - id: b
+    use:
+      x: { lazy: true, path: a, default: 1 }
+    exec: "echo {{use.x}}"
+"#;
+        let formatted = format_workflow(yaml).unwrap();
+        assert!(formatted
+            .contains("      x:\n        path: a\n        lazy: true\n        default: 1\n"));
+    }
+
+    #[test]
+    fn test_invalid_workflow_is_error() {
+        assert!(format_workflow("schema: nika/workflow@0.5\ntasks: 3\n").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Prompt-injection policy for untrusted content (v0.7)
+//!
+//! Content from `fetch:` and `invoke:` tasks, and MCP tool results inside
+//! `agent:` loops, is checked before it reaches a prompt:
+//!
+//! ```yaml
+//! injection:
+//!   action: wrap                           # off | flag (default) | strip | wrap
+//!   patterns: ["(?i)send .* to http"]      # extra regexes
+//!   builtin: true                          # keep the built-in heuristics
+//! ```
+//!
+//! The heuristics themselves live in `util::injection`.
+
+use serde::Deserialize;
+
+/// What to do with untrusted content that trips a heuristic
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionAction {
+    /// Don't scan
+    Off,
+    /// Pass the content through and emit a `SecurityWarning` event
+    #[default]
+    Flag,
+    /// Remove the matched text
+    Strip,
+    /// Quote the content behind a preamble telling the model it is data
+    Wrap,
+}
+
+impl InjectionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Flag => "flag",
+            Self::Strip => "strip",
+            Self::Wrap => "wrap",
+        }
+    }
+}
+
+/// Workflow-level `injection:` block
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InjectionPolicy {
+    #[serde(default)]
+    pub action: InjectionAction,
+    /// Extra regexes checked alongside the built-in heuristics
+    #[serde(default)]
+    pub patterns: Vec<String>,
+    /// Use the built-in heuristics (default true)
+    #[serde(default = "default_builtin")]
+    pub builtin: bool,
+}
+
+fn default_builtin() -> bool {
+    true
+}
+
+impl Default for InjectionPolicy {
+    fn default() -> Self {
+        Self {
+            action: InjectionAction::default(),
+            patterns: Vec::new(),
+            builtin: true,
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Workflow Inputs - typed parameters supplied at run time (v0.7)
+//!
+//! A workflow declares its parameters under `inputs:`; values come from
+//! `nika run --input name=value` or the TUI's input form, and tasks bind
+//! them like an upstream output: `use: { topic: inputs.topic }`.
+//!
+//! ```yaml
+//! inputs:
+//!   topic:
+//!     description: "What to write about"
+//!   tone:
+//!     type: enum
+//!     values: [formal, casual]
+//!     default: casual
+//!   source:
+//!     type: file
+//!     extensions: [md, txt]
+//!   words: { type: number, default: 300 }
+//! ```
+//!
+//! An input without a `default` is required. Declarations keep their YAML
+//! order, which is the order of the TUI form.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::error::NikaError;
+
+/// Kind of value an input takes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+    /// One of `values`
+    Enum,
+    /// Path to an existing file, optionally limited to `extensions`
+    File,
+}
+
+/// One declared input
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InputSpec {
+    #[serde(default, rename = "type")]
+    pub kind: InputType,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Value when none is given; inputs without one are required
+    #[serde(default)]
+    pub default: Option<Value>,
+    /// Allowed values of an `enum` input
+    #[serde(default)]
+    pub values: Vec<String>,
+    /// Allowed extensions of a `file` input, without the dot
+    #[serde(default)]
+    pub extensions: Vec<String>,
+}
+
+impl InputSpec {
+    /// Static checks of the declaration
+    pub fn check(&self) -> Result<(), String> {
+        match self.kind {
+            InputType::Enum if self.values.is_empty() => {
+                return Err("enum inputs need `values`".to_string())
+            }
+            InputType::Enum => {}
+            _ if !self.values.is_empty() => {
+                return Err("`values` only applies to enum inputs".to_string())
+            }
+            _ => {}
+        }
+        if self.kind != InputType::File && !self.extensions.is_empty() {
+            return Err("`extensions` only applies to file inputs".to_string());
+        }
+        // File defaults are checked when used: the file may not exist yet
+        match self.default_text() {
+            Some(text) if self.kind != InputType::File => self
+                .parse(&text)
+                .map(|_| ())
+                .map_err(|e| format!("default: {}", e)),
+            _ => Ok(()),
+        }
+    }
+
+    /// The default as typed in a form field
+    pub fn default_text(&self) -> Option<String> {
+        self.default.as_ref().map(|value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    /// Parse a value given as text (`--input`, form field)
+    pub fn parse(&self, text: &str) -> Result<Value, String> {
+        match self.kind {
+            InputType::String => Ok(Value::String(text.to_string())),
+            InputType::Number => {
+                let text = text.trim();
+                if let Ok(n) = text.parse::<i64>() {
+                    return Ok(Value::from(n));
+                }
+                text.parse::<f64>()
+                    .ok()
+                    .and_then(|n| serde_json::Number::from_f64(n).map(Value::Number))
+                    .ok_or_else(|| format!("'{}' is not a number", text))
+            }
+            InputType::Boolean => match text.trim().to_ascii_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Ok(Value::Bool(true)),
+                "false" | "no" | "n" | "0" => Ok(Value::Bool(false)),
+                _ => Err(format!("'{}' is not true or false", text)),
+            },
+            InputType::Enum => {
+                if self.values.iter().any(|v| v == text) {
+                    Ok(Value::String(text.to_string()))
+                } else {
+                    Err(format!(
+                        "'{}' is not one of: {}",
+                        text,
+                        self.values.join(", ")
+                    ))
+                }
+            }
+            InputType::File => {
+                let path = Path::new(text);
+                if !path.is_file() {
+                    return Err(format!("no such file: {}", text));
+                }
+                if !self.accepts_extension(path) {
+                    return Err(format!("expected a .{} file", self.extensions.join(", .")));
+                }
+                Ok(Value::String(text.to_string()))
+            }
+        }
+    }
+
+    /// Whether `path` has one of the allowed extensions (any when unset)
+    pub fn accepts_extension(&self, path: &Path) -> bool {
+        self.extensions.is_empty()
+            || path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| {
+                    self.extensions
+                        .iter()
+                        .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(ext))
+                })
+    }
+}
+
+/// The `inputs:` of a workflow, in declaration order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Inputs(Vec<(String, InputSpec)>);
+
+impl Inputs {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &InputSpec)> {
+        self.0.iter().map(|(name, spec)| (name.as_str(), spec))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&InputSpec> {
+        self.iter().find(|(n, _)| *n == name).map(|(_, spec)| spec)
+    }
+
+    /// Check every declaration (NIKA-009)
+    pub fn check(&self) -> Result<(), NikaError> {
+        for (name, spec) in self.iter() {
+            if name.is_empty() || name.contains('.') {
+                return Err(input_error(name, "use a name without dots".to_string()));
+            }
+            spec.check().map_err(|reason| input_error(name, reason))?;
+        }
+        Ok(())
+    }
+
+    /// Values of every input as one object, from `given` or defaults
+    ///
+    /// Fails with NIKA-009 on an unknown name, an invalid value or a
+    /// missing required input.
+    pub fn resolve(&self, given: &BTreeMap<String, String>) -> Result<Value, NikaError> {
+        if let Some(name) = given.keys().find(|name| self.get(name).is_none()) {
+            return Err(input_error(name, "not declared under inputs:".to_string()));
+        }
+        let mut values = serde_json::Map::new();
+        for (name, spec) in self.iter() {
+            let value = match (given.get(name), &spec.default) {
+                (Some(text), _) => spec.parse(text),
+                (None, Some(default)) => Ok(default.clone()),
+                (None, None) => Err("missing required input".to_string()),
+            };
+            values.insert(
+                name.to_string(),
+                value.map_err(|reason| input_error(name, reason))?,
+            );
+        }
+        Ok(Value::Object(values))
+    }
+}
+
+fn input_error(name: &str, reason: String) -> NikaError {
+    NikaError::InvalidInput {
+        name: name.to_string(),
+        reason,
+    }
+}
+
+/// Parse `--input name=value` arguments
+pub fn parse_input_args(args: &[String]) -> Result<BTreeMap<String, String>, NikaError> {
+    args.iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((name, value)) => Ok((name.trim().to_string(), value.to_string())),
+            None => Err(input_error(arg, "expected --input name=value".to_string())),
+        })
+        .collect()
+}
+
+impl<'de> Deserialize<'de> for Inputs {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct InputsVisitor;
+
+        impl<'de> Visitor<'de> for InputsVisitor {
+            type Value = Inputs;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of input names to declarations")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Inputs, A::Error> {
+                let mut inputs = Vec::new();
+                while let Some((name, spec)) = map.next_entry::<String, Option<InputSpec>>()? {
+                    // `topic:` alone declares a required string
+                    inputs.push((name, spec.unwrap_or_default()));
+                }
+                Ok(Inputs(inputs))
+            }
+        }
+
+        deserializer.deserialize_map(InputsVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn inputs(yaml: &str) -> Inputs {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_parse_keeps_order_and_checks() {
+        let declared = inputs(
+            "topic:\nwords: { type: number, default: 300 }\ntone: { type: enum, values: [formal, casual], default: casual }",
+        );
+        let names: Vec<&str> = declared.iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["topic", "words", "tone"]);
+        assert!(declared.check().is_ok());
+
+        let bad_enum = inputs("tone: { type: enum, values: [a], default: b }");
+        assert_eq!(bad_enum.check().unwrap_err().code(), "NIKA-009");
+        assert!(inputs("tone: { type: enum }").check().is_err());
+        assert!(inputs("n: { type: number, extensions: [md] }")
+            .check()
+            .is_err());
+        assert!(serde_yaml::from_str::<Inputs>("n: { kind: number }").is_err());
+    }
+
+    #[test]
+    fn test_resolve_values() {
+        let declared = inputs(
+            "topic:\nwords: { type: number, default: 300 }\ndraft: { type: boolean, default: false }",
+        );
+        let given = BTreeMap::from([
+            ("topic".to_string(), "rust".to_string()),
+            ("draft".to_string(), "yes".to_string()),
+        ]);
+        assert_eq!(
+            declared.resolve(&given).unwrap(),
+            json!({"topic": "rust", "words": 300, "draft": true})
+        );
+
+        let err = declared.resolve(&BTreeMap::new()).unwrap_err();
+        assert!(err.to_string().contains("'topic'"), "{}", err);
+        let unknown = BTreeMap::from([("topc".to_string(), "x".to_string())]);
+        assert!(declared.resolve(&unknown).is_err());
+        let bad = BTreeMap::from([
+            ("topic".to_string(), "x".to_string()),
+            ("words".to_string(), "many".to_string()),
+        ]);
+        assert!(declared
+            .resolve(&bad)
+            .unwrap_err()
+            .to_string()
+            .contains("not a number"));
+    }
+
+    #[test]
+    fn test_file_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "# notes").unwrap();
+        let spec = InputSpec {
+            kind: InputType::File,
+            extensions: vec!["md".to_string()],
+            ..InputSpec::default()
+        };
+        assert!(spec.parse(notes.to_str().unwrap()).is_ok());
+        let missing = dir.path().join("missing.md");
+        assert!(spec
+            .parse(missing.to_str().unwrap())
+            .unwrap_err()
+            .contains("no such file"));
+        let text = dir.path().join("notes.txt");
+        std::fs::write(&text, "notes").unwrap();
+        assert!(spec
+            .parse(text.to_str().unwrap())
+            .unwrap_err()
+            .contains(".md"));
+    }
+}
@@ -0,0 +1,743 @@
+//! Keyword-format importer for `nika migrate --from keyword` (v0.7)
+//!
+//! Converts v4.x workflows, whose tasks use one of seven keywords, into the
+//! verb format:
+//!
+//! | v4.x keyword | Verb      | Notes                                            |
+//! |--------------|-----------|--------------------------------------------------|
+//! | `llm:`       | `infer:`  |                                                  |
+//! | `agent:`     | `agent:`  | own context; `mainAgent.systemPrompt` → `system` |
+//! | `subagent:`  | `agent:`  |                                                  |
+//! | `shell:`     | `exec:`   | `cwd` becomes a `cd` prefix                      |
+//! | `http:`      | `fetch:`  | non-string bodies are sent as JSON               |
+//! | `mcp:`       | `invoke:` | `server::tool` shorthand                         |
+//! | `function:`  | `exec:`   | failing placeholder, to port by hand             |
+//!
+//! `{{id}}`, `{{id.output}}` and `{{id.output.field}}` references to other
+//! tasks become `use:` bindings, with a flow edge when the legacy `flows:`
+//! lack one. Ids are renamed to the verb format's `[a-z][a-z0-9_]*`. Anything without an equivalent is dropped and listed as a
+//! [`MigrationNote`]. The result goes through [`format_workflow`], so it is
+//! canonical and parses as a workflow.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+
+use super::format::format_workflow;
+use super::workflow::SCHEMA_V05;
+use crate::error::NikaError;
+
+/// v4.x keywords and the verb each becomes
+const KEYWORDS: &[(&str, &str)] = &[
+    ("llm", "infer"),
+    ("agent", "agent"),
+    ("subagent", "agent"),
+    ("shell", "exec"),
+    ("http", "fetch"),
+    ("mcp", "invoke"),
+    ("function", "exec"),
+];
+
+/// Top-level keys handled by the importer
+const HEADER_KEYS: &[&str] = &[
+    "schema",
+    "workflow",
+    "name",
+    "description",
+    "mainAgent",
+    "tasks",
+    "flows",
+];
+
+/// `{{id}}` or `{{id.path}}`, with hyphens allowed in ids
+static REF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z0-9_-]+)((?:\.[A-Za-z0-9_]+)*)\s*\}\}").unwrap());
+
+/// A v4.x workflow converted to the verb format
+#[derive(Debug, Clone)]
+pub struct KeywordMigration {
+    /// Canonical verb-format YAML
+    pub yaml: String,
+    /// Constructs that were dropped or changed meaning
+    pub notes: Vec<MigrationNote>,
+}
+
+/// Something the importer could not carry over as is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationNote {
+    /// Task concerned (None for workflow-level keys)
+    pub task: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for MigrationNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.task {
+            Some(task) => write!(f, "task '{}': {}", task, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Whether `yaml` is a v4.x keyword-format workflow
+///
+/// Verb-format workflows declare a `nika/workflow@` schema; keyword-format
+/// ones don't, and their tasks use the v4.x keywords. Used by `nika run` and
+/// `nika check` to translate legacy workflows on load.
+pub fn is_keyword_format(yaml: &str) -> bool {
+    let Ok(doc) = serde_yaml::from_str::<Value>(yaml) else {
+        return false;
+    };
+    let verb_schema = doc
+        .get("schema")
+        .and_then(Value::as_str)
+        .is_some_and(|schema| schema.starts_with("nika/workflow@"));
+    let tasks = doc.get("tasks").and_then(Value::as_sequence);
+    !verb_schema
+        && tasks.is_some_and(|tasks| {
+            tasks.iter().any(|task| {
+                KEYWORDS
+                    .iter()
+                    .any(|(keyword, _)| task.get(*keyword).is_some())
+            })
+        })
+}
+
+/// Convert a v4.x keyword-format workflow to the verb format
+pub fn migrate_keyword(yaml: &str) -> Result<KeywordMigration, NikaError> {
+    let doc: Value = serde_yaml::from_str(yaml)?;
+    let doc = doc
+        .as_mapping()
+        .ok_or_else(|| parse_error("workflow must be a YAML mapping"))?;
+    let tasks = doc
+        .get("tasks")
+        .and_then(Value::as_sequence)
+        .ok_or_else(|| parse_error("keyword workflow has no `tasks:` list"))?;
+
+    let mut migrator = Migrator {
+        ids: tasks
+            .iter()
+            .filter_map(|task| task.get("id").and_then(Value::as_str))
+            .map(|id| (id.to_string(), verb_id(id)))
+            .collect(),
+        ..Migrator::default()
+    };
+    let renamed: Vec<_> = migrator
+        .ids
+        .iter()
+        .filter(|(legacy, id)| legacy != id)
+        .map(|(legacy, id)| (legacy.clone(), id.clone()))
+        .collect();
+    for (legacy, id) in renamed {
+        migrator.note(Some(&legacy), format!("renamed to '{}'", id));
+    }
+
+    let mut out = Mapping::new();
+    out.insert("schema".into(), SCHEMA_V05.into());
+    if let Some(name) = doc.get("workflow").or_else(|| doc.get("name")) {
+        out.insert("workflow".into(), name.clone());
+    }
+    if let Some(main) = doc.get("mainAgent").and_then(Value::as_mapping) {
+        migrator.main_agent(main, &mut out);
+        if out.contains_key("model") && !out.contains_key("provider") {
+            migrator.note(
+                None,
+                "mainAgent has a model but no provider; the default provider (claude) applies"
+                    .to_string(),
+            );
+        }
+    }
+    for (key, _) in doc {
+        let key = key.as_str().unwrap_or_default();
+        if !HEADER_KEYS.contains(&key) {
+            migrator.note(None, format!("top-level `{}` has no equivalent", key));
+        }
+    }
+
+    let mut migrated = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        migrated.push(Value::Mapping(migrator.task(task)?));
+    }
+    out.insert("tasks".into(), Value::Sequence(migrated));
+
+    let flows = migrator.flows(doc.get("flows"));
+    if !flows.is_empty() {
+        out.insert("flows".into(), Value::Sequence(flows));
+    }
+    if migrator.main_system.is_some() && !migrator.main_system_used {
+        migrator.note(
+            None,
+            "mainAgent.systemPrompt only applies to agent: tasks; dropped".to_string(),
+        );
+    }
+
+    let mut header = String::from("# Migrated from the v4.x keyword format by `nika migrate`\n");
+    if let Some(description) = doc.get("description").and_then(Value::as_str) {
+        for line in description.lines() {
+            header.push_str(&format!("# {}\n", line).replace("# \n", "#\n"));
+        }
+    }
+    let body = serde_yaml::to_string(&Value::Mapping(out))?;
+    Ok(KeywordMigration {
+        yaml: format_workflow(&format!("{}\n{}", header, body))?,
+        notes: migrator.notes,
+    })
+}
+
+fn parse_error(details: impl Into<String>) -> NikaError {
+    NikaError::ParseError {
+        details: details.into(),
+    }
+}
+
+#[derive(Default)]
+struct Migrator {
+    /// Legacy id → verb-format id, for all tasks
+    ids: BTreeMap<String, String>,
+    /// `mainAgent.systemPrompt`, given to `agent:` tasks
+    main_system: Option<Value>,
+    main_system_used: bool,
+    /// (source, target) edges implied by template references
+    edges: Vec<(String, String)>,
+    notes: Vec<MigrationNote>,
+}
+
+impl Migrator {
+    fn note(&mut self, task: Option<&str>, message: String) {
+        self.notes.push(MigrationNote {
+            task: task.map(str::to_string),
+            message,
+        });
+    }
+
+    fn main_agent(&mut self, main: &Mapping, out: &mut Mapping) {
+        for (key, value) in main {
+            match key.as_str().unwrap_or_default() {
+                "provider" => {
+                    out.insert("provider".into(), value.clone());
+                }
+                "model" => {
+                    out.insert("model".into(), value.clone());
+                }
+                "systemPrompt" => self.main_system = Some(value.clone()),
+                other => self.note(None, format!("mainAgent.{} has no equivalent", other)),
+            }
+        }
+    }
+
+    fn task(&mut self, task: &Value) -> Result<Mapping, NikaError> {
+        let task = task
+            .as_mapping()
+            .ok_or_else(|| parse_error("each task must be a mapping"))?;
+        let id = task
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| parse_error("each task needs a string `id`"))?
+            .to_string();
+
+        let keywords: Vec<_> = KEYWORDS
+            .iter()
+            .filter(|(keyword, _)| task.contains_key(*keyword))
+            .collect();
+        let &[&(keyword, verb)] = keywords.as_slice() else {
+            return Err(parse_error(format!(
+                "task '{}' needs exactly one of llm, agent, subagent, shell, http, mcp, function",
+                id
+            )));
+        };
+        for (key, _) in task {
+            let key = key.as_str().unwrap_or_default();
+            if key != "id" && key != keyword {
+                self.note(Some(&id), format!("`{}` has no equivalent", key));
+            }
+        }
+
+        let params = &task[keyword];
+        let body = match keyword {
+            "llm" => self.fields(&id, keyword, params, "prompt", LLM_FIELDS),
+            "agent" | "subagent" => self.agent(&id, keyword, params),
+            "shell" => self.shell(&id, params),
+            "http" => self.http(&id, params),
+            "mcp" => self.mcp(&id, params)?,
+            _ => self.function(&id, params),
+        };
+
+        let mut uses = BTreeMap::new();
+        let body = self.rewrite_refs(&id, body, &mut uses);
+        let mut out = Mapping::new();
+        out.insert("id".into(), self.ids[&id].as_str().into());
+        if !uses.is_empty() {
+            let wiring = uses
+                .into_iter()
+                .map(|(alias, source)| (Value::from(alias), Value::from(source)))
+                .collect();
+            out.insert("use".into(), Value::Mapping(wiring));
+        }
+        out.insert(verb.into(), body);
+        Ok(out)
+    }
+
+    /// Copy known fields (renamed), noting the others
+    fn fields(
+        &mut self,
+        id: &str,
+        keyword: &str,
+        params: &Value,
+        shorthand: &str,
+        known: &[(&str, &str)],
+    ) -> Value {
+        let mut out = Mapping::new();
+        match params {
+            Value::Mapping(params) => {
+                for (key, value) in params {
+                    let key = key.as_str().unwrap_or_default();
+                    match known.iter().find(|(from, _)| *from == key) {
+                        Some((_, to)) => {
+                            out.insert((*to).into(), value.clone());
+                        }
+                        None => {
+                            self.note(Some(id), format!("{}.{} has no equivalent", keyword, key))
+                        }
+                    }
+                }
+            }
+            value => {
+                out.insert(shorthand.into(), value.clone());
+            }
+        }
+        Value::Mapping(out)
+    }
+
+    fn agent(&mut self, id: &str, keyword: &str, params: &Value) -> Value {
+        let mut body = self.fields(id, keyword, params, "prompt", AGENT_FIELDS);
+        if let Some(mcp) = body.get_mut("mcp") {
+            if mcp.is_string() {
+                *mcp = Value::Sequence(vec![mcp.clone()]);
+            }
+        }
+        if keyword == "agent" {
+            if let (Some(system), Value::Mapping(body)) = (&self.main_system, &mut body) {
+                if !body.contains_key("system") {
+                    body.insert("system".into(), system.clone());
+                }
+                self.main_system_used = true;
+            }
+            self.note(
+                Some(id),
+                "agent: no longer shares the main agent's conversation; it runs with its own context"
+                    .to_string(),
+            );
+        }
+        body
+    }
+
+    fn shell(&mut self, id: &str, params: &Value) -> Value {
+        let mut body = self.fields(id, "shell", params, "command", SHELL_FIELDS);
+        if let Value::Mapping(body) = &mut body {
+            if let Some(cwd) = body.remove("cwd") {
+                let command = body
+                    .get("command")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let cwd = cwd.as_str().unwrap_or_default();
+                let command = format!("cd {} && {}", shell_quote(cwd), command);
+                body.insert("command".into(), command.into());
+            }
+        }
+        body
+    }
+
+    fn http(&mut self, id: &str, params: &Value) -> Value {
+        let mut body = self.fields(id, "http", params, "url", HTTP_FIELDS);
+        if let Value::Mapping(body) = &mut body {
+            if let Some(Value::String(method)) = body.get_mut("method") {
+                *method = method.to_uppercase();
+            }
+            if let Some(Value::Mapping(headers)) = body.get_mut("headers") {
+                for (_, value) in headers.iter_mut() {
+                    if !value.is_string() {
+                        *value = yaml_scalar_string(value).into();
+                    }
+                }
+            }
+            if let Some(payload) = body.get_mut("body") {
+                if !payload.is_string() {
+                    let json = serde_json::to_value(&*payload)
+                        .map(|json| json.to_string())
+                        .unwrap_or_default();
+                    *payload = json.into();
+                }
+            }
+        }
+        body
+    }
+
+    fn mcp(&mut self, id: &str, params: &Value) -> Result<Value, NikaError> {
+        if let Some(shorthand) = params.as_str() {
+            let Some((server, tool)) = shorthand.split_once("::") else {
+                return Err(parse_error(format!(
+                    "task '{}': mcp shorthand must be `server::tool`, got '{}'",
+                    id, shorthand
+                )));
+            };
+            let mut body = Mapping::new();
+            body.insert("mcp".into(), server.into());
+            body.insert("tool".into(), tool.into());
+            return Ok(Value::Mapping(body));
+        }
+        Ok(self.fields(id, "mcp", params, "mcp", MCP_FIELDS))
+    }
+
+    fn function(&mut self, id: &str, params: &Value) -> Value {
+        let reference = match params {
+            Value::Mapping(params) => ["name", "path", "module"]
+                .iter()
+                .find_map(|key| params.get(*key).and_then(Value::as_str))
+                .unwrap_or("?")
+                .to_string(),
+            value => yaml_scalar_string(value),
+        };
+        self.note(
+            Some(id),
+            format!(
+                "function: '{}' has no verb equivalent; replaced by a failing exec: to port by hand (exec: or script:)",
+                reference
+            ),
+        );
+        let message = format!("function: {} was not migrated", reference);
+        let mut body = Mapping::new();
+        body.insert(
+            "command".into(),
+            format!("echo {} >&2; exit 1", shell_quote(&message)).into(),
+        );
+        Value::Mapping(body)
+    }
+
+    /// Turn `{{id...}}` references into `{{use.alias...}}` plus a `use:` entry
+    fn rewrite_refs(
+        &mut self,
+        id: &str,
+        value: Value,
+        uses: &mut BTreeMap<String, String>,
+    ) -> Value {
+        match value {
+            Value::String(text) => {
+                let mut unknown = Vec::new();
+                let rewritten = REF_RE.replace_all(&text, |caps: &regex::Captures| {
+                    let source = &caps[1];
+                    let Some(alias) = self.ids.get(source).filter(|_| source != id) else {
+                        unknown.push(caps[0].to_string());
+                        return caps[0].to_string();
+                    };
+                    let path = caps[2].strip_prefix(".output").unwrap_or(&caps[2]);
+                    uses.insert(alias.clone(), alias.clone());
+                    format!("{{{{use.{}{}}}}}", alias, path)
+                });
+                let rewritten = rewritten.into_owned();
+                for source in uses.values() {
+                    let edge = (source.clone(), self.ids[id].clone());
+                    if !self.edges.contains(&edge) {
+                        self.edges.push(edge);
+                    }
+                }
+                for reference in unknown {
+                    self.note(
+                        Some(id),
+                        format!("template {} is not a task reference; left as is", reference),
+                    );
+                }
+                Value::String(rewritten)
+            }
+            Value::Mapping(map) => Value::Mapping(
+                map.into_iter()
+                    .map(|(key, value)| (key, self.rewrite_refs(id, value, uses)))
+                    .collect(),
+            ),
+            Value::Sequence(items) => Value::Sequence(
+                items
+                    .into_iter()
+                    .map(|item| self.rewrite_refs(id, item, uses))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Legacy flows, plus edges implied by template references
+    fn flows(&mut self, legacy: Option<&Value>) -> Vec<Value> {
+        let mut flows = Vec::new();
+        let mut covered = BTreeSet::new();
+        for flow in legacy.and_then(Value::as_sequence).into_iter().flatten() {
+            let (Some(source), Some(target)) = (flow.get("source"), flow.get("target")) else {
+                self.note(None, "flow without source and target dropped".to_string());
+                continue;
+            };
+            let (source, target) = (self.rename(source), self.rename(target));
+            for from in endpoints(&source) {
+                for to in endpoints(&target) {
+                    covered.insert((from.clone(), to));
+                }
+            }
+            let mut edge = Mapping::new();
+            edge.insert("source".into(), source);
+            edge.insert("target".into(), target);
+            flows.push(Value::Mapping(edge));
+        }
+        for (source, target) in &self.edges {
+            if covered.insert((source.clone(), target.clone())) {
+                let mut edge = Mapping::new();
+                edge.insert("source".into(), source.as_str().into());
+                edge.insert("target".into(), target.as_str().into());
+                flows.push(Value::Mapping(edge));
+            }
+        }
+        flows
+    }
+
+    /// Flow endpoint with verb-format ids
+    fn rename(&self, endpoint: &Value) -> Value {
+        match endpoint {
+            Value::String(id) => self.ids.get(id).unwrap_or(id).as_str().into(),
+            Value::Sequence(ids) => Value::Sequence(ids.iter().map(|id| self.rename(id)).collect()),
+            other => other.clone(),
+        }
+    }
+}
+
+/// Verb-format task id: lowercase letters, digits and `_`, starting with a letter
+fn verb_id(id: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '_') => c,
+            _ => '_',
+        })
+        .collect();
+    if id.starts_with(|c: char| c.is_ascii_lowercase()) {
+        id
+    } else {
+        format!("task_{}", id)
+    }
+}
+
+const LLM_FIELDS: &[(&str, &str)] = &[
+    ("prompt", "prompt"),
+    ("provider", "provider"),
+    ("model", "model"),
+    ("maxTokens", "max_tokens"),
+    ("max_tokens", "max_tokens"),
+];
+
+const AGENT_FIELDS: &[(&str, &str)] = &[
+    ("prompt", "prompt"),
+    ("provider", "provider"),
+    ("model", "model"),
+    ("systemPrompt", "system"),
+    ("system", "system"),
+    ("maxTurns", "max_turns"),
+    ("max_turns", "max_turns"),
+    ("mcp", "mcp"),
+];
+
+const SHELL_FIELDS: &[(&str, &str)] = &[
+    ("command", "command"),
+    ("cmd", "command"),
+    ("timeout", "timeout"),
+    ("cwd", "cwd"),
+];
+
+const HTTP_FIELDS: &[(&str, &str)] = &[
+    ("url", "url"),
+    ("method", "method"),
+    ("headers", "headers"),
+    ("body", "body"),
+];
+
+const MCP_FIELDS: &[(&str, &str)] = &[
+    ("server", "mcp"),
+    ("mcp", "mcp"),
+    ("tool", "tool"),
+    ("params", "params"),
+    ("args", "params"),
+    ("arguments", "params"),
+    ("resource", "resource"),
+];
+
+fn endpoints(value: &Value) -> Vec<String> {
+    match value {
+        Value::Sequence(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        value => value.as_str().map(str::to_string).into_iter().collect(),
+    }
+}
+
+fn yaml_scalar_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+    }
+}
+
+/// Single-quote for `sh`
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{TaskAction, Workflow};
+
+    const LEGACY: &str = r#"
+workflow: release-notes
+description: Drafts release notes.
+mainAgent:
+  model: claude-sonnet-4
+  systemPrompt: You are a release manager.
+  temperature: 0.2
+tasks:
+  - id: git-log
+    shell:
+      command: git log --oneline -20
+      cwd: /srv/app
+  - id: issues
+    http:
+      url: https://api.example.com/issues
+      method: post
+      body: { state: closed }
+  - id: graph
+    mcp: novanet::novanet_describe
+  - id: draft
+    llm:
+      prompt: "Summarize {{git-log.output}} and {{issues.output.items}}"
+      temperature: 0.7
+  - id: review
+    agent: "Review {{draft}}"
+  - id: polish
+    subagent: { prompt: "Polish {{draft}}", maxTurns: 3 }
+  - id: publish
+    function: scripts/publish.ts
+flows:
+  - source: [git-log, issues]
+    target: draft
+"#;
+
+    fn migrated() -> (Workflow, KeywordMigration) {
+        let migration = migrate_keyword(LEGACY).unwrap();
+        let workflow: Workflow = serde_yaml::from_str(&migration.yaml).unwrap();
+        (workflow, migration)
+    }
+
+    fn notes_for(migration: &KeywordMigration, task: &str) -> Vec<String> {
+        migration
+            .notes
+            .iter()
+            .filter(|note| note.task.as_deref() == Some(task))
+            .map(|note| note.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_keyword_format_detection() {
+        assert!(is_keyword_format(LEGACY));
+        assert!(!is_keyword_format(&migrate_keyword(LEGACY).unwrap().yaml));
+        assert!(!is_keyword_format(
+            "schema: nika/workflow@0.5\ntasks:\n  - id: a\n    agent: { prompt: hi }\n"
+        ));
+        assert!(!is_keyword_format("tasks: [\n"));
+    }
+
+    #[test]
+    fn test_keywords_become_verbs() {
+        let (workflow, _) = migrated();
+        assert_eq!(workflow.schema, SCHEMA_V05);
+        assert_eq!(workflow.name.as_deref(), Some("release-notes"));
+        assert_eq!(workflow.model.as_deref(), Some("claude-sonnet-4"));
+
+        let action = |id: &str| &workflow.tasks.iter().find(|t| t.id == id).unwrap().action;
+        assert!(matches!(
+            action("git_log"),
+            TaskAction::Exec { exec } if exec.command == "cd '/srv/app' && git log --oneline -20"
+        ));
+        assert!(matches!(
+            action("issues"),
+            TaskAction::Fetch { fetch }
+                if fetch.method == "POST" && fetch.body.as_deref() == Some(r#"{"state":"closed"}"#)
+        ));
+        assert!(matches!(
+            action("graph"),
+            TaskAction::Invoke { invoke }
+                if invoke.mcp == "novanet" && invoke.tool.as_deref() == Some("novanet_describe")
+        ));
+        assert!(matches!(
+            action("draft"),
+            TaskAction::Infer { infer }
+                if infer.prompt == "Summarize {{use.git_log}} and {{use.issues.items}}"
+        ));
+        assert!(matches!(
+            action("review"),
+            TaskAction::Agent { agent }
+                if agent.system.as_deref() == Some("You are a release manager.")
+        ));
+        assert!(matches!(
+            action("polish"),
+            TaskAction::Agent { agent } if agent.system.is_none() && agent.max_turns == Some(3)
+        ));
+    }
+
+    #[test]
+    fn test_references_become_bindings_and_flows() {
+        let (workflow, migration) = migrated();
+        let draft = workflow.tasks.iter().find(|t| t.id == "draft").unwrap();
+        assert!(draft.use_wiring.as_ref().unwrap().contains_key("issues"));
+
+        // git_log/issues → draft were declared; draft → review and polish are added
+        assert!(migration.yaml.contains("target: review }"));
+        assert!(migration.yaml.contains("target: polish }"));
+        assert_eq!(workflow.flows.len(), 3);
+        assert!(migration
+            .yaml
+            .starts_with("# Migrated from the v4.x keyword format"));
+        assert!(migration.yaml.contains("# Drafts release notes."));
+    }
+
+    #[test]
+    fn test_unsupported_constructs_are_reported() {
+        let (_, migration) = migrated();
+        assert!(migration
+            .notes
+            .iter()
+            .any(|note| note.task.is_none() && note.message.contains("mainAgent.temperature")));
+        assert_eq!(
+            notes_for(&migration, "draft"),
+            vec!["llm.temperature has no equivalent"]
+        );
+        assert!(notes_for(&migration, "review")[0].contains("own context"));
+        assert!(notes_for(&migration, "polish").is_empty());
+        assert!(notes_for(&migration, "publish")[0].contains("scripts/publish.ts"));
+        assert_eq!(
+            notes_for(&migration, "git-log"),
+            vec!["renamed to 'git_log'"]
+        );
+        assert!(migration.yaml.contains("exit 1"));
+    }
+
+    #[test]
+    fn test_rejects_tasks_without_one_keyword() {
+        let yaml = "tasks:\n  - id: a\n    llm: hi\n    shell: echo hi\n";
+        let err = migrate_keyword(yaml).unwrap_err();
+        assert!(err.to_string().contains("exactly one"), "{}", err);
+
+        let err = migrate_keyword("tasks:\n  - id: a\n    mcp: novanet\n").unwrap_err();
+        assert!(err.to_string().contains("server::tool"), "{}", err);
+    }
+}
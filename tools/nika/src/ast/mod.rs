@@ -1,34 +1,110 @@
 //! AST Module - Abstract Syntax Tree for YAML workflows
 //!
 //! Contains parsed Rust types from YAML workflow definitions:
-//! - `workflow`: Workflow, Task, Flow, FlowEndpoint
+//! - `workflow`: Workflow, Task, Flow, FlowEndpoint, Triggers
 //! - `action`: TaskAction, InferParams, ExecParams, FetchParams
 //! - `invoke`: InvokeParams (v0.2 - MCP integration)
 //! - `agent`: AgentParams (v0.2 - Agentic execution)
 //! - `output`: OutputPolicy, OutputFormat
+//! - `reduce`: ReduceParams, ReduceStrategy (v0.7 - array aggregation)
+//! - `approve`: ApproveParams, ApprovalDecision (v0.7 - human-in-the-loop)
+//! - `chunk`: ChunkParams, ChunkBy (v0.7 - split text for for_each fan-out)
+//! - `context_pool`: ContextPoolSpec, PoolExhausted (v0.7 - token budget shared by LLM tasks)
+//! - `container`: ExecContainer, ContainerRuntime (v0.7 - exec in docker/podman)
+//! - `dedupe`: DedupeParams, DedupeBy (v0.7 - drop near-duplicate items)
+//! - `detect_lang`: DetectLangParams (v0.7 - offline language detection)
+//! - `embed`: EmbedParams, RecallParams (v0.7 - embeddings and vector memory)
+//! - `fetch`: FetchRetry, FetchPaginate, FetchGraphql (v0.7 - fetch retries, pagination and GraphQL)
+//! - `inputs`: Inputs, InputSpec, InputType (v0.7 - typed run-time parameters)
+//! - `injection`: InjectionPolicy, InjectionAction (v0.7 - untrusted content heuristics)
+//! - `migrate`: migrate_keyword, is_keyword_format (v0.7 - v4.x keyword-format importer)
+//! - `moderate`: ModerateSpec, ModerationPolicy (v0.7 - content safety gate)
+//! - `retrieve`: RetrieveParams, RetrieveMode (v0.7 - RAG over local files)
+//! - `rows`: RowsParams (v0.7 - filter, group, sort and limit row arrays)
+//! - `sandbox`: ExecSandbox (v0.7 - exec working dir, env and resource limits)
+//! - `script`: ScriptParams (v0.7 - sandboxed Rhai snippets)
+//! - `sheet`: ImportParams, ExportParams, SheetFormat (v0.7 - CSV/XLSX in and out)
+//! - `transcribe`: TranscribeParams (v0.7 - speech to text)
+//! - `transform`: TransformSpec, TransformStep (v0.7 - output post-processing)
+//! - `translate`: TranslateParams (v0.7 - translation with a glossary)
+//! - `validate`: ValidateParams, ValidateRule (v0.7 - data quality gate)
+//! - `ws`: WsParams (v0.7 - WebSocket message collection)
 //!
 //! These types represent the "what" - static structure parsed from YAML.
 //! For runtime execution, see the `runtime` module.
 
 mod action;
 mod agent;
+mod approve;
+pub mod chunk;
+pub mod container;
+pub mod context_pool;
 pub mod decompose;
+pub mod dedupe;
+pub mod detect_lang;
+pub mod embed;
+pub mod fetch;
+mod format;
+pub mod injection;
+pub mod inputs;
 mod invoke;
+mod migrate;
+pub mod moderate;
 mod output;
+pub mod overrides;
+mod reduce;
+pub mod retrieve;
+pub mod rows;
+pub mod sandbox;
 pub mod schema_validator;
+pub mod script;
+pub mod sheet;
+pub mod transcribe;
+mod transform;
+pub mod translate;
+mod validate;
 mod workflow;
+pub mod ws;
 
 // Re-export all public types
-pub use action::{ExecParams, FetchParams, InferParams, TaskAction};
+pub use action::{ExecParams, FetchParams, HedgeSpec, InferParams, TaskAction};
 // AgentParams is defined in agent.rs (v0.2 - Agentic execution)
-pub use agent::AgentParams;
+pub use agent::{AgentContext, AgentLimits, AgentParams};
+pub use approve::{ApprovalDecision, ApproveParams};
+pub use chunk::{ChunkBy, ChunkParams};
+pub use container::{ContainerRuntime, ExecContainer};
+pub use context_pool::{ContextPoolSpec, PoolExhausted};
+pub use dedupe::{DedupeBy, DedupeParams};
+pub use detect_lang::DetectLangParams;
+pub use embed::{EmbedParams, RecallParams};
+pub use fetch::{FetchGraphql, FetchPaginate, FetchRetry};
+pub use format::format_workflow;
+pub use injection::{InjectionAction, InjectionPolicy};
+pub use inputs::{InputSpec, InputType, Inputs};
 // InvokeParams is defined in invoke.rs and re-exported here
 // (also used by action.rs for TaskAction::Invoke variant)
 pub use invoke::InvokeParams;
+pub use migrate::{is_keyword_format, migrate_keyword, KeywordMigration, MigrationNote};
+pub use moderate::{
+    ModerateSpec, ModerationClassifier, ModerationPolicy, ModerationStage, CATEGORIES,
+};
 pub use output::{OutputFormat, OutputPolicy};
+pub use overrides::apply_overrides;
+pub use reduce::{ReduceParams, ReduceStrategy};
+pub use retrieve::{RetrieveMode, RetrieveParams};
+pub use rows::RowsParams;
+pub use sandbox::ExecSandbox;
+pub use script::ScriptParams;
+pub use sheet::{ExportParams, ImportParams, SheetFormat};
+pub use transcribe::TranscribeParams;
+pub use transform::{TransformSpec, TransformStep};
+pub use translate::TranslateParams;
+pub use validate::{OnFail, ValidateParams, ValidateRule, ValueType};
 pub use workflow::{
-    Flow, FlowEndpoint, McpConfigInline, Task, Workflow, SCHEMA_V01, SCHEMA_V02, SCHEMA_V03,
-    SCHEMA_V04, SCHEMA_V05,
+    Flow, FlowEndpoint, McpConfigInline, StateSpec, Task, TaskDefaults, Triggers, Workflow,
+    DEFAULT_WATCH_DEBOUNCE_MS, INPUTS_TASK_ID, SCHEMA_V01, SCHEMA_V02, SCHEMA_V03, SCHEMA_V04,
+    SCHEMA_V05, STATE_TASK_ID, TRIGGER_TASK_ID,
 };
+pub use ws::WsParams;
 // DecomposeSpec is defined in decompose.rs (v0.5 - Runtime DAG expansion)
 pub use decompose::{DecomposeSpec, DecomposeStrategy};
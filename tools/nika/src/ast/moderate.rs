@@ -0,0 +1,205 @@
+//! Moderation - content safety gate for task inputs and outputs (v0.7)
+//!
+//! `moderate:` classifies a task's resolved `use:` values, its output, or
+//! both, and applies a policy when a category score reaches its threshold:
+//!
+//! ```yaml
+//! moderate:
+//!   on: [input, output]          # default: [output]
+//!   classifier: openai           # local (default) | openai | mistral
+//!   thresholds:                  # score in 0..1 that trips a category (default 0.5)
+//!     violence: 0.8
+//!     self_harm: 0.2
+//!   policy: redact               # block (default) | flag | redact
+//! ```
+//!
+//! Classifiers live in `runtime::moderation`.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Categories every classifier reports, in summary order
+pub const CATEGORIES: &[&str] = &[
+    "hate",
+    "harassment",
+    "self_harm",
+    "sexual",
+    "violence",
+    "illicit",
+];
+
+/// Threshold for categories not listed in `thresholds:`
+pub const DEFAULT_THRESHOLD: f64 = 0.5;
+
+/// Which side of the task is classified
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationStage {
+    /// Resolved `use:` values, before the task runs
+    Input,
+    /// The task output, before it is stored
+    Output,
+}
+
+impl ModerationStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Output => "output",
+        }
+    }
+}
+
+/// Where scores come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationClassifier {
+    /// Built-in lexicon, no network (default)
+    #[default]
+    Local,
+    /// OpenAI moderation endpoint (`OPENAI_API_KEY`)
+    Openai,
+    /// Mistral moderation endpoint (`MISTRAL_API_KEY`)
+    Mistral,
+}
+
+impl ModerationClassifier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Openai => "openai",
+            Self::Mistral => "mistral",
+        }
+    }
+}
+
+/// What to do with content over a threshold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModerationPolicy {
+    /// Fail the task with NIKA-066 (default)
+    #[default]
+    Block,
+    /// Keep the content and record a `ModerationChecked` event
+    Flag,
+    /// Replace the offending text with `[redacted]`
+    Redact,
+}
+
+impl ModerationPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Block => "block",
+            Self::Flag => "flag",
+            Self::Redact => "redact",
+        }
+    }
+}
+
+/// Task-level `moderate:` block
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModerateSpec {
+    #[serde(default = "default_stages")]
+    pub on: Vec<ModerationStage>,
+    #[serde(default)]
+    pub classifier: ModerationClassifier,
+    /// Provider model, e.g. `omni-moderation-latest`
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Per-category thresholds; unlisted categories use 0.5
+    #[serde(default)]
+    pub thresholds: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub policy: ModerationPolicy,
+}
+
+fn default_stages() -> Vec<ModerationStage> {
+    vec![ModerationStage::Output]
+}
+
+impl Default for ModerateSpec {
+    fn default() -> Self {
+        Self {
+            on: default_stages(),
+            classifier: ModerationClassifier::default(),
+            model: None,
+            thresholds: BTreeMap::new(),
+            policy: ModerationPolicy::default(),
+        }
+    }
+}
+
+impl ModerateSpec {
+    /// Whether `stage` is moderated
+    pub fn covers(&self, stage: ModerationStage) -> bool {
+        self.on.contains(&stage)
+    }
+
+    /// Score at which `category` trips
+    pub fn threshold(&self, category: &str) -> f64 {
+        self.thresholds
+            .get(category)
+            .copied()
+            .unwrap_or(DEFAULT_THRESHOLD)
+    }
+
+    /// Check the settings, returning the first problem found
+    pub fn check(&self) -> Result<(), String> {
+        if self.on.is_empty() {
+            return Err("on: must list input, output or both".to_string());
+        }
+        if self.classifier == ModerationClassifier::Local && self.model.is_some() {
+            return Err("model: needs classifier openai or mistral".to_string());
+        }
+        for (category, threshold) in &self.thresholds {
+            if !CATEGORIES.contains(&category.as_str()) {
+                return Err(format!(
+                    "unknown category '{}' (expected one of: {})",
+                    category,
+                    CATEGORIES.join(", ")
+                ));
+            }
+            if !(0.0..=1.0).contains(threshold) {
+                return Err(format!(
+                    "threshold for '{}' must be between 0 and 1, got {}",
+                    category, threshold
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_check_moderate() {
+        let yaml = r#"
+on: [input, output]
+classifier: openai
+thresholds:
+  violence: 0.8
+policy: redact
+"#;
+        let spec: ModerateSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(spec.covers(ModerationStage::Input));
+        assert_eq!(spec.threshold("violence"), 0.8);
+        assert_eq!(spec.threshold("hate"), DEFAULT_THRESHOLD);
+        assert_eq!(spec.policy, ModerationPolicy::Redact);
+        assert!(spec.check().is_ok());
+
+        let defaults: ModerateSpec = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(defaults, ModerateSpec::default());
+        assert!(!defaults.covers(ModerationStage::Input));
+
+        let unknown: ModerateSpec = serde_yaml::from_str("thresholds: { gore: 0.1 }").unwrap();
+        assert!(unknown.check().unwrap_err().contains("gore"));
+        let range: ModerateSpec = serde_yaml::from_str("thresholds: { hate: 2 }").unwrap();
+        assert!(range.check().is_err());
+        assert!(serde_yaml::from_str::<ModerateSpec>("action: block").is_err());
+    }
+}
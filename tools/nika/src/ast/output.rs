@@ -1,26 +1,131 @@
 //! Output Policy - format and validation configuration (v0.1)
 //!
 //! Defines how task output should be formatted and validated:
-//! - `OutputFormat`: Text (default) or JSON
+//! - `OutputFormat`: codec name - text (default), json, yaml, csv,
+//!   markdown-table, xml, or a custom codec (v0.7, see `crate::codec`)
 //! - `OutputPolicy`: Format + optional JSON Schema validation
+//! - `stamp`: Provenance metadata stamping (v0.7)
+//! - Inheritance: fields a task leaves out come from the workflow's
+//!   `defaults:`, and `output: false` opts out of them (v0.7)
 
-use serde::Deserialize;
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
 
 /// Output policy configuration
-#[derive(Debug, Clone, Deserialize, Default)]
+///
+/// A field left as `None` is inherited from the workflow's `defaults:`.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct OutputPolicy {
-    /// Output format (text or json)
-    #[serde(default)]
-    pub format: OutputFormat,
+    /// Output format (codec name, text when unset)
+    pub format: Option<OutputFormat>,
 
     /// Optional JSON Schema path for validation
-    #[serde(default)]
     pub schema: Option<String>,
+
+    /// Stamp provenance metadata into the output (v0.7)
+    ///
+    /// Markdown front-matter, HTML trailer comment, or SVG XMP packet.
+    pub stamp: Option<bool>,
+
+    /// `output: false`: no policy, workflow defaults included (v0.7)
+    pub disabled: bool,
+}
+
+/// Format of a policy that doesn't set one
+static TEXT: OutputFormat = OutputFormat::Text;
+
+impl OutputPolicy {
+    /// Output format, text when unset
+    pub fn format(&self) -> &OutputFormat {
+        self.format.as_ref().unwrap_or(&TEXT)
+    }
+
+    /// Whether the output is stamped with provenance metadata
+    pub fn stamps(&self) -> bool {
+        self.stamp.unwrap_or(false)
+    }
+
+    /// Whether this is `output: false`
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// Fill the fields left unset from `defaults` (v0.7)
+    pub fn inherit(&mut self, defaults: &OutputPolicy) {
+        if self.disabled {
+            return;
+        }
+        if self.format.is_none() {
+            self.format = defaults.format.clone();
+        }
+        if self.schema.is_none() {
+            self.schema = defaults.schema.clone();
+        }
+        if self.stamp.is_none() {
+            self.stamp = defaults.stamp;
+        }
+    }
+
+    /// Whether any field is set
+    pub fn is_set(&self) -> bool {
+        self.format.is_some() || self.schema.is_some() || self.stamp.is_some()
+    }
+}
+
+/// An `output:` block as written
+#[derive(Deserialize)]
+struct OutputPolicyFields {
+    #[serde(default)]
+    format: Option<OutputFormat>,
+    #[serde(default)]
+    schema: Option<String>,
+    #[serde(default)]
+    stamp: Option<bool>,
+}
+
+impl<'de> Deserialize<'de> for OutputPolicy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PolicyVisitor;
+
+        impl<'de> Visitor<'de> for PolicyVisitor {
+            type Value = OutputPolicy;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an output block or `false`")
+            }
+
+            fn visit_bool<E: de::Error>(self, value: bool) -> Result<Self::Value, E> {
+                if value {
+                    return Err(E::custom(
+                        "`output: true` is not valid; use an output block, or `false` to skip the workflow defaults",
+                    ));
+                }
+                Ok(OutputPolicy {
+                    disabled: true,
+                    ..OutputPolicy::default()
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let fields =
+                    OutputPolicyFields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                Ok(OutputPolicy {
+                    format: fields.format,
+                    schema: fields.schema,
+                    stamp: fields.stamp,
+                    disabled: false,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(PolicyVisitor)
+    }
 }
 
-/// Output format enum
-#[derive(Debug, Clone, Deserialize, Default, PartialEq)]
-#[serde(rename_all = "lowercase")]
+/// Output format: the name of the codec that parses the output
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum OutputFormat {
     /// Raw text output (default)
     #[default]
@@ -28,6 +133,63 @@ pub enum OutputFormat {
 
     /// JSON parsed output
     Json,
+
+    /// YAML parsed output (v0.7)
+    Yaml,
+
+    /// CSV rows as an array of objects (v0.7)
+    Csv,
+
+    /// Markdown pipe table as an array of objects (v0.7)
+    MarkdownTable,
+
+    /// XML document as a nested object (v0.7)
+    Xml,
+
+    /// Codec registered by an embedder (v0.7)
+    Custom(String),
+}
+
+impl OutputFormat {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Csv => "csv",
+            Self::MarkdownTable => "markdown-table",
+            Self::Xml => "xml",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Whether output is parsed into a value rather than kept as text
+    pub fn is_structured(&self) -> bool {
+        *self != Self::Text
+    }
+}
+
+impl From<&str> for OutputFormat {
+    fn from(name: &str) -> Self {
+        match name {
+            "text" => Self::Text,
+            "json" => Self::Json,
+            "yaml" => Self::Yaml,
+            "csv" => Self::Csv,
+            "markdown-table" => Self::MarkdownTable,
+            "xml" => Self::Xml,
+            custom => Self::Custom(custom.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for OutputFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Custom names are checked against the codec registry at run time,
+        // since embedders may register codecs after the workflow is parsed
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::from(name.as_str()))
+    }
 }
 
 #[cfg(test)]
@@ -38,7 +200,7 @@ mod tests {
     fn parse_text_format() {
         let yaml = "format: text";
         let policy: OutputPolicy = serde_yaml::from_str(yaml).unwrap();
-        assert_eq!(policy.format, OutputFormat::Text);
+        assert_eq!(*policy.format(), OutputFormat::Text);
         assert!(policy.schema.is_none());
     }
 
@@ -49,13 +211,57 @@ mod tests {
             schema: .nika/schemas/result.json
         "#;
         let policy: OutputPolicy = serde_yaml::from_str(yaml).unwrap();
-        assert_eq!(policy.format, OutputFormat::Json);
+        assert_eq!(*policy.format(), OutputFormat::Json);
         assert_eq!(policy.schema.as_deref(), Some(".nika/schemas/result.json"));
     }
 
+    #[test]
+    fn parse_stamp() {
+        let policy: OutputPolicy = serde_yaml::from_str("stamp: true").unwrap();
+        assert!(policy.stamps());
+        assert!(!OutputPolicy::default().stamps());
+    }
+
+    #[test]
+    fn parse_codec_formats() {
+        let policy: OutputPolicy = serde_yaml::from_str("format: markdown-table").unwrap();
+        assert_eq!(*policy.format(), OutputFormat::MarkdownTable);
+        assert_eq!(policy.format().as_str(), "markdown-table");
+
+        let policy: OutputPolicy = serde_yaml::from_str("format: toml").unwrap();
+        assert_eq!(*policy.format(), OutputFormat::Custom("toml".into()));
+        assert!(policy.format().is_structured());
+        assert!(!OutputFormat::Text.is_structured());
+    }
+
+    #[test]
+    fn inherit_fills_unwritten_fields() {
+        let defaults: OutputPolicy =
+            serde_yaml::from_str("{ format: json, schema: a.json }").unwrap();
+        let mut policy: OutputPolicy = serde_yaml::from_str("schema: b.json").unwrap();
+        policy.inherit(&defaults);
+        assert_eq!(*policy.format(), OutputFormat::Json);
+        assert_eq!(policy.schema.as_deref(), Some("b.json"));
+
+        // Built in code: only the fields left `None` are inherited
+        let mut built = OutputPolicy {
+            format: Some(OutputFormat::Yaml),
+            ..Default::default()
+        };
+        built.inherit(&defaults);
+        assert_eq!(*built.format(), OutputFormat::Yaml);
+        assert_eq!(built.schema.as_deref(), Some("a.json"));
+
+        let mut disabled: OutputPolicy = serde_yaml::from_str("false").unwrap();
+        disabled.inherit(&defaults);
+        assert!(disabled.is_disabled());
+        assert!(!disabled.is_set());
+        assert!(serde_yaml::from_str::<OutputPolicy>("true").is_err());
+    }
+
     #[test]
     fn default_is_text() {
         let policy = OutputPolicy::default();
-        assert_eq!(policy.format, OutputFormat::Text);
+        assert_eq!(*policy.format(), OutputFormat::Text);
     }
 }
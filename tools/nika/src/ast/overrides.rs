@@ -0,0 +1,263 @@
+//! CLI Overrides - `--set path=value` patches for workflow YAML (v0.7)
+//!
+//! Overrides are applied to the parsed YAML document before schema
+//! validation, so patched workflows are validated exactly like edited ones.
+//!
+//! Paths are dotted keys. Sequence items are addressed by index or by `id`,
+//! and task paths reach through to the verb block:
+//!
+//! ```text
+//! model=gpt-4o                        # workflow default model
+//! tasks.summarize.model=gpt-4o        # → tasks[id=summarize].infer.model
+//! tasks.0.exec.command="ls -la"       # by index, explicit verb block
+//! tasks.fetch_docs.headers.X-Debug=1  # nested maps are created on demand
+//! ```
+//!
+//! Values are parsed as YAML scalars or flow lists (`3` is a number, `true`
+//! a bool, `[a, b]` a list); anything else is kept as a string.
+
+use std::borrow::Cow;
+
+use serde_yaml::{Mapping, Value};
+
+use crate::ast::TaskAction;
+use crate::error::NikaError;
+
+/// Task-level keys (everything else on a task is routed into its verb block)
+const TASK_KEYS: &[&str] = &[
+    "id",
+    "use",
+    "output",
+    "transform",
+    "moderate",
+    "decompose",
+    "for_each",
+    "as",
+    "concurrency",
+    "fail_fast",
+    "state",
+    "tags",
+    "template_engine",
+];
+
+/// Verbs with a shorthand string form and the field it maps to
+const SHORTHAND: &[(&str, &str)] = &[("infer", "prompt"), ("exec", "command"), ("script", "code")];
+
+/// Apply `path=value` overrides to workflow YAML
+///
+/// Returns the input unchanged when there are no overrides.
+pub fn apply_overrides<'a>(yaml: &'a str, overrides: &[String]) -> Result<Cow<'a, str>, NikaError> {
+    if overrides.is_empty() {
+        return Ok(Cow::Borrowed(yaml));
+    }
+
+    let mut doc: Value = serde_yaml::from_str(yaml)?;
+    for raw in overrides {
+        let (path, value) = raw
+            .split_once('=')
+            .ok_or_else(|| invalid(raw, "expected path=value"))?;
+        let path = path.trim();
+        if path.is_empty() || path.split('.').any(str::is_empty) {
+            return Err(invalid(path, "empty path segment"));
+        }
+        let value = match serde_yaml::from_str(value) {
+            // `key: value` text is a string here, not a nested map
+            Ok(Value::Mapping(_)) | Err(_) => Value::String(value.into()),
+            Ok(parsed) => parsed,
+        };
+        set_path(&mut doc, path, value)?;
+    }
+
+    Ok(Cow::Owned(serde_yaml::to_string(&doc)?))
+}
+
+fn set_path(doc: &mut Value, path: &str, value: Value) -> Result<(), NikaError> {
+    let segments: Vec<&str> = path.split('.').collect();
+    let mut node = doc;
+
+    for (i, segment) in segments.iter().enumerate() {
+        // tasks.<id>.<field>: fields that aren't task keys live in the verb block
+        let is_task = i == 2 && segments[0] == "tasks";
+        if is_task && !TASK_KEYS.contains(segment) && !TaskAction::VERBS.contains(segment) {
+            node = verb_block(node, path)?;
+        }
+
+        if node.is_null() {
+            *node = Value::Mapping(Mapping::new());
+        }
+        node = match node {
+            Value::Sequence(items) => {
+                let found = match segment.parse::<usize>() {
+                    Ok(idx) => items.get_mut(idx),
+                    Err(_) => items
+                        .iter_mut()
+                        .find(|item| item.get("id").and_then(Value::as_str) == Some(segment)),
+                };
+                found.ok_or_else(|| invalid(path, &format!("no item '{}'", segment)))?
+            }
+            Value::Mapping(map) => map
+                .entry(Value::String(segment.to_string()))
+                .or_insert(Value::Null),
+            _ => {
+                return Err(invalid(
+                    path,
+                    &format!("'{}' is not a map or list", segments[..i].join(".")),
+                ))
+            }
+        };
+    }
+
+    *node = value;
+    Ok(())
+}
+
+/// Find a task's verb block, expanding shorthand (`infer: "..."`) to a map
+fn verb_block<'v>(task: &'v mut Value, path: &str) -> Result<&'v mut Value, NikaError> {
+    let map = task
+        .as_mapping_mut()
+        .ok_or_else(|| invalid(path, "task is not a map"))?;
+    let verb = TaskAction::VERBS
+        .iter()
+        .find(|verb| map.contains_key(**verb))
+        .ok_or_else(|| invalid(path, "task has no verb"))?;
+    let block = map.get_mut(*verb).expect("verb key checked above");
+
+    if let Value::String(s) = block {
+        let (_, field) = SHORTHAND
+            .iter()
+            .find(|(v, _)| v == verb)
+            .ok_or_else(|| invalid(path, "verb block is not a map"))?;
+        let mut expanded = Mapping::new();
+        expanded.insert(Value::String(field.to_string()), Value::String(s.clone()));
+        *block = Value::Mapping(expanded);
+    }
+    Ok(block)
+}
+
+fn invalid(path: &str, reason: &str) -> NikaError {
+    NikaError::InvalidOverride {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Workflow;
+
+    const YAML: &str = r#"
+schema: "nika/workflow@0.5"
+provider: claude
+tasks:
+  - id: summarize
+    infer: "Summarize this"
+  - id: list
+    exec:
+      command: "ls"
+"#;
+
+    fn patched(overrides: &[&str]) -> Workflow {
+        let overrides: Vec<String> = overrides.iter().map(|s| s.to_string()).collect();
+        let yaml = apply_overrides(YAML, &overrides).unwrap();
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_no_overrides_borrows() {
+        assert!(matches!(apply_overrides(YAML, &[]), Ok(Cow::Borrowed(_))));
+    }
+
+    #[test]
+    fn test_top_level_override() {
+        let wf = patched(&["provider=openai", "model=gpt-4o"]);
+        assert_eq!(wf.provider, "openai");
+        assert_eq!(wf.model.as_deref(), Some("gpt-4o"));
+    }
+
+    #[test]
+    fn test_task_field_routes_into_shorthand_verb() {
+        let wf = patched(&["tasks.summarize.model=gpt-4o"]);
+        let TaskAction::Infer { infer } = &wf.tasks[0].action else {
+            panic!("expected infer");
+        };
+        assert_eq!(infer.prompt, "Summarize this");
+        assert_eq!(infer.model.as_deref(), Some("gpt-4o"));
+    }
+
+    #[test]
+    fn test_task_by_index_and_typed_values() {
+        let wf = patched(&["tasks.1.command=pwd", "tasks.list.concurrency=4"]);
+        let TaskAction::Exec { exec } = &wf.tasks[1].action else {
+            panic!("expected exec");
+        };
+        assert_eq!(exec.command, "pwd");
+        assert_eq!(wf.tasks[1].concurrency, Some(4));
+    }
+
+    #[test]
+    fn test_later_verbs_and_task_keys() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: tr
+    translate:
+      source: "Hello"
+      to: fr
+  - id: calc
+    script: "1 + 1"
+"#;
+        let overrides: Vec<String> = [
+            "tasks.tr.to=de",
+            "tasks.tr.transform.regex=(\\w+)",
+            "tasks.calc.timeout_ms=50",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let wf: Workflow =
+            serde_yaml::from_str(&apply_overrides(yaml, &overrides).unwrap()).unwrap();
+
+        let TaskAction::Translate { translate } = &wf.tasks[0].action else {
+            panic!("expected translate");
+        };
+        assert_eq!(translate.to, "de");
+        assert_eq!(
+            wf.tasks[0].transform.as_ref().unwrap().steps[0].name(),
+            "regex"
+        );
+        let TaskAction::Script { script } = &wf.tasks[1].action else {
+            panic!("expected script");
+        };
+        assert_eq!(script.code.as_deref(), Some("1 + 1"));
+        assert_eq!(script.timeout_ms, Some(50));
+    }
+
+    #[test]
+    fn test_every_verb_takes_task_fields() {
+        for verb in TaskAction::VERBS {
+            let yaml = format!("tasks:\n  - id: t\n    {}: {{}}\n", verb);
+            let patched = apply_overrides(&yaml, &["tasks.t.model=x".to_string()]).unwrap();
+            let doc: Value = serde_yaml::from_str(&patched).unwrap();
+            assert_eq!(
+                doc["tasks"][0][*verb]["model"],
+                Value::from("x"),
+                "{}",
+                verb
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_task_is_error() {
+        let err = apply_overrides(YAML, &["tasks.missing.model=x".to_string()]).unwrap_err();
+        assert_eq!(err.code(), "NIKA-006");
+        assert!(err.to_string().contains("no item 'missing'"));
+    }
+
+    #[test]
+    fn test_missing_equals_is_error() {
+        let err = apply_overrides(YAML, &["model".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("expected path=value"));
+    }
+}
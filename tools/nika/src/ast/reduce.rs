@@ -0,0 +1,113 @@
+//! Reduce Action - combine an array into a single output (v0.7)
+//!
+//! The `reduce:` verb takes an array binding (typically the aggregated
+//! output of a `for_each` or `decompose` task) and folds it into one value,
+//! either structurally or with an LLM summarization prompt.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: combine
+//!     use:
+//!       parts: summarize_each
+//!     reduce:
+//!       source: $parts
+//!       strategy: summarize
+//!       prompt: "Merge these page summaries into one overview"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// How array items are combined
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReduceStrategy {
+    /// Flatten nested arrays into one array
+    #[default]
+    Concat,
+    /// Deep-merge objects left to right (later keys win)
+    Merge,
+    /// Join items as text with a separator
+    Join,
+    /// Ask the LLM to combine the items
+    Summarize,
+}
+
+impl ReduceStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Concat => "concat",
+            Self::Merge => "merge",
+            Self::Join => "join",
+            Self::Summarize => "summarize",
+        }
+    }
+}
+
+/// Reduce action parameters (v0.7)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReduceParams {
+    /// Array binding expression (e.g., "$parts", "$task.items", "{{use.parts}}")
+    pub source: String,
+    /// Combination strategy (default: concat)
+    #[serde(default)]
+    pub strategy: ReduceStrategy,
+    /// Separator for `join` (default: blank line)
+    #[serde(default)]
+    pub separator: Option<String>,
+    /// Instruction for `summarize` (items are appended, numbered)
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Override provider for `summarize`
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Override model for `summarize`
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Maximum tokens to generate for `summarize`
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+impl ReduceParams {
+    /// Separator used by `join`
+    pub fn separator(&self) -> &str {
+        self.separator.as_deref().unwrap_or("\n\n")
+    }
+
+    /// Instruction used by `summarize`
+    pub fn prompt(&self) -> &str {
+        self.prompt
+            .as_deref()
+            .unwrap_or("Combine the following results into a single coherent answer.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduce_params_defaults() {
+        let params: ReduceParams = serde_yaml::from_str("source: $parts").unwrap();
+        assert_eq!(params.strategy, ReduceStrategy::Concat);
+        assert_eq!(params.separator(), "\n\n");
+        assert!(params.prompt().starts_with("Combine"));
+    }
+
+    #[test]
+    fn test_reduce_params_full() {
+        let yaml = r#"
+source: "{{use.parts}}"
+strategy: summarize
+prompt: "Merge"
+model: gpt-4o
+max_tokens: 800
+"#;
+        let params: ReduceParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(params.strategy, ReduceStrategy::Summarize);
+        assert_eq!(params.prompt(), "Merge");
+        assert_eq!(params.max_tokens, Some(800));
+    }
+}
@@ -0,0 +1,110 @@
+//! Retrieve Action - RAG over local documents (v0.7)
+//!
+//! `retrieve:` chunks the files matched by a glob, ranks the chunks
+//! against a query and returns the best ones, ready to bind into a prompt.
+//! Ranking uses embeddings when the provider has an embeddings API, and
+//! falls back to BM25 keyword scoring otherwise (or when `mode: bm25`).
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: context
+//!     retrieve:
+//!       query: "How do I rotate API keys?"
+//!       files: "docs/**/*.md"
+//!       top_k: 4
+//!       collection: docs-index   # optional: reuse embeddings across runs
+//!
+//!   - id: answer
+//!     use:
+//!       notes: context
+//!     infer: "Answer from these excerpts: {{use.notes}}"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::embed::DEFAULT_TOP_K;
+
+/// Default chunk length, in characters
+pub const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+/// Default overlap between consecutive chunks, in characters
+pub const DEFAULT_CHUNK_OVERLAP: usize = 200;
+
+/// How `retrieve:` ranks chunks
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetrieveMode {
+    /// Embeddings if the provider supports them, else BM25
+    #[default]
+    Auto,
+    /// Always embed (fails on providers without embeddings)
+    Embeddings,
+    /// Keyword scoring, no provider calls
+    Bm25,
+}
+
+/// Retrieve action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetrieveParams {
+    /// Search text (supports `{{use.alias}}`)
+    pub query: String,
+    /// Glob of files to search, relative to the working directory
+    pub files: String,
+    /// Maximum number of chunks
+    #[serde(default)]
+    pub top_k: Option<usize>,
+    /// Chunk length in characters
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    /// Characters shared by consecutive chunks
+    #[serde(default)]
+    pub chunk_overlap: Option<usize>,
+    #[serde(default)]
+    pub mode: RetrieveMode,
+    /// Vector store collection caching chunk embeddings across runs
+    #[serde(default)]
+    pub collection: Option<String>,
+    /// Override workflow provider (embeddings mode)
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Embedding model (default: the provider's)
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl RetrieveParams {
+    pub fn top_k(&self) -> usize {
+        self.top_k.unwrap_or(DEFAULT_TOP_K)
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Overlap, kept below the chunk size
+    pub fn chunk_overlap(&self) -> usize {
+        self.chunk_overlap
+            .unwrap_or(DEFAULT_CHUNK_OVERLAP)
+            .min(self.chunk_size() / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retrieve() {
+        let retrieve: RetrieveParams = serde_yaml::from_str(
+            "query: keys\nfiles: \"docs/**/*.md\"\nmode: bm25\nchunk_size: 300",
+        )
+        .unwrap();
+        assert_eq!(retrieve.mode, RetrieveMode::Bm25);
+        assert_eq!(retrieve.top_k(), DEFAULT_TOP_K);
+        assert_eq!(retrieve.chunk_overlap(), 150);
+        assert!(serde_yaml::from_str::<RetrieveParams>("query: keys").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Rows Action - dataframe-style shaping of row arrays (v0.7)
+//!
+//! The `rows:` verb filters, groups, aggregates, sorts and limits an array
+//! of objects (an `import:` result, a `for_each` aggregate, a JSON answer)
+//! without calling a model or shelling out. Steps run in that order; each
+//! is optional.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: top_accounts
+//!     use:
+//!       leads: leads
+//!     rows:
+//!       source: $leads
+//!       filter: "score >= 50 and country != 'US'"
+//!       group_by: [company]
+//!       aggregate:
+//!         seats: sum(seats)
+//!         leads: count
+//!         best: max(score)
+//!       sort: [-seats, company]
+//!       limit: 10
+//! ```
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Rows action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RowsParams {
+    /// Array binding expression (`$alias`, `$task.field`, `{{use.alias}}`)
+    pub source: String,
+    /// Keep rows where this expression is true, e.g.
+    /// `score >= 50 and (plan == 'pro' or seats > 10)`
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// Field paths to group by; one output row per distinct combination
+    #[serde(default)]
+    pub group_by: Vec<String>,
+    /// Output key → `count`, or `count`/`sum`/`avg`/`min`/`max`/`first`/
+    /// `last`/`collect` of a field, e.g. `sum(seats)`
+    #[serde(default)]
+    pub aggregate: FxHashMap<String, String>,
+    /// Field paths to sort by; `-field` sorts descending
+    #[serde(default)]
+    pub sort: Vec<String>,
+    /// Maximum number of rows to output
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl RowsParams {
+    /// Whether rows are grouped (by keys, or all into one row by `aggregate:`)
+    pub fn is_grouped(&self) -> bool {
+        !self.group_by.is_empty() || !self.aggregate.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rows() {
+        let yaml = r#"
+source: $leads
+filter: "score >= 50"
+group_by: [company]
+aggregate:
+  seats: sum(seats)
+sort: [-seats]
+limit: 5
+"#;
+        let rows: RowsParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(rows.aggregate["seats"], "sum(seats)");
+        assert_eq!(rows.sort, vec!["-seats"]);
+        assert!(rows.is_grouped());
+
+        let rows: RowsParams = serde_yaml::from_str("source: $leads\nlimit: 3").unwrap();
+        assert!(!rows.is_grouped());
+        assert!(serde_yaml::from_str::<RowsParams>("source: $x\norder_by: [a]").is_err());
+    }
+}
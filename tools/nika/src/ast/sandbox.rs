@@ -0,0 +1,156 @@
+//! Exec sandbox settings (v0.7)
+//!
+//! Optional limits on an `exec:` command:
+//!
+//! ```yaml
+//! exec:
+//!   command: "make report"
+//!   timeout: 120
+//!   sandbox:
+//!     cwd: ./build           # runs here; must exist
+//!     env: [LANG, CI_*]      # everything else but PATH is dropped
+//!     cpu_secs: 30           # RLIMIT_CPU
+//!     memory_mb: 512         # RLIMIT_AS
+//!     network: false         # private network namespace (Linux)
+//! ```
+//!
+//! Limits are applied with rlimits in the child before `sh` starts, so they
+//! cover every process the command spawns. Enforcement lives in
+//! `runtime::sandbox`.
+
+use serde::{Deserialize, Serialize};
+
+/// Sandbox for an `exec:` command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecSandbox {
+    /// Working directory (relative to where nika runs)
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Environment allowlist; a trailing `*` matches a prefix.
+    /// `None` keeps the whole environment.
+    #[serde(default)]
+    pub env: Option<Vec<String>>,
+    /// CPU time limit in seconds
+    #[serde(default)]
+    pub cpu_secs: Option<u64>,
+    /// Address space limit in megabytes
+    #[serde(default)]
+    pub memory_mb: Option<u64>,
+    /// Allow network access (default true)
+    #[serde(default = "default_network")]
+    pub network: bool,
+}
+
+fn default_network() -> bool {
+    true
+}
+
+impl Default for ExecSandbox {
+    fn default() -> Self {
+        Self {
+            cwd: None,
+            env: None,
+            cpu_secs: None,
+            memory_mb: None,
+            network: true,
+        }
+    }
+}
+
+impl ExecSandbox {
+    /// Whether `name` passes the env allowlist
+    pub fn allows_env(&self, name: &str) -> bool {
+        match &self.env {
+            None => true,
+            Some(allow) => allow.iter().any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == pattern,
+            }),
+        }
+    }
+
+    /// Check the settings, returning the first problem found
+    pub fn check(&self) -> Result<(), String> {
+        if self.cwd.as_deref().is_some_and(|cwd| cwd.trim().is_empty()) {
+            return Err("cwd must not be empty".to_string());
+        }
+        if self.cpu_secs == Some(0) {
+            return Err("cpu_secs must be at least 1".to_string());
+        }
+        if self.memory_mb == Some(0) {
+            return Err("memory_mb must be at least 1".to_string());
+        }
+        let names = self.env.iter().flatten();
+        if let Some(bad) = names.into_iter().find(|n| !is_env_pattern(n)) {
+            return Err(format!("'{}' is not an environment variable name", bad));
+        }
+        if !self.network && !cfg!(target_os = "linux") {
+            return Err("network: false is only supported on Linux".to_string());
+        }
+        Ok(())
+    }
+
+    /// One-line summary for `nika check`, e.g. `cwd=./build env=2 cpu=30s mem=512MB net=off`
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(cwd) = &self.cwd {
+            parts.push(format!("cwd={}", cwd));
+        }
+        if let Some(env) = &self.env {
+            parts.push(format!("env={}", env.len()));
+        }
+        if let Some(cpu) = self.cpu_secs {
+            parts.push(format!("cpu={}s", cpu));
+        }
+        if let Some(mem) = self.memory_mb {
+            parts.push(format!("mem={}MB", mem));
+        }
+        parts.push(format!("net={}", if self.network { "on" } else { "off" }));
+        parts.join(" ")
+    }
+}
+
+fn is_env_pattern(pattern: &str) -> bool {
+    let name = pattern.strip_suffix('*').unwrap_or(pattern);
+    !pattern.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_check_sandbox() {
+        let yaml = r#"
+cwd: ./build
+env: [PATH, CI_*]
+cpu_secs: 30
+memory_mb: 512
+"#;
+        let sandbox: ExecSandbox = serde_yaml::from_str(yaml).unwrap();
+        assert!(sandbox.network);
+        assert!(sandbox.check().is_ok());
+        assert!(sandbox.allows_env("PATH"));
+        assert!(sandbox.allows_env("CI_JOB"));
+        assert!(!sandbox.allows_env("AWS_SECRET_ACCESS_KEY"));
+        assert_eq!(
+            sandbox.summary(),
+            "cwd=./build env=2 cpu=30s mem=512MB net=on"
+        );
+
+        let bad = ExecSandbox {
+            env: Some(vec!["MY-VAR".to_string()]),
+            ..Default::default()
+        };
+        assert!(bad.check().unwrap_err().contains("MY-VAR"));
+        let zero = ExecSandbox {
+            memory_mb: Some(0),
+            ..Default::default()
+        };
+        assert!(zero.check().is_err());
+        assert!(serde_yaml::from_str::<ExecSandbox>("cpu: 3").is_err());
+    }
+}
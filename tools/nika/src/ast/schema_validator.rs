@@ -101,6 +101,35 @@ impl WorkflowSchemaValidator {
     }
 }
 
+/// Export the embedded workflow schema (the one the validator uses)
+///
+/// With a `version` (`0.2` or `nika/workflow@0.2`), the `schema:` field is
+/// pinned to that version so editors flag workflows written for another one.
+pub fn export_schema(version: Option<&str>) -> Result<Value, NikaError> {
+    let mut schema: Value = serde_json::from_str(SCHEMA_JSON)?;
+
+    if let Some(version) = version {
+        let tag = if version.starts_with("nika/workflow@") {
+            version.to_string()
+        } else {
+            format!("nika/workflow@{}", version)
+        };
+        let versions = &mut schema["properties"]["schema"]["enum"];
+        let known = versions
+            .as_array()
+            .is_some_and(|v| v.iter().any(|t| t.as_str() == Some(tag.as_str())));
+        if !known {
+            return Err(NikaError::InvalidSchema {
+                expected: versions.to_string(),
+                actual: tag,
+            });
+        }
+        *versions = Value::Array(vec![Value::String(tag)]);
+    }
+
+    Ok(schema)
+}
+
 /// Schema validation error details
 #[derive(Debug, Clone)]
 pub struct SchemaError {
@@ -199,6 +228,28 @@ mod tests {
     // ========================================================================
     // Test: Validator creation succeeds
     // ========================================================================
+    #[test]
+    fn test_export_schema_pins_version() {
+        let full = export_schema(None).unwrap();
+        assert_eq!(
+            full["properties"]["schema"]["enum"]
+                .as_array()
+                .unwrap()
+                .len(),
+            5
+        );
+
+        let pinned = export_schema(Some("0.2")).unwrap();
+        assert_eq!(
+            pinned["properties"]["schema"]["enum"],
+            serde_json::json!(["nika/workflow@0.2"])
+        );
+        assert_eq!(pinned["properties"]["tasks"], full["properties"]["tasks"]);
+
+        let err = export_schema(Some("9.9")).unwrap_err();
+        assert_eq!(err.code(), "NIKA-010");
+    }
+
     #[test]
     fn test_validator_creation_succeeds() {
         let validator = WorkflowSchemaValidator::new();
@@ -542,6 +593,25 @@ tasks:
         assert!(result.is_err(), "Invalid decompose strategy should fail");
     }
 
+    #[test]
+    fn test_exec_host_excludes_image() {
+        let validator = WorkflowSchemaValidator::new().unwrap();
+        let remote = r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: build
+    exec: { host: build-server, cmd: "make release" }
+"#;
+        assert!(validator.validate_yaml(remote).is_ok());
+        let both = r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: build
+    exec: { host: build-server, image: alpine, cmd: "make" }
+"#;
+        assert!(validator.validate_yaml(both).is_err());
+    }
+
     // ========================================================================
     // Test: Valid lazy binding passes
     // ========================================================================
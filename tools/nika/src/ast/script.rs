@@ -0,0 +1,129 @@
+//! Script Action - sandboxed Rhai snippets (v0.7)
+//!
+//! `script:` runs a short [Rhai](https://rhai.rs) script for data munging
+//! (filtering arrays, computing diffs, reshaping JSON) without a shell or
+//! an MCP server. Every `use:` alias is a variable; the value of the last
+//! expression is the task output. Scripts can't touch files, the network
+//! or the environment, and run under an operation budget and a timeout.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: diff
+//!     use:
+//!       before: snapshot_old
+//!       after: snapshot_new
+//!     script: |
+//!       let added = after.filter(|x| !before.contains(x));
+//!       #{ added: added, count: added.len() }
+//! ```
+//!
+//! Full form:
+//!
+//! ```yaml
+//! script:
+//!   file: scripts/score.rhai     # or code: "..."
+//!   timeout_ms: 2000
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Default wall-clock limit for a script, in milliseconds
+pub const DEFAULT_SCRIPT_TIMEOUT_MS: u64 = 5_000;
+
+/// Default operation budget for a script
+pub const DEFAULT_MAX_OPERATIONS: u64 = 10_000_000;
+
+/// Script action parameters (v0.7)
+///
+/// Supports shorthand: `script: "code"` or full form `script: { code: "..." }`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScriptParams {
+    /// Rhai source
+    pub code: Option<String>,
+    /// Rhai file relative to the working directory (instead of `code`)
+    pub file: Option<String>,
+    /// Wall-clock limit in milliseconds (default 5000)
+    pub timeout_ms: Option<u64>,
+    /// Operation budget (default 10 million)
+    pub max_operations: Option<u64>,
+}
+
+impl ScriptParams {
+    pub fn timeout_ms(&self) -> u64 {
+        self.timeout_ms.unwrap_or(DEFAULT_SCRIPT_TIMEOUT_MS)
+    }
+
+    pub fn max_operations(&self) -> u64 {
+        self.max_operations.unwrap_or(DEFAULT_MAX_OPERATIONS).max(1)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScriptParams {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ScriptParamsHelper {
+            Short(String),
+            Full {
+                #[serde(default)]
+                code: Option<String>,
+                #[serde(default)]
+                file: Option<String>,
+                #[serde(default)]
+                timeout_ms: Option<u64>,
+                #[serde(default)]
+                max_operations: Option<u64>,
+            },
+        }
+
+        match ScriptParamsHelper::deserialize(deserializer)? {
+            ScriptParamsHelper::Short(code) => Ok(ScriptParams {
+                code: Some(code),
+                ..Default::default()
+            }),
+            ScriptParamsHelper::Full {
+                code,
+                file,
+                timeout_ms,
+                max_operations,
+            } => {
+                if code.is_some() == file.is_some() {
+                    return Err(serde::de::Error::custom(
+                        "script: needs exactly one of `code` or `file`",
+                    ));
+                }
+                Ok(ScriptParams {
+                    code,
+                    file,
+                    timeout_ms,
+                    max_operations,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script() {
+        let script: ScriptParams = serde_yaml::from_str("\"items.len()\"").unwrap();
+        assert_eq!(script.code.as_deref(), Some("items.len()"));
+        assert_eq!(script.timeout_ms(), DEFAULT_SCRIPT_TIMEOUT_MS);
+
+        let script: ScriptParams =
+            serde_yaml::from_str("file: scripts/diff.rhai\nmax_operations: 0").unwrap();
+        assert_eq!(script.file.as_deref(), Some("scripts/diff.rhai"));
+        assert_eq!(script.max_operations(), 1);
+
+        assert!(serde_yaml::from_str::<ScriptParams>("timeout_ms: 10").is_err());
+        assert!(serde_yaml::from_str::<ScriptParams>("code: x\nfile: y.rhai").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Import/Export Actions - spreadsheets in and out (v0.7)
+//!
+//! `import:` reads a CSV or XLSX file into a JSON array of row objects,
+//! keyed by the header row. `export:` writes a binding back out as a CSV
+//! or XLSX file for whoever picks up the results.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: leads
+//!     import:
+//!       file: "data/leads.xlsx"
+//!       sheet: "Q3"
+//!       columns:                # header → key (only these are kept)
+//!         "E-mail Address": email
+//!         "Company Name": company
+//!       limit: 500
+//!
+//!   - id: report
+//!     use:
+//!       rows: scored
+//!     export:
+//!       source: $rows
+//!       file: "out/scored-leads.xlsx"
+//!       columns: [company, email, score]
+//! ```
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Sheet name used by `export:` to XLSX when none is given
+pub const DEFAULT_SHEET_NAME: &str = "Sheet1";
+
+/// Spreadsheet file format
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SheetFormat {
+    Csv,
+    Xlsx,
+}
+
+impl SheetFormat {
+    /// Format implied by a file extension (`.csv`, `.tsv`, `.xlsx`)
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = std::path::Path::new(path)
+            .extension()?
+            .to_str()?
+            .to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" | "tsv" => Some(Self::Csv),
+            "xlsx" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Field delimiter: explicit, else tab for `.tsv`, else comma
+fn delimiter_for(delimiter: Option<char>, file: &str) -> char {
+    delimiter.unwrap_or_else(|| {
+        if file.to_ascii_lowercase().ends_with(".tsv") {
+            '\t'
+        } else {
+            ','
+        }
+    })
+}
+
+/// Import action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportParams {
+    /// File to read, relative to the working directory (supports `{{use.alias}}`)
+    pub file: String,
+    /// File format (default: from the extension)
+    #[serde(default)]
+    pub format: Option<SheetFormat>,
+    /// XLSX worksheet name (default: the first sheet)
+    #[serde(default)]
+    pub sheet: Option<String>,
+    /// CSV field delimiter (default: `,`, or tab for `.tsv`)
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Whether the first row names the columns (default true; else A, B, C…)
+    #[serde(default)]
+    pub header: Option<bool>,
+    /// Header → output key; when set, only these columns are kept
+    #[serde(default)]
+    pub columns: FxHashMap<String, String>,
+    /// Type CSV cells as numbers, booleans and null (default true)
+    #[serde(default)]
+    pub infer_types: Option<bool>,
+    /// Rows to skip before the header (titles, notes)
+    #[serde(default)]
+    pub skip_rows: Option<usize>,
+    /// Maximum number of data rows
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ImportParams {
+    pub fn has_header(&self) -> bool {
+        self.header.unwrap_or(true)
+    }
+
+    pub fn infer_types(&self) -> bool {
+        self.infer_types.unwrap_or(true)
+    }
+
+    pub fn delimiter(&self) -> char {
+        delimiter_for(self.delimiter, &self.file)
+    }
+}
+
+/// Export action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportParams {
+    /// Rows to write: `$alias`, `$task.field` or `{{use.alias}}`
+    pub source: String,
+    /// File to write, relative to the working directory (supports `{{use.alias}}`)
+    pub file: String,
+    /// File format (default: from the extension)
+    #[serde(default)]
+    pub format: Option<SheetFormat>,
+    /// XLSX worksheet name (default: Sheet1)
+    #[serde(default)]
+    pub sheet: Option<String>,
+    /// CSV field delimiter (default: `,`, or tab for `.tsv`)
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Columns to write, in order (default: every key, sorted)
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+}
+
+impl ExportParams {
+    pub fn sheet(&self) -> &str {
+        self.sheet.as_deref().unwrap_or(DEFAULT_SHEET_NAME)
+    }
+
+    pub fn delimiter(&self) -> char {
+        delimiter_for(self.delimiter, &self.file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_import_and_export() {
+        let import: ImportParams =
+            serde_yaml::from_str("file: data/leads.TSV\ncolumns:\n  \"E-mail\": email\nlimit: 10")
+                .unwrap();
+        assert_eq!(SheetFormat::from_path(&import.file), Some(SheetFormat::Csv));
+        assert_eq!(import.delimiter(), '\t');
+        assert!(import.has_header());
+        assert_eq!(import.columns["E-mail"], "email");
+
+        let export: ExportParams =
+            serde_yaml::from_str("source: $rows\nfile: out.xlsx\ndelimiter: \";\"").unwrap();
+        assert_eq!(
+            SheetFormat::from_path(&export.file),
+            Some(SheetFormat::Xlsx)
+        );
+        assert_eq!(export.sheet(), DEFAULT_SHEET_NAME);
+        assert_eq!(export.delimiter(), ';');
+        assert!(serde_yaml::from_str::<ExportParams>("file: out.csv").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! Transcribe Action - speech to text (v0.7)
+//!
+//! `transcribe:` sends an audio file to a speech-to-text API and outputs
+//! the transcript as text, ready to bind into a prompt. Long WAV and MP3
+//! recordings are split into chunks that are transcribed in order, each
+//! one primed with the end of the previous transcript.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: transcript
+//!     transcribe:
+//!       file: "recordings/standup.mp3"
+//!       language: en
+//!       prompt: "Nika, DAG, MCP"   # spelling hints
+//!
+//!   - id: notes
+//!     use:
+//!       text: transcript
+//!     infer: "Summarize the action items: {{use.text}}"
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Default chunk length for long recordings, in seconds
+pub const DEFAULT_CHUNK_SECONDS: u32 = 600;
+
+/// Transcribe action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TranscribeParams {
+    /// Audio file, relative to the working directory (supports `{{use.alias}}`)
+    pub file: String,
+    /// Override workflow provider (openai or groq)
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Speech-to-text model (default: the provider's Whisper model)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Spoken language as ISO-639-1 (e.g. `en`); detected when absent
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Context or spelling hints for the first chunk
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Maximum chunk length in seconds (WAV and MP3)
+    #[serde(default)]
+    pub chunk_seconds: Option<u32>,
+}
+
+impl TranscribeParams {
+    pub fn chunk_seconds(&self) -> u32 {
+        self.chunk_seconds.unwrap_or(DEFAULT_CHUNK_SECONDS).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transcribe() {
+        let transcribe: TranscribeParams =
+            serde_yaml::from_str("file: call.wav\nlanguage: fr\nchunk_seconds: 0").unwrap();
+        assert_eq!(transcribe.language.as_deref(), Some("fr"));
+        assert_eq!(transcribe.chunk_seconds(), 1);
+        assert_eq!(
+            TranscribeParams::default().chunk_seconds(),
+            DEFAULT_CHUNK_SECONDS
+        );
+        assert!(serde_yaml::from_str::<TranscribeParams>("language: fr").is_err());
+    }
+}
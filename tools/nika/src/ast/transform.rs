@@ -0,0 +1,135 @@
+//! Transform - post-processing of task output (v0.7)
+//!
+//! `transform:` cleans a task's raw output before it is stored, so bindings
+//! downstream see data instead of a provider envelope or chatty text. Steps
+//! run in the order written; each gets the previous step's result.
+//!
+//! ```yaml
+//! transform:
+//!   extract: $.choices[0].message.content   # JSONPath; JSON text is parsed first
+//!   regex: '(?s)```json\s*(.*?)```'         # capture group 1 (or named groups)
+//!   parse: json                             # any output.format codec
+//! ```
+//!
+//! A map is shorthand for a list of one-key steps, which also allows a step
+//! kind to repeat:
+//!
+//! ```yaml
+//! transform:
+//!   - regex: 'Answer: (.*)'
+//!   - parse: yaml
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use super::output::OutputFormat;
+
+/// One step of a `transform:` pipeline
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformStep {
+    /// Select with a JSONPath (text input is parsed as JSON first)
+    Extract(String),
+    /// First match of a regex: capture group 1, named groups as an object,
+    /// or the whole match when the pattern has no groups
+    Regex(String),
+    /// Parse text with an output format codec (json, yaml, csv, ...)
+    Parse(OutputFormat),
+}
+
+/// Ordered `transform:` steps
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransformSpec {
+    pub steps: Vec<TransformStep>,
+}
+
+impl TransformStep {
+    /// Step kind, as written in YAML
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransformStep::Extract(_) => "extract",
+            TransformStep::Regex(_) => "regex",
+            TransformStep::Parse(_) => "parse",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TransformSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StepsVisitor;
+
+        impl<'de> Visitor<'de> for StepsVisitor {
+            type Value = TransformSpec;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map or list of extract/regex/parse steps")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                // Keys are read in document order, which is the pipeline order
+                let mut steps = Vec::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    steps.push(match key.as_str() {
+                        "extract" => TransformStep::Extract(map.next_value()?),
+                        "regex" => TransformStep::Regex(map.next_value()?),
+                        "parse" => TransformStep::Parse(map.next_value()?),
+                        other => {
+                            return Err(de::Error::unknown_field(
+                                other,
+                                &["extract", "regex", "parse"],
+                            ))
+                        }
+                    });
+                }
+                Ok(TransformSpec { steps })
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut steps = Vec::new();
+                while let Some(TransformSpec { steps: one }) = seq.next_element()? {
+                    if one.len() != 1 {
+                        return Err(de::Error::invalid_length(
+                            one.len(),
+                            &"one step per list item",
+                        ));
+                    }
+                    steps.extend(one);
+                }
+                Ok(TransformSpec { steps })
+            }
+        }
+
+        deserializer.deserialize_any(StepsVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transform_map_and_list() {
+        let yaml = "extract: $.choices[0]\nregex: 'x(.*)'\nparse: json";
+        let spec: TransformSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            spec.steps,
+            vec![
+                TransformStep::Extract("$.choices[0]".to_string()),
+                TransformStep::Regex("x(.*)".to_string()),
+                TransformStep::Parse(OutputFormat::Json),
+            ]
+        );
+
+        // Order follows the document, not the step kind
+        let spec: TransformSpec =
+            serde_yaml::from_str("- parse: yaml\n- extract: items\n- parse: csv").unwrap();
+        let names: Vec<&str> = spec.steps.iter().map(TransformStep::name).collect();
+        assert_eq!(names, vec!["parse", "extract", "parse"]);
+
+        assert!(serde_yaml::from_str::<TransformSpec>("trim: true").is_err());
+        assert!(serde_yaml::from_str::<TransformSpec>("- jq: .a").is_err());
+        assert!(serde_yaml::from_str::<TransformSpec>("- {parse: json, extract: a}").is_err());
+    }
+}
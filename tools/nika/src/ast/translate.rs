@@ -0,0 +1,69 @@
+//! Translate Action - provider-backed translation with a glossary (v0.7)
+//!
+//! `translate:` sends one consistent translation prompt instead of a
+//! bespoke `infer:` per workflow. Glossary terms that occur in the text must
+//! be rendered exactly as given (brand names, product vocabulary), and the
+//! output is the translated text only.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: localize
+//!     use:
+//!       page: draft_page
+//!     translate:
+//!       source: $page
+//!       to: fr                  # code (fr, fra) or language name
+//!       from: en                # optional: detected by the model when absent
+//!       style: "Formal, vous"   # optional register/tone guidance
+//!       glossary:
+//!         Nika: Nika            # keep untranslated
+//!         workflow: flux de travail
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Translate action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TranslateParams {
+    /// Text binding expression (`$alias`, `$task.field`, `{{use.alias}}`)
+    pub source: String,
+    /// Target language: ISO 639-1/639-3 code or name (supports templates)
+    pub to: String,
+    /// Source language (default: left to the model, supports templates)
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Term → required rendering in the target language
+    #[serde(default)]
+    pub glossary: BTreeMap<String, String>,
+    /// Register and tone guidance, e.g. "informal, short sentences"
+    #[serde(default)]
+    pub style: Option<String>,
+    /// Override workflow provider
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Override workflow model
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_translate() {
+        let yaml = "source: $page\nto: fr\nglossary:\n  Nika: Nika\n  workflow: flux de travail";
+        let translate: TranslateParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(translate.to, "fr");
+        assert!(translate.from.is_none());
+        assert_eq!(translate.glossary["workflow"], "flux de travail");
+
+        assert!(serde_yaml::from_str::<TranslateParams>("source: $page").is_err());
+        assert!(serde_yaml::from_str::<TranslateParams>("source: $p\nto: de\ntone: x").is_err());
+    }
+}
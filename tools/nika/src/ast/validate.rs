@@ -0,0 +1,169 @@
+//! Validate Action - deterministic data quality gate (v0.7)
+//!
+//! The `validate:` verb checks a binding against a JSON Schema and/or a
+//! list of field rules, without calling a model. By default the task fails
+//! with a report of every violation; with `on_fail: warn` it succeeds and
+//! the report is in its output, so a flow can branch on `{{from.valid}}`.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: check
+//!     use:
+//!       lead: extract
+//!     validate:
+//!       source: $lead
+//!       schema: ./schemas/lead.json      # file path, or an inline schema
+//!       rules:
+//!         - field: email
+//!           required: true
+//!           pattern: "^[^@]+@[^@]+$"
+//!         - field: score
+//!           type: number
+//!           min: 0
+//!           max: 100
+//!         - field: tags.*
+//!           one_of: [hot, warm, cold]
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What a failed `validate:` task does
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFail {
+    /// Fail the task with the violations
+    #[default]
+    Fail,
+    /// Succeed, with `valid: false` and the violations in the output
+    Warn,
+}
+
+impl OnFail {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fail => "fail",
+            Self::Warn => "warn",
+        }
+    }
+}
+
+/// JSON type a rule can require
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Object,
+    Null,
+}
+
+impl ValueType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+            Self::Array => "array",
+            Self::Object => "object",
+            Self::Null => "null",
+        }
+    }
+
+    /// Whether `value` has this type (integers are numbers too)
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+            Self::Null => value.is_null(),
+        }
+    }
+}
+
+/// Checks on one field (v0.7)
+///
+/// Checks other than `required` are skipped when the field is missing.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateRule {
+    /// Dot path into the value (`contact.email`, `items.0.id`); `*` checks
+    /// every array element (`items.*.id`), `.` the value itself
+    pub field: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, rename = "type")]
+    pub kind: Option<ValueType>,
+    /// Regex a string must match
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Inclusive bounds for numbers
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    /// Inclusive bounds on string (characters), array or object length
+    #[serde(default)]
+    pub min_length: Option<usize>,
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Allowed values
+    #[serde(default)]
+    pub one_of: Option<Vec<Value>>,
+}
+
+/// Validate action parameters (v0.7)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidateParams {
+    /// Binding expression to check (`$alias`, `$task.field`, `{{use.alias}}`)
+    pub source: String,
+    /// JSON Schema: a file path, or the schema inline
+    #[serde(default)]
+    pub schema: Option<Value>,
+    #[serde(default)]
+    pub rules: Vec<ValidateRule>,
+    #[serde(default)]
+    pub on_fail: OnFail,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_validate() {
+        let yaml = r#"
+source: $lead
+schema: { type: object, required: [email] }
+rules:
+  - field: score
+    type: integer
+    min: 0
+on_fail: warn
+"#;
+        let params: ValidateParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(params.on_fail, OnFail::Warn);
+        assert_eq!(params.rules[0].kind, Some(ValueType::Integer));
+        assert_eq!(params.schema.unwrap()["required"], json!(["email"]));
+        assert!(serde_yaml::from_str::<ValidateRule>("field: a\nregex: x").is_err());
+    }
+
+    #[test]
+    fn test_value_types() {
+        assert!(ValueType::Number.matches(&json!(3)));
+        assert!(ValueType::Integer.matches(&json!(3)));
+        assert!(!ValueType::Integer.matches(&json!(3.5)));
+        assert!(!ValueType::String.matches(&json!(null)));
+    }
+}
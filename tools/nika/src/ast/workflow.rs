@@ -8,16 +8,28 @@
 //! - `McpConfigInline`: Inline MCP server configuration (v0.2+)
 
 use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use serde::Deserialize;
 
-use crate::binding::WiringSpec;
+use crate::binding::{TemplateEngine, TemplateMode, WiringSpec};
 use crate::error::NikaError;
+use crate::util::{jsonpath, CronSchedule, InjectionGuard, WatchPattern};
 
 use super::action::TaskAction;
+use super::container::ExecContainer;
+use super::context_pool::ContextPoolSpec;
 use super::decompose::DecomposeSpec;
+use super::fetch::FetchPaginate;
+use super::injection::InjectionPolicy;
+use super::inputs::Inputs;
+use super::moderate::ModerateSpec;
 use super::output::OutputPolicy;
+use super::sandbox::ExecSandbox;
+use super::transform::{TransformSpec, TransformStep};
 
 /// Expected schema version for v0.1 workflows
 pub const SCHEMA_V01: &str = "nika/workflow@0.1";
@@ -63,17 +75,181 @@ pub struct McpConfigInline {
     pub cwd: Option<String>,
 }
 
+/// Pseudo-task holding the file that fired a watch trigger (v0.7)
+///
+/// Bound like a task output: `use: { file: trigger.path }`.
+pub const TRIGGER_TASK_ID: &str = "trigger";
+
+/// Pseudo-task holding the values of the workflow's `inputs:` (v0.7)
+///
+/// Bound like a task output: `use: { topic: inputs.topic }`.
+pub const INPUTS_TASK_ID: &str = "inputs";
+
+/// Pseudo-task holding the project's persisted state (v0.7)
+///
+/// Read as `{{state.key}}` or bound with `use: { last: state.key ?? 0 }`.
+pub const STATE_TASK_ID: &str = "state";
+
+/// Quiet period before a watched file fires, unless `debounce_ms` is set
+pub const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 500;
+
+/// Event-driven triggers (v0.7)
+///
+/// `cron` is picked up by `nika schedule start`, `watch` by
+/// `nika daemon start --watch`:
+///
+/// ```yaml
+/// triggers:
+///   cron: "0 9 * * MON"   # every Monday at 09:00, local time
+///   watch: "./inbox/*.md" # whenever a matching file appears or changes
+///   debounce_ms: 500      # wait for writes to settle (default 500)
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Triggers {
+    /// Cron expression (see [`CronSchedule`])
+    #[serde(default)]
+    pub cron: Option<String>,
+    /// File glob relative to the workflow file (see [`WatchPattern`])
+    #[serde(default)]
+    pub watch: Option<String>,
+    /// Quiet period for `watch`, in milliseconds
+    #[serde(default)]
+    pub debounce_ms: Option<u64>,
+}
+
+impl Triggers {
+    /// Parsed cron schedule, if one is set
+    pub fn schedule(&self) -> Option<Result<CronSchedule, NikaError>> {
+        self.cron.as_deref().map(CronSchedule::parse)
+    }
+
+    /// Parsed watch pattern anchored at `base` (the workflow's directory)
+    pub fn watch_pattern(&self, base: &Path) -> Option<Result<WatchPattern, NikaError>> {
+        self.watch
+            .as_deref()
+            .map(|pattern| WatchPattern::parse(pattern, base))
+    }
+
+    /// Debounce window for `watch`
+    pub fn debounce(&self) -> Duration {
+        Duration::from_millis(self.debounce_ms.unwrap_or(DEFAULT_WATCH_DEBOUNCE_MS))
+    }
+}
+
+/// Values a task saves to the project state when it succeeds (v0.7)
+///
+/// ```yaml
+/// state:
+///   set:
+///     last_id: items.0.id  # path in this task's output ("" = whole output)
+///   ttl_secs: 604800       # optional; kept until overwritten otherwise
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateSpec {
+    /// State key → path in the task's output
+    #[serde(default)]
+    pub set: BTreeMap<String, String>,
+    /// Expiry of the written keys, in seconds
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
+impl StateSpec {
+    /// Expiry of the written keys
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl_secs.map(Duration::from_secs)
+    }
+}
+
+/// Settings inherited by every task, or every task of one verb (v0.7)
+///
+/// ```yaml
+/// defaults:
+///   all:
+///     output: { stamp: true }
+///   infer:
+///     output: { format: json, schema: .nika/schemas/answer.json }
+///     max_tokens: 4000
+/// ```
+///
+/// Keys are `all` or a verb name. A task's own `output:` fields and
+/// `max_tokens:` win over its verb's defaults, which win over `all`;
+/// `output: false` opts a task out of the output defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskDefaults {
+    /// Default `output:` fields
+    #[serde(default)]
+    pub output: Option<OutputPolicy>,
+    /// Default `max_tokens:` for verbs that have one (infer, reduce)
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+/// `defaults:` key for tasks of every verb
+const ALL_TASKS_DEFAULTS: &str = "all";
+
+/// Apply the `defaults:` matching `task`'s verb to it (v0.7)
+fn inherit_defaults(mut task: Task, defaults: &BTreeMap<String, TaskDefaults>) -> Task {
+    let inherited: Vec<&TaskDefaults> = [task.action.verb_name(), ALL_TASKS_DEFAULTS]
+        .into_iter()
+        .filter_map(|key| defaults.get(key))
+        .collect();
+
+    if let Some(max_tokens) = task.action.max_tokens_mut() {
+        if max_tokens.is_none() {
+            *max_tokens = inherited.iter().find_map(|d| d.max_tokens);
+        }
+    }
+
+    let mut output = task.output.take().unwrap_or_default();
+    if output.is_disabled() {
+        return task;
+    }
+    for policy in inherited.iter().filter_map(|d| d.output.as_ref()) {
+        output.inherit(policy);
+    }
+    task.output = output.is_set().then_some(output);
+    task
+}
+
 /// Workflow parsed from YAML (raw)
 #[derive(Debug, Deserialize)]
 struct WorkflowRaw {
     pub schema: String,
+    /// Workflow name (`workflow:` key)
+    #[serde(default, rename = "workflow")]
+    pub name: Option<String>,
     #[serde(default = "default_provider")]
     pub provider: String,
     #[serde(default)]
     pub model: Option<String>,
+    /// Template resolution mode (v0.7)
+    #[serde(default)]
+    pub templates: TemplateMode,
     /// MCP server configurations (v0.2)
     #[serde(default)]
     pub mcp: Option<FxHashMap<String, McpConfigInline>>,
+    /// Scheduling triggers (v0.7)
+    #[serde(default)]
+    pub triggers: Option<Triggers>,
+    /// Prompt-injection heuristics for untrusted content (v0.7)
+    #[serde(default)]
+    pub injection: InjectionPolicy,
+    /// Typed parameters given at run time (v0.7)
+    #[serde(default)]
+    pub inputs: Inputs,
+    /// Probe providers and MCP servers before running (v0.7)
+    #[serde(default)]
+    pub preflight: bool,
+    /// Token budget shared by LLM tasks (v0.7)
+    #[serde(default)]
+    pub context_pool: Option<ContextPoolSpec>,
+    /// Task settings inherited by verb (v0.7)
+    #[serde(default)]
+    pub defaults: BTreeMap<String, TaskDefaults>,
     pub tasks: Vec<Task>,
     #[serde(default)]
     pub flows: Vec<Flow>,
@@ -83,14 +259,33 @@ struct WorkflowRaw {
 #[derive(Debug)]
 pub struct Workflow {
     pub schema: String,
+    /// Workflow name from the `workflow:` key (v0.7)
+    pub name: Option<String>,
     pub provider: String,
     pub model: Option<String>,
+    /// Template resolution mode: `lenient` (default) or `strict` (v0.7)
+    ///
+    /// In strict mode, `{{...}}` sequences that aren't valid `{{use.*}}`
+    /// references fail the task instead of passing through literally.
+    pub templates: TemplateMode,
     /// MCP server configurations (v0.2)
     ///
     /// Allows workflows to define MCP servers inline rather than
     /// referencing external configuration. The map key is the server
     /// name used in `invoke.mcp` fields.
     pub mcp: Option<FxHashMap<String, McpConfigInline>>,
+    /// Scheduling triggers, e.g. `triggers: { cron: "0 9 * * MON" }` (v0.7)
+    pub triggers: Option<Triggers>,
+    /// Checks on `fetch:`/`invoke:` content and agent tool results before
+    /// they reach a prompt, e.g. `injection: { action: wrap }` (v0.7)
+    pub injection: InjectionPolicy,
+    /// Typed parameters given at run time, e.g. `inputs: { topic: }` (v0.7)
+    pub inputs: Inputs,
+    /// Send one tiny request per provider and connect every MCP server
+    /// before the first task, failing early with a report (v0.7)
+    pub preflight: bool,
+    /// Token budget shared by `agent:` and `infer:` tasks (v0.7)
+    pub context_pool: Option<ContextPoolSpec>,
     pub tasks: Vec<Arc<Task>>,
     pub flows: Vec<Flow>,
 }
@@ -101,12 +296,44 @@ impl<'de> Deserialize<'de> for Workflow {
         D: serde::Deserializer<'de>,
     {
         let raw = WorkflowRaw::deserialize(deserializer)?;
+        if let Some(key) = raw
+            .defaults
+            .keys()
+            .find(|key| *key != ALL_TASKS_DEFAULTS && !TaskAction::VERBS.contains(&key.as_str()))
+        {
+            return Err(serde::de::Error::custom(format!(
+                "unknown key '{}' in defaults: (expected `all` or a verb name)",
+                key
+            )));
+        }
+        if let Some(key) = raw.defaults.iter().find_map(|(key, d)| {
+            let limited =
+                key == ALL_TASKS_DEFAULTS || TaskAction::TOKEN_LIMIT_VERBS.contains(&key.as_str());
+            (d.max_tokens.is_some() && !limited).then_some(key)
+        }) {
+            return Err(serde::de::Error::custom(format!(
+                "defaults.{}.max_tokens: {} tasks have no max_tokens (use infer, reduce or all)",
+                key, key
+            )));
+        }
+        let defaults = raw.defaults;
         Ok(Workflow {
             schema: raw.schema,
+            name: raw.name,
             provider: raw.provider,
             model: raw.model,
+            templates: raw.templates,
             mcp: raw.mcp,
-            tasks: raw.tasks.into_iter().map(Arc::new).collect(),
+            triggers: raw.triggers,
+            injection: raw.injection,
+            inputs: raw.inputs,
+            preflight: raw.preflight,
+            context_pool: raw.context_pool,
+            tasks: raw
+                .tasks
+                .into_iter()
+                .map(|task| Arc::new(inherit_defaults(task, &defaults)))
+                .collect(),
             flows: raw.flows,
         })
     }
@@ -144,6 +371,11 @@ impl Workflow {
     /// Returns error if:
     /// - Schema doesn't match expected version (v0.1, v0.2, v0.3, v0.4, or v0.5)
     /// - Any task has invalid for_each configuration (non-array or empty)
+    /// - Any `transform:` step has an invalid JSONPath or regex (v0.7)
+    /// - A `fetch:` has a bad `paginate:` or GraphQL query, or no URL (v0.7)
+    /// - Any `exec:` sandbox has a zero limit or a bad env name (v0.7)
+    /// - An `injection:` pattern is not a valid regex (v0.7)
+    /// - `context_pool:` sizes are out of range or name unknown tasks (v0.7)
     pub fn validate_schema(&self) -> Result<(), NikaError> {
         // Validate schema version
         if self.schema != SCHEMA_V01
@@ -161,9 +393,77 @@ impl Workflow {
             });
         }
 
-        // Validate for_each on all tasks
+        // Validate for_each, transform:, moderate: and exec sandboxes on all tasks
         for task in &self.tasks {
             task.validate_for_each()?;
+            task.validate_transform()?;
+            task.validate_moderate()?;
+            task.validate_sandbox()?;
+            task.validate_fetch()?;
+            task.validate_ws()?;
+        }
+
+        // `state` is the persisted-state binding (v0.7)
+        if self.tasks.iter().any(|t| t.id == STATE_TASK_ID) {
+            return Err(NikaError::ValidationError {
+                reason: format!(
+                    "task id '{}' is reserved for the project state binding",
+                    STATE_TASK_ID
+                ),
+            });
+        }
+        for task in &self.tasks {
+            let mut keys = task.state.iter().flat_map(|spec| spec.set.keys());
+            if let Some(key) = keys.find(|k| k.is_empty() || k.contains('.')) {
+                return Err(NikaError::ValidationError {
+                    reason: format!(
+                        "task '{}': invalid state key '{}' (use a name without dots)",
+                        task.id, key
+                    ),
+                });
+            }
+        }
+
+        // Input declarations; their binding needs the `inputs` id (v0.7)
+        self.inputs.check()?;
+        if !self.inputs.is_empty() && self.tasks.iter().any(|t| t.id == INPUTS_TASK_ID) {
+            return Err(NikaError::InvalidInput {
+                name: INPUTS_TASK_ID.to_string(),
+                reason: format!(
+                    "task id '{}' is reserved for the inputs binding",
+                    INPUTS_TASK_ID
+                ),
+            });
+        }
+
+        // Custom injection patterns must compile (v0.7)
+        InjectionGuard::new(&self.injection)?;
+
+        // Pool sizes and reservation targets (v0.7)
+        if let Some(pool) = &self.context_pool {
+            pool.check(self.tasks.iter().map(|t| t.id.as_str()))
+                .map_err(|reason| NikaError::ValidationError {
+                    reason: format!("context_pool: {}", reason),
+                })?;
+        }
+
+        // Validate the cron trigger (v0.7)
+        if let Some(schedule) = self.triggers.as_ref().and_then(Triggers::schedule) {
+            schedule?;
+        }
+
+        // Validate the watch trigger (v0.7); its binding needs the `trigger` id
+        if let Some(pattern) = self.triggers.as_ref().and_then(|t| t.watch.as_deref()) {
+            WatchPattern::parse(pattern, Path::new("."))?;
+            if self.tasks.iter().any(|t| t.id == TRIGGER_TASK_ID) {
+                return Err(NikaError::InvalidWatchTrigger {
+                    pattern: pattern.to_string(),
+                    reason: format!(
+                        "task id '{}' is reserved for the trigger binding",
+                        TRIGGER_TASK_ID
+                    ),
+                });
+            }
         }
 
         Ok(())
@@ -183,6 +483,12 @@ pub struct Task {
     /// Output format and validation (v0.1)
     #[serde(default)]
     pub output: Option<OutputPolicy>,
+    /// Post-processing of the raw output before it is stored (v0.7)
+    #[serde(default)]
+    pub transform: Option<TransformSpec>,
+    /// Content safety gate on inputs and/or output (v0.7)
+    #[serde(default)]
+    pub moderate: Option<ModerateSpec>,
     /// Runtime DAG expansion via semantic traversal (v0.5)
     ///
     /// When specified, the task will be decomposed at runtime based on
@@ -250,6 +556,25 @@ pub struct Task {
     /// ```
     #[serde(default)]
     pub fail_fast: Option<bool>,
+    /// Save parts of the output to the project state (v0.7)
+    #[serde(default)]
+    pub state: Option<StateSpec>,
+    /// Labels matched by `[router]` rules for `model: auto` (v0.7)
+    ///
+    /// ```yaml
+    /// model: auto
+    /// tags: [premium]
+    /// ```
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// `jinja` renders this task's templates with minijinja (v0.7)
+    ///
+    /// ```yaml
+    /// template_engine: jinja
+    /// infer: "{% for p in use.posts %}- {{ p.title }}\n{% endfor %}"
+    /// ```
+    #[serde(default)]
+    pub template_engine: TemplateEngine,
     #[serde(flatten)]
     pub action: TaskAction,
 }
@@ -293,6 +618,99 @@ impl Task {
         Ok(())
     }
 
+    /// Check that `transform:` paths and patterns compile (v0.7)
+    pub fn validate_transform(&self) -> Result<(), NikaError> {
+        let steps = self.transform.iter().flat_map(|spec| spec.steps.iter());
+        for (i, step) in steps.enumerate() {
+            let reason = match step {
+                TransformStep::Extract(path) => {
+                    jsonpath::validate(path).err().map(|e| e.to_string())
+                }
+                TransformStep::Regex(pattern) => {
+                    regex::Regex::new(pattern).err().map(|e| e.to_string())
+                }
+                TransformStep::Parse(_) => None,
+            };
+            if let Some(reason) = reason {
+                return Err(NikaError::ValidationError {
+                    reason: format!(
+                        "task '{}': transform step {} ({}): {}",
+                        self.id,
+                        i + 1,
+                        step.name(),
+                        reason
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check `moderate:` settings (v0.7)
+    pub fn validate_moderate(&self) -> Result<(), NikaError> {
+        match self.moderate.as_ref().map(ModerateSpec::check) {
+            Some(Err(reason)) => Err(NikaError::ValidationError {
+                reason: format!("task '{}': moderate: {}", self.id, reason),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check `fetch: { paginate, graphql }` settings (v0.7)
+    pub fn validate_fetch(&self) -> Result<(), NikaError> {
+        let TaskAction::Fetch { fetch } = &self.action else {
+            return Ok(());
+        };
+        let invalid = |reason: String| NikaError::ValidationError {
+            reason: format!("task '{}': fetch {}", self.id, reason),
+        };
+        if let Some(Err(reason)) = fetch.paginate.as_ref().map(FetchPaginate::check) {
+            return Err(invalid(format!("paginate: {}", reason)));
+        }
+        let Some(graphql) = &fetch.graphql else {
+            if fetch.url.is_empty() {
+                return Err(invalid("needs 'url' or 'graphql'".to_string()));
+            }
+            return Ok(());
+        };
+        if !fetch.url.is_empty() || fetch.body.is_some() || fetch.paginate.is_some() {
+            return Err(invalid(
+                "graphql: cannot be combined with 'url', 'body' or 'paginate'".to_string(),
+            ));
+        }
+        graphql
+            .check()
+            .map_err(|reason| invalid(format!("graphql: {}", reason)))
+    }
+
+    /// Check `ws:` URL scheme and limits (v0.7)
+    pub fn validate_ws(&self) -> Result<(), NikaError> {
+        let TaskAction::Ws { ws } = &self.action else {
+            return Ok(());
+        };
+        ws.check().map_err(|reason| NikaError::ValidationError {
+            reason: format!("task '{}': ws {}", self.id, reason),
+        })
+    }
+
+    /// Check `exec: { sandbox }` and `exec: { image }` settings (v0.7)
+    pub fn validate_sandbox(&self) -> Result<(), NikaError> {
+        let TaskAction::Exec { exec } = &self.action else {
+            return Ok(());
+        };
+        if let Some(Err(reason)) = exec.sandbox.as_ref().map(ExecSandbox::check) {
+            return Err(NikaError::ValidationError {
+                reason: format!("task '{}': exec sandbox: {}", self.id, reason),
+            });
+        }
+        match exec.container.as_ref().map(ExecContainer::check) {
+            Some(Err(reason)) => Err(NikaError::ValidationError {
+                reason: format!("task '{}': exec image: {}", self.id, reason),
+            }),
+            _ => Ok(()),
+        }
+    }
+
     /// Check if this task has for_each iteration
     pub fn has_for_each(&self) -> bool {
         self.for_each.is_some()
@@ -332,14 +750,34 @@ impl Task {
     /// - 🛰️ fetch (HTTP request)
     /// - 🔌 invoke (MCP tool)
     /// - 🐔 agent (Agentic loop - parent)
+    /// - 🧮 reduce (Array aggregation)
+    /// - ✋ approve (Human-in-the-loop)
+    /// - 🧬 embed (Embeddings)
+    /// - 🔎 recall (Vector search)
     /// - 🐤 subagent (spawned via spawn_agent)
     pub fn action_icon(&self) -> &'static str {
         match &self.action {
-            TaskAction::Infer { .. } => "⚡",  // LLM generation
-            TaskAction::Exec { .. } => "📟",   // Shell command
-            TaskAction::Fetch { .. } => "🛰️",  // HTTP request
-            TaskAction::Invoke { .. } => "🔌", // MCP tool
-            TaskAction::Agent { .. } => "🐔",  // Agentic loop (parent)
+            TaskAction::Infer { .. } => "⚡",      // LLM generation
+            TaskAction::Exec { .. } => "📟",       // Shell command
+            TaskAction::Fetch { .. } => "🛰️",      // HTTP request
+            TaskAction::Invoke { .. } => "🔌",     // MCP tool
+            TaskAction::Agent { .. } => "🐔",      // Agentic loop (parent)
+            TaskAction::Reduce { .. } => "🧮",     // Array aggregation
+            TaskAction::Approve { .. } => "✋",    // Human-in-the-loop
+            TaskAction::Embed { .. } => "🧬",      // Embeddings
+            TaskAction::Recall { .. } => "🔎",     // Vector search
+            TaskAction::Retrieve { .. } => "📚",   // Document retrieval
+            TaskAction::Validate { .. } => "🛡️",   // Data quality gate
+            TaskAction::Transcribe { .. } => "🎙️", // Speech to text
+            TaskAction::Import { .. } => "📥",     // Spreadsheet in
+            TaskAction::Export { .. } => "📤",     // Spreadsheet out
+            TaskAction::Rows { .. } => "🗃️",       // Row batch ops
+            TaskAction::Script { .. } => "📜",     // Sandboxed script
+            TaskAction::Chunk { .. } => "✂️",      // Text splitting
+            TaskAction::Dedupe { .. } => "🧹",     // Near-duplicate removal
+            TaskAction::DetectLang { .. } => "🏳️", // Language detection
+            TaskAction::Translate { .. } => "🌐",  // Translation
+            TaskAction::Ws { .. } => "📶",         // WebSocket stream
         }
     }
 
@@ -362,6 +800,11 @@ impl Task {
 pub struct Flow {
     pub source: FlowEndpoint,
     pub target: FlowEndpoint,
+    /// Condition over the source output, e.g. `"{{from.status}} == 'approved'"` (v0.7)
+    ///
+    /// When false the edge is not taken; targets with no taken edge are skipped.
+    #[serde(default)]
+    pub when: Option<String>,
 }
 
 /// Handles string OR array for source/target
@@ -384,6 +827,7 @@ impl FlowEndpoint {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::OutputFormat;
 
     // ═══════════════════════════════════════════════════════════════════════════
     // WORKFLOW PARSING TESTS
@@ -667,6 +1111,37 @@ infer: "No decompose"
         assert!(task.decompose_spec().is_none());
     }
 
+    #[test]
+    fn test_validate_fetch_graphql() {
+        let task: Task = serde_yaml::from_str(
+            r#"
+id: repo
+fetch:
+  graphql:
+    endpoint: "https://api.example.com/graphql"
+    query: "query($id: ID!) { node(id: $id) { id } }"
+    variables: { id: "{{use.id}}" }
+"#,
+        )
+        .unwrap();
+        assert!(task.validate_fetch().is_ok());
+
+        let broken: Task = serde_yaml::from_str(
+            "id: repo\nfetch:\n  graphql:\n    endpoint: https://x\n    query: \"{ node(id: ) { id } }\"",
+        )
+        .unwrap();
+        let err = broken.validate_fetch().unwrap_err().to_string();
+        assert!(err.contains("fetch graphql: query: line 1"), "{}", err);
+
+        let both: Task = serde_yaml::from_str(
+            "id: repo\nfetch:\n  url: https://x\n  graphql: { endpoint: https://x, query: \"{ a }\" }",
+        )
+        .unwrap();
+        assert!(both.validate_fetch().is_err());
+        let neither: Task = serde_yaml::from_str("id: repo\nfetch: { method: GET }").unwrap();
+        assert!(neither.validate_fetch().is_err());
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // FOR_EACH VALIDATION TESTS
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1204,6 +1679,223 @@ tasks:
         assert_eq!(workflow.provider, "claude");
     }
 
+    #[test]
+    fn test_tasks_inherit_output_defaults() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+defaults:
+  all:
+    output: { stamp: true }
+  infer:
+    output: { format: json, schema: answer.json }
+tasks:
+  - id: plain
+    infer: "Answer"
+  - id: custom
+    infer: "Answer"
+    output: { schema: custom.json, stamp: false }
+  - id: opted_out
+    infer: "Answer"
+    output: false
+  - id: shell
+    exec: "date"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).expect("Failed to parse");
+        let output = |i: usize| workflow.tasks[i].output.as_ref();
+
+        let plain = output(0).unwrap();
+        assert_eq!(*plain.format(), OutputFormat::Json);
+        assert_eq!(plain.schema.as_deref(), Some("answer.json"));
+        assert!(plain.stamps());
+
+        let custom = output(1).unwrap();
+        assert_eq!(*custom.format(), OutputFormat::Json);
+        assert_eq!(custom.schema.as_deref(), Some("custom.json"));
+        assert!(!custom.stamps());
+
+        assert!(output(2).is_none());
+
+        let shell = output(3).unwrap();
+        assert_eq!(*shell.format(), OutputFormat::Text);
+        assert!(shell.schema.is_none());
+        assert!(shell.stamps());
+    }
+
+    #[test]
+    fn test_tasks_inherit_max_tokens_defaults() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+defaults:
+  all:
+    max_tokens: 1000
+  infer:
+    max_tokens: 4000
+tasks:
+  - id: draft
+    infer: "Draft"
+  - id: short
+    infer: { prompt: "Title", max_tokens: 20 }
+  - id: merge
+    reduce: { source: "$draft", strategy: summarize }
+  - id: shell
+    exec: "date"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).expect("Failed to parse");
+        let max_tokens = |i: usize| match &workflow.tasks[i].action {
+            TaskAction::Infer { infer } => infer.max_tokens,
+            TaskAction::Reduce { reduce } => reduce.max_tokens,
+            _ => None,
+        };
+        assert_eq!(max_tokens(0), Some(4000));
+        assert_eq!(max_tokens(1), Some(20));
+        assert_eq!(max_tokens(2), Some(1000));
+        assert!(workflow.tasks[3].output.is_none());
+
+        let err = serde_yaml::from_str::<Workflow>(
+            "schema: nika/workflow@0.5\ndefaults:\n  exec:\n    max_tokens: 10\ntasks: []\n",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("defaults.exec.max_tokens"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_defaults_reject_unknown_verb() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+defaults:
+  inference:
+    output: { format: json }
+tasks:
+  - id: task1
+    infer: "Test"
+"#;
+        let err = serde_yaml::from_str::<Workflow>(yaml).unwrap_err();
+        assert!(err.to_string().contains("unknown key 'inference'"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_schema_watch_trigger() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+triggers:
+  watch: "./inbox/*.md"
+  debounce_ms: 200
+tasks:
+  - id: summarize
+    use:
+      file: trigger.path
+    exec: "wc -l {{use.file}}"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).expect("Failed to parse");
+        assert!(workflow.validate_schema().is_ok());
+        let triggers = workflow.triggers.as_ref().unwrap();
+        assert_eq!(triggers.debounce(), Duration::from_millis(200));
+
+        // The binding id is reserved
+        let reserved = yaml.replace("id: summarize", "id: trigger");
+        let workflow: Workflow = serde_yaml::from_str(&reserved).expect("Failed to parse");
+        let err = workflow.validate_schema().unwrap_err();
+        assert_eq!(err.code(), "NIKA-008");
+
+        let invalid = yaml.replace("./inbox/*.md", "./inbox/[*.md");
+        let workflow: Workflow = serde_yaml::from_str(&invalid).expect("Failed to parse");
+        assert!(workflow.validate_schema().is_err());
+    }
+
+    #[test]
+    fn test_validate_schema_state_block() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: fetch
+    exec: "echo '{\"last\": 7}'"
+    state:
+      set:
+        last_id: last
+      ttl_secs: 3600
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).expect("Failed to parse");
+        assert!(workflow.validate_schema().is_ok());
+        let state = workflow.tasks[0].state.as_ref().unwrap();
+        assert_eq!(state.set["last_id"], "last");
+        assert_eq!(state.ttl(), Some(Duration::from_secs(3600)));
+
+        // The binding id is reserved
+        let reserved = yaml.replace("id: fetch", "id: state");
+        let workflow: Workflow = serde_yaml::from_str(&reserved).expect("Failed to parse");
+        assert_eq!(workflow.validate_schema().unwrap_err().code(), "NIKA-004");
+
+        // Keys are flat: `{{state.a.b}}` reads into the value of `a`
+        let dotted = yaml.replace("last_id: last", "run.last_id: last");
+        let workflow: Workflow = serde_yaml::from_str(&dotted).expect("Failed to parse");
+        assert!(workflow.validate_schema().is_err());
+    }
+
+    #[test]
+    fn test_validate_schema_transform_block() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: fetch
+    exec: "echo '{\"choices\": [\"id: 42\"]}'"
+    transform:
+      extract: "$.choices[0]"
+      regex: "id: (\\d+)"
+      parse: json
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).expect("Failed to parse");
+        assert!(workflow.validate_schema().is_ok());
+        let names: Vec<_> = workflow.tasks[0]
+            .transform
+            .as_ref()
+            .unwrap()
+            .steps
+            .iter()
+            .map(TransformStep::name)
+            .collect();
+        assert_eq!(names, ["extract", "regex", "parse"]);
+
+        let bad_regex = yaml.replace(r#"id: (\\d+)"#, "id: (");
+        let workflow: Workflow = serde_yaml::from_str(&bad_regex).expect("Failed to parse");
+        let err = workflow.validate_schema().unwrap_err();
+        assert_eq!(err.code(), "NIKA-004");
+        assert!(err.to_string().contains("transform step 2 (regex)"));
+
+        let bad_path = yaml.replace("$.choices[0]", "$.choices[");
+        let workflow: Workflow = serde_yaml::from_str(&bad_path).expect("Failed to parse");
+        assert!(workflow.validate_schema().is_err());
+    }
+
+    #[test]
+    fn test_validate_schema_moderate_block() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: draft
+    infer: "Write a reply"
+    moderate:
+      on: [input, output]
+      thresholds:
+        self_harm: 0.2
+      policy: flag
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).expect("Failed to parse");
+        assert!(workflow.validate_schema().is_ok());
+        let spec = workflow.tasks[0].moderate.as_ref().unwrap();
+        assert_eq!(spec.threshold("self_harm"), 0.2);
+
+        let bad = yaml.replace("self_harm", "spam");
+        let workflow: Workflow = serde_yaml::from_str(&bad).expect("Failed to parse");
+        let err = workflow.validate_schema().unwrap_err();
+        assert_eq!(err.code(), "NIKA-004");
+        assert!(err
+            .to_string()
+            .contains("task 'draft': moderate: unknown category 'spam'"));
+    }
+
     #[test]
     fn test_task_as_field_empty_string() {
         let yaml = r#"
@@ -0,0 +1,114 @@
+//! WebSocket Action - collect messages from a streaming API (v0.7)
+//!
+//! `ws:` connects to a WebSocket endpoint, sends optional messages (a
+//! subscription request, usually), then collects what the server sends
+//! until one of:
+//!
+//! - a message matches `until` (it is kept)
+//! - `max_messages` have arrived
+//! - `timeout_ms` has elapsed, or the server closes the connection
+//!
+//! The output is the array of messages: JSON messages as values, others as
+//! strings. Each one is also emitted as a `WsMessage` event, so the Monitor
+//! shows them live.
+//!
+//! # Example
+//!
+//! ```yaml
+//! tasks:
+//!   - id: ticker
+//!     ws:
+//!       url: "wss://stream.example.com/v1"
+//!       headers:
+//!         Authorization: "Bearer {{use.token}}"
+//!       send:
+//!         - '{"op": "subscribe", "channel": "trades"}'
+//!       until: "type == 'snapshot_end'"   # rows: filter grammar
+//!       max_messages: 500
+//!       timeout_ms: 10000
+//! ```
+//!
+//! `until` uses the `rows:` filter grammar over each message; text messages
+//! are matched as `{ text: "..." }`.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Default collection window
+pub const DEFAULT_WS_TIMEOUT_MS: u64 = 30_000;
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_WS_TIMEOUT_MS
+}
+
+/// WebSocket action parameters (v0.7)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct WsParams {
+    /// `ws://` or `wss://` endpoint (supports templates)
+    pub url: String,
+    /// Handshake headers (values support templates)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Text messages sent in order once connected (support templates)
+    #[serde(default)]
+    pub send: Vec<String>,
+    /// Stop after the first message matching this filter expression
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Stop after this many messages
+    #[serde(default)]
+    pub max_messages: Option<usize>,
+    /// Stop collecting after this long (milliseconds, default 30s)
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl WsParams {
+    /// Static checks: URL scheme and limits
+    pub fn check(&self) -> Result<(), String> {
+        let scheme_ok = self.url.starts_with("ws://") || self.url.starts_with("wss://");
+        // Templated URLs are checked once resolved, by the handshake
+        if !scheme_ok && !self.url.contains("{{") {
+            return Err(format!(
+                "url '{}' must start with ws:// or wss://",
+                self.url
+            ));
+        }
+        if self.timeout_ms == 0 {
+            return Err("timeout_ms must be greater than 0".to_string());
+        }
+        if self.max_messages == Some(0) {
+            return Err("max_messages must be greater than 0".to_string());
+        }
+        if self.until.as_deref().is_some_and(|u| u.trim().is_empty()) {
+            return Err("until must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_check_ws() {
+        let yaml =
+            "url: wss://stream.example.com\nsend: ['{\"op\":\"sub\"}']\nuntil: \"type == 'end'\"";
+        let ws: WsParams = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(ws.timeout_ms, DEFAULT_WS_TIMEOUT_MS);
+        assert_eq!(ws.send.len(), 1);
+        assert!(ws.check().is_ok());
+
+        let http: WsParams = serde_yaml::from_str("url: https://example.com").unwrap();
+        assert!(http.check().unwrap_err().contains("ws://"));
+        let templated: WsParams = serde_yaml::from_str("url: \"{{use.endpoint}}\"").unwrap();
+        assert!(templated.check().is_ok());
+        let none: WsParams =
+            serde_yaml::from_str("url: ws://localhost:9000\nmax_messages: 0").unwrap();
+        assert!(none.check().is_err());
+        assert!(serde_yaml::from_str::<WsParams>("url: ws://x\nretries: 2").is_err());
+    }
+}
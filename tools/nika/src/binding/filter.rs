@@ -0,0 +1,461 @@
+//! Template Filters - `{{use.alias | name(args)}}` transforms (v0.7)
+//!
+//! Filters run left to right on the resolved JSON value before it is
+//! substituted, so small reshaping no longer needs an extra `exec:` task:
+//!
+//! ```yaml
+//! infer: |
+//!   {{use.items | length}} items, first: {{use.items | first | upper}}
+//!   Context: {{use.page | truncate(2000)}}
+//!   Score: {{use.report | jsonpath('$.scores[0]') | default(0)}}
+//! ```
+//!
+//! Built-in filters:
+//! - `length`: characters of text, items of an array, keys of an object
+//! - `truncate(n, suffix?)`: first `n` characters (or items), `suffix` appended when cut
+//! - `jsonpath(path)`: value at `path`; JSON text is parsed first
+//! - `upper`, `lower`, `trim`: text case and whitespace
+//! - `default(value)`: `value` when the input is null or the path is missing
+//! - `join(sep?)`: array items as text, separated by `sep` (default `", "`)
+//! - `first`, `last`: first or last array item (or character)
+//! - `json`: value as compact JSON text (strings get quotes)
+//!
+//! Embedders add their own with [`register`]:
+//!
+//! ```rust,ignore
+//! nika::binding::filter::register(Arc::new(SlugifyFilter));   // {{use.title | slugify}}
+//! ```
+
+use std::sync::{Arc, LazyLock};
+
+use dashmap::DashMap;
+use serde_json::Value;
+
+use crate::util::jsonpath;
+
+/// A named template filter
+pub trait Filter: Send + Sync {
+    /// Name used after `|` in a template
+    fn name(&self) -> &str;
+
+    /// Transform `value`; the error says which input or argument is wrong
+    fn apply(&self, value: Value, args: &[Value]) -> Result<Value, String>;
+}
+
+static REGISTRY: LazyLock<DashMap<String, Arc<dyn Filter>>> = LazyLock::new(|| {
+    let builtins: [Arc<dyn Filter>; 11] = [
+        Arc::new(Length),
+        Arc::new(Truncate),
+        Arc::new(JsonPath),
+        Arc::new(Case("upper", str::to_uppercase)),
+        Arc::new(Case("lower", str::to_lowercase)),
+        Arc::new(Case("trim", |s| s.trim().to_string())),
+        Arc::new(DefaultTo),
+        Arc::new(Join),
+        Arc::new(Pick("first", false)),
+        Arc::new(Pick("last", true)),
+        Arc::new(Json),
+    ];
+    builtins
+        .into_iter()
+        .map(|filter| (filter.name().to_string(), filter))
+        .collect()
+});
+
+/// Add a filter, replacing any filter with the same name
+pub fn register(filter: Arc<dyn Filter>) {
+    REGISTRY.insert(filter.name().to_string(), filter);
+}
+
+/// The filter registered under `name`
+pub fn get(name: &str) -> Option<Arc<dyn Filter>> {
+    REGISTRY.get(name).map(|entry| Arc::clone(entry.value()))
+}
+
+/// Registered filter names, sorted
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = REGISTRY.iter().map(|entry| entry.key().clone()).collect();
+    names.sort();
+    names
+}
+
+/// A parsed `| name(args)` step
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterCall {
+    pub name: String,
+    pub args: Vec<Value>,
+}
+
+/// Parse a filter chain (`| length`, `| truncate(500) | upper`)
+///
+/// Arguments are `'text'`, `"text"`, numbers, `true`, `false` or `null`.
+pub fn parse_chain(chain: &str) -> Result<Vec<FilterCall>, String> {
+    split_outside_quotes(chain, '|')
+        .into_iter()
+        .skip(1)
+        .map(|step| {
+            let step = step.trim();
+            let (name, args) = match step.split_once('(') {
+                Some((name, rest)) => {
+                    let inner = rest
+                        .trim_end()
+                        .strip_suffix(')')
+                        .ok_or_else(|| format!("missing ')' in '{}'", step))?;
+                    (name.trim(), parse_args(inner)?)
+                }
+                None => (step, Vec::new()),
+            };
+            if name.is_empty() {
+                return Err("empty filter name".to_string());
+            }
+            Ok(FilterCall {
+                name: name.to_string(),
+                args,
+            })
+        })
+        .collect()
+}
+
+/// Run `calls` on `value` in order
+pub fn apply_chain(mut value: Value, calls: &[FilterCall]) -> Result<Value, String> {
+    for call in calls {
+        let filter = get(&call.name).ok_or_else(|| {
+            format!(
+                "unknown filter '{}' (available: {})",
+                call.name,
+                names().join(", ")
+            )
+        })?;
+        value = filter
+            .apply(value, &call.args)
+            .map_err(|e| format!("{}: {}", call.name, e))?;
+    }
+    Ok(value)
+}
+
+fn parse_args(inner: &str) -> Result<Vec<Value>, String> {
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    split_outside_quotes(inner, ',')
+        .into_iter()
+        .map(|arg| {
+            let arg = arg.trim();
+            let quoted = ['\'', '"'].into_iter().find_map(|q| {
+                arg.strip_prefix(q)
+                    .and_then(|rest| rest.strip_suffix(q))
+                    .filter(|_| arg.len() >= 2)
+            });
+            match quoted {
+                Some(text) => Ok(Value::String(text.to_string())),
+                None => serde_json::from_str::<Value>(arg)
+                    .ok()
+                    .filter(|v| !v.is_object() && !v.is_array() && !v.is_string())
+                    .ok_or_else(|| format!("invalid argument '{}' (quote text)", arg)),
+            }
+        })
+        .collect()
+}
+
+fn split_outside_quotes(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, c) if c == sep => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn text(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(format!("expects text, got {}", type_name(other))),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn usize_arg(args: &[Value], i: usize) -> Result<usize, String> {
+    args.get(i)
+        .and_then(Value::as_u64)
+        .map(|n| n as usize)
+        .ok_or_else(|| "expects a non-negative integer argument".to_string())
+}
+
+fn str_arg<'a>(args: &'a [Value], i: usize, default: &'a str) -> Result<&'a str, String> {
+    match args.get(i) {
+        None => Ok(default),
+        Some(Value::String(s)) => Ok(s),
+        Some(other) => Err(format!("expects a text argument, got {}", type_name(other))),
+    }
+}
+
+struct Length;
+
+impl Filter for Length {
+    fn name(&self) -> &str {
+        "length"
+    }
+
+    fn apply(&self, value: Value, _args: &[Value]) -> Result<Value, String> {
+        let len = match &value {
+            Value::String(s) => s.chars().count(),
+            Value::Array(items) => items.len(),
+            Value::Object(map) => map.len(),
+            other => {
+                return Err(format!(
+                    "expects text, array or object, got {}",
+                    type_name(other)
+                ))
+            }
+        };
+        Ok(len.into())
+    }
+}
+
+struct Truncate;
+
+impl Filter for Truncate {
+    fn name(&self) -> &str {
+        "truncate"
+    }
+
+    fn apply(&self, value: Value, args: &[Value]) -> Result<Value, String> {
+        let max = usize_arg(args, 0)?;
+        let suffix = str_arg(args, 1, "")?;
+        if let Value::Array(mut items) = value {
+            items.truncate(max);
+            return Ok(Value::Array(items));
+        }
+        let s = text(&value)?;
+        match s.char_indices().nth(max) {
+            Some((cut, _)) => Ok(format!("{}{}", &s[..cut], suffix).into()),
+            None => Ok(s.into()),
+        }
+    }
+}
+
+struct JsonPath;
+
+impl Filter for JsonPath {
+    fn name(&self) -> &str {
+        "jsonpath"
+    }
+
+    fn apply(&self, value: Value, args: &[Value]) -> Result<Value, String> {
+        let path = match args.first() {
+            Some(Value::String(path)) => path,
+            _ => return Err("expects a path argument, e.g. jsonpath('$.a[0]')".to_string()),
+        };
+        let value = match value {
+            Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::String(s)),
+            other => other,
+        };
+        let found = jsonpath::resolve(&value, path).map_err(|e| e.to_string())?;
+        Ok(found.unwrap_or(Value::Null))
+    }
+}
+
+struct Case(&'static str, fn(&str) -> String);
+
+impl Filter for Case {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn apply(&self, value: Value, _args: &[Value]) -> Result<Value, String> {
+        Ok((self.1)(&text(&value)?).into())
+    }
+}
+
+struct DefaultTo;
+
+impl Filter for DefaultTo {
+    fn name(&self) -> &str {
+        "default"
+    }
+
+    fn apply(&self, value: Value, args: &[Value]) -> Result<Value, String> {
+        let fallback = args
+            .first()
+            .ok_or_else(|| "expects a fallback argument".to_string())?;
+        Ok(if value.is_null() {
+            fallback.clone()
+        } else {
+            value
+        })
+    }
+}
+
+struct Join;
+
+impl Filter for Join {
+    fn name(&self) -> &str {
+        "join"
+    }
+
+    fn apply(&self, value: Value, args: &[Value]) -> Result<Value, String> {
+        let sep = str_arg(args, 0, ", ")?;
+        let Value::Array(items) = value else {
+            return Err(format!("expects an array, got {}", type_name(&value)));
+        };
+        let parts = items
+            .iter()
+            .map(|item| match item {
+                Value::Array(_) | Value::Object(_) => Ok(item.to_string()),
+                other => text(other),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(parts.join(sep).into())
+    }
+}
+
+struct Pick(&'static str, bool);
+
+impl Filter for Pick {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn apply(&self, value: Value, _args: &[Value]) -> Result<Value, String> {
+        let last = self.1;
+        match value {
+            Value::Array(mut items) => Ok(if last {
+                items.pop()
+            } else {
+                items.into_iter().next()
+            }
+            .unwrap_or(Value::Null)),
+            Value::String(s) => {
+                let c = if last {
+                    s.chars().last()
+                } else {
+                    s.chars().next()
+                };
+                Ok(c.map_or(Value::Null, |c| c.to_string().into()))
+            }
+            other => Err(format!(
+                "expects an array or text, got {}",
+                type_name(&other)
+            )),
+        }
+    }
+}
+
+struct Json;
+
+impl Filter for Json {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn apply(&self, value: Value, _args: &[Value]) -> Result<Value, String> {
+        Ok(value.to_string().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(value: Value, chain: &str) -> Result<Value, String> {
+        apply_chain(value, &parse_chain(chain)?)
+    }
+
+    #[test]
+    fn test_parse_chain() {
+        let calls = parse_chain(" | truncate(10, '...') | jsonpath('$.a|b') | upper").unwrap();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].args, vec![json!(10), json!("...")]);
+        assert_eq!(calls[1].args, vec![json!("$.a|b")]);
+        assert!(calls[2].args.is_empty());
+
+        assert!(parse_chain("| truncate(10").is_err());
+        assert!(parse_chain("| default(bare)").is_err());
+        assert!(parse_chain("| ").is_err());
+    }
+
+    #[test]
+    fn test_builtin_filters() {
+        assert_eq!(run(json!([1, 2, 3]), "| length").unwrap(), json!(3));
+        assert_eq!(run(json!("héllo"), "| length").unwrap(), json!(5));
+        assert_eq!(
+            run(json!("abcdef"), "| truncate(3, '…')").unwrap(),
+            json!("abc…")
+        );
+        assert_eq!(
+            run(json!("abc"), "| truncate(3, '…')").unwrap(),
+            json!("abc")
+        );
+        assert_eq!(
+            run(json!([1, 2, 3]), "| truncate(2) | length").unwrap(),
+            json!(2)
+        );
+        assert_eq!(
+            run(json!(r#"{"a": [7, 8]}"#), "| jsonpath('$.a[0]')").unwrap(),
+            json!(7)
+        );
+        assert_eq!(run(json!("  Hi "), "| trim | upper").unwrap(), json!("HI"));
+        assert_eq!(
+            run(json!(null), "| default('none')").unwrap(),
+            json!("none")
+        );
+        assert_eq!(
+            run(json!({"a": 1}), "| jsonpath('b') | default(0)").unwrap(),
+            json!(0)
+        );
+        assert_eq!(
+            run(json!(["a", 1]), "| join(' + ')").unwrap(),
+            json!("a + 1")
+        );
+        assert_eq!(run(json!(["x", "y"]), "| last").unwrap(), json!("y"));
+        assert_eq!(run(json!("x"), "| json").unwrap(), json!("\"x\""));
+    }
+
+    #[test]
+    fn test_filter_errors_and_registry() {
+        let err = run(json!(1), "| nope").unwrap_err();
+        assert!(err.contains("unknown filter 'nope'") && err.contains("length"));
+        assert!(run(json!({"a": 1}), "| upper")
+            .unwrap_err()
+            .starts_with("upper:"));
+
+        struct Double;
+        impl Filter for Double {
+            fn name(&self) -> &str {
+                "double"
+            }
+            fn apply(&self, value: Value, _args: &[Value]) -> Result<Value, String> {
+                value
+                    .as_i64()
+                    .map(|n| json!(n * 2))
+                    .ok_or_else(|| "expects an integer".to_string())
+            }
+        }
+        register(Arc::new(Double));
+        assert_eq!(run(json!([1, 2]), "| length | double").unwrap(), json!(4));
+        assert!(names().contains(&"double".to_string()));
+    }
+}
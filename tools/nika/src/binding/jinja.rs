@@ -0,0 +1,328 @@
+//! Jinja Templates - opt-in minijinja engine per task (v0.7)
+//!
+//! `template_engine: jinja` renders a task's templates with minijinja
+//! instead of `{{use.alias}}` substitution, for loops, conditionals and
+//! the builtin filters. The binding namespace is the context:
+//! - `use`: every `use:` alias (lazy ones resolved first) and the
+//!   `for_each` item
+//! - `state`: the project state (`{{ state.key }}`)
+//!
+//! ```yaml
+//! - id: digest
+//!   template_engine: jinja
+//!   use:
+//!     posts: fetch_posts.items
+//!   infer: |
+//!     {% for post in use.posts %}
+//!     - {% if post.draft %}[draft] {% endif %}{{ post.title }}
+//!     {% endfor %}
+//! ```
+//!
+//! Undefined values are errors, like unresolved `{{use.x}}` refs. Block
+//! tags don't leave their own line behind (`trim_blocks`, `lstrip_blocks`:
+//! the newline right after a tag is dropped) and trailing newlines are kept. A template that is a JSON document
+//! (`invoke:` params, GraphQL variables) renders string by string, so
+//! values never break the JSON.
+//!
+//! Needs the `jinja` feature (on by default).
+
+use rustc_hash::FxHashSet;
+use serde_json::{Map, Value};
+
+use crate::ast::STATE_TASK_ID;
+use crate::error::NikaError;
+use crate::store::DataStore;
+
+use super::resolve::ResolvedBindings;
+
+/// Whether a template contains any Jinja syntax
+pub(super) fn has_syntax(template: &str) -> bool {
+    template.contains("{{") || template.contains("{%") || template.contains("{#")
+}
+
+/// Render `template` with the task's bindings and the project state
+pub(super) fn render(
+    template: &str,
+    bindings: &ResolvedBindings,
+    datastore: &DataStore,
+) -> Result<String, NikaError> {
+    let context = context(bindings, datastore);
+    match json_document(template) {
+        Some(mut document) => {
+            render_strings(&mut document, &context)?;
+            Ok(document.to_string())
+        }
+        None => engine::render(template, &context),
+    }
+}
+
+/// Check syntax and `use.` references (static validation)
+///
+/// References to aliases missing from `declared_aliases` are NIKA-071,
+/// like with the default engine.
+pub fn validate(
+    template: &str,
+    declared_aliases: &FxHashSet<String>,
+    task_id: &str,
+) -> Result<(), NikaError> {
+    if !has_syntax(template) {
+        return Ok(());
+    }
+    let templates = match json_document(template) {
+        Some(document) => {
+            let mut strings = Vec::new();
+            collect_strings(&document, &mut strings);
+            strings
+        }
+        None => vec![template.to_string()],
+    };
+    for template in templates.iter().filter(|t| has_syntax(t)) {
+        for alias in engine::used_aliases(template)? {
+            if !declared_aliases.contains(&alias) {
+                return Err(NikaError::UnknownAlias {
+                    alias,
+                    task_id: task_id.to_string(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `{"use": {alias: value}, "state": {...}}`
+///
+/// Lazy bindings that can't resolve yet are left out: using one is an
+/// undefined-value error, not using it is fine.
+fn context(bindings: &ResolvedBindings, datastore: &DataStore) -> Value {
+    let uses: Map<String, Value> = bindings
+        .aliases()
+        .filter_map(|alias| {
+            let value = bindings.get_resolved(alias, datastore).ok()?;
+            Some((alias.to_string(), value))
+        })
+        .collect();
+    let state = datastore
+        .get_output(STATE_TASK_ID)
+        .map(|state| state.as_ref().clone())
+        .unwrap_or_else(|| Value::Object(Map::new()));
+
+    let mut context = Map::new();
+    context.insert("use".to_string(), Value::Object(uses));
+    context.insert(STATE_TASK_ID.to_string(), state);
+    Value::Object(context)
+}
+
+/// The template parsed as a JSON object or array, if it is one
+fn json_document(template: &str) -> Option<Value> {
+    let trimmed = template.trim_start();
+    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+        return None;
+    }
+    serde_json::from_str::<Value>(template)
+        .ok()
+        .filter(|value| value.is_object() || value.is_array())
+}
+
+fn render_strings(value: &mut Value, context: &Value) -> Result<(), NikaError> {
+    match value {
+        Value::String(s) if has_syntax(s) => *s = engine::render(s, context)?,
+        Value::Array(items) => {
+            for item in items {
+                render_strings(item, context)?;
+            }
+        }
+        Value::Object(map) => {
+            for item in map.values_mut() {
+                render_strings(item, context)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(s) => strings.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, strings)),
+        _ => {}
+    }
+}
+
+/// First line of a template, shortened, for error messages
+fn snippet(template: &str) -> String {
+    let line = template.lines().next().unwrap_or_default();
+    match line.char_indices().nth(60) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(feature = "jinja")]
+mod engine {
+    use std::sync::LazyLock;
+
+    use minijinja::{Environment, UndefinedBehavior};
+    use serde_json::Value;
+
+    use super::snippet;
+    use crate::error::NikaError;
+
+    /// Shared environment (no loader: templates are rendered from strings)
+    static ENV: LazyLock<Environment<'static>> = LazyLock::new(|| {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        env.set_keep_trailing_newline(true);
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env
+    });
+
+    pub(super) fn render(template: &str, context: &Value) -> Result<String, NikaError> {
+        ENV.render_str(template, context)
+            .map_err(|e| error(template, e))
+    }
+
+    /// Aliases read through `use.<alias>` in the template
+    pub(super) fn used_aliases(template: &str) -> Result<Vec<String>, NikaError> {
+        let compiled = ENV
+            .template_from_str(template)
+            .map_err(|e| error(template, e))?;
+        let mut aliases: Vec<String> = compiled
+            .undeclared_variables(true)
+            .into_iter()
+            .filter_map(|var| {
+                let alias = var.strip_prefix("use.")?;
+                Some(alias.split('.').next().unwrap_or(alias).to_string())
+            })
+            .collect();
+        aliases.sort_unstable();
+        aliases.dedup();
+        Ok(aliases)
+    }
+
+    fn error(template: &str, e: minijinja::Error) -> NikaError {
+        NikaError::TemplateError {
+            template: snippet(template),
+            reason: e.to_string(),
+        }
+    }
+}
+
+#[cfg(not(feature = "jinja"))]
+mod engine {
+    use serde_json::Value;
+
+    use super::snippet;
+    use crate::error::NikaError;
+
+    fn disabled(template: &str) -> NikaError {
+        NikaError::TemplateError {
+            template: snippet(template),
+            reason: "template_engine: jinja requires the `jinja` feature".to_string(),
+        }
+    }
+
+    pub(super) fn render(template: &str, _context: &Value) -> Result<String, NikaError> {
+        Err(disabled(template))
+    }
+
+    pub(super) fn used_aliases(template: &str) -> Result<Vec<String>, NikaError> {
+        Err(disabled(template))
+    }
+}
+
+#[cfg(all(test, feature = "jinja"))]
+mod tests {
+    use super::*;
+    use crate::store::TaskResult;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn bindings(values: Value) -> ResolvedBindings {
+        let mut bindings = ResolvedBindings::new();
+        for (alias, value) in values.as_object().unwrap() {
+            bindings.set(alias.clone(), value.clone());
+        }
+        bindings
+    }
+
+    #[test]
+    fn test_renders_loops_conditionals_and_filters() {
+        let bindings = bindings(json!({
+            "posts": [
+                {"title": "Launch", "draft": false},
+                {"title": "Roadmap", "draft": true}
+            ]
+        }));
+        let template = "\
+{% for post in use.posts %}
+- {% if post.draft %}[draft] {% endif %}{{ post.title | upper }}
+{% endfor %}
+{{ use.posts | length }} posts
+";
+        let rendered = render(template, &bindings, &DataStore::new()).unwrap();
+        assert_eq!(rendered, "- LAUNCH\n- [draft] ROADMAP\n2 posts\n");
+    }
+
+    #[test]
+    fn test_reads_state_and_lazy_bindings() {
+        let datastore = DataStore::new();
+        datastore.insert(
+            Arc::from(STATE_TASK_ID),
+            TaskResult::success(json!({"last_id": 41}), Duration::ZERO),
+        );
+        datastore.insert(
+            Arc::from("draft"),
+            TaskResult::success(json!({"text": "Hello"}), Duration::ZERO),
+        );
+        let wiring: crate::binding::WiringSpec = serde_yaml::from_str(
+            "text:\n  path: draft.text\n  lazy: true\nlater:\n  path: missing.value\n  lazy: true\n",
+        )
+        .unwrap();
+        let bindings = ResolvedBindings::from_wiring_spec(Some(&wiring), &datastore).unwrap();
+
+        let rendered = render(
+            "{{ use.text }} #{{ state.last_id + 1 }}",
+            &bindings,
+            &datastore,
+        )
+        .unwrap();
+        assert_eq!(rendered, "Hello #42");
+
+        let err = render("{{ use.later }}", &bindings, &datastore).unwrap_err();
+        assert!(err.to_string().contains("NIKA-041"), "{err}");
+    }
+
+    #[test]
+    fn test_json_documents_render_string_by_string() {
+        let bindings = bindings(json!({"query": "say \"hi\"", "tags": ["a", "b"]}));
+        let rendered = render(
+            r#"{"q": "{{ use.query }}", "tags": "{{ use.tags | join(',') }}", "n": 3}"#,
+            &bindings,
+            &DataStore::new(),
+        )
+        .unwrap();
+        let params: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(params, json!({"q": "say \"hi\"", "tags": "a,b", "n": 3}));
+    }
+
+    #[test]
+    fn test_validate_checks_syntax_and_aliases() {
+        let declared: FxHashSet<String> = ["posts".to_string()].into_iter().collect();
+        assert!(validate(
+            "{% for p in use.posts %}{{ p.title }}{% endfor %}",
+            &declared,
+            "digest"
+        )
+        .is_ok());
+
+        let err = validate("{{ use.post.title }}", &declared, "digest").unwrap_err();
+        assert!(matches!(err, NikaError::UnknownAlias { ref alias, .. } if alias == "post"));
+
+        let err = validate("{% for p in use.posts %}", &declared, "digest").unwrap_err();
+        assert!(err.to_string().contains("NIKA-041"), "{err}");
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! Handles the `use:` block system for explicit data binding:
 //! - `entry`: YAML types (WiringSpec, UseEntry) - unified and extended syntax
+//! - `filter`: Template filters (`{{use.items | length}}`) and their registry (v0.7)
+//! - `jinja`: minijinja rendering for `template_engine: jinja` tasks (v0.7)
 //! - `resolve`: Runtime resolution (ResolvedBindings) with lazy support
-//! - `template`: Template substitution (`{{use.alias}}`)
+//! - `template`: Template substitution (`{{use.alias}}`), escaping and strict mode
 //!
 //! Unified `use:` syntax (eager resolution):
 //! ```yaml
@@ -45,12 +47,18 @@
 //! ```
 
 mod entry;
+pub mod filter;
+mod jinja;
 mod resolve;
 mod template;
 mod validate;
 
 // Re-export public types
 pub use entry::{parse_use_entry, UseEntry, WiringSpec};
+pub use jinja::validate as validate_jinja_template;
 pub use resolve::{LazyBinding, ResolvedBindings};
-pub use template::{extract_refs, resolve as template_resolve, validate_refs};
+pub use template::{
+    extract_refs, resolve as template_resolve, resolve_with_mode as template_resolve_with_mode,
+    validate_refs, validate_syntax as validate_template_syntax, TemplateEngine, TemplateMode,
+};
 pub use validate::validate_task_id;
@@ -8,7 +8,7 @@
 //!
 //! Uses FxHashMap for faster hashing (consistent with FlowGraph).
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde_json::Value;
 
 use crate::error::NikaError;
@@ -16,6 +16,7 @@ use crate::store::DataStore;
 use crate::util::jsonpath;
 
 use super::entry::{UseEntry, WiringSpec};
+use super::template::TemplateEngine;
 
 /// Lazy binding state - either resolved or pending (v0.5)
 #[derive(Debug, Clone)]
@@ -52,6 +53,10 @@ impl LazyBinding {
 pub struct ResolvedBindings {
     /// Alias -> binding mappings (resolved or pending)
     bindings: FxHashMap<String, LazyBinding>,
+    /// Eager aliases that fell back to their `?? default` (v0.7)
+    defaulted: FxHashSet<String>,
+    /// Engine rendering templates against these bindings (v0.7)
+    engine: TemplateEngine,
 }
 
 impl ResolvedBindings {
@@ -92,7 +97,10 @@ impl ResolvedBindings {
                 );
             } else {
                 // Eager binding - resolve immediately
-                let value = resolve_entry(entry, alias, datastore)?;
+                let (value, used_default) = resolve_entry_tracked(entry, alias, datastore)?;
+                if used_default {
+                    bindings.defaulted.insert(alias.clone());
+                }
                 bindings
                     .bindings
                     .insert(alias.clone(), LazyBinding::Resolved(value));
@@ -102,6 +110,17 @@ impl ResolvedBindings {
         Ok(bindings)
     }
 
+    /// Render this task's templates with `engine` (v0.7)
+    pub fn with_engine(mut self, engine: TemplateEngine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Engine rendering templates against these bindings (v0.7)
+    pub fn engine(&self) -> TemplateEngine {
+        self.engine
+    }
+
     /// Set a resolved value (always eager)
     pub fn set(&mut self, alias: impl Into<String>, value: Value) {
         self.bindings
@@ -149,6 +168,11 @@ impl ResolvedBindings {
             .unwrap_or(false)
     }
 
+    /// Check if an eager binding fell back to its default value (v0.7)
+    pub fn used_default(&self, alias: &str) -> bool {
+        self.defaulted.contains(alias)
+    }
+
     /// Check if context has any bindings
     #[allow(dead_code)] // Used in tests
     pub fn is_empty(&self) -> bool {
@@ -165,6 +189,11 @@ impl ResolvedBindings {
             .filter_map(|(alias, binding)| binding.get_value().map(|value| (alias.as_str(), value)))
     }
 
+    /// Every alias, resolved or pending (v0.7)
+    pub fn aliases(&self) -> impl Iterator<Item = &str> {
+        self.bindings.keys().map(String::as_str)
+    }
+
     /// Serialize context to JSON Value for event logging
     ///
     /// Returns the full resolved inputs as a JSON object.
@@ -198,6 +227,15 @@ impl ResolvedBindings {
 /// 3. Resolve remaining path within output
 /// 4. Apply default if value is null/missing
 fn resolve_entry(entry: &UseEntry, alias: &str, datastore: &DataStore) -> Result<Value, NikaError> {
+    resolve_entry_tracked(entry, alias, datastore).map(|(value, _)| value)
+}
+
+/// Resolve a UseEntry, also reporting whether the default was applied
+fn resolve_entry_tracked(
+    entry: &UseEntry,
+    alias: &str,
+    datastore: &DataStore,
+) -> Result<(Value, bool), NikaError> {
     let path = &entry.path;
 
     // Split path into task_id and remaining path
@@ -217,11 +255,11 @@ fn resolve_entry(entry: &UseEntry, alias: &str, datastore: &DataStore) -> Result
 
     // Apply default if value is null or missing
     match value {
-        Some(v) if !v.is_null() => Ok(v),
+        Some(v) if !v.is_null() => Ok((v, false)),
         Some(_) => entry
             .default
             .as_ref()
-            .cloned()
+            .map(|d| (d.clone(), true))
             .ok_or_else(|| NikaError::NullValue {
                 path: path.clone(),
                 alias: alias.to_string(),
@@ -229,7 +267,7 @@ fn resolve_entry(entry: &UseEntry, alias: &str, datastore: &DataStore) -> Result
         None => entry
             .default
             .as_ref()
-            .cloned()
+            .map(|d| (d.clone(), true))
             .ok_or_else(|| NikaError::PathNotFound { path: path.clone() }),
     }
 }
@@ -379,6 +417,26 @@ mod tests {
 
         let bindings = ResolvedBindings::from_wiring_spec(Some(&wiring), &store).unwrap();
         assert_eq!(bindings.get("forecast"), Some(&json!("N/A")));
+        assert!(bindings.used_default("forecast"));
+    }
+
+    #[test]
+    fn used_default_false_when_path_resolves() {
+        let store = DataStore::new();
+        store.insert(
+            Arc::from("weather"),
+            TaskResult::success(json!({"summary": "Sunny"}), Duration::from_secs(1)),
+        );
+
+        let mut wiring = WiringSpec::default();
+        wiring.insert(
+            "forecast".to_string(),
+            UseEntry::with_default("weather.summary", json!("N/A")),
+        );
+
+        let bindings = ResolvedBindings::from_wiring_spec(Some(&wiring), &store).unwrap();
+        assert_eq!(bindings.get("forecast"), Some(&json!("Sunny")));
+        assert!(!bindings.used_default("forecast"));
     }
 
     #[test]
@@ -465,6 +523,37 @@ mod tests {
         assert_eq!(bindings.get("first"), Some(&json!("first")));
     }
 
+    #[test]
+    fn resolve_jsonpath_filter_and_descent() {
+        let store = DataStore::new();
+        store.insert(
+            Arc::from("shop"),
+            TaskResult::success(
+                json!({"items": [
+                    {"id": 1, "price": 5, "name": "pen"},
+                    {"id": 2, "price": 15, "name": "lamp"}
+                ]}),
+                Duration::from_secs(1),
+            ),
+        );
+
+        let mut wiring = WiringSpec::default();
+        wiring.insert(
+            "pricey".to_string(),
+            UseEntry::new("shop.items[?(@.price > 10)].name"),
+        );
+        wiring.insert("ids".to_string(), UseEntry::new("shop..id"));
+        wiring.insert(
+            "none".to_string(),
+            UseEntry::new("shop.items[?(@.price > 99)]"),
+        );
+
+        let bindings = ResolvedBindings::from_wiring_spec(Some(&wiring), &store).unwrap();
+        assert_eq!(bindings.get("pricey"), Some(&json!(["lamp"])));
+        assert_eq!(bindings.get("ids"), Some(&json!([1, 2])));
+        assert_eq!(bindings.get("none"), Some(&json!([])));
+    }
+
     // ═══════════════════════════════════════════════════════════════
     // split_path() tests
     // ═══════════════════════════════════════════════════════════════
@@ -9,23 +9,56 @@
 //! - Better capacity estimation for result string
 //!
 //! v0.5: Supports lazy bindings via DataStore parameter.
+//!
+//! v0.7: `\{{` escapes a literal delimiter, and `TemplateMode::Strict`
+//! rejects `{{...}}` sequences that aren't valid `{{use.*}}` references.
+//! `{{state.key}}` reads the project state (see `store::StateStore`).
+//! Substituted values are never re-scanned, so inputs containing `{{`
+//! can't inject template references.
+//! `{{use.items | length}}` runs a filter chain (see `binding::filter`).
+//! Tasks with `template_engine: jinja` render with minijinja (see
+//! `binding::jinja`).
 
 use std::borrow::Cow;
 use std::sync::LazyLock;
 
 use regex::Regex;
 use rustc_hash::FxHashSet;
+use serde::Deserialize;
 use serde_json::Value;
 use smallvec::SmallVec;
 
+use crate::ast::STATE_TASK_ID;
 use crate::error::NikaError;
 use crate::store::DataStore;
+use crate::util::jsonpath;
 
+use super::filter::{self, FilterCall};
+use super::jinja;
 use super::resolve::ResolvedBindings;
 
-/// Pre-compiled regex for {{use.alias}} or {{use.alias.field}} pattern
-static USE_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"\{\{\s*use\.(\w+(?:\.\w+)*)\s*\}\}").unwrap());
+/// Optional `| name(args)` filter chain after a reference path (v0.7)
+const FILTERS: &str = r#"((?:\s*\|\s*\w+\s*(?:\((?:'[^']*'|"[^"]*"|[^)'"])*\))?)*)"#;
+
+/// Pre-compiled regex for {{use.alias}} or {{use.alias.field}} pattern,
+/// anchored at the start of the text
+static USE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"^\{{\{{\s*use\.(\w+(?:\.\w+)*){}\s*\}}\}}",
+        FILTERS
+    ))
+    .unwrap()
+});
+
+/// Pre-compiled regex for {{state.key}} or {{state.key.field}} (v0.7),
+/// anchored at the start of the text
+static STATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"^\{{\{{\s*state\.(\w+(?:\.\w+)*){}\s*\}}\}}",
+        FILTERS
+    ))
+    .unwrap()
+});
 
 /// Escape for JSON string context
 fn escape_for_json(s: &str) -> String {
@@ -46,6 +79,153 @@ fn escape_for_json(s: &str) -> String {
     result
 }
 
+/// Template resolution mode (v0.7)
+///
+/// Set at workflow level with `templates: strict`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateMode {
+    /// `{{...}}` sequences that aren't valid `{{use.*}}` refs pass through literally
+    #[default]
+    Lenient,
+    /// Any unescaped `{{` that isn't a valid `{{use.*}}` ref is an error (NIKA-074)
+    Strict,
+}
+
+/// Template engine of a task (v0.7)
+///
+/// Set per task with `template_engine: jinja`; see `binding::jinja`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateEngine {
+    /// `{{use.alias}}` substitution with `| filter` chains
+    #[default]
+    Nika,
+    /// minijinja: loops, conditionals, filters (`jinja` feature)
+    Jinja,
+}
+
+/// A `{{` occurrence found while scanning a template
+enum Token<'a> {
+    /// `\{{` escaped delimiter - `start..end` is rendered as a literal `{{`
+    Escaped { start: usize, end: usize },
+    /// Valid `{{use.path}}` reference, with its `| filter` chain (v0.7)
+    Ref {
+        start: usize,
+        end: usize,
+        path: &'a str,
+        filters: &'a str,
+    },
+    /// Valid `{{state.path}}` reference (v0.7)
+    State {
+        start: usize,
+        end: usize,
+        path: &'a str,
+        filters: &'a str,
+    },
+    /// `{{` that doesn't start a valid reference
+    Unmatched { start: usize },
+}
+
+/// Scan a template for escaped delimiters, references and stray `{{`
+///
+/// Scanning only ever looks at the template source, never at substituted
+/// values, so inputs containing `{{` are never re-expanded.
+fn tokenize(template: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while let Some(rel) = template[i..].find("{{") {
+        let start = i + rel;
+        let before = &template[..start];
+
+        // `\{{` escapes the delimiter. Inside a JSON string (invoke params)
+        // the backslash itself is encoded as `\\`.
+        if before.ends_with('\\') {
+            let escape_len = if before.ends_with("\\\\") && is_in_json_context(template, start) {
+                2
+            } else {
+                1
+            };
+            tokens.push(Token::Escaped {
+                start: start - escape_len,
+                end: start + 2,
+            });
+            i = start + 2;
+            continue;
+        }
+
+        // Anchored at `start`: a `{{` that isn't a ref fails right away
+        let at_start = |re: &Regex| {
+            re.captures(&template[start..]).map(|cap| {
+                (
+                    start + cap.get(0).unwrap().end(),
+                    cap.get(1).unwrap().as_str(),
+                    cap.get(2).unwrap().as_str(),
+                )
+            })
+        };
+        match (at_start(&USE_RE), at_start(&STATE_RE)) {
+            (Some((end, path, filters)), _) => {
+                tokens.push(Token::Ref {
+                    start,
+                    end,
+                    path,
+                    filters,
+                });
+                i = end;
+            }
+            (None, Some((end, path, filters))) => {
+                tokens.push(Token::State {
+                    start,
+                    end,
+                    path,
+                    filters,
+                });
+                i = end;
+            }
+            (None, None) => {
+                tokens.push(Token::Unmatched { start });
+                // Advance by one so `{{{use.x}}}` still finds the inner ref
+                i = start + 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Check template syntax for the given mode (static validation)
+///
+/// In strict mode, any unescaped `{{` that isn't a valid `{{use.*}}`
+/// reference is reported as NIKA-074 with its byte position.
+pub fn validate_syntax(template: &str, mode: TemplateMode) -> Result<(), NikaError> {
+    if mode == TemplateMode::Lenient || !template.contains("{{") {
+        return Ok(());
+    }
+    check_unmatched(template, &tokenize(template))
+}
+
+fn check_unmatched(template: &str, tokens: &[Token<'_>]) -> Result<(), NikaError> {
+    for token in tokens {
+        if let Token::Unmatched { start } = token {
+            let rest = &template[*start..];
+            let snippet = match rest.find("}}") {
+                Some(end) if end <= 64 => &rest[..end + 2],
+                _ => &rest[..rest.len().min(16)],
+            };
+            return Err(NikaError::TemplateParse {
+                position: *start,
+                details: format!(
+                    "unresolved reference '{}' (strict mode; escape literal braces as \\{{{{)",
+                    snippet
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Resolve all {{use.alias}} templates using bindings (v0.5)
 ///
 /// Returns Cow::Borrowed when no templates (zero allocation).
@@ -62,10 +242,51 @@ pub fn resolve<'a>(
     bindings: &ResolvedBindings,
     datastore: &DataStore,
 ) -> Result<Cow<'a, str>, NikaError> {
+    resolve_with_mode(template, bindings, datastore, TemplateMode::Lenient)
+}
+
+/// Resolve templates with an explicit mode (v0.7)
+///
+/// Both modes render `\{{` as a literal `{{`. Substituted values are
+/// inserted verbatim and never scanned again. Bindings of a
+/// `template_engine: jinja` task render with minijinja instead (the mode
+/// doesn't apply).
+pub fn resolve_with_mode<'a>(
+    template: &'a str,
+    bindings: &ResolvedBindings,
+    datastore: &DataStore,
+    mode: TemplateMode,
+) -> Result<Cow<'a, str>, NikaError> {
+    // `template_engine: jinja` tasks (v0.7)
+    if bindings.engine() == TemplateEngine::Jinja {
+        if !jinja::has_syntax(template) {
+            return Ok(Cow::Borrowed(template));
+        }
+        return jinja::render(template, bindings, datastore).map(Cow::Owned);
+    }
     // Early return with borrowed string (zero alloc)
-    // Fast check: must contain `{{` followed eventually by `use.`
-    // Regex handles whitespace variations like `{{ use.` or `{{\tuse.`
-    if !template.contains("{{") || !template.contains("use.") {
+    if !template.contains("{{") {
+        return Ok(Cow::Borrowed(template));
+    }
+    // Lenient fast path: nothing to substitute or unescape
+    if mode == TemplateMode::Lenient
+        && !template.contains("use.")
+        && !template.contains("state.")
+        && !template.contains("\\{{")
+    {
+        return Ok(Cow::Borrowed(template));
+    }
+
+    let tokens = tokenize(template);
+    if mode == TemplateMode::Strict {
+        check_unmatched(template, &tokens)?;
+    }
+    if !tokens.iter().any(|t| {
+        matches!(
+            t,
+            Token::Ref { .. } | Token::State { .. } | Token::Escaped { .. }
+        )
+    }) {
         return Ok(Cow::Borrowed(template));
     }
 
@@ -74,90 +295,55 @@ pub fn resolve<'a>(
     let mut result = String::with_capacity(template.len() + 64);
     let mut last_end = 0;
     // SmallVec: stack-allocated for up to 4 errors (common case: 0-1 errors)
-    // Note: must be String because alias borrows from cap which is dropped each iteration
     let mut errors: SmallVec<[String; 4]> = SmallVec::new();
 
-    for cap in USE_RE.captures_iter(template) {
-        let m = cap.get(0).unwrap();
-        let path = &cap[1]; // e.g., "forecast" or "flight_info.departure"
-
-        // Copy segment before this match
-        result.push_str(&template[last_end..m.start()]);
-
-        // Split: first segment is alias, rest is nested path
-        let mut parts = path.split('.');
-        let alias = parts.next().unwrap();
-
-        // Get the resolved value for this alias (supports lazy bindings via DataStore)
-        match bindings.get_resolved(alias, datastore) {
-            Ok(base_value) => {
-                // Zero-clone traversal: use references until we need the final value
-                let mut value_ref: &Value = &base_value;
-                let mut traversed_segments: SmallVec<[&str; 8]> = SmallVec::new();
-                traversed_segments.push(alias);
-
-                // Traverse nested path if present (all by reference)
-                for segment in parts {
-                    let next = if let Ok(idx) = segment.parse::<usize>() {
-                        value_ref.get(idx)
+    for token in tokens {
+        match token {
+            Token::Escaped { start, end } => {
+                result.push_str(&template[last_end..start]);
+                result.push_str("{{");
+                last_end = end;
+            }
+            Token::Ref {
+                start,
+                end,
+                path,
+                filters,
+            } => {
+                // Copy segment before this match
+                result.push_str(&template[last_end..start]);
+
+                let filters = Filters::parse(filters, &template[start..end])?;
+                if let Some(replacement) =
+                    resolve_ref(path, &filters, bindings, datastore, &mut errors)?
+                {
+                    // Escape if we're in a JSON context
+                    if is_in_json_context(template, start) {
+                        result.push_str(&escape_for_json(&replacement));
                     } else {
-                        value_ref.get(segment)
-                    };
-
-                    match next {
-                        Some(v) => {
-                            traversed_segments.push(segment);
-                            value_ref = v;
-                        }
-                        None => {
-                            // Determine if it's an invalid traversal or missing field
-                            let value_type = match value_ref {
-                                Value::Null => "null",
-                                Value::Bool(_) => "bool",
-                                Value::Number(_) => "number",
-                                Value::String(_) => "string",
-                                Value::Array(_) => "array",
-                                Value::Object(_) => "object",
-                            };
-
-                            if matches!(value_ref, Value::Object(_) | Value::Array(_)) {
-                                // Field/index doesn't exist - build path for error
-                                let traversed_path = traversed_segments.join(".");
-                                return Err(NikaError::PathNotFound {
-                                    path: format!("{}.{}", traversed_path, segment),
-                                });
-                            } else {
-                                // Trying to traverse a primitive
-                                return Err(NikaError::InvalidTraversal {
-                                    segment: segment.to_string(),
-                                    value_type: value_type.to_string(),
-                                    full_path: path.to_string(),
-                                });
-                            }
-                        }
+                        result.push_str(&replacement);
                     }
                 }
-
-                // Convert Value to string (strict mode - null is error)
-                // This is the ONLY place we convert/allocate for the value
-                let replacement = value_to_string(value_ref, path, alias)?;
-
-                // Escape if we're in a JSON context
-                let replacement = if is_in_json_context(template, m.start()) {
-                    escape_for_json(&replacement)
-                } else {
-                    replacement
-                };
-
-                result.push_str(&replacement);
+                last_end = end;
             }
-            Err(_) => {
-                // Binding not found (neither eager nor lazy)
-                errors.push(alias.to_string());
+            Token::State {
+                start,
+                end,
+                path,
+                filters,
+            } => {
+                result.push_str(&template[last_end..start]);
+                let filters = Filters::parse(filters, &template[start..end])?;
+                let replacement = resolve_state(path, &filters, datastore)?;
+                if is_in_json_context(template, start) {
+                    result.push_str(&escape_for_json(&replacement));
+                } else {
+                    result.push_str(&replacement);
+                }
+                last_end = end;
             }
+            Token::Unmatched { .. } => {}
         }
-
-        last_end = m.end();
     }
 
     if !errors.is_empty() {
@@ -173,6 +359,153 @@ pub fn resolve<'a>(
     Ok(Cow::Owned(result))
 }
 
+/// Resolve a single `use.` path to its string replacement
+///
+/// Unknown aliases are collected into `errors` (reported together);
+/// traversal and null errors fail immediately.
+fn resolve_ref(
+    path: &str,
+    filters: &Filters<'_>,
+    bindings: &ResolvedBindings,
+    datastore: &DataStore,
+    errors: &mut SmallVec<[String; 4]>,
+) -> Result<Option<String>, NikaError> {
+    // Split: first segment is alias, rest is nested path
+    let mut parts = path.split('.');
+    let alias = parts.next().unwrap();
+
+    // Get the resolved value for this alias (supports lazy bindings via DataStore)
+    let Ok(base_value) = bindings.get_resolved(alias, datastore) else {
+        // Binding not found (neither eager nor lazy)
+        errors.push(alias.to_string());
+        return Ok(None);
+    };
+
+    // Zero-clone traversal: use references until we need the final value
+    let mut value_ref: &Value = &base_value;
+    let mut traversed_segments: SmallVec<[&str; 8]> = SmallVec::new();
+    traversed_segments.push(alias);
+
+    // Traverse nested path if present (all by reference)
+    for segment in parts {
+        let next = if let Ok(idx) = segment.parse::<usize>() {
+            value_ref.get(idx)
+        } else {
+            value_ref.get(segment)
+        };
+
+        match next {
+            Some(v) => {
+                traversed_segments.push(segment);
+                value_ref = v;
+            }
+            None => {
+                // Determine if it's an invalid traversal or missing field
+                let value_type = match value_ref {
+                    Value::Null => "null",
+                    Value::Bool(_) => "bool",
+                    Value::Number(_) => "number",
+                    Value::String(_) => "string",
+                    Value::Array(_) => "array",
+                    Value::Object(_) => "object",
+                };
+
+                if matches!(value_ref, Value::Object(_) | Value::Array(_)) {
+                    // `| default(x)` covers a missing field (v0.7)
+                    if filters.has_default() {
+                        return filters.apply(Value::Null, path, alias).map(Some);
+                    }
+                    // Field/index doesn't exist - build path for error
+                    let traversed_path = traversed_segments.join(".");
+                    return Err(NikaError::PathNotFound {
+                        path: format!("{}.{}", traversed_path, segment),
+                    });
+                } else {
+                    // Trying to traverse a primitive
+                    return Err(NikaError::InvalidTraversal {
+                        segment: segment.to_string(),
+                        value_type: value_type.to_string(),
+                        full_path: path.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    if !filters.is_empty() {
+        return filters.apply(value_ref.clone(), path, alias).map(Some);
+    }
+
+    // Convert Value to string (strict mode - null is error)
+    // This is the ONLY place we convert/allocate for the value
+    value_to_string(value_ref, path, alias).map(Some)
+}
+
+/// Resolve a `state.` path from the `state` binding (v0.7)
+fn resolve_state(
+    path: &str,
+    filters: &Filters<'_>,
+    datastore: &DataStore,
+) -> Result<String, NikaError> {
+    let (key, rest) = path.split_once('.').unwrap_or((path, ""));
+    let state = datastore.get_output(STATE_TASK_ID);
+    let value = match state.as_ref().and_then(|state| state.get(key)) {
+        Some(value) if rest.is_empty() => Some(value.clone()),
+        Some(value) => jsonpath::resolve(value, rest)?,
+        None if filters.has_default() => None,
+        None => {
+            return Err(NikaError::StateKeyMissing {
+                key: key.to_string(),
+            })
+        }
+    };
+    let value = match value {
+        Some(value) => value,
+        None if filters.has_default() => Value::Null,
+        None => {
+            return Err(NikaError::PathNotFound {
+                path: format!("{}.{}", STATE_TASK_ID, path),
+            })
+        }
+    };
+    filters.apply(value, path, STATE_TASK_ID)
+}
+
+/// A parsed `| filter` chain and the reference it belongs to (v0.7)
+struct Filters<'a> {
+    calls: Vec<FilterCall>,
+    raw: &'a str,
+}
+
+impl<'a> Filters<'a> {
+    fn parse(chain: &str, raw: &'a str) -> Result<Self, NikaError> {
+        let calls = filter::parse_chain(chain).map_err(|reason| NikaError::TemplateError {
+            template: raw.to_string(),
+            reason,
+        })?;
+        Ok(Self { calls, raw })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Whether a `default` step turns a missing value into its fallback
+    fn has_default(&self) -> bool {
+        self.calls.iter().any(|call| call.name == "default")
+    }
+
+    /// Run the chain, then convert like an unfiltered reference
+    fn apply(&self, value: Value, path: &str, alias: &str) -> Result<String, NikaError> {
+        let value =
+            filter::apply_chain(value, &self.calls).map_err(|reason| NikaError::TemplateError {
+                template: self.raw.to_string(),
+                reason,
+            })?;
+        value_to_string(&value, path, alias)
+    }
+}
+
 /// Convert JSON Value to string for template substitution (strict mode)
 ///
 /// Returns error for null values - this prevents silent bugs from missing data.
@@ -217,12 +550,15 @@ fn is_in_json_context(template: &str, pos: usize) -> bool {
 /// Example: "{{use.weather.temp}}" → vec![("weather", "weather.temp")]
 #[allow(dead_code)] // Used in tests and future static validation
 pub fn extract_refs(template: &str) -> Vec<(String, String)> {
-    USE_RE
-        .captures_iter(template)
-        .map(|cap| {
-            let full_path = cap[1].to_string();
-            let alias = full_path.split('.').next().unwrap().to_string();
-            (alias, full_path)
+    // Escaped `\{{use.x}}` is literal text, not a reference
+    tokenize(template)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Ref { path, .. } => {
+                let alias = path.split('.').next().unwrap().to_string();
+                Some((alias, path.to_string()))
+            }
+            _ => None,
         })
         .collect()
 }
@@ -300,6 +636,78 @@ mod tests {
         assert_eq!(result, "first and second");
     }
 
+    #[test]
+    fn resolve_state_refs() {
+        use crate::store::TaskResult;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let ds = empty_datastore();
+        ds.insert(
+            Arc::from(STATE_TASK_ID),
+            TaskResult::success(
+                json!({"last_id": 42, "cursor": {"page": 2}}),
+                Duration::ZERO,
+            ),
+        );
+        let bindings = ResolvedBindings::new();
+
+        let result = resolve_with_mode(
+            "since={{state.last_id}} page={{ state.cursor.page }}",
+            &bindings,
+            &ds,
+            TemplateMode::Strict,
+        )
+        .unwrap();
+        assert_eq!(result, "since=42 page=2");
+
+        let err = resolve("{{state.missing}}", &bindings, &ds).unwrap_err();
+        assert_eq!(err.code(), "NIKA-191");
+        // Not an alias: static `use:` validation ignores it
+        assert!(extract_refs("{{state.last_id}}").is_empty());
+    }
+
+    #[test]
+    fn resolve_filters() {
+        let mut bindings = ResolvedBindings::new();
+        bindings.set("items", json!(["alpha", "beta", "gamma"]));
+        bindings.set("report", json!(r#"{"scores": [91, 78]}"#));
+        bindings.set("data", json!({"name": "nika"}));
+        let ds = empty_datastore();
+
+        let result = resolve(
+            "{{use.items | length}} items, first {{ use.items | first | upper }}, top {{use.report | jsonpath('$.scores[0]')}}",
+            &bindings,
+            &ds,
+        )
+        .unwrap();
+        assert_eq!(result, "3 items, first ALPHA, top 91");
+
+        let result = resolve(
+            "{{use.data.owner | default('nobody')}} / {{use.items | truncate(2) | join('|')}}",
+            &bindings,
+            &ds,
+        )
+        .unwrap();
+        assert_eq!(result, "nobody / alpha|beta");
+
+        // Without default, a missing field is still an error
+        let err = resolve("{{use.data.owner | upper}}", &bindings, &ds).unwrap_err();
+        assert_eq!(err.code(), "NIKA-052");
+        let err = resolve("{{use.items | shout}}", &bindings, &ds).unwrap_err();
+        assert_eq!(err.code(), "NIKA-041");
+        assert!(err.to_string().contains("unknown filter 'shout'"));
+
+        // Filtered refs are still references for static validation and strict mode
+        assert_eq!(
+            extract_refs("{{use.items | length}}"),
+            vec![("items".to_string(), "items".to_string())]
+        );
+        assert!(
+            validate_syntax("{{use.items | truncate(5, '...')}}", TemplateMode::Strict).is_ok()
+        );
+    }
+
     #[test]
     fn resolve_object() {
         let mut bindings = ResolvedBindings::new();
@@ -466,4 +874,131 @@ mod tests {
         assert!(err.to_string().contains("NIKA-071"));
         assert!(err.to_string().contains("unknown"));
     }
+
+    // ═══════════════════════════════════════════════════════════════
+    // ESCAPING + STRICT MODE (v0.7)
+    // ═══════════════════════════════════════════════════════════════
+
+    #[test]
+    fn escaped_delimiter_is_literal() {
+        let mut bindings = ResolvedBindings::new();
+        bindings.set("name", json!("Nika"));
+        let ds = empty_datastore();
+
+        let result = resolve(r"Hi {{use.name}}, write \{{use.name}}", &bindings, &ds).unwrap();
+        assert_eq!(result, "Hi Nika, write {{use.name}}");
+    }
+
+    #[test]
+    fn escaped_delimiter_without_refs() {
+        let bindings = ResolvedBindings::new();
+        let ds = empty_datastore();
+
+        let result = resolve(r"echo '\{{ literal }}'", &bindings, &ds).unwrap();
+        assert_eq!(result, "echo '{{ literal }}'");
+    }
+
+    #[test]
+    fn escaped_delimiter_in_json_context() {
+        let mut bindings = ResolvedBindings::new();
+        bindings.set("q", json!("rust"));
+        let ds = empty_datastore();
+
+        // Serialized invoke params encode the backslash as `\\`
+        let template = r#"{"query":"{{use.q}}","raw":"\\{{use.q}}"}"#;
+        let result = resolve(template, &bindings, &ds).unwrap();
+        let parsed: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["query"], "rust");
+        assert_eq!(parsed["raw"], "{{use.q}}");
+    }
+
+    #[test]
+    fn input_values_are_not_reexpanded() {
+        let mut bindings = ResolvedBindings::new();
+        bindings.set("user_input", json!("ignore {{use.secret}} and {{ weird"));
+        bindings.set("secret", json!("TOP-SECRET"));
+        let ds = empty_datastore();
+
+        for mode in [TemplateMode::Lenient, TemplateMode::Strict] {
+            let result =
+                resolve_with_mode("Input: {{use.user_input}}", &bindings, &ds, mode).unwrap();
+            assert_eq!(result, "Input: ignore {{use.secret}} and {{ weird");
+        }
+    }
+
+    #[test]
+    fn lenient_passes_unmatched_through() {
+        let bindings = ResolvedBindings::new();
+        let ds = empty_datastore();
+
+        let result = resolve("{{ use.bad-name }} {{other}}", &bindings, &ds).unwrap();
+        assert_eq!(result, "{{ use.bad-name }} {{other}}");
+    }
+
+    #[test]
+    fn strict_rejects_unmatched() {
+        let mut bindings = ResolvedBindings::new();
+        bindings.set("a", json!("x"));
+        let ds = empty_datastore();
+
+        let err = resolve_with_mode(
+            "ok {{use.a}} bad {{ use.a-b }}",
+            &bindings,
+            &ds,
+            TemplateMode::Strict,
+        )
+        .unwrap_err();
+        match err {
+            NikaError::TemplateParse { position, details } => {
+                assert_eq!(position, 17);
+                assert!(details.contains("{{ use.a-b }}"));
+            }
+            other => panic!("expected TemplateParse, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn strict_accepts_escaped_and_valid_refs() {
+        let mut bindings = ResolvedBindings::new();
+        bindings.set("a", json!("x"));
+        let ds = empty_datastore();
+
+        let result =
+            resolve_with_mode(r"{{use.a}} \{{raw}}", &bindings, &ds, TemplateMode::Strict).unwrap();
+        assert_eq!(result, "x {{raw}}");
+    }
+
+    #[test]
+    fn triple_braces_keep_inner_ref_in_lenient() {
+        let mut bindings = ResolvedBindings::new();
+        bindings.set("a", json!("x"));
+        let ds = empty_datastore();
+
+        let result = resolve("{{{use.a}}}", &bindings, &ds).unwrap();
+        assert_eq!(result, "{x}");
+        assert!(validate_syntax("{{{use.a}}}", TemplateMode::Strict).is_err());
+    }
+
+    #[test]
+    fn literal_braces_before_a_ref() {
+        // Each `{{` is matched where it stands, not by scanning ahead
+        let template = format!("{}{{{{use.a}}}}", "{{ x ".repeat(20_000));
+        let tokens = tokenize(&template);
+        assert_eq!(tokens.len(), 20_001);
+        assert!(matches!(tokens.last(), Some(Token::Ref { path: "a", .. })));
+        assert_eq!(extract_refs(&template).len(), 1);
+    }
+
+    #[test]
+    fn extract_refs_skips_escaped() {
+        let refs = extract_refs(r"{{use.a}} \{{use.b}}");
+        assert_eq!(refs, vec![("a".to_string(), "a".to_string())]);
+    }
+
+    #[test]
+    fn parse_template_mode() {
+        let mode: TemplateMode = serde_yaml::from_str("strict").unwrap();
+        assert_eq!(mode, TemplateMode::Strict);
+        assert_eq!(TemplateMode::default(), TemplateMode::Lenient);
+    }
 }
@@ -0,0 +1,252 @@
+//! Codec Module - output formats as pluggable parse/serialize pairs (v0.7)
+//!
+//! `output: { format: <name> }` looks the name up in a process-wide codec
+//! registry. The codec parses the task's raw text into a JSON value (what
+//! the DataStore holds and `schema:` validates), and serializes a value back
+//! to text (`nika run --output-only` prints the final output that way).
+//!
+//! Built-in codecs:
+//! - `text`: raw string (the default)
+//! - `json`
+//! - `yaml`
+//! - `csv`: header row + records ↔ array of objects
+//! - `markdown-table`: pipe table ↔ array of objects
+//! - `xml`: elements ↔ objects (`@attr`, `#text`, repeated children as arrays)
+//!
+//! The tabular helpers also back the `import:`/`export:` verbs, which read
+//! and write CSV and XLSX files (`sheet`, `xlsx`).
+//!
+//! Embedders add their own with [`register`]:
+//!
+//! ```rust,ignore
+//! nika::codec::register(Arc::new(TomlCodec));   // format: toml
+//! ```
+
+mod sheet;
+mod table;
+mod xlsx;
+mod xml;
+
+use std::sync::{Arc, LazyLock};
+
+use dashmap::DashMap;
+use serde_json::Value;
+
+use crate::ast::OutputFormat;
+use crate::error::NikaError;
+
+pub use sheet::{read_rows, write_rows};
+pub use table::{CsvCodec, MarkdownTableCodec};
+pub(crate) use xlsx::write_zip;
+pub use xml::XmlCodec;
+
+/// A named text format
+pub trait Codec: Send + Sync {
+    /// Name used in `output: { format: <name> }`
+    fn name(&self) -> &str;
+
+    /// Text → value; the error says what is wrong with the text
+    fn parse(&self, text: &str) -> Result<Value, String>;
+
+    /// Value → text; the error says which values the format can't hold
+    fn serialize(&self, value: &Value) -> Result<String, String>;
+}
+
+static REGISTRY: LazyLock<DashMap<String, Arc<dyn Codec>>> = LazyLock::new(|| {
+    let builtins: [Arc<dyn Codec>; 6] = [
+        Arc::new(TextCodec),
+        Arc::new(JsonCodec),
+        Arc::new(YamlCodec),
+        Arc::new(CsvCodec),
+        Arc::new(MarkdownTableCodec),
+        Arc::new(XmlCodec),
+    ];
+    builtins
+        .into_iter()
+        .map(|codec| (codec.name().to_string(), codec))
+        .collect()
+});
+
+/// Add a codec, replacing any codec with the same name
+pub fn register(codec: Arc<dyn Codec>) {
+    REGISTRY.insert(codec.name().to_string(), codec);
+}
+
+/// The codec registered under `name`
+pub fn get(name: &str) -> Option<Arc<dyn Codec>> {
+    REGISTRY.get(name).map(|entry| Arc::clone(entry.value()))
+}
+
+/// Registered codec names, sorted
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = REGISTRY.iter().map(|entry| entry.key().clone()).collect();
+    names.sort();
+    names
+}
+
+/// Parse task output declared as `format`
+///
+/// JSON keeps its own error (`NIKA-060`); other codecs fail with `NIKA-063`.
+pub fn parse_output(format: &OutputFormat, text: &str) -> Result<Value, NikaError> {
+    if *format == OutputFormat::Json {
+        return serde_json::from_str(text).map_err(|e| NikaError::InvalidJson {
+            details: e.to_string(),
+        });
+    }
+    lookup(format)?
+        .parse(text)
+        .map_err(|reason| NikaError::CodecError {
+            format: format.as_str().to_string(),
+            reason,
+        })
+}
+
+/// Serialize a value as `format`
+pub fn serialize_output(format: &OutputFormat, value: &Value) -> Result<String, NikaError> {
+    lookup(format)?
+        .serialize(value)
+        .map_err(|reason| NikaError::CodecError {
+            format: format.as_str().to_string(),
+            reason,
+        })
+}
+
+fn lookup(format: &OutputFormat) -> Result<Arc<dyn Codec>, NikaError> {
+    get(format.as_str()).ok_or_else(|| NikaError::CodecError {
+        format: format.as_str().to_string(),
+        reason: format!("no codec registered (available: {})", names().join(", ")),
+    })
+}
+
+/// Raw text: parses to a string, serializes strings as-is
+pub struct TextCodec;
+
+impl Codec for TextCodec {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn parse(&self, text: &str) -> Result<Value, String> {
+        Ok(Value::String(text.to_string()))
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, String> {
+        Ok(match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+}
+
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn parse(&self, text: &str) -> Result<Value, String> {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, String> {
+        Ok(value.to_string())
+    }
+}
+
+pub struct YamlCodec;
+
+impl Codec for YamlCodec {
+    fn name(&self) -> &str {
+        "yaml"
+    }
+
+    fn parse(&self, text: &str) -> Result<Value, String> {
+        serde_yaml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, String> {
+        serde_yaml::to_string(value).map_err(|e| e.to_string())
+    }
+}
+
+/// A cell's value: JSON numbers and booleans are typed, the rest is text
+fn infer_scalar(cell: &str) -> Value {
+    match serde_json::from_str::<Value>(cell) {
+        Ok(value @ (Value::Number(_) | Value::Bool(_))) => value,
+        _ => Value::String(cell.to_string()),
+    }
+}
+
+/// A value as cell text (strings unquoted, null empty)
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// An empty or whitespace-only cell
+fn is_blank(cell: &Value) -> bool {
+    match cell {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct ShoutCodec;
+
+    impl Codec for ShoutCodec {
+        fn name(&self) -> &str {
+            "shout"
+        }
+
+        fn parse(&self, text: &str) -> Result<Value, String> {
+            Ok(Value::String(text.to_uppercase()))
+        }
+
+        fn serialize(&self, value: &Value) -> Result<String, String> {
+            Ok(cell_text(value).to_uppercase())
+        }
+    }
+
+    #[test]
+    fn test_builtins_are_registered() {
+        let names = names();
+        for name in ["csv", "json", "markdown-table", "text", "xml", "yaml"] {
+            assert!(names.contains(&name.to_string()), "missing {}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse_output_errors_by_format() {
+        let err = parse_output(&OutputFormat::Json, "{oops").unwrap_err();
+        assert_eq!(err.code(), "NIKA-060");
+
+        let err = parse_output(&OutputFormat::Custom("nope".into()), "x").unwrap_err();
+        assert_eq!(err.code(), "NIKA-063");
+        assert!(err.to_string().contains("available: "));
+
+        let yaml = parse_output(&OutputFormat::Yaml, "name: nika\nstars: 5").unwrap();
+        assert_eq!(yaml, json!({"name": "nika", "stars": 5}));
+        assert_eq!(
+            serialize_output(&OutputFormat::Text, &json!("plain")).unwrap(),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn test_register_custom_codec() {
+        register(Arc::new(ShoutCodec));
+        let format = OutputFormat::Custom("shout".into());
+        assert_eq!(parse_output(&format, "hi").unwrap(), json!("HI"));
+        assert_eq!(serialize_output(&format, &json!("hey")).unwrap(), "HEY");
+    }
+}
@@ -0,0 +1,258 @@
+//! Spreadsheet files for `import:` and `export:`
+//!
+//! Reading turns a CSV or XLSX file into an array of row objects keyed by
+//! the header row (renamed and filtered by `columns:`). Writing takes an
+//! array of objects or scalars, like the `csv` codec, and writes it with a
+//! header row.
+
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use super::table::{csv_quote, csv_records, value_to_cells};
+use super::xlsx::{self, column_index, column_name};
+use super::{cell_text, infer_scalar, is_blank};
+use crate::ast::{ExportParams, ImportParams, SheetFormat};
+use crate::error::NikaError;
+
+/// Rows of `file` as objects keyed by column name
+pub fn read_rows(
+    file: &str,
+    params: &ImportParams,
+    format: SheetFormat,
+) -> Result<Vec<Value>, NikaError> {
+    let failed = |reason: String| NikaError::SpreadsheetError {
+        file: file.to_string(),
+        reason,
+    };
+    let bytes = std::fs::read(file).map_err(|e| failed(e.to_string()))?;
+    let grid = match format {
+        SheetFormat::Csv => {
+            let text = String::from_utf8(bytes)
+                .map_err(|_| failed("CSV file is not UTF-8".to_string()))?;
+            let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+            let infer = params.infer_types();
+            csv_records(text, params.delimiter())
+                .map_err(failed)?
+                .into_iter()
+                .map(|record| {
+                    record
+                        .into_iter()
+                        .map(|cell| match cell.as_str() {
+                            _ if !infer => Value::String(cell),
+                            "" => Value::Null,
+                            _ => infer_scalar(&cell),
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+        SheetFormat::Xlsx => {
+            // Enough non-blank rows for the skipped rows, header and `limit`
+            let max_rows = params.limit.map(|limit| {
+                limit + params.skip_rows.unwrap_or(0) + usize::from(params.has_header())
+            });
+            xlsx::read_sheet(&bytes, params.sheet.as_deref(), max_rows).map_err(failed)?
+        }
+    };
+    grid_to_rows(grid, params).map_err(failed)
+}
+
+/// Write `value` to `file`; returns the columns and the number of rows
+pub fn write_rows(
+    file: &str,
+    value: &Value,
+    params: &ExportParams,
+    format: SheetFormat,
+) -> Result<(Vec<String>, usize), NikaError> {
+    let failed = |reason: String| NikaError::SpreadsheetError {
+        file: file.to_string(),
+        reason,
+    };
+    let (mut columns, mut rows) = value_to_cells(value).map_err(failed)?;
+    if let Some(selected) = &params.columns {
+        let positions: Vec<Option<usize>> = selected
+            .iter()
+            .map(|name| columns.iter().position(|c| c == name))
+            .collect();
+        rows = rows
+            .into_iter()
+            .map(|row| {
+                positions
+                    .iter()
+                    .map(|at| at.map_or(Value::Null, |i| row[i].clone()))
+                    .collect()
+            })
+            .collect();
+        columns = selected.clone();
+    }
+
+    let bytes = match format {
+        SheetFormat::Csv => {
+            let delimiter = params.delimiter();
+            let mut out = String::new();
+            let header = columns.iter().map(|c| Value::String(c.clone()));
+            for row in std::iter::once(header.collect::<Vec<_>>()).chain(rows.iter().cloned()) {
+                let cells: Vec<String> = row
+                    .iter()
+                    .map(|cell| csv_quote(&cell_text(cell), delimiter))
+                    .collect();
+                out.push_str(&cells.join(&delimiter.to_string()));
+                out.push('\n');
+            }
+            out.into_bytes()
+        }
+        SheetFormat::Xlsx => xlsx::write_sheet(params.sheet(), &columns, &rows).map_err(failed)?,
+    };
+    if let Some(parent) = Path::new(file).parent() {
+        std::fs::create_dir_all(parent).map_err(|e| failed(e.to_string()))?;
+    }
+    std::fs::write(file, bytes).map_err(|e| failed(e.to_string()))?;
+    Ok((columns, rows.len()))
+}
+
+/// Apply `skip_rows`, the header, `columns:` and `limit` to raw cells
+fn grid_to_rows(grid: Vec<Vec<Value>>, params: &ImportParams) -> Result<Vec<Value>, String> {
+    let mut grid = grid.into_iter().skip(params.skip_rows.unwrap_or(0));
+    let header: Vec<String> = match params.has_header() {
+        true => header_names(&grid.next().unwrap_or_default()),
+        false => Vec::new(),
+    };
+
+    // (cell position, output key) of every kept column
+    let keep: Option<Vec<(usize, String)>> = match params.columns.is_empty() {
+        true => None,
+        false => {
+            let mut keep = params
+                .columns
+                .iter()
+                .map(|(name, key)| {
+                    let at = match params.has_header() {
+                        true => header.iter().position(|h| h == name),
+                        false if name.bytes().all(|b| b.is_ascii_uppercase()) => column_index(name),
+                        false => None,
+                    };
+                    at.map(|at| (at, key.clone())).ok_or_else(|| {
+                        format!(
+                            "column '{}' not found (header: {})",
+                            name,
+                            header.join(", ")
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            keep.sort_by_key(|(at, _)| *at);
+            Some(keep)
+        }
+    };
+
+    let rows = grid
+        .filter(|row| row.iter().any(|cell| !is_blank(cell)))
+        .take(params.limit.unwrap_or(usize::MAX))
+        .map(|row| {
+            let cell = |at: usize| row.get(at).cloned().unwrap_or(Value::Null);
+            let record: Map<String, Value> = match &keep {
+                Some(keep) => keep
+                    .iter()
+                    .map(|(at, key)| (key.clone(), cell(*at)))
+                    .collect(),
+                None => {
+                    let width = header.len().max(row.len());
+                    (0..width)
+                        .map(|at| {
+                            let key = header.get(at).cloned().unwrap_or_else(|| column_name(at));
+                            (key, cell(at))
+                        })
+                        .collect()
+                }
+            };
+            Value::Object(record)
+        })
+        .collect();
+    Ok(rows)
+}
+
+/// Column names from a header row: blanks become the column letter and
+/// repeats get a `_2`, `_3` suffix
+fn header_names(row: &[Value]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(row.len());
+    for (at, cell) in row.iter().enumerate() {
+        let base = match cell_text(cell).trim() {
+            "" => column_name(at),
+            name => name.to_string(),
+        };
+        let mut name = base.clone();
+        let mut n = 2;
+        while names.contains(&name) {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn import(yaml: &str) -> ImportParams {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_grid_header_columns_and_limit() {
+        let grid = vec![
+            vec![json!("Leads export")],
+            vec![json!("Name"), json!("E-mail"), Value::Null, json!("Name")],
+            vec![json!("Ada"), json!("ada@x.io"), json!(1), json!("A")],
+            vec![],
+            vec![json!("Bob"), Value::Null],
+            vec![json!("Cy")],
+        ];
+        let rows = grid_to_rows(grid.clone(), &import("file: x\nskip_rows: 1\nlimit: 2")).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                json!({"Name": "Ada", "E-mail": "ada@x.io", "C": 1, "Name_2": "A"}),
+                json!({"Name": "Bob", "E-mail": null, "C": null, "Name_2": null}),
+            ]
+        );
+
+        let params = import("file: x\nskip_rows: 1\ncolumns:\n  E-mail: email\n  Name: name");
+        let rows = grid_to_rows(grid.clone(), &params).unwrap();
+        assert_eq!(rows[0], json!({"name": "Ada", "email": "ada@x.io"}));
+        assert_eq!(rows.len(), 3);
+
+        let params = import("file: x\nskip_rows: 1\ncolumns:\n  Phone: phone");
+        let err = grid_to_rows(grid.clone(), &params).unwrap_err();
+        assert!(err.starts_with("column 'Phone' not found"));
+
+        let rows = grid_to_rows(grid, &import("file: x\nheader: false\ncolumns:\n  A: a")).unwrap();
+        assert_eq!(rows[0], json!({"a": "Leads export"}));
+    }
+
+    #[test]
+    fn test_csv_write_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out/leads.tsv");
+        let file = file.to_str().unwrap();
+        let value = json!([{"name": "Ada", "score": 9}, {"name": "B\tob", "note": "x"}]);
+        let export: ExportParams =
+            serde_yaml::from_str(&format!("source: $x\nfile: {}", file)).unwrap();
+        let (columns, count) = write_rows(file, &value, &export, SheetFormat::Csv).unwrap();
+        assert_eq!(columns, vec!["name", "score", "note"]);
+        assert_eq!(count, 2);
+        assert_eq!(
+            std::fs::read_to_string(file).unwrap(),
+            "name\tscore\tnote\nAda\t9\t\n\"B\tob\"\t\tx\n"
+        );
+
+        let rows = read_rows(file, &import(&format!("file: {}", file)), SheetFormat::Csv).unwrap();
+        assert_eq!(rows[0], json!({"name": "Ada", "score": 9, "note": null}));
+        let raw = import(&format!("file: {}\ninfer_types: false", file));
+        let rows = read_rows(file, &raw, SheetFormat::Csv).unwrap();
+        assert_eq!(rows[0]["score"], json!("9"));
+    }
+}
@@ -0,0 +1,265 @@
+//! Tabular codecs: CSV (RFC 4180) and Markdown pipe tables
+//!
+//! Both map a header row plus records to an array of objects keyed by
+//! column name. Cells that read as JSON numbers or booleans are typed.
+//! Serializing takes an array of objects (columns are the union of keys,
+//! in first-seen order) or an array of scalars (one `value` column).
+
+use serde_json::{Map, Value};
+
+use super::{cell_text, infer_scalar, Codec};
+
+pub struct CsvCodec;
+
+impl Codec for CsvCodec {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn parse(&self, text: &str) -> Result<Value, String> {
+        let mut rows = csv_records(text, ',')?.into_iter();
+        let header = rows.next().ok_or("no header row")?;
+        records_to_value(&header, rows)
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, String> {
+        let (columns, rows) = value_to_records(value)?;
+        let mut out = String::new();
+        for row in std::iter::once(columns).chain(rows) {
+            let cells: Vec<String> = row.iter().map(|cell| csv_quote(cell, ',')).collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+pub struct MarkdownTableCodec;
+
+impl Codec for MarkdownTableCodec {
+    fn name(&self) -> &str {
+        "markdown-table"
+    }
+
+    fn parse(&self, text: &str) -> Result<Value, String> {
+        // Models often wrap the table in prose; keep only the pipe rows
+        let mut rows = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('|'))
+            .map(markdown_cells);
+        let header = rows.next().ok_or("no table found (rows start with '|')")?;
+        let rows: Vec<Vec<String>> = rows.filter(|row| !is_delimiter_row(row)).collect();
+        records_to_value(&header, rows.into_iter())
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, String> {
+        let (columns, rows) = value_to_records(value)?;
+        let line = |cells: &[String]| {
+            let cells: Vec<String> = cells
+                .iter()
+                .map(|cell| cell.replace('|', "\\|").replace('\n', "<br>"))
+                .collect();
+            format!("| {} |\n", cells.join(" | "))
+        };
+        let mut out = line(&columns);
+        out.push_str(&line(&vec!["---".to_string(); columns.len()]));
+        for row in &rows {
+            out.push_str(&line(row));
+        }
+        Ok(out)
+    }
+}
+
+/// Zip each record with the header; short records get `null` cells
+fn records_to_value(
+    header: &[String],
+    rows: impl Iterator<Item = Vec<String>>,
+) -> Result<Value, String> {
+    rows.enumerate()
+        .map(|(i, row)| {
+            if row.len() > header.len() {
+                return Err(format!(
+                    "row {} has {} cells, the header has {}",
+                    i + 1,
+                    row.len(),
+                    header.len()
+                ));
+            }
+            let mut record = Map::new();
+            for (j, column) in header.iter().enumerate() {
+                let cell = row.get(j).map_or(Value::Null, |cell| infer_scalar(cell));
+                record.insert(column.clone(), cell);
+            }
+            Ok(Value::Object(record))
+        })
+        .collect()
+}
+
+/// Column names and cell texts of an array
+fn value_to_records(value: &Value) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let (columns, rows) = value_to_cells(value)?;
+    let rows = rows
+        .iter()
+        .map(|row| row.iter().map(cell_text).collect())
+        .collect();
+    Ok((columns, rows))
+}
+
+/// Column names and cell values of an array (missing cells are `null`)
+///
+/// Rows are objects (columns are the union of keys, in first-seen order)
+/// or scalars (one `value` column).
+pub(super) fn value_to_cells(value: &Value) -> Result<(Vec<String>, Vec<Vec<Value>>), String> {
+    let items = value
+        .as_array()
+        .ok_or_else(|| "expected an array of rows".to_string())?;
+    if items.iter().all(|item| !item.is_object()) {
+        let rows = items.iter().map(|item| vec![item.clone()]).collect();
+        return Ok((vec!["value".to_string()], rows));
+    }
+
+    let mut columns: Vec<String> = Vec::new();
+    for item in items {
+        let record = item
+            .as_object()
+            .ok_or_else(|| "rows must all be objects or all be scalars".to_string())?;
+        for key in record.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    let rows = items
+        .iter()
+        .map(|item| {
+            columns
+                .iter()
+                .map(|column| item.get(column).cloned().unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect();
+    Ok((columns, rows))
+}
+
+/// Split CSV text into records, honouring quoted fields
+pub(super) fn csv_records(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, c) if c == delimiter => record.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+    Ok(records)
+}
+
+pub(super) fn csv_quote(cell: &str, delimiter: char) -> String {
+    if cell.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Cells of a `| a | b |` row (`\|` is a literal pipe)
+fn markdown_cells(line: &str) -> Vec<String> {
+    let inner = line.strip_prefix('|').unwrap_or(line);
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    let mut cells = vec![String::new()];
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                cells.last_mut().unwrap().push('|');
+            }
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells
+        .into_iter()
+        .map(|cell| cell.trim().replace("<br>", "\n"))
+        .collect()
+}
+
+/// `|---|:---:|` separator between header and body
+fn is_delimiter_row(cells: &[String]) -> bool {
+    cells.iter().all(|cell| {
+        !cell.is_empty() && cell.chars().all(|c| matches!(c, '-' | ':')) && cell.contains('-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_round_trip_with_quotes() {
+        let text = "name,stars,note\nnika,5,\"fast, small\"\n\"say \"\"hi\"\"\",true,\n";
+        let value = CsvCodec.parse(text).unwrap();
+        assert_eq!(
+            value,
+            json!([
+                {"name": "nika", "stars": 5, "note": "fast, small"},
+                {"name": "say \"hi\"", "stars": true, "note": ""},
+            ])
+        );
+        let again = CsvCodec
+            .parse(&CsvCodec.serialize(&value).unwrap())
+            .unwrap();
+        assert_eq!(again, value);
+        assert!(CsvCodec.parse("a,b\n\"open").is_err());
+        assert!(CsvCodec.parse("a\n1,2").is_err());
+    }
+
+    #[test]
+    fn test_markdown_table_parse_ignores_prose() {
+        let text = "Here you go:\n\n| Name | Stars |\n|:-----|------:|\n| nika | 5 |\n| a\\|b | 3 |\n\nAnything else?";
+        let value = MarkdownTableCodec.parse(text).unwrap();
+        assert_eq!(
+            value,
+            json!([{"Name": "nika", "Stars": 5}, {"Name": "a|b", "Stars": 3}])
+        );
+        assert!(MarkdownTableCodec.parse("no table").is_err());
+    }
+
+    #[test]
+    fn test_markdown_table_serialize() {
+        let table = MarkdownTableCodec
+            .serialize(&json!([{"a": 1, "b": "x|y"}, {"a": 2}]))
+            .unwrap();
+        assert_eq!(table, "| a | b |\n| --- | --- |\n| 1 | x\\|y |\n| 2 |  |\n");
+        assert_eq!(
+            MarkdownTableCodec.serialize(&json!(["one"])).unwrap(),
+            "| value |\n| --- |\n| one |\n"
+        );
+        assert!(MarkdownTableCodec.serialize(&json!({"a": 1})).is_err());
+    }
+}
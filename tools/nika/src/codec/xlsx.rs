@@ -0,0 +1,706 @@
+//! Minimal XLSX reader and writer
+//!
+//! An XLSX file is a zip archive of SpreadsheetML parts. Reading takes one
+//! worksheet's cell values: shared and inline strings, numbers, booleans,
+//! formula results (cached values) and error codes. Numbers styled as
+//! dates come out as ISO 8601 strings. Formatting, merged cells and
+//! formulas themselves are ignored.
+//!
+//! Writing produces a single-sheet workbook with a header row, inline
+//! strings, numbers and booleans, which Excel, LibreOffice and Google
+//! Sheets all open.
+
+use std::io::{Read, Write};
+
+use chrono::{Duration, NaiveDate};
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+use super::xml::{escape, parse_document};
+use super::{cell_text, is_blank};
+
+/// Rows and columns of the largest worksheet Excel supports
+const MAX_ROWS: usize = 1_048_576;
+const MAX_COLUMNS: usize = 16_384;
+
+/// Largest inflated zip entry (guards against zip bombs)
+const MAX_PART_SIZE: u64 = 256 << 20;
+
+/// Cell values of one worksheet (the first, or `sheet` by name), row by
+/// row; blank cells are `null`. Reading stops once `max_rows` non-blank
+/// rows have been read.
+pub fn read_sheet(
+    bytes: &[u8],
+    sheet: Option<&str>,
+    max_rows: Option<usize>,
+) -> Result<Vec<Vec<Value>>, String> {
+    let archive = Archive::open(bytes)?;
+    let workbook = archive.xml("xl/workbook.xml")?;
+    let sheets: Vec<(String, String)> = many(workbook.get("sheets").and_then(|s| s.get("sheet")))
+        .into_iter()
+        .filter_map(|s| Some((attr(s, "name")?.to_string(), attr(s, "r:id")?.to_string())))
+        .collect();
+    let (_, rel_id) = match sheet {
+        Some(name) => sheets.iter().find(|(n, _)| n == name).ok_or_else(|| {
+            let names: Vec<&str> = sheets.iter().map(|(n, _)| n.as_str()).collect();
+            format!("no sheet '{}' (sheets: {})", name, names.join(", "))
+        })?,
+        None => sheets.first().ok_or("workbook has no sheets")?,
+    };
+    let rels = archive.xml("xl/_rels/workbook.xml.rels")?;
+    let target = many(rels.get("Relationship"))
+        .into_iter()
+        .find(|r| attr(r, "Id") == Some(rel_id.as_str()))
+        .and_then(|r| attr(r, "Target"))
+        .ok_or_else(|| format!("sheet relationship '{}' is missing", rel_id))?;
+    let path = match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("xl/{}", target),
+    };
+
+    let shared: Vec<String> = match archive.has("xl/sharedStrings.xml") {
+        true => many(archive.xml("xl/sharedStrings.xml")?.get("si"))
+            .into_iter()
+            .map(rich_text)
+            .collect(),
+        false => Vec::new(),
+    };
+    let date_styles = match archive.has("xl/styles.xml") {
+        true => date_styles(&archive.xml("xl/styles.xml")?),
+        false => Vec::new(),
+    };
+    let epoch = match workbook.get("workbookPr").and_then(|p| attr(p, "date1904")) {
+        Some("1" | "true") => Epoch::Date1904,
+        _ => Epoch::Date1900,
+    };
+
+    let worksheet = archive.xml(&path)?;
+    let mut grid: Vec<Vec<Value>> = Vec::new();
+    let mut filled = 0;
+    let rows = many(worksheet.get("sheetData").and_then(|d| d.get("row")));
+    for row in rows {
+        if max_rows.is_some_and(|max| filled >= max) {
+            break;
+        }
+        let r = match attr(row, "r") {
+            Some(r) => match r.parse::<usize>() {
+                Ok(r @ 1..=MAX_ROWS) => r - 1,
+                _ => return Err(format!("row '{}' is out of range", r)),
+            },
+            None => grid.len(),
+        };
+        if r >= MAX_ROWS {
+            return Err(format!("sheet has more than {} rows", MAX_ROWS));
+        }
+        if grid.len() <= r {
+            grid.resize(r + 1, Vec::new());
+        }
+        for c in many(row.get("c")) {
+            let col = match attr(c, "r") {
+                Some(reference) if reference.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    column_index(reference)
+                        .ok_or_else(|| format!("cell '{}' is out of range", reference))?
+                }
+                _ => grid[r].len(),
+            };
+            if col >= MAX_COLUMNS {
+                return Err(format!(
+                    "row {} has more than {} columns",
+                    r + 1,
+                    MAX_COLUMNS
+                ));
+            }
+            let value = cell_value(c, &shared, &date_styles, epoch)?;
+            if grid[r].len() <= col {
+                grid[r].resize(col + 1, Value::Null);
+            }
+            grid[r][col] = value;
+        }
+        if grid[r].iter().any(|cell| !is_blank(cell)) {
+            filled += 1;
+        }
+    }
+    Ok(grid)
+}
+
+/// Single-sheet workbook with `columns` as the header row
+pub fn write_sheet(
+    sheet: &str,
+    columns: &[String],
+    rows: &[Vec<Value>],
+) -> Result<Vec<u8>, String> {
+    if sheet.is_empty()
+        || sheet.chars().count() > 31
+        || sheet.contains(['[', ']', ':', '*', '?', '/', '\\'])
+    {
+        return Err(format!(
+            "'{}' is not a valid sheet name (1-31 characters, none of []:*?/\\)",
+            sheet
+        ));
+    }
+    let header: Vec<Value> = columns.iter().map(|c| Value::from(c.as_str())).collect();
+    let mut data = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    for (r, row) in std::iter::once(&header).chain(rows).enumerate() {
+        data.push_str(&format!(r#"<row r="{}">"#, r + 1));
+        for (c, value) in row.iter().enumerate() {
+            let at = format!("{}{}", column_name(c), r + 1);
+            match value {
+                Value::Null => {}
+                Value::Number(n) => data.push_str(&format!(r#"<c r="{}"><v>{}</v></c>"#, at, n)),
+                Value::Bool(b) => data.push_str(&format!(
+                    r#"<c r="{}" t="b"><v>{}</v></c>"#,
+                    at,
+                    u8::from(*b)
+                )),
+                other => {
+                    let text: String = cell_text(other)
+                        .chars()
+                        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+                        .collect();
+                    data.push_str(&format!(
+                        r#"<c r="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                        at,
+                        escape(&text)
+                    ));
+                }
+            }
+        }
+        data.push_str("</row>");
+    }
+    data.push_str("</sheetData></worksheet>");
+
+    const RELS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+    const PACKAGE_RELS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+    let files = [
+        (
+            "[Content_Types].xml",
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+                r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+                r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+                r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+                r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                "</Types>"
+            )
+            .to_string(),
+        ),
+        (
+            "_rels/.rels",
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="{}"><Relationship Id="rId1" Type="{}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+                PACKAGE_RELS, RELS
+            ),
+        ),
+        (
+            "xl/workbook.xml",
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="{}"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+                RELS,
+                escape(sheet)
+            ),
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="{}"><Relationship Id="rId1" Type="{}/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+                PACKAGE_RELS, RELS
+            ),
+        ),
+        ("xl/worksheets/sheet1.xml", data),
+    ];
+    write_zip(&files).map_err(|e| e.to_string())
+}
+
+/// Spreadsheet column name of a 0-based index (A, B, …, Z, AA, …)
+pub fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        name.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// 0-based column of a cell reference such as `AB12`; `None` without
+/// column letters or past the last XLSX column (`XFD`)
+pub fn column_index(reference: &str) -> Option<usize> {
+    let letters: Vec<u8> = reference
+        .bytes()
+        .take_while(u8::is_ascii_alphabetic)
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    let n = letters.iter().try_fold(0usize, |n, &b| {
+        n.checked_mul(26)?
+            .checked_add((b.to_ascii_uppercase() - b'A') as usize + 1)
+    })?;
+    (n <= MAX_COLUMNS).then_some(n - 1)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// CELLS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Clone, Copy)]
+enum Epoch {
+    Date1900,
+    Date1904,
+}
+
+fn cell_value(
+    c: &Value,
+    shared: &[String],
+    date_styles: &[bool],
+    epoch: Epoch,
+) -> Result<Value, String> {
+    let kind = attr(c, "t").unwrap_or("n");
+    if kind == "inlineStr" {
+        return Ok(c
+            .get("is")
+            .map_or(Value::Null, |is| Value::String(rich_text(is))));
+    }
+    let Some(v) = c.get("v").map(text) else {
+        return Ok(Value::Null);
+    };
+    Ok(match kind {
+        "s" => {
+            let index: usize = v
+                .trim()
+                .parse()
+                .map_err(|_| format!("bad shared string index '{}'", v))?;
+            Value::String(
+                shared
+                    .get(index)
+                    .ok_or_else(|| format!("shared string {} is missing", index))?
+                    .clone(),
+            )
+        }
+        "b" => Value::Bool(v.trim() == "1"),
+        "str" | "e" => Value::String(v),
+        _ => {
+            let number: f64 = v
+                .trim()
+                .parse()
+                .map_err(|_| format!("bad number '{}'", v))?;
+            let style = attr(c, "s").and_then(|s| s.parse::<usize>().ok());
+            if style.is_some_and(|s| date_styles.get(s).copied().unwrap_or(false)) {
+                if let Some(date) = excel_date(number, epoch) {
+                    return Ok(Value::String(date));
+                }
+            }
+            number_value(number)
+        }
+    })
+}
+
+/// Whole numbers as integers, the rest as floats
+fn number_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() < 9_007_199_254_740_992.0 {
+        Value::from(number as i64)
+    } else {
+        serde_json::Number::from_f64(number).map_or(Value::Null, Value::Number)
+    }
+}
+
+/// ISO 8601 date (or date-time, or time) of a serial day number
+fn excel_date(serial: f64, epoch: Epoch) -> Option<String> {
+    if !(0.0..2_958_466.0).contains(&serial) {
+        return None;
+    }
+    let mut days = serial.floor() as i64;
+    let seconds = ((serial - serial.floor()) * 86_400.0).round() as i64;
+    let base = match epoch {
+        // Serial 60 is Excel's fictional 1900-02-29; earlier serials are off by one
+        Epoch::Date1900 if days < 60 => {
+            days += 1;
+            NaiveDate::from_ymd_opt(1899, 12, 30)?
+        }
+        Epoch::Date1900 => NaiveDate::from_ymd_opt(1899, 12, 30)?,
+        Epoch::Date1904 => NaiveDate::from_ymd_opt(1904, 1, 1)?,
+    };
+    let at = base.and_hms_opt(0, 0, 0)? + Duration::days(days) + Duration::seconds(seconds);
+    Some(match (days, seconds) {
+        (0, s) if s > 0 && matches!(epoch, Epoch::Date1900) => at.format("%H:%M:%S").to_string(),
+        (_, 0) => at.format("%Y-%m-%d").to_string(),
+        _ => at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+    })
+}
+
+/// Per cell style (`xf` index): whether its number format is a date or time
+fn date_styles(styles: &Value) -> Vec<bool> {
+    let custom: FxHashMap<&str, &str> = many(styles.get("numFmts").and_then(|n| n.get("numFmt")))
+        .into_iter()
+        .filter_map(|f| Some((attr(f, "numFmtId")?, attr(f, "formatCode")?)))
+        .collect();
+    many(styles.get("cellXfs").and_then(|x| x.get("xf")))
+        .into_iter()
+        .map(|xf| {
+            let id = attr(xf, "numFmtId").unwrap_or("0");
+            match id.parse::<u32>() {
+                Ok(14..=22 | 45..=47) => true,
+                _ => custom.get(id).is_some_and(|code| is_date_format(code)),
+            }
+        })
+        .collect()
+}
+
+/// Whether a custom number format shows a date or time
+fn is_date_format(code: &str) -> bool {
+    // Ignore quoted text, [colors]/[$locales] and escaped characters
+    let mut plain = String::new();
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => for _ in chars.by_ref().take_while(|&c| c != '"') {},
+            '[' => for _ in chars.by_ref().take_while(|&c| c != ']') {},
+            '\\' | '_' | '*' => {
+                chars.next();
+            }
+            c => plain.push(c.to_ascii_lowercase()),
+        }
+    }
+    plain.contains(['y', 'd', 'h', 's'])
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// XML HELPERS
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Elements parsed as one value (single) or an array (repeated)
+fn many(value: Option<&Value>) -> Vec<&Value> {
+    match value {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(one) => vec![one],
+    }
+}
+
+fn attr<'a>(element: &'a Value, name: &str) -> Option<&'a str> {
+    element.get(format!("@{}", name))?.as_str()
+}
+
+/// Text content of an element
+fn text(value: &Value) -> String {
+    match value {
+        Value::Object(map) => map.get("#text").map(text).unwrap_or_default(),
+        other => cell_text(other),
+    }
+}
+
+/// Text of a string item: `<t>` or the runs `<r><t>` of rich text
+fn rich_text(item: &Value) -> String {
+    match item.get("t") {
+        Some(t) => text(t),
+        None => many(item.get("r"))
+            .into_iter()
+            .filter_map(|run| run.get("t"))
+            .map(text)
+            .collect(),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// ZIP
+// ═══════════════════════════════════════════════════════════════════════════
+
+struct Entry {
+    method: u16,
+    compressed: usize,
+    offset: usize,
+}
+
+/// Zip archive index (no zip64: XLSX parts are small)
+struct Archive<'a> {
+    bytes: &'a [u8],
+    entries: FxHashMap<String, Entry>,
+}
+
+impl<'a> Archive<'a> {
+    fn open(bytes: &'a [u8]) -> Result<Self, String> {
+        let not_xlsx = || "not an XLSX file (zip directory not found)".to_string();
+        let end = (0..bytes.len().saturating_sub(21))
+            .rev()
+            .take(65_557)
+            .find(|&i| bytes[i..].starts_with(b"PK\x05\x06"))
+            .ok_or_else(not_xlsx)?;
+        let count = u16_at(bytes, end + 10).ok_or_else(not_xlsx)? as usize;
+        let mut pos = u32_at(bytes, end + 16).ok_or_else(not_xlsx)? as usize;
+
+        let mut entries = FxHashMap::default();
+        for _ in 0..count {
+            if !bytes
+                .get(pos..)
+                .is_some_and(|b| b.starts_with(b"PK\x01\x02"))
+            {
+                return Err("corrupt zip directory".to_string());
+            }
+            let field = |at: usize| u16_at(bytes, pos + at).map(usize::from);
+            let (Some(name_len), Some(extra_len), Some(comment_len)) =
+                (field(28), field(30), field(32))
+            else {
+                return Err("corrupt zip directory".to_string());
+            };
+            let name = bytes
+                .get(pos + 46..pos + 46 + name_len)
+                .ok_or("corrupt zip directory")?;
+            entries.insert(
+                String::from_utf8_lossy(name).into_owned(),
+                Entry {
+                    method: u16_at(bytes, pos + 10).unwrap_or(0),
+                    compressed: u32_at(bytes, pos + 20).unwrap_or(0) as usize,
+                    offset: u32_at(bytes, pos + 42).unwrap_or(0) as usize,
+                },
+            );
+            pos += 46 + name_len + extra_len + comment_len;
+        }
+        Ok(Self { bytes, entries })
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    fn read(&self, name: &str) -> Result<Vec<u8>, String> {
+        let entry = self
+            .entries
+            .get(name)
+            .ok_or_else(|| format!("'{}' is missing from the workbook", name))?;
+        let corrupt = || format!("corrupt zip entry '{}'", name);
+        let at = entry.offset;
+        if !self
+            .bytes
+            .get(at..)
+            .is_some_and(|b| b.starts_with(b"PK\x03\x04"))
+        {
+            return Err(corrupt());
+        }
+        let name_len = u16_at(self.bytes, at + 26).ok_or_else(corrupt)? as usize;
+        let extra_len = u16_at(self.bytes, at + 28).ok_or_else(corrupt)? as usize;
+        let start = at + 30 + name_len + extra_len;
+        let data = self
+            .bytes
+            .get(start..start + entry.compressed)
+            .ok_or_else(corrupt)?;
+        match entry.method {
+            0 => Ok(data.to_vec()),
+            8 => {
+                let mut out = Vec::new();
+                DeflateDecoder::new(data)
+                    .take(MAX_PART_SIZE + 1)
+                    .read_to_end(&mut out)
+                    .map_err(|e| format!("{}: {}", corrupt(), e))?;
+                if out.len() as u64 > MAX_PART_SIZE {
+                    return Err(format!(
+                        "zip entry '{}' inflates past {} MiB",
+                        name,
+                        MAX_PART_SIZE >> 20
+                    ));
+                }
+                Ok(out)
+            }
+            method => Err(format!(
+                "zip entry '{}' uses unsupported compression {}",
+                name, method
+            )),
+        }
+    }
+
+    /// Root element value of an XML part
+    fn xml(&self, name: &str) -> Result<Value, String> {
+        let bytes = self.read(name)?;
+        let text = String::from_utf8(bytes).map_err(|_| format!("'{}' is not UTF-8", name))?;
+        let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+        parse_document(text, false)
+            .map(|(_, root)| root)
+            .map_err(|e| format!("{}: {}", name, e))
+    }
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Deflated zip archive of (name, content) files
+pub(crate) fn write_zip(files: &[(&str, String)]) -> std::io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut directory = Vec::new();
+    for (name, content) in files {
+        let mut crc = Crc::new();
+        crc.update(content.as_bytes());
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content.as_bytes())?;
+        let data = encoder.finish()?;
+
+        // version, flags (UTF-8 names), method (deflate), time, date (1980-01-01)
+        let mut common = Vec::new();
+        for field in [20u16, 0x0800, 8, 0, 0x21] {
+            common.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [crc.sum(), data.len() as u32, content.len() as u32] {
+            common.extend_from_slice(&field.to_le_bytes());
+        }
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // extra length
+
+        directory.extend_from_slice(b"PK\x01\x02");
+        directory.extend_from_slice(&20u16.to_le_bytes()); // made by
+        directory.extend_from_slice(&common);
+        directory.extend_from_slice(&[0; 6]); // comment length, disk, internal attributes
+        directory.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        directory.extend_from_slice(&(out.len() as u32).to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+
+        out.extend_from_slice(b"PK\x03\x04");
+        out.extend_from_slice(&common);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&data);
+    }
+    let directory_offset = out.len() as u32;
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(b"PK\x05\x06");
+    out.extend_from_slice(&[0; 4]); // disk numbers
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    out.extend_from_slice(&directory_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_xlsx_round_trip() {
+        let columns = vec!["name".to_string(), "stars".to_string(), "ok".to_string()];
+        let rows = vec![
+            vec![json!("nika <fast>"), json!(5), json!(true)],
+            vec![json!("1.50"), json!(2.5), Value::Null],
+        ];
+        let bytes = write_sheet("Leads", &columns, &rows).unwrap();
+
+        let grid = read_sheet(&bytes, Some("Leads"), None).unwrap();
+        assert_eq!(grid[0], vec![json!("name"), json!("stars"), json!("ok")]);
+        assert_eq!(grid[1], rows[0]);
+        assert_eq!(grid[2], vec![json!("1.50"), json!(2.5)]);
+        assert_eq!(read_sheet(&bytes, None, None).unwrap(), grid);
+
+        let err = read_sheet(&bytes, Some("Q3"), None).unwrap_err();
+        assert_eq!(err, "no sheet 'Q3' (sheets: Leads)");
+        assert!(read_sheet(b"name,stars", None, None).is_err());
+        assert!(write_sheet("a/b", &columns, &rows).is_err());
+    }
+
+    #[test]
+    fn test_read_shared_strings_and_dates() {
+        let files = [
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns:r="r"><sheets><sheet name="S" sheetId="1" r:id="rId7"/></sheets></workbook>"#.to_string(),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId7" Target="/xl/worksheets/data.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst><si><t>Due</t></si><si><r><t>Rich </t></r><r><t>text</t></r></si></sst>"#.to_string(),
+            ),
+            (
+                "xl/styles.xml",
+                r#"<styleSheet><numFmts><numFmt numFmtId="164" formatCode="yyyy\-mm\-dd"/></numFmts><cellXfs><xf numFmtId="0"/><xf numFmtId="164"/><xf numFmtId="22"/></cellXfs></styleSheet>"#.to_string(),
+            ),
+            (
+                "xl/worksheets/data.xml",
+                r#"<worksheet><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="C1" t="s"><v>1</v></c></row><row r="3"><c r="A3" s="1"><v>45292</v></c><c r="B3" s="2"><v>45292.5</v></c><c r="C3" t="e"><v>#N/A</v></c></row></sheetData></worksheet>"#.to_string(),
+            ),
+        ];
+        let bytes = write_zip(&files).unwrap();
+        let grid = read_sheet(&bytes, None, None).unwrap();
+        assert_eq!(grid[0], vec![json!("Due"), Value::Null, json!("Rich text")]);
+        assert!(grid[1].is_empty());
+        assert_eq!(
+            grid[2],
+            vec![
+                json!("2024-01-01"),
+                json!("2024-01-01T12:00:00"),
+                json!("#N/A")
+            ]
+        );
+    }
+
+    fn workbook(sheet_data: &str) -> Vec<u8> {
+        let files = [
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns:r="r"><sheets><sheet name="S" sheetId="1" r:id="rId1"/></sheets></workbook>"#.to_string(),
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/></Relationships>"#.to_string(),
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                format!("<worksheet><sheetData>{}</sheetData></worksheet>", sheet_data),
+            ),
+        ];
+        write_zip(&files).unwrap()
+    }
+
+    #[test]
+    fn test_read_rejects_refs_past_sheet_limits() {
+        let far_column = workbook(r#"<row r="1"><c r="ZZZZZZZZZZZZZZZ1"><v>1</v></c></row>"#);
+        let err = read_sheet(&far_column, None, None).unwrap_err();
+        assert_eq!(err, "cell 'ZZZZZZZZZZZZZZZ1' is out of range");
+
+        let far_row = workbook(r#"<row r="99999999999"><c r="A99999999999"><v>1</v></c></row>"#);
+        let err = read_sheet(&far_row, None, None).unwrap_err();
+        assert_eq!(err, "row '99999999999' is out of range");
+
+        let last = workbook(r#"<row r="1048576"><c r="XFD1048576"><v>1</v></c></row>"#);
+        let grid = read_sheet(&last, None, None).unwrap();
+        assert_eq!(grid.len(), MAX_ROWS);
+        assert_eq!(grid[MAX_ROWS - 1].len(), MAX_COLUMNS);
+    }
+
+    #[test]
+    fn test_read_stops_after_max_rows() {
+        // a blank first row doesn't count toward `max_rows`
+        let rows: String = (2..=50)
+            .map(|r| format!(r#"<row r="{r}"><c r="A{r}"><v>{r}</v></c></row>"#))
+            .collect();
+        let blank = r#"<row r="1"><c r="A1" t="inlineStr"><is><t> </t></is></c></row>"#;
+        let bytes = workbook(&format!("{}{}", blank, rows));
+        let grid = read_sheet(&bytes, None, Some(3)).unwrap();
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid[3], vec![json!(4)]);
+        assert_eq!(read_sheet(&bytes, None, None).unwrap().len(), 50);
+    }
+
+    #[test]
+    fn test_column_names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(27), "AB");
+        assert_eq!(column_index("AB12"), Some(27));
+        assert_eq!(column_index("XFD1"), Some(MAX_COLUMNS - 1));
+        assert_eq!(column_index("XFE1"), None);
+        assert_eq!(column_index("12"), None);
+        assert!(is_date_format("[$-409]d-mmm-yy"));
+        assert!(!is_date_format("#,##0.00 \"days\""));
+    }
+}
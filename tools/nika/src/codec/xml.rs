@@ -0,0 +1,385 @@
+//! Minimal XML codec
+//!
+//! Enough XML for model output and config-style documents, not a full
+//! parser: no namespaces or DTDs. The document becomes `{root: value}`:
+//! - an element with only text is its text (numbers and booleans typed)
+//! - an empty element is `null`
+//! - otherwise an object: attributes as `@name`, text as `#text`, children
+//!   by tag name, repeated children as an array
+//!
+//! Serializing reverses the mapping. A value that is not a single-key
+//! object is wrapped in `<root>` (array elements as `<item>`).
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use serde_json::{Map, Value};
+
+use super::{cell_text, infer_scalar, Codec};
+
+pub struct XmlCodec;
+
+impl Codec for XmlCodec {
+    fn name(&self) -> &str {
+        "xml"
+    }
+
+    fn parse(&self, text: &str) -> Result<Value, String> {
+        let (name, value) = parse_document(text, true)?;
+        let mut root = Map::new();
+        root.insert(name, value);
+        Ok(Value::Object(root))
+    }
+
+    fn serialize(&self, value: &Value) -> Result<String, String> {
+        let mut out = String::new();
+        match value.as_object() {
+            Some(map) if map.len() == 1 && is_name(map.keys().next().unwrap()) => {
+                let (name, value) = map.iter().next().unwrap();
+                write_element(&mut out, name, value)?;
+            }
+            _ if value.is_array() => {
+                out.push_str("<root>");
+                write_element(&mut out, "item", value)?;
+                out.push_str("</root>");
+            }
+            _ => write_element(&mut out, "root", value)?,
+        }
+        Ok(out)
+    }
+}
+
+/// Root element name and value of a document
+///
+/// With `typed: false`, text stays a string as written (no number or
+/// boolean typing, no trimming), for formats where text is data.
+pub(super) fn parse_document(text: &str, typed: bool) -> Result<(String, Value), String> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+        typed,
+    };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.chars.peek().is_some() {
+        return Err("content after the root element".to_string());
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    typed: bool,
+}
+
+impl Parser<'_> {
+    /// Skip whitespace, `<?...?>`, comments and `<!DOCTYPE ...>`
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+            let mut ahead = self.chars.clone();
+            if ahead.next() != Some('<') {
+                return Ok(());
+            }
+            match ahead.next() {
+                Some('?') => self.skip_past("?>")?,
+                Some('!') if self.rest_starts_with("<!--") => self.skip_past("-->")?,
+                Some('!') if !self.rest_starts_with("<![CDATA[") => self.skip_past(">")?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn rest_starts_with(&self, prefix: &str) -> bool {
+        let mut ahead = self.chars.clone();
+        prefix.chars().all(|c| ahead.next() == Some(c))
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<(), String> {
+        let mut seen = String::new();
+        for c in self.chars.by_ref() {
+            seen.push(c);
+            if seen.ends_with(end) {
+                return Ok(());
+            }
+        }
+        Err(format!("missing '{}'", end))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected '{}', found '{}'", expected, c)),
+            None => Err(format!("expected '{}', found end of input", expected)),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        let mut name = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+        {
+            name.push(c);
+        }
+        if name.is_empty() {
+            return Err("expected a tag or attribute name".to_string());
+        }
+        Ok(name)
+    }
+
+    /// One element, starting at its `<`
+    fn element(&mut self) -> Result<(String, Value), String> {
+        self.expect('<')?;
+        let name = self.name()?;
+        let mut object = Map::new();
+        loop {
+            while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+            match self.chars.peek() {
+                Some('/') => {
+                    self.chars.next();
+                    self.expect('>')?;
+                    let value = if object.is_empty() {
+                        Value::Null
+                    } else {
+                        Value::Object(object)
+                    };
+                    return Ok((name, value));
+                }
+                Some('>') => {
+                    self.chars.next();
+                    break;
+                }
+                _ => {
+                    let attr = self.name()?;
+                    self.expect('=')?;
+                    let quote = self
+                        .chars
+                        .next_if(|c| matches!(c, '"' | '\''))
+                        .ok_or_else(|| format!("attribute '{}' value must be quoted", attr))?;
+                    let mut raw = String::new();
+                    loop {
+                        match self.chars.next() {
+                            Some(c) if c == quote => break,
+                            Some(c) => raw.push(c),
+                            None => return Err(format!("unterminated attribute '{}'", attr)),
+                        }
+                    }
+                    object.insert(format!("@{}", attr), Value::String(unescape(&raw)?));
+                }
+            }
+        }
+
+        let mut text = String::new();
+        loop {
+            if self.rest_starts_with("</") {
+                self.chars.next();
+                self.chars.next();
+                let close = self.name()?;
+                if close != name {
+                    return Err(format!("<{}> closed by </{}>", name, close));
+                }
+                while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+                self.expect('>')?;
+                break;
+            } else if self.rest_starts_with("<![CDATA[") {
+                (0..9).for_each(|_| {
+                    self.chars.next();
+                });
+                let mut data = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some(c) => data.push(c),
+                        None => return Err("unterminated CDATA section".to_string()),
+                    }
+                    if data.ends_with("]]>") {
+                        data.truncate(data.len() - 3);
+                        break;
+                    }
+                }
+                text.push_str(&data);
+            } else if self.rest_starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.chars.peek() == Some(&'<') {
+                let (child, value) = self.element()?;
+                match object.get_mut(&child) {
+                    Some(Value::Array(items)) => items.push(value),
+                    Some(existing) => {
+                        let first = existing.take();
+                        *existing = Value::Array(vec![first, value]);
+                    }
+                    None => {
+                        object.insert(child, value);
+                    }
+                }
+            } else {
+                let mut raw = String::new();
+                while let Some(c) = self.chars.next_if(|c| *c != '<') {
+                    raw.push(c);
+                }
+                if raw.is_empty() {
+                    return Err(format!("unclosed <{}>", name));
+                }
+                text.push_str(&unescape(&raw)?);
+            }
+        }
+
+        let text = match text.trim() {
+            "" => "",
+            trimmed if self.typed => trimmed,
+            _ => text.as_str(),
+        };
+        Ok((
+            name,
+            match (object.is_empty(), text.is_empty()) {
+                (true, true) => Value::Null,
+                (true, false) if self.typed => infer_scalar(text),
+                (true, false) => Value::String(text.to_string()),
+                (false, true) => Value::Object(object),
+                (false, false) => {
+                    object.insert("#text".to_string(), Value::String(text.to_string()));
+                    Value::Object(object)
+                }
+            },
+        ))
+    }
+}
+
+fn unescape(raw: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let end = rest[amp..]
+            .find(';')
+            .ok_or_else(|| format!("unterminated entity in '{}'", raw))?;
+        let entity = &rest[amp + 1..amp + end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .ok_or_else(|| format!("unknown entity '&{};'", entity))?,
+        };
+        out.push(c);
+        rest = &rest[amp + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn is_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+fn write_element(out: &mut String, name: &str, value: &Value) -> Result<(), String> {
+    if !is_name(name) {
+        return Err(format!("'{}' is not a valid element name", name));
+    }
+    match value {
+        Value::Null => out.push_str(&format!("<{}/>", name)),
+        Value::Array(items) => {
+            for item in items {
+                if item.is_array() {
+                    return Err(format!("nested arrays under '{}' have no XML form", name));
+                }
+                write_element(out, name, item)?;
+            }
+        }
+        Value::Object(map) => {
+            out.push('<');
+            out.push_str(name);
+            for (key, attr) in map {
+                if let Some(attr_name) = key.strip_prefix('@') {
+                    if attr.is_object() || attr.is_array() {
+                        return Err(format!("attribute '{}' must be a scalar", key));
+                    }
+                    out.push_str(&format!(" {}=\"{}\"", attr_name, escape(&cell_text(attr))));
+                }
+            }
+            out.push('>');
+            if let Some(text) = map.get("#text") {
+                out.push_str(&escape(&cell_text(text)));
+            }
+            for (key, child) in map {
+                if !key.starts_with('@') && key != "#text" {
+                    write_element(out, key, child)?;
+                }
+            }
+            out.push_str(&format!("</{}>", name));
+        }
+        scalar => out.push_str(&format!("<{0}>{1}</{0}>", name, escape(&cell_text(scalar)))),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_xml_document() {
+        let text = r#"<?xml version="1.0"?>
+<!-- leads -->
+<leads source="crm">
+  <lead id="1"><name>Ada &amp; Co</name><score>92</score></lead>
+  <lead id="2"><name><![CDATA[<Bob>]]></name><score>40</score><vip/></lead>
+  <note>two &#x2764; leads</note>
+</leads>"#;
+        let value = XmlCodec.parse(text).unwrap();
+        assert_eq!(
+            value,
+            json!({"leads": {
+                "@source": "crm",
+                "lead": [
+                    {"@id": "1", "name": "Ada & Co", "score": 92},
+                    {"@id": "2", "name": "<Bob>", "score": 40, "vip": null},
+                ],
+                "note": "two ❤ leads",
+            }})
+        );
+        assert!(XmlCodec.parse("<a><b></a>").is_err());
+        assert!(XmlCodec.parse("<a/><b/>").is_err());
+    }
+
+    #[test]
+    fn test_xml_round_trip() {
+        let value = json!({"order": {
+            "@id": "7",
+            "#text": "rush",
+            "item": [{"sku": "a<1"}, {"sku": "b"}],
+            "gift": null,
+        }});
+        let text = XmlCodec.serialize(&value).unwrap();
+        assert_eq!(
+            text,
+            r#"<order id="7">rush<gift/><item><sku>a&lt;1</sku></item><item><sku>b</sku></item></order>"#
+        );
+        assert_eq!(XmlCodec.parse(&text).unwrap(), value);
+        assert_eq!(
+            XmlCodec.serialize(&json!([1, 2])).unwrap(),
+            "<root><item>1</item><item>2</item></root>"
+        );
+        assert!(XmlCodec.serialize(&json!({"a": {"b c": 1}})).is_err());
+    }
+}
@@ -9,8 +9,9 @@
 //! 2. Config file (`~/.config/nika/config.toml`)
 //! 3. Defaults
 
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +27,42 @@ pub struct NikaConfig {
     /// Default provider and model settings
     #[serde(default)]
     pub defaults: Defaults,
+
+    /// DataStore backend for task results (v0.7)
+    #[serde(default)]
+    pub store: StoreConfig,
+
+    /// Model routing for `model: auto` (v0.7)
+    #[serde(default)]
+    pub router: RouterConfig,
+
+    /// Named credentials for `fetch: { auth }` (v0.7)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub auth: BTreeMap<String, AuthProfile>,
+
+    /// Relay for `nika run --share` (v0.7)
+    #[serde(default)]
+    pub share: ShareConfig,
+
+    /// Adaptive in-flight limits per provider (v0.7)
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+
+    /// Backend of the agents' `web_search` tool (v0.7)
+    #[serde(default)]
+    pub web_search: WebSearchConfig,
+
+    /// Several API keys per provider, used in turn (v0.7)
+    #[serde(default)]
+    pub key_rotation: KeyRotationConfig,
+
+    /// Caps on prompt and response size per provider (v0.7)
+    #[serde(default)]
+    pub size_limits: SizeLimitsConfig,
+
+    /// Store of the agents' `remember`/`recall` tools (v0.7)
+    #[serde(default)]
+    pub memory: MemoryConfig,
 }
 
 /// API keys configuration
@@ -48,6 +85,554 @@ pub struct Defaults {
     pub model: Option<String>,
 }
 
+/// DataStore backend settings (v0.7)
+///
+/// ```toml
+/// [store]
+/// backend = "redis"          # memory (default) | sled | redis
+/// url = "redis://127.0.0.1/"  # redis only
+/// # path = ".nika/store"     # sled only (default)
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StoreConfig {
+    #[serde(default)]
+    pub backend: StoreBackendKind,
+
+    /// sled database directory
+    pub path: Option<PathBuf>,
+
+    /// Redis connection URL
+    pub url: Option<String>,
+}
+
+/// Where task results are kept
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreBackendKind {
+    /// In-process DashMap, gone when the run ends
+    #[default]
+    Memory,
+    /// Local on-disk database (`store-sled` feature)
+    Sled,
+    /// Redis server shared by several workers (`store-redis` feature)
+    Redis,
+}
+
+impl StoreBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::Sled => "sled",
+            Self::Redis => "redis",
+        }
+    }
+}
+
+/// Model router settings for `model: auto` (v0.7)
+///
+/// Rules are tried in order; the first whose conditions all hold picks the
+/// tier, otherwise `default` applies. Tier models fall back to each
+/// provider's cheap/premium pair (see `ProviderInfo::tiers`).
+///
+/// ```toml
+/// [router]
+/// default = "cheap"
+/// premium = "claude-opus-4-20250514"  # optional: override the tier model
+///
+/// [[router.rules]]
+/// min_prompt_chars = 8000
+/// tier = "premium"
+///
+/// [[router.rules]]
+/// tags = ["critical"]
+/// tier = "premium"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RouterConfig {
+    /// Tier when no rule matches
+    #[serde(default)]
+    pub default: ModelTier,
+
+    /// Model for the cheap tier, for every provider
+    pub cheap: Option<String>,
+
+    /// Model for the premium tier, for every provider
+    pub premium: Option<String>,
+
+    /// Routing rules, first match wins
+    #[serde(default = "default_route_rules")]
+    pub rules: Vec<RouteRule>,
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            default: ModelTier::default(),
+            cheap: None,
+            premium: None,
+            rules: default_route_rules(),
+        }
+    }
+}
+
+/// Long prompts, tool use and `tags: [premium]` go to the premium tier
+fn default_route_rules() -> Vec<RouteRule> {
+    vec![
+        RouteRule {
+            min_prompt_chars: Some(8000),
+            tier: ModelTier::Premium,
+            ..Default::default()
+        },
+        RouteRule {
+            tools: Some(true),
+            tier: ModelTier::Premium,
+            ..Default::default()
+        },
+        RouteRule {
+            tags: vec!["premium".to_string()],
+            tier: ModelTier::Premium,
+            ..Default::default()
+        },
+    ]
+}
+
+/// Model class picked by the router
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelTier {
+    #[default]
+    Cheap,
+    Premium,
+}
+
+impl ModelTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cheap => "cheap",
+            Self::Premium => "premium",
+        }
+    }
+}
+
+/// One routing rule; unset conditions always hold
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteRule {
+    /// Resolved prompt is at least this many characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_prompt_chars: Option<usize>,
+
+    /// Task can call tools (an `agent:` with `mcp:` servers), or can't
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<bool>,
+
+    /// Task has any of these `tags:`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Tier to use when the rule matches
+    pub tier: ModelTier,
+}
+
+/// Credentials a `fetch:` task refers to by name (v0.7)
+///
+/// Kept in the user config so secrets stay out of workflow files. `${NAME}`
+/// in a value is replaced with that environment variable when used.
+///
+/// ```toml
+/// [auth.github]
+/// type = "bearer"
+/// token = "${GITHUB_TOKEN}"
+///
+/// [auth.legacy]
+/// type = "basic"
+/// username = "svc-reports"
+/// password = "${LEGACY_PASSWORD}"
+///
+/// [auth.partner]
+/// type = "headers"
+/// headers = { "X-Api-Key" = "${PARTNER_KEY}", "X-Tenant" = "acme" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum AuthProfile {
+    /// `Authorization: Bearer <token>`
+    Bearer { token: String },
+    /// `Authorization: Basic base64(username:password)`
+    Basic {
+        username: String,
+        #[serde(default)]
+        password: Option<String>,
+    },
+    /// Arbitrary header set
+    Headers { headers: BTreeMap<String, String> },
+}
+
+impl AuthProfile {
+    /// Request headers for this profile, with `${NAME}` expanded
+    pub fn headers(&self) -> std::result::Result<Vec<(String, String)>, String> {
+        use base64::Engine as _;
+
+        Ok(match self {
+            Self::Bearer { token } => vec![(
+                "Authorization".to_string(),
+                format!("Bearer {}", expand_env(token)?),
+            )],
+            Self::Basic { username, password } => {
+                let password = password.as_deref().map(expand_env).transpose()?;
+                let credentials =
+                    format!("{}:{}", expand_env(username)?, password.unwrap_or_default());
+                let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+                vec![("Authorization".to_string(), format!("Basic {}", encoded))]
+            }
+            Self::Headers { headers } => headers
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), expand_env(value)?)))
+                .collect::<std::result::Result<_, String>>()?,
+        })
+    }
+}
+
+/// Session sharing for `nika run --share` (v0.7)
+///
+/// ```toml
+/// [share]
+/// relay = "http://relay.internal:7878"   # a `nika relay` server
+/// redact = ["ACME-[0-9]{6}"]             # extra patterns to scrub
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ShareConfig {
+    /// Base URL of the relay (`--relay` overrides it)
+    pub relay: Option<String>,
+
+    /// Regexes redacted from shared events, on top of the built-in rules
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redact: Vec<String>,
+}
+
+/// Adaptive provider concurrency (v0.7)
+///
+/// Each provider starts at `initial` requests in flight. A 429/5xx answer
+/// halves its limit (never below `min`); every `limit` successful calls in
+/// a row raise it by one (never above `max`).
+///
+/// ```toml
+/// [concurrency]
+/// adaptive = true   # false: no per-provider limit
+/// initial = 16
+/// min = 1
+/// max = 64
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ConcurrencyConfig {
+    /// Limit in-flight requests per provider
+    pub adaptive: bool,
+
+    /// Limit before any feedback
+    pub initial: usize,
+
+    /// Floor after repeated throttling
+    pub min: usize,
+
+    /// Ceiling after sustained success
+    pub max: usize,
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            adaptive: true,
+            initial: 16,
+            min: 1,
+            max: 64,
+        }
+    }
+}
+
+/// Search backend for the `web_search` agent tool (v0.7)
+///
+/// Without `backend`, the first of `BRAVE_API_KEY`, `TAVILY_API_KEY` and
+/// `SEARXNG_URL` set in the environment picks it. `api_key` and `url`
+/// default to the same variables.
+///
+/// ```toml
+/// [web_search]
+/// backend = "brave"              # brave | tavily | searxng
+/// api_key = "${BRAVE_API_KEY}"   # brave and tavily
+/// # url = "http://localhost:8888" # searxng instance (or another API base)
+/// max_results = 5
+/// per_minute = 30                # 0: no rate limit
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WebSearchConfig {
+    /// Search API to call
+    pub backend: Option<SearchBackendKind>,
+
+    /// API key, `${NAME}` expanded
+    pub api_key: Option<String>,
+
+    /// Base URL of the API
+    pub url: Option<String>,
+
+    /// Results per query when the agent doesn't ask for a count
+    pub max_results: usize,
+
+    /// Searches per minute across all agents of a run
+    pub per_minute: u32,
+}
+
+impl Default for WebSearchConfig {
+    fn default() -> Self {
+        Self {
+            backend: None,
+            api_key: None,
+            url: None,
+            max_results: 5,
+            per_minute: 30,
+        }
+    }
+}
+
+impl WebSearchConfig {
+    /// `api_key` with `${NAME}` expanded
+    pub fn resolved_api_key(&self) -> std::result::Result<Option<String>, String> {
+        self.api_key.as_deref().map(expand_env).transpose()
+    }
+}
+
+/// API keys used in turn per provider (v0.7)
+///
+/// Providers listed here take their key from this list instead of the
+/// environment. A key answering with an auth error (401/403) is dropped
+/// for the rest of the process; one out of quota, or over its rate limit
+/// `quarantine_limited` times in a row, sits out `quarantine_secs`.
+///
+/// ```toml
+/// [key_rotation]
+/// strategy = "least_limited"  # round_robin (default) | least_limited
+/// quarantine_secs = 300
+/// quarantine_limited = 3      # 0: rate limits never quarantine
+///
+/// [key_rotation.keys]
+/// claude = ["${ANTHROPIC_API_KEY}", "${ANTHROPIC_API_KEY_2}"]
+/// openai = ["${OPENAI_KEY_TEAM_A}", "${OPENAI_KEY_TEAM_B}"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct KeyRotationConfig {
+    /// How the next key is picked
+    pub strategy: RotationStrategy,
+
+    /// Seconds a key sits out after a quota error
+    pub quarantine_secs: u64,
+
+    /// Rate-limit errors in a row that quarantine a key
+    pub quarantine_limited: u32,
+
+    /// Keys per provider (`claude`, `openai`, `mistral`, `groq`, `deepseek`), `${NAME}` expanded
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for KeyRotationConfig {
+    fn default() -> Self {
+        Self {
+            strategy: RotationStrategy::default(),
+            quarantine_secs: 300,
+            quarantine_limited: 3,
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl KeyRotationConfig {
+    /// `keys` with `${NAME}` expanded
+    pub fn resolved_keys(&self) -> std::result::Result<BTreeMap<String, Vec<String>>, String> {
+        self.keys
+            .iter()
+            .map(|(provider, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| expand_env(key))
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(|e| format!("{}: {}", provider, e))?;
+                Ok((provider.clone(), keys))
+            })
+            .collect()
+    }
+}
+
+/// Which key of a provider the next call uses
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationStrategy {
+    /// Each key in turn
+    #[default]
+    RoundRobin,
+    /// The key whose last rate-limit error is oldest (never limited first)
+    LeastLimited,
+}
+
+/// Prompt and response size caps (v0.7)
+///
+/// An `infer:` or `agent:` task whose resolved prompt is longer than
+/// `max_prompt_chars`, or whose `max_tokens` is above `max_response_tokens`,
+/// fails with NIKA-122 before the request is sent. `infer:` tasks without
+/// `max_tokens` get `max_response_tokens` as their limit. A provider table
+/// overrides either cap for that provider.
+///
+/// ```toml
+/// [size_limits]
+/// max_prompt_chars = 400000
+/// max_response_tokens = 8192
+///
+/// [size_limits.providers.groq]
+/// max_prompt_chars = 100000
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SizeLimitsConfig {
+    /// Longest resolved prompt, in characters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_prompt_chars: Option<usize>,
+
+    /// Highest `max_tokens` a task may ask for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_tokens: Option<u32>,
+
+    /// Overrides per provider (`claude`, `openai`, ...)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub providers: BTreeMap<String, SizeLimit>,
+}
+
+/// Caps of one provider; unset fields keep the `[size_limits]` value
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SizeLimit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_prompt_chars: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_tokens: Option<u32>,
+}
+
+impl SizeLimitsConfig {
+    /// Caps that apply to `provider`
+    pub fn for_provider(&self, provider: &str) -> SizeLimit {
+        let own = self.providers.get(provider).copied().unwrap_or_default();
+        SizeLimit {
+            max_prompt_chars: own.max_prompt_chars.or(self.max_prompt_chars),
+            max_response_tokens: own.max_response_tokens.or(self.max_response_tokens),
+        }
+    }
+}
+
+/// Agent memory store (v0.7)
+///
+/// Where `agent:` tasks with `memory: true` keep what they `remember`
+/// across runs. Memories are namespaced per project, so one store can be
+/// shared by several projects.
+///
+/// ```toml
+/// [memory]
+/// backend = "sqlite"          # file (default) | sqlite
+/// # path = ".nika/memory.db"  # default: .nika/memory.json or .nika/memory.db
+/// # namespace = "triage"      # default: the project directory's name
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct MemoryConfig {
+    pub backend: MemoryBackendKind,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+/// Where agent memories are kept
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryBackendKind {
+    /// JSON file, re-read on each access
+    #[default]
+    File,
+    /// SQLite database (`memory-sqlite` feature)
+    Sqlite,
+}
+
+impl MemoryBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Sqlite => "sqlite",
+        }
+    }
+}
+
+impl MemoryConfig {
+    /// Store location, defaulting by backend
+    pub fn path(&self) -> PathBuf {
+        self.path.clone().unwrap_or_else(|| match self.backend {
+            MemoryBackendKind::File => PathBuf::from(".nika").join("memory.json"),
+            MemoryBackendKind::Sqlite => PathBuf::from(".nika").join("memory.db"),
+        })
+    }
+
+    /// Namespace of this project's memories
+    pub fn namespace(&self) -> String {
+        self.namespace.clone().unwrap_or_else(|| {
+            std::env::current_dir()
+                .ok()
+                .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+                .unwrap_or_else(|| "default".to_string())
+        })
+    }
+}
+
+/// Web search API
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchBackendKind {
+    /// Brave Search API
+    Brave,
+    /// Tavily search API
+    Tavily,
+    /// Self-hosted SearxNG instance (JSON format enabled)
+    Searxng,
+}
+
+impl SearchBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Brave => "brave",
+            Self::Tavily => "tavily",
+            Self::Searxng => "searxng",
+        }
+    }
+}
+
+/// Replace each `${NAME}` with the environment variable `NAME`
+pub(crate) fn expand_env(value: &str) -> std::result::Result<String, String> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed ${{ in '{}'", value))?;
+        let name = &rest[start + 2..start + end];
+        let var = std::env::var(name).map_err(|_| format!("${{{}}} is not set", name))?;
+        out.push_str(&rest[..start]);
+        out.push_str(&var);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
 impl NikaConfig {
     /// Get the config directory path
     ///
@@ -182,6 +767,24 @@ pub fn mask_api_key(key: &str, visible_chars: usize) -> String {
     format!("{}***", &key[..visible])
 }
 
+/// Find the project's `.nika/config.toml` in `start` or its ancestors
+///
+/// Holds per-project settings such as `[lint]` and `[hosts]` (v0.7).
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(".nika").join("config.toml"))
+        .find(|path| path.is_file())
+}
+
+/// Absolute directory of a workflow file, where project config lookups start
+///
+/// A bare filename (`wf.nika.yaml`) resolves to the current directory.
+pub fn workflow_dir(file: &Path) -> Result<PathBuf> {
+    let dir = file.parent().unwrap_or(Path::new(""));
+    Ok(fs::canonicalize(dir.join("."))?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +828,19 @@ mod tests {
                 provider: Some("claude".into()),
                 model: Some("claude-sonnet-4-20250514".into()),
             },
+            store: StoreConfig {
+                backend: StoreBackendKind::Sled,
+                path: Some(PathBuf::from("/var/lib/nika")),
+                url: None,
+            },
+            router: RouterConfig::default(),
+            auth: BTreeMap::new(),
+            share: ShareConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            web_search: WebSearchConfig::default(),
+            key_rotation: KeyRotationConfig::default(),
+            size_limits: SizeLimitsConfig::default(),
+            memory: MemoryConfig::default(),
         };
 
         // Manually save to temp path
@@ -338,6 +954,7 @@ mod tests {
                 provider: Some("openai".into()),
                 model: None,
             },
+            ..Default::default()
         };
         assert_eq!(explicit.default_provider(), Some("openai"));
     }
@@ -364,6 +981,15 @@ mod tests {
                 provider: Some("claude".into()),
                 model: None,
             },
+            store: StoreConfig::default(),
+            router: RouterConfig::default(),
+            auth: BTreeMap::new(),
+            share: ShareConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            web_search: WebSearchConfig::default(),
+            key_rotation: KeyRotationConfig::default(),
+            size_limits: SizeLimitsConfig::default(),
+            memory: MemoryConfig::default(),
         };
 
         let toml_str = toml::to_string_pretty(&config).unwrap();
@@ -375,6 +1001,201 @@ mod tests {
         assert!(toml_str.contains("provider = \"claude\""));
     }
 
+    #[test]
+    fn test_store_section() {
+        let config: NikaConfig =
+            toml::from_str("[store]\nbackend = \"redis\"\nurl = \"redis://cache:6379/\"\n")
+                .unwrap();
+        assert_eq!(config.store.backend, StoreBackendKind::Redis);
+        assert_eq!(config.store.url.as_deref(), Some("redis://cache:6379/"));
+
+        // Missing section keeps results in memory
+        let config: NikaConfig = toml::from_str("[defaults]\n").unwrap();
+        assert_eq!(config.store.backend, StoreBackendKind::Memory);
+        assert!(toml::from_str::<NikaConfig>("[store]\nbackend = \"etcd\"\n").is_err());
+    }
+
+    #[test]
+    fn test_router_section() {
+        let config: NikaConfig = toml::from_str(
+            "[router]\ndefault = \"premium\"\ncheap = \"gpt-4o-mini\"\n\n\
+             [[router.rules]]\ntags = [\"draft\"]\ntier = \"cheap\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.router.default, ModelTier::Premium);
+        assert_eq!(config.router.cheap.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(config.router.rules.len(), 1);
+        assert_eq!(config.router.rules[0].tags, vec!["draft"]);
+
+        // Missing section (or rules) keeps the built-in rules
+        let config: NikaConfig = toml::from_str("[router]\ndefault = \"cheap\"\n").unwrap();
+        assert_eq!(config.router, RouterConfig::default());
+        assert!(toml::from_str::<NikaConfig>(
+            "[[router.rules]]\nmax_tokens = 10\ntier = \"cheap\"\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_concurrency_section() {
+        let config: NikaConfig = toml::from_str("[concurrency]\ninitial = 4\nmax = 8\n").unwrap();
+        assert!(config.concurrency.adaptive);
+        assert_eq!(
+            (
+                config.concurrency.initial,
+                config.concurrency.min,
+                config.concurrency.max
+            ),
+            (4, 1, 8)
+        );
+
+        let config: NikaConfig = toml::from_str("").unwrap();
+        assert_eq!(config.concurrency, ConcurrencyConfig::default());
+    }
+
+    #[test]
+    fn test_web_search_section() {
+        std::env::set_var("NIKA_TEST_SEARCH_KEY", "tvly-123");
+        let config: NikaConfig = toml::from_str(
+            r#"
+[web_search]
+backend = "tavily"
+api_key = "${NIKA_TEST_SEARCH_KEY}"
+per_minute = 0
+"#,
+        )
+        .unwrap();
+        let search = &config.web_search;
+        assert_eq!(search.backend, Some(SearchBackendKind::Tavily));
+        assert_eq!(
+            search.resolved_api_key().unwrap().as_deref(),
+            Some("tvly-123")
+        );
+        assert_eq!((search.max_results, search.per_minute), (5, 0));
+
+        assert!(toml::from_str::<NikaConfig>("[web_search]\nbackend = \"bing\"\n").is_err());
+    }
+
+    #[test]
+    fn test_key_rotation_section() {
+        std::env::set_var("NIKA_TEST_ROTATION_KEY", "sk-ant-2");
+        let config: NikaConfig = toml::from_str(
+            r#"
+[key_rotation]
+strategy = "least_limited"
+
+[key_rotation.keys]
+claude = ["sk-ant-1", "${NIKA_TEST_ROTATION_KEY}"]
+"#,
+        )
+        .unwrap();
+        let rotation = &config.key_rotation;
+        assert_eq!(rotation.strategy, RotationStrategy::LeastLimited);
+        assert_eq!(
+            (rotation.quarantine_secs, rotation.quarantine_limited),
+            (300, 3)
+        );
+        assert_eq!(
+            rotation.resolved_keys().unwrap()["claude"],
+            vec!["sk-ant-1", "sk-ant-2"]
+        );
+
+        let unset: KeyRotationConfig =
+            toml::from_str("[keys]\nopenai = [\"${NIKA_TEST_UNSET_KEY}\"]\n").unwrap();
+        assert_eq!(
+            unset.resolved_keys().unwrap_err(),
+            "openai: ${NIKA_TEST_UNSET_KEY} is not set"
+        );
+    }
+
+    #[test]
+    fn test_size_limits_section() {
+        let config: NikaConfig = toml::from_str(
+            r#"
+[size_limits]
+max_prompt_chars = 400000
+max_response_tokens = 8192
+
+[size_limits.providers.groq]
+max_prompt_chars = 100000
+"#,
+        )
+        .unwrap();
+        let limits = &config.size_limits;
+        assert_eq!(
+            limits.for_provider("groq"),
+            SizeLimit {
+                max_prompt_chars: Some(100000),
+                max_response_tokens: Some(8192),
+            }
+        );
+        assert_eq!(limits.for_provider("claude").max_prompt_chars, Some(400000));
+        assert_eq!(
+            NikaConfig::default().size_limits.for_provider("claude"),
+            SizeLimit::default()
+        );
+    }
+
+    #[test]
+    fn test_memory_section() {
+        let config: NikaConfig = toml::from_str(
+            r#"
+[memory]
+backend = "sqlite"
+namespace = "triage"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.memory.backend, MemoryBackendKind::Sqlite);
+        assert_eq!(config.memory.path(), PathBuf::from(".nika/memory.db"));
+        assert_eq!(config.memory.namespace(), "triage");
+
+        let default = MemoryConfig::default();
+        assert_eq!(default.path(), PathBuf::from(".nika/memory.json"));
+        assert!(!default.namespace().is_empty());
+    }
+
+    #[test]
+    fn test_auth_section() {
+        std::env::set_var("NIKA_TEST_AUTH_TOKEN", "s3cret");
+        let config: NikaConfig = toml::from_str(
+            r#"
+[auth.github]
+type = "bearer"
+token = "${NIKA_TEST_AUTH_TOKEN}"
+
+[auth.legacy]
+type = "basic"
+username = "svc"
+password = "pw"
+
+[auth.partner]
+type = "headers"
+headers = { "X-Api-Key" = "k-${NIKA_TEST_AUTH_TOKEN}" }
+"#,
+        )
+        .unwrap();
+        let headers = |name: &str| config.auth[name].headers().unwrap();
+        assert_eq!(
+            headers("github"),
+            [("Authorization".to_string(), "Bearer s3cret".to_string())]
+        );
+        assert_eq!(headers("legacy")[0].1, "Basic c3ZjOnB3");
+        assert_eq!(
+            headers("partner"),
+            [("X-Api-Key".to_string(), "k-s3cret".to_string())]
+        );
+
+        let unset = AuthProfile::Bearer {
+            token: "${NIKA_TEST_AUTH_UNSET}".to_string(),
+        };
+        assert!(unset
+            .headers()
+            .unwrap_err()
+            .contains("NIKA_TEST_AUTH_UNSET"));
+        std::env::remove_var("NIKA_TEST_AUTH_TOKEN");
+    }
+
     #[test]
     fn test_load_nonexistent_file_returns_default() {
         // This test uses the actual config path, so we save/restore if it exists
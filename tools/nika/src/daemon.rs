@@ -0,0 +1,991 @@
+//! Keep-alive daemon for instant runs (v0.7, `nika daemon`)
+//!
+//! `nika daemon start` keeps MCP servers connected and provider sessions
+//! warm in one long-lived process ([`WarmResources`]). `nika run` looks for
+//! its socket at `.nika/daemon.sock` in the current directory and, when a
+//! daemon answers, submits the workflow there instead of starting up from
+//! scratch. Without a daemon (or with `--no-daemon`) runs are local as before.
+//!
+//! ## Protocol
+//!
+//! Newline-delimited JSON over the unix socket, one [`Request`] per
+//! connection. A `run` is answered with task progress messages followed by
+//! `done` or `error`; `status` and `stop` get a single reply.
+//!
+//! Runs use the daemon's working directory, environment and credentials,
+//! and never prompt: workflows with `approve:` tasks run locally.
+//!
+//! ## Watch triggers
+//!
+//! With `--watch`, the daemon also runs workflows that declare
+//! `triggers: { watch: ... }` whenever a matching file appears or changes
+//! (see [`crate::runtime::trigger`]), on the same warm resources. These runs
+//! are unattended: `approve:` tasks use their `default:`.
+//!
+//! ## Run events over HTTP
+//!
+//! With `--http`, the daemon also serves the events of its runs, redacted,
+//! as Server-Sent Events, for `nika watch <run-id> --server <url>`
+//! ([`crate::tail`]):
+//!
+//! - `GET /runs` lists the runs in progress
+//! - `GET /runs/<run-id>/events` sends every event so far, then new ones
+//!   until the run ends (`event: end`). A reconnecting client's
+//!   `Last-Event-ID` skips what it already has; runs that have finished are
+//!   served from their trace.
+//! - `GET /metrics` exposes counters and histograms of the runs finished
+//!   since start, and the active runs and MCP connections, for Prometheus
+//!   ([`RunMetrics`]).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio_util::sync::CancellationToken;
+
+use crate::ast::schema_validator::WorkflowSchemaValidator;
+use crate::ast::Workflow;
+use crate::config::{
+    ConcurrencyConfig, MemoryConfig, RouterConfig, SizeLimitsConfig, StoreConfig, WebSearchConfig,
+};
+use crate::error::{NikaError, Result};
+use crate::event::metrics::{Gauges, RunMetrics};
+use crate::event::redact::Redactor;
+use crate::event::{read_trace_events, trace_path, Event, EventKind, EventLog};
+use crate::provider::KeyPool;
+use crate::relay::{read_request, write_reply, Reply};
+#[cfg(feature = "watch")]
+use crate::runtime::trigger::{TriggerEvent, TriggerWatcher, WatchedWorkflow};
+use crate::runtime::{ApprovalGate, Runner, WarmResources};
+use crate::store::{HttpCache, StateStore, VectorStore};
+use crate::tools::WebSearchTool;
+
+/// Socket location, relative to the project directory
+pub const SOCKET_PATH: &str = ".nika/daemon.sock";
+/// How often event streams look for new events of a live run
+const STREAM_POLL: Duration = Duration::from_millis(100);
+/// Comment line sent on idle event streams
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// A workflow submitted by `nika run`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRequest {
+    /// Workflow YAML with `--set` overrides already applied
+    pub yaml: String,
+    /// `--provider` override
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// `--model` override
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Record `PhaseCompleted` events (`--phases`)
+    #[serde(default)]
+    pub phases: bool,
+}
+
+/// Client → daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    Run(RunRequest),
+    Status,
+    Stop,
+}
+
+/// Daemon → client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    TaskCompleted {
+        task_id: String,
+        duration_ms: u64,
+    },
+    TaskFailed {
+        task_id: String,
+        error: String,
+    },
+    TaskSkipped {
+        task_id: String,
+        reason: String,
+    },
+    /// Run finished; `output` is the workflow's final output
+    Done {
+        output: String,
+        generation_id: String,
+    },
+    /// Request failed (parse, validation or run error)
+    Error {
+        message: String,
+    },
+    Status(DaemonStatus),
+    Stopping,
+    /// Run accepted; its events are served as `/runs/<generation_id>/events`
+    Started {
+        generation_id: String,
+    },
+}
+
+impl Response {
+    /// Progress message for a runtime event, if it is one the client shows
+    fn from_event(event: &Event) -> Option<Self> {
+        match &event.kind {
+            EventKind::TaskCompleted {
+                task_id,
+                duration_ms,
+                ..
+            } => Some(Self::TaskCompleted {
+                task_id: task_id.to_string(),
+                duration_ms: *duration_ms,
+            }),
+            EventKind::TaskFailed { task_id, error, .. } => Some(Self::TaskFailed {
+                task_id: task_id.to_string(),
+                error: error.clone(),
+            }),
+            EventKind::TaskSkipped { task_id, reason } => Some(Self::TaskSkipped {
+                task_id: task_id.to_string(),
+                reason: reason.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// What `nika daemon status` reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub uptime_secs: u64,
+    /// Runs accepted since start
+    pub runs: u64,
+    /// Runs in progress
+    pub active_runs: usize,
+    pub warm_sessions: usize,
+    pub session_hits: u64,
+    pub session_misses: u64,
+    /// Connected MCP servers
+    pub mcp_servers: Vec<String>,
+    /// Workflows run by watch triggers (`--watch`)
+    #[serde(default)]
+    pub watched_workflows: Vec<String>,
+    /// Runs fired by watch triggers (included in `runs`)
+    #[serde(default)]
+    pub triggered_runs: u64,
+}
+
+/// A run fired by a watch trigger, for `nika daemon start --watch` output
+#[derive(Debug, Clone)]
+pub enum TriggerReport {
+    Started {
+        workflow: String,
+        file: PathBuf,
+    },
+    Finished {
+        workflow: String,
+        file: PathBuf,
+        generation_id: String,
+        /// Final output, or the run error
+        result: std::result::Result<String, String>,
+    },
+}
+
+/// The daemon process state
+pub struct Daemon {
+    warm: WarmResources,
+    started: Instant,
+    runs: AtomicU64,
+    active_runs: AtomicUsize,
+    watched: Mutex<Vec<String>>,
+    triggered_runs: AtomicU64,
+    shutdown: CancellationToken,
+    /// Backend for task results of every run (v0.7)
+    store: StoreConfig,
+    /// Project state shared by every run (v0.7)
+    state: Arc<StateStore>,
+    /// `embed:`/`recall:` collections shared by every run (v0.7)
+    vectors: Arc<VectorStore>,
+    /// `model: auto` rules of every run (v0.7)
+    router: RouterConfig,
+    /// Provider in-flight limits of every run (v0.7)
+    concurrency: ConcurrencyConfig,
+    /// `web_search` tool of every run, one rate limit for all (v0.7)
+    web_search: Arc<WebSearchTool>,
+    /// `[key_rotation]` keys of every run, quarantines shared (v0.7)
+    keys: Arc<KeyPool>,
+    /// Prompt and response caps of every run (v0.7)
+    size_limits: SizeLimitsConfig,
+    /// Agent memory store of every run (v0.7)
+    memory: MemoryConfig,
+    /// Event logs of the runs in progress, by generation id (v0.7)
+    live: Mutex<BTreeMap<String, EventLog>>,
+    /// Prometheus metrics of finished runs (v0.7)
+    metrics: Mutex<RunMetrics>,
+}
+
+impl Daemon {
+    /// Create a daemon keeping up to `session_pool_size` warm provider sessions
+    pub fn new(session_pool_size: usize) -> Self {
+        Self {
+            warm: WarmResources::new(session_pool_size),
+            started: Instant::now(),
+            runs: AtomicU64::new(0),
+            active_runs: AtomicUsize::new(0),
+            watched: Mutex::new(Vec::new()),
+            triggered_runs: AtomicU64::new(0),
+            shutdown: CancellationToken::new(),
+            store: StoreConfig::default(),
+            state: Arc::new(StateStore::project()),
+            vectors: Arc::new(VectorStore::project()),
+            router: RouterConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            web_search: Arc::new(WebSearchTool::new(&WebSearchConfig::default())),
+            keys: Arc::new(KeyPool::default()),
+            size_limits: SizeLimitsConfig::default(),
+            memory: MemoryConfig::default(),
+            live: Mutex::new(BTreeMap::new()),
+            metrics: Mutex::new(RunMetrics::new()),
+        }
+    }
+
+    /// Keep task results of daemon runs in a configured backend (v0.7)
+    pub fn with_store(mut self, store: StoreConfig) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Route `model: auto` in daemon runs with configured rules (v0.7)
+    pub fn with_router(mut self, router: RouterConfig) -> Self {
+        self.router = router;
+        self
+    }
+
+    /// Bound provider calls in daemon runs with configured limits (v0.7)
+    pub fn with_concurrency(mut self, concurrency: ConcurrencyConfig) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Search for `web_search: true` agents in daemon runs (v0.7)
+    pub fn with_web_search(mut self, config: WebSearchConfig) -> Self {
+        self.web_search = Arc::new(WebSearchTool::new(&config));
+        self
+    }
+
+    /// Rotate provider API keys in daemon runs (v0.7)
+    pub fn with_key_pool(mut self, keys: Arc<KeyPool>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Cap prompt and response sizes in daemon runs (v0.7)
+    pub fn with_size_limits(mut self, size_limits: SizeLimitsConfig) -> Self {
+        self.size_limits = size_limits;
+        self
+    }
+
+    /// Keep agent memories of daemon runs in the `[memory]` store (v0.7)
+    pub fn with_memory(mut self, memory: MemoryConfig) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    pub fn status(&self) -> DaemonStatus {
+        let stats = self.warm.session_stats();
+        DaemonStatus {
+            pid: std::process::id(),
+            uptime_secs: self.started.elapsed().as_secs(),
+            runs: self.runs.load(Ordering::Relaxed),
+            active_runs: self.active_runs.load(Ordering::Relaxed),
+            warm_sessions: self.warm.warm_sessions(),
+            session_hits: stats.hits,
+            session_misses: stats.misses,
+            mcp_servers: self.warm.connected_mcp_servers(),
+            watched_workflows: self.watched.lock().clone(),
+            triggered_runs: self.triggered_runs.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Accept connections on `listener` until a `stop` request
+    pub async fn serve(self: Arc<Self>, listener: UnixListener) -> Result<()> {
+        loop {
+            tokio::select! {
+                _ = self.shutdown.cancelled() => return Ok(()),
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let daemon = Arc::clone(&self);
+                    tokio::spawn(async move { daemon.handle(stream).await });
+                }
+            }
+        }
+    }
+
+    /// Run `workflows` on their watch triggers until a `stop` request
+    #[cfg(feature = "watch")]
+    pub async fn watch(
+        self: Arc<Self>,
+        workflows: Vec<WatchedWorkflow>,
+        report: impl Fn(TriggerReport) + Send + Sync + 'static,
+    ) -> Result<()> {
+        *self.watched.lock() = workflows.iter().map(|w| w.name.clone()).collect();
+        let report = Arc::new(report);
+        let watcher = TriggerWatcher::new(workflows);
+        watcher
+            .run(self.shutdown.clone(), |workflow, event| {
+                let daemon = Arc::clone(&self);
+                let workflow = workflow.clone();
+                let report = Arc::clone(&report);
+                tokio::spawn(async move {
+                    report(TriggerReport::Started {
+                        workflow: workflow.name.clone(),
+                        file: event.path.clone(),
+                    });
+                    let (generation_id, result) = daemon.run_triggered(&workflow, &event).await;
+                    report(TriggerReport::Finished {
+                        workflow: workflow.name,
+                        file: event.path,
+                        generation_id,
+                        result: result.map_err(|e| e.to_string()),
+                    });
+                });
+            })
+            .await
+    }
+
+    /// Execute a watch-triggered workflow with `event` bound as `trigger`
+    #[cfg(feature = "watch")]
+    async fn run_triggered(
+        &self,
+        workflow: &WatchedWorkflow,
+        event: &TriggerEvent,
+    ) -> (String, Result<String>) {
+        let parsed = tokio::fs::read_to_string(&workflow.path)
+            .await
+            .map_err(NikaError::from)
+            .and_then(|yaml| {
+                prepare(&RunRequest {
+                    yaml,
+                    provider: None,
+                    model: None,
+                    phases: false,
+                })
+            });
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => return (String::new(), Err(e)),
+        };
+        let runner = match Runner::new(parsed).with_store(&self.store) {
+            Ok(runner) => runner,
+            Err(e) => return (String::new(), Err(e)),
+        };
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        self.triggered_runs.fetch_add(1, Ordering::Relaxed);
+        self.active_runs.fetch_add(1, Ordering::Relaxed);
+
+        let runner = runner
+            .quiet()
+            .with_warm_resources(&self.warm)
+            .with_router(&self.router)
+            .with_concurrency(&self.concurrency)
+            .with_web_search_tool(Arc::clone(&self.web_search))
+            .with_key_pool(Arc::clone(&self.keys))
+            .with_size_limits(&self.size_limits)
+            .with_memory(&self.memory)
+            .with_state_store(Arc::clone(&self.state))
+            .with_vector_store(Arc::clone(&self.vectors))
+            .with_http_cache(Arc::new(HttpCache::project()))
+            .with_approval_gate(ApprovalGate::non_interactive())
+            .with_trigger(event.binding());
+        runner.preconnect();
+        let generation_id = runner.generation_id().to_string();
+        self.live
+            .lock()
+            .insert(generation_id.clone(), runner.event_log().clone());
+        let result = tokio::select! {
+            result = runner.run() => result,
+            // Dropping the run aborts its tasks
+            _ = self.shutdown.cancelled() => {
+                Err(NikaError::DaemonError {
+                    reason: "daemon stopped during the run".to_string(),
+                })
+            }
+        };
+        self.finish(&generation_id);
+        (generation_id, result)
+    }
+
+    async fn handle(&self, stream: UnixStream) {
+        let (reader, mut writer) = stream.into_split();
+        let mut line = String::new();
+        if BufReader::new(reader).read_line(&mut line).await.is_err() {
+            return;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::Run(request)) => self.run(request, &mut writer).await,
+            Ok(Request::Status) => Response::Status(self.status()),
+            Ok(Request::Stop) => {
+                self.shutdown.cancel();
+                Response::Stopping
+            }
+            Err(e) => Response::Error {
+                message: format!("Invalid request: {}", e),
+            },
+        };
+        let _ = send(&mut writer, &response).await;
+    }
+
+    /// Execute a submitted workflow, streaming task progress to `writer`
+    async fn run(&self, request: RunRequest, writer: &mut (impl AsyncWrite + Unpin)) -> Response {
+        let workflow = match prepare(&request) {
+            Ok(workflow) => workflow,
+            Err(e) => {
+                return Response::Error {
+                    message: e.to_string(),
+                }
+            }
+        };
+        let (event_log, mut events) = EventLog::new_with_broadcast();
+        let runner = match Runner::with_event_log(workflow, event_log).with_store(&self.store) {
+            Ok(runner) => runner,
+            Err(e) => {
+                return Response::Error {
+                    message: e.to_string(),
+                }
+            }
+        };
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        self.active_runs.fetch_add(1, Ordering::Relaxed);
+
+        let runner = runner
+            .quiet()
+            .with_warm_resources(&self.warm)
+            .with_router(&self.router)
+            .with_concurrency(&self.concurrency)
+            .with_web_search_tool(Arc::clone(&self.web_search))
+            .with_key_pool(Arc::clone(&self.keys))
+            .with_size_limits(&self.size_limits)
+            .with_memory(&self.memory)
+            .with_state_store(Arc::clone(&self.state))
+            .with_vector_store(Arc::clone(&self.vectors))
+            .with_http_cache(Arc::new(HttpCache::project()))
+            .with_phase_events(request.phases)
+            .with_approval_gate(ApprovalGate::non_interactive());
+        runner.preconnect();
+        let generation_id = runner.generation_id().to_string();
+        self.live
+            .lock()
+            .insert(generation_id.clone(), runner.event_log().clone());
+        let started = Response::Started {
+            generation_id: generation_id.clone(),
+        };
+        if send(writer, &started).await.is_err() {
+            runner.cancel_token().cancel();
+        }
+
+        let run = runner.run();
+        tokio::pin!(run);
+        let result = loop {
+            tokio::select! {
+                result = &mut run => break result,
+                Ok(event) = events.recv() => {
+                    if let Some(progress) = Response::from_event(&event) {
+                        // Client went away (Ctrl-C): stop the run
+                        if send(writer, &progress).await.is_err() {
+                            runner.cancel_token().cancel();
+                        }
+                    }
+                }
+            }
+        };
+        while let Ok(event) = events.try_recv() {
+            if let Some(progress) = Response::from_event(&event) {
+                let _ = send(writer, &progress).await;
+            }
+        }
+        self.finish(&generation_id);
+
+        match result {
+            Ok(output) => Response::Done {
+                output,
+                generation_id,
+            },
+            Err(e) => Response::Error {
+                message: e.to_string(),
+            },
+        }
+    }
+
+    /// Record a run that ended in the metrics and stop serving it live
+    fn finish(&self, generation_id: &str) {
+        if let Some(event_log) = self.live.lock().remove(generation_id) {
+            self.metrics.lock().record_run(&event_log.events());
+        }
+        self.active_runs.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Prometheus text exposition of the daemon's metrics (v0.7)
+    pub fn metrics(&self) -> String {
+        self.metrics.lock().render(Gauges {
+            active_runs: self.active_runs.load(Ordering::Relaxed),
+            mcp_connections: self.warm.connected_mcp_servers().len(),
+        })
+    }
+
+    /// Serve run events and metrics on `listener` until a `stop` request (v0.7)
+    ///
+    /// Every string in the events goes through `redactor` first.
+    pub async fn serve_http(
+        self: Arc<Self>,
+        listener: TcpListener,
+        redactor: Redactor,
+    ) -> Result<()> {
+        let redactor = Arc::new(redactor);
+        loop {
+            tokio::select! {
+                _ = self.shutdown.cancelled() => return Ok(()),
+                accepted = listener.accept() => {
+                    let (stream, _) = accepted?;
+                    let daemon = Arc::clone(&self);
+                    let redactor = Arc::clone(&redactor);
+                    tokio::spawn(async move {
+                        if let Err(e) = daemon.handle_http(stream, &redactor).await {
+                            tracing::debug!(error = %e, "Event stream closed");
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    async fn handle_http(&self, stream: TcpStream, redactor: &Redactor) -> std::io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        let request = match read_request(&mut reader).await? {
+            Ok(request) => request,
+            Err(reply) => return write_reply(&mut writer, reply).await,
+        };
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["runs"]) => {
+                let runs: Vec<String> = self.live.lock().keys().cloned().collect();
+                let reply = Reply::json(200, serde_json::json!({ "runs": runs }));
+                write_reply(&mut writer, reply).await
+            }
+            ("GET", ["metrics"]) => {
+                let reply = Reply::text(200, "text/plain; version=0.0.4", self.metrics());
+                write_reply(&mut writer, reply).await
+            }
+            ("GET", ["runs", id, "events"]) => {
+                // Events the client already has are skipped
+                let from = request.last_event_id.map_or(0, |id| id + 1);
+                self.stream_events(id, from, redactor, &mut writer).await
+            }
+            _ => write_reply(&mut writer, Reply::error(404, "not found")).await,
+        }
+    }
+
+    /// Send the events of run `id` from event id `from` as Server-Sent Events
+    async fn stream_events(
+        &self,
+        id: &str,
+        from: u64,
+        redactor: &Redactor,
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> std::io::Result<()> {
+        let live = self.live.lock().get(id).cloned();
+        // Finished (or from before a restart): replay the trace
+        let recorded = match &live {
+            Some(_) => Vec::new(),
+            None => match trace_path(id).map(|path| read_trace_events(&path)) {
+                Some(Ok(events)) => events,
+                _ => return write_reply(writer, Reply::error(404, "no such run")).await,
+            },
+        };
+        writer
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                  Cache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            )
+            .await?;
+
+        let Some(event_log) = live else {
+            let backlog: Vec<&Event> = recorded.iter().filter(|e| e.id >= from).collect();
+            write_sse(writer, &backlog, redactor).await?;
+            return writer.write_all(b"event: end\ndata: {}\n\n").await;
+        };
+        let mut sent = from as usize;
+        let mut idle = Duration::ZERO;
+        loop {
+            // The log is complete once the run is no longer live
+            let ended = !self.live.lock().contains_key(id);
+            let batch: Vec<Event> =
+                event_log.with_events(|events| events.get(sent..).unwrap_or_default().to_vec());
+            sent += batch.len();
+            if batch.is_empty() {
+                idle += STREAM_POLL;
+            } else {
+                idle = Duration::ZERO;
+                write_sse(writer, &batch.iter().collect::<Vec<_>>(), redactor).await?;
+            }
+            if ended {
+                return writer.write_all(b"event: end\ndata: {}\n\n").await;
+            }
+            if idle >= KEEPALIVE {
+                idle = Duration::ZERO;
+                writer.write_all(b": keep-alive\n\n").await?;
+                writer.flush().await?;
+            }
+            tokio::time::sleep(STREAM_POLL).await;
+        }
+    }
+}
+
+/// Write `events` as Server-Sent Events, each with its event id
+async fn write_sse(
+    writer: &mut (impl AsyncWrite + Unpin),
+    events: &[&Event],
+    redactor: &Redactor,
+) -> std::io::Result<()> {
+    let mut chunk = String::new();
+    for event in events {
+        let data = redactor.redact_event(event);
+        chunk.push_str(&format!("id: {}\ndata: {}\n\n", event.id, data));
+    }
+    if !chunk.is_empty() {
+        writer.write_all(chunk.as_bytes()).await?;
+        writer.flush().await?;
+    }
+    Ok(())
+}
+
+/// Parse and validate a submitted workflow, applying CLI overrides
+fn prepare(request: &RunRequest) -> Result<Workflow> {
+    WorkflowSchemaValidator::new()?.validate_yaml(&request.yaml)?;
+    let mut workflow: Workflow = serde_yaml::from_str(&request.yaml)?;
+    workflow.validate_schema()?;
+    if let Some(provider) = &request.provider {
+        workflow.provider = provider.clone();
+    }
+    if let Some(model) = &request.model {
+        workflow.model = Some(model.clone());
+    }
+    Ok(workflow)
+}
+
+async fn send(writer: &mut (impl AsyncWrite + Unpin), response: &Response) -> Result<()> {
+    let mut line = serde_json::to_string(response)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Bind the daemon socket, replacing a stale one left by a crashed daemon
+pub async fn bind(path: &Path) -> Result<UnixListener> {
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(NikaError::DaemonError {
+                reason: format!("a daemon is already listening on {}", path.display()),
+            });
+        }
+        std::fs::remove_file(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(UnixListener::bind(path)?)
+}
+
+/// Connection to a running daemon
+pub struct DaemonClient {
+    stream: UnixStream,
+}
+
+impl DaemonClient {
+    /// Default socket path in the current directory
+    pub fn socket_path() -> PathBuf {
+        PathBuf::from(SOCKET_PATH)
+    }
+
+    /// Connect if a daemon is listening (`None` when absent or stale)
+    pub async fn connect(path: &Path) -> Option<Self> {
+        UnixStream::connect(path)
+            .await
+            .ok()
+            .map(|stream| Self { stream })
+    }
+
+    pub async fn status(self) -> Result<DaemonStatus> {
+        match self.request(&Request::Status, |_| {}).await? {
+            Response::Status(status) => Ok(status),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Ask the daemon to exit
+    pub async fn stop(self) -> Result<()> {
+        match self.request(&Request::Stop, |_| {}).await? {
+            Response::Stopping => Ok(()),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Submit a run; `on_progress` sees the run id, then each task message
+    /// as it arrives
+    ///
+    /// Returns the final output and the generation id of the trace.
+    pub async fn run(
+        self,
+        request: RunRequest,
+        on_progress: impl FnMut(&Response),
+    ) -> Result<(String, String)> {
+        match self.request(&Request::Run(request), on_progress).await? {
+            Response::Done {
+                output,
+                generation_id,
+            } => Ok((output, generation_id)),
+            Response::Error { message } => Err(NikaError::DaemonRunFailed { message }),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Send `request` and read messages up to the final one
+    async fn request(
+        self,
+        request: &Request,
+        mut on_progress: impl FnMut(&Response),
+    ) -> Result<Response> {
+        let (reader, mut writer) = self.stream.into_split();
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        writer.write_all(line.as_bytes()).await?;
+
+        let mut lines = BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let response: Response = serde_json::from_str(&line)?;
+            match response {
+                Response::Started { .. }
+                | Response::TaskCompleted { .. }
+                | Response::TaskFailed { .. }
+                | Response::TaskSkipped { .. } => on_progress(&response),
+                Response::Error { message } if !matches!(request, Request::Run(_)) => {
+                    return Err(NikaError::DaemonError { reason: message })
+                }
+                final_response => return Ok(final_response),
+            }
+        }
+        Err(NikaError::DaemonError {
+            reason: "daemon closed the connection".to_string(),
+        })
+    }
+}
+
+fn unexpected(response: Response) -> NikaError {
+    NikaError::DaemonError {
+        reason: format!("unexpected reply: {:?}", response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn start(dir: &Path) -> PathBuf {
+        let path = dir.join("daemon.sock");
+        let listener = bind(&path).await.unwrap();
+        tokio::spawn(Arc::new(Daemon::new(2)).serve(listener));
+        path
+    }
+
+    #[test]
+    fn test_request_wire_format() {
+        let json = serde_json::to_string(&Request::Status).unwrap();
+        assert_eq!(json, r#"{"op":"status"}"#);
+        let request: Request = serde_json::from_str(r#"{"op":"run","yaml":"tasks: []"}"#).unwrap();
+        assert!(matches!(
+            request,
+            Request::Run(RunRequest { phases: false, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_status_and_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = start(dir.path()).await;
+
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: greet
+    exec: "echo hello"
+"#;
+        let mut progress = Vec::new();
+        let client = DaemonClient::connect(&path).await.unwrap();
+        let (output, generation_id) = client
+            .run(
+                RunRequest {
+                    yaml: yaml.to_string(),
+                    provider: None,
+                    model: None,
+                    phases: false,
+                },
+                |response| progress.push(response.clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.trim(), "hello");
+        assert!(generation_id.starts_with("gen-"));
+        assert!(matches!(
+            progress.as_slice(),
+            [
+                Response::Started { generation_id: started },
+                Response::TaskCompleted { task_id, .. },
+            ] if task_id == "greet" && *started == generation_id
+        ));
+
+        let status = DaemonClient::connect(&path)
+            .await
+            .unwrap()
+            .status()
+            .await
+            .unwrap();
+        assert_eq!(status.runs, 1);
+        assert_eq!(status.active_runs, 0);
+
+        // A second daemon on the same socket is refused
+        assert!(matches!(
+            bind(&path).await,
+            Err(NikaError::DaemonError { .. })
+        ));
+
+        DaemonClient::connect(&path)
+            .await
+            .unwrap()
+            .stop()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_finished_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        let daemon = Arc::new(Daemon::new(2));
+        tokio::spawn(Arc::clone(&daemon).serve(bind(&path).await.unwrap()));
+
+        for command in ["echo ok", "exit 3"] {
+            let yaml = format!(
+                "schema: \"nika/workflow@0.5\"\nprovider: mock\ntasks:\n  - id: step\n    exec: \"{}\"\n",
+                command
+            );
+            let request = RunRequest {
+                yaml,
+                provider: None,
+                model: None,
+                phases: false,
+            };
+            let client = DaemonClient::connect(&path).await.unwrap();
+            let _ = client.run(request, |_| {}).await;
+        }
+
+        // A failed task with nothing after it doesn't fail the run
+        let metrics = daemon.metrics();
+        for line in [
+            "nika_runs_total{outcome=\"completed\"} 2",
+            "nika_task_failures_total 1",
+            "nika_task_duration_seconds_count 2",
+            "nika_active_runs 0",
+            "nika_mcp_connections 0",
+        ] {
+            assert!(
+                metrics.lines().any(|l| l == line),
+                "missing {line:?} in\n{metrics}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_workflow_reports_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = start(dir.path()).await;
+
+        let client = DaemonClient::connect(&path).await.unwrap();
+        let result = client
+            .run(
+                RunRequest {
+                    yaml: "tasks: [".to_string(),
+                    provider: None,
+                    model: None,
+                    phases: false,
+                },
+                |_| {},
+            )
+            .await;
+        assert!(matches!(result, Err(NikaError::DaemonRunFailed { .. })));
+    }
+
+    #[cfg(feature = "watch")]
+    #[tokio::test]
+    async fn test_watch_trigger_runs_with_file_binding() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("inbox.nika.yaml"),
+            r#"
+schema: "nika/workflow@0.5"
+workflow: inbox-summary
+provider: mock
+triggers:
+  watch: "./inbox/*.md"
+  debounce_ms: 100
+tasks:
+  - id: summarize
+    use:
+      file: trigger.path
+    exec: "cat {{use.file}}"
+"#,
+        )
+        .unwrap();
+        let workflows = crate::runtime::trigger::discover(&[dir.path().to_path_buf()]).workflows;
+
+        let daemon = Arc::new(Daemon::new(2));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(Arc::clone(&daemon).watch(workflows, move |report| {
+            let _ = tx.send(report);
+        }));
+        // Let the watcher register before writing
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        std::fs::write(dir.path().join("inbox/note.md"), "hello from the inbox").unwrap();
+        std::fs::write(dir.path().join("inbox/ignored.txt"), "not matched").unwrap();
+
+        let started = rx.recv().await.unwrap();
+        assert!(matches!(
+            started,
+            TriggerReport::Started { ref file, .. } if file.ends_with("inbox/note.md")
+        ));
+        let TriggerReport::Finished { result, .. } = rx.recv().await.unwrap() else {
+            panic!("expected a finished run");
+        };
+        assert_eq!(result.unwrap().trim(), "hello from the inbox");
+
+        let status = daemon.status();
+        assert_eq!(status.watched_workflows, vec!["inbox-summary"]);
+        assert_eq!(status.triggered_runs, 1);
+        assert_eq!(status.runs, 1);
+        daemon.shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_stale_socket_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("daemon.sock");
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        assert!(DaemonClient::connect(&path).await.is_none());
+        assert!(bind(&path).await.is_ok());
+    }
+}
@@ -0,0 +1,262 @@
+//! Flow conditions - `when:` on flow edges (v0.7)
+//!
+//! A condition is evaluated over the source task's output once it has
+//! succeeded. If it is false, the edge is not taken; a target whose
+//! incoming edges are all untaken is skipped.
+//!
+//! Grammar (deliberately small):
+//! - `{{from.path}} == 'value'` / `{{from.path}} != 'value'`
+//! - `{{from.path}}` alone: truthiness (null, false, 0, "", [] and {} are false)
+//! - operands: `{{from}}`, `{{from.path}}`, `'text'`, `"text"`, numbers,
+//!   `true`, `false`, `null`, or a bare word (compared as text)
+//!
+//! ```yaml
+//! flows:
+//!   - source: review
+//!     target: publish
+//!     when: "{{from.status}} == 'approved'"
+//! ```
+
+use serde_json::Value;
+
+use crate::util::jsonpath::{self, Segment};
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+}
+
+/// One side of a comparison
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    /// `{{from}}` or `{{from.path}}` (segments into the source output)
+    From(Vec<Segment>),
+    Literal(Value),
+}
+
+/// Parsed `when:` expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowCondition {
+    lhs: Operand,
+    rhs: Option<(Op, Operand)>,
+}
+
+impl FlowCondition {
+    /// Parse a `when:` expression (error message is the reason)
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        if expr.is_empty() {
+            return Err("empty condition".to_string());
+        }
+
+        let split = [("==", Op::Eq), ("!=", Op::Ne)]
+            .into_iter()
+            .find_map(|(token, op)| find_operator(expr, token).map(|i| (i, token.len(), op)));
+
+        let (lhs, rhs) = match split {
+            Some((i, len, op)) => {
+                let lhs = parse_operand(&expr[..i])?;
+                let rhs = parse_operand(&expr[i + len..])?;
+                (lhs, Some((op, rhs)))
+            }
+            None => (parse_operand(expr)?, None),
+        };
+
+        let references_from =
+            matches!(lhs, Operand::From(_)) || matches!(rhs, Some((_, Operand::From(_))));
+        if !references_from {
+            return Err("condition must reference {{from}} or {{from.path}}".to_string());
+        }
+
+        Ok(Self { lhs, rhs })
+    }
+
+    /// Evaluate over the source task's output
+    ///
+    /// Text outputs holding JSON are parsed first, so `{{from.status}}`
+    /// works for `infer:` tasks that answer with a JSON object.
+    pub fn evaluate(&self, output: &Value) -> bool {
+        let parsed;
+        let output = match output {
+            Value::String(s) => match serde_json::from_str::<Value>(s) {
+                Ok(v) if v.is_object() || v.is_array() => {
+                    parsed = v;
+                    &parsed
+                }
+                _ => output,
+            },
+            other => other,
+        };
+
+        let lhs = self.lhs.value(output);
+        match &self.rhs {
+            None => truthy(&lhs),
+            Some((op, rhs)) => {
+                let equal = loose_eq(&lhs, &rhs.value(output));
+                match op {
+                    Op::Eq => equal,
+                    Op::Ne => !equal,
+                }
+            }
+        }
+    }
+}
+
+impl Operand {
+    fn value(&self, output: &Value) -> Value {
+        match self {
+            Operand::From(segments) => jsonpath::apply(output, segments).unwrap_or(Value::Null),
+            Operand::Literal(v) => v.clone(),
+        }
+    }
+}
+
+/// Find an operator outside quotes and `{{ }}`
+fn find_operator(expr: &str, token: &str) -> Option<usize> {
+    let bytes = expr.as_bytes();
+    let mut quote: Option<u8> = None;
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => {}
+            None if b == b'\'' || b == b'"' => quote = Some(b),
+            None if expr[i..].starts_with("{{") => {
+                depth += 1;
+                i += 1;
+            }
+            None if expr[i..].starts_with("}}") => {
+                depth = depth.saturating_sub(1);
+                i += 1;
+            }
+            None if depth == 0 && expr[i..].starts_with(token) => return Some(i),
+            None => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn parse_operand(raw: &str) -> Result<Operand, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err("missing operand".to_string());
+    }
+
+    if let Some(inner) = raw.strip_prefix("{{").and_then(|r| r.strip_suffix("}}")) {
+        let inner = inner.trim();
+        let path = match inner.strip_prefix("from") {
+            Some("") => return Ok(Operand::From(Vec::new())),
+            Some(rest) => rest
+                .strip_prefix('.')
+                .ok_or_else(|| format!("unknown reference '{{{{{}}}}}'", inner))?,
+            None => {
+                return Err(format!(
+                    "unknown reference '{{{{{}}}}}' (only {{{{from.path}}}} is available)",
+                    inner
+                ))
+            }
+        };
+        let segments = jsonpath::parse(path).map_err(|e| e.to_string())?;
+        return Ok(Operand::From(segments));
+    }
+    if raw.contains("{{") || raw.contains("}}") {
+        return Err(format!("malformed reference '{}'", raw));
+    }
+
+    for q in ['\'', '"'] {
+        if let Some(text) = raw.strip_prefix(q).and_then(|r| r.strip_suffix(q)) {
+            return Ok(Operand::Literal(Value::String(text.to_string())));
+        }
+    }
+
+    Ok(Operand::Literal(
+        serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    ))
+}
+
+/// Equality that treats `"42"` and `42` (and `"true"`/`true`) as equal
+pub(crate) fn loose_eq(a: &Value, b: &Value) -> bool {
+    if a == b {
+        return true;
+    }
+    match (a, b) {
+        (Value::String(s), other) | (other, Value::String(s)) if !other.is_string() => {
+            serde_json::from_str::<Value>(s.trim()).is_ok_and(|v| &v == other)
+        }
+        _ => false,
+    }
+}
+
+/// Truthiness: null, false, 0, "", "false", [] and {} are false
+pub(crate) fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::String(s) => !s.is_empty() && s != "false",
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn eval(expr: &str, output: Value) -> bool {
+        FlowCondition::parse(expr).unwrap().evaluate(&output)
+    }
+
+    #[test]
+    fn test_equality_on_field() {
+        let out = json!({"status": "approved"});
+        assert!(eval("{{from.status}} == 'approved'", out.clone()));
+        assert!(!eval("{{from.status}} == 'rejected'", out.clone()));
+        assert!(eval("{{from.status}} != \"rejected\"", out));
+    }
+
+    #[test]
+    fn test_json_text_output_is_parsed() {
+        let out = json!("{\"status\": \"approved\", \"score\": 9}");
+        assert!(eval("{{from.status}} == approved", out.clone()));
+        assert!(eval("{{from.score}} == 9", out));
+    }
+
+    #[test]
+    fn test_whole_output_and_loose_numbers() {
+        assert!(eval("{{from}} == 'yes'", json!("yes")));
+        assert!(eval("{{from.count}} == 3", json!({"count": "3"})));
+        assert!(eval(
+            "{{ from.items[1] }} == 'b'",
+            json!({"items": ["a", "b"]})
+        ));
+    }
+
+    #[test]
+    fn test_truthiness() {
+        assert!(eval("{{from.ok}}", json!({"ok": true})));
+        assert!(!eval("{{from.ok}}", json!({"ok": false})));
+        assert!(!eval("{{from.missing}}", json!({})));
+        assert!(!eval("{{from.items}}", json!({"items": []})));
+    }
+
+    #[test]
+    fn test_operator_inside_quotes_is_literal() {
+        assert!(eval("{{from}} == 'a == b'", json!("a == b")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(FlowCondition::parse("").is_err());
+        assert!(FlowCondition::parse("'a' == 'a'").is_err());
+        assert!(FlowCondition::parse("{{use.x}} == 1").is_err());
+        assert!(FlowCondition::parse("{{from.status}} ==").is_err());
+        assert!(FlowCondition::parse("{{from.status == 1").is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Connection Policy - which verbs may feed which over use: (v0.7)
+//!
+//! A `use:` binding connects the source task's output to the task using it.
+//! The `[connections]` table of `.nika/config.toml` restricts those
+//! connections by verb pair, like the v4.x blocked connections:
+//!
+//! ```toml
+//! [connections]
+//! "agent -> agent" = "bridge"          # through an infer: task in between
+//! "fetch -> agent" = "bridge:validate" # through a validate: task
+//! "exec -> *" = "block"                # exec output never feeds a task
+//! "exec -> export" = "allow"           # ...except export:
+//! ```
+//!
+//! The most specific rule wins (`a -> b`, then `a -> *`, `* -> b`,
+//! `* -> *`); pairs without a rule are allowed. Checked by
+//! `validate_use_wiring`:
+//! - NIKA-084: the pair needs a bridge task (the error names one to insert)
+//! - NIKA-085: the pair is blocked
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::ast::TaskAction;
+use crate::config::{find_project_config, workflow_dir};
+use crate::error::NikaError;
+
+/// Verb of the bridge task when a rule doesn't name one
+const DEFAULT_BRIDGE: &str = "infer";
+
+/// What a connection rule allows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Connection {
+    /// The tasks may connect directly
+    Allow,
+    /// A task of this verb must sit between them
+    Bridge(&'static str),
+    /// The tasks may not connect
+    Block,
+}
+
+impl Connection {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "allow" => Some(Self::Allow),
+            "block" => Some(Self::Block),
+            "bridge" => Some(Self::Bridge(DEFAULT_BRIDGE)),
+            other => {
+                let verb = other.strip_prefix("bridge:")?.trim();
+                TaskAction::VERBS
+                    .iter()
+                    .find(|known| **known == verb)
+                    .map(|known| Self::Bridge(known))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Allow => f.write_str("allow"),
+            Self::Bridge(verb) => write!(f, "bridge:{}", verb),
+            Self::Block => f.write_str("block"),
+        }
+    }
+}
+
+/// Verb-pair rules for use: connections (`[connections]` in `.nika/config.toml`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConnectionPolicy {
+    /// (source verb, target verb) -> rule; `*` matches any verb
+    rules: BTreeMap<(String, String), Connection>,
+}
+
+#[derive(Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    connections: BTreeMap<String, String>,
+}
+
+impl ConnectionPolicy {
+    /// Parse the `[connections]` table of a project config
+    pub fn from_toml(content: &str) -> Result<Self, NikaError> {
+        let config: ProjectConfig =
+            toml::from_str(content).map_err(|e| NikaError::ConfigError {
+                reason: format!("Invalid [connections] config: {}", e),
+            })?;
+
+        let mut policy = Self::default();
+        for (pair, value) in config.connections {
+            let (from, to) = parse_pair(&pair).ok_or_else(|| NikaError::ConfigError {
+                reason: format!(
+                    "Invalid connection '{}': expected 'verb -> verb' with known verbs or '*'",
+                    pair
+                ),
+            })?;
+            let rule = Connection::parse(&value).ok_or_else(|| NikaError::ConfigError {
+                reason: format!(
+                    "Invalid rule '{}' for '{}': expected allow, block, bridge or bridge:<verb>",
+                    value, pair
+                ),
+            })?;
+            policy = policy.with_rule(from, to, rule);
+        }
+        Ok(policy)
+    }
+
+    /// Find `.nika/config.toml` in `start` or its ancestors
+    ///
+    /// Returns a policy allowing every connection when there is none.
+    pub fn discover(start: &Path) -> Result<Self, NikaError> {
+        match find_project_config(start) {
+            Some(path) => Self::from_toml(&fs::read_to_string(path)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Discover the policy for a workflow file, starting in its directory
+    ///
+    /// A bare filename (`wf.nika.yaml`) resolves from the current directory.
+    pub fn for_workflow_file(file: &Path) -> Result<Self, NikaError> {
+        Self::discover(&workflow_dir(file)?)
+    }
+
+    /// Add a rule (`*` matches any verb)
+    pub fn with_rule(mut self, from: &str, to: &str, rule: Connection) -> Self {
+        self.rules.insert((from.to_string(), to.to_string()), rule);
+        self
+    }
+
+    /// Rule for output of a `from` task feeding a `to` task
+    pub fn check(&self, from: &str, to: &str) -> &Connection {
+        [(from, to), (from, "*"), ("*", to), ("*", "*")]
+            .into_iter()
+            .find_map(|(a, b)| self.rules.get(&(a.to_string(), b.to_string())))
+            .unwrap_or(&Connection::Allow)
+    }
+}
+
+/// `"agent -> infer"` into its verbs
+fn parse_pair(pair: &str) -> Option<(&str, &str)> {
+    let (from, to) = pair.split_once("->")?;
+    let known = |verb: &str| verb == "*" || TaskAction::VERBS.contains(&verb);
+    let (from, to) = (from.trim(), to.trim());
+    (known(from) && known(to)).then_some((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_specific_rule_wins() {
+        let policy = ConnectionPolicy::from_toml(
+            r#"
+[connections]
+"exec -> *" = "block"
+"exec -> export" = "allow"
+"* -> agent" = "bridge:validate"
+"agent -> agent" = "bridge"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.check("exec", "infer"), &Connection::Block);
+        assert_eq!(policy.check("exec", "export"), &Connection::Allow);
+        // `exec -> *` is more specific than `* -> agent`
+        assert_eq!(policy.check("exec", "agent"), &Connection::Block);
+        assert_eq!(
+            policy.check("fetch", "agent"),
+            &Connection::Bridge("validate")
+        );
+        assert_eq!(policy.check("agent", "agent"), &Connection::Bridge("infer"));
+        assert_eq!(policy.check("fetch", "infer"), &Connection::Allow);
+    }
+
+    #[test]
+    fn default_policy_allows_everything() {
+        let policy = ConnectionPolicy::from_toml("[lint]\nunused_alias = \"off\"\n").unwrap();
+        assert_eq!(policy, ConnectionPolicy::default());
+        assert_eq!(policy.check("agent", "agent"), &Connection::Allow);
+    }
+
+    #[test]
+    fn bad_rules_are_config_errors() {
+        for config in [
+            "[connections]\n\"agnet -> infer\" = \"block\"\n",
+            "[connections]\n\"agent infer\" = \"block\"\n",
+            "[connections]\n\"agent -> infer\" = \"deny\"\n",
+            "[connections]\n\"agent -> infer\" = \"bridge:llm\"\n",
+        ] {
+            let err = ConnectionPolicy::from_toml(config).unwrap_err();
+            assert!(matches!(err, NikaError::ConfigError { .. }), "{}", config);
+        }
+    }
+
+    #[test]
+    fn bare_filename_resolves_from_current_dir() {
+        let bare = ConnectionPolicy::for_workflow_file(Path::new("wf.nika.yaml")).unwrap();
+        let cwd = ConnectionPolicy::discover(&std::env::current_dir().unwrap()).unwrap();
+        assert_eq!(bare, cwd);
+    }
+}
@@ -0,0 +1,185 @@
+//! DagDiff - compact summary of what changed between two workflow versions
+//!
+//! Compares tasks by ID (added, removed, changed definition) and flow edges.
+//! Works on raw YAML so it can run on drafts before they fully validate.
+//! Used by `nika watch` after each file change.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde_yaml::Value;
+
+use crate::error::NikaError;
+
+/// Differences between two workflow versions
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DagDiff {
+    pub added_tasks: Vec<String>,
+    pub removed_tasks: Vec<String>,
+    /// Tasks whose definition (verb, prompt, bindings, ...) changed
+    pub changed_tasks: Vec<String>,
+    pub added_edges: Vec<(String, String)>,
+    pub removed_edges: Vec<(String, String)>,
+}
+
+impl DagDiff {
+    /// Diff two workflow YAML documents
+    pub fn between(old_yaml: &str, new_yaml: &str) -> Result<Self, NikaError> {
+        let old: Value = serde_yaml::from_str(old_yaml)?;
+        let new: Value = serde_yaml::from_str(new_yaml)?;
+
+        let (old_tasks, new_tasks) = (tasks(&old), tasks(&new));
+        let (old_edges, new_edges) = (edges(&old), edges(&new));
+
+        Ok(Self {
+            added_tasks: new_tasks
+                .keys()
+                .filter(|id| !old_tasks.contains_key(*id))
+                .cloned()
+                .collect(),
+            removed_tasks: old_tasks
+                .keys()
+                .filter(|id| !new_tasks.contains_key(*id))
+                .cloned()
+                .collect(),
+            changed_tasks: new_tasks
+                .iter()
+                .filter(|(id, task)| old_tasks.get(*id).is_some_and(|old| old != *task))
+                .map(|(id, _)| id.clone())
+                .collect(),
+            added_edges: new_edges.difference(&old_edges).cloned().collect(),
+            removed_edges: old_edges.difference(&new_edges).cloned().collect(),
+        })
+    }
+
+    /// True when nothing in the DAG changed
+    pub fn is_empty(&self) -> bool {
+        self.added_tasks.is_empty()
+            && self.removed_tasks.is_empty()
+            && self.changed_tasks.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+impl fmt::Display for DagDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for id in &self.added_tasks {
+            writeln!(f, "+ task {}", id)?;
+        }
+        for id in &self.removed_tasks {
+            writeln!(f, "- task {}", id)?;
+        }
+        for id in &self.changed_tasks {
+            writeln!(f, "~ task {}", id)?;
+        }
+        for (source, target) in &self.added_edges {
+            writeln!(f, "+ edge {} → {}", source, target)?;
+        }
+        for (source, target) in &self.removed_edges {
+            writeln!(f, "- edge {} → {}", source, target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Task ID -> task definition
+fn tasks(doc: &Value) -> BTreeMap<String, &Value> {
+    doc.get("tasks")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .filter_map(|task| Some((task.get("id")?.as_str()?.to_string(), task)))
+        .collect()
+}
+
+/// All (source, target) pairs from `flows:`
+fn edges(doc: &Value) -> BTreeSet<(String, String)> {
+    fn endpoints(value: Option<&Value>) -> Vec<String> {
+        match value {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Sequence(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    let mut edges = BTreeSet::new();
+    for flow in doc
+        .get("flows")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+    {
+        let targets = endpoints(flow.get("target"));
+        for source in endpoints(flow.get("source")) {
+            for target in &targets {
+                edges.insert((source.clone(), target.clone()));
+            }
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: fetch
+    exec: "curl example.com"
+  - id: summarize
+    infer: "Summarize"
+  - id: old
+    exec: "echo old"
+flows:
+  - source: fetch
+    target: [summarize, old]
+"#;
+
+    #[test]
+    fn test_identical_is_empty() {
+        let diff = DagDiff::between(OLD, OLD).unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "");
+    }
+
+    #[test]
+    fn test_task_and_edge_changes() {
+        let new = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: fetch
+    exec: "curl example.com"
+  - id: summarize
+    infer: "Summarize in 3 bullets"
+  - id: publish
+    exec: "echo done"
+flows:
+  - source: fetch
+    target: summarize
+  - source: summarize
+    target: publish
+"#;
+        let diff = DagDiff::between(OLD, new).unwrap();
+        assert_eq!(diff.added_tasks, vec!["publish"]);
+        assert_eq!(diff.removed_tasks, vec!["old"]);
+        assert_eq!(diff.changed_tasks, vec!["summarize"]);
+        assert_eq!(
+            diff.added_edges,
+            vec![("summarize".to_string(), "publish".to_string())]
+        );
+        assert_eq!(
+            diff.removed_edges,
+            vec![("fetch".to_string(), "old".to_string())]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "+ task publish\n- task old\n~ task summarize\n+ edge summarize → publish\n- edge fetch → old\n"
+        );
+    }
+}
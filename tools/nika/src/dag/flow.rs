@@ -7,6 +7,9 @@
 //!
 //! DAG Validation:
 //! - Cycle detection using DFS three-color algorithm
+//!
+//! Conditional edges (v0.7): flows with `when:` keep their parsed
+//! [`FlowCondition`]; the runner evaluates it when the source succeeds.
 
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -14,6 +17,7 @@ use std::sync::Arc;
 use rustc_hash::{FxHashMap, FxHashSet};
 use smallvec::SmallVec;
 
+use super::condition::FlowCondition;
 use crate::ast::Workflow;
 use crate::error::NikaError;
 use crate::util::intern;
@@ -34,6 +38,8 @@ pub struct FlowGraph {
     /// Quick lookup for task existence (FxHashSet: faster hashing)
     #[allow(dead_code)] // Used in from_workflow for Arc<str> reuse
     task_set: FxHashSet<Arc<str>>,
+    /// source -> target -> `when:` condition (v0.7, conditional edges only)
+    conditions: FxHashMap<Arc<str>, FxHashMap<Arc<str>, FlowCondition>>,
 }
 
 impl FlowGraph {
@@ -46,6 +52,10 @@ impl FlowGraph {
         let mut task_ids: Vec<Arc<str>> = Vec::with_capacity(capacity);
         let mut task_set: FxHashSet<Arc<str>> =
             FxHashSet::with_capacity_and_hasher(capacity, Default::default());
+        let mut conditions: FxHashMap<Arc<str>, FxHashMap<Arc<str>, FlowCondition>> =
+            FxHashMap::default();
+        // Edges declared at least once without `when:` are unconditional
+        let mut unconditional: FxHashSet<(Arc<str>, Arc<str>)> = FxHashSet::default();
 
         // Intern task IDs once, reuse everywhere (single allocation per unique ID)
         for task in &workflow.tasks {
@@ -60,6 +70,11 @@ impl FlowGraph {
         for flow in &workflow.flows {
             let sources = flow.source.as_vec();
             let targets = flow.target.as_vec();
+            // Invalid conditions are reported by validate_use_wiring (NIKA-022)
+            let condition = flow
+                .when
+                .as_deref()
+                .and_then(|expr| FlowCondition::parse(expr).ok());
 
             for source in &sources {
                 for target in &targets {
@@ -73,6 +88,24 @@ impl FlowGraph {
                         .cloned()
                         .unwrap_or_else(|| intern(target));
 
+                    let edge = (Arc::clone(&src_arc), Arc::clone(&tgt_arc));
+                    match &condition {
+                        Some(c) if !unconditional.contains(&edge) => {
+                            conditions
+                                .entry(edge.0)
+                                .or_default()
+                                .entry(edge.1)
+                                .or_insert_with(|| c.clone());
+                        }
+                        Some(_) => {}
+                        None => {
+                            if let Some(targets) = conditions.get_mut(&edge.0) {
+                                targets.remove(&edge.1);
+                            }
+                            unconditional.insert(edge);
+                        }
+                    }
+
                     adjacency
                         .entry(Arc::clone(&src_arc))
                         .or_default()
@@ -87,9 +120,22 @@ impl FlowGraph {
             predecessors,
             task_ids,
             task_set,
+            conditions,
         }
     }
 
+    /// Get the `when:` condition of an edge (None = unconditional, v0.7)
+    #[inline]
+    pub fn edge_condition(&self, source: &str, target: &str) -> Option<&FlowCondition> {
+        self.conditions.get(source)?.get(target)
+    }
+
+    /// Whether any flow carries a `when:` condition (v0.7)
+    #[inline]
+    pub fn has_conditions(&self) -> bool {
+        self.conditions.values().any(|targets| !targets.is_empty())
+    }
+
     /// Get dependencies of a task (returns Arc<str> slice)
     #[inline]
     pub fn get_dependencies(&self, task_id: &str) -> &[Arc<str>] {
@@ -428,4 +474,45 @@ flows:
         // Should contain cycle path
         assert!(err_msg.contains("→"));
     }
+
+    // ═══════════════════════════════════════════════════════════════
+    // CONDITIONAL EDGE TESTS (v0.7)
+    // ═══════════════════════════════════════════════════════════════
+
+    #[test]
+    fn test_edge_conditions() {
+        let yaml = r#"
+schema: nika/workflow@0.1
+tasks:
+  - id: review
+    exec: "echo"
+  - id: publish
+    exec: "echo"
+  - id: revise
+    exec: "echo"
+  - id: log
+    exec: "echo"
+flows:
+  - source: review
+    target: publish
+    when: "{{from.status}} == 'approved'"
+  - source: review
+    target: revise
+    when: "{{from.status}} != 'approved'"
+  - source: review
+    target: log
+    when: "{{from.ok}}"
+  - source: review
+    target: log
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let graph = FlowGraph::from_workflow(&workflow);
+
+        assert!(graph.has_conditions());
+        assert!(graph.edge_condition("review", "publish").is_some());
+        assert!(graph.edge_condition("review", "revise").is_some());
+        // Also declared without when: -> unconditional
+        assert!(graph.edge_condition("review", "log").is_none());
+        assert_eq!(graph.get_dependencies("publish").len(), 1);
+    }
 }
@@ -0,0 +1,580 @@
+//! Workflow Lints - semantic checks beyond validation (v0.7)
+//!
+//! A workflow that passes `nika check` can still be wasteful or fragile.
+//! `nika lint` reports those cases with a NIKA-16x code:
+//!
+//! | Code     | Rule                       | Default |
+//! |----------|----------------------------|---------|
+//! | NIKA-160 | `unreachable_task`         | warn    |
+//! | NIKA-161 | `unused_alias`             | warn    |
+//! | NIKA-162 | `unconsumed_output`        | info    |
+//! | NIKA-163 | `infer_without_max_tokens` | info    |
+//! | NIKA-164 | `exec_without_timeout`     | info    |
+//! | NIKA-165 | `agent_unbounded_turns`    | warn    |
+//! | NIKA-166 | `unknown_provider`         | warn    |
+//! | NIKA-167 | `provider_capability`      | error   |
+//!
+//! The provider rules check `infer:`, `agent:`, `embed:` and `transcribe:`
+//! tasks against the provider [`registry`], so a provider registered by an
+//! embedder counts as known.
+//!
+//! Severities are configured per rule (by name or code) in the `[lint]`
+//! table of `.nika/config.toml`:
+//!
+//! ```toml
+//! [lint]
+//! unconsumed_output = "off"
+//! "NIKA-163" = "error"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
+
+use crate::ast::{TaskAction, Workflow};
+use crate::binding::{extract_refs, TemplateEngine};
+use crate::config::{find_project_config, workflow_dir};
+use crate::error::NikaError;
+use crate::provider::registry::{self, Capability};
+
+use super::flow::FlowGraph;
+use super::validate::{collect_string_values, extract_templates_from_action};
+
+/// Lint severity (`off` disables the rule)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Off => "off",
+            Self::Info => "info",
+            Self::Warn => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// Lint rules: (code, name, default severity)
+pub const RULES: &[(&str, &str, Severity)] = &[
+    ("NIKA-160", "unreachable_task", Severity::Warn),
+    ("NIKA-161", "unused_alias", Severity::Warn),
+    ("NIKA-162", "unconsumed_output", Severity::Info),
+    ("NIKA-163", "infer_without_max_tokens", Severity::Info),
+    ("NIKA-164", "exec_without_timeout", Severity::Info),
+    ("NIKA-165", "agent_unbounded_turns", Severity::Warn),
+    ("NIKA-166", "unknown_provider", Severity::Warn),
+    ("NIKA-167", "provider_capability", Severity::Error),
+];
+
+/// A single lint finding
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Lint {
+    pub code: &'static str,
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub task_id: String,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {} ({})",
+            self.severity, self.code, self.task_id, self.message, self.rule
+        )
+    }
+}
+
+/// Per-rule severity overrides (`[lint]` in `.nika/config.toml`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintConfig {
+    /// Rule name -> severity
+    overrides: BTreeMap<&'static str, Severity>,
+}
+
+#[derive(Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    lint: BTreeMap<String, Severity>,
+}
+
+impl LintConfig {
+    /// Parse the `[lint]` table of a project config
+    pub fn from_toml(content: &str) -> Result<Self, NikaError> {
+        let config: ProjectConfig =
+            toml::from_str(content).map_err(|e| NikaError::ConfigError {
+                reason: format!("Invalid [lint] config: {}", e),
+            })?;
+
+        let mut overrides = BTreeMap::new();
+        for (key, severity) in config.lint {
+            let (_, rule, _) = RULES
+                .iter()
+                .find(|(code, name, _)| *code == key || *name == key)
+                .ok_or_else(|| NikaError::ConfigError {
+                    reason: format!("Unknown lint rule '{}'", key),
+                })?;
+            overrides.insert(*rule, severity);
+        }
+        Ok(Self { overrides })
+    }
+
+    /// Find `.nika/config.toml` in `start` or its ancestors
+    ///
+    /// Returns the default config when there is none.
+    pub fn discover(start: &Path) -> Result<Self, NikaError> {
+        match find_project_config(start) {
+            Some(path) => Self::from_toml(&fs::read_to_string(path)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Discover the config for a workflow file, starting in its directory
+    ///
+    /// A bare filename (`wf.nika.yaml`) resolves from the current directory.
+    pub fn for_workflow_file(file: &Path) -> Result<Self, NikaError> {
+        Self::discover(&workflow_dir(file)?)
+    }
+
+    /// Effective severity of a rule
+    pub fn severity(&self, rule: &str) -> Severity {
+        self.overrides.get(rule).copied().unwrap_or_else(|| {
+            RULES
+                .iter()
+                .find(|(_, name, _)| *name == rule)
+                .map_or(Severity::Off, |(_, _, default)| *default)
+        })
+    }
+}
+
+/// Run all enabled lints on a workflow
+///
+/// Findings are ordered by task position, then rule code.
+pub fn lint_workflow(workflow: &Workflow, config: &LintConfig) -> Vec<Lint> {
+    let graph = FlowGraph::from_workflow(workflow);
+    let has_flows = !workflow.flows.is_empty();
+
+    // Tasks whose output some other task binds with use:
+    let consumed: FxHashSet<&str> = workflow
+        .tasks
+        .iter()
+        .filter_map(|t| t.use_wiring.as_ref())
+        .flat_map(|w| w.values().map(|entry| entry.task_id()))
+        .collect();
+
+    let mut lints = Vec::new();
+    let mut report = |rule: &'static str, task_id: &str, message: String| {
+        let severity = config.severity(rule);
+        if severity == Severity::Off {
+            return;
+        }
+        let (code, _, _) = RULES.iter().find(|(_, name, _)| *name == rule).unwrap();
+        lints.push(Lint {
+            code,
+            rule,
+            severity,
+            task_id: task_id.to_string(),
+            message,
+        });
+    };
+
+    for task in &workflow.tasks {
+        let id = task.id.as_str();
+        let successors = graph.get_successors(id);
+
+        // NIKA-160: no flow edges at all in a workflow that uses flows
+        if has_flows
+            && workflow.tasks.len() > 1
+            && successors.is_empty()
+            && graph.get_dependencies(id).is_empty()
+        {
+            report(
+                "unreachable_task",
+                id,
+                "task is not connected to any flow".to_string(),
+            );
+        }
+
+        // NIKA-161: use: alias never referenced by a template
+        if let Some(wiring) = &task.use_wiring {
+            let used = used_aliases(task);
+            let mut unused: Vec<&String> = wiring
+                .keys()
+                .filter(|a| !used.contains(a.as_str()))
+                .collect();
+            unused.sort();
+            for alias in unused {
+                report(
+                    "unused_alias",
+                    id,
+                    format!("use alias '{}' is never referenced", alias),
+                );
+            }
+        }
+
+        // NIKA-162: has downstream tasks, but none of them reads the output
+        if !successors.is_empty() && !consumed.contains(id) {
+            report(
+                "unconsumed_output",
+                id,
+                "output is never bound by a downstream use: block".to_string(),
+            );
+        }
+
+        match &task.action {
+            TaskAction::Infer { infer } if infer.max_tokens.is_none() => report(
+                "infer_without_max_tokens",
+                id,
+                "infer has no max_tokens limit".to_string(),
+            ),
+            TaskAction::Exec { exec } if exec.timeout.is_none() => report(
+                "exec_without_timeout",
+                id,
+                "exec has no timeout (default 60s)".to_string(),
+            ),
+            TaskAction::Agent { agent } if agent.max_turns.is_none() => report(
+                "agent_unbounded_turns",
+                id,
+                format!(
+                    "agent has no max_turns (runs up to {} turns)",
+                    agent.effective_max_turns()
+                ),
+            ),
+            _ => {}
+        }
+
+        // NIKA-166/167: provider missing from the registry, or lacking a feature
+        if let Some((provider, needs)) = provider_needs(&task.action, &workflow.provider) {
+            match registry::info(provider) {
+                None => report(
+                    "unknown_provider",
+                    id,
+                    format!(
+                        "provider '{}' is not registered (registered: {})",
+                        provider,
+                        registry::names().join(", ")
+                    ),
+                ),
+                Some(info) => {
+                    for capability in needs {
+                        if !info.capabilities.supports(capability) {
+                            report(
+                                "provider_capability",
+                                id,
+                                format!("provider '{}' has no {} support", info.name, capability),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    lints
+}
+
+/// Provider a task calls (its own or the workflow's) and what it needs from it
+fn provider_needs<'a>(
+    action: &'a TaskAction,
+    default: &'a str,
+) -> Option<(&'a str, Vec<Capability>)> {
+    let with_images = |capability, images: &[String]| {
+        let mut needs = vec![capability];
+        if !images.is_empty() {
+            needs.push(Capability::Vision);
+        }
+        needs
+    };
+    let (provider, needs) = match action {
+        TaskAction::Infer { infer } => (
+            &infer.provider,
+            with_images(Capability::Infer, &infer.images),
+        ),
+        TaskAction::Agent { agent } => (
+            &agent.provider,
+            with_images(Capability::Agent, &agent.images),
+        ),
+        TaskAction::Embed { embed } => (&embed.provider, vec![Capability::Embed]),
+        TaskAction::Transcribe { transcribe } => {
+            (&transcribe.provider, vec![Capability::Transcribe])
+        }
+        _ => return None,
+    };
+    Some((provider.as_deref().unwrap_or(default), needs))
+}
+
+/// Aliases referenced by a task's templates, for_each and decompose source
+fn used_aliases(task: &crate::ast::Task) -> FxHashSet<String> {
+    let mut templates = extract_templates_from_action(&task.action);
+    if let Some(for_each) = &task.for_each {
+        collect_string_values(for_each, &mut templates);
+    }
+    if let Some(decompose) = &task.decompose {
+        templates.push(decompose.source.clone());
+    }
+
+    let mut used: FxHashSet<String> = templates
+        .iter()
+        .flat_map(|t| extract_refs(t))
+        .map(|(alias, _)| alias)
+        .collect();
+    // `$alias` shorthand (for_each / decompose source)
+    for template in &templates {
+        if let Some(alias) = template.trim().strip_prefix('$') {
+            used.insert(alias.split('.').next().unwrap_or(alias).to_string());
+        }
+    }
+    // jinja: `use.alias` anywhere in an expression or tag
+    if task.template_engine == TemplateEngine::Jinja {
+        for template in &templates {
+            used.extend(
+                paths(template)
+                    .filter_map(|path| path.strip_prefix("use."))
+                    .map(|rest| rest.split('.').next().unwrap_or(rest).to_string()),
+            );
+        }
+    }
+    // script: aliases are Rhai variables; a script file can't be read here
+    if let TaskAction::Script { script } = &task.action {
+        match &script.code {
+            Some(code) => used
+                .extend(paths(code).map(|path| path.split('.').next().unwrap_or(path).to_string())),
+            None => used.extend(task.use_wiring.iter().flat_map(|w| w.keys().cloned())),
+        }
+    }
+    used
+}
+
+/// Dotted identifier paths in source text (`use.posts`, `xs.len`)
+fn paths(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|word| !word.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(yaml: &str) -> Vec<Lint> {
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        lint_workflow(&workflow, &LintConfig::default())
+    }
+
+    fn codes(lints: &[Lint]) -> Vec<(&str, &str)> {
+        lints.iter().map(|l| (l.code, l.task_id.as_str())).collect()
+    }
+
+    #[test]
+    fn test_clean_workflow_has_no_lints() {
+        let lints = lint(
+            r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: fetch
+    exec:
+      command: "curl example.com"
+      timeout: 30
+  - id: summarize
+    use:
+      page: fetch
+    infer:
+      prompt: "Summarize {{use.page}}"
+      max_tokens: 500
+flows:
+  - source: fetch
+    target: summarize
+"#,
+        );
+        assert!(lints.is_empty(), "{:?}", lints);
+    }
+
+    #[test]
+    fn test_each_rule_fires() {
+        let lints = lint(
+            r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: fetch
+    exec: "curl example.com"
+  - id: summarize
+    use:
+      page: fetch
+      unused: fetch
+    infer: "Summarize {{use.page}}"
+  - id: research
+    agent:
+      prompt: "Research"
+  - id: publish
+    exec:
+      command: "echo done"
+      timeout: 5
+flows:
+  - source: fetch
+    target: summarize
+  - source: summarize
+    target: publish
+"#,
+        );
+        assert_eq!(
+            codes(&lints),
+            vec![
+                ("NIKA-164", "fetch"),
+                ("NIKA-161", "summarize"),
+                ("NIKA-162", "summarize"),
+                ("NIKA-163", "summarize"),
+                ("NIKA-160", "research"),
+                ("NIKA-165", "research"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_provider_rules_use_the_registry() {
+        let lints = lint(
+            r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: caption
+    infer:
+      prompt: "Describe"
+      max_tokens: 100
+      images: ["photo.png"]
+  - id: vectors
+    embed:
+      input: "hello"
+      provider: no-such-provider
+"#,
+        );
+        assert_eq!(
+            codes(&lints),
+            vec![("NIKA-167", "caption"), ("NIKA-166", "vectors")]
+        );
+        assert_eq!(lints[0].message, "provider 'mock' has no vision support");
+        assert_eq!(lints[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_for_each_alias_counts_as_used() {
+        let lints = lint(
+            r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: list
+    exec:
+      command: "echo '[1,2]'"
+      timeout: 5
+  - id: each
+    use:
+      items: list
+    for_each: "$items"
+    exec:
+      command: "echo {{use.item}}"
+      timeout: 5
+flows:
+  - source: list
+    target: each
+"#,
+        );
+        assert!(lints.is_empty(), "{:?}", lints);
+    }
+
+    #[test]
+    fn test_script_variables_count_as_used() {
+        let lints = lint(
+            r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: list
+    exec:
+      command: "echo '[1,2]'"
+      timeout: 5
+  - id: count
+    use:
+      xs: list
+      unused: list
+    script: "xs.len()"
+  - id: from_file
+    use:
+      xs: list
+    script:
+      file: count.rhai
+flows:
+  - source: list
+    target: [count, from_file]
+"#,
+        );
+        assert_eq!(codes(&lints), vec![("NIKA-161", "count")]);
+        assert_eq!(lints[0].message, "use alias 'unused' is never referenced");
+    }
+
+    #[test]
+    fn test_jinja_use_refs_count_as_used() {
+        let lints = lint(
+            r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: list
+    exec:
+      command: "echo '[1,2]'"
+      timeout: 5
+  - id: render
+    use:
+      posts: list
+      title: list
+      unused: list
+    template_engine: jinja
+    exec:
+      command: "{% for p in use.posts %}{{ p }}{% endfor %} {{ use.title | upper }}"
+      timeout: 5
+flows:
+  - source: list
+    target: render
+"#,
+        );
+        assert_eq!(codes(&lints), vec![("NIKA-161", "render")]);
+        assert_eq!(lints[0].message, "use alias 'unused' is never referenced");
+    }
+
+    #[test]
+    fn test_config_overrides_by_name_and_code() {
+        let config = LintConfig::from_toml(
+            r#"
+[tools]
+permission = "plan"
+
+[lint]
+exec_without_timeout = "off"
+"NIKA-165" = "error"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.severity("exec_without_timeout"), Severity::Off);
+        assert_eq!(config.severity("agent_unbounded_turns"), Severity::Error);
+        assert_eq!(config.severity("unused_alias"), Severity::Warn);
+
+        let err = LintConfig::from_toml("[lint]\nno_such_rule = \"warn\"").unwrap_err();
+        assert_eq!(err.code(), "NIKA-140");
+    }
+
+    #[test]
+    fn bare_filename_resolves_from_current_dir() {
+        let bare = LintConfig::for_workflow_file(Path::new("wf.nika.yaml")).unwrap();
+        let cwd = LintConfig::discover(&std::env::current_dir().unwrap()).unwrap();
+        assert_eq!(bare, cwd);
+    }
+}
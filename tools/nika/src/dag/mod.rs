@@ -2,14 +2,27 @@
 //!
 //! Contains the DAG representation and validation:
 //! - `flow`: FlowGraph built from workflow flows
+//! - `condition`: FlowCondition for `when:` on flows (conditional edges)
 //! - `validate`: DAG validation for use: bindings
+//! - `connect`: verb-pair rules for use: connections (`[connections]`, v0.7)
+//! - `diff`: DagDiff between two workflow versions (nika watch)
+//! - `lint`: semantic lints with configurable severities (nika lint)
 //!
 //! The DAG represents task dependencies and execution order.
 //! FlowGraph is immutable after construction (architectural decision #2).
 
+mod condition;
+mod connect;
+mod diff;
 mod flow;
+mod lint;
 mod validate;
 
 // Re-export public types
+pub use condition::FlowCondition;
+pub(crate) use condition::{loose_eq, truthy};
+pub use connect::{Connection, ConnectionPolicy};
+pub use diff::DagDiff;
 pub use flow::FlowGraph;
-pub use validate::validate_use_wiring;
+pub use lint::{lint_workflow, Lint, LintConfig, Severity};
+pub use validate::{validate_use_wiring, validate_use_wiring_with};
@@ -1,39 +1,100 @@
 //! DAG Validation - use: wiring validation (v0.1)
 //!
 //! Validates:
-//! - use: wiring references (task exists, is upstream)
+//! - use: wiring references (task exists, is upstream; `trigger` in
+//!   watch-triggered workflows, v0.7)
 //! - Template refs match use: declarations
 //! - Task ID format (snake_case)
+//! - `when:` conditions on flows parse and only reference `{{from...}}` (v0.7)
+//! - Verb pairs of use: connections against the [`ConnectionPolicy`] (v0.7)
 //!
 //! Error codes:
+//! - NIKA-022: Invalid `when:` condition on a flow
 //! - NIKA-055: Invalid task ID format (non-snake_case)
 //! - NIKA-080: use.alias references unknown task
 //! - NIKA-081: use.alias references non-upstream task
 //! - NIKA-082: use.alias creates self-reference
 //! - NIKA-083: Template {{use.alias}} references undeclared alias
+//! - NIKA-084: use.alias connects verbs that need a bridge task (v0.7)
+//! - NIKA-085: use.alias connects verbs whose connection is blocked (v0.7)
+//! - NIKA-074: Malformed template reference (strict template mode, v0.7)
 
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::ast::{TaskAction, Workflow};
-use crate::binding::{validate_refs, validate_task_id, WiringSpec};
+use crate::ast::{TaskAction, Workflow, INPUTS_TASK_ID, STATE_TASK_ID, TRIGGER_TASK_ID};
+use crate::binding::{
+    validate_jinja_template, validate_refs, validate_task_id, validate_template_syntax,
+    TemplateEngine, TemplateMode, WiringSpec,
+};
 use crate::error::NikaError;
 
+use super::condition::FlowCondition;
+use super::connect::{Connection, ConnectionPolicy};
 use super::flow::FlowGraph;
 
 /// Validate a workflow's use: wiring against the flow graph
+///
+/// Every verb pair may connect; see [`validate_use_wiring_with`].
 pub fn validate_use_wiring(workflow: &Workflow, flow_graph: &FlowGraph) -> Result<(), NikaError> {
+    validate_use_wiring_with(workflow, flow_graph, &ConnectionPolicy::default())
+}
+
+/// Validate a workflow's use: wiring, with the project's `[connections]` rules (v0.7)
+pub fn validate_use_wiring_with(
+    workflow: &Workflow,
+    flow_graph: &FlowGraph,
+    policy: &ConnectionPolicy,
+) -> Result<(), NikaError> {
     // Zero-clone: use &str references instead of owned Strings
     let all_task_ids: FxHashSet<&str> = workflow.tasks.iter().map(|t| t.id.as_str()).collect();
+    let verbs: FxHashMap<&str, &'static str> = workflow
+        .tasks
+        .iter()
+        .map(|t| (t.id.as_str(), t.action.verb_name()))
+        .collect();
+    // Runs are seeded with the `state` binding, watch-triggered runs with
+    // `trigger` and workflows declaring `inputs:` with `inputs` (v0.7)
+    let mut seeded = vec![STATE_TASK_ID];
+    if workflow
+        .triggers
+        .as_ref()
+        .is_some_and(|t| t.watch.is_some())
+    {
+        seeded.push(TRIGGER_TASK_ID);
+    }
+    if !workflow.inputs.is_empty() {
+        seeded.push(INPUTS_TASK_ID);
+    }
 
     for task in &workflow.tasks {
         if let Some(ref wiring) = task.use_wiring {
-            validate_wiring(&task.id, wiring, &all_task_ids, flow_graph)?;
+            validate_wiring(&task.id, wiring, &all_task_ids, flow_graph, &seeded)?;
+            validate_connections(&task.id, wiring, &verbs, policy)?;
         }
 
         // FIX: Validate that {{use.alias}} refs in templates match declared aliases
-        validate_template_refs(task)?;
+        validate_template_refs(task, workflow.templates)?;
     }
 
+    validate_flow_conditions(workflow)?;
+
+    Ok(())
+}
+
+/// Validate that every `when:` on a flow parses (v0.7)
+fn validate_flow_conditions(workflow: &Workflow) -> Result<(), NikaError> {
+    for flow in &workflow.flows {
+        let Some(ref expr) = flow.when else {
+            continue;
+        };
+        if let Err(reason) = FlowCondition::parse(expr) {
+            return Err(NikaError::InvalidFlowCondition {
+                source_task: flow.source.as_vec().join(", "),
+                target_task: flow.target.as_vec().join(", "),
+                reason,
+            });
+        }
+    }
     Ok(())
 }
 
@@ -41,7 +102,7 @@ pub fn validate_use_wiring(workflow: &Workflow, flow_graph: &FlowGraph) -> Resul
 ///
 /// BUG FIX (2026-02-21): Previously validate_refs() existed but was never called.
 /// Now it's called during `nika check` to catch template typos early.
-fn validate_template_refs(task: &crate::ast::Task) -> Result<(), NikaError> {
+fn validate_template_refs(task: &crate::ast::Task, mode: TemplateMode) -> Result<(), NikaError> {
     // Collect declared aliases from use: block
     let mut declared_aliases: FxHashSet<String> = task
         .use_wiring
@@ -61,6 +122,12 @@ fn validate_template_refs(task: &crate::ast::Task) -> Result<(), NikaError> {
     let templates = extract_templates_from_action(&task.action);
 
     for template in templates {
+        // Jinja templates: minijinja syntax, `use.alias` reads (v0.7)
+        if task.template_engine == TemplateEngine::Jinja {
+            validate_jinja_template(&template, &declared_aliases, &task.id)?;
+            continue;
+        }
+        validate_template_syntax(&template, mode)?;
         validate_refs(&template, &declared_aliases, &task.id)?;
     }
 
@@ -68,12 +135,13 @@ fn validate_template_refs(task: &crate::ast::Task) -> Result<(), NikaError> {
 }
 
 /// Extract all template strings from a task action
-fn extract_templates_from_action(action: &TaskAction) -> Vec<String> {
+pub(super) fn extract_templates_from_action(action: &TaskAction) -> Vec<String> {
     let mut templates = Vec::new();
 
     match action {
         TaskAction::Infer { infer } => {
             templates.push(infer.prompt.clone());
+            templates.extend(infer.images.iter().cloned());
         }
         TaskAction::Exec { exec } => {
             templates.push(exec.command.clone());
@@ -83,6 +151,12 @@ fn extract_templates_from_action(action: &TaskAction) -> Vec<String> {
             if let Some(ref body) = fetch.body {
                 templates.push(body.clone());
             }
+            if let Some(ref graphql) = fetch.graphql {
+                templates.push(graphql.endpoint.clone());
+                if let Some(variables) = &graphql.variables {
+                    collect_string_values(variables, &mut templates);
+                }
+            }
         }
         TaskAction::Invoke { invoke } => {
             // params can contain templates in values
@@ -95,6 +169,63 @@ fn extract_templates_from_action(action: &TaskAction) -> Vec<String> {
             if let Some(ref system) = agent.system {
                 templates.push(system.clone());
             }
+            templates.extend(agent.images.iter().cloned());
+        }
+        TaskAction::Reduce { reduce } => {
+            templates.push(reduce.source.clone());
+            if let Some(ref prompt) = reduce.prompt {
+                templates.push(prompt.clone());
+            }
+        }
+        TaskAction::Approve { approve } => {
+            templates.push(approve.prompt.clone());
+        }
+        TaskAction::Embed { embed } => {
+            templates.push(embed.input.clone());
+        }
+        TaskAction::Recall { recall } => {
+            templates.push(recall.query.clone());
+        }
+        TaskAction::Retrieve { retrieve } => {
+            templates.push(retrieve.query.clone());
+        }
+        TaskAction::Validate { validate } => {
+            templates.push(validate.source.clone());
+        }
+        TaskAction::Transcribe { transcribe } => {
+            templates.push(transcribe.file.clone());
+        }
+        TaskAction::Import { import } => {
+            templates.push(import.file.clone());
+        }
+        TaskAction::Export { export } => {
+            templates.push(export.source.clone());
+            templates.push(export.file.clone());
+        }
+        TaskAction::Rows { rows } => {
+            templates.push(rows.source.clone());
+        }
+        TaskAction::Script { script } => {
+            templates.extend(script.file.clone());
+        }
+        TaskAction::Chunk { chunk } => {
+            templates.push(chunk.source.clone());
+        }
+        TaskAction::Dedupe { dedupe } => {
+            templates.push(dedupe.source.clone());
+        }
+        TaskAction::DetectLang { detect_lang } => {
+            templates.push(detect_lang.source.clone());
+        }
+        TaskAction::Translate { translate } => {
+            templates.push(translate.source.clone());
+            templates.push(translate.to.clone());
+            templates.extend(translate.from.clone());
+        }
+        TaskAction::Ws { ws } => {
+            templates.push(ws.url.clone());
+            templates.extend(ws.headers.values().cloned());
+            templates.extend(ws.send.iter().cloned());
         }
     }
 
@@ -102,7 +233,7 @@ fn extract_templates_from_action(action: &TaskAction) -> Vec<String> {
 }
 
 /// Recursively collect string values from JSON that might contain templates
-fn collect_string_values(value: &serde_json::Value, templates: &mut Vec<String>) {
+pub(super) fn collect_string_values(value: &serde_json::Value, templates: &mut Vec<String>) {
     match value {
         serde_json::Value::String(s) => {
             templates.push(s.clone());
@@ -129,15 +260,22 @@ fn collect_string_values(value: &serde_json::Value, templates: &mut Vec<String>)
 /// 2. Source task exists in workflow
 /// 3. Source is not self-reference
 /// 4. Source task has path to current task
+///
+/// The `seeded` pseudo-tasks (`state`, `trigger`, `inputs`) are accepted
+/// as sources: they are available to every task before the run starts.
 fn validate_wiring(
     task_id: &str,
     wiring: &WiringSpec,
     all_task_ids: &FxHashSet<&str>,
     flow_graph: &FlowGraph,
+    seeded: &[&str],
 ) -> Result<(), NikaError> {
     for (alias, entry) in wiring {
         // Extract task_id from the path (first segment before '.')
         let from_task = entry.task_id();
+        if seeded.contains(&from_task) {
+            continue;
+        }
 
         // Validate the source task ID format (snake_case) - O(n) check
         validate_task_id(from_task)?;
@@ -149,6 +287,47 @@ fn validate_wiring(
     Ok(())
 }
 
+/// Check each use: source against the connection policy (v0.7)
+///
+/// Runs after [`validate_wiring`], so every non-seeded source is a task.
+fn validate_connections(
+    task_id: &str,
+    wiring: &WiringSpec,
+    verbs: &FxHashMap<&str, &'static str>,
+    policy: &ConnectionPolicy,
+) -> Result<(), NikaError> {
+    let verb = verbs[task_id];
+    for (alias, entry) in wiring {
+        let from_task = entry.task_id();
+        let Some(&from_verb) = verbs.get(from_task) else {
+            continue;
+        };
+        match policy.check(from_verb, verb) {
+            Connection::Allow => {}
+            &Connection::Bridge(bridge) => {
+                return Err(NikaError::ConnectionNeedsBridge {
+                    alias: alias.to_string(),
+                    from_task: from_task.to_string(),
+                    from_verb,
+                    task_id: task_id.to_string(),
+                    verb,
+                    bridge,
+                });
+            }
+            Connection::Block => {
+                return Err(NikaError::ConnectionBlocked {
+                    alias: alias.to_string(),
+                    from_task: from_task.to_string(),
+                    from_verb,
+                    task_id: task_id.to_string(),
+                    verb,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Validate that from_task exists and is upstream
 ///
 /// Checks in order:
@@ -196,7 +375,7 @@ fn validate_from_task(
 mod tests {
     use super::*;
     use crate::ast::{FetchParams, InferParams, InvokeParams, Task};
-    use crate::binding::UseEntry;
+    use crate::binding::{TemplateEngine, UseEntry};
     use serde_json::json;
 
     // ═══════════════════════════════════════════════════════════════
@@ -737,6 +916,9 @@ flows:
                     prompt: "Generate based on {{use.data}}".to_string(),
                     provider: None,
                     model: None,
+                    max_tokens: None,
+                    hedge: None,
+                    images: Vec::new(),
                 },
             },
             use_wiring: Some({
@@ -748,14 +930,29 @@ flows:
             for_each_as: None,
             output: None,
             decompose: None,
+            state: None,
+            tags: Vec::new(),
+            template_engine: TemplateEngine::default(),
+            transform: None,
+            moderate: None,
             concurrency: None,
             fail_fast: None,
         };
 
-        let result = validate_template_refs(&task);
+        let result = validate_template_refs(&task, TemplateMode::Lenient);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn validate_template_strict_rejects_malformed_ref() {
+        let task: Task =
+            serde_yaml::from_str("id: t\nexec: \"echo {{ use.a-b }} \\\\{{ok}}\"").unwrap();
+
+        assert!(validate_template_refs(&task, TemplateMode::Lenient).is_ok());
+        let err = validate_template_refs(&task, TemplateMode::Strict).unwrap_err();
+        assert!(err.to_string().contains("NIKA-074"));
+    }
+
     #[test]
     fn validate_template_undeclared_alias() {
         let task = Task {
@@ -765,6 +962,9 @@ flows:
                     prompt: "Generate based on {{use.missing}}".to_string(),
                     provider: None,
                     model: None,
+                    max_tokens: None,
+                    hedge: None,
+                    images: Vec::new(),
                 },
             },
             use_wiring: Some({
@@ -776,11 +976,16 @@ flows:
             for_each_as: None,
             output: None,
             decompose: None,
+            state: None,
+            tags: Vec::new(),
+            template_engine: TemplateEngine::default(),
+            transform: None,
+            moderate: None,
             concurrency: None,
             fail_fast: None,
         };
 
-        let result = validate_template_refs(&task);
+        let result = validate_template_refs(&task, TemplateMode::Lenient);
         assert!(result.is_err());
         // validate_refs returns UnknownAlias error (NIKA-071) for undeclared aliases
         assert!(result.unwrap_err().to_string().contains("NIKA-071"));
@@ -795,6 +1000,9 @@ flows:
                     prompt: "Process {{use.item}}".to_string(),
                     provider: None,
                     model: None,
+                    max_tokens: None,
+                    hedge: None,
+                    images: Vec::new(),
                 },
             },
             use_wiring: None,
@@ -802,11 +1010,16 @@ flows:
             for_each_as: Some("item".to_string()),
             output: None,
             decompose: None,
+            state: None,
+            tags: Vec::new(),
+            template_engine: TemplateEngine::default(),
+            transform: None,
+            moderate: None,
             concurrency: None,
             fail_fast: None,
         };
 
-        let result = validate_template_refs(&task);
+        let result = validate_template_refs(&task, TemplateMode::Lenient);
         assert!(result.is_ok());
     }
 
@@ -820,6 +1033,10 @@ flows:
                     method: "GET".to_string(),
                     headers: rustc_hash::FxHashMap::default(),
                     body: None,
+                    auth: None,
+                    retry: None,
+                    paginate: None,
+                    graphql: None,
                 },
             },
             use_wiring: Some({
@@ -831,11 +1048,16 @@ flows:
             for_each_as: None,
             output: None,
             decompose: None,
+            state: None,
+            tags: Vec::new(),
+            template_engine: TemplateEngine::default(),
+            transform: None,
+            moderate: None,
             concurrency: None,
             fail_fast: None,
         };
 
-        let result = validate_template_refs(&task);
+        let result = validate_template_refs(&task, TemplateMode::Lenient);
         assert!(result.is_ok());
     }
 
@@ -864,11 +1086,16 @@ flows:
             for_each_as: None,
             output: None,
             decompose: None,
+            state: None,
+            tags: Vec::new(),
+            template_engine: TemplateEngine::default(),
+            transform: None,
+            moderate: None,
             concurrency: None,
             fail_fast: None,
         };
 
-        let result = validate_template_refs(&task);
+        let result = validate_template_refs(&task, TemplateMode::Lenient);
         assert!(result.is_ok());
     }
 
@@ -926,6 +1153,46 @@ tasks:
         assert!(result.unwrap_err().to_string().contains("NIKA-080"));
     }
 
+    #[test]
+    fn validate_wiring_trigger_binding() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+triggers:
+  watch: "./inbox/*.md"
+tasks:
+  - id: task1
+    infer:
+      prompt: "Summarize {{use.file}}"
+    use:
+      file: trigger.path
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let flow_graph = FlowGraph::from_workflow(&workflow);
+        assert!(validate_use_wiring(&workflow, &flow_graph).is_ok());
+
+        // Without a watch trigger, `trigger` is an unknown task
+        let yaml = yaml.replace("triggers:\n  watch: \"./inbox/*.md\"\n", "");
+        let workflow: Workflow = serde_yaml::from_str(&yaml).unwrap();
+        let flow_graph = FlowGraph::from_workflow(&workflow);
+        let result = validate_use_wiring(&workflow, &flow_graph);
+        assert!(result.unwrap_err().to_string().contains("NIKA-080"));
+    }
+
+    #[test]
+    fn validate_wiring_state_binding() {
+        let yaml = r#"
+schema: nika/workflow@0.5
+tasks:
+  - id: task1
+    exec: "fetch --since {{use.since}}"
+    use:
+      since: state.last_id ?? 0
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let flow_graph = FlowGraph::from_workflow(&workflow);
+        assert!(validate_use_wiring(&workflow, &flow_graph).is_ok());
+    }
+
     #[test]
     fn validate_wiring_self_reference() {
         let yaml = r#"
@@ -1034,4 +1301,67 @@ flows:
         let result = validate_use_wiring(&workflow, &flow_graph);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn validate_flow_condition_rejects_unknown_reference() {
+        let yaml = r#"
+schema: nika/workflow@0.1
+tasks:
+  - id: review
+    exec: "echo"
+  - id: publish
+    exec: "echo"
+flows:
+  - source: review
+    target: publish
+    when: "{{use.status}} == 'approved'"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let flow_graph = FlowGraph::from_workflow(&workflow);
+
+        let err = validate_use_wiring(&workflow, &flow_graph).unwrap_err();
+        assert_eq!(err.code(), "NIKA-022");
+        assert!(err.to_string().contains("review -> publish"));
+    }
+
+    #[test]
+    fn validate_connections_against_policy() {
+        let yaml = r#"
+schema: nika/workflow@0.1
+tasks:
+  - id: draft
+    agent:
+      prompt: "Draft the release notes"
+  - id: summary
+    infer: "Summarize {{use.notes}}"
+    use:
+      notes: draft
+  - id: review
+    agent:
+      prompt: "Review {{use.notes}} against {{use.short}}"
+    use:
+      notes: draft
+      short: summary
+flows:
+  - source: draft
+    target: summary
+  - source: summary
+    target: review
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let flow_graph = FlowGraph::from_workflow(&workflow);
+        assert!(validate_use_wiring(&workflow, &flow_graph).is_ok());
+
+        // review uses draft directly: agent -> agent needs the bridge
+        let policy =
+            ConnectionPolicy::default().with_rule("agent", "agent", Connection::Bridge("infer"));
+        let err = validate_use_wiring_with(&workflow, &flow_graph, &policy).unwrap_err();
+        assert_eq!(err.code(), "NIKA-084");
+        assert!(err.to_string().contains("'draft_to_review'"));
+
+        let policy = ConnectionPolicy::default().with_rule("*", "infer", Connection::Block);
+        let err = validate_use_wiring_with(&workflow, &flow_graph, &policy).unwrap_err();
+        assert_eq!(err.code(), "NIKA-085");
+        assert!(err.to_string().contains("use.notes: 'summary' (infer:)"));
+    }
 }
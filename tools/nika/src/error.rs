@@ -19,6 +19,11 @@
 //! - NIKA-110-119: Agent errors (v0.2)
 //! - NIKA-120-129: Resilience errors (v0.2) [122-124 deprecated in v0.4]
 //! - NIKA-130-139: TUI errors (v0.2)
+//! - NIKA-150-159: Cassette record/replay errors (v0.7)
+//! - NIKA-160-169: Lint findings (v0.7, see `dag::lint`; not error variants)
+//! - NIKA-170-179: Approval errors (v0.7)
+//! - NIKA-180-189: Daemon and share relay errors (v0.7)
+//! - NIKA-190-199: DataStore backend, state, vector store, retrieval and agent memory errors (v0.7)
 //!
 //! v0.6.1: Added miette for fancy error display with source spans
 
@@ -96,6 +101,34 @@ pub enum NikaError {
         errors: Vec<crate::ast::schema_validator::SchemaError>,
     },
 
+    #[error("[NIKA-006] Invalid override '{path}': {reason}")]
+    #[diagnostic(
+        code(nika::invalid_override),
+        help("Use --set dotted.path=value, e.g. tasks.summarize.model=gpt-4o")
+    )]
+    InvalidOverride { path: String, reason: String },
+
+    #[error("[NIKA-007] Invalid cron expression '{expr}': {reason}")]
+    #[diagnostic(
+        code(nika::invalid_cron),
+        help("Use 5 fields: minute hour day-of-month month day-of-week, e.g. \"0 9 * * MON\"")
+    )]
+    InvalidCron { expr: String, reason: String },
+
+    #[error("[NIKA-008] Invalid watch trigger '{pattern}': {reason}")]
+    #[diagnostic(
+        code(nika::invalid_watch_trigger),
+        help("Use a glob relative to the workflow file, e.g. \"./inbox/*.md\"")
+    )]
+    InvalidWatchTrigger { pattern: String, reason: String },
+
+    #[error("[NIKA-009] Input '{name}': {reason}")]
+    #[diagnostic(
+        code(nika::invalid_input),
+        help("Pass --input name=value for each input without a default")
+    )]
+    InvalidInput { name: String, reason: String },
+
     // ═══════════════════════════════════════════
     // SCHEMA ERRORS (010-019) - v0.1 compat
     // ═══════════════════════════════════════════
@@ -117,6 +150,13 @@ pub enum NikaError {
     #[error("[NIKA-021] Missing dependency: task '{task_id}' depends on unknown '{dep_id}'")]
     MissingDependency { task_id: String, dep_id: String },
 
+    #[error("[NIKA-022] Invalid flow condition on {source_task} -> {target_task}: {reason}")]
+    InvalidFlowCondition {
+        source_task: String,
+        target_task: String,
+        reason: String,
+    },
+
     // ═══════════════════════════════════════════
     // PROVIDER ERRORS (030-039)
     // ═══════════════════════════════════════════
@@ -136,6 +176,34 @@ pub enum NikaError {
     #[error("[NIKA-033] Invalid configuration: {message}")]
     InvalidConfig { message: String },
 
+    /// v0.7: `embed:`/`recall:` with a provider that can't embed
+    #[error("[NIKA-034] Provider '{provider}' has no embeddings API")]
+    EmbeddingsUnsupported { provider: String },
+
+    /// v0.7: `images:` on a task whose provider can't take image input
+    #[error("[NIKA-035] Provider '{provider}' does not accept images")]
+    VisionUnsupported { provider: String },
+
+    /// v0.7: an `images:` entry that can't be sent
+    #[error("[NIKA-036] Invalid image '{image}': {reason}")]
+    InvalidImage { image: String, reason: String },
+
+    /// v0.7: `transcribe:` with a provider that has no speech-to-text API
+    #[error("[NIKA-037] Provider '{provider}' has no transcription API")]
+    TranscriptionUnsupported { provider: String },
+
+    /// v0.7: a `transcribe:` file that can't be read or split
+    #[error("[NIKA-038] Invalid audio '{file}': {reason}")]
+    InvalidAudio { file: String, reason: String },
+
+    /// v0.7: `preflight: true` found providers or MCP servers that don't answer
+    #[error("[NIKA-039] Preflight failed for {failed} of {checked} targets: {report}")]
+    PreflightFailed {
+        failed: usize,
+        checked: usize,
+        report: String,
+    },
+
     // ═══════════════════════════════════════════
     // TEMPLATE/BINDING ERRORS (040-049)
     // ═══════════════════════════════════════════
@@ -175,12 +243,47 @@ pub enum NikaError {
     #[error("[NIKA-052] Path '{path}' not found (task may not have JSON output)")]
     PathNotFound { path: String },
 
+    /// v0.7: an `exec: { host }` command that could not reach its host
+    #[error("[NIKA-053] exec: task '{task_id}' on host '{host}' failed: {reason}")]
+    RemoteExecError {
+        task_id: String,
+        host: String,
+        reason: String,
+    },
+
+    /// v0.7: a `fetch: { graphql }` response that carried `errors`
+    #[error("[NIKA-054] fetch: GraphQL request of '{task_id}' failed: {}", errors.join("; "))]
+    GraphqlError {
+        task_id: String,
+        errors: Vec<String>,
+    },
+
     #[error("[NIKA-055] Invalid task ID '{id}': {reason}")]
     InvalidTaskId { id: String, reason: String },
 
     #[error("[NIKA-056] Invalid default value '{raw}': {reason}")]
     InvalidDefault { raw: String, reason: String },
 
+    /// v0.7: a `script:` task that failed to load, compile or run
+    #[error("[NIKA-057] script: task '{task_id}' failed: {reason}")]
+    ScriptError { task_id: String, reason: String },
+
+    /// v0.7: an `exec:` command that broke one of its sandbox limits
+    #[error("[NIKA-058] exec: task '{task_id}' violated its sandbox ({limit}): {reason}")]
+    ExecSandboxViolation {
+        task_id: String,
+        limit: String,
+        reason: String,
+    },
+
+    /// v0.7: an `exec: { image }` container that could not be started
+    #[error("[NIKA-059] exec: task '{task_id}' container ({runtime}) failed: {reason}")]
+    ContainerError {
+        task_id: String,
+        runtime: String,
+        reason: String,
+    },
+
     // ═══════════════════════════════════════════
     // OUTPUT ERRORS (060-069) - v0.1
     // ═══════════════════════════════════════════
@@ -190,6 +293,37 @@ pub enum NikaError {
     #[error("[NIKA-061] Schema validation failed: {details}")]
     SchemaFailed { details: String },
 
+    /// A `validate:` task found violations (v0.7)
+    #[error("[NIKA-062] Validation failed for '{task_id}' ({} violations): {}", violations.len(), violations.join("; "))]
+    DataValidationFailed {
+        task_id: String,
+        violations: Vec<String>,
+    },
+
+    /// Output couldn't be parsed or serialized by its codec (v0.7)
+    #[error("[NIKA-063] Output format '{format}' failed: {reason}")]
+    CodecError { format: String, reason: String },
+
+    /// v0.7: a `rows:` task whose source or operations are invalid
+    #[error("[NIKA-064] rows: task '{task_id}' failed: {reason}")]
+    RowsError { task_id: String, reason: String },
+
+    /// v0.7: a `transform:` step couldn't process the task output
+    #[error("[NIKA-065] transform step {step} ({op}) failed: {reason}")]
+    TransformFailed {
+        step: usize,
+        op: String,
+        reason: String,
+    },
+
+    /// v0.7: `moderate: { policy: block }` stopped a task input or output
+    #[error("[NIKA-066] moderation blocked the {stage} of '{task_id}': {}", categories.join(", "))]
+    ContentBlocked {
+        task_id: String,
+        stage: String,
+        categories: Vec<String>,
+    },
+
     // ═══════════════════════════════════════════
     // USE BLOCK VALIDATION (070-079) - v0.1
     // ═══════════════════════════════════════════
@@ -238,10 +372,35 @@ pub enum NikaError {
         task_id: String,
     },
 
+    #[error(
+        "[NIKA-084] use.{alias}: '{task_id}' ({verb}:) can't use '{from_task}' ({from_verb}:) \
+         directly; insert a bridge task ({bridge}:), e.g. '{from_task}_to_{task_id}', between them"
+    )]
+    ConnectionNeedsBridge {
+        alias: String,
+        from_task: String,
+        from_verb: &'static str,
+        task_id: String,
+        verb: &'static str,
+        bridge: &'static str,
+    },
+
+    #[error(
+        "[NIKA-085] use.{alias}: '{task_id}' ({verb}:) can't use '{from_task}' ({from_verb}:), \
+         {from_verb} -> {verb} connections are blocked"
+    )]
+    ConnectionBlocked {
+        alias: String,
+        from_task: String,
+        from_verb: &'static str,
+        task_id: String,
+        verb: &'static str,
+    },
+
     // ═══════════════════════════════════════════
     // JSONPATH / IO ERRORS (090-099) - v0.1
     // ═══════════════════════════════════════════
-    #[error("[NIKA-090] JSONPath '{path}' is not supported in v0.1 (use $.a.b or $.a[0].b)")]
+    #[error("[NIKA-090] JSONPath '{path}' is invalid or not supported")]
     JsonPathUnsupported { path: String },
 
     #[error("[NIKA-091] JSONPath '{path}' matched nothing in output")]
@@ -263,6 +422,22 @@ pub enum NikaError {
     )]
     YamlParse(#[from] serde_yaml::Error),
 
+    /// v0.7: an `import:`/`export:` spreadsheet that can't be read or written
+    #[error("[NIKA-096] Spreadsheet '{file}': {reason}")]
+    SpreadsheetError { file: String, reason: String },
+
+    /// v0.7: a `ws:` task that can't connect or loses its connection
+    #[error("[NIKA-097] ws: task '{task_id}' on {url}: {reason}")]
+    WebSocketError {
+        task_id: String,
+        url: String,
+        reason: String,
+    },
+
+    /// v0.7: a trace or checkpoint written by an incompatible Nika version
+    #[error("[NIKA-098] '{file}' is incompatible: {reason}")]
+    IncompatibleVersion { file: String, reason: String },
+
     // ═══════════════════════════════════════════
     // MCP ERRORS (100-109) - NEW v0.2
     // ═══════════════════════════════════════════
@@ -362,9 +537,20 @@ pub enum NikaError {
     #[error("[NIKA-121] Operation '{operation}' timed out after {duration_ms}ms")]
     Timeout { operation: String, duration_ms: u64 },
 
+    #[error("[NIKA-122] Request to '{provider}' too large: {reason}")]
+    RequestTooLarge { provider: String, reason: String },
+
     #[error("[NIKA-125] MCP tool call '{tool}' failed: {reason}")]
     McpToolCallFailed { tool: String, reason: String },
 
+    #[error("[NIKA-126] Context pool exhausted for task '{task_id}': needs {needed} tokens, {available} of {capacity} left")]
+    ContextPoolExhausted {
+        task_id: String,
+        needed: u64,
+        available: u64,
+        capacity: u64,
+    },
+
     // ═══════════════════════════════════════════
     // TUI ERRORS (130-139) - NEW v0.2
     // ═══════════════════════════════════════════
@@ -377,6 +563,54 @@ pub enum NikaError {
     #[error("[NIKA-140] Config error: {reason}")]
     ConfigError { reason: String },
 
+    // ═══════════════════════════════════════════
+    // CASSETTE ERRORS (150-159) - NEW v0.7
+    // ═══════════════════════════════════════════
+    #[error("[NIKA-150] No cassette entry for {request} (key {key}) in replay mode")]
+    CassetteMiss { key: String, request: String },
+
+    // ═══════════════════════════════════════════
+    // APPROVAL ERRORS (170-179) - NEW v0.7
+    // ═══════════════════════════════════════════
+    #[error("[NIKA-170] Approval for task '{task_id}' could not be obtained: {reason}")]
+    ApprovalUnavailable { task_id: String, reason: String },
+
+    /// `.nika/policy.yaml` blocked an `exec:` command
+    #[error("[NIKA-171] Task '{task_id}' blocked by .nika/policy.yaml: {rule}")]
+    PolicyBlocked { task_id: String, rule: String },
+
+    // ═══════════════════════════════════════════
+    // DAEMON ERRORS (180-189) - NEW v0.7
+    // ═══════════════════════════════════════════
+    #[error("[NIKA-180] Daemon error: {reason}")]
+    DaemonError { reason: String },
+
+    /// A run submitted to the daemon failed there (message is the daemon-side error)
+    #[error("[NIKA-181] Daemon run failed: {message}")]
+    DaemonRunFailed { message: String },
+
+    /// `nika run --share` or `nika watch <url>` could not use the relay
+    #[error("[NIKA-182] Share relay {url}: {reason}")]
+    RelayError { url: String, reason: String },
+
+    // ═══════════════════════════════════════════
+    // STORE ERRORS (190-199) - NEW v0.7
+    // ═══════════════════════════════════════════
+    #[error("[NIKA-190] DataStore backend '{backend}' failed: {reason}")]
+    StoreError { backend: String, reason: String },
+
+    #[error("[NIKA-191] State key '{key}' is not set (or expired)")]
+    StateKeyMissing { key: String },
+
+    #[error("[NIKA-192] Vector collection '{collection}': {reason}")]
+    VectorStoreError { collection: String, reason: String },
+
+    #[error("[NIKA-193] retrieve: files '{pattern}': {reason}")]
+    RetrieveError { pattern: String, reason: String },
+
+    #[error("[NIKA-194] Agent memory store '{backend}' failed: {reason}")]
+    MemoryStoreError { backend: String, reason: String },
+
     // ═══════════════════════════════════════════
     // TOOL ERRORS (200-219) - NEW v0.6
     // ═══════════════════════════════════════════
@@ -394,6 +628,10 @@ impl NikaError {
             Self::WorkflowNotFound { .. } => "NIKA-003",
             Self::ValidationError { .. } => "NIKA-004",
             Self::SchemaValidationFailed { .. } => "NIKA-005",
+            Self::InvalidOverride { .. } => "NIKA-006",
+            Self::InvalidCron { .. } => "NIKA-007",
+            Self::InvalidWatchTrigger { .. } => "NIKA-008",
+            Self::InvalidInput { .. } => "NIKA-009",
             // Schema errors
             Self::InvalidSchema { .. } => "NIKA-010",
             Self::TaskFailed { .. } => "NIKA-011",
@@ -401,12 +639,19 @@ impl NikaError {
             // DAG errors
             Self::CycleDetected { .. } => "NIKA-020",
             Self::MissingDependency { .. } => "NIKA-021",
+            Self::InvalidFlowCondition { .. } => "NIKA-022",
             // Provider errors
             Self::Provider(_) => "NIKA-030", // legacy
             Self::ProviderNotConfigured { .. } => "NIKA-030",
             Self::ProviderApiError { .. } => "NIKA-031",
             Self::MissingApiKey { .. } => "NIKA-032",
             Self::InvalidConfig { .. } => "NIKA-033",
+            Self::EmbeddingsUnsupported { .. } => "NIKA-034",
+            Self::VisionUnsupported { .. } => "NIKA-035",
+            Self::InvalidImage { .. } => "NIKA-036",
+            Self::TranscriptionUnsupported { .. } => "NIKA-037",
+            Self::InvalidAudio { .. } => "NIKA-038",
+            Self::PreflightFailed { .. } => "NIKA-039",
             // Binding/Template errors
             Self::Template(_) => "NIKA-040",  // legacy
             Self::Execution(_) => "NIKA-041", // legacy
@@ -418,11 +663,21 @@ impl NikaError {
             Self::InvalidPath { .. } => "NIKA-050",
             Self::TaskNotFound { .. } => "NIKA-051",
             Self::PathNotFound { .. } => "NIKA-052",
+            Self::RemoteExecError { .. } => "NIKA-053",
+            Self::GraphqlError { .. } => "NIKA-054",
             Self::InvalidTaskId { .. } => "NIKA-055",
             Self::InvalidDefault { .. } => "NIKA-056",
+            Self::ScriptError { .. } => "NIKA-057",
+            Self::ExecSandboxViolation { .. } => "NIKA-058",
+            Self::ContainerError { .. } => "NIKA-059",
             // Output errors
             Self::InvalidJson { .. } => "NIKA-060",
             Self::SchemaFailed { .. } => "NIKA-061",
+            Self::DataValidationFailed { .. } => "NIKA-062",
+            Self::CodecError { .. } => "NIKA-063",
+            Self::RowsError { .. } => "NIKA-064",
+            Self::TransformFailed { .. } => "NIKA-065",
+            Self::ContentBlocked { .. } => "NIKA-066",
             // Use block errors
             Self::DuplicateAlias { .. } => "NIKA-070",
             Self::UnknownAlias { .. } => "NIKA-071",
@@ -433,6 +688,8 @@ impl NikaError {
             Self::UseUnknownTask { .. } => "NIKA-080",
             Self::UseNotUpstream { .. } => "NIKA-081",
             Self::UseCircularDep { .. } => "NIKA-082",
+            Self::ConnectionNeedsBridge { .. } => "NIKA-084",
+            Self::ConnectionBlocked { .. } => "NIKA-085",
             // JSONPath/IO errors
             Self::JsonPathUnsupported { .. } => "NIKA-090",
             Self::JsonPathNoMatch { .. } => "NIKA-091",
@@ -440,6 +697,9 @@ impl NikaError {
             Self::IoError(_) => "NIKA-093",
             Self::JsonError(_) => "NIKA-094",
             Self::YamlParse(_) => "NIKA-095",
+            Self::SpreadsheetError { .. } => "NIKA-096",
+            Self::WebSocketError { .. } => "NIKA-097",
+            Self::IncompatibleVersion { .. } => "NIKA-098",
             // MCP errors
             Self::McpNotConnected { .. } => "NIKA-100",
             Self::McpStartError { .. } => "NIKA-101",
@@ -463,11 +723,28 @@ impl NikaError {
             // Resilience errors
             Self::ProviderError { .. } => "NIKA-120",
             Self::Timeout { .. } => "NIKA-121",
+            Self::RequestTooLarge { .. } => "NIKA-122",
             Self::McpToolCallFailed { .. } => "NIKA-125",
+            Self::ContextPoolExhausted { .. } => "NIKA-126",
             // TUI errors
             Self::TuiError { .. } => "NIKA-130",
             // Config errors
             Self::ConfigError { .. } => "NIKA-140",
+            // Cassette errors
+            Self::CassetteMiss { .. } => "NIKA-150",
+            // Approval errors
+            Self::ApprovalUnavailable { .. } => "NIKA-170",
+            Self::PolicyBlocked { .. } => "NIKA-171",
+            // Daemon errors
+            Self::DaemonError { .. } => "NIKA-180",
+            Self::DaemonRunFailed { .. } => "NIKA-181",
+            Self::RelayError { .. } => "NIKA-182",
+            // Store errors
+            Self::StoreError { .. } => "NIKA-190",
+            Self::StateKeyMissing { .. } => "NIKA-191",
+            Self::VectorStoreError { .. } => "NIKA-192",
+            Self::RetrieveError { .. } => "NIKA-193",
+            Self::MemoryStoreError { .. } => "NIKA-194",
             // Tool errors (code is dynamic)
             Self::ToolError { .. } => "NIKA-2XX",
         }
@@ -501,7 +778,28 @@ impl FixSuggestion for NikaError {
             NikaError::SchemaValidationFailed { .. } => {
                 Some("Check YAML against schemas/nika-workflow.schema.json")
             }
+            NikaError::InvalidOverride { .. } => {
+                Some("Use --set dotted.path=value, e.g. tasks.summarize.model=gpt-4o")
+            }
+            NikaError::InvalidCron { .. } => {
+                Some("Use 5 fields: minute hour day-of-month month day-of-week, e.g. \"0 9 * * MON\"")
+            }
+            NikaError::InvalidWatchTrigger { .. } => {
+                Some("Use a glob relative to the workflow file, e.g. \"./inbox/*.md\"")
+            }
+            NikaError::InvalidInput { .. } => {
+                Some("Pass --input name=value for each input without a default")
+            }
             NikaError::YamlParse(_) => Some("Check YAML syntax: indentation and quoting"),
+            NikaError::SpreadsheetError { .. } => Some(
+                "Use a .csv, .tsv or .xlsx file (or set format:), and check sheet: and columns: against the header row",
+            ),
+            NikaError::WebSocketError { .. } => Some(
+                "Check the ws:// or wss:// URL and headers:, or raise timeout_ms if the server is slow to answer",
+            ),
+            NikaError::IncompatibleVersion { .. } => Some(
+                "Upgrade nika to read files of newer versions, or read the file with the nika version that wrote it",
+            ),
             NikaError::InvalidSchema { .. } => {
                 Some("Use 'nika/workflow@0.5' as the schema version")
             }
@@ -513,6 +811,9 @@ impl FixSuggestion for NikaError {
             NikaError::MissingDependency { .. } => {
                 Some("Add the missing task or fix the dependency reference")
             }
+            NikaError::InvalidFlowCondition { .. } => {
+                Some("Use when: \"{{from.path}} == 'value'\" (==, != or a bare {{from.path}})")
+            }
             NikaError::Provider(_) => Some("Check API key env var is set"),
             NikaError::ProviderNotConfigured { .. } => {
                 Some("Add provider configuration to your workflow")
@@ -522,6 +823,24 @@ impl FixSuggestion for NikaError {
                 Some("Set the API key env var (ANTHROPIC_API_KEY or OPENAI_API_KEY)")
             }
             NikaError::InvalidConfig { .. } => Some("Check configuration value is valid"),
+            NikaError::EmbeddingsUnsupported { .. } => {
+                Some("Set provider: openai, mistral or ollama on the embed:/recall: task")
+            }
+            NikaError::VisionUnsupported { .. } => {
+                Some("Set provider: claude, openai or ollama on the task with images:")
+            }
+            NikaError::InvalidImage { .. } => Some(
+                "Use a PNG, JPEG, GIF or WebP file under 5 MB, at most 8 per task",
+            ),
+            NikaError::TranscriptionUnsupported { .. } => {
+                Some("Set provider: openai or groq on the transcribe: task")
+            }
+            NikaError::InvalidAudio { .. } => Some(
+                "Use WAV or MP3 for long recordings (they can be split); other formats must be under 25 MB",
+            ),
+            NikaError::PreflightFailed { .. } => Some(
+                "Check the API keys and model names of the failed providers and that the MCP servers start",
+            ),
             NikaError::Template(_) => Some("Use {{use.alias}} format with use: block"),
             NikaError::Execution(_) => Some("Check command/URL is valid"),
             NikaError::BindingError { .. } => Some("Check binding syntax and source task output"),
@@ -534,11 +853,41 @@ impl FixSuggestion for NikaError {
             NikaError::InvalidTaskId { .. } => {
                 Some("Task IDs must be snake_case: lowercase letters, digits, underscores")
             }
+            NikaError::RemoteExecError { .. } => Some(
+                "Define the host under [hosts] in .nika/config.toml and check that `ssh` can log in with its key non-interactively",
+            ),
+            NikaError::GraphqlError { .. } => Some(
+                "Check the query and variables against the API's schema; `nika check` only validates syntax",
+            ),
             NikaError::InvalidDefault { .. } => {
                 Some("Default values must be valid JSON. Strings must be quoted.")
             }
+            NikaError::ScriptError { .. } => Some(
+                "Check the Rhai syntax at the reported line; raise max_operations or timeout_ms for heavy scripts",
+            ),
+            NikaError::ExecSandboxViolation { .. } => Some(
+                "Raise the limit in exec.sandbox (or timeout), or drop the setting if the command needs it",
+            ),
+            NikaError::ContainerError { .. } => Some(
+                "Check that the docker or podman daemon is running and the image name is right, or set exec.runtime",
+            ),
             NikaError::InvalidJson { .. } => Some("Ensure output is valid JSON"),
             NikaError::SchemaFailed { .. } => Some("Fix output to match declared schema"),
+            NikaError::DataValidationFailed { .. } => Some(
+                "Fix the upstream output, or set on_fail: warn and branch on {{from.valid}}",
+            ),
+            NikaError::CodecError { .. } => Some(
+                "Ask for the format explicitly in the prompt, or register a codec for custom formats",
+            ),
+            NikaError::RowsError { .. } => Some(
+                "Bind an array of objects as source:; quote text in filter: ('pro') and name a field in aggregates (sum(seats))",
+            ),
+            NikaError::TransformFailed { .. } => Some(
+                "Check the step against the raw output; steps run in the order written, each on the previous result",
+            ),
+            NikaError::ContentBlocked { .. } => Some(
+                "Raise the category in moderate: thresholds, or use policy: flag or redact to keep the run going",
+            ),
             NikaError::DuplicateAlias { .. } => Some("Use unique alias names in use: block"),
             NikaError::UnknownAlias { .. } => {
                 Some("Declare the alias in use: block before referencing")
@@ -555,7 +904,15 @@ impl FixSuggestion for NikaError {
                 Some("Add a flow from the source task to this task")
             }
             NikaError::UseCircularDep { .. } => Some("Remove the circular dependency"),
-            NikaError::JsonPathUnsupported { .. } => Some("Use simple paths like $.field.subfield"),
+            NikaError::ConnectionNeedsBridge { .. } => {
+                Some("Have the bridge task use the source and use the bridge instead")
+            }
+            NikaError::ConnectionBlocked { .. } => {
+                Some("Remove the binding or change [connections] in .nika/config.toml")
+            }
+            NikaError::JsonPathUnsupported { .. } => {
+                Some("Use fields, [0], [-1], [*], [1:3], [?(@.x > 1)] or ..field (no unions)")
+            }
             NikaError::JsonPathNoMatch { .. } => {
                 Some("Check the path exists in source task output")
             }
@@ -629,18 +986,61 @@ impl FixSuggestion for NikaError {
                 Some("Check provider configuration and network connectivity")
             }
             NikaError::Timeout { .. } => Some("Increase timeout or check for slow operations"),
+            NikaError::RequestTooLarge { .. } => Some(
+                "Bind less data (a JSONPath, chunk: or a summary) or raise the cap in [size_limits]",
+            ),
             NikaError::McpTimeout { .. } => {
                 Some("MCP server is slow or unresponsive. Check network and server health.")
             }
             NikaError::McpToolCallFailed { .. } => {
                 Some("Check MCP tool parameters and server logs")
             }
+            NikaError::ContextPoolExhausted { .. } => Some(
+                "Raise context_pool.tokens, lower the task's reservation, or use on_exhausted: summarize",
+            ),
             // TUI errors
             NikaError::TuiError { .. } => Some("Check terminal compatibility and size"),
             // Config errors
             NikaError::ConfigError { .. } => {
                 Some("Check ~/.config/nika/config.toml for syntax errors")
             }
+            // Cassette errors
+            NikaError::CassetteMiss { .. } => {
+                Some("Re-record the cassette with NIKA_CASSETTE_MODE=record (or auto)")
+            }
+            // Approval errors
+            NikaError::ApprovalUnavailable { .. } => Some(
+                "Run in a terminal or the TUI to answer, or set `default: approve|reject` on the task",
+            ),
+            NikaError::PolicyBlocked { .. } => Some(
+                "Change the command, or the matching rule in .nika/policy.yaml if it should run",
+            ),
+            // Daemon errors
+            NikaError::DaemonError { .. } => {
+                Some("Check `nika daemon status`; remove a stale .nika/daemon.sock if needed")
+            }
+            NikaError::DaemonRunFailed { .. } => {
+                Some("Re-run with --no-daemon for the full local error report")
+            }
+            NikaError::RelayError { .. } => Some(
+                "Check that `nika relay` is running at that address, and set [share] relay in ~/.config/nika/config.toml or pass --relay",
+            ),
+            // Store errors
+            NikaError::StoreError { .. } => Some(
+                "Check [store] in ~/.config/nika/config.toml, or set backend = \"memory\"",
+            ),
+            NikaError::StateKeyMissing { .. } => Some(
+                "Set it with `nika state set`, or bind it with a default: use: { x: state.key ?? 0 }",
+            ),
+            NikaError::VectorStoreError { .. } => Some(
+                "Fill the collection with an embed: task first; keep one embedding model per collection",
+            ),
+            NikaError::RetrieveError { .. } => Some(
+                "Use a glob relative to the working directory that matches text files, e.g. \"docs/**/*.md\"",
+            ),
+            NikaError::MemoryStoreError { .. } => Some(
+                "Check [memory] in ~/.config/nika/config.toml; sqlite needs the memory-sqlite feature",
+            ),
             // Tool errors
             NikaError::ToolError { .. } => {
                 Some("Check file path and permissions. Use Read before Edit.")
@@ -719,6 +1119,20 @@ mod tests {
         assert!(msg.contains("no errors"));
     }
 
+    #[test]
+    fn test_invalid_override_error() {
+        let err = NikaError::InvalidOverride {
+            path: "tasks.missing.model".to_string(),
+            reason: "no task with id 'missing'".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-006");
+        let msg = err.to_string();
+        assert!(msg.contains("[NIKA-006]"));
+        assert!(<NikaError as FixSuggestion>::fix_suggestion(&err)
+            .unwrap()
+            .contains("--set"));
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // SCHEMA ERRORS (010-019)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -965,6 +1379,82 @@ mod tests {
         assert!(msg.contains("[NIKA-056]"));
     }
 
+    #[test]
+    fn test_script_error() {
+        let err = NikaError::ScriptError {
+            task_id: "diff".to_string(),
+            reason: "timed out after 5000 ms".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-057");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-057] script: task 'diff' failed: timed out after 5000 ms"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("max_operations"));
+    }
+
+    #[test]
+    fn test_exec_sandbox_violation_error() {
+        let err = NikaError::ExecSandboxViolation {
+            task_id: "build".to_string(),
+            limit: "cpu".to_string(),
+            reason: "exceeded 2s of CPU time".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-058");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-058] exec: task 'build' violated its sandbox (cpu): exceeded 2s of CPU time"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("exec.sandbox"));
+    }
+
+    #[test]
+    fn test_remote_exec_error() {
+        let err = NikaError::RemoteExecError {
+            task_id: "deploy".to_string(),
+            host: "build-server".to_string(),
+            reason: "Permission denied (publickey)".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-053");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-053] exec: task 'deploy' on host 'build-server' failed: Permission denied (publickey)"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("[hosts]"));
+    }
+
+    #[test]
+    fn test_graphql_error() {
+        let err = NikaError::GraphqlError {
+            task_id: "repo".to_string(),
+            errors: vec![
+                "Field 'stars' doesn't exist on type 'Repository'".to_string(),
+                "Variable $owner is required".to_string(),
+            ],
+        };
+        assert_eq!(err.code(), "NIKA-054");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-054] fetch: GraphQL request of 'repo' failed: Field 'stars' doesn't exist on type 'Repository'; Variable $owner is required"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("schema"));
+    }
+
+    #[test]
+    fn test_container_error() {
+        let err = NikaError::ContainerError {
+            task_id: "analyze".to_string(),
+            runtime: "auto".to_string(),
+            reason: "no docker or podman on PATH".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-059");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-059] exec: task 'analyze' container (auto) failed: no docker or podman on PATH"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("exec.runtime"));
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // OUTPUT ERRORS (060-069)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -989,6 +1479,80 @@ mod tests {
         assert!(msg.contains("[NIKA-061]"));
     }
 
+    #[test]
+    fn test_data_validation_failed_error() {
+        let err = NikaError::DataValidationFailed {
+            task_id: "check".to_string(),
+            violations: vec![
+                "email: is missing".to_string(),
+                "score: 120 is above the maximum 100".to_string(),
+            ],
+        };
+        assert_eq!(err.code(), "NIKA-062");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-062] Validation failed for 'check' (2 violations): email: is missing; \
+             score: 120 is above the maximum 100"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("on_fail: warn"));
+    }
+
+    #[test]
+    fn test_codec_error() {
+        let err = NikaError::CodecError {
+            format: "csv".to_string(),
+            reason: "unterminated quoted field".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-063");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-063] Output format 'csv' failed: unterminated quoted field"
+        );
+        assert!(<NikaError as FixSuggestion>::fix_suggestion(&err).is_some());
+    }
+
+    #[test]
+    fn test_rows_error() {
+        let err = NikaError::RowsError {
+            task_id: "top".to_string(),
+            reason: "aggregate 'x': unknown aggregate 'median'".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-064");
+        assert!(err
+            .to_string()
+            .starts_with("[NIKA-064] rows: task 'top' failed: aggregate 'x'"));
+        assert!(err.fix_suggestion().unwrap().contains("sum(seats)"));
+    }
+
+    #[test]
+    fn test_transform_failed_error() {
+        let err = NikaError::TransformFailed {
+            step: 2,
+            op: "regex".to_string(),
+            reason: "no match for 'id: (\\d+)'".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-065");
+        assert!(err
+            .to_string()
+            .starts_with("[NIKA-065] transform step 2 (regex) failed: no match"));
+        assert!(err.fix_suggestion().is_some());
+    }
+
+    #[test]
+    fn test_content_blocked_error() {
+        let err = NikaError::ContentBlocked {
+            task_id: "draft".to_string(),
+            stage: "output".to_string(),
+            categories: vec!["hate".to_string(), "violence".to_string()],
+        };
+        assert_eq!(err.code(), "NIKA-066");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-066] moderation blocked the output of 'draft': hate, violence"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("thresholds"));
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // USE BLOCK VALIDATION (070-079)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1094,6 +1658,35 @@ mod tests {
         assert!(msg.contains("circular"));
     }
 
+    #[test]
+    fn test_connection_policy_errors() {
+        let err = NikaError::ConnectionNeedsBridge {
+            alias: "draft".to_string(),
+            from_task: "write".to_string(),
+            from_verb: "agent",
+            task_id: "review".to_string(),
+            verb: "agent",
+            bridge: "infer",
+        };
+        assert_eq!(err.code(), "NIKA-084");
+        let msg = err.to_string();
+        assert!(msg.contains("[NIKA-084]"));
+        assert!(msg.contains("bridge task (infer:), e.g. 'write_to_review'"));
+
+        let err = NikaError::ConnectionBlocked {
+            alias: "out".to_string(),
+            from_task: "build".to_string(),
+            from_verb: "exec",
+            task_id: "review".to_string(),
+            verb: "agent",
+        };
+        assert_eq!(err.code(), "NIKA-085");
+        assert!(err
+            .to_string()
+            .contains("exec -> agent connections are blocked"));
+        assert!(err.fix_suggestion().is_some());
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // JSONPATH / IO ERRORS (090-099)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1163,6 +1756,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spreadsheet_error() {
+        let err = NikaError::SpreadsheetError {
+            file: "leads.xlsx".to_string(),
+            reason: "no sheet 'Q3' (sheets: Q1, Q2)".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-096");
+        assert!(err.fix_suggestion().unwrap().contains("sheet:"));
+        assert!(err
+            .to_string()
+            .starts_with("[NIKA-096] Spreadsheet 'leads.xlsx'"));
+    }
+
+    #[test]
+    fn test_websocket_error() {
+        let err = NikaError::WebSocketError {
+            task_id: "ticker".to_string(),
+            url: "wss://stream.example.com".to_string(),
+            reason: "connection refused".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-097");
+        assert!(err.fix_suggestion().unwrap().contains("wss://"));
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-097] ws: task 'ticker' on wss://stream.example.com: connection refused"
+        );
+    }
+
+    #[test]
+    fn test_incompatible_version_error() {
+        let err = NikaError::IncompatibleVersion {
+            file: ".nika/traces/gen.ndjson".to_string(),
+            reason: "trace schema 3 was written by nika 0.9.0".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-098");
+        assert!(err.fix_suggestion().unwrap().contains("Upgrade nika"));
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-098] '.nika/traces/gen.ndjson' is incompatible: trace schema 3 was written by nika 0.9.0"
+        );
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // MCP ERRORS (100-109)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1407,6 +2042,19 @@ mod tests {
         assert!(msg.contains("5000"));
     }
 
+    #[test]
+    fn test_request_too_large_error() {
+        let err = NikaError::RequestTooLarge {
+            provider: "groq".to_string(),
+            reason: "prompt is 120000 chars, over max_prompt_chars = 100000".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-122");
+        assert!(!err.is_recoverable());
+        let msg = err.to_string();
+        assert!(msg.contains("[NIKA-122]"));
+        assert!(msg.contains("groq"));
+    }
+
     #[test]
     fn test_mcp_tool_call_failed_error() {
         let err = NikaError::McpToolCallFailed {
@@ -1418,6 +2066,21 @@ mod tests {
         assert!(msg.contains("[NIKA-125]"));
     }
 
+    #[test]
+    fn test_context_pool_exhausted_error() {
+        let err = NikaError::ContextPoolExhausted {
+            task_id: "research".to_string(),
+            needed: 20000,
+            available: 4500,
+            capacity: 100000,
+        };
+        assert_eq!(err.code(), "NIKA-126");
+        assert!(err
+            .to_string()
+            .contains("needs 20000 tokens, 4500 of 100000 left"));
+        assert!(<NikaError as FixSuggestion>::fix_suggestion(&err).is_some());
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // TUI ERRORS (130-139)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1446,6 +2109,211 @@ mod tests {
         assert!(msg.contains("[NIKA-140]"));
     }
 
+    // ═══════════════════════════════════════════════════════════════════════════
+    // CASSETTE ERRORS (150-159)
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    #[test]
+    fn test_cassette_miss_error() {
+        let err = NikaError::CassetteMiss {
+            key: "0123abcd".to_string(),
+            request: "infer claude".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-150");
+        assert!(err.to_string().contains("[NIKA-150]"));
+        assert!(err.fix_suggestion().unwrap().contains("NIKA_CASSETTE_MODE"));
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    // APPROVAL ERRORS (170-179)
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    #[test]
+    fn test_approval_unavailable_error() {
+        let err = NikaError::ApprovalUnavailable {
+            task_id: "review".to_string(),
+            reason: "timed out after 30s".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-170");
+        assert!(err.to_string().contains("review"));
+        assert!(err.fix_suggestion().unwrap().contains("default:"));
+    }
+
+    #[test]
+    fn test_policy_blocked_error() {
+        let err = NikaError::PolicyBlocked {
+            task_id: "cleanup".to_string(),
+            rule: "commands.deny 'rm\\s+-rf' matches 'rm -rf /'".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-171");
+        assert!(err.to_string().contains("cleanup"));
+        assert!(err.fix_suggestion().unwrap().contains("policy.yaml"));
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    // DAEMON ERRORS (180-189)
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    #[test]
+    fn test_daemon_errors() {
+        let err = NikaError::DaemonError {
+            reason: "already running".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-180");
+        let err = NikaError::DaemonRunFailed {
+            message: "[NIKA-031] Provider error".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-181");
+        assert!(err.to_string().contains("[NIKA-031]"));
+        assert!(err.fix_suggestion().unwrap().contains("--no-daemon"));
+        let err = NikaError::RelayError {
+            url: "http://relay.internal:7878".to_string(),
+            reason: "connection refused".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-182");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-182] Share relay http://relay.internal:7878: connection refused"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("nika relay"));
+    }
+
+    // ═══════════════════════════════════════════════════════════════════════════
+    // STORE ERRORS (190-199)
+    // ═══════════════════════════════════════════════════════════════════════════
+
+    #[test]
+    fn test_store_error() {
+        let err = NikaError::StoreError {
+            backend: "redis".to_string(),
+            reason: "connection refused".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-190");
+        assert!(err.to_string().contains("'redis'"));
+        assert!(err.fix_suggestion().unwrap().contains("[store]"));
+
+        let err = NikaError::StateKeyMissing {
+            key: "last_id".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-191");
+        assert!(err.fix_suggestion().unwrap().contains("state.key ?? 0"));
+
+        let err = NikaError::VectorStoreError {
+            collection: "docs".to_string(),
+            reason: "no such collection".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-192");
+        assert!(err.to_string().contains("'docs'"));
+
+        let err = NikaError::RetrieveError {
+            pattern: "docs/*.md".to_string(),
+            reason: "no files match".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-193");
+        assert!(err.fix_suggestion().unwrap().contains("docs/**/*.md"));
+
+        let err = NikaError::MemoryStoreError {
+            backend: "sqlite".to_string(),
+            reason: "database is locked".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-194");
+        assert!(err.fix_suggestion().unwrap().contains("[memory]"));
+    }
+
+    #[test]
+    fn test_embeddings_unsupported_error() {
+        let err = NikaError::EmbeddingsUnsupported {
+            provider: "claude".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-034");
+        assert!(err.fix_suggestion().unwrap().contains("openai"));
+    }
+
+    #[test]
+    fn test_vision_errors() {
+        let err = NikaError::VisionUnsupported {
+            provider: "groq".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-035");
+        assert!(err.fix_suggestion().unwrap().contains("claude"));
+
+        let err = NikaError::InvalidImage {
+            image: "./scan.tiff".to_string(),
+            reason: "unsupported image type".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-036");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-036] Invalid image './scan.tiff': unsupported image type"
+        );
+    }
+
+    #[test]
+    fn test_transcription_errors() {
+        let err = NikaError::TranscriptionUnsupported {
+            provider: "claude".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-037");
+        assert!(err.fix_suggestion().unwrap().contains("groq"));
+
+        let err = NikaError::InvalidAudio {
+            file: "call.m4a".to_string(),
+            reason: "31.0 MB, only WAV and MP3 can be split".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-038");
+        assert!(err
+            .to_string()
+            .starts_with("[NIKA-038] Invalid audio 'call.m4a'"));
+    }
+
+    #[test]
+    fn test_preflight_failed_error() {
+        let err = NikaError::PreflightFailed {
+            failed: 1,
+            checked: 3,
+            report: "mcp:novanet: connection refused".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-039");
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-039] Preflight failed for 1 of 3 targets: mcp:novanet: connection refused"
+        );
+        assert!(err.fix_suggestion().unwrap().contains("API keys"));
+    }
+
+    #[test]
+    fn test_invalid_cron_error() {
+        let err = NikaError::InvalidCron {
+            expr: "0 9 * *".to_string(),
+            reason: "expected 5 fields, found 4".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-007");
+        assert!(err.to_string().contains("0 9 * *"));
+        assert!(err.fix_suggestion().unwrap().contains("0 9 * * MON"));
+    }
+
+    #[test]
+    fn test_invalid_watch_trigger_error() {
+        let err = NikaError::InvalidWatchTrigger {
+            pattern: "./inbox/[*.md".to_string(),
+            reason: "unclosed character class".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-008");
+        assert!(err.to_string().contains("./inbox/[*.md"));
+        assert!(err.fix_suggestion().unwrap().contains("inbox"));
+    }
+
+    #[test]
+    fn test_invalid_input_error() {
+        let err = NikaError::InvalidInput {
+            name: "topic".to_string(),
+            reason: "missing required input".to_string(),
+        };
+        assert_eq!(err.code(), "NIKA-009");
+        assert!(err.to_string().contains("'topic'"));
+        assert!(err.fix_suggestion().unwrap().contains("--input"));
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // TOOL ERRORS (200-219)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -1654,6 +2522,15 @@ mod tests {
             .code(),
             "NIKA-021"
         );
+        assert_eq!(
+            NikaError::InvalidFlowCondition {
+                source_task: "a".into(),
+                target_task: "b".into(),
+                reason: "x".into()
+            }
+            .code(),
+            "NIKA-022"
+        );
     }
 
     #[test]
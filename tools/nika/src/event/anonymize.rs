@@ -0,0 +1,398 @@
+//! Trace anonymization for bug reports (v0.7, `nika trace export --anonymize`)
+//!
+//! An anonymized trace keeps what a Nika maintainer needs to reproduce a
+//! scheduling, timing or provider problem, and nothing about the user's
+//! data:
+//!
+//! - event ids, timestamps, durations, token counts, sizes and flags as is
+//! - structural fields (event type, verb, provider, model, phase, finish
+//!   reason, MCP server and tool names, ...) as is
+//! - identifiers (task ids, aliases, generation id, paths, URLs, hosts,
+//!   keys) hashed with a per-export salt, so the same id maps to the same
+//!   hash across events and `task[2]` stays `<hash>[2]`
+//! - every other string (prompts, outputs, commands, errors, ...) replaced
+//!   by a placeholder of the same length, keeping whitespace and a leading
+//!   `[NIKA-xxx]` error code; numbers inside outputs and inputs become 0
+//!
+//! Object keys are kept, so the shape of outputs survives; the keys of a
+//! task's `inputs` are its `use:` aliases and are hashed like `alias`.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use xxhash_rust::xxh3::xxh3_64_with_seed;
+
+use super::{Event, EventKind};
+
+/// Placeholder character for anonymized text
+const MASK: char = 'x';
+
+/// Fields whose string values describe Nika itself, kept as is
+const KEEP_FIELDS: &[&str] = &[
+    "type",
+    "verb",
+    "provider",
+    "model",
+    "phase",
+    "finish_reason",
+    "stop_reason",
+    "tier",
+    "classifier",
+    "format",
+    "kind",
+    "outcome",
+    "stage",
+    "stream",
+    "action",
+    "strategy",
+    "nika_version",
+    "workflow_hash",
+    "content_type",
+    "license",
+    "mcp_server",
+    "server_name",
+    "tool",
+    "rules",
+    "flagged",
+    "scores",
+    "mcp_servers",
+];
+
+/// Fields holding identifiers, hashed (one level of arrays included)
+const ID_FIELDS: &[&str] = &[
+    "task_id",
+    "parent_task_id",
+    "child_task_id",
+    "failed_task",
+    "source_task",
+    "dependencies",
+    "running_tasks",
+    "generation_id",
+    "alias",
+    "node",
+    "path",
+    "url",
+    "host",
+    "key",
+    "target",
+    "by",
+    "call_id",
+    "request_id",
+    "source",
+    "collection",
+];
+
+/// `[NIKA-123]` at the start of an error message
+static ERROR_CODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[NIKA-[0-9]+\]").expect("valid error code regex"));
+
+/// Replaces user data in trace events (see the module docs)
+#[derive(Debug, Clone, Copy)]
+pub struct Anonymizer {
+    salt: u64,
+}
+
+impl Anonymizer {
+    /// Anonymizer hashing identifiers with `salt`
+    pub fn new(salt: u64) -> Self {
+        Self { salt }
+    }
+
+    /// Anonymizer with a random salt: hashes can't be matched across exports
+    pub fn random() -> Self {
+        Self::new(uuid::Uuid::new_v4().as_u64_pair().0)
+    }
+
+    /// `event` as JSON, anonymized
+    pub fn anonymize_event(&self, event: &Event) -> Value {
+        let mut value = serde_json::to_value(event).unwrap_or(Value::Null);
+        if let Some(Value::Object(kind)) = value.get_mut("kind") {
+            self.fields(kind);
+        }
+        value
+    }
+
+    /// Hash of an identifier, keeping a `[n]` iteration suffix
+    pub fn id(&self, id: &str) -> String {
+        let (base, suffix) = match id.find('[') {
+            Some(at) if id.ends_with(']') => id.split_at(at),
+            _ => (id, ""),
+        };
+        format!(
+            "{:012x}{}",
+            xxh3_64_with_seed(base.as_bytes(), self.salt) >> 16,
+            suffix
+        )
+    }
+
+    fn fields(&self, map: &mut Map<String, Value>) {
+        for (key, value) in map.iter_mut() {
+            if KEEP_FIELDS.contains(&key.as_str()) {
+                continue;
+            }
+            if ID_FIELDS.contains(&key.as_str()) {
+                match value {
+                    Value::String(id) => *id = self.id(id),
+                    Value::Array(ids) => {
+                        for id in ids {
+                            if let Value::String(text) = id {
+                                *text = self.id(text);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if let ("inputs", Value::Object(inputs)) = (key.as_str(), &mut *value) {
+                *inputs = std::mem::take(inputs)
+                    .into_iter()
+                    .map(|(alias, mut input)| {
+                        self.content(&mut input, Nested::Yes);
+                        (self.id(&alias), input)
+                    })
+                    .collect();
+                continue;
+            }
+            self.content(value, Nested::No);
+        }
+    }
+
+    /// Mask strings, and numbers inside payload values
+    fn content(&self, value: &mut Value, nested: Nested) {
+        match value {
+            Value::String(text) => *text = placeholder(text),
+            Value::Number(number) if nested == Nested::Yes => *number = 0.into(),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.content(item, nested)),
+            // Event sub-structs (`sources`, `metadata`) have named fields;
+            // payload objects are masked whole
+            Value::Object(map) if nested == Nested::No && is_struct(map) => self.fields(map),
+            Value::Object(map) => map
+                .values_mut()
+                .for_each(|item| self.content(item, Nested::Yes)),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Nested {
+    No,
+    Yes,
+}
+
+/// Sub-structs of events whose fields follow the same rules as the event's
+fn is_struct(map: &Map<String, Value>) -> bool {
+    ["node", "response_text", "thinking"]
+        .iter()
+        .any(|field| map.contains_key(*field))
+}
+
+/// `text` with every non-whitespace character masked, keeping an error code
+pub fn placeholder(text: &str) -> String {
+    let code = ERROR_CODE.find(text).map_or("", |m| m.as_str());
+    let rest = &text[code.len()..];
+    let mut out = String::with_capacity(text.len());
+    out.push_str(code);
+    out.extend(
+        rest.chars()
+            .map(|c| if c.is_whitespace() { c } else { MASK }),
+    );
+    out
+}
+
+/// Environment of this build, for a bug report
+pub fn environment(events: &[Event]) -> Value {
+    let features: Vec<&str> = [
+        ("cli", cfg!(feature = "cli")),
+        ("http", cfg!(feature = "http")),
+        ("mcp", cfg!(feature = "mcp")),
+        ("providers", cfg!(feature = "providers")),
+        ("tui", cfg!(feature = "tui")),
+        ("watch", cfg!(feature = "watch")),
+        ("lsp", cfg!(feature = "lsp")),
+        ("script", cfg!(feature = "script")),
+        ("jinja", cfg!(feature = "jinja")),
+        ("store-sled", cfg!(feature = "store-sled")),
+        ("store-redis", cfg!(feature = "store-redis")),
+        ("memory-sqlite", cfg!(feature = "memory-sqlite")),
+    ]
+    .into_iter()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect();
+
+    let mut traced_version = None;
+    let mut models = Vec::new();
+    for event in events {
+        match &event.kind {
+            EventKind::WorkflowStarted { nika_version, .. } => {
+                traced_version = Some(nika_version.clone());
+            }
+            EventKind::ProviderCalled {
+                provider, model, ..
+            } => {
+                let model = format!("{}/{}", provider, model);
+                if !models.contains(&model) {
+                    models.push(model);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    json!({
+        "nika_version": env!("CARGO_PKG_VERSION"),
+        "traced_with": traced_version,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "features": features,
+        "events": events.len(),
+        "duration_ms": events.last().map_or(0, |e| e.timestamp_ms),
+        "models": models,
+    })
+}
+
+/// Zip archive of the anonymized trace (`trace.ndjson`) and `environment.json`
+pub fn bug_report(events: &[Event], anonymizer: &Anonymizer) -> std::io::Result<Vec<u8>> {
+    let mut trace = String::new();
+    for event in events {
+        trace.push_str(&anonymizer.anonymize_event(event).to_string());
+        trace.push('\n');
+    }
+    let environment = serde_json::to_string_pretty(&environment(events))?;
+    crate::codec::write_zip(&[("trace.ndjson", trace), ("environment.json", environment)])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::event::{AgentTurnMetadata, ContextSource};
+
+    fn event(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id * 10,
+            kind,
+        }
+    }
+
+    #[test]
+    fn anonymizes_content_and_ids() {
+        let anonymizer = Anonymizer::new(7);
+        let task: Arc<str> = Arc::from("summarize[2]");
+        let events = [
+            event(
+                1,
+                EventKind::ProviderCalled {
+                    task_id: Arc::clone(&task),
+                    provider: "claude".into(),
+                    model: "claude-sonnet-4".into(),
+                    prompt_len: 42,
+                },
+            ),
+            event(
+                2,
+                EventKind::TaskCompleted {
+                    task_id: Arc::clone(&task),
+                    output: Arc::new(json!({"title": "Q3 plan", "seats": 12})),
+                    duration_ms: 1200,
+                },
+            ),
+            event(
+                3,
+                EventKind::TaskStarted {
+                    task_id: Arc::clone(&task),
+                    verb: "infer".into(),
+                    inputs: json!({"xs": ["a"]}),
+                },
+            ),
+            event(
+                4,
+                EventKind::TaskFailed {
+                    task_id: Arc::from("publish"),
+                    error: "[NIKA-031] quota for acme".to_string(),
+                    duration_ms: 5,
+                },
+            ),
+        ];
+        let values: Vec<Value> = events
+            .iter()
+            .map(|e| anonymizer.anonymize_event(e)["kind"].clone())
+            .collect();
+
+        let hashed = anonymizer.id("summarize[2]");
+        assert!(hashed.ends_with("[2]") && hashed.len() == 15, "{}", hashed);
+        assert_eq!(values[0]["task_id"], hashed);
+        assert_eq!(values[0]["model"], "claude-sonnet-4");
+        assert_eq!(values[0]["prompt_len"], 42);
+
+        assert_eq!(values[1]["task_id"], hashed);
+        assert_eq!(values[1]["output"], json!({"title": "xx xxxx", "seats": 0}));
+        assert_eq!(values[1]["duration_ms"], 1200);
+
+        let alias = anonymizer.id("xs");
+        assert_eq!(values[2]["inputs"], json!({ alias: ["x"] }));
+
+        assert_eq!(values[3]["error"], "[NIKA-031] xxxxx xxx xxxx");
+        assert_ne!(values[3]["task_id"], "publish");
+        assert_ne!(Anonymizer::new(8).id("publish"), anonymizer.id("publish"));
+    }
+
+    #[test]
+    fn anonymizes_sub_structs() {
+        let value = Anonymizer::new(1).anonymize_event(&event(
+            4,
+            EventKind::ContextAssembled {
+                task_id: Arc::from("t"),
+                sources: vec![ContextSource {
+                    node: "docs".to_string(),
+                    tokens: 300,
+                }],
+                excluded: Vec::new(),
+                total_tokens: 300,
+                budget_used_pct: 0.0,
+                truncated: false,
+            },
+        ));
+        assert_eq!(value["kind"]["sources"][0]["tokens"], 300);
+        assert_ne!(value["kind"]["sources"][0]["node"], "docs");
+
+        let metadata = AgentTurnMetadata {
+            input_tokens: 10,
+            ..AgentTurnMetadata::text_only("Done: 3 rows", "end_turn")
+        };
+        let value = serde_json::to_value(&metadata).unwrap();
+        let mut map = json!({ "metadata": value });
+        Anonymizer::new(1).fields(map.as_object_mut().unwrap());
+        assert_eq!(map["metadata"]["response_text"], "xxxxx x xxxx");
+        assert_eq!(map["metadata"]["input_tokens"], 10);
+        assert_eq!(map["metadata"]["stop_reason"], "end_turn");
+    }
+
+    #[test]
+    fn bug_report_archive() {
+        let events = [event(
+            0,
+            EventKind::WorkflowStarted {
+                task_count: 1,
+                generation_id: "gen-1".to_string(),
+                workflow_hash: "abc".to_string(),
+                workflow_name: None,
+                nika_version: "0.7.0".to_string(),
+            },
+        )];
+        let environment = environment(&events);
+        assert_eq!(environment["traced_with"], "0.7.0");
+        assert_eq!(environment["os"], std::env::consts::OS);
+        let features = environment["features"].as_array().unwrap();
+        assert_eq!(features.contains(&json!("jinja")), cfg!(feature = "jinja"));
+
+        let archive = bug_report(&events, &Anonymizer::new(3)).unwrap();
+        assert!(archive.starts_with(b"PK\x03\x04"));
+        let names = String::from_utf8_lossy(&archive);
+        assert!(names.contains("trace.ndjson") && names.contains("environment.json"));
+    }
+}
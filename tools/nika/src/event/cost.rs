@@ -0,0 +1,134 @@
+//! Run Cost - provider spend and prompt cache savings (v0.7)
+//!
+//! Sums the tokens of every `ProviderResponded` event, priced by the model
+//! of the matching `ProviderCalled` (see [`pricing`](crate::provider::pricing)).
+//! A response that already carries a `cost_usd` (agent loops, transcription)
+//! keeps it; others are priced from their tokens.
+//!
+//! Used by the run summary.
+
+use rustc_hash::FxHashMap;
+
+use super::{Event, EventKind};
+use crate::provider::{ModelPrice, TokenUsage};
+
+/// Spend of a run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunCost {
+    /// Tokens of all provider calls
+    pub usage: TokenUsage,
+    /// Total cost in USD (calls to unpriced models count as free)
+    pub cost_usd: f64,
+    /// What prompt caching saved in USD (negative: writes never read back)
+    pub cache_savings_usd: f64,
+    /// Provider calls
+    pub calls: usize,
+}
+
+/// Cost of the provider calls in `events` (None when there are none)
+pub fn run_cost(events: &[Event]) -> Option<RunCost> {
+    // Model of the call in flight, per task
+    let mut in_flight: FxHashMap<&str, &str> = FxHashMap::default();
+    let mut cost = RunCost::default();
+
+    for event in events {
+        match &event.kind {
+            EventKind::ProviderCalled { task_id, model, .. } => {
+                in_flight.insert(task_id, model);
+            }
+            EventKind::ProviderResponded {
+                task_id,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+                cost_usd,
+                ..
+            } => {
+                let usage = TokenUsage {
+                    input_tokens: u64::from(*input_tokens),
+                    output_tokens: u64::from(*output_tokens),
+                    cache_read_tokens: u64::from(*cache_read_tokens),
+                    cache_write_tokens: u64::from(*cache_write_tokens),
+                };
+                let price = in_flight.remove(&**task_id).and_then(ModelPrice::for_model);
+                cost.cost_usd += match price {
+                    Some(price) if *cost_usd == 0.0 => price.cost(&usage),
+                    _ => *cost_usd,
+                };
+                if let Some(price) = price {
+                    cost.cache_savings_usd += price.cache_savings(&usage);
+                }
+                cost.usage.add(&usage);
+                cost.calls += 1;
+            }
+            _ => {}
+        }
+    }
+    (cost.calls > 0).then_some(cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id,
+            kind,
+        }
+    }
+
+    fn call(id: u64, task: &str, model: &str, cache: (u32, u32), cost_usd: f64) -> [Event; 2] {
+        [
+            event(
+                id,
+                EventKind::ProviderCalled {
+                    task_id: task.into(),
+                    provider: "claude".into(),
+                    model: model.into(),
+                    prompt_len: 10,
+                },
+            ),
+            event(
+                id + 1,
+                EventKind::ProviderResponded {
+                    task_id: task.into(),
+                    request_id: None,
+                    input_tokens: 1_000,
+                    output_tokens: 1_000,
+                    cache_read_tokens: cache.0,
+                    cache_write_tokens: cache.1,
+                    ttft_ms: None,
+                    finish_reason: "end_turn".into(),
+                    cost_usd,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_prices_calls_and_cache_savings() {
+        let mut events = Vec::new();
+        // Sonnet: $3 in, $15 out per MTok
+        events.extend(call(0, "a", "claude-sonnet-4-20250514", (0, 10_000), 0.0));
+        events.extend(call(2, "a", "claude-sonnet-4-20250514", (10_000, 0), 0.0));
+        // Unpriced model with a recorded cost
+        events.extend(call(4, "b", "whisper-1", (0, 0), 0.5));
+
+        let cost = run_cost(&events).unwrap();
+        assert_eq!(cost.calls, 3);
+        assert_eq!(cost.usage.cache_read_tokens, 10_000);
+        assert_eq!(cost.usage.cache_write_tokens, 10_000);
+        // 2 x (0.003 + 0.015) + 0.0375 writes + 0.003 reads + 0.5
+        assert!((cost.cost_usd - 0.5765).abs() < 1e-9, "{}", cost.cost_usd);
+        // 10k reads save 0.027, 10k writes add 0.0075
+        assert!((cost.cache_savings_usd - 0.0195).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_calls_no_cost() {
+        assert!(run_cost(&[]).is_none());
+    }
+}
@@ -0,0 +1,521 @@
+//! Fine-tuning Dataset Builder (v0.7)
+//!
+//! Sweeps NDJSON traces and extracts (resolved prompt, output) pairs for a
+//! single task, ready to be written as JSONL for fine-tuning jobs.
+//!
+//! - Prompt: `TemplateResolved.result` (what the provider actually saw)
+//! - Completion: `TaskCompleted.output` (or `TaskFailed.error` with `status=failed`)
+//! - Score: numeric `score` field of the task output, or of a judge task output
+//!
+//! for_each iterations (`task[0]`, `task[1]`, ...) are included and matched
+//! against the judge task iteration with the same index.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use crate::error::{NikaError, Result};
+use crate::event::{read_trace_events, Event, EventKind, TraceInfo};
+
+/// Status filter for dataset examples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFilter {
+    /// Only successful task executions (default)
+    #[default]
+    Success,
+    /// Only failed task executions (completion = error message)
+    Failed,
+    /// Both successful and failed executions
+    Any,
+}
+
+/// Selection criteria for `nika dataset build`
+#[derive(Debug, Clone, Default)]
+pub struct DatasetFilter {
+    /// Task to extract examples for
+    pub task_id: String,
+    /// Execution status to keep
+    pub status: StatusFilter,
+    /// Only keep examples produced by this model
+    pub model: Option<String>,
+    /// Minimum judge score (examples without a score are dropped)
+    pub min_score: Option<f64>,
+    /// Task whose output carries the score (defaults to the task itself)
+    pub judge_task: Option<String>,
+}
+
+impl DatasetFilter {
+    /// Create a filter for a task with default criteria
+    pub fn new(task_id: impl Into<String>) -> Self {
+        Self {
+            task_id: task_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Apply a `key=value` filter expression (e.g. `status=success`)
+    pub fn apply_expr(&mut self, expr: &str) -> Result<()> {
+        let (key, value) = expr
+            .split_once('=')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .ok_or_else(|| NikaError::ValidationError {
+                reason: format!("Invalid filter '{}': expected key=value", expr),
+            })?;
+
+        match key {
+            "status" => {
+                self.status = match value {
+                    "success" => StatusFilter::Success,
+                    "failed" => StatusFilter::Failed,
+                    "any" => StatusFilter::Any,
+                    other => {
+                        return Err(NikaError::ValidationError {
+                            reason: format!(
+                                "Invalid status filter '{}'. Use: success, failed, any",
+                                other
+                            ),
+                        })
+                    }
+                }
+            }
+            "model" => self.model = Some(value.to_string()),
+            other => {
+                return Err(NikaError::ValidationError {
+                    reason: format!("Unknown filter key '{}'. Use: status, model", other),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Check whether an event task ID belongs to the given base task
+    ///
+    /// Matches `task` exactly and for_each iterations `task[N]`.
+    fn matches_task(base: &str, task_id: &str) -> bool {
+        match task_id.strip_prefix(base) {
+            Some("") => true,
+            Some(rest) => rest.starts_with('[') && rest.ends_with(']'),
+            None => false,
+        }
+    }
+}
+
+/// A single (prompt, completion) pair extracted from a trace
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetExample {
+    /// Generation the example was extracted from
+    pub generation_id: String,
+    /// Task ID (including for_each index, if any)
+    pub task_id: String,
+    /// Resolved prompt sent to the provider
+    pub prompt: String,
+    /// Task output (or error message for failed examples)
+    pub completion: String,
+    /// Model used, if a provider was called
+    pub model: Option<String>,
+    /// Judge score, if available
+    pub score: Option<f64>,
+    /// Whether the task succeeded
+    pub success: bool,
+}
+
+impl DatasetExample {
+    /// Convert to a chat-format JSONL record
+    ///
+    /// Format: `{"messages": [user, assistant], "metadata": {...}}`
+    pub fn to_record(&self) -> Value {
+        json!({
+            "messages": [
+                { "role": "user", "content": self.prompt },
+                { "role": "assistant", "content": self.completion },
+            ],
+            "metadata": {
+                "generation_id": self.generation_id,
+                "task_id": self.task_id,
+                "model": self.model,
+                "score": self.score,
+                "status": if self.success { "success" } else { "failed" },
+            }
+        })
+    }
+
+    /// Dedup key: identical prompt + completion pairs collapse to one example
+    fn dedup_key(&self) -> u64 {
+        use xxhash_rust::xxh3::xxh3_64;
+
+        xxh3_64(format!("{}\0{}", self.prompt, self.completion).as_bytes())
+    }
+}
+
+/// Per-task data gathered while scanning a trace
+#[derive(Default)]
+struct TaskTrace {
+    prompt: Option<String>,
+    model: Option<String>,
+    output: Option<Value>,
+    error: Option<String>,
+}
+
+/// Extract examples for a task from a single trace
+pub fn extract_examples(
+    generation_id: &str,
+    events: &[Event],
+    filter: &DatasetFilter,
+) -> Vec<DatasetExample> {
+    let judge = filter.judge_task.as_deref();
+    let mut tasks: FxHashMap<String, TaskTrace> = FxHashMap::default();
+    let mut judge_outputs: FxHashMap<String, Value> = FxHashMap::default();
+    // Preserve first-seen order for deterministic output
+    let mut order: Vec<String> = Vec::new();
+
+    for event in events {
+        let Some(task_id) = event.kind.task_id() else {
+            continue;
+        };
+
+        if let Some(judge) = judge {
+            if DatasetFilter::matches_task(judge, task_id) {
+                if let EventKind::TaskCompleted { output, .. } = &event.kind {
+                    // Key by iteration suffix so judge[i] scores task[i]
+                    let suffix = &task_id[judge.len()..];
+                    judge_outputs.insert(suffix.to_string(), (**output).clone());
+                }
+                continue;
+            }
+        }
+
+        if !DatasetFilter::matches_task(&filter.task_id, task_id) {
+            continue;
+        }
+
+        let entry = tasks.entry(task_id.to_string()).or_insert_with(|| {
+            order.push(task_id.to_string());
+            TaskTrace::default()
+        });
+
+        match &event.kind {
+            EventKind::TemplateResolved { result, .. } => entry.prompt = Some(result.clone()),
+            EventKind::ProviderCalled { model, .. } => entry.model = Some(model.to_string()),
+            EventKind::TaskCompleted { output, .. } => entry.output = Some((**output).clone()),
+            EventKind::TaskFailed { error, .. } => entry.error = Some(error.clone()),
+            _ => {}
+        }
+    }
+
+    let mut examples = Vec::new();
+    for task_id in order {
+        let Some(trace) = tasks.remove(&task_id) else {
+            continue;
+        };
+        let Some(prompt) = trace.prompt else {
+            continue;
+        };
+
+        let (completion, success, own_output) = match (trace.output, trace.error) {
+            (Some(output), _) => (value_to_text(&output), true, Some(output)),
+            (None, Some(error)) => (error, false, None),
+            (None, None) => continue,
+        };
+
+        match filter.status {
+            StatusFilter::Success if !success => continue,
+            StatusFilter::Failed if success => continue,
+            _ => {}
+        }
+
+        if let Some(ref wanted) = filter.model {
+            if trace.model.as_deref() != Some(wanted.as_str()) {
+                continue;
+            }
+        }
+
+        let score = match judge {
+            Some(_) => judge_outputs
+                .get(&task_id[filter.task_id.len()..])
+                .and_then(extract_score),
+            None => own_output.as_ref().and_then(extract_score),
+        };
+
+        if let Some(min) = filter.min_score {
+            if score.is_none_or(|s| s < min) {
+                continue;
+            }
+        }
+
+        examples.push(DatasetExample {
+            generation_id: generation_id.to_string(),
+            task_id,
+            prompt,
+            completion,
+            model: trace.model,
+            score,
+            success,
+        });
+    }
+
+    examples
+}
+
+/// Build a deduplicated dataset from a set of traces
+///
+/// Unreadable trace files are skipped with a warning.
+pub fn build_dataset(traces: &[TraceInfo], filter: &DatasetFilter) -> Vec<DatasetExample> {
+    let mut seen: FxHashSet<u64> = FxHashSet::default();
+    let mut dataset = Vec::new();
+
+    for trace in traces {
+        let events = match read_trace_events(&trace.path) {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!(path = %trace.path.display(), error = %e, "Skipping unreadable trace");
+                continue;
+            }
+        };
+
+        for example in extract_examples(&trace.generation_id, &events, filter) {
+            if seen.insert(example.dedup_key()) {
+                dataset.push(example);
+            }
+        }
+    }
+
+    dataset
+}
+
+/// Render examples as JSONL (one record per line)
+pub fn to_jsonl(examples: &[DatasetExample]) -> Result<String> {
+    let mut out = String::new();
+    for example in examples {
+        out.push_str(&serde_json::to_string(&example.to_record())?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Render a task output as completion text (strings are unquoted)
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Extract a numeric `score` from a JSON output (number or numeric string)
+fn extract_score(output: &Value) -> Option<f64> {
+    match output {
+        Value::Object(map) => map.get("score").and_then(extract_score),
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok().or_else(|| {
+            serde_json::from_str::<Value>(s)
+                .ok()
+                .and_then(|v| extract_score(&v))
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn ev(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id * 10,
+            kind,
+        }
+    }
+
+    fn prompt(task_id: &str, text: &str) -> EventKind {
+        EventKind::TemplateResolved {
+            task_id: task_id.into(),
+            template: "{{use.x}}".into(),
+            result: text.into(),
+        }
+    }
+
+    fn completed(task_id: &str, output: Value) -> EventKind {
+        EventKind::TaskCompleted {
+            task_id: task_id.into(),
+            output: Arc::new(output),
+            duration_ms: 5,
+        }
+    }
+
+    fn failed(task_id: &str, error: &str) -> EventKind {
+        EventKind::TaskFailed {
+            task_id: task_id.into(),
+            error: error.into(),
+            duration_ms: 5,
+        }
+    }
+
+    #[test]
+    fn test_extract_success_pair() {
+        let events = vec![
+            ev(0, prompt("summarize", "Summarize: foo")),
+            ev(1, completed("summarize", json!("foo in short"))),
+            ev(2, prompt("other", "ignored")),
+            ev(3, completed("other", json!("ignored"))),
+        ];
+
+        let examples = extract_examples("gen-1", &events, &DatasetFilter::new("summarize"));
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].prompt, "Summarize: foo");
+        assert_eq!(examples[0].completion, "foo in short");
+        assert!(examples[0].success);
+    }
+
+    #[test]
+    fn test_extract_status_filter() {
+        let events = vec![
+            ev(0, prompt("summarize", "p")),
+            ev(1, failed("summarize", "boom")),
+        ];
+
+        let filter = DatasetFilter::new("summarize");
+        assert!(extract_examples("g", &events, &filter).is_empty());
+
+        let mut filter = DatasetFilter::new("summarize");
+        filter.apply_expr("status=failed").unwrap();
+        let examples = extract_examples("g", &events, &filter);
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].completion, "boom");
+        assert!(!examples[0].success);
+    }
+
+    #[test]
+    fn test_extract_for_each_iterations() {
+        let events = vec![
+            ev(0, prompt("summarize[0]", "a")),
+            ev(1, prompt("summarize[1]", "b")),
+            ev(2, completed("summarize[1]", json!("B"))),
+            ev(3, completed("summarize[0]", json!("A"))),
+            ev(4, prompt("summarize_all", "not an iteration")),
+            ev(5, completed("summarize_all", json!("x"))),
+        ];
+
+        let examples = extract_examples("g", &events, &DatasetFilter::new("summarize"));
+        let ids: Vec<_> = examples.iter().map(|e| e.task_id.as_str()).collect();
+        assert_eq!(ids, vec!["summarize[0]", "summarize[1]"]);
+    }
+
+    #[test]
+    fn test_min_score_from_own_output() {
+        let events = vec![
+            ev(0, prompt("rate", "p1")),
+            ev(1, completed("rate", json!({"text": "hi", "score": 9}))),
+        ];
+
+        let mut filter = DatasetFilter::new("rate");
+        filter.min_score = Some(8.0);
+        assert_eq!(extract_examples("g", &events, &filter).len(), 1);
+
+        filter.min_score = Some(9.5);
+        assert!(extract_examples("g", &events, &filter).is_empty());
+    }
+
+    #[test]
+    fn test_min_score_from_judge_task() {
+        let events = vec![
+            ev(0, prompt("summarize[0]", "a")),
+            ev(1, completed("summarize[0]", json!("A"))),
+            ev(2, prompt("summarize[1]", "b")),
+            ev(3, completed("summarize[1]", json!("B"))),
+            ev(4, completed("judge[0]", json!("{\"score\": 9}"))),
+            ev(5, completed("judge[1]", json!("3"))),
+        ];
+
+        let mut filter = DatasetFilter::new("summarize");
+        filter.judge_task = Some("judge".into());
+        filter.min_score = Some(8.0);
+
+        let examples = extract_examples("g", &events, &filter);
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].task_id, "summarize[0]");
+        assert_eq!(examples[0].score, Some(9.0));
+    }
+
+    #[test]
+    fn test_model_filter() {
+        let events = vec![
+            ev(0, prompt("t", "p")),
+            ev(
+                1,
+                EventKind::ProviderCalled {
+                    task_id: "t".into(),
+                    provider: "claude".into(),
+                    model: "claude-sonnet".into(),
+                    prompt_len: 1,
+                },
+            ),
+            ev(2, completed("t", json!("out"))),
+        ];
+
+        let mut filter = DatasetFilter::new("t");
+        filter.apply_expr("model=claude-sonnet").unwrap();
+        assert_eq!(extract_examples("g", &events, &filter).len(), 1);
+
+        filter.apply_expr("model=gpt-4o").unwrap();
+        assert!(extract_examples("g", &events, &filter).is_empty());
+    }
+
+    #[test]
+    fn test_apply_expr_rejects_invalid() {
+        let mut filter = DatasetFilter::new("t");
+        assert!(filter.apply_expr("status").is_err());
+        assert!(filter.apply_expr("status=maybe").is_err());
+        assert!(filter.apply_expr("color=blue").is_err());
+    }
+
+    #[test]
+    fn test_build_dataset_dedupes_across_traces() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut traces = Vec::new();
+        for gen in ["gen-a", "gen-b"] {
+            let path = temp_dir.path().join(format!("{}.ndjson", gen));
+            let lines: Vec<String> = [
+                ev(0, prompt("summarize", "same prompt")),
+                ev(1, completed("summarize", json!("same output"))),
+            ]
+            .iter()
+            .map(|e| serde_json::to_string(e).unwrap())
+            .collect();
+            std::fs::write(&path, lines.join("\n")).unwrap();
+            traces.push(TraceInfo {
+                generation_id: gen.to_string(),
+                version: None,
+                path,
+                size_bytes: 0,
+                created: None,
+            });
+        }
+        let dataset = build_dataset(&traces, &DatasetFilter::new("summarize"));
+        assert_eq!(dataset.len(), 1);
+        assert_eq!(dataset[0].generation_id, "gen-a");
+    }
+
+    #[test]
+    fn test_to_jsonl_record_format() {
+        let example = DatasetExample {
+            generation_id: "g".into(),
+            task_id: "t".into(),
+            prompt: "hello".into(),
+            completion: "world".into(),
+            model: None,
+            score: Some(8.5),
+            success: true,
+        };
+
+        let jsonl = to_jsonl(&[example]).unwrap();
+        assert!(jsonl.ends_with('\n'));
+        let record: Value = serde_json::from_str(jsonl.trim()).unwrap();
+        assert_eq!(record["messages"][0]["role"], "user");
+        assert_eq!(record["messages"][0]["content"], "hello");
+        assert_eq!(record["messages"][1]["content"], "world");
+        assert_eq!(record["metadata"]["score"], 8.5);
+        assert_eq!(record["metadata"]["status"], "success");
+    }
+}
@@ -0,0 +1,428 @@
+//! Trace Diff - what changed between two runs (v0.7)
+//!
+//! Aligns the tasks of two traces by ID and compares, per task:
+//! - status (`completed`, `failed`, `skipped`, or missing from a run)
+//! - duration, ignoring changes under [`DURATION_NOISE_PCT`] percent or
+//!   [`DURATION_NOISE_MS`] milliseconds
+//! - tokens of its provider calls (`ProviderResponded`)
+//! - the resolved prompt (last `TemplateResolved`) and the output or error,
+//!   as line diffs
+//!
+//! Used by `nika trace diff <id1> <id2>`.
+
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+use super::{Event, EventKind};
+
+/// Duration changes below this percentage are noise
+pub const DURATION_NOISE_PCT: u64 = 25;
+/// Duration changes below this many milliseconds are noise
+pub const DURATION_NOISE_MS: u64 = 50;
+
+/// Lines of unchanged context kept around changed lines
+const CONTEXT_LINES: usize = 2;
+
+/// Largest LCS table (changed lines before × after) worth computing;
+/// bigger changes are summarized as line counts
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// One task as a trace recorded it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskRun {
+    /// `completed`, `failed`, `skipped` or `started` (never finished)
+    pub status: &'static str,
+    pub duration_ms: Option<u64>,
+    /// Input and output tokens of the task's provider calls
+    pub tokens: u64,
+    /// Prompt or command after template resolution
+    pub prompt: Option<String>,
+    /// Output, error or skip reason
+    pub output: Option<String>,
+}
+
+/// Tasks of a trace by ID, in the order they first appear
+pub fn task_runs(events: &[Event]) -> Vec<(String, TaskRun)> {
+    let mut runs: Vec<(String, TaskRun)> = Vec::new();
+    let mut index: FxHashMap<String, usize> = FxHashMap::default();
+
+    for event in events {
+        match &event.kind {
+            EventKind::TaskStarted { task_id, .. } => {
+                run_of(&mut runs, &mut index, task_id).status = "started"
+            }
+            EventKind::TaskCompleted {
+                task_id,
+                output,
+                duration_ms,
+            } => {
+                let task = run_of(&mut runs, &mut index, task_id);
+                task.status = "completed";
+                task.duration_ms = Some(*duration_ms);
+                task.output = Some(output_text(output));
+            }
+            EventKind::TaskFailed {
+                task_id,
+                error,
+                duration_ms,
+            } => {
+                let task = run_of(&mut runs, &mut index, task_id);
+                task.status = "failed";
+                task.duration_ms = Some(*duration_ms);
+                task.output = Some(error.clone());
+            }
+            EventKind::TaskSkipped { task_id, reason } => {
+                let task = run_of(&mut runs, &mut index, task_id);
+                task.status = "skipped";
+                task.output = Some(reason.clone());
+            }
+            EventKind::TemplateResolved {
+                task_id, result, ..
+            } => run_of(&mut runs, &mut index, task_id).prompt = Some(result.clone()),
+            EventKind::ProviderResponded {
+                task_id,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+                ..
+            } => {
+                run_of(&mut runs, &mut index, task_id).tokens += u64::from(*input_tokens)
+                    + u64::from(*output_tokens)
+                    + u64::from(*cache_read_tokens)
+                    + u64::from(*cache_write_tokens);
+            }
+            _ => {}
+        }
+    }
+
+    runs
+}
+
+fn run_of<'a>(
+    runs: &'a mut Vec<(String, TaskRun)>,
+    index: &mut FxHashMap<String, usize>,
+    task_id: &str,
+) -> &'a mut TaskRun {
+    let i = *index.entry(task_id.to_string()).or_insert(runs.len());
+    if i == runs.len() {
+        runs.push((task_id.to_string(), TaskRun::default()));
+    }
+    &mut runs[i].1
+}
+
+fn output_text(output: &Value) -> String {
+    match output {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+/// How one task differs between two runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskDiff {
+    pub task_id: String,
+    /// None: the task is not in the first run
+    pub left: Option<TaskRun>,
+    /// None: the task is not in the second run
+    pub right: Option<TaskRun>,
+}
+
+impl TaskDiff {
+    pub fn status_changed(&self) -> bool {
+        self.left.as_ref().map(|t| t.status) != self.right.as_ref().map(|t| t.status)
+    }
+
+    /// (before, after) when the change is more than noise
+    pub fn duration_change(&self) -> Option<(u64, u64)> {
+        let before = self.left.as_ref()?.duration_ms?;
+        let after = self.right.as_ref()?.duration_ms?;
+        let delta = before.abs_diff(after);
+        (delta >= DURATION_NOISE_MS && delta * 100 >= before.max(1) * DURATION_NOISE_PCT)
+            .then_some((before, after))
+    }
+
+    pub fn token_change(&self) -> Option<(u64, u64)> {
+        let (before, after) = (self.left.as_ref()?.tokens, self.right.as_ref()?.tokens);
+        (before != after).then_some((before, after))
+    }
+
+    pub fn prompt_diff(&self) -> Option<Vec<DiffLine<'_>>> {
+        self.text_diff(|t| t.prompt.as_deref())
+    }
+
+    pub fn output_diff(&self) -> Option<Vec<DiffLine<'_>>> {
+        self.text_diff(|t| t.output.as_deref())
+    }
+
+    fn text_diff<'a>(
+        &'a self,
+        text: impl Fn(&'a TaskRun) -> Option<&'a str>,
+    ) -> Option<Vec<DiffLine<'a>>> {
+        let before = text(self.left.as_ref()?).unwrap_or_default();
+        let after = text(self.right.as_ref()?).unwrap_or_default();
+        (before != after).then(|| line_diff(before, after))
+    }
+
+    /// Whether anything beyond noise changed
+    pub fn changed(&self) -> bool {
+        self.status_changed()
+            || self.duration_change().is_some()
+            || self.token_change().is_some()
+            || self.prompt_diff().is_some()
+            || self.output_diff().is_some()
+    }
+}
+
+/// Tasks of both runs aligned by ID: the first run's order, then tasks
+/// only the second run has
+pub fn diff_runs(left: &[Event], right: &[Event]) -> Vec<TaskDiff> {
+    let mut right: Vec<(String, Option<TaskRun>)> = task_runs(right)
+        .into_iter()
+        .map(|(id, task)| (id, Some(task)))
+        .collect();
+    let mut diffs: Vec<TaskDiff> = task_runs(left)
+        .into_iter()
+        .map(|(task_id, task)| {
+            let other = right
+                .iter_mut()
+                .find(|(id, _)| *id == task_id)
+                .and_then(|(_, task)| task.take());
+            TaskDiff {
+                task_id,
+                left: Some(task),
+                right: other,
+            }
+        })
+        .collect();
+    diffs.extend(
+        right
+            .into_iter()
+            .filter_map(|(task_id, task)| task.map(|task| (task_id, task)))
+            .map(|(task_id, task)| TaskDiff {
+                task_id,
+                left: None,
+                right: Some(task),
+            }),
+    );
+    diffs
+}
+
+/// A line of a text diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+    /// Unchanged lines left out
+    Skipped(usize),
+    /// Changed block too large to diff line by line (lines before, after)
+    Changed(usize, usize),
+}
+
+/// Line diff of `before` and `after` (longest common subsequence), with
+/// unchanged runs cut down to a little context
+pub fn line_diff<'a>(before: &'a str, after: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    // Common prefix and suffix stay out of the LCS table
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut lines: Vec<DiffLine<'a>> = old[..prefix].iter().map(|l| DiffLine::Same(l)).collect();
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_DIFF_CELLS {
+        lines.push(DiffLine::Changed(old_mid.len(), new_mid.len()));
+    } else {
+        lines.extend(lcs_diff(old_mid, new_mid));
+    }
+    lines.extend(old[old.len() - suffix..].iter().map(|l| DiffLine::Same(l)));
+    collapse(lines)
+}
+
+fn lcs_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    // lcs[i][j]: common lines of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// Keep `CONTEXT_LINES` unchanged lines around each change
+fn collapse(lines: Vec<DiffLine<'_>>) -> Vec<DiffLine<'_>> {
+    // Distance from each line to the nearest change, from both sides
+    let mut distance = vec![usize::MAX; lines.len()];
+    let mut last = None;
+    for (i, line) in lines.iter().enumerate() {
+        if !matches!(line, DiffLine::Same(_)) {
+            last = Some(i);
+        }
+        distance[i] = last.map_or(usize::MAX, |c| i - c);
+    }
+    let mut next = None;
+    for (i, line) in lines.iter().enumerate().rev() {
+        if !matches!(line, DiffLine::Same(_)) {
+            next = Some(i);
+        }
+        distance[i] = distance[i].min(next.map_or(usize::MAX, |c| c - i));
+    }
+
+    let mut out = Vec::new();
+    let mut skipped = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        if distance[i] <= CONTEXT_LINES {
+            if skipped > 0 {
+                out.push(DiffLine::Skipped(skipped));
+                skipped = 0;
+            }
+            out.push(line);
+        } else {
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        out.push(DiffLine::Skipped(skipped));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn events(kinds: Vec<EventKind>) -> Vec<Event> {
+        kinds
+            .into_iter()
+            .enumerate()
+            .map(|(i, kind)| Event {
+                id: i as u64,
+                timestamp_ms: i as u64,
+                kind,
+            })
+            .collect()
+    }
+
+    fn completed(task_id: &str, output: Value, duration_ms: u64) -> EventKind {
+        EventKind::TaskCompleted {
+            task_id: Arc::from(task_id),
+            output: Arc::new(output),
+            duration_ms,
+        }
+    }
+
+    fn prompt(task_id: &str, result: &str) -> EventKind {
+        EventKind::TemplateResolved {
+            task_id: Arc::from(task_id),
+            template: String::new(),
+            result: result.to_string(),
+        }
+    }
+
+    #[test]
+    fn aligns_tasks_and_reports_changes() {
+        let before = events(vec![
+            completed("fetch", json!({ "items": 3 }), 100),
+            prompt("summarize", "Summarize 3 items"),
+            completed("summarize", json!("Three items."), 1000),
+            completed("old", json!("gone"), 5),
+        ]);
+        let after = events(vec![
+            completed("fetch", json!({ "items": 3 }), 110),
+            prompt("summarize", "Summarize 0 items"),
+            EventKind::TaskFailed {
+                task_id: Arc::from("summarize"),
+                error: "empty input".to_string(),
+                duration_ms: 3000,
+            },
+            completed("new", json!("here"), 5),
+        ]);
+
+        let diffs = diff_runs(&before, &after);
+        let ids: Vec<&str> = diffs.iter().map(|d| d.task_id.as_str()).collect();
+        assert_eq!(ids, ["fetch", "summarize", "old", "new"]);
+
+        // 10ms slower is noise
+        assert!(!diffs[0].changed());
+
+        let summarize = &diffs[1];
+        assert!(summarize.status_changed());
+        assert_eq!(summarize.duration_change(), Some((1000, 3000)));
+        assert_eq!(
+            summarize.prompt_diff().unwrap(),
+            [
+                DiffLine::Removed("Summarize 3 items"),
+                DiffLine::Added("Summarize 0 items")
+            ]
+        );
+        assert!(diffs[2].right.is_none() && diffs[2].status_changed());
+        assert!(diffs[3].left.is_none());
+    }
+
+    #[test]
+    fn line_diff_keeps_context_around_changes() {
+        let before = "a\nb\nc\nd\ne\nf\ng\nh";
+        let after = "a\nb\nc\nd\ne\nF\ng\nh";
+        assert_eq!(
+            line_diff(before, after),
+            [
+                DiffLine::Skipped(3),
+                DiffLine::Same("d"),
+                DiffLine::Same("e"),
+                DiffLine::Removed("f"),
+                DiffLine::Added("F"),
+                DiffLine::Same("g"),
+                DiffLine::Same("h"),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_diff_summarizes_large_changes() {
+        let before: String = (0..3000).map(|i| format!("old {}\n", i)).collect();
+        let after: String = (0..2000).map(|i| format!("new {}\n", i)).collect();
+        let before = format!("head\n{}tail", before);
+        let after = format!("head\n{}tail", after);
+        assert_eq!(
+            line_diff(&before, &after),
+            [
+                DiffLine::Same("head"),
+                DiffLine::Changed(3000, 2000),
+                DiffLine::Same("tail"),
+            ]
+        );
+    }
+}
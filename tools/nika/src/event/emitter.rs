@@ -75,6 +75,7 @@ mod tests {
             task_count: 1,
             generation_id: "test".to_string(),
             workflow_hash: "hash".to_string(),
+            workflow_name: None,
             nika_version: TEST_VERSION.to_string(),
         });
         assert_eq!(id, 0); // First event
@@ -108,6 +109,7 @@ mod tests {
             task_count: 2,
             generation_id: "gen1".to_string(),
             workflow_hash: "hash1".to_string(),
+            workflow_name: None,
             nika_version: TEST_VERSION.to_string(),
         });
         let id2 = emitter.emit(EventKind::TaskStarted {
@@ -138,6 +140,7 @@ mod tests {
             task_count: 5,
             generation_id: "gen".to_string(),
             workflow_hash: "hash".to_string(),
+            workflow_name: None,
             nika_version: TEST_VERSION.to_string(),
         });
         let id2 = noop.emit(EventKind::TaskStarted {
@@ -169,6 +172,7 @@ mod tests {
                 task_count: 1,
                 generation_id: "".to_string(),
                 workflow_hash: "".to_string(),
+                workflow_name: None,
                 nika_version: TEST_VERSION.to_string(),
             }),
             0
@@ -190,6 +194,7 @@ mod tests {
             task_count,
             generation_id: "test-gen".to_string(),
             workflow_hash: "test-hash".to_string(),
+            workflow_name: None,
             nika_version: TEST_VERSION.to_string(),
         })
     }
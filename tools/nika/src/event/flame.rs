@@ -0,0 +1,281 @@
+//! Trace Flamegraph - where wall-clock time goes in a run (v0.7)
+//!
+//! Folds a recorded trace into `workflow;task;phase` stacks:
+//!
+//! | Phase             | Measured from                                        |
+//! |-------------------|------------------------------------------------------|
+//! | `binding-resolve` | `TaskScheduled` → `TaskStarted`                      |
+//! | `provider-wait`   | each `ProviderCalled` → `ProviderResponded`          |
+//! | `tool-call`       | `McpResponse.duration_ms`                            |
+//! | `post-process`    | last provider/tool response → `TaskCompleted`       |
+//!
+//! Whatever is left of a task's run time is the task frame's own time
+//! (shell commands, HTTP requests, template rendering).
+//!
+//! The folded output (`stack count` per line, in milliseconds) is the input
+//! format of `inferno-flamegraph`, `flamegraph.pl` and speedscope.
+
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+
+use super::{Event, EventKind};
+
+/// A phase inside a task frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    BindingResolve,
+    ProviderWait,
+    ToolCall,
+    PostProcess,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [
+        Phase::BindingResolve,
+        Phase::ProviderWait,
+        Phase::ToolCall,
+        Phase::PostProcess,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BindingResolve => "binding-resolve",
+            Self::ProviderWait => "provider-wait",
+            Self::ToolCall => "tool-call",
+            Self::PostProcess => "post-process",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Time profile of one task
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskProfile {
+    pub task_id: String,
+    /// Scheduled → completed/failed (ms)
+    pub total_ms: u64,
+    /// Time per phase (ms), in `Phase::ALL` order
+    pub phases: Vec<(Phase, u64)>,
+}
+
+impl TaskProfile {
+    /// Task time not attributed to any phase
+    pub fn self_ms(&self) -> u64 {
+        let phased: u64 = self.phases.iter().map(|(_, ms)| ms).sum();
+        self.total_ms.saturating_sub(phased)
+    }
+}
+
+/// Per-task profiles of a trace, in first-scheduled order
+pub fn profile(events: &[Event]) -> Vec<TaskProfile> {
+    #[derive(Default)]
+    struct Acc {
+        scheduled: Option<u64>,
+        started: Option<u64>,
+        ended: Option<u64>,
+        pending_calls: Vec<u64>,
+        provider_ms: u64,
+        tool_ms: u64,
+        last_io: Option<u64>,
+    }
+
+    let mut order: Vec<String> = Vec::new();
+    let mut tasks: FxHashMap<String, Acc> = FxHashMap::default();
+
+    for event in events {
+        let Some(task_id) = event.kind.task_id() else {
+            continue;
+        };
+        let acc = tasks.entry(task_id.to_string()).or_insert_with(|| {
+            order.push(task_id.to_string());
+            Acc::default()
+        });
+        let ts = event.timestamp_ms;
+
+        match &event.kind {
+            EventKind::TaskScheduled { .. } => {
+                acc.scheduled.get_or_insert(ts);
+            }
+            EventKind::TaskStarted { .. } => {
+                acc.started.get_or_insert(ts);
+            }
+            EventKind::ProviderCalled { .. } => acc.pending_calls.push(ts),
+            EventKind::ProviderResponded { .. } => {
+                if let Some(called) = acc.pending_calls.pop() {
+                    acc.provider_ms += ts.saturating_sub(called);
+                }
+                acc.last_io = Some(ts);
+            }
+            EventKind::McpResponse { duration_ms, .. } => {
+                acc.tool_ms += duration_ms;
+                acc.last_io = Some(ts);
+            }
+            EventKind::TaskCompleted { .. } | EventKind::TaskFailed { .. } => {
+                acc.ended = Some(ts);
+            }
+            _ => {}
+        }
+    }
+
+    order
+        .into_iter()
+        .filter_map(|task_id| {
+            let acc = tasks.remove(&task_id)?;
+            let started = acc.started?;
+            let scheduled = acc.scheduled.unwrap_or(started);
+            let ended = acc.ended.unwrap_or(started);
+            let post = acc.last_io.map_or(0, |io| ended.saturating_sub(io));
+
+            Some(TaskProfile {
+                task_id,
+                total_ms: ended.saturating_sub(scheduled),
+                phases: vec![
+                    (Phase::BindingResolve, started.saturating_sub(scheduled)),
+                    (Phase::ProviderWait, acc.provider_ms),
+                    (Phase::ToolCall, acc.tool_ms),
+                    (Phase::PostProcess, post),
+                ],
+            })
+        })
+        .collect()
+}
+
+/// Folded stacks (`workflow;task;phase ms`), skipping empty frames
+pub fn folded_stacks(workflow: &str, profiles: &[TaskProfile]) -> String {
+    let mut out = String::new();
+    for task in profiles {
+        let frame = format!("{};{}", workflow, task.task_id);
+        if task.self_ms() > 0 {
+            out.push_str(&format!("{} {}\n", frame, task.self_ms()));
+        }
+        for (phase, ms) in &task.phases {
+            if *ms > 0 {
+                out.push_str(&format!("{};{} {}\n", frame, phase, ms));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn event(id: u64, timestamp_ms: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms,
+            kind,
+        }
+    }
+
+    fn trace() -> Vec<Event> {
+        let t: Arc<str> = "summarize".into();
+        vec![
+            event(
+                0,
+                0,
+                EventKind::TaskScheduled {
+                    task_id: t.clone(),
+                    dependencies: vec![],
+                },
+            ),
+            event(
+                1,
+                5,
+                EventKind::TaskStarted {
+                    task_id: t.clone(),
+                    verb: "agent".into(),
+                    inputs: json!({}),
+                },
+            ),
+            event(
+                2,
+                10,
+                EventKind::ProviderCalled {
+                    task_id: t.clone(),
+                    provider: "mock".into(),
+                    model: "m".into(),
+                    prompt_len: 10,
+                },
+            ),
+            event(
+                3,
+                110,
+                EventKind::McpResponse {
+                    task_id: t.clone(),
+                    call_id: "c1".into(),
+                    output_len: 3,
+                    duration_ms: 40,
+                    cached: false,
+                    is_error: false,
+                    response: None,
+                },
+            ),
+            event(
+                4,
+                310,
+                EventKind::ProviderResponded {
+                    task_id: t.clone(),
+                    request_id: None,
+                    input_tokens: 1,
+                    output_tokens: 1,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                    ttft_ms: None,
+                    finish_reason: "stop".into(),
+                    cost_usd: 0.0,
+                },
+            ),
+            event(
+                5,
+                330,
+                EventKind::TaskCompleted {
+                    task_id: t,
+                    output: Arc::new(json!("done")),
+                    duration_ms: 325,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_profile_attributes_phases() {
+        let profiles = profile(&trace());
+        assert_eq!(profiles.len(), 1);
+        let task = &profiles[0];
+        assert_eq!(task.total_ms, 330);
+        assert_eq!(
+            task.phases,
+            vec![
+                (Phase::BindingResolve, 5),
+                (Phase::ProviderWait, 300),
+                (Phase::ToolCall, 40),
+                (Phase::PostProcess, 20),
+            ]
+        );
+        // Overlapping tool time is clamped, never negative
+        assert_eq!(task.self_ms(), 0);
+    }
+
+    #[test]
+    fn test_folded_stacks_skip_empty_frames() {
+        let mut profiles = profile(&trace());
+        profiles[0].phases[2].1 = 0;
+        assert_eq!(
+            folded_stacks("wf", &profiles),
+            "wf;summarize 5\n\
+             wf;summarize;binding-resolve 5\n\
+             wf;summarize;provider-wait 300\n\
+             wf;summarize;post-process 20\n"
+        );
+    }
+}
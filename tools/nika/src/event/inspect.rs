@@ -0,0 +1,360 @@
+//! Trace Inspect - the DataStore as of any point in a past run (v0.7)
+//!
+//! Replays a recorded trace up to a cursor and rebuilds what the DataStore
+//! held there: each task's output, or whether it was still running, failed
+//! or skipped. The bindings a task saw come from its `TaskStarted` inputs,
+//! with provenance from `BindingResolved`.
+//!
+//! | Cursor                   | Events replayed                            |
+//! |--------------------------|--------------------------------------------|
+//! | `--at event:230`         | up to and including event #230             |
+//! | `--at task:summarize`    | up to `summarize` completing (or failing)  |
+//! | `--before event:230`     | up to event #229                           |
+//! | `--before task:summarize`| up to just before `summarize` started      |
+//!
+//! `trigger` and `state` entries are not in the event log, so they are not
+//! part of the snapshot.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{Event, EventKind};
+
+/// A point in a trace: an event id, or a task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Point {
+    Event(u64),
+    Task(String),
+}
+
+impl FromStr for Point {
+    type Err = String;
+
+    /// Parse `event:<id>` or `task:<id>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("event", id)) => id
+                .parse()
+                .map(Point::Event)
+                .map_err(|_| format!("'{}' is not an event id", id)),
+            Some(("task", id)) if !id.is_empty() => Ok(Point::Task(id.to_string())),
+            _ => Err(format!(
+                "'{}' is not a trace point (use event:<id> or task:<id>)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Event(id) => write!(f, "event:{}", id),
+            Self::Task(id) => write!(f, "task:{}", id),
+        }
+    }
+}
+
+/// Where to stop replaying
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cursor {
+    /// Include the point (a task point = once the task finished)
+    At(Point),
+    /// Stop just before the point (a task point = before the task started)
+    Before(Point),
+}
+
+/// A task's entry in the reconstructed DataStore
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TaskEntry {
+    /// Started, no result yet
+    Running,
+    Completed {
+        output: Value,
+    },
+    Failed {
+        error: String,
+    },
+    Skipped {
+        reason: String,
+    },
+}
+
+/// A `use:` binding as a task received it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeenBinding {
+    pub alias: String,
+    pub value: Value,
+    /// Binding path (e.g. `weather.summary`), when recorded
+    pub path: Option<String>,
+    /// Event that produced the value (the source task's `TaskCompleted`)
+    pub source_event_id: Option<u64>,
+}
+
+/// The DataStore as of a cursor
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snapshot {
+    /// Last event replayed (None = cursor is before the first event)
+    pub last_event_id: Option<u64>,
+    /// Events replayed / events in the trace
+    pub replayed: usize,
+    pub total: usize,
+    /// Run time at the last replayed event (ms)
+    pub timestamp_ms: u64,
+    /// Tasks that had started or finished, by id
+    pub tasks: BTreeMap<String, TaskEntry>,
+}
+
+/// Rebuild the DataStore at `cursor`
+pub fn snapshot(events: &[Event], cursor: &Cursor) -> Result<Snapshot, String> {
+    let end = replay_end(events, cursor)?;
+    let mut tasks = BTreeMap::new();
+    for event in &events[..end] {
+        let (task_id, entry) = match &event.kind {
+            EventKind::TaskStarted { task_id, .. } => (task_id, TaskEntry::Running),
+            EventKind::TaskCompleted {
+                task_id, output, ..
+            } => (
+                task_id,
+                TaskEntry::Completed {
+                    output: Value::clone(output),
+                },
+            ),
+            EventKind::TaskFailed { task_id, error, .. } => (
+                task_id,
+                TaskEntry::Failed {
+                    error: error.clone(),
+                },
+            ),
+            EventKind::TaskSkipped { task_id, reason } => (
+                task_id,
+                TaskEntry::Skipped {
+                    reason: reason.clone(),
+                },
+            ),
+            _ => continue,
+        };
+        tasks.insert(task_id.to_string(), entry);
+    }
+
+    let last = end.checked_sub(1).map(|i| &events[i]);
+    Ok(Snapshot {
+        last_event_id: last.map(|e| e.id),
+        replayed: end,
+        total: events.len(),
+        timestamp_ms: last.map_or(0, |e| e.timestamp_ms),
+        tasks,
+    })
+}
+
+/// The bindings `task_id` received, in alias order (None = it never started)
+pub fn bindings_seen(events: &[Event], task_id: &str) -> Option<Vec<SeenBinding>> {
+    let inputs = events.iter().find_map(|e| match &e.kind {
+        EventKind::TaskStarted {
+            task_id: started,
+            inputs,
+            ..
+        } if &**started == task_id => Some(inputs),
+        _ => None,
+    })?;
+
+    let Value::Object(inputs) = inputs else {
+        return Some(Vec::new());
+    };
+    Some(
+        inputs
+            .iter()
+            .map(|(alias, value)| {
+                let resolved = events.iter().find_map(|e| match &e.kind {
+                    EventKind::BindingResolved {
+                        task_id: bound,
+                        alias: bound_alias,
+                        path,
+                        source_event_id,
+                        ..
+                    } if &**bound == task_id && bound_alias == alias => {
+                        Some((path.clone(), *source_event_id))
+                    }
+                    _ => None,
+                });
+                SeenBinding {
+                    alias: alias.clone(),
+                    value: value.clone(),
+                    path: resolved.as_ref().map(|(path, _)| path.to_string()),
+                    source_event_id: resolved.and_then(|(_, id)| id),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Number of leading events the cursor covers
+fn replay_end(events: &[Event], cursor: &Cursor) -> Result<usize, String> {
+    let (point, inclusive) = match cursor {
+        Cursor::At(point) => (point, true),
+        Cursor::Before(point) => (point, false),
+    };
+    let index = match point {
+        Point::Event(id) => events
+            .iter()
+            .position(|e| e.id == *id)
+            .ok_or_else(|| match events.last() {
+                Some(last) => format!("No event #{} in this trace (last is #{})", id, last.id),
+                None => "The trace has no events".to_string(),
+            })?,
+        Point::Task(task) => {
+            let matches = |id: &str, finished: bool| id == task && finished == inclusive;
+            events
+                .iter()
+                .position(|e| match &e.kind {
+                    EventKind::TaskStarted { task_id, .. } => matches(task_id, false),
+                    EventKind::TaskCompleted { task_id, .. }
+                    | EventKind::TaskFailed { task_id, .. } => matches(task_id, true),
+                    // A skipped task never starts: both cursors land on the skip
+                    EventKind::TaskSkipped { task_id, .. } => &**task_id == task,
+                    _ => false,
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "Task '{}' never {} in this trace",
+                        task,
+                        if inclusive { "finished" } else { "started" }
+                    )
+                })?
+        }
+    };
+    Ok(if inclusive { index + 1 } else { index })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn event(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id * 10,
+            kind,
+        }
+    }
+
+    fn started(task: &str, inputs: Value) -> EventKind {
+        EventKind::TaskStarted {
+            task_id: task.into(),
+            verb: "infer".into(),
+            inputs,
+        }
+    }
+
+    fn completed(task: &str, output: Value) -> EventKind {
+        EventKind::TaskCompleted {
+            task_id: task.into(),
+            output: Arc::new(output),
+            duration_ms: 10,
+        }
+    }
+
+    fn trace() -> Vec<Event> {
+        vec![
+            event(0, started("fetch", json!({}))),
+            event(1, completed("fetch", json!({"body": "hello"}))),
+            event(
+                2,
+                EventKind::BindingResolved {
+                    task_id: "summarize".into(),
+                    alias: "doc".into(),
+                    source_task: Some("fetch".into()),
+                    path: "fetch.body".into(),
+                    source_event_id: Some(1),
+                    used_default: false,
+                    lazy: false,
+                },
+            ),
+            event(3, started("summarize", json!({"doc": "hello"}))),
+            event(4, completed("summarize", json!("Hi."))),
+            event(
+                5,
+                EventKind::TaskSkipped {
+                    task_id: "publish".into(),
+                    reason: "when is false".into(),
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_parse_points() {
+        assert_eq!("event:230".parse(), Ok(Point::Event(230)));
+        assert_eq!(
+            "task:summarize".parse(),
+            Ok(Point::Task("summarize".into()))
+        );
+        assert!("event:x".parse::<Point>().is_err());
+        assert!("summarize".parse::<Point>().is_err());
+        assert_eq!(Point::Event(7).to_string(), "event:7");
+    }
+
+    #[test]
+    fn test_snapshot_at_and_before_cursors() {
+        let events = trace();
+
+        let at = snapshot(&events, &Cursor::At(Point::Event(3))).unwrap();
+        assert_eq!(at.last_event_id, Some(3));
+        assert_eq!(at.replayed, 4);
+        assert_eq!(at.tasks["summarize"], TaskEntry::Running);
+        assert_eq!(
+            at.tasks["fetch"],
+            TaskEntry::Completed {
+                output: json!({"body": "hello"})
+            }
+        );
+
+        let before = snapshot(&events, &Cursor::Before(Point::Task("summarize".into()))).unwrap();
+        assert_eq!(before.last_event_id, Some(2));
+        assert!(!before.tasks.contains_key("summarize"));
+
+        let done = snapshot(&events, &Cursor::At(Point::Task("summarize".into()))).unwrap();
+        assert_eq!(done.replayed, 5);
+        assert!(matches!(
+            done.tasks["summarize"],
+            TaskEntry::Completed { .. }
+        ));
+
+        let skipped = snapshot(&events, &Cursor::At(Point::Task("publish".into()))).unwrap();
+        assert!(matches!(
+            skipped.tasks["publish"],
+            TaskEntry::Skipped { .. }
+        ));
+
+        let start = snapshot(&events, &Cursor::Before(Point::Event(0))).unwrap();
+        assert_eq!(start.last_event_id, None);
+        assert!(start.tasks.is_empty());
+
+        assert!(snapshot(&events, &Cursor::At(Point::Event(99))).is_err());
+        assert!(snapshot(&events, &Cursor::At(Point::Task("nope".into()))).is_err());
+    }
+
+    #[test]
+    fn test_bindings_seen_with_provenance() {
+        let events = trace();
+        let seen = bindings_seen(&events, "summarize").unwrap();
+        assert_eq!(
+            seen,
+            vec![SeenBinding {
+                alias: "doc".into(),
+                value: json!("hello"),
+                path: Some("fetch.body".into()),
+                source_event_id: Some(1),
+            }]
+        );
+        assert_eq!(bindings_seen(&events, "fetch"), Some(Vec::new()));
+        assert_eq!(bindings_seen(&events, "publish"), None);
+    }
+}
@@ -0,0 +1,162 @@
+//! First-Token Latency - time-to-first-token per model (v0.7)
+//!
+//! Streaming provider calls record `ProviderResponded.ttft_ms`. This module
+//! groups those samples by the model of the matching `ProviderCalled` event
+//! and reports p50/p95/max, so models can be compared by responsiveness.
+//!
+//! Used by the run summary, `nika trace show` and the OTel metrics export.
+
+use std::collections::BTreeMap;
+
+use rustc_hash::FxHashMap;
+
+use super::{Event, EventKind};
+
+/// TTFT statistics for one model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TtftStats {
+    pub model: String,
+    /// Provider calls with a measured first token
+    pub calls: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl TtftStats {
+    /// Stats over raw samples (None when there are none)
+    pub fn from_samples(model: impl Into<String>, samples: &[u64]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        Some(Self {
+            model: model.into(),
+            calls: sorted.len(),
+            p50_ms: percentile(&sorted, 50)?,
+            p95_ms: percentile(&sorted, 95)?,
+            max_ms: *sorted.last()?,
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted samples
+pub fn percentile(sorted: &[u64], pct: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// TTFT samples (ms) per model, in event order
+pub fn ttft_samples(events: &[Event]) -> BTreeMap<String, Vec<u64>> {
+    // Model of the call in flight, per task
+    let mut in_flight: FxHashMap<&str, &str> = FxHashMap::default();
+    let mut samples: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+
+    for event in events {
+        match &event.kind {
+            EventKind::ProviderCalled { task_id, model, .. } => {
+                in_flight.insert(task_id, model);
+            }
+            EventKind::ProviderResponded {
+                task_id, ttft_ms, ..
+            } => {
+                let model = in_flight.remove(&**task_id).unwrap_or("unknown");
+                if let Some(ttft) = ttft_ms {
+                    samples.entry(model.to_string()).or_default().push(*ttft);
+                }
+            }
+            _ => {}
+        }
+    }
+    samples
+}
+
+/// TTFT statistics per model, sorted by model name
+pub fn ttft_by_model(events: &[Event]) -> Vec<TtftStats> {
+    ttft_samples(events)
+        .into_iter()
+        .filter_map(|(model, samples)| TtftStats::from_samples(model, &samples))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id,
+            kind,
+        }
+    }
+
+    fn call(id: u64, task: &str, model: &str, ttft_ms: Option<u64>) -> [Event; 2] {
+        [
+            event(
+                id,
+                EventKind::ProviderCalled {
+                    task_id: task.into(),
+                    provider: "claude".into(),
+                    model: model.into(),
+                    prompt_len: 10,
+                },
+            ),
+            event(
+                id + 1,
+                EventKind::ProviderResponded {
+                    task_id: task.into(),
+                    request_id: None,
+                    input_tokens: 1,
+                    output_tokens: 1,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                    ttft_ms,
+                    finish_reason: "stop".into(),
+                    cost_usd: 0.0,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let sorted: Vec<u64> = (1..=20).collect();
+        assert_eq!(percentile(&sorted, 50), Some(10));
+        assert_eq!(percentile(&sorted, 95), Some(19));
+        assert_eq!(percentile(&[7], 95), Some(7));
+        assert_eq!(percentile(&[], 95), None);
+    }
+
+    #[test]
+    fn test_ttft_grouped_by_model() {
+        let mut events = Vec::new();
+        events.extend(call(0, "a", "fast", Some(100)));
+        events.extend(call(2, "b", "slow", Some(900)));
+        events.extend(call(4, "a", "fast", Some(300)));
+        // Non-streaming call: no sample
+        events.extend(call(6, "c", "slow", None));
+
+        let stats = ttft_by_model(&events);
+        assert_eq!(
+            stats,
+            vec![
+                TtftStats {
+                    model: "fast".into(),
+                    calls: 2,
+                    p50_ms: 100,
+                    p95_ms: 300,
+                    max_ms: 300,
+                },
+                TtftStats {
+                    model: "slow".into(),
+                    calls: 1,
+                    p50_ms: 900,
+                    p95_ms: 900,
+                    max_ms: 900,
+                },
+            ]
+        );
+    }
+}
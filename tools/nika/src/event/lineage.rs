@@ -0,0 +1,505 @@
+//! Run Lineage - traces as a property graph (v0.7)
+//!
+//! Turns recorded runs into nodes and relationships for a graph database:
+//!
+//! | Node       | Key    | From                                         |
+//! |------------|--------|----------------------------------------------|
+//! | `Run`      | `id`   | `WorkflowStarted` (generation ID)            |
+//! | `Workflow` | `hash` | `WorkflowStarted.workflow_hash`              |
+//! | `Task`     | `id`   | `TaskStarted` (`<generation>/<task>`)        |
+//! | `Model`    | `name` | `ProviderCalled`                             |
+//! | `Source`   | `url`  | `SourceFetched`                              |
+//! | `Artifact` | `path` | `ArtifactWritten`                            |
+//!
+//! Relationships: `(Run)-[:OF_WORKFLOW]->(Workflow)`,
+//! `(Run)-[:HAS_TASK]->(Task)`, `(Task)-[:USED_MODEL]->(Model)`,
+//! `(Task)-[:FETCHED]->(Source)`, `(Task)-[:WROTE]->(Artifact)` and
+//! `(Task)-[:USED_OUTPUT_OF]->(Task)` for `use:` bindings.
+//!
+//! Output is either Cypher (`MERGE` statements, safe to re-import) or
+//! GraphML with `labels`/`label` keys as read by `apoc.import.graphml`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+use super::{Event, EventKind};
+
+/// A node, identified by its label and key property
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub label: &'static str,
+    pub key: String,
+    pub props: BTreeMap<&'static str, Value>,
+}
+
+impl Node {
+    /// Name of the property that identifies nodes with this label
+    pub fn key_name(&self) -> &'static str {
+        match self.label {
+            "Workflow" => "hash",
+            "Model" => "name",
+            "Source" => "url",
+            "Artifact" => "path",
+            _ => "id",
+        }
+    }
+}
+
+/// A directed relationship between two nodes (indices into `nodes`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub rel: &'static str,
+    pub props: BTreeMap<&'static str, Value>,
+}
+
+/// Lineage of one or more runs
+#[derive(Debug, Clone, Default)]
+pub struct Lineage {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    index: FxHashMap<(&'static str, String), usize>,
+    edge_index: FxHashMap<(usize, &'static str, usize), usize>,
+}
+
+impl Lineage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a run's events; `started_at` (RFC 3339) is stored on the `Run`
+    pub fn add_run(&mut self, generation_id: &str, started_at: Option<&str>, events: &[Event]) {
+        let run = self.node("Run", generation_id);
+        if let Some(started_at) = started_at {
+            self.set(run, "started_at", started_at);
+        }
+        let task_key = |task_id: &str| format!("{}/{}", generation_id, task_id);
+
+        for event in events {
+            match &event.kind {
+                EventKind::WorkflowStarted {
+                    workflow_hash,
+                    nika_version,
+                    ..
+                } => {
+                    self.set(run, "nika_version", nika_version.as_str());
+                    let workflow = self.node("Workflow", workflow_hash);
+                    self.edge(run, "OF_WORKFLOW", workflow);
+                }
+                EventKind::RunLabeled { labels } => {
+                    let labels: Vec<String> =
+                        labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+                    self.set(run, "labels", labels.join(","));
+                }
+                EventKind::WorkflowCompleted {
+                    total_duration_ms, ..
+                } => {
+                    self.set(run, "status", "completed");
+                    self.set(run, "duration_ms", *total_duration_ms);
+                }
+                EventKind::WorkflowFailed { .. } => self.set(run, "status", "failed"),
+                EventKind::WorkflowAborted { .. } => self.set(run, "status", "aborted"),
+                EventKind::TaskStarted { task_id, verb, .. } => {
+                    let task = self.node("Task", &task_key(task_id));
+                    self.set(task, "name", task_id.as_ref());
+                    self.set(task, "verb", verb.as_ref());
+                    self.set(task, "run", generation_id);
+                    self.set(task, "status", "running");
+                    self.edge(run, "HAS_TASK", task);
+                }
+                EventKind::TaskCompleted {
+                    task_id,
+                    duration_ms,
+                    ..
+                } => {
+                    let task = self.node("Task", &task_key(task_id));
+                    self.set(task, "status", "completed");
+                    self.set(task, "duration_ms", *duration_ms);
+                }
+                EventKind::TaskFailed { task_id, .. } => {
+                    let task = self.node("Task", &task_key(task_id));
+                    self.set(task, "status", "failed");
+                }
+                EventKind::ProviderCalled {
+                    task_id,
+                    provider,
+                    model,
+                    ..
+                } if !model.is_empty() => {
+                    let task = self.node("Task", &task_key(task_id));
+                    let model_node = self.node("Model", model);
+                    self.set(model_node, "provider", provider.as_str());
+                    self.edge(task, "USED_MODEL", model_node);
+                }
+                EventKind::SourceFetched {
+                    task_id,
+                    url,
+                    retrieved_at,
+                    license,
+                    attribution,
+                    ..
+                } => {
+                    let task = self.node("Task", &task_key(task_id));
+                    let source = self.node("Source", url);
+                    if let Some(license) = license {
+                        self.set(source, "license", license.as_str());
+                    }
+                    if let Some(author) = attribution {
+                        self.set(source, "attribution", author.as_str());
+                    }
+                    let edge = self.edge(task, "FETCHED", source);
+                    self.edges[edge]
+                        .props
+                        .insert("retrieved_at", retrieved_at.as_str().into());
+                }
+                EventKind::ArtifactWritten {
+                    task_id,
+                    path,
+                    format,
+                } => {
+                    let task = self.node("Task", &task_key(task_id));
+                    let artifact = self.node("Artifact", path);
+                    self.set(artifact, "format", format.as_str());
+                    self.edge(task, "WROTE", artifact);
+                }
+                EventKind::BindingResolved {
+                    task_id,
+                    source_task: Some(source),
+                    ..
+                } => {
+                    let task = self.node("Task", &task_key(task_id));
+                    let upstream = self.node("Task", &task_key(source));
+                    self.edge(task, "USED_OUTPUT_OF", upstream);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Index of the node, created on first use
+    fn node(&mut self, label: &'static str, key: &str) -> usize {
+        if let Some(&i) = self.index.get(&(label, key.to_string())) {
+            return i;
+        }
+        self.nodes.push(Node {
+            label,
+            key: key.to_string(),
+            props: BTreeMap::new(),
+        });
+        self.index
+            .insert((label, key.to_string()), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    fn set(&mut self, node: usize, name: &'static str, value: impl Into<Value>) {
+        self.nodes[node].props.insert(name, value.into());
+    }
+
+    /// Index of the edge, added once per (from, rel, to)
+    fn edge(&mut self, from: usize, rel: &'static str, to: usize) -> usize {
+        if let Some(&i) = self.edge_index.get(&(from, rel, to)) {
+            return i;
+        }
+        self.edges.push(Edge {
+            from,
+            to,
+            rel,
+            props: BTreeMap::new(),
+        });
+        self.edge_index
+            .insert((from, rel, to), self.edges.len() - 1);
+        self.edges.len() - 1
+    }
+
+    /// Cypher `MERGE` statements, one per line
+    pub fn to_cypher(&self) -> String {
+        let mut out = String::new();
+        for node in &self.nodes {
+            let _ = write!(
+                out,
+                "MERGE (n:{} {{{}: {}}})",
+                node.label,
+                node.key_name(),
+                cypher_value(&Value::from(node.key.as_str()))
+            );
+            if !node.props.is_empty() {
+                let _ = write!(out, " SET {}", cypher_assignments("n", &node.props));
+            }
+            out.push_str(";\n");
+        }
+        for edge in &self.edges {
+            let (from, to) = (&self.nodes[edge.from], &self.nodes[edge.to]);
+            let _ = write!(
+                out,
+                "MATCH (a:{} {{{}: {}}}), (b:{} {{{}: {}}}) MERGE (a)-[r:{}]->(b)",
+                from.label,
+                from.key_name(),
+                cypher_value(&Value::from(from.key.as_str())),
+                to.label,
+                to.key_name(),
+                cypher_value(&Value::from(to.key.as_str())),
+                edge.rel
+            );
+            if !edge.props.is_empty() {
+                let _ = write!(out, " SET {}", cypher_assignments("r", &edge.props));
+            }
+            out.push_str(";\n");
+        }
+        out
+    }
+
+    /// GraphML document
+    pub fn to_graphml(&self) -> String {
+        // One <key> per property name; integers are `long`, the rest `string`
+        let mut node_keys: BTreeMap<&str, &str> = BTreeMap::new();
+        for node in &self.nodes {
+            node_keys.insert(node.key_name(), "string");
+            for (name, value) in &node.props {
+                let kind = if value.is_i64() || value.is_u64() {
+                    "long"
+                } else {
+                    "string"
+                };
+                node_keys
+                    .entry(name)
+                    .and_modify(|k| {
+                        if *k != kind {
+                            *k = "string";
+                        }
+                    })
+                    .or_insert(kind);
+            }
+        }
+        let mut edge_keys: BTreeMap<&str, &str> = BTreeMap::new();
+        for edge in &self.edges {
+            for name in edge.props.keys() {
+                edge_keys.insert(name, "string");
+            }
+        }
+
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <key id=\"labels\" for=\"node\" attr.name=\"labels\" attr.type=\"string\"/>\n  \
+             <key id=\"label\" for=\"edge\" attr.name=\"label\" attr.type=\"string\"/>\n",
+        );
+        for (name, kind) in &node_keys {
+            let _ = writeln!(
+                out,
+                "  <key id=\"n_{0}\" for=\"node\" attr.name=\"{0}\" attr.type=\"{1}\"/>",
+                name, kind
+            );
+        }
+        for (name, kind) in &edge_keys {
+            let _ = writeln!(
+                out,
+                "  <key id=\"e_{0}\" for=\"edge\" attr.name=\"{0}\" attr.type=\"{1}\"/>",
+                name, kind
+            );
+        }
+        out.push_str("  <graph id=\"lineage\" edgedefault=\"directed\">\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let _ = write!(
+                out,
+                "    <node id=\"n{}\" labels=\":{}\"><data key=\"labels\">:{}</data>",
+                i, node.label, node.label
+            );
+            let _ = write!(
+                out,
+                "<data key=\"n_{}\">{}</data>",
+                node.key_name(),
+                xml_escape(&node.key)
+            );
+            for (name, value) in &node.props {
+                let _ = write!(
+                    out,
+                    "<data key=\"n_{}\">{}</data>",
+                    name,
+                    xml_escape(&plain(value))
+                );
+            }
+            out.push_str("</node>\n");
+        }
+        for (i, edge) in self.edges.iter().enumerate() {
+            let _ = write!(
+                out,
+                "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\" label=\"{}\"><data key=\"label\">{}</data>",
+                i, edge.from, edge.to, edge.rel, edge.rel
+            );
+            for (name, value) in &edge.props {
+                let _ = write!(
+                    out,
+                    "<data key=\"e_{}\">{}</data>",
+                    name,
+                    xml_escape(&plain(value))
+                );
+            }
+            out.push_str("</edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+}
+
+fn cypher_assignments(var: &str, props: &BTreeMap<&'static str, Value>) -> String {
+    props
+        .iter()
+        .map(|(name, value)| format!("{}.{} = {}", var, name, cypher_value(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Cypher literal: JSON string escapes are valid Cypher string escapes
+fn cypher_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => serde_json::to_string(s).unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn event(kind: EventKind) -> Event {
+        Event {
+            id: 0,
+            timestamp_ms: 0,
+            kind,
+        }
+    }
+
+    fn run() -> Vec<Event> {
+        vec![
+            event(EventKind::WorkflowStarted {
+                task_count: 2,
+                generation_id: "gen-1".to_string(),
+                workflow_hash: "xxh3:abc".to_string(),
+                workflow_name: None,
+                nika_version: "0.7.0".to_string(),
+            }),
+            event(EventKind::TaskStarted {
+                task_id: "research".into(),
+                verb: "fetch".into(),
+                inputs: json!({}),
+            }),
+            event(EventKind::SourceFetched {
+                task_id: "research".into(),
+                url: "https://example.com/a\"b".to_string(),
+                retrieved_at: "2026-10-17T09:00:00+00:00".to_string(),
+                status: 200,
+                content_type: None,
+                license: Some("CC-BY-4.0".to_string()),
+                attribution: None,
+            }),
+            event(EventKind::TaskStarted {
+                task_id: "report".into(),
+                verb: "infer".into(),
+                inputs: json!({}),
+            }),
+            event(EventKind::BindingResolved {
+                task_id: "report".into(),
+                alias: "notes".into(),
+                source_task: Some("research".into()),
+                path: "research".into(),
+                source_event_id: None,
+                used_default: false,
+                lazy: false,
+            }),
+            event(EventKind::ProviderCalled {
+                task_id: "report".into(),
+                provider: "claude".into(),
+                model: "claude-sonnet-4".into(),
+                prompt_len: 10,
+            }),
+            event(EventKind::ArtifactWritten {
+                task_id: "report".into(),
+                path: "/out/report.csv".to_string(),
+                format: "csv".to_string(),
+            }),
+            event(EventKind::TaskCompleted {
+                task_id: "report".into(),
+                output: Arc::new(json!("ok")),
+                duration_ms: 1200,
+            }),
+        ]
+    }
+
+    #[test]
+    fn lineage_nodes_and_edges() {
+        let mut lineage = Lineage::new();
+        lineage.add_run("gen-1", Some("2026-10-17T09:00:00+00:00"), &run());
+
+        let labels: Vec<&str> = lineage.nodes.iter().map(|n| n.label).collect();
+        assert_eq!(
+            labels,
+            ["Run", "Workflow", "Task", "Source", "Task", "Model", "Artifact"]
+        );
+        let rels: Vec<&str> = lineage.edges.iter().map(|e| e.rel).collect();
+        assert_eq!(
+            rels,
+            [
+                "OF_WORKFLOW",
+                "HAS_TASK",
+                "FETCHED",
+                "HAS_TASK",
+                "USED_OUTPUT_OF",
+                "USED_MODEL",
+                "WROTE"
+            ]
+        );
+        assert_eq!(lineage.nodes[4].key, "gen-1/report");
+        assert_eq!(lineage.nodes[4].props["duration_ms"], json!(1200));
+
+        // A second run shares the workflow and model nodes
+        lineage.add_run("gen-2", None, &run());
+        let models = lineage.nodes.iter().filter(|n| n.label == "Model").count();
+        assert_eq!(models, 1);
+        assert_eq!(lineage.nodes.iter().filter(|n| n.label == "Run").count(), 2);
+    }
+
+    #[test]
+    fn lineage_cypher_and_graphml() {
+        let mut lineage = Lineage::new();
+        lineage.add_run("gen-1", Some("2026-10-17T09:00:00+00:00"), &run());
+
+        let cypher = lineage.to_cypher();
+        assert!(cypher.contains(
+            "MERGE (n:Run {id: \"gen-1\"}) SET n.nika_version = \"0.7.0\", n.started_at = \"2026-10-17T09:00:00+00:00\";"
+        ));
+        assert!(cypher.contains("MERGE (n:Source {url: \"https://example.com/a\\\"b\"})"));
+        assert!(cypher.contains(
+            "MATCH (a:Task {id: \"gen-1/report\"}), (b:Model {name: \"claude-sonnet-4\"}) MERGE (a)-[r:USED_MODEL]->(b);"
+        ));
+        assert!(cypher.contains("MERGE (a)-[r:FETCHED]->(b) SET r.retrieved_at = "));
+
+        let graphml = lineage.to_graphml();
+        assert!(graphml.contains(
+            "<key id=\"n_duration_ms\" for=\"node\" attr.name=\"duration_ms\" attr.type=\"long\"/>"
+        ));
+        assert!(graphml.contains("<data key=\"n_url\">https://example.com/a&quot;b</data>"));
+        assert!(graphml.contains("label=\"WROTE\"><data key=\"label\">WROTE</data>"));
+        assert!(graphml.trim_end().ends_with("</graphml>"));
+    }
+}
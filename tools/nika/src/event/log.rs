@@ -9,6 +9,7 @@
 //! - Added `AgentTurnMetadata` for reasoning capture (thinking, tokens, stop_reason)
 //! - Updated `AgentTurn` variant to include optional metadata
 
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -19,6 +20,9 @@ use tokio::sync::broadcast;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::transcript::AgentTranscript;
+use crate::util::SmartString;
+
 // ═══════════════════════════════════════════════════════════════
 // Helper structs for ContextAssembled event
 // ═══════════════════════════════════════════════════════════════
@@ -79,6 +83,10 @@ pub struct AgentTurnMetadata {
     #[serde(default)]
     pub cache_read_tokens: u32,
 
+    /// Cache write tokens (Anthropic prompt caching, v0.7)
+    #[serde(default)]
+    pub cache_write_tokens: u32,
+
     /// Stop reason: "end_turn", "tool_use", "max_tokens", "stop_sequence"
     pub stop_reason: String,
 }
@@ -92,6 +100,7 @@ impl AgentTurnMetadata {
             input_tokens: 0,
             output_tokens: 0,
             cache_read_tokens: 0,
+            cache_write_tokens: 0,
             stop_reason: stop_reason.into(),
         }
     }
@@ -109,6 +118,7 @@ impl AgentTurnMetadata {
             input_tokens,
             output_tokens,
             cache_read_tokens: 0,
+            cache_write_tokens: 0,
             stop_reason: stop_reason.into(),
         }
     }
@@ -124,6 +134,29 @@ impl AgentTurnMetadata {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════
+// TaskPhase for sub-task timing (v0.7)
+// ═══════════════════════════════════════════════════════════════
+
+/// Sub-task phase reported by `PhaseCompleted` (v0.7, `nika run --phases`)
+///
+/// Each phase's duration runs from the end of the previous one, so the
+/// phases of a task add up to its wall-clock time.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPhase {
+    /// use: bindings resolved (task scheduled → inputs ready)
+    BindingsResolved,
+    /// Prompt rendered and provider request sent
+    ProviderRequestSent,
+    /// First streamed token arrived (time to first token)
+    FirstTokenReceived,
+    /// Output policy applied (format parsing, schema validation)
+    OutputValidated,
+    /// Result written to the datastore
+    ResultStored,
+}
+
 /// Single event in the workflow execution log
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Event {
@@ -137,7 +170,9 @@ pub struct Event {
 
 /// All possible event types (3 levels)
 ///
-/// Uses Arc<str> for task_id fields to enable zero-cost cloning.
+/// Uses Arc<str> for task_id fields to enable zero-cost cloning, and
+/// [`SmartString`] for short labels (providers, models, MCP servers...) so
+/// replaying a trace does not allocate them.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum EventKind {
@@ -150,9 +185,49 @@ pub enum EventKind {
         generation_id: String,
         /// Hash of workflow file for cache invalidation
         workflow_hash: String,
+        /// Workflow name from the `workflow:` key (v0.7, None if unnamed or recorded before)
+        #[serde(default)]
+        workflow_name: Option<String>,
         /// Nika version
         nika_version: String,
     },
+    /// Labels attached to this run, e.g. the `nika run --matrix` combination (v0.7)
+    RunLabeled { labels: BTreeMap<String, String> },
+    /// A provider or MCP server probed by `preflight: true` (v0.7)
+    PreflightChecked {
+        /// `provider/model` or `mcp:server`
+        target: String,
+        latency_ms: u64,
+        /// Why the probe failed (None: reachable and authorized)
+        error: Option<String>,
+    },
+    /// Warm-session pool counters for the run, emitted before completion (v0.7)
+    SessionPoolReport {
+        /// Provider calls served by a pre-warmed session
+        hits: u64,
+        /// Provider calls that built a session (cold start)
+        misses: u64,
+        /// Sessions built ahead of time
+        warmed: u64,
+        /// Configured pool size
+        capacity: usize,
+    },
+    /// A provider's in-flight limit moved after throttling or a run of successes (v0.7)
+    ConcurrencyAdjusted {
+        provider: SmartString,
+        previous: usize,
+        limit: usize,
+    },
+    /// A `[key_rotation]` key was taken out of rotation (v0.7)
+    ApiKeyQuarantined {
+        provider: String,
+        /// Provider and position (`claude#2`), never the key itself
+        key: String,
+        /// `auth`, `quota` or `rate_limited`
+        reason: String,
+        /// None: for the rest of the process
+        seconds: Option<u64>,
+    },
     WorkflowCompleted {
         final_output: Arc<Value>,
         total_duration_ms: u64,
@@ -182,6 +257,24 @@ pub enum EventKind {
         task_id: Arc<str>,
         dependencies: Vec<Arc<str>>,
     },
+    /// A use: binding was resolved, with the task/path that produced it (v0.7)
+    ///
+    /// Emitted once per alias, just before TaskStarted.
+    BindingResolved {
+        task_id: Arc<str>,
+        /// Alias as referenced in templates (`{{use.alias}}`)
+        alias: SmartString,
+        /// Task that produced the value (None for for_each items)
+        source_task: Option<Arc<str>>,
+        /// Binding path (e.g. `weather.summary`, or `for_each[2]`)
+        path: SmartString,
+        /// ID of the source task's TaskCompleted event (its offset in the log)
+        source_event_id: Option<u64>,
+        /// Value fell back to the `?? default`
+        used_default: bool,
+        /// Lazy binding (resolved on first access, not here)
+        lazy: bool,
+    },
     /// Task execution begins with resolved inputs from use: block
     TaskStarted {
         task_id: Arc<str>,
@@ -200,6 +293,47 @@ pub enum EventKind {
         error: String,
         duration_ms: u64,
     },
+    /// Task not run because none of its incoming flows was taken (v0.7)
+    ///
+    /// Emitted for the unselected side of a `when:` branch, and cascades
+    /// to tasks that only depend on skipped tasks.
+    TaskSkipped {
+        task_id: Arc<str>,
+        /// Why, e.g. `review -> publish: when "{{from.status}} == 'approved'" is false`
+        reason: String,
+    },
+    /// A sub-task phase finished (v0.7, only with `nika run --phases`)
+    PhaseCompleted {
+        task_id: Arc<str>,
+        phase: TaskPhase,
+        /// Time spent in this phase (ms)
+        duration_ms: u64,
+    },
+    /// A debugger command changed how a task ran (v0.7, `nika debug`)
+    TaskDebugged {
+        task_id: Arc<str>,
+        /// `edit_prompt`, `skip` or `inject_output`
+        action: String,
+    },
+    /// An `approve:` task or agent tool call is waiting for a decision (v0.7)
+    ApprovalRequested {
+        task_id: Arc<str>,
+        /// Resolved question
+        prompt: String,
+        /// Seconds before the `default:` decision applies
+        timeout_secs: Option<u64>,
+    },
+    /// An `approve:` task or agent tool call was approved (v0.7)
+    ApprovalGranted {
+        task_id: Arc<str>,
+        /// `cli`, `tui`, `timeout`, `default`, or `checkpoint` (resumed run)
+        by: String,
+    },
+    /// An `approve:` task or agent tool call was rejected (v0.7)
+    ///
+    /// Tool calls are also refused `by` `policy` (`permission = "deny"`)
+    /// or `unavailable` (no one could be asked).
+    ApprovalDenied { task_id: Arc<str>, by: String },
 
     // ═══════════════════════════════════════════
     // FINE-GRAINED (template/provider)
@@ -209,10 +343,20 @@ pub enum EventKind {
         template: String,
         result: String,
     },
+    /// `model: auto` was resolved by the model router (v0.7)
+    ModelRouted {
+        task_id: Arc<str>,
+        /// `cheap` or `premium`
+        tier: SmartString,
+        /// Chosen model (`default` = provider default)
+        model: SmartString,
+        /// Matching rule, e.g. `rule 1 (min_prompt_chars = 8000)`, or `default`
+        reason: String,
+    },
     ProviderCalled {
         task_id: Arc<str>,
-        provider: String,
-        model: String,
+        provider: SmartString,
+        model: SmartString,
         prompt_len: usize,
     },
     ProviderResponded {
@@ -225,13 +369,67 @@ pub enum EventKind {
         output_tokens: u32,
         /// Cache read tokens (if any)
         cache_read_tokens: u32,
+        /// Cache write tokens (Anthropic prompt caching, v0.7)
+        #[serde(default)]
+        cache_write_tokens: u32,
         /// Time to first token (ms), if known
         ttft_ms: Option<u64>,
         /// Finish reason
-        finish_reason: String,
+        finish_reason: SmartString,
         /// Estimated cost in USD
         cost_usd: f64,
     },
+    /// A `fetch:` task retrieved external content (v0.7)
+    SourceFetched {
+        task_id: Arc<str>,
+        /// Final URL, after redirects
+        url: String,
+        /// Retrieval time (RFC 3339)
+        retrieved_at: String,
+        status: u16,
+        content_type: Option<String>,
+        /// License the source declares (Link header, HTML meta or JSON)
+        license: Option<String>,
+        /// Author the source declares
+        attribution: Option<String>,
+    },
+    /// A `fetch:` GET request went through the HTTP cache (v0.7)
+    HttpCacheLookup {
+        task_id: Arc<str>,
+        /// Requested URL
+        url: String,
+        /// `hit` (fresh), `revalidated` (304) or `miss`
+        outcome: String,
+        /// Responses served from the cache so far in this run
+        hits: u64,
+        /// Responses fetched from the server so far in this run
+        misses: u64,
+    },
+    /// An `export:` task wrote a file (v0.7)
+    ArtifactWritten {
+        task_id: Arc<str>,
+        /// Absolute path when it can be resolved
+        path: String,
+        /// File format, e.g. `csv` or `xlsx`
+        format: String,
+    },
+    /// One line of output from an `exec: { host }` command, as it arrives (v0.7)
+    ExecOutput {
+        task_id: Arc<str>,
+        /// `[hosts]` entry the command runs on
+        host: String,
+        /// `stdout` or `stderr`
+        stream: String,
+        line: String,
+    },
+    /// One message received by a `ws:` task, as it arrives (v0.7)
+    WsMessage {
+        task_id: Arc<str>,
+        /// 0-based position in the task's output
+        index: usize,
+        /// Message text (JSON messages as sent)
+        message: String,
+    },
 
     // ═══════════════════════════════════════════
     // CONTEXT ASSEMBLY (v0.2)
@@ -250,6 +448,57 @@ pub enum EventKind {
         /// Was context truncated?
         truncated: bool,
     },
+    /// A task drew from or returned to the workflow's `context_pool:` (v0.7)
+    ContextPoolUpdated {
+        task_id: Arc<str>,
+        /// `reserved`, `summarized`, `queued`, `released` or `exhausted`
+        action: String,
+        /// Reservation (or, for `released`, the tokens charged) of the task
+        tokens: u64,
+        /// Tokens charged by finished tasks
+        used: u64,
+        /// Tokens held by running tasks
+        reserved: u64,
+        capacity: u64,
+        /// Tasks waiting for tokens
+        waiting: usize,
+    },
+
+    // ═══════════════════════════════════════════
+    // SECURITY EVENTS (v0.7)
+    // ═══════════════════════════════════════════
+    /// Untrusted content tripped the prompt-injection heuristics
+    SecurityWarning {
+        task_id: Arc<str>,
+        /// Where the content came from: `use.<alias>` or `tool:<name>`
+        source: String,
+        /// Matched rules, e.g. `ignore-instructions`
+        rules: Vec<String>,
+        /// Action taken: `flag`, `strip` or `wrap`
+        action: String,
+    },
+    /// A `moderate:` gate classified a task input or output (v0.7)
+    ModerationChecked {
+        task_id: Arc<str>,
+        /// `input` or `output`
+        stage: String,
+        /// `local`, `openai` or `mistral`
+        classifier: String,
+        /// Highest score per category
+        scores: BTreeMap<String, f64>,
+        /// Categories at or over their threshold
+        flagged: Vec<String>,
+        /// `pass`, or the policy applied: `block`, `flag` or `redact`
+        action: String,
+    },
+    /// `.nika/policy.yaml` blocked an agent tool call or `exec:` command (v0.7)
+    ToolBlocked {
+        task_id: Arc<str>,
+        /// Tool name, or `exec`
+        tool: String,
+        /// The rule that matched, e.g. `tools.deny 'github_delete_*' matches ...`
+        rule: String,
+    },
 
     // ═══════════════════════════════════════════
     // MCP EVENTS (v0.2, enhanced v0.5.2)
@@ -258,9 +507,9 @@ pub enum EventKind {
     McpInvoke {
         task_id: Arc<str>,
         /// Unique call ID for correlating with McpResponse
-        call_id: String,
-        mcp_server: String,
-        tool: Option<String>,
+        call_id: SmartString,
+        mcp_server: SmartString,
+        tool: Option<SmartString>,
         resource: Option<String>,
         /// Full params passed to MCP tool (for TUI display)
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -270,7 +519,7 @@ pub enum EventKind {
     McpResponse {
         task_id: Arc<str>,
         /// Correlates with McpInvoke.call_id
-        call_id: String,
+        call_id: SmartString,
         output_len: usize,
         /// Duration of MCP call in milliseconds
         duration_ms: u64,
@@ -285,12 +534,12 @@ pub enum EventKind {
     /// MCP server connection established (v0.7.0)
     McpConnected {
         /// Name of the connected MCP server
-        server_name: String,
+        server_name: SmartString,
     },
     /// MCP server connection failed (v0.7.0)
     McpError {
         /// Name of the MCP server
-        server_name: String,
+        server_name: SmartString,
         /// Error description
         error: String,
     },
@@ -320,11 +569,30 @@ pub enum EventKind {
         #[serde(skip_serializing_if = "Option::is_none")]
         metadata: Option<AgentTurnMetadata>,
     },
+    /// Several tool calls of one agent turn ran concurrently (v0.7)
+    AgentToolBatch {
+        task_id: Arc<str>,
+        /// Tool calls in the turn
+        calls: usize,
+        /// Calls allowed to run at once (`tool_concurrency`)
+        concurrency: usize,
+        /// From the first call's start to the last call's end
+        wall_ms: u64,
+        /// Sum of the calls' own durations (the sequential cost)
+        sequential_ms: u64,
+        /// Latency win: `sequential_ms - wall_ms`
+        saved_ms: u64,
+    },
+    /// Full conversation of an agent loop, emitted when it ends (v0.7)
+    AgentTranscript {
+        task_id: Arc<str>,
+        transcript: AgentTranscript,
+    },
     /// Agent loop completed (reached stop condition or max turns)
     AgentComplete {
         task_id: Arc<str>,
         turns: u32,
-        stop_reason: String,
+        stop_reason: SmartString,
     },
 
     // ═══════════════════════════════════════════
@@ -347,21 +615,45 @@ impl EventKind {
     pub fn task_id(&self) -> Option<&str> {
         match self {
             Self::TaskScheduled { task_id, .. }
+            | Self::BindingResolved { task_id, .. }
             | Self::TaskStarted { task_id, .. }
             | Self::TaskCompleted { task_id, .. }
             | Self::TaskFailed { task_id, .. }
+            | Self::TaskSkipped { task_id, .. }
+            | Self::PhaseCompleted { task_id, .. }
+            | Self::TaskDebugged { task_id, .. }
+            | Self::ApprovalRequested { task_id, .. }
+            | Self::ApprovalGranted { task_id, .. }
+            | Self::ApprovalDenied { task_id, .. }
             | Self::TemplateResolved { task_id, .. }
+            | Self::ModelRouted { task_id, .. }
             | Self::ProviderCalled { task_id, .. }
             | Self::ProviderResponded { task_id, .. }
+            | Self::SourceFetched { task_id, .. }
+            | Self::HttpCacheLookup { task_id, .. }
+            | Self::ArtifactWritten { task_id, .. }
+            | Self::ExecOutput { task_id, .. }
+            | Self::WsMessage { task_id, .. }
             | Self::ContextAssembled { task_id, .. }
+            | Self::ContextPoolUpdated { task_id, .. }
+            | Self::SecurityWarning { task_id, .. }
+            | Self::ModerationChecked { task_id, .. }
+            | Self::ToolBlocked { task_id, .. }
             | Self::McpInvoke { task_id, .. }
             | Self::McpResponse { task_id, .. }
             | Self::AgentStart { task_id, .. }
             | Self::AgentTurn { task_id, .. }
+            | Self::AgentToolBatch { task_id, .. }
+            | Self::AgentTranscript { task_id, .. }
             | Self::AgentComplete { task_id, .. } => Some(task_id),
             // AgentSpawned uses parent_task_id as the primary task reference
             Self::AgentSpawned { parent_task_id, .. } => Some(parent_task_id),
             Self::WorkflowStarted { .. }
+            | Self::RunLabeled { .. }
+            | Self::PreflightChecked { .. }
+            | Self::SessionPoolReport { .. }
+            | Self::ConcurrencyAdjusted { .. }
+            | Self::ApiKeyQuarantined { .. }
             | Self::WorkflowCompleted { .. }
             | Self::WorkflowFailed { .. }
             | Self::WorkflowAborted { .. }
@@ -378,6 +670,11 @@ impl EventKind {
         matches!(
             self,
             Self::WorkflowStarted { .. }
+                | Self::RunLabeled { .. }
+                | Self::PreflightChecked { .. }
+                | Self::SessionPoolReport { .. }
+                | Self::ConcurrencyAdjusted { .. }
+                | Self::ApiKeyQuarantined { .. }
                 | Self::WorkflowCompleted { .. }
                 | Self::WorkflowFailed { .. }
                 | Self::WorkflowAborted { .. }
@@ -462,6 +759,12 @@ impl EventLog {
         self.events.read().clone()
     }
 
+    /// Last `n` events, or None if the log is being written (crash handlers, v0.7)
+    pub fn try_recent(&self, n: usize) -> Option<Vec<Event>> {
+        let events = self.events.try_read()?;
+        Some(events[events.len().saturating_sub(n)..].to_vec())
+    }
+
     /// Zero-copy access to events via callback
     ///
     /// Holds read lock for duration of callback - keep it short.
@@ -557,6 +860,7 @@ mod tests {
             task_count,
             generation_id: "test-gen-123".to_string(),
             workflow_hash: "abc123".to_string(),
+            workflow_name: None,
             nika_version: TEST_VERSION.to_string(),
         }
     }
@@ -569,8 +873,9 @@ mod tests {
             input_tokens,
             output_tokens,
             cache_read_tokens: 0,
+            cache_write_tokens: 0,
             ttft_ms: Some(150),
-            finish_reason: "stop".to_string(),
+            finish_reason: "stop".into(),
             cost_usd: 0.001,
         }
     }
@@ -870,6 +1175,7 @@ mod tests {
             task_count: 3,
             generation_id: "gen-abc-123".to_string(),
             workflow_hash: "sha256:deadbeef".to_string(),
+            workflow_name: None,
             nika_version: TEST_VERSION.to_string(),
         });
 
@@ -898,8 +1204,9 @@ mod tests {
             input_tokens: 500,
             output_tokens: 150,
             cache_read_tokens: 200,
+            cache_write_tokens: 0,
             ttft_ms: Some(85),
-            finish_reason: "stop".to_string(),
+            finish_reason: "stop".into(),
             cost_usd: 0.0025,
         });
 
@@ -1061,6 +1368,7 @@ mod tests {
             input_tokens: 200,
             output_tokens: 100,
             cache_read_tokens: 50,
+            cache_write_tokens: 0,
             stop_reason: "end_turn".to_string(),
         };
 
@@ -1093,6 +1401,7 @@ mod tests {
             input_tokens: 50,
             output_tokens: 25,
             cache_read_tokens: 0,
+            cache_write_tokens: 0,
             stop_reason: "end_turn".to_string(),
         };
         let json = serde_json::to_value(&metadata).unwrap();
@@ -0,0 +1,355 @@
+//! Run Metrics - Prometheus exposition of finished runs (v0.7)
+//!
+//! [`RunMetrics`] accumulates the events of every run a long-lived process
+//! finishes, and renders them with the gauges it is given in the
+//! Prometheus text format (version 0.0.4):
+//!
+//! | Metric | Type | Source |
+//! |--------|------|--------|
+//! | `nika_runs_total{outcome}` | counter | `WorkflowCompleted` / `WorkflowFailed` / `WorkflowAborted` |
+//! | `nika_task_failures_total` | counter | `TaskFailed` |
+//! | `nika_provider_errors_total{provider}` | counter | `TaskFailed` with a `ProviderCalled` unanswered |
+//! | `nika_task_duration_seconds` | histogram | `TaskCompleted` / `TaskFailed` |
+//! | `nika_provider_call_tokens` | histogram | `ProviderResponded` input + output |
+//! | `nika_active_runs` | gauge | [`Gauges`] |
+//! | `nika_mcp_connections` | gauge | [`Gauges`] |
+//!
+//! Used by `GET /metrics` of `nika daemon start --http`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use rustc_hash::FxHashMap;
+
+use super::{Event, EventKind};
+
+/// Upper bounds of the task duration buckets (seconds)
+const DURATION_BUCKETS: &[f64] = &[0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0];
+/// Upper bounds of the tokens-per-call buckets
+const TOKEN_BUCKETS: &[f64] = &[
+    100.0, 500.0, 1_000.0, 2_500.0, 5_000.0, 10_000.0, 25_000.0, 50_000.0, 100_000.0,
+];
+/// Run outcomes, always exported (zero until seen)
+const OUTCOMES: &[&str] = &["completed", "failed", "aborted"];
+
+/// Values read at scrape time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Gauges {
+    pub active_runs: usize,
+    pub mcp_connections: usize,
+}
+
+/// Counters and histograms over the runs recorded so far
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunMetrics {
+    /// Finished runs by outcome
+    runs: BTreeMap<&'static str, u64>,
+    task_failures: u64,
+    /// Failed provider calls by provider
+    provider_errors: BTreeMap<String, u64>,
+    task_duration: Histogram,
+    call_tokens: Histogram,
+}
+
+impl Default for RunMetrics {
+    fn default() -> Self {
+        Self {
+            runs: OUTCOMES.iter().map(|outcome| (*outcome, 0)).collect(),
+            task_failures: 0,
+            provider_errors: BTreeMap::new(),
+            task_duration: Histogram::new(DURATION_BUCKETS),
+            call_tokens: Histogram::new(TOKEN_BUCKETS),
+        }
+    }
+}
+
+impl RunMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the events of a finished run
+    ///
+    /// A run without a final workflow event (stopped mid-run) counts as
+    /// `failed`.
+    pub fn record_run(&mut self, events: &[Event]) {
+        // Provider of the call in flight, per task
+        let mut in_flight: FxHashMap<&str, &str> = FxHashMap::default();
+        let mut outcome = "failed";
+
+        for event in events {
+            match &event.kind {
+                EventKind::ProviderCalled {
+                    task_id, provider, ..
+                } => {
+                    in_flight.insert(task_id, provider);
+                }
+                EventKind::ProviderResponded {
+                    task_id,
+                    input_tokens,
+                    output_tokens,
+                    ..
+                } => {
+                    in_flight.remove(&**task_id);
+                    self.call_tokens
+                        .observe(f64::from(*input_tokens) + f64::from(*output_tokens));
+                }
+                EventKind::TaskCompleted { duration_ms, .. } => {
+                    self.task_duration.observe(*duration_ms as f64 / 1000.0);
+                }
+                EventKind::TaskFailed {
+                    task_id,
+                    duration_ms,
+                    ..
+                } => {
+                    self.task_failures += 1;
+                    self.task_duration.observe(*duration_ms as f64 / 1000.0);
+                    if let Some(provider) = in_flight.remove(&**task_id) {
+                        *self
+                            .provider_errors
+                            .entry(provider.to_string())
+                            .or_default() += 1;
+                    }
+                }
+                EventKind::WorkflowCompleted { .. } => outcome = "completed",
+                EventKind::WorkflowFailed { .. } => outcome = "failed",
+                EventKind::WorkflowAborted { .. } => outcome = "aborted",
+                _ => {}
+            }
+        }
+        *self.runs.entry(outcome).or_default() += 1;
+    }
+
+    /// Prometheus text exposition of the metrics and `gauges`
+    pub fn render(&self, gauges: Gauges) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "nika_runs_total",
+            "counter",
+            "Workflow runs finished, by outcome",
+        );
+        for (outcome, count) in &self.runs {
+            let _ = writeln!(out, "nika_runs_total{{outcome=\"{}\"}} {}", outcome, count);
+        }
+
+        header(
+            &mut out,
+            "nika_task_failures_total",
+            "counter",
+            "Tasks that failed",
+        );
+        let _ = writeln!(out, "nika_task_failures_total {}", self.task_failures);
+
+        header(
+            &mut out,
+            "nika_provider_errors_total",
+            "counter",
+            "Provider calls that failed their task, by provider",
+        );
+        for (provider, count) in &self.provider_errors {
+            let _ = writeln!(
+                out,
+                "nika_provider_errors_total{{provider=\"{}\"}} {}",
+                escape_label(provider),
+                count
+            );
+        }
+
+        header(
+            &mut out,
+            "nika_task_duration_seconds",
+            "histogram",
+            "Duration of completed and failed tasks",
+        );
+        self.task_duration
+            .render(&mut out, "nika_task_duration_seconds");
+
+        header(
+            &mut out,
+            "nika_provider_call_tokens",
+            "histogram",
+            "Input and output tokens per provider call",
+        );
+        self.call_tokens
+            .render(&mut out, "nika_provider_call_tokens");
+
+        header(&mut out, "nika_active_runs", "gauge", "Runs in progress");
+        let _ = writeln!(out, "nika_active_runs {}", gauges.active_runs);
+
+        header(
+            &mut out,
+            "nika_mcp_connections",
+            "gauge",
+            "Connected MCP servers",
+        );
+        let _ = writeln!(out, "nika_mcp_connections {}", gauges.mcp_connections);
+
+        out
+    }
+}
+
+/// Bucketed observations (counts per bucket, not cumulative)
+#[derive(Debug, Clone, PartialEq)]
+struct Histogram {
+    bounds: &'static [f64],
+    /// One count per bound, plus `+Inf`
+    counts: Vec<u64>,
+    sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+    }
+
+    fn render(&self, out: &mut String, name: &str) {
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+        }
+        let total: u64 = self.counts.iter().sum();
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, total);
+        let _ = writeln!(out, "{}_sum {}", name, self.sum);
+        let _ = writeln!(out, "{}_count {}", name, total);
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Label value with `\`, `"` and newlines escaped
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn run(kinds: Vec<EventKind>) -> Vec<Event> {
+        kinds
+            .into_iter()
+            .enumerate()
+            .map(|(id, kind)| Event {
+                id: id as u64,
+                timestamp_ms: id as u64,
+                kind,
+            })
+            .collect()
+    }
+
+    fn called(task: &str) -> EventKind {
+        EventKind::ProviderCalled {
+            task_id: task.into(),
+            provider: "claude".into(),
+            model: "claude-sonnet-4".into(),
+            prompt_len: 10,
+        }
+    }
+
+    fn failed(task: &str, duration_ms: u64) -> EventKind {
+        EventKind::TaskFailed {
+            task_id: task.into(),
+            error: "boom".into(),
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn test_counts_runs_failures_and_provider_errors() {
+        let mut metrics = RunMetrics::new();
+        metrics.record_run(&run(vec![
+            called("summarize"),
+            EventKind::ProviderResponded {
+                task_id: "summarize".into(),
+                request_id: None,
+                input_tokens: 700,
+                output_tokens: 300,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+                ttft_ms: None,
+                finish_reason: "end_turn".into(),
+                cost_usd: 0.0,
+            },
+            EventKind::TaskCompleted {
+                task_id: "summarize".into(),
+                output: Arc::new(json!("ok")),
+                duration_ms: 800,
+            },
+            EventKind::WorkflowCompleted {
+                final_output: Arc::new(json!("ok")),
+                total_duration_ms: 900,
+            },
+        ]));
+        // Provider call never answered, then an exec failure
+        metrics.record_run(&run(vec![
+            called("translate"),
+            failed("translate", 40_000),
+            failed("publish", 20),
+            EventKind::WorkflowFailed {
+                error: "boom".into(),
+                failed_task: Some("translate".into()),
+            },
+        ]));
+        // Stopped mid-run
+        metrics.record_run(&[]);
+
+        let text = metrics.render(Gauges {
+            active_runs: 2,
+            mcp_connections: 1,
+        });
+        for line in [
+            "nika_runs_total{outcome=\"aborted\"} 0",
+            "nika_runs_total{outcome=\"completed\"} 1",
+            "nika_runs_total{outcome=\"failed\"} 2",
+            "nika_task_failures_total 2",
+            "nika_provider_errors_total{provider=\"claude\"} 1",
+            "nika_task_duration_seconds_bucket{le=\"0.1\"} 1",
+            "nika_task_duration_seconds_bucket{le=\"1\"} 2",
+            "nika_task_duration_seconds_bucket{le=\"30\"} 2",
+            "nika_task_duration_seconds_bucket{le=\"60\"} 3",
+            "nika_task_duration_seconds_bucket{le=\"+Inf\"} 3",
+            "nika_task_duration_seconds_count 3",
+            "nika_provider_call_tokens_bucket{le=\"500\"} 0",
+            "nika_provider_call_tokens_bucket{le=\"1000\"} 1",
+            "nika_provider_call_tokens_sum 1000",
+            "nika_active_runs 2",
+            "nika_mcp_connections 1",
+            "# TYPE nika_task_duration_seconds histogram",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {line:?} in\n{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_escapes_label_values() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
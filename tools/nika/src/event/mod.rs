@@ -9,14 +9,51 @@
 //! - `NoopEmitter`: Zero-cost no-op for testing (v0.3)
 //! - `TraceWriter`: NDJSON file writer for debugging
 //! - `AgentTurnMetadata`: Agent turn response metadata (v0.4.1)
+//! - `DatasetFilter`: Fine-tuning dataset extraction from traces (v0.7)
+//! - `OtelEmitter`: OTLP span/metric exporter (v0.7, `http` feature)
+//! - `anonymize`: user data out of traces for bug reports (v0.7)
+//! - `cost`: provider spend and prompt cache savings of a run (v0.7)
+//! - `diff`: task-by-task comparison of two runs (v0.7)
+//! - `flame`: per-task phase profile / folded stacks from traces (v0.7)
+//! - `inspect`: DataStore snapshot at any point of a trace (v0.7)
+//! - `latency`: time-to-first-token p50/p95 per model (v0.7)
+//! - `lineage`: runs as a property graph (Cypher / GraphML) (v0.7)
+//! - `metrics`: Prometheus metrics of finished runs for `nika daemon --http` (v0.7)
+//! - `redact`: secret scrubbing for `nika run --share` (v0.7)
+//! - `report`: the JSON document of `nika run --output json` (v0.7)
+//! - `sources`: external sources fetched in a run, and per-task lineage (v0.7)
+//! - `stats`: per-workflow run history for `nika stats` (v0.7)
+//! - `transcript`: full agent conversations for `nika trace show --agent` (v0.7)
 
+pub mod anonymize;
+pub mod cost;
+pub mod dataset;
+pub mod diff;
 mod emitter;
+pub mod flame;
+pub mod inspect;
+pub mod latency;
+pub mod lineage;
 mod log;
+pub mod metrics;
+#[cfg(feature = "http")]
+mod otel;
+pub mod redact;
+pub mod report;
+pub mod sources;
+pub mod stats;
 mod trace;
+pub mod transcript;
 
 // Re-export all public types
 pub use emitter::{EventEmitter, NoopEmitter};
-pub use log::{AgentTurnMetadata, ContextSource, Event, EventKind, EventLog, ExcludedItem};
+pub use log::{
+    AgentTurnMetadata, ContextSource, Event, EventKind, EventLog, ExcludedItem, TaskPhase,
+};
+#[cfg(feature = "http")]
+pub use otel::{OtelConfig, OtelEmitter, OtelMetrics};
 pub use trace::{
-    calculate_workflow_hash, generate_generation_id, list_traces, TraceInfo, TraceWriter,
+    calculate_workflow_hash, generate_generation_id, list_traces, read_trace_events,
+    read_trace_version, replay_trace, trace_path, TraceInfo, TraceWriter,
 };
+pub use transcript::AgentTranscript;
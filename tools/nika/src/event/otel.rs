@@ -0,0 +1,936 @@
+//! OpenTelemetry Exporter (v0.7)
+//!
+//! `OtelEmitter` maps Nika events to OTLP spans and metrics and exports them
+//! over OTLP/HTTP (JSON encoding), so runs show up in Jaeger, Tempo, etc.
+//!
+//! Span hierarchy:
+//! ```text
+//! workflow
+//! └── task:<id>
+//!     ├── provider:<name>   (ProviderCalled → ProviderResponded)
+//!     └── mcp:<server>      (McpInvoke → McpResponse)
+//! ```
+//!
+//! Metrics are cumulative sums (tasks, tokens, cost, MCP calls, warm/cold
+//! provider sessions) plus a `nika.provider.ttft.p95` gauge per model and a
+//! `nika.provider.session.warm_hit_rate` gauge.
+//!
+//! Configuration uses the standard OTEL_ env vars:
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT` (base URL, `/v1/traces` + `/v1/metrics` appended)
+//! - `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` / `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT`
+//! - `OTEL_EXPORTER_OTLP_HEADERS` (`key=value,key2=value2`)
+//! - `OTEL_SERVICE_NAME` (default: `nika`)
+//! - `OTEL_RESOURCE_ATTRIBUTES` (`key=value,...`)
+//! - `OTEL_SDK_DISABLED=true` disables export
+
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use super::emitter::EventEmitter;
+use super::latency::TtftStats;
+use super::log::{Event, EventKind};
+use crate::error::{NikaError, Result};
+use crate::util::FETCH_TIMEOUT;
+
+// ═══════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════
+
+/// OTLP exporter configuration (from OTEL_ env vars)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtelConfig {
+    /// Full URL for span export (e.g. `http://localhost:4318/v1/traces`)
+    pub traces_endpoint: String,
+    /// Full URL for metric export (e.g. `http://localhost:4318/v1/metrics`)
+    pub metrics_endpoint: String,
+    /// Extra HTTP headers (auth tokens for hosted collectors)
+    pub headers: Vec<(String, String)>,
+    /// `service.name` resource attribute
+    pub service_name: String,
+    /// Additional resource attributes
+    pub resource_attributes: Vec<(String, String)>,
+}
+
+impl OtelConfig {
+    /// Load configuration from process environment
+    ///
+    /// Returns `None` when no OTLP endpoint is configured or the SDK is disabled.
+    pub fn from_env() -> Option<Self> {
+        Self::from_vars(|key| std::env::var(key).ok())
+    }
+
+    /// Load configuration from a variable lookup (testable without env mutation)
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        if var("OTEL_SDK_DISABLED").is_some_and(|v| v.eq_ignore_ascii_case("true")) {
+            return None;
+        }
+
+        let base = var("OTEL_EXPORTER_OTLP_ENDPOINT").map(|s| s.trim_end_matches('/').to_string());
+        let traces_endpoint = var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+            .or_else(|| base.as_ref().map(|b| format!("{}/v1/traces", b)))?;
+        let metrics_endpoint = var("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT")
+            .or_else(|| base.as_ref().map(|b| format!("{}/v1/metrics", b)))
+            .unwrap_or_else(|| traces_endpoint.replace("/v1/traces", "/v1/metrics"));
+
+        if let Some(protocol) = var("OTEL_EXPORTER_OTLP_PROTOCOL") {
+            if protocol != "http/json" {
+                tracing::warn!(
+                    protocol = %protocol,
+                    "Only OTLP http/json is supported, exporting as http/json"
+                );
+            }
+        }
+
+        Some(Self {
+            traces_endpoint,
+            metrics_endpoint,
+            headers: var("OTEL_EXPORTER_OTLP_HEADERS")
+                .map(|s| parse_key_values(&s))
+                .unwrap_or_default(),
+            service_name: var("OTEL_SERVICE_NAME").unwrap_or_else(|| "nika".to_string()),
+            resource_attributes: var("OTEL_RESOURCE_ATTRIBUTES")
+                .map(|s| parse_key_values(&s))
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Parse `key=value,key2=value2` lists (OTEL header/attribute format)
+fn parse_key_values(s: &str) -> Vec<(String, String)> {
+    s.split(',')
+        .filter_map(|pair| {
+            let (k, v) = pair.split_once('=')?;
+            let k = k.trim();
+            (!k.is_empty()).then(|| (k.to_string(), v.trim().to_string()))
+        })
+        .collect()
+}
+
+// ═══════════════════════════════════════════════════════════════
+// Span state
+// ═══════════════════════════════════════════════════════════════
+
+/// A span that has started but not yet ended
+struct OpenSpan {
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    start_nanos: u128,
+    attributes: Vec<Value>,
+}
+
+/// Cumulative counters exported as OTLP sums
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OtelMetrics {
+    pub tasks_completed: u64,
+    pub tasks_failed: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    pub mcp_calls: u64,
+    /// Time-to-first-token samples (ms) per model
+    pub ttft_ms: BTreeMap<String, Vec<u64>>,
+    /// Provider calls served by a warm session
+    pub session_hits: u64,
+    /// Provider calls that built a session (cold start)
+    pub session_misses: u64,
+    /// Latest in-flight limit per provider, once adjusted
+    pub concurrency_limits: BTreeMap<String, usize>,
+}
+
+struct OtelState {
+    trace_id: String,
+    workflow: Option<OpenSpan>,
+    tasks: FxHashMap<String, OpenSpan>,
+    /// Keyed by task_id (one provider call in flight per task)
+    providers: FxHashMap<String, OpenSpan>,
+    /// Model of the provider call in flight, keyed by task_id
+    provider_models: FxHashMap<String, String>,
+    /// Keyed by call_id
+    mcp_calls: FxHashMap<String, OpenSpan>,
+    finished: Vec<Value>,
+    metrics: OtelMetrics,
+    next_id: u64,
+}
+
+// ═══════════════════════════════════════════════════════════════
+// OtelEmitter
+// ═══════════════════════════════════════════════════════════════
+
+/// EventEmitter that records events as OTLP spans and metrics
+///
+/// Spans are buffered in memory and sent with `export()` at the end of a run.
+pub struct OtelEmitter {
+    config: OtelConfig,
+    /// Wall-clock time (unix nanos) corresponding to event timestamp 0
+    base_nanos: u128,
+    state: Mutex<OtelState>,
+}
+
+impl OtelEmitter {
+    /// Create an emitter (call right before the EventLog is created)
+    pub fn new(config: OtelConfig) -> Self {
+        Self {
+            config,
+            base_nanos: unix_nanos_now(),
+            state: Mutex::new(OtelState {
+                trace_id: random_hex(16),
+                workflow: None,
+                tasks: FxHashMap::default(),
+                providers: FxHashMap::default(),
+                provider_models: FxHashMap::default(),
+                mcp_calls: FxHashMap::default(),
+                finished: Vec::new(),
+                metrics: OtelMetrics::default(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// Record an already-logged event (uses its relative timestamp)
+    pub fn record(&self, event: &Event) {
+        let at = self.base_nanos + u128::from(event.timestamp_ms) * 1_000_000;
+        self.record_at(&event.kind, at);
+    }
+
+    /// Record all events from a trace or EventLog snapshot
+    pub fn record_all(&self, events: &[Event]) {
+        for event in events {
+            self.record(event);
+        }
+    }
+
+    /// Trace ID shared by all spans of this run (32 hex chars)
+    pub fn trace_id(&self) -> String {
+        self.state.lock().trace_id.clone()
+    }
+
+    /// Snapshot of cumulative metrics
+    pub fn metrics(&self) -> OtelMetrics {
+        self.state.lock().metrics.clone()
+    }
+
+    /// Number of finished spans waiting for export
+    pub fn pending_spans(&self) -> usize {
+        self.state.lock().finished.len()
+    }
+
+    fn record_at(&self, kind: &EventKind, at: u128) {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+
+        match kind {
+            EventKind::WorkflowStarted {
+                task_count,
+                generation_id,
+                workflow_hash,
+                nika_version,
+                ..
+            } => {
+                state.workflow = Some(OpenSpan {
+                    span_id: random_hex(8),
+                    parent_span_id: None,
+                    name: "workflow".to_string(),
+                    start_nanos: at,
+                    attributes: vec![
+                        attr_int("nika.task_count", *task_count as i64),
+                        attr_str("nika.generation_id", generation_id),
+                        attr_str("nika.workflow_hash", workflow_hash),
+                        attr_str("nika.version", nika_version),
+                    ],
+                });
+            }
+            EventKind::RunLabeled { labels } => {
+                if let Some(span) = state.workflow.as_mut() {
+                    for (key, value) in labels {
+                        span.attributes
+                            .push(attr_str(&format!("nika.label.{}", key), value));
+                    }
+                }
+            }
+            EventKind::SessionPoolReport {
+                hits,
+                misses,
+                warmed,
+                ..
+            } => {
+                state.metrics.session_hits += hits;
+                state.metrics.session_misses += misses;
+                if let Some(span) = state.workflow.as_mut() {
+                    span.attributes.extend([
+                        attr_int("nika.session.warm_hits", *hits as i64),
+                        attr_int("nika.session.cold_starts", *misses as i64),
+                        attr_int("nika.session.warmed", *warmed as i64),
+                    ]);
+                }
+            }
+            EventKind::ConcurrencyAdjusted {
+                provider, limit, ..
+            } => {
+                state
+                    .metrics
+                    .concurrency_limits
+                    .insert(provider.to_string(), *limit);
+            }
+            EventKind::WorkflowCompleted {
+                total_duration_ms, ..
+            } => {
+                if let Some(mut span) = state.workflow.take() {
+                    span.attributes
+                        .push(attr_int("nika.duration_ms", *total_duration_ms as i64));
+                    state
+                        .finished
+                        .push(finish_span(&state.trace_id, span, at, None));
+                }
+            }
+            EventKind::WorkflowFailed { error, .. } => {
+                if let Some(span) = state.workflow.take() {
+                    state
+                        .finished
+                        .push(finish_span(&state.trace_id, span, at, Some(error)));
+                }
+            }
+            EventKind::WorkflowAborted { reason, .. } => {
+                if let Some(span) = state.workflow.take() {
+                    state
+                        .finished
+                        .push(finish_span(&state.trace_id, span, at, Some(reason)));
+                }
+            }
+            EventKind::TaskStarted { task_id, verb, .. } => {
+                let parent = state.workflow.as_ref().map(|w| w.span_id.clone());
+                state.tasks.insert(
+                    task_id.to_string(),
+                    OpenSpan {
+                        span_id: random_hex(8),
+                        parent_span_id: parent,
+                        name: format!("task:{}", task_id),
+                        start_nanos: at,
+                        attributes: vec![
+                            attr_str("nika.task_id", task_id),
+                            attr_str("nika.verb", verb),
+                        ],
+                    },
+                );
+            }
+            EventKind::TaskCompleted { task_id, .. } => {
+                state.metrics.tasks_completed += 1;
+                if let Some(span) = state.tasks.remove(&**task_id) {
+                    state
+                        .finished
+                        .push(finish_span(&state.trace_id, span, at, None));
+                }
+            }
+            EventKind::TaskFailed { task_id, error, .. } => {
+                state.metrics.tasks_failed += 1;
+                if let Some(span) = state.tasks.remove(&**task_id) {
+                    state
+                        .finished
+                        .push(finish_span(&state.trace_id, span, at, Some(error)));
+                }
+            }
+            EventKind::ProviderCalled {
+                task_id,
+                provider,
+                model,
+                prompt_len,
+            } => {
+                let parent = state.tasks.get(&**task_id).map(|t| t.span_id.clone());
+                state
+                    .provider_models
+                    .insert(task_id.to_string(), model.to_string());
+                state.providers.insert(
+                    task_id.to_string(),
+                    OpenSpan {
+                        span_id: random_hex(8),
+                        parent_span_id: parent,
+                        name: format!("provider:{}", provider),
+                        start_nanos: at,
+                        attributes: vec![
+                            attr_str("gen_ai.system", provider),
+                            attr_str("gen_ai.request.model", model),
+                            attr_int("nika.prompt_len", *prompt_len as i64),
+                        ],
+                    },
+                );
+            }
+            EventKind::ProviderResponded {
+                task_id,
+                input_tokens,
+                output_tokens,
+                finish_reason,
+                cost_usd,
+                ttft_ms,
+                ..
+            } => {
+                state.metrics.input_tokens += u64::from(*input_tokens);
+                state.metrics.output_tokens += u64::from(*output_tokens);
+                state.metrics.cost_usd += cost_usd;
+                let model = state.provider_models.remove(&**task_id);
+                if let Some(ttft) = ttft_ms {
+                    let model = model.unwrap_or_else(|| "unknown".to_string());
+                    state.metrics.ttft_ms.entry(model).or_default().push(*ttft);
+                }
+                if let Some(mut span) = state.providers.remove(&**task_id) {
+                    span.attributes.extend([
+                        attr_int("gen_ai.usage.input_tokens", i64::from(*input_tokens)),
+                        attr_int("gen_ai.usage.output_tokens", i64::from(*output_tokens)),
+                        attr_str("gen_ai.response.finish_reason", finish_reason),
+                        attr_double("nika.cost_usd", *cost_usd),
+                    ]);
+                    if let Some(ttft) = ttft_ms {
+                        span.attributes.push(attr_int("nika.ttft_ms", *ttft as i64));
+                    }
+                    state
+                        .finished
+                        .push(finish_span(&state.trace_id, span, at, None));
+                }
+            }
+            EventKind::McpInvoke {
+                task_id,
+                call_id,
+                mcp_server,
+                tool,
+                resource,
+                ..
+            } => {
+                let parent = state.tasks.get(&**task_id).map(|t| t.span_id.clone());
+                let mut attributes = vec![attr_str("nika.mcp.server", mcp_server)];
+                if let Some(tool) = tool {
+                    attributes.push(attr_str("nika.mcp.tool", tool));
+                }
+                if let Some(resource) = resource {
+                    attributes.push(attr_str("nika.mcp.resource", resource));
+                }
+                state.mcp_calls.insert(
+                    call_id.to_string(),
+                    OpenSpan {
+                        span_id: random_hex(8),
+                        parent_span_id: parent,
+                        name: format!("mcp:{}", mcp_server),
+                        start_nanos: at,
+                        attributes,
+                    },
+                );
+            }
+            EventKind::McpResponse {
+                call_id,
+                cached,
+                is_error,
+                ..
+            } => {
+                state.metrics.mcp_calls += 1;
+                if let Some(mut span) = state.mcp_calls.remove(call_id.as_str()) {
+                    span.attributes.push(attr_bool("nika.mcp.cached", *cached));
+                    let error = is_error.then_some("MCP tool returned an error");
+                    state
+                        .finished
+                        .push(finish_span(&state.trace_id, span, at, error));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Build the OTLP/JSON `ExportTraceServiceRequest` body
+    pub fn traces_payload(&self) -> Value {
+        let state = self.state.lock();
+        json!({
+            "resourceSpans": [{
+                "resource": self.resource(),
+                "scopeSpans": [{
+                    "scope": scope(),
+                    "spans": state.finished,
+                }]
+            }]
+        })
+    }
+
+    /// Build the OTLP/JSON `ExportMetricsServiceRequest` body
+    pub fn metrics_payload(&self) -> Value {
+        let metrics = self.metrics();
+        let now = unix_nanos_now().to_string();
+        let start = self.base_nanos.to_string();
+        let sum_int = |name: &str, unit: &str, value: u64| {
+            json!({
+                "name": name,
+                "unit": unit,
+                "sum": {
+                    "aggregationTemporality": 2,
+                    "isMonotonic": true,
+                    "dataPoints": [{
+                        "asInt": value.to_string(),
+                        "startTimeUnixNano": start,
+                        "timeUnixNano": now,
+                    }]
+                }
+            })
+        };
+
+        let mut metrics_json = vec![
+            sum_int("nika.tasks.completed", "{task}", metrics.tasks_completed),
+            sum_int("nika.tasks.failed", "{task}", metrics.tasks_failed),
+            sum_int("nika.tokens.input", "{token}", metrics.input_tokens),
+            sum_int("nika.tokens.output", "{token}", metrics.output_tokens),
+            sum_int("nika.mcp.calls", "{call}", metrics.mcp_calls),
+            sum_int(
+                "nika.provider.session.warm_hits",
+                "{call}",
+                metrics.session_hits,
+            ),
+            sum_int(
+                "nika.provider.session.cold_starts",
+                "{call}",
+                metrics.session_misses,
+            ),
+            json!({
+                "name": "nika.cost",
+                "unit": "USD",
+                "sum": {
+                    "aggregationTemporality": 2,
+                    "isMonotonic": true,
+                    "dataPoints": [{
+                        "asDouble": metrics.cost_usd,
+                        "startTimeUnixNano": start,
+                        "timeUnixNano": now,
+                    }]
+                }
+            }),
+        ];
+
+        // TTFT p95 per model (gauge: recomputed over all samples of the run)
+        let ttft_points: Vec<Value> = metrics
+            .ttft_ms
+            .iter()
+            .filter_map(|(model, samples)| TtftStats::from_samples(model.as_str(), samples))
+            .map(|stats| {
+                json!({
+                    "asInt": stats.p95_ms.to_string(),
+                    "timeUnixNano": now,
+                    "attributes": [attr_str("gen_ai.request.model", &stats.model)],
+                })
+            })
+            .collect();
+        if !ttft_points.is_empty() {
+            metrics_json.push(json!({
+                "name": "nika.provider.ttft.p95",
+                "unit": "ms",
+                "gauge": { "dataPoints": ttft_points }
+            }));
+        }
+
+        // Warm-hit rate of the session pool (gauge over the whole run)
+        let session_calls = metrics.session_hits + metrics.session_misses;
+        if session_calls > 0 {
+            metrics_json.push(json!({
+                "name": "nika.provider.session.warm_hit_rate",
+                "unit": "1",
+                "gauge": { "dataPoints": [{
+                    "asDouble": metrics.session_hits as f64 / session_calls as f64,
+                    "timeUnixNano": now,
+                }]}
+            }));
+        }
+
+        // In-flight limit per provider (gauge: latest adjustment)
+        let limit_points: Vec<Value> = metrics
+            .concurrency_limits
+            .iter()
+            .map(|(provider, limit)| {
+                json!({
+                    "asInt": limit.to_string(),
+                    "timeUnixNano": now,
+                    "attributes": [attr_str("gen_ai.system", provider)],
+                })
+            })
+            .collect();
+        if !limit_points.is_empty() {
+            metrics_json.push(json!({
+                "name": "nika.provider.concurrency.limit",
+                "unit": "{request}",
+                "gauge": { "dataPoints": limit_points }
+            }));
+        }
+
+        json!({
+            "resourceMetrics": [{
+                "resource": self.resource(),
+                "scopeMetrics": [{
+                    "scope": scope(),
+                    "metrics": metrics_json,
+                }]
+            }]
+        })
+    }
+
+    /// Export buffered spans and current metrics to the collector
+    ///
+    /// Spans are cleared after a successful export.
+    pub async fn export(&self) -> Result<()> {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .map_err(|e| NikaError::Execution(format!("OTLP client error: {}", e)))?;
+
+        self.post(&client, &self.config.traces_endpoint, self.traces_payload())
+            .await?;
+        self.post(
+            &client,
+            &self.config.metrics_endpoint,
+            self.metrics_payload(),
+        )
+        .await?;
+
+        self.state.lock().finished.clear();
+        Ok(())
+    }
+
+    async fn post(&self, client: &reqwest::Client, url: &str, body: Value) -> Result<()> {
+        let mut request = client.post(url).json(&body);
+        for (key, value) in &self.config.headers {
+            request = request.header(key, value);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| NikaError::Execution(format!("OTLP export to {} failed: {}", url, e)))?;
+
+        if !response.status().is_success() {
+            return Err(NikaError::Execution(format!(
+                "OTLP export to {} failed: HTTP {}",
+                url,
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+
+    fn resource(&self) -> Value {
+        let mut attributes = vec![attr_str("service.name", &self.config.service_name)];
+        attributes.extend(
+            self.config
+                .resource_attributes
+                .iter()
+                .map(|(k, v)| attr_str(k, v)),
+        );
+        json!({ "attributes": attributes })
+    }
+}
+
+impl EventEmitter for OtelEmitter {
+    fn emit(&self, kind: EventKind) -> u64 {
+        self.record_at(&kind, unix_nanos_now());
+        let mut state = self.state.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        id
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════
+// OTLP JSON helpers
+// ═══════════════════════════════════════════════════════════════
+
+fn scope() -> Value {
+    json!({ "name": "nika", "version": env!("CARGO_PKG_VERSION") })
+}
+
+fn finish_span(trace_id: &str, span: OpenSpan, end_nanos: u128, error: Option<&str>) -> Value {
+    // Status codes: 1 = OK, 2 = ERROR
+    let status = match error {
+        Some(message) => json!({ "code": 2, "message": message }),
+        None => json!({ "code": 1 }),
+    };
+    let mut value = json!({
+        "traceId": trace_id,
+        "spanId": span.span_id,
+        "name": span.name,
+        "kind": 1,
+        "startTimeUnixNano": span.start_nanos.to_string(),
+        "endTimeUnixNano": end_nanos.max(span.start_nanos).to_string(),
+        "attributes": span.attributes,
+        "status": status,
+    });
+    if let Some(parent) = span.parent_span_id {
+        value["parentSpanId"] = Value::String(parent);
+    }
+    value
+}
+
+fn attr_str(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn attr_int(key: &str, value: i64) -> Value {
+    // OTLP/JSON encodes int64 as string
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+fn attr_double(key: &str, value: f64) -> Value {
+    json!({ "key": key, "value": { "doubleValue": value } })
+}
+
+fn attr_bool(key: &str, value: bool) -> Value {
+    json!({ "key": key, "value": { "boolValue": value } })
+}
+
+fn unix_nanos_now() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+fn random_hex(bytes: usize) -> String {
+    (0..bytes)
+        .map(|_| format!("{:02x}", rand::random::<u8>()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn config() -> OtelConfig {
+        OtelConfig::from_vars(|k| match k {
+            "OTEL_EXPORTER_OTLP_ENDPOINT" => Some("http://localhost:4318/".into()),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    fn run_events(emitter: &OtelEmitter) {
+        emitter.emit(EventKind::WorkflowStarted {
+            task_count: 1,
+            generation_id: "gen-1".into(),
+            workflow_hash: "xxh3:0".into(),
+            workflow_name: None,
+            nika_version: "0.7.1".into(),
+        });
+        emitter.emit(EventKind::TaskStarted {
+            task_id: "summarize".into(),
+            verb: "infer".into(),
+            inputs: json!({}),
+        });
+        emitter.emit(EventKind::ProviderCalled {
+            task_id: "summarize".into(),
+            provider: "claude".into(),
+            model: "claude-sonnet".into(),
+            prompt_len: 42,
+        });
+        emitter.emit(EventKind::ProviderResponded {
+            task_id: "summarize".into(),
+            request_id: None,
+            input_tokens: 10,
+            output_tokens: 20,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            ttft_ms: Some(120),
+            finish_reason: "stop".into(),
+            cost_usd: 0.01,
+        });
+        emitter.emit(EventKind::TaskCompleted {
+            task_id: "summarize".into(),
+            output: Arc::new(json!("done")),
+            duration_ms: 5,
+        });
+        emitter.emit(EventKind::SessionPoolReport {
+            hits: 3,
+            misses: 1,
+            warmed: 1,
+            capacity: 8,
+        });
+        emitter.emit(EventKind::ConcurrencyAdjusted {
+            provider: "claude".into(),
+            previous: 16,
+            limit: 8,
+        });
+        emitter.emit(EventKind::WorkflowCompleted {
+            final_output: Arc::new(json!("done")),
+            total_duration_ms: 6,
+        });
+    }
+
+    #[test]
+    fn test_config_from_vars() {
+        let cfg = config();
+        assert_eq!(cfg.traces_endpoint, "http://localhost:4318/v1/traces");
+        assert_eq!(cfg.metrics_endpoint, "http://localhost:4318/v1/metrics");
+        assert_eq!(cfg.service_name, "nika");
+    }
+
+    #[test]
+    fn test_config_disabled_or_missing() {
+        assert!(OtelConfig::from_vars(|_| None).is_none());
+        assert!(OtelConfig::from_vars(|k| match k {
+            "OTEL_EXPORTER_OTLP_ENDPOINT" => Some("http://x".into()),
+            "OTEL_SDK_DISABLED" => Some("true".into()),
+            _ => None,
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn test_config_headers_and_attributes() {
+        let cfg = OtelConfig::from_vars(|k| match k {
+            "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT" => Some("http://c/v1/traces".into()),
+            "OTEL_EXPORTER_OTLP_HEADERS" => Some("x-api-key=abc, tenant=t1".into()),
+            "OTEL_RESOURCE_ATTRIBUTES" => Some("deployment.environment=prod".into()),
+            "OTEL_SERVICE_NAME" => Some("pipeline".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(cfg.metrics_endpoint, "http://c/v1/metrics");
+        assert_eq!(
+            cfg.headers,
+            vec![
+                ("x-api-key".to_string(), "abc".to_string()),
+                ("tenant".to_string(), "t1".to_string())
+            ]
+        );
+        assert_eq!(cfg.service_name, "pipeline");
+        assert_eq!(cfg.resource_attributes.len(), 1);
+    }
+
+    #[test]
+    fn test_span_hierarchy() {
+        let emitter = OtelEmitter::new(config());
+        run_events(&emitter);
+
+        let payload = emitter.traces_payload();
+        let spans = payload["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        assert_eq!(spans.len(), 3);
+
+        let by_name = |name: &str| spans.iter().find(|s| s["name"] == name).unwrap();
+        let workflow = by_name("workflow");
+        let task = by_name("task:summarize");
+        let provider = by_name("provider:claude");
+
+        assert!(workflow.get("parentSpanId").is_none());
+        assert_eq!(task["parentSpanId"], workflow["spanId"]);
+        assert_eq!(provider["parentSpanId"], task["spanId"]);
+        assert_eq!(task["traceId"], emitter.trace_id());
+        assert_eq!(emitter.trace_id().len(), 32);
+    }
+
+    #[test]
+    fn test_failed_task_sets_error_status() {
+        let emitter = OtelEmitter::new(config());
+        emitter.emit(EventKind::TaskStarted {
+            task_id: "t".into(),
+            verb: "exec".into(),
+            inputs: json!({}),
+        });
+        emitter.emit(EventKind::TaskFailed {
+            task_id: "t".into(),
+            error: "exit 1".into(),
+            duration_ms: 1,
+        });
+
+        let payload = emitter.traces_payload();
+        let span = &payload["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["status"]["code"], 2);
+        assert_eq!(span["status"]["message"], "exit 1");
+        assert_eq!(emitter.metrics().tasks_failed, 1);
+    }
+
+    #[test]
+    fn test_metrics_accumulate() {
+        let emitter = OtelEmitter::new(config());
+        run_events(&emitter);
+
+        let metrics = emitter.metrics();
+        assert_eq!(metrics.tasks_completed, 1);
+        assert_eq!(metrics.input_tokens, 10);
+        assert_eq!(metrics.output_tokens, 20);
+
+        let payload = emitter.metrics_payload();
+        let names: Vec<_> = payload["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["name"].as_str().unwrap().to_string())
+            .collect();
+        assert!(names.contains(&"nika.tokens.input".to_string()));
+        assert!(names.contains(&"nika.cost".to_string()));
+        assert!(names.contains(&"nika.provider.ttft.p95".to_string()));
+        assert!(names.contains(&"nika.provider.session.warm_hit_rate".to_string()));
+        assert!(names.contains(&"nika.provider.concurrency.limit".to_string()));
+        assert_eq!(metrics.ttft_ms.values().flatten().count(), 1);
+        assert_eq!((metrics.session_hits, metrics.session_misses), (3, 1));
+    }
+
+    #[test]
+    fn test_record_uses_event_timestamps() {
+        let emitter = OtelEmitter::new(config());
+        let events = vec![
+            Event {
+                id: 0,
+                timestamp_ms: 0,
+                kind: EventKind::TaskStarted {
+                    task_id: "t".into(),
+                    verb: "infer".into(),
+                    inputs: json!({}),
+                },
+            },
+            Event {
+                id: 1,
+                timestamp_ms: 250,
+                kind: EventKind::TaskCompleted {
+                    task_id: "t".into(),
+                    output: Arc::new(json!("x")),
+                    duration_ms: 250,
+                },
+            },
+        ];
+        emitter.record_all(&events);
+
+        let payload = emitter.traces_payload();
+        let span = &payload["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        let start: u128 = span["startTimeUnixNano"].as_str().unwrap().parse().unwrap();
+        let end: u128 = span["endTimeUnixNano"].as_str().unwrap().parse().unwrap();
+        assert_eq!(end - start, 250_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_export_posts_to_collector() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/traces"))
+            .and(header("x-api-key", "secret"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/metrics"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let uri = server.uri();
+        let cfg = OtelConfig::from_vars(|k| match k {
+            "OTEL_EXPORTER_OTLP_ENDPOINT" => Some(uri.clone()),
+            "OTEL_EXPORTER_OTLP_HEADERS" => Some("x-api-key=secret".into()),
+            _ => None,
+        })
+        .unwrap();
+
+        let emitter = OtelEmitter::new(cfg);
+        run_events(&emitter);
+        emitter.export().await.unwrap();
+        assert_eq!(emitter.pending_spans(), 0);
+    }
+}
@@ -0,0 +1,347 @@
+//! Run Report - the JSON document of `nika run --output json` (v0.7)
+//!
+//! Built from a run's events once it ends: generation id, workflow name,
+//! outcome, wall time, tokens and cost, and per task its status, duration,
+//! tokens, cost and output (or error / skip reason). [`ReportOutputs`]
+//! decides which task outputs are included, since they can be large or
+//! sensitive:
+//!
+//! ```json
+//! {"generation_id":"gen-7f3a...","workflow":"digest","status":"completed",
+//!  "duration_ms":5120,"tokens":{"input":1840,"output":412,...},"cost_usd":0.0117,
+//!  "tasks":[{"id":"fetch","status":"completed","duration_ms":310,"tokens":0,
+//!            "cost_usd":0.0,"output":{"items":[...]}}, ...]}
+//! ```
+
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::cost::run_cost;
+use super::diff::task_runs;
+use super::{Event, EventKind};
+
+/// Which task outputs a [`RunReport`] includes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportOutputs {
+    /// Every completed task's output (default)
+    #[default]
+    All,
+    /// Outputs of the final tasks (no outgoing flow) only
+    Final,
+    /// No outputs: status, timings and usage only
+    None,
+}
+
+impl ReportOutputs {
+    /// Parse `all`, `final` or `none`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "all" => Some(Self::All),
+            "final" => Some(Self::Final),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// A finished run, as `nika run --output json` prints it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunReport {
+    pub generation_id: String,
+    /// `workflow:` name, if the workflow has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<String>,
+    /// `completed`, `failed` or `aborted`
+    pub status: &'static str,
+    pub duration_ms: u64,
+    pub tokens: ReportTokens,
+    pub cost_usd: f64,
+    /// Why the run failed or was aborted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub tasks: Vec<TaskReport>,
+}
+
+/// Tokens of all provider calls of a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ReportTokens {
+    pub input: u64,
+    pub output: u64,
+    pub cache_read: u64,
+    pub cache_write: u64,
+}
+
+/// One task of a [`RunReport`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskReport {
+    pub id: String,
+    /// `completed`, `failed`, `skipped` or `started` (never finished)
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Input (cache included) and output tokens of the task's provider calls
+    pub tokens: u64,
+    pub cost_usd: f64,
+    /// Output, when completed and included by [`ReportOutputs`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Why a skipped task did not run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl RunReport {
+    /// Report of the run recorded in `events`
+    ///
+    /// `final_tasks` are the tasks without outgoing flows, for
+    /// [`ReportOutputs::Final`].
+    pub fn from_events(
+        generation_id: &str,
+        events: &[Event],
+        outputs: ReportOutputs,
+        final_tasks: &[&str],
+    ) -> Self {
+        let mut report = Self {
+            generation_id: generation_id.to_string(),
+            workflow: None,
+            status: "failed",
+            duration_ms: events.last().map_or(0, |event| event.timestamp_ms),
+            tokens: ReportTokens::default(),
+            cost_usd: 0.0,
+            error: None,
+            tasks: Vec::new(),
+        };
+
+        let mut task_outputs: FxHashMap<&str, &Value> = FxHashMap::default();
+        for event in events {
+            match &event.kind {
+                EventKind::WorkflowStarted { workflow_name, .. } => {
+                    report.workflow = workflow_name.clone();
+                }
+                EventKind::WorkflowCompleted {
+                    total_duration_ms, ..
+                } => {
+                    report.status = "completed";
+                    report.duration_ms = *total_duration_ms;
+                }
+                EventKind::WorkflowFailed { error, .. } => {
+                    report.status = "failed";
+                    report.error = Some(error.clone());
+                }
+                EventKind::WorkflowAborted {
+                    reason,
+                    duration_ms,
+                    ..
+                } => {
+                    report.status = "aborted";
+                    report.duration_ms = *duration_ms;
+                    report.error = Some(reason.clone());
+                }
+                EventKind::TaskCompleted {
+                    task_id, output, ..
+                } => {
+                    task_outputs.insert(task_id, output);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(cost) = run_cost(events) {
+            report.tokens = ReportTokens {
+                input: cost.usage.input_tokens,
+                output: cost.usage.output_tokens,
+                cache_read: cost.usage.cache_read_tokens,
+                cache_write: cost.usage.cache_write_tokens,
+            };
+            report.cost_usd = cost.cost_usd;
+        }
+
+        report.tasks = task_runs(events)
+            .into_iter()
+            .map(|(id, run)| {
+                let included = match outputs {
+                    ReportOutputs::All => true,
+                    ReportOutputs::Final => final_tasks.contains(&id.as_str()),
+                    ReportOutputs::None => false,
+                };
+                let output = included
+                    .then(|| task_outputs.get(id.as_str()).map(|&value| value.clone()))
+                    .flatten();
+                let task_events: Vec<Event> = events
+                    .iter()
+                    .filter(|event| event.kind.task_id() == Some(id.as_str()))
+                    .cloned()
+                    .collect();
+                TaskReport {
+                    status: run.status,
+                    duration_ms: run.duration_ms,
+                    tokens: run.tokens,
+                    cost_usd: run_cost(&task_events).map_or(0.0, |cost| cost.cost_usd),
+                    output,
+                    error: (run.status == "failed")
+                        .then(|| run.output.clone())
+                        .flatten(),
+                    reason: (run.status == "skipped").then_some(run.output).flatten(),
+                    id,
+                }
+            })
+            .collect();
+        report
+    }
+
+    /// Record an error that ended the run before its events said so
+    /// (a failure before the workflow started, for instance)
+    pub fn with_error(mut self, error: &str) -> Self {
+        if self.status == "completed" {
+            return self;
+        }
+        self.error.get_or_insert_with(|| error.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn event(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id * 10,
+            kind,
+        }
+    }
+
+    fn events() -> Vec<Event> {
+        vec![
+            event(
+                0,
+                EventKind::WorkflowStarted {
+                    task_count: 3,
+                    generation_id: "gen-1".to_string(),
+                    workflow_hash: "abc".to_string(),
+                    workflow_name: Some("digest".to_string()),
+                    nika_version: "0.7.0".to_string(),
+                },
+            ),
+            event(
+                1,
+                EventKind::TaskCompleted {
+                    task_id: "fetch".into(),
+                    output: Arc::new(json!({"items": [1, 2]})),
+                    duration_ms: 5,
+                },
+            ),
+            event(
+                2,
+                EventKind::ProviderCalled {
+                    task_id: "summarize".into(),
+                    provider: "claude".into(),
+                    model: "unpriced-model".into(),
+                    prompt_len: 10,
+                },
+            ),
+            event(
+                3,
+                EventKind::ProviderResponded {
+                    task_id: "summarize".into(),
+                    request_id: None,
+                    input_tokens: 100,
+                    output_tokens: 20,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                    ttft_ms: None,
+                    finish_reason: "end_turn".into(),
+                    cost_usd: 0.25,
+                },
+            ),
+            event(
+                4,
+                EventKind::TaskCompleted {
+                    task_id: "summarize".into(),
+                    output: Arc::new(json!("Two items")),
+                    duration_ms: 20,
+                },
+            ),
+            event(
+                5,
+                EventKind::TaskSkipped {
+                    task_id: "notify".into(),
+                    reason: "branch not taken".to_string(),
+                },
+            ),
+            event(
+                6,
+                EventKind::WorkflowCompleted {
+                    final_output: Arc::new(json!("Two items")),
+                    total_duration_ms: 60,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_report_of_completed_run() {
+        let report = RunReport::from_events("gen-1", &events(), ReportOutputs::All, &[]);
+        assert_eq!(report.workflow.as_deref(), Some("digest"));
+        assert_eq!(report.status, "completed");
+        assert_eq!(report.duration_ms, 60);
+        assert_eq!(report.tokens.input, 100);
+        assert_eq!(report.tokens.output, 20);
+        assert_eq!(report.cost_usd, 0.25);
+        assert!(report.error.is_none());
+
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["tasks"][0]["id"], "fetch");
+        assert_eq!(value["tasks"][0]["output"], json!({"items": [1, 2]}));
+        assert_eq!(value["tasks"][0]["cost_usd"], 0.0);
+        assert_eq!(value["tasks"][1]["tokens"], 120);
+        assert_eq!(value["tasks"][1]["cost_usd"], 0.25);
+        assert_eq!(value["tasks"][2]["status"], "skipped");
+        assert_eq!(value["tasks"][2]["reason"], "branch not taken");
+        assert!(value["tasks"][2].get("duration_ms").is_none());
+    }
+
+    #[test]
+    fn test_outputs_policy() {
+        let report =
+            RunReport::from_events("gen-1", &events(), ReportOutputs::Final, &["summarize"]);
+        assert!(report.tasks[0].output.is_none());
+        assert_eq!(report.tasks[1].output, Some(json!("Two items")));
+
+        let report =
+            RunReport::from_events("gen-1", &events(), ReportOutputs::None, &["summarize"]);
+        assert!(report.tasks.iter().all(|task| task.output.is_none()));
+        assert_eq!(ReportOutputs::parse("FINAL"), Some(ReportOutputs::Final));
+        assert_eq!(ReportOutputs::parse("some"), None);
+    }
+
+    #[test]
+    fn test_failed_run_keeps_error() {
+        let mut events = events();
+        events.truncate(5);
+        events.push(event(
+            5,
+            EventKind::TaskFailed {
+                task_id: "notify".into(),
+                error: "HTTP 500".to_string(),
+                duration_ms: 3,
+            },
+        ));
+        let report = RunReport::from_events("gen-1", &events, ReportOutputs::All, &[])
+            .with_error("[NIKA-100] task failed");
+        assert_eq!(report.status, "failed");
+        assert_eq!(report.error.as_deref(), Some("[NIKA-100] task failed"));
+        assert_eq!(report.tasks[2].error.as_deref(), Some("HTTP 500"));
+
+        let report = RunReport::from_events("gen-2", &[], ReportOutputs::All, &[])
+            .with_error("[NIKA-020] parse error");
+        assert_eq!(report.status, "failed");
+        assert!(report.tasks.is_empty());
+    }
+}
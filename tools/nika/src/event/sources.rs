@@ -0,0 +1,208 @@
+//! External Sources - fetched content a run drew on (v0.7)
+//!
+//! `fetch:` tasks emit a `SourceFetched` event per response. This module
+//! lists those sources for the run report and follows `use:` bindings
+//! (`BindingResolved.source_task`) back from a task, so a stamped output
+//! can name the sources its inputs came from.
+//!
+//! Used by the run summary, `nika trace show` and `output.stamp`.
+
+use std::collections::VecDeque;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{Event, EventKind};
+
+/// One external source used in a run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    /// Final URL, after redirects
+    pub url: String,
+    /// First retrieval in the run (RFC 3339)
+    pub retrieved_at: String,
+    /// License the source declares, if any
+    pub license: Option<String>,
+    /// Author the source declares, if any
+    pub attribution: Option<String>,
+    /// Tasks that fetched it
+    pub tasks: Vec<String>,
+}
+
+impl Source {
+    /// `retrieved <time>, license <l>, by <author>`
+    pub fn details(&self) -> String {
+        let mut parts = vec![format!("retrieved {}", self.retrieved_at)];
+        if let Some(license) = &self.license {
+            parts.push(format!("license {}", license));
+        }
+        if let Some(author) = &self.attribution {
+            parts.push(format!("by {}", author));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Every fetched source, once per URL, in first-retrieval order
+pub fn run_sources(events: &[Event]) -> Vec<Source> {
+    let mut sources: Vec<Source> = Vec::new();
+    for event in events {
+        let EventKind::SourceFetched {
+            task_id,
+            url,
+            retrieved_at,
+            license,
+            attribution,
+            ..
+        } = &event.kind
+        else {
+            continue;
+        };
+        match sources.iter_mut().find(|s| &s.url == url) {
+            Some(source) => {
+                if !source.tasks.iter().any(|t| **t == **task_id) {
+                    source.tasks.push(task_id.to_string());
+                }
+                source.license = source.license.take().or_else(|| license.clone());
+                source.attribution = source.attribution.take().or_else(|| attribution.clone());
+            }
+            None => sources.push(Source {
+                url: url.clone(),
+                retrieved_at: retrieved_at.clone(),
+                license: license.clone(),
+                attribution: attribution.clone(),
+                tasks: vec![task_id.to_string()],
+            }),
+        }
+    }
+    sources
+}
+
+/// URLs that reached `task_id`, fetched by it or by any task upstream of
+/// its `use:` bindings
+pub fn sources_for(events: &[Event], task_id: &str) -> Vec<String> {
+    let mut fetched: FxHashMap<&str, Vec<&str>> = FxHashMap::default();
+    let mut inputs: FxHashMap<&str, Vec<&str>> = FxHashMap::default();
+    for event in events {
+        match &event.kind {
+            EventKind::SourceFetched { task_id, url, .. } => {
+                fetched.entry(task_id).or_default().push(url);
+            }
+            EventKind::BindingResolved {
+                task_id,
+                source_task: Some(source),
+                ..
+            } => inputs.entry(task_id).or_default().push(source),
+            _ => {}
+        }
+    }
+
+    let mut urls: Vec<String> = Vec::new();
+    let mut seen: FxHashSet<&str> = FxHashSet::default();
+    let mut queue = VecDeque::from([task_id]);
+    while let Some(target) = queue.pop_front() {
+        if !seen.insert(target) {
+            continue;
+        }
+        // An upstream for_each task's output holds all of its iterations
+        let matching = |id: &&str| is_run_of(id, target);
+        for url in fetched
+            .iter()
+            .filter(|(id, _)| matching(id))
+            .flat_map(|(_, u)| u)
+        {
+            if !urls.iter().any(|u| u == url) {
+                urls.push(url.to_string());
+            }
+        }
+        for source in inputs
+            .iter()
+            .filter(|(id, _)| matching(id))
+            .flat_map(|(_, s)| s)
+        {
+            queue.push_back(source);
+        }
+    }
+    urls.sort();
+    urls
+}
+
+/// `id` is `target` itself or one of its for_each iterations (`target[2]`)
+fn is_run_of(id: &str, target: &str) -> bool {
+    id.strip_prefix(target)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('['))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind) -> Event {
+        Event {
+            id: 0,
+            timestamp_ms: 0,
+            kind,
+        }
+    }
+
+    fn fetched(task: &str, url: &str, license: Option<&str>) -> Event {
+        event(EventKind::SourceFetched {
+            task_id: task.into(),
+            url: url.to_string(),
+            retrieved_at: "2026-10-17T09:00:00+00:00".to_string(),
+            status: 200,
+            content_type: None,
+            license: license.map(str::to_string),
+            attribution: None,
+        })
+    }
+
+    fn bound(task: &str, source: &str) -> Event {
+        event(EventKind::BindingResolved {
+            task_id: task.into(),
+            alias: "x".into(),
+            source_task: Some(source.into()),
+            path: source.into(),
+            source_event_id: None,
+            used_default: false,
+            lazy: false,
+        })
+    }
+
+    #[test]
+    fn test_sources_follow_bindings() {
+        let events = [
+            fetched("pages[0]", "https://b.example/1", Some("CC-BY-4.0")),
+            fetched("pages[1]", "https://a.example/2", None),
+            fetched("other", "https://c.example", None),
+            bound("summary", "pages"),
+            bound("post", "summary"),
+            bound("post", "post"),
+        ];
+        assert_eq!(
+            sources_for(&events, "post"),
+            ["https://a.example/2", "https://b.example/1"]
+        );
+        assert_eq!(sources_for(&events, "pages[1]"), ["https://a.example/2"]);
+        assert!(sources_for(&events, "pagesx").is_empty());
+
+        let all = run_sources(&events);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].tasks, ["pages[0]"]);
+        assert_eq!(
+            all[0].details(),
+            "retrieved 2026-10-17T09:00:00+00:00, license CC-BY-4.0"
+        );
+    }
+
+    #[test]
+    fn test_run_sources_dedupe_urls() {
+        let events = [
+            fetched("a", "https://x.example", None),
+            fetched("b", "https://x.example", Some("MIT")),
+        ];
+        let all = run_sources(&events);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].tasks, ["a", "b"]);
+        assert_eq!(all[0].license.as_deref(), Some("MIT"));
+    }
+}
@@ -0,0 +1,374 @@
+//! Run Statistics - per-workflow history across traces (v0.7)
+//!
+//! Each trace is summarized into a [`RunSummary`]: workflow, outcome, wall
+//! time, per-task status and duration ([`task_runs`]), tokens and cost
+//! ([`run_cost`]). Summaries are then grouped by workflow into
+//! [`WorkflowStats`]: success rate, p50/p95 duration per task, and tokens
+//! and cost per day.
+//!
+//! Workflows are grouped by their `workflow:` name; unnamed ones (and traces
+//! recorded before names were) by their hash, so editing an unnamed
+//! workflow starts a new group. Days come from the creation time of the
+//! trace file (UTC).
+//!
+//! Used by `nika stats`.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::cost::run_cost;
+use super::diff::task_runs;
+use super::latency::percentile;
+use super::{read_trace_events, Event, EventKind, TraceInfo};
+
+/// One run as its trace recorded it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub generation_id: String,
+    /// Workflow `workflow:` name, or its hash when unnamed
+    pub workflow: String,
+    /// `completed`, `failed`, `aborted` or `running` (never finished)
+    pub status: &'static str,
+    pub duration_ms: u64,
+    /// `(task_id, status, duration_ms)` per task, as [`task_runs`] reports it
+    pub tasks: Vec<(String, &'static str, Option<u64>)>,
+    /// Input (cache included) and output tokens of all provider calls
+    pub tokens: u64,
+    pub cost_usd: f64,
+    /// Day the trace was written (`YYYY-MM-DD`, UTC), if known
+    pub day: Option<String>,
+}
+
+impl RunSummary {
+    /// Summary of a trace file (None if unreadable or not a workflow run)
+    pub fn from_trace(trace: &TraceInfo) -> Option<Self> {
+        let events = read_trace_events(&trace.path).ok()?;
+        let mut run = Self::from_events(&trace.generation_id, &events)?;
+        run.day = trace.created.map(|created| {
+            chrono::DateTime::<chrono::Utc>::from(created)
+                .format("%Y-%m-%d")
+                .to_string()
+        });
+        Some(run)
+    }
+
+    /// Summary of a trace (None without a `WorkflowStarted` event)
+    pub fn from_events(generation_id: &str, events: &[Event]) -> Option<Self> {
+        let workflow = events.iter().find_map(|event| match &event.kind {
+            EventKind::WorkflowStarted {
+                workflow_hash,
+                workflow_name,
+                ..
+            } => Some(
+                workflow_name
+                    .clone()
+                    .unwrap_or_else(|| workflow_hash.clone()),
+            ),
+            _ => None,
+        })?;
+
+        let mut status = "running";
+        let mut duration_ms = events.last().map_or(0, |event| event.timestamp_ms);
+        for event in events {
+            match &event.kind {
+                EventKind::WorkflowCompleted {
+                    total_duration_ms, ..
+                } => {
+                    status = "completed";
+                    duration_ms = *total_duration_ms;
+                }
+                EventKind::WorkflowFailed { .. } => status = "failed",
+                EventKind::WorkflowAborted {
+                    duration_ms: aborted_after,
+                    ..
+                } => {
+                    status = "aborted";
+                    duration_ms = *aborted_after;
+                }
+                _ => {}
+            }
+        }
+
+        let cost = run_cost(events).unwrap_or_default();
+        Some(Self {
+            generation_id: generation_id.to_string(),
+            workflow,
+            status,
+            duration_ms,
+            tasks: task_runs(events)
+                .into_iter()
+                .map(|(task_id, run)| (task_id, run.status, run.duration_ms))
+                .collect(),
+            tokens: cost.usage.total_input_tokens() + cost.usage.output_tokens,
+            cost_usd: cost.cost_usd,
+            day: None,
+        })
+    }
+}
+
+/// Statistics of one workflow over its runs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkflowStats {
+    pub workflow: String,
+    pub runs: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Share of finished runs that completed (0.0 - 1.0)
+    pub success_rate: f64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    /// Tasks in the order they first appear
+    pub tasks: Vec<TaskStats>,
+    /// Oldest day first; runs of an unknown day are left out
+    pub days: Vec<DayStats>,
+}
+
+/// Durations and failures of one task across runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskStats {
+    pub task_id: String,
+    /// Runs where the task completed or failed
+    pub runs: usize,
+    pub failures: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+}
+
+/// Runs, tokens and cost of one workflow on one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayStats {
+    pub day: String,
+    pub runs: usize,
+    pub tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// Statistics per workflow, most runs first
+pub fn workflow_stats(runs: &[RunSummary]) -> Vec<WorkflowStats> {
+    let mut by_workflow: BTreeMap<&str, Vec<&RunSummary>> = BTreeMap::new();
+    for run in runs {
+        by_workflow.entry(&run.workflow).or_default().push(run);
+    }
+
+    let mut stats: Vec<WorkflowStats> = by_workflow
+        .into_iter()
+        .map(|(workflow, runs)| stats_of(workflow, &runs))
+        .collect();
+    stats.sort_by_key(|s| std::cmp::Reverse(s.runs));
+    stats
+}
+
+fn stats_of(workflow: &str, runs: &[&RunSummary]) -> WorkflowStats {
+    let succeeded = runs.iter().filter(|r| r.status == "completed").count();
+    let failed = runs
+        .iter()
+        .filter(|r| matches!(r.status, "failed" | "aborted"))
+        .count();
+    let finished = succeeded + failed;
+
+    let mut durations: Vec<u64> = runs
+        .iter()
+        .filter(|r| r.status != "running")
+        .map(|r| r.duration_ms)
+        .collect();
+    durations.sort_unstable();
+
+    // (runs, failures, durations) per task, in first-seen order
+    let mut tasks: Vec<(String, usize, usize, Vec<u64>)> = Vec::new();
+    let mut days: BTreeMap<&str, DayStats> = BTreeMap::new();
+    for run in runs {
+        for (task_id, status, duration_ms) in &run.tasks {
+            if !matches!(*status, "completed" | "failed") {
+                continue;
+            }
+            let i = match tasks.iter().position(|t| &t.0 == task_id) {
+                Some(i) => i,
+                None => {
+                    tasks.push((task_id.clone(), 0, 0, Vec::new()));
+                    tasks.len() - 1
+                }
+            };
+            let task = &mut tasks[i];
+            task.1 += 1;
+            if *status == "failed" {
+                task.2 += 1;
+            }
+            task.3.extend(duration_ms);
+        }
+
+        if let Some(day) = run.day.as_deref() {
+            let entry = days.entry(day).or_insert_with(|| DayStats {
+                day: day.to_string(),
+                runs: 0,
+                tokens: 0,
+                cost_usd: 0.0,
+            });
+            entry.runs += 1;
+            entry.tokens += run.tokens;
+            entry.cost_usd += run.cost_usd;
+        }
+    }
+
+    WorkflowStats {
+        workflow: workflow.to_string(),
+        runs: runs.len(),
+        succeeded,
+        failed,
+        success_rate: if finished == 0 {
+            0.0
+        } else {
+            succeeded as f64 / finished as f64
+        },
+        p50_ms: percentile(&durations, 50).unwrap_or(0),
+        p95_ms: percentile(&durations, 95).unwrap_or(0),
+        tasks: tasks
+            .into_iter()
+            .map(|(task_id, runs, failures, mut durations)| {
+                durations.sort_unstable();
+                TaskStats {
+                    task_id,
+                    runs,
+                    failures,
+                    p50_ms: percentile(&durations, 50).unwrap_or(0),
+                    p95_ms: percentile(&durations, 95).unwrap_or(0),
+                }
+            })
+            .collect(),
+        days: days.into_values().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn event(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id * 100,
+            kind,
+        }
+    }
+
+    /// A run of `summarize` that completes or fails after `ms`
+    fn run(day: &str, name: Option<&str>, ms: u64, ok: bool) -> RunSummary {
+        let mut events = vec![
+            event(
+                0,
+                EventKind::WorkflowStarted {
+                    task_count: 1,
+                    generation_id: format!("gen-{}", day),
+                    workflow_hash: "xxh3:0".to_string(),
+                    workflow_name: name.map(str::to_string),
+                    nika_version: "0.7.1".to_string(),
+                },
+            ),
+            event(
+                1,
+                EventKind::ProviderResponded {
+                    task_id: "summarize".into(),
+                    request_id: None,
+                    input_tokens: 100,
+                    output_tokens: 50,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                    ttft_ms: None,
+                    finish_reason: "end_turn".into(),
+                    cost_usd: 0.01,
+                },
+            ),
+        ];
+        events.push(event(
+            2,
+            if ok {
+                EventKind::TaskCompleted {
+                    task_id: "summarize".into(),
+                    output: Arc::new(json!("done")),
+                    duration_ms: ms,
+                }
+            } else {
+                EventKind::TaskFailed {
+                    task_id: "summarize".into(),
+                    error: "timeout".to_string(),
+                    duration_ms: ms,
+                }
+            },
+        ));
+        events.push(event(
+            3,
+            if ok {
+                EventKind::WorkflowCompleted {
+                    final_output: Arc::new(json!("done")),
+                    total_duration_ms: ms + 10,
+                }
+            } else {
+                EventKind::WorkflowFailed {
+                    error: "timeout".to_string(),
+                    failed_task: Some("summarize".into()),
+                }
+            },
+        ));
+        let mut run = RunSummary::from_events(&format!("gen-{}", day), &events).unwrap();
+        run.day = Some(day.to_string());
+        run
+    }
+
+    #[test]
+    fn test_summarizes_a_trace() {
+        let ok = run("2026-10-01", Some("digest"), 400, true);
+        assert_eq!(ok.workflow, "digest");
+        assert_eq!(ok.status, "completed");
+        assert_eq!(ok.duration_ms, 410);
+        assert_eq!(
+            ok.tasks,
+            [("summarize".to_string(), "completed", Some(400))]
+        );
+        assert_eq!(ok.tokens, 150);
+
+        // Unnamed: grouped by hash; failed: wall time up to the last event
+        let failed = run("2026-10-01", None, 400, false);
+        assert_eq!(failed.workflow, "xxh3:0");
+        assert_eq!(failed.status, "failed");
+        assert_eq!(failed.duration_ms, 300);
+
+        assert!(RunSummary::from_events("empty", &[]).is_none());
+    }
+
+    #[test]
+    fn test_aggregates_per_workflow() {
+        let runs = [
+            run("2026-10-01", Some("digest"), 100, true),
+            run("2026-10-01", Some("digest"), 200, true),
+            run("2026-10-02", Some("digest"), 900, false),
+            run("2026-10-02", Some("other"), 50, true),
+        ];
+        let stats = workflow_stats(&runs);
+        assert_eq!(stats.len(), 2);
+
+        let digest = &stats[0];
+        assert_eq!(digest.workflow, "digest");
+        assert_eq!((digest.runs, digest.succeeded, digest.failed), (3, 2, 1));
+        assert!((digest.success_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!((digest.p50_ms, digest.p95_ms), (210, 300));
+        assert_eq!(
+            digest.tasks,
+            [TaskStats {
+                task_id: "summarize".to_string(),
+                runs: 3,
+                failures: 1,
+                p50_ms: 200,
+                p95_ms: 900,
+            }]
+        );
+        let days: Vec<(&str, usize, u64)> = digest
+            .days
+            .iter()
+            .map(|d| (d.day.as_str(), d.runs, d.tokens))
+            .collect();
+        assert_eq!(days, [("2026-10-01", 2, 300), ("2026-10-02", 1, 150)]);
+        assert!((digest.days[0].cost_usd - 0.02).abs() < 1e-9);
+    }
+}
@@ -1,19 +1,30 @@
 //! NDJSON Trace Writer
 //!
 //! Writes events to newline-delimited JSON files for debugging and replay.
+//! The first line is a version header (v0.7, see [`crate::util::compat`]).
 
 use crate::error::Result;
 use crate::event::{Event, EventLog};
+use crate::util::compat::{self, FileKind, VersionStamp};
 use std::fs::{self, File};
-use std::io::{BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 
 /// Directory for trace files
 const TRACE_DIR: &str = ".nika/traces";
 
+/// First line of a trace: `{"nika_trace":{"schema":2,"nika_version":"0.7.0"}}`
+#[derive(Serialize, Deserialize)]
+struct TraceHeader {
+    nika_trace: VersionStamp,
+}
+
 /// NDJSON trace writer
 pub struct TraceWriter {
     writer: Arc<Mutex<BufWriter<File>>>,
@@ -29,14 +40,7 @@ impl TraceWriter {
     /// Only alphanumeric characters, hyphens, and underscores are allowed.
     pub fn new(generation_id: &str) -> Result<Self> {
         // Validate generation_id to prevent path traversal
-        if generation_id.is_empty()
-            || generation_id.contains("..")
-            || generation_id.contains('/')
-            || generation_id.contains('\\')
-            || !generation_id
-                .chars()
-                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == 'T')
-        {
+        if !is_valid_generation_id(generation_id) {
             return Err(crate::error::NikaError::ValidationError {
                 reason: format!(
                     "Invalid generation_id: must be alphanumeric with hyphens/underscores only, got: {}",
@@ -53,7 +57,11 @@ impl TraceWriter {
         let filename = format!("{}.ndjson", generation_id);
         let path = trace_dir.join(&filename);
         let file = File::create(&path)?;
-        let writer = BufWriter::new(file);
+        let mut writer = BufWriter::new(file);
+        let header = TraceHeader {
+            nika_trace: VersionStamp::current(FileKind::Trace),
+        };
+        writeln!(writer, "{}", serde_json::to_string(&header)?)?;
 
         tracing::info!(path = %path.display(), "Created trace file");
 
@@ -120,6 +128,24 @@ pub fn calculate_workflow_hash(yaml: &str) -> String {
     format!("xxh3:{:016x}", hash)
 }
 
+/// Whether `generation_id` is safe to use as a trace file name
+fn is_valid_generation_id(generation_id: &str) -> bool {
+    !generation_id.is_empty()
+        && !generation_id.contains("..")
+        && generation_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Trace file of `generation_id`, if one was written (v0.7)
+pub fn trace_path(generation_id: &str) -> Option<PathBuf> {
+    if !is_valid_generation_id(generation_id) {
+        return None;
+    }
+    let path = Path::new(TRACE_DIR).join(format!("{}.ndjson", generation_id));
+    path.is_file().then_some(path)
+}
+
 /// List all trace files
 pub fn list_traces() -> Result<Vec<TraceInfo>> {
     let trace_dir = Path::new(TRACE_DIR);
@@ -144,6 +170,7 @@ pub fn list_traces() -> Result<Vec<TraceInfo>> {
 
             traces.push(TraceInfo {
                 generation_id,
+                version: read_trace_version(&path),
                 path,
                 size_bytes: metadata.len(),
                 created: metadata.created().ok(),
@@ -157,10 +184,99 @@ pub fn list_traces() -> Result<Vec<TraceInfo>> {
     Ok(traces)
 }
 
+/// Version header of a trace file (`None` if unreadable or empty)
+///
+/// Traces written before headers existed read as [`VersionStamp::legacy`].
+pub fn read_trace_version(path: &Path) -> Option<VersionStamp> {
+    let mut first = String::new();
+    BufReader::new(File::open(path).ok()?)
+        .read_line(&mut first)
+        .ok()?;
+    if first.trim().is_empty() {
+        return None;
+    }
+    Some(
+        serde_json::from_str::<TraceHeader>(&first)
+            .map(|header| header.nika_trace)
+            .unwrap_or_else(|_| VersionStamp::legacy()),
+    )
+}
+
+/// Read all events from an NDJSON trace file
+///
+/// Events of older trace schemas are migrated; traces of a newer schema
+/// fail with `NIKA-098`. Lines that fail to parse are skipped (traces may
+/// be truncated on crash).
+pub fn read_trace_events(path: &Path) -> Result<Vec<Event>> {
+    let content = fs::read_to_string(path)?;
+    let mut lines = content.lines().peekable();
+    let stamp = match lines
+        .peek()
+        .and_then(|line| serde_json::from_str::<TraceHeader>(line).ok())
+    {
+        Some(header) => {
+            lines.next();
+            header.nika_trace
+        }
+        None => VersionStamp::legacy(),
+    };
+    let migrations = compat::check(FileKind::Trace, path, &stamp)?;
+
+    let mut events = Vec::new();
+    for line in lines {
+        // Current schema: decode straight from the line, borrowing its
+        // strings instead of allocating a JSON tree first
+        if migrations.is_empty() {
+            if let Ok(event) = serde_json::from_str(line) {
+                events.push(event);
+            }
+            continue;
+        }
+        let Ok(mut value) = serde_json::from_str(line) else {
+            continue;
+        };
+        compat::migrate(path, &migrations, &mut value)?;
+        if let Ok(event) = serde_json::from_value(value) {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+/// Re-emit recorded events into a channel, paced by their timestamps (v0.7)
+///
+/// `speed` scales the original timing: `1.0` is real time, `4.0` is four
+/// times faster, and `0.0` (or any non-positive value) sends everything
+/// immediately. Stops early if the receiver is dropped.
+pub async fn replay_trace(events: Vec<Event>, speed: f64, tx: mpsc::Sender<Event>) {
+    let mut prev_ms = events.first().map(|e| e.timestamp_ms).unwrap_or(0);
+    for event in events {
+        if let Some(delay) = replay_delay(prev_ms, event.timestamp_ms, speed) {
+            tokio::time::sleep(delay).await;
+        }
+        prev_ms = event.timestamp_ms;
+        if tx.send(event).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Delay between two recorded timestamps at the given replay speed
+fn replay_delay(prev_ms: u64, ts_ms: u64, speed: f64) -> Option<Duration> {
+    if speed <= 0.0 || ts_ms <= prev_ms {
+        return None;
+    }
+    Some(Duration::from_secs_f64(
+        (ts_ms - prev_ms) as f64 / 1000.0 / speed,
+    ))
+}
+
 /// Information about a trace file
 #[derive(Debug)]
 pub struct TraceInfo {
     pub generation_id: String,
+    /// Version header (`None` if the file is empty or unreadable)
+    pub version: Option<VersionStamp>,
     pub path: PathBuf,
     pub size_bytes: u64,
     pub created: Option<std::time::SystemTime>,
@@ -270,6 +386,61 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_read_trace_events_skips_bad_lines() {
+        use crate::event::EventKind;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gen.ndjson");
+        let event = Event {
+            id: 0,
+            timestamp_ms: 0,
+            kind: EventKind::WorkflowPaused,
+        };
+        let content = format!("{}\n{{truncated\n", serde_json::to_string(&event).unwrap());
+        fs::write(&path, content).unwrap();
+
+        let events = read_trace_events(&path).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, EventKind::WorkflowPaused);
+        assert_eq!(read_trace_version(&path), Some(VersionStamp::legacy()));
+    }
+
+    #[test]
+    fn test_read_trace_events_checks_version_header() {
+        use crate::event::EventKind;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gen.ndjson");
+        let event = Event {
+            id: 0,
+            timestamp_ms: 0,
+            kind: EventKind::WorkflowPaused,
+        };
+        let header = TraceHeader {
+            nika_trace: VersionStamp::current(FileKind::Trace),
+        };
+        let content = format!(
+            "{}\n{}\n",
+            serde_json::to_string(&header).unwrap(),
+            serde_json::to_string(&event).unwrap()
+        );
+        fs::write(&path, content).unwrap();
+        assert_eq!(read_trace_events(&path).unwrap().len(), 1);
+        assert_eq!(
+            read_trace_version(&path),
+            Some(VersionStamp::current(FileKind::Trace))
+        );
+
+        let newer = r#"{"nika_trace":{"schema":99,"nika_version":"9.0.0"}}"#;
+        fs::write(&path, format!("{}\n", newer)).unwrap();
+        let err = read_trace_events(&path).unwrap_err();
+        assert_eq!(err.code(), "NIKA-098");
+        assert!(err.to_string().contains("nika 9.0.0"), "{}", err);
+    }
+
     #[test]
     fn test_trace_writer_rejects_path_traversal() {
         // Path traversal attempts should be rejected
@@ -299,4 +470,34 @@ mod tests {
             .chars()
             .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == 'T'));
     }
+
+    #[test]
+    fn test_replay_delay_scales_with_speed() {
+        assert_eq!(replay_delay(0, 1000, 1.0), Some(Duration::from_secs(1)));
+        assert_eq!(replay_delay(0, 1000, 4.0), Some(Duration::from_millis(250)));
+        assert_eq!(replay_delay(0, 1000, 0.0), None);
+        assert_eq!(replay_delay(500, 500, 1.0), None);
+    }
+
+    #[tokio::test]
+    async fn test_replay_trace_preserves_order() {
+        use crate::event::EventKind;
+
+        let events: Vec<Event> = (0..3)
+            .map(|id| Event {
+                id,
+                timestamp_ms: id * 10,
+                kind: EventKind::WorkflowPaused,
+            })
+            .collect();
+
+        let (tx, mut rx) = mpsc::channel(8);
+        replay_trace(events, 0.0, tx).await;
+
+        let mut ids = Vec::new();
+        while let Some(event) = rx.recv().await {
+            ids.push(event.id);
+        }
+        assert_eq!(ids, vec![0, 1, 2]);
+    }
 }
@@ -0,0 +1,249 @@
+//! Agent transcripts - the conversation of an `agent:` task (v0.7)
+//!
+//! `AgentTurn` events only carry each loop's final text, so a dialogue
+//! can't be rebuilt from them. The agent loop records every model turn
+//! (text, thinking when the provider returns it, tool calls with their
+//! arguments and results) and emits the whole conversation once as an
+//! `AgentTranscript` event when the loop ends, successful or not.
+//!
+//! `nika trace show <id> --agent <task>` prints it with [`render`].
+
+use std::fmt::Write;
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Conversation of one agent loop
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentTranscript {
+    /// System prompt (preamble) sent with every turn
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    /// User prompt that started the loop
+    pub prompt: String,
+    /// Model turns, in order
+    #[serde(default)]
+    pub turns: Vec<TranscriptTurn>,
+}
+
+/// One model response and the tool calls it made
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptTurn {
+    /// Reasoning (extended thinking), when the provider returns it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    /// Text of the response
+    #[serde(default)]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<TranscriptToolCall>,
+}
+
+/// A tool call and what the model got back
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptToolCall {
+    pub name: String,
+    pub args: Value,
+    /// Tool output, or the notice returned instead of running it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// Not run (over `limits:`); `result` holds the notice
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+}
+
+/// Builds a transcript from the agent loop's hook callbacks
+///
+/// Tool calls are matched to the current turn's calls in the order the
+/// loop starts them, which is the order of the model's message.
+#[derive(Debug, Default)]
+pub struct TranscriptRecorder {
+    state: Mutex<Recording>,
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    transcript: AgentTranscript,
+    /// Started calls of the current turn
+    started: usize,
+    /// Loop call ID -> (turn, call) position
+    calls: FxHashMap<String, (usize, usize)>,
+}
+
+impl TranscriptRecorder {
+    pub fn new(system: Option<String>, prompt: impl Into<String>) -> Self {
+        Self {
+            state: Mutex::new(Recording {
+                transcript: AgentTranscript {
+                    system,
+                    prompt: prompt.into(),
+                    turns: Vec::new(),
+                },
+                ..Recording::default()
+            }),
+        }
+    }
+
+    /// A model response; `tool_calls` are (name, arguments)
+    pub fn turn(&self, text: String, thinking: Option<String>, tool_calls: Vec<(String, Value)>) {
+        let mut state = self.state.lock();
+        state.started = 0;
+        state.transcript.turns.push(TranscriptTurn {
+            thinking,
+            text,
+            tool_calls: tool_calls
+                .into_iter()
+                .map(|(name, args)| TranscriptToolCall {
+                    name,
+                    args,
+                    ..TranscriptToolCall::default()
+                })
+                .collect(),
+        });
+    }
+
+    /// The loop started the next tool call of the current turn
+    pub fn call_started(&self, call_id: &str) {
+        let mut state = self.state.lock();
+        let Some(turn) = state.transcript.turns.len().checked_sub(1) else {
+            return;
+        };
+        let position = (turn, state.started);
+        state.started += 1;
+        state.calls.insert(call_id.to_string(), position);
+    }
+
+    /// Output of a started call
+    pub fn call_finished(&self, call_id: &str, result: &str) {
+        self.set_result(call_id, result, false);
+    }
+
+    /// A started call was not run; `notice` went to the model instead
+    pub fn call_skipped(&self, call_id: &str, notice: &str) {
+        self.set_result(call_id, notice, true);
+    }
+
+    fn set_result(&self, call_id: &str, result: &str, skipped: bool) {
+        let mut state = self.state.lock();
+        let Some(&(turn, index)) = state.calls.get(call_id) else {
+            return;
+        };
+        if let Some(call) = state.transcript.turns[turn].tool_calls.get_mut(index) {
+            call.result = Some(result.to_string());
+            call.skipped = skipped;
+        }
+    }
+
+    /// The transcript so far
+    pub fn transcript(&self) -> AgentTranscript {
+        self.state.lock().transcript.clone()
+    }
+}
+
+/// Transcript as a readable dialogue
+pub fn render(transcript: &AgentTranscript) -> String {
+    let mut out = String::new();
+    if let Some(system) = &transcript.system {
+        section(&mut out, "System", system);
+    }
+    section(&mut out, "User", &transcript.prompt);
+    for (i, turn) in transcript.turns.iter().enumerate() {
+        let _ = writeln!(out, "── Turn {} ──", i + 1);
+        if let Some(thinking) = &turn.thinking {
+            section(&mut out, "Thinking", thinking);
+        }
+        if !turn.text.is_empty() {
+            section(&mut out, "Assistant", &turn.text);
+        }
+        for call in &turn.tool_calls {
+            let _ = writeln!(out, "→ {} {}", call.name, call.args);
+            let label = if call.skipped { "← skipped:" } else { "←" };
+            match &call.result {
+                Some(result) => indented(&mut out, label, result),
+                None => {
+                    let _ = writeln!(out, "  (no result)");
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn section(out: &mut String, title: &str, text: &str) {
+    let _ = writeln!(out, "{}:", title);
+    for line in text.lines() {
+        let _ = writeln!(out, "  {}", line);
+    }
+    out.push('\n');
+}
+
+fn indented(out: &mut String, label: &str, text: &str) {
+    let mut lines = text.lines();
+    let _ = writeln!(out, "  {} {}", label, lines.next().unwrap_or_default());
+    for line in lines {
+        let _ = writeln!(out, "    {}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn records_calls_in_start_order() {
+        let recorder = TranscriptRecorder::new(Some("Be brief".into()), "Compare both crates");
+        recorder.turn(
+            "Reading both.".into(),
+            Some("Two reads, then compare.".into()),
+            vec![
+                ("read".into(), json!({ "path": "a/Cargo.toml" })),
+                ("read".into(), json!({ "path": "b/Cargo.toml" })),
+            ],
+        );
+        recorder.call_started("x1");
+        recorder.call_started("x2");
+        // Concurrent calls may finish out of order
+        recorder.call_finished("x2", "name = \"b\"");
+        recorder.call_skipped("x1", "Tool call limit reached");
+        recorder.turn("Both are named after their folder.".into(), None, vec![]);
+
+        let transcript = recorder.transcript();
+        assert_eq!(transcript.turns.len(), 2);
+        let calls = &transcript.turns[0].tool_calls;
+        assert_eq!(calls[0].args, json!({ "path": "a/Cargo.toml" }));
+        assert!(calls[0].skipped);
+        assert_eq!(calls[1].result.as_deref(), Some("name = \"b\""));
+        assert!(!calls[1].skipped);
+
+        // Round-trips through the trace
+        let value = serde_json::to_value(&transcript).unwrap();
+        assert!(value["turns"][1].get("tool_calls").is_none());
+        assert_eq!(
+            serde_json::from_value::<AgentTranscript>(value).unwrap(),
+            transcript
+        );
+    }
+
+    #[test]
+    fn render_reads_as_a_dialogue() {
+        let recorder = TranscriptRecorder::new(None, "What's in the repo?");
+        recorder.turn(
+            String::new(),
+            None,
+            vec![("list".into(), json!({ "dir": "." }))],
+        );
+        recorder.call_started("c1");
+        recorder.call_finished("c1", "src\nCargo.toml");
+        recorder.turn("A Rust crate.".into(), None, vec![]);
+
+        let text = render(&recorder.transcript());
+        assert!(!text.contains("System:"));
+        assert!(text.starts_with("User:\n  What's in the repo?\n"));
+        assert!(text.contains("── Turn 1 ──\n→ list {\"dir\":\".\"}\n  ← src\n    Cargo.toml\n"));
+        assert!(text.contains("── Turn 2 ──\nAssistant:\n  A Rust crate.\n"));
+    }
+}
@@ -33,11 +33,16 @@
 //! | [`ast`] | YAML parsing → `Workflow`, `Task`, `TaskAction`, `OutputPolicy` |
 //! | [`runtime`] | DAG execution with tokio concurrency |
 //! | [`dag`] | Dependency graph with FxHashMap optimization |
-//! | [`binding`] | Use block system: entry, resolve, template |
+//! | [`binding`] | Use block system: entry, resolve, template, filter |
 //! | [`store`] | Thread-safe task output storage (DashMap) |
+//! | [`codec`] | Output format registry: json, yaml, csv, markdown-table, xml |
 //! | [`event`] | Event sourcing for audit trail |
 //! | [`provider`] | LLM provider abstraction (rig-core v0.31) |
 //! | [`util`] | String interning, JSONPath parser |
+//! | `daemon` | Keep-alive daemon for `nika run` (unix only, feature `cli`) |
+//! | `relay` | Share relay for `nika run --share` / `nika watch <url>` (feature `http`) |
+//! | `tail` | Remote tail of daemon runs (`nika watch <run-id> --server`, feature `http`) |
+//! | `lsp` | Language server for `.nika.yaml` (feature `lsp`) |
 //! | [`error`] | Error types with fix suggestions |
 
 // ═══════════════════════════════════════════════════════════════
@@ -55,10 +60,19 @@ pub mod runtime;
 // ═══════════════════════════════════════════════════════════════
 // INFRASTRUCTURE LAYER - Storage, events, providers
 // ═══════════════════════════════════════════════════════════════
+pub mod codec;
+#[cfg(all(unix, feature = "cli"))]
+pub mod daemon;
 pub mod event;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod mcp;
 pub mod provider;
+#[cfg(feature = "http")]
+pub mod relay;
 pub mod store;
+#[cfg(feature = "http")]
+pub mod tail;
 pub mod tools;
 pub mod tui;
 pub mod util;
@@ -98,7 +112,7 @@ pub use ast::{
 pub use runtime::{Runner, TaskExecutor};
 
 // DAG types
-pub use dag::{validate_use_wiring, FlowGraph};
+pub use dag::{validate_use_wiring, validate_use_wiring_with, FlowGraph};
 
 // Binding types
 pub use binding::{validate_task_id, ResolvedBindings, UseEntry, WiringSpec};
@@ -0,0 +1,570 @@
+//! Document analysis for the language server
+//!
+//! Pure functions over the document text, so they can be tested without a
+//! client: diagnostics (validation + lints), completion, go-to-definition.
+//!
+//! serde_yaml has no node spans, so positions come from a light line scan:
+//! task `id:` lines, top-level keys and the keys named in a JSON pointer.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, NumberOrString, Position,
+    Range,
+};
+
+use crate::ast::schema_validator::WorkflowSchemaValidator;
+use crate::ast::Workflow;
+use crate::dag::{
+    lint_workflow, validate_use_wiring_with, ConnectionPolicy, FlowGraph, LintConfig, Severity,
+};
+use crate::error::NikaError;
+
+/// Task verbs with a short description
+const VERBS: &[(&str, &str)] = &[
+    ("infer", "One-shot LLM call"),
+    ("exec", "Shell command"),
+    ("fetch", "HTTP request"),
+    ("invoke", "MCP tool call or resource read"),
+    ("agent", "Multi-turn agent loop with MCP tools"),
+    ("reduce", "Combine an array binding into one output"),
+    ("approve", "Wait for a human to approve or reject"),
+    ("embed", "Embed text, optionally into a vector collection"),
+    ("recall", "Find the stored texts closest to a query"),
+    ("retrieve", "Rank chunks of local files against a query"),
+    (
+        "validate",
+        "Check a binding against a JSON Schema or field rules",
+    ),
+    ("transcribe", "Speech to text from an audio file"),
+    ("import", "Read a CSV or XLSX file into rows"),
+    ("export", "Write rows to a CSV or XLSX file"),
+    ("rows", "Filter, group, sort and limit an array of rows"),
+    ("script", "Run a sandboxed Rhai snippet over the bindings"),
+    ("chunk", "Split long text into chunks for for_each"),
+    ("dedupe", "Drop near-duplicate items of an array"),
+    ("detect_lang", "Detect the language of text offline"),
+    ("translate", "Translate text with a glossary"),
+    ("ws", "Collect messages from a WebSocket endpoint"),
+];
+
+/// Task-level keys offered next to the verbs
+const TASK_KEYS: &[&str] = &[
+    "id",
+    "use",
+    "output",
+    "for_each",
+    "as",
+    "concurrency",
+    "fail_fast",
+    "decompose",
+];
+
+/// Model names offered after `model:` (provider defaults and common picks)
+const MODELS: &[&str] = &[
+    "claude-sonnet-4-20250514",
+    "claude-opus-4-20250514",
+    "claude-3-5-haiku-latest",
+    "gpt-4o",
+    "gpt-4o-mini",
+    "mistral-large-latest",
+    "llama3.2",
+    "llama-3.3-70b-versatile",
+    "deepseek-chat",
+];
+
+static VALIDATOR: LazyLock<Option<WorkflowSchemaValidator>> =
+    LazyLock::new(|| WorkflowSchemaValidator::new().ok());
+
+static TASK_ID_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*(?:-\s+)?id:\s*["']?([A-Za-z0-9_\-]+)["']?\s*(?:#.*)?$"#).unwrap()
+});
+
+/// Validate a document: YAML syntax, JSON Schema, semantics, then lints
+///
+/// `dir` is the document's directory, used to find `.nika/config.toml`.
+pub fn diagnostics(text: &str, dir: Option<&Path>) -> Vec<Diagnostic> {
+    let doc = Document::new(text);
+
+    let value: serde_yaml::Value = match serde_yaml::from_str(text) {
+        Ok(value) => value,
+        Err(e) => {
+            let line = e.location().map_or(0, |l| l.line().saturating_sub(1));
+            return vec![error_at(line, "NIKA-001", e.to_string())];
+        }
+    };
+
+    if let Some(validator) = VALIDATOR.as_ref() {
+        let json = serde_json::to_value(&value).unwrap_or_default();
+        if let Err(err) = validator.validate_value(&json) {
+            let NikaError::SchemaValidationFailed { errors } = &err else {
+                return vec![error_at(0, err.code(), err.to_string())];
+            };
+            // One diagnostic per schema violation, at the offending key
+            return errors
+                .iter()
+                .map(|e| {
+                    let line = doc.pointer_line(&e.path);
+                    error_at(line, err.code(), format!("{}: {}", e.path, e.message))
+                })
+                .collect();
+        }
+    }
+
+    let workflow: Workflow = match serde_yaml::from_value(value) {
+        Ok(workflow) => workflow,
+        Err(e) => return vec![error_at(0, "NIKA-001", e.to_string())],
+    };
+    let flow_graph = FlowGraph::from_workflow(&workflow);
+    let semantic = workflow
+        .validate_schema()
+        .and_then(|_| flow_graph.detect_cycles())
+        .and_then(|_| {
+            let policy = dir
+                .and_then(|dir| ConnectionPolicy::discover(dir).ok())
+                .unwrap_or_default();
+            validate_use_wiring_with(&workflow, &flow_graph, &policy)
+        });
+    if let Err(e) = semantic {
+        let line = error_task_id(&e)
+            .and_then(|id| doc.task_line(id))
+            .unwrap_or(0);
+        return vec![error_at(line, e.code(), e.to_string())];
+    }
+
+    let config = dir
+        .and_then(|dir| LintConfig::discover(dir).ok())
+        .unwrap_or_default();
+    lint_workflow(&workflow, &config)
+        .into_iter()
+        .map(|lint| Diagnostic {
+            range: doc.line_range(doc.task_line(&lint.task_id).unwrap_or(0)),
+            severity: Some(match lint.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warn => DiagnosticSeverity::WARNING,
+                _ => DiagnosticSeverity::INFORMATION,
+            }),
+            code: Some(NumberOrString::String(lint.code.to_string())),
+            source: Some("nika".to_string()),
+            message: format!("{} ({})", lint.message, lint.rule),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Completion items at a position
+///
+/// `mcp_tools` maps MCP server names to their tool names (may be partial).
+pub fn completions(
+    text: &str,
+    position: Position,
+    mcp_tools: &HashMap<String, Vec<String>>,
+) -> Vec<CompletionItem> {
+    let doc = Document::new(text);
+    let line = position.line as usize;
+    let Some(current) = doc.lines.get(line) else {
+        return Vec::new();
+    };
+    let prefix: String = current.chars().take(position.character as usize).collect();
+    let key = prefix
+        .trim_start()
+        .trim_start_matches("- ")
+        .split_once(':')
+        .map(|(key, _)| key.trim());
+
+    let items = |names: Vec<String>, kind| -> Vec<CompletionItem> {
+        names
+            .into_iter()
+            .map(|label| CompletionItem {
+                label,
+                kind: Some(kind),
+                ..Default::default()
+            })
+            .collect()
+    };
+
+    match key {
+        Some("model") => items(
+            MODELS.iter().map(|m| m.to_string()).collect(),
+            CompletionItemKind::VALUE,
+        ),
+        Some("mcp") => items(doc.mcp_servers(), CompletionItemKind::MODULE),
+        Some("tool") => {
+            let servers = doc.task_mcp(line);
+            let tools = mcp_tools
+                .iter()
+                .filter(|(server, _)| servers.is_empty() || servers.contains(server))
+                .flat_map(|(_, tools)| tools.iter().cloned())
+                .collect();
+            items(tools, CompletionItemKind::FUNCTION)
+        }
+        Some("source" | "target") => items(doc.task_ids(), CompletionItemKind::REFERENCE),
+        Some(_) if doc.parent_key(line).as_deref() == Some("use") => {
+            items(doc.task_ids(), CompletionItemKind::REFERENCE)
+        }
+        Some(_) => Vec::new(),
+        None => match doc.parent_key(line).as_deref() {
+            Some("mcp") => items(doc.mcp_servers(), CompletionItemKind::MODULE),
+            Some("tasks") => VERBS
+                .iter()
+                .map(|(verb, detail)| CompletionItem {
+                    label: verb.to_string(),
+                    kind: Some(CompletionItemKind::KEYWORD),
+                    detail: Some(detail.to_string()),
+                    ..Default::default()
+                })
+                .chain(TASK_KEYS.iter().map(|key| CompletionItem {
+                    label: key.to_string(),
+                    kind: Some(CompletionItemKind::PROPERTY),
+                    ..Default::default()
+                }))
+                .collect(),
+            _ => Vec::new(),
+        },
+    }
+}
+
+/// Location of the task whose id is under the cursor (flows, use: bindings)
+pub fn definition(text: &str, position: Position) -> Option<Range> {
+    let doc = Document::new(text);
+    let line = doc.lines.get(position.line as usize)?;
+    if TASK_ID_LINE.is_match(line) {
+        return None;
+    }
+
+    let chars: Vec<char> = line.chars().collect();
+    let is_word = |c: &char| c.is_ascii_alphanumeric() || *c == '_' || *c == '-';
+    let col = (position.character as usize).min(chars.len());
+    let start = chars[..col]
+        .iter()
+        .rposition(|c| !is_word(c))
+        .map_or(0, |i| i + 1);
+    let end = chars[col..]
+        .iter()
+        .position(|c| !is_word(c))
+        .map_or(chars.len(), |i| col + i);
+    let word: String = chars[start..end].iter().collect();
+
+    doc.task_line(&word).map(|line| doc.line_range(line))
+}
+
+/// Task ID an error points at, if any
+fn error_task_id(error: &NikaError) -> Option<&str> {
+    match error {
+        NikaError::UseUnknownTask { task_id, .. }
+        | NikaError::UseNotUpstream { task_id, .. }
+        | NikaError::UseCircularDep { task_id, .. }
+        | NikaError::ConnectionNeedsBridge { task_id, .. }
+        | NikaError::ConnectionBlocked { task_id, .. }
+        | NikaError::UnknownAlias { task_id, .. } => Some(task_id),
+        NikaError::InvalidTaskId { id, .. } => Some(id),
+        _ => None,
+    }
+}
+
+fn error_at(line: usize, code: &str, message: String) -> Diagnostic {
+    Diagnostic {
+        range: Range::new(
+            Position::new(line as u32, 0),
+            Position::new(line as u32, u32::MAX),
+        ),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(code.to_string())),
+        source: Some("nika".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Line-oriented view of a workflow document
+struct Document<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Document<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().collect(),
+        }
+    }
+
+    fn line_range(&self, line: usize) -> Range {
+        let len = self.lines.get(line).map_or(0, |l| l.chars().count());
+        Range::new(
+            Position::new(line as u32, 0),
+            Position::new(line as u32, len as u32),
+        )
+    }
+
+    /// (task id, line) for every `id:` line
+    fn task_lines(&self) -> impl Iterator<Item = (&'a str, usize)> + '_ {
+        self.lines.iter().enumerate().filter_map(|(i, line)| {
+            let id = TASK_ID_LINE.captures(line)?.get(1)?.as_str();
+            Some((id, i))
+        })
+    }
+
+    fn task_ids(&self) -> Vec<String> {
+        self.task_lines().map(|(id, _)| id.to_string()).collect()
+    }
+
+    fn task_line(&self, id: &str) -> Option<usize> {
+        self.task_lines()
+            .find(|(task, _)| *task == id)
+            .map(|(_, line)| line)
+    }
+
+    /// Best-effort line for a JSON pointer like `/tasks/2/infer/model`
+    fn pointer_line(&self, pointer: &str) -> usize {
+        let segments: Vec<&str> = pointer.split('/').filter(|s| !s.is_empty()).collect();
+        let mut line = match segments.as_slice() {
+            ["tasks", idx, ..] => match idx.parse::<usize>() {
+                Ok(idx) => self.task_lines().nth(idx).map(|(_, line)| line),
+                Err(_) => None,
+            },
+            [key, ..] => self.top_level_key(key),
+            [] => None,
+        }
+        .unwrap_or(0);
+
+        // Narrow down to the deepest named key inside the task
+        if segments.first() == Some(&"tasks") {
+            let end = self
+                .task_lines()
+                .map(|(_, l)| l)
+                .find(|l| *l > line)
+                .unwrap_or(self.lines.len());
+            for segment in segments
+                .iter()
+                .skip(2)
+                .filter(|s| s.parse::<usize>().is_err())
+            {
+                let key = format!("{}:", segment);
+                if let Some(found) = (line..end).find(|i| {
+                    let trimmed = self.lines[*i].trim_start().trim_start_matches("- ");
+                    trimmed.starts_with(&key)
+                }) {
+                    line = found;
+                }
+            }
+        }
+        line
+    }
+
+    fn top_level_key(&self, key: &str) -> Option<usize> {
+        let key = format!("{}:", key);
+        self.lines.iter().position(|l| l.starts_with(&key))
+    }
+
+    /// Nearest enclosing mapping key of a line
+    ///
+    /// Keys of a `- ` sequence item resolve to the key owning the sequence,
+    /// so every key inside a task reports `tasks`.
+    fn parent_key(&self, line: usize) -> Option<String> {
+        let current = self.lines.get(line)?;
+        let indent = indent_of(current);
+        let is_item = current.trim_start().starts_with('-');
+
+        for (idx, l) in self.lines[..line].iter().enumerate().rev() {
+            if l.trim().is_empty() || l.trim_start().starts_with('#') {
+                continue;
+            }
+            let l_indent = indent_of(l);
+            let l_item = l.trim_start().starts_with("- ");
+            if is_item {
+                // Sequence items may sit at the same indent as their key
+                if l_indent < indent || (l_indent == indent && !l_item) {
+                    return key_of(l);
+                }
+            } else if l_indent < indent {
+                if l_item && indent == l_indent + 2 {
+                    return self.parent_key(idx);
+                }
+                return key_of(l);
+            }
+        }
+        None
+    }
+
+    /// Server names declared under the workflow `mcp:` block
+    fn mcp_servers(&self) -> Vec<String> {
+        let Some(start) = self.top_level_key("mcp") else {
+            return Vec::new();
+        };
+        let mut servers = Vec::new();
+        let mut child_indent = None;
+        for line in &self.lines[start + 1..] {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let indent = indent_of(line);
+            if indent == 0 {
+                break;
+            }
+            if *child_indent.get_or_insert(indent) == indent {
+                if let Some((name, _)) = line.trim().split_once(':') {
+                    servers.push(name.trim().to_string());
+                }
+            }
+        }
+        servers
+    }
+
+    /// `mcp:` server(s) named by the task containing `line`
+    fn task_mcp(&self, line: usize) -> Vec<String> {
+        let starts: Vec<usize> = self.task_lines().map(|(_, l)| l).collect();
+        let start = starts
+            .iter()
+            .rev()
+            .find(|l| **l <= line)
+            .copied()
+            .unwrap_or(0);
+        let end = starts
+            .iter()
+            .find(|l| **l > line)
+            .copied()
+            .unwrap_or(self.lines.len());
+        self.lines[start..end]
+            .iter()
+            .filter_map(|l| l.trim().strip_prefix("mcp:"))
+            .flat_map(|v| {
+                v.trim_matches(|c: char| c.is_whitespace() || c == '[' || c == ']')
+                    .split(',')
+                    .map(|s| s.trim().trim_matches(['"', '\'']).to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+fn key_of(line: &str) -> Option<String> {
+    let body = line.trim_start().trim_start_matches("- ");
+    body.split_once(':').map(|(key, _)| key.trim().to_string())
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"schema: "nika/workflow@0.5"
+mcp:
+  novanet:
+    command: novanet-mcp
+tasks:
+  - id: fetch
+    exec:
+      command: "curl example.com"
+      timeout: 10
+  - id: summarize
+    use:
+      page: fetch
+    infer:
+      prompt: "Summarize {{use.page}}"
+      max_tokens: 200
+  - id: lookup
+    invoke:
+      mcp: novanet
+      tool:
+flows:
+  - source: fetch
+    target: summarize
+"#;
+
+    fn labels(items: Vec<CompletionItem>) -> Vec<String> {
+        items.into_iter().map(|i| i.label).collect()
+    }
+
+    #[test]
+    fn test_clean_document_has_no_errors() {
+        let yaml = YAML.replace("tool:\n", "tool: novanet_describe\n");
+        let diags = diagnostics(&yaml, None);
+        assert!(
+            diags
+                .iter()
+                .all(|d| d.severity != Some(DiagnosticSeverity::ERROR)),
+            "{:?}",
+            diags
+        );
+    }
+
+    #[test]
+    fn test_wiring_error_points_at_task() {
+        let yaml = YAML
+            .replace("tool:\n", "tool: novanet_describe\n")
+            .replace("page: fetch", "page: missing");
+        let diags = diagnostics(&yaml, None);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].code,
+            Some(NumberOrString::String("NIKA-080".to_string()))
+        );
+        assert_eq!(diags[0].range.start.line, 9); // - id: summarize
+    }
+
+    #[test]
+    fn test_schema_error_points_at_key() {
+        let yaml = YAML.replace("max_tokens: 200", "max_tokens: \"lots\"");
+        let diags = diagnostics(&yaml, None);
+        // infer is string-or-object, so the violation is reported on `infer:`
+        assert_eq!(diags[0].range.start.line, 12);
+    }
+
+    #[test]
+    fn test_yaml_syntax_error() {
+        let diags = diagnostics("tasks: [\n  - id: a\n", None);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].severity, Some(DiagnosticSeverity::ERROR));
+    }
+
+    #[test]
+    fn test_completion_contexts() {
+        let none = HashMap::new();
+        let verbs = labels(completions(YAML, Position::new(16, 4), &none));
+        assert!(verbs.contains(&"infer".to_string()) && verbs.contains(&"agent".to_string()));
+
+        let models = labels(completions("    model: ", Position::new(0, 11), &none));
+        assert!(models.contains(&"gpt-4o".to_string()));
+
+        assert_eq!(
+            labels(completions(YAML, Position::new(17, 11), &none)),
+            vec!["novanet"]
+        );
+        assert_eq!(
+            labels(completions(YAML, Position::new(20, 13), &none)),
+            vec!["fetch", "summarize", "lookup"]
+        );
+        assert_eq!(
+            labels(completions(YAML, Position::new(11, 12), &none)),
+            vec!["fetch", "summarize", "lookup"]
+        );
+
+        let tools = HashMap::from([
+            ("novanet".to_string(), vec!["novanet_describe".to_string()]),
+            ("other".to_string(), vec!["other_tool".to_string()]),
+        ]);
+        assert_eq!(
+            labels(completions(YAML, Position::new(18, 12), &tools)),
+            vec!["novanet_describe"]
+        );
+    }
+
+    #[test]
+    fn test_definition_of_task_references() {
+        // flows: target: summarize
+        let range = definition(YAML, Position::new(21, 14)).unwrap();
+        assert_eq!(range.start.line, 9);
+        // use: page: fetch
+        let range = definition(YAML, Position::new(11, 15)).unwrap();
+        assert_eq!(range.start.line, 5);
+        // on the id line itself there is nothing to jump to
+        assert!(definition(YAML, Position::new(5, 10)).is_none());
+    }
+}
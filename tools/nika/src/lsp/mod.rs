@@ -0,0 +1,18 @@
+//! Language Server - `nika lsp` for `.nika.yaml` files (v0.7)
+//!
+//! Speaks LSP over stdio (tower-lsp):
+//! - `analysis`: diagnostics, completion and go-to-definition on raw text
+//! - `server`: document store, MCP tool cache, LanguageServer impl
+//!
+//! Diagnostics run the same pipeline as `nika check` (YAML, JSON Schema,
+//! use: wiring) followed by `nika lint`. Completion covers verbs, model
+//! names, MCP server and tool names, and task ids in `flows:` and `use:`;
+//! go-to-definition jumps from a task id reference to its `id:` line.
+//!
+//! Editor setup: run `nika lsp` as the server command for `*.nika.yaml`.
+
+mod analysis;
+mod server;
+
+pub use analysis::{completions, definition, diagnostics};
+pub use server::run_stdio;
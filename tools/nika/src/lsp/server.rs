@@ -0,0 +1,179 @@
+//! tower-lsp backend for `nika lsp`
+//!
+//! Keeps open documents in memory (full sync), re-publishes diagnostics on
+//! every change, and lists tools of the workflow's MCP servers once per
+//! server in the background so `tool:` completion works offline afterwards.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use rustc_hash::FxHashMap;
+use tower_lsp::jsonrpc::Result;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{Client, LanguageServer, LspService, Server};
+
+use crate::ast::McpConfigInline;
+use crate::mcp::{McpClient, McpConfig};
+use crate::util::MCP_CALL_TIMEOUT;
+
+use super::analysis;
+
+/// Tool names per MCP server (empty when listing failed)
+type ToolCache = Arc<RwLock<HashMap<String, Vec<String>>>>;
+
+struct Backend {
+    client: Client,
+    documents: DashMap<Url, String>,
+    mcp_tools: ToolCache,
+}
+
+impl Backend {
+    async fn refresh(&self, uri: Url, text: String, version: Option<i32>) {
+        let dir = uri
+            .to_file_path()
+            .ok()
+            .and_then(|p| p.parent().map(PathBuf::from));
+        let diagnostics = analysis::diagnostics(&text, dir.as_deref());
+        self.client
+            .publish_diagnostics(uri.clone(), diagnostics, version)
+            .await;
+
+        self.load_mcp_tools(&text);
+        self.documents.insert(uri, text);
+    }
+
+    /// List tools of MCP servers not seen yet (background, best effort)
+    fn load_mcp_tools(&self, text: &str) {
+        let Some(servers) = serde_yaml::from_str::<serde_yaml::Value>(text)
+            .ok()
+            .and_then(|doc| doc.get("mcp").cloned())
+            .and_then(|mcp| serde_yaml::from_value::<FxHashMap<String, McpConfigInline>>(mcp).ok())
+        else {
+            return;
+        };
+
+        for (name, inline_config) in servers {
+            // Reserve the entry so each server is only started once
+            if self.mcp_tools.read().contains_key(&name) {
+                continue;
+            }
+            self.mcp_tools.write().insert(name.clone(), Vec::new());
+
+            let cache = Arc::clone(&self.mcp_tools);
+            tokio::spawn(async move {
+                let mut config = McpConfig::new(&name, &inline_config.command)
+                    .with_args(inline_config.args.iter().cloned());
+                for (key, value) in &inline_config.env {
+                    config = config.with_env(key, value);
+                }
+                if let Some(ref cwd) = inline_config.cwd {
+                    config = config.with_cwd(cwd);
+                }
+
+                let listed = tokio::time::timeout(MCP_CALL_TIMEOUT, async {
+                    let client = McpClient::new(config)?;
+                    client.connect().await?;
+                    let tools = client.list_tools().await;
+                    let _ = client.disconnect().await;
+                    tools
+                })
+                .await;
+
+                match listed {
+                    Ok(Ok(tools)) => {
+                        let names = tools.into_iter().map(|t| t.name).collect();
+                        cache.write().insert(name, names);
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!(server = %name, error = %e, "MCP tool listing failed")
+                    }
+                    Err(_) => tracing::warn!(server = %name, "MCP tool listing timed out"),
+                }
+            });
+        }
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string(), " ".to_string()]),
+                    ..Default::default()
+                }),
+                definition_provider: Some(OneOf::Left(true)),
+                ..Default::default()
+            },
+            server_info: Some(ServerInfo {
+                name: "nika".to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let doc = params.text_document;
+        self.refresh(doc.uri, doc.text, Some(doc.version)).await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        // Full sync: the last change holds the whole document
+        if let Some(change) = params.content_changes.into_iter().last() {
+            let doc = params.text_document;
+            self.refresh(doc.uri, change.text, Some(doc.version)).await;
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.documents.remove(&uri);
+        self.client.publish_diagnostics(uri, Vec::new(), None).await;
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let position = params.text_document_position;
+        let Some(text) = self.documents.get(&position.text_document.uri) else {
+            return Ok(None);
+        };
+        let tools = self.mcp_tools.read().clone();
+        let items = analysis::completions(&text, position.position, &tools);
+        Ok((!items.is_empty()).then_some(CompletionResponse::Array(items)))
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let uri = position.text_document.uri;
+        let Some(text) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        Ok(analysis::definition(&text, position.position)
+            .map(|range| GotoDefinitionResponse::Scalar(Location::new(uri.clone(), range))))
+    }
+}
+
+/// Serve the language server over stdin/stdout until the client exits
+pub async fn run_stdio() {
+    let (service, socket) = LspService::new(|client| Backend {
+        client,
+        documents: DashMap::new(),
+        mcp_tools: ToolCache::default(),
+    });
+    Server::new(tokio::io::stdin(), tokio::io::stdout(), socket)
+        .serve(service)
+        .await;
+}
@@ -4,17 +4,34 @@ use clap::{Parser, Subcommand};
 use colored::Colorize;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 // Import from lib modules
+use nika::ast::inputs::parse_input_args;
 use nika::ast::schema_validator::WorkflowSchemaValidator;
-use nika::ast::{TaskAction, Workflow};
-use nika::dag::{validate_use_wiring, FlowGraph};
+use nika::ast::{apply_overrides, OutputFormat, TaskAction, Workflow};
+use nika::config::NikaConfig;
+#[cfg(unix)]
+use nika::daemon::{Daemon, DaemonClient, Response as DaemonResponse, RunRequest};
+use nika::dag::{validate_use_wiring_with, ConnectionPolicy, FlowGraph};
 use nika::error::NikaError;
+use nika::event::dataset::{self, build_dataset, DatasetFilter};
+use nika::event::report::{ReportOutputs, RunReport};
+use nika::event::{OtelConfig, OtelEmitter};
 use nika::mcp::validation::{McpValidator, ValidationConfig};
 use nika::mcp::{McpClient, McpConfig};
-use nika::runtime::Runner;
+use nika::provider::KeyPool;
+use nika::runtime::debugger::parse_command as parse_debug_command;
+use nika::runtime::scheduler::{self, RunStatus};
+use nika::runtime::{ApprovalCheckpoint, ApprovalGate, DebugCommand, DebugStop, Debugger, Runner};
+use nika::runtime::{ScheduleEvent, ScheduleState, Scheduler, ToolPolicy};
+use nika::store::{HttpCache, PresetStore, RunPreset, StateStore, VectorStore, STATE_FILE};
 use nika::tools::PermissionMode;
+use nika::util::log_format::{JsonLines, LogFormat};
 use nika::Event;
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::fmt::format::JsonFields;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 // ═══════════════════════════════════════════════════════════════════════════
 // HELP TEXT
@@ -37,11 +54,30 @@ const AFTER_HELP: &str = r#"EXAMPLES:
     nika chat                         Start conversational AI agent
     nika chat --provider openai       Chat with OpenAI
     nika run my-flow.nika.yaml        Run workflow (explicit)
+    nika run flow.yaml --set tasks.summarize.model=gpt-4o
+                                      Override a workflow field for one run
+    nika run flow.yaml --matrix model=claude-sonnet-4,gpt-4o
+                                      Compare runs across override values
+    nika run flow.yaml --output json  Status, outputs, tokens and cost as JSON
     nika check my-flow.nika.yaml      Validate workflow syntax
     nika check flow.yaml --strict     Validate with MCP connections
+    nika check flow.yaml --render     Write resolved prompts to rendered/
+    nika watch flow.yaml --run        Re-run on every save
+    nika daemon start &               Keep servers warm; later runs use it
+    nika daemon start --watch         Also run workflows when watched files change
+    nika schedule start               Run workflows with cron triggers on time
+    nika state set last_id 42         Persist a value read as {{state.last_id}}
+    nika debug flow.yaml --break summarize
+                                      Step through tasks, edit prompts, skip
+    nika fmt *.nika.yaml --check      Verify canonical formatting (CI)
     nika studio my-flow.nika.yaml     Open workflow in editor
     nika init                         Initialize a new project
     nika trace list                   View execution traces
+    nika dataset build --task summarize
+                                      Build fine-tuning JSONL from traces
+    nika stats --workflow digest      Success rate, p50/p95, tokens and cost
+    nika --log-format json run flow.yaml
+                                      JSON log lines with run_id/task_id (CI)
 
 VIEWS (in TUI):
     [a] Chat     Conversational agent interface
@@ -69,6 +105,11 @@ struct Cli {
     #[arg(value_name = "WORKFLOW")]
     file: Option<PathBuf>,
 
+    /// Log output: text or json (one object per line, with run_id/task_id).
+    /// Defaults to NIKA_LOG_FORMAT, else text.
+    #[arg(long, global = true, value_name = "FORMAT")]
+    log_format: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -106,6 +147,84 @@ enum Commands {
         /// Override default model
         #[arg(short, long)]
         model: Option<String>,
+
+        /// Override a workflow field (repeatable): --set tasks.summarize.model=gpt-4o
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<String>,
+
+        /// Value of a declared input (repeatable): --input topic=rust
+        #[arg(long = "input", value_name = "NAME=VALUE")]
+        inputs: Vec<String>,
+
+        /// Run once per combination (repeatable): --matrix model=claude-sonnet-4,gpt-4o
+        #[arg(long, value_name = "PATH=V1,V2")]
+        matrix: Vec<String>,
+
+        /// Maximum matrix runs in parallel
+        #[arg(long, default_value = "2", requires = "matrix")]
+        matrix_concurrency: usize,
+
+        /// Print only the final output on stdout (logs and errors on stderr)
+        #[arg(long, conflicts_with = "matrix")]
+        output_only: bool,
+
+        /// Print a JSON report of the run on stdout instead of text (`json`)
+        #[arg(long, value_name = "FORMAT", conflicts_with_all = ["matrix", "output_only"])]
+        output: Option<String>,
+
+        /// Task outputs in the --output json report: all (default), final or none
+        #[arg(long, value_name = "WHICH", requires = "output")]
+        report_outputs: Option<String>,
+
+        /// Record per-phase timings (bindings, provider, first token, output, store) in the trace
+        #[arg(long, conflicts_with = "matrix")]
+        phases: bool,
+
+        /// Warm provider sessions kept across tasks, routed by model (0 disables)
+        #[arg(long, value_name = "N", default_value_t = nika::provider::pool::DEFAULT_POOL_SIZE)]
+        session_pool: usize,
+
+        /// Run locally even when a `nika daemon` is listening
+        #[arg(long)]
+        no_daemon: bool,
+
+        /// Stream redacted events to a relay and print a `nika watch` URL
+        #[arg(long, conflicts_with = "matrix")]
+        share: bool,
+
+        /// Relay for --share (default: [share] relay in the user config)
+        #[arg(long, value_name = "URL", requires = "share")]
+        relay: Option<String>,
+
+        /// Ignore cached fetch: responses and fetch them again
+        #[arg(long)]
+        refresh: bool,
+
+        /// Apply a saved preset (flags given here win over its options)
+        #[arg(long, value_name = "NAME")]
+        preset: Option<String>,
+
+        /// Save this run's provider, model, --set and --input as a preset
+        #[arg(long, value_name = "NAME")]
+        save_preset: Option<String>,
+
+        /// Let agents call tools matching this glob without asking (repeatable)
+        #[arg(long = "auto-approve", value_name = "PATTERN")]
+        auto_approve: Vec<String>,
+    },
+
+    /// Step through a workflow, pausing before each task
+    Debug {
+        /// Path to .nika.yaml file
+        file: String,
+
+        /// Only pause at these tasks (repeatable): --break summarize
+        #[arg(long = "break", value_name = "TASK")]
+        breakpoints: Vec<String>,
+
+        /// Debug in the TUI Monitor view instead of the terminal
+        #[arg(long)]
+        tui: bool,
     },
 
     /// Validate a workflow file
@@ -117,6 +236,98 @@ enum Commands {
         /// Enable strict mode: connect to MCP servers and validate invoke params
         #[arg(long)]
         strict: bool,
+
+        /// Render every task's prompt/command/URL to files for review
+        #[arg(long)]
+        render: bool,
+
+        /// Fill bindings with outputs from a past trace (generation ID or prefix)
+        #[arg(long, requires = "render")]
+        trace: Option<String>,
+
+        /// Output directory for rendered files
+        #[arg(long, default_value = "rendered", requires = "render")]
+        out: PathBuf,
+
+        /// Override a workflow field (repeatable): --set tasks.summarize.model=gpt-4o
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<String>,
+    },
+
+    /// Report semantic lints (unused aliases, missing limits, ...)
+    Lint {
+        /// Path to .nika.yaml file
+        file: String,
+
+        /// Output format (text, json)
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// Override a workflow field (repeatable): --set tasks.summarize.model=gpt-4o
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<String>,
+    },
+
+    /// Start the language server (LSP over stdio) for editors
+    #[cfg(feature = "lsp")]
+    Lsp,
+
+    /// Format workflow files canonically
+    Fmt {
+        /// Workflow files to format
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Don't write; fail if any file isn't formatted (for CI)
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Convert a workflow from an older format to the verb format
+    Migrate {
+        /// Workflow file to convert
+        file: PathBuf,
+
+        /// Source format (keyword: v4.x llm/agent/subagent/shell/http/mcp/function)
+        #[arg(long)]
+        from: String,
+
+        /// Write the converted workflow here instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Re-validate (or re-run) a workflow whenever it changes, or follow a shared or remote run
+    #[cfg(feature = "watch")]
+    Watch {
+        /// Path to .nika.yaml file, a URL printed by `nika run --share`, or a
+        /// run id with --server
+        file: String,
+
+        /// Follow run <FILE> on the daemon serving events at this URL
+        /// (`nika daemon start --http`)
+        #[arg(long, value_name = "URL")]
+        server: Option<String>,
+
+        /// Print a followed run's events instead of opening the Monitor
+        #[arg(long)]
+        headless: bool,
+
+        /// Re-run the workflow after each successful validation
+        #[arg(long)]
+        run: bool,
+
+        /// Also watch these files or directories (e.g. prompt files)
+        #[arg(long = "also", value_name = "PATH")]
+        also: Vec<PathBuf>,
+
+        /// Quiet period before reacting to changes (milliseconds)
+        #[arg(long, default_value = "300")]
+        debounce: u64,
+
+        /// Override a workflow field (repeatable): --set tasks.summarize.model=gpt-4o
+        #[arg(long = "set", value_name = "PATH=VALUE")]
+        overrides: Vec<String>,
     },
 
     /// Initialize a new Nika project in the current directory
@@ -136,6 +347,57 @@ enum Commands {
         action: TraceAction,
     },
 
+    /// Build datasets from accumulated traces
+    Dataset {
+        #[command(subcommand)]
+        action: DatasetAction,
+    },
+
+    /// Success rate, durations, tokens and cost per workflow, from traces
+    Stats {
+        /// Only this workflow (its name, or its hash if unnamed)
+        #[arg(short, long)]
+        workflow: Option<String>,
+        /// Only the last N traces
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Serve shared runs to `nika watch` (for `nika run --share`)
+    Relay {
+        /// Address to listen on (use 0.0.0.0:PORT to accept teammates)
+        #[arg(long, default_value = nika::relay::DEFAULT_LISTEN)]
+        listen: String,
+    },
+
+    /// Keep MCP servers connected and providers warm for instant runs
+    #[cfg(unix)]
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+
+    /// Run workflows on their `triggers: { cron: ... }` schedule
+    Schedule {
+        #[command(subcommand)]
+        action: ScheduleAction,
+    },
+
+    /// Inspect and edit the project state (.nika/state.json)
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Workflow JSON Schema for editor integration
+    Schema {
+        #[command(subcommand)]
+        action: SchemaAction,
+    },
+
     /// [deprecated] Use 'nika' instead
     #[cfg(feature = "tui")]
     #[command(hide = true)]
@@ -158,18 +420,74 @@ enum TraceAction {
     Show {
         /// Generation ID or partial match
         id: String,
+        /// Show where each use: binding value came from
+        #[arg(long)]
+        bindings: bool,
+        /// Print the conversation of an agent: task
+        #[arg(long, value_name = "TASK", conflicts_with = "bindings")]
+        agent: Option<String>,
     },
 
     /// Export trace to file
     Export {
         /// Generation ID
-        id: String,
-        /// Output format (json, yaml)
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+        /// Output format (json, yaml, or cypher/graphml for run lineage)
         #[arg(short, long, default_value = "json")]
         format: String,
         /// Output file (stdout if not specified)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        /// Export the lineage of every trace (cypher, graphml)
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+        /// Zip an anonymized trace and environment info for a bug report
+        #[arg(long, conflicts_with_all = ["all", "format"])]
+        anonymize: bool,
+    },
+
+    /// Show where wall-clock time went, per task and phase
+    Flame {
+        /// Generation ID or partial match
+        id: String,
+        /// Print folded stacks (for inferno-flamegraph / speedscope)
+        #[arg(long)]
+        folded: bool,
+        /// Output file (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Compare two runs task by task (status, duration, tokens, prompts, outputs)
+    Diff {
+        /// Generation ID or partial match of the earlier run
+        a: String,
+        /// Generation ID or partial match of the later run
+        b: String,
+    },
+
+    /// Show the DataStore as of a point in a run, and a task's bindings
+    Inspect {
+        /// Generation ID or partial match
+        id: String,
+        /// Replay up to and including this point (event:<id> or task:<id>)
+        #[arg(
+            long,
+            value_name = "POINT",
+            conflicts_with = "before",
+            required_unless_present = "before"
+        )]
+        at: Option<String>,
+        /// Replay up to just before this point (event:<id> or task:<id>)
+        #[arg(long, value_name = "POINT")]
+        before: Option<String>,
+        /// Show the bindings this task saw (default: the task in the point)
+        #[arg(short, long)]
+        task: Option<String>,
+        /// Print the snapshot as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Delete old traces
@@ -178,6 +496,131 @@ enum TraceAction {
         #[arg(short, long, default_value = "10")]
         keep: usize,
     },
+
+    /// Replay a recorded trace (TUI Monitor, or headless)
+    Replay {
+        /// Generation ID or partial match
+        id: String,
+        /// Playback speed multiplier (0 = instant)
+        #[arg(short, long, default_value = "1.0")]
+        speed: f64,
+        /// Print events to stdout instead of launching the TUI
+        #[arg(long)]
+        headless: bool,
+        /// Re-execute this workflow using recorded provider responses
+        #[arg(short, long)]
+        workflow: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DatasetAction {
+    /// Extract (prompt, output) pairs for a task as fine-tuning JSONL
+    Build {
+        /// Task ID to extract examples for
+        #[arg(short, long)]
+        task: String,
+
+        /// Filter expression key=value (status=success|failed|any, model=NAME)
+        #[arg(short, long)]
+        filter: Vec<String>,
+
+        /// Minimum judge score (reads numeric `score` from output)
+        #[arg(long)]
+        min_score: Option<f64>,
+
+        /// Task whose output carries the score (default: the task itself)
+        #[arg(long)]
+        judge: Option<String>,
+
+        /// Output file (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[cfg(unix)]
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Run the daemon in the foreground (socket: .nika/daemon.sock)
+    Start {
+        /// Warm provider sessions kept across runs, routed by model
+        #[arg(long, value_name = "N", default_value_t = nika::provider::pool::DEFAULT_POOL_SIZE)]
+        session_pool: usize,
+
+        /// Also run workflows with `triggers: { watch }` found in these
+        /// files or directories (default: .)
+        #[cfg(feature = "watch")]
+        #[arg(long, value_name = "PATH", num_args = 0..)]
+        watch: Option<Vec<PathBuf>>,
+
+        /// Also serve run events over HTTP for `nika watch <run-id> --server`
+        /// (e.g. 127.0.0.1:7879; use 0.0.0.0 to reach other machines)
+        #[arg(long, value_name = "ADDR")]
+        http: Option<String>,
+    },
+
+    /// Stop the daemon listening in this directory
+    Stop,
+
+    /// Show uptime, runs and warm sessions/servers
+    Status,
+}
+
+#[derive(Subcommand)]
+enum ScheduleAction {
+    /// Run the scheduler in the foreground (history: .nika/schedule.json)
+    Start {
+        /// Workflow files or directories to scan
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Show scheduled workflows, their next run and last result
+    List {
+        /// Workflow files or directories to scan
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateAction {
+    /// List keys with their values and expiry
+    List,
+
+    /// Print a value (strings unquoted, for shell use)
+    Get { key: String },
+
+    /// Set a value (parsed as JSON, else stored as a string)
+    Set {
+        key: String,
+        value: String,
+
+        /// Expire the key after this many seconds
+        #[arg(long, value_name = "SECS")]
+        ttl_secs: Option<u64>,
+    },
+
+    /// Remove a key
+    Delete { key: String },
+
+    /// Remove every key
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum SchemaAction {
+    /// Print the workflow JSON Schema (for yaml-language-server)
+    Export {
+        /// Pin the schema to one workflow version (e.g. 0.2)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Output file (stdout if not specified)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 #[tokio::main]
@@ -187,16 +630,43 @@ async fn main() {
 
     let cli = Cli::parse();
 
+    // Caught task panics keep their backtrace for the crash bundle (v0.7)
+    nika::util::crash::install_hook();
+
     // Determine if we're running TUI (skip tracing to avoid terminal pollution)
     let is_tui = is_tui_mode(&cli);
 
     if !is_tui {
-        tracing_subscriber::fmt()
+        let log_format = match LogFormat::resolve(cli.log_format.as_deref()) {
+            Ok(format) => format,
+            Err(value) => {
+                eprintln!(
+                    "{} Unknown log format: {}. Use 'text' or 'json'",
+                    "Error:".red().bold(),
+                    value
+                );
+                std::process::exit(1);
+            }
+        };
+        let writer = if stdout_reserved(&cli) {
+            BoxMakeWriter::new(std::io::stderr)
+        } else {
+            BoxMakeWriter::new(std::io::stdout)
+        };
+        let subscriber = tracing_subscriber::fmt()
             .with_env_filter(
                 tracing_subscriber::EnvFilter::from_default_env()
                     .add_directive(tracing::Level::INFO.into()),
             )
-            .init();
+            .with_writer(writer);
+        match log_format {
+            LogFormat::Text => subscriber.init(),
+            // Structured lines for log pipelines (v0.7)
+            LogFormat::Json => subscriber
+                .fmt_fields(JsonFields::new())
+                .event_format(JsonLines)
+                .init(),
+        }
     }
 
     // Handle positional file argument first (nika workflow.nika.yaml)
@@ -211,7 +681,14 @@ async fn main() {
 
         // Check if it's a .nika.yaml file
         if is_nika_workflow(file) {
-            let result = run_workflow(&file.display().to_string(), None, None).await;
+            let result = run_workflow(
+                &file.display().to_string(),
+                None,
+                None,
+                &[],
+                RunOptions::default(),
+            )
+            .await;
             handle_result(result);
             return;
         } else {
@@ -254,17 +731,143 @@ async fn main() {
             file,
             provider,
             model,
-        }) => run_workflow(&file, provider, model).await,
+            overrides,
+            inputs,
+            matrix,
+            matrix_concurrency,
+            output_only,
+            output,
+            report_outputs,
+            phases,
+            session_pool,
+            no_daemon,
+            share,
+            relay,
+            refresh,
+            preset,
+            save_preset,
+            auto_approve,
+        }) => match resolve_run_preset(
+            &file,
+            preset.as_deref(),
+            save_preset.as_deref(),
+            (provider, model, overrides, &inputs),
+            output_only || output.is_some(),
+        ) {
+            Err(e) => Err(e),
+            Ok(preset) if matrix.is_empty() => {
+                let options = RunOptions {
+                    output_only,
+                    output,
+                    report_outputs,
+                    phases,
+                    session_pool,
+                    no_daemon,
+                    share: share.then_some(relay),
+                    refresh,
+                    inputs: preset.input_args(),
+                    auto_approve,
+                };
+                run_workflow(
+                    &file,
+                    preset.provider,
+                    preset.model,
+                    &preset.overrides,
+                    options,
+                )
+                .await
+            }
+            Ok(RunPreset {
+                provider,
+                model,
+                mut overrides,
+                ..
+            }) => {
+                // --provider/--model become plain overrides for every variant
+                overrides.extend(provider.map(|p| format!("provider={}", p)));
+                overrides.extend(model.map(|m| format!("model={}", m)));
+                run_matrix_cli(&file, &overrides, &matrix, matrix_concurrency).await
+            }
+        },
+
+        // Step-through debugger
+        Some(Commands::Debug {
+            file,
+            breakpoints,
+            tui,
+        }) => {
+            if tui {
+                nika::tui::run_tui_debug(Path::new(&file), breakpoints).await
+            } else {
+                debug_workflow(&file, breakpoints).await
+            }
+        }
 
         // Check/Validate workflow
-        Some(Commands::Check { file, strict }) => {
-            if strict {
-                validate_workflow_strict(&file).await
+        Some(Commands::Check {
+            file,
+            strict,
+            render,
+            trace,
+            out,
+            overrides,
+        }) => {
+            let result = if strict {
+                validate_workflow_strict(&file, &overrides).await
+            } else {
+                validate_workflow(&file, &overrides)
+            };
+            match result {
+                Ok(()) if render => render_prompts(&file, &overrides, trace.as_deref(), &out),
+                other => other,
+            }
+        }
+
+        // Lint workflow
+        Some(Commands::Lint {
+            file,
+            format,
+            overrides,
+        }) => lint_file(&file, &format, &overrides),
+
+        // Language server
+        #[cfg(feature = "lsp")]
+        Some(Commands::Lsp) => {
+            nika::lsp::run_stdio().await;
+            Ok(())
+        }
+
+        // Format workflows
+        Some(Commands::Fmt { files, check }) => format_files(&files, check),
+
+        // Convert a legacy workflow
+        Some(Commands::Migrate { file, from, output }) => {
+            migrate_file(&file, &from, output.as_deref())
+        }
+
+        // Watch workflow
+        #[cfg(feature = "watch")]
+        Some(Commands::Watch {
+            file,
+            server,
+            headless,
+            run,
+            also,
+            debounce,
+            overrides,
+        }) => {
+            if let Some(server) = server {
+                watch_remote(&server, &file, headless).await
+            } else if is_share_url(&file) {
+                watch_shared(&file, headless).await
             } else {
-                validate_workflow(&file)
+                watch_workflow(&file, run, &also, debounce, &overrides).await
             }
         }
 
+        // Share relay
+        Some(Commands::Relay { listen }) => serve_relay(&listen).await,
+
         // Init project
         Some(Commands::Init {
             permission,
@@ -272,7 +875,32 @@ async fn main() {
         }) => init_project(&permission, no_example),
 
         // Trace commands
-        Some(Commands::Trace { action }) => handle_trace_command(action),
+        Some(Commands::Trace { action }) => handle_trace_command(action).await,
+
+        // Dataset commands
+        Some(Commands::Dataset { action }) => handle_dataset_command(action),
+
+        // Run statistics
+        Some(Commands::Stats {
+            workflow,
+            limit,
+            json,
+        }) => show_stats(workflow.as_deref(), limit, json),
+
+        // Daemon commands
+        #[cfg(unix)]
+        Some(Commands::Daemon { action }) => handle_daemon_command(action).await,
+
+        // Schedule commands
+        Some(Commands::Schedule { action }) => handle_schedule_command(action).await,
+
+        // State commands
+        Some(Commands::State { action }) => handle_state_command(action),
+
+        // Schema commands
+        Some(Commands::Schema {
+            action: SchemaAction::Export { version, output },
+        }) => export_schema(version.as_deref(), output.as_deref()),
 
         // Legacy TUI command (hidden, backward compat)
         #[cfg(feature = "tui")]
@@ -302,18 +930,62 @@ fn is_tui_mode(cli: &Cli) -> bool {
         return true;
     }
 
+    // Following a shared run opens the Monitor
+    #[cfg(all(feature = "tui", feature = "watch"))]
+    if let Some(Commands::Watch {
+        file,
+        server,
+        headless: false,
+        ..
+    }) = &cli.command
+    {
+        return server.is_some() || is_share_url(file);
+    }
+
     // Check TUI-related commands
     #[cfg(feature = "tui")]
     if let Some(ref cmd) = cli.command {
         return matches!(
             cmd,
-            Commands::Chat { .. } | Commands::Studio { .. } | Commands::Tui { .. }
+            Commands::Chat { .. }
+                | Commands::Studio { .. }
+                | Commands::Tui { .. }
+                | Commands::Debug { tui: true, .. }
+                | Commands::Trace {
+                    action: TraceAction::Replay {
+                        headless: false,
+                        workflow: None,
+                        ..
+                    }
+                }
         );
     }
 
     false
 }
 
+/// Whether stdout carries a machine-readable stream (logs must go to stderr)
+///
+/// `nika lsp` speaks JSON-RPC on stdout; `run --output-only` prints only
+/// the workflow's final output there, and `run --output json` its report.
+fn stdout_reserved(cli: &Cli) -> bool {
+    match &cli.command {
+        Some(Commands::Run {
+            output_only,
+            output,
+            ..
+        }) => *output_only || output.is_some(),
+        #[cfg(feature = "lsp")]
+        Some(Commands::Lsp) => true,
+        _ => false,
+    }
+}
+
+/// Whether a `nika watch` argument is a shared-run URL rather than a file
+fn is_share_url(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
 /// Check if a file is a Nika workflow (.nika.yaml)
 fn is_nika_workflow(file: &Path) -> bool {
     let filename = file
@@ -337,84 +1009,1270 @@ fn handle_result(result: Result<(), NikaError>) {
 // WORKFLOW COMMANDS
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// `nika run` switches that shape a single (non-matrix) execution
+struct RunOptions {
+    /// Print only the final output on stdout
+    output_only: bool,
+    /// `--output` format of the run's report (`json`)
+    output: Option<String>,
+    /// `--report-outputs`: task outputs included in the JSON report
+    report_outputs: Option<String>,
+    /// Record per-phase timings in the trace
+    phases: bool,
+    /// Warm provider sessions kept across tasks (0 disables)
+    session_pool: usize,
+    /// Never submit to a running `nika daemon`
+    no_daemon: bool,
+    /// Share the run (`--share`), with the `--relay` override if any
+    share: Option<Option<String>>,
+    /// Skip HTTP cache lookups (`--refresh`)
+    refresh: bool,
+    /// `--input name=value` arguments for the workflow's `inputs:`
+    inputs: Vec<String>,
+    /// `--auto-approve` tool globs for agent tool calls
+    auto_approve: Vec<String>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            output_only: false,
+            output: None,
+            report_outputs: None,
+            phases: false,
+            session_pool: nika::provider::pool::DEFAULT_POOL_SIZE,
+            no_daemon: false,
+            share: None,
+            refresh: false,
+            inputs: Vec::new(),
+            auto_approve: Vec::new(),
+        }
+    }
+}
+
 async fn run_workflow(
     file: &str,
     provider_override: Option<String>,
     model_override: Option<String>,
+    overrides: &[String],
+    options: RunOptions,
 ) -> Result<(), NikaError> {
+    let RunOptions {
+        output_only,
+        output,
+        report_outputs,
+        phases,
+        session_pool,
+        no_daemon,
+        share,
+        refresh,
+        inputs,
+        auto_approve,
+    } = options;
+    let report = run_report_outputs(output.as_deref(), report_outputs.as_deref())?;
+    // Console text is left out when stdout carries the output or the report
+    let quiet = output_only || report.is_some();
+    let inputs = parse_input_args(&inputs)?;
+    let tool_policy =
+        ToolPolicy::discover(&std::env::current_dir()?)?.with_auto_approve(&auto_approve)?;
+
     // Read and parse (async to not block runtime)
-    let yaml = tokio::fs::read_to_string(file).await?;
+    let yaml = adapt_keyword_format(file, tokio::fs::read_to_string(file).await?)?;
+    let yaml = apply_overrides(&yaml, overrides)?;
 
-    // Validate YAML against JSON Schema (catches structural errors early)
+    // Parse before the JSON Schema check so MCP connects and provider checks
+    // can start in the background while it runs (v0.7). On a parse error the
+    // schema check still runs first, since its messages are more precise.
     let validator = WorkflowSchemaValidator::new()?;
-    validator.validate_yaml(&yaml)?;
-
-    // Parse into Workflow struct (now we know structure is valid)
-    let mut workflow: Workflow = serde_yaml::from_str(&yaml)?;
+    let mut workflow: Workflow = match serde_yaml::from_str(&yaml) {
+        Ok(workflow) => workflow,
+        Err(e) => {
+            validator.validate_yaml(&yaml)?;
+            return Err(e.into());
+        }
+    };
 
     // Validate schema version and task config
     workflow.validate_schema()?;
 
     // Apply CLI overrides
-    if let Some(p) = provider_override {
-        workflow.provider = p;
+    if let Some(p) = &provider_override {
+        workflow.provider = p.clone();
     }
-    if let Some(m) = model_override {
-        workflow.model = Some(m);
+    if let Some(m) = &model_override {
+        workflow.model = Some(m.clone());
     }
 
-    println!(
-        "{} Using provider: {} | model: {}",
-        "→".cyan(),
-        workflow.provider.cyan().bold(),
-        workflow.model.as_deref().unwrap_or("(default)").cyan()
-    );
-
-    // Run
-    let runner = Runner::new(workflow);
-    let output = runner.run().await?;
+    if !quiet {
+        println!(
+            "{} Using provider: {} | model: {}",
+            "→".cyan(),
+            workflow.provider.cyan().bold(),
+            workflow.model.as_deref().unwrap_or("(default)").cyan()
+        );
+    }
 
-    // Print output
-    if !output.is_empty() {
-        println!("{}", "Output:".cyan().bold());
-        println!("{}", output);
+    // Final output is re-serialized when a final task declares a parsed
+    // format (`output: { format: json | yaml | csv | ... }`)
+    let final_tasks = FlowGraph::from_workflow(&workflow).get_final_tasks();
+    let final_format = final_tasks.iter().find_map(|id| {
+        workflow
+            .tasks
+            .iter()
+            .find(|t| t.id == id.as_ref())
+            .and_then(|t| t.output.as_ref())
+            .map(|o| o.format().clone())
+            .filter(OutputFormat::is_structured)
+    });
+
+    // A daemon in this directory runs it with warm servers and sessions
+    // (v0.7); approve: tasks and agent tool approvals need this terminal,
+    // so those runs stay local, and so do shared runs, whose events are
+    // published from here, --refresh and --input runs, whose options are
+    // local, and --output json runs, whose report is read from the events
+    #[cfg(unix)]
+    if !no_daemon
+        && share.is_none()
+        && !refresh
+        && inputs.is_empty()
+        && report.is_none()
+        && !has_approve_tasks(&workflow)
+        && !asks_for_tools(&workflow, &tool_policy)
+    {
+        if let Some(client) = DaemonClient::connect(&DaemonClient::socket_path()).await {
+            let request = RunRequest {
+                yaml: yaml.into_owned(),
+                provider: provider_override,
+                model: model_override,
+                phases,
+            };
+            if !output_only {
+                println!("{} Submitted to nika daemon", "→".cyan());
+            }
+            let (output, _generation_id) = client
+                .run(request, |progress| {
+                    if !output_only {
+                        print_daemon_progress(progress);
+                    }
+                })
+                .await?;
+            print_run_output(&output, output_only, final_format.as_ref());
+            return Ok(());
+        }
     }
 
-    Ok(())
-}
+    // OpenTelemetry export (enabled by OTEL_EXPORTER_OTLP_ENDPOINT)
+    let otel = OtelConfig::from_env().map(OtelEmitter::new);
 
-fn validate_workflow(file: &str) -> Result<(), NikaError> {
-    let yaml = fs::read_to_string(file)?;
+    // approve: tasks ask on the terminal; decisions survive a restart
+    let approvals =
+        ApprovalGate::prompt().with_checkpoint(ApprovalCheckpoint::for_workflow(&workflow)?);
 
-    // Validate YAML against JSON Schema (catches structural errors early)
-    let validator = WorkflowSchemaValidator::new()?;
+    // Run
+    let config = NikaConfig::load()?;
+
+    // Open the shared session before anything runs, so no event is missed
+    let shared = match share {
+        Some(relay) => {
+            let relay = relay.or_else(|| config.share.relay.clone()).ok_or_else(|| {
+                NikaError::ConfigError {
+                    reason: "--share needs a relay: pass --relay URL or set [share] relay in ~/.config/nika/config.toml".to_string(),
+                }
+            })?;
+            let redactor = nika::event::redact::Redactor::from_environment(&config)?;
+            let session = nika::relay::ShareSession::create(&relay).await?;
+            if !quiet {
+                println!(
+                    "{} Sharing this run: {}",
+                    "→".cyan(),
+                    format!("nika watch {}", session.url()).bold()
+                );
+            } else {
+                eprintln!("Sharing this run: nika watch {}", session.url());
+            }
+            Some((session, redactor))
+        }
+        None => None,
+    };
+
+    let runner = Runner::new(workflow)
+        .with_store(&config.store)?
+        .with_router(&config.router)
+        .with_concurrency(&config.concurrency)
+        .with_web_search(&config.web_search)
+        .with_key_rotation(&config.key_rotation)?
+        .with_size_limits(&config.size_limits)
+        .with_memory(&config.memory)
+        .with_state_store(Arc::new(StateStore::project()))
+        .with_vector_store(Arc::new(VectorStore::project()))
+        .with_http_cache(Arc::new(HttpCache::project().refreshing(refresh)))
+        .with_inputs(inputs)
+        .with_phase_events(phases)
+        .with_session_pool_size(session_pool)
+        .with_approval_gate(approvals)
+        .with_tool_policy(tool_policy)
+        .with_project_dir(&nika::config::workflow_dir(Path::new(file))?);
+    let runner = if quiet { runner.quiet() } else { runner };
+    runner.preconnect();
+
+    // Validate YAML against JSON Schema while servers connect
     validator.validate_yaml(&yaml)?;
 
-    // Parse into Workflow struct (now we know structure is valid)
-    let workflow: Workflow = serde_yaml::from_str(&yaml)?;
+    let publisher = shared.map(|(session, redactor)| {
+        let done = CancellationToken::new();
+        let handle = session.spawn_publisher(runner.event_log().clone(), redactor, done.clone());
+        (done, handle)
+    });
 
-    // Validate schema version and task config
-    workflow.validate_schema()?;
+    let result = runner.run().await;
 
-    // Build flow graph and validate use: bindings (NIKA-080, NIKA-081, NIKA-082)
-    let flow_graph = FlowGraph::from_workflow(&workflow);
-    validate_use_wiring(&workflow, &flow_graph)?;
+    // A relay that went away costs the viewers, not the run
+    if let Some((done, handle)) = publisher {
+        done.cancel();
+        if let Ok(Err(e)) = handle.await {
+            eprintln!("{} sharing stopped: {}", "Warning:".yellow(), e);
+        }
+    }
+
+    if let Some(otel) = otel {
+        otel.record_all(&runner.event_log().events());
+        if let Err(e) = otel.export().await {
+            eprintln!("{} {}", "Warning:".yellow(), e);
+        }
+    }
+
+    // `--output json`: the report is the whole stdout, failed runs included
+    if let Some(outputs) = report {
+        let final_tasks: Vec<&str> = final_tasks.iter().map(|id| id.as_ref()).collect();
+        let mut report = RunReport::from_events(
+            runner.generation_id(),
+            &runner.event_log().events(),
+            outputs,
+            &final_tasks,
+        );
+        if let Err(e) = &result {
+            report = report.with_error(&e.to_string());
+        }
+        println!("{}", serde_json::to_string(&report)?);
+        return result.map(|_| ());
+    }
+
+    let output = result?;
+    print_run_output(&output, output_only, final_format.as_ref());
+    Ok(())
+}
+
+/// `--output` and `--report-outputs` of `nika run` (v0.7)
+///
+/// Some when a JSON report replaces the console text.
+fn run_report_outputs(
+    output: Option<&str>,
+    report_outputs: Option<&str>,
+) -> Result<Option<ReportOutputs>, NikaError> {
+    match output.map(|format| format.trim().to_ascii_lowercase()) {
+        None => Ok(None),
+        Some(format) if format == "text" => Ok(None),
+        Some(format) if format == "json" => match report_outputs {
+            None => Ok(Some(ReportOutputs::default())),
+            Some(which) => {
+                ReportOutputs::parse(which)
+                    .map(Some)
+                    .ok_or_else(|| NikaError::ConfigError {
+                        reason: format!(
+                            "unknown --report-outputs '{}' (expected all, final or none)",
+                            which
+                        ),
+                    })
+            }
+        },
+        Some(format) => Err(NikaError::ConfigError {
+            reason: format!("unknown --output '{}' (expected text or json)", format),
+        }),
+    }
+}
+
+/// Apply `--preset` to the run's flags and save `--save-preset` (v0.7)
+///
+/// Returns the options to run with.
+fn resolve_run_preset(
+    file: &str,
+    preset: Option<&str>,
+    save_preset: Option<&str>,
+    (provider, model, overrides, inputs): (Option<String>, Option<String>, Vec<String>, &[String]),
+    output_only: bool,
+) -> Result<RunPreset, NikaError> {
+    let flags = RunPreset {
+        provider,
+        model,
+        overrides,
+        inputs: parse_input_args(inputs)?,
+    };
+    let store = PresetStore::project();
+    let workflow = Path::new(file);
+    let resolved = match preset {
+        Some(name) => store.load(workflow, name)?.merged(flags),
+        None => flags,
+    };
+    if let Some(name) = save_preset {
+        store.save(workflow, name, &resolved)?;
+        let saved = format!(
+            "{} Saved preset '{}' ({})",
+            "✓".green(),
+            name,
+            resolved.summary()
+        );
+        // Stdout carries only the final output with --output-only
+        if output_only {
+            eprintln!("{}", saved);
+        } else {
+            println!("{}", saved);
+        }
+    }
+    Ok(resolved)
+}
+
+/// Print a run's final output
+fn print_run_output(output: &str, output_only: bool, format: Option<&OutputFormat>) {
+    // Stdout contract: exactly the final output, in the final task's format
+    // (compact JSON, YAML, CSV, ...) or raw text
+    if output_only {
+        let formatted = format.and_then(|format| {
+            let value = serde_json::from_str::<serde_json::Value>(output).ok()?;
+            nika::codec::serialize_output(format, &value).ok()
+        });
+        match formatted {
+            Some(text) => println!("{}", text.trim_end()),
+            None => println!("{}", output),
+        }
+        return;
+    }
+
+    if !output.is_empty() {
+        println!("{}", "Output:".cyan().bold());
+        println!("{}", output);
+    }
+}
+
+#[cfg(unix)]
+fn has_approve_tasks(workflow: &Workflow) -> bool {
+    workflow
+        .tasks
+        .iter()
+        .any(|task| matches!(task.action, TaskAction::Approve { .. }))
+}
+
+/// Whether agents of `workflow` may ask before tool calls
+#[cfg(unix)]
+fn asks_for_tools(workflow: &Workflow, policy: &ToolPolicy) -> bool {
+    policy.restricts()
+        && workflow
+            .tasks
+            .iter()
+            .any(|task| matches!(task.action, TaskAction::Agent { .. }))
+}
+
+/// One line per task finished by the daemon
+#[cfg(unix)]
+fn print_daemon_progress(progress: &DaemonResponse) {
+    match progress {
+        DaemonResponse::TaskCompleted {
+            task_id,
+            duration_ms,
+        } => println!(
+            "  {} {} ({:.1}s)",
+            "✓".green(),
+            task_id,
+            *duration_ms as f64 / 1000.0
+        ),
+        DaemonResponse::TaskFailed { task_id, error } => {
+            println!("  {} {}: {}", "✗".red(), task_id, error)
+        }
+        DaemonResponse::TaskSkipped { task_id, reason } => {
+            println!("  {} {} ({})", "↷".yellow(), task_id, reason)
+        }
+        DaemonResponse::Started { generation_id } => {
+            println!("  {} run {}", "·".dimmed(), generation_id.dimmed())
+        }
+        _ => {}
+    }
+}
+
+#[cfg(unix)]
+async fn handle_daemon_command(action: DaemonAction) -> Result<(), NikaError> {
+    let path = DaemonClient::socket_path();
+    match action {
+        DaemonAction::Start {
+            session_pool,
+            #[cfg(feature = "watch")]
+            watch,
+            http,
+        } => {
+            let listener = nika::daemon::bind(&path).await?;
+            println!(
+                "{} nika daemon listening on {} (pid {})",
+                "✓".green(),
+                path.display().to_string().cyan(),
+                std::process::id()
+            );
+            let config = NikaConfig::load()?;
+            // Events served over HTTP are scrubbed like shared runs
+            let redactor = match http {
+                Some(_) => Some(nika::event::redact::Redactor::from_environment(&config)?),
+                None => None,
+            };
+            let daemon = Arc::new(
+                Daemon::new(session_pool)
+                    .with_store(config.store)
+                    .with_router(config.router)
+                    .with_concurrency(config.concurrency)
+                    .with_web_search(config.web_search)
+                    .with_key_pool(Arc::new(KeyPool::new(&config.key_rotation)?))
+                    .with_size_limits(config.size_limits)
+                    .with_memory(config.memory),
+            );
+            #[cfg(feature = "watch")]
+            if let Some(paths) = watch {
+                start_watch_triggers(&daemon, paths);
+            }
+            if let (Some(addr), Some(redactor)) = (http, redactor) {
+                let http_listener = tokio::net::TcpListener::bind(&addr).await.map_err(|e| {
+                    NikaError::DaemonError {
+                        reason: format!("cannot listen on {}: {}", addr, e),
+                    }
+                })?;
+                let server = format!("http://{}", http_listener.local_addr()?);
+                println!(
+                    "{} Run events on {} ({})",
+                    "→".cyan(),
+                    server.cyan(),
+                    format!("nika watch <run-id> --server {}", server).dimmed()
+                );
+                tokio::spawn(Arc::clone(&daemon).serve_http(http_listener, redactor));
+            }
+            let result = daemon.serve(listener).await;
+            let _ = fs::remove_file(&path);
+            result
+        }
+        DaemonAction::Stop => match DaemonClient::connect(&path).await {
+            Some(client) => {
+                client.stop().await?;
+                println!("{} nika daemon stopped", "✓".green());
+                Ok(())
+            }
+            None => {
+                println!("No daemon running in this directory");
+                Ok(())
+            }
+        },
+        DaemonAction::Status => match DaemonClient::connect(&path).await {
+            Some(client) => {
+                let status = client.status().await?;
+                println!("{} nika daemon (pid {})", "●".green(), status.pid);
+                println!("  Uptime:        {}s", status.uptime_secs);
+                println!(
+                    "  Runs:          {} ({} active)",
+                    status.runs, status.active_runs
+                );
+                println!(
+                    "  Warm sessions: {} ({} hits, {} misses)",
+                    status.warm_sessions, status.session_hits, status.session_misses
+                );
+                let servers = if status.mcp_servers.is_empty() {
+                    "none".to_string()
+                } else {
+                    status.mcp_servers.join(", ")
+                };
+                println!("  MCP servers:   {}", servers);
+                if !status.watched_workflows.is_empty() {
+                    println!(
+                        "  Watching:      {} ({} triggered runs)",
+                        status.watched_workflows.join(", "),
+                        status.triggered_runs
+                    );
+                }
+                Ok(())
+            }
+            None => {
+                println!("{} No daemon running in this directory", "○".dimmed());
+                Ok(())
+            }
+        },
+    }
+}
+
+/// Discover watch-triggered workflows and run them from the daemon
+#[cfg(all(unix, feature = "watch"))]
+fn start_watch_triggers(daemon: &Arc<Daemon>, mut paths: Vec<PathBuf>) {
+    use nika::daemon::TriggerReport;
+
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+    let discovery = nika::runtime::trigger::discover(&paths);
+    for (path, error) in &discovery.errors {
+        println!("  {} {}: {}", "✗".red(), path.display(), error);
+    }
+    if discovery.workflows.is_empty() {
+        println!("{} No workflows with a watch trigger", "○".dimmed());
+        return;
+    }
+    for workflow in &discovery.workflows {
+        println!(
+            "  {} {} ← {} {}",
+            "👁".cyan(),
+            workflow.name,
+            workflow.pattern.as_str(),
+            format!("({})", workflow.path.display()).dimmed()
+        );
+    }
+
+    let watch = Arc::clone(daemon).watch(discovery.workflows, |report| match report {
+        TriggerReport::Started { workflow, file } => println!(
+            "{} {} {} ({})",
+            chrono::Local::now()
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+                .dimmed(),
+            "▶".cyan(),
+            workflow,
+            file.display()
+        ),
+        TriggerReport::Finished {
+            workflow,
+            generation_id,
+            result,
+            ..
+        } => match result {
+            Ok(_) => println!(
+                "  {} {} ({})",
+                "✓".green(),
+                workflow,
+                generation_id.dimmed()
+            ),
+            Err(error) => println!("  {} {}: {}", "✗".red(), workflow, error),
+        },
+    });
+    tokio::spawn(async move {
+        if let Err(e) = watch.await {
+            eprintln!("{} Watch triggers stopped: {}", "✗".red(), e);
+        }
+    });
+}
+
+async fn handle_schedule_command(action: ScheduleAction) -> Result<(), NikaError> {
+    match action {
+        ScheduleAction::Start { paths } => {
+            let scheduler =
+                Scheduler::new(paths, scheduler::STATE_PATH)?.with_reporter(|event| match event {
+                    ScheduleEvent::Scanned { workflows, errors } => {
+                        for (path, error) in errors {
+                            println!("  {} {}: {}", "✗".red(), path.display(), error);
+                        }
+                        if workflows.is_empty() {
+                            println!("{} No workflows with a cron trigger", "○".dimmed());
+                        }
+                    }
+                    ScheduleEvent::Started { name, .. } => {
+                        println!(
+                            "{} {} {}",
+                            chrono::Local::now()
+                                .format("%Y-%m-%d %H:%M")
+                                .to_string()
+                                .dimmed(),
+                            "▶".cyan(),
+                            name
+                        );
+                    }
+                    ScheduleEvent::Finished { name, record, .. } => {
+                        let elapsed = format!("{:.1}s", record.duration_ms as f64 / 1000.0);
+                        match record.status {
+                            RunStatus::Success => println!(
+                                "  {} {} ({}, {})",
+                                "✓".green(),
+                                name,
+                                elapsed,
+                                record.generation_id.unwrap_or_default().dimmed()
+                            ),
+                            RunStatus::Failed => println!(
+                                "  {} {} ({}): {}",
+                                "✗".red(),
+                                name,
+                                elapsed,
+                                record.error.unwrap_or_default()
+                            ),
+                        }
+                    }
+                    ScheduleEvent::Overlapping { name } => {
+                        println!(
+                            "  {} {} still running, skipping this run",
+                            "↷".yellow(),
+                            name
+                        );
+                    }
+                });
+            println!(
+                "{} nika schedule running (pid {}), Ctrl+C to stop",
+                "✓".green(),
+                std::process::id()
+            );
+            scheduler.run(CancellationToken::new()).await
+        }
+        ScheduleAction::List { paths } => {
+            let discovery = scheduler::discover(&paths);
+            let state = ScheduleState::load(Path::new(scheduler::STATE_PATH))?;
+            let now = chrono::Local::now();
+
+            if state.scheduler_alive(now) {
+                println!(
+                    "{} Scheduler running (pid {})",
+                    "●".green(),
+                    state.pid.unwrap_or_default()
+                );
+            } else {
+                println!(
+                    "{} Scheduler not running (start it with `nika schedule start`)",
+                    "○".dimmed()
+                );
+            }
+            if discovery.workflows.is_empty() {
+                println!("No workflows with a cron trigger");
+            } else {
+                println!();
+                println!(
+                    "{:<24} {:<16} {:<18} {:<18} {}",
+                    "WORKFLOW".bold(),
+                    "CRON".bold(),
+                    "NEXT RUN".bold(),
+                    "LAST RUN".bold(),
+                    "STATUS".bold()
+                );
+            }
+            for workflow in &discovery.workflows {
+                let next = workflow
+                    .schedule
+                    .next_after(&now)
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "never".to_string());
+                let last = state.last_run(&workflow.path);
+                let last_run = last
+                    .and_then(|r| chrono::DateTime::parse_from_rfc3339(&r.started_at).ok())
+                    .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                let status = match last {
+                    Some(r) if r.status == RunStatus::Success => {
+                        format!("{} ({} runs)", "success".green(), r.runs)
+                    }
+                    Some(r) => format!("{} ({} runs)", "failed".red(), r.runs),
+                    None => "-".dimmed().to_string(),
+                };
+                println!(
+                    "{:<24} {:<16} {:<18} {:<18} {}",
+                    workflow.name,
+                    workflow.schedule.as_str(),
+                    next,
+                    last_run,
+                    status
+                );
+            }
+            for (path, error) in &discovery.errors {
+                println!("{} {}: {}", "✗".red(), path.display(), error);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// `nika debug`: run with the step-through debugger answered on the terminal
+async fn debug_workflow(file: &str, breakpoints: Vec<String>) -> Result<(), NikaError> {
+    let yaml = tokio::fs::read_to_string(file).await?;
+    WorkflowSchemaValidator::new()?.validate_yaml(&yaml)?;
+    let workflow: Workflow = serde_yaml::from_str(&yaml)?;
+    workflow.validate_schema()?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    let debugger = Arc::new(Debugger::new(tx).with_breakpoints(breakpoints));
+    let approvals =
+        ApprovalGate::prompt().with_checkpoint(ApprovalCheckpoint::for_workflow(&workflow)?);
+    let runner = Runner::new(workflow)
+        .with_approval_gate(approvals)
+        .with_state_store(Arc::new(StateStore::project()))
+        .with_vector_store(Arc::new(VectorStore::project()))
+        .with_http_cache(Arc::new(HttpCache::project()))
+        .with_project_dir(&nika::config::workflow_dir(Path::new(file))?)
+        .with_debugger(Arc::clone(&debugger));
 
-    println!("{} Workflow '{}' is valid", "✓".green(), file);
-    println!("  Provider: {}", workflow.provider);
+    println!(
+        "{} Debugging {} (s step, c continue, k skip, p <prompt>, o <output>, b <task>, ? help)",
+        "🐞".cyan(),
+        file.cyan().bold()
+    );
+
+    let mut run = tokio::spawn(async move { runner.run().await });
+    let output = loop {
+        tokio::select! {
+            result = &mut run => {
+                break result.map_err(|e| NikaError::Execution(format!("Debug run panicked: {}", e)))??;
+            }
+            Some(paused) = rx.recv() => {
+                let command = prompt_debug_command(&paused.stop, &debugger).await;
+                paused.resume(command);
+            }
+        }
+    };
+
+    if !output.is_empty() {
+        println!("{}", "Output:".cyan().bold());
+        println!("{}", output);
+    }
+    Ok(())
+}
+
+/// Show a debugger stop and read commands until one resumes the task
+///
+/// EOF on stdin continues the run without further pauses.
+async fn prompt_debug_command(stop: &DebugStop, debugger: &Debugger) -> DebugCommand {
+    println!(
+        "\n{} Paused before {} [{}]",
+        "🐞".cyan(),
+        stop.task_id.bold(),
+        stop.verb
+    );
+    if stop
+        .inputs
+        .as_object()
+        .is_some_and(|inputs| !inputs.is_empty())
+    {
+        println!("{}", "Inputs:".cyan());
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&stop.inputs).unwrap_or_default()
+        );
+    }
+    println!("{}", format!("{}:", stop.rendered_label()).cyan());
+    println!("{}", stop.rendered);
+
+    loop {
+        let line = tokio::task::spawn_blocking(|| {
+            use std::io::{BufRead, Write};
+            print!("(debug) ");
+            let _ = std::io::stdout().flush();
+            let mut line = String::new();
+            match std::io::stdin().lock().read_line(&mut line) {
+                Ok(0) | Err(_) => None,
+                Ok(_) => Some(line),
+            }
+        })
+        .await
+        .ok()
+        .flatten();
+        let Some(line) = line else {
+            return DebugCommand::Continue;
+        };
+
+        let line = line.trim();
+        if let Some(task_id) = line.strip_prefix("b ") {
+            let task_id = task_id.trim();
+            let state = if debugger.toggle_breakpoint(task_id) {
+                "set"
+            } else {
+                "removed"
+            };
+            println!("Breakpoint {}: {}", state, task_id);
+            continue;
+        }
+        if line == "?" {
+            println!("  s, Enter      run this task, pause before the next");
+            println!("  c             run until the next breakpoint");
+            println!("  k             skip this task");
+            println!(
+                "  p <text>      run with this {} instead",
+                stop.rendered_label()
+            );
+            println!("  o <text>      use this output without running the task");
+            println!("  b <task>      toggle a breakpoint");
+            continue;
+        }
+        match parse_debug_command(line) {
+            Ok(DebugCommand::EditPrompt(_)) if !stop.editable => {
+                println!("{} {} cannot be edited", "Error:".red(), stop.verb);
+            }
+            Ok(command) => return command,
+            Err(e) => println!("{} {} (? for help)", "Error:".red(), e),
+        }
+    }
+}
+
+/// Format workflow files in place (or report unformatted files with --check)
+fn format_files(files: &[PathBuf], check: bool) -> Result<(), NikaError> {
+    let mut unformatted = 0;
+    for file in files {
+        let yaml = fs::read_to_string(file)?;
+        let formatted = nika::ast::format_workflow(&yaml)?;
+        if formatted == yaml {
+            continue;
+        }
+        unformatted += 1;
+        if check {
+            println!("{} {} is not formatted", "✗".red(), file.display());
+        } else {
+            fs::write(file, formatted)?;
+            println!("{} Formatted {}", "✓".green(), file.display());
+        }
+    }
+
+    if check && unformatted > 0 {
+        return Err(NikaError::ValidationError {
+            reason: format!("{} file(s) need formatting (run 'nika fmt')", unformatted),
+        });
+    }
+    Ok(())
+}
+
+/// Convert a legacy workflow, listing what could not be carried over (v0.7)
+fn migrate_file(file: &Path, from: &str, output: Option<&Path>) -> Result<(), NikaError> {
+    let yaml = fs::read_to_string(file)?;
+    let migration = match from {
+        "keyword" => nika::ast::migrate_keyword(&yaml)?,
+        other => {
+            return Err(NikaError::ValidationError {
+                reason: format!("Unknown migration source '{}' (use keyword)", other),
+            })
+        }
+    };
+
+    for note in &migration.notes {
+        eprintln!("{} {}", "⚠".yellow(), note);
+    }
+    match output {
+        Some(path) => {
+            fs::write(path, &migration.yaml)?;
+            println!(
+                "{} Migrated {} → {} ({} note(s))",
+                "✓".green(),
+                file.display(),
+                path.display(),
+                migration.notes.len()
+            );
+        }
+        None => print!("{}", migration.yaml),
+    }
+    Ok(())
+}
+
+/// Watch a workflow (and extra paths), re-validating or re-running on change
+/// Follow a run shared with `nika run --share` (v0.7)
+#[cfg(feature = "watch")]
+async fn watch_shared(url: &str, headless: bool) -> Result<(), NikaError> {
+    if !headless {
+        return nika::tui::run_tui_shared(url).await;
+    }
+    let subscription = nika::relay::Subscription::connect(url).await?;
+    print_followed(url, |tx| subscription.forward(tx)).await
+}
+
+/// Follow a run on a remote daemon (`nika watch <run-id> --server <url>`, v0.7)
+async fn watch_remote(server: &str, run_id: &str, headless: bool) -> Result<(), NikaError> {
+    if !headless {
+        return nika::tui::run_tui_remote(server, run_id).await;
+    }
+    let tail = nika::tail::RunTail::connect(server, run_id).await?;
+    let url = tail.url().to_string();
+    print_followed(&url, |tx| tail.forward(tx)).await
+}
+
+/// Print the events `forward` sends, one line each
+async fn print_followed<F>(
+    source: &str,
+    forward: impl FnOnce(tokio::sync::mpsc::Sender<nika::event::Event>) -> F,
+) -> Result<(), NikaError>
+where
+    F: std::future::Future<Output = Result<(), NikaError>> + Send + 'static,
+{
+    println!("Following: {}\n", source);
+    let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+    let forward = tokio::spawn(forward(tx));
+    while let Some(event) = rx.recv().await {
+        println!("[{:>6}ms] {:?}", event.timestamp_ms, event.kind);
+    }
+    forward
+        .await
+        .map_err(|e| NikaError::Execution(format!("watch task failed: {}", e)))?
+}
+
+/// Run the share relay in the foreground (v0.7)
+async fn serve_relay(listen: &str) -> Result<(), NikaError> {
+    let listener =
+        tokio::net::TcpListener::bind(listen)
+            .await
+            .map_err(|e| NikaError::RelayError {
+                url: listen.to_string(),
+                reason: format!("cannot listen: {}", e),
+            })?;
+    println!(
+        "{} nika relay listening on http://{} (Ctrl+C to stop)",
+        "→".cyan(),
+        listener.local_addr()?
+    );
+    std::sync::Arc::new(nika::relay::Relay::default())
+        .serve(listener)
+        .await
+}
+
+#[cfg(feature = "watch")]
+async fn watch_workflow(
+    file: &str,
+    run: bool,
+    also: &[PathBuf],
+    debounce_ms: u64,
+    overrides: &[String],
+) -> Result<(), NikaError> {
+    use nika::dag::DagDiff;
+    use notify::{RecursiveMode, Watcher};
+    use std::time::Duration;
+
+    let path = fs::canonicalize(file)?;
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let watched_name = path.file_name().map(|n| n.to_os_string());
+    let extra: Vec<PathBuf> = also
+        .iter()
+        .filter_map(|p| fs::canonicalize(p).ok())
+        .collect();
+    let filter_extra = extra.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        if event.kind.is_access() {
+            return;
+        }
+        // Editors often save by rename, so the parent dir is watched and filtered here
+        let relevant = event.paths.iter().any(|p| {
+            p.file_name().map(|n| n.to_os_string()) == watched_name
+                || filter_extra.iter().any(|e| p.starts_with(e))
+        });
+        if relevant {
+            let _ = tx.send(());
+        }
+    })
+    .map_err(|e| NikaError::ConfigError {
+        reason: format!("Failed to start file watcher: {}", e),
+    })?;
+
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let watch_err = |e: notify::Error| NikaError::ConfigError {
+        reason: format!("Failed to watch path: {}", e),
+    };
+    watcher
+        .watch(parent, RecursiveMode::NonRecursive)
+        .map_err(watch_err)?;
+    for p in &extra {
+        watcher
+            .watch(p, RecursiveMode::Recursive)
+            .map_err(watch_err)?;
+    }
+
+    println!(
+        "{} Watching {} (Ctrl+C to stop)\n",
+        "👁".cyan(),
+        file.cyan().bold()
+    );
+
+    let mut previous = fs::read_to_string(&path)?;
+    loop {
+        let ok = match validate_workflow(file, overrides) {
+            Ok(()) if run => run_workflow(file, None, None, overrides, RunOptions::default()).await,
+            other => other,
+        };
+        if let Err(e) = ok {
+            eprintln!("{} {}", "✗".red(), e);
+        }
+        println!("\n{} Waiting for changes...", "→".cyan());
+
+        // Wait for a change, then for a quiet period (debounce)
+        if rx.recv().await.is_none() {
+            return Ok(());
+        }
+        while let Ok(Some(())) =
+            tokio::time::timeout(Duration::from_millis(debounce_ms), rx.recv()).await
+        {}
+
+        println!("\n{} Change detected", "↻".cyan());
+        if let Ok(yaml) = fs::read_to_string(&path) {
+            match DagDiff::between(&previous, &yaml) {
+                Ok(diff) => print!("{}", diff),
+                Err(e) => eprintln!("{} {}", "✗".red(), e),
+            }
+            previous = yaml;
+        }
+    }
+}
+
+/// Run every --matrix combination and print a comparison table
+async fn run_matrix_cli(
+    file: &str,
+    overrides: &[String],
+    matrix: &[String],
+    concurrency: usize,
+) -> Result<(), NikaError> {
+    let yaml = tokio::fs::read_to_string(file).await?;
+    let combos = nika::runtime::expand_matrix(matrix)?.len();
+    println!(
+        "{} Running {} matrix combination(s), {} at a time...\n",
+        "→".cyan(),
+        combos,
+        concurrency.max(1)
+    );
+
+    let runs = nika::runtime::run_matrix(&yaml, overrides, matrix, concurrency).await?;
+
+    let header = format!(
+        "{:<3} {:<40} {:>9} {:>10}  {:<42} OUTPUT",
+        "#", "COMBINATION", "DURATION", "COST", "GENERATION"
+    );
+    println!("{}", header.bold());
+    for (idx, run) in runs.iter().enumerate() {
+        let combination = run
+            .combination
+            .iter()
+            .map(|(path, value)| format!("{}={}", path, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let output = match &run.output {
+            Ok(out) => {
+                let first = out.lines().next().unwrap_or("");
+                let preview: String = first.chars().take(50).collect();
+                if preview.len() < out.len() {
+                    format!("{}…", preview)
+                } else {
+                    preview
+                }
+            }
+            Err(e) => format!("✗ {}", e).red().to_string(),
+        };
+        println!(
+            "{:<3} {:<40} {:>8.1}s {:>10}  {:<42} {}",
+            idx + 1,
+            combination,
+            run.duration.as_secs_f64(),
+            format!("${:.4}", run.cost_usd),
+            run.generation_id,
+            output
+        );
+    }
+
+    let failed = runs.iter().filter(|r| r.output.is_err()).count();
+    if failed > 0 {
+        return Err(NikaError::ValidationError {
+            reason: format!("{} of {} matrix run(s) failed", failed, runs.len()),
+        });
+    }
+    Ok(())
+}
+
+/// `[connections]` rules of the project holding `file` (v0.7)
+fn connection_policy(file: &str) -> Result<ConnectionPolicy, NikaError> {
+    ConnectionPolicy::for_workflow_file(Path::new(file))
+}
+
+/// Translate a v4.x keyword-format workflow to verbs on load (v0.7)
+///
+/// Verb-format YAML is returned as is. The importer's notes are printed as
+/// warnings, as `nika migrate --from keyword` would list them.
+fn adapt_keyword_format(file: &str, yaml: String) -> Result<String, NikaError> {
+    if !nika::ast::is_keyword_format(&yaml) {
+        return Ok(yaml);
+    }
+    let migration = nika::ast::migrate_keyword(&yaml)?;
+    eprintln!(
+        "{} '{}' uses the v4.x keyword format; running it converted to verbs (see `nika migrate --from keyword`)",
+        "⚠".yellow(),
+        file
+    );
+    for note in &migration.notes {
+        eprintln!("{} {}", "⚠".yellow(), note);
+    }
+    Ok(migration.yaml)
+}
+
+fn validate_workflow(file: &str, overrides: &[String]) -> Result<(), NikaError> {
+    let yaml = adapt_keyword_format(file, fs::read_to_string(file)?)?;
+    let yaml = apply_overrides(&yaml, overrides)?;
+
+    // Validate YAML against JSON Schema (catches structural errors early)
+    let validator = WorkflowSchemaValidator::new()?;
+    validator.validate_yaml(&yaml)?;
+
+    // Parse into Workflow struct (now we know structure is valid)
+    let workflow: Workflow = serde_yaml::from_str(&yaml)?;
+
+    // Validate schema version and task config
+    workflow.validate_schema()?;
+
+    // Build flow graph and validate use: bindings (NIKA-080..085)
+    let flow_graph = FlowGraph::from_workflow(&workflow);
+    validate_use_wiring_with(&workflow, &flow_graph, &connection_policy(file)?)?;
+
+    println!("{} Workflow '{}' is valid", "✓".green(), file);
+    println!("  Provider: {}", workflow.provider);
     println!(
         "  Model: {}",
         workflow.model.as_deref().unwrap_or("(default)")
     );
     println!("  Tasks: {}", workflow.tasks.len());
     println!("  Flows: {}", workflow.flows.len());
+    let remote = workflow
+        .tasks
+        .iter()
+        .any(|t| matches!(&t.action, TaskAction::Exec { exec } if exec.host.is_some()));
+    let hosts = if remote {
+        nika::runtime::Hosts::discover(&nika::config::workflow_dir(Path::new(file))?)?
+    } else {
+        nika::runtime::Hosts::default()
+    };
+    for task in &workflow.tasks {
+        if let TaskAction::Exec { exec } = &task.action {
+            if let Some(sandbox) = &exec.sandbox {
+                println!("  Sandbox: {} ({})", task.id, sandbox.summary());
+            }
+            if let Some(container) = &exec.container {
+                let runtime = container.runtime.map_or("auto", |r| r.as_str());
+                println!(
+                    "  Container: {} ({} via {})",
+                    task.id, container.image, runtime
+                );
+            }
+            if let Some(name) = &exec.host {
+                match hosts.get(name) {
+                    Some(host) => println!("  Remote: {} ({} at {})", task.id, name, host.target()),
+                    None => println!(
+                        "  {} Remote: {} (host '{}' is not in .nika/config.toml)",
+                        "⚠".yellow(),
+                        task.id,
+                        name
+                    ),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Lint a workflow with the project's `[lint]` severities (nika lint)
+///
+/// Fails when any finding has `error` severity.
+fn lint_file(file: &str, format: &str, overrides: &[String]) -> Result<(), NikaError> {
+    use nika::dag::{lint_workflow, LintConfig, Severity};
+
+    let yaml = fs::read_to_string(file)?;
+    let yaml = apply_overrides(&yaml, overrides)?;
+    WorkflowSchemaValidator::new()?.validate_yaml(&yaml)?;
+    let workflow: Workflow = serde_yaml::from_str(&yaml)?;
+    workflow.validate_schema()?;
+
+    let config = LintConfig::for_workflow_file(Path::new(file))?;
+    let lints = lint_workflow(&workflow, &config);
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&lints)?),
+        "text" => {
+            for lint in &lints {
+                let severity = match lint.severity {
+                    Severity::Error => lint.severity.to_string().red().bold(),
+                    Severity::Warn => lint.severity.to_string().yellow().bold(),
+                    _ => lint.severity.to_string().cyan(),
+                };
+                println!(
+                    "{}[{}] {}: {} {}",
+                    severity,
+                    lint.code,
+                    lint.task_id.bold(),
+                    lint.message,
+                    format!("({})", lint.rule).dimmed()
+                );
+            }
+            if lints.is_empty() {
+                println!("{} No lints in '{}'", "✓".green(), file);
+            }
+        }
+        other => {
+            return Err(NikaError::ValidationError {
+                reason: format!("Unknown lint format '{}' (use text or json)", other),
+            })
+        }
+    }
+
+    let errors = lints
+        .iter()
+        .filter(|l| l.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(NikaError::ValidationError {
+            reason: format!("{} lint error(s) in '{}'", errors, file),
+        });
+    }
+    Ok(())
+}
+
+/// Render resolved templates for every task into `out` (check --render)
+fn render_prompts(
+    file: &str,
+    overrides: &[String],
+    trace: Option<&str>,
+    out: &Path,
+) -> Result<(), NikaError> {
+    use nika::runtime::{datastore_from_events, render_workflow};
+    use nika::store::DataStore;
+
+    // Already checked (and warned about) by validate_workflow
+    let mut yaml = fs::read_to_string(file)?;
+    if nika::ast::is_keyword_format(&yaml) {
+        yaml = nika::ast::migrate_keyword(&yaml)?.yaml;
+    }
+    let workflow: Workflow = serde_yaml::from_str(&apply_overrides(&yaml, overrides)?)?;
+
+    let datastore = match trace {
+        Some(id) => {
+            let traces = nika::list_traces()?;
+            let trace = traces
+                .iter()
+                .find(|t| t.generation_id.contains(id))
+                .ok_or_else(|| NikaError::ValidationError {
+                    reason: format!("No trace matching '{}'", id),
+                })?;
+            datastore_from_events(&nika::event::read_trace_events(&trace.path)?)
+        }
+        None => DataStore::new(),
+    };
+
+    let rendered = render_workflow(&workflow, &datastore)?;
+    fs::create_dir_all(out)?;
+
+    println!(
+        "\n{} Rendered {} task(s) to {}",
+        "→".cyan(),
+        rendered.len(),
+        out.display()
+    );
+    for task in &rendered {
+        let path = out.join(format!("{}.txt", task.id));
+        fs::write(&path, &task.text)?;
+        let note = if task.placeholders.is_empty() {
+            String::new()
+        } else {
+            format!(" ({} placeholder(s))", task.placeholders.len())
+                .yellow()
+                .to_string()
+        };
+        println!("  {:<8} {}{}", task.verb, path.display(), note);
+    }
 
     Ok(())
 }
 
 /// Validate a workflow with --strict mode (connects to MCP servers)
-async fn validate_workflow_strict(file: &str) -> Result<(), NikaError> {
-    let yaml = tokio::fs::read_to_string(file).await?;
+async fn validate_workflow_strict(file: &str, overrides: &[String]) -> Result<(), NikaError> {
+    let yaml = adapt_keyword_format(file, tokio::fs::read_to_string(file).await?)?;
+    let yaml = apply_overrides(&yaml, overrides)?;
 
     // Phase 1: JSON Schema validation
     let schema_validator = WorkflowSchemaValidator::new()?;
@@ -428,7 +2286,7 @@ async fn validate_workflow_strict(file: &str) -> Result<(), NikaError> {
 
     // Phase 2: Binding validation
     let flow_graph = FlowGraph::from_workflow(&workflow);
-    validate_use_wiring(&workflow, &flow_graph)?;
+    validate_use_wiring_with(&workflow, &flow_graph, &connection_policy(file)?)?;
 
     // Phase 3: MCP parameter validation (strict mode)
     println!(
@@ -532,75 +2390,399 @@ async fn validate_workflow_strict(file: &str) -> Result<(), NikaError> {
                         println!("      {} [{}] {}", "→".yellow(), error.path, error.message);
                     }
                 }
-            } else {
+            } else {
+                println!(
+                    "    {} Task '{}': resource read (no params to validate)",
+                    "•".cyan(),
+                    task_id
+                );
+            }
+        }
+
+        if !all_valid {
+            return Err(NikaError::ValidationError {
+                reason: "Strict validation failed: invoke parameters don't match tool schemas"
+                    .to_string(),
+            });
+        }
+    }
+
+    println!("{} Workflow '{}' is valid (strict)", "✓".green(), file);
+    println!("  Provider: {}", workflow.provider);
+    println!(
+        "  Model: {}",
+        workflow.model.as_deref().unwrap_or("(default)")
+    );
+    println!("  Tasks: {}", workflow.tasks.len());
+    println!("  Flows: {}", workflow.flows.len());
+
+    Ok(())
+}
+
+async fn handle_trace_command(action: TraceAction) -> Result<(), NikaError> {
+    match action {
+        TraceAction::List { limit } => {
+            let traces = nika::list_traces()?;
+            let traces = match limit {
+                Some(n) => traces.into_iter().take(n).collect::<Vec<_>>(),
+                None => traces,
+            };
+
+            println!("Found {} traces:\n", traces.len());
+            println!(
+                "{:<30} {:>10} {:>20} {:>16}",
+                "GENERATION ID", "SIZE", "CREATED", "NIKA"
+            );
+            println!("{}", "-".repeat(79));
+
+            for trace in traces {
+                // Format size
+                let size = if trace.size_bytes > 1024 * 1024 {
+                    format!("{:.1}MB", trace.size_bytes as f64 / 1024.0 / 1024.0)
+                } else if trace.size_bytes > 1024 {
+                    format!("{:.1}KB", trace.size_bytes as f64 / 1024.0)
+                } else {
+                    format!("{}B", trace.size_bytes)
+                };
+
+                // Format created time
+                let created = trace
+                    .created
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| {
+                        chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
+                            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_else(|| "unknown".to_string())
+                    })
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                let version = trace
+                    .version
+                    .map(|v| v.nika_version)
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "{:<30} {:>10} {:>20} {:>16}",
+                    trace.generation_id, size, created, version
+                );
+            }
+            Ok(())
+        }
+
+        TraceAction::Show {
+            id,
+            bindings,
+            agent,
+        } => {
+            let traces = nika::list_traces()?;
+            let trace = traces
+                .iter()
+                .find(|t| t.generation_id.contains(&id))
+                .ok_or_else(|| NikaError::ValidationError {
+                    reason: format!("No trace matching '{}'", id),
+                })?;
+
+            let events = nika::event::read_trace_events(&trace.path)?;
+
+            println!("Trace: {}", trace.generation_id);
+            if let Some(version) = &trace.version {
+                println!(
+                    "Written by: nika {} (trace schema {})",
+                    version.nika_version, version.schema
+                );
+            }
+            println!("Events: {}", events.len());
+            println!("Size: {} bytes", trace.size_bytes);
+            for stats in nika::event::latency::ttft_by_model(&events) {
+                println!(
+                    "TTFT: {} p95 {}ms (p50 {}ms, max {}ms, {} calls)",
+                    stats.model, stats.p95_ms, stats.p50_ms, stats.max_ms, stats.calls
+                );
+            }
+            let sources = nika::event::sources::run_sources(&events);
+            if !sources.is_empty() {
+                println!("Sources: {}", sources.len());
+                for source in &sources {
+                    println!(
+                        "  {} ({}; fetched by {})",
+                        source.url,
+                        source.details(),
+                        source.tasks.join(", ")
+                    );
+                }
+            }
+            println!();
+
+            if bindings {
+                print_binding_provenance(&events);
+                return Ok(());
+            }
+            if let Some(task) = agent {
+                return print_agent_transcripts(&events, &task);
+            }
+
+            for event in events {
+                println!("[{:>6}ms] {:?}", event.timestamp_ms, event.kind);
+            }
+            Ok(())
+        }
+
+        TraceAction::Export {
+            id,
+            format,
+            output,
+            all,
+            anonymize,
+        } => {
+            use nika::event::anonymize::{bug_report, Anonymizer};
+            use nika::event::lineage::Lineage;
+
+            let traces = nika::list_traces()?;
+            let selected: Vec<&nika::TraceInfo> = match &id {
+                Some(id) => vec![traces
+                    .iter()
+                    .find(|t| t.generation_id.contains(id.as_str()))
+                    .ok_or_else(|| NikaError::ValidationError {
+                        reason: format!("No trace matching '{}'", id),
+                    })?],
+                None => traces.iter().collect(),
+            };
+            let read_events = |trace: &nika::TraceInfo| -> Result<Vec<Event>, NikaError> {
+                nika::event::read_trace_events(&trace.path)
+            };
+
+            // Bug report archive: no prompts, outputs or names (v0.7)
+            if anonymize {
+                let events = read_events(selected[0])?;
+                let archive = bug_report(&events, &Anonymizer::random())?;
+                let path = output.unwrap_or_else(|| {
+                    let id = selected[0].generation_id.trim_start_matches("gen-");
+                    let short: String = id.chars().take(8).collect();
+                    PathBuf::from(format!("nika-trace-{}.zip", short))
+                });
+                fs::write(&path, archive)?;
+                println!(
+                    "Exported {} anonymized events and environment info to {}",
+                    events.len(),
+                    path.display()
+                );
+                println!(
+                    "  Prompts, outputs and errors are masked, ids hashed; check the archive before attaching it"
+                );
+                return Ok(());
+            }
+
+            // Lineage graph of one or all runs (v0.7)
+            let (exported, summary) = match format.as_str() {
+                "cypher" | "graphml" => {
+                    let mut lineage = Lineage::new();
+                    for trace in &selected {
+                        let started_at = trace
+                            .created
+                            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339());
+                        lineage.add_run(
+                            &trace.generation_id,
+                            started_at.as_deref(),
+                            &read_events(trace)?,
+                        );
+                    }
+                    let exported = if format == "cypher" {
+                        lineage.to_cypher()
+                    } else {
+                        lineage.to_graphml()
+                    };
+                    let summary = format!(
+                        "{} nodes and {} relationships from {} runs",
+                        lineage.nodes.len(),
+                        lineage.edges.len(),
+                        selected.len()
+                    );
+                    (exported, summary)
+                }
+                "json" | "yaml" if all => {
+                    return Err(NikaError::ValidationError {
+                        reason: "--all exports lineage; use --format cypher or graphml".to_string(),
+                    })
+                }
+                "json" | "yaml" => {
+                    let events = read_events(selected[0])?;
+                    let exported = if format == "json" {
+                        serde_json::to_string_pretty(&events)?
+                    } else {
+                        serde_yaml::to_string(&events)?
+                    };
+                    (exported, format!("{} events", events.len()))
+                }
+                other => {
+                    return Err(NikaError::ValidationError {
+                        reason: format!(
+                            "Unknown format: {}. Use 'json', 'yaml', 'cypher' or 'graphml'",
+                            other
+                        ),
+                    })
+                }
+            };
+
+            match output {
+                Some(path) => {
+                    fs::write(&path, &exported)?;
+                    println!("Exported {} to {}", summary, path.display());
+                }
+                None => println!("{}", exported.trim_end()),
+            }
+            Ok(())
+        }
+
+        TraceAction::Flame { id, folded, output } => {
+            use nika::event::flame::{folded_stacks, profile, Phase};
+
+            let traces = nika::list_traces()?;
+            let trace = traces
+                .iter()
+                .find(|t| t.generation_id.contains(&id))
+                .ok_or_else(|| NikaError::ValidationError {
+                    reason: format!("No trace matching '{}'", id),
+                })?;
+            let profiles = profile(&nika::event::read_trace_events(&trace.path)?);
+
+            if folded || output.is_some() {
+                let stacks = folded_stacks(&trace.generation_id, &profiles);
+                match output {
+                    Some(path) => {
+                        fs::write(&path, &stacks)?;
+                        println!("Wrote {} task stacks to {}", profiles.len(), path.display());
+                        println!(
+                            "  Render: inferno-flamegraph {} > flame.svg",
+                            path.display()
+                        );
+                    }
+                    None => print!("{}", stacks),
+                }
+                return Ok(());
+            }
+
+            // Terminal view: one bar per task, scaled to the slowest task
+            const WIDTH: u64 = 40;
+            let longest = profiles
+                .iter()
+                .map(|p| p.total_ms)
+                .max()
+                .unwrap_or(0)
+                .max(1);
+            let id_width = profiles.iter().map(|p| p.task_id.len()).max().unwrap_or(4);
+            println!("Trace: {}\n", trace.generation_id);
+            for task in &profiles {
+                let bar_len = (task.total_ms * WIDTH).div_ceil(longest) as usize;
+                let breakdown: Vec<String> = Phase::ALL
+                    .iter()
+                    .zip(&task.phases)
+                    .filter(|(_, (_, ms))| *ms > 0)
+                    .map(|(phase, (_, ms))| format!("{} {}ms", phase, ms))
+                    .chain((task.self_ms() > 0).then(|| format!("self {}ms", task.self_ms())))
+                    .collect();
                 println!(
-                    "    {} Task '{}': resource read (no params to validate)",
-                    "•".cyan(),
-                    task_id
+                    "{:<id_width$}  {:<width$} {:>7}ms  {}",
+                    task.task_id,
+                    "█".repeat(bar_len).cyan(),
+                    task.total_ms,
+                    breakdown.join(", ").dimmed(),
+                    width = WIDTH as usize,
                 );
             }
+            Ok(())
         }
 
-        if !all_valid {
-            return Err(NikaError::ValidationError {
-                reason: "Strict validation failed: invoke parameters don't match tool schemas"
-                    .to_string(),
-            });
-        }
-    }
-
-    println!("{} Workflow '{}' is valid (strict)", "✓".green(), file);
-    println!("  Provider: {}", workflow.provider);
-    println!(
-        "  Model: {}",
-        workflow.model.as_deref().unwrap_or("(default)")
-    );
-    println!("  Tasks: {}", workflow.tasks.len());
-    println!("  Flows: {}", workflow.flows.len());
-
-    Ok(())
-}
+        TraceAction::Diff { a, b } => {
+            use nika::event::diff::{diff_runs, DiffLine};
 
-fn handle_trace_command(action: TraceAction) -> Result<(), NikaError> {
-    match action {
-        TraceAction::List { limit } => {
             let traces = nika::list_traces()?;
-            let traces = match limit {
-                Some(n) => traces.into_iter().take(n).collect::<Vec<_>>(),
-                None => traces,
+            let find = |id: &str| {
+                traces
+                    .iter()
+                    .find(|t| t.generation_id.contains(id))
+                    .ok_or_else(|| NikaError::ValidationError {
+                        reason: format!("No trace matching '{}'", id),
+                    })
             };
+            let (left, right) = (find(&a)?, find(&b)?);
+            let diffs = diff_runs(
+                &nika::event::read_trace_events(&left.path)?,
+                &nika::event::read_trace_events(&right.path)?,
+            );
 
-            println!("Found {} traces:\n", traces.len());
-            println!("{:<30} {:>10} {:>20}", "GENERATION ID", "SIZE", "CREATED");
-            println!("{}", "-".repeat(62));
-
-            for trace in traces {
-                // Format size
-                let size = if trace.size_bytes > 1024 * 1024 {
-                    format!("{:.1}MB", trace.size_bytes as f64 / 1024.0 / 1024.0)
-                } else if trace.size_bytes > 1024 {
-                    format!("{:.1}KB", trace.size_bytes as f64 / 1024.0)
-                } else {
-                    format!("{}B", trace.size_bytes)
-                };
-
-                // Format created time
-                let created = trace
-                    .created
-                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                    .map(|d| {
-                        chrono::DateTime::from_timestamp(d.as_secs() as i64, 0)
-                            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
-                            .unwrap_or_else(|| "unknown".to_string())
-                    })
-                    .unwrap_or_else(|| "unknown".to_string());
+            println!("{} {}", "---".red(), left.generation_id);
+            println!("{} {}\n", "+++".green(), right.generation_id);
+
+            let print_lines = |label: &str, lines: Vec<DiffLine<'_>>| {
+                println!("  {}:", label);
+                for line in lines {
+                    match line {
+                        DiffLine::Same(text) => println!("      {}", text.dimmed()),
+                        DiffLine::Removed(text) => println!("    {} {}", "-".red(), text.red()),
+                        DiffLine::Added(text) => println!("    {} {}", "+".green(), text.green()),
+                        DiffLine::Skipped(n) => {
+                            println!("      {}", format!("… {} unchanged lines", n).dimmed())
+                        }
+                        DiffLine::Changed(before, after) => println!(
+                            "    {} {}",
+                            "~".yellow(),
+                            format!("changed ({} → {} lines)", before, after).yellow()
+                        ),
+                    }
+                }
+            };
 
-                println!("{:<30} {:>10} {:>20}", trace.generation_id, size, created);
+            let mut unchanged = 0;
+            for diff in &diffs {
+                if !diff.changed() {
+                    unchanged += 1;
+                    continue;
+                }
+                let status =
+                    |run: Option<&nika::event::diff::TaskRun>| run.map_or("missing", |r| r.status);
+                println!("{}", diff.task_id.cyan().bold());
+                if diff.status_changed() {
+                    println!(
+                        "  status:   {} → {}",
+                        status(diff.left.as_ref()),
+                        status(diff.right.as_ref())
+                    );
+                }
+                if let Some((before, after)) = diff.duration_change() {
+                    println!("  duration: {}ms → {}ms", before, after);
+                }
+                if let Some((before, after)) = diff.token_change() {
+                    println!("  tokens:   {} → {}", before, after);
+                }
+                if let Some(lines) = diff.prompt_diff() {
+                    print_lines("prompt", lines);
+                }
+                if let Some(lines) = diff.output_diff() {
+                    print_lines("output", lines);
+                }
+                println!();
             }
+            println!(
+                "{}",
+                format!(
+                    "{} of {} tasks changed, {} unchanged",
+                    diffs.len() - unchanged,
+                    diffs.len(),
+                    unchanged
+                )
+                .dimmed()
+            );
             Ok(())
         }
 
-        TraceAction::Show { id } => {
+        TraceAction::Inspect {
+            id,
+            at,
+            before,
+            task,
+            json,
+        } => {
+            use nika::event::inspect::{bindings_seen, snapshot, Cursor, Point, TaskEntry};
+
             let traces = nika::list_traces()?;
             let trace = traces
                 .iter()
@@ -608,24 +2790,121 @@ fn handle_trace_command(action: TraceAction) -> Result<(), NikaError> {
                 .ok_or_else(|| NikaError::ValidationError {
                     reason: format!("No trace matching '{}'", id),
                 })?;
+            let events = nika::event::read_trace_events(&trace.path)?;
 
-            let content = fs::read_to_string(&trace.path)?;
-            let events: Vec<Event> = content
-                .lines()
-                .filter_map(|line| serde_json::from_str(line).ok())
-                .collect();
+            let cursor = match (at, before) {
+                (Some(point), _) => Cursor::At(point.parse().map_err(invalid_point)?),
+                (None, Some(point)) => Cursor::Before(point.parse().map_err(invalid_point)?),
+                (None, None) => unreachable!("clap requires --at or --before"),
+            };
+            let snap = snapshot(&events, &cursor)
+                .map_err(|reason| NikaError::ValidationError { reason })?;
+            let task = task.or(match &cursor {
+                Cursor::At(Point::Task(t)) | Cursor::Before(Point::Task(t)) => Some(t.clone()),
+                _ => None,
+            });
+            let bindings = task.as_deref().and_then(|t| bindings_seen(&events, t));
+
+            if json {
+                let mut out = serde_json::to_value(&snap)?;
+                if let Some(task) = &task {
+                    out["bindings"] = serde_json::json!({
+                        "task": task,
+                        "seen": bindings,
+                    });
+                }
+                println!("{}", serde_json::to_string_pretty(&out)?);
+                return Ok(());
+            }
 
             println!("Trace: {}", trace.generation_id);
-            println!("Events: {}", events.len());
-            println!("Size: {} bytes\n", trace.size_bytes);
+            match snap.last_event_id {
+                Some(last) => println!(
+                    "At: event #{} ({} of {} events, {}ms into the run)\n",
+                    last, snap.replayed, snap.total, snap.timestamp_ms
+                ),
+                None => println!("At: start of the run (0 of {} events)\n", snap.total),
+            }
 
-            for event in events {
-                println!("[{:>6}ms] {:?}", event.timestamp_ms, event.kind);
+            println!("DataStore ({} tasks):", snap.tasks.len());
+            let id_width = snap.tasks.keys().map(|t| t.len()).max().unwrap_or(0);
+            for (task_id, entry) in &snap.tasks {
+                let (status, detail) = match entry {
+                    TaskEntry::Running => ("running".yellow(), String::new()),
+                    TaskEntry::Completed { output } => ("done".green(), output.to_string()),
+                    TaskEntry::Failed { error } => ("failed".red(), error.clone()),
+                    TaskEntry::Skipped { reason } => ("skipped".dimmed(), reason.clone()),
+                };
+                let preview: String = detail.chars().take(80).collect();
+                let ellipsis = if detail.chars().count() > 80 {
+                    "…"
+                } else {
+                    ""
+                };
+                println!(
+                    "  {:<id_width$}  {:<7}  {}{}",
+                    task_id.cyan(),
+                    status,
+                    preview,
+                    ellipsis
+                );
+            }
+
+            if let Some(task) = &task {
+                println!();
+                match bindings {
+                    None => println!("Task '{}' never started in this trace", task),
+                    Some(seen) if seen.is_empty() => {
+                        println!("Task '{}' had no use: bindings", task)
+                    }
+                    Some(seen) => {
+                        println!("Bindings seen by {}:", task.cyan());
+                        for binding in seen {
+                            let origin = match (&binding.path, binding.source_event_id) {
+                                (Some(path), Some(id)) => format!(" ← {} @ event #{}", path, id),
+                                (Some(path), None) => format!(" ← {}", path),
+                                _ => String::new(),
+                            };
+                            let value = binding.value.to_string();
+                            let preview: String = value.chars().take(80).collect();
+                            let ellipsis = if value.chars().count() > 80 {
+                                "…"
+                            } else {
+                                ""
+                            };
+                            println!(
+                                "  {} = {}{}{}",
+                                binding.alias.bold(),
+                                preview,
+                                ellipsis,
+                                origin.dimmed()
+                            );
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        TraceAction::Clean { keep } => {
+            let traces = nika::list_traces()?;
+            let to_delete: Vec<_> = traces.into_iter().skip(keep).collect();
+            let count = to_delete.len();
+
+            for trace in to_delete {
+                fs::remove_file(&trace.path)?;
             }
+
+            println!("Deleted {} old traces, kept {}", count, keep);
             Ok(())
         }
 
-        TraceAction::Export { id, format, output } => {
+        TraceAction::Replay {
+            id,
+            speed,
+            headless,
+            workflow,
+        } => {
             let traces = nika::list_traces()?;
             let trace = traces
                 .iter()
@@ -633,48 +2912,392 @@ fn handle_trace_command(action: TraceAction) -> Result<(), NikaError> {
                 .ok_or_else(|| NikaError::ValidationError {
                     reason: format!("No trace matching '{}'", id),
                 })?;
+            let events = nika::event::read_trace_events(&trace.path)?;
 
-            let content = fs::read_to_string(&trace.path)?;
-            let events: Vec<Event> = content
-                .lines()
-                .filter_map(|line| serde_json::from_str(line).ok())
-                .collect();
+            if let Some(workflow) = workflow {
+                return replay_workflow(&workflow, &events).await;
+            }
 
-            let exported = match format.as_str() {
-                "json" => serde_json::to_string_pretty(&events)?,
-                "yaml" => serde_yaml::to_string(&events)?,
-                other => {
-                    return Err(NikaError::ValidationError {
-                        reason: format!("Unknown format: {}. Use 'json' or 'yaml'", other),
-                    })
-                }
+            if !headless {
+                return nika::tui::run_tui_replay(&trace.path, events, speed).await;
+            }
+
+            println!(
+                "Replaying: {} ({} events)\n",
+                trace.generation_id,
+                events.len()
+            );
+            let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+            let replay = tokio::spawn(nika::event::replay_trace(events, speed, tx));
+            while let Some(event) = rx.recv().await {
+                println!("[{:>6}ms] {:?}", event.timestamp_ms, event.kind);
+            }
+            let _ = replay.await;
+            Ok(())
+        }
+    }
+}
+
+/// Re-execute a workflow with provider calls answered from a trace
+async fn replay_workflow(file: &Path, events: &[Event]) -> Result<(), NikaError> {
+    use nika::event::EventKind;
+    use nika::provider::ReplayProvider;
+    use std::sync::Arc;
+
+    let yaml = tokio::fs::read_to_string(file).await?;
+    WorkflowSchemaValidator::new()?.validate_yaml(&yaml)?;
+    let workflow: Workflow = serde_yaml::from_str(&yaml)?;
+    workflow.validate_schema()?;
+
+    // Warn if the workflow changed since the trace was recorded
+    let recorded_hash = events.iter().find_map(|e| match &e.kind {
+        EventKind::WorkflowStarted { workflow_hash, .. } => Some(workflow_hash.clone()),
+        _ => None,
+    });
+    if let Some(recorded) = recorded_hash {
+        if recorded != workflow.compute_hash() {
+            eprintln!(
+                "{} workflow changed since this trace was recorded; responses are matched by task ID, then prompt",
+                "Warning:".yellow()
+            );
+        }
+    }
+
+    let replay = Arc::new(ReplayProvider::from_events(events));
+    println!(
+        "{} Replaying {} recorded provider responses",
+        "→".cyan(),
+        replay.remaining()
+    );
+
+    let runner = Runner::new(workflow).with_replay(Arc::clone(&replay));
+    let output = runner.run().await?;
+
+    if !output.is_empty() {
+        println!("{}", "Output:".cyan().bold());
+        println!("{}", output);
+    }
+    if replay.remaining() > 0 {
+        eprintln!(
+            "{} {} recorded responses were not used",
+            "Note:".yellow(),
+            replay.remaining()
+        );
+    }
+
+    Ok(())
+}
+
+/// Print where each use: binding came from (`nika trace show --bindings`)
+fn invalid_point(reason: String) -> NikaError {
+    NikaError::ValidationError { reason }
+}
+
+/// Print the conversations recorded for an agent task (`trace show --agent`)
+fn print_agent_transcripts(events: &[Event], task: &str) -> Result<(), NikaError> {
+    use nika::event::transcript::render;
+    use nika::event::EventKind;
+
+    let transcripts: Vec<_> = events
+        .iter()
+        .filter_map(|event| match &event.kind {
+            EventKind::AgentTranscript {
+                task_id,
+                transcript,
+            } => Some((task_id.as_ref(), transcript)),
+            _ => None,
+        })
+        .collect();
+
+    let mut found = false;
+    for (_, transcript) in transcripts.iter().filter(|(id, _)| *id == task) {
+        found = true;
+        print!("{}", render(transcript));
+    }
+    if found {
+        return Ok(());
+    }
+
+    let mut recorded: Vec<&str> = transcripts.iter().map(|(id, _)| *id).collect();
+    recorded.sort_unstable();
+    recorded.dedup();
+    Err(NikaError::ValidationError {
+        reason: if recorded.is_empty() {
+            "No agent transcripts in this trace".to_string()
+        } else {
+            format!(
+                "No transcript for task '{}' (recorded: {})",
+                task,
+                recorded.join(", ")
+            )
+        },
+    })
+}
+
+fn print_binding_provenance(events: &[Event]) {
+    use nika::event::EventKind;
+
+    let mut count = 0;
+    for event in events {
+        let EventKind::BindingResolved {
+            task_id,
+            alias,
+            source_task,
+            path,
+            source_event_id,
+            used_default,
+            lazy,
+        } = &event.kind
+        else {
+            continue;
+        };
+        count += 1;
+
+        // Value as the task received it (from its TaskStarted inputs)
+        let value = events.iter().find_map(|e| match &e.kind {
+            EventKind::TaskStarted {
+                task_id: started,
+                inputs,
+                ..
+            } if started == task_id => inputs.get(alias.as_str()).map(|v| v.to_string()),
+            _ => None,
+        });
+
+        let origin = match (source_task, source_event_id) {
+            (Some(src), Some(id)) => format!("{} @ event #{}", src, id),
+            (Some(src), None) => src.to_string(),
+            (None, _) => "for_each item".to_string(),
+        };
+        let mut flags = Vec::new();
+        if *used_default {
+            flags.push("default");
+        }
+        if *lazy {
+            flags.push("lazy");
+        }
+
+        println!(
+            "{}.{} ← {} ({}){}",
+            task_id.cyan(),
+            alias.bold(),
+            path,
+            origin.dimmed(),
+            if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", flags.join(", ")).yellow().to_string()
+            }
+        );
+        if let Some(value) = value {
+            let preview: String = value.chars().take(80).collect();
+            let ellipsis = if value.chars().count() > 80 {
+                "…"
+            } else {
+                ""
             };
+            println!("    = {}{}", preview, ellipsis);
+        }
+    }
 
-            match output {
-                Some(path) => {
-                    fs::write(&path, &exported)?;
-                    println!("Exported {} events to {}", events.len(), path.display());
+    if count == 0 {
+        println!("No binding provenance recorded in this trace");
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// DATASET COMMANDS
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Print or write the embedded workflow JSON Schema (schema export)
+fn export_schema(version: Option<&str>, output: Option<&Path>) -> Result<(), NikaError> {
+    let schema = nika::ast::schema_validator::export_schema(version)?;
+    let json = serde_json::to_string_pretty(&schema)?;
+    match output {
+        Some(path) => {
+            fs::write(path, format!("{}\n", json))?;
+            eprintln!("{} Wrote schema to {}", "✓".green(), path.display());
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+fn handle_state_command(action: StateAction) -> Result<(), NikaError> {
+    let state = StateStore::project();
+    match action {
+        StateAction::List => {
+            let entries = state.list()?;
+            if entries.is_empty() {
+                println!("No state in {}", STATE_FILE);
+                return Ok(());
+            }
+            println!(
+                "{:<24} {:<40} {}",
+                "KEY".bold(),
+                "VALUE".bold(),
+                "EXPIRES".bold()
+            );
+            for (key, entry) in entries {
+                let mut value = entry.value.to_string();
+                if value.chars().count() > 40 {
+                    value = format!("{}…", value.chars().take(39).collect::<String>());
                 }
-                None => println!("{}", exported),
+                let expires = entry
+                    .expires_at
+                    .map(|at| {
+                        at.with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "never".to_string());
+                println!("{:<24} {:<40} {}", key, value, expires.dimmed());
+            }
+            Ok(())
+        }
+        StateAction::Get { key } => match state.get(&key)? {
+            Some(serde_json::Value::String(text)) => {
+                println!("{}", text);
+                Ok(())
+            }
+            Some(value) => {
+                println!("{}", value);
+                Ok(())
+            }
+            None => Err(NikaError::StateKeyMissing { key }),
+        },
+        StateAction::Set {
+            key,
+            value,
+            ttl_secs,
+        } => {
+            let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+            state.set(&key, value, ttl_secs.map(std::time::Duration::from_secs))?;
+            Ok(())
+        }
+        StateAction::Delete { key } => {
+            if !state.delete(&key)? {
+                return Err(NikaError::StateKeyMissing { key });
             }
             Ok(())
         }
+        StateAction::Clear => state.clear(),
+    }
+}
+
+fn handle_dataset_command(action: DatasetAction) -> Result<(), NikaError> {
+    match action {
+        DatasetAction::Build {
+            task,
+            filter,
+            min_score,
+            judge,
+            output,
+        } => {
+            let mut dataset_filter = DatasetFilter::new(task);
+            for expr in &filter {
+                dataset_filter.apply_expr(expr)?;
+            }
+            dataset_filter.min_score = min_score;
+            dataset_filter.judge_task = judge;
 
-        TraceAction::Clean { keep } => {
             let traces = nika::list_traces()?;
-            let to_delete: Vec<_> = traces.into_iter().skip(keep).collect();
-            let count = to_delete.len();
+            let examples = build_dataset(&traces, &dataset_filter);
+            let jsonl = dataset::to_jsonl(&examples)?;
 
-            for trace in to_delete {
-                fs::remove_file(&trace.path)?;
+            match output {
+                Some(path) => {
+                    fs::write(&path, &jsonl)?;
+                    eprintln!(
+                        "{} Wrote {} examples from {} traces to {}",
+                        "✓".green(),
+                        examples.len(),
+                        traces.len(),
+                        path.display()
+                    );
+                }
+                None => print!("{}", jsonl),
             }
-
-            println!("Deleted {} old traces, kept {}", count, keep);
             Ok(())
         }
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// STATS COMMAND
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Print per-workflow statistics over the traces in `.nika/traces`
+fn show_stats(workflow: Option<&str>, limit: Option<usize>, json: bool) -> Result<(), NikaError> {
+    use nika::event::stats::{workflow_stats, RunSummary};
+
+    let traces = nika::list_traces()?;
+    let runs: Vec<RunSummary> = traces
+        .iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .filter_map(RunSummary::from_trace)
+        .filter(|run| workflow.is_none_or(|w| run.workflow == w))
+        .collect();
+    let stats = workflow_stats(&runs);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.is_empty() {
+        println!("No runs found in .nika/traces");
+        return Ok(());
+    }
+
+    for wf in &stats {
+        println!(
+            "{}  {} runs, {} ({}/{} succeeded), p50 {}ms, p95 {}ms",
+            wf.workflow.cyan().bold(),
+            wf.runs,
+            format!("{:.0}%", wf.success_rate * 100.0).bold(),
+            wf.succeeded,
+            wf.succeeded + wf.failed,
+            wf.p50_ms,
+            wf.p95_ms
+        );
+
+        let id_width = wf
+            .tasks
+            .iter()
+            .map(|t| t.task_id.len())
+            .max()
+            .unwrap_or(4)
+            .max(4);
+        println!(
+            "\n  {:<id_width$} {:>6} {:>8} {:>10} {:>10}",
+            "TASK", "RUNS", "FAILED", "P50", "P95"
+        );
+        for task in &wf.tasks {
+            println!(
+                "  {:<id_width$} {:>6} {:>8} {:>8}ms {:>8}ms",
+                task.task_id, task.runs, task.failures, task.p50_ms, task.p95_ms
+            );
+        }
+
+        if !wf.days.is_empty() {
+            println!(
+                "\n  {:<10} {:>6} {:>12} {:>10}",
+                "DAY", "RUNS", "TOKENS", "COST"
+            );
+            for day in &wf.days {
+                println!(
+                    "  {:<10} {:>6} {:>12} {:>10}",
+                    day.day,
+                    day.runs,
+                    day.tokens,
+                    format!("${:.4}", day.cost_usd)
+                );
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // INIT COMMAND
 // ═══════════════════════════════════════════════════════════════════════════
@@ -684,6 +3307,7 @@ fn handle_trace_command(action: TraceAction) -> Result<(), NikaError> {
 /// Creates:
 /// - `.nika/` directory
 /// - `.nika/config.toml` with permission settings
+/// - `.nika/nika-workflow.schema.json` for editor validation
 /// - Example workflow (unless --no-example)
 fn init_project(permission: &str, no_example: bool) -> Result<(), NikaError> {
     let cwd = std::env::current_dir()?;
@@ -700,20 +3324,13 @@ fn init_project(permission: &str, no_example: bool) -> Result<(), NikaError> {
     }
 
     // Parse permission mode
-    let permission_mode = match permission.to_lowercase().as_str() {
-        "deny" => PermissionMode::Deny,
-        "plan" => PermissionMode::Plan,
-        "accept-edits" | "acceptedits" => PermissionMode::AcceptEdits,
-        "accept-all" | "acceptall" | "yolo" => PermissionMode::YoloMode,
-        other => {
-            return Err(NikaError::ValidationError {
-                reason: format!(
-                    "Invalid permission mode: '{}'. Use: deny, plan, accept-edits, yolo",
-                    other
-                ),
-            });
-        }
-    };
+    let permission_mode =
+        PermissionMode::parse(permission).ok_or_else(|| NikaError::ValidationError {
+            reason: format!(
+                "Invalid permission mode: '{}'. Use: deny, plan, accept-edits, yolo",
+                permission.to_lowercase()
+            ),
+        })?;
 
     // Create .nika directory
     fs::create_dir_all(&nika_dir)?;
@@ -742,6 +3359,11 @@ default = "claude"
 # Default model (provider-specific)
 # Can be overridden with NIKA_MODEL env var or --model flag
 # model = "claude-sonnet-4-20250514"
+
+[lint]
+# Severity per rule (off, info, warn, error), by name or code
+# unconsumed_output = "off"
+# infer_without_max_tokens = "warn"
 "#,
         permission_mode
             .display_name()
@@ -751,11 +3373,18 @@ default = "claude"
     fs::write(&config_path, config_content)?;
     println!("{} Created {}", "✓".green(), config_path.display());
 
+    // Local copy of the workflow schema for yaml-language-server
+    let schema_path = nika_dir.join("nika-workflow.schema.json");
+    let schema = nika::ast::schema_validator::export_schema(None)?;
+    fs::write(&schema_path, serde_json::to_string_pretty(&schema)?)?;
+    println!("{} Created {}", "✓".green(), schema_path.display());
+
     // Create example workflow unless --no-example
     if !no_example {
         let example_path = cwd.join("hello.nika.yaml");
         if !example_path.exists() {
-            let example_content = r#"# Example Nika Workflow
+            let example_content = r#"# yaml-language-server: $schema=.nika/nika-workflow.schema.json
+# Example Nika Workflow
 # Run with: nika run hello.nika.yaml
 
 schema: nika/workflow@0.2
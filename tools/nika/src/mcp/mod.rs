@@ -6,7 +6,8 @@
 //! ## Module Structure
 //!
 //! - [`client`]: High-level MCP client with mock support
-//! - [`rmcp_adapter`]: Thin wrapper around rmcp SDK (internal)
+//! - [`rmcp_adapter`]: Thin wrapper around rmcp SDK (internal; never connects
+//!   without the `mcp` feature, where only mock clients work)
 //! - [`types`]: Core MCP types (McpConfig, ToolCallRequest, ToolCallResult, etc.)
 //! - [`protocol`]: JSON-RPC 2.0 types (utility, for testing/debugging)
 //! - [`validation`]: Parameter validation with schema caching (v0.5.1)
@@ -75,6 +76,10 @@
 
 pub mod client;
 pub mod protocol;
+#[cfg(feature = "mcp")]
+pub mod rmcp_adapter;
+#[cfg(not(feature = "mcp"))]
+#[path = "offline.rs"]
 pub mod rmcp_adapter;
 pub mod types;
 pub mod validation;
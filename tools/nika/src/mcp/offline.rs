@@ -0,0 +1,74 @@
+//! Stand-in for [`rmcp_adapter`](self) in builds without the `mcp` feature
+//!
+//! Mock clients ([`McpClient::mock`](super::McpClient::mock)) work as usual;
+//! real servers fail to connect with `NIKA-101` and, never connected, fail
+//! every call with `NIKA-100`.
+
+use crate::error::{NikaError, Result};
+use crate::mcp::types::{McpConfig, ResourceContent, ToolCallResult, ToolDefinition};
+
+/// Adapter that never connects
+#[derive(Debug)]
+pub(crate) struct RmcpClientAdapter {
+    name: String,
+}
+
+impl RmcpClientAdapter {
+    pub fn new(config: McpConfig) -> Self {
+        Self { name: config.name }
+    }
+
+    #[allow(dead_code)] // Used in tests
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn disabled(&self) -> NikaError {
+        NikaError::McpStartError {
+            name: self.name.clone(),
+            reason: "nika was built without the `mcp` feature".to_string(),
+        }
+    }
+
+    pub async fn is_connected(&self) -> bool {
+        false
+    }
+
+    pub async fn connect(&self) -> Result<()> {
+        Err(self.disabled())
+    }
+
+    pub async fn disconnect(&self) -> Result<()> {
+        Ok(())
+    }
+
+    pub async fn reconnect(&self) -> Result<()> {
+        Err(self.disabled())
+    }
+
+    fn not_connected(&self) -> NikaError {
+        NikaError::McpNotConnected {
+            name: self.name.clone(),
+        }
+    }
+
+    pub async fn call_tool(
+        &self,
+        _name: &str,
+        _params: serde_json::Value,
+    ) -> Result<ToolCallResult> {
+        Err(self.not_connected())
+    }
+
+    pub async fn read_resource(&self, _uri: &str) -> Result<ResourceContent> {
+        Err(self.not_connected())
+    }
+
+    pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>> {
+        Err(self.not_connected())
+    }
+
+    pub fn get_cached_tools(&self) -> Vec<ToolDefinition> {
+        Vec::new()
+    }
+}
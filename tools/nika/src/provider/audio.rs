@@ -0,0 +1,350 @@
+//! Audio input for `transcribe:` (v0.7)
+//!
+//! Speech-to-text APIs take one upload of at most 25 MB. Long recordings
+//! are split before sending, on boundaries that keep every chunk playable:
+//!
+//! | Format | Split | Duration |
+//! |--------|-------|----------|
+//! | WAV | at sample frames, one header per chunk | exact |
+//! | MP3 | at MPEG frames (ID3 tags dropped) | exact, VBR included |
+//! | flac, m4a, mp4, mpeg, mpga, ogg, webm | not split (≤ 25 MB) | unknown |
+//!
+//! The duration drives cost tracking: speech-to-text is billed per minute
+//! of audio, see [`price_per_minute`].
+
+use std::path::Path;
+
+use crate::error::NikaError;
+
+/// Largest upload the transcription APIs accept
+pub const MAX_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+/// Extensions the transcription APIs accept
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "flac", "m4a", "mp3", "mp4", "mpeg", "mpga", "ogg", "wav", "webm",
+];
+
+/// One upload-sized piece of a recording
+#[derive(Debug, Clone)]
+pub struct AudioChunk {
+    /// File name sent with the upload (the extension tells the format)
+    pub filename: String,
+    pub data: Vec<u8>,
+    /// Length in seconds, when the format can be measured
+    pub seconds: Option<f64>,
+}
+
+/// USD per minute of audio for known speech-to-text models (0 if unknown)
+///
+/// | Model | $/min |
+/// |-------|-------|
+/// | whisper-1, gpt-4o-transcribe | 0.006 |
+/// | gpt-4o-mini-transcribe | 0.003 |
+/// | whisper-large-v3 (Groq) | 0.00185 |
+/// | whisper-large-v3-turbo (Groq) | 0.00067 |
+pub fn price_per_minute(model: &str) -> f64 {
+    match model {
+        "whisper-1" | "gpt-4o-transcribe" => 0.006,
+        "gpt-4o-mini-transcribe" => 0.003,
+        "whisper-large-v3" => 0.111 / 60.0,
+        "whisper-large-v3-turbo" => 0.04 / 60.0,
+        _ => 0.0,
+    }
+}
+
+/// Read `file` (relative to `base`) and split it into chunks of at most
+/// `chunk_seconds` and [`MAX_UPLOAD_BYTES`]
+pub fn split_audio(
+    file: &str,
+    base: &Path,
+    chunk_seconds: u32,
+) -> Result<Vec<AudioChunk>, NikaError> {
+    let invalid = |reason: String| NikaError::InvalidAudio {
+        file: file.to_string(),
+        reason,
+    };
+    let path = base.join(file);
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .filter(|e| AUDIO_EXTENSIONS.contains(&e.as_str()))
+        .ok_or_else(|| {
+            invalid(format!(
+                "unsupported audio type (use {})",
+                AUDIO_EXTENSIONS.join(", ")
+            ))
+        })?;
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("audio")
+        .to_string();
+    let bytes = std::fs::read(&path).map_err(|e| invalid(e.to_string()))?;
+    if bytes.is_empty() {
+        return Err(invalid("file is empty".to_string()));
+    }
+
+    let pieces = match extension.as_str() {
+        "wav" => measured(split_wav(&bytes, chunk_seconds).map_err(invalid)?),
+        "mp3" => measured(split_mp3(&bytes, chunk_seconds).map_err(invalid)?),
+        _ if bytes.len() > MAX_UPLOAD_BYTES => {
+            return Err(invalid(format!(
+                "{:.1} MB, only WAV and MP3 can be split (the upload limit is 25 MB)",
+                bytes.len() as f64 / (1024.0 * 1024.0)
+            )));
+        }
+        _ => vec![(bytes, None)],
+    };
+
+    let count = pieces.len();
+    Ok(pieces
+        .into_iter()
+        .enumerate()
+        .map(|(i, (data, seconds))| AudioChunk {
+            filename: if count == 1 {
+                format!("{}.{}", stem, extension)
+            } else {
+                format!("{}-{}.{}", stem, i + 1, extension)
+            },
+            data,
+            seconds,
+        })
+        .collect())
+}
+
+/// (bytes, seconds) of each chunk of a splittable format
+type Pieces = Vec<(Vec<u8>, f64)>;
+
+fn measured(pieces: Pieces) -> Vec<(Vec<u8>, Option<f64>)> {
+    pieces
+        .into_iter()
+        .map(|(data, seconds)| (data, Some(seconds)))
+        .collect()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// WAV
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Split a RIFF/WAVE file into standalone WAV files
+fn split_wav(bytes: &[u8], chunk_seconds: u32) -> Result<Pieces, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".to_string());
+    }
+    let mut fmt: Option<&[u8]> = None;
+    let mut data: Option<&[u8]> = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() && data.is_none() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        let body = pos + 8;
+        // Streamed WAVs leave the data size unset; take the rest of the file
+        let end = body.saturating_add(size).min(bytes.len());
+        match id {
+            b"fmt " => fmt = Some(&bytes[body..end]),
+            b"data" => data = Some(&bytes[body..end]),
+            _ => {}
+        }
+        pos = end + (size & 1);
+    }
+    let fmt = fmt.filter(|f| f.len() >= 16).ok_or("missing fmt chunk")?;
+    let data = data.ok_or("missing data chunk")?;
+    let byte_rate = u32::from_le_bytes(fmt[8..12].try_into().unwrap()) as usize;
+    let block_align = (u16::from_le_bytes(fmt[12..14].try_into().unwrap()) as usize).max(1);
+    if byte_rate == 0 {
+        return Err("byte rate is 0".to_string());
+    }
+
+    let header_len = 20 + fmt.len() + 8;
+    let max_len = (chunk_seconds as usize)
+        .saturating_mul(byte_rate)
+        .min(MAX_UPLOAD_BYTES - header_len);
+    let step = (max_len / block_align * block_align).max(block_align);
+    Ok(data
+        .chunks(step)
+        .map(|samples| {
+            let mut wav = Vec::with_capacity(header_len + samples.len());
+            wav.extend_from_slice(b"RIFF");
+            wav.extend_from_slice(&((header_len - 8 + samples.len()) as u32).to_le_bytes());
+            wav.extend_from_slice(b"WAVEfmt ");
+            wav.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+            wav.extend_from_slice(fmt);
+            wav.extend_from_slice(b"data");
+            wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+            wav.extend_from_slice(samples);
+            (wav, samples.len() as f64 / byte_rate as f64)
+        })
+        .collect())
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// MP3
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// kbps by bitrate index, MPEG-1 Layer III
+const MPEG1_L3_KBPS: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+/// kbps by bitrate index, MPEG-2/2.5 Layer III
+const MPEG2_L3_KBPS: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// (frame length in bytes, seconds) of the Layer III frame header at `b`
+fn mp3_frame(b: &[u8]) -> Option<(usize, f64)> {
+    if b.len() < 4 || b[0] != 0xFF || b[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    let version = (b[1] >> 3) & 0b11; // 3 = MPEG-1, 2 = MPEG-2, 0 = MPEG-2.5
+    let layer = (b[1] >> 1) & 0b11; // 1 = Layer III
+    if version == 1 || layer != 1 {
+        return None;
+    }
+    let kbps = if version == 3 {
+        MPEG1_L3_KBPS
+    } else {
+        MPEG2_L3_KBPS
+    }
+    .get((b[2] >> 4) as usize)
+    .copied()
+    .filter(|&kbps| kbps > 0)?;
+    let base_rate: u32 = *[44_100, 48_000, 32_000].get(((b[2] >> 2) & 0b11) as usize)?;
+    let rate = match version {
+        3 => base_rate,
+        2 => base_rate / 2,
+        _ => base_rate / 4,
+    };
+    let padding = ((b[2] >> 1) & 1) as u32;
+    let (coefficient, samples) = if version == 3 { (144, 1152) } else { (72, 576) };
+    let len = (coefficient * kbps * 1000 / rate + padding) as usize;
+    Some((len, samples as f64 / rate as f64))
+}
+
+/// Split an MP3 stream between frames
+fn split_mp3(bytes: &[u8], chunk_seconds: u32) -> Result<Pieces, String> {
+    let mut pos = 0;
+    if bytes.len() >= 10 && &bytes[0..3] == b"ID3" {
+        let size = bytes[6..10]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize);
+        let footer = if bytes[5] & 0x10 != 0 { 10 } else { 0 };
+        pos = 10 + size + footer;
+    }
+
+    let mut pieces = Vec::new();
+    let (mut start, mut seconds) = (None::<usize>, 0.0);
+    while pos < bytes.len() {
+        let Some((len, frame_seconds)) = mp3_frame(&bytes[pos..]) else {
+            // Resync past junk (or stop at a trailing ID3v1 tag)
+            if bytes[pos..].starts_with(b"TAG") && bytes.len() - pos == 128 {
+                break;
+            }
+            pos += 1;
+            continue;
+        };
+        let end = (pos + len).min(bytes.len());
+        let first = *start.get_or_insert(pos);
+        if end - first > MAX_UPLOAD_BYTES || seconds + frame_seconds > chunk_seconds as f64 {
+            pieces.push((bytes[first..pos].to_vec(), seconds));
+            start = Some(pos);
+            seconds = 0.0;
+        }
+        seconds += frame_seconds;
+        pos = end;
+    }
+    match start {
+        Some(first) => pieces.push((bytes[first..pos].to_vec(), seconds)),
+        None => return Err("no MPEG Layer III frames found".to_string()),
+    }
+    Ok(pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16-bit mono PCM WAV of `seconds` at `rate` Hz
+    fn wav(rate: u32, seconds: u32) -> Vec<u8> {
+        let samples = vec![0u8; (rate * 2 * seconds) as usize];
+        let mut out = b"RIFF".to_vec();
+        out.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&16u32.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        out.extend_from_slice(&1u16.to_le_bytes()); // mono
+        out.extend_from_slice(&rate.to_le_bytes());
+        out.extend_from_slice(&(rate * 2).to_le_bytes());
+        out.extend_from_slice(&2u16.to_le_bytes());
+        out.extend_from_slice(&16u16.to_le_bytes());
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        out.extend_from_slice(&samples);
+        out
+    }
+
+    #[test]
+    fn test_split_wav_into_playable_chunks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("call.wav"), wav(8000, 25)).unwrap();
+
+        let chunks = split_audio("call.wav", dir.path(), 10).unwrap();
+        let seconds: Vec<f64> = chunks.iter().map(|c| c.seconds.unwrap()).collect();
+        assert_eq!(seconds, vec![10.0, 10.0, 5.0]);
+        assert_eq!(chunks[2].filename, "call-3.wav");
+        // Each chunk is a WAV of its own
+        let again = split_wav(&chunks[0].data, 600).unwrap();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].1, 10.0);
+
+        let single = split_audio("call.wav", dir.path(), 600).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].filename, "call.wav");
+    }
+
+    #[test]
+    fn test_split_mp3_at_frames() {
+        // MPEG-1 Layer III, 128 kbps, 44.1 kHz: 417-byte frames of 1152 samples
+        let frame = |padding: u8| {
+            let mut f = vec![0xFF, 0xFB, 0x90 | (padding << 1), 0x64];
+            f.resize(417 + padding as usize, 0);
+            f
+        };
+        let mut mp3 = b"ID3\x04\x00\x00\x00\x00\x00\x02xy".to_vec();
+        for i in 0..100 {
+            mp3.extend(frame((i % 2) as u8));
+        }
+        let (len, seconds) = mp3_frame(&frame(0)).unwrap();
+        assert_eq!(len, 417);
+
+        let pieces = split_mp3(&mp3, 1).unwrap();
+        let total: f64 = pieces.iter().map(|p| p.1).sum();
+        assert!((total - 100.0 * seconds).abs() < 1e-9);
+        assert_eq!(pieces.len(), 3);
+        assert!(pieces.iter().all(|p| p.0.starts_with(&[0xFF, 0xFB])));
+        assert!(split_mp3(b"not audio at all", 60).is_err());
+    }
+
+    #[test]
+    fn test_split_audio_rejects_bad_input() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+        std::fs::write(dir.path().join("broken.wav"), "RIFF????").unwrap();
+        let load = |file: &str| split_audio(file, dir.path(), 600).unwrap_err();
+
+        assert!(load("notes.txt")
+            .to_string()
+            .contains("unsupported audio type"));
+        assert_eq!(load("missing.mp3").code(), "NIKA-038");
+        assert!(load("broken.wav").to_string().contains("not a RIFF/WAVE"));
+
+        let big = std::fs::File::create(dir.path().join("big.m4a")).unwrap();
+        big.set_len(MAX_UPLOAD_BYTES as u64 + 1).unwrap();
+        assert!(load("big.m4a").to_string().contains("only WAV and MP3"));
+    }
+
+    #[test]
+    fn test_price_per_minute() {
+        assert_eq!(price_per_minute("whisper-1"), 0.006);
+        assert!(price_per_minute("whisper-large-v3-turbo") < 0.001);
+        assert_eq!(price_per_minute("mock-whisper"), 0.0);
+    }
+}
@@ -0,0 +1,484 @@
+//! Cassettes - VCR-style record/replay of provider and MCP calls (v0.7)
+//!
+//! A cassette is a YAML file of recorded interactions keyed by a hash of
+//! the request, so workflow integration tests can run in CI without API
+//! keys or MCP servers.
+//!
+//! | `NIKA_CASSETTE_MODE` | Behavior |
+//! |----------------------|----------|
+//! | `off` (default)      | No cassette |
+//! | `record`             | Always call through, overwrite recorded entries |
+//! | `replay`             | Never call through, miss = `NIKA-150` |
+//! | `auto`               | Replay hits, record misses |
+//!
+//! The cassette path comes from `NIKA_CASSETTE`, defaulting to
+//! `.nika/cassettes/<workflow>.yaml`.
+//!
+//! Wrapped calls:
+//! - `infer:` provider calls ([`Cassette::infer`])
+//! - `invoke:` MCP tool calls and resource reads ([`Cassette::call_tool`], [`Cassette::read_resource`])
+//! - `agent:` tasks, recorded as a whole ([`Cassette::through`])
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::error::{NikaError, Result};
+use crate::mcp::types::{ResourceContent, ToolCallResult};
+use crate::mcp::McpClient;
+use crate::provider::registry::Provider;
+use crate::provider::rig::{Image, StreamChunk, StreamResult};
+use crate::provider::vision;
+
+/// Directory for cassettes when `NIKA_CASSETTE` is not set
+const CASSETTE_DIR: &str = ".nika/cassettes";
+
+/// Cassette file format version
+const CASSETTE_VERSION: u32 = 1;
+
+/// Cassette mode (`NIKA_CASSETTE_MODE`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CassetteMode {
+    #[default]
+    Off,
+    Record,
+    Replay,
+    Auto,
+}
+
+impl CassetteMode {
+    /// Read the mode from `NIKA_CASSETTE_MODE` (unknown values = off)
+    pub fn from_env() -> Self {
+        std::env::var("NIKA_CASSETTE_MODE")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+
+    /// Parse a mode name (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "" => Some(Self::Off),
+            "record" => Some(Self::Record),
+            "replay" => Some(Self::Replay),
+            "auto" => Some(Self::Auto),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of recorded interaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionKind {
+    Infer,
+    Agent,
+    McpTool,
+    McpResource,
+}
+
+/// One recorded request/response pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub key: String,
+    pub kind: InteractionKind,
+    pub request: Value,
+    pub response: Value,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CassetteFile {
+    version: u32,
+    #[serde(default)]
+    interactions: Vec<Interaction>,
+}
+
+/// Recorded interactions backed by a YAML file
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    /// Sorted by key so re-recording produces stable diffs
+    interactions: Mutex<BTreeMap<String, Interaction>>,
+}
+
+impl Cassette {
+    /// Load a cassette (a missing file starts empty)
+    pub fn load(path: impl Into<PathBuf>, mode: CassetteMode) -> Result<Self> {
+        let path = path.into();
+        let interactions = if path.exists() {
+            let file: CassetteFile = serde_yaml::from_str(&std::fs::read_to_string(&path)?)?;
+            file.interactions
+                .into_iter()
+                .map(|i| (i.key.clone(), i))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            path,
+            mode,
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    /// Load the cassette selected by `NIKA_CASSETTE_MODE` / `NIKA_CASSETTE`
+    ///
+    /// Returns `Ok(None)` when cassettes are off. `name` picks the default
+    /// file under `.nika/cassettes/`.
+    pub fn from_env(name: &str) -> Result<Option<Arc<Self>>> {
+        let mode = CassetteMode::from_env();
+        if mode == CassetteMode::Off {
+            return Ok(None);
+        }
+        let path = std::env::var("NIKA_CASSETTE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| Path::new(CASSETTE_DIR).join(format!("{}.yaml", name)));
+        Self::load(path, mode).map(|c| Some(Arc::new(c)))
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of recorded interactions
+    pub fn len(&self) -> usize {
+        self.interactions.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hash a request into a cassette key
+    pub fn request_key(kind: InteractionKind, request: &Value) -> String {
+        let input = format!("{:?}:{}", kind, request);
+        format!("{:016x}", xxh3_64(input.as_bytes()))
+    }
+
+    /// Run `call` through the cassette according to the mode
+    ///
+    /// The response is stored as JSON, so `T` must round-trip through serde.
+    pub async fn through<T, F, Fut>(
+        &self,
+        kind: InteractionKind,
+        request: Value,
+        call: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let key = Self::request_key(kind, &request);
+
+        if matches!(self.mode, CassetteMode::Replay | CassetteMode::Auto) {
+            let recorded = self
+                .interactions
+                .lock()
+                .get(&key)
+                .map(|i| i.response.clone());
+            match recorded {
+                Some(response) => return Ok(serde_json::from_value(response)?),
+                None if self.mode == CassetteMode::Replay => {
+                    return Err(NikaError::CassetteMiss {
+                        key,
+                        request: describe(kind, &request),
+                    })
+                }
+                None => {}
+            }
+        }
+
+        let response = call().await?;
+        if self.mode != CassetteMode::Off {
+            self.record(Interaction {
+                key,
+                kind,
+                request,
+                response: serde_json::to_value(&response)?,
+            })?;
+        }
+        Ok(response)
+    }
+
+    /// Store an interaction and rewrite the cassette file
+    fn record(&self, interaction: Interaction) -> Result<()> {
+        let mut interactions = self.interactions.lock();
+        interactions.insert(interaction.key.clone(), interaction);
+
+        let file = CassetteFile {
+            version: CASSETTE_VERSION,
+            interactions: interactions.values().cloned().collect(),
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_yaml::to_string(&file)?)?;
+        Ok(())
+    }
+
+    /// Provider wrapper: `infer:` completion
+    ///
+    /// The provider is only built on a cassette miss, so replay works
+    /// without API keys.
+    pub async fn infer<F>(
+        &self,
+        provider_name: &str,
+        prompt: &str,
+        model: Option<&str>,
+        max_tokens: Option<u64>,
+        images: &[Image],
+        get_provider: F,
+    ) -> Result<StreamResult>
+    where
+        F: FnOnce() -> Result<Arc<dyn Provider>>,
+    {
+        let mut request = json!({
+            "provider": provider_name,
+            "model": model,
+            "prompt": prompt,
+        });
+        // Only part of the key when set, so existing cassettes keep matching
+        if let Some(max_tokens) = max_tokens {
+            request["max_tokens"] = json!(max_tokens);
+        }
+        if !images.is_empty() {
+            request["images"] = json!(vision::images_key(images));
+        }
+        let recorded: RecordedCompletion = self
+            .through(InteractionKind::Infer, request, || async {
+                let provider = get_provider()?;
+                let (tx, _rx) = tokio::sync::mpsc::channel::<StreamChunk>(64);
+                let result = provider
+                    .infer_stream_with(prompt, tx, model, max_tokens, images)
+                    .await
+                    .map_err(|e| NikaError::Provider(e.to_string()))?;
+                Ok(RecordedCompletion::from(result))
+            })
+            .await?;
+        Ok(recorded.into())
+    }
+
+    /// McpClient wrapper: tool call (connects only on a cassette miss)
+    pub async fn call_tool<F, Fut>(
+        &self,
+        server: &str,
+        tool: &str,
+        params: Value,
+        connect: F,
+    ) -> Result<ToolCallResult>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<McpClient>>>,
+    {
+        let request = json!({"server": server, "tool": tool, "params": params});
+        self.through(InteractionKind::McpTool, request, || async move {
+            connect().await?.call_tool(tool, params).await
+        })
+        .await
+    }
+
+    /// McpClient wrapper: resource read (connects only on a cassette miss)
+    pub async fn read_resource<F, Fut>(
+        &self,
+        server: &str,
+        uri: &str,
+        connect: F,
+    ) -> Result<ResourceContent>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<McpClient>>>,
+    {
+        let request = json!({"server": server, "resource": uri});
+        self.through(InteractionKind::McpResource, request, || async move {
+            connect().await?.read_resource(uri).await
+        })
+        .await
+    }
+}
+
+/// Serializable form of a provider completion
+#[derive(Serialize, Deserialize)]
+struct RecordedCompletion {
+    text: String,
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cached_input_tokens: u64,
+}
+
+impl From<StreamResult> for RecordedCompletion {
+    fn from(r: StreamResult) -> Self {
+        Self {
+            text: r.text,
+            input_tokens: r.input_tokens,
+            output_tokens: r.output_tokens,
+            cached_input_tokens: r.cached_input_tokens,
+        }
+    }
+}
+
+impl From<RecordedCompletion> for StreamResult {
+    fn from(r: RecordedCompletion) -> Self {
+        Self {
+            text: r.text,
+            input_tokens: r.input_tokens,
+            output_tokens: r.output_tokens,
+            total_tokens: r.input_tokens + r.output_tokens,
+            cached_input_tokens: r.cached_input_tokens,
+        }
+    }
+}
+
+/// Short human-readable request summary for error messages
+fn describe(kind: InteractionKind, request: &Value) -> String {
+    let field = |name: &str| request.get(name).and_then(Value::as_str).unwrap_or("?");
+    match kind {
+        InteractionKind::Infer => format!("infer ({})", field("provider")),
+        InteractionKind::Agent => format!("agent ({})", field("provider")),
+        InteractionKind::McpTool => format!("MCP tool {}/{}", field("server"), field("tool")),
+        InteractionKind::McpResource => {
+            format!("MCP resource {}/{}", field("server"), field("resource"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    fn request() -> Value {
+        json!({"provider": "claude", "model": null, "prompt": "Say hi"})
+    }
+
+    #[test]
+    fn test_mode_parse() {
+        assert_eq!(CassetteMode::parse("REPLAY"), Some(CassetteMode::Replay));
+        assert_eq!(CassetteMode::parse("auto"), Some(CassetteMode::Auto));
+        assert_eq!(CassetteMode::parse(""), Some(CassetteMode::Off));
+        assert_eq!(CassetteMode::parse("tape"), None);
+    }
+
+    #[test]
+    fn test_request_key_is_stable() {
+        let a = Cassette::request_key(InteractionKind::Infer, &request());
+        let b = Cassette::request_key(InteractionKind::Infer, &request());
+        let c = Cassette::request_key(InteractionKind::Agent, &request());
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("flow.yaml");
+        let calls = AtomicUsize::new(0);
+
+        let cassette = Cassette::load(&path, CassetteMode::Record).unwrap();
+        let out: String = cassette
+            .through(InteractionKind::Infer, request(), || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok("Hi!".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(out, "Hi!");
+        assert!(path.exists());
+
+        let replay = Cassette::load(&path, CassetteMode::Replay).unwrap();
+        assert_eq!(replay.len(), 1);
+        let out: String = replay
+            .through(InteractionKind::Infer, request(), || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok("live".to_string())
+            })
+            .await
+            .unwrap();
+        assert_eq!(out, "Hi!");
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            1,
+            "replay must not call through"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_miss_errors() {
+        let dir = TempDir::new().unwrap();
+        let cassette = Cassette::load(dir.path().join("empty.yaml"), CassetteMode::Replay).unwrap();
+
+        let err = cassette
+            .through(
+                InteractionKind::McpTool,
+                json!({"server": "novanet", "tool": "describe"}),
+                || async { Ok(json!("live")) },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "NIKA-150");
+        assert!(err.to_string().contains("MCP tool novanet/describe"));
+    }
+
+    #[tokio::test]
+    async fn test_auto_records_misses_only() {
+        let dir = TempDir::new().unwrap();
+        let cassette = Cassette::load(dir.path().join("auto.yaml"), CassetteMode::Auto).unwrap();
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            let _: String = cassette
+                .through(InteractionKind::Infer, request(), || async {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Ok("Hi!".to_string())
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cassette.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_replay_does_not_connect() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mcp.yaml");
+
+        let recorder = Cassette::load(&path, CassetteMode::Record).unwrap();
+        let recorded = recorder
+            .call_tool("novanet", "describe", json!({}), || async {
+                let client = Arc::new(McpClient::mock("novanet"));
+                Ok(client)
+            })
+            .await
+            .unwrap();
+
+        let replay = Cassette::load(&path, CassetteMode::Replay).unwrap();
+        let replayed = replay
+            .call_tool("novanet", "describe", json!({}), || async {
+                Err(NikaError::McpNotConnected {
+                    name: "novanet".to_string(),
+                })
+            })
+            .await
+            .unwrap();
+        assert_eq!(replayed, recorded);
+    }
+}
@@ -0,0 +1,400 @@
+//! API key rotation per provider (v0.7)
+//!
+//! Per-key rate limits cap throughput long before the provider does. With
+//! `[key_rotation]`, each live call checks out one of several keys of its
+//! provider, picked round-robin or least-recently-limited, and reports how
+//! the call went:
+//!
+//! - auth errors (401/403, invalid key) drop the key for the rest of the
+//!   process
+//! - quota errors (insufficient quota, billing) quarantine it for
+//!   `quarantine_secs`
+//! - `quarantine_limited` rate-limit errors in a row do the same
+//!
+//! Keys never appear in logs or events: they are named by provider and
+//! position in the list (`claude#2`).
+
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+
+use crate::config::{KeyRotationConfig, RotationStrategy};
+use crate::error::NikaError;
+
+/// Providers that take an API key
+const KEYED_PROVIDERS: &[&str] = &["claude", "openai", "mistral", "groq", "deepseek"];
+
+/// Provider name as used in `[key_rotation.keys]`
+fn canonical(provider: &str) -> &str {
+    match provider {
+        "anthropic" => "claude",
+        "gpt" => "openai",
+        "deep-seek" => "deepseek",
+        name => name,
+    }
+}
+
+/// Why a call failed, as far as its key is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFault {
+    /// 429: the key is over its rate limit
+    RateLimited,
+    /// Out of quota or credit
+    Quota,
+    /// Rejected key (401/403)
+    Auth,
+}
+
+impl KeyFault {
+    /// Classify a provider error message (None: not the key's fault)
+    pub fn classify(message: &str) -> Option<Self> {
+        let lower = message.to_lowercase();
+        let has = |phrases: &[&str]| phrases.iter().any(|phrase| lower.contains(phrase));
+        let statuses: Vec<u16> = lower
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|word| word.parse().ok())
+            .collect();
+        if has(&["insufficient_quota", "quota", "billing", "credit balance"]) {
+            Some(Self::Quota)
+        } else if statuses.iter().any(|s| matches!(s, 401 | 403))
+            || has(&[
+                "unauthorized",
+                "invalid api key",
+                "invalid_api_key",
+                "invalid x-api-key",
+                "authentication_error",
+                "permission_error",
+            ])
+        {
+            Some(Self::Auth)
+        } else if statuses.contains(&429) || has(&["rate limit", "rate_limit", "too many requests"])
+        {
+            Some(Self::RateLimited)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::Quota => "quota",
+            Self::Auth => "auth",
+        }
+    }
+}
+
+/// A key checked out for one call
+#[derive(Clone)]
+pub struct KeyLease {
+    provider: String,
+    index: usize,
+    key: String,
+}
+
+impl KeyLease {
+    /// The API key itself (never log it, use [`label`](Self::label))
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Position in the provider's list, from 0
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Loggable name: provider and position from 1 (`claude#2`)
+    pub fn label(&self) -> String {
+        format!("{}#{}", self.provider, self.index + 1)
+    }
+}
+
+impl std::fmt::Debug for KeyLease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("KeyLease").field(&self.label()).finish()
+    }
+}
+
+/// A key taken out of rotation, reported by [`KeyPool::report`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quarantine {
+    pub provider: String,
+    /// `claude#2`
+    pub key: String,
+    pub fault: KeyFault,
+    /// None: for the rest of the process
+    pub seconds: Option<u64>,
+}
+
+#[derive(Default)]
+struct Slot {
+    key: String,
+    /// Dropped after an auth error
+    revoked: bool,
+    quarantined_until: Option<Instant>,
+    /// Last rate-limit error
+    limited_at: Option<Instant>,
+    /// Rate-limit errors since the last success
+    limited_streak: u32,
+    last_used: Option<Instant>,
+}
+
+impl Slot {
+    fn available(&self, now: Instant) -> bool {
+        !self.revoked && self.quarantined_until.is_none_or(|until| until <= now)
+    }
+}
+
+#[derive(Default)]
+struct Ring {
+    slots: Vec<Slot>,
+    /// Next round-robin position
+    next: usize,
+}
+
+/// Rotating API keys of every provider in `[key_rotation]`
+#[derive(Default)]
+pub struct KeyPool {
+    strategy: RotationStrategy,
+    quarantine: Duration,
+    quarantine_limited: u32,
+    rings: FxHashMap<String, Mutex<Ring>>,
+}
+
+impl KeyPool {
+    /// Build the pool from `[key_rotation]`
+    ///
+    /// Fails with NIKA-140 for an unknown provider or an unset `${NAME}`.
+    pub fn new(config: &KeyRotationConfig) -> Result<Self, NikaError> {
+        let keys = config
+            .resolved_keys()
+            .map_err(|reason| NikaError::ConfigError {
+                reason: format!("[key_rotation] keys.{}", reason),
+            })?;
+        let mut rings = FxHashMap::default();
+        for (provider, keys) in keys {
+            let name = canonical(&provider);
+            if !KEYED_PROVIDERS.contains(&name) {
+                return Err(NikaError::ConfigError {
+                    reason: format!(
+                        "[key_rotation] keys.{}: not a provider with API keys ({})",
+                        provider,
+                        KEYED_PROVIDERS.join(", ")
+                    ),
+                });
+            }
+            let slots: Vec<Slot> = keys
+                .into_iter()
+                .filter(|key| !key.trim().is_empty())
+                .map(|key| Slot {
+                    key,
+                    ..Default::default()
+                })
+                .collect();
+            if !slots.is_empty() {
+                rings.insert(name.to_string(), Mutex::new(Ring { slots, next: 0 }));
+            }
+        }
+        Ok(Self {
+            strategy: config.strategy,
+            quarantine: Duration::from_secs(config.quarantine_secs),
+            quarantine_limited: config.quarantine_limited,
+            rings,
+        })
+    }
+
+    /// Whether `provider` has keys to rotate
+    pub fn rotates(&self, provider: &str) -> bool {
+        self.rings.contains_key(canonical(provider))
+    }
+
+    /// Pick a key for one call to `provider` (None: use the environment)
+    ///
+    /// Fails with NIKA-031 when every key of the provider is quarantined.
+    pub fn checkout(&self, provider: &str) -> Result<Option<KeyLease>, NikaError> {
+        let name = canonical(provider);
+        let Some(ring) = self.rings.get(name) else {
+            return Ok(None);
+        };
+        let mut ring = ring.lock();
+        let now = Instant::now();
+        let count = ring.slots.len();
+        let index = match self.strategy {
+            RotationStrategy::RoundRobin => (0..count)
+                .map(|offset| (ring.next + offset) % count)
+                .find(|&i| ring.slots[i].available(now)),
+            RotationStrategy::LeastLimited => (0..count)
+                .filter(|&i| ring.slots[i].available(now))
+                .min_by_key(|&i| (ring.slots[i].limited_at, ring.slots[i].last_used)),
+        };
+        let Some(index) = index else {
+            return Err(NikaError::ProviderApiError {
+                message: format!(
+                    "all {} API keys for {} are quarantined ([key_rotation])",
+                    count, name
+                ),
+            });
+        };
+        ring.next = (index + 1) % count;
+        let slot = &mut ring.slots[index];
+        slot.last_used = Some(now);
+        Ok(Some(KeyLease {
+            provider: name.to_string(),
+            index,
+            key: slot.key.clone(),
+        }))
+    }
+
+    /// Record how a call with `lease` went; Some when its key was quarantined
+    pub fn report<T, E: std::fmt::Display>(
+        &self,
+        lease: &KeyLease,
+        result: &Result<T, E>,
+    ) -> Option<Quarantine> {
+        let mut ring = self.rings.get(&lease.provider)?.lock();
+        let slot = ring.slots.get_mut(lease.index)?;
+        let fault = match result {
+            Ok(_) => {
+                slot.limited_streak = 0;
+                return None;
+            }
+            Err(e) => KeyFault::classify(&e.to_string())?,
+        };
+        let now = Instant::now();
+        let seconds = match fault {
+            KeyFault::Auth => {
+                slot.revoked = true;
+                None
+            }
+            KeyFault::Quota => Some(self.quarantine.as_secs()),
+            KeyFault::RateLimited => {
+                slot.limited_at = Some(now);
+                slot.limited_streak += 1;
+                if self.quarantine_limited == 0 || slot.limited_streak < self.quarantine_limited {
+                    return None;
+                }
+                slot.limited_streak = 0;
+                Some(self.quarantine.as_secs())
+            }
+        };
+        if seconds.is_some() {
+            slot.quarantined_until = Some(now + self.quarantine);
+        }
+        Some(Quarantine {
+            provider: lease.provider.clone(),
+            key: lease.label(),
+            fault,
+            seconds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(strategy: RotationStrategy, keys: &[&str]) -> KeyPool {
+        KeyPool::new(&KeyRotationConfig {
+            strategy,
+            quarantine_limited: 2,
+            keys: [(
+                "anthropic".to_string(),
+                keys.iter().map(|k| k.to_string()).collect(),
+            )]
+            .into(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn next(pool: &KeyPool) -> KeyLease {
+        pool.checkout("claude").unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_classifies_key_faults() {
+        let fault = KeyFault::classify;
+        assert_eq!(fault("HTTP 401 Unauthorized"), Some(KeyFault::Auth));
+        assert_eq!(fault("invalid x-api-key"), Some(KeyFault::Auth));
+        assert_eq!(
+            fault("429: You exceeded your current quota (insufficient_quota)"),
+            Some(KeyFault::Quota)
+        );
+        assert_eq!(fault("rate_limit_error"), Some(KeyFault::RateLimited));
+        assert_eq!(fault("status: 503 overloaded"), None);
+        assert_eq!(fault("prompt is 4013 tokens too long"), None);
+    }
+
+    #[test]
+    fn test_round_robin_skips_quarantined_keys() {
+        let pool = pool(RotationStrategy::RoundRobin, &["k1", "k2", "k3"]);
+        assert!(pool.rotates("claude") && !pool.rotates("openai"));
+        assert!(pool.checkout("openai").unwrap().is_none());
+
+        let order: Vec<usize> = (0..4).map(|_| next(&pool).index()).collect();
+        assert_eq!(order, [0, 1, 2, 0]);
+
+        let second = next(&pool);
+        let quarantine = pool
+            .report(&second, &Err::<(), _>("401 invalid api key"))
+            .unwrap();
+        assert_eq!(quarantine.key, "claude#2");
+        assert_eq!(
+            (quarantine.fault, quarantine.seconds),
+            (KeyFault::Auth, None)
+        );
+        assert_eq!(format!("{:?}", second), "KeyLease(\"claude#2\")");
+
+        let order: Vec<usize> = (0..3).map(|_| next(&pool).index()).collect();
+        assert_eq!(order, [2, 0, 2]);
+
+        for lease in [next(&pool), next(&pool)] {
+            pool.report(&lease, &Err::<(), _>("insufficient_quota"));
+        }
+        let err = pool.checkout("claude").unwrap_err();
+        assert_eq!(err.code(), "NIKA-031");
+        assert!(
+            err.to_string().contains("all 3 API keys for claude"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_least_limited_prefers_keys_not_throttled() {
+        let pool = pool(RotationStrategy::LeastLimited, &["k1", "k2"]);
+        let first = next(&pool);
+        assert_eq!(first.key(), "k1");
+        assert_eq!(
+            pool.report(&first, &Err::<(), _>("429 Too Many Requests")),
+            None
+        );
+
+        // k2 was never limited, then k1 is the least recently limited
+        assert_eq!(next(&pool).key(), "k2");
+        let second = next(&pool);
+        assert_eq!(second.key(), "k2");
+        pool.report(&second, &Err::<(), _>("rate limit"));
+        assert_eq!(next(&pool).key(), "k1");
+
+        // Two limits in a row quarantine; a success in between resets
+        let first = next(&pool);
+        pool.report(&first, &Ok::<_, String>(()));
+        pool.report(&first, &Err::<(), _>("rate limit"));
+        let quarantine = pool.report(&first, &Err::<(), _>("rate limit")).unwrap();
+        assert_eq!(quarantine.seconds, Some(300));
+        assert_eq!(next(&pool).key(), "k2");
+        assert_eq!(next(&pool).key(), "k2");
+    }
+
+    #[test]
+    fn test_rejects_unknown_providers() {
+        let config = KeyRotationConfig {
+            keys: [("ollama".to_string(), vec!["x".to_string()])].into(),
+            ..Default::default()
+        };
+        assert_eq!(KeyPool::new(&config).err().unwrap().code(), "NIKA-140");
+        assert!(!KeyPool::default().rotates("claude"));
+    }
+}
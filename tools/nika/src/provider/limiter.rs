@@ -0,0 +1,375 @@
+//! Adaptive per-provider concurrency (v0.7)
+//!
+//! Every live provider call (one `infer:` request, or a whole `agent:` loop)
+//! holds a permit from its provider's limit. The limit follows AIMD, like
+//! TCP congestion control: a call that fails with 429/5xx (or a "rate
+//! limit"/"overloaded" error) halves it, and a full window of successful
+//! calls (as many as the current limit) raises it by one. Other errors
+//! leave it unchanged.
+//!
+//! Calls beyond the limit wait for a permit instead of piling onto a
+//! provider that is already pushing back. `[concurrency]` sets the bounds.
+//!
+//! Interactive calls (TUI chat) have their own lane: they only wait for
+//! other interactive calls, never behind workflow calls, and while they run
+//! they take up slots, so workflow calls back off until they finish.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use tokio::sync::Notify;
+
+use crate::config::ConcurrencyConfig;
+
+/// How a call went, as far as the provider's capacity is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallOutcome {
+    Success,
+    /// 429/5xx: the provider asked for less load
+    Overloaded,
+    /// Failed for another reason (bad request, missing key, cancelled)
+    Other,
+}
+
+impl CallOutcome {
+    /// Classify a provider call from its error message, if any
+    pub fn from_result<T, E: std::fmt::Display>(result: &Result<T, E>) -> Self {
+        match result {
+            Ok(_) => Self::Success,
+            Err(e) if is_overload(&e.to_string()) => Self::Overloaded,
+            Err(_) => Self::Other,
+        }
+    }
+}
+
+/// Whether an error message reports throttling or a server-side failure
+fn is_overload(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    if [
+        "rate limit",
+        "rate_limit",
+        "too many requests",
+        "overloaded",
+    ]
+    .iter()
+    .any(|phrase| lower.contains(phrase))
+    {
+        return true;
+    }
+    lower
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|word| word.parse::<u16>().ok())
+        .any(|status| status == 429 || (500..=599).contains(&status))
+}
+
+/// Who a call is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Someone is waiting at the screen (chat)
+    Interactive,
+    /// A workflow task
+    Workflow,
+}
+
+/// A limit change, reported by [`Permit::finish`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Adjustment {
+    pub previous: usize,
+    pub limit: usize,
+}
+
+/// In-flight limits keyed by provider name
+pub struct ConcurrencyLimiter {
+    config: Option<ConcurrencyConfig>,
+    providers: Mutex<FxHashMap<String, Arc<ProviderLimit>>>,
+}
+
+impl ConcurrencyLimiter {
+    /// Limiter with `[concurrency]` bounds (no limit when `adaptive = false`)
+    pub fn new(config: &ConcurrencyConfig) -> Self {
+        let config = config.adaptive.then(|| {
+            let min = config.min.max(1);
+            let max = config.max.max(min);
+            ConcurrencyConfig {
+                adaptive: true,
+                initial: config.initial.clamp(min, max),
+                min,
+                max,
+            }
+        });
+        Self {
+            config,
+            providers: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Limiter that never waits
+    pub fn disabled() -> Self {
+        Self::new(&ConcurrencyConfig {
+            adaptive: false,
+            ..Default::default()
+        })
+    }
+
+    /// Wait for a free workflow slot on `provider` (None when limits are disabled)
+    pub async fn acquire(&self, provider: &str) -> Option<Permit> {
+        self.acquire_as(provider, Priority::Workflow).await
+    }
+
+    /// Wait for a free slot on `provider` in the lane of `priority`
+    pub async fn acquire_as(&self, provider: &str, priority: Priority) -> Option<Permit> {
+        let config = self.config.as_ref()?;
+        let limit = Arc::clone(
+            self.providers
+                .lock()
+                .entry(provider.to_string())
+                .or_insert_with(|| Arc::new(ProviderLimit::new(config))),
+        );
+        loop {
+            // Register before checking, so a release in between isn't missed
+            let notified = limit.released.notified();
+            if limit.try_enter(priority) {
+                return Some(Permit {
+                    limit: Arc::clone(&limit),
+                    priority,
+                    finished: false,
+                });
+            }
+            notified.await;
+        }
+    }
+
+    /// Current limit per provider used so far
+    pub fn limits(&self) -> Vec<(String, usize)> {
+        let mut limits: Vec<_> = self
+            .providers
+            .lock()
+            .iter()
+            .map(|(name, limit)| (name.clone(), limit.state.lock().limit))
+            .collect();
+        limits.sort();
+        limits
+    }
+}
+
+impl Default for ConcurrencyLimiter {
+    fn default() -> Self {
+        Self::new(&ConcurrencyConfig::default())
+    }
+}
+
+struct LimitState {
+    limit: usize,
+    /// Calls of both lanes
+    in_flight: usize,
+    /// Interactive calls among `in_flight`
+    interactive: usize,
+    /// Successful calls since the last change
+    successes: usize,
+}
+
+struct ProviderLimit {
+    min: usize,
+    max: usize,
+    state: Mutex<LimitState>,
+    released: Notify,
+}
+
+impl ProviderLimit {
+    fn new(config: &ConcurrencyConfig) -> Self {
+        Self {
+            min: config.min,
+            max: config.max,
+            state: Mutex::new(LimitState {
+                limit: config.initial,
+                in_flight: 0,
+                interactive: 0,
+                successes: 0,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    fn try_enter(&self, priority: Priority) -> bool {
+        let mut state = self.state.lock();
+        let free = match priority {
+            Priority::Interactive => state.interactive < state.limit,
+            Priority::Workflow => state.in_flight < state.limit,
+        };
+        if free {
+            state.in_flight += 1;
+            if priority == Priority::Interactive {
+                state.interactive += 1;
+            }
+        }
+        free
+    }
+
+    fn leave(&self, priority: Priority, outcome: CallOutcome) -> Option<Adjustment> {
+        let adjustment = {
+            let mut state = self.state.lock();
+            state.in_flight -= 1;
+            if priority == Priority::Interactive {
+                state.interactive -= 1;
+            }
+            let previous = state.limit;
+            match outcome {
+                CallOutcome::Success => {
+                    state.successes += 1;
+                    if state.successes >= state.limit {
+                        state.limit = (state.limit + 1).min(self.max);
+                        state.successes = 0;
+                    }
+                }
+                CallOutcome::Overloaded => {
+                    state.limit = (state.limit / 2).max(self.min);
+                    state.successes = 0;
+                }
+                CallOutcome::Other => {}
+            }
+            (state.limit != previous).then_some(Adjustment {
+                previous,
+                limit: state.limit,
+            })
+        };
+        self.released.notify_waiters();
+        adjustment
+    }
+}
+
+/// One in-flight call; dropping it without [`finish`](Self::finish) counts as `Other`
+pub struct Permit {
+    limit: Arc<ProviderLimit>,
+    priority: Priority,
+    finished: bool,
+}
+
+impl Permit {
+    /// Release the slot, adjusting the limit for `outcome`
+    pub fn finish(mut self, outcome: CallOutcome) -> Option<Adjustment> {
+        self.finished = true;
+        self.limit.leave(self.priority, outcome)
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.limit.leave(self.priority, CallOutcome::Other);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter(initial: usize, min: usize, max: usize) -> ConcurrencyLimiter {
+        ConcurrencyLimiter::new(&ConcurrencyConfig {
+            adaptive: true,
+            initial,
+            min,
+            max,
+        })
+    }
+
+    #[test]
+    fn test_classifies_overload_errors() {
+        let outcome = |e: &str| CallOutcome::from_result::<(), _>(&Err(e));
+        assert_eq!(
+            outcome("HTTP 429 Too Many Requests"),
+            CallOutcome::Overloaded
+        );
+        assert_eq!(outcome("status: 503"), CallOutcome::Overloaded);
+        assert_eq!(outcome("Overloaded"), CallOutcome::Overloaded);
+        assert_eq!(outcome("rate_limit_error"), CallOutcome::Overloaded);
+        assert_eq!(outcome("400 invalid model"), CallOutcome::Other);
+        assert_eq!(
+            CallOutcome::from_result::<_, String>(&Ok(())),
+            CallOutcome::Success
+        );
+    }
+
+    #[tokio::test]
+    async fn test_halves_on_overload_and_grows_per_window() {
+        let limiter = limiter(4, 1, 5);
+
+        let permit = limiter.acquire("claude").await.unwrap();
+        let adjustment = permit.finish(CallOutcome::Overloaded);
+        assert_eq!(
+            adjustment,
+            Some(Adjustment {
+                previous: 4,
+                limit: 2
+            })
+        );
+
+        // One window (2 successes) adds one slot
+        let first = limiter.acquire("claude").await.unwrap();
+        assert_eq!(first.finish(CallOutcome::Success), None);
+        let second = limiter.acquire("claude").await.unwrap();
+        assert_eq!(second.finish(CallOutcome::Success).unwrap().limit, 3);
+
+        // Floor and ceiling
+        for _ in 0..4 {
+            limiter
+                .acquire("claude")
+                .await
+                .unwrap()
+                .finish(CallOutcome::Overloaded);
+        }
+        assert_eq!(limiter.limits(), vec![("claude".to_string(), 1)]);
+        for _ in 0..20 {
+            limiter
+                .acquire("claude")
+                .await
+                .unwrap()
+                .finish(CallOutcome::Success);
+        }
+        assert_eq!(limiter.limits(), vec![("claude".to_string(), 5)]);
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_a_free_slot() {
+        let limiter = Arc::new(limiter(1, 1, 1));
+        let held = limiter.acquire("openai").await.unwrap();
+
+        let waiter = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { limiter.acquire("openai").await.is_some() }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        // Other providers have their own limit
+        assert!(limiter.acquire("claude").await.is_some());
+
+        drop(held);
+        assert!(waiter.await.unwrap());
+        assert!(ConcurrencyLimiter::disabled().acquire("x").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interactive_calls_skip_the_workflow_queue() {
+        let limiter = Arc::new(limiter(1, 1, 1));
+        let workflow = limiter.acquire("claude").await.unwrap();
+
+        // The workflow lane is full, the chat still goes through
+        let chat = limiter
+            .acquire_as("claude", Priority::Interactive)
+            .await
+            .unwrap();
+        drop(workflow);
+
+        // ...and holds its slot: the next workflow call waits for it
+        let waiter = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { limiter.acquire("claude").await.is_some() }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        chat.finish(CallOutcome::Success);
+        assert!(waiter.await.unwrap());
+    }
+}
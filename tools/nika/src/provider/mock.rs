@@ -0,0 +1,95 @@
+//! The `mock` provider: canned responses, no network (v0.7)
+//!
+//! Used by tests and available in every build, including the minimal
+//! `--no-default-features` one where it is the only provider.
+
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use super::registry::Provider;
+use super::rig::Image;
+use super::stream::{RigInferError, StreamChunk, StreamResult};
+
+/// Text of every mock `infer:` response
+pub const MOCK_INFER_RESPONSE: &str = "Mock response from infer";
+
+/// Model reported by mock `embed:` calls
+pub const MOCK_EMBEDDING_MODEL: &str = "mock-embed";
+
+/// Model reported by mock `transcribe:` calls
+pub const MOCK_TRANSCRIPTION_MODEL: &str = "mock-whisper";
+
+/// Response to an `infer:` prompt (tokens estimated at ~4 chars/token)
+pub fn infer(prompt: &str) -> StreamResult {
+    let input_tokens = (prompt.len() / 4) as u64;
+    let output_tokens = (MOCK_INFER_RESPONSE.len() / 4) as u64;
+    StreamResult {
+        text: MOCK_INFER_RESPONSE.to_string(),
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens + output_tokens,
+        cached_input_tokens: 0,
+    }
+}
+
+/// Deterministic bag-of-words embedding
+///
+/// Each lowercased word adds ±1 to one of 64 hashed buckets; texts sharing
+/// words score high under cosine similarity.
+pub fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; 64];
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        let hash = xxhash_rust::xxh3::xxh3_64(word.to_lowercase().as_bytes());
+        let sign = if hash & 1 == 0 { 1.0 } else { -1.0 };
+        vector[(hash >> 1) as usize % 64] += sign;
+    }
+    vector
+}
+
+/// Registry session of the `mock` provider
+#[derive(Debug, Clone, Copy)]
+pub struct MockProvider;
+
+#[async_trait]
+impl Provider for MockProvider {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn default_model(&self) -> &str {
+        "mock"
+    }
+
+    async fn infer_stream_with(
+        &self,
+        prompt: &str,
+        _tx: mpsc::Sender<StreamChunk>,
+        _model: Option<&str>,
+        _max_tokens: Option<u64>,
+        _images: &[Image],
+    ) -> Result<StreamResult, RigInferError> {
+        Ok(infer(prompt))
+    }
+
+    async fn embed(
+        &self,
+        texts: Vec<String>,
+        _model: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, RigInferError> {
+        Ok(texts.iter().map(|text| embed(text)).collect())
+    }
+
+    async fn transcribe(
+        &self,
+        _data: Vec<u8>,
+        filename: &str,
+        _model: Option<&str>,
+        _language: Option<&str>,
+        _prompt: Option<&str>,
+    ) -> Result<String, RigInferError> {
+        Ok(format!("[mock transcript of {}]", filename))
+    }
+}
@@ -8,7 +8,19 @@
 //! |-----------|----------------|
 //! | `agent:` verb | [`RigAgentLoop`](crate::runtime::RigAgentLoop) + rig-core |
 //! | `infer:` verb | [`RigProvider`](rig::RigProvider) + rig-core |
+//! | `provider:` names | [`registry`] (capabilities, credential env vars, [`register`](registry::register) for custom providers, v0.7) |
 //! | Tool calling | [`NikaMcpTool`](rig::NikaMcpTool) (rig `ToolDyn`) |
+//! | `provider: mock` | [`mock`] (canned responses, the only provider without the `providers` feature) |
+//! | Trace replay | [`ReplayProvider`](replay::ReplayProvider) (recorded responses) |
+//! | Test cassettes | [`Cassette`](cassette::Cassette) (`NIKA_CASSETTE_MODE`) |
+//! | Warm sessions | [`SessionPool`](pool::SessionPool) (model affinity, `--session-pool`) |
+//! | `model: auto` | [`ModelRouter`](router::ModelRouter) (`[router]` rules) |
+//! | In-flight limits | [`ConcurrencyLimiter`](limiter::ConcurrencyLimiter) (AIMD, `[concurrency]`) |
+//! | API key rotation | [`KeyPool`](keys::KeyPool) (quarantine, `[key_rotation]`) |
+//! | Size caps | [`SizeGuard`](size_guard::SizeGuard) (pre-call NIKA-122, `[size_limits]`) |
+//! | `images:` | [`vision`] (validation, base64 encoding, v0.7) |
+//! | `transcribe:` | [`audio`] (chunking, duration pricing, v0.7) |
+//! | Cost summary | [`pricing`] (token prices, prompt cache savings, v0.7) |
 //!
 //! ## Example
 //!
@@ -27,7 +39,36 @@
 //! let result = agent.run_claude().await?;
 //! ```
 
+pub mod audio;
+pub mod cassette;
+pub mod keys;
+pub mod limiter;
+pub mod mock;
+pub mod pool;
+pub mod pricing;
+pub mod registry;
+pub mod replay;
+#[cfg(feature = "providers")]
 pub mod rig;
+#[cfg(not(feature = "providers"))]
+#[path = "offline.rs"]
+pub mod rig;
+pub mod router;
+pub mod size_guard;
+pub mod stream;
+pub mod vision;
 
 // Re-export main types for convenience
-pub use rig::{NikaMcpTool, RigProvider, StreamResult};
+pub use cassette::{Cassette, CassetteMode};
+pub use keys::{KeyFault, KeyLease, KeyPool, Quarantine};
+pub use limiter::{CallOutcome, ConcurrencyLimiter, Priority};
+pub use pool::{PoolStats, SessionKey, SessionPool};
+pub use pricing::{ModelPrice, TokenUsage};
+pub use registry::{Capabilities, Capability, Provider, ProviderInfo};
+pub use replay::{RecordedResponse, ReplayProvider};
+#[cfg(feature = "providers")]
+pub use rig::NikaMcpTool;
+pub use rig::RigProvider;
+pub use router::{ModelRouter, Route, RouteInput, AUTO_MODEL};
+pub use size_guard::SizeGuard;
+pub use stream::StreamResult;
@@ -0,0 +1,74 @@
+//! Stand-in for [`rig`](self) in builds without the `providers` feature
+//!
+//! Only the `mock` provider is registered: [`RigProvider`] and [`Image`] have
+//! no values, so every path that would reach a real provider fails at
+//! [`registry::build`](super::registry::build) or [`disabled`] instead.
+
+use tokio::sync::mpsc;
+
+pub use super::stream::{RigInferError, StreamChunk, StreamResult};
+use crate::error::NikaError;
+
+/// Error of every real provider in this build
+pub fn disabled(name: &str) -> NikaError {
+    NikaError::Provider(format!(
+        "nika was built without the `providers` feature: '{}' is unavailable, only 'mock' is",
+        name
+    ))
+}
+
+/// A provider session (none can exist in this build)
+#[derive(Debug, Clone)]
+pub enum RigProvider {}
+
+/// An `images:` entry (none can be loaded in this build)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Image {}
+
+impl RigProvider {
+    pub fn name(&self) -> &'static str {
+        match *self {}
+    }
+
+    pub fn default_model(&self) -> &'static str {
+        match *self {}
+    }
+
+    pub async fn infer(
+        &self,
+        _prompt: &str,
+        _model: Option<&str>,
+    ) -> Result<String, RigInferError> {
+        match *self {}
+    }
+
+    pub async fn infer_stream_with(
+        &self,
+        _prompt: &str,
+        _tx: mpsc::Sender<StreamChunk>,
+        _model: Option<&str>,
+        _max_tokens: Option<u64>,
+        _images: &[Image],
+    ) -> Result<StreamResult, RigInferError> {
+        match *self {}
+    }
+
+    pub async fn embed(
+        &self,
+        _texts: Vec<String>,
+        _model: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, RigInferError> {
+        match *self {}
+    }
+
+    pub async fn transcribe(
+        &self,
+        _data: Vec<u8>,
+        _filename: &str,
+        _model: Option<&str>,
+        _language: Option<&str>,
+        _prompt: Option<&str>,
+    ) -> Result<String, RigInferError> {
+        match *self {}
+    }
+}
@@ -0,0 +1,257 @@
+//! Warm session pool with model affinity (v0.7)
+//!
+//! Building a provider session (client construction, credential lookup,
+//! connection setup) dominates short workflows. The pool keeps up to
+//! `capacity` sessions keyed by `(provider, model)` and routes each call
+//! to the session already warmed for its model, evicting the least
+//! recently used one when full.
+//!
+//! The runner pre-warms sessions for the models a workflow references, so
+//! the first task per model is a warm hit instead of a cold start.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use super::registry::Provider;
+use crate::error::NikaError;
+
+/// Default number of warm sessions kept by the executor
+pub const DEFAULT_POOL_SIZE: usize = 8;
+
+/// Affinity key: provider name + model (None = provider default)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    pub provider: String,
+    pub model: Option<String>,
+    /// Position of a `[key_rotation]` key (None = key from the environment)
+    pub key: Option<usize>,
+}
+
+impl SessionKey {
+    pub fn new(provider: &str, model: Option<&str>) -> Self {
+        Self {
+            provider: provider.to_string(),
+            model: model.map(str::to_string),
+            key: None,
+        }
+    }
+
+    /// Session using the `index`-th rotated key of the provider (v0.7)
+    pub fn with_key(mut self, index: usize) -> Self {
+        self.key = Some(index);
+        self
+    }
+}
+
+/// Warm-hit counters for a pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Calls served by an already-warm session
+    pub hits: u64,
+    /// Calls that had to build a session (cold start)
+    pub misses: u64,
+    /// Sessions built ahead of time by `warm`
+    pub warmed: u64,
+    /// Maximum number of sessions kept
+    pub capacity: usize,
+}
+
+impl PoolStats {
+    /// Fraction of calls served warm (None before any call)
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// LRU pool of provider sessions routed by model affinity
+pub struct SessionPool {
+    capacity: usize,
+    /// Most recently used last
+    sessions: Mutex<Vec<(SessionKey, Arc<dyn Provider>)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    warmed: AtomicU64,
+}
+
+impl SessionPool {
+    /// Create a pool keeping up to `capacity` sessions (0 disables pooling)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            sessions: Mutex::new(Vec::with_capacity(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            warmed: AtomicU64::new(0),
+        }
+    }
+
+    /// Get the warm session for `key`, building (and pooling) it on a miss
+    pub fn get_or_build(
+        &self,
+        key: &SessionKey,
+        build: impl FnOnce() -> Result<Arc<dyn Provider>, NikaError>,
+    ) -> Result<Arc<dyn Provider>, NikaError> {
+        if let Some(session) = self.touch(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(session);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let session = build()?;
+        self.insert(key.clone(), Arc::clone(&session));
+        Ok(session)
+    }
+
+    /// Build a session ahead of time (no-op if already warm or pool is full)
+    pub fn warm(
+        &self,
+        key: SessionKey,
+        build: impl FnOnce() -> Result<Arc<dyn Provider>, NikaError>,
+    ) -> Result<(), NikaError> {
+        if self.contains(&key) || self.len() >= self.capacity {
+            return Ok(());
+        }
+        let session = build()?;
+        self.insert(key, session);
+        self.warmed.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Whether a session for `key` is warm
+    pub fn contains(&self, key: &SessionKey) -> bool {
+        self.sessions.lock().iter().any(|(k, _)| k == key)
+    }
+
+    /// Number of warm sessions
+    pub fn len(&self) -> usize {
+        self.sessions.lock().len()
+    }
+
+    /// Whether the pool holds no sessions
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Snapshot of the warm-hit counters
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            warmed: self.warmed.load(Ordering::Relaxed),
+            capacity: self.capacity,
+        }
+    }
+
+    /// Return the session for `key` and mark it most recently used
+    fn touch(&self, key: &SessionKey) -> Option<Arc<dyn Provider>> {
+        let mut sessions = self.sessions.lock();
+        let pos = sessions.iter().position(|(k, _)| k == key)?;
+        let entry = sessions.remove(pos);
+        let session = Arc::clone(&entry.1);
+        sessions.push(entry);
+        Some(session)
+    }
+
+    /// Insert as most recently used, evicting the least recently used when full
+    fn insert(&self, key: SessionKey, session: Arc<dyn Provider>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|(k, _)| k != &key);
+        if sessions.len() >= self.capacity {
+            sessions.remove(0);
+        }
+        sessions.push((key, session));
+    }
+}
+
+impl Default for SessionPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_POOL_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::mock::MockProvider;
+
+    fn session() -> Result<Arc<dyn Provider>, NikaError> {
+        Ok(Arc::new(MockProvider))
+    }
+
+    #[test]
+    fn test_pool_hit_after_miss() {
+        let pool = SessionPool::new(2);
+        let key = SessionKey::new("ollama", Some("llama3.2"));
+
+        pool.get_or_build(&key, session).unwrap();
+        pool.get_or_build(&key, session).unwrap();
+
+        let stats = pool.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        assert_eq!(stats.hit_rate(), Some(0.5));
+    }
+
+    #[test]
+    fn test_pool_model_affinity() {
+        let pool = SessionPool::new(4);
+        pool.get_or_build(&SessionKey::new("ollama", Some("a")), session)
+            .unwrap();
+        pool.get_or_build(&SessionKey::new("ollama", Some("b")), session)
+            .unwrap();
+
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.stats().hits, 0);
+    }
+
+    #[test]
+    fn test_pool_evicts_least_recently_used() {
+        let pool = SessionPool::new(2);
+        let (a, b, c) = (
+            SessionKey::new("ollama", Some("a")),
+            SessionKey::new("ollama", Some("b")),
+            SessionKey::new("ollama", Some("c")),
+        );
+        pool.get_or_build(&a, session).unwrap();
+        pool.get_or_build(&b, session).unwrap();
+        pool.get_or_build(&a, session).unwrap(); // a is now most recent
+        pool.get_or_build(&c, session).unwrap(); // evicts b
+
+        assert!(pool.contains(&a));
+        assert!(!pool.contains(&b));
+        assert!(pool.contains(&c));
+    }
+
+    #[test]
+    fn test_pool_warm_counts_separately() {
+        let pool = SessionPool::new(2);
+        let key = SessionKey::new("ollama", None);
+
+        pool.warm(key.clone(), session).unwrap();
+        pool.warm(key.clone(), session).unwrap(); // already warm
+        pool.get_or_build(&key, session).unwrap();
+
+        let stats = pool.stats();
+        assert_eq!(stats.warmed, 1);
+        assert_eq!((stats.hits, stats.misses), (1, 0));
+    }
+
+    #[test]
+    fn test_pool_zero_capacity_disables_pooling() {
+        let pool = SessionPool::new(0);
+        let key = SessionKey::new("ollama", None);
+
+        pool.warm(key.clone(), session).unwrap();
+        pool.get_or_build(&key, session).unwrap();
+        pool.get_or_build(&key, session).unwrap();
+
+        assert!(pool.is_empty());
+        let stats = pool.stats();
+        assert_eq!((stats.hits, stats.misses, stats.warmed), (0, 2, 0));
+        assert_eq!(stats.hit_rate(), Some(0.0));
+    }
+}
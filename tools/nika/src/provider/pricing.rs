@@ -0,0 +1,141 @@
+//! Token pricing - per-call cost and prompt cache savings (v0.7)
+//!
+//! Anthropic bills prompt caching separately from regular input: writing
+//! a cache breakpoint costs 1.25x the input rate, reading it back 0.1x.
+//! [`TokenUsage`] keeps the three kinds of input apart so a run can report
+//! both its cost and what the cache saved.
+//!
+//! Prices are USD per million tokens. Models without a known price cost
+//! nothing here; the run summary then only reports tokens.
+
+/// Cache writes cost this much of the input rate (5-minute cache)
+pub const CACHE_WRITE_MULTIPLIER: f64 = 1.25;
+
+/// Cache reads cost this much of the input rate
+pub const CACHE_READ_MULTIPLIER: f64 = 0.1;
+
+/// Tokens of one provider call, or a sum of calls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    /// Input tokens billed at the base rate (not read from or written to the cache)
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Input tokens served from the prompt cache
+    pub cache_read_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_write_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Add another call's tokens
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+    }
+
+    /// All input tokens, cached or not
+    pub fn total_input_tokens(&self) -> u64 {
+        self.input_tokens + self.cache_read_tokens + self.cache_write_tokens
+    }
+
+    /// Whether the prompt cache was used at all
+    pub fn has_cache_activity(&self) -> bool {
+        self.cache_read_tokens > 0 || self.cache_write_tokens > 0
+    }
+}
+
+/// USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+}
+
+impl ModelPrice {
+    /// Price of a model, matched on its family prefix
+    pub fn for_model(model: &str) -> Option<Self> {
+        let (input, output) = match model {
+            m if m.starts_with("claude-opus-4-5") => (5.0, 25.0),
+            m if m.starts_with("claude-opus-4") || m.starts_with("claude-3-opus") => (15.0, 75.0),
+            m if m.starts_with("claude-sonnet-4")
+                || m.starts_with("claude-3-7-sonnet")
+                || m.starts_with("claude-3-5-sonnet") =>
+            {
+                (3.0, 15.0)
+            }
+            m if m.starts_with("claude-haiku-4-5") => (1.0, 5.0),
+            m if m.starts_with("claude-3-5-haiku") => (0.8, 4.0),
+            m if m.starts_with("claude-3-haiku") => (0.25, 1.25),
+            _ => return None,
+        };
+        Some(Self { input, output })
+    }
+
+    /// Cost of `usage` in USD
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let input = usage.input_tokens as f64
+            + usage.cache_write_tokens as f64 * CACHE_WRITE_MULTIPLIER
+            + usage.cache_read_tokens as f64 * CACHE_READ_MULTIPLIER;
+        (input * self.input + usage.output_tokens as f64 * self.output) / 1_000_000.0
+    }
+
+    /// What `usage` would have cost without caching, minus what it cost
+    ///
+    /// Negative when cache writes were never read back.
+    pub fn cache_savings(&self, usage: &TokenUsage) -> f64 {
+        let saved = usage.cache_read_tokens as f64 * (1.0 - CACHE_READ_MULTIPLIER)
+            - usage.cache_write_tokens as f64 * (CACHE_WRITE_MULTIPLIER - 1.0);
+        saved * self.input / 1_000_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_models_by_family() {
+        let sonnet = ModelPrice::for_model("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(sonnet.input, 3.0);
+        assert_eq!(
+            ModelPrice::for_model("claude-opus-4-5-20251101")
+                .unwrap()
+                .input,
+            5.0
+        );
+        assert_eq!(
+            ModelPrice::for_model("claude-opus-4-1-20250805")
+                .unwrap()
+                .input,
+            15.0
+        );
+        assert!(ModelPrice::for_model("gpt-4o").is_none());
+    }
+
+    #[test]
+    fn costs_cache_reads_and_writes() {
+        let price = ModelPrice {
+            input: 3.0,
+            output: 15.0,
+        };
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_read_tokens: 2_000_000,
+            cache_write_tokens: 1_000_000,
+        };
+        // 3.0 input + 1.5 output + 0.6 reads + 3.75 writes
+        assert!((price.cost(&usage) - 8.85).abs() < 1e-9);
+        // reads save 2M x 0.9 x $3, writes add 1M x 0.25 x $3
+        assert!((price.cache_savings(&usage) - 4.65).abs() < 1e-9);
+        assert_eq!(usage.total_input_tokens(), 4_000_000);
+
+        let write_only = TokenUsage {
+            cache_write_tokens: 1_000_000,
+            ..TokenUsage::default()
+        };
+        assert!(price.cache_savings(&write_only) < 0.0);
+    }
+}
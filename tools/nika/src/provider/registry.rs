@@ -0,0 +1,405 @@
+//! Provider registry - providers as named, pluggable factories (v0.7)
+//!
+//! Every `provider:` name is looked up in a process-wide registry. An entry
+//! pairs a [`ProviderInfo`] (aliases, credential env vars, capabilities,
+//! default models) with a factory building [`Provider`] sessions, optionally
+//! from an explicit API key (`[key_rotation]`).
+//!
+//! Built-in entries:
+//! - `mock`: canned responses, in every build
+//! - `claude`, `openai`, `mistral`, `ollama`, `groq`, `deepseek`: rig-core
+//!   clients (feature `providers`)
+//!
+//! Embedders and plugins add their own with [`register`]; `infer:`,
+//! `embed:` and `transcribe:` then accept the new name. `agent:` loops
+//! still run on the built-in rig-core clients.
+//!
+//! ```rust,ignore
+//! let info = ProviderInfo::new("local-llm", "qwen2.5").with_env("LOCAL_LLM_URL");
+//! nika::provider::registry::register(info, |_key| Ok(Arc::new(LocalLlm::from_env()?)));
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use tokio::sync::mpsc;
+
+use super::mock::{self, MockProvider};
+use super::rig::Image;
+use super::stream::{RigInferError, StreamChunk, StreamResult};
+use crate::error::NikaError;
+
+/// A provider session: one client, ready for calls
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// Registry name of the provider
+    fn name(&self) -> &str;
+
+    /// Model used when a task sets none
+    fn default_model(&self) -> &str;
+
+    /// Stream a completion, sending tokens to `tx` as they arrive
+    async fn infer_stream_with(
+        &self,
+        prompt: &str,
+        tx: mpsc::Sender<StreamChunk>,
+        model: Option<&str>,
+        max_tokens: Option<u64>,
+        images: &[Image],
+    ) -> Result<StreamResult, RigInferError>;
+
+    /// Embed texts, one vector per text in input order
+    async fn embed(
+        &self,
+        _texts: Vec<String>,
+        _model: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, RigInferError> {
+        Err(RigInferError::PromptError(format!(
+            "{} has no embeddings API",
+            self.name()
+        )))
+    }
+
+    /// Transcribe one audio upload (`filename` carries the format)
+    async fn transcribe(
+        &self,
+        _data: Vec<u8>,
+        _filename: &str,
+        _model: Option<&str>,
+        _language: Option<&str>,
+        _prompt: Option<&str>,
+    ) -> Result<String, RigInferError> {
+        Err(RigInferError::PromptError(format!(
+            "{} has no transcription API",
+            self.name()
+        )))
+    }
+}
+
+/// A feature a task can need from its provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    Infer,
+    Agent,
+    Vision,
+    Embed,
+    Transcribe,
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Infer => "infer",
+            Self::Agent => "agent",
+            Self::Vision => "vision",
+            Self::Embed => "embed",
+            Self::Transcribe => "transcribe",
+        }
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a provider can do
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `infer:` completions
+    pub infer: bool,
+    /// `agent:` tool-calling loops (built-in rig-core clients only)
+    pub agent: bool,
+    /// `images:` input
+    pub vision: bool,
+    /// `embed:` and `retrieve:` embeddings
+    pub embed: bool,
+    /// `transcribe:` speech-to-text
+    pub transcribe: bool,
+}
+
+impl Capabilities {
+    pub fn supports(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Infer => self.infer,
+            Capability::Agent => self.agent,
+            Capability::Vision => self.vision,
+            Capability::Embed => self.embed,
+            Capability::Transcribe => self.transcribe,
+        }
+    }
+}
+
+/// Static description of a registered provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderInfo {
+    pub name: String,
+    /// Other names accepted in `provider:`
+    pub aliases: Vec<String>,
+    /// Credential env vars; any one set is enough (empty = none needed)
+    pub env: Vec<String>,
+    pub capabilities: Capabilities,
+    pub default_model: String,
+    /// Default model of `embed:` (None = no embeddings API)
+    pub embedding_model: Option<String>,
+    /// Default model of `transcribe:` (None = no speech-to-text API)
+    pub transcription_model: Option<String>,
+    /// (cheap, premium) models for `model: auto`
+    pub tiers: Option<(String, String)>,
+}
+
+impl ProviderInfo {
+    /// A provider answering `infer:` with `default_model`
+    pub fn new(name: impl Into<String>, default_model: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            aliases: Vec::new(),
+            env: Vec::new(),
+            capabilities: Capabilities {
+                infer: true,
+                ..Capabilities::default()
+            },
+            default_model: default_model.into(),
+            embedding_model: None,
+            transcription_model: None,
+            tiers: None,
+        }
+    }
+
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    pub fn with_env(mut self, var: impl Into<String>) -> Self {
+        self.env.push(var.into());
+        self
+    }
+
+    pub fn with_agent(mut self) -> Self {
+        self.capabilities.agent = true;
+        self
+    }
+
+    pub fn with_vision(mut self) -> Self {
+        self.capabilities.vision = true;
+        self
+    }
+
+    pub fn with_embeddings(mut self, model: impl Into<String>) -> Self {
+        self.capabilities.embed = true;
+        self.embedding_model = Some(model.into());
+        self
+    }
+
+    pub fn with_transcription(mut self, model: impl Into<String>) -> Self {
+        self.capabilities.transcribe = true;
+        self.transcription_model = Some(model.into());
+        self
+    }
+
+    pub fn with_tiers(mut self, cheap: impl Into<String>, premium: impl Into<String>) -> Self {
+        self.tiers = Some((cheap.into(), premium.into()));
+        self
+    }
+
+    /// Whether one of the credential env vars is set (empty values count as unset)
+    pub fn has_credentials(&self) -> bool {
+        self.env.is_empty()
+            || self
+                .env
+                .iter()
+                .any(|var| std::env::var(var).is_ok_and(|v| !v.is_empty()))
+    }
+}
+
+/// Builds a session, from `api_key` when given, else from the environment
+pub type Factory = dyn Fn(Option<&str>) -> Result<Arc<dyn Provider>, NikaError> + Send + Sync;
+
+struct Entry {
+    info: ProviderInfo,
+    factory: Box<Factory>,
+}
+
+/// Name and alias -> entry
+static REGISTRY: LazyLock<DashMap<String, Arc<Entry>>> = LazyLock::new(|| {
+    let registry = DashMap::new();
+    let mock = ProviderInfo::new("mock", "mock")
+        .with_agent()
+        .with_embeddings(mock::MOCK_EMBEDDING_MODEL)
+        .with_transcription(mock::MOCK_TRANSCRIPTION_MODEL);
+    insert(&registry, mock, Box::new(|_| Ok(Arc::new(MockProvider))));
+    #[cfg(feature = "providers")]
+    for info in super::rig::builtins() {
+        let name = info.name.clone();
+        let factory = move |key: Option<&str>| {
+            super::rig::RigProvider::build(&name, key)
+                .map(|provider| Arc::new(provider) as Arc<dyn Provider>)
+        };
+        insert(&registry, info, Box::new(factory));
+    }
+    registry
+});
+
+fn insert(registry: &DashMap<String, Arc<Entry>>, info: ProviderInfo, factory: Box<Factory>) {
+    registry.retain(|_, entry| entry.info.name != info.name);
+    let names: Vec<String> = std::iter::once(info.name.clone())
+        .chain(info.aliases.iter().cloned())
+        .collect();
+    let entry = Arc::new(Entry { info, factory });
+    for name in names {
+        registry.insert(name, Arc::clone(&entry));
+    }
+}
+
+/// Add a provider, replacing any provider with the same name
+pub fn register(
+    info: ProviderInfo,
+    factory: impl Fn(Option<&str>) -> Result<Arc<dyn Provider>, NikaError> + Send + Sync + 'static,
+) {
+    insert(&REGISTRY, info, Box::new(factory));
+}
+
+/// Description of the provider registered under `name` (or an alias)
+pub fn info(name: &str) -> Option<ProviderInfo> {
+    REGISTRY.get(name).map(|entry| entry.info.clone())
+}
+
+/// Registered providers, sorted by name (aliases folded in)
+pub fn list() -> Vec<ProviderInfo> {
+    let mut infos: Vec<ProviderInfo> = REGISTRY
+        .iter()
+        .filter(|entry| *entry.key() == entry.info.name)
+        .map(|entry| entry.info.clone())
+        .collect();
+    infos.sort_by(|a, b| a.name.cmp(&b.name));
+    infos
+}
+
+/// Registered provider names, sorted
+pub fn names() -> Vec<String> {
+    list().into_iter().map(|info| info.name).collect()
+}
+
+/// Build a session of `name`, from `api_key` or the environment
+pub fn build(name: &str, api_key: Option<&str>) -> Result<Arc<dyn Provider>, NikaError> {
+    let entry = REGISTRY.get(name).map(|entry| Arc::clone(entry.value()));
+    let Some(entry) = entry else {
+        let hint = if cfg!(feature = "providers") {
+            ""
+        } else {
+            " (nika was built without the `providers` feature)"
+        };
+        return Err(NikaError::Provider(format!(
+            "Unknown provider: {}. Registered: {}{}",
+            name,
+            names().join(", "),
+            hint
+        )));
+    };
+    (entry.factory)(api_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Echo;
+
+    #[async_trait]
+    impl Provider for Echo {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn default_model(&self) -> &str {
+            "echo-1"
+        }
+
+        async fn infer_stream_with(
+            &self,
+            prompt: &str,
+            _tx: mpsc::Sender<StreamChunk>,
+            _model: Option<&str>,
+            _max_tokens: Option<u64>,
+            _images: &[Image],
+        ) -> Result<StreamResult, RigInferError> {
+            Ok(StreamResult {
+                text: prompt.to_string(),
+                ..StreamResult::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_and_build_by_alias() {
+        register(
+            ProviderInfo::new("echo-test", "echo-1").with_alias("parrot-test"),
+            |_| Ok(Arc::new(Echo)),
+        );
+
+        let info = info("parrot-test").unwrap();
+        assert_eq!(info.name, "echo-test");
+        assert!(info.capabilities.supports(Capability::Infer));
+        assert!(!info.capabilities.supports(Capability::Embed));
+        assert!(names().contains(&"echo-test".to_string()));
+        assert!(!names().contains(&"parrot-test".to_string()));
+
+        let provider = build("parrot-test", None).unwrap();
+        let (tx, _) = mpsc::channel(1);
+        let result = provider
+            .infer_stream_with("hi", tx, None, None, &[])
+            .await
+            .unwrap();
+        assert_eq!(result.text, "hi");
+        assert!(provider.embed(vec![], None).await.is_err());
+    }
+
+    #[test]
+    fn test_reregister_drops_old_aliases() {
+        register(
+            ProviderInfo::new("swap-test", "a").with_alias("swap-old"),
+            |_| Ok(Arc::new(Echo)),
+        );
+        register(
+            ProviderInfo::new("swap-test", "b").with_alias("swap-new"),
+            |_| Ok(Arc::new(Echo)),
+        );
+
+        assert!(info("swap-old").is_none());
+        assert_eq!(info("swap-new").unwrap().default_model, "b");
+    }
+
+    #[test]
+    fn test_unknown_provider_lists_registered() {
+        let Err(err) = build("nope", None) else {
+            panic!("'nope' is not registered");
+        };
+        assert!(err.to_string().contains("Unknown provider: nope"));
+        assert!(err.to_string().contains("mock"));
+    }
+
+    #[test]
+    fn test_credentials_from_env_list() {
+        assert!(ProviderInfo::new("x", "m").has_credentials());
+        assert!(!ProviderInfo::new("x", "m")
+            .with_env("NIKA_TEST_UNSET_PROVIDER_KEY")
+            .has_credentials());
+    }
+
+    #[cfg(feature = "providers")]
+    #[test]
+    fn test_builtin_metadata() {
+        let openai = info("gpt").unwrap();
+        assert_eq!(openai.name, "openai");
+        assert_eq!(openai.env, vec!["OPENAI_API_KEY"]);
+        assert!(openai.capabilities.embed && openai.capabilities.transcribe);
+        assert!(info("claude").unwrap().capabilities.vision);
+        assert!(!info("deepseek").unwrap().capabilities.vision);
+        assert_eq!(info("local").unwrap().name, "ollama");
+    }
+}
@@ -0,0 +1,281 @@
+//! Replay Provider - deterministic re-execution from recorded traces (v0.7)
+//!
+//! Builds a table of recorded LLM responses from an NDJSON trace so a
+//! workflow can be re-run without calling any provider. Responses are
+//! matched by task ID first (including `task[N]` for_each iterations),
+//! then by the exact resolved prompt.
+//!
+//! ```rust,ignore
+//! let events = nika::event::read_trace_events(&trace.path)?;
+//! let runner = Runner::new(workflow).with_replay(Arc::new(ReplayProvider::from_events(&events)));
+//! ```
+
+use std::collections::VecDeque;
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::error::NikaError;
+use crate::event::{Event, EventKind};
+
+/// A provider response captured in a trace
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedResponse {
+    /// Response text (task output as recorded)
+    pub text: String,
+    /// Model that produced the response
+    pub model: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub cache_read_tokens: u32,
+}
+
+/// Provider returning recorded responses instead of calling an LLM
+#[derive(Debug, Default)]
+pub struct ReplayProvider {
+    /// Task ID -> responses in recorded order
+    by_task: Mutex<FxHashMap<String, VecDeque<RecordedResponse>>>,
+    /// xxh3(prompt) -> response (fallback when task IDs changed)
+    by_prompt: FxHashMap<u64, RecordedResponse>,
+}
+
+/// Per-task scratch state while scanning a trace
+#[derive(Default)]
+struct Pending {
+    llm: bool,
+    prompt: Option<String>,
+    model: String,
+    tokens: (u32, u32, u32),
+}
+
+impl ReplayProvider {
+    /// Build a replay table from trace events
+    ///
+    /// Only `infer:` and `agent:` tasks that completed are recorded.
+    pub fn from_events(events: &[Event]) -> Self {
+        let mut pending: FxHashMap<&str, Pending> = FxHashMap::default();
+        let mut provider = Self::default();
+        let by_task = provider.by_task.get_mut();
+
+        for event in events {
+            match &event.kind {
+                EventKind::TaskStarted { task_id, verb, .. } => {
+                    pending.insert(
+                        task_id.as_ref(),
+                        Pending {
+                            llm: matches!(verb.as_ref(), "infer" | "agent"),
+                            ..Default::default()
+                        },
+                    );
+                }
+                EventKind::TemplateResolved {
+                    task_id, result, ..
+                } => {
+                    if let Some(p) = pending.get_mut(task_id.as_ref()) {
+                        p.prompt = Some(result.clone());
+                    }
+                }
+                EventKind::ProviderCalled { task_id, model, .. } => {
+                    if let Some(p) = pending.get_mut(task_id.as_ref()) {
+                        p.model = model.to_string();
+                    }
+                }
+                EventKind::ProviderResponded {
+                    task_id,
+                    input_tokens,
+                    output_tokens,
+                    cache_read_tokens,
+                    ..
+                } => {
+                    if let Some(p) = pending.get_mut(task_id.as_ref()) {
+                        p.tokens.0 += input_tokens;
+                        p.tokens.1 += output_tokens;
+                        p.tokens.2 += cache_read_tokens;
+                    }
+                }
+                EventKind::TaskCompleted {
+                    task_id, output, ..
+                } => {
+                    let Some(p) = pending.remove(task_id.as_ref()) else {
+                        continue;
+                    };
+                    if !p.llm {
+                        continue;
+                    }
+                    let response = RecordedResponse {
+                        text: match output.as_ref() {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        },
+                        model: p.model,
+                        input_tokens: p.tokens.0,
+                        output_tokens: p.tokens.1,
+                        cache_read_tokens: p.tokens.2,
+                    };
+                    if let Some(prompt) = p.prompt {
+                        provider
+                            .by_prompt
+                            .entry(xxh3_64(prompt.as_bytes()))
+                            .or_insert_with(|| response.clone());
+                    }
+                    by_task
+                        .entry(task_id.to_string())
+                        .or_default()
+                        .push_back(response);
+                }
+                _ => {}
+            }
+        }
+
+        provider
+    }
+
+    /// Number of recorded responses not yet replayed
+    pub fn remaining(&self) -> usize {
+        self.by_task.lock().values().map(VecDeque::len).sum()
+    }
+
+    /// Take the recorded response for a task (or, failing that, its prompt)
+    pub fn respond(&self, task_id: &str, prompt: &str) -> Result<RecordedResponse, NikaError> {
+        if let Some(response) = self
+            .by_task
+            .lock()
+            .get_mut(task_id)
+            .and_then(VecDeque::pop_front)
+        {
+            return Ok(response);
+        }
+
+        self.by_prompt
+            .get(&xxh3_64(prompt.as_bytes()))
+            .cloned()
+            .ok_or_else(|| {
+                NikaError::Provider(format!(
+                    "No recorded response for task '{}' in replay trace",
+                    task_id
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn event(id: u64, kind: EventKind) -> Event {
+        Event {
+            id,
+            timestamp_ms: id,
+            kind,
+        }
+    }
+
+    fn infer_trace(task_id: &str, prompt: &str, output: Value) -> Vec<Event> {
+        let task: Arc<str> = Arc::from(task_id);
+        vec![
+            event(
+                0,
+                EventKind::TaskStarted {
+                    task_id: Arc::clone(&task),
+                    verb: Arc::from("infer"),
+                    inputs: json!({}),
+                },
+            ),
+            event(
+                1,
+                EventKind::TemplateResolved {
+                    task_id: Arc::clone(&task),
+                    template: prompt.to_string(),
+                    result: prompt.to_string(),
+                },
+            ),
+            event(
+                2,
+                EventKind::ProviderCalled {
+                    task_id: Arc::clone(&task),
+                    provider: "claude".into(),
+                    model: "claude-sonnet".into(),
+                    prompt_len: prompt.len(),
+                },
+            ),
+            event(
+                3,
+                EventKind::ProviderResponded {
+                    task_id: Arc::clone(&task),
+                    request_id: None,
+                    input_tokens: 10,
+                    output_tokens: 20,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                    ttft_ms: None,
+                    finish_reason: "stop".into(),
+                    cost_usd: 0.0,
+                },
+            ),
+            event(
+                4,
+                EventKind::TaskCompleted {
+                    task_id: task,
+                    output: Arc::new(output),
+                    duration_ms: 5,
+                },
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_respond_by_task_id() {
+        let replay = ReplayProvider::from_events(&infer_trace("greet", "Say hi", json!("Hi!")));
+        assert_eq!(replay.remaining(), 1);
+
+        let response = replay.respond("greet", "anything").unwrap();
+        assert_eq!(response.text, "Hi!");
+        assert_eq!(response.model, "claude-sonnet");
+        assert_eq!(response.output_tokens, 20);
+        assert_eq!(replay.remaining(), 0);
+    }
+
+    #[test]
+    fn test_respond_falls_back_to_prompt() {
+        let replay = ReplayProvider::from_events(&infer_trace("greet", "Say hi", json!("Hi!")));
+        let response = replay.respond("renamed", "Say hi").unwrap();
+        assert_eq!(response.text, "Hi!");
+    }
+
+    #[test]
+    fn test_json_output_recorded_as_text() {
+        let replay =
+            ReplayProvider::from_events(&infer_trace("data", "Give JSON", json!({"a": 1})));
+        assert_eq!(replay.respond("data", "").unwrap().text, r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn test_non_llm_tasks_not_recorded() {
+        let task: Arc<str> = Arc::from("shell");
+        let events = vec![
+            event(
+                0,
+                EventKind::TaskStarted {
+                    task_id: Arc::clone(&task),
+                    verb: Arc::from("exec"),
+                    inputs: json!({}),
+                },
+            ),
+            event(
+                1,
+                EventKind::TaskCompleted {
+                    task_id: task,
+                    output: Arc::new(json!("out")),
+                    duration_ms: 1,
+                },
+            ),
+        ];
+        let replay = ReplayProvider::from_events(&events);
+        assert_eq!(replay.remaining(), 0);
+        assert!(replay.respond("shell", "").is_err());
+    }
+}
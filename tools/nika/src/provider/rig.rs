@@ -23,13 +23,22 @@
 //! rig-core AgentBuilder.tool()
 //! ```
 
+use super::registry::{Provider, ProviderInfo};
+pub use super::stream::{RigInferError, StreamChunk, StreamResult};
+use super::vision;
+use crate::error::NikaError;
 use crate::mcp::McpClient;
+use async_trait::async_trait;
 use futures::StreamExt;
-use rig::client::{CompletionClient, Nothing, ProviderClient};
+use rig::client::transcription::TranscriptionClient;
+use rig::client::{CompletionClient, EmbeddingsClient, Nothing, ProviderClient};
+pub use rig::completion::message::Image;
 use rig::completion::{CompletionModel as _, GetTokenUsage, Prompt, PromptError, ToolDefinition};
+use rig::embeddings::EmbeddingModel;
 use rig::providers::{anthropic, deepseek, groq, mistral, ollama, openai};
 use rig::streaming::StreamedAssistantContent;
 use rig::tool::{ToolDyn, ToolError};
+use rig::transcription::TranscriptionModel;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -168,6 +177,53 @@ impl RigProvider {
         RigProvider::DeepSeek(client)
     }
 
+    /// Create a provider with an explicit API key (v0.7, `[key_rotation]`)
+    ///
+    /// OpenAI still reads `OPENAI_BASE_URL`. Ollama takes no key.
+    pub fn with_api_key(name: &str, key: &str) -> Result<Self, NikaError> {
+        let client = match name {
+            "claude" | "anthropic" => anthropic::Client::new(key).map(RigProvider::Claude),
+            "openai" | "gpt" => {
+                let mut builder = openai::Client::builder().api_key(key);
+                if let Ok(base) = std::env::var("OPENAI_BASE_URL") {
+                    builder = builder.base_url(&base);
+                }
+                builder.build().map(RigProvider::OpenAI)
+            }
+            "mistral" => mistral::Client::new(key).map(RigProvider::Mistral),
+            "groq" => groq::Client::new(key).map(RigProvider::Groq),
+            "deepseek" | "deep-seek" => deepseek::Client::new(key).map(RigProvider::DeepSeek),
+            _ => {
+                return Err(NikaError::ProviderNotConfigured {
+                    provider: name.to_string(),
+                })
+            }
+        };
+        client.map_err(|e| NikaError::Provider(format!("{} client: {}", name, e)))
+    }
+
+    /// Create a provider from an explicit key or the environment (v0.7)
+    ///
+    /// Unlike the env constructors, a missing key is an error (NIKA-032)
+    /// instead of a panic. Ollama takes no key.
+    pub fn build(name: &str, key: Option<&str>) -> Result<Self, NikaError> {
+        match (name, key) {
+            ("ollama" | "local", _) => Ok(Self::ollama()),
+            (_, Some(key)) => Self::with_api_key(name, key),
+            (_, None) if !Self::has_credentials(name) => Err(NikaError::MissingApiKey {
+                provider: name.to_string(),
+            }),
+            ("claude" | "anthropic", None) => Ok(Self::claude()),
+            ("openai" | "gpt", None) => Ok(Self::openai()),
+            ("mistral", None) => Ok(Self::mistral()),
+            ("groq", None) => Ok(Self::groq()),
+            ("deepseek" | "deep-seek", None) => Ok(Self::deepseek()),
+            _ => Err(NikaError::ProviderNotConfigured {
+                provider: name.to_string(),
+            }),
+        }
+    }
+
     /// Get the provider name
     pub fn name(&self) -> &'static str {
         match self {
@@ -260,6 +316,85 @@ impl RigProvider {
         }
     }
 
+    /// Embed texts, one vector per text in input order (v0.7)
+    ///
+    /// Fails for providers without an embeddings API (see
+    /// [`default_embedding_model`](Self::default_embedding_model)).
+    pub async fn embed(
+        &self,
+        texts: Vec<String>,
+        model: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, RigInferError> {
+        let Some(default_model) = Self::default_embedding_model(self.name()) else {
+            return Err(RigInferError::PromptError(format!(
+                "{} has no embeddings API",
+                self.name()
+            )));
+        };
+        let model_id = model.unwrap_or(default_model);
+        match self {
+            RigProvider::OpenAI(client) => {
+                embed_with(client.embedding_model(model_id), texts).await
+            }
+            RigProvider::Mistral(client) => {
+                embed_with(client.embedding_model(model_id), texts).await
+            }
+            RigProvider::Ollama(client) => {
+                embed_with(client.embedding_model(model_id), texts).await
+            }
+            RigProvider::Claude(_) | RigProvider::Groq(_) | RigProvider::DeepSeek(_) => {
+                unreachable!("no default embedding model")
+            }
+        }
+    }
+
+    /// Transcribe one audio upload with the provider's speech-to-text API (v0.7)
+    ///
+    /// `filename` carries the format (its extension). `prompt` primes the
+    /// model with context or spellings.
+    pub async fn transcribe(
+        &self,
+        data: Vec<u8>,
+        filename: &str,
+        model: Option<&str>,
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String, RigInferError> {
+        let Some(default_model) = Self::default_transcription_model(self.name()) else {
+            return Err(RigInferError::PromptError(format!(
+                "{} has no transcription API",
+                self.name()
+            )));
+        };
+        let model_id = model.unwrap_or(default_model);
+        match self {
+            RigProvider::OpenAI(client) => {
+                transcribe_with(
+                    client.transcription_model(model_id),
+                    data,
+                    filename,
+                    language,
+                    prompt,
+                )
+                .await
+            }
+            RigProvider::Groq(client) => {
+                transcribe_with(
+                    client.transcription_model(model_id),
+                    data,
+                    filename,
+                    language,
+                    prompt,
+                )
+                .await
+            }
+            RigProvider::Claude(_)
+            | RigProvider::Mistral(_)
+            | RigProvider::Ollama(_)
+            | RigProvider::DeepSeek(_) => unreachable!("no default transcription model"),
+        }
+    }
+
     /// Auto-detect and create a provider from available environment variables (v0.6)
     ///
     /// Provider detection order:
@@ -297,68 +432,156 @@ impl RigProvider {
         }
         None
     }
-}
 
-/// Error type for RigProvider infer operations
-#[derive(Debug, thiserror::Error)]
-pub enum RigInferError {
-    #[error("Completion error: {0}")]
-    PromptError(String),
-}
+    /// Check whether credentials for a provider name are set (v0.7)
+    ///
+    /// Used before pre-warming sessions, since `from_env` panics on a
+    /// missing key. Ollama only needs `OLLAMA_API_BASE_URL` to be opted in.
+    pub fn has_credentials(name: &str) -> bool {
+        let has_key = |key: &str| std::env::var(key).is_ok_and(|v| !v.is_empty());
+        match name {
+            "claude" | "anthropic" => has_key("ANTHROPIC_API_KEY"),
+            "openai" | "gpt" => has_key("OPENAI_API_KEY"),
+            "mistral" => has_key("MISTRAL_API_KEY"),
+            "groq" => has_key("GROQ_API_KEY"),
+            "deepseek" | "deep-seek" => has_key("DEEPSEEK_API_KEY"),
+            "ollama" => has_key("OLLAMA_API_BASE_URL"),
+            _ => false,
+        }
+    }
 
-// =============================================================================
-// StreamChunk - Communication type for streaming responses
-// =============================================================================
+    /// Default embedding model of a provider, if it has an embeddings API (v0.7)
+    ///
+    /// | Provider | Model |
+    /// |----------|-------|
+    /// | OpenAI | text-embedding-3-small |
+    /// | Mistral | mistral-embed |
+    /// | Ollama | nomic-embed-text |
+    pub fn default_embedding_model(name: &str) -> Option<&'static str> {
+        match name {
+            "openai" | "gpt" => Some(openai::TEXT_EMBEDDING_3_SMALL),
+            "mistral" => Some(mistral::MISTRAL_EMBED),
+            "ollama" => Some(ollama::NOMIC_EMBED_TEXT),
+            _ => None,
+        }
+    }
 
-/// Chunk of streaming response for real-time display
-#[derive(Debug, Clone)]
-pub enum StreamChunk {
-    /// Text token from the model
-    Token(String),
-    /// Thinking/reasoning content (Claude extended thinking)
-    Thinking(String),
-    /// Stream completed successfully with final text
-    Done(String),
-    /// Stream failed with error
-    Error(String),
-    /// Token usage metrics (sent after completion)
-    Metrics {
-        input_tokens: u64,
-        output_tokens: u64,
-    },
-    /// MCP server connected successfully (v0.7.0)
-    McpConnected(String),
-    /// MCP server connection failed (v0.7.0)
-    McpError { server_name: String, error: String },
+    /// Default speech-to-text model of a provider, if it has one (v0.7)
+    ///
+    /// | Provider | Model |
+    /// |----------|-------|
+    /// | OpenAI | whisper-1 |
+    /// | Groq | whisper-large-v3-turbo |
+    pub fn default_transcription_model(name: &str) -> Option<&'static str> {
+        match name {
+            "openai" | "gpt" => Some(openai::WHISPER_1),
+            "groq" => Some("whisper-large-v3-turbo"),
+            _ => None,
+        }
+    }
+
+    /// (cheap, premium) models of a provider, for `model: auto` (v0.7)
+    ///
+    /// | Provider | Cheap | Premium |
+    /// |----------|-------|---------|
+    /// | Claude | claude-3-5-haiku-20241022 | claude-sonnet-4-20250514 |
+    /// | OpenAI | gpt-4o-mini | gpt-4o |
+    /// | Mistral | mistral-small-latest | mistral-large-latest |
+    /// | Ollama | llama3.2 | llama3.2 |
+    /// | Groq | llama-3.1-8b-instant | llama-3.3-70b-versatile |
+    /// | DeepSeek | deepseek-chat | deepseek-reasoner |
+    pub fn tier_models(name: &str) -> Option<(&'static str, &'static str)> {
+        Some(match name {
+            "claude" | "anthropic" => ("claude-3-5-haiku-20241022", "claude-sonnet-4-20250514"),
+            "openai" | "gpt" => (openai::GPT_4O_MINI, openai::GPT_4O),
+            "mistral" => (mistral::MISTRAL_SMALL, mistral::MISTRAL_LARGE),
+            "ollama" => ("llama3.2", "llama3.2"),
+            "groq" => ("llama-3.1-8b-instant", "llama-3.3-70b-versatile"),
+            "deepseek" | "deep-seek" => ("deepseek-chat", "deepseek-reasoner"),
+            _ => return None,
+        })
+    }
 }
 
-// =============================================================================
-// StreamResult - Complete streaming response with token usage
-// =============================================================================
+/// Registry entries of the rig-core providers (v0.7)
+///
+/// Models come from [`RigProvider::default_embedding_model`],
+/// [`RigProvider::default_transcription_model`] and [`RigProvider::tier_models`].
+pub(crate) fn builtins() -> Vec<ProviderInfo> {
+    let rig_info = |name: &str, default_model: &str, env: &str| {
+        let mut info = ProviderInfo::new(name, default_model)
+            .with_env(env)
+            .with_agent();
+        if let Some(model) = RigProvider::default_embedding_model(name) {
+            info = info.with_embeddings(model);
+        }
+        if let Some(model) = RigProvider::default_transcription_model(name) {
+            info = info.with_transcription(model);
+        }
+        if let Some((cheap, premium)) = RigProvider::tier_models(name) {
+            info = info.with_tiers(cheap, premium);
+        }
+        info
+    };
+    vec![
+        rig_info("claude", "claude-sonnet-4-20250514", "ANTHROPIC_API_KEY")
+            .with_alias("anthropic")
+            .with_vision(),
+        rig_info("openai", openai::GPT_4O, "OPENAI_API_KEY")
+            .with_alias("gpt")
+            .with_vision(),
+        rig_info("mistral", mistral::MISTRAL_LARGE, "MISTRAL_API_KEY"),
+        rig_info("ollama", "llama3.2", "OLLAMA_API_BASE_URL")
+            .with_alias("local")
+            .with_vision(),
+        rig_info("groq", "llama-3.3-70b-versatile", "GROQ_API_KEY"),
+        rig_info("deepseek", "deepseek-chat", "DEEPSEEK_API_KEY").with_alias("deep-seek"),
+    ]
+}
 
-/// Complete streaming response with text and token usage metrics
-#[derive(Debug, Clone, Default)]
-pub struct StreamResult {
-    /// The complete response text
-    pub text: String,
-    /// Number of input tokens used
-    pub input_tokens: u64,
-    /// Number of output tokens generated
-    pub output_tokens: u64,
-    /// Total tokens (input + output)
-    pub total_tokens: u64,
-    /// Cached input tokens (from prompt caching)
-    pub cached_input_tokens: u64,
+/// Embed in batches of the model's request limit
+async fn embed_with<M: EmbeddingModel>(
+    model: M,
+    texts: Vec<String>,
+) -> Result<Vec<Vec<f32>>, RigInferError> {
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(M::MAX_DOCUMENTS.max(1)) {
+        let embeddings = model
+            .embed_texts(batch.to_vec())
+            .await
+            .map_err(|e| RigInferError::PromptError(e.to_string()))?;
+        vectors.extend(
+            embeddings
+                .into_iter()
+                .map(|e| e.vec.into_iter().map(|x| x as f32).collect()),
+        );
+    }
+    Ok(vectors)
 }
 
-impl StreamResult {
-    /// Create a new StreamResult with just text (zero tokens)
-    pub fn from_text(text: impl Into<String>) -> Self {
-        Self {
-            text: text.into(),
-            ..Default::default()
-        }
+/// Send one transcription request
+async fn transcribe_with<M: TranscriptionModel>(
+    model: M,
+    data: Vec<u8>,
+    filename: &str,
+    language: Option<&str>,
+    prompt: Option<&str>,
+) -> Result<String, RigInferError> {
+    let mut request = model
+        .transcription_request()
+        .data(data)
+        .filename(Some(filename.to_string()));
+    if let Some(language) = language {
+        request = request.language(language.to_string());
+    }
+    if let Some(prompt) = prompt {
+        request = request.prompt(prompt.to_string());
     }
+    request
+        .send()
+        .await
+        .map(|response| response.text)
+        .map_err(|e| RigInferError::PromptError(e.to_string()))
 }
 
 impl RigProvider {
@@ -378,15 +601,35 @@ impl RigProvider {
         prompt: &str,
         tx: mpsc::Sender<StreamChunk>,
         model: Option<&str>,
+    ) -> Result<StreamResult, RigInferError> {
+        self.infer_stream_with(prompt, tx, model, None, &[]).await
+    }
+
+    /// Stream text completion with an output token limit and images (v0.7)
+    ///
+    /// Same as [`infer_stream`](Self::infer_stream), with `max_tokens`
+    /// passed through to the completion request (`infer.max_tokens`) and
+    /// `images` sent ahead of the prompt (see [`vision`](super::vision)).
+    pub async fn infer_stream_with(
+        &self,
+        prompt: &str,
+        tx: mpsc::Sender<StreamChunk>,
+        model: Option<&str>,
+        max_tokens: Option<u64>,
+        images: &[Image],
     ) -> Result<StreamResult, RigInferError> {
         let model_id = model.unwrap_or_else(|| self.default_model());
+        let message = vision::user_message(prompt, images);
         let mut response_parts: Vec<String> = Vec::new();
         let mut result = StreamResult::default();
 
         match self {
             RigProvider::Claude(client) => {
                 let model = client.completion_model(model_id);
-                let request = model.completion_request(prompt).build();
+                let request = model
+                    .completion_request(message.clone())
+                    .max_tokens_opt(max_tokens)
+                    .build();
 
                 let mut stream = model
                     .stream(request)
@@ -425,7 +668,10 @@ impl RigProvider {
             }
             RigProvider::OpenAI(client) => {
                 let model = client.completion_model(model_id);
-                let request = model.completion_request(prompt).build();
+                let request = model
+                    .completion_request(message.clone())
+                    .max_tokens_opt(max_tokens)
+                    .build();
 
                 let mut stream = model
                     .stream(request)
@@ -460,7 +706,10 @@ impl RigProvider {
             // v0.7: Full streaming support for all providers
             RigProvider::Mistral(client) => {
                 let model = client.completion_model(model_id);
-                let request = model.completion_request(prompt).build();
+                let request = model
+                    .completion_request(message.clone())
+                    .max_tokens_opt(max_tokens)
+                    .build();
 
                 let mut stream = model
                     .stream(request)
@@ -493,7 +742,10 @@ impl RigProvider {
             }
             RigProvider::Groq(client) => {
                 let model = client.completion_model(model_id);
-                let request = model.completion_request(prompt).build();
+                let request = model
+                    .completion_request(message.clone())
+                    .max_tokens_opt(max_tokens)
+                    .build();
 
                 let mut stream = model
                     .stream(request)
@@ -526,7 +778,10 @@ impl RigProvider {
             }
             RigProvider::DeepSeek(client) => {
                 let model = client.completion_model(model_id);
-                let request = model.completion_request(prompt).build();
+                let request = model
+                    .completion_request(message.clone())
+                    .max_tokens_opt(max_tokens)
+                    .build();
 
                 let mut stream = model
                     .stream(request)
@@ -559,7 +814,10 @@ impl RigProvider {
             }
             RigProvider::Ollama(client) => {
                 let model = client.completion_model(model_id);
-                let request = model.completion_request(prompt).build();
+                let request = model
+                    .completion_request(message.clone())
+                    .max_tokens_opt(max_tokens)
+                    .build();
 
                 let mut stream = model
                     .stream(request)
@@ -608,6 +866,47 @@ impl RigProvider {
     }
 }
 
+#[async_trait]
+impl Provider for RigProvider {
+    fn name(&self) -> &str {
+        RigProvider::name(self)
+    }
+
+    fn default_model(&self) -> &str {
+        RigProvider::default_model(self)
+    }
+
+    async fn infer_stream_with(
+        &self,
+        prompt: &str,
+        tx: mpsc::Sender<StreamChunk>,
+        model: Option<&str>,
+        max_tokens: Option<u64>,
+        images: &[Image],
+    ) -> Result<StreamResult, RigInferError> {
+        RigProvider::infer_stream_with(self, prompt, tx, model, max_tokens, images).await
+    }
+
+    async fn embed(
+        &self,
+        texts: Vec<String>,
+        model: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, RigInferError> {
+        RigProvider::embed(self, texts, model).await
+    }
+
+    async fn transcribe(
+        &self,
+        data: Vec<u8>,
+        filename: &str,
+        model: Option<&str>,
+        language: Option<&str>,
+        prompt: Option<&str>,
+    ) -> Result<String, RigInferError> {
+        RigProvider::transcribe(self, data, filename, model, language, prompt).await
+    }
+}
+
 // =============================================================================
 // NikaMcpTool - Wrapper for MCP tools implementing rig-core's ToolDyn
 // =============================================================================
@@ -790,6 +1089,15 @@ mod tests {
         assert!(matches!(provider, RigProvider::OpenAI(_)));
     }
 
+    #[test]
+    fn test_rig_provider_with_api_key() {
+        let provider = RigProvider::with_api_key("anthropic", "sk-ant-rotated").unwrap();
+        assert_eq!(provider.name(), "claude");
+        let provider = RigProvider::with_api_key("groq", "gsk-rotated").unwrap();
+        assert_eq!(provider.name(), "groq");
+        assert!(RigProvider::with_api_key("ollama", "unused").is_err());
+    }
+
     #[test]
     #[serial]
     fn test_rig_provider_default_model_claude() {
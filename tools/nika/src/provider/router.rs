@@ -0,0 +1,186 @@
+//! Model router for `model: auto` (v0.7)
+//!
+//! Tasks whose model is `auto` (directly or through the workflow default)
+//! get a cheap or premium model per call. The `[router]` rules in
+//! `~/.config/nika/config.toml` look at the resolved prompt length, whether
+//! the task can call tools, and the task's `tags:`; the first matching rule
+//! picks the tier. Each decision is recorded as a `ModelRouted` event.
+
+use super::registry;
+use crate::config::{ModelTier, RouteRule, RouterConfig};
+
+/// Model name that asks for routing
+pub const AUTO_MODEL: &str = "auto";
+
+/// True for `model: auto`
+pub fn is_auto(model: Option<&str>) -> bool {
+    model == Some(AUTO_MODEL)
+}
+
+/// What the router knows about a call
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RouteInput<'a> {
+    /// Provider the call goes to
+    pub provider: &'a str,
+    /// Resolved prompt length, in characters
+    pub prompt_chars: usize,
+    /// The task can call tools
+    pub tools: bool,
+    /// The task's `tags:`
+    pub tags: &'a [String],
+}
+
+/// A routing decision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub tier: ModelTier,
+    /// None = provider default (provider without a known tier pair)
+    pub model: Option<String>,
+    /// Rule that matched, e.g. `rule 1 (min_prompt_chars = 8000)`, or `default`
+    pub reason: String,
+}
+
+/// Picks a model tier per call from the `[router]` rules
+#[derive(Debug, Clone, Default)]
+pub struct ModelRouter {
+    config: RouterConfig,
+}
+
+impl ModelRouter {
+    pub fn new(config: RouterConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn route(&self, input: &RouteInput<'_>) -> Route {
+        let matched = self
+            .config
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| matches(rule, input));
+        let (tier, reason) = match matched {
+            Some((i, rule)) => (rule.tier, format!("rule {} ({})", i + 1, describe(rule))),
+            None => (self.config.default, "default".to_string()),
+        };
+        Route {
+            tier,
+            model: self.model(input.provider, tier),
+            reason,
+        }
+    }
+
+    /// Configured tier model, else the provider's own
+    fn model(&self, provider: &str, tier: ModelTier) -> Option<String> {
+        let configured = match tier {
+            ModelTier::Cheap => self.config.cheap.as_deref(),
+            ModelTier::Premium => self.config.premium.as_deref(),
+        };
+        configured.map(str::to_string).or_else(|| {
+            let (cheap, premium) = registry::info(provider)?.tiers?;
+            Some(match tier {
+                ModelTier::Cheap => cheap,
+                ModelTier::Premium => premium,
+            })
+        })
+    }
+}
+
+fn matches(rule: &RouteRule, input: &RouteInput<'_>) -> bool {
+    rule.min_prompt_chars
+        .is_none_or(|min| input.prompt_chars >= min)
+        && rule.tools.is_none_or(|tools| input.tools == tools)
+        && (rule.tags.is_empty() || rule.tags.iter().any(|tag| input.tags.contains(tag)))
+}
+
+/// Conditions of a rule, as written in the config
+fn describe(rule: &RouteRule) -> String {
+    let mut conditions = Vec::new();
+    if let Some(min) = rule.min_prompt_chars {
+        conditions.push(format!("min_prompt_chars = {}", min));
+    }
+    if let Some(tools) = rule.tools {
+        conditions.push(format!("tools = {}", tools));
+    }
+    if !rule.tags.is_empty() {
+        conditions.push(format!("tags = {:?}", rule.tags));
+    }
+    if conditions.is_empty() {
+        return "always".to_string();
+    }
+    conditions.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "providers")]
+    fn default_rules_route_by_length_tools_and_tags() {
+        let router = ModelRouter::default();
+        let short = RouteInput {
+            provider: "claude",
+            prompt_chars: 200,
+            ..Default::default()
+        };
+        let route = router.route(&short);
+        assert_eq!(route.tier, ModelTier::Cheap);
+        assert_eq!(route.model.as_deref(), Some("claude-3-5-haiku-20241022"));
+        assert_eq!(route.reason, "default");
+
+        let long = RouteInput {
+            prompt_chars: 20_000,
+            ..short
+        };
+        let route = router.route(&long);
+        assert_eq!(route.tier, ModelTier::Premium);
+        assert_eq!(route.reason, "rule 1 (min_prompt_chars = 8000)");
+
+        let agent = RouteInput {
+            provider: "openai",
+            tools: true,
+            ..short
+        };
+        assert_eq!(router.route(&agent).model.as_deref(), Some("gpt-4o"));
+
+        let tags = vec!["premium".to_string()];
+        let tagged = RouteInput {
+            tags: &tags,
+            ..short
+        };
+        assert_eq!(router.route(&tagged).tier, ModelTier::Premium);
+    }
+
+    #[test]
+    fn configured_rules_and_models_win() {
+        let router = ModelRouter::new(RouterConfig {
+            default: ModelTier::Premium,
+            cheap: Some("local-small".to_string()),
+            premium: None,
+            rules: vec![RouteRule {
+                tags: vec!["draft".to_string()],
+                tier: ModelTier::Cheap,
+                ..Default::default()
+            }],
+        });
+        let tags = vec!["draft".to_string(), "blog".to_string()];
+        let route = router.route(&RouteInput {
+            provider: "mock",
+            prompt_chars: 50_000,
+            tools: false,
+            tags: &tags,
+        });
+        assert_eq!(route.model.as_deref(), Some("local-small"));
+        assert_eq!(route.reason, "rule 1 (tags = [\"draft\"])");
+
+        // Unknown provider without a configured premium model: provider default
+        let route = router.route(&RouteInput {
+            provider: "mock",
+            ..Default::default()
+        });
+        assert_eq!(route.tier, ModelTier::Premium);
+        assert_eq!(route.model, None);
+        assert!(is_auto(Some("auto")));
+        assert!(!is_auto(None));
+    }
+}
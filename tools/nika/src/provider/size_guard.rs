@@ -0,0 +1,139 @@
+//! Request and response size guards (v0.7)
+//!
+//! The `[size_limits]` caps in `~/.config/nika/config.toml` are checked
+//! before a prompt is sent, so an oversized request fails with NIKA-122
+//! naming the bindings that made it large, instead of a provider 400
+//! after the upload.
+
+use crate::config::SizeLimitsConfig;
+use crate::error::NikaError;
+
+/// Bindings named in a prompt-size error
+const MAX_NAMED_BINDINGS: usize = 3;
+
+/// Checks requests against the `[size_limits]` caps
+#[derive(Debug, Clone, Default)]
+pub struct SizeGuard {
+    config: SizeLimitsConfig,
+}
+
+impl SizeGuard {
+    pub fn new(config: SizeLimitsConfig) -> Self {
+        Self { config }
+    }
+
+    /// Check a request to `provider`, returning the `max_tokens` to send
+    ///
+    /// That is the task's own `max_tokens`, else the provider's
+    /// `max_response_tokens` cap. `bindings` (alias, resolved size in
+    /// characters) is only called when the prompt is too long.
+    pub fn check(
+        &self,
+        provider: &str,
+        prompt: &str,
+        max_tokens: Option<u64>,
+        bindings: impl FnOnce() -> Vec<(String, usize)>,
+    ) -> Result<Option<u64>, NikaError> {
+        let limit = self.config.for_provider(provider);
+        let too_large = |reason: String| NikaError::RequestTooLarge {
+            provider: provider.to_string(),
+            reason,
+        };
+
+        if let Some(max) = limit.max_prompt_chars {
+            let chars = prompt.chars().count();
+            if chars > max {
+                let mut reason =
+                    format!("prompt is {} chars, over max_prompt_chars = {}", chars, max);
+                let mut bindings = bindings();
+                bindings.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                if !bindings.is_empty() {
+                    let largest: Vec<String> = bindings
+                        .iter()
+                        .take(MAX_NAMED_BINDINGS)
+                        .map(|(alias, size)| format!("{} ({} chars)", alias, size))
+                        .collect();
+                    reason.push_str(&format!("; largest bindings: {}", largest.join(", ")));
+                }
+                return Err(too_large(reason));
+            }
+        }
+
+        match (max_tokens, limit.max_response_tokens) {
+            (Some(asked), Some(max)) if asked > u64::from(max) => Err(too_large(format!(
+                "max_tokens is {}, over max_response_tokens = {}",
+                asked, max
+            ))),
+            (None, max) => Ok(max.map(u64::from)),
+            (asked, _) => Ok(asked),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SizeLimit;
+
+    fn guard() -> SizeGuard {
+        SizeGuard::new(SizeLimitsConfig {
+            max_prompt_chars: Some(20),
+            max_response_tokens: Some(1000),
+            providers: [(
+                "ollama".to_string(),
+                SizeLimit {
+                    max_prompt_chars: Some(100),
+                    max_response_tokens: None,
+                },
+            )]
+            .into(),
+        })
+    }
+
+    #[test]
+    fn prompt_over_cap_names_largest_bindings() {
+        let err = guard()
+            .check("claude", &"x".repeat(50), None, || {
+                vec![
+                    ("title".to_string(), 5),
+                    ("docs".to_string(), 40),
+                    ("a".to_string(), 1),
+                    ("b".to_string(), 1),
+                ]
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "[NIKA-122] Request to 'claude' too large: prompt is 50 chars, over \
+             max_prompt_chars = 20; largest bindings: docs (40 chars), title (5 chars), a (1 chars)"
+        );
+
+        // Provider override, bindings untouched when the prompt fits
+        let sent = guard()
+            .check("ollama", &"x".repeat(50), None, || unreachable!())
+            .unwrap();
+        assert_eq!(sent, Some(1000));
+    }
+
+    #[test]
+    fn response_cap_limits_max_tokens() {
+        let guard = guard();
+        assert_eq!(
+            guard.check("claude", "hi", Some(500), Vec::new).unwrap(),
+            Some(500)
+        );
+        let err = guard
+            .check("claude", "hi", Some(4096), Vec::new)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("max_tokens is 4096, over max_response_tokens = 1000"));
+
+        let open = SizeGuard::default();
+        assert_eq!(
+            open.check("claude", &"x".repeat(1 << 20), None, Vec::new)
+                .unwrap(),
+            None
+        );
+    }
+}
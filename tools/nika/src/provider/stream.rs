@@ -0,0 +1,66 @@
+//! Streaming results shared by all providers
+//!
+//! Kept apart from [`rig`](super::rig) so that builds without the
+//! `providers` feature (mock provider only) use the same types.
+
+/// Error type for RigProvider infer operations
+#[derive(Debug, thiserror::Error)]
+pub enum RigInferError {
+    #[error("Completion error: {0}")]
+    PromptError(String),
+}
+
+// =============================================================================
+// StreamChunk - Communication type for streaming responses
+// =============================================================================
+
+/// Chunk of streaming response for real-time display
+#[derive(Debug, Clone)]
+pub enum StreamChunk {
+    /// Text token from the model
+    Token(String),
+    /// Thinking/reasoning content (Claude extended thinking)
+    Thinking(String),
+    /// Stream completed successfully with final text
+    Done(String),
+    /// Stream failed with error
+    Error(String),
+    /// Token usage metrics (sent after completion)
+    Metrics {
+        input_tokens: u64,
+        output_tokens: u64,
+    },
+    /// MCP server connected successfully (v0.7.0)
+    McpConnected(String),
+    /// MCP server connection failed (v0.7.0)
+    McpError { server_name: String, error: String },
+}
+
+// =============================================================================
+// StreamResult - Complete streaming response with token usage
+// =============================================================================
+
+/// Complete streaming response with text and token usage metrics
+#[derive(Debug, Clone, Default)]
+pub struct StreamResult {
+    /// The complete response text
+    pub text: String,
+    /// Number of input tokens used
+    pub input_tokens: u64,
+    /// Number of output tokens generated
+    pub output_tokens: u64,
+    /// Total tokens (input + output)
+    pub total_tokens: u64,
+    /// Cached input tokens (from prompt caching)
+    pub cached_input_tokens: u64,
+}
+
+impl StreamResult {
+    /// Create a new StreamResult with just text (zero tokens)
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Image input for `infer:` and `agent:` (v0.7)
+//!
+//! `images:` entries are local paths or `http(s)` URLs. Local files are
+//! checked (type, size) and sent base64-encoded; URLs are passed through
+//! for the provider to fetch. Images go before the prompt text in the user
+//! message, which is the order vision models handle best.
+//!
+//! | Provider | Local files | URLs |
+//! |----------|-------------|------|
+//! | claude | ✓ | ✓ |
+//! | openai | ✓ | ✓ |
+//! | ollama | ✓ | — |
+
+use std::path::Path;
+
+#[cfg(feature = "providers")]
+use base64::Engine as _;
+#[cfg(feature = "providers")]
+use rig::completion::message::{
+    DocumentSourceKind, ImageDetail, ImageMediaType, Message, UserContent,
+};
+#[cfg(feature = "providers")]
+use rig::OneOrMany;
+
+use super::registry;
+use super::rig::Image;
+use crate::error::NikaError;
+
+/// Maximum images per task
+pub const MAX_IMAGES: usize = 8;
+
+/// Maximum size of a local image file (the strictest provider limit)
+pub const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Providers that accept image input (the `vision` capability)
+pub fn supports_vision(provider: &str) -> bool {
+    registry::info(provider).is_some_and(|info| info.capabilities.vision)
+}
+
+/// Load `images:` entries, resolving relative paths against `base`
+pub fn load_images(sources: &[String], base: &Path) -> Result<Vec<Image>, NikaError> {
+    if sources.len() > MAX_IMAGES {
+        return Err(NikaError::InvalidImage {
+            image: sources[MAX_IMAGES].clone(),
+            reason: format!(
+                "{} images given, at most {} per task",
+                sources.len(),
+                MAX_IMAGES
+            ),
+        });
+    }
+    sources
+        .iter()
+        .map(|source| load_image(source, base))
+        .collect()
+}
+
+#[cfg(feature = "providers")]
+fn load_image(source: &str, base: &Path) -> Result<Image, NikaError> {
+    let invalid = |reason: String| NikaError::InvalidImage {
+        image: source.to_string(),
+        reason,
+    };
+    if source.starts_with("https://") || source.starts_with("http://") {
+        let path = source.split(['?', '#']).next().unwrap_or(source);
+        return Ok(Image {
+            data: DocumentSourceKind::Url(source.to_string()),
+            media_type: media_type(path),
+            detail: Some(ImageDetail::Auto),
+            additional_params: None,
+        });
+    }
+
+    let path = base.join(source);
+    let media_type = media_type(source)
+        .ok_or_else(|| invalid("unsupported image type (use png, jpeg, gif or webp)".into()))?;
+    let size = std::fs::metadata(&path)
+        .map_err(|e| invalid(e.to_string()))?
+        .len();
+    if size > MAX_IMAGE_BYTES {
+        return Err(invalid(format!(
+            "{:.1} MB, the limit is {} MB",
+            size as f64 / (1024.0 * 1024.0),
+            MAX_IMAGE_BYTES / (1024 * 1024)
+        )));
+    }
+    let bytes = std::fs::read(&path).map_err(|e| invalid(e.to_string()))?;
+    Ok(Image {
+        data: DocumentSourceKind::Base64(base64::engine::general_purpose::STANDARD.encode(bytes)),
+        media_type: Some(media_type),
+        detail: Some(ImageDetail::Auto),
+        additional_params: None,
+    })
+}
+
+/// Built without the `providers` feature: no image can be sent
+#[cfg(not(feature = "providers"))]
+fn load_image(source: &str, _base: &Path) -> Result<Image, NikaError> {
+    Err(NikaError::InvalidImage {
+        image: source.to_string(),
+        reason: "nika was built without the `providers` feature".to_string(),
+    })
+}
+
+/// Fail unless `provider` can take these images
+pub fn check_provider(provider: &str, images: &[Image]) -> Result<(), NikaError> {
+    if images.is_empty() {
+        return Ok(());
+    }
+    if !supports_vision(provider) {
+        return Err(NikaError::VisionUnsupported {
+            provider: provider.to_string(),
+        });
+    }
+    #[cfg(feature = "providers")]
+    if matches!(provider, "ollama" | "local") {
+        if let Some(DocumentSourceKind::Url(url)) = images
+            .iter()
+            .map(|image| &image.data)
+            .find(|data| matches!(data, DocumentSourceKind::Url(_)))
+        {
+            return Err(NikaError::InvalidImage {
+                image: url.clone(),
+                reason: "ollama only takes local image files".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// User message with the images followed by the prompt text
+#[cfg(feature = "providers")]
+pub fn user_message(prompt: &str, images: &[Image]) -> Message {
+    let content: Vec<UserContent> = images
+        .iter()
+        .cloned()
+        .map(UserContent::Image)
+        .chain(std::iter::once(UserContent::text(prompt)))
+        .collect();
+    Message::User {
+        content: OneOrMany::many(content).expect("prompt text is always present"),
+    }
+}
+
+/// Stable identity of the images, for cassette keys
+#[cfg(not(feature = "providers"))]
+pub fn images_key(images: &[Image]) -> Vec<String> {
+    images.iter().map(|image| match *image {}).collect()
+}
+
+/// Stable identity of the images, for cassette keys
+#[cfg(feature = "providers")]
+pub fn images_key(images: &[Image]) -> Vec<String> {
+    images
+        .iter()
+        .map(|image| match &image.data {
+            DocumentSourceKind::Url(url) => url.clone(),
+            DocumentSourceKind::Base64(data) => {
+                format!("{:016x}", xxhash_rust::xxh3::xxh3_64(data.as_bytes()))
+            }
+            other => format!("{:?}", other),
+        })
+        .collect()
+}
+
+#[cfg(feature = "providers")]
+fn media_type(path: &str) -> Option<ImageMediaType> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some(ImageMediaType::PNG),
+        "jpg" | "jpeg" => Some(ImageMediaType::JPEG),
+        "gif" => Some(ImageMediaType::GIF),
+        "webp" => Some(ImageMediaType::WEBP),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "providers"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_images_encodes_files_and_keeps_urls() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("chart.PNG"), b"\x89PNG fake").unwrap();
+        let images = load_images(
+            &[
+                "chart.PNG".to_string(),
+                "https://example.com/photo.jpg?size=large".to_string(),
+            ],
+            dir.path(),
+        )
+        .unwrap();
+
+        assert_eq!(images[0].media_type, Some(ImageMediaType::PNG));
+        assert_eq!(
+            images[0].data,
+            DocumentSourceKind::Base64("iVBORyBmYWtl".to_string())
+        );
+        assert_eq!(images[1].media_type, Some(ImageMediaType::JPEG));
+        assert!(matches!(images[1].data, DocumentSourceKind::Url(_)));
+        assert_eq!(
+            images_key(&images)[1],
+            "https://example.com/photo.jpg?size=large"
+        );
+
+        let Message::User { content } = user_message("What trend?", &images) else {
+            panic!("expected a user message");
+        };
+        assert_eq!(content.len(), 3);
+        assert!(matches!(content.last(), UserContent::Text(_)));
+    }
+
+    #[test]
+    fn test_load_images_rejects_bad_input() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("scan.tiff"), b"II*").unwrap();
+        let load = |source: &str| load_images(&[source.to_string()], dir.path()).unwrap_err();
+
+        assert!(load("scan.tiff")
+            .to_string()
+            .contains("unsupported image type"));
+        assert_eq!(load("missing.png").code(), "NIKA-036");
+
+        let big = std::fs::File::create(dir.path().join("big.png")).unwrap();
+        big.set_len(MAX_IMAGE_BYTES + 1).unwrap();
+        assert!(load("big.png").to_string().contains("the limit is 5 MB"));
+
+        let many = vec!["a.png".to_string(); MAX_IMAGES + 1];
+        let err = load_images(&many, dir.path()).unwrap_err();
+        assert!(err.to_string().contains("at most 8 per task"));
+    }
+
+    #[test]
+    fn test_check_provider() {
+        let url = load_images(&["https://example.com/a.png".to_string()], Path::new(".")).unwrap();
+        assert!(check_provider("groq", &[]).is_ok());
+        assert!(check_provider("claude", &url).is_ok());
+        assert_eq!(check_provider("groq", &url).unwrap_err().code(), "NIKA-035");
+        assert_eq!(
+            check_provider("ollama", &url).unwrap_err().code(),
+            "NIKA-036"
+        );
+    }
+}
@@ -0,0 +1,600 @@
+//! Relay for live shared runs (v0.7, `nika relay`)
+//!
+//! `nika run --share` publishes the run's events (scrubbed by
+//! [`Redactor`]) to a self-hosted relay and prints a URL; teammates open it
+//! with `nika watch <url>` to follow the same Monitor view, read-only.
+//!
+//! ## Protocol
+//!
+//! Plain HTTP/1.1 with newline-delimited JSON events:
+//!
+//! - `POST /sessions` creates a session: `{"id": "...", "token": "..."}`
+//! - `POST /sessions/<id>/events` appends events (`Authorization: Bearer <token>`)
+//! - `DELETE /sessions/<id>` ends the session (same token)
+//! - `GET /sessions/<id>` streams every event so far, then new ones as they
+//!   arrive, until the session ends; empty lines are keep-alives
+//!
+//! Only the token holder can write, and the relay never interprets events.
+//! Sessions live in memory: ended ones are dropped after
+//! [`ENDED_SESSION_TTL`], abandoned ones after [`IDLE_SESSION_TTL`]. For
+//! TLS, put the relay behind a reverse proxy.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::{NikaError, Result};
+use crate::event::redact::Redactor;
+use crate::event::{Event, EventLog};
+
+/// Default `nika relay` listen address
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:7878";
+/// Ended sessions stay viewable this long
+pub const ENDED_SESSION_TTL: Duration = Duration::from_secs(10 * 60);
+/// Sessions without events for this long are dropped
+pub const IDLE_SESSION_TTL: Duration = Duration::from_secs(60 * 60);
+/// Sessions held at once
+const MAX_SESSIONS: usize = 64;
+/// Events kept per session
+const MAX_EVENTS: usize = 200_000;
+/// Largest request body
+const MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+/// Keep-alive interval on viewer streams
+const KEEPALIVE: Duration = Duration::from_secs(15);
+/// How often `nika run --share` sends new events
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Reply to `POST /sessions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedSession {
+    pub id: String,
+    /// Write token, known only to the publisher
+    pub token: String,
+}
+
+struct Session {
+    token: String,
+    events: Vec<Arc<str>>,
+    ended: bool,
+    touched: Instant,
+    /// Event count, bumped on every append (and on end) to wake viewers
+    notify: watch::Sender<usize>,
+}
+
+impl Session {
+    fn expired(&self, now: Instant) -> bool {
+        let ttl = if self.ended {
+            ENDED_SESSION_TTL
+        } else {
+            IDLE_SESSION_TTL
+        };
+        now.duration_since(self.touched) > ttl
+    }
+}
+
+/// In-memory sessions of a relay
+#[derive(Default)]
+pub struct Relay {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+/// A parsed request (also read by the daemon's run event server)
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) token: Option<String>,
+    /// `Last-Event-ID` of a reconnecting event stream
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    pub(crate) last_event_id: Option<u64>,
+    pub(crate) body: Vec<u8>,
+}
+
+/// A reply that is not a viewer stream
+pub(crate) struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Reply {
+    pub(crate) fn json(status: u16, value: impl Serialize) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_string(&value).unwrap_or_default(),
+        }
+    }
+
+    /// Plain-text body of the given content type (v0.7)
+    pub(crate) fn text(status: u16, content_type: &'static str, body: String) -> Self {
+        Self {
+            status,
+            content_type,
+            body,
+        }
+    }
+
+    pub(crate) fn error(status: u16, message: &str) -> Self {
+        Self::json(status, serde_json::json!({ "error": message }))
+    }
+}
+
+impl Relay {
+    /// Accept connections until the listener fails
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let relay = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = relay.handle(stream).await {
+                    tracing::debug!(error = %e, "Relay connection closed");
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, stream: TcpStream) -> std::io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        let request = match read_request(&mut reader).await? {
+            Ok(request) => request,
+            Err(reply) => return write_reply(&mut writer, reply).await,
+        };
+
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["sessions", id]) => self.stream(id, &mut writer).await,
+            ("POST", ["sessions"]) => write_reply(&mut writer, self.create()).await,
+            ("POST", ["sessions", id, "events"]) => {
+                let reply = self.append(id, request.token.as_deref(), &request.body);
+                write_reply(&mut writer, reply).await
+            }
+            ("DELETE", ["sessions", id]) => {
+                let reply = self.end(id, request.token.as_deref());
+                write_reply(&mut writer, reply).await
+            }
+            _ => write_reply(&mut writer, Reply::error(404, "not found")).await,
+        }
+    }
+
+    fn create(&self) -> Reply {
+        let mut sessions = self.sessions.lock();
+        let now = Instant::now();
+        sessions.retain(|_, session| !session.expired(now));
+        if sessions.len() >= MAX_SESSIONS {
+            return Reply::error(503, "relay is full, try again later");
+        }
+        let created = CreatedSession {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            token: uuid::Uuid::new_v4().simple().to_string(),
+        };
+        sessions.insert(
+            created.id.clone(),
+            Session {
+                token: created.token.clone(),
+                events: Vec::new(),
+                ended: false,
+                touched: now,
+                notify: watch::channel(0).0,
+            },
+        );
+        Reply::json(201, created)
+    }
+
+    /// Look up a session the caller may write to
+    fn writable<'a>(
+        sessions: &'a mut HashMap<String, Session>,
+        id: &str,
+        token: Option<&str>,
+    ) -> std::result::Result<&'a mut Session, Reply> {
+        let session = sessions
+            .get_mut(id)
+            .ok_or_else(|| Reply::error(404, "no such session"))?;
+        if token != Some(session.token.as_str()) {
+            return Err(Reply::error(403, "wrong session token"));
+        }
+        if session.ended {
+            return Err(Reply::error(409, "session has ended"));
+        }
+        Ok(session)
+    }
+
+    fn append(&self, id: &str, token: Option<&str>, body: &[u8]) -> Reply {
+        let mut sessions = self.sessions.lock();
+        let session = match Self::writable(&mut sessions, id, token) {
+            Ok(session) => session,
+            Err(reply) => return reply,
+        };
+        let Ok(body) = std::str::from_utf8(body) else {
+            return Reply::error(400, "body is not UTF-8");
+        };
+        let lines: Vec<&str> = body.lines().filter(|l| !l.trim().is_empty()).collect();
+        if session.events.len() + lines.len() > MAX_EVENTS {
+            return Reply::error(413, "session holds too many events");
+        }
+        session.events.extend(lines.into_iter().map(Arc::from));
+        session.touched = Instant::now();
+        session.notify.send_replace(session.events.len());
+        Reply::json(200, serde_json::json!({ "events": session.events.len() }))
+    }
+
+    fn end(&self, id: &str, token: Option<&str>) -> Reply {
+        let mut sessions = self.sessions.lock();
+        let session = match Self::writable(&mut sessions, id, token) {
+            Ok(session) => session,
+            Err(reply) => return reply,
+        };
+        session.ended = true;
+        session.touched = Instant::now();
+        session.notify.send_replace(session.events.len());
+        Reply::json(200, serde_json::json!({ "ended": true }))
+    }
+
+    /// Send the session's events to a viewer until it ends
+    async fn stream(
+        &self,
+        id: &str,
+        writer: &mut (impl AsyncWrite + Unpin),
+    ) -> std::io::Result<()> {
+        let changed = self.sessions.lock().get(id).map(|s| s.notify.subscribe());
+        let Some(mut changed) = changed else {
+            return write_reply(writer, Reply::error(404, "no such session")).await;
+        };
+        writer
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\n\
+                  Cache-Control: no-cache\r\nConnection: close\r\n\r\n",
+            )
+            .await?;
+
+        let mut sent = 0;
+        loop {
+            // Copy out under the lock, write without it
+            let (batch, ended) = {
+                let sessions = self.sessions.lock();
+                let Some(session) = sessions.get(id) else {
+                    return Ok(());
+                };
+                (session.events[sent..].to_vec(), session.ended)
+            };
+            sent += batch.len();
+            let mut chunk = String::new();
+            for line in &batch {
+                chunk.push_str(line);
+                chunk.push('\n');
+            }
+            if !chunk.is_empty() {
+                writer.write_all(chunk.as_bytes()).await?;
+                writer.flush().await?;
+            }
+            if ended {
+                return Ok(());
+            }
+            match tokio::time::timeout(KEEPALIVE, changed.changed()).await {
+                Ok(Ok(())) => {}
+                // Session dropped from the relay
+                Ok(Err(_)) => return Ok(()),
+                Err(_) => {
+                    writer.write_all(b"\n").await?;
+                    writer.flush().await?;
+                }
+            }
+        }
+    }
+}
+
+/// Read one request; an `Err` reply is sent back as is
+pub(crate) async fn read_request(
+    reader: &mut (impl AsyncBufReadExt + Unpin),
+) -> std::io::Result<std::result::Result<Request, Reply>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(Err(Reply::error(400, "malformed request line")));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut content_length = 0;
+    let mut token = None;
+    let mut last_event_id = None;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().unwrap_or(0);
+        } else if name.eq_ignore_ascii_case("authorization") {
+            token = value.strip_prefix("Bearer ").map(|t| t.trim().to_string());
+        } else if name.eq_ignore_ascii_case("last-event-id") {
+            last_event_id = value.parse().ok();
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Ok(Err(Reply::error(413, "request body too large")));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(Ok(Request {
+        method,
+        path,
+        token,
+        last_event_id,
+        body,
+    }))
+}
+
+pub(crate) async fn write_reply(
+    writer: &mut (impl AsyncWrite + Unpin),
+    reply: Reply,
+) -> std::io::Result<()> {
+    let reason = match reply.status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        _ => "Service Unavailable",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        reply.status,
+        reason,
+        reply.content_type,
+        reply.body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    writer.write_all(reply.body.as_bytes()).await?;
+    writer.flush().await
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// CLIENTS
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn relay_error(url: &str, reason: impl std::fmt::Display) -> NikaError {
+    NikaError::RelayError {
+        url: url.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// A session `nika run --share` publishes to
+#[derive(Debug, Clone)]
+pub struct ShareSession {
+    client: reqwest::Client,
+    /// `<relay>/sessions/<id>`
+    url: String,
+    token: String,
+}
+
+impl ShareSession {
+    /// Open a new session on the relay at `relay`
+    pub async fn create(relay: &str) -> Result<Self> {
+        let relay = relay.trim_end_matches('/');
+        let client = reqwest::Client::new();
+        let response = client
+            .post(format!("{}/sessions", relay))
+            .send()
+            .await
+            .map_err(|e| relay_error(relay, e))?;
+        if !response.status().is_success() {
+            return Err(relay_error(relay, response_error(response).await));
+        }
+        let created: CreatedSession = response
+            .json()
+            .await
+            .map_err(|e| relay_error(relay, format!("unexpected reply: {}", e)))?;
+        Ok(Self {
+            client,
+            url: format!("{}/sessions/{}", relay, created.id),
+            token: created.token,
+        })
+    }
+
+    /// URL for `nika watch`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Append NDJSON `lines` to the session
+    pub async fn publish(&self, lines: String) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/events", self.url))
+            .bearer_auth(&self.token)
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(lines)
+            .send()
+            .await
+            .map_err(|e| relay_error(&self.url, e))?;
+        if !response.status().is_success() {
+            return Err(relay_error(&self.url, response_error(response).await));
+        }
+        Ok(())
+    }
+
+    /// End the session; viewers get the remaining events, then end
+    pub async fn end(&self) -> Result<()> {
+        let response = self
+            .client
+            .delete(&self.url)
+            .bearer_auth(&self.token)
+            .send()
+            .await
+            .map_err(|e| relay_error(&self.url, e))?;
+        if !response.status().is_success() {
+            return Err(relay_error(&self.url, response_error(response).await));
+        }
+        Ok(())
+    }
+
+    /// Publish `event_log` as it grows, until `done` is cancelled
+    ///
+    /// The last batch is sent and the session ended after `done`. Events are
+    /// redacted before they leave the process.
+    pub fn spawn_publisher(
+        self,
+        event_log: EventLog,
+        redactor: Redactor,
+        done: CancellationToken,
+    ) -> JoinHandle<Result<()>> {
+        tokio::spawn(async move {
+            let mut sent = 0;
+            loop {
+                let finished = tokio::select! {
+                    _ = done.cancelled() => true,
+                    _ = tokio::time::sleep(PUBLISH_INTERVAL) => false,
+                };
+                let lines = event_log.with_events(|events| {
+                    let lines: Vec<String> = events[sent..]
+                        .iter()
+                        .map(|event| redactor.redact_event(event).to_string())
+                        .collect();
+                    sent = events.len();
+                    lines
+                });
+                if !lines.is_empty() {
+                    self.publish(lines.join("\n")).await?;
+                }
+                if finished {
+                    return self.end().await;
+                }
+            }
+        })
+    }
+}
+
+/// The relay's `{"error": ...}` message, or the HTTP status
+async fn response_error(response: reqwest::Response) -> String {
+    let status = response.status();
+    let message = response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+    match message {
+        Some(message) => format!("{} ({})", message, status),
+        None => format!("HTTP {}", status),
+    }
+}
+
+/// A shared session `nika watch` is connected to
+#[derive(Debug)]
+pub struct Subscription {
+    url: String,
+    response: reqwest::Response,
+}
+
+impl Subscription {
+    /// Connect to the session at `url` (from `nika run --share`)
+    pub async fn connect(url: &str) -> Result<Self> {
+        let response = reqwest::Client::new()
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| relay_error(url, e))?;
+        if !response.status().is_success() {
+            return Err(relay_error(url, response_error(response).await));
+        }
+        Ok(Self {
+            url: url.to_string(),
+            response,
+        })
+    }
+
+    /// Send the session's events to `tx` until it ends
+    ///
+    /// Returns when the session ends, the relay drops the connection or
+    /// `tx` is closed.
+    pub async fn forward(self, tx: mpsc::Sender<Event>) -> Result<()> {
+        let Self { url, mut response } = self;
+        let url = url.as_str();
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| relay_error(url, e))? {
+            buffer.extend_from_slice(&chunk);
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let event: Event = serde_json::from_str(&line)
+                    .map_err(|e| relay_error(url, format!("unreadable event: {}", e)))?;
+                if tx.send(event).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_id(reply: &Reply) -> CreatedSession {
+        serde_json::from_str(&reply.body).unwrap()
+    }
+
+    #[test]
+    fn only_the_token_holder_writes() {
+        let relay = Relay::default();
+        let created = relay.create();
+        assert_eq!(created.status, 201);
+        let CreatedSession { id, token } = session_id(&created);
+
+        assert_eq!(relay.append(&id, None, b"{}\n").status, 403);
+        assert_eq!(relay.append(&id, Some("guess"), b"{}\n").status, 403);
+        assert_eq!(relay.append("missing", Some(&token), b"{}").status, 404);
+        assert_eq!(
+            relay
+                .append(&id, Some(&token), b"{\"a\":1}\n\n{\"b\":2}\n")
+                .status,
+            200
+        );
+        assert_eq!(relay.sessions.lock()[&id].events.len(), 2);
+
+        assert_eq!(relay.end(&id, Some("guess")).status, 403);
+        assert_eq!(relay.end(&id, Some(&token)).status, 200);
+        assert_eq!(relay.append(&id, Some(&token), b"{}").status, 409);
+    }
+
+    #[test]
+    fn sessions_expire() {
+        let relay = Relay::default();
+        let CreatedSession { id, token } = session_id(&relay.create());
+        relay.end(&id, Some(&token));
+        let later = Instant::now() + ENDED_SESSION_TTL + Duration::from_secs(1);
+        assert!(relay.sessions.lock()[&id].expired(later));
+        let CreatedSession { id: open, .. } = session_id(&relay.create());
+        assert!(!relay.sessions.lock()[&open].expired(later));
+    }
+
+    #[tokio::test]
+    async fn parses_requests() {
+        let raw = b"POST /sessions/abc/events HTTP/1.1\r\nHost: x\r\nAuthorization: Bearer t0k\r\nContent-Length: 3\r\n\r\n{}\n";
+        let mut reader = BufReader::new(&raw[..]);
+        let request = read_request(&mut reader).await.unwrap().ok().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/sessions/abc/events");
+        assert_eq!(request.token.as_deref(), Some("t0k"));
+        assert_eq!(request.body, b"{}\n");
+    }
+}
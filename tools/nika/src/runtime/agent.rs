@@ -0,0 +1,553 @@
+//! Agent loop outcome, `limits:` tracking, tool batches and the `mock`
+//! provider's agent turn
+//!
+//! Kept free of rig-core so that builds without the `providers` feature
+//! still run `agent:` tasks with `provider: mock`.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+use tokio::sync::Notify;
+
+use crate::ast::AgentParams;
+use crate::event::transcript::TranscriptRecorder;
+use crate::event::{AgentTurnMetadata, EventKind, EventLog};
+use crate::provider::{ModelPrice, TokenUsage};
+
+/// Status of the rig-based agent execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RigAgentStatus {
+    /// Agent completed naturally (no more tool calls)
+    NaturalCompletion,
+    /// Stop condition matched in output
+    StopConditionMet,
+    /// Maximum turns reached
+    MaxTurnsReached,
+    /// Token budget exceeded
+    TokenBudgetExceeded,
+    /// `limits.max_cost_usd` exceeded (v0.7)
+    CostBudgetExceeded,
+    /// `limits.max_tool_calls` exceeded (v0.7)
+    ToolBudgetExceeded,
+    /// Agent failed with error
+    Failed,
+}
+
+impl RigAgentStatus {
+    /// Convert to canonical snake_case string for event logging.
+    /// Aligns with Anthropic API's stop_reason values.
+    pub fn as_canonical_str(&self) -> &'static str {
+        match self {
+            Self::NaturalCompletion => "end_turn",
+            Self::StopConditionMet => "stop_sequence",
+            Self::MaxTurnsReached => "max_turns",
+            Self::TokenBudgetExceeded => "max_tokens",
+            Self::CostBudgetExceeded => "max_cost",
+            Self::ToolBudgetExceeded => "max_tool_calls",
+            Self::Failed => "error",
+        }
+    }
+}
+
+/// Result of running the rig-based agent loop
+#[derive(Debug)]
+pub struct RigAgentLoopResult {
+    /// Final status
+    pub status: RigAgentStatus,
+    /// Number of turns executed
+    pub turns: usize,
+    /// Final output from agent
+    pub final_output: Value,
+    /// Total tokens used (if available)
+    pub total_tokens: u64,
+}
+
+/// Limit of an agent's `limits:` that was hit (v0.7)
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetLimit {
+    Tokens,
+    Cost,
+    ToolCalls,
+}
+
+impl BudgetLimit {
+    /// Status of a loop stopped by this limit
+    pub fn status(&self) -> RigAgentStatus {
+        match self {
+            Self::Tokens => RigAgentStatus::TokenBudgetExceeded,
+            Self::Cost => RigAgentStatus::CostBudgetExceeded,
+            Self::ToolCalls => RigAgentStatus::ToolBudgetExceeded,
+        }
+    }
+}
+
+/// Spend of an agent loop against its `limits:` (v0.7)
+///
+/// Fed by the loop's hooks: every model response adds its tokens, every
+/// tool call asks first. Once a limit is hit, tool calls are answered with
+/// [`notice`](Self::on_tool_call) instead of running, which gives the model
+/// one turn to summarize; a model that keeps calling tools after that is
+/// stopped with its last text.
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+pub(crate) struct AgentBudget {
+    max_tokens: Option<u64>,
+    max_cost_usd: Option<f64>,
+    max_tool_calls: Option<u32>,
+    price: Option<ModelPrice>,
+    state: Mutex<BudgetState>,
+}
+
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+#[derive(Default)]
+struct BudgetState {
+    usage: TokenUsage,
+    tool_calls: u32,
+    exceeded: Option<BudgetLimit>,
+    /// A tool call was answered with the notice
+    notified: bool,
+    /// Text of the response the loop was stopped at
+    stopped_at: Option<String>,
+}
+
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+impl AgentBudget {
+    pub fn new(params: &AgentParams, model: &str) -> Self {
+        let limits = params.limits.clone().unwrap_or_default();
+        Self {
+            max_tokens: params.token_limit(),
+            max_cost_usd: limits.max_cost_usd,
+            max_tool_calls: limits.max_tool_calls,
+            price: ModelPrice::for_model(model),
+            state: Mutex::new(BudgetState::default()),
+        }
+    }
+
+    /// Add one model response; true when the loop must stop now
+    ///
+    /// That is the case once the model was told to stop and still calls tools.
+    pub fn on_response(&self, usage: &TokenUsage, tool_calls: usize, text: String) -> bool {
+        let mut state = self.state.lock();
+        state.usage.add(usage);
+        if state.exceeded.is_none() {
+            let tokens = state.usage.total_input_tokens() + state.usage.output_tokens;
+            let cost = self.price.map_or(0.0, |price| price.cost(&state.usage));
+            if self.max_tokens.is_some_and(|max| tokens > max) {
+                state.exceeded = Some(BudgetLimit::Tokens);
+            } else if self.max_cost_usd.is_some_and(|max| cost > max) {
+                state.exceeded = Some(BudgetLimit::Cost);
+            }
+        }
+        let stop = state.notified && tool_calls > 0;
+        if stop {
+            state.stopped_at = Some(text);
+        }
+        stop
+    }
+
+    /// Notice to return instead of running a tool call, once over a limit
+    pub fn on_tool_call(&self) -> Option<String> {
+        let mut state = self.state.lock();
+        if state.exceeded.is_none()
+            && self
+                .max_tool_calls
+                .is_some_and(|max| state.tool_calls >= max)
+        {
+            state.exceeded = Some(BudgetLimit::ToolCalls);
+        }
+        let Some(limit) = state.exceeded else {
+            state.tool_calls += 1;
+            return None;
+        };
+        state.notified = true;
+        Some(format!(
+            "Budget exceeded ({}): this tool call was not run. Do not call any more \
+             tools; summarize what you have found so far and give your final answer.",
+            self.describe(limit, &state)
+        ))
+    }
+
+    /// The limit hit, if any
+    pub fn exceeded(&self) -> Option<BudgetLimit> {
+        self.state.lock().exceeded
+    }
+
+    /// Last text of a loop stopped by [`on_response`](Self::on_response)
+    pub fn stopped_at(&self) -> Option<String> {
+        self.state.lock().stopped_at.clone()
+    }
+
+    fn describe(&self, limit: BudgetLimit, state: &BudgetState) -> String {
+        match limit {
+            BudgetLimit::Tokens => format!(
+                "max_tokens {}, used {}",
+                self.max_tokens.unwrap_or_default(),
+                state.usage.total_input_tokens() + state.usage.output_tokens
+            ),
+            BudgetLimit::Cost => format!(
+                "max_cost_usd {}, spent {:.4}",
+                self.max_cost_usd.unwrap_or_default(),
+                self.price.map_or(0.0, |price| price.cost(&state.usage))
+            ),
+            BudgetLimit::ToolCalls => {
+                format!("max_tool_calls {}", self.max_tool_calls.unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Tool calls of one agent turn, run concurrently (v0.7)
+///
+/// rig runs up to `tool_concurrency` calls of a turn at once and collects
+/// results as they complete. Each result is held here until the calls made
+/// before it have finished, so the model gets them back in the order of its
+/// message. Calls are numbered as rig starts them, which is message order.
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+pub(crate) struct ToolBatch {
+    state: Mutex<BatchState>,
+    released: Notify,
+}
+
+#[derive(Default)]
+struct BatchState {
+    /// Tool calls of the turn
+    calls: usize,
+    /// Number and start of each call, by rig's internal call id
+    started: FxHashMap<String, (usize, Instant)>,
+    /// Whether each call has finished, by number
+    finished: Vec<bool>,
+    first_start: Option<Instant>,
+    /// Sum of the calls' own durations
+    busy: Duration,
+}
+
+/// Timing of a finished batch of two or more tool calls (v0.7)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ToolBatchReport {
+    pub calls: usize,
+    /// From the first call's start to the last call's end
+    pub wall_ms: u64,
+    /// What running the calls one after the other would have taken
+    pub sequential_ms: u64,
+}
+
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+impl ToolBatch {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(BatchState::default()),
+            released: Notify::new(),
+        }
+    }
+
+    /// Start a turn with `calls` tool calls
+    pub fn begin_turn(&self, calls: usize) {
+        *self.state.lock() = BatchState {
+            calls,
+            finished: vec![false; calls],
+            ..BatchState::default()
+        };
+    }
+
+    /// A call starts running
+    pub fn start(&self, call_id: &str) {
+        let mut state = self.state.lock();
+        let number = state.started.len();
+        let now = Instant::now();
+        state.started.insert(call_id.to_string(), (number, now));
+        state.first_start.get_or_insert(now);
+        if state.finished.len() <= number {
+            state.finished.resize(number + 1, false);
+        }
+    }
+
+    /// A started call was not run (its result goes back right away)
+    pub fn skip(&self, call_id: &str) -> Option<ToolBatchReport> {
+        let report = self
+            .mark_finished(call_id, false)
+            .and_then(|(_, report)| report);
+        self.released.notify_waiters();
+        report
+    }
+
+    /// A call finished: wait until the calls before it have too
+    ///
+    /// Returns the batch timing to the call that completes the batch.
+    pub async fn finish(&self, call_id: &str) -> Option<ToolBatchReport> {
+        let (number, report) = self.mark_finished(call_id, true)?;
+        self.released.notify_waiters();
+        loop {
+            let released = self.released.notified();
+            if self.state.lock().finished[..number]
+                .iter()
+                .all(|done| *done)
+            {
+                return report;
+            }
+            released.await;
+        }
+    }
+
+    fn mark_finished(&self, call_id: &str, ran: bool) -> Option<(usize, Option<ToolBatchReport>)> {
+        let mut state = self.state.lock();
+        let (number, start) = *state.started.get(call_id)?;
+        if ran {
+            state.busy += start.elapsed();
+        }
+        state.finished[number] = true;
+        let complete = state.finished.iter().all(|done| *done);
+        let report = (complete && state.calls > 1).then(|| ToolBatchReport {
+            calls: state.finished.len(),
+            wall_ms: state
+                .first_start
+                .map_or(0, |first| first.elapsed().as_millis() as u64),
+            sequential_ms: state.busy.as_millis() as u64,
+        });
+        Some((number, report))
+    }
+}
+
+/// Status of a finished loop: stop condition, else limit hit, else natural end
+pub(crate) fn final_status(
+    params: &AgentParams,
+    budget: &AgentBudget,
+    output: &str,
+) -> RigAgentStatus {
+    if stop_condition_met(params, output) {
+        RigAgentStatus::StopConditionMet
+    } else if let Some(limit) = budget.exceeded() {
+        limit.status()
+    } else {
+        RigAgentStatus::NaturalCompletion
+    }
+}
+
+/// Whether `output` contains one of the agent's `stop_conditions`
+pub(crate) fn stop_condition_met(params: &AgentParams, output: &str) -> bool {
+    params
+        .stop_conditions
+        .iter()
+        .any(|cond| output.contains(cond))
+}
+
+/// One simulated turn of the `mock` provider, with its AgentTurn events
+pub(crate) fn run_mock(
+    task_id: &str,
+    params: &AgentParams,
+    event_log: &EventLog,
+) -> RigAgentLoopResult {
+    // Emit start event (no metadata for "started")
+    event_log.emit(EventKind::AgentTurn {
+        task_id: Arc::from(task_id),
+        turn_index: 1,
+        kind: "started".to_string(),
+        metadata: None,
+    });
+
+    // For mock execution, we simulate a single turn with natural completion
+    let response_text = "Mock response from rig agent".to_string();
+    let final_output = serde_json::json!({
+        "response": &response_text,
+        "completed": true
+    });
+
+    // Check stop conditions, then limits (the mock turn costs 100 tokens)
+    let budget = AgentBudget::new(params, "mock");
+    let usage = TokenUsage {
+        input_tokens: 50,
+        output_tokens: 50,
+        ..TokenUsage::default()
+    };
+    budget.on_response(&usage, 0, response_text.clone());
+    let status = final_status(params, &budget, &final_output.to_string());
+
+    // Build metadata for completion event (v0.4.1)
+    let stop_reason = status.as_canonical_str();
+    let metadata = AgentTurnMetadata {
+        thinking: None, // Mock mode doesn't have thinking
+        response_text: response_text.clone(),
+        input_tokens: 50,
+        output_tokens: 50,
+        cache_read_tokens: 0,
+        cache_write_tokens: 0,
+        stop_reason: stop_reason.to_string(),
+    };
+
+    // Emit completion event with metadata
+    event_log.emit(EventKind::AgentTurn {
+        task_id: Arc::from(task_id),
+        turn_index: 1,
+        kind: stop_reason.to_string(),
+        metadata: Some(metadata),
+    });
+
+    // EMIT: AgentTranscript (v0.7)
+    let transcript = TranscriptRecorder::new(params.system.clone(), params.prompt.as_str());
+    transcript.turn(response_text, None, Vec::new());
+    event_log.emit(EventKind::AgentTranscript {
+        task_id: Arc::from(task_id),
+        transcript: transcript.transcript(),
+    });
+
+    RigAgentLoopResult {
+        status,
+        turns: 1,
+        final_output,
+        total_tokens: 100, // Mock token count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rig_agent_status_variants() {
+        let status = RigAgentStatus::NaturalCompletion;
+        assert_eq!(status, RigAgentStatus::NaturalCompletion);
+
+        let status = RigAgentStatus::MaxTurnsReached;
+        assert_eq!(status, RigAgentStatus::MaxTurnsReached);
+    }
+
+    #[test]
+    fn test_rig_agent_loop_result_debug() {
+        let result = RigAgentLoopResult {
+            status: RigAgentStatus::NaturalCompletion,
+            turns: 1,
+            final_output: serde_json::json!({}),
+            total_tokens: 50,
+        };
+        let debug = format!("{:?}", result);
+        assert!(debug.contains("NaturalCompletion"));
+    }
+
+    #[test]
+    fn test_run_mock_checks_stop_conditions() {
+        let params = AgentParams {
+            prompt: "Test".to_string(),
+            stop_conditions: vec!["completed".to_string()],
+            ..Default::default()
+        };
+        let event_log = EventLog::new();
+        let result = run_mock("agent", &params, &event_log);
+        assert_eq!(result.status, RigAgentStatus::StopConditionMet);
+        let events = event_log.events();
+        assert_eq!(events.len(), 3);
+        let EventKind::AgentTranscript { transcript, .. } = &events[2].kind else {
+            panic!("expected AgentTranscript, got {:?}", events[2].kind);
+        };
+        assert_eq!(transcript.prompt, "Test");
+        assert_eq!(transcript.turns[0].text, "Mock response from rig agent");
+    }
+
+    fn limited(yaml: &str) -> AgentParams {
+        serde_yaml::from_str(&format!("prompt: \"Test\"\nlimits: {}\n", yaml)).unwrap()
+    }
+
+    fn usage(input_tokens: u64, output_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            input_tokens,
+            output_tokens,
+            ..TokenUsage::default()
+        }
+    }
+
+    #[test]
+    fn test_budget_notifies_then_stops_on_tokens() {
+        let params = limited("{ max_tokens: 1000 }");
+        let budget = AgentBudget::new(&params, "gpt-4o");
+
+        assert!(!budget.on_response(&usage(400, 100), 1, String::new()));
+        assert_eq!(budget.on_tool_call(), None);
+        // Over the limit: the tool calls of this response get the notice
+        assert!(!budget.on_response(&usage(600, 100), 2, String::new()));
+        let notice = budget.on_tool_call().unwrap();
+        assert!(notice.contains("max_tokens 1000, used 1200"), "{}", notice);
+        assert_eq!(budget.exceeded(), Some(BudgetLimit::Tokens));
+        // The model calls tools again instead of answering: stop with its text
+        assert!(budget.on_response(&usage(10, 10), 1, "partial".to_string()));
+        assert_eq!(budget.stopped_at().as_deref(), Some("partial"));
+    }
+
+    #[test]
+    fn test_budget_summary_turn_ends_naturally() {
+        let params = limited("{ max_tool_calls: 2 }");
+        let budget = AgentBudget::new(&params, "gpt-4o");
+
+        assert_eq!(budget.on_tool_call(), None);
+        assert_eq!(budget.on_tool_call(), None);
+        assert!(budget.on_tool_call().unwrap().contains("max_tool_calls 2"));
+        // The summary answers without tool calls
+        assert!(!budget.on_response(&usage(10, 10), 0, "summary".to_string()));
+        assert_eq!(budget.stopped_at(), None);
+        assert_eq!(
+            final_status(&params, &budget, "summary"),
+            RigAgentStatus::ToolBudgetExceeded
+        );
+    }
+
+    #[test]
+    fn test_budget_cost_uses_model_price() {
+        // claude-sonnet-4: $3 in / $15 out per million tokens
+        let params = limited("{ max_cost_usd: 0.01 }");
+        let budget = AgentBudget::new(&params, "claude-sonnet-4-20250514");
+        budget.on_response(&usage(1000, 100), 1, String::new());
+        assert_eq!(budget.exceeded(), None);
+        budget.on_response(&usage(2000, 200), 1, String::new());
+        assert_eq!(budget.exceeded(), Some(BudgetLimit::Cost));
+
+        // Unpriced models never hit a cost limit
+        let budget = AgentBudget::new(&params, "llama3.2");
+        budget.on_response(&usage(1_000_000, 1_000_000), 1, String::new());
+        assert_eq!(budget.exceeded(), None);
+    }
+
+    #[tokio::test]
+    async fn test_tool_batch_keeps_call_order() {
+        let batch = Arc::new(ToolBatch::new());
+        batch.begin_turn(2);
+        batch.start("first");
+        batch.start("second");
+
+        // The second call ends first but waits for the first one
+        let second = tokio::spawn({
+            let batch = Arc::clone(&batch);
+            async move { batch.finish("second").await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!second.is_finished());
+
+        let report = batch.finish("first").await.unwrap();
+        assert_eq!(report.calls, 2);
+        assert!(report.sequential_ms >= 20, "{:?}", report);
+        assert_eq!(second.await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_tool_batch_skipped_calls_release() {
+        let batch = ToolBatch::new();
+        batch.begin_turn(2);
+        batch.start("first");
+        batch.start("second");
+
+        assert_eq!(batch.skip("first"), None);
+        assert!(batch.finish("second").await.is_some());
+
+        // A single call is not a batch
+        batch.begin_turn(1);
+        batch.start("only");
+        assert_eq!(batch.finish("only").await, None);
+    }
+
+    #[test]
+    fn test_run_mock_reports_token_limit() {
+        let params = limited("{ max_tokens: 80 }");
+        let result = run_mock("agent", &params, &EventLog::new());
+        assert_eq!(result.status, RigAgentStatus::TokenBudgetExceeded);
+        assert_eq!(result.status.as_canonical_str(), "max_tokens");
+    }
+}
@@ -0,0 +1,225 @@
+//! Agent context - isolated and shared conversations of `agent:` tasks (v0.7)
+//!
+//! An agent runs through one of two runners, picked by its `context:`:
+//! - [`IsolatedAgentRunner`] (default): the loop starts with no history.
+//!   The runner holds nothing, so it has no way to reach the run's
+//!   [`SharedContext`].
+//! - [`SharedAgentRunner`]: the loop starts with the shared conversation so
+//!   far and, when it succeeds, appends its prompt and final answer.
+//!   Shared agents hold the conversation while they run, so they take
+//!   turns in the order they start and never see a half-written history.
+//!
+//! Only prompts and final answers are shared, not tool calls: a later
+//! agent may not have the tools an earlier one called. Outputs cross
+//! between isolated and shared agents only through `use:` bindings.
+
+use std::future::Future;
+
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::error::NikaError;
+use crate::runtime::RigAgentLoopResult;
+
+/// A shared agent that finished: what it was asked and what it answered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextTurn {
+    pub task_id: String,
+    pub prompt: String,
+    pub response: String,
+}
+
+/// The run's shared conversation, one per executor
+#[derive(Debug, Default)]
+pub struct SharedContext {
+    turns: Mutex<Vec<ContextTurn>>,
+}
+
+impl SharedContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turns so far (waits for a running shared agent)
+    pub async fn turns(&self) -> Vec<ContextTurn> {
+        self.turns.lock().await.clone()
+    }
+}
+
+/// Runs an agent on a fresh conversation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IsolatedAgentRunner;
+
+impl IsolatedAgentRunner {
+    /// `run` gets the history to start from, always empty
+    pub async fn run<F, Fut>(self, run: F) -> Result<RigAgentLoopResult, NikaError>
+    where
+        F: FnOnce(Vec<ContextTurn>) -> Fut,
+        Fut: Future<Output = Result<RigAgentLoopResult, NikaError>>,
+    {
+        run(Vec::new()).await
+    }
+}
+
+/// Runs an agent on the run's shared conversation
+#[derive(Debug, Clone, Copy)]
+pub struct SharedAgentRunner<'a> {
+    context: &'a SharedContext,
+}
+
+impl<'a> SharedAgentRunner<'a> {
+    pub fn new(context: &'a SharedContext) -> Self {
+        Self { context }
+    }
+
+    /// `run` gets the conversation so far; a failed run adds nothing
+    pub async fn run<F, Fut>(
+        self,
+        task_id: &str,
+        prompt: &str,
+        run: F,
+    ) -> Result<RigAgentLoopResult, NikaError>
+    where
+        F: FnOnce(Vec<ContextTurn>) -> Fut,
+        Fut: Future<Output = Result<RigAgentLoopResult, NikaError>>,
+    {
+        let mut turns = self.context.turns.lock().await;
+        let result = run(turns.clone()).await;
+        if let Ok(result) = &result {
+            turns.push(ContextTurn {
+                task_id: task_id.to_string(),
+                prompt: prompt.to_string(),
+                response: response_text(&result.final_output),
+            });
+        }
+        result
+    }
+}
+
+/// Final answer of an agent loop (`{ "response": ... }`)
+fn response_text(output: &Value) -> String {
+    match output.get("response") {
+        Some(Value::String(response)) => response.clone(),
+        _ => output.to_string(),
+    }
+}
+
+/// History for rig: a user and an assistant message per turn
+#[cfg(feature = "providers")]
+pub fn messages(turns: &[ContextTurn]) -> Vec<rig::completion::Message> {
+    use rig::completion::Message;
+
+    turns
+        .iter()
+        .flat_map(|turn| {
+            [
+                Message::user(turn.prompt.as_str()),
+                Message::assistant(turn.response.as_str()),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::RigAgentStatus;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn answer(response: &str) -> Result<RigAgentLoopResult, NikaError> {
+        Ok(RigAgentLoopResult {
+            status: RigAgentStatus::NaturalCompletion,
+            turns: 1,
+            final_output: json!({ "response": response }),
+            total_tokens: 0,
+        })
+    }
+
+    fn prompts(turns: &[ContextTurn]) -> Vec<&str> {
+        turns.iter().map(|t| t.prompt.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn shared_agents_continue_one_conversation() {
+        let context = SharedContext::new();
+        SharedAgentRunner::new(&context)
+            .run("outline", "Outline the post", |history| async move {
+                assert!(history.is_empty());
+                answer("1. Intro 2. Body")
+            })
+            .await
+            .unwrap();
+
+        // A failed turn is not part of the conversation
+        SharedAgentRunner::new(&context)
+            .run("broken", "Fail", |_| async {
+                Err(NikaError::AgentExecutionError {
+                    task_id: "broken".to_string(),
+                    reason: "provider down".to_string(),
+                })
+            })
+            .await
+            .unwrap_err();
+
+        SharedAgentRunner::new(&context)
+            .run("draft", "Draft it", |history| async move {
+                assert_eq!(prompts(&history), ["Outline the post"]);
+                assert_eq!(history[0].response, "1. Intro 2. Body");
+                answer("Draft")
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            prompts(&context.turns().await),
+            ["Outline the post", "Draft it"]
+        );
+    }
+
+    #[tokio::test]
+    async fn isolated_agents_see_nothing_and_add_nothing() {
+        let context = SharedContext::new();
+        SharedAgentRunner::new(&context)
+            .run("outline", "Outline the post", |_| async {
+                answer("Outline")
+            })
+            .await
+            .unwrap();
+
+        IsolatedAgentRunner
+            .run(|history| async move {
+                assert!(history.is_empty());
+                answer("Review")
+            })
+            .await
+            .unwrap();
+        assert_eq!(prompts(&context.turns().await), ["Outline the post"]);
+    }
+
+    #[tokio::test]
+    async fn concurrent_shared_agents_take_turns() {
+        let context = Arc::new(SharedContext::new());
+        let first = {
+            let context = Arc::clone(&context);
+            tokio::spawn(async move {
+                SharedAgentRunner::new(&context)
+                    .run("slow", "Slow", |_| async {
+                        tokio::time::sleep(Duration::from_millis(30)).await;
+                        answer("done")
+                    })
+                    .await
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        SharedAgentRunner::new(&context)
+            .run("next", "Next", |history| async move {
+                // Started while `slow` ran, but sees its whole turn
+                assert_eq!(prompts(&history), ["Slow"]);
+                answer("ok")
+            })
+            .await
+            .unwrap();
+        first.await.unwrap().unwrap();
+    }
+}
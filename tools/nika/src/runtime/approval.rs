@@ -0,0 +1,398 @@
+//! Approval gates - human decisions for `approve:` tasks (v0.7)
+//!
+//! The executor asks an [`ApprovalGate`] for a decision. Where the question
+//! goes depends on the front-end:
+//!
+//! | Gate | Used by | Behavior |
+//! |------|---------|----------|
+//! | [`ApprovalGate::prompt`] | `nika run` | Asks on stderr/stdin when stdin is a terminal |
+//! | [`ApprovalGate::channel`] | TUI | Sends a [`PendingApproval`] for the app to answer |
+//! | [`ApprovalGate::non_interactive`] | matrix runs | Never asks; the task's `default:` applies |
+//!
+//! When no one can be asked (or the timeout expires) the task's `default:`
+//! decision is used; without one the task fails with `NIKA-170`.
+//!
+//! ## Checkpoints
+//!
+//! Decisions are saved to `.nika/approvals/<workflow>.json` as they are made.
+//! A run restarted after a crash or Ctrl-C re-executes upstream tasks but
+//! passes already-decided gates without asking again. Entries are keyed by
+//! task id and resolved prompt, so a changed question is asked again. The
+//! file is removed once the workflow completes. It carries a version stamp,
+//! so checkpoints of older versions are migrated on load and those of newer
+//! versions fail with `NIKA-098` (see [`crate::util::compat`]).
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::ast::{ApprovalDecision, Workflow};
+use crate::error::Result;
+use crate::util::compat::{self, FileKind, VersionStamp};
+
+/// Directory for approval checkpoints
+const CHECKPOINT_DIR: &str = ".nika/approvals";
+
+/// A question waiting for a decision
+#[derive(Debug, Clone)]
+pub struct ApprovalRequest {
+    pub task_id: Arc<str>,
+    /// Resolved prompt (templates substituted)
+    pub prompt: String,
+}
+
+/// An approval handed to the TUI, answered with [`PendingApproval::answer`]
+#[derive(Debug)]
+pub struct PendingApproval {
+    pub request: ApprovalRequest,
+    reply: oneshot::Sender<ApprovalDecision>,
+}
+
+impl PendingApproval {
+    /// Send the decision back to the waiting task
+    pub fn answer(self, decision: ApprovalDecision) {
+        // The task may have timed out in the meantime
+        let _ = self.reply.send(decision);
+    }
+}
+
+#[derive(Debug)]
+enum GateMode {
+    Prompt,
+    Channel(mpsc::Sender<PendingApproval>),
+    NonInteractive,
+}
+
+/// Where approval questions go (see module docs)
+#[derive(Debug)]
+pub struct ApprovalGate {
+    mode: GateMode,
+    checkpoint: Option<ApprovalCheckpoint>,
+}
+
+impl ApprovalGate {
+    /// Ask on the terminal (stderr prompt, stdin answer)
+    pub fn prompt() -> Self {
+        Self {
+            mode: GateMode::Prompt,
+            checkpoint: None,
+        }
+    }
+
+    /// Forward questions to a front-end (TUI)
+    pub fn channel(tx: mpsc::Sender<PendingApproval>) -> Self {
+        Self {
+            mode: GateMode::Channel(tx),
+            checkpoint: None,
+        }
+    }
+
+    /// Never ask; only `default:` decisions (and checkpoints) apply
+    pub fn non_interactive() -> Self {
+        Self {
+            mode: GateMode::NonInteractive,
+            checkpoint: None,
+        }
+    }
+
+    /// Persist decisions so a restarted run can resume past decided gates
+    pub fn with_checkpoint(mut self, checkpoint: ApprovalCheckpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    pub fn checkpoint(&self) -> Option<&ApprovalCheckpoint> {
+        self.checkpoint.as_ref()
+    }
+
+    /// Ask for a decision
+    ///
+    /// Returns the decision and who made it (`cli` or `tui`), or `None`
+    /// when no one can answer.
+    pub async fn ask(&self, request: ApprovalRequest) -> Option<(ApprovalDecision, &'static str)> {
+        match &self.mode {
+            GateMode::Prompt => {
+                if !std::io::stdin().is_terminal() {
+                    return None;
+                }
+                tokio::task::spawn_blocking(move || prompt_stdin(&request))
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|decision| (decision, "cli"))
+            }
+            GateMode::Channel(tx) => {
+                let (reply, rx) = oneshot::channel();
+                tx.send(PendingApproval { request, reply }).await.ok()?;
+                rx.await.ok().map(|decision| (decision, "tui"))
+            }
+            GateMode::NonInteractive => None,
+        }
+    }
+}
+
+/// Blocking terminal prompt (`None` on EOF)
+fn prompt_stdin(request: &ApprovalRequest) -> Option<ApprovalDecision> {
+    let stdin = std::io::stdin();
+    let mut stderr = std::io::stderr();
+    let _ = writeln!(
+        stderr,
+        "\n✋ Approval required [{}]\n{}",
+        request.task_id, request.prompt
+    );
+    loop {
+        let _ = write!(stderr, "Approve? [y/n]: ");
+        let _ = stderr.flush();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        if let Some(decision) = parse_answer(&line) {
+            return Some(decision);
+        }
+    }
+}
+
+/// Parse a typed answer (y/yes/approve, n/no/reject)
+pub fn parse_answer(input: &str) -> Option<ApprovalDecision> {
+    match input.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" | "approve" | "approved" => Some(ApprovalDecision::Approve),
+        "n" | "no" | "reject" | "rejected" => Some(ApprovalDecision::Reject),
+        _ => None,
+    }
+}
+
+/// One recorded decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CheckpointEntry {
+    task_id: String,
+    decision: ApprovalDecision,
+    by: String,
+}
+
+/// Checkpoint file: `{"nika": <stamp>, "entries": {...}}`
+#[derive(Serialize)]
+struct CheckpointFileRef<'a> {
+    nika: VersionStamp,
+    entries: &'a BTreeMap<String, CheckpointEntry>,
+}
+
+#[derive(Deserialize)]
+struct CheckpointFile {
+    entries: BTreeMap<String, CheckpointEntry>,
+}
+
+/// Approval decisions backed by a JSON file (see module docs)
+#[derive(Debug)]
+pub struct ApprovalCheckpoint {
+    path: PathBuf,
+    /// Keyed by `task_id:prompt-hash`
+    entries: Mutex<BTreeMap<String, CheckpointEntry>>,
+}
+
+impl ApprovalCheckpoint {
+    /// Load a checkpoint (a missing file starts empty)
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let entries = if path.exists() {
+            let mut value: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            // Schema 1 files are the bare entry map, without a stamp
+            let stamp = value
+                .get("nika")
+                .and_then(|stamp| serde_json::from_value(stamp.clone()).ok())
+                .unwrap_or_else(VersionStamp::legacy);
+            let migrations = compat::check(FileKind::ApprovalCheckpoint, &path, &stamp)?;
+            compat::migrate(&path, &migrations, &mut value)?;
+            serde_json::from_value::<CheckpointFile>(value)?.entries
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Load `.nika/approvals/<name>.json` (name falls back to the workflow hash)
+    pub fn for_workflow(workflow: &Workflow) -> Result<Self> {
+        let name = workflow
+            .name
+            .clone()
+            .unwrap_or_else(|| workflow.compute_hash());
+        Self::load(Path::new(CHECKPOINT_DIR).join(format!("{}.json", name)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of recorded decisions
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn key(task_id: &str, prompt: &str) -> String {
+        format!("{}:{:016x}", task_id, xxh3_64(prompt.as_bytes()))
+    }
+
+    /// Recorded decision for this task and prompt
+    pub fn get(&self, task_id: &str, prompt: &str) -> Option<ApprovalDecision> {
+        self.entries
+            .lock()
+            .get(&Self::key(task_id, prompt))
+            .map(|e| e.decision)
+    }
+
+    /// Record a decision and write the file
+    pub fn record(
+        &self,
+        task_id: &str,
+        prompt: &str,
+        decision: ApprovalDecision,
+        by: &str,
+    ) -> Result<()> {
+        let json = {
+            let mut entries = self.entries.lock();
+            entries.insert(
+                Self::key(task_id, prompt),
+                CheckpointEntry {
+                    task_id: task_id.to_string(),
+                    decision,
+                    by: by.to_string(),
+                },
+            );
+            serde_json::to_string_pretty(&CheckpointFileRef {
+                nika: VersionStamp::current(FileKind::ApprovalCheckpoint),
+                entries: &entries,
+            })?
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, json)?;
+        Ok(())
+    }
+
+    /// Forget all decisions and remove the file (run completed)
+    pub fn clear(&self) -> Result<()> {
+        let mut entries = self.entries.lock();
+        if entries.is_empty() && !self.path.exists() {
+            return Ok(());
+        }
+        entries.clear();
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answer() {
+        assert_eq!(parse_answer("Y\n"), Some(ApprovalDecision::Approve));
+        assert_eq!(parse_answer(" approve "), Some(ApprovalDecision::Approve));
+        assert_eq!(parse_answer("no"), Some(ApprovalDecision::Reject));
+        assert_eq!(parse_answer("maybe"), None);
+    }
+
+    #[tokio::test]
+    async fn test_channel_gate_round_trip() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let gate = ApprovalGate::channel(tx);
+
+        let answer = tokio::spawn(async move {
+            let pending = rx.recv().await.unwrap();
+            assert_eq!(&*pending.request.task_id, "review");
+            pending.answer(ApprovalDecision::Reject);
+        });
+
+        let decision = gate
+            .ask(ApprovalRequest {
+                task_id: Arc::from("review"),
+                prompt: "Ship it?".to_string(),
+            })
+            .await;
+        answer.await.unwrap();
+        assert_eq!(decision, Some((ApprovalDecision::Reject, "tui")));
+    }
+
+    #[tokio::test]
+    async fn test_non_interactive_gate_never_answers() {
+        let gate = ApprovalGate::non_interactive();
+        let request = ApprovalRequest {
+            task_id: Arc::from("review"),
+            prompt: "Ship it?".to_string(),
+        };
+        assert!(gate.ask(request).await.is_none());
+    }
+
+    #[test]
+    fn test_checkpoint_survives_reload_and_clears() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("approvals").join("wf.json");
+
+        let checkpoint = ApprovalCheckpoint::load(&path).unwrap();
+        assert!(checkpoint.is_empty());
+        checkpoint
+            .record("review", "Ship it?", ApprovalDecision::Approve, "cli")
+            .unwrap();
+
+        // Restarted process
+        let reloaded = ApprovalCheckpoint::load(&path).unwrap();
+        assert_eq!(
+            reloaded.get("review", "Ship it?"),
+            Some(ApprovalDecision::Approve)
+        );
+        // A different question is not covered by the old decision
+        assert_eq!(reloaded.get("review", "Ship it now?"), None);
+
+        reloaded.clear().unwrap();
+        assert!(!path.exists());
+        assert!(reloaded.is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wf.json");
+
+        // Written before checkpoints were stamped
+        let key = ApprovalCheckpoint::key("review", "Ship it?");
+        let legacy = serde_json::json!({
+            key: {"task_id": "review", "decision": "approve", "by": "cli"}
+        });
+        std::fs::write(&path, legacy.to_string()).unwrap();
+        let checkpoint = ApprovalCheckpoint::load(&path).unwrap();
+        assert_eq!(
+            checkpoint.get("review", "Ship it?"),
+            Some(ApprovalDecision::Approve)
+        );
+        checkpoint
+            .record("deploy", "Deploy?", ApprovalDecision::Reject, "cli")
+            .unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["nika"]["schema"], 2);
+        assert_eq!(written["entries"].as_object().unwrap().len(), 2);
+
+        let newer = r#"{"nika": {"schema": 7, "nika_version": "2.0.0"}, "entries": {}}"#;
+        std::fs::write(&path, newer).unwrap();
+        let err = ApprovalCheckpoint::load(&path).unwrap_err();
+        assert_eq!(err.code(), "NIKA-098");
+    }
+}
@@ -0,0 +1,265 @@
+//! Text splitting behind `chunk:` tasks (v0.7)
+//!
+//! `by: chars` reuses the `retrieve:` chunker (paragraph, line, then word
+//! breaks). `by: tokens` does the same with sizes in estimated tokens.
+//! `by: sentences` packs whole sentences, and `by: headings` starts a new
+//! chunk at every Markdown heading, splitting long sections by sentence and
+//! repeating the heading on each of their chunks. Packed sentences keep the
+//! whitespace between them. Overlap repeats whole trailing sentences of the
+//! previous chunk.
+
+use crate::ast::{ChunkBy, ChunkParams};
+use crate::store::retrieve::chunk_text;
+
+/// Rough characters per token, as used for token estimates elsewhere
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Split `text` into chunks as configured by `params`
+pub fn split(text: &str, params: &ChunkParams) -> Vec<String> {
+    let (size, overlap) = (params.size(), params.overlap);
+    match params.by {
+        ChunkBy::Chars => chunk_text(text, size, overlap),
+        ChunkBy::Tokens => chunk_text(text, size * CHARS_PER_TOKEN, overlap * CHARS_PER_TOKEN),
+        ChunkBy::Sentences => pack(&sentence_units(text, size), size, overlap),
+        ChunkBy::Headings => sections(text)
+            .iter()
+            .flat_map(|section| {
+                if estimate_tokens(section) <= size {
+                    vec![section.trim().to_string()]
+                } else {
+                    split_section(section, size, overlap)
+                }
+            })
+            .filter(|chunk| !chunk.is_empty())
+            .collect(),
+    }
+}
+
+/// Estimated token count (~4 characters per token)
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// A sentence (or piece of one) to pack, with the whitespace before it
+struct Unit {
+    sep: String,
+    text: String,
+}
+
+impl Unit {
+    fn tokens(&self) -> usize {
+        (self.sep.chars().count() + self.text.chars().count()).div_ceil(CHARS_PER_TOKEN)
+    }
+}
+
+/// Sentences of `text`, with any sentence over `size` tokens split further
+fn sentence_units(text: &str, size: usize) -> Vec<Unit> {
+    let mut units = Vec::new();
+    let mut prev_end = 0;
+    for (start, end) in sentences(text) {
+        let sep = &text[prev_end..start];
+        let sentence = &text[start..end];
+        prev_end = end;
+        if estimate_tokens(sentence) <= size {
+            units.push(Unit {
+                sep: sep.to_string(),
+                text: sentence.to_string(),
+            });
+            continue;
+        }
+        for (i, piece) in chunk_text(sentence, size * CHARS_PER_TOKEN, 0)
+            .into_iter()
+            .enumerate()
+        {
+            units.push(Unit {
+                sep: if i == 0 {
+                    sep.to_string()
+                } else {
+                    " ".to_string()
+                },
+                text: piece,
+            });
+        }
+    }
+    units
+}
+
+/// Byte ranges of the sentences of `text`, trimmed; sentences end at `.`,
+/// `!` or `?` before whitespace, or at a blank line
+fn sentences(text: &str) -> Vec<(usize, usize)> {
+    let mut out = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let raw = &text[start..end];
+        let start = start + (raw.len() - raw.trim_start().len());
+        let end = start + raw.trim().len();
+        if start < end {
+            out.push((start, end));
+        }
+    };
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let Some(&(next_i, next)) = chars.peek() else {
+            break;
+        };
+        let end = match c {
+            '.' | '!' | '?' if next.is_whitespace() => Some(next_i),
+            '\n' if next == '\n' => Some(i),
+            _ => None,
+        };
+        if let Some(end) = end {
+            push(start, end);
+            start = end;
+        }
+    }
+    push(start, text.len());
+    out
+}
+
+/// Sections of a Markdown document, each starting at a heading line
+///
+/// `#` lines inside fenced code blocks are not headings.
+fn sections(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut fenced = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fenced = !fenced;
+        } else if !fenced && is_heading(trimmed) && offset > start {
+            out.push(&text[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    out.push(&text[start..]);
+    out
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with([' ', '\t', '\n'])
+}
+
+/// A section over `size` tokens packed by sentence, with its heading
+/// repeated at the top of every chunk
+fn split_section(section: &str, size: usize, overlap: usize) -> Vec<String> {
+    let (heading, body) = match section.split_once('\n') {
+        Some((first, body)) if is_heading(first.trim_start()) => (first.trim(), body),
+        _ => return pack(&sentence_units(section, size), size, overlap),
+    };
+    // Room left for the body once the heading line is in
+    let budget = size.saturating_sub(estimate_tokens(heading) + 1).max(1);
+    pack(&sentence_units(body, budget), budget, overlap.min(budget))
+        .into_iter()
+        .map(|chunk| format!("{}\n{}", heading, chunk))
+        .collect()
+}
+
+/// Greedily pack `units` into chunks of at most `size` tokens, starting
+/// each chunk with the trailing units of the previous one that fit in
+/// `overlap` tokens; units are joined by the whitespace they had
+fn pack(units: &[Unit], size: usize, overlap: usize) -> Vec<String> {
+    let tokens: Vec<usize> = units.iter().map(Unit::tokens).collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < units.len() {
+        let mut end = start + 1;
+        let mut used = tokens[start];
+        while end < units.len() && used + tokens[end] <= size {
+            used += tokens[end];
+            end += 1;
+        }
+        let mut chunk = units[start].text.clone();
+        for unit in &units[start + 1..end] {
+            chunk.push_str(&unit.sep);
+            chunk.push_str(&unit.text);
+        }
+        chunks.push(chunk);
+        if end == units.len() {
+            break;
+        }
+
+        let mut next = end;
+        let mut carried = 0;
+        while next > start + 1 && carried + tokens[next - 1] <= overlap {
+            carried += tokens[next - 1];
+            next -= 1;
+        }
+        start = next;
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(by: ChunkBy, size: usize, overlap: usize) -> ChunkParams {
+        ChunkParams {
+            source: "$doc".to_string(),
+            by,
+            size: Some(size),
+            overlap,
+        }
+    }
+
+    #[test]
+    fn test_sentences_pack_with_overlap() {
+        let text = "One two three. Four five six! Seven eight nine? Ten eleven twelve.";
+        let chunks = split(text, &params(ChunkBy::Sentences, 10, 5));
+        assert_eq!(
+            chunks,
+            vec![
+                "One two three. Four five six!",
+                "Four five six! Seven eight nine?",
+                "Seven eight nine? Ten eleven twelve.",
+            ]
+        );
+
+        let chunks = split(text, &params(ChunkBy::Sentences, 10, 0));
+        assert_eq!(chunks.len(), 2);
+    }
+
+    #[test]
+    fn test_headings_start_new_chunks_outside_code() {
+        let text =
+            "# Intro\nShort.\n\n## Setup\n```sh\n# not a heading\n```\n\n## Usage\nRun it.\n";
+        let chunks = split(text, &params(ChunkBy::Headings, 100, 0));
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].starts_with("## Setup") && chunks[1].contains("# not a heading"));
+        assert_eq!(chunks[2], "## Usage\nRun it.");
+    }
+
+    #[test]
+    fn test_long_sentences_and_tokens_fit_size() {
+        let text = "word ".repeat(200);
+        for by in [ChunkBy::Tokens, ChunkBy::Sentences, ChunkBy::Headings] {
+            let chunks = split(&text, &params(by, 50, 5));
+            assert!(chunks.len() > 1);
+            assert!(chunks.iter().all(|c| estimate_tokens(c) <= 50));
+        }
+        assert!(split("", &params(ChunkBy::Headings, 50, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_split_sections_keep_breaks_and_heading() {
+        let text = "# Guide\nOne two three. Four five six.\n\nSeven eight nine ten.\n";
+        let chunks = split(text, &params(ChunkBy::Headings, 12, 0));
+        assert_eq!(
+            chunks,
+            vec![
+                "# Guide\nOne two three. Four five six.",
+                "# Guide\nSeven eight nine ten.",
+            ]
+        );
+
+        let chunks = split(text, &params(ChunkBy::Sentences, 100, 0));
+        assert_eq!(
+            chunks,
+            vec!["# Guide\nOne two three. Four five six.\n\nSeven eight nine ten."]
+        );
+    }
+}
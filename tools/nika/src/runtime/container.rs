@@ -0,0 +1,284 @@
+//! Container backend for `exec: { image }` (v0.7)
+//!
+//! Runs an `exec:` command with `sh -c` inside a throwaway container:
+//!
+//! - the working directory (or `sandbox.cwd`) is mounted read-only at
+//!   `/workspace`, where the command starts
+//! - the artifacts directory (default `.nika/artifacts`) is mounted
+//!   read-write at `/artifacts` and exported as `NIKA_ARTIFACTS`
+//! - sandbox settings become run flags: `memory_mb` → `--memory`,
+//!   `cpu_secs` → `--ulimit cpu`, `network: false` → `--network none`, and
+//!   only `env` allowlisted host variables are passed in
+//!
+//! Docker and podman share the `run` CLI; a [`ContainerDriver`] carries what
+//! differs between them.
+
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::ast::{ContainerRuntime, ExecContainer, ExecSandbox};
+
+/// Mount point of the working directory
+pub const WORKSPACE_MOUNT: &str = "/workspace";
+/// Mount point of the artifacts directory
+pub const ARTIFACTS_MOUNT: &str = "/artifacts";
+/// Artifacts directory when `artifacts:` is not set
+pub const DEFAULT_ARTIFACTS: &str = ".nika/artifacts";
+/// Exit code of `docker run` / `podman run` when the engine itself failed
+/// (daemon unreachable, image not found, bad flag)
+pub const ENGINE_FAILURE: i32 = 125;
+
+/// A container engine CLI
+pub trait ContainerDriver: Send + Sync {
+    fn runtime(&self) -> ContainerRuntime;
+
+    /// Flags that make files written to the mounts belong to the caller
+    fn user_flags(&self) -> Vec<String>;
+}
+
+/// `docker run`: runs as the caller's uid:gid
+pub struct Docker;
+
+/// `podman run`: keeps the caller's uid in rootless mode
+pub struct Podman;
+
+impl ContainerDriver for Docker {
+    fn runtime(&self) -> ContainerRuntime {
+        ContainerRuntime::Docker
+    }
+
+    fn user_flags(&self) -> Vec<String> {
+        match caller_ids() {
+            Some((uid, gid)) if uid != 0 => vec!["--user".to_string(), format!("{}:{}", uid, gid)],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl ContainerDriver for Podman {
+    fn runtime(&self) -> ContainerRuntime {
+        ContainerRuntime::Podman
+    }
+
+    fn user_flags(&self) -> Vec<String> {
+        // keep-id is only valid for rootless podman
+        match caller_ids() {
+            Some((uid, _)) if uid != 0 => vec!["--userns=keep-id".to_string()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn caller_ids() -> Option<(u32, u32)> {
+    // SAFETY: getuid/getgid cannot fail and touch no memory
+    Some(unsafe { (libc::getuid(), libc::getgid()) })
+}
+
+#[cfg(not(unix))]
+fn caller_ids() -> Option<(u32, u32)> {
+    None
+}
+
+/// The requested driver, or the first of docker and podman found on PATH
+pub fn driver(runtime: Option<ContainerRuntime>) -> Result<Box<dyn ContainerDriver>, String> {
+    let runtime = match runtime {
+        Some(runtime) if on_path(runtime.as_str()) => runtime,
+        Some(runtime) => return Err(format!("'{}' is not on PATH", runtime.as_str())),
+        None => [ContainerRuntime::Docker, ContainerRuntime::Podman]
+            .into_iter()
+            .find(|r| on_path(r.as_str()))
+            .ok_or("no docker or podman on PATH")?,
+    };
+    Ok(match runtime {
+        ContainerRuntime::Docker => Box::new(Docker),
+        ContainerRuntime::Podman => Box::new(Podman),
+    })
+}
+
+fn on_path(binary: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(binary).is_file()))
+}
+
+/// One `run` invocation
+#[derive(Debug, Clone)]
+pub struct ContainerRun {
+    pub runtime: ContainerRuntime,
+    /// Container name, used to remove it after a timeout
+    pub name: String,
+    /// Arguments after the engine binary
+    pub args: Vec<String>,
+}
+
+impl ContainerRun {
+    /// Resolve the mounts (creating the artifacts directory) and build the
+    /// `run` arguments for `command`
+    pub fn new(
+        driver: &dyn ContainerDriver,
+        container: &ExecContainer,
+        sandbox: Option<&ExecSandbox>,
+        command: &str,
+    ) -> Result<Self, String> {
+        let workspace = Path::new(sandbox.and_then(|s| s.cwd.as_deref()).unwrap_or("."));
+        let workspace = workspace
+            .canonicalize()
+            .map_err(|e| format!("workspace '{}': {}", workspace.display(), e))?;
+        let artifacts = Path::new(container.artifacts.as_deref().unwrap_or(DEFAULT_ARTIFACTS));
+        std::fs::create_dir_all(artifacts)
+            .and_then(|_| artifacts.canonicalize())
+            .map_err(|e| format!("artifacts '{}': {}", artifacts.display(), e))
+            .map(|artifacts| {
+                let name = format!("nika-{}", Uuid::new_v4().simple());
+                let mounts = Mounts {
+                    workspace,
+                    artifacts,
+                };
+                let args = run_args(driver, &name, container, &mounts, sandbox, command);
+                Self {
+                    runtime: driver.runtime(),
+                    name,
+                    args,
+                }
+            })
+    }
+
+    pub fn command(&self) -> tokio::process::Command {
+        let mut cmd = tokio::process::Command::new(self.runtime.as_str());
+        cmd.args(&self.args)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true);
+        cmd
+    }
+
+    /// Force-remove the container; killing the CLI doesn't stop it
+    pub async fn remove(&self) {
+        let removed = tokio::process::Command::new(self.runtime.as_str())
+            .args(["rm", "-f", &self.name])
+            .output()
+            .await;
+        if let Err(e) = removed {
+            tracing::warn!(container = %self.name, error = %e, "Container not removed");
+        }
+    }
+}
+
+/// Host directories mounted into the container
+struct Mounts {
+    workspace: PathBuf,
+    artifacts: PathBuf,
+}
+
+fn run_args(
+    driver: &dyn ContainerDriver,
+    name: &str,
+    container: &ExecContainer,
+    mounts: &Mounts,
+    sandbox: Option<&ExecSandbox>,
+    command: &str,
+) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "run".into(),
+        "--rm".into(),
+        "--name".into(),
+        name.into(),
+        "-v".into(),
+        format!("{}:{}:ro", mounts.workspace.display(), WORKSPACE_MOUNT),
+        "-v".into(),
+        format!("{}:{}", mounts.artifacts.display(), ARTIFACTS_MOUNT),
+        "-w".into(),
+        WORKSPACE_MOUNT.into(),
+        "-e".into(),
+        format!("NIKA_ARTIFACTS={}", ARTIFACTS_MOUNT),
+    ];
+    args.extend(driver.user_flags());
+
+    if let Some(sandbox) = sandbox {
+        if let Some(mb) = sandbox.memory_mb {
+            args.extend(["--memory".into(), format!("{}m", mb)]);
+        }
+        if let Some(secs) = sandbox.cpu_secs {
+            args.extend(["--ulimit".into(), format!("cpu={}:{}", secs, secs + 1)]);
+        }
+        if !sandbox.network {
+            args.extend(["--network".into(), "none".into()]);
+        }
+        if sandbox.env.is_some() {
+            // `-e NAME` copies the value from the CLI's environment; the
+            // image keeps its own PATH
+            let mut names: Vec<String> = std::env::vars_os()
+                .filter_map(|(name, _)| name.into_string().ok())
+                .filter(|name| name != "PATH" && sandbox.allows_env(name))
+                .collect();
+            names.sort();
+            for name in names {
+                args.extend(["-e".into(), name]);
+            }
+        }
+    }
+
+    args.extend([
+        container.image.clone(),
+        "sh".into(),
+        "-c".into(),
+        command.into(),
+    ]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mounts() -> Mounts {
+        Mounts {
+            workspace: PathBuf::from("/home/me/project"),
+            artifacts: PathBuf::from("/home/me/project/.nika/artifacts"),
+        }
+    }
+
+    #[test]
+    fn run_args_mount_workspace_and_artifacts() {
+        let container = ExecContainer::new("python:3.12");
+        let args = run_args(
+            &Podman,
+            "nika-1",
+            &container,
+            &mounts(),
+            None,
+            "python a.py",
+        );
+        let line = args.join(" ");
+        assert!(line.starts_with("run --rm --name nika-1 -v /home/me/project:/workspace:ro"));
+        assert!(line.contains("-v /home/me/project/.nika/artifacts:/artifacts -w /workspace"));
+        assert!(line.contains("-e NIKA_ARTIFACTS=/artifacts"));
+        assert!(line.ends_with("python:3.12 sh -c python a.py"));
+        assert_eq!(args.last().unwrap(), "python a.py");
+    }
+
+    #[test]
+    fn run_args_map_sandbox_limits() {
+        std::env::set_var("NIKA_CONTAINER_TEST_TOKEN", "x");
+        let sandbox = ExecSandbox {
+            env: Some(vec!["NIKA_CONTAINER_TEST_*".to_string()]),
+            cpu_secs: Some(30),
+            memory_mb: Some(512),
+            network: false,
+            ..Default::default()
+        };
+        let container = ExecContainer::new("alpine");
+        let args = run_args(&Docker, "n", &container, &mounts(), Some(&sandbox), "ls");
+        let line = args.join(" ");
+        assert!(line.contains("--memory 512m --ulimit cpu=30:31 --network none"));
+        assert!(line.contains("-e NIKA_CONTAINER_TEST_TOKEN alpine"));
+        assert!(!line.contains("-e PATH"));
+        std::env::remove_var("NIKA_CONTAINER_TEST_TOKEN");
+    }
+
+    #[test]
+    fn on_path_finds_binaries() {
+        assert!(!on_path("nika-no-such-engine"));
+        assert!(on_path("sh"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Context pool - token budget shared by `agent:` and `infer:` tasks (v0.7)
+//!
+//! Built from the workflow's `context_pool:` block (see
+//! [`ContextPoolSpec`]). A task reserves its share before it starts and
+//! holds it while it runs; when it ends, the tokens its provider calls
+//! reported are charged and the reservation is released. Every change is
+//! emitted as a `ContextPoolUpdated` event, which the Monitor view shows.
+//!
+//! When the pool can't cover a reservation, `on_exhausted` decides:
+//! - `queue`: wait for running tasks to release tokens
+//! - `summarize`: run with what is left as a hard limit
+//! - `fail`: fail the task (NIKA-126)
+//!
+//! A task that could only ever wait (nothing is running to release
+//! tokens) fails as well.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+
+use crate::ast::{AgentLimits, ContextPoolSpec, PoolExhausted, TaskAction};
+use crate::error::NikaError;
+use crate::event::{EventKind, EventLog};
+use crate::provider::TokenUsage;
+
+/// Tokens a running task holds from the pool
+#[derive(Debug)]
+pub struct PoolGrant {
+    task_id: Arc<str>,
+    tokens: u64,
+    /// Set when `summarize` granted less than the reservation
+    limit: Option<u64>,
+}
+
+impl PoolGrant {
+    /// The task's action, limited to the grant when it was cut short
+    ///
+    /// Agents get `limits.max_tokens` (they summarize and stop once it is
+    /// spent), `infer:` gets `max_tokens`.
+    pub fn limit(&self, action: &TaskAction) -> Option<TaskAction> {
+        let limit = self.limit?;
+        let mut action = action.clone();
+        match &mut action {
+            TaskAction::Agent { agent } => {
+                let limits = agent.limits.get_or_insert_with(AgentLimits::default);
+                limits.max_tokens = Some(limits.max_tokens.map_or(limit, |max| max.min(limit)));
+            }
+            TaskAction::Infer { infer } => {
+                let limit = u32::try_from(limit).unwrap_or(u32::MAX);
+                infer.max_tokens = Some(infer.max_tokens.map_or(limit, |max| max.min(limit)));
+            }
+            _ => return None,
+        }
+        Some(action)
+    }
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    used: u64,
+    reserved: u64,
+    running: usize,
+    waiting: usize,
+}
+
+/// Workflow-wide token pool
+pub struct ContextPool {
+    spec: ContextPoolSpec,
+    state: Mutex<PoolState>,
+    released: Notify,
+    event_log: EventLog,
+}
+
+impl ContextPool {
+    pub fn new(spec: ContextPoolSpec, event_log: EventLog) -> Self {
+        Self {
+            spec,
+            state: Mutex::new(PoolState::default()),
+            released: Notify::new(),
+            event_log,
+        }
+    }
+
+    /// Whether tasks of this verb draw from the pool
+    pub fn covers(action: &TaskAction) -> bool {
+        matches!(action, TaskAction::Agent { .. } | TaskAction::Infer { .. })
+    }
+
+    /// Reserve tokens for `task_id` (a `for_each` iteration reserves what
+    /// its parent task is given)
+    pub async fn acquire(
+        &self,
+        task_id: &Arc<str>,
+        parent_task_id: &str,
+    ) -> Result<PoolGrant, NikaError> {
+        let wanted = self.spec.reservation(parent_task_id);
+        let mut queued = false;
+        loop {
+            // Registered before checking, so a release in between isn't missed
+            let released = self.released.notified();
+            {
+                let mut state = self.state.lock();
+                let free = self.spec.tokens.saturating_sub(state.used + state.reserved);
+                let grant = if free >= wanted {
+                    Some((wanted, None, "reserved"))
+                } else if self.spec.on_exhausted == PoolExhausted::Summarize && free > 0 {
+                    Some((free, Some(free), "summarized"))
+                } else {
+                    None
+                };
+
+                if let Some((tokens, limit, action)) = grant {
+                    if queued {
+                        state.waiting -= 1;
+                    }
+                    state.reserved += tokens;
+                    state.running += 1;
+                    self.emit(&state, task_id, action, tokens);
+                    return Ok(PoolGrant {
+                        task_id: Arc::clone(task_id),
+                        tokens,
+                        limit,
+                    });
+                }
+
+                if self.spec.on_exhausted == PoolExhausted::Fail || state.running == 0 {
+                    if queued {
+                        state.waiting -= 1;
+                    }
+                    self.emit(&state, task_id, "exhausted", wanted);
+                    return Err(NikaError::ContextPoolExhausted {
+                        task_id: task_id.to_string(),
+                        needed: wanted,
+                        available: free,
+                        capacity: self.spec.tokens,
+                    });
+                }
+
+                if !queued {
+                    queued = true;
+                    state.waiting += 1;
+                    self.emit(&state, task_id, "queued", wanted);
+                }
+            }
+            released.await;
+        }
+    }
+
+    /// Charge the tokens the task used and give back its reservation
+    pub fn release(&self, grant: PoolGrant) {
+        let charged = tokens_used(&self.event_log, &grant.task_id);
+        {
+            let mut state = self.state.lock();
+            state.reserved -= grant.tokens;
+            state.running -= 1;
+            state.used += charged;
+            self.emit(&state, &grant.task_id, "released", charged);
+        }
+        self.released.notify_waiters();
+    }
+
+    fn emit(&self, state: &PoolState, task_id: &Arc<str>, action: &str, tokens: u64) {
+        // EMIT: ContextPoolUpdated
+        self.event_log.emit(EventKind::ContextPoolUpdated {
+            task_id: Arc::clone(task_id),
+            action: action.to_string(),
+            tokens,
+            used: state.used,
+            reserved: state.reserved,
+            capacity: self.spec.tokens,
+            waiting: state.waiting,
+        });
+    }
+}
+
+/// Tokens of the task's provider calls (agent turns when none were reported)
+fn tokens_used(event_log: &EventLog, task_id: &str) -> u64 {
+    let events = event_log.filter_task(task_id);
+    let mut responded = None;
+    let mut turns = 0;
+    for event in &events {
+        match &event.kind {
+            EventKind::ProviderResponded {
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+                ..
+            } => {
+                let usage = TokenUsage {
+                    input_tokens: u64::from(*input_tokens),
+                    output_tokens: u64::from(*output_tokens),
+                    cache_read_tokens: u64::from(*cache_read_tokens),
+                    cache_write_tokens: u64::from(*cache_write_tokens),
+                };
+                *responded.get_or_insert(0) += usage.total_input_tokens() + usage.output_tokens;
+            }
+            EventKind::AgentTurn {
+                metadata: Some(metadata),
+                ..
+            } => {
+                turns += u64::from(metadata.input_tokens) + u64::from(metadata.output_tokens);
+            }
+            _ => {}
+        }
+    }
+    responded.unwrap_or(turns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::AgentParams;
+    use std::time::Duration;
+
+    fn new_pool(yaml: &str) -> (Arc<ContextPool>, EventLog) {
+        let event_log = EventLog::new();
+        let spec = serde_yaml::from_str(yaml).unwrap();
+        (
+            Arc::new(ContextPool::new(spec, event_log.clone())),
+            event_log,
+        )
+    }
+
+    fn respond(event_log: &EventLog, task_id: &str, input_tokens: u32, output_tokens: u32) {
+        event_log.emit(EventKind::ProviderResponded {
+            task_id: Arc::from(task_id),
+            request_id: None,
+            input_tokens,
+            output_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            ttft_ms: None,
+            finish_reason: "end_turn".into(),
+            cost_usd: 0.0,
+        });
+    }
+
+    fn actions(event_log: &EventLog) -> Vec<(String, String)> {
+        event_log
+            .events()
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                EventKind::ContextPoolUpdated {
+                    task_id, action, ..
+                } => Some((task_id.to_string(), action)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn queued_task_runs_after_a_release() {
+        let (pool, event_log) = new_pool("tokens: 1000\nreserve: 600\n");
+        let a: Arc<str> = Arc::from("a");
+        let first = pool.acquire(&a, "a").await.unwrap();
+
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move { pool.acquire(&Arc::from("b"), "b").await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        // `a` used less than it held
+        respond(&event_log, "a", 200, 100);
+        pool.release(first);
+        let second = waiter.await.unwrap().unwrap();
+        assert_eq!(second.tokens, 600);
+        assert!(second.limit.is_none());
+
+        assert_eq!(
+            actions(&event_log),
+            [
+                ("a", "reserved"),
+                ("b", "queued"),
+                ("a", "released"),
+                ("b", "reserved")
+            ]
+            .map(|(t, a)| (t.to_string(), a.to_string()))
+        );
+        let state = pool.state.lock();
+        assert_eq!((state.used, state.reserved, state.waiting), (300, 600, 0));
+    }
+
+    #[tokio::test]
+    async fn summarize_grants_what_is_left_as_a_limit() {
+        let (pool, _) = new_pool("tokens: 1000\nreserve: 700\non_exhausted: summarize\n");
+        let _first = pool.acquire(&Arc::from("a"), "a").await.unwrap();
+        let second = pool.acquire(&Arc::from("b"), "b").await.unwrap();
+        assert_eq!(second.limit, Some(300));
+
+        let agent = TaskAction::Agent {
+            agent: AgentParams {
+                prompt: "Research".to_string(),
+                ..Default::default()
+            },
+        };
+        let Some(TaskAction::Agent { agent }) = second.limit(&agent) else {
+            panic!("expected a limited agent");
+        };
+        assert_eq!(agent.limits.and_then(|l| l.max_tokens), Some(300));
+
+        let infer: TaskAction =
+            serde_yaml::from_str("infer: { prompt: Draft, max_tokens: 200 }").unwrap();
+        let Some(TaskAction::Infer { infer }) = second.limit(&infer) else {
+            panic!("expected a limited infer");
+        };
+        assert_eq!(infer.max_tokens, Some(200));
+    }
+
+    #[tokio::test]
+    async fn fail_policy_and_lone_waiters_error() {
+        let (pool, _) = new_pool("tokens: 1000\nreserve: 700\non_exhausted: fail\n");
+        let _first = pool.acquire(&Arc::from("a"), "a").await.unwrap();
+        let err = pool.acquire(&Arc::from("b"), "b").await.unwrap_err();
+        assert!(matches!(
+            err,
+            NikaError::ContextPoolExhausted {
+                needed: 700,
+                available: 300,
+                ..
+            }
+        ));
+
+        // Nothing running will ever release tokens
+        let (pool, event_log) = new_pool("tokens: 1000\nreserve: 700\n");
+        let first = pool.acquire(&Arc::from("a"), "a").await.unwrap();
+        respond(&event_log, "a", 900, 0);
+        pool.release(first);
+        assert!(pool.acquire(&Arc::from("b"), "b").await.is_err());
+    }
+}
@@ -0,0 +1,306 @@
+//! Step-through debugger - pause before tasks (v0.7, `nika debug`)
+//!
+//! The [`Debugger`] sits between the runner and the executor. Before a task
+//! runs, the runner hands a [`PausedTask`] (resolved inputs and rendered
+//! prompt) to the front-end and waits for a [`DebugCommand`]:
+//!
+//! | Command | Effect |
+//! |---------|--------|
+//! | `Step` | Run the task, pause before the next one |
+//! | `Continue` | Run the task, pause only at breakpoints |
+//! | `EditPrompt` | Run the task with a replacement prompt (or command) |
+//! | `Skip` | Skip the task (`TaskSkipped`), dependents see no output |
+//! | `InjectOutput` | Complete the task with the given output, without running it |
+//!
+//! Edits, skips and injections are recorded as `TaskDebugged` events so a
+//! trace shows where a run was tampered with.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use rustc_hash::FxHashSet;
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::ast::TaskAction;
+
+/// What the front-end sees when a task is paused
+#[derive(Debug, Clone)]
+pub struct DebugStop {
+    pub task_id: Arc<str>,
+    pub verb: &'static str,
+    /// Resolved `use:` (and `for_each`) bindings
+    pub inputs: Value,
+    /// Prompt, command or request with templates resolved
+    pub rendered: String,
+    /// Whether `EditPrompt` applies to this verb
+    pub editable: bool,
+}
+
+impl DebugStop {
+    /// What `rendered` holds, for display
+    pub fn rendered_label(&self) -> &'static str {
+        match self.verb {
+            "exec" => "command",
+            "fetch" | "ws" => "request",
+            "invoke" => "call",
+            "reduce" => "reduce",
+            "embed" => "input",
+            "recall" | "retrieve" => "query",
+            "validate" => "source",
+            "transcribe" | "import" => "file",
+            "export" | "rows" | "chunk" | "dedupe" | "detect_lang" | "translate" => "source",
+            "script" => "script",
+            _ => "prompt",
+        }
+    }
+}
+
+/// Front-end decision for a paused task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugCommand {
+    Step,
+    Continue,
+    EditPrompt(String),
+    Skip,
+    InjectOutput(String),
+}
+
+impl DebugCommand {
+    /// Short name recorded in `TaskDebugged` events
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Step => "step",
+            Self::Continue => "continue",
+            Self::EditPrompt(_) => "edit_prompt",
+            Self::Skip => "skip",
+            Self::InjectOutput(_) => "inject_output",
+        }
+    }
+}
+
+/// A task waiting for a [`DebugCommand`], answered with [`PausedTask::resume`]
+#[derive(Debug)]
+pub struct PausedTask {
+    pub stop: DebugStop,
+    reply: oneshot::Sender<DebugCommand>,
+}
+
+impl PausedTask {
+    pub fn resume(self, command: DebugCommand) {
+        // The run may have been aborted in the meantime
+        let _ = self.reply.send(command);
+    }
+}
+
+/// Debugger controller shared by the runner and a front-end
+#[derive(Debug)]
+pub struct Debugger {
+    tx: mpsc::Sender<PausedTask>,
+    /// Pause before every task (cleared by `Continue`)
+    stepping: AtomicBool,
+    /// Task ids that always pause (`for_each` iterations match their parent)
+    breakpoints: Mutex<FxHashSet<String>>,
+}
+
+impl Debugger {
+    /// Create a debugger that pauses before every task
+    pub fn new(tx: mpsc::Sender<PausedTask>) -> Self {
+        Self {
+            tx,
+            stepping: AtomicBool::new(true),
+            breakpoints: Mutex::new(FxHashSet::default()),
+        }
+    }
+
+    /// Only pause at these tasks (until a `Step` turns stepping back on)
+    pub fn with_breakpoints(self, task_ids: impl IntoIterator<Item = String>) -> Self {
+        {
+            let mut breakpoints = self.breakpoints.lock();
+            breakpoints.extend(task_ids);
+            if !breakpoints.is_empty() {
+                self.stepping.store(false, Ordering::Relaxed);
+            }
+        }
+        self
+    }
+
+    /// Add or remove a breakpoint; returns true if it is now set
+    pub fn toggle_breakpoint(&self, task_id: &str) -> bool {
+        let mut breakpoints = self.breakpoints.lock();
+        if breakpoints.remove(task_id) {
+            false
+        } else {
+            breakpoints.insert(task_id.to_string());
+            true
+        }
+    }
+
+    /// Current breakpoints, sorted
+    pub fn breakpoints(&self) -> Vec<String> {
+        let mut breakpoints: Vec<String> = self.breakpoints.lock().iter().cloned().collect();
+        breakpoints.sort();
+        breakpoints
+    }
+
+    pub fn is_stepping(&self) -> bool {
+        self.stepping.load(Ordering::Relaxed)
+    }
+
+    /// Whether the runner should pause before this task
+    pub fn should_pause(&self, task_id: &str, parent_task_id: &str) -> bool {
+        if self.is_stepping() {
+            return true;
+        }
+        let breakpoints = self.breakpoints.lock();
+        breakpoints.contains(task_id) || breakpoints.contains(parent_task_id)
+    }
+
+    /// Hand the stop to the front-end and wait for its command
+    ///
+    /// A front-end that went away counts as `Continue`.
+    pub async fn pause(&self, stop: DebugStop) -> DebugCommand {
+        let (reply, rx) = oneshot::channel();
+        if self.tx.send(PausedTask { stop, reply }).await.is_err() {
+            self.stepping.store(false, Ordering::Relaxed);
+            return DebugCommand::Continue;
+        }
+        let command = rx.await.unwrap_or(DebugCommand::Continue);
+        match command {
+            DebugCommand::Continue => self.stepping.store(false, Ordering::Relaxed),
+            DebugCommand::Step => self.stepping.store(true, Ordering::Relaxed),
+            _ => {}
+        }
+        command
+    }
+}
+
+/// Whether `EditPrompt` can rewrite this action
+pub fn is_editable(action: &TaskAction) -> bool {
+    with_prompt(action, String::new()).is_some()
+}
+
+/// Copy of `action` with its prompt (or exec command) replaced
+pub fn with_prompt(action: &TaskAction, prompt: String) -> Option<TaskAction> {
+    let mut action = action.clone();
+    match &mut action {
+        TaskAction::Infer { infer } => infer.prompt = prompt,
+        TaskAction::Agent { agent } => agent.prompt = prompt,
+        TaskAction::Exec { exec } => exec.command = prompt,
+        TaskAction::Approve { approve } => approve.prompt = prompt,
+        TaskAction::Reduce { reduce } => reduce.prompt = Some(prompt),
+        TaskAction::Embed { embed } => embed.input = prompt,
+        TaskAction::Recall { recall } => recall.query = prompt,
+        TaskAction::Retrieve { retrieve } => retrieve.query = prompt,
+        TaskAction::Transcribe { transcribe } => transcribe.file = prompt,
+        TaskAction::Import { import } => import.file = prompt,
+        TaskAction::Script { script } => {
+            script.code = Some(prompt);
+            script.file = None;
+        }
+        TaskAction::Fetch { .. }
+        | TaskAction::Invoke { .. }
+        | TaskAction::Validate { .. }
+        | TaskAction::Export { .. }
+        | TaskAction::Rows { .. }
+        | TaskAction::Chunk { .. }
+        | TaskAction::Dedupe { .. }
+        | TaskAction::DetectLang { .. }
+        | TaskAction::Translate { .. }
+        | TaskAction::Ws { .. } => return None,
+    }
+    Some(action)
+}
+
+/// Parse a typed debugger command
+///
+/// `s`/empty = step, `c` = continue, `k` = skip, `p <text>` = edit prompt,
+/// `o <text>` = inject output.
+pub fn parse_command(input: &str) -> Result<DebugCommand, String> {
+    let input = input.trim();
+    let (word, rest) = match input.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (input, ""),
+    };
+    match word {
+        "" | "s" | "step" => Ok(DebugCommand::Step),
+        "c" | "continue" => Ok(DebugCommand::Continue),
+        "k" | "skip" => Ok(DebugCommand::Skip),
+        "p" | "prompt" if !rest.is_empty() => Ok(DebugCommand::EditPrompt(rest.to_string())),
+        "o" | "output" if !rest.is_empty() => Ok(DebugCommand::InjectOutput(rest.to_string())),
+        "p" | "prompt" | "o" | "output" => Err(format!("'{}' needs text", word)),
+        other => Err(format!("unknown command '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop(task_id: &str) -> DebugStop {
+        DebugStop {
+            task_id: Arc::from(task_id),
+            verb: "exec",
+            inputs: Value::Null,
+            rendered: "echo hi".to_string(),
+            editable: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command(""), Ok(DebugCommand::Step));
+        assert_eq!(parse_command("c"), Ok(DebugCommand::Continue));
+        assert_eq!(parse_command("skip"), Ok(DebugCommand::Skip));
+        assert_eq!(
+            parse_command("p  Say hi in French "),
+            Ok(DebugCommand::EditPrompt("Say hi in French".to_string()))
+        );
+        assert_eq!(
+            parse_command("o {\"ok\": true}"),
+            Ok(DebugCommand::InjectOutput("{\"ok\": true}".to_string()))
+        );
+        assert!(parse_command("p").is_err());
+        assert!(parse_command("jump").is_err());
+    }
+
+    #[test]
+    fn test_breakpoints_disable_stepping() {
+        let (tx, _rx) = mpsc::channel(1);
+        let debugger = Debugger::new(tx);
+        assert!(debugger.should_pause("a", "a"));
+
+        let (tx, _rx) = mpsc::channel(1);
+        let debugger = Debugger::new(tx).with_breakpoints(["fanout".to_string()]);
+        assert!(!debugger.should_pause("a", "a"));
+        // for_each iterations break on their parent id
+        assert!(debugger.should_pause("fanout[2]", "fanout"));
+
+        assert!(!debugger.toggle_breakpoint("fanout"));
+        assert!(!debugger.should_pause("fanout[2]", "fanout"));
+    }
+
+    #[tokio::test]
+    async fn test_continue_stops_stepping() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let debugger = Debugger::new(tx);
+        tokio::spawn(async move {
+            rx.recv().await.unwrap().resume(DebugCommand::Continue);
+        });
+
+        assert_eq!(debugger.pause(stop("a")).await, DebugCommand::Continue);
+        assert!(!debugger.is_stepping());
+    }
+
+    #[test]
+    fn test_with_prompt() {
+        let action: TaskAction = serde_yaml::from_str("exec: echo hi").unwrap();
+        match with_prompt(&action, "echo bye".to_string()) {
+            Some(TaskAction::Exec { exec }) => assert_eq!(exec.command, "echo bye"),
+            other => panic!("expected exec, got {other:?}"),
+        }
+        let fetch: TaskAction = serde_yaml::from_str("fetch:\n  url: http://x").unwrap();
+        assert!(!is_editable(&fetch));
+    }
+}
@@ -0,0 +1,213 @@
+//! Near-duplicate detection behind `dedupe:` tasks (v0.7)
+//!
+//! Items are compared in order; an item at least `threshold` similar to an
+//! item already kept is dropped. MinHash estimates the Jaccard similarity of
+//! 5-character shingles of the normalized text (lowercase, punctuation and
+//! runs of whitespace collapsed) with 128 hash functions. Items without
+//! text (missing `field`, empty string) are always kept.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_json::{json, Value};
+
+use crate::error::NikaError;
+use crate::util::jsonpath;
+
+/// Hash functions per MinHash signature
+const NUM_HASHES: usize = 128;
+
+/// Characters per shingle
+const SHINGLE: usize = 5;
+
+/// An item dropped as a near-duplicate of an earlier kept item
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub index: usize,
+    pub duplicate_of: usize,
+    pub similarity: f32,
+}
+
+/// The text compared for each item: `field` of the item, or the item itself
+///
+/// Strings are used as-is, other values as JSON text; `None` when the field
+/// is missing or null or the text is blank.
+pub fn item_texts(items: &[Value], field: Option<&str>) -> Result<Vec<Option<String>>, NikaError> {
+    items
+        .iter()
+        .map(|item| {
+            let value = match field {
+                Some(path) => jsonpath::resolve(item, path)?,
+                None => Some(item.clone()),
+            };
+            Ok(match value {
+                None | Some(Value::Null) => None,
+                Some(Value::String(s)) => Some(s).filter(|s| !s.trim().is_empty()),
+                Some(other) => Some(other.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// MinHash signature of `text`
+pub fn minhash(text: &str) -> Vec<u64> {
+    let normalized: Vec<char> = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect();
+
+    let mut signature = vec![u64::MAX; NUM_HASHES];
+    for shingle in normalized.windows(SHINGLE.min(normalized.len()).max(1)) {
+        let mut hasher = DefaultHasher::new();
+        shingle.hash(&mut hasher);
+        let base = hasher.finish();
+        for (i, slot) in signature.iter_mut().enumerate() {
+            *slot = (*slot).min(mix(base ^ mix(i as u64 + 1)));
+        }
+    }
+    signature
+}
+
+/// Fraction of matching MinHash slots (estimated Jaccard similarity)
+pub fn minhash_similarity(a: &[u64], b: &[u64]) -> f32 {
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f32 / a.len().max(1) as f32
+}
+
+/// splitmix64 finalizer
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Greedy pass over `n` items in order
+///
+/// `similarity(i, j)` compares item `i` with kept item `j` (`None` when
+/// either has no text). Each duplicate points at its most similar kept item.
+pub fn find_duplicates(
+    n: usize,
+    threshold: f32,
+    similarity: impl Fn(usize, usize) -> Option<f32>,
+) -> Vec<Duplicate> {
+    let mut kept: Vec<usize> = Vec::with_capacity(n);
+    let mut dropped = Vec::new();
+    for i in 0..n {
+        let best = kept
+            .iter()
+            .filter_map(|&j| similarity(i, j).map(|s| (j, s)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((j, s)) if s >= threshold => dropped.push(Duplicate {
+                index: i,
+                duplicate_of: j,
+                similarity: s,
+            }),
+            _ => kept.push(i),
+        }
+    }
+    dropped
+}
+
+/// `{"kept": [...], "dropped": [{"index", "duplicate_of", "similarity", "item"}]}`
+pub fn dedupe_output(items: Vec<Value>, dropped: &[Duplicate]) -> Value {
+    let report: Vec<Value> = dropped
+        .iter()
+        .map(|d| {
+            json!({
+                "index": d.index,
+                "duplicate_of": d.duplicate_of,
+                "similarity": (d.similarity as f64 * 1000.0).round() / 1000.0,
+                "item": items[d.index],
+            })
+        })
+        .collect();
+    let kept: Vec<Value> = items
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !dropped.iter().any(|d| d.index == *i))
+        .map(|(_, item)| item)
+        .collect();
+    json!({ "kept": kept, "dropped": report })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dedupe_minhash(items: Vec<Value>, field: Option<&str>, threshold: f32) -> Value {
+        let signatures: Vec<Option<Vec<u64>>> = item_texts(&items, field)
+            .unwrap()
+            .iter()
+            .map(|text| text.as_deref().map(minhash))
+            .collect();
+        let dropped = find_duplicates(items.len(), threshold, |i, j| {
+            Some(minhash_similarity(
+                signatures[i].as_ref()?,
+                signatures[j].as_ref()?,
+            ))
+        });
+        dedupe_output(items, &dropped)
+    }
+
+    #[test]
+    fn test_minhash_similarity() {
+        let a = minhash("The printer on floor 3 is out of toner again");
+        let b = minhash("the printer on floor 3 is out of toner again!!");
+        let c = minhash("Payroll export fails with a timeout on Mondays");
+        assert_eq!(minhash_similarity(&a, &b), 1.0);
+        assert!(minhash_similarity(&a, &c) < 0.2);
+        assert_eq!(minhash("ok").len(), NUM_HASHES);
+    }
+
+    #[test]
+    fn test_dedupe_keeps_first_and_reports_drops() {
+        let items = vec![
+            json!({"id": 1, "body": "Printer on floor 3 is out of toner"}),
+            json!({"id": 2, "body": "Payroll export times out"}),
+            json!({"id": 3, "body": "printer on floor 3 is out of toner!"}),
+            json!({"id": 4}),
+            json!({"id": 5}),
+        ];
+        let out = dedupe_minhash(items, Some("body"), 0.8);
+        let kept: Vec<i64> = out["kept"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(kept, vec![1, 2, 4, 5]);
+        assert_eq!(out["dropped"][0]["index"], 2);
+        assert_eq!(out["dropped"][0]["duplicate_of"], 0);
+        assert_eq!(out["dropped"][0]["similarity"], 1.0);
+        assert_eq!(out["dropped"][0]["item"]["id"], 3);
+    }
+
+    #[test]
+    fn test_threshold_one_keeps_near_duplicates() {
+        let items = vec![
+            json!("alpha beta gamma delta"),
+            json!("alpha beta gamma delt"),
+        ];
+        assert_eq!(
+            dedupe_minhash(items.clone(), None, 1.0)["kept"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            dedupe_minhash(items, None, 0.5)["kept"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Hedged requests for `infer: { hedge: ... }` (v0.7)
+//!
+//! [`race`] runs a primary request and, after an optional delay, a
+//! secondary one. The first success wins; the other future is dropped,
+//! which cancels its provider stream. A failure does not end the race
+//! while the other leg can still succeed, and a primary failure fires the
+//! secondary right away instead of waiting out the delay.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+/// `finish_reason` of the ProviderResponded reported for a cancelled leg
+pub(crate) const HEDGE_CANCELLED: &str = "hedge_cancelled";
+
+/// What a provider call got through before it settled or was dropped
+#[derive(Debug, Default)]
+pub(crate) struct CallUsage {
+    /// ProviderCalled was emitted (the request went out)
+    pub called: AtomicBool,
+    /// Response text streamed so far, in bytes
+    pub streamed_bytes: AtomicUsize,
+}
+
+/// How one leg of a race ended
+#[derive(Debug)]
+pub(crate) enum Leg<T, E> {
+    /// Never fired: the race was over before the delay
+    NotStarted,
+    Won(T),
+    Failed(E),
+    /// Dropped while in flight
+    Cancelled,
+}
+
+/// Outcome of both legs
+#[derive(Debug)]
+pub(crate) struct Race<T, E> {
+    pub primary: Leg<T, E>,
+    pub secondary: Leg<T, E>,
+}
+
+impl<T, E> Leg<T, E> {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NotStarted => "not_started",
+            Self::Won(_) => "won",
+            Self::Failed(_) => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl<T, E> Race<T, E> {
+    /// The winning value, or the primary's error when both failed
+    pub fn into_result(self) -> Result<T, E> {
+        match (self.primary, self.secondary) {
+            (Leg::Won(value), _) | (_, Leg::Won(value)) => Ok(value),
+            (Leg::Failed(e), _) | (_, Leg::Failed(e)) => Err(e),
+            _ => unreachable!("a race ends with a win or two failures"),
+        }
+    }
+}
+
+/// Race `primary` against `secondary`, started `delay` later
+pub(crate) async fn race<T, E>(
+    primary: impl Future<Output = Result<T, E>>,
+    secondary: impl Future<Output = Result<T, E>>,
+    delay: Duration,
+) -> Race<T, E> {
+    let started = AtomicBool::new(false);
+    let primary_failed = Notify::new();
+    let mut primary = std::pin::pin!(primary);
+    let mut secondary = std::pin::pin!(async {
+        if !delay.is_zero() {
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = primary_failed.notified() => {}
+            }
+        }
+        started.store(true, Ordering::Relaxed);
+        secondary.await
+    });
+
+    let (mut first, mut second) = (None, None);
+    loop {
+        tokio::select! {
+            biased;
+            result = &mut primary, if first.is_none() => {
+                if result.is_err() {
+                    primary_failed.notify_one();
+                }
+                first = Some(result);
+            }
+            result = &mut secondary, if second.is_none() => second = Some(result),
+        }
+        let won = matches!(first, Some(Ok(_))) || matches!(second, Some(Ok(_)));
+        if won || (first.is_some() && second.is_some()) {
+            break;
+        }
+    }
+
+    let leg = |result: Option<Result<T, E>>, started: bool| match result {
+        Some(Ok(value)) => Leg::Won(value),
+        Some(Err(e)) => Leg::Failed(e),
+        None if started => Leg::Cancelled,
+        None => Leg::NotStarted,
+    };
+    Race {
+        primary: leg(first, true),
+        secondary: leg(second, started.load(Ordering::Relaxed)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    async fn respond(
+        after_ms: u64,
+        result: Result<&'static str, &'static str>,
+    ) -> Result<&'static str, &'static str> {
+        tokio::time::sleep(Duration::from_millis(after_ms)).await;
+        result
+    }
+
+    #[tokio::test]
+    async fn test_faster_leg_wins_and_other_is_cancelled() {
+        let dropped = Arc::new(AtomicBool::new(true));
+        let finished = Arc::clone(&dropped);
+        let slow = async move {
+            let result = respond(500, Ok("slow")).await;
+            finished.store(false, Ordering::SeqCst);
+            result
+        };
+
+        let outcome = race(slow, respond(10, Ok("fast")), Duration::ZERO).await;
+        assert!(matches!(outcome.primary, Leg::Cancelled));
+        assert!(matches!(outcome.secondary, Leg::Won("fast")));
+        assert!(dropped.load(Ordering::SeqCst));
+        assert_eq!(outcome.into_result(), Ok("fast"));
+    }
+
+    #[tokio::test]
+    async fn test_delayed_hedge_never_fires_when_primary_is_quick() {
+        let outcome = race(
+            respond(5, Ok("primary")),
+            respond(0, Ok("hedge")),
+            Duration::from_millis(200),
+        )
+        .await;
+        assert!(matches!(outcome.primary, Leg::Won("primary")));
+        assert!(matches!(outcome.secondary, Leg::NotStarted));
+    }
+
+    #[tokio::test]
+    async fn test_failure_waits_for_the_other_leg() {
+        // The primary fails at once: the hedge fires without waiting 10s
+        let outcome = race(
+            respond(0, Err("rate limited")),
+            respond(20, Ok("hedge")),
+            Duration::from_secs(10),
+        )
+        .await;
+        assert!(matches!(outcome.primary, Leg::Failed("rate limited")));
+        assert!(matches!(outcome.secondary, Leg::Won("hedge")));
+
+        let outcome = race(respond(0, Err("a")), respond(5, Err("b")), Duration::ZERO).await;
+        assert_eq!(outcome.into_result(), Err("a"));
+    }
+}
@@ -0,0 +1,337 @@
+//! Retries and pagination for `fetch:` (v0.7)
+//!
+//! - 429 and 503 are retried for every method; other 5xx only for
+//!   idempotent ones (`GET`, `PUT`, `DELETE`), so a `POST` the server may
+//!   have applied is not sent twice
+//! - the wait is the response's `Retry-After` (seconds or HTTP date) when
+//!   present, else `backoff_ms` doubled per attempt; either is capped at
+//!   [`MAX_RETRY_WAIT`]
+//! - pages are followed through `paginate.next` (JSONPath) or the `Link`
+//!   header's `rel="next"`, and their items concatenated
+//!
+//! Without the `http` feature the client is a stand-in and every request
+//! fails; fresh `fetch:` cache entries are still served.
+#![cfg_attr(not(feature = "http"), allow(dead_code))]
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::ast::{FetchPaginate, FetchRetry};
+use crate::error::NikaError;
+use crate::util::jsonpath;
+
+/// Longest wait between two attempts
+pub const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+/// Client shared by `fetch:` and provider moderation (connection pooling)
+#[cfg(feature = "http")]
+pub type Client = reqwest::Client;
+
+/// Built without the `http` feature: a client that sends nothing
+#[cfg(not(feature = "http"))]
+#[derive(Debug, Clone, Default)]
+pub struct Client;
+
+/// Build the shared client with nika's timeouts and redirect limit
+#[cfg(feature = "http")]
+pub fn client() -> Client {
+    use crate::util::{CONNECT_TIMEOUT, FETCH_TIMEOUT, REDIRECT_LIMIT};
+
+    // SAFETY: ClientBuilder::build() only fails with custom TLS or proxy config.
+    // We use defaults, so this is effectively infallible.
+    reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::limited(REDIRECT_LIMIT))
+        .user_agent("nika-cli/0.1")
+        .build()
+        .unwrap_or_else(|e| {
+            tracing::error!("HTTP client build failed: {e}. Using default client.");
+            reqwest::Client::new()
+        })
+}
+
+/// Built without the `http` feature: the stand-in client
+#[cfg(not(feature = "http"))]
+pub fn client() -> Client {
+    Client
+}
+
+/// One `fetch:` request, with the cache validators to revalidate
+pub struct Request<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub headers: &'a [(String, String)],
+    pub body: Option<&'a str>,
+    /// Sent as `If-None-Match`
+    pub etag: Option<&'a str>,
+    /// Sent as `If-Modified-Since`
+    pub last_modified: Option<&'a str>,
+}
+
+/// A `fetch:` response with the headers nika reads
+pub struct Response {
+    pub status: u16,
+    /// Final URL, after redirects
+    pub url: String,
+    pub cache_control: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+    /// `Link` header, for pagination
+    pub link: Option<String>,
+    pub body: String,
+}
+
+/// Send `request`, retrying per `policy`
+#[cfg(feature = "http")]
+pub async fn send(
+    client: &Client,
+    request: Request<'_>,
+    policy: &FetchRetry,
+) -> Result<Response, NikaError> {
+    use reqwest::header;
+
+    let method = request.method;
+    let mut attempt = 0;
+    let response = loop {
+        let mut builder = if method.eq_ignore_ascii_case("POST") {
+            client.post(request.url)
+        } else if method.eq_ignore_ascii_case("PUT") {
+            client.put(request.url)
+        } else if method.eq_ignore_ascii_case("DELETE") {
+            client.delete(request.url)
+        } else {
+            client.get(request.url) // Default to GET
+        };
+        for (key, value) in request.headers {
+            builder = builder.header(key, value);
+        }
+        if let Some(etag) = request.etag {
+            builder = builder.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = request.last_modified {
+            builder = builder.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body.to_string());
+        }
+
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| NikaError::Execution(format!("HTTP request failed: {}", e)))?;
+        let status = response.status().as_u16();
+        if attempt == policy.max || !is_retryable(method, status) {
+            break response;
+        }
+        attempt += 1;
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok());
+        let wait = retry_wait(policy, attempt, retry_after);
+        tracing::warn!(
+            status,
+            attempt,
+            wait_ms = wait.as_millis() as u64,
+            "Retrying fetch {}",
+            request.url
+        );
+        tokio::time::sleep(wait).await;
+    };
+
+    let value = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let (cache_control, etag, last_modified, content_type, link) = (
+        value(header::CACHE_CONTROL),
+        value(header::ETAG),
+        value(header::LAST_MODIFIED),
+        value(header::CONTENT_TYPE),
+        value(header::LINK),
+    );
+    let status = response.status().as_u16();
+    let url = response.url().to_string();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| NikaError::Execution(format!("Failed to read response: {}", e)))?;
+    Ok(Response {
+        status,
+        url,
+        cache_control,
+        etag,
+        last_modified,
+        content_type,
+        link,
+        body,
+    })
+}
+
+/// Built without the `http` feature: every request fails
+#[cfg(not(feature = "http"))]
+pub async fn send(
+    _client: &Client,
+    _request: Request<'_>,
+    _policy: &FetchRetry,
+) -> Result<Response, NikaError> {
+    Err(NikaError::Execution(
+        "HTTP request failed: nika was built without the `http` feature".to_string(),
+    ))
+}
+
+/// Whether a response with `status` is worth sending again
+pub fn is_retryable(method: &str, status: u16) -> bool {
+    match status {
+        429 | 503 => true,
+        500..=599 => ["GET", "PUT", "DELETE"]
+            .iter()
+            .any(|m| method.eq_ignore_ascii_case(m)),
+        _ => false,
+    }
+}
+
+/// Wait before retry number `attempt` (1-based)
+pub fn retry_wait(policy: &FetchRetry, attempt: u32, retry_after: Option<&str>) -> Duration {
+    let backoff = || {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(policy.backoff_ms.saturating_mul(factor))
+    };
+    retry_after
+        .and_then(parse_retry_after)
+        .unwrap_or_else(backoff)
+        .min(MAX_RETRY_WAIT)
+}
+
+/// `Retry-After: 120` or `Retry-After: Wed, 21 Oct 2026 07:28:00 GMT`
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = at.signed_duration_since(chrono::Utc::now());
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Items of one page: the `items` array, or the page itself
+pub fn page_items(page: Value, paginate: &FetchPaginate) -> Vec<Value> {
+    let selected = match &paginate.items {
+        Some(path) => jsonpath::resolve(&page, path).ok().flatten(),
+        None => Some(page),
+    };
+    match selected {
+        Some(Value::Array(items)) => items,
+        Some(Value::Null) | None => Vec::new(),
+        Some(other) => vec![other],
+    }
+}
+
+/// URL of the page after `page`, resolved against `current`
+pub fn next_url(
+    page: &Value,
+    link_header: Option<&str>,
+    paginate: &FetchPaginate,
+    current: &str,
+) -> Option<String> {
+    let next = match &paginate.next {
+        Some(path) => match jsonpath::resolve(page, path).ok().flatten()? {
+            Value::String(url) if !url.is_empty() => url,
+            _ => return None,
+        },
+        None => link_next(link_header?)?,
+    };
+    let base = url::Url::parse(current).ok()?;
+    base.join(&next).ok().map(String::from)
+}
+
+/// Target of `<...>; rel="next"` in a `Link` header
+fn link_next(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params.split(';').any(|p| {
+            p.trim().strip_prefix("rel=").is_some_and(|rel| {
+                rel.trim_matches('"')
+                    .split_whitespace()
+                    .any(|r| r == "next")
+            })
+        });
+        is_next.then(|| {
+            target
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paginate(yaml: &str) -> FetchPaginate {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        assert!(is_retryable("POST", 429));
+        assert!(is_retryable("post", 503));
+        assert!(!is_retryable("POST", 500));
+        assert!(is_retryable("GET", 502));
+        assert!(!is_retryable("GET", 404));
+    }
+
+    #[test]
+    fn retry_wait_prefers_retry_after() {
+        let policy = FetchRetry {
+            max: 3,
+            backoff_ms: 100,
+        };
+        assert_eq!(retry_wait(&policy, 1, None), Duration::from_millis(100));
+        assert_eq!(retry_wait(&policy, 3, None), Duration::from_millis(400));
+        assert_eq!(retry_wait(&policy, 1, Some("2")), Duration::from_secs(2));
+        assert_eq!(retry_wait(&policy, 1, Some("86400")), MAX_RETRY_WAIT);
+        let past = "Wed, 21 Oct 2015 07:28:00 GMT";
+        assert_eq!(retry_wait(&policy, 1, Some(past)), Duration::ZERO);
+        assert_eq!(
+            retry_wait(&policy, 2, Some("soon")),
+            Duration::from_millis(200)
+        );
+    }
+
+    #[test]
+    fn next_page_from_body_or_link() {
+        let page = json!({"data": [1, 2], "next_url": "/items?page=2"});
+        let by_path = paginate("next: $.next_url\nitems: $.data");
+        assert_eq!(
+            next_url(&page, None, &by_path, "https://api.example.com/items").as_deref(),
+            Some("https://api.example.com/items?page=2")
+        );
+        assert_eq!(page_items(page.clone(), &by_path), [json!(1), json!(2)]);
+        let last = json!({"data": [], "next_url": null});
+        assert!(next_url(&last, None, &by_path, "https://api.example.com/").is_none());
+
+        let link = r#"<https://api.example.com/items?page=1>; rel="prev", <https://api.example.com/items?page=3>; rel="next""#;
+        let by_link = paginate("limit: 5");
+        assert_eq!(
+            next_url(
+                &json!([]),
+                Some(link),
+                &by_link,
+                "https://api.example.com/items"
+            )
+            .as_deref(),
+            Some("https://api.example.com/items?page=3")
+        );
+        assert_eq!(page_items(json!({"id": 1}), &by_link), [json!({"id": 1})]);
+    }
+}
@@ -0,0 +1,282 @@
+//! Language helpers behind `detect_lang:` and `translate:` tasks (v0.7)
+//!
+//! Detection runs offline on whatlang's trigram profiles (69 languages).
+//! Languages are reported with their ISO 639-1 code where one exists, and
+//! accepted as ISO 639-1 or 639-3 codes. The translation prompt is built
+//! here so every workflow sends the same instructions.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+use whatlang::{Detector, Lang};
+
+/// ISO 639-3 codes used by whatlang with their ISO 639-1 equivalent
+const ISO_639_1: &[(&str, &str)] = &[
+    ("afr", "af"),
+    ("aka", "ak"),
+    ("amh", "am"),
+    ("ara", "ar"),
+    ("aze", "az"),
+    ("bel", "be"),
+    ("ben", "bn"),
+    ("bul", "bg"),
+    ("cat", "ca"),
+    ("ces", "cs"),
+    ("cmn", "zh"),
+    ("dan", "da"),
+    ("deu", "de"),
+    ("ell", "el"),
+    ("eng", "en"),
+    ("epo", "eo"),
+    ("est", "et"),
+    ("fin", "fi"),
+    ("fra", "fr"),
+    ("guj", "gu"),
+    ("heb", "he"),
+    ("hin", "hi"),
+    ("hrv", "hr"),
+    ("hun", "hu"),
+    ("hye", "hy"),
+    ("ind", "id"),
+    ("ita", "it"),
+    ("jav", "jv"),
+    ("jpn", "ja"),
+    ("kan", "kn"),
+    ("kat", "ka"),
+    ("khm", "km"),
+    ("kor", "ko"),
+    ("lat", "la"),
+    ("lav", "lv"),
+    ("lit", "lt"),
+    ("mal", "ml"),
+    ("mar", "mr"),
+    ("mkd", "mk"),
+    ("mya", "my"),
+    ("nep", "ne"),
+    ("nld", "nl"),
+    ("nob", "nb"),
+    ("ori", "or"),
+    ("pan", "pa"),
+    ("pes", "fa"),
+    ("pol", "pl"),
+    ("por", "pt"),
+    ("ron", "ro"),
+    ("rus", "ru"),
+    ("sin", "si"),
+    ("slk", "sk"),
+    ("slv", "sl"),
+    ("sna", "sn"),
+    ("spa", "es"),
+    ("srp", "sr"),
+    ("swe", "sv"),
+    ("tam", "ta"),
+    ("tel", "te"),
+    ("tgl", "tl"),
+    ("tha", "th"),
+    ("tuk", "tk"),
+    ("tur", "tr"),
+    ("ukr", "uk"),
+    ("urd", "ur"),
+    ("uzb", "uz"),
+    ("vie", "vi"),
+    ("yid", "yi"),
+    ("zul", "zu"),
+];
+
+/// ISO 639-1 code of `lang`, or its 639-3 code when it has none
+pub fn short_code(lang: Lang) -> &'static str {
+    ISO_639_1
+        .iter()
+        .find(|(long, _)| *long == lang.code())
+        .map_or(lang.code(), |(_, short)| short)
+}
+
+/// Language of an ISO 639-1 or 639-3 code (case-insensitive)
+pub fn parse_code(code: &str) -> Option<Lang> {
+    let code = code.trim().to_lowercase();
+    let long = ISO_639_1
+        .iter()
+        .find(|(_, short)| *short == code)
+        .map_or(code.as_str(), |(long, _)| long);
+    Lang::from_code(long)
+}
+
+/// Detector limited to the `allow` codes (all languages when empty)
+pub fn detector(allow: &[String]) -> Result<Detector, String> {
+    if allow.is_empty() {
+        return Ok(Detector::new());
+    }
+    let langs = allow
+        .iter()
+        .map(|code| parse_code(code).ok_or_else(|| format!("unknown language code '{}'", code)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Detector::with_allowlist(langs))
+}
+
+/// `{"code", "iso639_3", "name", "script", "confidence", "reliable"}`
+///
+/// Every field but `confidence` and `reliable` is null when the text has
+/// no detectable language (empty, digits only).
+pub fn detect(detector: &Detector, text: &str) -> Value {
+    match detector.detect(text) {
+        Some(info) => json!({
+            "code": short_code(info.lang()),
+            "iso639_3": info.lang().code(),
+            "name": info.lang().eng_name(),
+            "script": info.script().name(),
+            "confidence": (info.confidence() * 1000.0).round() / 1000.0,
+            "reliable": info.is_reliable(),
+        }),
+        None => json!({
+            "code": null,
+            "iso639_3": null,
+            "name": null,
+            "script": null,
+            "confidence": 0.0,
+            "reliable": false,
+        }),
+    }
+}
+
+/// "French (fr)" for a known code; anything else as written ("pt-BR")
+pub fn language_label(language: &str) -> String {
+    let language = language.trim();
+    match parse_code(language) {
+        Some(lang) => format!("{} ({})", lang.eng_name(), short_code(lang)),
+        None => language.to_string(),
+    }
+}
+
+/// Glossary entries whose term occurs in `text` (case-insensitive)
+pub fn relevant_glossary<'a>(
+    text: &str,
+    glossary: &'a BTreeMap<String, String>,
+) -> Vec<(&'a str, &'a str)> {
+    let text = text.to_lowercase();
+    glossary
+        .iter()
+        .filter(|(term, _)| !term.is_empty() && text.contains(&term.to_lowercase()))
+        .map(|(term, rendering)| (term.as_str(), rendering.as_str()))
+        .collect()
+}
+
+/// The instructions sent for a `translate:` task
+pub fn translate_prompt(
+    text: &str,
+    to: &str,
+    from: Option<&str>,
+    glossary: &BTreeMap<String, String>,
+    style: Option<&str>,
+) -> String {
+    let mut prompt = match from {
+        Some(from) => format!(
+            "Translate the text between the <text> tags from {} to {}.",
+            language_label(from),
+            language_label(to)
+        ),
+        None => format!(
+            "Translate the text between the <text> tags to {}.",
+            language_label(to)
+        ),
+    };
+    if let Some(style) = style.map(str::trim).filter(|s| !s.is_empty()) {
+        prompt.push_str(&format!("\nStyle: {}", style));
+    }
+    let terms = relevant_glossary(text, glossary);
+    if !terms.is_empty() {
+        prompt.push_str("\nTranslate these terms exactly as given:");
+        for (term, rendering) in terms {
+            prompt.push_str(&format!("\n- {} → {}", term, rendering));
+        }
+    }
+    prompt.push_str(
+        "\nKeep the formatting (Markdown, line breaks), numbers, URLs, code and \
+         placeholders such as {name} or {{...}} unchanged. Reply with the \
+         translation only: no tags, quotes or comments.",
+    );
+    prompt.push_str(&format!("\n\n<text>\n{}\n</text>", text));
+    prompt
+}
+
+/// The model reply without surrounding whitespace or echoed `<text>` tags
+pub fn clean_translation(reply: &str) -> String {
+    let reply = reply.trim();
+    let reply = reply.strip_prefix("<text>").unwrap_or(reply);
+    let reply = reply.strip_suffix("</text>").unwrap_or(reply);
+    reply.trim().to_string()
+}
+
+/// Glossary terms of `text` whose rendering is missing from `translation`
+pub fn missing_glossary_terms<'a>(
+    text: &str,
+    translation: &str,
+    glossary: &'a BTreeMap<String, String>,
+) -> Vec<&'a str> {
+    let translation = translation.to_lowercase();
+    relevant_glossary(text, glossary)
+        .into_iter()
+        .filter(|(_, rendering)| !translation.contains(&rendering.to_lowercase()))
+        .map(|(term, _)| term)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_codes() {
+        let detector = detector(&[]).unwrap();
+        let out = detect(
+            &detector,
+            "Bonjour à tous, merci d'être venus aussi nombreux ce soir pour la réunion.",
+        );
+        assert_eq!(out["code"], "fr");
+        assert_eq!(out["iso639_3"], "fra");
+        assert_eq!(out["name"], "French");
+        assert_eq!(out["script"], "Latin");
+        assert_eq!(detect(&detector, "1234")["code"], Value::Null);
+
+        // An allowlist narrows the candidates
+        let detector = super::detector(&["en".to_string(), "deu".to_string()]).unwrap();
+        let out = detect(
+            &detector,
+            "Das ist ein kurzer Satz auf Deutsch, oder nicht?",
+        );
+        assert_eq!(out["code"], "de");
+        assert!(super::detector(&["xx".to_string()]).is_err());
+
+        assert_eq!(parse_code("ZH"), Some(Lang::Cmn));
+        assert_eq!(short_code(Lang::Nob), "nb");
+        assert_eq!(language_label("fra"), "French (fr)");
+        assert_eq!(language_label("pt-BR"), "pt-BR");
+    }
+
+    #[test]
+    fn test_translate_prompt_and_glossary() {
+        let glossary = BTreeMap::from([
+            ("Nika".to_string(), "Nika".to_string()),
+            ("workflow".to_string(), "flux de travail".to_string()),
+            ("invoice".to_string(), "facture".to_string()),
+        ]);
+        let text = "Run the Workflow with Nika.";
+        let prompt = translate_prompt(text, "fr", Some("en"), &glossary, Some("formal"));
+        assert!(prompt.starts_with(
+            "Translate the text between the <text> tags from English (en) to French (fr)."
+        ));
+        assert!(prompt.contains("\nStyle: formal"));
+        assert!(prompt.contains("- workflow → flux de travail"));
+        assert!(!prompt.contains("invoice"));
+        assert!(prompt.ends_with("<text>\nRun the Workflow with Nika.\n</text>"));
+
+        let prompt = translate_prompt(text, "Brazilian Portuguese", None, &BTreeMap::new(), None);
+        assert!(prompt.starts_with("Translate the text between the <text> tags to Brazilian"));
+        assert!(!prompt.contains("Style:") && !prompt.contains("terms"));
+
+        assert_eq!(clean_translation("<text>\nBonjour\n</text>\n"), "Bonjour");
+        assert_eq!(
+            missing_glossary_terms(text, "Lancez le workflow avec Nika.", &glossary),
+            vec!["workflow"]
+        );
+    }
+}
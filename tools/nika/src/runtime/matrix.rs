@@ -0,0 +1,191 @@
+//! Run Matrix - execute a workflow once per override combination (v0.7)
+//!
+//! `nika run flow.yaml --matrix model=claude-sonnet,gpt-4o --matrix tasks.draft.provider=claude,openai`
+//! expands to the cartesian product of the axes, applies each combination
+//! as `--set` overrides, and runs the variants with bounded concurrency.
+//! Each run is labeled with its combination (`RunLabeled` event) so the
+//! traces stay comparable after the fact.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::ast::schema_validator::WorkflowSchemaValidator;
+use crate::ast::{apply_overrides, Workflow};
+use crate::error::NikaError;
+use crate::event::EventKind;
+
+use super::{ApprovalGate, Runner};
+
+/// One combination of matrix values, as (override path, value) pairs
+pub type Combination = Vec<(String, String)>;
+
+/// Outcome of one matrix run
+#[derive(Debug, Clone)]
+pub struct MatrixRun {
+    pub combination: Combination,
+    /// Generation ID of the run (trace file name)
+    pub generation_id: String,
+    pub duration: Duration,
+    /// Summed `cost_usd` of all provider responses
+    pub cost_usd: f64,
+    /// Final output, or the error message if the run failed
+    pub output: Result<String, String>,
+}
+
+/// Expand `path=v1,v2` axes into every combination (cartesian product)
+pub fn expand_matrix(specs: &[String]) -> Result<Vec<Combination>, NikaError> {
+    let mut combinations: Vec<Combination> = vec![Vec::new()];
+
+    for spec in specs {
+        let (path, values) = spec
+            .split_once('=')
+            .ok_or_else(|| NikaError::InvalidOverride {
+                path: spec.clone(),
+                reason: "expected path=value1,value2".to_string(),
+            })?;
+        let values: Vec<&str> = values.split(',').map(str::trim).collect();
+        if path.trim().is_empty() || values.iter().any(|v| v.is_empty()) {
+            return Err(NikaError::InvalidOverride {
+                path: spec.clone(),
+                reason: "empty path or value".to_string(),
+            });
+        }
+
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combo| {
+                values.iter().map(move |value| {
+                    let mut next = combo.clone();
+                    next.push((path.trim().to_string(), value.to_string()));
+                    next
+                })
+            })
+            .collect();
+    }
+
+    Ok(combinations)
+}
+
+/// Run every matrix combination of a workflow (at most `concurrency` at once)
+///
+/// All variants are validated before any of them runs. Results come back in
+/// combination order; a failed run is reported, not propagated.
+pub async fn run_matrix(
+    yaml: &str,
+    overrides: &[String],
+    matrix: &[String],
+    concurrency: usize,
+) -> Result<Vec<MatrixRun>, NikaError> {
+    let validator = WorkflowSchemaValidator::new()?;
+    let mut variants = Vec::new();
+
+    for combination in expand_matrix(matrix)? {
+        let mut all = overrides.to_vec();
+        all.extend(combination.iter().map(|(p, v)| format!("{}={}", p, v)));
+        let patched = apply_overrides(yaml, &all)?;
+        validator.validate_yaml(&patched)?;
+        let workflow: Workflow = serde_yaml::from_str(&patched)?;
+        workflow.validate_schema()?;
+        variants.push((combination, workflow));
+    }
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut join_set = JoinSet::new();
+
+    for (idx, (combination, workflow)) in variants.into_iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        join_set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let labels: BTreeMap<String, String> = combination.iter().cloned().collect();
+            // Cells run concurrently, so approve: tasks take their default
+            let runner = Runner::new(workflow)
+                .quiet()
+                .with_labels(labels)
+                .with_approval_gate(ApprovalGate::non_interactive());
+
+            let start = Instant::now();
+            let output = runner.run().await.map_err(|e| e.to_string());
+            let cost_usd = runner
+                .event_log()
+                .events()
+                .iter()
+                .map(|e| match &e.kind {
+                    EventKind::ProviderResponded { cost_usd, .. } => *cost_usd,
+                    _ => 0.0,
+                })
+                .sum();
+
+            (
+                idx,
+                MatrixRun {
+                    combination,
+                    generation_id: runner.generation_id().to_string(),
+                    duration: start.elapsed(),
+                    cost_usd,
+                    output,
+                },
+            )
+        });
+    }
+
+    let mut runs: Vec<(usize, MatrixRun)> = join_set.join_all().await.into_iter().collect();
+    runs.sort_by_key(|(idx, _)| *idx);
+    Ok(runs.into_iter().map(|(_, run)| run).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_matrix_cartesian() {
+        let combos =
+            expand_matrix(&["model=a,b".to_string(), "tasks.t.provider=x, y".to_string()]).unwrap();
+        assert_eq!(combos.len(), 4);
+        assert_eq!(
+            combos[1],
+            vec![
+                ("model".to_string(), "a".to_string()),
+                ("tasks.t.provider".to_string(), "y".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_matrix_empty_is_single_run() {
+        assert_eq!(expand_matrix(&[]).unwrap(), vec![Vec::new()]);
+    }
+
+    #[test]
+    fn test_expand_matrix_rejects_bad_spec() {
+        let err = expand_matrix(&["model".to_string()]).unwrap_err();
+        assert_eq!(err.code(), "NIKA-006");
+        assert!(expand_matrix(&["model=a,".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_matrix_labels_each_run() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: greet
+    exec: "echo hello"
+"#;
+        let runs = run_matrix(
+            yaml,
+            &[],
+            &["tasks.greet.command=echo a,echo b".to_string()],
+            2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].output.as_deref(), Ok("a"));
+        assert_eq!(runs[1].output.as_deref(), Ok("b"));
+        assert_eq!(runs[1].combination[0].1, "echo b");
+    }
+}
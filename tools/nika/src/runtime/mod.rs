@@ -2,24 +2,101 @@
 //!
 //! Contains the runtime execution components:
 //! - `runner`: DAG execution with tokio concurrency
+//! - `agent`: Agent loop outcome and the mock provider's agent turn (v0.7)
+//! - `agent_context`: Isolated and shared conversations of `agent:` tasks (v0.7, `context:`)
+//! - `approval`: Approval gates and checkpoints for `approve:` tasks (v0.7)
+//! - `chunk`: Text splitting for `chunk:` tasks (v0.7)
+//! - `container`: Docker/podman drivers for `exec: { image }` (v0.7)
+//! - `context_pool`: Token budget shared by `agent:`/`infer:` tasks (v0.7, `context_pool:`)
+//! - `debugger`: Step-through debugger controller (v0.7, `nika debug`)
+//! - `dedupe`: MinHash and near-duplicate detection for `dedupe:` tasks (v0.7)
 //! - `executor`: Individual task execution (infer, exec, fetch, invoke, agent)
 //!   - Includes decompose: modifier expansion (v0.5 MVP 8 Phase 4)
+//! - `hedge`: Racing a second request for `infer: { hedge }` (v0.7)
+//! - `http`: Retries and pagination for `fetch:` (v0.7)
+//! - `lang`: Language detection and translation prompts for `detect_lang:`/`translate:` (v0.7)
+//! - `moderation`: Lexicon and provider classifiers for `moderate:` (v0.7)
 //! - `output`: Output format handling and schema validation
-//! - `rig_agent_loop`: Rig-based agentic execution (v0.3+)
-//! - `spawn`: Nested agent spawning (v0.5 MVP 8 Phase 2)
+//! - `policy_file`: Allow/deny rules for tool calls from `.nika/policy.yaml` (v0.7)
+//! - `preflight`: Provider and MCP probes before a run (v0.7, `preflight: true`)
+//! - `remote`: SSH hosts and streaming for `exec: { host }` (v0.7)
+//! - `rig_agent_loop`: Rig-based agentic execution (v0.3+, `providers` feature)
+//! - `rows`: Filter, group, sort and limit for `rows:` tasks (v0.7)
+//! - `sandbox`: Working dir, env and rlimit enforcement for `exec: { sandbox }` (v0.7)
+//! - `script`: Sandboxed Rhai engine for `script:` tasks (v0.7, `script` feature)
+//! - `scheduler`: Cron scheduler for `triggers:` workflows (v0.7, `nika schedule`)
+//! - `spawn`: Nested agent spawning (v0.5 MVP 8 Phase 2, `providers` feature)
+//! - `stamp`: Provenance metadata stamping for outputs (v0.7)
+//! - `subagent`: `spawn_subagent` tool for isolated subagents (v0.7, `providers` feature)
+//! - `validate`: Schema and rule checks for `validate:` tasks (v0.7)
+//! - `tool_policy`: Permission mode and auto-approval for agent tool calls (v0.7)
+//! - `trigger`: Watch triggers run by the daemon (v0.7, `nika daemon start --watch`)
+//! - `warm`: Provider sessions and MCP clients shared across runs (v0.7, `nika daemon`)
+//! - `ws`: WebSocket client for `ws:` tasks (v0.7, `http` feature)
 //!
 //! This module represents the "how" - runtime execution.
 //! For static structure, see the `ast` module.
 
+mod agent;
+mod agent_context;
+mod approval;
+mod chunk;
+mod container;
+mod context_pool;
+pub mod debugger;
+mod dedupe;
 mod executor;
+mod hedge;
+mod http;
+mod lang;
+mod matrix;
+mod moderation;
 mod output;
+mod policy_file;
+mod preflight;
+mod remote;
+mod render;
+#[cfg(feature = "providers")]
 mod rig_agent_loop;
+mod rows;
 mod runner;
+mod sandbox;
+pub mod scheduler;
+mod script;
+#[cfg(feature = "providers")]
 pub mod spawn;
+mod stamp;
+#[cfg(feature = "providers")]
+pub mod subagent;
+mod tool_policy;
+#[cfg(feature = "watch")]
+pub mod trigger;
+mod validate;
+mod warm;
+mod ws;
 
 // Re-export public types
+pub use agent::{RigAgentLoopResult, RigAgentStatus};
+pub use agent_context::{ContextTurn, IsolatedAgentRunner, SharedAgentRunner, SharedContext};
+pub use approval::{ApprovalCheckpoint, ApprovalGate, ApprovalRequest, PendingApproval};
+pub use context_pool::{ContextPool, PoolGrant};
+pub use debugger::{DebugCommand, DebugStop, Debugger, PausedTask};
 pub use executor::TaskExecutor;
+pub use matrix::{expand_matrix, run_matrix, Combination, MatrixRun};
+pub use moderation::{moderation_summary, ModerationSummary};
 pub use output::make_task_result;
-pub use rig_agent_loop::{RigAgentLoop, RigAgentLoopResult, RigAgentStatus};
+pub use policy_file::PolicyFile;
+pub use preflight::PreflightCheck;
+pub use remote::{HostConfig, Hosts};
+pub use render::{datastore_from_events, render_workflow, RenderedTask};
+#[cfg(feature = "providers")]
+pub use rig_agent_loop::RigAgentLoop;
 pub use runner::Runner;
+pub use scheduler::{ScheduleEvent, ScheduleState, Scheduler};
+#[cfg(feature = "providers")]
 pub use spawn::{SpawnAgentParams, SpawnAgentTool};
+pub use stamp::{stamp_output, Provenance};
+#[cfg(feature = "providers")]
+pub use subagent::SpawnSubagentTool;
+pub use tool_policy::{ToolPolicy, ToolVerdict};
+pub use warm::WarmResources;
@@ -0,0 +1,499 @@
+//! Content moderation for `moderate:` (v0.7)
+//!
+//! Scores every string in a value per category and applies the task's
+//! policy. Scores come from one of:
+//!
+//! - `local`: a small built-in lexicon; a hit scores its rule weight and
+//!   several hits in one category combine (`1 - Π(1 - w)`)
+//! - `openai` / `mistral`: the provider moderation endpoint, one request per
+//!   check with every string as a batch input
+//!
+//! Provider categories are folded onto [`CATEGORIES`] by taking the highest
+//! sub-score (`violence/graphic` counts as `violence`). Callers emit the
+//! `ModerationChecked` event and turn `block` into NIKA-066.
+
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+#[cfg(feature = "http")]
+use serde_json::json;
+use serde_json::Value;
+
+use super::http::Client;
+use crate::ast::{ModerateSpec, ModerationClassifier, ModerationPolicy, CATEGORIES};
+use crate::error::NikaError;
+use crate::event::{Event, EventKind};
+
+/// Replacement for redacted text
+const REDACTED: &str = "[redacted]";
+
+/// `(category, weight, pattern)` rules of the local classifier
+const LEXICON: &[(&str, f64, &str)] = &[
+    (
+        "hate",
+        0.9,
+        r"(?i)\b(ethnic\s+cleansing|subhuman|inferior\s+races?|exterminate\s+(all|the)\s+\w+)\b",
+    ),
+    (
+        "hate",
+        0.5,
+        r"(?i)\b(hate\s+(all|those)\s+\w+|go\s+back\s+to\s+your\s+country)\b",
+    ),
+    (
+        "harassment",
+        0.8,
+        r"(?i)\b(kill\s+yourself|nobody\s+(likes|wants)\s+you|i\s+will\s+find\s+(you|where\s+you\s+live))\b",
+    ),
+    (
+        "harassment",
+        0.5,
+        r"(?i)\byou\s*('re|\s+are)\s+(worthless|pathetic|disgusting|a\s+loser|an?\s+idiot)\b",
+    ),
+    (
+        "self_harm",
+        0.9,
+        r"(?i)\b(kill\s+myself|end\s+my\s+life|want\s+to\s+die|cut(ting)?\s+myself)\b",
+    ),
+    ("self_harm", 0.6, r"(?i)\b(suicid(e|al)|self[-\s]harm)\b"),
+    (
+        "sexual",
+        0.8,
+        r"(?i)\b(porn(ography|ographic)?|sexually\s+explicit|explicit\s+sex)\b",
+    ),
+    ("sexual", 0.5, r"(?i)\b(nsfw|nude|naked|erotic)\b"),
+    (
+        "violence",
+        0.9,
+        r"(?i)\b(i('ll|\s+will)\s+(kill|shoot|stab|hurt)\s+(you|him|her|them)|mass\s+shooting|behead(ed|ing)?)\b",
+    ),
+    (
+        "violence",
+        0.5,
+        r"(?i)\b(murder(ed|ing)?|massacre|stabb(ed|ing)|bloodbath)\b",
+    ),
+    (
+        "illicit",
+        0.9,
+        r"(?i)\bhow\s+to\s+(make|build|cook|synthesi[sz]e)\s+(a\s+)?(bomb|meth|explosives?|nerve\s+agent)\b",
+    ),
+    (
+        "illicit",
+        0.6,
+        r"(?i)\b(buy\s+(cocaine|heroin|meth|fentanyl)|launder(ing)?\s+money|stolen\s+credit\s+cards?)\b",
+    ),
+];
+
+static RULES: LazyLock<Vec<(&'static str, f64, Regex)>> = LazyLock::new(|| {
+    LEXICON
+        .iter()
+        .map(|(category, weight, pattern)| {
+            let regex = Regex::new(pattern).expect("built-in moderation rule compiles");
+            (*category, *weight, regex)
+        })
+        .collect()
+});
+
+/// Category scores of one string
+type Scores = BTreeMap<&'static str, f64>;
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Moderation {
+    /// Highest score per category across all strings
+    pub scores: BTreeMap<String, f64>,
+    /// Categories at or over their threshold, in [`CATEGORIES`] order
+    pub flagged: Vec<String>,
+    /// The value with flagged text replaced (`policy: redact` only)
+    pub redacted: Option<Value>,
+}
+
+impl Moderation {
+    /// Policy applied, or `pass` when nothing tripped
+    pub fn action(&self, spec: &ModerateSpec) -> &'static str {
+        if self.flagged.is_empty() {
+            "pass"
+        } else {
+            spec.policy.as_str()
+        }
+    }
+}
+
+/// Classify every string in `value` and apply `spec`
+pub async fn moderate(
+    spec: &ModerateSpec,
+    value: &Value,
+    client: &Client,
+) -> Result<Moderation, NikaError> {
+    let mut texts = Vec::new();
+    collect_strings(value, &mut texts);
+
+    let per_text: Vec<Scores> = match spec.classifier {
+        ModerationClassifier::Local => texts.iter().map(|t| local_scores(t)).collect(),
+        remote if texts.iter().any(|t| !t.trim().is_empty()) => {
+            remote_scores(remote, spec.model.as_deref(), &texts, client).await?
+        }
+        _ => vec![Scores::new(); texts.len()],
+    };
+
+    let flagged_per_text: Vec<Vec<&'static str>> = per_text
+        .iter()
+        .map(|scores| {
+            CATEGORIES
+                .iter()
+                .copied()
+                .filter(|c| scores.get(c).is_some_and(|s| *s >= spec.threshold(c)))
+                .collect()
+        })
+        .collect();
+
+    let mut scores = BTreeMap::new();
+    for text_scores in &per_text {
+        for (category, score) in text_scores {
+            let best = scores.entry(category.to_string()).or_insert(0.0_f64);
+            *best = best.max(*score);
+        }
+    }
+    let flagged: Vec<String> = CATEGORIES
+        .iter()
+        .filter(|c| flagged_per_text.iter().any(|f| f.contains(c)))
+        .map(|c| c.to_string())
+        .collect();
+
+    let redacted = (spec.policy == ModerationPolicy::Redact && !flagged.is_empty()).then(|| {
+        let mut next = 0;
+        redact_value(value, &mut |text| {
+            let categories = &flagged_per_text[next];
+            next += 1;
+            redact_text(text, categories, spec.classifier)
+        })
+    });
+
+    Ok(Moderation {
+        scores,
+        flagged,
+        redacted,
+    })
+}
+
+/// Score one string with the built-in lexicon
+pub fn local_scores(text: &str) -> Scores {
+    let mut misses: BTreeMap<&'static str, f64> = BTreeMap::new();
+    for (category, weight, regex) in RULES.iter() {
+        for _ in regex.find_iter(text) {
+            *misses.entry(*category).or_insert(1.0) *= 1.0 - weight;
+        }
+    }
+    misses
+        .into_iter()
+        .map(|(category, miss)| (category, 1.0 - miss))
+        .collect()
+}
+
+#[cfg(feature = "http")]
+async fn remote_scores(
+    classifier: ModerationClassifier,
+    model: Option<&str>,
+    texts: &[String],
+    client: &Client,
+) -> Result<Vec<Scores>, NikaError> {
+    let (url, key_var, default_model) = match classifier {
+        ModerationClassifier::Mistral => (
+            "https://api.mistral.ai/v1/moderations",
+            "MISTRAL_API_KEY",
+            "mistral-moderation-latest",
+        ),
+        _ => (
+            "https://api.openai.com/v1/moderations",
+            "OPENAI_API_KEY",
+            "omni-moderation-latest",
+        ),
+    };
+    let key = std::env::var(key_var)
+        .ok()
+        .filter(|k| !k.is_empty())
+        .ok_or_else(|| NikaError::MissingApiKey {
+            provider: classifier.as_str().to_string(),
+        })?;
+    let provider_error = |message: String| NikaError::ProviderApiError {
+        message: format!("{} moderation: {}", classifier.as_str(), message),
+    };
+
+    let response = client
+        .post(url)
+        .bearer_auth(key)
+        .json(&json!({ "model": model.unwrap_or(default_model), "input": texts }))
+        .send()
+        .await
+        .map_err(|e| provider_error(e.to_string()))?;
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| provider_error(e.to_string()))?;
+    if !status.is_success() {
+        return Err(provider_error(format!("HTTP {}: {}", status, body)));
+    }
+    parse_results(&body, texts.len()).map_err(provider_error)
+}
+
+/// Built without the `http` feature: only `local` can classify
+#[cfg(not(feature = "http"))]
+async fn remote_scores(
+    classifier: ModerationClassifier,
+    _model: Option<&str>,
+    _texts: &[String],
+    _client: &Client,
+) -> Result<Vec<Scores>, NikaError> {
+    Err(NikaError::ProviderApiError {
+        message: format!(
+            "{} moderation: nika was built without the `http` feature",
+            classifier.as_str()
+        ),
+    })
+}
+
+/// Read `results[].category_scores` from an OpenAI or Mistral response
+#[cfg_attr(not(feature = "http"), allow(dead_code))]
+fn parse_results(body: &Value, expected: usize) -> Result<Vec<Scores>, String> {
+    let results = body
+        .get("results")
+        .and_then(Value::as_array)
+        .ok_or("response has no results array")?;
+    if results.len() != expected {
+        return Err(format!(
+            "expected {} results, got {}",
+            expected,
+            results.len()
+        ));
+    }
+    results
+        .iter()
+        .map(|result| {
+            let raw = result
+                .get("category_scores")
+                .and_then(Value::as_object)
+                .ok_or("result has no category_scores")?;
+            let mut scores = Scores::new();
+            for (name, score) in raw {
+                let (Some(category), Some(score)) = (category_of(name), score.as_f64()) else {
+                    continue;
+                };
+                let best = scores.entry(category).or_insert(0.0);
+                *best = best.max(score);
+            }
+            Ok(scores)
+        })
+        .collect()
+}
+
+/// Fold a provider category name onto one of [`CATEGORIES`]
+#[cfg_attr(not(feature = "http"), allow(dead_code))]
+fn category_of(name: &str) -> Option<&'static str> {
+    let base = name.split('/').next().unwrap_or(name);
+    match base {
+        "hate" | "hate_and_discrimination" => Some("hate"),
+        "harassment" => Some("harassment"),
+        "self-harm" | "selfharm" => Some("self_harm"),
+        "sexual" => Some("sexual"),
+        "violence" | "violence_and_threats" => Some("violence"),
+        "illicit" | "dangerous_and_criminal_content" => Some("illicit"),
+        _ => None,
+    }
+}
+
+fn redact_text(
+    text: &str,
+    categories: &[&'static str],
+    classifier: ModerationClassifier,
+) -> String {
+    if categories.is_empty() {
+        return text.to_string();
+    }
+    if classifier != ModerationClassifier::Local {
+        // Providers score whole inputs, so the whole string goes
+        return REDACTED.to_string();
+    }
+    let mut text = text.to_string();
+    for (category, _, regex) in RULES.iter() {
+        if categories.contains(category) {
+            text = regex.replace_all(&text, REDACTED).into_owned();
+        }
+    }
+    text
+}
+
+fn collect_strings(value: &Value, out: &mut Vec<String>) {
+    match value {
+        Value::String(text) => out.push(text.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, out)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, out)),
+        _ => {}
+    }
+}
+
+/// Rebuild `value`, passing strings through `f` in [`collect_strings`] order
+fn redact_value(value: &Value, f: &mut impl FnMut(&str) -> String) -> Value {
+    match value {
+        Value::String(text) => Value::String(f(text)),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|item| redact_value(item, f)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), redact_value(v, f)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Moderation totals for the run summary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModerationSummary {
+    pub checks: usize,
+    pub flagged: usize,
+    pub blocked: usize,
+    pub redacted: usize,
+    /// Flagged checks per category
+    pub categories: BTreeMap<String, usize>,
+}
+
+/// Tally `ModerationChecked` events; None when no task was moderated
+pub fn moderation_summary(events: &[Event]) -> Option<ModerationSummary> {
+    let mut summary = ModerationSummary::default();
+    for event in events {
+        let EventKind::ModerationChecked {
+            flagged, action, ..
+        } = &event.kind
+        else {
+            continue;
+        };
+        summary.checks += 1;
+        if flagged.is_empty() {
+            continue;
+        }
+        summary.flagged += 1;
+        match action.as_str() {
+            "block" => summary.blocked += 1,
+            "redact" => summary.redacted += 1,
+            _ => {}
+        }
+        for category in flagged {
+            *summary.categories.entry(category.clone()).or_insert(0) += 1;
+        }
+    }
+    (summary.checks > 0).then_some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::ModerationStage;
+    use serde_json::json;
+
+    fn spec(policy: ModerationPolicy) -> ModerateSpec {
+        ModerateSpec {
+            on: vec![ModerationStage::Output],
+            policy,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn local_classifier_flags_and_redacts() {
+        let client = crate::runtime::http::client();
+        let value = json!({
+            "title": "Weekly notes",
+            "body": "The film ends in a massacre. I'll kill you if you spoil it.",
+        });
+
+        let result = moderate(&spec(ModerationPolicy::Flag), &value, &client)
+            .await
+            .unwrap();
+        assert_eq!(result.flagged, ["violence"]);
+        assert!(result.scores["violence"] > 0.9);
+        assert!(result.redacted.is_none());
+
+        let result = moderate(&spec(ModerationPolicy::Redact), &value, &client)
+            .await
+            .unwrap();
+        let redacted = result.redacted.unwrap();
+        assert_eq!(redacted["title"], "Weekly notes");
+        assert_eq!(
+            redacted["body"],
+            "The film ends in a [redacted]. [redacted] if you spoil it."
+        );
+
+        let clean = moderate(
+            &spec(ModerationPolicy::Block),
+            &json!("A calm day."),
+            &client,
+        )
+        .await
+        .unwrap();
+        assert!(clean.flagged.is_empty());
+        assert_eq!(clean.action(&spec(ModerationPolicy::Block)), "pass");
+    }
+
+    #[test]
+    fn local_scores_combine_hits() {
+        // One weak hit scores 0.5, two combine to 0.75
+        assert_eq!(local_scores("a murder")["violence"], 0.5);
+        assert_eq!(local_scores("a murder, then a massacre")["violence"], 0.75);
+        assert!(local_scores("A quiet week in the garden").is_empty());
+    }
+
+    #[test]
+    fn parse_provider_results() {
+        let openai = json!({"results": [{
+            "flagged": true,
+            "category_scores": {
+                "violence": 0.2, "violence/graphic": 0.91,
+                "self-harm/intent": 0.4, "harassment": 0.01
+            }
+        }]});
+        let scores = &parse_results(&openai, 1).unwrap()[0];
+        assert_eq!(scores["violence"], 0.91);
+        assert_eq!(scores["self_harm"], 0.4);
+
+        let mistral = json!({"results": [{
+            "categories": {"pii": true},
+            "category_scores": {"hate_and_discrimination": 0.7, "pii": 0.99}
+        }]});
+        let scores = &parse_results(&mistral, 1).unwrap()[0];
+        assert_eq!(scores["hate"], 0.7);
+        assert_eq!(scores.len(), 1);
+
+        assert!(parse_results(&mistral, 2)
+            .unwrap_err()
+            .contains("expected 2"));
+    }
+
+    #[test]
+    fn summary_counts_checks() {
+        let checked = |flagged: &[&str], action: &str| Event {
+            id: 0,
+            timestamp_ms: 0,
+            kind: EventKind::ModerationChecked {
+                task_id: "t".into(),
+                stage: "output".to_string(),
+                classifier: "local".to_string(),
+                scores: BTreeMap::new(),
+                flagged: flagged.iter().map(|c| c.to_string()).collect(),
+                action: action.to_string(),
+            },
+        };
+        assert!(moderation_summary(&[]).is_none());
+        let summary = moderation_summary(&[
+            checked(&[], "pass"),
+            checked(&["violence"], "redact"),
+            checked(&["violence", "hate"], "block"),
+        ])
+        .unwrap();
+        assert_eq!(summary.checks, 3);
+        assert_eq!(summary.flagged, 2);
+        assert_eq!((summary.blocked, summary.redacted), (1, 1));
+        assert_eq!(summary.categories["violence"], 2);
+    }
+}
@@ -1,7 +1,8 @@
 //! Output Handling - task result processing (v0.1)
 //!
 //! Extracted from runner.rs for cleaner separation:
-//! - `make_task_result`: Convert raw output to TaskResult with format handling
+//! - `make_task_result`: Convert raw output to TaskResult, parsed by its codec
+//! - `apply_transform`: Run a task's `transform:` steps over raw output (v0.7)
 //! - `validate_schema`: Validate JSON output against JSON Schema (with caching)
 
 use std::sync::{Arc, LazyLock};
@@ -9,32 +10,49 @@ use std::sync::{Arc, LazyLock};
 use dashmap::DashMap;
 use serde_json::Value;
 
-use crate::ast::OutputFormat;
+use crate::ast::{TransformSpec, TransformStep};
+use crate::codec;
 use crate::error::NikaError;
 use crate::store::TaskResult;
+use crate::util::jsonpath;
 
 /// Global schema cache: path → parsed JSON schema
 /// Avoids re-reading and re-parsing schema files on repeated validations.
 static SCHEMA_CACHE: LazyLock<DashMap<Arc<str>, Arc<Value>>> = LazyLock::new(DashMap::new);
 
-/// Convert execution output to TaskResult, parsing as JSON if output format is json.
-/// Also validates against schema if declared.
+/// Convert execution output to TaskResult, parsing it with the codec named by
+/// the output format (json, yaml, csv, ...). Also validates against schema if
+/// declared.
+///
+/// `transform:` steps run first. When they end with text, the format codec
+/// parses it as usual; a structured result is stored as-is (still checked
+/// against the schema).
 pub async fn make_task_result(
     output: String,
     policy: Option<&crate::ast::OutputPolicy>,
+    transform: Option<&TransformSpec>,
     duration: std::time::Duration,
 ) -> TaskResult {
+    let output = match transform {
+        None => output,
+        Some(spec) => match apply_transform(output, spec) {
+            Err(e) => return TaskResult::failed(e.to_string(), duration),
+            Ok(Value::String(text)) => text,
+            Ok(value) => {
+                if let Some(schema_path) = policy.and_then(|p| p.schema.as_deref()) {
+                    if let Err(e) = validate_schema(&value, schema_path).await {
+                        return TaskResult::failed(e.to_string(), duration);
+                    }
+                }
+                return TaskResult::success(value, duration);
+            }
+        },
+    };
     if let Some(policy) = policy {
-        if policy.format == OutputFormat::Json {
-            // Parse as JSON
-            let json_value = match serde_json::from_str::<Value>(&output) {
+        if policy.format().is_structured() {
+            let json_value = match codec::parse_output(policy.format(), &output) {
                 Ok(v) => v,
-                Err(e) => {
-                    return TaskResult::failed(
-                        format!("NIKA-060: Invalid JSON output: {}", e),
-                        duration,
-                    );
-                }
+                Err(e) => return TaskResult::failed(e.to_string(), duration),
             };
 
             // Validate against schema if declared
@@ -50,10 +68,91 @@ pub async fn make_task_result(
     TaskResult::success_str(output, duration)
 }
 
+/// Run `transform:` steps over raw output (v0.7)
+///
+/// Each step gets the previous result. `regex` and `parse` read strings
+/// as-is and other values as JSON text. A failing step is reported with its
+/// position and kind.
+pub fn apply_transform(output: String, spec: &TransformSpec) -> Result<Value, NikaError> {
+    let mut value = Value::String(output);
+    for (i, step) in spec.steps.iter().enumerate() {
+        value = apply_step(value, step).map_err(|reason| NikaError::TransformFailed {
+            step: i + 1,
+            op: step.name().to_string(),
+            reason,
+        })?;
+    }
+    Ok(value)
+}
+
+fn apply_step(value: Value, step: &TransformStep) -> Result<Value, String> {
+    let text = |value: Value| match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    };
+    match step {
+        TransformStep::Extract(path) => {
+            let value = match value {
+                Value::String(s) => serde_json::from_str(s.trim())
+                    .map_err(|e| format!("input is not JSON: {}", e))?,
+                other => other,
+            };
+            jsonpath::resolve(&value, path)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("'{}' matched nothing", path))
+        }
+        TransformStep::Regex(pattern) => {
+            let re = regex::Regex::new(pattern).map_err(|e| e.to_string())?;
+            let input = text(value);
+            let caps = re
+                .captures(&input)
+                .ok_or_else(|| format!("no match for '{}'", pattern))?;
+            let names: Vec<&str> = re.capture_names().flatten().collect();
+            Ok(if !names.is_empty() {
+                names
+                    .into_iter()
+                    .map(|name| {
+                        let group = caps.name(name).map(|m| m.as_str().into());
+                        (name.to_string(), group.unwrap_or(Value::Null))
+                    })
+                    .collect::<serde_json::Map<_, _>>()
+                    .into()
+            } else {
+                let m = caps.get(1).or_else(|| caps.get(0)).expect("match");
+                Value::String(m.as_str().to_string())
+            })
+        }
+        TransformStep::Parse(format) => {
+            codec::parse_output(format, text(value).trim()).map_err(|e| e.to_string())
+        }
+    }
+}
+
 /// Validate JSON value against a JSON Schema file (with caching)
 ///
 /// Schema files are cached after first load to avoid repeated file I/O.
 pub async fn validate_schema(value: &Value, schema_path: &str) -> Result<(), NikaError> {
+    let schema = load_schema(schema_path).await?;
+
+    // Compile and validate (compilation is fast, validation needs fresh instance)
+    let compiled = jsonschema::validator_for(&schema).map_err(|e| NikaError::SchemaFailed {
+        details: format!("Invalid schema '{}': {}", schema_path, e),
+    })?;
+
+    // Collect all validation errors
+    let errors: Vec<_> = compiled.iter_errors(value).collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        let error_msgs: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        Err(NikaError::SchemaFailed {
+            details: error_msgs.join("; "),
+        })
+    }
+}
+
+/// Read and parse a JSON Schema file, from the cache after the first load
+pub(crate) async fn load_schema(schema_path: &str) -> Result<Arc<Value>, NikaError> {
     // Try cache first (fast path)
     let schema = if let Some(cached) = SCHEMA_CACHE.get(schema_path) {
         Arc::clone(cached.value())
@@ -76,27 +175,13 @@ pub async fn validate_schema(value: &Value, schema_path: &str) -> Result<(), Nik
         SCHEMA_CACHE.insert(Arc::from(schema_path), Arc::clone(&schema));
         schema
     };
-
-    // Compile and validate (compilation is fast, validation needs fresh instance)
-    let compiled = jsonschema::validator_for(&schema).map_err(|e| NikaError::SchemaFailed {
-        details: format!("Invalid schema '{}': {}", schema_path, e),
-    })?;
-
-    // Collect all validation errors
-    let errors: Vec<_> = compiled.iter_errors(value).collect();
-    if errors.is_empty() {
-        Ok(())
-    } else {
-        let error_msgs: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
-        Err(NikaError::SchemaFailed {
-            details: error_msgs.join("; "),
-        })
-    }
+    Ok(schema)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ast::OutputFormat;
     use std::io::Write;
     use std::time::Duration;
     use tempfile::NamedTempFile;
@@ -147,14 +232,16 @@ mod tests {
         let schema_path = schema_file.path().to_string_lossy().to_string();
 
         let policy = OutputPolicy {
-            format: OutputFormat::Json,
+            format: Some(OutputFormat::Json),
             schema: Some(schema_path),
+            ..Default::default()
         };
 
         // Valid JSON object
         let result = make_task_result(
             r#"{"key": "value"}"#.to_string(),
             Some(&policy),
+            None,
             Duration::from_millis(100),
         )
         .await;
@@ -164,6 +251,7 @@ mod tests {
         let result = make_task_result(
             "not json".to_string(),
             Some(&policy),
+            None,
             Duration::from_millis(100),
         )
         .await;
@@ -179,6 +267,7 @@ mod tests {
         let result = make_task_result(
             "plain text output".to_string(),
             None,
+            None,
             Duration::from_millis(50),
         )
         .await;
@@ -196,13 +285,15 @@ mod tests {
         use crate::ast::OutputPolicy;
 
         let policy = OutputPolicy {
-            format: OutputFormat::Json,
+            format: Some(OutputFormat::Json),
             schema: None, // No schema validation
+            ..Default::default()
         };
 
         let result = make_task_result(
             r#"{"key": "value", "nested": {"a": 1}}"#.to_string(),
             Some(&policy),
+            None,
             Duration::from_millis(50),
         )
         .await;
@@ -219,13 +310,15 @@ mod tests {
         use crate::ast::OutputPolicy;
 
         let policy = OutputPolicy {
-            format: OutputFormat::Json,
+            format: Some(OutputFormat::Json),
             schema: None,
+            ..Default::default()
         };
 
         let result = make_task_result(
             "{ invalid json".to_string(),
             Some(&policy),
+            None,
             Duration::from_millis(50),
         )
         .await;
@@ -245,14 +338,16 @@ mod tests {
         use crate::ast::OutputPolicy;
 
         let policy = OutputPolicy {
-            format: OutputFormat::Text,
+            format: Some(OutputFormat::Text),
             schema: None,
+            ..Default::default()
         };
 
         // Even valid JSON should be treated as text
         let result = make_task_result(
             r#"{"key": "value"}"#.to_string(),
             Some(&policy),
+            None,
             Duration::from_millis(50),
         )
         .await;
@@ -266,6 +361,54 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn make_task_result_csv_is_parsed_then_validated() {
+        use crate::ast::OutputPolicy;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let schema_path = dir.path().join("rows.json");
+        std::fs::write(
+            &schema_path,
+            r#"{"type": "array", "items": {"required": ["name", "stars"]}}"#,
+        )
+        .unwrap();
+        let policy = OutputPolicy {
+            format: Some(OutputFormat::Csv),
+            schema: Some(schema_path.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+
+        let result = make_task_result(
+            "name,stars\nnika,5\n".to_string(),
+            Some(&policy),
+            None,
+            Duration::from_millis(5),
+        )
+        .await;
+        assert_eq!(
+            result.output.as_ref(),
+            &serde_json::json!([{"name": "nika", "stars": 5}])
+        );
+
+        let result = make_task_result(
+            "name\nnika\n".to_string(),
+            Some(&policy),
+            None,
+            Duration::from_millis(5),
+        )
+        .await;
+        assert!(result.error().unwrap().contains("NIKA-061"));
+
+        let result = make_task_result(
+            "name,stars\n\"nika,5\n".to_string(),
+            Some(&policy),
+            None,
+            Duration::from_millis(5),
+        )
+        .await;
+        assert!(result.error().unwrap().contains("NIKA-063"));
+    }
+
     // ══════════════════════════════════════════════════════════════
     // validate_schema ERROR PATHS
     // ══════════════════════════════════════════════════════════════
@@ -365,15 +508,17 @@ mod tests {
         use crate::ast::OutputPolicy;
 
         let policy = OutputPolicy {
-            format: OutputFormat::Json,
+            format: Some(OutputFormat::Json),
             schema: None,
+            ..Default::default()
         };
 
         // Generate large JSON array
         let large_array: Vec<i32> = (0..10000).collect();
         let json_str = serde_json::to_string(&large_array).unwrap();
 
-        let result = make_task_result(json_str, Some(&policy), Duration::from_millis(100)).await;
+        let result =
+            make_task_result(json_str, Some(&policy), None, Duration::from_millis(100)).await;
 
         assert!(result.is_success());
         assert!(result.output.is_array());
@@ -385,8 +530,9 @@ mod tests {
         use crate::ast::OutputPolicy;
 
         let policy = OutputPolicy {
-            format: OutputFormat::Json,
+            format: Some(OutputFormat::Json),
             schema: None,
+            ..Default::default()
         };
 
         // JSON with various Unicode characters
@@ -395,6 +541,7 @@ mod tests {
         let result = make_task_result(
             json_str.to_string(),
             Some(&policy),
+            None,
             Duration::from_millis(50),
         )
         .await;
@@ -433,7 +580,7 @@ mod tests {
     #[tokio::test]
     async fn make_task_result_preserves_duration() {
         let duration = Duration::from_secs(5);
-        let result = make_task_result("output".to_string(), None, duration).await;
+        let result = make_task_result("output".to_string(), None, None, duration).await;
 
         assert_eq!(result.duration, duration);
     }
@@ -443,13 +590,15 @@ mod tests {
         use crate::ast::OutputPolicy;
 
         let policy = OutputPolicy {
-            format: OutputFormat::Json,
+            format: Some(OutputFormat::Json),
             schema: None,
+            ..Default::default()
         };
 
         let result = make_task_result(
             r#"[1, 2, 3, "four"]"#.to_string(),
             Some(&policy),
+            None,
             Duration::from_millis(50),
         )
         .await;
@@ -460,4 +609,92 @@ mod tests {
         assert_eq!(arr.len(), 4);
         assert_eq!(arr[3], "four");
     }
+
+    // ══════════════════════════════════════════════════════════════
+    // transform: PIPELINE (v0.7)
+    // ══════════════════════════════════════════════════════════════
+
+    fn spec(yaml: &str) -> TransformSpec {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn apply_transform_runs_steps_in_order() {
+        let envelope =
+            r#"{"choices": [{"message": {"content": "Sure!\n```json\n{\"score\": 7}\n```"}}]}"#;
+        let out = apply_transform(
+            envelope.to_string(),
+            &spec("extract: $.choices[0].message.content\nregex: '(?s)```json\\s*(.*?)```'\nparse: json"),
+        )
+        .unwrap();
+        assert_eq!(out, serde_json::json!({"score": 7}));
+
+        // Named groups become an object; no groups keep the whole match
+        let out = apply_transform(
+            "Order 42 shipped to Lyon".to_string(),
+            &spec("regex: 'Order (?P<id>\\d+) shipped to (?P<city>\\w+)'"),
+        )
+        .unwrap();
+        assert_eq!(out, serde_json::json!({"id": "42", "city": "Lyon"}));
+        let out = apply_transform("a 12 b".to_string(), &spec("regex: '\\d+'")).unwrap();
+        assert_eq!(out, "12");
+    }
+
+    #[test]
+    fn apply_transform_reports_failing_step() {
+        let err = apply_transform(
+            r#"{"a": 1}"#.to_string(),
+            &spec("- extract: a\n- regex: 'id: (\\d+)'"),
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), "NIKA-065");
+        assert!(err.to_string().contains("step 2 (regex)"), "{}", err);
+
+        let err = apply_transform("plain".to_string(), &spec("extract: a")).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("step 1 (extract) failed: input is not JSON"));
+        let err = apply_transform("{}".to_string(), &spec("extract: a")).unwrap_err();
+        assert!(err.to_string().contains("'a' matched nothing"));
+    }
+
+    #[tokio::test]
+    async fn make_task_result_transforms_before_format() {
+        use crate::ast::OutputPolicy;
+
+        let policy = OutputPolicy {
+            format: Some(OutputFormat::Json),
+            schema: None,
+            ..Default::default()
+        };
+
+        // Text left by the steps is parsed by output.format
+        let result = make_task_result(
+            "Result: [1, 2]".to_string(),
+            Some(&policy),
+            Some(&spec("regex: 'Result: (.*)'")),
+            Duration::from_millis(5),
+        )
+        .await;
+        assert_eq!(result.output.as_ref(), &serde_json::json!([1, 2]));
+
+        // A structured result is stored as-is
+        let result = make_task_result(
+            r#"{"data": {"items": [3]}}"#.to_string(),
+            None,
+            Some(&spec("extract: data.items")),
+            Duration::from_millis(5),
+        )
+        .await;
+        assert_eq!(result.output.as_ref(), &serde_json::json!([3]));
+
+        let result = make_task_result(
+            "nothing here".to_string(),
+            Some(&policy),
+            Some(&spec("regex: 'Result: (.*)'")),
+            Duration::from_millis(5),
+        )
+        .await;
+        assert!(result.error().unwrap().contains("NIKA-065"));
+    }
 }
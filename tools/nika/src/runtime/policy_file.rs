@@ -0,0 +1,508 @@
+//! Policy file - allow/deny rules for tool calls (v0.7)
+//!
+//! `.nika/policy.yaml` blocks agent tool calls and `exec:` commands by
+//! tool name glob, shell command regex, path glob and URL host glob, for
+//! the whole project, per task id, per workflow (its `name:`) or per task
+//! of a workflow:
+//!
+//! ```yaml
+//! tools:
+//!   deny: ["github_delete_*"]         # built-in and MCP tool names
+//! commands:
+//!   deny: ['rm\s+-rf', 'curl .*\|\s*sh']
+//! paths:
+//!   deny: ["**/.env*"]
+//! domains:
+//!   allow: ["docs.rs", "*.wikipedia.org"]  # read_url and other url args
+//! tasks:
+//!   publish:
+//!     tools:
+//!       allow: ["novanet_*", "read"]  # anything else is blocked
+//! workflows:
+//!   release-notes:
+//!     paths:
+//!       allow: ["drafts/**"]
+//!     tasks:
+//!       draft:
+//!         commands:
+//!           allow: ['^git (status|diff|log)\b']
+//! ```
+//!
+//! Every scope that applies must pass: a value matching a `deny` pattern,
+//! or missing from a non-empty `allow` list, is blocked. Commands are the
+//! `command`/`cmd` arguments of a tool call and `exec:` commands; paths are
+//! the `path`, `file_path`, `paths`, `directory` and `cwd` arguments plus
+//! the files named in a `patch` argument's diff headers, normalized (`a/../b` is `b`) before matching; domains are the lowercase
+//! hosts of the `url` and `urls` arguments.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::RegexSet;
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::NikaError;
+
+/// Tool arguments holding shell commands
+const COMMAND_ARGS: &[&str] = &["command", "cmd"];
+
+/// Tool arguments holding file paths
+const PATH_ARGS: &[&str] = &["path", "file_path", "paths", "directory", "cwd"];
+
+/// Tool arguments holding URLs
+const URL_ARGS: &[&str] = &["url", "urls"];
+
+/// Parsed `.nika/policy.yaml`
+#[derive(Debug, Clone, Default)]
+pub struct PolicyFile {
+    rules: Option<Rules>,
+    tasks: FxHashMap<String, Rules>,
+    workflows: FxHashMap<String, WorkflowRules>,
+}
+
+#[derive(Debug, Clone)]
+struct WorkflowRules {
+    rules: Option<Rules>,
+    tasks: FxHashMap<String, Rules>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileRaw {
+    #[serde(default)]
+    tools: ListRaw,
+    #[serde(default)]
+    commands: ListRaw,
+    #[serde(default)]
+    paths: ListRaw,
+    #[serde(default)]
+    domains: ListRaw,
+    #[serde(default)]
+    tasks: FxHashMap<String, RulesRaw>,
+    #[serde(default)]
+    workflows: FxHashMap<String, WorkflowRaw>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorkflowRaw {
+    #[serde(default)]
+    tools: ListRaw,
+    #[serde(default)]
+    commands: ListRaw,
+    #[serde(default)]
+    paths: ListRaw,
+    #[serde(default)]
+    domains: ListRaw,
+    #[serde(default)]
+    tasks: FxHashMap<String, RulesRaw>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesRaw {
+    #[serde(default)]
+    tools: ListRaw,
+    #[serde(default)]
+    commands: ListRaw,
+    #[serde(default)]
+    paths: ListRaw,
+    #[serde(default)]
+    domains: ListRaw,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListRaw {
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+impl PolicyFile {
+    /// Parse a policy file
+    pub fn from_yaml(content: &str) -> Result<Self, NikaError> {
+        // An empty file (or only comments) is a null document
+        let raw: FileRaw = serde_yaml::from_str::<Option<FileRaw>>(content)
+            .map_err(|e| NikaError::ConfigError {
+                reason: format!("Invalid .nika/policy.yaml: {}", e),
+            })?
+            .unwrap_or_default();
+        let tasks = |tasks: FxHashMap<String, RulesRaw>, prefix: &str| {
+            tasks
+                .into_iter()
+                .filter_map(|(id, raw)| {
+                    Rules::compile(raw, &format!("{}tasks.{}.", prefix, id))
+                        .transpose()
+                        .map(|rules| rules.map(|rules| (id, rules)))
+                })
+                .collect::<Result<FxHashMap<_, _>, NikaError>>()
+        };
+        let mut workflows: FxHashMap<String, WorkflowRules> = raw
+            .workflows
+            .into_iter()
+            .map(|(name, raw)| {
+                let prefix = format!("workflows.{}.", name);
+                let rules = RulesRaw {
+                    tools: raw.tools,
+                    commands: raw.commands,
+                    paths: raw.paths,
+                    domains: raw.domains,
+                };
+                let rules = WorkflowRules {
+                    rules: Rules::compile(rules, &prefix)?,
+                    tasks: tasks(raw.tasks, &prefix)?,
+                };
+                Ok((name, rules))
+            })
+            .collect::<Result<_, NikaError>>()?;
+        workflows.retain(|_, w| w.rules.is_some() || !w.tasks.is_empty());
+        let rules = RulesRaw {
+            tools: raw.tools,
+            commands: raw.commands,
+            paths: raw.paths,
+            domains: raw.domains,
+        };
+        Ok(Self {
+            rules: Rules::compile(rules, "")?,
+            tasks: tasks(raw.tasks, "")?,
+            workflows,
+        })
+    }
+
+    /// Find `.nika/policy.yaml` in `start` or its ancestors
+    ///
+    /// Without one, nothing is blocked.
+    pub fn discover(start: &Path) -> Result<Self, NikaError> {
+        let found = start
+            .ancestors()
+            .map(|dir| dir.join(".nika").join("policy.yaml"))
+            .find(|path| path.is_file());
+        match found {
+            Some(path) => Self::from_yaml(&fs::read_to_string(path)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Whether the file has no rules at all
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_none() && self.tasks.is_empty() && self.workflows.is_empty()
+    }
+
+    /// The rule blocking a call to `tool` with JSON `args`, if any
+    pub fn check_tool(
+        &self,
+        workflow: Option<&str>,
+        task: &str,
+        tool: &str,
+        args: &str,
+    ) -> Option<String> {
+        let args: Value = serde_json::from_str(args).unwrap_or(Value::Null);
+        let commands = string_args(&args, COMMAND_ARGS);
+        let patched = string_args(&args, &["patch"])
+            .into_iter()
+            .flat_map(crate::tools::patch_paths);
+        let paths: Vec<String> = string_args(&args, PATH_ARGS)
+            .into_iter()
+            .map(normalize)
+            .chain(patched.map(|p| normalize(&p)))
+            .collect();
+        let hosts: Vec<String> = string_args(&args, URL_ARGS).into_iter().map(host).collect();
+        self.scopes(workflow, task).find_map(|rules| {
+            rules
+                .tools
+                .check(tool)
+                .or_else(|| commands.iter().find_map(|c| rules.commands.check(c)))
+                .or_else(|| paths.iter().find_map(|p| rules.paths.check(p)))
+                .or_else(|| hosts.iter().find_map(|h| rules.domains.check(h)))
+        })
+    }
+
+    /// The rule blocking a request to `url`, if any (e.g. a redirect hop)
+    pub fn check_url(&self, workflow: Option<&str>, task: &str, url: &str) -> Option<String> {
+        let host = host(url);
+        self.scopes(workflow, task)
+            .find_map(|rules| rules.domains.check(&host))
+    }
+
+    /// The rule blocking an `exec:` command, if any
+    pub fn check_command(
+        &self,
+        workflow: Option<&str>,
+        task: &str,
+        command: &str,
+    ) -> Option<String> {
+        self.scopes(workflow, task)
+            .find_map(|rules| rules.commands.check(command))
+    }
+
+    /// Rules applying to `task` of `workflow`, broadest first
+    fn scopes<'a>(&'a self, workflow: Option<&str>, task: &str) -> impl Iterator<Item = &'a Rules> {
+        let workflow = workflow.and_then(|name| self.workflows.get(name));
+        self.rules
+            .iter()
+            .chain(self.tasks.get(task))
+            .chain(workflow.and_then(|w| w.rules.as_ref()))
+            .chain(workflow.and_then(|w| w.tasks.get(task)))
+    }
+}
+
+/// Allow/deny lists of one scope
+#[derive(Debug, Clone)]
+struct Rules {
+    tools: Rule,
+    commands: Rule,
+    paths: Rule,
+    domains: Rule,
+}
+
+impl Rules {
+    /// Compile a scope; None when it has no patterns
+    fn compile(raw: RulesRaw, prefix: &str) -> Result<Option<Self>, NikaError> {
+        let rules = Self {
+            tools: Rule::compile(raw.tools, &format!("{}tools", prefix), Matcher::globs)?,
+            commands: Rule::compile(
+                raw.commands,
+                &format!("{}commands", prefix),
+                Matcher::regexes,
+            )?,
+            paths: Rule::compile(raw.paths, &format!("{}paths", prefix), Matcher::globs)?,
+            domains: Rule::compile(raw.domains, &format!("{}domains", prefix), Matcher::globs)?,
+        };
+        let empty = [&rules.tools, &rules.commands, &rules.paths, &rules.domains]
+            .iter()
+            .all(|rule| rule.allow.is_none() && rule.deny.is_none());
+        Ok((!empty).then_some(rules))
+    }
+}
+
+/// One allow/deny pair, e.g. `tasks.publish.tools`
+#[derive(Debug, Clone)]
+struct Rule {
+    /// Dotted location in the file, for audit events
+    name: String,
+    allow: Option<Matcher>,
+    deny: Option<Matcher>,
+}
+
+impl Rule {
+    fn compile(
+        raw: ListRaw,
+        name: &str,
+        build: fn(&[String]) -> Result<Matcher, String>,
+    ) -> Result<Self, NikaError> {
+        let list = |patterns: &[String], kind: &str| -> Result<Option<Matcher>, NikaError> {
+            if patterns.is_empty() {
+                return Ok(None);
+            }
+            build(patterns)
+                .map(Some)
+                .map_err(|e| NikaError::ConfigError {
+                    reason: format!("Invalid {}.{} in .nika/policy.yaml: {}", name, kind, e),
+                })
+        };
+        Ok(Self {
+            name: name.to_string(),
+            allow: list(&raw.allow, "allow")?,
+            deny: list(&raw.deny, "deny")?,
+        })
+    }
+
+    /// Why `value` is blocked, if it is
+    fn check(&self, value: &str) -> Option<String> {
+        if let Some(pattern) = self.deny.as_ref().and_then(|deny| deny.find(value)) {
+            return Some(format!(
+                "{}.deny '{}' matches '{}'",
+                self.name, pattern, value
+            ));
+        }
+        match &self.allow {
+            Some(allow) if allow.find(value).is_none() => {
+                Some(format!("'{}' is not in {}.allow", value, self.name))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Compiled patterns, keeping the source text for reports
+#[derive(Debug, Clone)]
+struct Matcher {
+    patterns: Vec<String>,
+    set: PatternSet,
+}
+
+#[derive(Debug, Clone)]
+enum PatternSet {
+    Globs(GlobSet),
+    Regexes(RegexSet),
+}
+
+impl Matcher {
+    fn globs(patterns: &[String]) -> Result<Self, String> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).map_err(|e| e.to_string())?);
+        }
+        Ok(Self {
+            patterns: patterns.to_vec(),
+            set: PatternSet::Globs(builder.build().map_err(|e| e.to_string())?),
+        })
+    }
+
+    fn regexes(patterns: &[String]) -> Result<Self, String> {
+        Ok(Self {
+            patterns: patterns.to_vec(),
+            set: PatternSet::Regexes(RegexSet::new(patterns).map_err(|e| e.to_string())?),
+        })
+    }
+
+    /// First pattern matching `value`
+    fn find(&self, value: &str) -> Option<&str> {
+        let index = match &self.set {
+            PatternSet::Globs(set) => set.matches(value).into_iter().next(),
+            PatternSet::Regexes(set) => set.matches(value).into_iter().next(),
+        }?;
+        Some(&self.patterns[index])
+    }
+}
+
+/// String values (or arrays of strings) of the given top-level arguments
+fn string_args<'a>(args: &'a Value, keys: &[&str]) -> Vec<&'a str> {
+    keys.iter()
+        .filter_map(|key| args.get(key))
+        .flat_map(|value| match value {
+            Value::String(s) => vec![s.as_str()],
+            Value::Array(items) => items.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+/// Lowercase host of a URL (the whole value when it doesn't parse)
+fn host(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+        .to_lowercase()
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize(path: &str) -> String {
+    let mut out = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"
+tools:
+  deny: ["github_delete_*"]
+commands:
+  deny: ['rm\s+-rf']
+paths:
+  deny: ["**/.env*"]
+domains:
+  deny: ["*.internal"]
+tasks:
+  publish:
+    tools:
+      allow: ["novanet_*", "read"]
+workflows:
+  release-notes:
+    tasks:
+      draft:
+        paths:
+          allow: ["drafts/**"]
+  research:
+    domains:
+      allow: ["docs.rs", "*.wikipedia.org"]
+"#;
+
+    #[test]
+    fn test_blocks_by_scope() {
+        let policy = PolicyFile::from_yaml(POLICY).unwrap();
+        assert!(!policy.is_empty());
+
+        let check = |workflow, task, tool, args| policy.check_tool(workflow, task, tool, args);
+        assert_eq!(check(None, "any", "novanet_search", "{}"), None);
+        assert_eq!(
+            check(None, "any", "github_delete_repo", "{}").unwrap(),
+            "tools.deny 'github_delete_*' matches 'github_delete_repo'"
+        );
+        assert!(check(None, "any", "shell", r#"{"command":"rm -rf /"}"#)
+            .unwrap()
+            .starts_with("commands.deny"));
+        assert!(check(None, "any", "read", r#"{"file_path":"src/../.env"}"#)
+            .unwrap()
+            .contains("'.env'"));
+        let patch =
+            r#"{"patch":"--- a/src/lib.rs\n+++ b/src/lib.rs\n--- /dev/null\n+++ b/.env\n"}"#;
+        assert!(check(None, "any", "apply_patch", patch)
+            .unwrap()
+            .contains("'.env'"));
+
+        // Task-level allow list
+        assert_eq!(check(None, "publish", "read", "{}"), None);
+        assert_eq!(
+            check(None, "publish", "write", "{}").unwrap(),
+            "'write' is not in tasks.publish.tools.allow"
+        );
+
+        // Workflow task scope only applies in that workflow
+        let args = r#"{"paths":["drafts/a.md","notes/b.md"]}"#;
+        assert_eq!(check(None, "draft", "write", args), None);
+        assert_eq!(
+            check(Some("release-notes"), "draft", "write", args).unwrap(),
+            "'notes/b.md' is not in workflows.release-notes.tasks.draft.paths.allow"
+        );
+
+        // Domains are URL hosts
+        let args = r#"{"url":"http://wiki.corp.internal/x"}"#;
+        assert_eq!(
+            check(None, "any", "read_url", args).unwrap(),
+            "domains.deny '*.internal' matches 'wiki.corp.internal'"
+        );
+        let research = |url| policy.check_url(Some("research"), "any", url);
+        assert_eq!(research("https://en.Wikipedia.org/wiki/Rust"), None);
+        assert_eq!(research("https://docs.rs/"), None);
+        assert_eq!(
+            research("https://example.com/").unwrap(),
+            "'example.com' is not in workflows.research.domains.allow"
+        );
+
+        assert!(policy
+            .check_command(None, "build", "rm  -rf target")
+            .is_some());
+        assert!(policy.check_command(None, "build", "cargo build").is_none());
+    }
+
+    #[test]
+    fn test_rejects_invalid_files() {
+        assert!(PolicyFile::from_yaml("").unwrap().is_empty());
+        assert!(PolicyFile::from_yaml("tools:\n  deny: []\n")
+            .unwrap()
+            .is_empty());
+        assert!(PolicyFile::from_yaml("commands:\n  deny: ['(']\n").is_err());
+        assert!(PolicyFile::from_yaml("tools:\n  block: [x]\n").is_err());
+        assert!(PolicyFile::from_yaml("tool:\n  deny: [x]\n").is_err());
+    }
+}
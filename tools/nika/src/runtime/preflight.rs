@@ -0,0 +1,118 @@
+//! Preflight - probing providers and MCP servers before a run (v0.7)
+//!
+//! With `preflight: true` the runner sends one tiny request (one output
+//! token) to each provider/model used by `infer:`, `agent:` and summarize
+//! `reduce:` tasks and connects each MCP server, all at once, before the
+//! first task. Each probe emits `PreflightChecked` with its latency; if any
+//! fails the run stops with `NIKA-039` listing every failure, instead of
+//! failing deep into a fan-out.
+//!
+//! Routed models (`model: auto`) are only known per prompt and are not probed.
+
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+
+use super::executor::TaskExecutor;
+use crate::error::NikaError;
+use crate::provider::SessionKey;
+
+/// Longest a single probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Result of one probe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightCheck {
+    /// `provider/model`, `provider` (default model) or `mcp:server`
+    pub target: String,
+    pub latency_ms: u64,
+    /// Why the probe failed (None: reachable and authorized)
+    pub error: Option<String>,
+}
+
+/// Probe all `providers` and `servers` concurrently, providers first
+pub async fn run_preflight(
+    executor: &TaskExecutor,
+    providers: &[SessionKey],
+    servers: &[String],
+) -> Vec<PreflightCheck> {
+    let providers = providers.iter().map(|key| {
+        let target = match &key.model {
+            Some(model) => format!("{}/{}", key.provider, model),
+            None => key.provider.clone(),
+        };
+        probe(target, executor.probe_provider(key))
+    });
+    let servers = servers
+        .iter()
+        .map(|server| probe(format!("mcp:{}", server), executor.probe_mcp(server)));
+    let (mut checks, servers) = futures::join!(join_all(providers), join_all(servers));
+    checks.extend(servers);
+    checks
+}
+
+async fn probe(
+    target: String,
+    call: impl std::future::Future<Output = Result<(), NikaError>>,
+) -> PreflightCheck {
+    let start = Instant::now();
+    let error = match tokio::time::timeout(PROBE_TIMEOUT, call).await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("no answer after {}s", PROBE_TIMEOUT.as_secs())),
+    };
+    PreflightCheck {
+        target,
+        latency_ms: start.elapsed().as_millis() as u64,
+        error,
+    }
+}
+
+/// `NIKA-039` listing the failed probes, if any
+pub fn check_results(checks: &[PreflightCheck]) -> Result<(), NikaError> {
+    let failed: Vec<String> = checks
+        .iter()
+        .filter_map(|check| {
+            let error = check.error.as_ref()?;
+            Some(format!("{}: {}", check.target, error))
+        })
+        .collect();
+    if failed.is_empty() {
+        return Ok(());
+    }
+    Err(NikaError::PreflightFailed {
+        failed: failed.len(),
+        checked: checks.len(),
+        report: failed.join("; "),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventLog;
+
+    #[tokio::test]
+    async fn test_reports_every_failed_target() {
+        let executor = TaskExecutor::new("mock", None, None, EventLog::new());
+        let providers = [
+            SessionKey::new("mock", None),
+            SessionKey::new("no-such-provider", Some("m1")),
+        ];
+        let servers = ["novanet".to_string()];
+
+        let checks = run_preflight(&executor, &providers, &servers).await;
+        let targets: Vec<&str> = checks.iter().map(|c| c.target.as_str()).collect();
+        assert_eq!(targets, ["mock", "no-such-provider/m1", "mcp:novanet"]);
+        assert!(checks[0].error.is_none());
+        assert!(checks[1].error.is_some());
+        assert!(checks[2].error.is_some());
+
+        let err = check_results(&checks).unwrap_err();
+        assert_eq!(err.code(), "NIKA-039");
+        let message = err.to_string();
+        assert!(message.contains("2 of 3"), "{}", message);
+        assert!(message.contains("mcp:novanet: "), "{}", message);
+        assert!(check_results(&checks[..1]).is_ok());
+    }
+}
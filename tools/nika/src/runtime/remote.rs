@@ -0,0 +1,261 @@
+//! SSH backend for `exec: { host }` (v0.7)
+//!
+//! Hosts are defined per project in `.nika/config.toml`:
+//!
+//! ```toml
+//! [hosts.build-server]
+//! address = "10.0.0.12"
+//! user = "deploy"                   # default: ssh's own default
+//! key = "~/.ssh/id_ed25519"         # default: ssh agent / ssh config
+//! port = 2222                       # default: 22
+//! ```
+//!
+//! Commands run through the system `ssh` client in batch mode, so host keys
+//! and `~/.ssh/config` apply as usual and a login that would prompt fails
+//! instead of hanging. Each line of output is emitted as an `ExecOutput`
+//! event while the command runs.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+use crate::config::find_project_config;
+use crate::error::NikaError;
+use crate::event::{EventKind, EventLog};
+
+/// Exit code of `ssh` when the connection or login failed
+pub const SSH_FAILURE: i32 = 255;
+/// Seconds `ssh` waits for the TCP connection
+const CONNECT_TIMEOUT_SECS: u32 = 10;
+
+/// One `[hosts.<name>]` entry
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HostConfig {
+    /// Hostname or IP address
+    pub address: String,
+    pub user: Option<String>,
+    /// Private key file; `~/` is expanded
+    pub key: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl HostConfig {
+    /// `user@address:port`, for messages
+    pub fn target(&self) -> String {
+        let mut target = match &self.user {
+            Some(user) => format!("{}@{}", user, self.address),
+            None => self.address.clone(),
+        };
+        if let Some(port) = self.port {
+            target.push_str(&format!(":{}", port));
+        }
+        target
+    }
+
+    /// Arguments after `ssh` that run `command` on this host
+    pub fn ssh_args(&self, command: &str) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "-o".into(),
+            "BatchMode=yes".into(),
+            "-o".into(),
+            format!("ConnectTimeout={}", CONNECT_TIMEOUT_SECS),
+        ];
+        if let Some(port) = self.port {
+            args.extend(["-p".into(), port.to_string()]);
+        }
+        if let Some(key) = &self.key {
+            args.extend([
+                "-i".into(),
+                expand_home(key).display().to_string(),
+                "-o".into(),
+                "IdentitiesOnly=yes".into(),
+            ]);
+        }
+        if let Some(user) = &self.user {
+            args.extend(["-l".into(), user.clone()]);
+        }
+        args.extend([self.address.clone(), "--".into(), command.into()]);
+        args
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// The `[hosts]` table of a project config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hosts {
+    hosts: BTreeMap<String, HostConfig>,
+}
+
+#[derive(Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    hosts: BTreeMap<String, HostConfig>,
+}
+
+impl Hosts {
+    /// Parse the `[hosts]` table of a project config
+    pub fn from_toml(content: &str) -> Result<Self, NikaError> {
+        let config: ProjectConfig =
+            toml::from_str(content).map_err(|e| NikaError::ConfigError {
+                reason: format!("Invalid [hosts] config: {}", e),
+            })?;
+        for (name, host) in &config.hosts {
+            if host.address.trim().is_empty() {
+                return Err(NikaError::ConfigError {
+                    reason: format!("Host '{}' has no address", name),
+                });
+            }
+        }
+        Ok(Self {
+            hosts: config.hosts,
+        })
+    }
+
+    /// Find `.nika/config.toml` in `start` or its ancestors
+    ///
+    /// Returns no hosts when there is none.
+    pub fn discover(start: &Path) -> Result<Self, NikaError> {
+        match find_project_config(start) {
+            Some(path) => Self::from_toml(&fs::read_to_string(path)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&HostConfig> {
+        self.hosts.get(name)
+    }
+}
+
+/// Output of a finished remote command
+#[derive(Debug)]
+pub struct RemoteOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run `command` on `host` over ssh, emitting each output line as it arrives
+pub async fn run(
+    event_log: &EventLog,
+    task_id: &Arc<str>,
+    name: &str,
+    host: &HostConfig,
+    command: &str,
+) -> std::io::Result<RemoteOutput> {
+    let mut child = tokio::process::Command::new("ssh")
+        .args(host.ssh_args(command))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let stderr = child.stderr.take().expect("stderr is piped");
+
+    let emit = |stream: &str, line: &str| {
+        event_log.emit(EventKind::ExecOutput {
+            task_id: Arc::clone(task_id),
+            host: name.to_string(),
+            stream: stream.to_string(),
+            line: line.to_string(),
+        });
+    };
+    let (stdout, stderr) = tokio::join!(
+        read_lines(stdout, |line| emit("stdout", line)),
+        read_lines(stderr, |line| emit("stderr", line)),
+    );
+    Ok(RemoteOutput {
+        status: child.wait().await?,
+        stdout: stdout?,
+        stderr: stderr?,
+    })
+}
+
+/// Collect a stream, calling `on_line` for each line
+async fn read_lines(
+    reader: impl AsyncRead + Unpin,
+    on_line: impl Fn(&str),
+) -> std::io::Result<String> {
+    let mut lines = BufReader::new(reader).lines();
+    let mut collected = String::new();
+    while let Some(line) = lines.next_line().await? {
+        on_line(&line);
+        collected.push_str(&line);
+        collected.push('\n');
+    }
+    Ok(collected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[lint]
+unused_alias = "off"
+
+[hosts.build-server]
+address = "10.0.0.12"
+user = "deploy"
+key = "/keys/deploy"
+port = 2222
+
+[hosts.gpu]
+address = "gpu.internal"
+"#;
+
+    #[test]
+    fn hosts_from_project_config() {
+        let hosts = Hosts::from_toml(CONFIG).unwrap();
+        let build = hosts.get("build-server").unwrap();
+        assert_eq!(build.target(), "deploy@10.0.0.12:2222");
+        assert_eq!(hosts.get("gpu").unwrap().target(), "gpu.internal");
+        assert!(hosts.get("missing").is_none());
+
+        let bad = "[hosts.x]\naddress = \"\"";
+        assert!(Hosts::from_toml(bad).is_err());
+        let typo = "[hosts.x]\naddress = \"a\"\nusr = \"me\"";
+        assert!(Hosts::from_toml(typo).is_err());
+    }
+
+    #[test]
+    fn ssh_args_for_host() {
+        let hosts = Hosts::from_toml(CONFIG).unwrap();
+        let line = hosts
+            .get("build-server")
+            .unwrap()
+            .ssh_args("make release")
+            .join(" ");
+        assert_eq!(
+            line,
+            "-o BatchMode=yes -o ConnectTimeout=10 -p 2222 -i /keys/deploy -o IdentitiesOnly=yes \
+             -l deploy 10.0.0.12 -- make release"
+        );
+        let args = hosts.get("gpu").unwrap().ssh_args("nvidia-smi");
+        assert_eq!(&args[4..], ["gpu.internal", "--", "nvidia-smi"]);
+    }
+
+    #[tokio::test]
+    async fn read_lines_reports_each_line() {
+        let seen = std::sync::Mutex::new(Vec::new());
+        let out = read_lines(&b"one\ntwo\n"[..], |line| {
+            seen.lock().unwrap().push(line.to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!(out, "one\ntwo\n");
+        assert_eq!(*seen.lock().unwrap(), ["one", "two"]);
+    }
+}
@@ -0,0 +1,423 @@
+//! Prompt Rendering - resolve task templates without executing (v0.7)
+//!
+//! Used by `nika check --render` to write every task's prompt, command,
+//! URL or params with its `use:` bindings filled in, so prompt changes can
+//! be reviewed as plain text in PRs.
+//!
+//! Binding values come from the given DataStore (e.g. outputs loaded from a
+//! past trace). Bindings that can't be resolved fall back to their `??`
+//! default, then to a `<task.path>` placeholder.
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::ast::{FetchParams, ReduceStrategy, TaskAction, Workflow};
+use crate::binding::{template_resolve_with_mode, ResolvedBindings, TemplateMode, WiringSpec};
+use crate::error::NikaError;
+use crate::event::{Event, EventKind};
+use crate::store::{DataStore, TaskResult};
+
+/// A task's templates rendered with sample values
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedTask {
+    /// Task ID (`task[N]` for for_each items)
+    pub id: String,
+    /// Verb name (infer, exec, fetch, invoke, agent)
+    pub verb: &'static str,
+    /// Rendered text
+    pub text: String,
+    /// Binding paths that had no value and were rendered as placeholders
+    pub placeholders: Vec<String>,
+}
+
+/// Load task outputs from recorded trace events into a DataStore
+pub fn datastore_from_events(events: &[Event]) -> DataStore {
+    let datastore = DataStore::new();
+    for event in events {
+        if let EventKind::TaskCompleted {
+            task_id,
+            output,
+            duration_ms,
+        } = &event.kind
+        {
+            datastore.insert(
+                task_id.clone(),
+                TaskResult::success(output.as_ref().clone(), Duration::from_millis(*duration_ms)),
+            );
+        }
+    }
+    datastore
+}
+
+/// Render every task of a workflow (for_each arrays render once per item)
+pub fn render_workflow(
+    workflow: &Workflow,
+    datastore: &DataStore,
+) -> Result<Vec<RenderedTask>, NikaError> {
+    let mut rendered = Vec::new();
+
+    for task in &workflow.tasks {
+        let (bindings, placeholders) = sample_bindings(task.use_wiring.as_ref(), datastore);
+        let mut bindings = bindings.with_engine(task.template_engine);
+
+        match task.for_each.as_ref().and_then(Value::as_array) {
+            Some(items) => {
+                for (idx, item) in items.iter().enumerate() {
+                    bindings.set(task.for_each_var(), item.clone());
+                    rendered.push(RenderedTask {
+                        id: format!("{}[{}]", task.id, idx),
+                        verb: task.action.verb_name(),
+                        text: render_action(
+                            &task.action,
+                            &bindings,
+                            datastore,
+                            workflow.templates,
+                        )?,
+                        placeholders: placeholders.clone(),
+                    });
+                }
+            }
+            None => rendered.push(RenderedTask {
+                id: task.id.clone(),
+                verb: task.action.verb_name(),
+                text: render_action(&task.action, &bindings, datastore, workflow.templates)?,
+                placeholders,
+            }),
+        }
+    }
+
+    Ok(rendered)
+}
+
+/// Resolve each alias on its own so one missing upstream doesn't hide the rest
+fn sample_bindings(
+    wiring: Option<&WiringSpec>,
+    datastore: &DataStore,
+) -> (ResolvedBindings, Vec<String>) {
+    let mut bindings = ResolvedBindings::new();
+    let mut placeholders = Vec::new();
+
+    let mut entries: Vec<_> = wiring.into_iter().flatten().collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+    for (alias, entry) in entries {
+        let mut single = WiringSpec::default();
+        single.insert(alias.clone(), entry.clone());
+        let value = ResolvedBindings::from_wiring_spec(Some(&single), datastore)
+            .and_then(|b| b.get_resolved(alias, datastore));
+        match value {
+            Ok(v) => bindings.set(alias, v),
+            Err(_) => {
+                bindings.set(alias, Value::String(format!("<{}>", entry.path)));
+                placeholders.push(entry.path.clone());
+            }
+        }
+    }
+
+    (bindings, placeholders)
+}
+
+pub(crate) fn render_action(
+    action: &TaskAction,
+    bindings: &ResolvedBindings,
+    datastore: &DataStore,
+    mode: TemplateMode,
+) -> Result<String, NikaError> {
+    let r = |template: &str| -> Result<String, NikaError> {
+        template_resolve_with_mode(template, bindings, datastore, mode).map(|c| c.into_owned())
+    };
+
+    Ok(match action {
+        TaskAction::Infer { infer } => r(&infer.prompt)?,
+        TaskAction::Exec { exec } => r(&exec.command)?,
+        TaskAction::Fetch {
+            fetch:
+                FetchParams {
+                    graphql: Some(graphql),
+                    ..
+                },
+        } => {
+            let mut out = format!(
+                "POST {} (graphql)\n\n{}\n",
+                r(&graphql.endpoint)?,
+                graphql.query.trim_end()
+            );
+            if let Some(variables) = &graphql.variables {
+                let resolved = r(&serde_json::to_string(variables)?)?;
+                let pretty = serde_json::from_str::<Value>(&resolved)
+                    .and_then(|v| serde_json::to_string_pretty(&v))
+                    .unwrap_or(resolved);
+                out.push_str(&format!("variables: {}\n", pretty));
+            }
+            out
+        }
+        TaskAction::Fetch { fetch } => {
+            let mut out = format!("{} {}\n", fetch.method, r(&fetch.url)?);
+            let mut headers: Vec<_> = fetch.headers.iter().collect();
+            headers.sort_unstable();
+            for (key, value) in headers {
+                out.push_str(&format!("{}: {}\n", key, r(value)?));
+            }
+            if let Some(body) = &fetch.body {
+                out.push('\n');
+                out.push_str(&r(body)?);
+            }
+            out
+        }
+        TaskAction::Invoke { invoke } => {
+            let mut out = format!("mcp: {}\n", invoke.mcp);
+            if let Some(tool) = &invoke.tool {
+                out.push_str(&format!("tool: {}\n", tool));
+            }
+            if let Some(resource) = &invoke.resource {
+                out.push_str(&format!("resource: {}\n", resource));
+            }
+            if let Some(params) = &invoke.params {
+                let resolved = r(&serde_json::to_string(params)?)?;
+                let pretty = serde_json::from_str::<Value>(&resolved)
+                    .and_then(|v| serde_json::to_string_pretty(&v))
+                    .unwrap_or(resolved);
+                out.push_str(&format!("params: {}\n", pretty));
+            }
+            out
+        }
+        TaskAction::Agent { agent } => {
+            let mut out = String::new();
+            if let Some(system) = &agent.system {
+                out.push_str(&format!("## system\n\n{}\n\n## prompt\n\n", r(system)?));
+            }
+            out.push_str(&r(&agent.prompt)?);
+            out
+        }
+        TaskAction::Reduce { reduce } => {
+            let mut out = format!(
+                "source: {}\nstrategy: {}\n",
+                reduce.source,
+                reduce.strategy.as_str()
+            );
+            if reduce.strategy == ReduceStrategy::Summarize {
+                out.push_str(&format!("\n{}\n", r(reduce.prompt())?));
+            }
+            out
+        }
+        TaskAction::Approve { approve } => {
+            let mut out = r(&approve.prompt)?;
+            if let Some(timeout) = approve.timeout {
+                let default = approve.default.map_or("none", |d| d.as_str());
+                out.push_str(&format!("\n\ntimeout: {}s (default: {})", timeout, default));
+            }
+            out
+        }
+        TaskAction::Embed { embed } => {
+            let mut out = r(&embed.input)?;
+            if let Some(collection) = &embed.collection {
+                out.push_str(&format!("\n\ncollection: {}", collection));
+            }
+            out
+        }
+        TaskAction::Recall { recall } => format!(
+            "{}\n\ncollection: {} (top {})",
+            r(&recall.query)?,
+            recall.collection,
+            recall.top_k()
+        ),
+        TaskAction::Retrieve { retrieve } => format!(
+            "{}\n\nfiles: {} (top {})",
+            r(&retrieve.query)?,
+            retrieve.files,
+            retrieve.top_k()
+        ),
+        TaskAction::Validate { validate } => {
+            let mut out = format!("source: {}", r(&validate.source)?);
+            match &validate.schema {
+                Some(Value::String(path)) => out.push_str(&format!("\nschema: {}", path)),
+                Some(schema) => out.push_str(&format!("\nschema: {}", schema)),
+                None => {}
+            }
+            out.push_str(&format!(
+                "\nrules: {} (on_fail: {})",
+                validate.rules.len(),
+                validate.on_fail.as_str()
+            ));
+            out
+        }
+        TaskAction::Transcribe { transcribe } => {
+            let mut out = format!("file: {}", r(&transcribe.file)?);
+            if let Some(language) = &transcribe.language {
+                out.push_str(&format!("\nlanguage: {}", language));
+            }
+            out.push_str(&format!("\nchunks: {}s", transcribe.chunk_seconds()));
+            out
+        }
+        TaskAction::Import { import } => {
+            let mut out = format!("file: {}", r(&import.file)?);
+            if let Some(sheet) = &import.sheet {
+                out.push_str(&format!("\nsheet: {}", sheet));
+            }
+            if !import.columns.is_empty() {
+                let mut columns: Vec<&str> = import.columns.keys().map(String::as_str).collect();
+                columns.sort_unstable();
+                out.push_str(&format!("\ncolumns: {}", columns.join(", ")));
+            }
+            out
+        }
+        TaskAction::Export { export } => {
+            let mut out = format!("source: {}\nfile: {}", r(&export.source)?, r(&export.file)?);
+            if let Some(columns) = &export.columns {
+                out.push_str(&format!("\ncolumns: {}", columns.join(", ")));
+            }
+            out
+        }
+        TaskAction::Rows { rows } => {
+            let mut out = format!("source: {}", r(&rows.source)?);
+            if let Some(filter) = &rows.filter {
+                out.push_str(&format!("\nfilter: {}", filter));
+            }
+            if !rows.group_by.is_empty() {
+                out.push_str(&format!("\ngroup_by: {}", rows.group_by.join(", ")));
+            }
+            if !rows.aggregate.is_empty() {
+                let mut aggregates: Vec<String> = rows
+                    .aggregate
+                    .iter()
+                    .map(|(name, spec)| format!("{} = {}", name, spec))
+                    .collect();
+                aggregates.sort_unstable();
+                out.push_str(&format!("\naggregate: {}", aggregates.join(", ")));
+            }
+            if !rows.sort.is_empty() {
+                out.push_str(&format!("\nsort: {}", rows.sort.join(", ")));
+            }
+            if let Some(limit) = rows.limit {
+                out.push_str(&format!("\nlimit: {}", limit));
+            }
+            out
+        }
+        TaskAction::Script { script } => match (&script.code, &script.file) {
+            (Some(code), _) => code.clone(),
+            (None, file) => format!("file: {}", r(file.as_deref().unwrap_or_default())?),
+        },
+        TaskAction::Chunk { chunk } => format!(
+            "source: {}\nby: {}, size: {}, overlap: {}",
+            r(&chunk.source)?,
+            chunk.by.as_str(),
+            chunk.size(),
+            chunk.overlap
+        ),
+        TaskAction::Dedupe { dedupe } => {
+            let mut out = format!("source: {}", r(&dedupe.source)?);
+            if let Some(field) = &dedupe.field {
+                out.push_str(&format!("\nfield: {}", field));
+            }
+            out.push_str(&format!(
+                "\nby: {}, threshold: {}",
+                dedupe.by.as_str(),
+                dedupe.threshold()
+            ));
+            out
+        }
+        TaskAction::DetectLang { detect_lang } => {
+            let mut out = format!("source: {}", r(&detect_lang.source)?);
+            if !detect_lang.allow.is_empty() {
+                out.push_str(&format!("\nallow: {}", detect_lang.allow.join(", ")));
+            }
+            out
+        }
+        TaskAction::Translate { translate } => {
+            let mut out = format!("source: {}", r(&translate.source)?);
+            match &translate.from {
+                Some(from) => {
+                    out.push_str(&format!("\nfrom: {} to: {}", r(from)?, r(&translate.to)?))
+                }
+                None => out.push_str(&format!("\nto: {}", r(&translate.to)?)),
+            }
+            if let Some(style) = &translate.style {
+                out.push_str(&format!("\nstyle: {}", style));
+            }
+            if !translate.glossary.is_empty() {
+                out.push_str(&format!("\nglossary: {} terms", translate.glossary.len()));
+            }
+            out
+        }
+        TaskAction::Ws { ws } => {
+            let mut out = format!("WS {}\n", r(&ws.url)?);
+            for (key, value) in &ws.headers {
+                out.push_str(&format!("{}: {}\n", key, r(value)?));
+            }
+            for message in &ws.send {
+                out.push_str(&format!("\nsend: {}", r(message)?));
+            }
+            out
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn workflow(yaml: &str) -> Workflow {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    const YAML: &str = r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: weather
+    exec: "curl wttr.in"
+  - id: report
+    use:
+      forecast: weather.summary
+      city: weather.city ?? "Paris"
+    infer: "Write about {{use.city}}: {{use.forecast}}"
+"#;
+
+    #[test]
+    fn test_render_uses_defaults_and_placeholders() {
+        let rendered = render_workflow(&workflow(YAML), &DataStore::new()).unwrap();
+        let report = rendered.iter().find(|t| t.id == "report").unwrap();
+        assert_eq!(report.verb, "infer");
+        assert_eq!(report.text, "Write about Paris: <weather.summary>");
+        assert_eq!(report.placeholders, vec!["weather.summary"]);
+    }
+
+    #[test]
+    fn test_render_with_trace_values() {
+        let events = vec![Event {
+            id: 0,
+            timestamp_ms: 0,
+            kind: EventKind::TaskCompleted {
+                task_id: Arc::from("weather"),
+                output: Arc::new(json!({"summary": "Sunny", "city": "Lyon"})),
+                duration_ms: 10,
+            },
+        }];
+        let datastore = datastore_from_events(&events);
+
+        let rendered = render_workflow(&workflow(YAML), &datastore).unwrap();
+        let report = rendered.iter().find(|t| t.id == "report").unwrap();
+        assert_eq!(report.text, "Write about Lyon: Sunny");
+        assert!(report.placeholders.is_empty());
+    }
+
+    #[test]
+    fn test_render_for_each_items() {
+        let wf = workflow(
+            r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: greet
+    for_each: ["fr", "en"]
+    as: lang
+    exec: "echo {{use.lang}}"
+"#,
+        );
+        let rendered = render_workflow(&wf, &DataStore::new()).unwrap();
+        let ids: Vec<_> = rendered.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["greet[0]", "greet[1]"]);
+        assert_eq!(rendered[1].text, "echo en");
+    }
+}
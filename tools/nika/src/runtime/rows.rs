@@ -0,0 +1,667 @@
+//! Batch operations behind `rows:` tasks (v0.7)
+//!
+//! Steps run in a fixed order: `filter`, then `group_by`/`aggregate`, then
+//! `sort`, then `limit`. Fields are dot paths into each row (`score`,
+//! `contact.email`, `tags[0]`); missing fields read as `null`.
+//!
+//! Filter grammar:
+//! - comparisons: `==`, `!=` (`"42"` equals `42`), `<`, `<=`, `>`, `>=`
+//! - `and`/`&&`, `or`/`||`, `not`/`!`, parentheses
+//! - operands: field paths (`` `E-mail` `` for names with other
+//!   characters), `'text'`, `"text"`, numbers, `true`, `false`, `null`
+//! - a field alone tests truthiness, as in `when:`
+
+use std::cmp::Ordering;
+
+use rustc_hash::FxHashMap;
+use serde_json::{Map, Number, Value};
+
+use crate::ast::RowsParams;
+use crate::dag::{loose_eq, truthy};
+use crate::util::jsonpath::{self, Segment};
+
+/// Apply `params` to an array of rows
+pub fn apply_rows(value: Value, params: &RowsParams) -> Result<Vec<Value>, String> {
+    let Value::Array(mut rows) = value else {
+        return Err(format!(
+            "expected an array of rows, got {}",
+            type_name(&value)
+        ));
+    };
+
+    if let Some(filter) = &params.filter {
+        let expr = Expr::parse(filter).map_err(|e| format!("filter '{}': {}", filter, e))?;
+        rows.retain(|row| truthy(&expr.eval(row)));
+    }
+    if params.is_grouped() {
+        rows = group(rows, params)?;
+    }
+    if !params.sort.is_empty() {
+        let keys = params
+            .sort
+            .iter()
+            .map(|key| match key.strip_prefix('-') {
+                Some(field) => Ok((field_path(field)?, true)),
+                None => Ok((field_path(key.strip_prefix('+').unwrap_or(key))?, false)),
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        rows.sort_by(|a, b| {
+            keys.iter()
+                .map(|(path, descending)| {
+                    compare_sort(&field(a, path), &field(b, path), *descending)
+                })
+                .find(|order| order.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+    if let Some(limit) = params.limit {
+        rows.truncate(limit);
+    }
+    Ok(rows)
+}
+
+/// A parsed `filter:` expression, for matching values one at a time (also
+/// `ws: { until }`, v0.7)
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "http"), allow(dead_code))]
+pub struct Filter(Expr);
+
+impl Filter {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Expr::parse(text).map(Self)
+    }
+
+    /// Whether `row` satisfies the expression
+    #[cfg_attr(not(feature = "http"), allow(dead_code))]
+    pub fn matches(&self, row: &Value) -> bool {
+        truthy(&self.0.eval(row))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// GROUP / AGGREGATE
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AggFn {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    First,
+    Last,
+    Collect,
+}
+
+/// One `aggregate:` entry, e.g. `sum(seats)`
+#[derive(Debug, Clone, PartialEq)]
+struct Aggregate {
+    func: AggFn,
+    /// `None` only for a bare `count`
+    field: Option<Vec<Segment>>,
+}
+
+impl Aggregate {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (name, field) = match spec.strip_suffix(')').and_then(|s| s.split_once('(')) {
+            Some((name, field)) => (name.trim(), Some(field.trim())),
+            None => (spec, None),
+        };
+        let func = match name {
+            "count" => AggFn::Count,
+            "sum" => AggFn::Sum,
+            "avg" | "mean" => AggFn::Avg,
+            "min" => AggFn::Min,
+            "max" => AggFn::Max,
+            "first" => AggFn::First,
+            "last" => AggFn::Last,
+            "collect" => AggFn::Collect,
+            other => {
+                return Err(format!(
+                    "unknown aggregate '{}' (count, sum, avg, min, max, first, last, collect)",
+                    other
+                ))
+            }
+        };
+        let field = match field {
+            Some("") | None if func == AggFn::Count => None,
+            Some("") | None => {
+                return Err(format!("'{}' needs a field, e.g. {}(score)", spec, name))
+            }
+            Some(path) => Some(field_path(path)?),
+        };
+        Ok(Self { func, field })
+    }
+
+    fn apply(&self, rows: &[Value]) -> Result<Value, String> {
+        let Some(path) = &self.field else {
+            return Ok(Value::from(rows.len()));
+        };
+        let values: Vec<Value> = rows
+            .iter()
+            .map(|row| field(row, path))
+            .filter(|v| !v.is_null())
+            .collect();
+        Ok(match self.func {
+            AggFn::Count => Value::from(values.len()),
+            AggFn::Sum | AggFn::Avg => {
+                let numbers = values
+                    .iter()
+                    .map(|v| number(v).ok_or_else(|| format!("{} is not a number", v)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                let sum: f64 = numbers.iter().sum();
+                match self.func {
+                    AggFn::Avg if numbers.is_empty() => Value::Null,
+                    AggFn::Avg => float(sum / numbers.len() as f64),
+                    _ if values.iter().all(|v| v.is_i64() || v.is_u64()) => {
+                        match values
+                            .iter()
+                            .try_fold(0i64, |acc, v| acc.checked_add(v.as_i64()?))
+                        {
+                            Some(total) => Value::from(total),
+                            None => float(sum),
+                        }
+                    }
+                    _ => float(sum),
+                }
+            }
+            AggFn::Min => values.into_iter().min_by(compare).unwrap_or(Value::Null),
+            AggFn::Max => values.into_iter().max_by(compare).unwrap_or(Value::Null),
+            AggFn::First => values.into_iter().next().unwrap_or(Value::Null),
+            AggFn::Last => values.into_iter().next_back().unwrap_or(Value::Null),
+            AggFn::Collect => Value::Array(values),
+        })
+    }
+}
+
+/// One row per distinct `group_by` combination (first-seen order), with
+/// the group keys and the aggregates
+fn group(rows: Vec<Value>, params: &RowsParams) -> Result<Vec<Value>, String> {
+    let keys = params
+        .group_by
+        .iter()
+        .map(|key| Ok((key.clone(), field_path(key)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let mut aggregates = params
+        .aggregate
+        .iter()
+        .map(|(name, spec)| {
+            if params.group_by.contains(name) {
+                return Err(format!(
+                    "aggregate '{}' has the same name as a group key",
+                    name
+                ));
+            }
+            let aggregate =
+                Aggregate::parse(spec).map_err(|e| format!("aggregate '{}': {}", name, e))?;
+            Ok((name.clone(), aggregate))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    aggregates.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut index: FxHashMap<String, usize> = FxHashMap::default();
+    let mut groups: Vec<(Vec<Value>, Vec<Value>)> = Vec::new();
+    for row in rows {
+        let values: Vec<Value> = keys.iter().map(|(_, path)| field(&row, path)).collect();
+        let id = Value::Array(values.clone()).to_string();
+        let at = *index.entry(id).or_insert_with(|| {
+            groups.push((values, Vec::new()));
+            groups.len() - 1
+        });
+        groups[at].1.push(row);
+    }
+    // Aggregating everything yields one row, even when nothing matched
+    if keys.is_empty() && groups.is_empty() {
+        groups.push((Vec::new(), Vec::new()));
+    }
+
+    groups
+        .into_iter()
+        .map(|(values, members)| {
+            let mut out = Map::new();
+            for ((name, _), value) in keys.iter().zip(values) {
+                out.insert(name.clone(), value);
+            }
+            for (name, aggregate) in &aggregates {
+                let value = aggregate
+                    .apply(&members)
+                    .map_err(|e| format!("aggregate '{}': {}", name, e))?;
+                out.insert(name.clone(), value);
+            }
+            Ok(Value::Object(out))
+        })
+        .collect()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// FILTER EXPRESSIONS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Field(Vec<Segment>),
+    Literal(Value),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Cmp(Box<Expr>, CmpOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Cmp(CmpOp),
+    Field(String),
+    Literal(Value),
+}
+
+impl Expr {
+    fn parse(text: &str) -> Result<Self, String> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser { tokens, at: 0 };
+        let expr = parser.or()?;
+        match parser.tokens.get(parser.at) {
+            None => Ok(expr),
+            Some(token) => Err(format!(
+                "unexpected {} after the expression",
+                describe(token)
+            )),
+        }
+    }
+
+    fn eval(&self, row: &Value) -> Value {
+        match self {
+            Expr::Field(path) => field(row, path),
+            Expr::Literal(value) => value.clone(),
+            Expr::Not(inner) => Value::Bool(!truthy(&inner.eval(row))),
+            Expr::And(a, b) => Value::Bool(truthy(&a.eval(row)) && truthy(&b.eval(row))),
+            Expr::Or(a, b) => Value::Bool(truthy(&a.eval(row)) || truthy(&b.eval(row))),
+            Expr::Cmp(a, op, b) => {
+                let (a, b) = (a.eval(row), b.eval(row));
+                Value::Bool(match op {
+                    CmpOp::Eq => loose_eq(&a, &b),
+                    CmpOp::Ne => !loose_eq(&a, &b),
+                    op => order(&a, &b).is_some_and(|o| match op {
+                        CmpOp::Lt => o.is_lt(),
+                        CmpOp::Le => o.is_le(),
+                        CmpOp::Gt => o.is_gt(),
+                        _ => o.is_ge(),
+                    }),
+                })
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+}
+
+impl Parser {
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.tokens.get(self.at) == Some(token);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.not()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        let lhs = self.operand()?;
+        match self.tokens.get(self.at) {
+            Some(Token::Cmp(op)) => {
+                let op = *op;
+                self.at += 1;
+                Ok(Expr::Cmp(Box::new(lhs), op, Box::new(self.operand()?)))
+            }
+            _ => Ok(lhs),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Expr, String> {
+        let token = self.tokens.get(self.at).cloned();
+        self.at += 1;
+        match token {
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                if !self.eat(&Token::Close) {
+                    return Err("missing ')'".to_string());
+                }
+                Ok(expr)
+            }
+            Some(Token::Field(path)) => Ok(Expr::Field(field_path(&path)?)),
+            Some(Token::Literal(value)) => Ok(Expr::Literal(value)),
+            Some(other) => Err(format!(
+                "expected a field or value, found {}",
+                describe(&other)
+            )),
+            None => Err("expected a field or value at the end".to_string()),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let two: String = chars.clone().take(2).collect();
+        let op = match two.as_str() {
+            "==" => Some((Token::Cmp(CmpOp::Eq), 2)),
+            "!=" => Some((Token::Cmp(CmpOp::Ne), 2)),
+            "<=" => Some((Token::Cmp(CmpOp::Le), 2)),
+            ">=" => Some((Token::Cmp(CmpOp::Ge), 2)),
+            "&&" => Some((Token::And, 2)),
+            "||" => Some((Token::Or, 2)),
+            _ => match c {
+                '<' => Some((Token::Cmp(CmpOp::Lt), 1)),
+                '>' => Some((Token::Cmp(CmpOp::Gt), 1)),
+                '!' => Some((Token::Not, 1)),
+                '(' => Some((Token::Open, 1)),
+                ')' => Some((Token::Close, 1)),
+                _ => None,
+            },
+        };
+        if let Some((token, len)) = op {
+            for _ in 0..len {
+                chars.next();
+            }
+            tokens.push(token);
+            continue;
+        }
+
+        match c {
+            '\'' | '"' | '`' => {
+                chars.next();
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some(ch) => quoted.push(ch),
+                        None => return Err(format!("unterminated {}", c)),
+                    }
+                }
+                tokens.push(match c {
+                    '`' => Token::Field(quoted),
+                    _ => Token::Literal(Value::String(quoted)),
+                });
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '$') => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | '[' | ']' | '$') {
+                        word.push(ch);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "true" | "false" | "null" => {
+                        Token::Literal(serde_json::from_str(&word).unwrap_or(Value::Null))
+                    }
+                    _ => match word.parse::<f64>() {
+                        Ok(n) if n.is_finite() => {
+                            Token::Literal(serde_json::from_str(&word).unwrap_or_else(|_| float(n)))
+                        }
+                        _ => Token::Field(word),
+                    },
+                });
+            }
+            other => return Err(format!("unexpected '{}'", other)),
+        }
+    }
+    if tokens.is_empty() {
+        return Err("empty expression".to_string());
+    }
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+        Token::And => "'and'".to_string(),
+        Token::Or => "'or'".to_string(),
+        Token::Not => "'not'".to_string(),
+        Token::Cmp(_) => "a comparison".to_string(),
+        Token::Field(path) => format!("field '{}'", path),
+        Token::Literal(value) => format!("value {}", value),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// VALUES
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn field_path(path: &str) -> Result<Vec<Segment>, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("empty field name".to_string());
+    }
+    jsonpath::parse(path).map_err(|_| format!("'{}' is not a field path", path))
+}
+
+fn field(row: &Value, path: &[Segment]) -> Value {
+    jsonpath::apply(row, path).unwrap_or(Value::Null)
+}
+
+/// A number, or a string holding one
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn float(n: f64) -> Value {
+    Number::from_f64(n).map_or(Value::Null, Value::Number)
+}
+
+/// Ordering for comparisons: numbers (or numeric strings against
+/// numbers) numerically, strings lexically; other pairs are unordered
+fn order(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        (Value::Null, _) | (_, Value::Null) => None,
+        _ if a.is_number() || b.is_number() => number(a)?.partial_cmp(&number(b)?),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        _ => None,
+    }
+}
+
+/// Total order for sorting and min/max: by type (numbers, strings,
+/// booleans, other), then by value
+fn compare(a: &Value, b: &Value) -> Ordering {
+    let rank = |v: &Value| match v {
+        Value::Number(_) => 0,
+        Value::String(_) => 1,
+        Value::Bool(_) => 2,
+        _ => 3,
+    };
+    rank(a)
+        .cmp(&rank(b))
+        .then_with(|| order(a, b).unwrap_or_else(|| a.to_string().cmp(&b.to_string())))
+}
+
+/// Sort order with nulls last in both directions
+fn compare_sort(a: &Value, b: &Value, descending: bool) -> Ordering {
+    match (a.is_null(), b.is_null()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        _ if descending => compare(b, a),
+        _ => compare(a, b),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "text",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn leads() -> Value {
+        json!([
+            {"company": "Acme", "seats": 40, "score": 80, "plan": "pro"},
+            {"company": "Initech", "seats": 12, "score": 35, "plan": "free"},
+            {"company": "Acme", "seats": 5, "score": 90, "plan": "team"},
+            {"company": "Globex", "seats": 25, "score": null, "plan": "pro"},
+            {"company": "Hooli", "seats": 60, "score": 55, "plan": "free"},
+        ])
+    }
+
+    fn params(yaml: &str) -> RowsParams {
+        serde_yaml::from_str(&format!("source: $x\n{}", yaml)).unwrap()
+    }
+
+    fn filter(expr: &str) -> Vec<String> {
+        let yaml = format!("filter: {}", serde_json::to_string(expr).unwrap());
+        apply_rows(leads(), &params(&yaml))
+            .unwrap()
+            .iter()
+            .map(|row| row["company"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_expressions() {
+        assert_eq!(filter("score >= 55"), vec!["Acme", "Acme", "Hooli"]);
+        assert_eq!(filter("plan == 'pro' and not (seats < 30)"), vec!["Acme"]);
+        assert_eq!(filter("plan != \"free\" && score"), vec!["Acme", "Acme"]);
+        assert_eq!(
+            filter("score == null || seats > 59"),
+            vec!["Globex", "Hooli"]
+        );
+        assert_eq!(filter("`company` == 'Acme' and seats == '5'"), vec!["Acme"]);
+        assert_eq!(filter("missing.field"), Vec::<String>::new());
+
+        for bad in [
+            "",
+            "score >=",
+            "(score > 1",
+            "score > 1 seats",
+            "plan = 'pro'",
+        ] {
+            let yaml = format!("filter: {}", serde_json::to_string(bad).unwrap());
+            assert!(apply_rows(leads(), &params(&yaml)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_group_aggregate_sort_limit() {
+        let yaml = r#"
+group_by: [company]
+aggregate:
+  seats: sum(seats)
+  leads: count
+  best: max(score)
+  avg_score: avg(score)
+  plans: collect(plan)
+sort: [-seats, company]
+limit: 3
+"#;
+        let rows = apply_rows(leads(), &params(yaml)).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                json!({"company": "Hooli", "seats": 60, "leads": 1, "best": 55, "avg_score": 55.0, "plans": ["free"]}),
+                json!({"company": "Acme", "seats": 45, "leads": 2, "best": 90, "avg_score": 85.0, "plans": ["pro", "team"]}),
+                json!({"company": "Globex", "seats": 25, "leads": 1, "best": null, "avg_score": null, "plans": ["pro"]}),
+            ]
+        );
+
+        let rows = apply_rows(
+            leads(),
+            &params("aggregate:\n  total: sum(seats)\n  n: count(score)"),
+        )
+        .unwrap();
+        assert_eq!(rows, vec![json!({"total": 142, "n": 4})]);
+        let rows = apply_rows(json!([]), &params("aggregate:\n  n: count")).unwrap();
+        assert_eq!(rows, vec![json!({"n": 0})]);
+    }
+
+    #[test]
+    fn test_sort_puts_nulls_last() {
+        let rows = apply_rows(leads(), &params("sort: [score]")).unwrap();
+        let scores: Vec<&Value> = rows.iter().map(|r| &r["score"]).collect();
+        assert_eq!(
+            scores,
+            vec![&json!(35), &json!(55), &json!(80), &json!(90), &Value::Null]
+        );
+        let rows = apply_rows(leads(), &params("sort: [-score]\nlimit: 2")).unwrap();
+        assert_eq!(rows[0]["score"], 90);
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = apply_rows(json!({"a": 1}), &params("limit: 1")).unwrap_err();
+        assert_eq!(err, "expected an array of rows, got an object");
+        let err = apply_rows(leads(), &params("aggregate:\n  x: median(seats)")).unwrap_err();
+        assert!(err.starts_with("aggregate 'x': unknown aggregate 'median'"));
+        let err = apply_rows(leads(), &params("aggregate:\n  x: sum(plan)")).unwrap_err();
+        assert_eq!(err, "aggregate 'x': \"pro\" is not a number");
+        let err = apply_rows(leads(), &params("aggregate:\n  x: sum")).unwrap_err();
+        assert!(err.contains("needs a field"));
+        let err = apply_rows(
+            leads(),
+            &params("group_by: [plan]\naggregate:\n  plan: count"),
+        )
+        .unwrap_err();
+        assert!(err.contains("same name as a group key"));
+    }
+}
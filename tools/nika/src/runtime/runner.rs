@@ -5,28 +5,58 @@
 //! - JoinSet for efficient parallel task collection
 //! - Tokio handles all concurrency (no artificial limits)
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use colored::Colorize;
+use parking_lot::Mutex;
 use serde_json::Value;
 use tokio::sync::{Notify, Semaphore};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, Instrument};
 
-use crate::ast::{Task, Workflow};
+use crate::ast::decompose::DecomposeStrategy;
+use crate::ast::{
+    InjectionPolicy, ModerationStage, ReduceStrategy, Task, TaskAction, Workflow, INPUTS_TASK_ID,
+    STATE_TASK_ID, TRIGGER_TASK_ID,
+};
 use crate::binding::ResolvedBindings;
-use crate::dag::{validate_use_wiring, FlowGraph};
+#[cfg(feature = "providers")]
+use crate::config::WebSearchConfig;
+use crate::config::{
+    ConcurrencyConfig, KeyRotationConfig, MemoryConfig, RouterConfig, SizeLimitsConfig, StoreConfig,
+};
+use crate::dag::{validate_use_wiring_with, ConnectionPolicy, FlowGraph};
 use crate::error::NikaError;
-use crate::event::{EventKind, EventLog, TraceWriter};
-use crate::store::{DataStore, TaskResult};
-use crate::util::intern;
-
+use crate::event::cost::run_cost;
+use crate::event::latency::ttft_by_model;
+use crate::event::sources::{run_sources, sources_for};
+use crate::event::{EventKind, EventLog, TaskPhase, TraceWriter};
+use crate::provider::router::is_auto;
+use crate::provider::{
+    Cassette, ConcurrencyLimiter, KeyPool, ModelRouter, ReplayProvider, SessionKey, SizeGuard,
+};
+use crate::store::{DataStore, HttpCache, StateStore, TaskResult, VectorStore};
+use crate::tools::PermissionMode;
+#[cfg(feature = "providers")]
+use crate::tools::WebSearchTool;
+use crate::util::{crash, intern, jsonpath, InjectionGuard};
+
+use super::approval::ApprovalGate;
+use super::context_pool::ContextPool;
+use super::debugger::{self, DebugCommand, DebugStop, Debugger};
 use super::executor::TaskExecutor;
+use super::moderation::moderation_summary;
 use super::output::make_task_result;
+use super::preflight;
+use super::stamp::{stamp_output, Provenance};
+use super::tool_policy::ToolPolicy;
+use super::warm::WarmResources;
 
 /// Result of executing a task iteration
 /// For for_each tasks, includes the iteration index for ordered aggregation
@@ -39,6 +69,22 @@ struct IterationResult {
     for_each_info: Option<(Arc<str>, usize)>,
 }
 
+/// Shared handles cloned into each spawned task iteration
+#[derive(Clone)]
+struct IterationContext {
+    datastore: DataStore,
+    executor: TaskExecutor,
+    event_log: EventLog,
+    /// Run metadata for `output.stamp` (v0.7)
+    provenance: Arc<Provenance>,
+    /// Emit `PhaseCompleted` events (v0.7)
+    phase_events: bool,
+    /// Step-through debugger (v0.7, `nika debug`)
+    debugger: Option<Arc<Debugger>>,
+    /// Token budget of the run's LLM tasks (v0.7, `context_pool:`)
+    context_pool: Option<Arc<ContextPool>>,
+}
+
 /// DAG workflow runner with event sourcing
 pub struct Runner {
     workflow: Workflow,
@@ -56,6 +102,20 @@ pub struct Runner {
     paused: Arc<AtomicBool>,
     /// Notify to wake runner from pause (v0.5.2+)
     resume_notify: Arc<Notify>,
+    /// Run labels recorded in the trace (v0.7)
+    labels: BTreeMap<String, String>,
+    /// Emit sub-task `PhaseCompleted` events (v0.7)
+    phase_events: bool,
+    /// Background MCP connects and provider checks (v0.7, see `preconnect`)
+    preconnect: Mutex<Option<JoinSet<()>>>,
+    /// Step-through debugger (v0.7, see `with_debugger`)
+    debugger: Option<Arc<Debugger>>,
+    /// Cross-run key-value state (v0.7, see `with_state_store`)
+    state: Arc<StateStore>,
+    /// `inputs:` values as given (v0.7, see `with_inputs`)
+    inputs: BTreeMap<String, String>,
+    /// Project `[connections]` rules for use: wiring (v0.7)
+    connections: ConnectionPolicy,
 }
 
 impl Runner {
@@ -75,8 +135,62 @@ impl Runner {
             workflow.model.as_deref(),
             workflow.mcp.clone(),
             event_log.clone(),
+        )
+        .with_template_mode(workflow.templates)
+        .with_injection_guard(
+            InjectionGuard::new(&workflow.injection).unwrap_or_else(|e| {
+                tracing::warn!("Custom injection patterns ignored: {}", e);
+                let builtin = InjectionPolicy {
+                    patterns: Vec::new(),
+                    ..workflow.injection.clone()
+                };
+                InjectionGuard::new(&builtin).expect("built-in injection rules compile")
+            }),
+        )
+        .with_untrusted_inputs(untrusted_inputs(&workflow))
+        .with_task_tags(
+            workflow
+                .tasks
+                .iter()
+                .filter(|task| !task.tags.is_empty())
+                .map(|task| (task.id.clone(), task.tags.clone()))
+                .collect(),
         );
 
+        // VCR-style cassette for tests (NIKA_CASSETTE_MODE, v0.7)
+        let cassette_name = workflow
+            .name
+            .clone()
+            .unwrap_or_else(|| workflow.compute_hash());
+        let executor = match Cassette::from_env(&cassette_name) {
+            Ok(Some(cassette)) => executor.with_cassette(cassette),
+            Ok(None) => executor,
+            Err(e) => {
+                tracing::warn!("Cassette disabled: {}", e);
+                executor
+            }
+        };
+
+        // Project `[tools]` permission for agent tool calls (v0.7)
+        let tool_policy = std::env::current_dir()
+            .map_err(NikaError::from)
+            .and_then(|dir| ToolPolicy::discover(&dir))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Asking before every agent tool call: {}", e);
+                ToolPolicy::new(PermissionMode::Plan)
+            });
+        let executor =
+            executor.with_tool_policy(tool_policy.for_workflow(workflow.name.as_deref()));
+
+        // Project `[connections]` rules, checked with the use: wiring (v0.7)
+        let connections = std::env::current_dir()
+            .map_err(NikaError::from)
+            .and_then(|dir| ConnectionPolicy::discover(&dir))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Not checking [connections] rules: {}", e);
+                ConnectionPolicy::default()
+            });
+
         // Generate unique ID for this execution (used for trace files)
         let generation_id = format!("gen-{}", uuid::Uuid::new_v4());
 
@@ -91,6 +205,13 @@ impl Runner {
             cancel_token: CancellationToken::new(),
             paused: Arc::new(AtomicBool::new(false)),
             resume_notify: Arc::new(Notify::new()),
+            labels: BTreeMap::new(),
+            phase_events: false,
+            preconnect: Mutex::new(None),
+            debugger: None,
+            state: Arc::new(StateStore::in_memory()),
+            inputs: BTreeMap::new(),
+            connections,
         }
     }
 
@@ -103,6 +224,448 @@ impl Runner {
         self
     }
 
+    /// Replay recorded provider responses instead of calling LLMs (v0.7)
+    ///
+    /// Infer and agent tasks are answered from the trace, so the workflow
+    /// re-executes deterministically (see `nika trace replay --workflow`).
+    pub fn with_replay(mut self, replay: Arc<ReplayProvider>) -> Self {
+        self.executor = self.executor.with_replay(replay);
+        self
+    }
+
+    /// Record/replay provider and MCP calls through a cassette (v0.7)
+    ///
+    /// Overrides the cassette selected by `NIKA_CASSETTE_MODE`.
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.executor = self.executor.with_cassette(cassette);
+        self
+    }
+
+    /// Pause before tasks and let a front-end steer them (v0.7, `nika debug`)
+    ///
+    /// See [`Debugger`] for the commands. Tasks in the same layer still run
+    /// concurrently, so several may be paused at once; the front-end gets
+    /// them one after the other.
+    pub fn with_debugger(mut self, debugger: Arc<Debugger>) -> Self {
+        self.debugger = Some(debugger);
+        self
+    }
+
+    /// Route `approve:` tasks through a gate (v0.7)
+    ///
+    /// Defaults to a terminal prompt without checkpointing; front-ends add
+    /// an `ApprovalCheckpoint` so a restarted run resumes past decided gates.
+    pub fn with_approval_gate(mut self, gate: ApprovalGate) -> Self {
+        self.executor = self.executor.with_approval_gate(Arc::new(gate));
+        self
+    }
+
+    /// Decide which `agent:` tool calls need approval (v0.7)
+    ///
+    /// Defaults to the project's `.nika/config.toml [tools]` settings and
+    /// `.nika/policy.yaml` rules, scoped to this workflow's `name:`.
+    /// Questions go through the approval gate.
+    pub fn with_tool_policy(mut self, policy: ToolPolicy) -> Self {
+        let policy = policy.for_workflow(self.workflow.name.as_deref());
+        self.executor = self.executor.with_tool_policy(policy);
+        self
+    }
+
+    /// Resolve project config such as `[hosts]` from the workflow's directory (v0.7)
+    ///
+    /// Defaults to the current directory; `nika run` passes the directory
+    /// of the workflow file, as `nika check` uses.
+    pub fn with_project_dir(mut self, dir: &Path) -> Self {
+        self.executor = self.executor.with_project_dir(dir);
+        self
+    }
+
+    /// Run tools matching these globs without asking (v0.7, `--auto-approve`)
+    pub fn with_auto_approve(self, patterns: &[String]) -> Result<Self, NikaError> {
+        let policy = self
+            .executor
+            .tool_policy()
+            .clone()
+            .with_auto_approve(patterns)?;
+        Ok(self.with_tool_policy(policy))
+    }
+
+    /// Attach labels to this run (v0.7)
+    ///
+    /// Emitted as a `RunLabeled` event right after `WorkflowStarted`, so
+    /// traces of `nika run --matrix` runs record their combination.
+    pub fn with_labels(mut self, labels: BTreeMap<String, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Record sub-task phase timings as `PhaseCompleted` events (v0.7)
+    ///
+    /// Adds bindings_resolved, provider_request_sent, first_token_received,
+    /// output_validated and result_stored events with their durations
+    /// (see `nika run --phases`). Off by default to keep traces small.
+    pub fn with_phase_events(mut self, enabled: bool) -> Self {
+        self.phase_events = enabled;
+        self.executor = self.executor.with_phase_events(enabled);
+        self
+    }
+
+    /// Keep up to `size` warm provider sessions (v0.7, `nika run --session-pool`)
+    ///
+    /// Sessions are keyed by provider and model; the models referenced by
+    /// `infer:` and summarizing `reduce:` tasks are pre-warmed at start.
+    /// 0 disables pooling (every call builds a fresh session).
+    pub fn with_session_pool_size(mut self, size: usize) -> Self {
+        self.executor = self.executor.with_session_pool_size(size);
+        self
+    }
+
+    /// Reuse provider sessions and MCP clients kept by a daemon (v0.7)
+    ///
+    /// Replaces the pool set by `with_session_pool_size`; warm-hit counts
+    /// in the run summary then cover every run sharing `warm`.
+    pub fn with_warm_resources(mut self, warm: &WarmResources) -> Self {
+        self.executor = self.executor.with_warm_resources(warm);
+        self
+    }
+
+    /// Route `model: auto` with the `[router]` rules (v0.7)
+    ///
+    /// Without it the built-in rules apply: long prompts, tool-using
+    /// agents and `tags: [premium]` get the premium tier.
+    pub fn with_router(mut self, config: &RouterConfig) -> Self {
+        self.executor = self
+            .executor
+            .with_model_router(ModelRouter::new(config.clone()));
+        self
+    }
+
+    /// Bound in-flight provider calls with the `[concurrency]` settings (v0.7)
+    ///
+    /// Without it the defaults apply: 16 calls per provider, adjusted
+    /// between 1 and 64 by throttling and success.
+    pub fn with_concurrency(self, config: &ConcurrencyConfig) -> Self {
+        self.with_concurrency_limiter(Arc::new(ConcurrencyLimiter::new(config)))
+    }
+
+    /// Share provider limits with interactive callers (v0.7, TUI chat)
+    ///
+    /// Calls acquired as [`Priority::Interactive`](crate::provider::Priority)
+    /// on the same limiter go before this run's pending provider calls.
+    pub fn with_concurrency_limiter(mut self, limiter: Arc<ConcurrencyLimiter>) -> Self {
+        self.executor = self.executor.with_concurrency_limiter(limiter);
+        self
+    }
+
+    /// Search for `web_search: true` agents with the `[web_search]` backend (v0.7)
+    ///
+    /// Without it the backend comes from `BRAVE_API_KEY`, `TAVILY_API_KEY`
+    /// or `SEARXNG_URL`, at 30 searches a minute.
+    #[cfg(feature = "providers")]
+    pub fn with_web_search(self, config: &WebSearchConfig) -> Self {
+        self.with_web_search_tool(Arc::new(WebSearchTool::new(config)))
+    }
+
+    /// Share one `web_search` tool, and its rate limit, with other runs (v0.7)
+    #[cfg(feature = "providers")]
+    pub fn with_web_search_tool(mut self, tool: Arc<WebSearchTool>) -> Self {
+        self.executor = self.executor.with_web_search(tool);
+        self
+    }
+
+    /// Use the `[key_rotation]` keys of each provider in turn (v0.7)
+    ///
+    /// Fails with NIKA-140 for an unknown provider or an unset `${NAME}`.
+    pub fn with_key_rotation(self, config: &KeyRotationConfig) -> Result<Self, NikaError> {
+        Ok(self.with_key_pool(Arc::new(KeyPool::new(config)?)))
+    }
+
+    /// Share rotated keys, and their quarantines, with other runs (v0.7)
+    pub fn with_key_pool(mut self, keys: Arc<KeyPool>) -> Self {
+        self.executor = self.executor.with_key_pool(keys);
+        self
+    }
+
+    /// Fail oversized requests before sending them, per `[size_limits]` (v0.7)
+    ///
+    /// Without it prompts and `max_tokens` go to the provider unchecked.
+    pub fn with_size_limits(mut self, config: &SizeLimitsConfig) -> Self {
+        self.executor = self
+            .executor
+            .with_size_guard(SizeGuard::new(config.clone()));
+        self
+    }
+
+    /// Keep the memories of `memory: true` agents in the `[memory]` store (v0.7)
+    pub fn with_memory(mut self, config: &MemoryConfig) -> Self {
+        self.executor = self.executor.with_memory(config.clone());
+        self
+    }
+
+    /// Keep task results in the backend selected by `[store]` (v0.7)
+    ///
+    /// Keys are scoped to this run's generation id. Fails with NIKA-190
+    /// when the backend can't be opened (or isn't compiled in).
+    pub fn with_store(mut self, config: &StoreConfig) -> Result<Self, NikaError> {
+        let datastore = DataStore::open(config, &self.generation_id)?;
+        if let Some(trigger) = self.datastore.get(TRIGGER_TASK_ID) {
+            datastore.insert(intern(TRIGGER_TASK_ID), trigger);
+        }
+        self.datastore = datastore;
+        Ok(self)
+    }
+
+    /// Read and write persisted state in `store` (v0.7)
+    ///
+    /// Defaults to an in-memory store; front-ends pass the project's
+    /// `.nika/state.json`. Its values are bound as `state` when the run
+    /// starts and after each `state: { set: ... }` write.
+    pub fn with_state_store(mut self, store: Arc<StateStore>) -> Self {
+        self.state = store;
+        self
+    }
+
+    /// Keep `embed:`/`recall:` collections in `store` (v0.7)
+    ///
+    /// Defaults to an in-memory store; front-ends pass the project's
+    /// `.nika/vectors/`.
+    pub fn with_vector_store(mut self, store: Arc<VectorStore>) -> Self {
+        self.executor = self.executor.with_vector_store(store);
+        self
+    }
+
+    /// Cache `fetch:` GET responses in `cache` (v0.7)
+    ///
+    /// Off by default; front-ends pass the project's `.nika/http-cache/`.
+    /// Every lookup emits an `HttpCacheLookup` event with the run's hit and
+    /// miss counts.
+    pub fn with_http_cache(mut self, cache: Arc<HttpCache>) -> Self {
+        self.executor = self.executor.with_http_cache(cache);
+        self
+    }
+
+    /// Give values for the workflow's `inputs:` as text (v0.7)
+    ///
+    /// Parsed by type and bound as `inputs` when the run starts; inputs not
+    /// given take their default. The run fails with NIKA-009 on a missing
+    /// required input, an invalid value or a name not declared.
+    pub fn with_inputs(mut self, inputs: BTreeMap<String, String>) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    /// Bind the event that fired a watch trigger as `trigger` (v0.7)
+    ///
+    /// Tasks read it like an upstream output: `use: { file: trigger.path }`.
+    pub fn with_trigger(self, binding: Value) -> Self {
+        self.datastore.insert(
+            intern(TRIGGER_TASK_ID),
+            TaskResult::success(binding, Duration::ZERO),
+        );
+        self
+    }
+
+    /// Bind the `inputs:` values as `inputs` (v0.7)
+    fn bind_inputs(&self) -> Result<(), NikaError> {
+        if self.workflow.inputs.is_empty() && self.inputs.is_empty() {
+            return Ok(());
+        }
+        let values = self.workflow.inputs.resolve(&self.inputs)?;
+        self.datastore.insert(
+            intern(INPUTS_TASK_ID),
+            TaskResult::success(values, Duration::ZERO),
+        );
+        Ok(())
+    }
+
+    /// Bind the current state values as `state` (v0.7)
+    fn bind_state(&self) -> Result<(), NikaError> {
+        self.datastore.insert(
+            intern(STATE_TASK_ID),
+            TaskResult::success(self.state.snapshot()?, Duration::ZERO),
+        );
+        Ok(())
+    }
+
+    /// Write a succeeded task's `state: { set: ... }` values (v0.7)
+    ///
+    /// Problems are reported as warnings: the task itself succeeded.
+    fn save_state(&self, task_id: &str, result: &TaskResult) {
+        let Some(spec) = self
+            .workflow
+            .tasks
+            .iter()
+            .find(|t| t.id == task_id)
+            .and_then(|t| t.state.as_ref())
+        else {
+            return;
+        };
+        if !result.is_success() {
+            return;
+        }
+
+        // Text output holding JSON is addressed like a JSON output
+        let parsed;
+        let output: &Value = match &*result.output {
+            Value::String(text) => {
+                parsed = serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.clone()));
+                &parsed
+            }
+            other => other,
+        };
+        let mut warnings = Vec::new();
+        for (key, path) in &spec.set {
+            let value = match path.trim() {
+                "" | "." => Some(output.clone()),
+                path => jsonpath::resolve(output, path).ok().flatten(),
+            };
+            let Some(value) = value else {
+                warnings.push(format!(
+                    "state '{}': '{}' not found in the output",
+                    key, path
+                ));
+                continue;
+            };
+            if let Err(e) = self.state.set(key, value, spec.ttl()) {
+                warnings.push(format!("state '{}' not saved: {}", key, e));
+            }
+        }
+        if let Err(e) = self.bind_state() {
+            warnings.push(format!("state not reloaded: {}", e));
+        }
+
+        for warning in warnings {
+            tracing::warn!(task_id, "{}", warning);
+            if !self.quiet {
+                println!("      {} {}", "Warning:".yellow(), warning);
+            }
+        }
+    }
+
+    /// Start MCP connects and provider checks in the background (v0.7)
+    ///
+    /// Call right after construction so startup overlaps with validation;
+    /// `run` starts it itself otherwise. Tasks that need a server still
+    /// connecting wait for it, while tasks that don't start right away.
+    /// Idempotent; connects still running are aborted when the runner drops.
+    pub fn preconnect(&self) {
+        let mut slot = self.preconnect.lock();
+        if slot.is_some() {
+            return;
+        }
+        let mut join_set = self.executor.preconnect_mcp(self.mcp_servers());
+        let executor = self.executor.clone();
+        let keys = self.session_keys();
+        join_set.spawn_blocking(move || executor.warm_sessions(keys));
+        *slot = Some(join_set);
+    }
+
+    /// Probe every provider and MCP server the workflow uses (`preflight: true`)
+    async fn preflight(&self) -> Result<(), NikaError> {
+        let checks =
+            preflight::run_preflight(&self.executor, &self.preflight_keys(), &self.mcp_servers())
+                .await;
+        for check in &checks {
+            // EMIT: PreflightChecked
+            self.event_log.emit(EventKind::PreflightChecked {
+                target: check.target.clone(),
+                latency_ms: check.latency_ms,
+                error: check.error.clone(),
+            });
+        }
+        if !self.quiet {
+            println!("{} Preflight ({} targets)", "→".cyan(), checks.len());
+            for check in &checks {
+                match &check.error {
+                    None => println!("  {} {} {}ms", "✓".green(), check.target, check.latency_ms),
+                    Some(error) => println!(
+                        "  {} {} {}ms: {}",
+                        "✗".red(),
+                        check.target,
+                        check.latency_ms,
+                        error
+                    ),
+                }
+            }
+            println!();
+        }
+        preflight::check_results(&checks)
+    }
+
+    /// (provider, model) pairs probed by preflight: pooled calls and agents
+    fn preflight_keys(&self) -> Vec<SessionKey> {
+        let mut keys = self.session_keys();
+        for task in &self.workflow.tasks {
+            if let TaskAction::Agent { agent } = &task.action {
+                let key = SessionKey::new(
+                    agent.provider.as_deref().unwrap_or(&self.workflow.provider),
+                    agent.model.as_deref().or(self.workflow.model.as_deref()),
+                );
+                if !is_auto(key.model.as_deref()) && !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+        keys
+    }
+
+    /// MCP servers referenced by tasks, in task order
+    fn mcp_servers(&self) -> Vec<String> {
+        let mut servers: Vec<String> = Vec::new();
+        for task in &self.workflow.tasks {
+            let used: Vec<&str> = match &task.action {
+                TaskAction::Invoke { invoke } => vec![invoke.mcp.as_str()],
+                TaskAction::Agent { agent } => agent.mcp.iter().map(String::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let decompose = task
+                .decompose_spec()
+                .filter(|spec| spec.strategy != DecomposeStrategy::Static)
+                .map(|spec| spec.mcp_server());
+            for server in used.into_iter().chain(decompose) {
+                if !servers.iter().any(|s| s == server) {
+                    servers.push(server.to_string());
+                }
+            }
+        }
+        servers
+    }
+
+    /// (provider, model) pairs used by pooled provider calls, in task order
+    fn session_keys(&self) -> Vec<SessionKey> {
+        let provider = self.workflow.provider.as_str();
+        let model = self.workflow.model.as_deref();
+        let mut keys: Vec<SessionKey> = Vec::new();
+        for task in &self.workflow.tasks {
+            let key = match &task.action {
+                TaskAction::Infer { infer } => SessionKey::new(
+                    infer.provider.as_deref().unwrap_or(provider),
+                    infer.model.as_deref().or(model),
+                ),
+                TaskAction::Reduce { reduce } if reduce.strategy == ReduceStrategy::Summarize => {
+                    SessionKey::new(
+                        reduce.provider.as_deref().unwrap_or(provider),
+                        reduce.model.as_deref().or(model),
+                    )
+                }
+                _ => continue,
+            };
+            // Routed models are only known once the prompt is resolved
+            if is_auto(key.model.as_deref()) {
+                continue;
+            }
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    /// Unique ID of this execution (trace file name)
+    pub fn generation_id(&self) -> &str {
+        &self.generation_id
+    }
+
     /// Set a custom cancellation token (v0.5.2)
     ///
     /// This allows external control of workflow cancellation.
@@ -159,7 +722,32 @@ impl Runner {
         &self.event_log
     }
 
+    /// Build the shared context handed to each task iteration
+    fn iteration_context(&self) -> IterationContext {
+        IterationContext {
+            datastore: self.datastore.clone(),
+            executor: self.executor.clone(),
+            event_log: self.event_log.clone(),
+            phase_events: self.phase_events,
+            debugger: self.debugger.clone(),
+            context_pool: self
+                .workflow
+                .context_pool
+                .clone()
+                .map(|spec| Arc::new(ContextPool::new(spec, self.event_log.clone()))),
+            provenance: Arc::new(Provenance {
+                workflow: self.workflow.name.clone(),
+                workflow_hash: self.workflow.compute_hash(),
+                generation_id: self.generation_id.clone(),
+                model: self.workflow.model.clone(),
+            }),
+        }
+    }
+
     /// Get tasks that are ready to run (all dependencies satisfied)
+    ///
+    /// Skipped dependencies count as settled: `skip_unselected_tasks` runs
+    /// first, so a pending task here always has at least one taken flow.
     fn get_ready_tasks(&self) -> Vec<Arc<Task>> {
         self.workflow
             .tasks
@@ -170,14 +758,93 @@ impl Runner {
                     return false;
                 }
 
-                // Check all dependencies are done AND successful
+                // Check all dependencies are done AND successful (or skipped)
                 let deps = self.flow_graph.get_dependencies(&task.id);
-                deps.iter().all(|dep| self.datastore.is_success(dep))
+                deps.iter().all(|dep| self.is_settled(dep))
             })
             .cloned() // Clone the Arc, not the Task
             .collect()
     }
 
+    /// Dependency finished in a way that lets successors proceed (v0.7)
+    fn is_settled(&self, task_id: &str) -> bool {
+        self.datastore
+            .get(task_id)
+            .is_some_and(|r| r.is_success() || r.is_skipped())
+    }
+
+    /// Whether the flow `source -> target` is taken (v0.7)
+    ///
+    /// Taken when the source succeeded and its `when:` (if any) holds over
+    /// the source output.
+    fn edge_taken(&self, source: &str, target: &str) -> bool {
+        if !self.datastore.is_success(source) {
+            return false;
+        }
+        match self.flow_graph.edge_condition(source, target) {
+            None => true,
+            Some(condition) => self
+                .datastore
+                .get_output(source)
+                .is_some_and(|output| condition.evaluate(&output)),
+        }
+    }
+
+    /// Mark pending tasks with no taken incoming flow as skipped (v0.7)
+    ///
+    /// A task is skipped once all its dependencies are settled and none of
+    /// its incoming flows is taken (every `when:` false or source skipped).
+    /// Repeats until stable so skips cascade down an unselected branch;
+    /// joins run as long as one incoming flow is taken.
+    fn skip_unselected_tasks(&self) {
+        loop {
+            let mut skipped_any = false;
+            for task in &self.workflow.tasks {
+                if self.datastore.contains(&task.id) {
+                    continue;
+                }
+                let deps = self.flow_graph.get_dependencies(&task.id);
+                if deps.is_empty()
+                    || !deps.iter().all(|dep| self.is_settled(dep))
+                    || deps.iter().any(|dep| self.edge_taken(dep, &task.id))
+                {
+                    continue;
+                }
+
+                let reasons: Vec<String> = deps
+                    .iter()
+                    .map(|dep| {
+                        if self.datastore.is_skipped(dep) {
+                            format!("{} skipped", dep)
+                        } else {
+                            format!("{} -> {} when: false", dep, task.id)
+                        }
+                    })
+                    .collect();
+                let reason = format!("no flow taken ({})", reasons.join(", "));
+
+                let task_id = intern(&task.id);
+                self.datastore
+                    .insert(Arc::clone(&task_id), TaskResult::skipped(reason.clone()));
+                if !self.quiet {
+                    println!(
+                        "  {} {} {}",
+                        "[-]".dimmed(),
+                        &task_id,
+                        format!("skipped: {}", reason).dimmed()
+                    );
+                }
+                // EMIT: TaskSkipped
+                self.event_log
+                    .emit(EventKind::TaskSkipped { task_id, reason });
+                skipped_any = true;
+            }
+            if !skipped_any {
+                break;
+            }
+        }
+    }
+
     /// Check if all tasks are done
     fn all_done(&self) -> bool {
         self.workflow
@@ -222,20 +889,26 @@ impl Runner {
     /// * `task` - The task to execute
     /// * `task_id` - ID for this specific execution (may include index for for_each)
     /// * `parent_task_id` - Original task ID (for for_each, this is the parent task ID)
-    /// * `datastore` - Data store for task results
-    /// * `executor` - Task executor
-    /// * `event_log` - Event log for observability
+    /// * `ctx` - Shared datastore, executor, event log and provenance
     /// * `for_each_binding` - Optional (var_name, value, index) for for_each iteration
+    #[instrument(name = "task", skip_all, fields(task_id = %task_id))]
     async fn execute_task_iteration(
         task: Arc<Task>,
         task_id: Arc<str>,
         parent_task_id: Arc<str>,
-        datastore: DataStore,
-        executor: TaskExecutor,
-        event_log: EventLog,
+        ctx: IterationContext,
         for_each_binding: Option<(String, Value, usize)>, // Added index
     ) -> IterationResult {
-        let start = Instant::now();
+        let IterationContext {
+            datastore,
+            executor,
+            event_log,
+            provenance,
+            phase_events,
+            debugger,
+            context_pool,
+        } = ctx;
+        let mut start = Instant::now();
 
         // Extract for_each info if present
         let for_each_info = for_each_binding
@@ -246,7 +919,7 @@ impl Runner {
         // Build bindings from use: wiring
         let mut bindings =
             match ResolvedBindings::from_wiring_spec(task.use_wiring.as_ref(), &datastore) {
-                Ok(b) => b,
+                Ok(b) => b.with_engine(task.template_engine),
                 Err(e) => {
                     let duration = start.elapsed();
                     // EMIT: TaskFailed (bindings build failed)
@@ -263,10 +936,141 @@ impl Runner {
                 }
             };
 
+        // EMIT: BindingResolved (provenance for each use: alias, v0.7)
+        if let Some(wiring) = task.use_wiring.as_ref() {
+            let mut aliases: Vec<_> = wiring.iter().collect();
+            aliases.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            for (alias, entry) in aliases {
+                let source = entry.task_id();
+                event_log.emit(EventKind::BindingResolved {
+                    task_id: Arc::clone(&task_id),
+                    alias: alias.as_str().into(),
+                    source_task: Some(Arc::from(source)),
+                    path: entry.path.as_str().into(),
+                    source_event_id: last_completion_id(&event_log, source),
+                    used_default: bindings.used_default(alias),
+                    lazy: entry.is_lazy(),
+                });
+            }
+        }
+
         // Add for_each binding if present (v0.3)
-        if let Some((var_name, value, _idx)) = for_each_binding {
+        if let Some((var_name, value, idx)) = for_each_binding {
+            event_log.emit(EventKind::BindingResolved {
+                task_id: Arc::clone(&task_id),
+                alias: var_name.as_str().into(),
+                source_task: None,
+                path: format!("for_each[{}]", idx).into(),
+                source_event_id: None,
+                used_default: false,
+                lazy: false,
+            });
             bindings.set(&var_name, value);
         }
+        emit_phase(
+            &event_log,
+            phase_events,
+            &task_id,
+            TaskPhase::BindingsResolved,
+            start.elapsed(),
+        );
+
+        // Debugger stop (v0.7): time spent paused is not task time
+        let mut command = DebugCommand::Step;
+        if let Some(debugger) = debugger.filter(|d| d.should_pause(&task_id, &parent_task_id)) {
+            let rendered = executor
+                .render(&task.action, &bindings, &datastore)
+                .unwrap_or_else(|e| format!("(render failed: {})", e));
+            command = debugger
+                .pause(DebugStop {
+                    task_id: Arc::clone(&task_id),
+                    verb: task.action.verb_name(),
+                    inputs: bindings.to_value(),
+                    rendered,
+                    editable: debugger::is_editable(&task.action),
+                })
+                .await;
+            start = Instant::now();
+
+            if !matches!(command, DebugCommand::Step | DebugCommand::Continue) {
+                // EMIT: TaskDebugged
+                event_log.emit(EventKind::TaskDebugged {
+                    task_id: Arc::clone(&task_id),
+                    action: command.as_str().to_string(),
+                });
+            }
+            if command == DebugCommand::Skip {
+                let reason = "skipped in debugger".to_string();
+                // EMIT: TaskSkipped
+                event_log.emit(EventKind::TaskSkipped {
+                    task_id: Arc::clone(&task_id),
+                    reason: reason.clone(),
+                });
+                return IterationResult {
+                    store_id: task_id,
+                    result: TaskResult::skipped(reason),
+                    for_each_info,
+                };
+            }
+        }
+
+        // Content safety gate on inputs (v0.7)
+        if let Some(spec) = task
+            .moderate
+            .as_ref()
+            .filter(|spec| spec.covers(ModerationStage::Input))
+        {
+            match executor
+                .moderate_bindings(&task_id, spec, &bindings, &datastore)
+                .await
+            {
+                Ok(Some(redacted)) => bindings = redacted,
+                Ok(None) => {}
+                Err(e) => {
+                    let duration = start.elapsed();
+                    // EMIT: TaskFailed (input blocked)
+                    event_log.emit(EventKind::TaskFailed {
+                        task_id: Arc::clone(&task_id),
+                        error: e.to_string(),
+                        duration_ms: duration.as_millis() as u64,
+                    });
+                    return IterationResult {
+                        store_id: task_id,
+                        result: TaskResult::failed(e.to_string(), duration),
+                        for_each_info,
+                    };
+                }
+            }
+        }
+
+        // Reserve tokens from the context pool (v0.7): waiting is not task time
+        let pool = context_pool.filter(|_| ContextPool::covers(&task.action));
+        let grant = match &pool {
+            Some(pool) => match pool.acquire(&task_id, &parent_task_id).await {
+                Ok(grant) => {
+                    start = Instant::now();
+                    Some(grant)
+                }
+                Err(e) => {
+                    let duration = start.elapsed();
+                    // EMIT: TaskFailed (pool exhausted)
+                    event_log.emit(EventKind::TaskFailed {
+                        task_id: Arc::clone(&task_id),
+                        error: e.to_string(),
+                        duration_ms: duration.as_millis() as u64,
+                    });
+                    return IterationResult {
+                        store_id: task_id,
+                        result: TaskResult::failed(e.to_string(), duration),
+                        for_each_info,
+                    };
+                }
+            },
+            None => None,
+        };
+        // `summarize` grants run with what was left as their limit
+        let limited = grant.as_ref().and_then(|grant| grant.limit(&task.action));
+        let action = limited.as_ref().unwrap_or(&task.action);
 
         // EMIT: TaskStarted (with resolved inputs from use: wiring)
         event_log.emit(EventKind::TaskStarted {
@@ -276,15 +1080,66 @@ impl Runner {
         });
 
         // Execute via TaskExecutor (v0.5: pass datastore for lazy binding support)
-        let result = executor
-            .execute(&task_id, &task.action, &bindings, &datastore)
-            .await;
+        let result = match command {
+            DebugCommand::InjectOutput(output) => Ok(output),
+            DebugCommand::EditPrompt(prompt) => match debugger::with_prompt(action, prompt) {
+                Some(edited) => {
+                    executor
+                        .execute(&task_id, &edited, &bindings, &datastore)
+                        .await
+                }
+                None => {
+                    executor
+                        .execute(&task_id, action, &bindings, &datastore)
+                        .await
+                }
+            },
+            _ => {
+                executor
+                    .execute(&task_id, action, &bindings, &datastore)
+                    .await
+            }
+        };
+        if let (Some(pool), Some(grant)) = (&pool, grant) {
+            pool.release(grant);
+        }
+        // Content safety gate on the raw output, before transform: (v0.7)
+        let result = match (result, task.moderate.as_ref()) {
+            (Ok(output), Some(spec)) if spec.covers(ModerationStage::Output) => {
+                executor.moderate_output(&task_id, spec, output).await
+            }
+            (result, _) => result,
+        };
         let duration = start.elapsed();
 
         // Convert result to TaskResult with output policy
         let task_result = match result {
             Ok(output) => {
-                let tr = make_task_result(output, task.output.as_ref(), duration).await;
+                // Stamp provenance metadata if requested (v0.7)
+                let sources = if task.output.as_ref().is_some_and(|p| p.stamps()) {
+                    event_log.with_events(|events| sources_for(events, &task_id))
+                } else {
+                    Vec::new()
+                };
+                let output = match stamp_output(&output, &task, &provenance, &sources) {
+                    std::borrow::Cow::Owned(stamped) => stamped,
+                    std::borrow::Cow::Borrowed(_) => output,
+                };
+                let validate_start = Instant::now();
+                let tr = make_task_result(
+                    output,
+                    task.output.as_ref(),
+                    task.transform.as_ref(),
+                    duration,
+                )
+                .await;
+                emit_phase(
+                    &event_log,
+                    phase_events,
+                    &task_id,
+                    TaskPhase::OutputValidated,
+                    validate_start.elapsed(),
+                );
                 // EMIT: TaskCompleted or TaskFailed (based on result)
                 if tr.is_success() {
                     event_log.emit(EventKind::TaskCompleted {
@@ -320,10 +1175,15 @@ impl Runner {
     }
 
     /// Main execution loop
-    #[instrument(skip(self), fields(workflow_tasks = self.workflow.tasks.len()))]
+    #[instrument(skip(self), fields(
+        run_id = %self.generation_id,
+        workflow_tasks = self.workflow.tasks.len()
+    ))]
     pub async fn run(&self) -> Result<String, NikaError> {
         let workflow_start = Instant::now();
         info!("Starting workflow execution");
+        // Background processes of agents don't outlive the run (v0.7)
+        let _reaper = self.executor.processes().reap_on_drop();
 
         // Check for cancellation before starting (v0.5.2)
         if self.cancel_token.is_cancelled() {
@@ -339,8 +1199,14 @@ impl Runner {
             ));
         }
 
+        // MCP connects and provider checks overlap with validation and
+        // with first-layer tasks that don't need them
+        self.preconnect();
+
         // Validate use: blocks before execution (fail-fast)
-        validate_use_wiring(&self.workflow, &self.flow_graph)?;
+        validate_use_wiring_with(&self.workflow, &self.flow_graph, &self.connections)?;
+        self.bind_inputs()?;
+        self.bind_state()?;
 
         let total_tasks = self.workflow.tasks.len();
         let mut completed = 0;
@@ -350,8 +1216,30 @@ impl Runner {
             task_count: total_tasks,
             generation_id: self.generation_id.clone(),
             workflow_hash: self.workflow.compute_hash(),
+            workflow_name: self.workflow.name.clone(),
             nika_version: env!("CARGO_PKG_VERSION").to_string(),
         });
+        if !self.labels.is_empty() {
+            self.event_log.emit(EventKind::RunLabeled {
+                labels: self.labels.clone(),
+            });
+        }
+
+        // Fail before the first task when a provider or server is down (v0.7)
+        if self.workflow.preflight {
+            if let Err(e) = self.preflight().await {
+                // EMIT: WorkflowFailed (preflight)
+                self.event_log.emit(EventKind::WorkflowFailed {
+                    error: e.to_string(),
+                    failed_task: None,
+                });
+                self.write_trace();
+                return Err(e);
+            }
+        }
+
+        // Shared handles for spawned task iterations
+        let iteration_ctx = self.iteration_context();
 
         if !self.quiet {
             println!(
@@ -416,6 +1304,10 @@ impl Runner {
                 }
             }
 
+            // Unselected `when:` branches are settled before scheduling
+            if self.flow_graph.has_conditions() {
+                self.skip_unselected_tasks();
+            }
             let ready = self.get_ready_tasks();
 
             // Check for completion or deadlock
@@ -489,6 +1381,25 @@ impl Runner {
                                 continue;
                             }
                         }
+                    } else if let Some(expr) = task.for_each.as_ref().and_then(Value::as_str) {
+                        // Binding expression (v0.7): "$items" or "{{use.items}}"
+                        match ResolvedBindings::from_wiring_spec(
+                            task.use_wiring.as_ref(),
+                            &self.datastore,
+                        )
+                        .and_then(|bindings| {
+                            self.executor
+                                .expand_for_each(expr, &bindings, &self.datastore)
+                        }) {
+                            Ok(items) => Some(items),
+                            Err(e) => {
+                                self.datastore.insert(
+                                    intern(&task.id),
+                                    TaskResult::failed(e.to_string(), std::time::Duration::ZERO),
+                                );
+                                continue;
+                            }
+                        }
                     } else if let Some(for_each) = &task.for_each {
                         for_each.as_array().cloned()
                     } else {
@@ -531,81 +1442,80 @@ impl Runner {
                             let task = Arc::clone(&task);
                             let task_id = intern(&format!("{}[{}]", task.id, idx));
                             let parent_task_id = intern(&task.id);
-                            let datastore = self.datastore.clone();
-                            let executor = self.executor.clone();
-                            let event_log = self.event_log.clone();
+                            let ctx = iteration_ctx.clone();
                             let item = item.clone();
                             let var_name = var_name.clone();
                             let semaphore = Arc::clone(&semaphore);
                             let cancelled = Arc::clone(&cancelled);
 
-                            join_set.spawn(async move {
-                                // Acquire semaphore permit (blocks if at concurrency limit)
-                                // Semaphore only errors when closed; we own it and never close it
-                                let _permit = match semaphore.acquire().await {
-                                    Ok(p) => p,
-                                    Err(_) => {
+                            join_set.spawn(
+                                async move {
+                                    // Acquire semaphore permit (blocks if at concurrency limit)
+                                    // Semaphore only errors when closed; we own it and never close it
+                                    let _permit = match semaphore.acquire().await {
+                                        Ok(p) => p,
+                                        Err(_) => {
+                                            return IterationResult {
+                                                store_id: task_id,
+                                                result: TaskResult::failed(
+                                                    "Semaphore closed unexpectedly".to_string(),
+                                                    std::time::Duration::ZERO,
+                                                ),
+                                                for_each_info: Some((parent_task_id, idx)),
+                                            };
+                                        }
+                                    };
+
+                                    // Check cancellation before executing
+                                    if cancelled.load(Ordering::Relaxed) {
                                         return IterationResult {
                                             store_id: task_id,
                                             result: TaskResult::failed(
-                                                "Semaphore closed unexpectedly".to_string(),
+                                                "Cancelled due to fail_fast".to_string(),
                                                 std::time::Duration::ZERO,
                                             ),
                                             for_each_info: Some((parent_task_id, idx)),
                                         };
                                     }
-                                };
 
-                                // Check cancellation before executing
-                                if cancelled.load(Ordering::Relaxed) {
-                                    return IterationResult {
-                                        store_id: task_id,
-                                        result: TaskResult::failed(
-                                            "Cancelled due to fail_fast".to_string(),
-                                            std::time::Duration::ZERO,
-                                        ),
-                                        for_each_info: Some((parent_task_id, idx)),
-                                    };
-                                }
+                                    let result = Self::execute_task_iteration(
+                                        task,
+                                        Arc::clone(&task_id),
+                                        Arc::clone(&parent_task_id),
+                                        ctx,
+                                        Some((var_name, item, idx)),
+                                    )
+                                    .await;
+
+                                    // If failed and fail_fast, set cancellation flag
+                                    if result.result.error().is_some() && fail_fast {
+                                        cancelled.store(true, Ordering::Relaxed);
+                                    }
 
-                                let result = Self::execute_task_iteration(
-                                    task,
-                                    Arc::clone(&task_id),
-                                    Arc::clone(&parent_task_id),
-                                    datastore,
-                                    executor,
-                                    event_log,
-                                    Some((var_name, item, idx)),
-                                )
-                                .await;
-
-                                // If failed and fail_fast, set cancellation flag
-                                if !result.result.is_success() && fail_fast {
-                                    cancelled.store(true, Ordering::Relaxed);
+                                    result
                                 }
-
-                                result
-                            });
+                                .in_current_span(),
+                            );
                         }
                     }
                 } else {
                     // Regular task without for_each
-                    let datastore = self.datastore.clone();
-                    let executor = self.executor.clone();
-                    let event_log = self.event_log.clone();
-
-                    join_set.spawn(async move {
-                        Self::execute_task_iteration(
-                            task,
-                            Arc::clone(&task_id),
-                            task_id,
-                            datastore,
-                            executor,
-                            event_log,
-                            None,
-                        )
-                        .await
-                    });
+                    let ctx = iteration_ctx.clone();
+
+                    // Spawned tasks keep the run span (run_id in logs)
+                    join_set.spawn(
+                        async move {
+                            Self::execute_task_iteration(
+                                task,
+                                Arc::clone(&task_id),
+                                task_id,
+                                ctx,
+                                None,
+                            )
+                            .await
+                        }
+                        .in_current_span(),
+                    );
                 }
             }
 
@@ -653,18 +1563,23 @@ impl Runner {
 
                                 completed += 1;
                                 let success = task_result.is_success();
+                                // Skipped in the debugger (v0.7)
+                                let skipped = task_result.is_skipped();
 
                                 let status = if success {
                                     format!("[{}/{}]", completed, total_tasks).green()
+                                } else if skipped {
+                                    format!("[{}/{}]", completed, total_tasks).yellow()
                                 } else {
                                     format!("[{}/{}]", completed, total_tasks).red()
                                 };
 
-                                let symbol = if success { "✓" } else { "✗" };
                                 let symbol_colored = if success {
-                                    symbol.green()
+                                    "✓".green()
+                                } else if skipped {
+                                    "↷".yellow()
                                 } else {
-                                    symbol.red()
+                                    "✗".red()
                                 };
                                 let duration_str =
                                     format!("({:.1}s)", task_result.duration.as_secs_f32()).dimmed();
@@ -681,8 +1596,20 @@ impl Runner {
                                 }
 
                                 // Store individual result
+                                let store_start = Instant::now();
                                 self.datastore
                                     .insert(Arc::clone(&store_id), task_result.clone());
+                                emit_phase(
+                                    &self.event_log,
+                                    self.phase_events,
+                                    &store_id,
+                                    TaskPhase::ResultStored,
+                                    store_start.elapsed(),
+                                );
+
+                                if for_each_info.is_none() {
+                                    self.save_state(&store_id, &task_result);
+                                }
 
                                 // If this is a for_each iteration, collect for aggregation
                                 if let Some((parent_id, idx)) = for_each_info {
@@ -693,13 +1620,30 @@ impl Runner {
                                 }
                             }
                             Some(Err(e)) => {
+                                let mut error = format!("Task panicked: {}", e);
+                                // Crash bundle for the issue tracker (v0.7)
+                                if e.is_panic() {
+                                    if let Some((path, bundle)) =
+                                        crash::report("task", &self.event_log)
+                                    {
+                                        error.push_str(&format!(
+                                            " (crash bundle: {})",
+                                            path.display()
+                                        ));
+                                        if !self.quiet {
+                                            let url = bundle.issue_url();
+                                            eprintln!("  Report it with a pre-filled issue:\n  {}", url);
+                                            crash::offer_issue(&url);
+                                        }
+                                    }
+                                }
                                 // EMIT: WorkflowFailed (task panic)
                                 self.event_log.emit(EventKind::WorkflowFailed {
-                                    error: format!("Task panicked: {}", e),
+                                    error: error.clone(),
                                     failed_task: None,
                                 });
                                 self.write_trace(); // FIX: Write trace on failure
-                                return Err(NikaError::Execution(format!("Task panicked: {}", e)));
+                                return Err(NikaError::Execution(error));
                             }
                             None => {
                                 // All tasks in this batch completed
@@ -715,9 +1659,11 @@ impl Runner {
                 // Sort by index to preserve order
                 results.sort_by_key(|(idx, _)| *idx);
 
-                // Collect outputs into JSON array
+                // Collect outputs into JSON array (iterations skipped in the
+                // debugger are left out)
                 let outputs: Vec<Value> = results
                     .iter()
+                    .filter(|(_, r)| !r.is_skipped())
                     .map(|(_, r)| {
                         // Try to parse as JSON, fall back to string
                         let output_str = r.output_str();
@@ -729,7 +1675,9 @@ impl Runner {
                 // Calculate aggregate duration and success
                 let total_duration: std::time::Duration =
                     results.iter().map(|(_, r)| r.duration).sum();
-                let all_success = results.iter().all(|(_, r)| r.is_success());
+                let all_success = results
+                    .iter()
+                    .all(|(_, r)| r.is_success() || r.is_skipped());
 
                 // Create aggregated result with JSON array
                 let aggregated_result = if all_success {
@@ -743,66 +1691,938 @@ impl Runner {
                     TaskResult::failed(errors.join("; "), total_duration)
                 };
 
-                // Store aggregated result under parent ID
-                self.datastore.insert(parent_id, aggregated_result);
-            }
-        }
+                // Store aggregated result under parent ID
+                self.save_state(&parent_id, &aggregated_result);
+                self.datastore.insert(parent_id, aggregated_result);
+            }
+        }
+
+        // Get final output
+        let output = self.get_final_output().unwrap_or_default();
+
+        // EMIT: SessionPoolReport (only when the pool was used)
+        let sessions = self.executor.session_stats();
+        if sessions.hits + sessions.misses + sessions.warmed > 0 {
+            self.event_log.emit(EventKind::SessionPoolReport {
+                hits: sessions.hits,
+                misses: sessions.misses,
+                warmed: sessions.warmed,
+                capacity: sessions.capacity,
+            });
+        }
+
+        // Approval decisions only need to survive until the run completes
+        if let Some(checkpoint) = self.executor.approval_gate().checkpoint() {
+            if let Err(e) = checkpoint.clear() {
+                tracing::warn!(error = %e, "Approval checkpoint not removed");
+            }
+        }
+
+        // EMIT: WorkflowCompleted
+        self.event_log.emit(EventKind::WorkflowCompleted {
+            final_output: Arc::new(Value::String(output.clone())),
+            total_duration_ms: workflow_start.elapsed().as_millis() as u64,
+        });
+
+        // Write execution trace to .nika/traces/
+        self.write_trace();
+
+        if !self.quiet {
+            // First-token latency per model (streaming infer calls)
+            let ttft = self.event_log.with_events(ttft_by_model);
+            for stats in ttft {
+                println!(
+                    "  {} {} p95 {}ms {}",
+                    "TTFT".dimmed(),
+                    stats.model,
+                    stats.p95_ms,
+                    format!("(p50 {}ms, {} calls)", stats.p50_ms, stats.calls).dimmed()
+                );
+            }
+            // Provider spend, with what the prompt cache saved (v0.7)
+            if let Some(cost) = self.event_log.with_events(run_cost) {
+                let usage = &cost.usage;
+                let cache = if usage.has_cache_activity() {
+                    format!(
+                        "; cache {} read, {} written, saved ${:.4}",
+                        usage.cache_read_tokens, usage.cache_write_tokens, cost.cache_savings_usd
+                    )
+                } else {
+                    String::new()
+                };
+                println!(
+                    "  {} ${:.4} {}",
+                    "Cost".dimmed(),
+                    cost.cost_usd,
+                    format!(
+                        "({} in, {} out{})",
+                        usage.total_input_tokens(),
+                        usage.output_tokens,
+                        cache
+                    )
+                    .dimmed()
+                );
+            }
+            if let Some(moderation) = self.event_log.with_events(moderation_summary) {
+                let categories: Vec<String> = moderation
+                    .categories
+                    .iter()
+                    .map(|(category, count)| format!("{} {}", category, count))
+                    .collect();
+                println!(
+                    "  {} {} checks, {} flagged{} {}",
+                    "Moderation".dimmed(),
+                    moderation.checks,
+                    moderation.flagged,
+                    if categories.is_empty() {
+                        String::new()
+                    } else {
+                        format!(": {}", categories.join(", "))
+                    },
+                    format!(
+                        "({} blocked, {} redacted)",
+                        moderation.blocked, moderation.redacted
+                    )
+                    .dimmed()
+                );
+            }
+            // External sources the run drew on (v0.7)
+            let sources = self.event_log.with_events(run_sources);
+            if !sources.is_empty() {
+                println!("  {} {} fetched", "Sources".dimmed(), sources.len());
+                for source in &sources {
+                    println!(
+                        "    {} {}",
+                        source.url,
+                        format!("({})", source.details()).dimmed()
+                    );
+                }
+            }
+            if let Some(rate) = sessions.hit_rate() {
+                println!(
+                    "  {} warm-hit {:.0}% {}",
+                    "Sessions".dimmed(),
+                    rate * 100.0,
+                    format!(
+                        "({} warm, {} cold, pool {})",
+                        sessions.hits, sessions.misses, sessions.capacity
+                    )
+                    .dimmed()
+                );
+            }
+            println!("\n{} Done!\n", "✓".green());
+        }
+
+        Ok(output)
+    }
+}
+
+/// EMIT: PhaseCompleted (only when phase events are enabled, v0.7)
+fn emit_phase(
+    event_log: &EventLog,
+    enabled: bool,
+    task_id: &Arc<str>,
+    phase: TaskPhase,
+    duration: Duration,
+) {
+    if enabled {
+        event_log.emit(EventKind::PhaseCompleted {
+            task_id: Arc::clone(task_id),
+            phase,
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+}
+
+/// Find the TaskCompleted event ID that produced a task's output (v0.7)
+///
+/// For for_each tasks the output is aggregated from `task[N]` iterations,
+/// so the last completed iteration is used.
+fn last_completion_id(event_log: &EventLog, source: &str) -> Option<u64> {
+    event_log.with_events(|events| {
+        events.iter().rev().find_map(|e| match &e.kind {
+            EventKind::TaskCompleted { task_id, .. }
+                if task_id.as_ref() == source
+                    || task_id
+                        .strip_prefix(source)
+                        .is_some_and(|rest| rest.starts_with('[')) =>
+            {
+                Some(e.id)
+            }
+            _ => None,
+        })
+    })
+}
+
+/// `use:` aliases of each task bound to `fetch:` or `invoke:` output (v0.7)
+///
+/// These carry third-party content, so the executor checks them with the
+/// injection guard before they are templated into a prompt.
+fn untrusted_inputs(workflow: &Workflow) -> FxHashMap<String, Vec<String>> {
+    let untrusted: FxHashSet<&str> = workflow
+        .tasks
+        .iter()
+        .filter(|t| {
+            matches!(
+                t.action,
+                TaskAction::Fetch { .. } | TaskAction::Invoke { .. }
+            )
+        })
+        .map(|t| t.id.as_str())
+        .collect();
+    workflow
+        .tasks
+        .iter()
+        .filter_map(|task| {
+            let wiring = task.use_wiring.as_ref()?;
+            let mut aliases: Vec<String> = wiring
+                .iter()
+                .filter(|(_, entry)| untrusted.contains(entry.task_id()))
+                .map(|(alias, _)| alias.clone())
+                .collect();
+            aliases.sort();
+            (!aliases.is_empty()).then(|| (task.id.clone(), aliases))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{ExecParams, Flow, FlowEndpoint, Inputs, Task, TaskAction};
+    use crate::binding::{TemplateEngine, TemplateMode};
+    use std::sync::Arc;
+
+    // ═══════════════════════════════════════════════════════════════
+    // QUIET MODE TEST
+    // ═══════════════════════════════════════════════════════════════
+
+    fn make_empty_workflow() -> Workflow {
+        Workflow {
+            schema: "nika/workflow@0.3".to_string(),
+            name: None,
+            provider: "mock".to_string(),
+            model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
+            mcp: None,
+            tasks: vec![],
+            flows: vec![],
+        }
+    }
+
+    #[test]
+    fn test_runner_quiet_mode() {
+        // Default should not be quiet
+        let runner = Runner::new(make_empty_workflow());
+        assert!(!runner.quiet, "Runner should not be quiet by default");
+
+        // quiet() should enable quiet mode
+        let runner = Runner::new(make_empty_workflow()).quiet();
+        assert!(runner.quiet, "Runner should be quiet after .quiet()");
+
+        // Can chain with with_event_log
+        let event_log = crate::event::EventLog::new();
+        let runner = Runner::with_event_log(make_empty_workflow(), event_log).quiet();
+        assert!(runner.quiet, "Runner should be quiet when chained");
+    }
+
+    #[tokio::test]
+    async fn test_trigger_binding_resolves() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+triggers:
+  watch: "./inbox/*.md"
+tasks:
+  - id: show
+    use:
+      file: trigger.path
+      event: trigger.event
+    exec: "echo {{use.event}} {{use.file}}"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow)
+            .quiet()
+            .with_trigger(serde_json::json!({
+                "path": "inbox/note.md",
+                "event": "created",
+            }));
+        let output = runner.run().await.unwrap();
+        assert_eq!(output.trim(), "created inbox/note.md");
+    }
+
+    #[tokio::test]
+    async fn test_state_persists_across_runs() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: batch
+    use:
+      since: state.last_id ?? 0
+    exec: "echo '{\"since\": {{use.since}}, \"last\": 7}'"
+    state:
+      set:
+        last_id: last
+      ttl_secs: 3600
+  - id: report
+    exec: "echo last={{state.last_id}}"
+flows:
+  - source: batch
+    target: report
+"#;
+        let state = Arc::new(StateStore::in_memory());
+        let run = || {
+            let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+            Runner::new(workflow)
+                .quiet()
+                .with_state_store(Arc::clone(&state))
+        };
+
+        // First run: no state yet, the default applies; later tasks see the write
+        let first = run();
+        assert_eq!(first.run().await.unwrap().trim(), "last=7");
+        assert_eq!(
+            first.datastore.resolve_path("batch").unwrap(),
+            serde_json::json!("{\"since\": 0, \"last\": 7}")
+        );
+        assert_eq!(state.get("last_id").unwrap(), Some(serde_json::json!(7)));
+
+        let second = run();
+        second.run().await.unwrap();
+        assert_eq!(
+            second.datastore.resolve_path("batch").unwrap(),
+            serde_json::json!("{\"since\": 7, \"last\": 7}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_embed_then_recall_from_vector_store() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: index
+    embed:
+      input: '[{"id": "keys", "text": "Rotate API keys every month"}, "Deploys run on Fridays", {"text": "Backups are nightly", "metadata": {"page": 3}}]'
+      collection: handbook
+  - id: lookup
+    recall:
+      query: "how often do we rotate keys"
+      collection: handbook
+      top_k: 2
+flows:
+  - source: index
+    target: lookup
+"#;
+        let vectors = Arc::new(VectorStore::in_memory());
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow)
+            .quiet()
+            .with_vector_store(Arc::clone(&vectors));
+        runner.run().await.unwrap();
+
+        let index = runner.datastore.resolve_path("index").unwrap();
+        let index: Value = serde_json::from_str(index.as_str().unwrap()).unwrap();
+        assert_eq!(index["collection"], "handbook");
+        assert_eq!(index["count"], 3);
+        assert_eq!(index["ids"][0], "keys");
+        assert!(index.get("embeddings").is_none());
+
+        let lookup = runner.datastore.resolve_path("lookup").unwrap();
+        let matches: Value = serde_json::from_str(lookup.as_str().unwrap()).unwrap();
+        assert_eq!(matches.as_array().unwrap().len(), 2);
+        assert_eq!(matches[0]["id"], "keys");
+        assert_eq!(
+            vectors.describe("handbook").unwrap(),
+            Some(("mock".to_string(), "mock-embed".to_string(), 3))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retrieve_ranks_local_chunks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("keys.md"),
+            "Rotate the API keys every month.",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("deploy.md"), "Deploys run on Fridays.").unwrap();
+        let yaml = format!(
+            r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: by_meaning
+    retrieve:
+      query: "when do we rotate api keys"
+      files: "{dir}/*.md"
+      top_k: 1
+      collection: kb
+  - id: by_keyword
+    retrieve:
+      query: "fridays"
+      files: "{dir}/*.md"
+      mode: bm25
+"#,
+            dir = dir.path().display()
+        );
+        let vectors = Arc::new(VectorStore::in_memory());
+        let workflow: Workflow = serde_yaml::from_str(&yaml).unwrap();
+        let runner = Runner::new(workflow)
+            .quiet()
+            .with_vector_store(Arc::clone(&vectors));
+        runner.run().await.unwrap();
+
+        let hits = runner.datastore.resolve_path("by_meaning").unwrap();
+        let hits: Value = serde_json::from_str(hits.as_str().unwrap()).unwrap();
+        assert_eq!(hits.as_array().unwrap().len(), 1);
+        assert!(hits[0]["file"].as_str().unwrap().ends_with("keys.md"));
+        assert_eq!(hits[0]["chunk"], 0);
+        // Both files' chunks were cached for the next run
+        assert_eq!(vectors.describe("kb").unwrap().unwrap().2, 2);
+
+        let hits = runner.datastore.resolve_path("by_keyword").unwrap();
+        let hits: Value = serde_json::from_str(hits.as_str().unwrap()).unwrap();
+        assert_eq!(hits.as_array().unwrap().len(), 1);
+        assert_eq!(hits[0]["text"], "Deploys run on Fridays.");
+    }
+
+    #[tokio::test]
+    async fn test_validate_gates_extracted_data() {
+        let yaml = |on_fail: &str| {
+            format!(
+                r#"
+schema: "nika/workflow@0.5"
+tasks:
+  - id: extract
+    exec: "echo '{{\"email\": \"ada@example.com\", \"score\": 120}}'"
+  - id: check
+    use:
+      lead: extract
+    validate:
+      source: $lead
+      schema: {{ type: object, required: [email] }}
+      rules:
+        - field: score
+          type: number
+          max: 100
+      on_fail: {on_fail}
+    output:
+      format: json
+  - id: notify
+    use:
+      email: check.value.email
+    exec: "echo {{{{use.email}}}}"
+flows:
+  - source: extract
+    target: check
+  - source: check
+    target: notify
+"#
+            )
+        };
+
+        let workflow: Workflow = serde_yaml::from_str(&yaml("warn")).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+        let check = runner.datastore.resolve_path("check").unwrap();
+        assert_eq!(check["valid"], false);
+        assert_eq!(check["violations"][0]["path"], "score");
+        assert_eq!(check["violations"][0]["rule"], "max");
+        assert_eq!(
+            runner.datastore.resolve_path("notify").unwrap(),
+            serde_json::json!("ada@example.com")
+        );
+
+        let workflow: Workflow = serde_yaml::from_str(&yaml("fail")).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        assert!(runner.run().await.is_err());
+        let check = runner.datastore.get("check").unwrap();
+        assert!(check.error().unwrap().contains("NIKA-062"));
+        assert!(runner.datastore.get("notify").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_chunks_and_costs_audio() {
+        // 25 s of 8 kHz 16-bit mono silence
+        let dir = tempfile::TempDir::new().unwrap();
+        let samples = vec![0u8; 8000 * 2 * 25];
+        let mut wav = b"RIFF".to_vec();
+        wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt \x10\0\0\0\x01\0\x01\0");
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(b"\x02\0\x10\0data");
+        wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&samples);
+        std::fs::write(dir.path().join("standup.wav"), wav).unwrap();
+
+        let yaml = format!(
+            r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: transcript
+    transcribe:
+      file: "{dir}/standup.wav"
+      model: whisper-1
+      chunk_seconds: 10
+  - id: unsupported
+    transcribe:
+      file: "{dir}/standup.wav"
+      provider: claude
+"#,
+            dir = dir.path().display()
+        );
+        let workflow: Workflow = serde_yaml::from_str(&yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+
+        assert_eq!(
+            runner.datastore.resolve_path("transcript").unwrap(),
+            serde_json::json!(
+                "[mock transcript of standup-1.wav (1/3)] \
+                 [mock transcript of standup-2.wav (2/3)] \
+                 [mock transcript of standup-3.wav (3/3)]"
+            )
+        );
+        // whisper-1 at $0.006/min for 25 s of audio
+        let cost: f64 = runner
+            .event_log()
+            .filter_task("transcript")
+            .iter()
+            .map(|e| match &e.kind {
+                EventKind::ProviderResponded { cost_usd, .. } => *cost_usd,
+                _ => 0.0,
+            })
+            .sum();
+        assert!((cost - 0.0025).abs() < 1e-9);
+
+        let unsupported = runner.datastore.get("unsupported").unwrap();
+        assert!(unsupported.error().unwrap().contains("NIKA-037"));
+    }
+
+    #[tokio::test]
+    async fn test_import_and_export_spreadsheets() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("leads.csv"),
+            "\u{feff}Company Name,E-mail Address,Seats\nAcme,ops@acme.io,40\nInitech,,12\n",
+        )
+        .unwrap();
+
+        let yaml = format!(
+            r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: leads
+    import:
+      file: "{dir}/leads.csv"
+      columns:
+        "Company Name": company
+        "Seats": seats
+    output:
+      format: json
+  - id: report
+    use:
+      rows: leads
+    export:
+      source: $rows
+      file: "{dir}/out/report.xlsx"
+      sheet: Leads
+      columns: [seats, company]
+    output:
+      format: json
+  - id: reread
+    import:
+      file: "{dir}/out/report.xlsx"
+      sheet: Leads
+    output:
+      format: json
+  - id: unknown
+    import:
+      file: "{dir}/leads.ods"
+flows:
+  - source: leads
+    target: report
+  - source: report
+    target: reread
+"#,
+            dir = dir.path().display()
+        );
+        let workflow: Workflow = serde_yaml::from_str(&yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+
+        assert_eq!(
+            runner.datastore.resolve_path("leads").unwrap(),
+            serde_json::json!([
+                {"company": "Acme", "seats": 40},
+                {"company": "Initech", "seats": 12},
+            ])
+        );
+        let report = runner.datastore.resolve_path("report").unwrap();
+        assert_eq!(report["format"], "xlsx");
+        assert_eq!(report["rows"], 2);
+        assert_eq!(report["columns"], serde_json::json!(["seats", "company"]));
+        assert_eq!(
+            runner.datastore.resolve_path("reread").unwrap(),
+            runner.datastore.resolve_path("leads").unwrap()
+        );
 
-        // Get final output
-        let output = self.get_final_output().unwrap_or_default();
+        let unknown = runner.datastore.get("unknown").unwrap();
+        assert!(unknown.error().unwrap().contains("NIKA-096"));
+    }
 
-        // EMIT: WorkflowCompleted
-        self.event_log.emit(EventKind::WorkflowCompleted {
-            final_output: Arc::new(Value::String(output.clone())),
-            total_duration_ms: workflow_start.elapsed().as_millis() as u64,
-        });
+    #[tokio::test]
+    async fn test_rows_filters_groups_and_sorts() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: deals
+    exec: "echo '[{\"rep\": \"ana\", \"amount\": 500, \"won\": true}, {\"rep\": \"bo\", \"amount\": 900, \"won\": true}, {\"rep\": \"ana\", \"amount\": 700, \"won\": true}, {\"rep\": \"bo\", \"amount\": 50, \"won\": false}]'"
+  - id: leaderboard
+    use:
+      deals: deals
+    rows:
+      source: $deals
+      filter: "won"
+      group_by: [rep]
+      aggregate:
+        total: sum(amount)
+        deals: count
+      sort: [-total]
+    output:
+      format: json
+  - id: broken
+    use:
+      deals: deals
+    rows:
+      source: $deals
+      filter: "amount >"
+flows:
+  - source: deals
+    target: [leaderboard, broken]
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
 
-        // Write execution trace to .nika/traces/
-        self.write_trace();
+        assert_eq!(
+            runner.datastore.resolve_path("leaderboard").unwrap(),
+            serde_json::json!([
+                {"rep": "ana", "total": 1200, "deals": 2},
+                {"rep": "bo", "total": 900, "deals": 1},
+            ])
+        );
+        let broken = runner.datastore.get("broken").unwrap();
+        assert!(broken.error().unwrap().contains("NIKA-064"));
+    }
 
-        if !self.quiet {
-            println!("\n{} Done!\n", "✓".green());
-        }
+    #[cfg(feature = "script")]
+    #[tokio::test]
+    async fn test_script_diffs_bindings() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: old
+    exec: "echo '[1, 2, 3]'"
+  - id: new
+    exec: "echo '[2, 3, 4, 5]'"
+  - id: diff
+    use:
+      before: old
+      after: new
+    script: |
+      let added = after.filter(|x| !before.contains(x));
+      #{ added: added, count: added.len() }
+    output:
+      format: json
+  - id: runaway
+    script:
+      code: "loop {}"
+      max_operations: 1000
+flows:
+  - source: [old, new]
+    target: diff
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
 
-        Ok(output)
+        assert_eq!(
+            runner.datastore.resolve_path("diff").unwrap(),
+            serde_json::json!({"added": [4, 5], "count": 2})
+        );
+        let runaway = runner.datastore.get("runaway").unwrap();
+        assert!(runaway.error().unwrap().contains("NIKA-057"));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::ast::{ExecParams, Flow, FlowEndpoint, Task, TaskAction};
-    use std::sync::Arc;
+    #[tokio::test]
+    async fn test_chunk_feeds_for_each() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: doc
+    exec: "printf '# One\\nFirst part.\\n\\n# Two\\nSecond part.\\n\\n# Three\\nThird.\\n'"
+  - id: pieces
+    use:
+      doc: doc
+    chunk:
+      source: $doc
+      by: headings
+    output:
+      format: json
+  - id: sizes
+    use:
+      pieces: pieces
+    for_each: "$pieces"
+    as: piece
+    exec: "echo {{use.piece}} | wc -l"
+flows:
+  - source: doc
+    target: pieces
+  - source: pieces
+    target: sizes
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
 
-    // ═══════════════════════════════════════════════════════════════
-    // QUIET MODE TEST
-    // ═══════════════════════════════════════════════════════════════
+        assert_eq!(
+            runner.datastore.resolve_path("pieces").unwrap(),
+            serde_json::json!([
+                "# One\nFirst part.",
+                "# Two\nSecond part.",
+                "# Three\nThird."
+            ])
+        );
+        let sizes = runner.datastore.resolve_path("sizes").unwrap();
+        assert_eq!(sizes.as_array().map(Vec::len), Some(3));
+    }
 
-    fn make_empty_workflow() -> Workflow {
-        Workflow {
-            schema: "nika/workflow@0.3".to_string(),
-            provider: "mock".to_string(),
-            model: None,
-            mcp: None,
-            tasks: vec![],
-            flows: vec![],
+    #[tokio::test]
+    async fn test_dedupe_minhash_and_embeddings() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: tickets
+    exec: |
+      echo '[{"id": 1, "body": "VPN drops every hour"}, {"id": 2, "body": "vpn drops every hour!"}, {"id": 3, "body": "Invoice PDF is blank"}]'
+  - id: unique
+    use:
+      tickets: tickets
+    dedupe:
+      source: $tickets
+      field: body
+    output:
+      format: json
+  - id: semantic
+    use:
+      tickets: tickets
+    dedupe:
+      source: $tickets
+      field: body
+      by: embeddings
+    output:
+      format: json
+  - id: broken
+    dedupe:
+      source: "not a list"
+flows:
+  - source: tickets
+    target: [unique, semantic]
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+
+        for task in ["unique", "semantic"] {
+            let out = runner.datastore.resolve_path(task).unwrap();
+            assert_eq!(out["kept"].as_array().map(Vec::len), Some(2), "{}", task);
+            assert_eq!(out["dropped"][0]["index"], 1);
+            assert_eq!(out["dropped"][0]["duplicate_of"], 0);
         }
+        let broken = runner.datastore.get("broken").unwrap();
+        assert!(broken.error().unwrap().contains("NIKA-043"));
     }
 
     #[test]
-    fn test_runner_quiet_mode() {
-        // Default should not be quiet
-        let runner = Runner::new(make_empty_workflow());
-        assert!(!runner.quiet, "Runner should not be quiet by default");
+    fn test_untrusted_inputs_follow_fetch_and_invoke() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+mcp:
+  search:
+    command: "echo"
+tasks:
+  - id: page
+    fetch: { url: "https://example.com" }
+  - id: hits
+    invoke: { mcp: search, tool: find }
+  - id: notes
+    exec: "echo notes"
+  - id: summary
+    use:
+      body: page.body
+      found: hits
+      mine: notes
+    infer: "Summarize {{use.body}} {{use.found}} {{use.mine}}"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let inputs = untrusted_inputs(&workflow);
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs["summary"], ["body", "found"]);
+    }
 
-        // quiet() should enable quiet mode
-        let runner = Runner::new(make_empty_workflow()).quiet();
-        assert!(runner.quiet, "Runner should be quiet after .quiet()");
+    #[tokio::test]
+    async fn test_detect_lang_and_blank_translate() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: notes
+    exec: |
+      echo '["The meeting moved to Thursday afternoon.", "La réunion est déplacée à jeudi après-midi."]'
+  - id: langs
+    use:
+      notes: notes
+    detect_lang:
+      source: $notes
+    output:
+      format: json
+  - id: blank
+    use:
+      lang: langs.1.code
+    translate:
+      source: "   "
+      to: "{{use.lang}}"
+  - id: broken
+    detect_lang:
+      source: "hello"
+      allow: [en, xx]
+flows:
+  - source: notes
+    target: langs
+  - source: langs
+    target: blank
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
 
-        // Can chain with with_event_log
-        let event_log = crate::event::EventLog::new();
-        let runner = Runner::with_event_log(make_empty_workflow(), event_log).quiet();
-        assert!(runner.quiet, "Runner should be quiet when chained");
+        let langs = runner.datastore.resolve_path("langs").unwrap();
+        assert_eq!(langs[0]["code"], "en");
+        assert_eq!(langs[1]["code"], "fr");
+        assert_eq!(langs[1]["name"], "French");
+        // Blank text never reaches the (unbuildable) mock provider
+        assert!(runner.datastore.is_success("blank"));
+        let broken = runner.datastore.get("broken").unwrap();
+        assert!(broken.error().unwrap().contains("NIKA-004"));
+    }
+
+    #[tokio::test]
+    async fn test_transform_pipeline_feeds_downstream() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: api
+    exec: |
+      echo '{"choices": [{"text": "result: {\"score\": 7}"}]}'
+    transform:
+      extract: "$.choices[0].text"
+      regex: "result: (.*)"
+      parse: json
+  - id: report
+    use:
+      score: api.score
+    exec: "echo score={{use.score}}"
+  - id: broken
+    exec: "echo plain"
+    transform:
+      - regex: "\\d+"
+flows:
+  - source: api
+    target: report
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+
+        let api = runner.datastore.resolve_path("api").unwrap();
+        assert_eq!(api["score"], 7);
+        let report = runner.datastore.resolve_path("report").unwrap();
+        assert_eq!(report.as_str().unwrap().trim(), "score=7");
+        let broken = runner.datastore.get("broken").unwrap();
+        let error = broken.error().unwrap();
+        assert!(error.contains("NIKA-065"));
+        assert!(error.contains("step 1 (regex)"));
+    }
+
+    #[tokio::test]
+    async fn test_moderate_gates_inputs_and_outputs() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: review
+    exec: "echo 'Great plot, but a massacre in act two. I will kill you if you spoil it.'"
+    moderate:
+      policy: redact
+  - id: summary
+    use:
+      review: review
+    exec: "echo {{use.review}}"
+  - id: reply
+    use:
+      message: forward
+    exec: "echo ok"
+    moderate:
+      on: [input]
+      thresholds:
+        violence: 0.4
+  - id: forward
+    exec: "echo 'I will kill you'"
+    moderate:
+      thresholds:
+        violence: 1.0
+flows:
+  - source: review
+    target: summary
+  - source: forward
+    target: reply
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+
+        let summary = runner.datastore.resolve_path("summary").unwrap();
+        assert_eq!(
+            summary.as_str().unwrap().trim(),
+            "Great plot, but a [redacted] in act two. [redacted] if you spoil it."
+        );
+        let reply = runner.datastore.get("reply").unwrap();
+        assert!(reply
+            .error()
+            .unwrap()
+            .starts_with("[NIKA-066] moderation blocked the input of 'reply': violence"));
+        assert!(runner.datastore.get("forward").unwrap().is_success());
+
+        let totals = runner.event_log.with_events(moderation_summary).unwrap();
+        assert_eq!(totals.checks, 3);
+        assert_eq!((totals.flagged, totals.blocked, totals.redacted), (2, 1, 1));
     }
 
     // ═══════════════════════════════════════════════════════════════
@@ -814,8 +2634,15 @@ mod tests {
         // Create workflow with for_each that runs 3 items
         let workflow = Workflow {
             schema: "nika/workflow@0.3".to_string(),
+            name: None,
             provider: "mock".to_string(),
             model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
             mcp: None,
             tasks: vec![Arc::new(Task {
                 id: "echo_items".to_string(),
@@ -824,9 +2651,18 @@ mod tests {
                 concurrency: None, // Default sequential
                 fail_fast: None,   // Default true
                 decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
                 action: TaskAction::Exec {
                     exec: ExecParams {
                         command: "echo {{use.item}}".to_string(),
+                        timeout: None,
+                        sandbox: None,
+                        container: None,
+                        host: None,
                     },
                 },
                 use_wiring: None,
@@ -869,8 +2705,15 @@ mod tests {
         // Create workflow with for_each that runs 5 items
         let workflow = Workflow {
             schema: "nika/workflow@0.3".to_string(),
+            name: None,
             provider: "mock".to_string(),
             model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
             mcp: None,
             tasks: vec![Arc::new(Task {
                 id: "ordered".to_string(),
@@ -879,9 +2722,18 @@ mod tests {
                 concurrency: None,
                 fail_fast: None,
                 decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
                 action: TaskAction::Exec {
                     exec: ExecParams {
                         command: "echo {{use.x}}".to_string(),
+                        timeout: None,
+                        sandbox: None,
+                        container: None,
+                        host: None,
                     },
                 },
                 use_wiring: None,
@@ -924,8 +2776,15 @@ mod tests {
     fn create_exec_workflow(tasks: Vec<(&str, &str)>, flows: Vec<(&str, &str)>) -> Workflow {
         Workflow {
             schema: "nika/workflow@0.1".to_string(),
+            name: None,
             provider: "mock".to_string(),
             model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
             mcp: None,
             tasks: tasks
                 .into_iter()
@@ -935,6 +2794,11 @@ mod tests {
                         use_wiring: None,
                         output: None,
                         decompose: None,
+                        state: None,
+                        tags: Vec::new(),
+                        template_engine: TemplateEngine::default(),
+                        transform: None,
+                        moderate: None,
                         for_each: None,
                         for_each_as: None,
                         concurrency: None,
@@ -942,6 +2806,10 @@ mod tests {
                         action: TaskAction::Exec {
                             exec: ExecParams {
                                 command: cmd.to_string(),
+                                timeout: None,
+                                sandbox: None,
+                                container: None,
+                                host: None,
                             },
                         },
                     })
@@ -952,11 +2820,33 @@ mod tests {
                 .map(|(src, tgt)| Flow {
                     source: FlowEndpoint::Single(src.to_string()),
                     target: FlowEndpoint::Single(tgt.to_string()),
+                    when: None,
                 })
                 .collect(),
         }
     }
 
+    #[tokio::test]
+    async fn strict_templates_fail_task_on_malformed_ref() {
+        let mut workflow = create_exec_workflow(vec![("greet", "echo {{ use.bad-ref }}")], vec![]);
+        workflow.templates = TemplateMode::Strict;
+
+        // Caught by pre-execution validation before any task runs
+        let runner = Runner::new(workflow).quiet();
+        let err = runner.run().await.unwrap_err();
+        assert!(err.to_string().contains("NIKA-074"));
+        assert!(runner.datastore.get("greet").is_none());
+    }
+
+    #[tokio::test]
+    async fn lenient_templates_pass_malformed_ref_through() {
+        let workflow = create_exec_workflow(vec![("greet", r"echo '\{{x}} {{y}}'")], vec![]);
+
+        let runner = Runner::new(workflow).quiet();
+        let output = runner.run().await.unwrap();
+        assert_eq!(output.trim(), "{{x}} {{y}}");
+    }
+
     #[tokio::test]
     async fn event_sequence_for_single_task() {
         let workflow = create_exec_workflow(vec![("greet", "echo hello")], vec![]);
@@ -1004,6 +2894,391 @@ mod tests {
         assert!(completed.is_some(), "TaskCompleted event not found");
     }
 
+    #[tokio::test]
+    async fn phase_events_only_when_enabled() {
+        let phases = |runner: &Runner| -> Vec<TaskPhase> {
+            runner
+                .event_log()
+                .filter_task("greet")
+                .iter()
+                .filter_map(|e| match &e.kind {
+                    EventKind::PhaseCompleted { phase, .. } => Some(*phase),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let workflow = create_exec_workflow(vec![("greet", "echo hello")], vec![]);
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+        assert!(phases(&runner).is_empty());
+
+        let workflow = create_exec_workflow(vec![("greet", "echo hello")], vec![]);
+        let runner = Runner::new(workflow).quiet().with_phase_events(true);
+        runner.run().await.unwrap();
+        assert_eq!(
+            phases(&runner),
+            vec![
+                TaskPhase::BindingsResolved,
+                TaskPhase::OutputValidated,
+                TaskPhase::ResultStored,
+            ]
+        );
+    }
+
+    #[test]
+    fn session_keys_follow_model_affinity() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: claude
+model: claude-sonnet-4
+tasks:
+  - id: a
+    infer: "one"
+  - id: b
+    infer: "two"
+  - id: c
+    infer:
+      prompt: "three"
+      model: gpt-4o
+      provider: openai
+  - id: d
+    exec: "echo hi"
+  - id: e
+    reduce:
+      source: $a
+      strategy: summarize
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        assert_eq!(
+            runner.session_keys(),
+            vec![
+                SessionKey::new("claude", Some("claude-sonnet-4")),
+                SessionKey::new("openai", Some("gpt-4o")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn preconnect_targets_referenced_servers_once() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: lookup
+    invoke:
+      mcp: novanet
+      tool: novanet_describe
+  - id: research
+    agent:
+      prompt: "dig"
+      mcp: [novanet, search]
+  - id: split
+    decompose:
+      traverse: HAS_CHILD
+      source: $lookup
+      mcp_server: graph
+    exec: "echo {{use.item}}"
+  - id: listed
+    decompose:
+      strategy: static
+      traverse: HAS_CHILD
+      source: $lookup
+      mcp_server: unused
+    exec: "echo {{use.item}}"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        assert_eq!(runner.mcp_servers(), vec!["novanet", "search", "graph"]);
+
+        // No inline mcp: config, so nothing connects; second call is a no-op
+        runner.preconnect();
+        runner.preconnect();
+        let mut join_set = runner.preconnect.lock().take().unwrap();
+        while join_set.join_next().await.is_some() {}
+    }
+
+    #[tokio::test]
+    async fn preflight_stops_before_the_first_task() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+preflight: true
+tasks:
+  - id: greet
+    exec: "echo hello"
+  - id: lookup
+    invoke:
+      mcp: novanet
+      tool: novanet_describe
+  - id: research
+    agent:
+      prompt: "dig"
+      provider: claude
+      model: auto
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        // Routed agent models are not probed
+        assert_eq!(runner.preflight_keys(), Vec::<SessionKey>::new());
+
+        let err = runner.run().await.unwrap_err();
+        assert_eq!(err.code(), "NIKA-039");
+        let events = runner.event_log().events();
+        assert!(events.iter().any(|e| matches!(
+            &e.kind,
+            EventKind::PreflightChecked { target, error: Some(_), .. } if target == "mcp:novanet"
+        )));
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e.kind, EventKind::TaskStarted { .. })));
+    }
+
+    #[tokio::test]
+    async fn context_pool_charges_llm_tasks_and_applies_policy() {
+        let workflow = |policy: &str| -> Workflow {
+            serde_yaml::from_str(&format!(
+                r#"
+schema: "nika/workflow@0.5"
+provider: mock
+context_pool:
+  tokens: 100
+  reserve: 100
+  on_exhausted: {}
+tasks:
+  - id: draft
+    infer: "Draft the intro"
+  - id: outline
+    infer: "Outline the rest"
+  - id: list
+    exec: "echo done"
+flows:
+  - source: draft
+    target: [outline, list]
+"#,
+                policy
+            ))
+            .unwrap()
+        };
+        let pool_events = |runner: &Runner| -> Vec<(String, String)> {
+            runner
+                .event_log()
+                .events()
+                .into_iter()
+                .filter_map(|e| match e.kind {
+                    EventKind::ContextPoolUpdated {
+                        task_id, action, ..
+                    } => Some((task_id.to_string(), action)),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // `draft` used part of the pool and nothing else runs to free more
+        let runner = Runner::new(workflow("queue")).quiet();
+        let _ = runner.run().await;
+        let events = pool_events(&runner);
+        assert_eq!(events.len(), 3, "{:?}", events);
+        assert_eq!(events[2], ("outline".to_string(), "exhausted".to_string()));
+        assert!(runner.event_log().events().iter().any(|e| matches!(
+            &e.kind,
+            EventKind::TaskFailed { task_id, error, .. }
+                if &**task_id == "outline" && error.contains("NIKA-126")
+        )));
+
+        // `summarize` runs it with what is left
+        let runner = Runner::new(workflow("summarize")).quiet();
+        runner.run().await.unwrap();
+        let actions: Vec<_> = pool_events(&runner).into_iter().map(|(_, a)| a).collect();
+        assert_eq!(actions, ["reserved", "released", "summarized", "released"]);
+    }
+
+    #[tokio::test]
+    async fn session_pool_report_only_when_used() {
+        let workflow = create_exec_workflow(vec![("greet", "echo hello")], vec![]);
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+        let reported = runner
+            .event_log()
+            .events()
+            .iter()
+            .any(|e| matches!(e.kind, EventKind::SessionPoolReport { .. }));
+        assert!(!reported, "exec-only workflows never touch the pool");
+    }
+
+    #[tokio::test]
+    async fn conditional_flows_skip_unselected_branch() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: review
+    exec: "echo '{\"status\": \"approved\"}'"
+  - id: publish
+    exec: "echo published"
+  - id: revise
+    exec: "echo revised"
+  - id: resubmit
+    exec: "echo resubmitted"
+  - id: notify
+    exec: "echo notified"
+flows:
+  - source: review
+    target: publish
+    when: "{{from.status}} == 'approved'"
+  - source: review
+    target: revise
+    when: "{{from.status}} != 'approved'"
+  - source: revise
+    target: resubmit
+  - source: [publish, resubmit]
+    target: notify
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let runner = Runner::new(workflow).quiet();
+        let output = runner.run().await.unwrap();
+        assert_eq!(output, "notified");
+
+        let store = &runner.datastore;
+        assert!(store.is_success("publish"));
+        assert!(store.is_skipped("revise"));
+        // Cascades down the unselected branch
+        assert!(store.is_skipped("resubmit"));
+        // Join runs: publish -> notify was taken
+        assert!(store.is_success("notify"));
+
+        let skipped: Vec<Arc<str>> = runner
+            .event_log()
+            .events()
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                EventKind::TaskSkipped { task_id, .. } => Some(task_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(skipped, vec![Arc::from("revise"), Arc::from("resubmit")]);
+    }
+
+    #[tokio::test]
+    async fn approval_rejection_routes_flow_and_clears_checkpoint() {
+        use crate::ast::ApprovalDecision;
+        use crate::runtime::{ApprovalCheckpoint, ApprovalGate};
+
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: draft
+    exec: "echo post"
+  - id: review
+    use:
+      text: draft
+    approve:
+      prompt: "Publish {{use.text}}?"
+  - id: publish
+    exec: "echo published"
+  - id: archive
+    exec: "echo archived"
+flows:
+  - source: draft
+    target: review
+  - source: review
+    target: publish
+    when: "{{from.approved}}"
+  - source: review
+    target: archive
+    when: "{{from.approved}} == false"
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("approvals.json");
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            let pending: crate::runtime::PendingApproval = rx.recv().await.unwrap();
+            assert_eq!(pending.request.prompt, "Publish post?");
+            pending.answer(ApprovalDecision::Reject);
+        });
+
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let gate =
+            ApprovalGate::channel(tx).with_checkpoint(ApprovalCheckpoint::load(&path).unwrap());
+        let runner = Runner::new(workflow).quiet().with_approval_gate(gate);
+        let output = runner.run().await.unwrap();
+
+        assert_eq!(output, "archived");
+        assert!(runner.datastore.is_skipped("publish"));
+        assert!(
+            !path.exists(),
+            "checkpoint is removed once the run completes"
+        );
+    }
+
+    #[tokio::test]
+    async fn debugger_edits_skips_and_injects() {
+        use crate::runtime::{DebugCommand, Debugger, PausedTask};
+
+        let workflow = create_exec_workflow(
+            vec![("a", "echo one"), ("b", "echo two"), ("c", "echo three")],
+            vec![("a", "c")],
+        );
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<PausedTask>(1);
+        let debugger = Arc::new(Debugger::new(tx));
+        tokio::spawn(async move {
+            while let Some(paused) = rx.recv().await {
+                let command = match &*paused.stop.task_id {
+                    "a" => {
+                        assert_eq!(paused.stop.rendered, "echo one");
+                        assert!(paused.stop.editable);
+                        DebugCommand::EditPrompt("echo edited".to_string())
+                    }
+                    "b" => DebugCommand::Skip,
+                    _ => DebugCommand::InjectOutput("injected".to_string()),
+                };
+                paused.resume(command);
+            }
+        });
+
+        let runner = Runner::new(workflow).quiet().with_debugger(debugger);
+        runner.run().await.unwrap();
+
+        let store = &runner.datastore;
+        assert_eq!(store.get_output("a").unwrap().as_str(), Some("edited"));
+        assert!(store.is_skipped("b"));
+        assert_eq!(store.get_output("c").unwrap().as_str(), Some("injected"));
+
+        let debugged: Vec<(String, String)> = runner
+            .event_log()
+            .events()
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                EventKind::TaskDebugged { task_id, action } => Some((task_id.to_string(), action)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(debugged.len(), 3);
+        assert!(debugged.contains(&("b".to_string(), "skip".to_string())));
+    }
+
+    #[tokio::test]
+    async fn conditional_flows_reject_invalid_condition() {
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: review
+    exec: "echo ok"
+  - id: publish
+    exec: "echo published"
+flows:
+  - source: review
+    target: publish
+    when: "{{review.status}} == 'approved'"
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let err = Runner::new(workflow).quiet().run().await.unwrap_err();
+        assert_eq!(err.code(), "NIKA-022");
+    }
+
     #[tokio::test]
     async fn event_sequence_for_chained_tasks() {
         // Two tasks: greet -> shout (shout depends on greet)
@@ -1049,6 +3324,308 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn binding_resolved_records_source_event() {
+        use crate::binding::{UseEntry, WiringSpec};
+
+        let mut wiring = WiringSpec::default();
+        wiring.insert("word".to_string(), UseEntry::new("greet"));
+        wiring.insert(
+            "missing".to_string(),
+            UseEntry::with_default("greet.nope", serde_json::json!("N/A")),
+        );
+
+        let exec = |id: &str, command: &str, use_wiring| {
+            Arc::new(Task {
+                id: id.to_string(),
+                for_each: None,
+                for_each_as: None,
+                concurrency: None,
+                fail_fast: None,
+                decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
+                action: TaskAction::Exec {
+                    exec: ExecParams {
+                        command: command.to_string(),
+                        timeout: None,
+                        sandbox: None,
+                        container: None,
+                        host: None,
+                    },
+                },
+                use_wiring,
+                output: None,
+            })
+        };
+        let workflow = Workflow {
+            schema: "nika/workflow@0.3".to_string(),
+            name: None,
+            provider: "mock".to_string(),
+            model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
+            mcp: None,
+            tasks: vec![
+                exec("greet", "echo hello", None),
+                exec("shout", "echo {{use.word}} {{use.missing}}", Some(wiring)),
+            ],
+            flows: vec![Flow {
+                source: FlowEndpoint::Single("greet".to_string()),
+                target: FlowEndpoint::Single("shout".to_string()),
+                when: None,
+            }],
+        };
+
+        let runner = Runner::new(workflow).quiet();
+        runner.run().await.unwrap();
+
+        let greet_completed = runner
+            .event_log()
+            .filter_task("greet")
+            .iter()
+            .find(|e| matches!(&e.kind, EventKind::TaskCompleted { .. }))
+            .map(|e| e.id);
+
+        let resolved: Vec<_> = runner
+            .event_log()
+            .filter_task("shout")
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                EventKind::BindingResolved {
+                    alias,
+                    source_task,
+                    source_event_id,
+                    used_default,
+                    ..
+                } => Some((alias, source_task, source_event_id, used_default)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(resolved.len(), 2);
+        // Sorted by alias for deterministic traces
+        assert_eq!(resolved[0].0, "missing");
+        assert!(resolved[0].3, "missing should fall back to default");
+        assert_eq!(resolved[1].0, "word");
+        assert_eq!(resolved[1].1.as_deref(), Some("greet"));
+        assert_eq!(resolved[1].2, greet_completed);
+        assert!(!resolved[1].3);
+    }
+
+    #[tokio::test]
+    async fn replay_answers_infer_from_recorded_trace() {
+        use crate::ast::InferParams;
+        use crate::event::Event;
+
+        let task_id: Arc<str> = Arc::from("greet");
+        let recorded = vec![
+            Event {
+                id: 0,
+                timestamp_ms: 0,
+                kind: EventKind::TaskStarted {
+                    task_id: Arc::clone(&task_id),
+                    verb: Arc::from("infer"),
+                    inputs: serde_json::json!({}),
+                },
+            },
+            Event {
+                id: 1,
+                timestamp_ms: 5,
+                kind: EventKind::TaskCompleted {
+                    task_id,
+                    output: Arc::new(serde_json::json!("Hi there!")),
+                    duration_ms: 5,
+                },
+            },
+        ];
+
+        let workflow = Workflow {
+            schema: "nika/workflow@0.3".to_string(),
+            name: None,
+            // No real provider is ever called in replay mode
+            provider: "claude".to_string(),
+            model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
+            mcp: None,
+            tasks: vec![Arc::new(Task {
+                id: "greet".to_string(),
+                for_each: None,
+                for_each_as: None,
+                concurrency: None,
+                fail_fast: None,
+                decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
+                action: TaskAction::Infer {
+                    infer: InferParams {
+                        prompt: "Say hi".to_string(),
+                        provider: None,
+                        model: None,
+                        max_tokens: None,
+                        hedge: None,
+                        images: Vec::new(),
+                    },
+                },
+                use_wiring: None,
+                output: None,
+            })],
+            flows: vec![],
+        };
+
+        let replay = Arc::new(ReplayProvider::from_events(&recorded));
+        let runner = Runner::new(workflow)
+            .quiet()
+            .with_replay(Arc::clone(&replay));
+        let output = runner.run().await.unwrap();
+
+        assert_eq!(output, "Hi there!");
+        assert_eq!(replay.remaining(), 0);
+        assert!(runner.event_log().events().iter().any(|e| matches!(
+            &e.kind,
+            EventKind::ProviderCalled { provider, .. } if provider == "replay"
+        )));
+    }
+
+    #[tokio::test]
+    async fn cassette_replay_answers_infer_without_provider() {
+        use crate::ast::InferParams;
+        use crate::provider::cassette::{CassetteMode, InteractionKind};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("greet.yaml");
+
+        // Record a completion for the exact request the executor will make
+        let recorder = Cassette::load(&path, CassetteMode::Record).unwrap();
+        let request = serde_json::json!({"provider": "claude", "model": null, "prompt": "Say hi"});
+        let _: Value = recorder
+            .through(InteractionKind::Infer, request, || async {
+                Ok(serde_json::json!({"text": "Hi from tape", "output_tokens": 3}))
+            })
+            .await
+            .unwrap();
+
+        let workflow = Workflow {
+            schema: "nika/workflow@0.3".to_string(),
+            name: None,
+            provider: "claude".to_string(),
+            model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
+            mcp: None,
+            tasks: vec![Arc::new(Task {
+                id: "greet".to_string(),
+                for_each: None,
+                for_each_as: None,
+                concurrency: None,
+                fail_fast: None,
+                decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
+                action: TaskAction::Infer {
+                    infer: InferParams {
+                        prompt: "Say hi".to_string(),
+                        provider: None,
+                        model: None,
+                        max_tokens: None,
+                        hedge: None,
+                        images: Vec::new(),
+                    },
+                },
+                use_wiring: None,
+                output: None,
+            })],
+            flows: vec![],
+        };
+
+        let cassette = Arc::new(Cassette::load(&path, CassetteMode::Replay).unwrap());
+        let runner = Runner::new(workflow).quiet().with_cassette(cassette);
+        let output = runner.run().await.unwrap();
+        assert_eq!(output, "Hi from tape");
+    }
+
+    #[tokio::test]
+    async fn hedged_infer_falls_back_to_the_hedge() {
+        use crate::provider::cassette::{CassetteMode, InteractionKind};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("hedge.yaml");
+
+        // Only the hedge's request is on tape: the primary fails (replay miss)
+        let recorder = Cassette::load(&path, CassetteMode::Record).unwrap();
+        let request =
+            serde_json::json!({"provider": "openai", "model": "gpt-4o", "prompt": "Say hi"});
+        let _: Value = recorder
+            .through(InteractionKind::Infer, request, || async {
+                Ok(serde_json::json!({"text": "Hi from the hedge", "output_tokens": 4}))
+            })
+            .await
+            .unwrap();
+
+        let yaml = r#"
+schema: "nika/workflow@0.5"
+provider: claude
+tasks:
+  - id: greet
+    infer:
+      prompt: "Say hi"
+      hedge:
+        provider: openai
+        model: gpt-4o
+        after_ms: 10000
+"#;
+        let workflow: Workflow = serde_yaml::from_str(yaml).unwrap();
+        let cassette = Arc::new(Cassette::load(&path, CassetteMode::Replay).unwrap());
+        let runner = Runner::new(workflow).quiet().with_cassette(cassette);
+        // The primary's failure fires the hedge without waiting 10s
+        let output = tokio::time::timeout(Duration::from_secs(5), runner.run())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(output, "Hi from the hedge");
+
+        let events = runner.event_log().events();
+        let calls: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match &e.kind {
+                EventKind::ProviderCalled { provider, .. } => Some(provider.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls, vec!["claude", "openai"]);
+        // Newest call answered first, so responses pair with their calls
+        let finishes: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match &e.kind {
+                EventKind::ProviderResponded { finish_reason, .. } => Some(finish_reason.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(finishes, vec!["stop", "error"]);
+    }
+
     #[tokio::test]
     async fn event_sequence_for_parallel_tasks() {
         // Two independent tasks that can run in parallel
@@ -1346,8 +3923,15 @@ mod tests {
         // Create workflow with for_each that specifies concurrency=2
         let workflow = Workflow {
             schema: "nika/workflow@0.3".to_string(),
+            name: None,
             provider: "mock".to_string(),
             model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
             mcp: None,
             tasks: vec![Arc::new(Task {
                 id: "concurrent".to_string(),
@@ -1356,9 +3940,18 @@ mod tests {
                 concurrency: Some(2), // Limit to 2 concurrent
                 fail_fast: None,
                 decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
                 action: TaskAction::Exec {
                     exec: ExecParams {
                         command: "echo {{use.item}}".to_string(),
+                        timeout: None,
+                        sandbox: None,
+                        container: None,
+                        host: None,
                     },
                 },
                 use_wiring: None,
@@ -1390,8 +3983,15 @@ mod tests {
         // Create workflow with for_each where middle item fails
         let workflow = Workflow {
             schema: "nika/workflow@0.3".to_string(),
+            name: None,
             provider: "mock".to_string(),
             model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
             mcp: None,
             tasks: vec![Arc::new(Task {
                 id: "failfast".to_string(),
@@ -1400,10 +4000,19 @@ mod tests {
                 concurrency: Some(1), // Sequential to make failure predictable
                 fail_fast: Some(true),
                 decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
                 action: TaskAction::Exec {
                     exec: ExecParams {
                         // Exit with error if item is "FAIL"
                         command: "test '{{use.item}}' != 'FAIL' && echo {{use.item}}".to_string(),
+                        timeout: None,
+                        sandbox: None,
+                        container: None,
+                        host: None,
                     },
                 },
                 use_wiring: None,
@@ -1426,8 +4035,15 @@ mod tests {
         // Create workflow with fail_fast=false
         let workflow = Workflow {
             schema: "nika/workflow@0.3".to_string(),
+            name: None,
             provider: "mock".to_string(),
             model: None,
+            templates: TemplateMode::default(),
+            triggers: None,
+            injection: InjectionPolicy::default(),
+            inputs: Inputs::default(),
+            preflight: false,
+            context_pool: None,
             mcp: None,
             tasks: vec![Arc::new(Task {
                 id: "continue".to_string(),
@@ -1436,9 +4052,18 @@ mod tests {
                 concurrency: None,
                 fail_fast: Some(false), // Explicitly disable fail_fast
                 decompose: None,
+                state: None,
+                tags: Vec::new(),
+                template_engine: TemplateEngine::default(),
+                transform: None,
+                moderate: None,
                 action: TaskAction::Exec {
                     exec: ExecParams {
                         command: "echo {{use.item}}".to_string(),
+                        timeout: None,
+                        sandbox: None,
+                        container: None,
+                        host: None,
                     },
                 },
                 use_wiring: None,
@@ -1804,4 +4429,19 @@ mod tests {
         let inner_result = result.unwrap().unwrap();
         assert!(inner_result.is_ok(), "Workflow should succeed");
     }
+
+    #[tokio::test]
+    async fn run_labels_follow_workflow_started() {
+        let workflow: Workflow = serde_yaml::from_str(
+            "schema: nika/workflow@0.5\ntasks:\n  - id: a\n    exec: \"echo a\"\n",
+        )
+        .unwrap();
+        let labels = BTreeMap::from([("model".to_string(), "gpt-4o".to_string())]);
+        let runner = Runner::new(workflow).quiet().with_labels(labels.clone());
+        runner.run().await.unwrap();
+
+        let events = runner.event_log().events();
+        assert!(matches!(&events[0].kind, EventKind::WorkflowStarted { .. }));
+        assert_eq!(events[1].kind, EventKind::RunLabeled { labels });
+    }
 }
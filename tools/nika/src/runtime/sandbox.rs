@@ -0,0 +1,211 @@
+//! Exec sandbox enforcement (v0.7)
+//!
+//! Applies an [`ExecSandbox`] to the `sh -c` child of an `exec:` task and
+//! maps failures back to the limit that caused them:
+//!
+//! - `cwd`: checked before spawn, the child starts there
+//! - `env`: the environment is cleared down to the allowlist (`PATH` is kept)
+//! - `cpu_secs` / `memory_mb`: `RLIMIT_CPU` / `RLIMIT_AS` set before `exec`
+//! - `network: false`: a fresh network namespace with only loopback (Linux)
+//!
+//! Rlimits are inherited, so they bound every process the command starts.
+
+use std::path::Path;
+use std::process::ExitStatus;
+
+use crate::ast::ExecSandbox;
+
+/// A sandbox limit that was hit: `(limit, reason)`
+pub type Violation = (&'static str, String);
+
+/// Signal numbers (`SIGABRT`, `SIGKILL`, `SIGSEGV`, `SIGXCPU`) as seen by `sh`
+const SIGABRT: i32 = 6;
+const SIGKILL: i32 = 9;
+const SIGSEGV: i32 = 11;
+const SIGXCPU: i32 = 24;
+
+/// stderr fragments of commands that died for lack of memory
+const MEMORY_ERRORS: &[&str] = &[
+    "cannot allocate memory",
+    "out of memory",
+    "memory exhausted",
+    "memoryerror",
+    "failed to allocate",
+];
+
+/// stderr fragments of commands that could not reach the network
+const NETWORK_ERRORS: &[&str] = &[
+    "network is unreachable",
+    "could not resolve host",
+    "temporary failure in name resolution",
+    "name or service not known",
+    "no route to host",
+];
+
+/// Configure `cmd` to run inside `sandbox`
+pub fn apply(cmd: &mut tokio::process::Command, sandbox: &ExecSandbox) -> Result<(), Violation> {
+    if let Some(cwd) = &sandbox.cwd {
+        if !Path::new(cwd).is_dir() {
+            return Err(("cwd", format!("'{}' is not a directory", cwd)));
+        }
+        cmd.current_dir(cwd);
+    }
+
+    if sandbox.env.is_some() {
+        cmd.env_clear();
+        for (name, value) in std::env::vars_os() {
+            let keep = name
+                .to_str()
+                .is_some_and(|n| n == "PATH" || sandbox.allows_env(n));
+            if keep {
+                cmd.env(name, value);
+            }
+        }
+    }
+
+    limit(cmd, sandbox)
+}
+
+#[cfg(unix)]
+fn limit(cmd: &mut tokio::process::Command, sandbox: &ExecSandbox) -> Result<(), Violation> {
+    if !sandbox.network && !cfg!(target_os = "linux") {
+        return Err((
+            "network",
+            "network: false is only supported on Linux".into(),
+        ));
+    }
+
+    let cpu = sandbox.cpu_secs;
+    let memory = sandbox.memory_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+    let isolate = !sandbox.network;
+    // SAFETY: the closure runs in the forked child and only makes
+    // async-signal-safe syscalls (setrlimit, unshare).
+    unsafe {
+        cmd.pre_exec(move || {
+            if let Some(secs) = cpu {
+                // SIGXCPU at the soft limit, SIGKILL one second later
+                let lim = libc::rlimit {
+                    rlim_cur: secs as libc::rlim_t,
+                    rlim_max: secs.saturating_add(1) as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_CPU, &lim) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            if let Some(bytes) = memory {
+                let lim = libc::rlimit {
+                    rlim_cur: bytes as libc::rlim_t,
+                    rlim_max: bytes as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_AS, &lim) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            #[cfg(target_os = "linux")]
+            if isolate
+                && libc::unshare(libc::CLONE_NEWNET) != 0
+                && libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) != 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+            #[cfg(not(target_os = "linux"))]
+            let _ = isolate;
+            Ok(())
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn limit(_cmd: &mut tokio::process::Command, sandbox: &ExecSandbox) -> Result<(), Violation> {
+    if sandbox.cpu_secs.is_some() || sandbox.memory_mb.is_some() || !sandbox.network {
+        return Err((
+            "platform",
+            "cpu_secs, memory_mb and network need a Unix host".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Explain why the sandboxed child could not be started
+pub fn spawn_failure(sandbox: &ExecSandbox, error: &std::io::Error) -> Violation {
+    if !sandbox.network {
+        (
+            "network",
+            format!(
+                "cannot isolate the network (needs user namespaces or CAP_SYS_ADMIN): {}",
+                error
+            ),
+        )
+    } else {
+        (
+            "setup",
+            format!("cannot start the sandboxed command: {}", error),
+        )
+    }
+}
+
+/// Attribute a failed run to a sandbox limit, if one explains it
+pub fn diagnose(sandbox: &ExecSandbox, status: &ExitStatus, stderr: &str) -> Option<Violation> {
+    let signal = killed_by(status);
+    let stderr = stderr.to_lowercase();
+
+    if let Some(secs) = sandbox.cpu_secs {
+        if signal == Some(SIGXCPU) || (signal == Some(SIGKILL) && sandbox.memory_mb.is_none()) {
+            return Some(("cpu", format!("exceeded {}s of CPU time", secs)));
+        }
+    }
+    if let Some(mb) = sandbox.memory_mb {
+        let crashed = matches!(signal, Some(SIGABRT | SIGSEGV | SIGKILL));
+        if crashed || MEMORY_ERRORS.iter().any(|e| stderr.contains(e)) {
+            return Some(("memory", format!("exceeded {} MB of memory", mb)));
+        }
+    }
+    if !sandbox.network && NETWORK_ERRORS.iter().any(|e| stderr.contains(e)) {
+        return Some(("network", "network access is disabled".to_string()));
+    }
+    None
+}
+
+/// Signal that ended the command, directly or as reported by `sh` (128 + n)
+fn killed_by(status: &ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return Some(signal);
+        }
+    }
+    status.code().filter(|c| *c > 128).map(|c| c - 128)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    #[test]
+    fn diagnose_maps_failures_to_limits() {
+        let sandbox = ExecSandbox {
+            cpu_secs: Some(2),
+            network: false,
+            ..Default::default()
+        };
+        // sh reports a child killed by SIGXCPU as exit code 152
+        let xcpu = ExitStatus::from_raw(152 << 8);
+        assert_eq!(diagnose(&sandbox, &xcpu, "").unwrap().0, "cpu");
+
+        let failed = ExitStatus::from_raw(6 << 8);
+        let (limit, _) = diagnose(&sandbox, &failed, "curl: (6) Could not resolve host").unwrap();
+        assert_eq!(limit, "network");
+        assert!(diagnose(&sandbox, &failed, "no such file").is_none());
+
+        let memory = ExecSandbox {
+            memory_mb: Some(64),
+            ..Default::default()
+        };
+        let (limit, reason) = diagnose(&memory, &failed, "MemoryError").unwrap();
+        assert_eq!(limit, "memory");
+        assert_eq!(reason, "exceeded 64 MB of memory");
+    }
+}
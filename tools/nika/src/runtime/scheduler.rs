@@ -0,0 +1,483 @@
+//! Cron scheduler for `triggers:` workflows (v0.7, `nika schedule`)
+//!
+//! `nika schedule start` discovers `.nika.yaml` files with a
+//! `triggers: { cron: ... }` block and runs each one when its schedule
+//! fires. Every run writes its own trace, like `nika run`. Workflow files
+//! are re-scanned every minute, so added, edited or removed schedules are
+//! picked up without a restart.
+//!
+//! The outcome of each workflow's last run is kept in
+//! `.nika/schedule.json`, which `nika schedule list` reads. Runs missed while
+//! the scheduler was down are not caught up, and a run still in progress
+//! when its next time comes is not started twice.
+//!
+//! Scheduled runs are unattended: `approve:` tasks use their `default:`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use ignore::WalkBuilder;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::ast::Workflow;
+use crate::error::{NikaError, Result};
+use crate::store::{StateStore, VectorStore};
+use crate::util::CronSchedule;
+
+use super::approval::ApprovalGate;
+use super::runner::Runner;
+
+/// Run history written by the scheduler
+pub const STATE_PATH: &str = ".nika/schedule.json";
+
+/// How often workflow files are re-scanned
+const RESCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A workflow with a cron trigger
+#[derive(Debug, Clone)]
+pub struct ScheduledWorkflow {
+    pub path: PathBuf,
+    /// `workflow:` name, or the file name
+    pub name: String,
+    pub schedule: CronSchedule,
+}
+
+/// Result of scanning for triggered workflows
+#[derive(Debug)]
+pub struct Discovery<T> {
+    pub workflows: Vec<T>,
+    /// Workflow files that could not be read or have an invalid trigger
+    pub errors: Vec<(PathBuf, NikaError)>,
+}
+
+impl<T> Discovery<T> {
+    /// Load each workflow file in `paths`, keeping those `load` accepts
+    pub(crate) fn scan(paths: &[PathBuf], load: impl Fn(&Path) -> Result<Option<T>>) -> Self {
+        let mut discovery = Self {
+            workflows: Vec::new(),
+            errors: Vec::new(),
+        };
+        for path in workflow_files(paths) {
+            match load(&path) {
+                Ok(Some(workflow)) => discovery.workflows.push(workflow),
+                Ok(None) => {}
+                Err(e) => discovery.errors.push((path, e)),
+            }
+        }
+        discovery
+    }
+}
+
+/// Workflow files in `paths` (files, or directories searched recursively,
+/// respecting .gitignore)
+fn workflow_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if path.is_file() {
+            files.push(path.clone());
+            continue;
+        }
+        let walker = WalkBuilder::new(path)
+            .hidden(true)
+            .follow_links(false)
+            .build();
+        files.extend(walker.flatten().map(|e| e.into_path()).filter(|p| {
+            p.is_file()
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.ends_with(".nika.yaml") || n.ends_with(".nika.yml"))
+        }));
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Find workflows with a cron trigger in `paths`
+pub fn discover(paths: &[PathBuf]) -> Discovery<ScheduledWorkflow> {
+    Discovery::scan(paths, load)
+}
+
+/// Read one workflow file (`None` if it has no cron trigger)
+fn load(path: &Path) -> Result<Option<ScheduledWorkflow>> {
+    let workflow: Workflow = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+    let Some(schedule) = workflow.triggers.as_ref().and_then(|t| t.schedule()) else {
+        return Ok(None);
+    };
+    Ok(Some(ScheduledWorkflow {
+        path: path.to_path_buf(),
+        name: workflow_name(&workflow, path),
+        schedule: schedule?,
+    }))
+}
+
+/// `workflow:` name, or the file name
+pub(crate) fn workflow_name(workflow: &Workflow, path: &Path) -> String {
+    workflow.name.clone().unwrap_or_else(|| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Outcome of a scheduled run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Success,
+    Failed,
+}
+
+/// Last run of one workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Start time (RFC 3339, local)
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub status: RunStatus,
+    /// Trace of the run (`nika trace show <id>`)
+    pub generation_id: Option<String>,
+    pub error: Option<String>,
+    /// Scheduled runs so far
+    pub runs: u64,
+}
+
+/// Contents of `.nika/schedule.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleState {
+    /// PID of the scheduler that last wrote the file
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// Last time that scheduler was alive (RFC 3339)
+    #[serde(default)]
+    pub heartbeat: Option<String>,
+    /// Keyed by workflow path
+    #[serde(default)]
+    pub workflows: BTreeMap<String, RunRecord>,
+}
+
+impl ScheduleState {
+    /// Load a state file (a missing file starts empty)
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn last_run(&self, workflow: &Path) -> Option<&RunRecord> {
+        self.workflows.get(&workflow.display().to_string())
+    }
+
+    /// Whether a scheduler wrote a heartbeat recently
+    pub fn scheduler_alive(&self, now: DateTime<Local>) -> bool {
+        self.heartbeat
+            .as_deref()
+            .and_then(|h| DateTime::parse_from_rfc3339(h).ok())
+            .is_some_and(|h| {
+                now.signed_duration_since(h).to_std().unwrap_or_default() < RESCAN_INTERVAL * 2
+            })
+    }
+}
+
+/// What the scheduler reports as it works
+#[derive(Debug, Clone)]
+pub enum ScheduleEvent {
+    /// Schedules found after a (re)scan
+    Scanned {
+        workflows: Vec<(ScheduledWorkflow, DateTime<Local>)>,
+        errors: Vec<(PathBuf, String)>,
+    },
+    Started {
+        name: String,
+        path: PathBuf,
+    },
+    Finished {
+        name: String,
+        path: PathBuf,
+        record: RunRecord,
+    },
+    /// The previous run is still going, this firing is dropped
+    Overlapping {
+        name: String,
+    },
+}
+
+/// Scheduler loop (see module docs)
+pub struct Scheduler {
+    paths: Vec<PathBuf>,
+    state_path: PathBuf,
+    state: Arc<Mutex<ScheduleState>>,
+    /// Next firing per workflow path, with the expression it was computed from
+    next: HashMap<PathBuf, (String, DateTime<Local>)>,
+    running: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Project `state:` store, shared by all runs so their writes are serialized
+    store: Arc<StateStore>,
+    report: Arc<dyn Fn(ScheduleEvent) + Send + Sync>,
+}
+
+impl Scheduler {
+    /// Schedule the workflows found in `paths`, recording runs in `state_path`
+    pub fn new(paths: Vec<PathBuf>, state_path: impl Into<PathBuf>) -> Result<Self> {
+        let state_path = state_path.into();
+        let state = ScheduleState::load(&state_path)?;
+        Ok(Self {
+            paths,
+            state_path,
+            state: Arc::new(Mutex::new(state)),
+            next: HashMap::new(),
+            running: Arc::new(Mutex::new(HashSet::new())),
+            store: Arc::new(StateStore::project()),
+            report: Arc::new(|_| {}),
+        })
+    }
+
+    /// Receive progress events (scans, run start and end)
+    pub fn with_reporter(mut self, report: impl Fn(ScheduleEvent) + Send + Sync + 'static) -> Self {
+        self.report = Arc::new(report);
+        self
+    }
+
+    /// Run until `shutdown` is cancelled
+    pub async fn run(mut self, shutdown: CancellationToken) -> Result<()> {
+        let mut last_scan: Option<Instant> = None;
+        loop {
+            let now = Local::now();
+            if last_scan.is_none_or(|t| t.elapsed() >= RESCAN_INTERVAL) {
+                self.rescan(now)?;
+                last_scan = Some(Instant::now());
+            }
+
+            let due: Vec<PathBuf> = self
+                .next
+                .iter()
+                .filter(|(_, (_, at))| *at <= now)
+                .map(|(path, _)| path.clone())
+                .collect();
+            for path in due {
+                self.fire(&path, now);
+            }
+
+            // Sleep until the next firing, waking at least once per rescan
+            let wake = self
+                .next
+                .values()
+                .map(|(_, at)| at.signed_duration_since(now).to_std().unwrap_or_default())
+                .min()
+                .unwrap_or(RESCAN_INTERVAL)
+                .min(RESCAN_INTERVAL);
+            tokio::select! {
+                _ = shutdown.cancelled() => return Ok(()),
+                _ = tokio::time::sleep(wake) => {}
+            }
+        }
+    }
+
+    /// Re-discover workflows and refresh their next firing times
+    fn rescan(&mut self, now: DateTime<Local>) -> Result<()> {
+        let discovery = discover(&self.paths);
+        let mut next = HashMap::new();
+        let mut scanned = Vec::new();
+        for workflow in discovery.workflows {
+            let at = match self.next.remove(&workflow.path) {
+                // Unchanged schedule keeps its pending firing
+                Some((expr, at)) if expr == workflow.schedule.as_str() => Some(at),
+                _ => workflow.schedule.next_after(&now),
+            };
+            if let Some(at) = at {
+                next.insert(
+                    workflow.path.clone(),
+                    (workflow.schedule.as_str().to_string(), at),
+                );
+                scanned.push((workflow, at));
+            }
+        }
+        self.next = next;
+
+        {
+            let mut state = self.state.lock();
+            state.pid = Some(std::process::id());
+            state.heartbeat = Some(now.to_rfc3339());
+            state.save(&self.state_path)?;
+        }
+        (self.report)(ScheduleEvent::Scanned {
+            workflows: scanned,
+            errors: discovery
+                .errors
+                .into_iter()
+                .map(|(path, e)| (path, e.to_string()))
+                .collect(),
+        });
+        Ok(())
+    }
+
+    /// Start a run of `path` and move its schedule forward
+    fn fire(&mut self, path: &Path, now: DateTime<Local>) {
+        let Ok(Some(workflow)) = load(path) else {
+            self.next.remove(path);
+            return;
+        };
+        match workflow.schedule.next_after(&now) {
+            Some(at) => {
+                self.next.insert(
+                    path.to_path_buf(),
+                    (workflow.schedule.as_str().to_string(), at),
+                );
+            }
+            None => {
+                self.next.remove(path);
+            }
+        }
+
+        if !self.running.lock().insert(path.to_path_buf()) {
+            (self.report)(ScheduleEvent::Overlapping {
+                name: workflow.name,
+            });
+            return;
+        }
+        let running = Arc::clone(&self.running);
+        let state = Arc::clone(&self.state);
+        let state_path = self.state_path.clone();
+        let store = Arc::clone(&self.store);
+        let report = Arc::clone(&self.report);
+        tokio::spawn(async move {
+            report(ScheduleEvent::Started {
+                name: workflow.name.clone(),
+                path: workflow.path.clone(),
+            });
+            let record = {
+                let previous = state.lock().last_run(&workflow.path).map(|r| r.runs);
+                execute(&workflow.path, now, previous.unwrap_or(0) + 1, store).await
+            };
+            {
+                let mut state = state.lock();
+                state
+                    .workflows
+                    .insert(workflow.path.display().to_string(), record.clone());
+                if let Err(e) = state.save(&state_path) {
+                    tracing::warn!(error = %e, "Failed to save schedule state");
+                }
+            }
+            running.lock().remove(&workflow.path);
+            report(ScheduleEvent::Finished {
+                name: workflow.name,
+                path: workflow.path,
+                record,
+            });
+        });
+    }
+}
+
+/// Run a workflow file unattended
+async fn execute(
+    path: &Path,
+    started_at: DateTime<Local>,
+    runs: u64,
+    store: Arc<StateStore>,
+) -> RunRecord {
+    let start = Instant::now();
+    let mut generation_id = None;
+    let result: Result<String> = async {
+        let yaml = tokio::fs::read_to_string(path).await?;
+        let workflow: Workflow = serde_yaml::from_str(&yaml)?;
+        workflow.validate_schema()?;
+        let runner = Runner::new(workflow)
+            .quiet()
+            .with_approval_gate(ApprovalGate::non_interactive())
+            .with_state_store(store)
+            .with_vector_store(Arc::new(VectorStore::project()));
+        generation_id = Some(runner.generation_id().to_string());
+        runner.run().await
+    }
+    .await;
+
+    RunRecord {
+        started_at: started_at.to_rfc3339(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        status: if result.is_ok() {
+            RunStatus::Success
+        } else {
+            RunStatus::Failed
+        },
+        generation_id,
+        error: result.err().map(|e| e.to_string()),
+        runs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEDULED: &str = r#"
+schema: "nika/workflow@0.5"
+workflow: weekly-report
+provider: mock
+triggers:
+  cron: "0 9 * * MON"
+tasks:
+  - id: report
+    exec: "echo report"
+"#;
+
+    #[test]
+    fn test_discover_finds_cron_workflows() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("report.nika.yaml"), SCHEDULED).unwrap();
+        std::fs::write(
+            dir.path().join("plain.nika.yaml"),
+            "schema: \"nika/workflow@0.5\"\ntasks:\n  - id: a\n    exec: echo a\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("broken.nika.yaml"),
+            SCHEDULED.replace("0 9 * * MON", "0 9 * *"),
+        )
+        .unwrap();
+
+        let discovery = discover(&[dir.path().to_path_buf()]);
+        assert_eq!(discovery.workflows.len(), 1);
+        assert_eq!(discovery.workflows[0].name, "weekly-report");
+        assert_eq!(discovery.workflows[0].schedule.as_str(), "0 9 * * MON");
+        assert_eq!(discovery.errors.len(), 1);
+        assert!(matches!(
+            discovery.errors[0].1,
+            NikaError::InvalidCron { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_records_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.nika.yaml");
+        std::fs::write(&path, SCHEDULED).unwrap();
+
+        let record = execute(&path, Local::now(), 3, Arc::new(StateStore::in_memory())).await;
+        assert_eq!(record.status, RunStatus::Success, "{:?}", record.error);
+        assert_eq!(record.runs, 3);
+        assert!(record.generation_id.unwrap().starts_with("gen-"));
+
+        let state_path = dir.path().join("schedule.json");
+        let state = ScheduleState {
+            heartbeat: Some(Local::now().to_rfc3339()),
+            ..Default::default()
+        };
+        state.save(&state_path).unwrap();
+        let state = ScheduleState::load(&state_path).unwrap();
+        assert!(state.scheduler_alive(Local::now()));
+        assert!(!state.scheduler_alive(Local::now() + chrono::Duration::minutes(5)));
+    }
+}
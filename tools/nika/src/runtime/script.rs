@@ -0,0 +1,145 @@
+//! Sandboxed Rhai engine behind `script:` tasks (v0.7)
+//!
+//! The engine has no file, network or environment access: `import` of
+//! script modules is disabled along with `eval`, and every run is bounded
+//! by an operation budget, a wall-clock timeout and size limits on strings,
+//! arrays, maps and nesting. `print`/`debug` go to the debug log.
+//!
+//! Bindings cross as JSON: objects become maps, arrays arrays, and text
+//! holding a JSON object or array is parsed first. The value of the last
+//! expression comes back as JSON (`()` is `null`).
+
+use serde_json::Value;
+
+/// Limits for one script run
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(feature = "script"), allow(dead_code))]
+pub struct ScriptLimits {
+    pub max_operations: u64,
+    pub timeout_ms: u64,
+}
+
+/// Run `code` with each `(alias, value)` in scope; the error is the reason
+#[cfg(feature = "script")]
+pub fn run_script(
+    code: &str,
+    inputs: Vec<(String, Value)>,
+    limits: ScriptLimits,
+) -> Result<Value, String> {
+    use std::time::{Duration, Instant};
+
+    use rhai::module_resolvers::DummyModuleResolver;
+    use rhai::{Dynamic, Engine, EvalAltResult, Scope};
+
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(limits.max_operations)
+        .set_max_call_levels(64)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(16 * 1024 * 1024)
+        .set_max_array_size(1_000_000)
+        .set_max_map_size(1_000_000)
+        .disable_symbol("eval");
+    engine.on_print(|text| tracing::debug!(target: "nika::script", "{}", text));
+    engine.on_debug(
+        |text, _, position| tracing::debug!(target: "nika::script", "{} ({})", text, position),
+    );
+    let deadline = Instant::now() + Duration::from_millis(limits.timeout_ms);
+    engine.on_progress(move |operations| {
+        (operations % 1024 == 0 && Instant::now() >= deadline).then(Dynamic::default)
+    });
+
+    let mut scope = Scope::new();
+    for (alias, value) in inputs {
+        let value = match value {
+            Value::String(s) => match serde_json::from_str::<Value>(&s) {
+                Ok(parsed) if parsed.is_object() || parsed.is_array() => parsed,
+                _ => Value::String(s),
+            },
+            other => other,
+        };
+        let dynamic =
+            rhai::serde::to_dynamic(&value).map_err(|e| format!("binding '{}': {}", alias, e))?;
+        scope.push_dynamic(alias, dynamic);
+    }
+
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, code)
+        .map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) => {
+                format!("timed out after {} ms", limits.timeout_ms)
+            }
+            EvalAltResult::ErrorTooManyOperations(..) => format!(
+                "exceeded the budget of {} operations",
+                limits.max_operations
+            ),
+            other => other.to_string(),
+        })?;
+    rhai::serde::from_dynamic::<Value>(&result)
+        .map_err(|e| format!("result is not JSON-compatible: {}", e))
+}
+
+/// Built without the `script` feature: every script fails
+#[cfg(not(feature = "script"))]
+pub fn run_script(
+    _code: &str,
+    _inputs: Vec<(String, Value)>,
+    _limits: ScriptLimits,
+) -> Result<Value, String> {
+    Err("nika was built without the `script` feature".to_string())
+}
+
+#[cfg(all(test, feature = "script"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LIMITS: ScriptLimits = ScriptLimits {
+        max_operations: 100_000,
+        timeout_ms: 2_000,
+    };
+
+    fn run(code: &str, inputs: Vec<(&str, Value)>) -> Result<Value, String> {
+        let inputs = inputs
+            .into_iter()
+            .map(|(alias, value)| (alias.to_string(), value))
+            .collect();
+        run_script(code, inputs, LIMITS)
+    }
+
+    #[test]
+    fn test_bindings_in_json_out() {
+        let out = run(
+            "let added = after.filter(|x| !before.contains(x)); #{ added: added, n: added.len() }",
+            vec![("before", json!([1, 2])), ("after", json!("[1, 2, 3, 4]"))],
+        )
+        .unwrap();
+        assert_eq!(out, json!({"added": [3, 4], "n": 2}));
+
+        let out = run(
+            "lead.contact.email.to_upper()",
+            vec![("lead", json!({"contact": {"email": "ada@x.io"}}))],
+        )
+        .unwrap();
+        assert_eq!(out, json!("ADA@X.IO"));
+        assert_eq!(run("let x = 1;", vec![]).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_sandbox_limits() {
+        let err = run("loop {}", vec![]).unwrap_err();
+        assert_eq!(err, "exceeded the budget of 100000 operations");
+
+        let slow = ScriptLimits {
+            max_operations: 0,
+            timeout_ms: 50,
+        };
+        let err = run_script("loop {}", Vec::new(), slow).unwrap_err();
+        assert_eq!(err, "timed out after 50 ms");
+
+        assert!(run("import \"secrets\" as s; 1", vec![]).is_err());
+        assert!(run("eval(\"1 + 1\")", vec![]).is_err());
+        assert!(run("let x = ;", vec![]).unwrap_err().contains("line 1"));
+    }
+}
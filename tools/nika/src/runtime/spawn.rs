@@ -47,6 +47,7 @@ use serde_json::{json, Value};
 use crate::ast::AgentParams;
 use crate::event::{EventKind, EventLog};
 use crate::mcp::McpClient;
+use crate::runtime::{ApprovalGate, ToolPolicy};
 
 /// Parameters for spawning a child agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +84,8 @@ pub struct SpawnAgentTool {
     mcp_clients: FxHashMap<String, Arc<McpClient>>,
     /// MCP server names for child agents (from parent AgentParams.mcp)
     mcp_names: Vec<String>,
+    /// Tool approval passed on to child agents (v0.7)
+    tool_policy: Option<(Arc<ToolPolicy>, Arc<ApprovalGate>)>,
 }
 
 impl SpawnAgentTool {
@@ -106,6 +109,7 @@ impl SpawnAgentTool {
             event_log,
             mcp_clients: FxHashMap::default(),
             mcp_names: Vec::new(),
+            tool_policy: None,
         }
     }
 
@@ -133,9 +137,16 @@ impl SpawnAgentTool {
             event_log,
             mcp_clients,
             mcp_names,
+            tool_policy: None,
         }
     }
 
+    /// Child agents ask before tool calls like their parent (v0.7)
+    pub fn with_tool_policy(mut self, policy: Arc<ToolPolicy>, gate: Arc<ApprovalGate>) -> Self {
+        self.tool_policy = Some((policy, gate));
+        self
+    }
+
     /// Get the tool name
     pub fn name(&self) -> &str {
         "spawn_agent"
@@ -241,6 +252,9 @@ impl SpawnAgentTool {
             self.mcp_clients.clone(),
         )
         .map_err(|e| SpawnAgentError::ExecutionFailed(e.to_string()))?;
+        if let Some((policy, gate)) = &self.tool_policy {
+            child_loop = child_loop.with_tool_policy(Arc::clone(policy), Arc::clone(gate));
+        }
 
         // Execute child agent with auto-detected provider (production mode)
         // Uses ANTHROPIC_API_KEY or OPENAI_API_KEY from environment
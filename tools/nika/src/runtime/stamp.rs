@@ -0,0 +1,269 @@
+//! Provenance Stamping - embed run metadata in task outputs (v0.7)
+//!
+//! When a task sets `output.stamp: true`, its text output is stamped with
+//! the workflow name, workflow hash, generation ID and model so downstream
+//! consumers can trace generated content back to the run that produced it.
+//! External sources behind the output (URLs fetched upstream, see
+//! `event::sources`) are listed alongside.
+//!
+//! | Detected content | Stamp |
+//! |------------------|-------|
+//! | HTML document    | Trailer comment `<!-- nika: ... -->` |
+//! | SVG image        | XMP packet in `<metadata>` |
+//! | Anything else    | Markdown front-matter (`nika:` key, merged into existing front-matter) |
+//!
+//! JSON outputs (`format: json`) are never stamped.
+
+use std::borrow::Cow;
+
+use crate::ast::{Task, TaskAction};
+
+/// Run metadata embedded in stamped outputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Provenance {
+    /// Workflow name (`workflow:` key), if declared
+    pub workflow: Option<String>,
+    /// Workflow version hash (see `Workflow::compute_hash`)
+    pub workflow_hash: String,
+    /// Generation ID of the run
+    pub generation_id: String,
+    /// Model used (workflow default, overridden per task)
+    pub model: Option<String>,
+}
+
+impl Provenance {
+    /// Resolve the model for a task (task override > workflow default)
+    fn model_for<'a>(&'a self, task: &'a Task) -> Option<&'a str> {
+        let task_model = match &task.action {
+            TaskAction::Infer { infer } => infer.model.as_deref(),
+            TaskAction::Agent { agent } => agent.model.as_deref(),
+            _ => None,
+        };
+        task_model.or(self.model.as_deref())
+    }
+
+    fn fields<'a>(&'a self, task: &'a Task) -> [(&'static str, &'a str); 4] {
+        [
+            ("workflow", self.workflow.as_deref().unwrap_or("")),
+            ("workflow_hash", &self.workflow_hash),
+            ("generation_id", &self.generation_id),
+            ("model", self.model_for(task).unwrap_or("")),
+        ]
+    }
+}
+
+/// Stamp a task output if its output policy requests it
+///
+/// `sources` are the external URLs the output was built from. Returns the
+/// output unchanged when `stamp` is off or the format is parsed (json,
+/// yaml, csv, ...), since a stamp would break the parse.
+pub fn stamp_output<'a>(
+    output: &'a str,
+    task: &Task,
+    provenance: &Provenance,
+    sources: &[String],
+) -> Cow<'a, str> {
+    let Some(policy) = task.output.as_ref() else {
+        return Cow::Borrowed(output);
+    };
+    if !policy.stamps() || policy.format().is_structured() {
+        return Cow::Borrowed(output);
+    }
+
+    let fields = provenance.fields(task);
+    let head = output.trim_start().get(..64).unwrap_or(output.trim_start());
+    let head = head.to_ascii_lowercase();
+
+    let stamped =
+        if head.starts_with("<svg") || (head.starts_with("<?xml") && output.contains("<svg")) {
+            stamp_svg(output, &fields, sources)
+        } else if head.starts_with("<!doctype html") || head.starts_with("<html") {
+            stamp_html(output, &fields, sources)
+        } else {
+            stamp_markdown(output, &fields, sources)
+        };
+    Cow::Owned(stamped)
+}
+
+/// Markdown: YAML front-matter with a `nika:` block
+fn stamp_markdown(output: &str, fields: &[(&str, &str)], sources: &[String]) -> String {
+    let mut block = String::from("nika:\n");
+    for (key, value) in fields {
+        block.push_str(&format!("  {}: {}\n", key, yaml_scalar(value)));
+    }
+    if !sources.is_empty() {
+        block.push_str("  sources:\n");
+        for url in sources {
+            block.push_str(&format!("    - {}\n", yaml_scalar(url)));
+        }
+    }
+
+    // Merge into existing front-matter if present
+    if let Some(rest) = output.strip_prefix("---\n") {
+        if let Some(end) = rest.find("\n---") {
+            let (front, body) = rest.split_at(end + 1);
+            return format!("---\n{}{}{}", front, block, body);
+        }
+    }
+
+    format!("---\n{}---\n\n{}", block, output)
+}
+
+/// HTML: trailer comment after the document
+fn stamp_html(output: &str, fields: &[(&str, &str)], sources: &[String]) -> String {
+    let mut attrs: Vec<String> = fields
+        .iter()
+        // "--" is not allowed inside HTML comments
+        .map(|(k, v)| format!("{}={}", k, v.replace("--", "-")))
+        .collect();
+    if !sources.is_empty() {
+        attrs.push(format!("sources={}", sources.join(",").replace("--", "-")));
+    }
+    let sep = if output.ends_with('\n') { "" } else { "\n" };
+    format!("{}{}<!-- nika: {} -->\n", output, sep, attrs.join(" "))
+}
+
+/// SVG: XMP packet inside `<metadata>` right after the opening `<svg>` tag
+fn stamp_svg(output: &str, fields: &[(&str, &str)], sources: &[String]) -> String {
+    let mut attrs: Vec<String> = fields
+        .iter()
+        .map(|(k, v)| format!("nika:{}=\"{}\"", k, xml_escape(v)))
+        .collect();
+    if !sources.is_empty() {
+        attrs.push(format!(
+            "nika:sources=\"{}\"",
+            xml_escape(&sources.join(" "))
+        ));
+    }
+    let packet = format!(
+        "<metadata><x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\
+         <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\
+         <rdf:Description rdf:about=\"\" xmlns:nika=\"https://nika.dev/ns/provenance/1.0/\" {}/>\
+         </rdf:RDF></x:xmpmeta></metadata>",
+        attrs.join(" ")
+    );
+
+    match output
+        .find("<svg")
+        .and_then(|start| output[start..].find('>').map(|end| start + end + 1))
+    {
+        Some(pos) => format!("{}{}{}", &output[..pos], packet, &output[pos..]),
+        None => output.to_string(),
+    }
+}
+
+/// Quote YAML scalars that would otherwise be misparsed
+fn yaml_scalar(value: &str) -> String {
+    if value.is_empty()
+        || value.contains(": ")
+        || value.contains('#')
+        || value.starts_with(|c: char| !c.is_ascii_alphanumeric())
+    {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provenance() -> Provenance {
+        Provenance {
+            workflow: Some("blog-post".into()),
+            workflow_hash: "00ff00ff00ff00ff".into(),
+            generation_id: "gen-1234".into(),
+            model: Some("claude-sonnet".into()),
+        }
+    }
+
+    fn task(yaml: &str) -> Task {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_no_stamp_by_default() {
+        let t = task("id: t\ninfer: \"x\"\noutput:\n  format: text");
+        let out = stamp_output("# Title", &t, &provenance(), &[]);
+        assert!(matches!(out, Cow::Borrowed("# Title")));
+    }
+
+    #[test]
+    fn test_markdown_front_matter() {
+        let t = task("id: t\ninfer: \"x\"\noutput:\n  stamp: true");
+        let out = stamp_output("# Title\n\nBody", &t, &provenance(), &[]);
+        assert!(out.starts_with("---\nnika:\n  workflow: blog-post\n"));
+        assert!(out.contains("  generation_id: gen-1234\n"));
+        assert!(out.contains("  model: claude-sonnet\n---\n\n# Title"));
+    }
+
+    #[test]
+    fn test_markdown_lists_sources() {
+        let t = task("id: t\ninfer: \"x\"\noutput:\n  stamp: true");
+        let sources = [
+            "https://a.example/post".to_string(),
+            "https://b.example/data.json".to_string(),
+        ];
+        let out = stamp_output("Body", &t, &provenance(), &sources);
+        assert!(out.contains(
+            "  sources:\n    - https://a.example/post\n    - https://b.example/data.json\n---\n"
+        ));
+        let out = stamp_output("<html></html>", &t, &provenance(), &sources);
+        assert!(out.contains(" sources=https://a.example/post,https://b.example/data.json -->"));
+    }
+
+    #[test]
+    fn test_markdown_merges_existing_front_matter() {
+        let t = task("id: t\ninfer: \"x\"\noutput:\n  stamp: true");
+        let out = stamp_output("---\ntitle: Hello\n---\nBody", &t, &provenance(), &[]);
+        assert!(out.starts_with("---\ntitle: Hello\nnika:\n"));
+        assert!(out.ends_with("\n---\nBody"));
+        assert_eq!(out.matches("---").count(), 2);
+    }
+
+    #[test]
+    fn test_html_trailer_comment() {
+        let t = task("id: t\ninfer: \"x\"\noutput:\n  stamp: true");
+        let out = stamp_output("<!DOCTYPE html><html></html>", &t, &provenance(), &[]);
+        assert!(out.starts_with("<!DOCTYPE html><html></html>\n<!-- nika: workflow=blog-post"));
+        assert!(out.trim_end().ends_with("-->"));
+    }
+
+    #[test]
+    fn test_svg_xmp_packet() {
+        let t = task("id: t\ninfer: \"x\"\noutput:\n  stamp: true");
+        let out = stamp_output(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\"><rect/></svg>",
+            &t,
+            &provenance(),
+            &["https://a.example/x?a=1&b=2".to_string()],
+        );
+        assert!(out.contains("\"><metadata><x:xmpmeta"));
+        assert!(out.contains("nika:sources=\"https://a.example/x?a=1&amp;b=2\""));
+        assert!(out.contains("nika:generation_id=\"gen-1234\""));
+        assert!(out.ends_with("</metadata><rect/></svg>"));
+    }
+
+    #[test]
+    fn test_task_model_override() {
+        let t = task("id: t\ninfer:\n  prompt: x\n  model: gpt-4o\noutput:\n  stamp: true");
+        let out = stamp_output("text", &t, &provenance(), &[]);
+        assert!(out.contains("  model: gpt-4o\n"));
+    }
+
+    #[test]
+    fn test_json_format_never_stamped() {
+        let t = task("id: t\ninfer: \"x\"\noutput:\n  format: json\n  stamp: true");
+        let out = stamp_output("{\"a\": 1}", &t, &provenance(), &[]);
+        assert_eq!(out, "{\"a\": 1}");
+    }
+}
@@ -0,0 +1,428 @@
+//! Subagent Delegation Tool (v0.7, `subagents: true`)
+//!
+//! `spawn_subagent` lets an agent hand a self-contained job to a fresh
+//! agent and get its final answer back as the tool result — the bridge
+//! pattern, without writing a second task and wiring it through `use:`.
+//!
+//! Unlike `spawn_agent`, the subagent is isolated: it sees none of the
+//! parent's conversation, only the prompt it is given, and only the
+//! parent's MCP tools the call names. It inherits the provider, model and
+//! tool approval of its parent.
+//!
+//! ## Depth Limit
+//!
+//! The root agent is at depth 1. A subagent may itself spawn subagents
+//! when `spawn_subagent` is in its allowlist and its depth is below the
+//! parent's `depth_limit`.
+//!
+//! ## Events
+//!
+//! Subagents run as `{parent}.sub{n}` (`triage.sub2.sub1` for a nested
+//! one), so their `AgentTurn` events can be traced back to the task.
+//! Each emits `AgentSpawned` before its first turn and `AgentComplete`
+//! when it answers.
+//!
+//! ## Example
+//!
+//! ```json
+//! {
+//!   "prompt": "List the public items of crates/core without docs",
+//!   "tools": ["fs_read", "fs_glob"],
+//!   "max_turns": 8
+//! }
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use rig::completion::ToolDefinition;
+use rig::tool::{ToolDyn, ToolError};
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::ast::AgentParams;
+use crate::event::{EventKind, EventLog};
+use crate::mcp::McpClient;
+use crate::runtime::{ApprovalGate, ToolPolicy};
+
+/// Arguments of a `spawn_subagent` call
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnSubagentParams {
+    /// Everything the subagent needs to know: it sees nothing else
+    pub prompt: String,
+    /// Names of the tools it may call
+    pub tools: Vec<String>,
+    /// Turn cap, at most the parent's `max_turns`
+    pub max_turns: u32,
+}
+
+/// Tool spawning isolated subagents
+///
+/// Added by [`RigAgentLoop::with_subagents`](super::RigAgentLoop::with_subagents).
+#[derive(Clone)]
+pub struct SpawnSubagentTool {
+    /// Depth of the agent holding the tool (1 = the task's agent)
+    depth: u32,
+    /// `depth_limit` of the task
+    max_depth: u32,
+    /// Task ID of the agent holding the tool
+    parent_task_id: Arc<str>,
+    /// Provider, model, MCP servers and turn cap subagents inherit
+    params: Arc<AgentParams>,
+    /// Event log for subagent events
+    event_log: EventLog,
+    /// MCP clients the allowlisted tools come from
+    mcp_clients: FxHashMap<String, Arc<McpClient>>,
+    /// Tool approval of the task
+    tool_policy: Option<(Arc<ToolPolicy>, Arc<ApprovalGate>)>,
+    /// Subagents spawned so far, numbering the next one
+    spawned: Arc<AtomicU32>,
+}
+
+impl SpawnSubagentTool {
+    /// Tool for the agent of `task_id`, at depth 1
+    pub fn new(
+        task_id: &str,
+        params: &AgentParams,
+        event_log: EventLog,
+        mcp_clients: FxHashMap<String, Arc<McpClient>>,
+    ) -> Self {
+        Self {
+            depth: 1,
+            max_depth: params.effective_depth_limit(),
+            parent_task_id: Arc::from(task_id),
+            params: Arc::new(params.clone()),
+            event_log,
+            mcp_clients,
+            tool_policy: None,
+            spawned: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Subagents ask before tool calls like their parent
+    pub fn with_tool_policy(mut self, policy: Arc<ToolPolicy>, gate: Arc<ApprovalGate>) -> Self {
+        self.tool_policy = Some((policy, gate));
+        self
+    }
+
+    /// Whether the agent holding the tool may still spawn
+    pub fn can_spawn(&self) -> bool {
+        self.depth < self.max_depth
+    }
+
+    /// Names a subagent may put in its allowlist: the parent's MCP tools,
+    /// then `spawn_subagent` if the subagent could nest further
+    pub fn available_tools(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .params
+            .mcp
+            .iter()
+            .filter_map(|name| self.mcp_clients.get(name))
+            .flat_map(|client| client.get_tool_definitions())
+            .map(|def| def.name)
+            .collect();
+        if self.depth + 1 < self.max_depth {
+            names.push("spawn_subagent".to_string());
+        }
+        names
+    }
+
+    /// The same tool, held by the subagent `task_id`
+    fn nested(&self, task_id: &str) -> Self {
+        Self {
+            depth: self.depth + 1,
+            parent_task_id: Arc::from(task_id),
+            spawned: Arc::new(AtomicU32::new(0)),
+            ..self.clone()
+        }
+    }
+
+    /// Tool definition, listing the tools a subagent can get
+    ///
+    /// All properties are required for OpenAI's strict mode.
+    pub fn definition(&self) -> ToolDefinition {
+        let available = self.available_tools();
+        let tools = if available.is_empty() {
+            "none".to_string()
+        } else {
+            available.join(", ")
+        };
+        ToolDefinition {
+            name: "spawn_subagent".to_string(),
+            description: format!(
+                "Delegate a self-contained job to a fresh subagent and get its final answer. \
+                 The subagent does not see this conversation: put everything it needs in the \
+                 prompt. Tools it can be given: {tools}."
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "prompt": {
+                        "type": "string",
+                        "description": "The job, with all the context the subagent needs"
+                    },
+                    "tools": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Names of the tools the subagent may call"
+                    },
+                    "max_turns": {
+                        "type": "integer",
+                        "description": "Turns the subagent may take"
+                    }
+                },
+                "required": ["prompt", "tools", "max_turns"],
+                "additionalProperties": false
+            }),
+        }
+    }
+
+    /// Run a subagent to completion and return its final answer
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The depth limit is reached
+    /// - The arguments are invalid or name a tool the subagent can't get
+    /// - The subagent fails
+    pub async fn call(&self, args: String) -> Result<String, SpawnSubagentError> {
+        let args: SpawnSubagentParams = serde_json::from_str(&args)
+            .map_err(|e| SpawnSubagentError::InvalidArgs(e.to_string()))?;
+        if !self.can_spawn() {
+            return Err(SpawnSubagentError::DepthLimitReached {
+                depth: self.depth,
+                max: self.max_depth,
+            });
+        }
+        if args.prompt.trim().is_empty() {
+            return Err(SpawnSubagentError::InvalidArgs(
+                "prompt cannot be empty".to_string(),
+            ));
+        }
+        let available = self.available_tools();
+        if let Some(unknown) = args.tools.iter().find(|name| !available.contains(name)) {
+            return Err(SpawnSubagentError::UnknownTool {
+                name: unknown.clone(),
+                available: available.join(", "),
+            });
+        }
+
+        let n = self.spawned.fetch_add(1, Ordering::Relaxed) + 1;
+        let child_id = format!("{}.sub{}", self.parent_task_id, n);
+        let child_params = AgentParams {
+            prompt: args.prompt,
+            provider: self.params.provider.clone(),
+            model: self.params.model.clone(),
+            mcp: self.params.mcp.clone(),
+            max_turns: Some(args.max_turns.clamp(1, self.params.effective_max_turns())),
+            // No spawn_agent: subagents only get their allowlist
+            depth_limit: Some(1),
+            tool_concurrency: self.params.tool_concurrency,
+            ..Default::default()
+        };
+
+        self.event_log.emit(EventKind::AgentSpawned {
+            parent_task_id: Arc::clone(&self.parent_task_id),
+            child_task_id: Arc::from(child_id.as_str()),
+            depth: self.depth + 1,
+        });
+
+        let mut child_loop = super::RigAgentLoop::new(
+            child_id.clone(),
+            child_params,
+            self.event_log.clone(),
+            self.mcp_clients.clone(),
+        )
+        .map_err(|e| SpawnSubagentError::ExecutionFailed(e.to_string()))?
+        .retain_tools(&args.tools);
+        if args.tools.iter().any(|name| name == "spawn_subagent") {
+            child_loop = child_loop.with_subagents(self.nested(&child_id));
+        }
+        if let Some((policy, gate)) = &self.tool_policy {
+            child_loop = child_loop.with_tool_policy(Arc::clone(policy), Arc::clone(gate));
+        }
+
+        let result = if self.params.provider.as_deref() == Some("mock") {
+            child_loop.run_mock().await
+        } else {
+            child_loop.run_auto().await
+        }
+        .map_err(|e| SpawnSubagentError::ExecutionFailed(e.to_string()))?;
+
+        self.event_log.emit(EventKind::AgentComplete {
+            task_id: Arc::from(child_id.as_str()),
+            turns: result.turns as u32,
+            stop_reason: format!("{:?}", result.status).into(),
+        });
+
+        let answer = result.final_output["response"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| result.final_output.to_string());
+        if answer.trim().is_empty() {
+            return Ok(format!(
+                "Subagent {} stopped ({}) without an answer",
+                child_id,
+                result.status.as_canonical_str()
+            ));
+        }
+        Ok(answer)
+    }
+}
+
+/// Errors of a `spawn_subagent` call, returned to the model
+#[derive(Debug, thiserror::Error)]
+pub enum SpawnSubagentError {
+    #[error("spawn_subagent: depth limit reached (depth: {depth}, max: {max})")]
+    DepthLimitReached { depth: u32, max: u32 },
+
+    #[error("spawn_subagent: invalid arguments - {0}")]
+    InvalidArgs(String),
+
+    #[error("spawn_subagent: no tool '{name}' for subagents (available: {available})")]
+    UnknownTool { name: String, available: String },
+
+    #[error("spawn_subagent: subagent failed - {0}")]
+    ExecutionFailed(String),
+}
+
+impl std::fmt::Debug for SpawnSubagentTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpawnSubagentTool")
+            .field("depth", &self.depth)
+            .field("max_depth", &self.max_depth)
+            .field("parent_task_id", &self.parent_task_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Type alias for boxed future (required by ToolDyn)
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+impl ToolDyn for SpawnSubagentTool {
+    fn name(&self) -> String {
+        "spawn_subagent".to_string()
+    }
+
+    fn definition(&self, _prompt: String) -> BoxFuture<'_, ToolDefinition> {
+        let def = SpawnSubagentTool::definition(self);
+        Box::pin(async move { def })
+    }
+
+    fn call(&self, args: String) -> BoxFuture<'_, Result<String, ToolError>> {
+        Box::pin(async move {
+            SpawnSubagentTool::call(self, args).await.map_err(|e| {
+                ToolError::ToolCallError(Box::new(std::io::Error::other(e.to_string())))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subagent_tool(depth_limit: u32, event_log: &EventLog) -> SpawnSubagentTool {
+        let params = AgentParams {
+            prompt: "Audit the workspace".to_string(),
+            provider: Some("mock".to_string()),
+            mcp: vec!["novanet".to_string()],
+            depth_limit: Some(depth_limit),
+            subagents: Some(true),
+            ..Default::default()
+        };
+        let mut clients = FxHashMap::default();
+        clients.insert("novanet".to_string(), Arc::new(McpClient::mock("novanet")));
+        SpawnSubagentTool::new("audit", &params, event_log.clone(), clients)
+    }
+
+    fn args(tools: &[&str]) -> String {
+        json!({ "prompt": "Describe the schema", "tools": tools, "max_turns": 3 }).to_string()
+    }
+
+    #[test]
+    fn definition_lists_available_tools() {
+        let tool = subagent_tool(3, &EventLog::new());
+        let def = tool.definition();
+        assert_eq!(def.name, "spawn_subagent");
+        assert!(def.description.contains("novanet_describe"));
+        assert!(def.description.contains("spawn_subagent"));
+        assert_eq!(def.parameters["required"].as_array().unwrap().len(), 3);
+        assert_eq!(def.parameters["additionalProperties"], false);
+
+        // A subagent at depth 2 of 3 can't nest further
+        assert!(!subagent_tool(2, &EventLog::new())
+            .available_tools()
+            .contains(&"spawn_subagent".to_string()));
+    }
+
+    #[tokio::test]
+    async fn subagent_answer_is_the_tool_result() {
+        let event_log = EventLog::new();
+        let tool = subagent_tool(3, &event_log);
+
+        let answer = tool.call(args(&["novanet_describe"])).await.unwrap();
+        assert_eq!(answer, "Mock response from rig agent");
+        tool.call(args(&[])).await.unwrap();
+
+        let events = event_log.events();
+        let spawned: Vec<_> = events
+            .iter()
+            .filter_map(|e| match &e.kind {
+                EventKind::AgentSpawned {
+                    parent_task_id,
+                    child_task_id,
+                    depth,
+                } => Some((
+                    parent_task_id.to_string(),
+                    child_task_id.to_string(),
+                    *depth,
+                )),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            spawned,
+            vec![
+                ("audit".to_string(), "audit.sub1".to_string(), 2),
+                ("audit".to_string(), "audit.sub2".to_string(), 2),
+            ]
+        );
+        // The subagent's turns and completion carry its own ID
+        assert!(events.iter().any(|e| matches!(
+            &e.kind,
+            EventKind::AgentTurn { task_id, .. } if &**task_id == "audit.sub1"
+        )));
+        assert!(events.iter().any(|e| matches!(
+            &e.kind,
+            EventKind::AgentComplete { task_id, .. } if &**task_id == "audit.sub2"
+        )));
+    }
+
+    #[tokio::test]
+    async fn unknown_tool_is_refused() {
+        let event_log = EventLog::new();
+        let err = subagent_tool(3, &event_log)
+            .call(args(&["fs_write"]))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, SpawnSubagentError::UnknownTool { ref name, .. } if name == "fs_write")
+        );
+        assert!(err.to_string().contains("novanet_describe"));
+        assert!(event_log.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn depth_limit_stops_nesting() {
+        let tool = subagent_tool(2, &EventLog::new());
+        let nested = tool.nested("audit.sub1");
+        assert!(tool.can_spawn());
+        assert!(!nested.can_spawn());
+
+        let err = ToolDyn::call(&nested, args(&[])).await.unwrap_err();
+        assert!(err.to_string().contains("depth limit"));
+    }
+}
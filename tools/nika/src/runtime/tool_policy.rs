@@ -0,0 +1,290 @@
+//! Tool policy - approving `agent:` tool calls (v0.7)
+//!
+//! The project's `.nika/config.toml` decides whether workflow agents may
+//! call tools without asking:
+//!
+//! ```toml
+//! [tools]
+//! permission = "plan"               # deny, plan, accept-edits, yolo
+//! auto_approve = ["novanet_*"]      # tool name globs that never ask
+//! ```
+//!
+//! | Mode | Tool calls |
+//! |------|------------|
+//! | `deny` | Refused |
+//! | `plan` | Asked through the run's [`ApprovalGate`](super::ApprovalGate) |
+//! | `accept-edits` | File edits run; other calls are asked |
+//! | `yolo` | Run (default without a `permission` setting) |
+//!
+//! Tools matching an `auto_approve` glob (or `nika run --auto-approve`)
+//! run in every mode. A refused call reaches the model as a tool result,
+//! so the agent can carry on without it.
+//!
+//! Calls blocked by `.nika/policy.yaml` ([`PolicyFile`]) are refused before
+//! any of this, whatever the mode.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+use super::policy_file::PolicyFile;
+use crate::config::find_project_config;
+use crate::error::NikaError;
+use crate::tools::{PermissionMode, ToolOperation};
+
+/// What to do with a tool call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolVerdict {
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// Permission mode, auto-approved tools and policy file rules for workflow agents
+#[derive(Debug, Clone)]
+pub struct ToolPolicy {
+    mode: PermissionMode,
+    patterns: Vec<String>,
+    auto_approve: GlobSet,
+    rules: Arc<PolicyFile>,
+    /// Workflow `name:` and task id selecting the rules' scopes
+    workflow: Option<String>,
+    task: Option<String>,
+}
+
+impl Default for ToolPolicy {
+    /// Without a project config tools run as before: no questions
+    fn default() -> Self {
+        Self::new(PermissionMode::YoloMode)
+    }
+}
+
+#[derive(Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    tools: ToolsSection,
+}
+
+#[derive(Default, Deserialize)]
+struct ToolsSection {
+    permission: Option<String>,
+    #[serde(default)]
+    auto_approve: Vec<String>,
+}
+
+impl ToolPolicy {
+    pub fn new(mode: PermissionMode) -> Self {
+        Self {
+            mode,
+            patterns: Vec::new(),
+            auto_approve: GlobSet::empty(),
+            rules: Arc::new(PolicyFile::default()),
+            workflow: None,
+            task: None,
+        }
+    }
+
+    /// Parse the `[tools]` table of a project config
+    pub fn from_toml(content: &str) -> Result<Self, NikaError> {
+        let config: ProjectConfig =
+            toml::from_str(content).map_err(|e| NikaError::ConfigError {
+                reason: format!("Invalid [tools] config: {}", e),
+            })?;
+        let mode = match config.tools.permission {
+            Some(value) => PermissionMode::parse(&value).ok_or_else(|| NikaError::ConfigError {
+                reason: format!(
+                    "Invalid [tools] permission '{}': use deny, plan, accept-edits or yolo",
+                    value
+                ),
+            })?,
+            // Configs for hosts or lint rules alone keep tools unrestricted
+            None => PermissionMode::YoloMode,
+        };
+        Self::new(mode).with_auto_approve(&config.tools.auto_approve)
+    }
+
+    /// Find `.nika/config.toml` and `.nika/policy.yaml` in `start` or its ancestors
+    ///
+    /// Without them, tools run without asking.
+    pub fn discover(start: &Path) -> Result<Self, NikaError> {
+        let policy = match find_project_config(start) {
+            Some(path) => Self::from_toml(&fs::read_to_string(path)?)?,
+            None => Self::default(),
+        };
+        Ok(policy.with_rules(PolicyFile::discover(start)?))
+    }
+
+    /// Block calls matching these policy file rules
+    pub fn with_rules(mut self, rules: PolicyFile) -> Self {
+        self.rules = Arc::new(rules);
+        self
+    }
+
+    /// Apply the rules for the workflow with this `name:`
+    pub fn for_workflow(mut self, name: Option<&str>) -> Self {
+        self.workflow = name.map(str::to_string);
+        self
+    }
+
+    /// Apply the rules for `task` (its sub-agents included)
+    pub fn for_task(&self, task: &str) -> Self {
+        Self {
+            task: Some(task.to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// Never ask for tools matching these globs (`novanet_*`, `read`)
+    pub fn with_auto_approve(mut self, patterns: &[String]) -> Result<Self, NikaError> {
+        if patterns.is_empty() {
+            return Ok(self);
+        }
+        self.patterns.extend(patterns.iter().cloned());
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &self.patterns {
+            let glob = Glob::new(pattern).map_err(|e| NikaError::ConfigError {
+                reason: format!("Invalid auto-approve pattern '{}': {}", pattern, e),
+            })?;
+            builder.add(glob);
+        }
+        self.auto_approve = builder.build().map_err(|e| NikaError::ConfigError {
+            reason: format!("Invalid auto-approve patterns: {}", e),
+        })?;
+        Ok(self)
+    }
+
+    pub fn mode(&self) -> PermissionMode {
+        self.mode
+    }
+
+    /// Whether any tool call can be asked or refused
+    pub fn restricts(&self) -> bool {
+        self.mode != PermissionMode::YoloMode || !self.rules.is_empty()
+    }
+
+    /// The policy file rule blocking a call to `tool` with JSON `args`, if any
+    pub fn blocked(&self, tool: &str, args: &str) -> Option<String> {
+        self.rules
+            .check_tool(self.workflow.as_deref(), self.task(), tool, args)
+    }
+
+    /// The policy file rule blocking an `exec:` command, if any
+    pub fn blocked_command(&self, command: &str) -> Option<String> {
+        self.rules
+            .check_command(self.workflow.as_deref(), self.task(), command)
+    }
+
+    /// The policy file rule blocking a request to `url`, if any (v0.7)
+    pub fn blocked_url(&self, url: &str) -> Option<String> {
+        self.rules
+            .check_url(self.workflow.as_deref(), self.task(), url)
+    }
+
+    fn task(&self) -> &str {
+        self.task.as_deref().unwrap_or_default()
+    }
+
+    /// Verdict for a call to `tool`
+    pub fn check(&self, tool: &str) -> ToolVerdict {
+        if self.auto_approve.is_match(tool) {
+            return ToolVerdict::Allow;
+        }
+        match self.mode {
+            PermissionMode::YoloMode => ToolVerdict::Allow,
+            PermissionMode::Deny => ToolVerdict::Deny,
+            mode => match file_operation(tool) {
+                Some(operation) if mode.allows(operation) => ToolVerdict::Allow,
+                _ => ToolVerdict::Ask,
+            },
+        }
+    }
+}
+
+/// Operation of a built-in file tool (MCP tools have none)
+fn file_operation(tool: &str) -> Option<ToolOperation> {
+    match tool {
+        "read" => Some(ToolOperation::Read),
+        "write" => Some(ToolOperation::Write),
+        "edit" | "multi_edit" | "apply_patch" => Some(ToolOperation::Edit),
+        "glob" | "grep" => Some(ToolOperation::Search),
+        _ => None,
+    }
+}
+
+/// Question shown for a tool call: the tool and its arguments
+#[cfg_attr(not(feature = "providers"), allow(dead_code))]
+pub(crate) fn tool_call_prompt(tool: &str, args: &str) -> String {
+    const MAX_ARGS: usize = 2000;
+    let args = serde_json::from_str::<serde_json::Value>(args)
+        .ok()
+        .and_then(|value| serde_json::to_string_pretty(&value).ok())
+        .unwrap_or_else(|| args.to_string());
+    let args = match args.char_indices().nth(MAX_ARGS) {
+        Some((end, _)) => format!("{}…", &args[..end]),
+        None => args,
+    };
+    format!("Allow tool call `{}`?\n{}", tool, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_tools_section() {
+        let policy = ToolPolicy::from_toml(
+            r#"
+[tools]
+permission = "accept-edits"
+auto_approve = ["novanet_*"]
+working_dir = "."
+
+[provider]
+default = "claude"
+"#,
+        )
+        .unwrap();
+        assert_eq!(policy.mode(), PermissionMode::AcceptEdits);
+        assert_eq!(policy.check("novanet_describe"), ToolVerdict::Allow);
+        assert_eq!(policy.check("edit"), ToolVerdict::Allow);
+        assert_eq!(policy.check("apply_patch"), ToolVerdict::Allow);
+        assert_eq!(policy.check("write"), ToolVerdict::Ask);
+        assert_eq!(policy.check("github_create_issue"), ToolVerdict::Ask);
+
+        // `nika init` writes the mode's display name
+        let yolo = ToolPolicy::from_toml("[tools]\npermission = \"yolomode\"\n").unwrap();
+        assert!(!yolo.restricts());
+        assert!(ToolPolicy::from_toml("[tools]\npermission = \"maybe\"\n").is_err());
+        assert!(!ToolPolicy::from_toml("[lint]\n").unwrap().restricts());
+    }
+
+    #[test]
+    fn test_auto_approve_wins_over_mode() {
+        let policy = ToolPolicy::new(PermissionMode::Deny)
+            .with_auto_approve(&["read".to_string()])
+            .unwrap();
+        assert_eq!(policy.check("read"), ToolVerdict::Allow);
+        assert_eq!(policy.check("novanet_search"), ToolVerdict::Deny);
+        assert_eq!(ToolPolicy::default().check("write"), ToolVerdict::Allow);
+        assert!(ToolPolicy::default()
+            .with_auto_approve(&["[".to_string()])
+            .is_err());
+
+        // Policy file rules refuse calls even in yolo mode
+        let rules =
+            PolicyFile::from_yaml("tasks:\n  t1:\n    tools:\n      deny: [write]\n").unwrap();
+        let policy = ToolPolicy::default().with_rules(rules);
+        assert!(policy.restricts());
+        assert!(policy.blocked("write", "{}").is_none());
+        assert!(policy.for_task("t1").blocked("write", "{}").is_some());
+
+        let prompt = tool_call_prompt("novanet_search", r#"{"query":"rust"}"#);
+        assert_eq!(
+            prompt,
+            "Allow tool call `novanet_search`?\n{\n  \"query\": \"rust\"\n}"
+        );
+    }
+}
@@ -0,0 +1,375 @@
+//! Watch triggers (v0.7, `nika daemon start --watch`)
+//!
+//! Workflows with `triggers: { watch: "./inbox/*.md" }` run whenever a
+//! matching file is created or modified. Changes are debounced per trigger
+//! and per file: a file fires once it has been quiet for the trigger's
+//! `debounce_ms`, so a file written in several chunks (or saved through a
+//! temporary file and a rename) runs the workflow once.
+//!
+//! The file is bound as the `trigger` pseudo-task, so tasks read it with
+//! `use: { file: trigger.path }`; `trigger.event` is `created` or
+//! `modified`. Removed files never fire. Triggers are discovered when the
+//! watcher starts, and missing watch roots are created.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+
+use crate::ast::Workflow;
+use crate::error::{NikaError, Result};
+use crate::util::WatchPattern;
+
+use super::scheduler::{workflow_name, Discovery};
+
+/// What happened to a watched file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    Created,
+    Modified,
+}
+
+/// A debounced change that fires a trigger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub path: PathBuf,
+    pub change: FileChange,
+}
+
+impl TriggerEvent {
+    /// Value bound as `trigger` (see [`Runner::with_trigger`](super::Runner::with_trigger))
+    pub fn binding(&self) -> Value {
+        serde_json::json!({
+            "path": self.path.display().to_string(),
+            "event": self.change,
+        })
+    }
+}
+
+/// A workflow with a watch trigger
+#[derive(Debug, Clone)]
+pub struct WatchedWorkflow {
+    pub path: PathBuf,
+    /// `workflow:` name, or the file name
+    pub name: String,
+    /// Anchored at the workflow file's directory
+    pub pattern: WatchPattern,
+    pub debounce: Duration,
+}
+
+/// Find workflows with a watch trigger in `paths` (files, or directories
+/// searched recursively, respecting .gitignore)
+pub fn discover(paths: &[PathBuf]) -> Discovery<WatchedWorkflow> {
+    Discovery::scan(paths, load)
+}
+
+/// Read one workflow file (`None` if it has no watch trigger)
+fn load(path: &Path) -> Result<Option<WatchedWorkflow>> {
+    let workflow: Workflow = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+    let Some(triggers) = workflow.triggers.as_ref() else {
+        return Ok(None);
+    };
+    let path = std::fs::canonicalize(path)?;
+    let base = path.parent().unwrap_or(Path::new("/"));
+    let Some(pattern) = triggers.watch_pattern(base) else {
+        return Ok(None);
+    };
+    Ok(Some(WatchedWorkflow {
+        name: workflow_name(&workflow, &path),
+        pattern: pattern?,
+        debounce: triggers.debounce(),
+        path,
+    }))
+}
+
+/// Per-file quiet period for one trigger
+#[derive(Debug)]
+struct Debouncer {
+    window: Duration,
+    /// Pending change and time of the last raw event, per file
+    pending: HashMap<PathBuf, (FileChange, Instant)>,
+}
+
+impl Debouncer {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Record a raw event; a file created then written stays `Created`
+    fn push(&mut self, path: PathBuf, change: FileChange, now: Instant) {
+        let entry = self.pending.entry(path).or_insert((change, now));
+        entry.1 = now;
+    }
+
+    /// When the earliest pending file becomes due
+    fn next_due(&self) -> Option<Instant> {
+        self.pending.values().map(|(_, at)| *at + self.window).min()
+    }
+
+    /// Files quiet for the whole window, in path order
+    fn due(&mut self, now: Instant) -> Vec<TriggerEvent> {
+        let window = self.window;
+        let mut due: Vec<TriggerEvent> = Vec::new();
+        self.pending.retain(|path, (change, at)| {
+            if now.duration_since(*at) < window {
+                return true;
+            }
+            due.push(TriggerEvent {
+                path: path.clone(),
+                change: *change,
+            });
+            false
+        });
+        due.sort_by(|a, b| a.path.cmp(&b.path));
+        due
+    }
+}
+
+/// Classify a raw notify event (`None` for removals and metadata)
+fn file_change(kind: &EventKind) -> Option<FileChange> {
+    match kind {
+        EventKind::Create(_) => Some(FileChange::Created),
+        // Atomic saves rename a temporary file into place
+        EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Both)) => {
+            Some(FileChange::Created)
+        }
+        EventKind::Modify(ModifyKind::Name(_) | ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) => Some(FileChange::Modified),
+        _ => None,
+    }
+}
+
+/// Watches the roots of a set of triggers and fires debounced changes
+pub struct TriggerWatcher {
+    triggers: Vec<(WatchedWorkflow, Debouncer)>,
+}
+
+impl TriggerWatcher {
+    pub fn new(workflows: Vec<WatchedWorkflow>) -> Self {
+        Self {
+            triggers: workflows
+                .into_iter()
+                .map(|workflow| {
+                    let debouncer = Debouncer::new(workflow.debounce);
+                    (workflow, debouncer)
+                })
+                .collect(),
+        }
+    }
+
+    /// Route one raw event to the debouncers of matching triggers
+    fn record(&mut self, event: &notify::Event, now: Instant) {
+        let Some(change) = file_change(&event.kind) else {
+            return;
+        };
+        // For a rename pair, only the destination matters
+        let paths = match event.kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => &event.paths[1..],
+            _ => &event.paths[..],
+        };
+        for path in paths {
+            for (workflow, debouncer) in &mut self.triggers {
+                if workflow.pattern.matches(path) {
+                    debouncer.push(path.clone(), change, now);
+                }
+            }
+        }
+    }
+
+    /// Watch until `shutdown` is cancelled, calling `fire` for each change
+    pub async fn run(
+        mut self,
+        shutdown: CancellationToken,
+        mut fire: impl FnMut(&WatchedWorkflow, TriggerEvent),
+    ) -> Result<()> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .map_err(|e| NikaError::ConfigError {
+            reason: format!("Failed to start file watcher: {}", e),
+        })?;
+
+        // One watch per root, recursive if any trigger on it needs it
+        let mut roots: BTreeMap<&Path, bool> = BTreeMap::new();
+        for (workflow, _) in &self.triggers {
+            let recursive = roots.entry(workflow.pattern.root()).or_default();
+            *recursive |= workflow.pattern.is_recursive();
+        }
+        for (root, recursive) in roots {
+            std::fs::create_dir_all(root)?;
+            let mode = if recursive {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            watcher
+                .watch(root, mode)
+                .map_err(|e| NikaError::ConfigError {
+                    reason: format!("Failed to watch {}: {}", root.display(), e),
+                })?;
+        }
+
+        loop {
+            let next_due = self
+                .triggers
+                .iter()
+                .filter_map(|(_, debouncer)| debouncer.next_due())
+                .min();
+            let sleep = async {
+                match next_due {
+                    Some(at) => tokio::time::sleep_until(at.into()).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = shutdown.cancelled() => return Ok(()),
+                event = rx.recv() => match event {
+                    Some(event) => self.record(&event, Instant::now()),
+                    None => return Ok(()),
+                },
+                _ = sleep => {}
+            }
+
+            let now = Instant::now();
+            for (workflow, debouncer) in &mut self.triggers {
+                for event in debouncer.due(now) {
+                    // Gone (or a directory) by the end of the quiet period
+                    if event.path.is_file() {
+                        fire(workflow, event);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    const WATCHED: &str = r#"
+schema: "nika/workflow@0.5"
+workflow: inbox-summary
+provider: mock
+triggers:
+  watch: "./inbox/*.md"
+  debounce_ms: 50
+tasks:
+  - id: summarize
+    use:
+      file: trigger.path
+    exec: "cat {{use.file}}"
+"#;
+
+    #[test]
+    fn test_discover_finds_watch_workflows() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("inbox.nika.yaml"), WATCHED).unwrap();
+        std::fs::write(
+            dir.path().join("plain.nika.yaml"),
+            "schema: \"nika/workflow@0.5\"\ntasks:\n  - id: a\n    exec: echo a\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("broken.nika.yaml"),
+            WATCHED.replace("./inbox/*.md", "./inbox/[*.md"),
+        )
+        .unwrap();
+
+        let discovery = discover(&[dir.path().to_path_buf()]);
+        assert_eq!(discovery.workflows.len(), 1);
+        let workflow = &discovery.workflows[0];
+        assert_eq!(workflow.name, "inbox-summary");
+        assert_eq!(workflow.debounce, Duration::from_millis(50));
+        let root = std::fs::canonicalize(dir.path()).unwrap().join("inbox");
+        assert_eq!(workflow.pattern.root(), root);
+        assert_eq!(discovery.errors.len(), 1);
+        assert_eq!(discovery.errors[0].1.code(), "NIKA-008");
+    }
+
+    #[test]
+    fn test_debouncer_fires_once_after_quiet_period() {
+        let window = Duration::from_millis(100);
+        let mut debouncer = Debouncer::new(window);
+        let start = Instant::now();
+        let note = PathBuf::from("/inbox/note.md");
+        debouncer.push(note.clone(), FileChange::Created, start);
+        debouncer.push(note.clone(), FileChange::Modified, start + window / 2);
+        debouncer.push(PathBuf::from("/inbox/a.md"), FileChange::Modified, start);
+
+        // Each write restarts the file's quiet period
+        assert_eq!(debouncer.next_due(), Some(start + window));
+        let due = debouncer.due(start + window);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].path, PathBuf::from("/inbox/a.md"));
+
+        let due = debouncer.due(start + window * 2);
+        assert_eq!(
+            due,
+            vec![TriggerEvent {
+                path: note,
+                change: FileChange::Created,
+            }]
+        );
+        assert!(debouncer.due(start + window * 3).is_empty());
+        assert_eq!(debouncer.next_due(), None);
+    }
+
+    #[test]
+    fn test_events_route_to_matching_triggers() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("inbox.nika.yaml"), WATCHED).unwrap();
+        let workflows = discover(&[dir.path().to_path_buf()]).workflows;
+        let root = workflows[0].pattern.root().to_path_buf();
+        let mut watcher = TriggerWatcher::new(workflows);
+        let now = Instant::now();
+
+        let event = |kind, name: &str| notify::Event::new(kind).add_path(root.join(name));
+        watcher.record(&event(EventKind::Create(CreateKind::File), "a.md"), now);
+        watcher.record(
+            &event(EventKind::Modify(ModifyKind::Data(DataChange::Any)), "b.md"),
+            now,
+        );
+        watcher.record(&event(EventKind::Create(CreateKind::File), "c.txt"), now);
+        watcher.record(&event(EventKind::Remove(RemoveKind::File), "d.md"), now);
+
+        let debouncer = &mut watcher.triggers[0].1;
+        let fired: Vec<_> = debouncer
+            .due(now + Duration::from_secs(1))
+            .into_iter()
+            .map(|e| (e.path.file_name().unwrap().to_owned(), e.change))
+            .collect();
+        assert_eq!(
+            fired,
+            vec![
+                ("a.md".into(), FileChange::Created),
+                ("b.md".into(), FileChange::Modified),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_binding_shape() {
+        let event = TriggerEvent {
+            path: PathBuf::from("/inbox/note.md"),
+            change: FileChange::Modified,
+        };
+        assert_eq!(
+            event.binding(),
+            serde_json::json!({"path": "/inbox/note.md", "event": "modified"})
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Checks behind `validate:` tasks (v0.7)
+//!
+//! Both JSON Schema errors and rule failures become `Violation`s with a dot
+//! path (`contact.email`, `items.2.id`, `.` for the value itself), so the
+//! report reads the same whichever check found the problem.
+
+use std::fmt;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::ast::ValidateRule;
+use crate::error::NikaError;
+
+/// One failed check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    /// Dot path of the offending value
+    pub path: String,
+    /// Check that failed: `schema`, `required`, `type`, `pattern`, `min`,
+    /// `max`, `min_length`, `max_length` or `one_of`
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Violations of a JSON Schema
+pub fn check_schema(value: &Value, schema: &Value) -> Result<Vec<Violation>, NikaError> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| NikaError::SchemaFailed {
+        details: format!("Invalid schema in validate: task: {}", e),
+    })?;
+    Ok(validator
+        .iter_errors(value)
+        .map(|error| {
+            let pointer = error.instance_path.to_string();
+            let path = pointer.trim_start_matches('/').replace('/', ".");
+            Violation {
+                path: if path.is_empty() {
+                    ".".to_string()
+                } else {
+                    path
+                },
+                rule: "schema",
+                message: error.to_string(),
+            }
+        })
+        .collect())
+}
+
+/// Violations of field rules, in rule order
+pub fn check_rules(value: &Value, rules: &[ValidateRule]) -> Result<Vec<Violation>, NikaError> {
+    let mut violations = Vec::new();
+    for rule in rules {
+        let pattern = rule
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| NikaError::ValidationError {
+                reason: format!(
+                    "validate: rule for '{}' has an invalid pattern: {}",
+                    rule.field, e
+                ),
+            })?;
+        for (path, found) in select(value, &rule.field) {
+            let mut fail = |rule: &'static str, message: String| {
+                violations.push(Violation {
+                    path: path.clone(),
+                    rule,
+                    message,
+                })
+            };
+            let Some(found) = found else {
+                if rule.required {
+                    fail("required", "is missing".to_string());
+                }
+                continue;
+            };
+
+            if let Some(kind) = rule.kind {
+                if !kind.matches(found) {
+                    fail(
+                        "type",
+                        format!("expected {}, got {}", kind.as_str(), type_name(found)),
+                    );
+                    continue;
+                }
+            }
+            if let Some(pattern) = &pattern {
+                match found.as_str() {
+                    Some(s) if pattern.is_match(s) => {}
+                    Some(s) => fail("pattern", format!("'{}' does not match /{}/", s, pattern)),
+                    None => fail(
+                        "pattern",
+                        format!("expected a string, got {}", type_name(found)),
+                    ),
+                }
+            }
+            if rule.min.is_some() || rule.max.is_some() {
+                match found.as_f64() {
+                    Some(n) => {
+                        if let Some(min) = rule.min.filter(|min| n < *min) {
+                            fail("min", format!("{} is below the minimum {}", found, min));
+                        }
+                        if let Some(max) = rule.max.filter(|max| n > *max) {
+                            fail("max", format!("{} is above the maximum {}", found, max));
+                        }
+                    }
+                    None => fail(
+                        if rule.min.is_some() { "min" } else { "max" },
+                        format!("expected a number, got {}", type_name(found)),
+                    ),
+                }
+            }
+            if rule.min_length.is_some() || rule.max_length.is_some() {
+                let len = match found {
+                    Value::String(s) => Some(s.chars().count()),
+                    Value::Array(items) => Some(items.len()),
+                    Value::Object(map) => Some(map.len()),
+                    _ => None,
+                };
+                match len {
+                    Some(len) => {
+                        if let Some(min) = rule.min_length.filter(|min| len < *min) {
+                            fail(
+                                "min_length",
+                                format!("length {} is below the minimum {}", len, min),
+                            );
+                        }
+                        if let Some(max) = rule.max_length.filter(|max| len > *max) {
+                            fail(
+                                "max_length",
+                                format!("length {} is above the maximum {}", len, max),
+                            );
+                        }
+                    }
+                    None => fail(
+                        if rule.min_length.is_some() {
+                            "min_length"
+                        } else {
+                            "max_length"
+                        },
+                        format!("{} has no length", type_name(found)),
+                    ),
+                }
+            }
+            if let Some(allowed) = &rule.one_of {
+                if !allowed.contains(found) {
+                    let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                    fail(
+                        "one_of",
+                        format!("{} is not one of {}", found, allowed.join(", ")),
+                    );
+                }
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Values at a rule's field path, with their concrete paths (None = missing)
+fn select<'a>(value: &'a Value, field: &str) -> Vec<(String, Option<&'a Value>)> {
+    let mut selected = vec![(String::new(), Some(value))];
+    for segment in field.split('.').filter(|s| !s.is_empty()) {
+        selected = selected
+            .into_iter()
+            .flat_map(|(path, value)| {
+                let join = |key: &str| match path.as_str() {
+                    "" => key.to_string(),
+                    parent => format!("{}.{}", parent, key),
+                };
+                match (segment, value) {
+                    ("*", Some(Value::Array(items))) => items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| (join(&i.to_string()), Some(item)))
+                        .collect(),
+                    ("*", Some(Value::Object(map))) => map
+                        .iter()
+                        .map(|(key, item)| (join(key), Some(item)))
+                        .collect(),
+                    (key, Some(Value::Object(map))) => vec![(join(key), map.get(key))],
+                    (key, Some(Value::Array(items))) => {
+                        let item = key.parse::<usize>().ok().and_then(|i| items.get(i));
+                        vec![(join(key), item)]
+                    }
+                    (key, _) => vec![(join(key), None)],
+                }
+            })
+            .collect();
+    }
+    selected
+        .into_iter()
+        .map(|(path, value)| {
+            (
+                if path.is_empty() {
+                    ".".to_string()
+                } else {
+                    path
+                },
+                value,
+            )
+        })
+        .collect()
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(yaml: &str) -> Vec<ValidateRule> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_check_rules_reports_each_violation() {
+        let lead = json!({
+            "email": "not-an-email",
+            "score": 120,
+            "tags": ["hot", "lukewarm"],
+        });
+        let violations = check_rules(
+            &lead,
+            &rules(
+                r#"
+- field: email
+  pattern: "^[^@]+@[^@]+$"
+- field: score
+  type: integer
+  max: 100
+- field: tags.*
+  one_of: [hot, warm, cold]
+- field: name
+  required: true
+- field: phone
+  min_length: 7
+"#,
+            ),
+        )
+        .unwrap();
+        let found: Vec<(&str, &str)> = violations
+            .iter()
+            .map(|v| (v.path.as_str(), v.rule))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("email", "pattern"),
+                ("score", "max"),
+                ("tags.1", "one_of"),
+                ("name", "required"),
+            ]
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "score: 120 is above the maximum 100"
+        );
+    }
+
+    #[test]
+    fn test_check_rules_on_whole_value_and_bad_pattern() {
+        let violations =
+            check_rules(&json!([1, 2]), &rules("- field: .\n  max_length: 1")).unwrap();
+        assert_eq!(violations[0].path, ".");
+        assert!(check_rules(&json!({}), &rules("- field: a\n  pattern: \"(\"")).is_err());
+    }
+
+    #[test]
+    fn test_check_schema_uses_dot_paths() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"contact": {"type": "object", "properties": {"email": {"type": "string"}}}},
+            "required": ["contact"],
+        });
+        let violations = check_schema(&json!({"contact": {"email": 42}}), &schema).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "contact.email");
+        assert_eq!(violations[0].rule, "schema");
+        assert_eq!(check_schema(&json!({}), &schema).unwrap()[0].path, ".");
+    }
+}
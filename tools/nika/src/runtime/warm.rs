@@ -0,0 +1,121 @@
+//! Warm resources shared across runs (v0.7, `nika daemon`)
+//!
+//! A single `nika run` connects its MCP servers and builds its provider
+//! sessions from scratch. [`WarmResources`] keeps both alive between runs:
+//! executors attached to it share one [`SessionPool`] and reuse MCP clients
+//! already connected by an earlier run.
+//!
+//! MCP clients are keyed by server name *and* configuration, so two
+//! workflows declaring a `novanet` server with different commands or env
+//! never share a process.
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use rustc_hash::FxHasher;
+use tokio::sync::OnceCell;
+
+use crate::ast::McpConfigInline;
+use crate::mcp::McpClient;
+use crate::provider::{PoolStats, SessionPool};
+
+/// Shared slot for one MCP client (same cell type as the executor cache)
+pub(crate) type McpClientCell = Arc<OnceCell<Arc<McpClient>>>;
+
+/// Provider sessions and MCP clients that outlive a single run
+pub struct WarmResources {
+    session_pool: Arc<SessionPool>,
+    /// Keyed by [`mcp_fingerprint`]
+    mcp_clients: DashMap<u64, McpClientCell>,
+}
+
+impl WarmResources {
+    /// Keep up to `session_pool_size` warm provider sessions
+    pub fn new(session_pool_size: usize) -> Self {
+        Self {
+            session_pool: Arc::new(SessionPool::new(session_pool_size)),
+            mcp_clients: DashMap::new(),
+        }
+    }
+
+    pub(crate) fn session_pool(&self) -> Arc<SessionPool> {
+        Arc::clone(&self.session_pool)
+    }
+
+    /// Client slot for this server configuration (created empty on first use)
+    pub(crate) fn mcp_client(&self, name: &str, config: &McpConfigInline) -> McpClientCell {
+        self.mcp_clients
+            .entry(mcp_fingerprint(name, config))
+            .or_default()
+            .clone()
+    }
+
+    /// Warm-hit counters of the shared session pool
+    pub fn session_stats(&self) -> PoolStats {
+        self.session_pool.stats()
+    }
+
+    /// Number of warm provider sessions
+    pub fn warm_sessions(&self) -> usize {
+        self.session_pool.len()
+    }
+
+    /// Names of connected MCP servers, sorted
+    pub fn connected_mcp_servers(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .mcp_clients
+            .iter()
+            .filter_map(|entry| entry.value().get().map(|client| client.name().to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+/// Identity of an MCP server: name plus everything used to spawn it
+fn mcp_fingerprint(name: &str, config: &McpConfigInline) -> u64 {
+    let mut hasher = FxHasher::default();
+    name.hash(&mut hasher);
+    config.command.hash(&mut hasher);
+    config.args.hash(&mut hasher);
+    config.cwd.hash(&mut hasher);
+    // FxHashMap iteration order is not stable
+    let mut env: Vec<_> = config.env.iter().collect();
+    env.sort();
+    env.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: &str, env: &[(&str, &str)]) -> McpConfigInline {
+        McpConfigInline {
+            command: command.to_string(),
+            args: vec!["--stdio".to_string()],
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_mcp_slots_are_keyed_by_config() {
+        let warm = WarmResources::new(2);
+        let a = warm.mcp_client("novanet", &config("novanet-mcp", &[("A", "1"), ("B", "2")]));
+        let same = warm.mcp_client("novanet", &config("novanet-mcp", &[("B", "2"), ("A", "1")]));
+        let other = warm.mcp_client("novanet", &config("other-mcp", &[]));
+
+        assert!(Arc::ptr_eq(&a, &same));
+        assert!(!Arc::ptr_eq(&a, &other));
+        assert!(warm.connected_mcp_servers().is_empty());
+
+        a.set(Arc::new(McpClient::mock("novanet"))).unwrap();
+        assert_eq!(warm.connected_mcp_servers(), vec!["novanet".to_string()]);
+    }
+}
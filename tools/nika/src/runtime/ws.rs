@@ -0,0 +1,143 @@
+//! WebSocket client for `ws:` tasks (v0.7)
+//!
+//! One connection per task: handshake with the task's headers, send the
+//! `send:` messages in order, then read until `until` matches,
+//! `max_messages` is reached, the deadline passes or the server closes.
+//! Pings are answered by the client library; only text and binary frames
+//! count as messages. Each message is emitted as a `WsMessage` event as it
+//! arrives.
+
+use std::sync::Arc;
+
+#[cfg(feature = "http")]
+use base64::Engine;
+#[cfg(feature = "http")]
+use futures::{SinkExt, StreamExt};
+#[cfg(feature = "http")]
+use serde_json::json;
+use serde_json::Value;
+use tokio::time::Instant;
+#[cfg(feature = "http")]
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+#[cfg(feature = "http")]
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue};
+#[cfg(feature = "http")]
+use tokio_tungstenite::tungstenite::Message;
+
+use super::rows::Filter;
+#[cfg(feature = "http")]
+use crate::event::EventKind;
+use crate::event::EventLog;
+
+/// A resolved `ws:` task
+#[cfg_attr(not(feature = "http"), allow(dead_code))]
+pub struct WsRequest<'a> {
+    pub url: &'a str,
+    pub headers: &'a [(String, String)],
+    pub send: &'a [String],
+    pub until: Option<&'a Filter>,
+    pub max_messages: Option<usize>,
+    pub deadline: Instant,
+}
+
+/// Collect messages for `request`, emitting each one on `event_log`
+///
+/// Running out of time after connecting is not an error: the messages so
+/// far are the output. Failing to connect or a broken connection is.
+#[cfg(feature = "http")]
+pub async fn collect(
+    event_log: &EventLog,
+    task_id: &Arc<str>,
+    request: WsRequest<'_>,
+) -> Result<Vec<Value>, String> {
+    let mut handshake = request
+        .url
+        .into_client_request()
+        .map_err(|e| format!("invalid url: {}", e))?;
+    for (key, value) in request.headers {
+        let name = HeaderName::from_bytes(key.as_bytes())
+            .map_err(|e| format!("header '{}': {}", key, e))?;
+        let value = HeaderValue::from_str(value).map_err(|e| format!("header '{}': {}", key, e))?;
+        handshake.headers_mut().insert(name, value);
+    }
+    let (stream, _) = tokio::time::timeout_at(
+        request.deadline,
+        tokio_tungstenite::connect_async(handshake),
+    )
+    .await
+    .map_err(|_| "timed out connecting".to_string())?
+    .map_err(|e| e.to_string())?;
+    let (mut write, mut read) = stream.split();
+
+    for message in request.send {
+        write
+            .send(Message::text(message.as_str()))
+            .await
+            .map_err(|e| format!("send failed: {}", e))?;
+    }
+
+    let mut messages = Vec::new();
+    while request.max_messages.is_none_or(|max| messages.len() < max) {
+        let frame = match tokio::time::timeout_at(request.deadline, read.next()).await {
+            Err(_) | Ok(None) => break,
+            Ok(Some(frame)) => frame.map_err(|e| e.to_string())?,
+        };
+        let text = match frame {
+            Message::Text(text) => text.to_string(),
+            Message::Binary(bytes) => match String::from_utf8(bytes.to_vec()) {
+                Ok(text) => text,
+                Err(_) => base64::engine::general_purpose::STANDARD.encode(&bytes),
+            },
+            Message::Close(_) => break,
+            Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => continue,
+        };
+        event_log.emit(EventKind::WsMessage {
+            task_id: Arc::clone(task_id),
+            index: messages.len(),
+            message: text.clone(),
+        });
+        let value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        let done = request
+            .until
+            .is_some_and(|until| matches_until(until, &value));
+        messages.push(value);
+        if done {
+            break;
+        }
+    }
+    let _ = write.send(Message::Close(None)).await;
+    Ok(messages)
+}
+
+/// Built without the `http` feature: every connection fails
+#[cfg(not(feature = "http"))]
+pub async fn collect(
+    _event_log: &EventLog,
+    _task_id: &Arc<str>,
+    _request: WsRequest<'_>,
+) -> Result<Vec<Value>, String> {
+    Err("nika was built without the `http` feature".to_string())
+}
+
+/// `until` over a message; text messages are matched as `{ text }`
+#[cfg(feature = "http")]
+fn matches_until(until: &Filter, message: &Value) -> bool {
+    match message {
+        Value::String(text) => until.matches(&json!({ "text": text })),
+        other => until.matches(other),
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn until_matches_json_and_text() {
+        let until = Filter::parse("type == 'end' or text == 'DONE'").unwrap();
+        assert!(matches_until(&until, &json!({"type": "end"})));
+        assert!(!matches_until(&until, &json!({"type": "tick"})));
+        assert!(matches_until(&until, &json!("DONE")));
+        assert!(!matches_until(&until, &json!("tick")));
+    }
+}
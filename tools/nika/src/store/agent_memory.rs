@@ -0,0 +1,400 @@
+//! AgentMemory - named memories of `agent:` tasks, kept across runs (v0.7)
+//!
+//! Agents with `memory: true` write memories with the `remember` tool and
+//! read them back with `recall`, so a nightly triage workflow can build on
+//! what earlier runs learned. Each memory is a name and a text, scoped to
+//! the project's namespace (see `[memory]` in the global config):
+//!
+//! - `file` (default): `.nika/memory.json`, re-read on each access like
+//!   `.nika/state.json`
+//! - `sqlite` (`memory-sqlite` feature): `.nika/memory.db`, for large or
+//!   shared stores
+//!
+//! Nothing is opened until an agent first uses its memory.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{MemoryBackendKind, MemoryConfig};
+use crate::error::{NikaError, Result};
+
+/// Memories one namespace may hold
+pub const MAX_MEMORIES: usize = 1000;
+
+/// Longest memory text, in characters
+pub const MAX_MEMORY_CHARS: usize = 20_000;
+
+/// One memory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub content: String,
+    /// Task that last wrote it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Named memories of one project namespace
+#[derive(Debug)]
+pub struct AgentMemory {
+    namespace: String,
+    storage: Storage,
+}
+
+#[derive(Debug)]
+enum Storage {
+    /// Namespaces in a JSON file; the lock serializes file access
+    File { path: PathBuf, lock: Mutex<()> },
+    /// Opened on first use
+    #[cfg(feature = "memory-sqlite")]
+    Sqlite {
+        path: PathBuf,
+        conn: Mutex<Option<rusqlite::Connection>>,
+    },
+    /// Gone with the store (tests)
+    Memory(Mutex<BTreeMap<String, MemoryEntry>>),
+}
+
+/// Memories of every namespace in a file store
+type Namespaces = BTreeMap<String, BTreeMap<String, MemoryEntry>>;
+
+impl AgentMemory {
+    /// Store selected by `config`; fails only for a backend not built in
+    pub fn new(config: &MemoryConfig) -> Result<Self> {
+        let path = config.path();
+        let storage = match config.backend {
+            MemoryBackendKind::File => Storage::File {
+                path,
+                lock: Mutex::new(()),
+            },
+            #[cfg(feature = "memory-sqlite")]
+            MemoryBackendKind::Sqlite => Storage::Sqlite {
+                path,
+                conn: Mutex::new(None),
+            },
+            #[allow(unreachable_patterns)]
+            other => {
+                return Err(NikaError::MemoryStoreError {
+                    backend: other.as_str().to_string(),
+                    reason: "nika was built without the `memory-sqlite` feature".to_string(),
+                })
+            }
+        };
+        Ok(Self {
+            namespace: config.namespace(),
+            storage,
+        })
+    }
+
+    /// Memories that live as long as the store
+    pub fn in_memory(namespace: &str) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            storage: Storage::Memory(Mutex::default()),
+        }
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Store `content` under `name`, returning whether it replaced a memory
+    pub fn remember(&self, name: &str, content: &str, task: Option<&str>) -> Result<bool> {
+        let chars = content.chars().count();
+        if chars > MAX_MEMORY_CHARS {
+            return Err(self.error(format!(
+                "memory '{}' is {} chars, over {}; keep the gist",
+                name, chars, MAX_MEMORY_CHARS
+            )));
+        }
+        let now = Utc::now();
+        let write = |memories: &mut BTreeMap<String, MemoryEntry>| {
+            if !memories.contains_key(name) && memories.len() >= MAX_MEMORIES {
+                return Err(self.error(format!(
+                    "{} memories already; overwrite or forget one",
+                    MAX_MEMORIES
+                )));
+            }
+            let created_at = memories.get(name).map_or(now, |old| old.created_at);
+            let replaced = memories
+                .insert(
+                    name.to_string(),
+                    MemoryEntry {
+                        content: content.to_string(),
+                        task: task.map(str::to_string),
+                        created_at,
+                        updated_at: now,
+                    },
+                )
+                .is_some();
+            Ok(replaced)
+        };
+
+        match &self.storage {
+            Storage::File { path, lock } => {
+                let _guard = lock.lock();
+                let mut all = load(path)?;
+                let replaced = write(all.entry(self.namespace.clone()).or_default())?;
+                save(path, &all)?;
+                Ok(replaced)
+            }
+            #[cfg(feature = "memory-sqlite")]
+            Storage::Sqlite { .. } => self.with_sqlite(|conn| {
+                let count: usize = conn.query_row(
+                    "SELECT COUNT(*) FROM memories WHERE namespace = ?1",
+                    [&self.namespace],
+                    |row| row.get(0),
+                )?;
+                let created_at: Option<DateTime<Utc>> = conn
+                    .query_row(
+                        "SELECT created_at FROM memories WHERE namespace = ?1 AND name = ?2",
+                        [&self.namespace, name],
+                        |row| row.get(0),
+                    )
+                    .ok();
+                if created_at.is_none() && count >= MAX_MEMORIES {
+                    return Ok(Err(self.error(format!(
+                        "{} memories already; overwrite or forget one",
+                        MAX_MEMORIES
+                    ))));
+                }
+                conn.execute(
+                    "INSERT OR REPLACE INTO memories \
+                     (namespace, name, content, task, created_at, updated_at) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    rusqlite::params![
+                        self.namespace,
+                        name,
+                        content,
+                        task,
+                        created_at.unwrap_or(now),
+                        now
+                    ],
+                )?;
+                Ok(Ok(created_at.is_some()))
+            })?,
+            Storage::Memory(memories) => write(&mut memories.lock()),
+        }
+    }
+
+    /// Memory named `name`
+    pub fn recall(&self, name: &str) -> Result<Option<MemoryEntry>> {
+        Ok(self.list()?.remove(name))
+    }
+
+    /// Memories whose name or text contains `query` (any case), latest first
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<(String, MemoryEntry)>> {
+        let query = query.to_lowercase();
+        let mut found: Vec<(String, MemoryEntry)> = self
+            .list()?
+            .into_iter()
+            .filter(|(name, entry)| {
+                name.to_lowercase().contains(&query)
+                    || entry.content.to_lowercase().contains(&query)
+            })
+            .collect();
+        found.sort_by_key(|b| std::cmp::Reverse(b.1.updated_at));
+        found.truncate(limit);
+        Ok(found)
+    }
+
+    /// Remove `name`; false if there was no such memory
+    pub fn forget(&self, name: &str) -> Result<bool> {
+        match &self.storage {
+            Storage::File { path, lock } => {
+                let _guard = lock.lock();
+                let mut all = load(path)?;
+                let removed = all
+                    .get_mut(&self.namespace)
+                    .and_then(|memories| memories.remove(name))
+                    .is_some();
+                if removed {
+                    save(path, &all)?;
+                }
+                Ok(removed)
+            }
+            #[cfg(feature = "memory-sqlite")]
+            Storage::Sqlite { .. } => self.with_sqlite(|conn| {
+                Ok(conn.execute(
+                    "DELETE FROM memories WHERE namespace = ?1 AND name = ?2",
+                    [&self.namespace, name],
+                )? > 0)
+            }),
+            Storage::Memory(memories) => Ok(memories.lock().remove(name).is_some()),
+        }
+    }
+
+    /// Every memory of the namespace, by name
+    pub fn list(&self) -> Result<BTreeMap<String, MemoryEntry>> {
+        match &self.storage {
+            Storage::File { path, lock } => {
+                let _guard = lock.lock();
+                Ok(load(path)?.remove(&self.namespace).unwrap_or_default())
+            }
+            #[cfg(feature = "memory-sqlite")]
+            Storage::Sqlite { .. } => self.with_sqlite(|conn| {
+                let mut statement = conn.prepare(
+                    "SELECT name, content, task, created_at, updated_at \
+                     FROM memories WHERE namespace = ?1",
+                )?;
+                let rows = statement.query_map([&self.namespace], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        MemoryEntry {
+                            content: row.get(1)?,
+                            task: row.get(2)?,
+                            created_at: row.get(3)?,
+                            updated_at: row.get(4)?,
+                        },
+                    ))
+                })?;
+                rows.collect()
+            }),
+            Storage::Memory(memories) => Ok(memories.lock().clone()),
+        }
+    }
+
+    /// Run `f` on the database, opening it (and its table) on first use
+    #[cfg(feature = "memory-sqlite")]
+    fn with_sqlite<T>(
+        &self,
+        f: impl FnOnce(&rusqlite::Connection) -> rusqlite::Result<T>,
+    ) -> Result<T> {
+        let Storage::Sqlite { path, conn } = &self.storage else {
+            unreachable!("with_sqlite on a sqlite store");
+        };
+        let sqlite_error = |e: rusqlite::Error| NikaError::MemoryStoreError {
+            backend: "sqlite".to_string(),
+            reason: format!("{}: {}", path.display(), e),
+        };
+        let mut conn = conn.lock();
+        if conn.is_none() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let opened = rusqlite::Connection::open(path).map_err(sqlite_error)?;
+            opened
+                .execute_batch(
+                    "CREATE TABLE IF NOT EXISTS memories (
+                        namespace TEXT NOT NULL,
+                        name TEXT NOT NULL,
+                        content TEXT NOT NULL,
+                        task TEXT,
+                        created_at TEXT NOT NULL,
+                        updated_at TEXT NOT NULL,
+                        PRIMARY KEY (namespace, name)
+                    );",
+                )
+                .map_err(sqlite_error)?;
+            *conn = Some(opened);
+        }
+        f(conn.as_ref().expect("opened above")).map_err(sqlite_error)
+    }
+
+    fn error(&self, reason: String) -> NikaError {
+        let backend = match self.storage {
+            Storage::File { .. } => "file",
+            #[cfg(feature = "memory-sqlite")]
+            Storage::Sqlite { .. } => "sqlite",
+            Storage::Memory(_) => "memory",
+        };
+        NikaError::MemoryStoreError {
+            backend: backend.to_string(),
+            reason,
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<Namespaces> {
+    if !path.exists() {
+        return Ok(Namespaces::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn save(path: &Path, all: &Namespaces) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write then rename, so a concurrent reader never sees half a file
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, serde_json::to_string_pretty(all)?)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path, backend: MemoryBackendKind, namespace: &str) -> MemoryConfig {
+        MemoryConfig {
+            backend,
+            path: Some(dir.join(match backend {
+                MemoryBackendKind::File => "memory.json",
+                MemoryBackendKind::Sqlite => "memory.db",
+            })),
+            namespace: Some(namespace.to_string()),
+        }
+    }
+
+    fn remember_recall_across_runs(backend: MemoryBackendKind) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let first = AgentMemory::new(&config(dir.path(), backend, "triage")).unwrap();
+        assert!(!first
+            .remember("flaky-tests", "test_upload times out on CI", Some("triage"))
+            .unwrap());
+        assert!(first
+            .remember("flaky-tests", "test_upload and test_sync", Some("triage"))
+            .unwrap());
+        first.remember("owners", "api: @ana", None).unwrap();
+
+        // A later run, and another project sharing the store
+        let second = AgentMemory::new(&config(dir.path(), backend, "triage")).unwrap();
+        let entry = second.recall("flaky-tests").unwrap().unwrap();
+        assert_eq!(entry.content, "test_upload and test_sync");
+        assert_eq!(entry.task.as_deref(), Some("triage"));
+        assert!(entry.created_at <= entry.updated_at);
+        let other = AgentMemory::new(&config(dir.path(), backend, "website")).unwrap();
+        assert!(other.list().unwrap().is_empty());
+
+        let found = second.search("TEST_", 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, "flaky-tests");
+        assert!(second.forget("owners").unwrap());
+        assert!(!second.forget("owners").unwrap());
+        assert_eq!(first.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn file_memories_persist_per_namespace() {
+        remember_recall_across_runs(MemoryBackendKind::File);
+    }
+
+    #[cfg(feature = "memory-sqlite")]
+    #[test]
+    fn sqlite_memories_persist_per_namespace() {
+        remember_recall_across_runs(MemoryBackendKind::Sqlite);
+    }
+
+    #[test]
+    fn limits() {
+        let memory = AgentMemory::in_memory("p");
+        let err = memory
+            .remember("big", &"x".repeat(MAX_MEMORY_CHARS + 1), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("keep the gist"), "{}", err);
+
+        #[cfg(not(feature = "memory-sqlite"))]
+        assert!(AgentMemory::new(&MemoryConfig {
+            backend: MemoryBackendKind::Sqlite,
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! StoreBackend - where DataStore keeps task results (v0.7)
+//!
+//! - `MemoryBackend`: DashMap, the default (lock-free, per process)
+//! - `SledBackend`: local sled database (`store-sled` feature)
+//! - `RedisBackend`: Redis server shared by workers (`store-redis` feature)
+//!
+//! Persistent backends keep a `MemoryBackend` in front of the database, so
+//! reads of results written by this process never leave it. Results are
+//! keyed by a namespace (the run's generation id) and the task id.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use super::TaskResult;
+use crate::config::{StoreBackendKind, StoreConfig};
+use crate::error::NikaError;
+
+/// Storage behind a [`DataStore`](super::DataStore)
+///
+/// Reads and writes are infallible for callers: a persistent backend logs
+/// I/O failures and answers from its in-memory copy.
+pub trait StoreBackend: Send + Sync {
+    /// Backend name for logs and errors ("memory", "sled", "redis")
+    fn name(&self) -> &'static str;
+
+    /// Store a task result, replacing any previous one
+    fn insert(&self, task_id: Arc<str>, result: TaskResult);
+
+    /// Look up a task result
+    fn get(&self, task_id: &str) -> Option<TaskResult>;
+
+    /// Check if a task result exists
+    fn contains(&self, task_id: &str) -> bool {
+        self.get(task_id).is_some()
+    }
+}
+
+/// In-process storage (lock-free DashMap)
+///
+/// Uses Arc<str> keys for zero-cost cloning with same Arc used in events.
+#[derive(Default)]
+pub struct MemoryBackend {
+    /// Task results: task_id → TaskResult
+    results: DashMap<Arc<str>, TaskResult>,
+}
+
+impl StoreBackend for MemoryBackend {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn insert(&self, task_id: Arc<str>, result: TaskResult) {
+        self.results.insert(task_id, result);
+    }
+
+    fn get(&self, task_id: &str) -> Option<TaskResult> {
+        self.results.get(task_id).map(|r| r.value().clone())
+    }
+
+    fn contains(&self, task_id: &str) -> bool {
+        self.results.contains_key(task_id)
+    }
+}
+
+/// Open the backend selected by `config`, scoped to `namespace`
+pub fn open_backend(
+    config: &StoreConfig,
+    namespace: &str,
+) -> Result<Arc<dyn StoreBackend>, NikaError> {
+    match config.backend {
+        StoreBackendKind::Memory => Ok(Arc::new(MemoryBackend::default())),
+        #[cfg(feature = "store-sled")]
+        StoreBackendKind::Sled => {
+            let path = config
+                .path
+                .clone()
+                .unwrap_or_else(|| std::path::PathBuf::from(".nika").join("store"));
+            Ok(Arc::new(super::sled_store::SledBackend::open(
+                &path, namespace,
+            )?))
+        }
+        #[cfg(feature = "store-redis")]
+        StoreBackendKind::Redis => {
+            let url = config.url.as_deref().ok_or_else(|| NikaError::StoreError {
+                backend: "redis".to_string(),
+                reason: "missing `url` (e.g. \"redis://127.0.0.1/\")".to_string(),
+            })?;
+            Ok(Arc::new(super::redis_store::RedisBackend::open(
+                url, namespace,
+            )?))
+        }
+        #[allow(unreachable_patterns)]
+        other => {
+            let _ = namespace;
+            Err(NikaError::StoreError {
+                backend: other.as_str().to_string(),
+                reason: format!(
+                    "nika was built without the `store-{}` feature",
+                    other.as_str()
+                ),
+            })
+        }
+    }
+}
+
+/// Serialize a result for a persistent backend
+#[cfg(any(feature = "store-sled", feature = "store-redis"))]
+pub(crate) fn encode(result: &TaskResult) -> Vec<u8> {
+    serde_json::to_vec(result).expect("TaskResult serializes to JSON")
+}
+
+/// Deserialize a result read from a persistent backend
+#[cfg(any(feature = "store-sled", feature = "store-redis"))]
+pub(crate) fn decode(backend: &str, bytes: &[u8]) -> Result<TaskResult, NikaError> {
+    serde_json::from_slice(bytes).map_err(|e| NikaError::StoreError {
+        backend: backend.to_string(),
+        reason: format!("corrupt task result: {}", e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn memory_is_the_default() {
+        let backend = open_backend(&StoreConfig::default(), "gen-1").unwrap();
+        assert_eq!(backend.name(), "memory");
+
+        backend.insert(
+            Arc::from("task"),
+            TaskResult::success_str("done", Duration::from_secs(1)),
+        );
+        assert!(backend.contains("task"));
+        assert_eq!(backend.get("task").unwrap().output_str(), "done");
+    }
+
+    #[cfg(not(feature = "store-redis"))]
+    #[test]
+    fn missing_feature_is_a_store_error() {
+        let config = StoreConfig {
+            backend: StoreBackendKind::Redis,
+            url: Some("redis://127.0.0.1/".to_string()),
+            ..Default::default()
+        };
+        let err = open_backend(&config, "gen-1").err().unwrap();
+        assert_eq!(err.code(), "NIKA-190");
+        assert!(err.to_string().contains("store-redis"));
+    }
+}
@@ -1,26 +1,31 @@
-//! DataStore - task output storage with DashMap (v0.1 optimized)
+//! DataStore - task output storage over a StoreBackend (v0.1 optimized)
 //!
-//! Single HashMap design with lock-free concurrent access.
+//! Lock-free DashMap by default; sled or Redis when configured (v0.7).
 //! Path resolution unified with jsonpath module.
 
 use std::borrow::Cow;
 use std::sync::Arc;
 use std::time::Duration;
 
-use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::backend::{open_backend, MemoryBackend, StoreBackend};
+use crate::config::StoreConfig;
+use crate::error::NikaError;
 use crate::util::jsonpath;
 
 /// Task execution status
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TaskStatus {
     Success,
     Failed(String),
+    /// Not run: no incoming flow was taken (v0.7, `when:` on flows)
+    Skipped(String),
 }
 
 /// Task execution result (unified storage)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskResult {
     /// Output as JSON Value (Arc for O(1) cloning of large JSON structures)
     pub output: Arc<Value>,
@@ -58,16 +63,30 @@ impl TaskResult {
         }
     }
 
+    /// Create a skipped result (unselected conditional branch, v0.7)
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Self {
+            output: Arc::new(Value::Null),
+            duration: Duration::ZERO,
+            status: TaskStatus::Skipped(reason.into()),
+        }
+    }
+
     /// Check if task succeeded
     pub fn is_success(&self) -> bool {
         matches!(self.status, TaskStatus::Success)
     }
 
+    /// Check if task was skipped (v0.7)
+    pub fn is_skipped(&self) -> bool {
+        matches!(self.status, TaskStatus::Skipped(_))
+    }
+
     /// Get error message if failed
     pub fn error(&self) -> Option<&str> {
         match &self.status {
             TaskStatus::Failed(e) => Some(e),
-            TaskStatus::Success => None,
+            TaskStatus::Success | TaskStatus::Skipped(_) => None,
         }
     }
 
@@ -80,13 +99,18 @@ impl TaskResult {
     }
 }
 
-/// Thread-safe storage for task results (lock-free)
+/// Thread-safe storage for task results
 ///
-/// Uses Arc<str> keys for zero-cost cloning with same Arc used in events.
-#[derive(Clone, Default)]
+/// Clones share one backend (in-memory DashMap unless configured).
+#[derive(Clone)]
 pub struct DataStore {
-    /// Task results: task_id → TaskResult
-    results: Arc<DashMap<Arc<str>, TaskResult>>,
+    backend: Arc<dyn StoreBackend>,
+}
+
+impl Default for DataStore {
+    fn default() -> Self {
+        Self::with_backend(Arc::new(MemoryBackend::default()))
+    }
 }
 
 impl DataStore {
@@ -94,19 +118,36 @@ impl DataStore {
         Self::default()
     }
 
+    /// Store results in a custom backend (v0.7)
+    pub fn with_backend(backend: Arc<dyn StoreBackend>) -> Self {
+        Self { backend }
+    }
+
+    /// Open the backend selected by `[store]` in config.toml (v0.7)
+    ///
+    /// `namespace` scopes the keys, normally the run's generation id.
+    pub fn open(config: &StoreConfig, namespace: &str) -> Result<Self, NikaError> {
+        open_backend(config, namespace).map(Self::with_backend)
+    }
+
+    /// Backend name ("memory", "sled", "redis")
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
     /// Insert a task result (accepts Arc<str> for zero-cost key reuse)
     pub fn insert(&self, task_id: Arc<str>, result: TaskResult) {
-        self.results.insert(task_id, result);
+        self.backend.insert(task_id, result);
     }
 
     /// Get a task result
     pub fn get(&self, task_id: &str) -> Option<TaskResult> {
-        self.results.get(task_id).map(|r| r.value().clone())
+        self.backend.get(task_id)
     }
 
     /// Check if task exists
     pub fn contains(&self, task_id: &str) -> bool {
-        self.results.contains_key(task_id)
+        self.backend.contains(task_id)
     }
 
     /// Check if task succeeded
@@ -114,10 +155,15 @@ impl DataStore {
         self.get(task_id).is_some_and(|r| r.is_success())
     }
 
+    /// Check if task was skipped (v0.7)
+    pub fn is_skipped(&self, task_id: &str) -> bool {
+        self.get(task_id).is_some_and(|r| r.is_skipped())
+    }
+
     /// Get just the output Value for a task (for JSONPath resolution)
     /// Returns Arc<Value> for O(1) cloning instead of deep copy
     pub fn get_output(&self, task_id: &str) -> Option<Arc<Value>> {
-        self.results.get(task_id).map(|r| Arc::clone(&r.output))
+        self.get(task_id).map(|r| r.output)
     }
 
     /// Resolve a dot-separated path (e.g., "weather.summary")
@@ -495,7 +541,7 @@ mod tests {
         // Clone the store
         let cloned = store.clone();
 
-        // Both should see the same data (shared Arc<DashMap>)
+        // Both should see the same data (shared backend)
         assert_eq!(
             store.get("task").unwrap().output,
             cloned.get("task").unwrap().output
@@ -507,7 +553,7 @@ mod tests {
             TaskResult::success(json!(1), Duration::from_secs(1)),
         );
 
-        // Clone should also see it (same underlying DashMap)
+        // Clone should also see it (same underlying backend)
         assert!(cloned.contains("new"));
     }
 }
@@ -0,0 +1,275 @@
+//! HttpCache - response cache for `fetch:` tasks (v0.7)
+//!
+//! `GET` responses are kept in `.nika/http-cache/<key>.json`, keyed by a
+//! hash of the URL and request headers, so polling-style workflows don't
+//! hammer upstream APIs:
+//!
+//! - fresh entries (`Cache-Control: max-age`) are served without a request
+//! - stale entries with an `ETag` or `Last-Modified` are revalidated with
+//!   `If-None-Match`/`If-Modified-Since`; a `304` serves the stored body
+//! - `no-store` responses are never kept, `no-cache` ones always revalidated
+//!
+//! Responses with neither a lifetime nor a validator are not stored.
+//! `nika run --refresh` skips lookups but still stores what it fetches.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_128;
+
+/// Project HTTP cache directory, relative to the working directory
+pub const HTTP_CACHE_DIR: &str = ".nika/http-cache";
+
+/// The directives of a `Cache-Control` header that matter to a client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheControl {
+    pub no_store: bool,
+    pub no_cache: bool,
+    pub max_age: Option<u64>,
+}
+
+impl CacheControl {
+    /// Parse `Cache-Control: max-age=60, must-revalidate` (unknown directives are ignored)
+    pub fn parse(header: &str) -> Self {
+        let mut control = Self::default();
+        for directive in header.split(',') {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name, Some(value.trim().trim_matches('"'))),
+                None => (directive, None),
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "no-store" => control.no_store = true,
+                "no-cache" => control.no_cache = true,
+                "max-age" => control.max_age = value.and_then(|v| v.parse().ok()),
+                _ => {}
+            }
+        }
+        control
+    }
+}
+
+/// A stored response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// Final URL, after redirects
+    pub url: String,
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// `Link` header, for pagination
+    pub link: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// When the response was fetched or last revalidated (RFC 3339)
+    pub stored_at: String,
+    /// Seconds the response is fresh after `stored_at`
+    pub max_age: Option<u64>,
+    /// `Cache-Control: no-cache`: revalidate on every use
+    #[serde(default)]
+    pub no_cache: bool,
+    pub body: String,
+}
+
+impl CachedResponse {
+    /// Whether the entry can be served without asking the server
+    pub fn is_fresh(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let Some(max_age) = self.max_age.filter(|_| !self.no_cache) else {
+            return false;
+        };
+        let Ok(stored_at) = chrono::DateTime::parse_from_rfc3339(&self.stored_at) else {
+            return false;
+        };
+        let age = now.signed_duration_since(stored_at).num_seconds();
+        age >= 0 && (age as u64) < max_age
+    }
+
+    /// Whether the server can be asked for changes only
+    pub fn has_validator(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Whether a response with `control` is worth storing
+    pub fn is_storable(&self, control: &CacheControl) -> bool {
+        self.status == 200
+            && !control.no_store
+            && (self.has_validator() || control.max_age.is_some_and(|age| age > 0))
+    }
+}
+
+/// Responses backed by JSON files, with per-run hit and miss counters
+#[derive(Debug, Default)]
+pub struct HttpCache {
+    /// `None` disables caching
+    dir: Option<PathBuf>,
+    /// Skip lookups, still store (`nika run --refresh`)
+    refresh: bool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl HttpCache {
+    /// Responses in `dir` (created on the first write)
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..Self::default()
+        }
+    }
+
+    /// The project's `.nika/http-cache/`
+    pub fn project() -> Self {
+        Self::open(HTTP_CACHE_DIR)
+    }
+
+    /// No caching: every request goes to the server
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Fetch everything again, replacing stored responses
+    pub fn refreshing(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Whether responses are looked up or stored at all
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Cache key of a request; headers are part of it, since they may
+    /// change the response (credentials, `Accept`, ...)
+    pub fn key(method: &str, url: &str, headers: &[(String, String)]) -> String {
+        let mut headers: Vec<(String, &str)> = headers
+            .iter()
+            .map(|(k, v)| (k.to_ascii_lowercase(), v.as_str()))
+            .collect();
+        headers.sort();
+        let mut request = format!("{} {}\n", method.to_ascii_uppercase(), url);
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\n", name, value));
+        }
+        format!("{:032x}", xxh3_128(request.as_bytes()))
+    }
+
+    /// The stored response for `key`, unless refreshing
+    ///
+    /// Unreadable entries count as missing.
+    pub fn get(&self, key: &str) -> Option<CachedResponse> {
+        if self.refresh {
+            return None;
+        }
+        let path = self.dir.as_ref()?.join(format!("{}.json", key));
+        let text = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    /// Store `response` under `key`
+    ///
+    /// A cache that can't be written only costs a request next time, so
+    /// failures are logged rather than returned.
+    pub fn put(&self, key: &str, response: &CachedResponse) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(dir)?;
+            let text = serde_json::to_string_pretty(response)?;
+            let path = dir.join(format!("{}.json", key));
+            let tmp = path.with_extension("json.tmp");
+            std::fs::write(&tmp, text)?;
+            std::fs::rename(tmp, path)
+        };
+        if let Err(e) = write() {
+            tracing::warn!(error = %e, url = %response.url, "Could not store HTTP cache entry");
+        }
+    }
+
+    /// Count a response served from the cache; returns `(hits, misses)`
+    pub fn record_hit(&self) -> (u64, u64) {
+        let hits = self.hits.fetch_add(1, Ordering::Relaxed) + 1;
+        (hits, self.misses.load(Ordering::Relaxed))
+    }
+
+    /// Count a response fetched from the server; returns `(hits, misses)`
+    pub fn record_miss(&self) -> (u64, u64) {
+        let misses = self.misses.fetch_add(1, Ordering::Relaxed) + 1;
+        (self.hits.load(Ordering::Relaxed), misses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(max_age: Option<u64>, etag: Option<&str>) -> CachedResponse {
+        CachedResponse {
+            url: "https://api.example.com/items".to_string(),
+            status: 200,
+            content_type: Some("application/json".to_string()),
+            link: None,
+            etag: etag.map(str::to_string),
+            last_modified: None,
+            stored_at: chrono::Utc::now().to_rfc3339(),
+            max_age,
+            no_cache: false,
+            body: "[1,2]".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_cache_control() {
+        let control = CacheControl::parse("public, Max-Age=\"120\", no-cache");
+        assert_eq!(control.max_age, Some(120));
+        assert!(control.no_cache);
+        assert!(!control.no_store);
+        assert!(CacheControl::parse("no-store").no_store);
+    }
+
+    #[test]
+    fn freshness_and_storability() {
+        let now = chrono::Utc::now();
+        assert!(response(Some(60), None).is_fresh(now));
+        assert!(!response(Some(60), None).is_fresh(now + chrono::Duration::seconds(61)));
+        assert!(!response(None, Some("\"v1\"")).is_fresh(now));
+
+        let storable = |max_age, etag: Option<&str>, header: &str| {
+            response(max_age, etag).is_storable(&CacheControl::parse(header))
+        };
+        assert!(storable(Some(60), None, "max-age=60"));
+        assert!(storable(None, Some("\"v1\""), ""));
+        assert!(!storable(None, None, ""));
+        assert!(!storable(None, Some("\"v1\""), "no-store"));
+    }
+
+    #[test]
+    fn stores_and_refreshes_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::open(dir.path());
+        let key = HttpCache::key("get", "https://api.example.com/items", &[]);
+        assert_eq!(
+            key,
+            HttpCache::key("GET", "https://api.example.com/items", &[])
+        );
+        assert_ne!(
+            key,
+            HttpCache::key(
+                "GET",
+                "https://api.example.com/items",
+                &[("Authorization".to_string(), "Bearer x".to_string())]
+            )
+        );
+
+        assert!(cache.get(&key).is_none());
+        cache.put(&key, &response(Some(60), Some("\"v1\"")));
+        assert_eq!(cache.get(&key).unwrap().body, "[1,2]");
+        assert!(HttpCache::open(dir.path())
+            .refreshing(true)
+            .get(&key)
+            .is_none());
+        assert!(HttpCache::disabled().get(&key).is_none());
+
+        assert_eq!(cache.record_miss(), (0, 1));
+        assert_eq!(cache.record_hit(), (1, 1));
+    }
+}
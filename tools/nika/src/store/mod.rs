@@ -1,14 +1,45 @@
 //! Store Module - state management (v0.1)
 //!
 //! Thread-safe storage for task execution results.
-//! Uses DashMap for lock-free concurrent access.
+//! Uses DashMap for lock-free concurrent access; sled and Redis backends
+//! are feature-gated (v0.7).
 //!
 //! Key types:
+//! - `AgentMemory`: Named memories of `agent:` tasks, kept across runs (v0.7)
 //! - `DataStore`: Central storage for task results
+//! - `HttpCache`: Response cache for `fetch:` tasks (v0.7)
+//! - `PresetStore`: Named `nika run` presets per workflow (v0.7)
+//! - `StateStore`: Key-value state kept across runs (v0.7)
+//! - `StoreBackend`: Where results live (memory, sled, redis)
 //! - `TaskResult`: Execution result with status and output
 //! - `TaskStatus`: Success or failure status
+//! - `VectorStore`: Embedding collections for `embed:`/`recall:` (v0.7)
+//! - `retrieve`: File chunking and BM25 ranking for `retrieve:` (v0.7)
 
+mod agent_memory;
+mod backend;
 mod datastore;
+mod http_cache;
+mod presets;
+#[cfg(feature = "store-redis")]
+mod redis_store;
+pub mod retrieve;
+#[cfg(feature = "store-sled")]
+mod sled_store;
+mod state;
+mod vector;
 
 // Re-export all public types
+pub use agent_memory::{AgentMemory, MemoryEntry, MAX_MEMORIES, MAX_MEMORY_CHARS};
+pub use backend::{MemoryBackend, StoreBackend};
 pub use datastore::{DataStore, TaskResult, TaskStatus};
+pub use http_cache::{CacheControl, CachedResponse, HttpCache, HTTP_CACHE_DIR};
+pub use presets::{PresetStore, RunPreset, PRESETS_DIR};
+#[cfg(feature = "store-redis")]
+pub use redis_store::RedisBackend;
+#[cfg(feature = "store-sled")]
+pub use sled_store::SledBackend;
+pub use state::{StateEntry, StateStore, STATE_FILE};
+pub use vector::{
+    cosine_similarity, Collection, VectorMatch, VectorRecord, VectorStore, VECTORS_DIR,
+};
@@ -0,0 +1,246 @@
+//! PresetStore - named run presets per workflow (v0.7)
+//!
+//! `nika run wf.nika.yaml --save-preset nightly` keeps the run's provider,
+//! model, `--set` overrides and `--input` values in
+//! `.nika/presets/wf/nightly.json`; `--preset nightly` applies them again.
+//! Flags given next to `--preset` win over the saved ones.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::NikaError;
+
+/// Project presets directory, relative to the working directory
+pub const PRESETS_DIR: &str = ".nika/presets";
+
+/// Options of a `nika run`, as saved in a preset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunPreset {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// `--set path=value` overrides, applied in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<String>,
+    /// `--input` values by input name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub inputs: BTreeMap<String, String>,
+}
+
+impl RunPreset {
+    /// This preset with `flags` applied on top
+    ///
+    /// Provider, model and inputs given as flags replace the saved ones;
+    /// overrides run after the saved ones, so the last one for a path wins.
+    pub fn merged(mut self, flags: RunPreset) -> RunPreset {
+        self.provider = flags.provider.or(self.provider);
+        self.model = flags.model.or(self.model);
+        self.overrides.extend(flags.overrides);
+        self.inputs.extend(flags.inputs);
+        self
+    }
+
+    /// `--input` arguments for the saved values
+    pub fn input_args(&self) -> Vec<String> {
+        self.inputs
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect()
+    }
+
+    /// One-line description (`provider=claude, 2 overrides, topic=rust`)
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        parts.extend(self.provider.as_ref().map(|p| format!("provider={}", p)));
+        parts.extend(self.model.as_ref().map(|m| format!("model={}", m)));
+        match self.overrides.len() {
+            0 => {}
+            1 => parts.push(format!("--set {}", self.overrides[0])),
+            n => parts.push(format!("{} overrides", n)),
+        }
+        parts.extend(self.input_args());
+        if parts.is_empty() {
+            "no options".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// Presets backed by one JSON file each, grouped by workflow
+#[derive(Debug, Clone)]
+pub struct PresetStore {
+    dir: PathBuf,
+}
+
+impl PresetStore {
+    /// Presets under `dir` (created on the first save)
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The project's `.nika/presets/`
+    pub fn project() -> Self {
+        Self::open(PRESETS_DIR)
+    }
+
+    /// Save `preset` as `name` for `workflow`, replacing any previous one
+    pub fn save(&self, workflow: &Path, name: &str, preset: &RunPreset) -> Result<(), NikaError> {
+        check_name(name)?;
+        let dir = self.workflow_dir(workflow);
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(&dir)?;
+            let path = dir.join(format!("{}.json", name));
+            let tmp = path.with_extension("json.tmp");
+            std::fs::write(&tmp, serde_json::to_string_pretty(preset)?)?;
+            std::fs::rename(tmp, path)
+        };
+        write().map_err(|e| NikaError::ConfigError {
+            reason: format!("Could not save preset '{}': {}", name, e),
+        })
+    }
+
+    /// The preset `name` of `workflow`
+    pub fn load(&self, workflow: &Path, name: &str) -> Result<RunPreset, NikaError> {
+        check_name(name)?;
+        let path = self.workflow_dir(workflow).join(format!("{}.json", name));
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let saved: Vec<String> = self.list(workflow).into_iter().map(|(n, _)| n).collect();
+                return Err(NikaError::ConfigError {
+                    reason: format!(
+                        "No preset '{}' for {} (saved: {})",
+                        name,
+                        workflow.display(),
+                        if saved.is_empty() {
+                            "none".to_string()
+                        } else {
+                            saved.join(", ")
+                        }
+                    ),
+                });
+            }
+            Err(e) => {
+                return Err(NikaError::ConfigError {
+                    reason: format!("Could not read preset '{}': {}", name, e),
+                })
+            }
+        };
+        serde_json::from_str(&text).map_err(|e| NikaError::ConfigError {
+            reason: format!("Invalid preset {}: {}", path.display(), e),
+        })
+    }
+
+    /// Saved presets of `workflow`, sorted by name (unreadable ones skipped)
+    pub fn list(&self, workflow: &Path) -> Vec<(String, RunPreset)> {
+        let Ok(entries) = std::fs::read_dir(self.workflow_dir(workflow)) else {
+            return Vec::new();
+        };
+        let mut presets: Vec<(String, RunPreset)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                let name = path.file_stem()?.to_str()?.to_string();
+                let text = std::fs::read_to_string(&path).ok()?;
+                Some((name, serde_json::from_str(&text).ok()?))
+            })
+            .collect();
+        presets.sort_by(|a, b| a.0.cmp(&b.0));
+        presets
+    }
+
+    /// `.nika/presets/<workflow name>/`, the file name without `.nika.yaml`
+    fn workflow_dir(&self, workflow: &Path) -> PathBuf {
+        let file = workflow
+            .file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = [".nika.yaml", ".nika.yml", ".yaml", ".yml"]
+            .iter()
+            .find_map(|ext| file.strip_suffix(ext))
+            .unwrap_or(&file);
+        self.dir.join(name)
+    }
+}
+
+/// Preset names become file names: letters, digits, `-` and `_` only
+fn check_name(name: &str) -> Result<(), NikaError> {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        Ok(())
+    } else {
+        Err(NikaError::ConfigError {
+            reason: format!(
+                "Invalid preset name '{}': use letters, digits, '-' and '_'",
+                name
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nightly() -> RunPreset {
+        RunPreset {
+            provider: Some("claude".to_string()),
+            model: None,
+            overrides: vec!["tasks.draft.infer.max_tokens=500".to_string()],
+            inputs: BTreeMap::from([
+                ("topic".to_string(), "rust".to_string()),
+                ("words".to_string(), "300".to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn saves_loads_and_lists_per_workflow() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PresetStore::open(dir.path());
+        let workflow = Path::new("flows/post.nika.yaml");
+
+        store.save(workflow, "nightly", &nightly()).unwrap();
+        store
+            .save(workflow, "draft", &RunPreset::default())
+            .unwrap();
+        assert!(dir.path().join("post/nightly.json").is_file());
+
+        assert_eq!(store.load(workflow, "nightly").unwrap(), nightly());
+        let names: Vec<String> = store.list(workflow).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, ["draft", "nightly"]);
+        assert!(store.list(Path::new("other.nika.yaml")).is_empty());
+
+        let err = store.load(workflow, "weekly").unwrap_err().to_string();
+        assert!(err.contains("saved: draft, nightly"), "{}", err);
+        assert!(store.save(workflow, "../up", &nightly()).is_err());
+    }
+
+    #[test]
+    fn flags_win_over_saved_options() {
+        let flags = RunPreset {
+            provider: Some("openai".to_string()),
+            overrides: vec!["tasks.draft.infer.max_tokens=800".to_string()],
+            inputs: BTreeMap::from([("topic".to_string(), "sled".to_string())]),
+            ..RunPreset::default()
+        };
+        let merged = nightly().merged(flags);
+        assert_eq!(merged.provider.as_deref(), Some("openai"));
+        assert_eq!(merged.overrides.len(), 2);
+        assert_eq!(merged.input_args(), ["topic=sled", "words=300"]);
+        assert_eq!(
+            nightly().summary(),
+            "provider=claude, --set tasks.draft.infer.max_tokens=500, topic=rust, words=300"
+        );
+    }
+}
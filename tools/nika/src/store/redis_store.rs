@@ -0,0 +1,103 @@
+//! RedisBackend - task results on a Redis server (v0.7)
+//!
+//! Keys are `nika:<namespace>:<task_id>`, so workers sharing a namespace
+//! see each other's results: a miss in the local copy falls through to
+//! Redis. Calls are synchronous; each is one round trip on a single
+//! connection.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use super::backend::{decode, encode, MemoryBackend, StoreBackend};
+use super::TaskResult;
+use crate::error::NikaError;
+
+/// Redis connection plus the in-memory copy of known results
+pub struct RedisBackend {
+    connection: Mutex<redis::Connection>,
+    prefix: String,
+    cache: MemoryBackend,
+}
+
+impl RedisBackend {
+    /// Connect to `url` and scope keys to `namespace`
+    pub fn open(url: &str, namespace: &str) -> Result<Self, NikaError> {
+        let connection = redis::Client::open(url)
+            .and_then(|client| client.get_connection())
+            .map_err(|e| store_error(format!("{}: {}", url, e)))?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+            prefix: format!("nika:{}:", namespace),
+            cache: MemoryBackend::default(),
+        })
+    }
+
+    fn key(&self, task_id: &str) -> String {
+        format!("{}{}", self.prefix, task_id)
+    }
+}
+
+impl StoreBackend for RedisBackend {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    fn insert(&self, task_id: Arc<str>, result: TaskResult) {
+        let written: redis::RedisResult<()> = redis::cmd("SET")
+            .arg(self.key(&task_id))
+            .arg(encode(&result))
+            .query(&mut *self.connection.lock());
+        if let Err(e) = written {
+            tracing::warn!("{}", store_error(format!("write '{}': {}", task_id, e)));
+        }
+        self.cache.insert(task_id, result);
+    }
+
+    fn get(&self, task_id: &str) -> Option<TaskResult> {
+        if let Some(result) = self.cache.get(task_id) {
+            return Some(result);
+        }
+        let read: redis::RedisResult<Option<Vec<u8>>> = redis::cmd("GET")
+            .arg(self.key(task_id))
+            .query(&mut *self.connection.lock());
+        let bytes = match read {
+            Ok(bytes) => bytes?,
+            Err(e) => {
+                tracing::warn!("{}", store_error(format!("read '{}': {}", task_id, e)));
+                return None;
+            }
+        };
+        match decode("redis", &bytes) {
+            Ok(result) => {
+                self.cache.insert(Arc::from(task_id), result.clone());
+                Some(result)
+            }
+            Err(e) => {
+                tracing::warn!("{}", e);
+                None
+            }
+        }
+    }
+}
+
+fn store_error(reason: String) -> NikaError {
+    NikaError::StoreError {
+        backend: "redis".to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreachable_server_fails_to_open() {
+        let err = RedisBackend::open("redis://127.0.0.1:1/", "gen-1")
+            .err()
+            .unwrap();
+        assert_eq!(err.code(), "NIKA-190");
+        assert!(RedisBackend::open("not a url", "gen-1").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Document chunks for `retrieve:` (v0.7)
+//!
+//! Files matched by a glob are split into overlapping chunks, preferring
+//! paragraph, line and word boundaries. Chunks are ranked by embeddings
+//! (see `VectorStore`) or, without an embeddings provider, by BM25.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use globset::GlobBuilder;
+use ignore::WalkBuilder;
+use serde::Serialize;
+
+use crate::error::NikaError;
+
+/// One chunk of a matched file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Chunk {
+    /// Path relative to the search base, with `/` separators
+    pub file: String,
+    /// Position of the chunk within its file (0-based)
+    pub chunk: usize,
+    pub text: String,
+}
+
+impl Chunk {
+    /// Stable id: changes when the file, position or text does
+    pub fn id(&self) -> String {
+        let key = format!("{}#{}\n{}", self.file, self.chunk, self.text);
+        format!("{:016x}", xxhash_rust::xxh3::xxh3_64(key.as_bytes()))
+    }
+}
+
+/// Chunk every text file matching `pattern`, sorted by path
+///
+/// A relative pattern is resolved against `base`; only the directory named
+/// by its literal prefix is walked (`docs/**/*.md` walks `docs/`). Hidden
+/// and git-ignored files are skipped, as are files that are not UTF-8. No
+/// match is an error, so a typo doesn't silently feed an empty context into
+/// the prompt.
+pub fn load_chunks(
+    pattern: &str,
+    base: &Path,
+    size: usize,
+    overlap: usize,
+) -> Result<Vec<Chunk>, NikaError> {
+    let error = |reason: String| NikaError::RetrieveError {
+        pattern: pattern.to_string(),
+        reason,
+    };
+    let components: Vec<&str> = pattern.trim_start_matches("./").split('/').collect();
+    let literal = components[..components.len() - 1]
+        .iter()
+        .take_while(|c| !c.contains(['*', '?', '[', '{']))
+        .count();
+    let root = base.join(components[..literal].join("/"));
+    let glob = GlobBuilder::new(&components[literal..].join("/"))
+        .literal_separator(true)
+        .build()
+        .map_err(|e| error(e.kind().to_string()))?
+        .compile_matcher();
+
+    let mut files: Vec<(String, String)> = WalkBuilder::new(&root)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            if !glob.is_match(path.strip_prefix(&root).ok()?) {
+                return None;
+            }
+            let text = std::fs::read_to_string(path).ok()?;
+            let file = path.strip_prefix(base).unwrap_or(path);
+            Some((file.to_string_lossy().replace('\\', "/"), text))
+        })
+        .collect();
+    if files.is_empty() {
+        return Err(error(format!("no text files match in {}", root.display())));
+    }
+    files.sort();
+
+    Ok(files
+        .into_iter()
+        .flat_map(|(file, text)| {
+            chunk_text(&text, size, overlap)
+                .into_iter()
+                .enumerate()
+                .map(move |(chunk, text)| Chunk {
+                    file: file.clone(),
+                    chunk,
+                    text,
+                })
+        })
+        .collect())
+}
+
+/// Split `text` into chunks of at most `size` characters
+///
+/// Each chunk ends at the last blank line, newline or space in its second
+/// half when there is one, and the next chunk starts `overlap` characters
+/// before that end.
+pub fn chunk_text(text: &str, size: usize, overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let size = size.max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let mut end = (start + size).min(chars.len());
+        if end < chars.len() {
+            let window = start + size / 2..end;
+            let last =
+                |is_break: &dyn Fn(usize) -> bool| window.clone().rev().find(|&i| is_break(i));
+            end = last(&|i| chars[i] == '\n' && chars[i - 1] == '\n')
+                .or_else(|| last(&|i| chars[i] == '\n'))
+                .or_else(|| last(&|i| chars[i] == ' '))
+                .map_or(end, |i| i + 1);
+        }
+        let chunk: String = chars[start..end].iter().collect();
+        let chunk = chunk.trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+        if end == chars.len() {
+            break;
+        }
+        start = end.saturating_sub(overlap).max(start + 1);
+    }
+    chunks
+}
+
+/// BM25 scores of `docs` for `query`, one per doc (k1 = 1.2, b = 0.75)
+pub fn bm25_scores(query: &str, docs: &[&str]) -> Vec<f32> {
+    const K1: f32 = 1.2;
+    const B: f32 = 0.75;
+
+    let docs: Vec<Vec<String>> = docs.iter().map(|doc| tokenize(doc)).collect();
+    let avg_len = docs.iter().map(Vec::len).sum::<usize>() as f32 / docs.len().max(1) as f32;
+    let mut query_terms = tokenize(query);
+    query_terms.sort();
+    query_terms.dedup();
+
+    let idf: Vec<f32> = query_terms
+        .iter()
+        .map(|term| {
+            let df = docs.iter().filter(|doc| doc.contains(term)).count() as f32;
+            ((docs.len() as f32 - df + 0.5) / (df + 0.5) + 1.0).ln()
+        })
+        .collect();
+
+    docs.iter()
+        .map(|doc| {
+            let mut tf: HashMap<&str, f32> = HashMap::new();
+            for word in doc {
+                *tf.entry(word).or_default() += 1.0;
+            }
+            let norm = K1 * (1.0 - B + B * doc.len() as f32 / avg_len.max(1.0));
+            query_terms
+                .iter()
+                .zip(&idf)
+                .map(|(term, idf)| {
+                    let f = tf.get(term.as_str()).copied().unwrap_or(0.0);
+                    idf * f * (K1 + 1.0) / (f + norm)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// Lowercased alphanumeric words
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_breaks_at_boundaries_with_overlap() {
+        let text = "First paragraph here.\n\nSecond paragraph is a bit longer than the first.";
+        let chunks = chunk_text(text, 30, 5);
+        assert_eq!(chunks[0], "First paragraph here.");
+        assert!(chunks.iter().all(|c| c.chars().count() <= 30));
+        assert!(chunks.last().unwrap().ends_with("first."));
+        assert_eq!(chunk_text("short", 100, 20), vec!["short"]);
+        assert!(chunk_text("", 100, 20).is_empty());
+    }
+
+    #[test]
+    fn test_bm25_prefers_rare_matching_terms() {
+        let docs = [
+            "rotate the api keys every month",
+            "the deploy runs on fridays",
+            "the backups run nightly",
+        ];
+        let scores = bm25_scores("how do I rotate keys", &docs);
+        assert!(scores[0] > 0.0);
+        assert_eq!(scores[1], 0.0);
+        assert_eq!(scores[2], 0.0);
+    }
+
+    #[test]
+    fn test_load_chunks_matches_glob_and_skips_ignored() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("docs/deep")).unwrap();
+        std::fs::write(dir.path().join("docs/a.md"), "alpha").unwrap();
+        std::fs::write(dir.path().join("docs/deep/b.md"), "beta").unwrap();
+        std::fs::write(dir.path().join("docs/c.txt"), "gamma").unwrap();
+        std::fs::write(dir.path().join("docs/.hidden.md"), "secret").unwrap();
+
+        let chunks = load_chunks("docs/**/*.md", dir.path(), 100, 10).unwrap();
+        let files: Vec<&str> = chunks.iter().map(|c| c.file.as_str()).collect();
+        assert_eq!(files, vec!["docs/a.md", "docs/deep/b.md"]);
+        assert_ne!(chunks[0].id(), chunks[1].id());
+
+        let absolute = format!("{}/docs/*.txt", dir.path().display());
+        let chunks = load_chunks(&absolute, Path::new("."), 100, 10).unwrap();
+        assert_eq!(chunks[0].text, "gamma");
+        assert!(chunks[0].file.ends_with("docs/c.txt"));
+
+        let err = load_chunks("./notes/*.md", dir.path(), 100, 10).unwrap_err();
+        assert_eq!(err.code(), "NIKA-193");
+    }
+}
@@ -0,0 +1,125 @@
+//! SledBackend - task results in a local sled database (v0.7)
+//!
+//! Each run gets its own tree, named after the namespace, so results of
+//! earlier runs stay readable after the process exits.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use parking_lot::Mutex;
+
+use super::backend::{decode, encode, MemoryBackend, StoreBackend};
+use super::TaskResult;
+use crate::error::NikaError;
+
+/// sled tree plus the in-memory copy of this process's results
+pub struct SledBackend {
+    tree: sled::Tree,
+    cache: MemoryBackend,
+}
+
+impl SledBackend {
+    /// Open (or create) the database at `path` and the tree for `namespace`
+    pub fn open(path: &Path, namespace: &str) -> Result<Self, NikaError> {
+        let tree = database(path)
+            .and_then(|db| db.open_tree(namespace))
+            .map_err(|e| store_error(format!("{}: {}", path.display(), e)))?;
+        Ok(Self {
+            tree,
+            cache: MemoryBackend::default(),
+        })
+    }
+}
+
+impl StoreBackend for SledBackend {
+    fn name(&self) -> &'static str {
+        "sled"
+    }
+
+    fn insert(&self, task_id: Arc<str>, result: TaskResult) {
+        if let Err(e) = self.tree.insert(task_id.as_bytes(), encode(&result)) {
+            tracing::warn!("{}", store_error(format!("write '{}': {}", task_id, e)));
+        }
+        self.cache.insert(task_id, result);
+    }
+
+    fn get(&self, task_id: &str) -> Option<TaskResult> {
+        if let Some(result) = self.cache.get(task_id) {
+            return Some(result);
+        }
+        let bytes = match self.tree.get(task_id) {
+            Ok(bytes) => bytes?,
+            Err(e) => {
+                tracing::warn!("{}", store_error(format!("read '{}': {}", task_id, e)));
+                return None;
+            }
+        };
+        match decode("sled", &bytes) {
+            Ok(result) => {
+                self.cache.insert(Arc::from(task_id), result.clone());
+                Some(result)
+            }
+            Err(e) => {
+                tracing::warn!("{}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Open databases, shared by every run of the process
+///
+/// sled locks its directory, so a second `sled::open` of the same path
+/// (e.g. the daemon's next run) would fail.
+static DATABASES: LazyLock<Mutex<HashMap<PathBuf, sled::Db>>> = LazyLock::new(Default::default);
+
+fn database(path: &Path) -> sled::Result<sled::Db> {
+    let mut databases = DATABASES.lock();
+    if let Some(db) = databases.get(path) {
+        return Ok(db.clone());
+    }
+    let db = sled::open(path)?;
+    databases.insert(path.to_path_buf(), db.clone());
+    Ok(db)
+}
+
+fn store_error(reason: String) -> NikaError {
+    NikaError::StoreError {
+        backend: "sled".to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn results_survive_reopening() {
+        let dir = tempfile::TempDir::new().unwrap();
+        {
+            let store = SledBackend::open(dir.path(), "gen-1").unwrap();
+            store.insert(
+                Arc::from("fetch"),
+                TaskResult::success(json!({"items": [1, 2]}), Duration::from_millis(40)),
+            );
+            store.insert(
+                Arc::from("broken"),
+                TaskResult::failed("timeout", Duration::from_secs(3)),
+            );
+        }
+
+        let store = SledBackend::open(dir.path(), "gen-1").unwrap();
+        let fetch = store.get("fetch").unwrap();
+        assert_eq!(*fetch.output, json!({"items": [1, 2]}));
+        assert_eq!(fetch.duration, Duration::from_millis(40));
+        assert_eq!(store.get("broken").unwrap().error(), Some("timeout"));
+
+        // Namespaces are isolated
+        let other = SledBackend::open(dir.path(), "gen-2").unwrap();
+        assert!(!other.contains("fetch"));
+    }
+}
@@ -0,0 +1,313 @@
+//! StateStore - key-value state that outlives a run (v0.7)
+//!
+//! Project-scoped values such as "last processed item id" for incremental
+//! pipelines. Kept in `.nika/state.json`:
+//!
+//! - read in templates as `{{state.key}}` (or `use: { x: state.key ?? 0 }`)
+//! - written by a task's `state: { set: ... }` block after it succeeds
+//! - from shell commands and by hand with `nika state get|set|list|delete`
+//!
+//! Every operation re-reads the file, so values written by `nika state set`
+//! inside an `exec:` task are seen by the rest of the run. Writes hold an
+//! exclusive lock on `state.json.lock` from read to rename, so concurrent
+//! writers in other processes don't lose each other's updates. Expired
+//! entries (`ttl_secs`) read as missing and are dropped on the next write.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Result;
+
+/// Project state file, relative to the working directory
+pub const STATE_FILE: &str = ".nika/state.json";
+
+/// One stored value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateEntry {
+    pub value: Value,
+    pub updated_at: DateTime<Utc>,
+    /// Reads as missing from this instant on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl StateEntry {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+/// Key-value state backed by a JSON file (or memory, for tests)
+#[derive(Debug, Default)]
+pub struct StateStore {
+    /// `None` keeps entries in memory only
+    path: Option<PathBuf>,
+    /// In-memory entries; serializes file access within the process
+    entries: Mutex<BTreeMap<String, StateEntry>>,
+}
+
+impl StateStore {
+    /// State in `path` (a missing file starts empty)
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// The project's `.nika/state.json`
+    pub fn project() -> Self {
+        Self::open(STATE_FILE)
+    }
+
+    /// State that lives as long as the store
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Live value of `key`
+    pub fn get(&self, key: &str) -> Result<Option<Value>> {
+        Ok(self.entry(key)?.map(|e| e.value))
+    }
+
+    /// Live entry of `key`, with its timestamps
+    pub fn entry(&self, key: &str) -> Result<Option<StateEntry>> {
+        let now = Utc::now();
+        Ok(self
+            .read()?
+            .remove(key)
+            .filter(|entry| !entry.is_expired(now)))
+    }
+
+    /// Live entries, sorted by key
+    pub fn list(&self) -> Result<BTreeMap<String, StateEntry>> {
+        let now = Utc::now();
+        let mut entries = self.read()?;
+        entries.retain(|_, entry| !entry.is_expired(now));
+        Ok(entries)
+    }
+
+    /// Live values as one object (the `state` binding)
+    pub fn snapshot(&self) -> Result<Value> {
+        Ok(Value::Object(
+            self.list()?
+                .into_iter()
+                .map(|(key, entry)| (key, entry.value))
+                .collect(),
+        ))
+    }
+
+    /// Set `key`, expiring after `ttl` if given
+    pub fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> Result<()> {
+        let now = Utc::now();
+        // A TTL too large to represent never expires
+        let expires_at = ttl
+            .and_then(|ttl| chrono::Duration::from_std(ttl).ok())
+            .and_then(|ttl| now.checked_add_signed(ttl));
+        self.update(|entries| {
+            entries.insert(
+                key.to_string(),
+                StateEntry {
+                    value,
+                    updated_at: now,
+                    expires_at,
+                },
+            );
+        })
+    }
+
+    /// Remove `key`; false if it wasn't set
+    pub fn delete(&self, key: &str) -> Result<bool> {
+        let mut removed = false;
+        self.update(|entries| removed = entries.remove(key).is_some())?;
+        Ok(removed)
+    }
+
+    /// Remove every key
+    pub fn clear(&self) -> Result<()> {
+        self.update(BTreeMap::clear)
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, StateEntry>> {
+        let entries = self.entries.lock();
+        match &self.path {
+            Some(path) => load(path),
+            None => Ok(entries.clone()),
+        }
+    }
+
+    /// Read-modify-write, pruning expired entries
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, StateEntry>)) -> Result<()> {
+        let mut entries = self.entries.lock();
+        let Some(path) = &self.path else {
+            change(&mut entries);
+            let now = Utc::now();
+            entries.retain(|_, entry| !entry.is_expired(now));
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let _lock = FileLock::acquire(&path.with_extension("json.lock"))?;
+
+        let mut current = load(path)?;
+        change(&mut current);
+        let now = Utc::now();
+        current.retain(|_, entry| !entry.is_expired(now));
+
+        // Write then rename, so a concurrent reader never sees half a file
+        let temp = path.with_extension(format!(
+            "json.{}-{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&temp, serde_json::to_string_pretty(&current)?)?;
+        if let Err(e) = std::fs::rename(&temp, path) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(e.into());
+        }
+        *entries = current;
+        Ok(())
+    }
+}
+
+/// Suffix for temp files, unique within the process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Exclusive advisory lock on a file, released when dropped
+///
+/// Without `flock` (non-Unix), only the in-process mutex serializes writes.
+struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    fn acquire(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            // SAFETY: flock on a descriptor owned by `file`
+            while unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+                let err = std::io::Error::last_os_error();
+                if err.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(err.into());
+                }
+            }
+        }
+        Ok(Self { _file: file })
+    }
+}
+
+fn load(path: &Path) -> Result<BTreeMap<String, StateEntry>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_concurrent_writers_keep_every_update() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".nika/state.json");
+
+        // Separate stores stand in for separate processes
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let store = StateStore::open(path);
+                    for _ in 0..25 {
+                        store
+                            .update(|entries| {
+                                let count = entries
+                                    .get("count")
+                                    .and_then(|e| e.value.as_u64())
+                                    .unwrap_or(0);
+                                entries.insert(
+                                    "count".to_string(),
+                                    StateEntry {
+                                        value: json!(count + 1),
+                                        updated_at: Utc::now(),
+                                        expires_at: None,
+                                    },
+                                );
+                            })
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(
+            StateStore::open(&path).get("count").unwrap(),
+            Some(json!(200))
+        );
+    }
+
+    #[test]
+    fn test_set_get_delete_across_instances() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".nika/state.json");
+
+        let store = StateStore::open(&path);
+        assert_eq!(store.get("last_id").unwrap(), None);
+        store.set("last_id", json!(41), None).unwrap();
+        store.set("cursor", json!({"page": 2}), None).unwrap();
+
+        // Another process (e.g. `nika state set` in an exec task) sees it
+        let other = StateStore::open(&path);
+        assert_eq!(other.get("last_id").unwrap(), Some(json!(41)));
+        other.set("last_id", json!(42), None).unwrap();
+        assert_eq!(store.get("last_id").unwrap(), Some(json!(42)));
+        assert_eq!(
+            store.snapshot().unwrap(),
+            json!({"cursor": {"page": 2}, "last_id": 42})
+        );
+
+        assert!(store.delete("cursor").unwrap());
+        assert!(!store.delete("cursor").unwrap());
+        assert_eq!(other.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_expired_entries_read_as_missing() {
+        let store = StateStore::in_memory();
+        store
+            .set("token", json!("abc"), Some(Duration::ZERO))
+            .unwrap();
+        store
+            .set("kept", json!(true), Some(Duration::from_secs(3600)))
+            .unwrap();
+
+        assert_eq!(store.get("token").unwrap(), None);
+        let kept = store.entry("kept").unwrap().unwrap();
+        assert!(kept.expires_at.unwrap() > kept.updated_at);
+        assert_eq!(store.snapshot().unwrap(), json!({"kept": true}));
+    }
+}
@@ -0,0 +1,352 @@
+//! VectorStore - local embedding collections for `embed:`/`recall:` (v0.7)
+//!
+//! Each collection is a JSON file in `.nika/vectors/<name>.json` holding the
+//! provider and model it was embedded with, so `recall:` embeds its query
+//! the same way. Search is a brute-force cosine scan, which is plenty for
+//! the few thousand chunks a workflow typically indexes.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ast::embed::is_valid_collection;
+use crate::error::NikaError;
+
+/// Project vector store directory, relative to the working directory
+pub const VECTORS_DIR: &str = ".nika/vectors";
+
+/// One embedded text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VectorRecord {
+    pub id: String,
+    pub text: String,
+    pub vector: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub metadata: Value,
+}
+
+/// A search hit, as returned by `recall:`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VectorMatch {
+    pub id: String,
+    pub text: String,
+    /// Cosine similarity with the query
+    pub score: f32,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub metadata: Value,
+}
+
+/// A named set of records embedded by one model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Collection {
+    pub provider: String,
+    pub model: String,
+    pub dimensions: usize,
+    pub records: Vec<VectorRecord>,
+}
+
+/// Embedding collections backed by JSON files (or memory, for tests)
+#[derive(Debug, Default)]
+pub struct VectorStore {
+    /// `None` keeps collections in memory only
+    dir: Option<PathBuf>,
+    /// Collections loaded so far
+    collections: Mutex<HashMap<String, Collection>>,
+}
+
+impl VectorStore {
+    /// Collections in `dir` (created on the first write)
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            collections: Mutex::default(),
+        }
+    }
+
+    /// The project's `.nika/vectors/`
+    pub fn project() -> Self {
+        Self::open(VECTORS_DIR)
+    }
+
+    /// Collections that live as long as the store
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Insert records, replacing those with the same id
+    ///
+    /// The first write fixes the collection's provider, model and
+    /// dimensions; vectors of another size are rejected.
+    pub fn upsert(
+        &self,
+        name: &str,
+        provider: &str,
+        model: &str,
+        records: Vec<VectorRecord>,
+    ) -> Result<(), NikaError> {
+        let mut collections = self.collections.lock();
+        let collection = self.load(&mut collections, name)?;
+        if collection.records.is_empty() {
+            collection.provider = provider.to_string();
+            collection.model = model.to_string();
+            collection.dimensions = records.first().map_or(0, |r| r.vector.len());
+        }
+        for record in records {
+            if record.vector.len() != collection.dimensions {
+                return Err(error(
+                    name,
+                    format!(
+                        "'{}' has {} dimensions, the collection {} ({})",
+                        record.id,
+                        record.vector.len(),
+                        collection.dimensions,
+                        collection.model
+                    ),
+                ));
+            }
+            match collection.records.iter_mut().find(|r| r.id == record.id) {
+                Some(existing) => *existing = record,
+                None => collection.records.push(record),
+            }
+        }
+        self.save(name, collection)
+    }
+
+    /// Provider, model and record count of a collection, if it exists
+    pub fn describe(&self, name: &str) -> Result<Option<(String, String, usize)>, NikaError> {
+        let mut collections = self.collections.lock();
+        let collection = self.load(&mut collections, name)?;
+        Ok((!collection.records.is_empty()).then(|| {
+            (
+                collection.provider.clone(),
+                collection.model.clone(),
+                collection.records.len(),
+            )
+        }))
+    }
+
+    /// Ids of the records in a collection (empty if it doesn't exist)
+    pub fn record_ids(&self, name: &str) -> Result<HashSet<String>, NikaError> {
+        let mut collections = self.collections.lock();
+        let collection = self.load(&mut collections, name)?;
+        Ok(collection.records.iter().map(|r| r.id.clone()).collect())
+    }
+
+    /// The `top_k` records most similar to `query`, best first
+    pub fn similarity_search(
+        &self,
+        name: &str,
+        query: &[f32],
+        top_k: usize,
+        min_score: Option<f32>,
+    ) -> Result<Vec<VectorMatch>, NikaError> {
+        self.search(name, query, top_k, |_, score| {
+            min_score.is_none_or(|min| score >= min)
+        })
+    }
+
+    /// Like `similarity_search`, over the records with the given ids only
+    pub fn similarity_search_among(
+        &self,
+        name: &str,
+        query: &[f32],
+        top_k: usize,
+        ids: &HashSet<String>,
+    ) -> Result<Vec<VectorMatch>, NikaError> {
+        self.search(name, query, top_k, |record, _| ids.contains(&record.id))
+    }
+
+    fn search(
+        &self,
+        name: &str,
+        query: &[f32],
+        top_k: usize,
+        keep: impl Fn(&VectorRecord, f32) -> bool,
+    ) -> Result<Vec<VectorMatch>, NikaError> {
+        let mut collections = self.collections.lock();
+        let collection = self.load(&mut collections, name)?;
+        if collection.records.is_empty() {
+            return Err(error(name, "no such collection".to_string()));
+        }
+        if query.len() != collection.dimensions {
+            return Err(error(
+                name,
+                format!(
+                    "query has {} dimensions, the collection {} ({})",
+                    query.len(),
+                    collection.dimensions,
+                    collection.model
+                ),
+            ));
+        }
+
+        let mut matches: Vec<VectorMatch> = collection
+            .records
+            .iter()
+            .map(|record| (record, cosine_similarity(query, &record.vector)))
+            .filter(|(record, score)| keep(record, *score))
+            .map(|(record, score)| VectorMatch {
+                id: record.id.clone(),
+                text: record.text.clone(),
+                score,
+                metadata: record.metadata.clone(),
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(top_k);
+        Ok(matches)
+    }
+
+    /// Collection from the cache, reading its file on first use
+    fn load<'a>(
+        &self,
+        collections: &'a mut HashMap<String, Collection>,
+        name: &str,
+    ) -> Result<&'a mut Collection, NikaError> {
+        if !is_valid_collection(name) {
+            return Err(error(
+                name,
+                "names may only use letters, digits, '-' and '_'".to_string(),
+            ));
+        }
+        if !collections.contains_key(name) {
+            let collection = match self.file(name).filter(|path| path.exists()) {
+                Some(path) => std::fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+                    .map_err(|e| error(name, format!("{}: {}", path.display(), e)))?,
+                None => Collection::default(),
+            };
+            collections.insert(name.to_string(), collection);
+        }
+        Ok(collections.get_mut(name).expect("collection loaded above"))
+    }
+
+    fn save(&self, name: &str, collection: &Collection) -> Result<(), NikaError> {
+        let Some(path) = self.file(name) else {
+            return Ok(());
+        };
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // Write then rename, so a crash never leaves half a collection
+            let temp = path.with_extension("json.tmp");
+            std::fs::write(&temp, serde_json::to_vec(collection)?)?;
+            std::fs::rename(&temp, &path)
+        };
+        write().map_err(|e| error(name, format!("{}: {}", path.display(), e)))
+    }
+
+    fn file(&self, name: &str) -> Option<PathBuf> {
+        self.dir
+            .as_deref()
+            .map(|dir: &Path| dir.join(format!("{}.json", name)))
+    }
+}
+
+/// Cosine similarity (0.0 when either vector is all zeros)
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+fn error(collection: &str, reason: String) -> NikaError {
+    NikaError::VectorStoreError {
+        collection: collection.to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(id: &str, vector: Vec<f32>) -> VectorRecord {
+        VectorRecord {
+            id: id.to_string(),
+            text: format!("text of {}", id),
+            vector,
+            metadata: Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_search_ranks_by_cosine_similarity() {
+        let store = VectorStore::in_memory();
+        store
+            .upsert(
+                "docs",
+                "openai",
+                "text-embedding-3-small",
+                vec![
+                    record("east", vec![1.0, 0.0]),
+                    record("north", vec![0.0, 1.0]),
+                    record("north-east", vec![1.0, 1.0]),
+                ],
+            )
+            .unwrap();
+
+        let hits = store
+            .similarity_search("docs", &[1.0, 0.1], 2, None)
+            .unwrap();
+        let ids: Vec<&str> = hits.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["east", "north-east"]);
+
+        let hits = store
+            .similarity_search("docs", &[0.0, 1.0], 5, Some(0.5))
+            .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].score > 0.99);
+    }
+
+    #[test]
+    fn test_upsert_persists_and_checks_dimensions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = VectorStore::open(dir.path());
+        store
+            .upsert(
+                "notes",
+                "ollama",
+                "nomic-embed-text",
+                vec![record("a", vec![0.5, 0.5])],
+            )
+            .unwrap();
+        let mut replaced = record("a", vec![1.0, 0.0]);
+        replaced.metadata = json!({"source": "a.md"});
+        store
+            .upsert("notes", "ollama", "nomic-embed-text", vec![replaced])
+            .unwrap();
+
+        let reopened = VectorStore::open(dir.path());
+        assert_eq!(
+            reopened.describe("notes").unwrap(),
+            Some(("ollama".to_string(), "nomic-embed-text".to_string(), 1))
+        );
+        let hits = reopened
+            .similarity_search("notes", &[1.0, 0.0], 1, None)
+            .unwrap();
+        assert_eq!(hits[0].metadata, json!({"source": "a.md"}));
+
+        let err = reopened
+            .upsert("notes", "ollama", "other", vec![record("b", vec![1.0])])
+            .unwrap_err();
+        assert_eq!(err.code(), "NIKA-192");
+        assert!(reopened
+            .similarity_search("missing", &[1.0], 1, None)
+            .is_err());
+        assert!(reopened.describe("../up").is_err());
+    }
+}
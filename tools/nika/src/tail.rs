@@ -0,0 +1,241 @@
+//! Remote tail of daemon runs (v0.7, `nika watch <run-id> --server <url>`)
+//!
+//! Follows the Server-Sent Events a daemon started with `--http` serves for
+//! one run (see the daemon module), so the Monitor can show a run executing
+//! on another machine.
+//!
+//! When the connection drops, the tail reconnects with the last event id it
+//! received; the daemon sends only what was missed, from memory while the
+//! run is live and from its trace once it has finished. After
+//! [`MAX_RECONNECTS`] failed attempts in a row the tail gives up.
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::error::{NikaError, Result};
+use crate::event::Event;
+
+/// Failed reconnects in a row before giving up
+pub const MAX_RECONNECTS: u32 = 8;
+/// First wait before reconnecting, doubled per failed attempt
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(250);
+/// Longest wait between two reconnects
+const MAX_RECONNECT_WAIT: Duration = Duration::from_secs(5);
+
+fn tail_error(url: &str, reason: impl std::fmt::Display) -> NikaError {
+    NikaError::DaemonError {
+        reason: format!("{}: {}", url, reason),
+    }
+}
+
+/// What one connection ended with
+enum Disconnect {
+    /// The daemon sent `event: end`, or the receiver went away
+    Done,
+    /// Connection lost; worth reconnecting
+    Lost(String),
+}
+
+/// A run followed on a remote daemon
+#[derive(Debug)]
+pub struct RunTail {
+    client: reqwest::Client,
+    /// `<server>/runs/<run-id>/events`
+    url: String,
+    response: reqwest::Response,
+}
+
+impl RunTail {
+    /// Connect to the events of run `run_id` on the daemon at `server`
+    ///
+    /// Fails right away when the daemon is unreachable or does not know the
+    /// run; only later connection losses are retried.
+    pub async fn connect(server: &str, run_id: &str) -> Result<Self> {
+        let url = format!("{}/runs/{}/events", server.trim_end_matches('/'), run_id);
+        let client = reqwest::Client::new();
+        let response = Self::request(&client, &url, None).await?;
+        Ok(Self {
+            client,
+            url,
+            response,
+        })
+    }
+
+    /// URL of the run's event stream
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn request(
+        client: &reqwest::Client,
+        url: &str,
+        last_event_id: Option<u64>,
+    ) -> Result<reqwest::Response> {
+        let mut request = client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        if let Some(id) = last_event_id {
+            request = request.header("Last-Event-ID", id.to_string());
+        }
+        let response = request.send().await.map_err(|e| tail_error(url, e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|v| v.get("error").and_then(|e| e.as_str()).map(str::to_string));
+            return Err(tail_error(
+                url,
+                message.unwrap_or_else(|| format!("HTTP {}", status)),
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Send the run's events to `tx` until it ends, reconnecting on loss
+    ///
+    /// Returns when the run ends or `tx` is closed.
+    pub async fn forward(self, tx: mpsc::Sender<Event>) -> Result<()> {
+        let Self {
+            client,
+            url,
+            mut response,
+        } = self;
+        let mut last_event_id = None;
+        let mut failures = 0;
+        loop {
+            let reason = match follow(response, &tx, &mut last_event_id).await {
+                Disconnect::Done => return Ok(()),
+                Disconnect::Lost(reason) => reason,
+            };
+            response = loop {
+                failures += 1;
+                if failures > MAX_RECONNECTS {
+                    return Err(tail_error(
+                        &url,
+                        format!("connection lost ({}), gave up reconnecting", reason),
+                    ));
+                }
+                let wait = RECONNECT_BACKOFF.saturating_mul(2u32.saturating_pow(failures - 1));
+                tokio::time::sleep(wait.min(MAX_RECONNECT_WAIT)).await;
+                tracing::debug!(url = %url, attempt = failures, "Reconnecting to run events");
+                match Self::request(&client, &url, last_event_id).await {
+                    Ok(response) => break response,
+                    Err(e) => tracing::debug!(error = %e, "Reconnect failed"),
+                }
+            };
+            failures = 0;
+        }
+    }
+}
+
+/// Forward the events of one connection, tracking the last event id
+async fn follow(
+    mut response: reqwest::Response,
+    tx: &mpsc::Sender<Event>,
+    last_event_id: &mut Option<u64>,
+) -> Disconnect {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut message = SseMessage::default();
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return Disconnect::Lost("closed by the daemon".to_string()),
+            Err(e) => return Disconnect::Lost(e.to_string()),
+        };
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(complete) = message.push_line(line.trim_end_matches(['\r', '\n'])) else {
+                continue;
+            };
+            if complete.event.as_deref() == Some("end") {
+                return Disconnect::Done;
+            }
+            let Ok(event) = serde_json::from_str::<Event>(&complete.data) else {
+                continue;
+            };
+            // Never hand out an event twice across reconnects
+            if last_event_id.is_some_and(|last| event.id <= last) {
+                continue;
+            }
+            *last_event_id = Some(event.id);
+            if tx.send(event).await.is_err() {
+                return Disconnect::Done;
+            }
+        }
+    }
+}
+
+/// A Server-Sent Events message being read
+#[derive(Debug, Default, PartialEq)]
+struct SseMessage {
+    event: Option<String>,
+    data: String,
+}
+
+impl SseMessage {
+    /// Add one line; returns the message when `line` completes it
+    fn push_line(&mut self, line: &str) -> Option<SseMessage> {
+        if line.is_empty() {
+            if self.event.is_none() && self.data.is_empty() {
+                return None;
+            }
+            return Some(std::mem::take(self));
+        }
+        // Comments (`: keep-alive`) and fields we do not use
+        let (field, value) = line.split_once(':')?;
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => {
+                if !self.data.is_empty() {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sse_messages() {
+        let mut message = SseMessage::default();
+        let mut complete = Vec::new();
+        for line in [
+            ": keep-alive",
+            "",
+            "id: 3",
+            "data: {\"a\":",
+            "data: 1}",
+            "",
+            "event: end",
+            "data: {}",
+            "",
+        ] {
+            complete.extend(message.push_line(line));
+        }
+        assert_eq!(
+            complete,
+            [
+                SseMessage {
+                    event: None,
+                    data: "{\"a\":\n1}".to_string()
+                },
+                SseMessage {
+                    event: Some("end".to_string()),
+                    data: "{}".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,674 @@
+//! ApplyPatch Tool - Apply a unified diff to one or more files
+//!
+//! Accepts `diff -u` / `git diff` output:
+//! - `--- a/path` / `+++ b/path` headers, relative to the working directory
+//!   (or absolute), `a/` and `b/` prefixes stripped
+//! - `--- /dev/null` creates a file, `+++ /dev/null` deletes one
+//! - Hunks match their context exactly, searched around the stated line
+//!   so patches from a slightly different version still apply
+//!
+//! Every hunk of every file is checked before anything is written; if a
+//! later write fails, the files already written are restored. Modified and
+//! deleted files follow the read-before-edit rule and need the `Edit`
+//! permission; created files need `Write`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::fs;
+
+use super::context::{ToolContext, ToolEvent, ToolOperation};
+use super::edit::{generate_diff, write_atomic};
+use super::{FileTool, ToolErrorCode, ToolOutput};
+use crate::error::NikaError;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PARAMETERS & RESULT
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Parameters for the ApplyPatch tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyPatchParams {
+    /// Unified diff, one or more files
+    pub patch: String,
+}
+
+/// What a patch did to one file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchAction {
+    Modified,
+    Created,
+    Deleted,
+}
+
+/// One file changed by a patch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchedFile {
+    /// Path as written in the patch
+    pub path: String,
+    pub action: PatchAction,
+    /// Hunks applied
+    pub hunks: usize,
+    /// Lines added
+    pub added: usize,
+    /// Lines removed
+    pub removed: usize,
+}
+
+/// Result from applying a patch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyPatchResult {
+    pub files: Vec<PatchedFile>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PATCH PARSING
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// One `@@` hunk
+#[derive(Debug, Default)]
+struct Hunk {
+    /// First line of the old side, from 1 (0 for an empty file)
+    old_start: usize,
+    /// Context and removed lines
+    old: Vec<String>,
+    /// Context and added lines
+    new: Vec<String>,
+    /// `+` lines
+    added: usize,
+    /// `-` lines
+    removed: usize,
+    /// The old side ends without a newline at end of file
+    old_no_newline: bool,
+    /// The new side ends without a newline at end of file
+    new_no_newline: bool,
+}
+
+/// The hunks of one file
+#[derive(Debug)]
+struct FilePatch {
+    /// None for `/dev/null` (file created)
+    old_path: Option<String>,
+    /// None for `/dev/null` (file deleted)
+    new_path: Option<String>,
+    hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Path of a `---`/`+++` header line (None for `/dev/null`)
+fn header_path(rest: &str) -> Option<String> {
+    // `diff -u` appends a tab and a timestamp
+    let path = rest.split('\t').next().unwrap_or_default().trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Files touched by a unified diff, as written in its headers
+///
+/// Used by `.nika/policy.yaml` path rules on the `patch` argument.
+pub fn patch_paths(patch: &str) -> Vec<String> {
+    let mut paths: Vec<String> = patch
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("--- ")
+                .or_else(|| line.strip_prefix("+++ "))
+        })
+        .filter_map(header_path)
+        .collect();
+    paths.dedup();
+    paths
+}
+
+/// `(start, count)` of one side of a hunk header (`12,3` or `12`)
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+fn parse_patch(patch: &str) -> Result<Vec<FilePatch>, String> {
+    let mut files = Vec::new();
+    let mut lines = patch.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some(old) = line.strip_prefix("--- ") else {
+            // `diff --git`, `index`, mode lines and prose are skipped
+            continue;
+        };
+        let new = lines
+            .next()
+            .and_then(|line| line.strip_prefix("+++ "))
+            .ok_or_else(|| format!("'--- {}' is not followed by a '+++' line", old))?;
+        let mut file = FilePatch {
+            old_path: header_path(old),
+            new_path: header_path(new),
+            hunks: Vec::new(),
+        };
+
+        while let Some(header) = lines.peek().and_then(|line| line.strip_prefix("@@ ")) {
+            lines.next();
+            let ranges = header.split(" @@").next().unwrap_or_default();
+            let (old_range, new_range) = ranges
+                .split_once(' ')
+                .and_then(|(old, new)| Some((old.strip_prefix('-')?, new.strip_prefix('+')?)))
+                .and_then(|(old, new)| Some((parse_range(old)?, parse_range(new)?)))
+                .ok_or_else(|| format!("bad hunk header '@@ {}'", header))?;
+            let (mut old_left, mut new_left) = (old_range.1, new_range.1);
+            let mut hunk = Hunk {
+                old_start: old_range.0,
+                ..Default::default()
+            };
+            let mut last = ' ';
+            while old_left > 0 || new_left > 0 || lines.peek().is_some_and(|l| l.starts_with('\\'))
+            {
+                let Some(line) = lines.next() else {
+                    return Err(format!("hunk '@@ {}' ends early", header));
+                };
+                // Some tools strip the space of empty context lines
+                let (kind, text) = match line.chars().next() {
+                    None => (' ', ""),
+                    Some(kind) => (kind, &line[1..]),
+                };
+                match kind {
+                    ' ' if old_left > 0 && new_left > 0 => {
+                        hunk.old.push(text.to_string());
+                        hunk.new.push(text.to_string());
+                        old_left -= 1;
+                        new_left -= 1;
+                    }
+                    '-' if old_left > 0 => {
+                        hunk.old.push(text.to_string());
+                        hunk.removed += 1;
+                        old_left -= 1;
+                    }
+                    '+' if new_left > 0 => {
+                        hunk.new.push(text.to_string());
+                        hunk.added += 1;
+                        new_left -= 1;
+                    }
+                    // `\ No newline at end of file`
+                    '\\' => {
+                        hunk.old_no_newline |= matches!(last, ' ' | '-');
+                        hunk.new_no_newline |= matches!(last, ' ' | '+');
+                        continue;
+                    }
+                    _ => {
+                        return Err(format!(
+                            "hunk '@@ {}' has more lines than its header says: '{}'",
+                            header, line
+                        ))
+                    }
+                }
+                last = kind;
+            }
+            file.hunks.push(hunk);
+        }
+
+        if file.hunks.is_empty() {
+            return Err(format!("no hunks for '{}'", file.path()));
+        }
+        files.push(file);
+    }
+    if files.is_empty() {
+        return Err("no '--- '/'+++ ' file headers found".to_string());
+    }
+    Ok(files)
+}
+
+/// Apply `hunks` to `content`; Err names the first hunk that doesn't match
+fn apply_hunks(content: &str, hunks: &[Hunk]) -> Result<String, String> {
+    let mut had_newline = content.is_empty() || content.ends_with('\n');
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    // Where the next hunk may start, and how far earlier hunks moved lines
+    let mut floor = 0;
+    let mut delta: isize = 0;
+    for (i, hunk) in hunks.iter().enumerate() {
+        let expected = (hunk.old_start.saturating_sub(1) as isize + delta).max(0) as usize;
+        let fits = |at: usize| {
+            at >= floor
+                && at + hunk.old.len() <= lines.len()
+                && lines[at..at + hunk.old.len()] == hunk.old[..]
+        };
+        // Nearest match to the stated line, looking both ways
+        let at = (0..=lines.len().max(expected))
+            .flat_map(|distance| [expected.checked_add(distance), expected.checked_sub(distance)])
+            .flatten()
+            .find(|&at| fits(at))
+            .ok_or_else(|| {
+                format!(
+                    "hunk {} (line {}) does not match the file: its context or removed lines differ",
+                    i + 1,
+                    hunk.old_start
+                )
+            })?;
+        lines.splice(at..at + hunk.old.len(), hunk.new.iter().cloned());
+        floor = at + hunk.new.len();
+        delta += hunk.new.len() as isize - hunk.old.len() as isize;
+        if hunk.old_no_newline || hunk.new_no_newline {
+            had_newline = !hunk.new_no_newline;
+        }
+    }
+    let mut out = lines.join("\n");
+    if had_newline && !lines.is_empty() {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// APPLY PATCH TOOL
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// A checked change, ready to write
+struct Planned {
+    file: PatchedFile,
+    path: PathBuf,
+    /// None for a created file
+    before: Option<String>,
+    /// None for a deleted file
+    after: Option<String>,
+}
+
+/// ApplyPatch tool for multi-file changes in one call
+///
+/// # Features
+///
+/// - Unified diffs with several files and hunks
+/// - File creation and deletion (`/dev/null`)
+/// - Context matched around the stated line
+/// - Nothing written unless every hunk applies
+pub struct ApplyPatchTool {
+    ctx: Arc<ToolContext>,
+}
+
+impl ApplyPatchTool {
+    /// Create a new ApplyPatch tool
+    pub fn new(ctx: Arc<ToolContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// Apply the patch
+    pub async fn execute(&self, params: ApplyPatchParams) -> Result<ApplyPatchResult, NikaError> {
+        let patches = parse_patch(&params.patch).map_err(failed)?;
+
+        let mut planned = Vec::with_capacity(patches.len());
+        for patch in &patches {
+            planned.push(self.plan(patch).await?);
+        }
+
+        let mut written: Vec<&Planned> = Vec::new();
+        for change in &planned {
+            if let Err(e) = self.commit(change).await {
+                for done in written.into_iter().rev() {
+                    let _ = self.restore(done).await;
+                }
+                return Err(e);
+            }
+            written.push(change);
+        }
+
+        for change in &planned {
+            let path = change.file.path.clone();
+            let event = match (&change.before, &change.after) {
+                (Some(before), Some(after)) => ToolEvent::FileEdited {
+                    path: path.clone(),
+                    replacements: change.file.hunks,
+                    diff_preview: generate_diff(before, after, &path),
+                },
+                (None, Some(after)) => ToolEvent::FileWritten {
+                    path,
+                    bytes: after.len(),
+                },
+                _ => ToolEvent::FileDeleted { path },
+            };
+            self.ctx.emit(event).await;
+        }
+
+        Ok(ApplyPatchResult {
+            files: planned.into_iter().map(|change| change.file).collect(),
+        })
+    }
+
+    /// Check permissions and hunks of one file, computing its new content
+    async fn plan(&self, patch: &FilePatch) -> Result<Planned, NikaError> {
+        let name = patch.path();
+        if let (Some(old), Some(new)) = (&patch.old_path, &patch.new_path) {
+            if old != new {
+                return Err(failed(format!(
+                    "renames are not supported ({} -> {}): delete and create instead",
+                    old, new
+                )));
+            }
+        }
+        if PathBuf::from(name)
+            .components()
+            .any(|c| c == std::path::Component::ParentDir)
+        {
+            return Err(NikaError::ToolError {
+                code: ToolErrorCode::PathOutOfBounds.code(),
+                message: format!("Patch path '{}' must not contain '..'", name),
+            });
+        }
+        let absolute = self.ctx.working_dir().join(name);
+        let path = self.ctx.validate_path(&absolute.to_string_lossy())?;
+
+        let mut file = PatchedFile {
+            path: name.to_string(),
+            action: PatchAction::Modified,
+            hunks: patch.hunks.len(),
+            added: patch.hunks.iter().map(|h| h.added).sum(),
+            removed: patch.hunks.iter().map(|h| h.removed).sum(),
+        };
+
+        let before = if patch.old_path.is_none() {
+            self.ctx.check_permission(ToolOperation::Write)?;
+            if path.exists() {
+                return Err(NikaError::ToolError {
+                    code: ToolErrorCode::FileAlreadyExists.code(),
+                    message: format!(
+                        "File already exists: {}. Patch it with a '--- a/{}' header instead.",
+                        name, name
+                    ),
+                });
+            }
+            file.action = PatchAction::Created;
+            None
+        } else {
+            self.ctx.check_permission(ToolOperation::Edit)?;
+            self.ctx.validate_read_before_edit(&path)?;
+            if !path.exists() {
+                return Err(NikaError::ToolError {
+                    code: ToolErrorCode::FileNotFound.code(),
+                    message: format!("File not found: {}", name),
+                });
+            }
+            Some(
+                fs::read_to_string(&path)
+                    .await
+                    .map_err(|e| failed(format!("cannot read {}: {}", name, e)))?,
+            )
+        };
+
+        let after = apply_hunks(before.as_deref().unwrap_or_default(), &patch.hunks)
+            .map_err(|reason| failed(format!("{}: {}", name, reason)))?;
+        let after = if patch.new_path.is_none() {
+            if !after.is_empty() {
+                return Err(failed(format!(
+                    "{}: the deletion does not remove every line of the file",
+                    name
+                )));
+            }
+            file.action = PatchAction::Deleted;
+            None
+        } else {
+            Some(after)
+        };
+
+        Ok(Planned {
+            file,
+            path,
+            before,
+            after,
+        })
+    }
+
+    async fn commit(&self, change: &Planned) -> Result<(), NikaError> {
+        match &change.after {
+            Some(after) => {
+                if let Some(parent) = change.path.parent() {
+                    fs::create_dir_all(parent).await.map_err(|e| {
+                        failed(format!("cannot create {}: {}", parent.display(), e))
+                    })?;
+                }
+                write_atomic(&change.path, after, ToolErrorCode::PatchFailed).await
+            }
+            None => fs::remove_file(&change.path)
+                .await
+                .map_err(|e| failed(format!("cannot delete {}: {}", change.file.path, e))),
+        }
+    }
+
+    /// Undo a committed change after a later one failed
+    async fn restore(&self, change: &Planned) -> Result<(), NikaError> {
+        match &change.before {
+            Some(before) => write_atomic(&change.path, before, ToolErrorCode::PatchFailed).await,
+            None => fs::remove_file(&change.path)
+                .await
+                .map_err(|e| failed(e.to_string())),
+        }
+    }
+}
+
+/// `ToolError` with NIKA-214
+fn failed(message: impl std::fmt::Display) -> NikaError {
+    NikaError::ToolError {
+        code: ToolErrorCode::PatchFailed.code(),
+        message: format!("Patch does not apply: {}. No files were changed.", message),
+    }
+}
+
+#[async_trait]
+impl FileTool for ApplyPatchTool {
+    fn name(&self) -> &'static str {
+        "apply_patch"
+    }
+
+    fn description(&self) -> &'static str {
+        "Apply a unified diff (diff -u / git diff format) to one or more files. Paths are \
+         relative to the working directory; use /dev/null as the old path to create a file \
+         and as the new path to delete one. IMPORTANT: You must read each file you modify \
+         first using the Read tool. Context lines must match the file exactly; if any hunk \
+         fails, no file is changed."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff with '--- a/path' and '+++ b/path' headers and '@@' hunks"
+                }
+            },
+            "required": ["patch"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: ApplyPatchParams =
+            serde_json::from_value(params).map_err(|e| NikaError::ToolError {
+                code: ToolErrorCode::PatchFailed.code(),
+                message: format!("Invalid parameters: {}", e),
+            })?;
+
+        let result = self.execute(params).await?;
+
+        let mut content = format!(
+            "Applied patch to {} file{}:",
+            result.files.len(),
+            if result.files.len() == 1 { "" } else { "s" }
+        );
+        for file in &result.files {
+            let mark = match file.action {
+                PatchAction::Modified => 'M',
+                PatchAction::Created => 'A',
+                PatchAction::Deleted => 'D',
+            };
+            content.push_str(&format!(
+                "\n{} {} (+{} -{})",
+                mark, file.path, file.added, file.removed
+            ));
+        }
+
+        Ok(ToolOutput::success_with_data(
+            content,
+            serde_json::to_value(&result).unwrap_or_default(),
+        ))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::PermissionMode;
+    use tempfile::TempDir;
+
+    const MAIN: &str =
+        "fn main() {\n    let x = 1;\n    println!(\"{}\", x);\n}\n\nfn helper() {}\n";
+
+    async fn setup(mode: PermissionMode) -> (TempDir, Arc<ToolContext>, ApplyPatchTool) {
+        let temp_dir = TempDir::new().unwrap();
+        let ctx = Arc::new(ToolContext::new(temp_dir.path().to_path_buf(), mode));
+        fs::create_dir_all(temp_dir.path().join("src"))
+            .await
+            .unwrap();
+        let main = temp_dir.path().join("src/main.rs");
+        fs::write(&main, MAIN).await.unwrap();
+        ctx.mark_as_read(&main);
+        let tool = ApplyPatchTool::new(Arc::clone(&ctx));
+        (temp_dir, ctx, tool)
+    }
+
+    #[tokio::test]
+    async fn test_applies_multi_file_patch() {
+        let (dir, _ctx, tool) = setup(PermissionMode::YoloMode).await;
+        // Line numbers are off by two: the context still places the hunks
+        let patch = r#"diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -3,3 +3,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 42;
+     println!("{}", x);
+@@ -8,1 +8,2 @@
+ fn helper() {}
++fn other() {}
+--- /dev/null
++++ b/src/lib.rs
+@@ -0,0 +1,2 @@
++pub mod util;
++pub use util::*;
+"#;
+
+        let output = tool.call(json!({ "patch": patch })).await.unwrap();
+        assert_eq!(
+            output.content,
+            "Applied patch to 2 files:\nM src/main.rs (+2 -1)\nA src/lib.rs (+2 -0)"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("src/main.rs"))
+                .await
+                .unwrap(),
+            MAIN.replace("x = 1", "x = 42") + "fn other() {}\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("src/lib.rs"))
+                .await
+                .unwrap(),
+            "pub mod util;\npub use util::*;\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bad_hunk_changes_nothing() {
+        let (dir, _ctx, tool) = setup(PermissionMode::YoloMode).await;
+        let patch = "--- /dev/null\n+++ b/notes.md\n@@ -0,0 +1 @@\n+hello\n\
+--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,2 +1,2 @@\n fn main() {\n-    let y = 1;\n+    let y = 2;\n";
+
+        let err = tool.call(json!({ "patch": patch })).await.unwrap_err();
+        assert!(err.to_string().contains("NIKA-214"), "{}", err);
+        assert!(err.to_string().contains("src/main.rs: hunk 1"), "{}", err);
+        assert!(!dir.path().join("notes.md").exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("src/main.rs"))
+                .await
+                .unwrap(),
+            MAIN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_rule_permissions_and_deletion() {
+        let (dir, ctx, tool) = setup(PermissionMode::YoloMode).await;
+        fs::write(dir.path().join("old.txt"), "a\nb\n")
+            .await
+            .unwrap();
+        let delete = "--- a/old.txt\n+++ /dev/null\n@@ -1,2 +0,0 @@\n-a\n-b\n";
+
+        let err = tool
+            .execute(ApplyPatchParams {
+                patch: delete.to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Must read file"), "{}", err);
+
+        ctx.mark_as_read(&dir.path().join("old.txt"));
+        let result = tool
+            .execute(ApplyPatchParams {
+                patch: delete.to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.files[0].action, PatchAction::Deleted);
+        assert!(!dir.path().join("old.txt").exists());
+
+        // AcceptEdits allows modifying, not creating
+        let (_dir, _ctx, tool) = setup(PermissionMode::AcceptEdits).await;
+        let create = "--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+x\n";
+        let err = tool.call(json!({ "patch": create })).await.unwrap_err();
+        assert!(err.to_string().contains("NIKA-205"), "{}", err);
+
+        let escape = "--- a/../x\n+++ b/../x\n@@ -1 +1 @@\n-a\n+b\n";
+        let err = tool.call(json!({ "patch": escape })).await.unwrap_err();
+        assert!(err.to_string().contains("NIKA-204"), "{}", err);
+    }
+
+    #[test]
+    fn test_no_newline_markers_and_paths() {
+        let hunks = parse_patch(
+            "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n\\ No newline at end of file\n",
+        )
+        .unwrap()
+        .remove(0)
+        .hunks;
+        assert_eq!(apply_hunks("old", &hunks).unwrap(), "new");
+
+        let hunks = parse_patch(
+            "--- a/f\n+++ b/f\n@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n",
+        )
+        .unwrap()
+        .remove(0)
+        .hunks;
+        assert_eq!(apply_hunks("old", &hunks).unwrap(), "new\n");
+
+        assert_eq!(
+            patch_paths("--- a/src/x.rs\t2026-01-01\n+++ b/src/x.rs\n--- /dev/null\n+++ b/.env\n"),
+            vec!["src/x.rs", ".env"]
+        );
+        assert!(parse_patch("just prose").is_err());
+    }
+}
@@ -47,6 +47,17 @@ pub enum PermissionMode {
 }
 
 impl PermissionMode {
+    /// Parse a `[tools] permission` value (deny, plan, accept-edits, yolo)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "deny" => Some(PermissionMode::Deny),
+            "plan" => Some(PermissionMode::Plan),
+            "accept-edits" | "acceptedits" => Some(PermissionMode::AcceptEdits),
+            "accept-all" | "acceptall" | "yolo" | "yolomode" => Some(PermissionMode::YoloMode),
+            _ => None,
+        }
+    }
+
     /// Check if an operation is allowed
     pub fn allows(&self, operation: ToolOperation) -> bool {
         match self {
@@ -105,6 +116,9 @@ pub enum ToolEvent {
         diff_preview: String,
     },
 
+    /// File was deleted (by a patch)
+    FileDeleted { path: String },
+
     /// Glob search completed
     GlobSearch {
         pattern: String,
@@ -6,6 +6,7 @@
 //! - Atomic updates (temp file + rename)
 //! - Diff preview
 
+use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -135,36 +136,7 @@ impl EditTool {
         // Generate diff preview
         let diff_preview = generate_diff(&content, &new_content, &params.file_path);
 
-        // Atomic write: temp file + rename
-        let temp_path = path.with_extension("tmp.nika.edit");
-
-        let mut file = fs::File::create(&temp_path)
-            .await
-            .map_err(|e| NikaError::ToolError {
-                code: ToolErrorCode::EditFailed.code(),
-                message: format!("Failed to create temp file: {}", e),
-            })?;
-
-        file.write_all(new_content.as_bytes())
-            .await
-            .map_err(|e| NikaError::ToolError {
-                code: ToolErrorCode::EditFailed.code(),
-                message: format!("Failed to write content: {}", e),
-            })?;
-
-        file.flush().await.map_err(|e| NikaError::ToolError {
-            code: ToolErrorCode::EditFailed.code(),
-            message: format!("Failed to flush file: {}", e),
-        })?;
-
-        // Atomic rename
-        fs::rename(&temp_path, &path).await.map_err(|e| {
-            let _ = std::fs::remove_file(&temp_path);
-            NikaError::ToolError {
-                code: ToolErrorCode::EditFailed.code(),
-                message: format!("Failed to finalize edit: {}", e),
-            }
-        })?;
+        write_atomic(&path, &new_content, ToolErrorCode::EditFailed).await?;
 
         // Emit event
         self.ctx
@@ -183,8 +155,45 @@ impl EditTool {
     }
 }
 
+/// Atomic write: temp file + rename (also used by `multi_edit` and `apply_patch`)
+pub(super) async fn write_atomic(
+    path: &Path,
+    content: &str,
+    code: ToolErrorCode,
+) -> Result<(), NikaError> {
+    let temp_path = path.with_extension("tmp.nika.edit");
+
+    let mut file = fs::File::create(&temp_path)
+        .await
+        .map_err(|e| NikaError::ToolError {
+            code: code.code(),
+            message: format!("Failed to create temp file: {}", e),
+        })?;
+
+    file.write_all(content.as_bytes())
+        .await
+        .map_err(|e| NikaError::ToolError {
+            code: code.code(),
+            message: format!("Failed to write content: {}", e),
+        })?;
+
+    file.flush().await.map_err(|e| NikaError::ToolError {
+        code: code.code(),
+        message: format!("Failed to flush file: {}", e),
+    })?;
+
+    // Atomic rename
+    fs::rename(&temp_path, path).await.map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        NikaError::ToolError {
+            code: code.code(),
+            message: format!("Failed to finalize edit: {}", e),
+        }
+    })
+}
+
 /// Generate a simple unified diff preview
-fn generate_diff(old: &str, new: &str, file_path: &str) -> String {
+pub(super) fn generate_diff(old: &str, new: &str, file_path: &str) -> String {
     let old_lines: Vec<&str> = old.lines().collect();
     let new_lines: Vec<&str> = new.lines().collect();
 
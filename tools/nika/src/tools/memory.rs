@@ -0,0 +1,296 @@
+//! Agent memory tools (v0.7, `memory: true` agents)
+//!
+//! - [`RememberTool`] (`remember`) - store a text under a name
+//! - [`RecallTool`] (`recall`) - read a memory by name, or list the ones
+//!   matching a query
+//!
+//! Memories outlive the run: they live in the project's [`AgentMemory`]
+//! store, so the next run of the workflow recalls them.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{FileTool, ToolErrorCode, ToolOutput};
+use crate::error::NikaError;
+use crate::store::AgentMemory;
+
+/// Longest memory name
+const MAX_NAME_CHARS: usize = 100;
+
+/// Default and highest number of memories `recall` lists
+const DEFAULT_LIST: usize = 20;
+const MAX_LIST: usize = 100;
+
+/// Characters of each memory shown in a list
+const PREVIEW_CHARS: usize = 120;
+
+/// Parameters for `remember`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RememberParams {
+    /// Memory name, e.g. `flaky-tests`
+    pub name: String,
+    /// What to remember (replaces an earlier memory of that name)
+    pub content: String,
+}
+
+/// Parameters for `recall`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecallParams {
+    /// Memory to read
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Without `name`: list memories whose name or text contains this
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Memories to list (default 20)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+fn failed(message: impl std::fmt::Display) -> NikaError {
+    NikaError::ToolError {
+        code: ToolErrorCode::MemoryFailed.code(),
+        message: message.to_string(),
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, NikaError> {
+    serde_json::from_value(params).map_err(|e| failed(format!("Invalid parameters: {}", e)))
+}
+
+/// `remember`: store a memory for later runs
+pub struct RememberTool {
+    memory: Arc<AgentMemory>,
+    task_id: String,
+}
+
+impl RememberTool {
+    /// Memories written by `task_id` go to `memory`
+    pub fn new(memory: Arc<AgentMemory>, task_id: impl Into<String>) -> Self {
+        Self {
+            memory,
+            task_id: task_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl FileTool for RememberTool {
+    fn name(&self) -> &'static str {
+        "remember"
+    }
+
+    fn description(&self) -> &'static str {
+        "Save a memory for future runs of this workflow: a short name and the text to \
+         remember. Saving under an existing name replaces that memory. Keep memories \
+         concise and factual (findings, decisions, what to check next time)."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "maxLength": MAX_NAME_CHARS,
+                    "description": "Memory name, e.g. 'flaky-tests'"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "What to remember"
+                }
+            },
+            "required": ["name", "content"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: RememberParams = parse(params)?;
+        let name = params.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_CHARS || name.contains('\n') {
+            return Err(failed(format!(
+                "memory names are one line of 1 to {} characters",
+                MAX_NAME_CHARS
+            )));
+        }
+        let replaced = self
+            .memory
+            .remember(name, &params.content, Some(&self.task_id))
+            .map_err(failed)?;
+        let verb = if replaced { "Updated" } else { "Saved" };
+        Ok(ToolOutput::success_with_data(
+            format!("{} memory '{}'", verb, name),
+            json!({ "name": name, "replaced": replaced }),
+        ))
+    }
+}
+
+/// `recall`: read memories of earlier runs
+pub struct RecallTool {
+    memory: Arc<AgentMemory>,
+}
+
+impl RecallTool {
+    pub fn new(memory: Arc<AgentMemory>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl FileTool for RecallTool {
+    fn name(&self) -> &'static str {
+        "recall"
+    }
+
+    fn description(&self) -> &'static str {
+        "Read memories saved by earlier runs with remember. With a name, returns that \
+         memory; otherwise lists the latest memories, filtered by query if given. \
+         Call it first to build on what earlier runs learned."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Memory to read"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Without name: only memories whose name or text contains this"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_LIST,
+                    "description": "Memories to list (default: 20)"
+                }
+            }
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: RecallParams = parse(params)?;
+
+        if let Some(name) = params.name.as_deref().map(str::trim) {
+            return match self.memory.recall(name).map_err(failed)? {
+                Some(entry) => Ok(ToolOutput::success_with_data(
+                    format!(
+                        "{} (updated {})\n{}",
+                        name,
+                        entry.updated_at.format("%Y-%m-%d %H:%M UTC"),
+                        entry.content
+                    ),
+                    json!({ "name": name, "memory": entry }),
+                )),
+                None => {
+                    let known = self.memory.list().map_err(failed)?;
+                    let names: Vec<&str> = known.keys().map(String::as_str).collect();
+                    Ok(ToolOutput::success(if names.is_empty() {
+                        format!("No memory named '{}' (no memories yet)", name)
+                    } else {
+                        format!("No memory named '{}'. Memories: {}", name, names.join(", "))
+                    }))
+                }
+            };
+        }
+
+        let limit = params.limit.unwrap_or(DEFAULT_LIST).clamp(1, MAX_LIST);
+        let found = self
+            .memory
+            .search(params.query.as_deref().unwrap_or(""), limit)
+            .map_err(failed)?;
+        if found.is_empty() {
+            return Ok(ToolOutput::success(match &params.query {
+                Some(query) => format!("No memories match '{}'", query),
+                None => "No memories yet".to_string(),
+            }));
+        }
+        let lines: Vec<String> = found
+            .iter()
+            .map(|(name, entry)| {
+                let mut preview: String = entry
+                    .content
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                if let Some((cut, _)) = preview.char_indices().nth(PREVIEW_CHARS) {
+                    preview.truncate(cut);
+                    preview.push('…');
+                }
+                format!("- {}: {}", name, preview)
+            })
+            .collect();
+        let names: Vec<&str> = found.iter().map(|(name, _)| name.as_str()).collect();
+        Ok(ToolOutput::success_with_data(
+            format!(
+                "{} memories (recall with a name for the full text):\n{}",
+                found.len(),
+                lines.join("\n")
+            ),
+            json!({ "names": names }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn remember_then_recall() {
+        let memory = Arc::new(AgentMemory::in_memory("triage"));
+        let remember = RememberTool::new(Arc::clone(&memory), "nightly");
+        let recall = RecallTool::new(Arc::clone(&memory));
+
+        let out = recall.call(json!({})).await.unwrap();
+        assert_eq!(out.content, "No memories yet");
+
+        let out = remember
+            .call(json!({"name": "flaky-tests", "content": "test_upload times out"}))
+            .await
+            .unwrap();
+        assert_eq!(out.content, "Saved memory 'flaky-tests'");
+        let out = remember
+            .call(json!({"name": "flaky-tests", "content": "test_upload and test_sync"}))
+            .await
+            .unwrap();
+        assert_eq!(out.content, "Updated memory 'flaky-tests'");
+
+        let out = recall.call(json!({"name": "flaky-tests"})).await.unwrap();
+        assert!(
+            out.content.ends_with("\ntest_upload and test_sync"),
+            "{}",
+            out.content
+        );
+        assert_eq!(
+            memory
+                .recall("flaky-tests")
+                .unwrap()
+                .unwrap()
+                .task
+                .as_deref(),
+            Some("nightly")
+        );
+
+        let out = recall.call(json!({"query": "SYNC"})).await.unwrap();
+        assert!(out
+            .content
+            .contains("- flaky-tests: test_upload and test_sync"));
+        let out = recall.call(json!({"name": "owners"})).await.unwrap();
+        assert_eq!(
+            out.content,
+            "No memory named 'owners'. Memories: flaky-tests"
+        );
+
+        let err = remember
+            .call(json!({"name": " ", "content": "x"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("NIKA-216"), "{}", err);
+    }
+}
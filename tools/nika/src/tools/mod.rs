@@ -1,12 +1,24 @@
 //! File Tools Module - Claude Code-like filesystem operations
 //!
-//! Provides 5 tools for filesystem interaction:
+//! Provides 7 tools for filesystem interaction:
 //! - [`ReadTool`] - Read files with line numbers
 //! - [`WriteTool`] - Create new files
 //! - [`EditTool`] - Modify existing files (requires read-before-edit)
+//! - [`MultiEditTool`] - Several edits to one file, all or nothing
+//! - [`ApplyPatchTool`] - Apply a unified diff to one or more files
 //! - [`GlobTool`] - Find files by pattern
 //! - [`GrepTool`] - Search file contents with regex
 //!
+//! Plus [`WebSearchTool`] (v0.7), which searches the web through Brave,
+//! Tavily or SearxNG for `agent:` tasks with `web_search: true`, and
+//! [`ReadUrlTool`] (v0.7), which fetches a page as readable Markdown for
+//! `agent:` tasks with `read_url: true`, and the background process tools
+//! [`RunBackgroundTool`], [`TailLogsTool`] and [`KillTool`] (v0.7) for
+//! `agent:` tasks with `background: true`, and [`RememberTool`] and
+//! [`RecallTool`] (v0.7), which keep memories across runs for `agent:`
+//! tasks with `memory: true`. The web tools need the `http` feature, and
+//! the rig adapter the `providers` feature.
+//!
 //! # Permission Model
 //!
 //! Inspired by Gemini CLI's Yolo Mode and Claude Code's permission levels:
@@ -50,20 +62,43 @@
 //! }
 //! ```
 
+mod apply_patch;
 mod context;
 mod edit;
 mod glob;
 mod grep;
+mod memory;
+mod multi_edit;
+mod process;
 mod read;
+#[cfg(feature = "http")]
+mod read_url;
+#[cfg(feature = "providers")]
 mod rig_adapter;
+#[cfg(feature = "http")]
+mod web_search;
 mod write;
 
+pub use apply_patch::{
+    patch_paths, ApplyPatchParams, ApplyPatchResult, ApplyPatchTool, PatchAction, PatchedFile,
+};
 pub use context::{PermissionMode, ToolContext, ToolEvent, ToolOperation};
 pub use edit::{EditParams, EditResult, EditTool};
 pub use glob::{GlobParams, GlobResult, GlobTool};
 pub use grep::{GrepOutputMode, GrepParams, GrepResult, GrepTool};
+pub use memory::{RecallParams, RecallTool, RememberParams, RememberTool};
+pub use multi_edit::{EditOperation, MultiEditParams, MultiEditResult, MultiEditTool};
+pub use process::{
+    KillParams, KillTool, ProcessRegistry, ProcessState, Reaper, RunBackgroundParams,
+    RunBackgroundTool, TailLogsParams, TailLogsTool,
+};
 pub use read::{ReadParams, ReadResult, ReadTool};
+#[cfg(feature = "http")]
+pub use read_url::{ReadUrlParams, ReadUrlResult, ReadUrlTool, UrlCheck};
+#[cfg(feature = "providers")]
 pub use rig_adapter::{create_rig_file_tools, RigFileTool};
+#[cfg(feature = "http")]
+pub use web_search::{SearchHit, WebSearchParams, WebSearchResult, WebSearchTool};
 pub use write::{WriteParams, WriteResult, WriteTool};
 
 use crate::error::NikaError;
@@ -165,6 +200,16 @@ pub enum ToolErrorCode {
     FileAlreadyExists = 210,
     /// NIKA-211: Path must be absolute
     RelativePath = 211,
+    /// NIKA-212: Web search request failed
+    WebSearchFailed = 212,
+    /// NIKA-213: Reading a URL failed
+    ReadUrlFailed = 213,
+    /// NIKA-214: Patch does not apply
+    PatchFailed = 214,
+    /// NIKA-215: Background process failed to start or is unknown
+    ProcessFailed = 215,
+    /// NIKA-216: Memory could not be read or written
+    MemoryFailed = 216,
 }
 
 impl ToolErrorCode {
@@ -202,5 +247,8 @@ mod tests {
         assert_eq!(ToolErrorCode::ReadFailed.code(), "NIKA-200");
         assert_eq!(ToolErrorCode::MustReadFirst.code(), "NIKA-203");
         assert_eq!(ToolErrorCode::PathOutOfBounds.code(), "NIKA-204");
+        assert_eq!(ToolErrorCode::PatchFailed.code(), "NIKA-214");
+        assert_eq!(ToolErrorCode::ProcessFailed.code(), "NIKA-215");
+        assert_eq!(ToolErrorCode::MemoryFailed.code(), "NIKA-216");
     }
 }
@@ -0,0 +1,366 @@
+//! MultiEdit Tool - Several replacements in one file, all or nothing
+//!
+//! Like [`EditTool`](super::EditTool), but with a list of edits:
+//! - Edits apply in order, each to the result of the previous one
+//! - If any edit fails (not found, not unique), the file is left untouched
+//! - One read-before-edit check, one atomic write, one diff preview
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::fs;
+
+use super::context::{ToolContext, ToolEvent, ToolOperation};
+use super::edit::{generate_diff, write_atomic};
+use super::{FileTool, ToolErrorCode, ToolOutput};
+use crate::error::NikaError;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PARAMETERS & RESULT
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// One find/replace of a multi-edit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditOperation {
+    /// Text to find and replace
+    pub old_string: String,
+
+    /// Replacement text
+    pub new_string: String,
+
+    /// Replace all occurrences (default: false)
+    #[serde(default)]
+    pub replace_all: bool,
+}
+
+/// Parameters for the MultiEdit tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiEditParams {
+    /// Absolute path to the file to edit
+    pub file_path: String,
+
+    /// Edits, applied in order
+    pub edits: Vec<EditOperation>,
+}
+
+/// Result from a multi-edit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiEditResult {
+    /// Path of the edited file
+    pub path: String,
+
+    /// Number of edits applied
+    pub edits: usize,
+
+    /// Number of replacements made, across all edits
+    pub replacements: usize,
+
+    /// Diff preview (unified format)
+    pub diff_preview: String,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// MULTI-EDIT TOOL
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// MultiEdit tool for several changes to one file in a single call
+///
+/// # Features
+///
+/// - Read-before-edit validation (must read file first)
+/// - Same matching rules as `edit`, per edit
+/// - All edits or none (validated in memory, then one atomic write)
+pub struct MultiEditTool {
+    ctx: Arc<ToolContext>,
+}
+
+impl MultiEditTool {
+    /// Create a new MultiEdit tool
+    pub fn new(ctx: Arc<ToolContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// Execute the edits
+    pub async fn execute(&self, params: MultiEditParams) -> Result<MultiEditResult, NikaError> {
+        // Validate path
+        let path = self.ctx.validate_path(&params.file_path)?;
+
+        // Check permission
+        self.ctx.check_permission(ToolOperation::Edit)?;
+
+        // Validate read-before-edit
+        self.ctx.validate_read_before_edit(&path)?;
+
+        if params.edits.is_empty() {
+            return Err(NikaError::ToolError {
+                code: ToolErrorCode::EditFailed.code(),
+                message: "edits is empty: give at least one old_string/new_string pair".to_string(),
+            });
+        }
+
+        // Check file exists
+        if !path.exists() {
+            return Err(NikaError::ToolError {
+                code: ToolErrorCode::FileNotFound.code(),
+                message: format!("File not found: {}", params.file_path),
+            });
+        }
+
+        // Read current content
+        let content = fs::read_to_string(&path)
+            .await
+            .map_err(|e| NikaError::ToolError {
+                code: ToolErrorCode::EditFailed.code(),
+                message: format!("Failed to read file: {}", e),
+            })?;
+
+        // Apply every edit in memory first: a failure leaves the file as is
+        let count = params.edits.len();
+        let mut new_content = content.clone();
+        let mut replacements = 0;
+        for (i, edit) in params.edits.iter().enumerate() {
+            let failed = |code: ToolErrorCode, reason: String| NikaError::ToolError {
+                code: code.code(),
+                message: format!(
+                    "Edit {} of {}: {}. No edits were applied.",
+                    i + 1,
+                    count,
+                    reason
+                ),
+            };
+            if edit.old_string.is_empty() || edit.old_string == edit.new_string {
+                return Err(failed(
+                    ToolErrorCode::EditFailed,
+                    "old_string must be non-empty and differ from new_string".to_string(),
+                ));
+            }
+
+            let occurrences = new_content.matches(&edit.old_string).count();
+            if occurrences == 0 {
+                return Err(failed(
+                    ToolErrorCode::EditFailed,
+                    "old_string not found (after the previous edits)".to_string(),
+                ));
+            }
+            if occurrences > 1 && !edit.replace_all {
+                return Err(failed(
+                    ToolErrorCode::OldStringNotUnique,
+                    format!(
+                        "old_string appears {} times; use replace_all: true or a more specific string",
+                        occurrences
+                    ),
+                ));
+            }
+
+            new_content = if edit.replace_all {
+                new_content.replace(&edit.old_string, &edit.new_string)
+            } else {
+                new_content.replacen(&edit.old_string, &edit.new_string, 1)
+            };
+            replacements += if edit.replace_all { occurrences } else { 1 };
+        }
+
+        let diff_preview = generate_diff(&content, &new_content, &params.file_path);
+        write_atomic(&path, &new_content, ToolErrorCode::EditFailed).await?;
+
+        // Emit event
+        self.ctx
+            .emit(ToolEvent::FileEdited {
+                path: params.file_path.clone(),
+                replacements,
+                diff_preview: diff_preview.clone(),
+            })
+            .await;
+
+        Ok(MultiEditResult {
+            path: params.file_path,
+            edits: count,
+            replacements,
+            diff_preview,
+        })
+    }
+}
+
+#[async_trait]
+impl FileTool for MultiEditTool {
+    fn name(&self) -> &'static str {
+        "multi_edit"
+    }
+
+    fn description(&self) -> &'static str {
+        "Make several find/replace edits to one file in a single call. Edits apply in order, \
+         each to the result of the previous one; if any fails, none is applied. IMPORTANT: You \
+         must read the file first using the Read tool. Each old_string must be unique unless \
+         replace_all is true."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file_path": {
+                    "type": "string",
+                    "description": "Absolute path to the file to edit"
+                },
+                "edits": {
+                    "type": "array",
+                    "minItems": 1,
+                    "description": "Edits to apply in order",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "old_string": {
+                                "type": "string",
+                                "description": "Exact text to find (unique unless replace_all is true)"
+                            },
+                            "new_string": {
+                                "type": "string",
+                                "description": "Replacement text"
+                            },
+                            "replace_all": {
+                                "type": "boolean",
+                                "description": "Replace all occurrences (default: false)",
+                                "default": false
+                            }
+                        },
+                        "required": ["old_string", "new_string"]
+                    }
+                }
+            },
+            "required": ["file_path", "edits"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: MultiEditParams =
+            serde_json::from_value(params).map_err(|e| NikaError::ToolError {
+                code: ToolErrorCode::EditFailed.code(),
+                message: format!("Invalid parameters: {}", e),
+            })?;
+
+        let result = self.execute(params).await?;
+
+        Ok(ToolOutput::success_with_data(
+            format!(
+                "Edited file: {} ({} edit{}, {} replacement{})\n\n{}",
+                result.path,
+                result.edits,
+                if result.edits == 1 { "" } else { "s" },
+                result.replacements,
+                if result.replacements == 1 { "" } else { "s" },
+                result.diff_preview
+            ),
+            serde_json::to_value(&result).unwrap_or_default(),
+        ))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::PermissionMode;
+    use tempfile::TempDir;
+
+    fn edit(old: &str, new: &str) -> EditOperation {
+        EditOperation {
+            old_string: old.to_string(),
+            new_string: new.to_string(),
+            replace_all: false,
+        }
+    }
+
+    async fn setup(content: &str, read: bool) -> (TempDir, MultiEditTool, String) {
+        let temp_dir = TempDir::new().unwrap();
+        let ctx = Arc::new(ToolContext::new(
+            temp_dir.path().to_path_buf(),
+            PermissionMode::YoloMode,
+        ));
+        let path = temp_dir.path().join("lib.rs");
+        fs::write(&path, content).await.unwrap();
+        if read {
+            ctx.mark_as_read(&path);
+        }
+        let file_path = path.to_string_lossy().to_string();
+        (temp_dir, MultiEditTool::new(ctx), file_path)
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_applies_in_order() {
+        let (_dir, tool, file_path) = setup("fn a() {}\nfn b() { a(); a(); }\n", true).await;
+
+        let output = tool
+            .call(json!({
+                "file_path": file_path,
+                "edits": [
+                    {"old_string": "fn a()", "new_string": "fn alpha()"},
+                    {"old_string": "a();", "new_string": "alpha();", "replace_all": true},
+                    {"old_string": "fn alpha() {}", "new_string": "fn alpha() { todo!() }"}
+                ]
+            }))
+            .await
+            .unwrap();
+        assert!(output.content.contains("(3 edits, 4 replacements)"));
+
+        let content = fs::read_to_string(&file_path).await.unwrap();
+        assert_eq!(
+            content,
+            "fn alpha() { todo!() }\nfn b() { alpha(); alpha(); }\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_is_all_or_nothing() {
+        let original = "let x = 1;\nlet y = 2;\nlet y = 3;\n";
+        let (_dir, tool, file_path) = setup(original, true).await;
+
+        let err = tool
+            .execute(MultiEditParams {
+                file_path: file_path.clone(),
+                edits: vec![edit("x = 1", "x = 10"), edit("let y", "let z")],
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Edit 2 of 2"), "{}", err);
+        assert!(err.to_string().contains("NIKA-209"), "{}", err);
+        assert_eq!(fs::read_to_string(&file_path).await.unwrap(), original);
+
+        let err = tool
+            .execute(MultiEditParams {
+                file_path: file_path.clone(),
+                edits: vec![edit("x = 1", "x = 10"), edit("x = 1;", "x = 100;")],
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"), "{}", err);
+        assert_eq!(fs::read_to_string(&file_path).await.unwrap(), original);
+    }
+
+    #[tokio::test]
+    async fn test_multi_edit_requires_read_and_edits() {
+        let (_dir, tool, file_path) = setup("content", false).await;
+        let err = tool
+            .execute(MultiEditParams {
+                file_path: file_path.clone(),
+                edits: vec![edit("content", "new")],
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Must read file"));
+
+        let (_dir, tool, file_path) = setup("content", true).await;
+        let err = tool
+            .execute(MultiEditParams {
+                file_path,
+                edits: Vec::new(),
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("edits is empty"));
+    }
+}
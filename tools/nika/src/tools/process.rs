@@ -0,0 +1,721 @@
+//! Background process tools (v0.7, `background: true` agents)
+//!
+//! - [`RunBackgroundTool`] (`run_background`) - start a dev server, watcher
+//!   or build with `sh -c` and get a handle (`bg-1`) back
+//! - [`TailLogsTool`] (`tail_logs`) - last lines of its output, optionally
+//!   waiting for a line such as "ready on"
+//! - [`KillTool`] (`kill`) - stop it
+//!
+//! Processes belong to one run's [`ProcessRegistry`]. Each is started in
+//! its own process group, so the children of `sh` (`npm` → `node`) go
+//! with it, and every process still running when the run ends is killed.
+//! Commands go through tool approval and `.nika/policy.yaml` rules like
+//! any other tool call.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+
+use super::{FileTool, ToolErrorCode, ToolOutput};
+use crate::error::NikaError;
+
+/// Processes one run may start
+const MAX_PROCESSES: usize = 8;
+
+/// Output lines kept per process (oldest dropped first)
+const MAX_LOG_LINES: usize = 2000;
+
+/// Longer lines are cut
+const MAX_LINE_CHARS: usize = 2000;
+
+/// Default and highest wait after starting a process
+const DEFAULT_START_WAIT_MS: u64 = 1000;
+const MAX_START_WAIT_MS: u64 = 30_000;
+
+/// Default and highest line count of `tail_logs`
+const DEFAULT_TAIL_LINES: usize = 50;
+const MAX_TAIL_LINES: usize = 500;
+
+/// Default and highest wait for a `wait_for` line
+const DEFAULT_WAIT_FOR_MS: u64 = 10_000;
+const MAX_WAIT_FOR_MS: u64 = 120_000;
+
+/// Time a process gets to exit after SIGTERM before `kill` uses SIGKILL
+const TERM_GRACE: Duration = Duration::from_secs(3);
+
+/// Poll interval while waiting on a process
+const POLL: Duration = Duration::from_millis(50);
+
+// ═══════════════════════════════════════════════════════════════════════════
+// REGISTRY
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Recent output of a process
+#[derive(Debug, Default)]
+struct Log {
+    lines: VecDeque<String>,
+    /// Lines written so far, dropped ones included
+    total: usize,
+}
+
+impl Log {
+    fn push(&mut self, line: String) {
+        if self.lines.len() == MAX_LOG_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+        self.total += 1;
+    }
+}
+
+/// A started process
+#[derive(Debug)]
+struct Process {
+    command: String,
+    pid: Option<u32>,
+    started: Instant,
+    child: Mutex<Child>,
+    status: Mutex<Option<ExitStatus>>,
+    log: Arc<Mutex<Log>>,
+}
+
+impl Process {
+    /// Exit status, once the process has exited
+    fn exit_status(&self) -> Option<ExitStatus> {
+        let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        if status.is_none() {
+            let mut child = self.child.lock().unwrap_or_else(|e| e.into_inner());
+            *status = child.try_wait().ok().flatten();
+        }
+        *status
+    }
+
+    /// Signal the process group (unix) or the process
+    fn signal(&self, force: bool) {
+        #[cfg(unix)]
+        if let Some(pid) = self.pid {
+            let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+            // SAFETY: kill(2) with a negative pid signals that process group
+            unsafe {
+                libc::kill(-(pid as i32), signal);
+            }
+            return;
+        }
+        let _ = force;
+        let mut child = self.child.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = child.start_kill();
+    }
+
+    fn state(&self, handle: &str) -> ProcessState {
+        let status = self.exit_status();
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        ProcessState {
+            handle: handle.to_string(),
+            command: self.command.clone(),
+            pid: self.pid,
+            running: status.is_none(),
+            exit_code: status.and_then(|s| s.code()),
+            uptime_secs: self.started.elapsed().as_secs(),
+            total_lines: log.total,
+            lines: Vec::new(),
+        }
+    }
+
+    fn tail(&self, count: usize) -> Vec<String> {
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = log.lines.len().saturating_sub(count);
+        log.lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Background processes of one run
+///
+/// Dropping the registry, or the guard from [`reap_on_drop`](Self::reap_on_drop),
+/// kills every process still running.
+#[derive(Debug, Default)]
+pub struct ProcessRegistry {
+    next: AtomicUsize,
+    processes: Mutex<BTreeMap<String, Arc<Process>>>,
+}
+
+impl ProcessRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start `command` with `sh -c`, returning its handle
+    pub fn spawn(&self, command: &str, cwd: Option<PathBuf>) -> Result<String, NikaError> {
+        let mut processes = self.processes.lock().unwrap_or_else(|e| e.into_inner());
+        let running = processes
+            .values()
+            .filter(|p| p.exit_status().is_none())
+            .count();
+        if running >= MAX_PROCESSES {
+            return Err(failed(format!(
+                "{} background processes are already running; kill one first",
+                running
+            )));
+        }
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(cwd) = cwd {
+            cmd.current_dir(cwd);
+        }
+        #[cfg(unix)]
+        cmd.process_group(0);
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| failed(format!("cannot start '{}': {}", command, e)))?;
+
+        let log = Arc::new(Mutex::new(Log::default()));
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(collect(stdout, "", Arc::clone(&log)));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(collect(stderr, "[stderr] ", Arc::clone(&log)));
+        }
+
+        let handle = format!("bg-{}", self.next.fetch_add(1, Ordering::Relaxed) + 1);
+        tracing::debug!(handle = %handle, pid = ?child.id(), command, "Background process started");
+        processes.insert(
+            handle.clone(),
+            Arc::new(Process {
+                command: command.to_string(),
+                pid: child.id(),
+                started: Instant::now(),
+                child: Mutex::new(child),
+                status: Mutex::new(None),
+                log,
+            }),
+        );
+        Ok(handle)
+    }
+
+    fn get(&self, handle: &str) -> Result<Arc<Process>, NikaError> {
+        let processes = self.processes.lock().unwrap_or_else(|e| e.into_inner());
+        processes.get(handle).cloned().ok_or_else(|| {
+            let known: Vec<&str> = processes.keys().map(String::as_str).collect();
+            failed(if known.is_empty() {
+                format!("no background process '{}' (none started)", handle)
+            } else {
+                format!(
+                    "no background process '{}' (started: {})",
+                    handle,
+                    known.join(", ")
+                )
+            })
+        })
+    }
+
+    /// Status and last `lines` of output of a process
+    pub fn state(&self, handle: &str, lines: usize) -> Result<ProcessState, NikaError> {
+        let process = self.get(handle)?;
+        let mut state = process.state(handle);
+        state.lines = process.tail(lines);
+        Ok(state)
+    }
+
+    /// Wait until a line contains `pattern`, the process exits, or `timeout`
+    ///
+    /// Returns whether the line was seen. Only lines written after the
+    /// first `from` lines count.
+    pub async fn wait_for(
+        &self,
+        handle: &str,
+        pattern: &str,
+        from: usize,
+        timeout: Duration,
+    ) -> Result<bool, NikaError> {
+        let process = self.get(handle)?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            {
+                let log = process.log.lock().unwrap_or_else(|e| e.into_inner());
+                let new = log.total.saturating_sub(from).min(log.lines.len());
+                let start = log.lines.len() - new;
+                if log.lines.iter().skip(start).any(|l| l.contains(pattern)) {
+                    return Ok(true);
+                }
+            }
+            if process.exit_status().is_some() || Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(POLL).await;
+        }
+    }
+
+    /// Wait up to `timeout` for a process to exit
+    async fn wait_exit(process: &Process, timeout: Duration) -> Option<ExitStatus> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = process.exit_status() {
+                return Some(status);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(POLL).await;
+        }
+    }
+
+    /// Stop a process: SIGTERM, then SIGKILL after a grace period
+    pub async fn kill(&self, handle: &str) -> Result<ProcessState, NikaError> {
+        let process = self.get(handle)?;
+        if process.exit_status().is_none() {
+            process.signal(false);
+            if Self::wait_exit(&process, TERM_GRACE).await.is_none() {
+                process.signal(true);
+                Self::wait_exit(&process, TERM_GRACE).await;
+            }
+        }
+        let mut state = process.state(handle);
+        state.lines = process.tail(10);
+        Ok(state)
+    }
+
+    /// SIGKILL every process still running, returning how many there were
+    pub fn kill_all(&self) -> usize {
+        let processes = self.processes.lock().unwrap_or_else(|e| e.into_inner());
+        let mut killed = 0;
+        for (handle, process) in processes.iter() {
+            if process.exit_status().is_none() {
+                tracing::warn!(handle = %handle, command = %process.command, "Killing background process left running");
+                process.signal(true);
+                killed += 1;
+            }
+        }
+        killed
+    }
+
+    /// Guard that kills every process still running when dropped
+    pub fn reap_on_drop(self: &Arc<Self>) -> Reaper {
+        Reaper(Arc::clone(self))
+    }
+}
+
+impl Drop for ProcessRegistry {
+    fn drop(&mut self) {
+        self.kill_all();
+    }
+}
+
+/// Kills a registry's processes when dropped, e.g. at the end of a run
+#[derive(Debug)]
+pub struct Reaper(Arc<ProcessRegistry>);
+
+impl Drop for Reaper {
+    fn drop(&mut self) {
+        self.0.kill_all();
+    }
+}
+
+/// Append lines of `stream` to `log`
+async fn collect(stream: impl AsyncRead + Unpin, prefix: &'static str, log: Arc<Mutex<Log>>) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let mut line = format!("{}{}", prefix, line);
+        if let Some((cut, _)) = line.char_indices().nth(MAX_LINE_CHARS) {
+            line.truncate(cut);
+            line.push('…');
+        }
+        log.lock().unwrap_or_else(|e| e.into_inner()).push(line);
+    }
+}
+
+/// `ToolError` with NIKA-215
+fn failed(message: impl std::fmt::Display) -> NikaError {
+    NikaError::ToolError {
+        code: ToolErrorCode::ProcessFailed.code(),
+        message: message.to_string(),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PARAMETERS & RESULT
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Parameters for `run_background`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunBackgroundParams {
+    /// Shell command
+    pub command: String,
+    /// Working directory (default: the current one)
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Milliseconds to wait for early output (default 1000)
+    #[serde(default)]
+    pub wait_ms: Option<u64>,
+}
+
+/// Parameters for `tail_logs`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TailLogsParams {
+    /// Handle from `run_background`
+    pub handle: String,
+    /// Lines to return (default 50)
+    #[serde(default)]
+    pub lines: Option<usize>,
+    /// Wait for a new line containing this text first
+    #[serde(default)]
+    pub wait_for: Option<String>,
+    /// Milliseconds to wait for `wait_for` (default 10000)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// Parameters for `kill`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillParams {
+    /// Handle from `run_background`
+    pub handle: String,
+}
+
+/// Status and recent output of a background process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessState {
+    pub handle: String,
+    pub command: String,
+    pub pid: Option<u32>,
+    pub running: bool,
+    /// None while running, or when killed by a signal
+    pub exit_code: Option<i32>,
+    pub uptime_secs: u64,
+    /// Lines written so far
+    pub total_lines: usize,
+    /// Last lines of output (stderr lines start with `[stderr] `)
+    pub lines: Vec<String>,
+}
+
+impl ProcessState {
+    fn render(&self, note: Option<&str>) -> String {
+        let status = match (self.running, self.exit_code) {
+            (true, _) => format!(
+                "running (pid {}, {}s)",
+                self.pid.unwrap_or(0),
+                self.uptime_secs
+            ),
+            (false, Some(code)) => format!("exited with code {}", code),
+            (false, None) => "killed".to_string(),
+        };
+        let mut out = format!("{} {}: {}", self.handle, status, self.command);
+        if let Some(note) = note {
+            out.push_str(&format!("\n{}", note));
+        }
+        if self.lines.is_empty() {
+            out.push_str("\n(no output yet)");
+        } else {
+            out.push_str(&format!(
+                "\n--- last {} of {} lines ---\n{}",
+                self.lines.len(),
+                self.total_lines,
+                self.lines.join("\n")
+            ));
+        }
+        out
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, NikaError> {
+    serde_json::from_value(params).map_err(|e| failed(format!("Invalid parameters: {}", e)))
+}
+
+fn output(state: ProcessState, note: Option<&str>) -> ToolOutput {
+    ToolOutput::success_with_data(
+        state.render(note),
+        serde_json::to_value(&state).unwrap_or_default(),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// TOOLS
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// `run_background`: start a long-running process
+pub struct RunBackgroundTool {
+    registry: Arc<ProcessRegistry>,
+}
+
+impl RunBackgroundTool {
+    pub fn new(registry: Arc<ProcessRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl FileTool for RunBackgroundTool {
+    fn name(&self) -> &'static str {
+        "run_background"
+    }
+
+    fn description(&self) -> &'static str {
+        "Start a long-running shell command (dev server, watcher, build) in the background \
+         and return a handle such as bg-1, with its first lines of output. Use tail_logs to \
+         read more output and kill to stop it; processes still running when the workflow \
+         ends are killed."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "Shell command, run with sh -c"
+                },
+                "cwd": {
+                    "type": "string",
+                    "description": "Working directory (default: the current one)"
+                },
+                "wait_ms": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": MAX_START_WAIT_MS,
+                    "description": "Milliseconds to wait for early output (default: 1000)"
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: RunBackgroundParams = parse(params)?;
+        let handle = self
+            .registry
+            .spawn(&params.command, params.cwd.map(PathBuf::from))?;
+
+        // Early output shows startup errors without a tail_logs round-trip
+        let wait = params
+            .wait_ms
+            .unwrap_or(DEFAULT_START_WAIT_MS)
+            .min(MAX_START_WAIT_MS);
+        if let Ok(process) = self.registry.get(&handle) {
+            ProcessRegistry::wait_exit(&process, Duration::from_millis(wait)).await;
+        }
+        let state = self.registry.state(&handle, DEFAULT_TAIL_LINES)?;
+        Ok(output(state, None))
+    }
+}
+
+/// `tail_logs`: status and recent output of a background process
+pub struct TailLogsTool {
+    registry: Arc<ProcessRegistry>,
+}
+
+impl TailLogsTool {
+    pub fn new(registry: Arc<ProcessRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl FileTool for TailLogsTool {
+    fn name(&self) -> &'static str {
+        "tail_logs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show whether a background process is still running and its last lines of output. \
+         With wait_for, first wait until a new line contains that text (e.g. 'ready on'), \
+         the process exits, or timeout_ms passes."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "handle": {
+                    "type": "string",
+                    "description": "Handle from run_background, e.g. bg-1"
+                },
+                "lines": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_TAIL_LINES,
+                    "description": "Lines to return (default: 50)"
+                },
+                "wait_for": {
+                    "type": "string",
+                    "description": "Wait for a new output line containing this text"
+                },
+                "timeout_ms": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "maximum": MAX_WAIT_FOR_MS,
+                    "description": "Milliseconds to wait for wait_for (default: 10000)"
+                }
+            },
+            "required": ["handle"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: TailLogsParams = parse(params)?;
+        let lines = params
+            .lines
+            .unwrap_or(DEFAULT_TAIL_LINES)
+            .clamp(1, MAX_TAIL_LINES);
+
+        let note = match &params.wait_for {
+            Some(pattern) => {
+                let from = self.registry.state(&params.handle, 0)?.total_lines;
+                let timeout = params
+                    .timeout_ms
+                    .unwrap_or(DEFAULT_WAIT_FOR_MS)
+                    .min(MAX_WAIT_FOR_MS);
+                let seen = self
+                    .registry
+                    .wait_for(
+                        &params.handle,
+                        pattern,
+                        from,
+                        Duration::from_millis(timeout),
+                    )
+                    .await?;
+                Some(if seen {
+                    format!("'{}' seen", pattern)
+                } else {
+                    format!("'{}' not seen", pattern)
+                })
+            }
+            None => None,
+        };
+
+        let state = self.registry.state(&params.handle, lines)?;
+        Ok(output(state, note.as_deref()))
+    }
+}
+
+/// `kill`: stop a background process
+pub struct KillTool {
+    registry: Arc<ProcessRegistry>,
+}
+
+impl KillTool {
+    pub fn new(registry: Arc<ProcessRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl FileTool for KillTool {
+    fn name(&self) -> &'static str {
+        "kill"
+    }
+
+    fn description(&self) -> &'static str {
+        "Stop a background process started with run_background (SIGTERM, then SIGKILL \
+         after 3 seconds), with its child processes."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "handle": {
+                    "type": "string",
+                    "description": "Handle from run_background, e.g. bg-1"
+                }
+            },
+            "required": ["handle"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: KillParams = parse(params)?;
+        let state = self.registry.kill(&params.handle).await?;
+        Ok(output(state, None))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn run_tail_and_kill() {
+        let registry = Arc::new(ProcessRegistry::new());
+        let run = RunBackgroundTool::new(Arc::clone(&registry));
+        let output = run
+            .call(json!({
+                "command": "echo starting; echo oops >&2; sleep 0.2; echo ready on 3000; sleep 30",
+                "wait_ms": 100
+            }))
+            .await
+            .unwrap();
+        assert!(
+            output.content.starts_with("bg-1 running"),
+            "{}",
+            output.content
+        );
+
+        let tail = TailLogsTool::new(Arc::clone(&registry));
+        let output = tail
+            .call(json!({"handle": "bg-1", "wait_for": "ready on", "timeout_ms": 5000}))
+            .await
+            .unwrap();
+        assert!(
+            output.content.contains("'ready on' seen"),
+            "{}",
+            output.content
+        );
+        assert!(
+            output.content.contains("[stderr] oops"),
+            "{}",
+            output.content
+        );
+        assert!(
+            output.content.contains("ready on 3000"),
+            "{}",
+            output.content
+        );
+
+        let output = KillTool::new(Arc::clone(&registry))
+            .call(json!({"handle": "bg-1"}))
+            .await
+            .unwrap();
+        assert!(
+            output.content.starts_with("bg-1 killed"),
+            "{}",
+            output.content
+        );
+
+        let err = tail.call(json!({"handle": "bg-9"})).await.unwrap_err();
+        assert!(err.to_string().contains("NIKA-215"), "{}", err);
+        assert!(err.to_string().contains("started: bg-1"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn exited_process_and_reaper() {
+        let registry = Arc::new(ProcessRegistry::new());
+        let handle = registry.spawn("exit 3", None).unwrap();
+        let process = registry.get(&handle).unwrap();
+        ProcessRegistry::wait_exit(&process, Duration::from_secs(5)).await;
+        let state = registry.state(&handle, 5).unwrap();
+        assert!(!state.running);
+        assert_eq!(state.exit_code, Some(3));
+
+        // The guard kills the group, sh's children included
+        let handle = registry.spawn("sleep 30 & sleep 30; wait", None).unwrap();
+        drop(registry.reap_on_drop());
+        let process = registry.get(&handle).unwrap();
+        assert!(ProcessRegistry::wait_exit(&process, Duration::from_secs(5))
+            .await
+            .is_some());
+        assert_eq!(registry.kill_all(), 0);
+    }
+}
@@ -0,0 +1,396 @@
+//! Read URL Tool - Fetch a web page as Markdown for agent loops (v0.7)
+//!
+//! Fetches `http(s)` URLs and returns the main content:
+//! - HTML goes through [`readability`](crate::util::readability): boilerplate
+//!   (navigation, sidebars, footers, scripts) is dropped, the rest becomes
+//!   Markdown with absolute links
+//! - Plain text, Markdown, JSON and XML are returned as they are
+//! - Downloads stop at [`ReadUrlTool::MAX_BYTES`]; the text is cut at
+//!   `max_chars`
+//!
+//! Redirects are followed by hand so every hop can be checked against the
+//! `domains` rules of `.nika/policy.yaml`, not only the first URL.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{FileTool, ToolErrorCode, ToolOutput};
+use crate::error::NikaError;
+use crate::util::readability;
+use crate::util::{CONNECT_TIMEOUT, FETCH_TIMEOUT, REDIRECT_LIMIT};
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PARAMETERS & RESULT
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Parameters for the read_url tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadUrlParams {
+    /// `http(s)` URL of the page
+    pub url: String,
+
+    /// Longest text to return (default: 20000 characters)
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+}
+
+/// Result from reading a URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadUrlResult {
+    /// Final URL, after redirects
+    pub url: String,
+
+    /// Page title, for HTML pages that have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Main content as Markdown (or the raw text of non-HTML pages)
+    pub markdown: String,
+
+    /// Whether the download or the text was cut
+    pub truncated: bool,
+}
+
+/// Why a URL may not be requested (None: allowed)
+pub type UrlCheck = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// READ URL TOOL
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// URL reader tool for agent loops
+///
+/// # Features
+///
+/// - Readability-style extraction to Markdown
+/// - Download and output size limits
+/// - Per-hop URL check (policy `domains` rules)
+pub struct ReadUrlTool {
+    http: reqwest::Client,
+    check: Option<UrlCheck>,
+}
+
+impl Default for ReadUrlTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReadUrlTool {
+    /// Largest download (bytes); the rest of the body is not read
+    pub const MAX_BYTES: usize = 5 * 1024 * 1024;
+
+    /// Default text length returned (characters)
+    pub const DEFAULT_MAX_CHARS: usize = 20_000;
+
+    /// Longest text an agent may ask for (characters)
+    pub const MAX_CHARS: usize = 100_000;
+
+    /// Create a URL reader
+    pub fn new() -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .redirect(reqwest::redirect::Policy::none())
+                .user_agent("nika-cli/0.1")
+                .build()
+                .unwrap_or_default(),
+            check: None,
+        }
+    }
+
+    /// Refuse URLs (first request and redirects) that `check` blocks
+    pub fn with_url_check(mut self, check: UrlCheck) -> Self {
+        self.check = Some(check);
+        self
+    }
+
+    /// Fetch the page and extract its content
+    pub async fn execute(&self, params: ReadUrlParams) -> Result<ReadUrlResult, NikaError> {
+        let max_chars = params
+            .max_chars
+            .unwrap_or(Self::DEFAULT_MAX_CHARS)
+            .clamp(1, Self::MAX_CHARS);
+        let mut url = Url::parse(params.url.trim())
+            .map_err(|e| failed(format!("invalid URL '{}': {}", params.url, e)))?;
+
+        let mut redirects = 0;
+        let response = loop {
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(failed(format!(
+                    "only http(s) URLs can be read, got '{}'",
+                    url
+                )));
+            }
+            if let Some(rule) = self.check.as_ref().and_then(|check| check(url.as_str())) {
+                return Err(NikaError::ToolError {
+                    code: ToolErrorCode::PermissionDenied.code(),
+                    message: format!(
+                        "Permission denied: {} is blocked by the project policy ({})",
+                        url, rule
+                    ),
+                });
+            }
+            let response = self
+                .http
+                .get(url.clone())
+                .send()
+                .await
+                .map_err(|e| failed(format!("request to {} failed: {}", url, e)))?;
+            if !response.status().is_redirection() {
+                break response;
+            }
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| failed(format!("{} redirected without a Location", url)))?;
+            url = url
+                .join(location)
+                .map_err(|e| failed(format!("bad redirect to '{}': {}", location, e)))?;
+            redirects += 1;
+            if redirects > REDIRECT_LIMIT {
+                return Err(failed(format!("more than {} redirects", REDIRECT_LIMIT)));
+            }
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(failed(format!("{} returned {}", url, status.as_u16())));
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let (body, mut truncated) = read_capped(response).await?;
+        let text = String::from_utf8_lossy(&body);
+        let is_html = content_type.contains("html")
+            || (content_type.is_empty()
+                && text.trim_start().get(..15).is_some_and(|head| {
+                    head.eq_ignore_ascii_case("<!doctype html>") || head.starts_with("<html")
+                }));
+        let (title, markdown) = if is_html {
+            let article = readability::extract(&text, Some(&url));
+            (article.title, article.markdown)
+        } else if content_type.is_empty()
+            || content_type.starts_with("text/")
+            || content_type.contains("json")
+            || content_type.contains("xml")
+        {
+            (None, text.trim().to_string())
+        } else {
+            return Err(failed(format!(
+                "{} is {}, not a web page",
+                url, content_type
+            )));
+        };
+
+        let markdown = match markdown.char_indices().nth(max_chars) {
+            Some((cut, _)) => {
+                truncated = true;
+                markdown[..cut].to_string()
+            }
+            None => markdown,
+        };
+        Ok(ReadUrlResult {
+            url: url.to_string(),
+            title,
+            markdown,
+            truncated,
+        })
+    }
+}
+
+/// Read the body up to `MAX_BYTES`; true when more was left
+async fn read_capped(mut response: reqwest::Response) -> Result<(Vec<u8>, bool), NikaError> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| failed(format!("reading the body failed: {}", e)))?
+    {
+        let room = ReadUrlTool::MAX_BYTES - body.len();
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            return Ok((body, true));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok((body, false))
+}
+
+/// `ToolError` with NIKA-213
+fn failed(message: impl std::fmt::Display) -> NikaError {
+    NikaError::ToolError {
+        code: ToolErrorCode::ReadUrlFailed.code(),
+        message: format!("Reading the URL failed: {}", message),
+    }
+}
+
+#[async_trait]
+impl FileTool for ReadUrlTool {
+    fn name(&self) -> &'static str {
+        "read_url"
+    }
+
+    fn description(&self) -> &'static str {
+        "Fetch a web page and return its main content as Markdown, without navigation, \
+         ads or other boilerplate. Plain text and JSON are returned as is. \
+         Use it to read pages found by search or linked from other pages."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "http(s) URL of the page"
+                },
+                "max_chars": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": Self::MAX_CHARS,
+                    "description": "Longest text to return (default: 20000)"
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: ReadUrlParams = serde_json::from_value(params)
+            .map_err(|e| failed(format!("invalid parameters: {}", e)))?;
+
+        let result = self.execute(params).await?;
+
+        let mut content = String::new();
+        if let Some(title) = &result.title {
+            content.push_str(&format!("# {}\n", title));
+        }
+        content.push_str(&format!("Source: {}\n\n{}", result.url, result.markdown));
+        if result.truncated {
+            content.push_str("\n\n[Truncated]");
+        }
+
+        Ok(ToolOutput::success_with_data(
+            content,
+            serde_json::to_value(&result).unwrap_or_default(),
+        ))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const PAGE: &str = "<html><head><title>Release notes</title></head><body>\
+        <nav><a href=\"/\">Home</a></nav>\
+        <article><h2>v0.7</h2><p>Adds the <a href=\"/tools\">read_url</a> tool, \
+        which reads pages, strips boilerplate, and returns Markdown to the agent.</p>\
+        <p>It also follows redirects, checking each one against the policy file.</p>\
+        </article><footer>Footer links</footer></body></html>";
+
+    #[tokio::test]
+    async fn test_reads_html_as_markdown_through_redirects() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/old"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", "/notes"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/notes"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(PAGE, "text/html; charset=utf-8"))
+            .mount(&server)
+            .await;
+
+        let tool = ReadUrlTool::new();
+        let output = tool
+            .call(json!({"url": format!("{}/old", server.uri())}))
+            .await
+            .unwrap();
+        assert!(output.content.starts_with("# Release notes\nSource: "));
+        assert!(output.content.contains("## v0.7"));
+        assert!(output
+            .content
+            .contains(&format!("[read_url]({}/tools)", server.uri())));
+        assert!(!output.content.contains("Footer links"));
+
+        let data: ReadUrlResult = serde_json::from_value(output.data.unwrap()).unwrap();
+        assert_eq!(data.url, format!("{}/notes", server.uri()));
+        assert!(!data.truncated);
+
+        let short = tool
+            .execute(ReadUrlParams {
+                url: format!("{}/notes", server.uri()),
+                max_chars: Some(10),
+            })
+            .await
+            .unwrap();
+        assert_eq!(short.markdown, "## v0.7\n\nA");
+        assert!(short.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_checks_every_hop_and_rejects_other_content() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/jump"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("Location", "http://blocked.test/x"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/logo.png"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(vec![0u8; 8], "image/png"))
+            .mount(&server)
+            .await;
+
+        let tool = ReadUrlTool::new().with_url_check(Arc::new(|url: &str| {
+            url.contains("blocked.test")
+                .then(|| "domains.deny 'blocked.*' matches 'blocked.test'".to_string())
+        }));
+        let err = tool
+            .call(json!({"url": format!("{}/jump", server.uri())}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "NIKA-2XX");
+        assert!(
+            err.to_string().contains("http://blocked.test/x is blocked"),
+            "{}",
+            err
+        );
+
+        let err = tool
+            .call(json!({"url": format!("{}/logo.png", server.uri())}))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("image/png, not a web page"),
+            "{}",
+            err
+        );
+
+        let err = tool
+            .call(json!({"url": "file:///etc/passwd"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("only http(s)"), "{}", err);
+    }
+}
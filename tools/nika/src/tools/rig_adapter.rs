@@ -119,7 +119,9 @@ impl<T: FileTool + Send + Sync + 'static> ToolDyn for RigFileTool<T> {
 // CONVENIENCE FUNCTIONS
 // ═══════════════════════════════════════════════════════════════════════════
 
-use super::{EditTool, GlobTool, GrepTool, ReadTool, ToolContext, WriteTool};
+use super::{
+    ApplyPatchTool, EditTool, GlobTool, GrepTool, MultiEditTool, ReadTool, ToolContext, WriteTool,
+};
 
 /// Create all file tools wrapped for rig integration
 ///
@@ -140,6 +142,8 @@ pub fn create_rig_file_tools(ctx: Arc<ToolContext>) -> Vec<Box<dyn ToolDyn>> {
         Box::new(RigFileTool::new(ReadTool::new(Arc::clone(&ctx)))),
         Box::new(RigFileTool::new(WriteTool::new(Arc::clone(&ctx)))),
         Box::new(RigFileTool::new(EditTool::new(Arc::clone(&ctx)))),
+        Box::new(RigFileTool::new(MultiEditTool::new(Arc::clone(&ctx)))),
+        Box::new(RigFileTool::new(ApplyPatchTool::new(Arc::clone(&ctx)))),
         Box::new(RigFileTool::new(GlobTool::new(Arc::clone(&ctx)))),
         Box::new(RigFileTool::new(GrepTool::new(ctx))),
     ]
@@ -253,13 +257,15 @@ mod tests {
 
         let tools = create_rig_file_tools(ctx);
 
-        assert_eq!(tools.len(), 5);
+        assert_eq!(tools.len(), 7);
 
         // Check tool names
         let names: Vec<String> = tools.iter().map(|t| t.name()).collect();
         assert!(names.contains(&"read".to_string()));
         assert!(names.contains(&"write".to_string()));
         assert!(names.contains(&"edit".to_string()));
+        assert!(names.contains(&"multi_edit".to_string()));
+        assert!(names.contains(&"apply_patch".to_string()));
         assert!(names.contains(&"glob".to_string()));
         assert!(names.contains(&"grep".to_string()));
     }
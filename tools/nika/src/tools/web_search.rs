@@ -0,0 +1,482 @@
+//! Web Search Tool - Search the web from agent loops (v0.7)
+//!
+//! One tool, three backends picked by `[web_search]` in the global config:
+//! - Brave Search API (`BRAVE_API_KEY`)
+//! - Tavily (`TAVILY_API_KEY`)
+//! - a SearxNG instance with the JSON format enabled (`SEARXNG_URL`)
+//!
+//! Results come back as numbered title/URL/snippet entries for the model,
+//! with the same hits as structured data in [`ToolOutput::data`].
+//! Searches are spaced out to `per_minute` across every agent sharing the
+//! tool; a call over the rate waits for its turn instead of failing.
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::{FileTool, ToolErrorCode, ToolOutput};
+use crate::config::{SearchBackendKind, WebSearchConfig};
+use crate::error::NikaError;
+use crate::util::constants::FETCH_TIMEOUT;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PARAMETERS & RESULT
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Parameters for the web_search tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSearchParams {
+    /// Search query
+    pub query: String,
+
+    /// Number of results (default: `max_results` from the config)
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+/// One search result
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub title: String,
+    pub url: String,
+    /// Excerpt of the page, as returned by the backend
+    pub snippet: String,
+}
+
+/// Result from a web search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSearchResult {
+    pub query: String,
+
+    /// Backend that answered (brave, tavily, searxng)
+    pub backend: String,
+
+    pub results: Vec<SearchHit>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// WEB SEARCH TOOL
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Resolved backend: kind, API base and key
+#[derive(Debug, Clone)]
+struct Backend {
+    kind: SearchBackendKind,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+/// Web search tool for agent loops
+///
+/// # Features
+///
+/// - Brave, Tavily or SearxNG, from `[web_search]` or the environment
+/// - Structured hits (title, URL, snippet) in the output data
+/// - Shared rate limit (`per_minute`)
+pub struct WebSearchTool {
+    /// Why no backend is usable, when none is
+    backend: Result<Backend, String>,
+    max_results: usize,
+    /// Minimum spacing between searches (None: unlimited)
+    interval: Option<Duration>,
+    /// Earliest start of the next search
+    next_slot: Mutex<Instant>,
+    http: reqwest::Client,
+}
+
+impl WebSearchTool {
+    /// Most results a single search may ask for
+    pub const MAX_RESULTS: usize = 20;
+
+    /// Longest snippet kept per result (characters)
+    pub const MAX_SNIPPET_CHARS: usize = 500;
+
+    /// Create a web search tool from `[web_search]`
+    ///
+    /// A missing backend or key is reported by [`check`](Self::check) and
+    /// by each call, not here, so runs without `web_search:` agents work
+    /// without any search setup.
+    pub fn new(config: &WebSearchConfig) -> Self {
+        let interval = (config.per_minute > 0).then(|| Duration::from_secs(60) / config.per_minute);
+        Self {
+            backend: resolve_backend(config),
+            max_results: config.max_results.clamp(1, Self::MAX_RESULTS),
+            interval,
+            next_slot: Mutex::new(Instant::now()),
+            http: reqwest::Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Backend in use, or NIKA-140 explaining how to set one up
+    pub fn check(&self) -> Result<SearchBackendKind, NikaError> {
+        self.backend
+            .as_ref()
+            .map(|backend| backend.kind)
+            .map_err(|reason| NikaError::ConfigError {
+                reason: format!("web_search: {}", reason),
+            })
+    }
+
+    /// Execute the web search
+    pub async fn execute(&self, params: WebSearchParams) -> Result<WebSearchResult, NikaError> {
+        let backend = self.backend.as_ref().map_err(failed)?;
+        let query = params.query.trim();
+        if query.is_empty() {
+            return Err(failed("query cannot be empty"));
+        }
+        let count = params
+            .count
+            .unwrap_or(self.max_results)
+            .clamp(1, Self::MAX_RESULTS);
+
+        self.wait_turn().await;
+
+        let request = match backend.kind {
+            SearchBackendKind::Brave => self
+                .http
+                .get(format!("{}/res/v1/web/search", backend.base_url))
+                .header("Accept", "application/json")
+                .header(
+                    "X-Subscription-Token",
+                    backend.api_key.as_deref().unwrap_or_default(),
+                )
+                .query(&[("q", query), ("count", &count.to_string())]),
+            SearchBackendKind::Tavily => self
+                .http
+                .post(format!("{}/search", backend.base_url))
+                .bearer_auth(backend.api_key.as_deref().unwrap_or_default())
+                .json(&json!({ "query": query, "max_results": count })),
+            SearchBackendKind::Searxng => self
+                .http
+                .get(format!("{}/search", backend.base_url))
+                .query(&[("q", query), ("format", "json")]),
+        };
+
+        let name = backend.kind.as_str();
+        let response = request
+            .send()
+            .await
+            .map_err(|e| failed(format!("{} request failed: {}", name, e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let body: String = body.chars().take(200).collect();
+            return Err(failed(format!(
+                "{} returned {}: {}",
+                name,
+                status.as_u16(),
+                body
+            )));
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| failed(format!("{} sent invalid JSON: {}", name, e)))?;
+
+        let mut results = parse_hits(backend.kind, &body);
+        results.truncate(count);
+        Ok(WebSearchResult {
+            query: query.to_string(),
+            backend: name.to_string(),
+            results,
+        })
+    }
+
+    /// Wait until the rate limit allows another search
+    async fn wait_turn(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let start = {
+            let mut next_slot = self.next_slot.lock().await;
+            let start = (*next_slot).max(Instant::now());
+            *next_slot = start + interval;
+            start
+        };
+        tokio::time::sleep_until(start.into()).await;
+    }
+}
+
+/// `ToolError` with NIKA-212
+fn failed(message: impl std::fmt::Display) -> NikaError {
+    NikaError::ToolError {
+        code: ToolErrorCode::WebSearchFailed.code(),
+        message: format!("Web search failed: {}", message),
+    }
+}
+
+/// Pick the backend from the config, then from the environment
+fn resolve_backend(config: &WebSearchConfig) -> Result<Backend, String> {
+    let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    let kind = match config.backend {
+        Some(kind) => kind,
+        None if env("BRAVE_API_KEY").is_some() => SearchBackendKind::Brave,
+        None if env("TAVILY_API_KEY").is_some() => SearchBackendKind::Tavily,
+        None if config.url.is_some() || env("SEARXNG_URL").is_some() => SearchBackendKind::Searxng,
+        None => {
+            return Err("no search backend: set [web_search] in the config, or \
+                        BRAVE_API_KEY, TAVILY_API_KEY or SEARXNG_URL"
+                .to_string())
+        }
+    };
+
+    let (key_var, default_url) = match kind {
+        SearchBackendKind::Brave => (Some("BRAVE_API_KEY"), Some("https://api.search.brave.com")),
+        SearchBackendKind::Tavily => (Some("TAVILY_API_KEY"), Some("https://api.tavily.com")),
+        SearchBackendKind::Searxng => (None, None),
+    };
+    let api_key = match key_var {
+        Some(var) => Some(
+            config
+                .resolved_api_key()?
+                .or_else(|| env(var))
+                .ok_or_else(|| format!("{} needs api_key or {}", kind.as_str(), var))?,
+        ),
+        None => None,
+    };
+    let base_url = config
+        .url
+        .clone()
+        .or_else(|| match kind {
+            SearchBackendKind::Searxng => env("SEARXNG_URL"),
+            _ => None,
+        })
+        .or(default_url.map(String::from))
+        .ok_or_else(|| "searxng needs url or SEARXNG_URL".to_string())?;
+
+    Ok(Backend {
+        kind,
+        base_url: base_url.trim_end_matches('/').to_string(),
+        api_key,
+    })
+}
+
+/// Hits from a backend's JSON answer
+fn parse_hits(kind: SearchBackendKind, body: &Value) -> Vec<SearchHit> {
+    // Brave nests web results and names the excerpt `description`
+    let (results, snippet_key) = match kind {
+        SearchBackendKind::Brave => (&body["web"]["results"], "description"),
+        SearchBackendKind::Tavily | SearchBackendKind::Searxng => (&body["results"], "content"),
+    };
+    let text = |value: &Value| value.as_str().unwrap_or_default().trim().to_string();
+    results
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|hit| {
+            let url = text(&hit["url"]);
+            if url.is_empty() {
+                return None;
+            }
+            let snippet = strip_tags(&text(&hit[snippet_key]));
+            Some(SearchHit {
+                title: strip_tags(&text(&hit["title"])),
+                url,
+                snippet: snippet
+                    .chars()
+                    .take(WebSearchTool::MAX_SNIPPET_CHARS)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Drop the `<strong>`-style highlighting some backends put in snippets
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[async_trait]
+impl FileTool for WebSearchTool {
+    fn name(&self) -> &'static str {
+        "web_search"
+    }
+
+    fn description(&self) -> &'static str {
+        "Search the web. Returns the top results as title, URL and snippet. \
+         Use it for current events or facts you are unsure about, then cite the URLs."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Search query"
+                },
+                "count": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": Self::MAX_RESULTS,
+                    "description": "Number of results (default: 5)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn call(&self, params: Value) -> Result<ToolOutput, NikaError> {
+        let params: WebSearchParams = serde_json::from_value(params)
+            .map_err(|e| failed(format!("invalid parameters: {}", e)))?;
+
+        let result = self.execute(params).await?;
+
+        let content = if result.results.is_empty() {
+            format!("No results for '{}'", result.query)
+        } else {
+            result
+                .results
+                .iter()
+                .enumerate()
+                .map(|(i, hit)| {
+                    format!(
+                        "{}. {}\n   {}\n   {}",
+                        i + 1,
+                        hit.title,
+                        hit.url,
+                        hit.snippet
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        };
+
+        Ok(ToolOutput::success_with_data(
+            content,
+            serde_json::to_value(&result).unwrap_or_default(),
+        ))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// TESTS
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn searxng(url: &str, per_minute: u32) -> WebSearchTool {
+        WebSearchTool::new(&WebSearchConfig {
+            backend: Some(SearchBackendKind::Searxng),
+            url: Some(format!("{}/", url)),
+            per_minute,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_searxng_results_as_structured_output() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/search"))
+            .and(query_param("q", "rust async"))
+            .and(query_param("format", "json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "results": [
+                    {
+                        "title": "Async Book",
+                        "url": "https://rust-lang.github.io/async-book/",
+                        "content": "Asynchronous <b>Rust</b>"
+                    },
+                    {"title": "No URL", "content": "skipped"},
+                    {"title": "Tokio", "url": "https://tokio.rs", "content": "A runtime"},
+                    {"title": "Extra", "url": "https://example.com", "content": "cut by count"}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let tool = searxng(&server.uri(), 0);
+        assert_eq!(tool.check().unwrap(), SearchBackendKind::Searxng);
+
+        let output = tool
+            .call(json!({"query": "rust async", "count": 2}))
+            .await
+            .unwrap();
+        assert!(!output.is_error);
+        assert!(output.content.starts_with(
+            "1. Async Book\n   https://rust-lang.github.io/async-book/\n   Asynchronous Rust"
+        ));
+        assert!(output.content.contains("2. Tokio"));
+        assert!(!output.content.contains("Extra"));
+
+        let data: WebSearchResult = serde_json::from_value(output.data.unwrap()).unwrap();
+        assert_eq!(data.backend, "searxng");
+        assert_eq!(data.results.len(), 2);
+        assert_eq!(data.results[1].url, "https://tokio.rs");
+    }
+
+    #[tokio::test]
+    async fn test_errors_and_rate_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("slow down"))
+            .mount(&server)
+            .await;
+
+        // 600 per minute: one search every 100ms
+        let tool = searxng(&server.uri(), 600);
+        let start = Instant::now();
+        let err = tool.call(json!({"query": "a"})).await.unwrap_err();
+        assert!(err.to_string().contains("searxng returned 429"), "{}", err);
+        let _ = tool.call(json!({"query": "b"})).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let empty = tool.call(json!({"query": "  "})).await.unwrap_err();
+        assert!(empty.to_string().contains("query cannot be empty"));
+
+        let unset = WebSearchTool::new(&WebSearchConfig {
+            backend: Some(SearchBackendKind::Searxng),
+            ..Default::default()
+        });
+        if std::env::var("SEARXNG_URL").is_err() {
+            assert_eq!(unset.check().unwrap_err().code(), "NIKA-140");
+        }
+    }
+
+    #[test]
+    fn test_parse_brave_and_tavily() {
+        let brave = json!({"web": {"results": [
+            {
+                "title": "Nika",
+                "url": "https://nika.sh",
+                "description": "Workflow <strong>engine</strong>"
+            }
+        ]}});
+        assert_eq!(
+            parse_hits(SearchBackendKind::Brave, &brave),
+            vec![SearchHit {
+                title: "Nika".to_string(),
+                url: "https://nika.sh".to_string(),
+                snippet: "Workflow engine".to_string(),
+            }]
+        );
+
+        let long = "x".repeat(900);
+        let tavily = json!({"results": [{"title": "T", "url": "https://t.dev", "content": long}]});
+        let hits = parse_hits(SearchBackendKind::Tavily, &tavily);
+        assert_eq!(hits[0].snippet.len(), WebSearchTool::MAX_SNIPPET_CHARS);
+        assert!(parse_hits(SearchBackendKind::Tavily, &json!({})).is_empty());
+    }
+}
@@ -3,9 +3,10 @@
 //! Main event loop with 60 FPS rendering.
 //! Handles keyboard input, event processing, and frame rendering.
 
+use std::collections::BTreeMap;
 use std::io::{self, Stdout};
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
 use crossterm::{
@@ -25,33 +26,42 @@ use ratatui::{
     Frame, Terminal,
 };
 use tokio::sync::{broadcast, mpsc, OnceCell};
-use tokio::task::AbortHandle;
 use tokio::time::timeout;
 
 use crate::util::constants::{EXEC_TIMEOUT, FETCH_TIMEOUT, INFER_TIMEOUT, WORKFLOW_TIMEOUT};
 
 use crate::ast::schema_validator::WorkflowSchemaValidator;
-use crate::ast::{AgentParams, McpConfigInline, Workflow};
+use crate::ast::{AgentParams, ApprovalDecision, McpConfigInline, Workflow};
 use crate::error::{NikaError, Result};
 use crate::event::{Event as NikaEvent, EventKind, EventLog};
 use crate::mcp::McpClient;
 use crate::mcp::McpConfig;
 use crate::provider::rig::{RigProvider, StreamChunk};
-use crate::runtime::{RigAgentLoop, RigAgentStatus, Runner};
+use crate::provider::{CallOutcome, ConcurrencyLimiter, Priority};
+use crate::runtime::{
+    ApprovalCheckpoint, ApprovalGate, ApprovalRequest, DebugCommand, Debugger, PausedTask,
+    PendingApproval, RigAgentLoop, RigAgentStatus, Runner,
+};
 use crate::tui::chat_agent::ChatAgent;
 use crate::tui::command::ModelProvider;
 use rustc_hash::FxHashMap;
 use std::path::PathBuf;
 
 use super::focus::{FocusState, PanelId as NavPanelId};
+use super::input_form::{self, FormKey, InputForm, INPUTS_MEMORY};
 use super::mode::InputMode;
 use super::panels::{ContextPanel, GraphPanel, ProgressPanel, ReasoningPanel};
 use super::standalone::{HistoryEntry, StandaloneState};
-use super::state::{PanelId, SettingsField, TuiMode, TuiState};
+use super::state::{
+    Breakpoint, DebugEdit, DebugEditKind, DebugPause, PanelId, SettingsField, TuiMode, TuiState,
+};
+use super::stream::StreamCoalescer;
+use super::tasks::{BackgroundTask, TaskGroup, TaskScope};
 use super::theme::Theme;
 use super::views::{ChatView, HomeView, McpAction, StudioView, TuiView, View, ViewAction};
 use super::widgets::{ConnectionStatus, Header, Provider, StatusBar, StatusMetrics};
-use crate::config::mask_api_key;
+use crate::config::{mask_api_key, NikaConfig};
+use crate::store::{HttpCache, StateStore, VectorStore};
 use crossterm::event::KeyEvent;
 
 /// Frame rate target (60 FPS)
@@ -111,6 +121,27 @@ pub enum Action {
     DismissNotification,
     /// Dismiss all notifications [N]
     DismissAllNotifications,
+    // ═══ Approval Actions (v0.7) ═══
+    /// Answer the pending `approve:` task [y/n]
+    AnswerApproval(ApprovalDecision),
+    // ═══ Debugger Actions (v0.7, `nika debug --tui`) ═══
+    /// Resume the paused task [s/Enter/c/k]
+    DebugResume(DebugCommand),
+    /// Start typing a replacement prompt [p] or output [o]
+    DebugEdit(DebugEditKind),
+    /// Insert character in the debugger edit buffer
+    DebugEditInput(char),
+    /// Backspace in the debugger edit buffer
+    DebugEditBackspace,
+    /// Submit the debugger edit [Enter]
+    DebugEditSubmit,
+    /// Cancel the debugger edit [Esc]
+    DebugEditCancel,
+    // ═══ Background Tasks (v0.7) ═══
+    /// Show/hide the live background tasks overlay [F12]
+    ToggleTaskOverlay,
+    /// Abort every background task, including workflow runs [x in overlay]
+    CancelBackgroundTasks,
     // ═══ Filter/Search Actions (TIER 1.5) ═══
     /// Enter search/filter mode
     EnterFilter,
@@ -199,6 +230,18 @@ pub struct App {
     event_rx: Option<mpsc::Receiver<NikaEvent>>,
     /// Broadcast receiver from runtime (v0.4.1 - preferred)
     broadcast_rx: Option<broadcast::Receiver<NikaEvent>>,
+    /// Approval questions from `approve:` tasks (v0.7)
+    approval_rx: Option<mpsc::Receiver<PendingApproval>>,
+    /// Approval waiting for y/n (one at a time)
+    pending_approval: Option<PendingApproval>,
+    /// Inputs asked before running a workflow that declares them (v0.7)
+    input_form: Option<InputForm>,
+    /// Step-through debugger shared with the runner (v0.7)
+    debugger: Option<Arc<Debugger>>,
+    /// Tasks paused by the debugger
+    debug_rx: Option<mpsc::Receiver<PausedTask>>,
+    /// Task waiting for a debugger command (one at a time)
+    paused_task: Option<PausedTask>,
     /// Should quit flag
     should_quit: bool,
     /// Workflow completed flag
@@ -229,18 +272,23 @@ pub struct App {
     stream_chunk_rx: mpsc::Receiver<StreamChunk>,
     /// Sender for streaming tokens (passed to ChatAgent)
     stream_chunk_tx: mpsc::Sender<StreamChunk>,
+    /// Merges streamed tokens per frame, draining more while behind (v0.7)
+    stream_coalescer: StreamCoalescer,
     // ═══ ChatAgent for full AI interface (Task 5.1) ═══
     /// ChatAgent for handling 5 verb commands in ChatView
     chat_agent: Option<ChatAgent>,
+    /// Provider limits shared by chat and workflow runs (v0.7)
+    /// Chat takes the interactive lane, ahead of workflow tasks
+    limiter: Arc<ConcurrencyLimiter>,
     // ═══ MCP Client Storage (v0.5.2) ═══
     /// MCP server configurations from loaded workflow
     mcp_configs: Option<FxHashMap<String, McpConfigInline>>,
     /// Cached MCP clients (lazy-initialized with OnceCell for thread-safe async init)
     mcp_client_cache: Arc<DashMap<String, Arc<OnceCell<Arc<McpClient>>>>>,
     // ═══ Background Task Tracking (v0.7.0) ═══
-    /// AbortHandles for tracked background tasks
-    /// Enables proper cancellation on app exit via abort_all()
-    background_handles: Arc<Mutex<Vec<AbortHandle>>>,
+    /// Spawned chat requests, MCP connects and workflow runs
+    /// View-scoped tasks are aborted on view switch, all of them on exit
+    tasks: TaskGroup,
 }
 
 impl App {
@@ -254,7 +302,20 @@ impl App {
                 path: workflow_path.display().to_string(),
             });
         }
+        Ok(Self::monitor(workflow_path))
+    }
+
+    /// Create a Monitor for a run followed from elsewhere (v0.7): shared by
+    /// `nika run --share`, or running on a remote daemon
+    ///
+    /// Events come from `url` (see `with_event_receiver`); there is no
+    /// local workflow file.
+    pub fn new_shared(url: &str) -> Self {
+        Self::monitor(Path::new(url))
+    }
 
+    /// Monitor view for `workflow_path`, which need not exist
+    fn monitor(workflow_path: &Path) -> Self {
         let state = TuiState::new(&workflow_path.display().to_string());
 
         // Initialize views
@@ -271,7 +332,7 @@ impl App {
         // Initialize ChatAgent (may fail if no API keys are set, but that's OK)
         let chat_agent = ChatAgent::new().ok();
 
-        Ok(Self {
+        Self {
             workflow_path: workflow_path.to_path_buf(),
             terminal: None,
             state,
@@ -279,6 +340,12 @@ impl App {
             theme: Theme::novanet(),
             event_rx: None,
             broadcast_rx: None,
+            approval_rx: None,
+            pending_approval: None,
+            input_form: None,
+            debugger: None,
+            debug_rx: None,
+            paused_task: None,
             should_quit: false,
             workflow_done: false,
             status_message: None,
@@ -294,11 +361,13 @@ impl App {
             llm_response_tx,
             stream_chunk_rx,
             stream_chunk_tx,
+            stream_coalescer: StreamCoalescer::new(),
             chat_agent,
+            limiter: project_limiter(),
             mcp_configs: None, // Loaded in init_mcp_clients()
             mcp_client_cache: Arc::new(DashMap::new()),
-            background_handles: Arc::new(Mutex::new(Vec::new())),
-        })
+            tasks: TaskGroup::new(),
+        }
     }
 
     /// Create a new TUI application in standalone mode (file browser)
@@ -328,6 +397,12 @@ impl App {
             theme: Theme::novanet(),
             event_rx: None,
             broadcast_rx: None,
+            approval_rx: None,
+            pending_approval: None,
+            input_form: None,
+            debugger: None,
+            debug_rx: None,
+            paused_task: None,
             should_quit: false,
             workflow_done: false,
             status_message: None,
@@ -343,10 +418,12 @@ impl App {
             llm_response_tx,
             stream_chunk_rx,
             stream_chunk_tx,
+            stream_coalescer: StreamCoalescer::new(),
             chat_agent,
+            limiter: project_limiter(),
             mcp_configs: None, // No workflow in standalone mode
             mcp_client_cache: Arc::new(DashMap::new()),
-            background_handles: Arc::new(Mutex::new(Vec::new())),
+            tasks: TaskGroup::new(),
         })
     }
 
@@ -390,6 +467,29 @@ impl App {
         self
     }
 
+    /// Set the approval receiver paired with `ApprovalGate::channel` (v0.7)
+    pub fn with_approval_receiver(mut self, rx: mpsc::Receiver<PendingApproval>) -> Self {
+        self.approval_rx = Some(rx);
+        self
+    }
+
+    /// Attach the step-through debugger and its paused-task receiver (v0.7)
+    pub fn with_debugger(
+        mut self,
+        debugger: Arc<Debugger>,
+        rx: mpsc::Receiver<PausedTask>,
+    ) -> Self {
+        self.state.breakpoints.extend(
+            debugger
+                .breakpoints()
+                .into_iter()
+                .map(Breakpoint::BeforeTask),
+        );
+        self.debugger = Some(debugger);
+        self.debug_rx = Some(rx);
+        self
+    }
+
     /// Set initial view (Chat, Home, Studio, Monitor)
     ///
     /// Used by CLI commands:
@@ -409,6 +509,14 @@ impl App {
         self
     }
 
+    /// Share provider limits with a workflow run started outside the App
+    ///
+    /// Chat requests then take the interactive lane of the runner's limits.
+    pub fn with_concurrency_limiter(mut self, limiter: Arc<ConcurrencyLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
     /// Set provider and model overrides for ChatAgent
     ///
     /// Used by `nika chat --provider claude --model claude-sonnet-4-20250514`.
@@ -464,7 +572,7 @@ impl App {
                 MessageRole::System => "System",
                 MessageRole::Tool => "Tool",
             };
-            context.push_str(&format!("{}: {}\n", role, msg.content));
+            context.push_str(&format!("{}: {}\n", role, msg.text()));
         }
         context.push_str("[Current request]\n");
         context
@@ -608,6 +716,29 @@ impl App {
                 events.push(event);
             }
         }
+        // Next approval question, once the previous one is answered
+        if self.pending_approval.is_none() {
+            if let Some(ref mut rx) = self.approval_rx {
+                self.pending_approval = rx.try_recv().ok();
+                self.state.approval = self.pending_approval.as_ref().map(|p| p.request.clone());
+            }
+        }
+        // Next debugger stop, once the previous one is resumed
+        if self.paused_task.is_none() {
+            if let Some(paused) = self.debug_rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+                let stop = &paused.stop;
+                self.state.debug_pause = Some(DebugPause {
+                    task_id: stop.task_id.to_string(),
+                    verb: stop.verb,
+                    rendered_label: stop.rendered_label(),
+                    inputs: serde_json::to_string_pretty(&stop.inputs).unwrap_or_default(),
+                    rendered: stop.rendered.clone(),
+                    editable: stop.editable,
+                    edit: None,
+                });
+                self.paused_task = Some(paused);
+            }
+        }
 
         // Process collected events (no borrow issues now)
         for event in events {
@@ -683,7 +814,8 @@ impl App {
         }
 
         // Poll streaming tokens for real-time display (Claude Code-like UX)
-        while let Ok(chunk) = self.stream_chunk_rx.try_recv() {
+        // Adjacent tokens arrive merged: one view update per frame, not per token
+        for chunk in self.stream_coalescer.drain(&mut self.stream_chunk_rx) {
             match chunk {
                 StreamChunk::Token(token) => {
                     // Append token to last message for real-time streaming
@@ -832,6 +964,9 @@ impl App {
     /// Render frame based on current view
     fn render_unified_frame(&mut self) -> Result<()> {
         let current_view = self.current_view;
+        if self.state.background_tasks.is_some() {
+            self.state.background_tasks = Some(self.tasks.live());
+        }
 
         if let Some(ref mut terminal) = self.terminal {
             // For Monitor view, use the existing full-screen render (backward compatible)
@@ -855,6 +990,7 @@ impl App {
             let chat_view = &self.chat_view;
             let home_view = &self.home_view;
             let studio_view = &self.studio_view;
+            let input_form = &self.input_form;
             let workflow_path = &self.state.workflow.path;
             let paused = self.state.paused;
             let input_mode = self.input_mode;
@@ -964,6 +1100,13 @@ impl App {
                         .metrics(metrics)
                         .custom_text(status_text);
                     frame.render_widget(status_bar, chunks[2]);
+
+                    if let Some(form) = input_form {
+                        form.render(frame, size, theme);
+                    }
+                    if let Some(tasks) = &state.background_tasks {
+                        render_tasks_overlay(frame, tasks, theme, size);
+                    }
                 })
                 .map_err(|e| NikaError::TuiError {
                     reason: format!("Failed to draw frame: {}", e),
@@ -996,6 +1139,65 @@ impl App {
             _ => {}
         }
 
+        // The input form takes every key but Ctrl+C (v0.7)
+        if self.input_form.is_some() {
+            if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+                return Action::Quit;
+            }
+            return self.handle_input_form_key(code);
+        }
+
+        // Background tasks overlay (v0.7): F12 from anywhere, even while typing
+        if code == KeyCode::F(12) {
+            return Action::ToggleTaskOverlay;
+        }
+        if self.state.background_tasks.is_some() {
+            return match code {
+                KeyCode::Esc => Action::ToggleTaskOverlay,
+                KeyCode::Char('x') => Action::CancelBackgroundTasks,
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+                _ => Action::Continue,
+            };
+        }
+
+        // A paused debugger stop takes its keys before anything else (v0.7)
+        if let Some(pause) = &self.state.debug_pause {
+            if pause.edit.is_some() {
+                return match code {
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+                    KeyCode::Char(c) => Action::DebugEditInput(c),
+                    KeyCode::Backspace => Action::DebugEditBackspace,
+                    KeyCode::Enter => Action::DebugEditSubmit,
+                    KeyCode::Esc => Action::DebugEditCancel,
+                    _ => Action::Continue,
+                };
+            }
+            if !modifiers.contains(KeyModifiers::CONTROL) {
+                match code {
+                    KeyCode::Char('s') | KeyCode::Enter => {
+                        return Action::DebugResume(DebugCommand::Step)
+                    }
+                    KeyCode::Char('c') => return Action::DebugResume(DebugCommand::Continue),
+                    KeyCode::Char('k') => return Action::DebugResume(DebugCommand::Skip),
+                    KeyCode::Char('p') if pause.editable => {
+                        return Action::DebugEdit(DebugEditKind::Prompt)
+                    }
+                    KeyCode::Char('o') => return Action::DebugEdit(DebugEditKind::Output),
+                    KeyCode::Char('b') => return Action::ToggleBreakpoint,
+                    _ => {}
+                }
+            }
+        }
+
+        // A pending approval takes y/n before anything else (v0.7)
+        if self.pending_approval.is_some() && !self.is_view_capturing_input() {
+            match code {
+                KeyCode::Char('y') => return Action::AnswerApproval(ApprovalDecision::Approve),
+                KeyCode::Char('n') => return Action::AnswerApproval(ApprovalDecision::Reject),
+                _ => {}
+            }
+        }
+
         // Global view-switching keys (work in all views, including during Chat input)
         // We check these first so users can always navigate views
         match code {
@@ -1106,13 +1308,14 @@ impl App {
             ViewAction::Quit => Action::Quit,
             ViewAction::SwitchView(view) => Action::SwitchView(view),
             ViewAction::RunWorkflow(path) => {
-                // Switch to Monitor view and store path for execution
-                self.workflow_path = path.clone();
-                self.current_view = TuiView::Monitor;
-                self.workflow_done = false;
-
-                // Trigger workflow execution asynchronously
-                self.start_workflow_execution(path);
+                // Declared inputs are asked first (v0.7)
+                match InputForm::open(&path) {
+                    Some(form) => {
+                        self.input_form = Some(form);
+                        self.set_status("Fill in the workflow inputs (Enter run, Esc cancel)");
+                    }
+                    None => self.run_workflow(path, BTreeMap::new()),
+                }
                 Action::Continue
             }
             ViewAction::OpenInStudio(path) => {
@@ -1135,34 +1338,43 @@ impl App {
 
                     // Spawn tracked task to call ChatAgent.infer() with timeout protection
                     let tx = self.llm_response_tx.clone();
+                    let limiter = Arc::clone(&self.limiter);
                     if self.ensure_chat_agent().is_some() {
-                        self.spawn_tracked(async move {
-                            match crate::tui::ChatAgent::new() {
-                                Ok(mut agent) => {
-                                    match timeout(INFER_TIMEOUT, agent.infer(&prompt_with_context))
+                        self.spawn_tracked(
+                            "chat message",
+                            TaskScope::View(TuiView::Chat),
+                            async move {
+                                match crate::tui::ChatAgent::new() {
+                                    Ok(agent) => {
+                                        let mut agent = agent.with_limiter(limiter);
+                                        match timeout(
+                                            INFER_TIMEOUT,
+                                            agent.infer(&prompt_with_context),
+                                        )
                                         .await
-                                    {
-                                        Ok(Ok(response)) => {
-                                            let _ = tx.send(response).await;
-                                        }
-                                        Ok(Err(e)) => {
-                                            let _ = tx.send(format!("Error: {}", e)).await;
-                                        }
-                                        Err(_) => {
-                                            let _ = tx
-                                                .send(format!(
-                                                    "Error: LLM inference timed out after {}s",
-                                                    INFER_TIMEOUT.as_secs()
-                                                ))
-                                                .await;
+                                        {
+                                            Ok(Ok(response)) => {
+                                                let _ = tx.send(response).await;
+                                            }
+                                            Ok(Err(e)) => {
+                                                let _ = tx.send(format!("Error: {}", e)).await;
+                                            }
+                                            Err(_) => {
+                                                let _ = tx
+                                                    .send(format!(
+                                                        "Error: LLM inference timed out after {}s",
+                                                        INFER_TIMEOUT.as_secs()
+                                                    ))
+                                                    .await;
+                                            }
                                         }
                                     }
+                                    Err(e) => {
+                                        let _ = tx.send(format!("Error: {}", e)).await;
+                                    }
                                 }
-                                Err(e) => {
-                                    let _ = tx.send(format!("Error: {}", e)).await;
-                                }
-                            }
-                        });
+                            },
+                        );
                     } else {
                         // No API key available
                         self.chat_view.messages.pop(); // Remove "Thinking..."
@@ -1233,7 +1445,7 @@ impl App {
 
     /// Switch to a specific view
     pub fn switch_view(&mut self, view: TuiView) {
-        self.current_view = view;
+        self.leave_view(view);
     }
 
     /// Handle keyboard input
@@ -1586,6 +1798,68 @@ impl App {
                 }
             }
             // Notification actions (TIER 3.4)
+            Action::AnswerApproval(decision) => {
+                if let Some(pending) = self.pending_approval.take() {
+                    self.state.approval = None;
+                    let msg = format!(
+                        "{} '{}'",
+                        if decision.is_approved() {
+                            "Approved"
+                        } else {
+                            "Rejected"
+                        },
+                        pending.request.task_id
+                    );
+                    pending.answer(decision);
+                    self.set_status(&msg);
+                }
+            }
+            Action::DebugResume(command) => self.resume_paused_task(command),
+            Action::DebugEdit(kind) => {
+                if let Some(pause) = self.state.debug_pause.as_mut() {
+                    let buffer = match kind {
+                        DebugEditKind::Prompt => pause.rendered.clone(),
+                        DebugEditKind::Output => String::new(),
+                    };
+                    pause.edit = Some(DebugEdit { kind, buffer });
+                }
+            }
+            Action::DebugEditInput(c) => {
+                if let Some(edit) = self.debug_edit_mut() {
+                    edit.buffer.push(c);
+                }
+            }
+            Action::DebugEditBackspace => {
+                if let Some(edit) = self.debug_edit_mut() {
+                    edit.buffer.pop();
+                }
+            }
+            Action::DebugEditSubmit => {
+                if let Some(edit) = self.state.debug_pause.as_mut().and_then(|p| p.edit.take()) {
+                    let command = match edit.kind {
+                        DebugEditKind::Prompt => DebugCommand::EditPrompt(edit.buffer),
+                        DebugEditKind::Output => DebugCommand::InjectOutput(edit.buffer),
+                    };
+                    self.resume_paused_task(command);
+                }
+            }
+            Action::DebugEditCancel => {
+                if let Some(pause) = self.state.debug_pause.as_mut() {
+                    pause.edit = None;
+                }
+            }
+            Action::ToggleTaskOverlay => {
+                self.state.background_tasks = match self.state.background_tasks {
+                    Some(_) => None,
+                    None => Some(self.tasks.live()),
+                };
+            }
+            Action::CancelBackgroundTasks => {
+                let count = self.tasks.cancel_all();
+                self.chat_view.is_streaming = false;
+                self.state.background_tasks = Some(Vec::new());
+                self.set_status(&format!("Cancelled {} background task(s)", count));
+            }
             Action::DismissNotification => {
                 let count = self.state.active_notification_count();
                 self.state.dismiss_notification();
@@ -1604,17 +1878,17 @@ impl App {
             }
             // View navigation actions (with Navigation 2.0 focus sync)
             Action::SwitchView(view) => {
-                self.current_view = view;
+                self.leave_view(view);
                 self.focus_state.reset_to_view(view);
                 self.input_mode = InputMode::Normal;
             }
             Action::NextView => {
-                self.current_view = self.current_view.next();
+                self.leave_view(self.current_view.next());
                 self.focus_state.reset_to_view(self.current_view);
                 self.input_mode = InputMode::Normal;
             }
             Action::PrevView => {
-                self.current_view = self.current_view.prev();
+                self.leave_view(self.current_view.prev());
                 self.focus_state.reset_to_view(self.current_view);
                 self.input_mode = InputMode::Normal;
             }
@@ -1648,25 +1922,29 @@ impl App {
                     // Spawn tracked task to call LLM with timeout protection
                     let tx = self.llm_response_tx.clone();
                     let prompt = message.clone();
-                    self.spawn_tracked(async move {
-                        let provider = RigProvider::openai();
-                        match timeout(INFER_TIMEOUT, provider.infer(&prompt, None)).await {
-                            Ok(Ok(response)) => {
-                                let _ = tx.send(response).await;
-                            }
-                            Ok(Err(e)) => {
-                                let _ = tx.send(format!("Error: {}", e)).await;
-                            }
-                            Err(_) => {
-                                let _ = tx
-                                    .send(format!(
-                                        "Error: LLM inference timed out after {}s",
-                                        INFER_TIMEOUT.as_secs()
-                                    ))
-                                    .await;
+                    self.spawn_tracked(
+                        "chat overlay message",
+                        TaskScope::View(TuiView::Monitor),
+                        async move {
+                            let provider = RigProvider::openai();
+                            match timeout(INFER_TIMEOUT, provider.infer(&prompt, None)).await {
+                                Ok(Ok(response)) => {
+                                    let _ = tx.send(response).await;
+                                }
+                                Ok(Err(e)) => {
+                                    let _ = tx.send(format!("Error: {}", e)).await;
+                                }
+                                Err(_) => {
+                                    let _ = tx
+                                        .send(format!(
+                                            "Error: LLM inference timed out after {}s",
+                                            INFER_TIMEOUT.as_secs()
+                                        ))
+                                        .await;
+                                }
                             }
-                        }
-                    });
+                        },
+                    );
                 }
             }
             Action::ChatOverlayClear => {
@@ -1764,35 +2042,55 @@ impl App {
     }
 
     /// Toggle breakpoint on the current task (TIER 2.3)
+    ///
+    /// With the debugger attached, the breakpoint also pauses the run (v0.7).
     fn toggle_breakpoint(&mut self) {
-        use super::state::Breakpoint;
-
-        // Get current task from state
-        if let Some(ref task_id) = self.state.current_task.clone() {
-            let bp = Breakpoint::BeforeTask(task_id.clone());
-            if self.state.breakpoints.contains(&bp) {
-                self.state.breakpoints.remove(&bp);
-                self.set_status(&format!("🔴 Breakpoint removed: {}", task_id));
-            } else {
-                self.state.breakpoints.insert(bp);
-                self.set_status(&format!("🔴 Breakpoint set: {}", task_id));
-            }
-        } else if !self.state.task_order.is_empty() {
-            // No current task, use first task
-            let task_id = self.state.task_order[0].clone();
-            let bp = Breakpoint::BeforeTask(task_id.clone());
-            if self.state.breakpoints.contains(&bp) {
-                self.state.breakpoints.remove(&bp);
-                self.set_status(&format!("🔴 Breakpoint removed: {}", task_id));
-            } else {
-                self.state.breakpoints.insert(bp);
-                self.set_status(&format!("🔴 Breakpoint set: {}", task_id));
-            }
-        } else {
+        // Paused task first, then current task, then the first task
+        let task_id = self
+            .state
+            .debug_pause
+            .as_ref()
+            .map(|pause| pause.task_id.clone())
+            .or_else(|| self.state.current_task.clone())
+            .or_else(|| self.state.task_order.first().cloned());
+        let Some(task_id) = task_id else {
             self.set_status("No tasks to set breakpoint on");
+            return;
+        };
+
+        let bp = Breakpoint::BeforeTask(task_id.clone());
+        if self.state.breakpoints.contains(&bp) {
+            self.state.breakpoints.remove(&bp);
+            self.set_status(&format!("🔴 Breakpoint removed: {}", task_id));
+        } else {
+            self.state.breakpoints.insert(bp);
+            self.set_status(&format!("🔴 Breakpoint set: {}", task_id));
+        }
+        if let Some(debugger) = &self.debugger {
+            debugger.toggle_breakpoint(&task_id);
         }
     }
 
+    /// Answer the paused debugger stop (v0.7)
+    fn resume_paused_task(&mut self, command: DebugCommand) {
+        self.state.debug_pause = None;
+        if let Some(paused) = self.paused_task.take() {
+            self.set_status(&format!(
+                "🐞 {} '{}'",
+                command.as_str(),
+                paused.stop.task_id
+            ));
+            paused.resume(command);
+        }
+    }
+
+    fn debug_edit_mut(&mut self) -> Option<&mut DebugEdit> {
+        self.state
+            .debug_pause
+            .as_mut()
+            .and_then(|pause| pause.edit.as_mut())
+    }
+
     /// Toggle theme between dark and light (TIER 2.4)
     fn toggle_theme(&mut self) {
         self.state.theme_mode = self.state.theme_mode.toggle();
@@ -1882,15 +2180,18 @@ impl App {
         // Only use stream_tx - streaming handles message display
         // (llm_response_tx would cause duplicate messages)
         let stream_tx = self.stream_chunk_tx.clone();
+        let limiter = Arc::clone(&self.limiter);
 
         // Check if agent exists or can be created
         if self.ensure_chat_agent().is_some() {
-            self.spawn_tracked(async move {
+            self.spawn_tracked("/infer", TaskScope::View(TuiView::Chat), async move {
                 // Create a new agent for the async task (ChatAgent is not Send)
                 // Wire streaming for real-time token display (Claude Code-like UX)
                 match ChatAgent::new() {
                     Ok(agent) => {
-                        let mut agent = agent.with_stream_chunks(stream_tx.clone());
+                        let mut agent = agent
+                            .with_stream_chunks(stream_tx.clone())
+                            .with_limiter(limiter);
                         // Wrap inference with timeout protection
                         match timeout(INFER_TIMEOUT, agent.infer(&prompt_with_context)).await {
                             Ok(Ok(_response)) => {
@@ -1945,29 +2246,33 @@ impl App {
 
         // Spawn tracked task for shell execution with timeout protection
         let tx = self.llm_response_tx.clone();
-        self.spawn_tracked(async move {
-            match ChatAgent::new() {
-                Ok(agent) => match timeout(EXEC_TIMEOUT, agent.exec_command(&command)).await {
-                    Ok(Ok(output)) => {
-                        let _ = tx.send(output).await;
-                    }
-                    Ok(Err(e)) => {
+        self.spawn_tracked(
+            format!("/exec {}", command),
+            TaskScope::View(TuiView::Chat),
+            async move {
+                match ChatAgent::new() {
+                    Ok(agent) => match timeout(EXEC_TIMEOUT, agent.exec_command(&command)).await {
+                        Ok(Ok(output)) => {
+                            let _ = tx.send(output).await;
+                        }
+                        Ok(Err(e)) => {
+                            let _ = tx.send(format!("Error: {}", e)).await;
+                        }
+                        Err(_) => {
+                            let _ = tx
+                                .send(format!(
+                                    "Error: Command timed out after {}s",
+                                    EXEC_TIMEOUT.as_secs()
+                                ))
+                                .await;
+                        }
+                    },
+                    Err(e) => {
                         let _ = tx.send(format!("Error: {}", e)).await;
                     }
-                    Err(_) => {
-                        let _ = tx
-                            .send(format!(
-                                "Error: Command timed out after {}s",
-                                EXEC_TIMEOUT.as_secs()
-                            ))
-                            .await;
-                    }
-                },
-                Err(e) => {
-                    let _ = tx.send(format!("Error: {}", e)).await;
                 }
-            }
-        });
+            },
+        );
     }
 
     /// Handle /fetch command - HTTP request
@@ -1984,41 +2289,45 @@ impl App {
 
         // Spawn tracked task for HTTP request with timeout protection
         let tx = self.llm_response_tx.clone();
-        self.spawn_tracked(async move {
-            match ChatAgent::new() {
-                Ok(agent) => {
-                    match timeout(FETCH_TIMEOUT, agent.fetch(&url, &method)).await {
-                        Ok(Ok(response)) => {
-                            // Truncate very long responses
-                            let truncated = if response.len() > 2000 {
-                                format!(
-                                    "{}...\n\n[Truncated, {} bytes total]",
-                                    &response[..2000],
-                                    response.len()
-                                )
-                            } else {
-                                response
-                            };
-                            let _ = tx.send(truncated).await;
-                        }
-                        Ok(Err(e)) => {
-                            let _ = tx.send(format!("Error: {}", e)).await;
-                        }
-                        Err(_) => {
-                            let _ = tx
-                                .send(format!(
-                                    "Error: HTTP request timed out after {}s",
-                                    FETCH_TIMEOUT.as_secs()
-                                ))
-                                .await;
+        self.spawn_tracked(
+            format!("/fetch {} {}", method, url),
+            TaskScope::View(TuiView::Chat),
+            async move {
+                match ChatAgent::new() {
+                    Ok(agent) => {
+                        match timeout(FETCH_TIMEOUT, agent.fetch(&url, &method)).await {
+                            Ok(Ok(response)) => {
+                                // Truncate very long responses
+                                let truncated = if response.len() > 2000 {
+                                    format!(
+                                        "{}...\n\n[Truncated, {} bytes total]",
+                                        &response[..2000],
+                                        response.len()
+                                    )
+                                } else {
+                                    response
+                                };
+                                let _ = tx.send(truncated).await;
+                            }
+                            Ok(Err(e)) => {
+                                let _ = tx.send(format!("Error: {}", e)).await;
+                            }
+                            Err(_) => {
+                                let _ = tx
+                                    .send(format!(
+                                        "Error: HTTP request timed out after {}s",
+                                        FETCH_TIMEOUT.as_secs()
+                                    ))
+                                    .await;
+                            }
                         }
                     }
+                    Err(e) => {
+                        let _ = tx.send(format!("Error: {}", e)).await;
+                    }
                 }
-                Err(e) => {
-                    let _ = tx.send(format!("Error: {}", e)).await;
-                }
-            }
-        });
+            },
+        );
     }
 
     /// Handle /invoke command - MCP tool call
@@ -2077,7 +2386,7 @@ impl App {
         // Spawn tracked task to connect (if needed) and call the tool
         let tool_name = tool.clone();
         let server_name_clone = server_name.clone();
-        self.spawn_tracked(async move {
+        self.spawn_tracked(format!("/invoke {}:{}", server_name, tool), TaskScope::View(TuiView::Chat), async move {
             // Lazy-initialize MCP client connection
             let client = {
                 let cell = mcp_client_cache
@@ -2256,9 +2565,10 @@ impl App {
         // Clone channel senders for async task
         let response_tx = self.llm_response_tx.clone();
         let status_tx = self.stream_chunk_tx.clone();
+        let limiter = Arc::clone(&self.limiter);
 
         // Spawn tracked task to connect MCP servers and run the agent
-        self.spawn_tracked(async move {
+        self.spawn_tracked(format!("/agent {}", task_id), TaskScope::View(TuiView::Chat), async move {
             // Connect MCP servers lazily
             let mut mcp_clients: FxHashMap<String, Arc<McpClient>> = FxHashMap::default();
             for server_name in &mcp_server_names {
@@ -2343,7 +2653,17 @@ impl App {
                 }
             };
 
-            match agent.run_auto().await {
+            // Interactive lane: don't wait behind a running workflow
+            let permit = match RigProvider::auto() {
+                Some(provider) => limiter.acquire_as(provider.name(), Priority::Interactive).await,
+                None => None,
+            };
+            let result = agent.run_auto().await;
+            if let Some(permit) = permit {
+                permit.finish(CallOutcome::from_result(&result));
+            }
+
+            match result {
                 Ok(result) => {
                     // Format the response with status and metrics
                     let status_emoji = match result.status {
@@ -2351,7 +2671,9 @@ impl App {
                         RigAgentStatus::MaxTurnsReached => "⏱️",
                         RigAgentStatus::StopConditionMet => "🛑",
                         RigAgentStatus::Failed => "❌",
-                        RigAgentStatus::TokenBudgetExceeded => "💰",
+                        RigAgentStatus::TokenBudgetExceeded
+                        | RigAgentStatus::CostBudgetExceeded
+                        | RigAgentStatus::ToolBudgetExceeded => "💰",
                     };
 
                     // Extract final output text
@@ -2567,12 +2889,44 @@ impl App {
         self.set_status("Chat history cleared");
     }
 
+    /// Switch to Monitor and run the workflow at `path`
+    fn run_workflow(&mut self, path: PathBuf, inputs: BTreeMap<String, String>) {
+        self.workflow_path = path.clone();
+        self.current_view = TuiView::Monitor;
+        self.workflow_done = false;
+
+        // Trigger workflow execution asynchronously
+        self.start_workflow_execution(path, inputs);
+    }
+
+    /// Route a key to the open input form (v0.7)
+    fn handle_input_form_key(&mut self, code: KeyCode) -> Action {
+        let Some(form) = self.input_form.as_mut() else {
+            return Action::Continue;
+        };
+        match form.handle_key(code) {
+            FormKey::Continue => {}
+            FormKey::Cancel => {
+                self.input_form = None;
+                self.set_status("Run cancelled");
+            }
+            FormKey::Submit(values) => {
+                if let Some(form) = self.input_form.take() {
+                    input_form::remember(Path::new(INPUTS_MEMORY), &form.path, &values);
+                    self.run_workflow(form.path, values);
+                }
+            }
+        }
+        Action::Continue
+    }
+
     /// Start workflow execution asynchronously (v0.5.2)
     ///
     /// Loads the workflow from the given path, creates a Runner with broadcast
     /// EventLog, and spawns execution in a background task. Events are routed
-    /// to the TUI state via the broadcast channel.
-    fn start_workflow_execution(&mut self, path: PathBuf) {
+    /// to the TUI state via the broadcast channel. `inputs` are the values of
+    /// the workflow's `inputs:` (v0.7).
+    fn start_workflow_execution(&mut self, path: PathBuf, inputs: BTreeMap<String, String>) {
         // Reset state for new workflow
         self.state.tasks.clear();
         self.set_status(&format!("🦋 Nika loading: {}", path.display()));
@@ -2582,97 +2936,130 @@ impl App {
 
         // Create broadcast channel for events
         let (event_log, event_rx) = EventLog::new_with_broadcast();
+        crate::util::crash::watch(&event_log);
 
         // Store the receiver for poll_events()
         self.broadcast_rx = Some(event_rx);
 
+        // approve: tasks are answered with y/n (v0.7)
+        let (approval_tx, approval_rx) = mpsc::channel(4);
+        self.approval_rx = Some(approval_rx);
+        self.pending_approval = None;
+        self.state.approval = None;
+        let limiter = Arc::clone(&self.limiter);
+
         // Spawn tracked task to load and run workflow
-        self.spawn_tracked(async move {
-            // Read workflow file
-            let yaml = match tokio::fs::read_to_string(&workflow_path).await {
-                Ok(content) => content,
-                Err(e) => {
-                    event_log.emit(EventKind::WorkflowFailed {
-                        error: format!("Failed to read file: {}", e),
-                        failed_task: None,
-                    });
-                    return;
-                }
-            };
+        self.spawn_tracked(
+            format!("workflow {}", path.display()),
+            TaskScope::App,
+            async move {
+                // Read workflow file
+                let yaml = match tokio::fs::read_to_string(&workflow_path).await {
+                    Ok(content) => content,
+                    Err(e) => {
+                        event_log.emit(EventKind::WorkflowFailed {
+                            error: format!("Failed to read file: {}", e),
+                            failed_task: None,
+                        });
+                        return;
+                    }
+                };
 
-            // Validate YAML schema
-            let validator: WorkflowSchemaValidator = match WorkflowSchemaValidator::new() {
-                Ok(v) => v,
-                Err(e) => {
+                // Validate YAML schema
+                let validator: WorkflowSchemaValidator = match WorkflowSchemaValidator::new() {
+                    Ok(v) => v,
+                    Err(e) => {
+                        event_log.emit(EventKind::WorkflowFailed {
+                            error: format!("Schema validator error: {}", e),
+                            failed_task: None,
+                        });
+                        return;
+                    }
+                };
+
+                if let Err(e) = validator.validate_yaml(&yaml) {
                     event_log.emit(EventKind::WorkflowFailed {
-                        error: format!("Schema validator error: {}", e),
+                        error: format!("Schema validation failed: {}", e),
                         failed_task: None,
                     });
                     return;
                 }
-            };
 
-            if let Err(e) = validator.validate_yaml(&yaml) {
-                event_log.emit(EventKind::WorkflowFailed {
-                    error: format!("Schema validation failed: {}", e),
-                    failed_task: None,
-                });
-                return;
-            }
+                // Parse workflow
+                let workflow: Workflow = match serde_yaml::from_str(&yaml) {
+                    Ok(w) => w,
+                    Err(e) => {
+                        event_log.emit(EventKind::WorkflowFailed {
+                            error: format!("YAML parse error: {}", e),
+                            failed_task: None,
+                        });
+                        return;
+                    }
+                };
 
-            // Parse workflow
-            let workflow: Workflow = match serde_yaml::from_str(&yaml) {
-                Ok(w) => w,
-                Err(e) => {
+                // Validate schema version
+                if let Err(e) = workflow.validate_schema() {
                     event_log.emit(EventKind::WorkflowFailed {
-                        error: format!("YAML parse error: {}", e),
+                        error: format!("Schema version error: {}", e),
                         failed_task: None,
                     });
                     return;
                 }
-            };
-
-            // Validate schema version
-            if let Err(e) = workflow.validate_schema() {
-                event_log.emit(EventKind::WorkflowFailed {
-                    error: format!("Schema version error: {}", e),
-                    failed_task: None,
-                });
-                return;
-            }
 
-            // Create and run workflow with timeout protection
-            let runner = Runner::with_event_log(workflow, event_log);
-            match timeout(WORKFLOW_TIMEOUT, runner.run()).await {
-                Ok(Ok(output)) => {
-                    tracing::info!("Workflow completed: {} chars output", output.len());
-                }
-                Ok(Err(e)) => {
-                    tracing::error!("Workflow execution failed: {}", e);
-                }
-                Err(_) => {
-                    tracing::error!("Workflow timed out after {}s", WORKFLOW_TIMEOUT.as_secs());
+                // Create and run workflow with timeout protection
+                let gate = approval_gate(approval_tx, &workflow);
+                let config = NikaConfig::load().unwrap_or_default();
+                let runner = match Runner::with_event_log(workflow, event_log.clone())
+                    .with_store(&config.store)
+                    .and_then(|runner| runner.with_key_rotation(&config.key_rotation))
+                {
+                    Ok(runner) => runner
+                        .with_router(&config.router)
+                        .with_concurrency_limiter(limiter)
+                        .with_web_search(&config.web_search)
+                        .with_size_limits(&config.size_limits)
+                        .with_memory(&config.memory)
+                        .with_approval_gate(gate)
+                        .with_state_store(Arc::new(StateStore::project()))
+                        .with_vector_store(Arc::new(VectorStore::project()))
+                        .with_http_cache(Arc::new(HttpCache::project()))
+                        .with_inputs(inputs),
+                    Err(e) => {
+                        event_log.emit(EventKind::WorkflowFailed {
+                            error: e.to_string(),
+                            failed_task: None,
+                        });
+                        return;
+                    }
+                };
+                match timeout(WORKFLOW_TIMEOUT, runner.run()).await {
+                    Ok(Ok(output)) => {
+                        tracing::info!("Workflow completed: {} chars output", output.len());
+                    }
+                    Ok(Err(e)) => {
+                        tracing::error!("Workflow execution failed: {}", e);
+                    }
+                    Err(_) => {
+                        tracing::error!("Workflow timed out after {}s", WORKFLOW_TIMEOUT.as_secs());
+                    }
                 }
-            }
-        });
+            },
+        );
 
         self.set_status(&format!("🌌 Warping through: {}", path.display()));
     }
 
     /// Spawn a tracked background task that will be cancelled on cleanup
     ///
-    /// Use this instead of raw `tokio::spawn()` for tasks that should be
-    /// cleaned up when the TUI exits. The task's AbortHandle is stored for
-    /// later cancellation via `cancel_background_tasks()`.
-    fn spawn_tracked<F>(&self, future: F)
+    /// Use this instead of raw `tokio::spawn()` for every task the TUI
+    /// starts. `scope` decides when it is aborted: on leaving its view, or
+    /// only on exit (`cancel_background_tasks()`). `label` is shown in the
+    /// background tasks overlay (F12).
+    fn spawn_tracked<F>(&self, label: impl Into<String>, scope: TaskScope, future: F)
     where
         F: std::future::Future<Output = ()> + Send + 'static,
     {
-        let handle = tokio::spawn(future);
-        self.background_handles
-            .lock()
-            .expect("background_handles mutex poisoned")
-            .push(handle.abort_handle());
+        self.tasks.spawn(label, scope, future);
     }
 
     /// Cancel all background tasks
@@ -2680,17 +3067,32 @@ impl App {
     /// Should be called during cleanup to ensure graceful shutdown.
     /// Tasks are aborted immediately; no waiting for completion.
     fn cancel_background_tasks(&self) {
-        let handles = self
-            .background_handles
-            .lock()
-            .expect("background_handles mutex poisoned");
-        let count = handles.len();
-        for handle in handles.iter() {
-            handle.abort();
-        }
+        let count = self.tasks.cancel_all();
         tracing::debug!("Aborted {} background tasks", count);
     }
 
+    /// Switch views, aborting tasks scoped to the view being left
+    fn leave_view(&mut self, next: TuiView) {
+        let previous = self.current_view;
+        self.current_view = next;
+        if previous == next {
+            return;
+        }
+        let cancelled = self.tasks.cancel_view(previous);
+        if cancelled == 0 {
+            return;
+        }
+        tracing::debug!(?previous, "Aborted {} view tasks", cancelled);
+        if previous == TuiView::Chat {
+            self.chat_view.is_streaming = false;
+            self.chat_view.add_system_message(format!(
+                "Cancelled {} running request(s) on leaving chat",
+                cancelled
+            ));
+        }
+        self.set_status(&format!("Cancelled {} background task(s)", cancelled));
+    }
+
     /// Cleanup terminal state
     fn cleanup(&mut self) -> Result<()> {
         // Note: Background tasks are cancelled in run_unified() after this,
@@ -2738,7 +3140,28 @@ impl Drop for App {
 // RENDER FUNCTIONS (standalone to avoid borrow checker issues)
 // ═══════════════════════════════════════════════════════════════════
 
+/// Provider limits from `[concurrency]` in the global config
+fn project_limiter() -> Arc<ConcurrencyLimiter> {
+    let config = NikaConfig::load().unwrap_or_default();
+    Arc::new(ConcurrencyLimiter::new(&config.concurrency))
+}
+
 /// Render a frame
+/// Approval gate answered from the TUI, checkpointed like `nika run` (v0.7)
+pub(crate) fn approval_gate(
+    tx: mpsc::Sender<PendingApproval>,
+    workflow: &Workflow,
+) -> ApprovalGate {
+    let gate = ApprovalGate::channel(tx);
+    match ApprovalCheckpoint::for_workflow(workflow) {
+        Ok(checkpoint) => gate.with_checkpoint(checkpoint),
+        Err(e) => {
+            tracing::warn!(error = %e, "Approval checkpoint unreadable, starting fresh");
+            gate
+        }
+    }
+}
+
 fn render_frame(frame: &mut Frame, state: &TuiState, theme: &Theme) {
     let size = frame.area();
 
@@ -2778,6 +3201,136 @@ fn render_frame(frame: &mut Frame, state: &TuiState, theme: &Theme) {
             }
         }
     }
+
+    // Debugger stops and approvals stay on top of everything (v0.7)
+    if let Some(pause) = &state.debug_pause {
+        render_debug_overlay(frame, pause, theme, size);
+    }
+    if let Some(request) = &state.approval {
+        render_approval_overlay(frame, request, theme, size);
+    }
+    if let Some(tasks) = &state.background_tasks {
+        render_tasks_overlay(frame, tasks, theme, size);
+    }
+}
+
+/// Render the step-through debugger stop (v0.7)
+fn render_debug_overlay(frame: &mut Frame, pause: &DebugPause, theme: &Theme, area: Rect) {
+    use ratatui::widgets::{Clear, Wrap};
+
+    let (title, body, keys) = match &pause.edit {
+        Some(edit) => {
+            let label = match edit.kind {
+                DebugEditKind::Prompt => pause.rendered_label,
+                DebugEditKind::Output => "output",
+            };
+            (
+                format!(" 🐞 {} [{}] - edit {} ", pause.task_id, pause.verb, label),
+                format!("{}▏", edit.buffer),
+                "Enter submit  Esc cancel",
+            )
+        }
+        None => (
+            format!(" 🐞 Paused before {} [{}] ", pause.task_id, pause.verb),
+            format!(
+                "Inputs:\n{}\n\n{}:\n{}",
+                pause.inputs,
+                capitalize(pause.rendered_label),
+                pause.rendered
+            ),
+            if pause.editable {
+                "s/Enter step  c continue  k skip  p edit  o inject output  b breakpoint"
+            } else {
+                "s/Enter step  c continue  k skip  o inject output  b breakpoint"
+            },
+        ),
+    };
+
+    let overlay = centered_rect(70, 60, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(format!(" {} ", keys))
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let paragraph = Paragraph::new(body)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .style(theme.text_style());
+
+    frame.render_widget(Clear, overlay);
+    frame.render_widget(paragraph, overlay);
+}
+
+/// Render the question of a pending approval (v0.7)
+fn render_approval_overlay(
+    frame: &mut Frame,
+    request: &ApprovalRequest,
+    theme: &Theme,
+    area: Rect,
+) {
+    use ratatui::widgets::{Clear, Wrap};
+
+    let overlay = centered_rect(60, 40, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" ✋ Approval required [{}] ", request.task_id))
+        .title_bottom(" y approve  n reject ")
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let paragraph = Paragraph::new(request.prompt.as_str())
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .style(theme.text_style());
+
+    frame.render_widget(Clear, overlay);
+    frame.render_widget(paragraph, overlay);
+}
+
+/// Render the live background tasks overlay (F12)
+fn render_tasks_overlay(frame: &mut Frame, tasks: &[BackgroundTask], theme: &Theme, area: Rect) {
+    use ratatui::widgets::Clear;
+
+    let body = if tasks.is_empty() {
+        "No background tasks running".to_string()
+    } else {
+        tasks
+            .iter()
+            .map(|task| {
+                let scope = match task.scope {
+                    TaskScope::View(view) => format!("{:?}", view).to_lowercase(),
+                    TaskScope::App => "app".to_string(),
+                };
+                format!(
+                    "#{:<4} {:>6.1}s  {:<8} {}",
+                    task.id,
+                    task.elapsed.as_secs_f64(),
+                    scope,
+                    task.label
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let overlay = centered_rect(60, 40, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Background tasks ({}) ", tasks.len()))
+        .title_bottom(" x abort all  Esc/F12 close ")
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let paragraph = Paragraph::new(body).block(block).style(theme.text_style());
+
+    frame.render_widget(Clear, overlay);
+    frame.render_widget(paragraph, overlay);
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
 }
 
 /// Render a single panel
@@ -2817,12 +3370,14 @@ fn render_help_overlay(frame: &mut Frame, theme: &Theme, area: Rect) {
 ║  Tab        Next view (or next panel in Monitor)                             ║
 ║  ?/F1       This help     m  Metrics       s  Settings     Esc  Close        ║
 ║  Ctrl+C     Quit app      q  Quit (when not editing)                         ║
+║  F12        Background tasks (x aborts all)                                  ║
 ║                                                                               ║
 ║  ═══ MONITOR VIEW ═════════════════════════════════════════════════════════  ║
 ║  1-4        Focus panel   h/l  Prev/Next panel    t/Tab  Cycle tabs          ║
 ║  j/k ↑↓     Scroll        Space  Pause    Enter  Step (when paused)          ║
 ║  y          Copy output   e  Export trace    r  Retry    /  Filter           ║
 ║  b          Breakpoint    T  Theme toggle    n/N  Dismiss notifications      ║
+║  Debugger   s/Enter Step  c Continue  k Skip  p Edit prompt  o Inject output ║
 ║                                                                               ║
 ║  ═══ CHAT VIEW ════════════════════════════════════════════════════════════  ║
 ║  i          Enter insert mode (start typing)                                 ║
@@ -3328,7 +3883,7 @@ fn render_chat_overlay(frame: &mut Frame, state: &TuiState, theme: &Theme, area:
 // ═══════════════════════════════════════════════════════════════════
 
 /// Create a centered rectangle
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+pub(super) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -3976,9 +4531,8 @@ mod tests {
         std::fs::write(&workflow_path, "schema: test").unwrap();
         let app = App::new(&workflow_path).unwrap();
 
-        // Background handles should start empty
-        let handles = app.background_handles.lock().unwrap();
-        assert!(handles.is_empty());
+        // Background tasks should start empty
+        assert!(app.tasks.live().is_empty());
     }
 
     #[tokio::test]
@@ -3988,17 +4542,18 @@ mod tests {
         std::fs::write(&workflow_path, "schema: test").unwrap();
         let app = App::new(&workflow_path).unwrap();
 
-        // Spawn a quick task
-        app.spawn_tracked(async {
-            // Empty task for testing
+        // Spawn a task that is still running when checked
+        app.spawn_tracked("test", TaskScope::App, async {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
         });
 
         // Give it a moment to spawn
         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
 
-        // Should have one handle
-        let handles = app.background_handles.lock().unwrap();
-        assert_eq!(handles.len(), 1, "spawn_tracked should add one handle");
+        // Should have one live task
+        let tasks = app.tasks.live();
+        assert_eq!(tasks.len(), 1, "spawn_tracked should add one task");
+        assert_eq!(tasks[0].label, "test");
     }
 
     #[tokio::test]
@@ -4010,7 +4565,7 @@ mod tests {
 
         // Spawn multiple tasks
         for _ in 0..5 {
-            app.spawn_tracked(async {
+            app.spawn_tracked("test", TaskScope::App, async {
                 tokio::time::sleep(std::time::Duration::from_millis(100)).await;
             });
         }
@@ -4018,9 +4573,12 @@ mod tests {
         // Give them a moment to spawn
         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
 
-        // Should have 5 handles
-        let handles = app.background_handles.lock().unwrap();
-        assert_eq!(handles.len(), 5, "spawn_tracked should track all tasks");
+        // Should have 5 live tasks
+        assert_eq!(
+            app.tasks.live().len(),
+            5,
+            "spawn_tracked should track all tasks"
+        );
     }
 
     #[tokio::test]
@@ -4035,7 +4593,7 @@ mod tests {
         let completed = Arc::new(AtomicBool::new(false));
         let completed_clone = Arc::clone(&completed);
 
-        app.spawn_tracked(async move {
+        app.spawn_tracked("test", TaskScope::App, async move {
             tokio::time::sleep(std::time::Duration::from_secs(10)).await;
             completed_clone.store(true, Ordering::SeqCst);
         });
@@ -4056,6 +4614,115 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_leaving_chat_cancels_chat_tasks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let workflow_path = temp_dir.path().join("test.yaml");
+        std::fs::write(&workflow_path, "schema: test").unwrap();
+        let mut app = App::new(&workflow_path).unwrap();
+        app.apply_action(Action::SwitchView(TuiView::Chat));
+
+        let pending = || std::future::pending::<()>();
+        app.spawn_tracked("/infer", TaskScope::View(TuiView::Chat), pending());
+        app.spawn_tracked("workflow", TaskScope::App, pending());
+
+        // Overlay lists both, refreshed on open
+        let action = app.handle_unified_key(KeyCode::F(12), KeyModifiers::empty());
+        app.apply_action(action);
+        assert_eq!(app.state.background_tasks.as_ref().map(Vec::len), Some(2));
+        let action = app.handle_unified_key(KeyCode::Esc, KeyModifiers::empty());
+        app.apply_action(action);
+        assert!(app.state.background_tasks.is_none());
+
+        // Leaving chat aborts the chat request, the workflow keeps running
+        app.apply_action(Action::SwitchView(TuiView::Monitor));
+        let labels: Vec<_> = app.tasks.live().into_iter().map(|t| t.label).collect();
+        assert_eq!(labels, vec!["workflow"]);
+        assert!(!app.chat_view.is_streaming);
+
+        app.cancel_background_tasks();
+        assert!(app.tasks.live().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_debug_overlay_edits_prompt() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let workflow_path = temp_dir.path().join("test.yaml");
+        std::fs::write(&workflow_path, "schema: test").unwrap();
+        let (tx, rx) = mpsc::channel(1);
+        let debugger = Arc::new(Debugger::new(tx));
+        let mut app = App::new(&workflow_path)
+            .unwrap()
+            .with_debugger(Arc::clone(&debugger), rx);
+
+        let pause = tokio::spawn(async move {
+            debugger
+                .pause(crate::runtime::DebugStop {
+                    task_id: Arc::from("greet"),
+                    verb: "infer",
+                    inputs: serde_json::json!({}),
+                    rendered: "Say hi".to_string(),
+                    editable: true,
+                })
+                .await
+        });
+        while app.state.debug_pause.is_none() {
+            tokio::task::yield_now().await;
+            app.poll_runtime_events();
+        }
+
+        // p pre-fills the rendered prompt; typed keys go to the buffer, not views
+        let action = app.handle_unified_key(KeyCode::Char('p'), KeyModifiers::empty());
+        app.apply_action(action);
+        for c in " now".chars() {
+            let action = app.handle_unified_key(KeyCode::Char(c), KeyModifiers::empty());
+            assert_eq!(action, Action::DebugEditInput(c));
+            app.apply_action(action);
+        }
+        let action = app.handle_unified_key(KeyCode::Enter, KeyModifiers::empty());
+        app.apply_action(action);
+
+        assert!(app.state.debug_pause.is_none());
+        assert_eq!(
+            pause.await.unwrap(),
+            DebugCommand::EditPrompt("Say hi now".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_approval_overlay_answers_tool_call() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let workflow_path = temp_dir.path().join("test.yaml");
+        std::fs::write(&workflow_path, "schema: test").unwrap();
+        let (tx, rx) = mpsc::channel(1);
+        let gate = ApprovalGate::channel(tx);
+        let mut app = App::new(&workflow_path).unwrap().with_approval_receiver(rx);
+
+        let ask = tokio::spawn(async move {
+            gate.ask(ApprovalRequest {
+                task_id: Arc::from("research"),
+                prompt: "Allow tool call `novanet_search`?\n{}".to_string(),
+            })
+            .await
+        });
+        while app.state.approval.is_none() {
+            tokio::task::yield_now().await;
+            app.poll_runtime_events();
+        }
+        assert!(app
+            .state
+            .approval
+            .as_ref()
+            .unwrap()
+            .prompt
+            .contains("novanet_search"));
+
+        let action = app.handle_unified_key(KeyCode::Char('n'), KeyModifiers::empty());
+        app.apply_action(action);
+        assert!(app.state.approval.is_none());
+        assert_eq!(ask.await.unwrap(), Some((ApprovalDecision::Reject, "tui")));
+    }
+
     #[test]
     fn test_cancel_background_tasks_empty() {
         let temp_dir = tempfile::tempdir().unwrap();
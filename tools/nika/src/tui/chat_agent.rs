@@ -51,8 +51,11 @@
 //! }
 //! ```
 
+use std::sync::Arc;
+
 use crate::error::NikaError;
 use crate::provider::rig::{RigProvider, StreamChunk};
+use crate::provider::{CallOutcome, ConcurrencyLimiter, Priority};
 use crate::tui::command::ModelProvider;
 use tokio::sync::mpsc;
 
@@ -211,6 +214,8 @@ pub struct ChatAgent {
     streaming_state: StreamingState,
     /// HTTP client for fetch operations
     http_client: reqwest::Client,
+    /// Provider limits shared with running workflows (interactive lane)
+    limiter: Option<Arc<ConcurrencyLimiter>>,
     /// Cumulative input tokens used
     pub total_input_tokens: u64,
     /// Cumulative output tokens used
@@ -242,6 +247,7 @@ impl ChatAgent {
             stream_chunk_tx: None,
             streaming_state: StreamingState::new(),
             http_client: reqwest::Client::new(),
+            limiter: None,
             total_input_tokens: 0,
             total_output_tokens: 0,
         })
@@ -350,6 +356,15 @@ impl ChatAgent {
         self
     }
 
+    /// Share provider limits with running workflows (v0.7)
+    ///
+    /// Chat calls take the interactive lane, so they never queue behind
+    /// workflow tasks on the same provider.
+    pub fn with_limiter(mut self, limiter: Arc<ConcurrencyLimiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
     /// Set streaming channel (takes ownership, for use after construction)
     pub fn set_stream_chunk_tx(&mut self, tx: mpsc::Sender<StreamChunk>) {
         self.stream_chunk_tx = Some(tx);
@@ -472,6 +487,15 @@ impl ChatAgent {
                 .await;
         }
 
+        let permit = match &self.limiter {
+            Some(limiter) => {
+                limiter
+                    .acquire_as(self.provider.name(), Priority::Interactive)
+                    .await
+            }
+            None => None,
+        };
+
         // Use streaming if stream_chunk_tx is set, otherwise blocking
         let response = if let Some(tx) = self.stream_chunk_tx.clone() {
             // Clone tx for metrics send (infer_stream takes ownership)
@@ -481,10 +505,13 @@ impl ChatAgent {
             let result = self
                 .provider
                 .infer_stream(prompt, tx, self.model_override.as_deref())
-                .await
-                .map_err(|e| NikaError::ProviderApiError {
-                    message: e.to_string(),
-                })?;
+                .await;
+            if let Some(permit) = permit {
+                permit.finish(CallOutcome::from_result(&result));
+            }
+            let result = result.map_err(|e| NikaError::ProviderApiError {
+                message: e.to_string(),
+            })?;
             // Accumulate token metrics for status bar display
             self.total_input_tokens += result.input_tokens;
             self.total_output_tokens += result.output_tokens;
@@ -500,12 +527,13 @@ impl ChatAgent {
             result.text
         } else {
             // Blocking call - full response at once
-            self.provider
-                .infer(prompt, None)
-                .await
-                .map_err(|e| NikaError::ProviderApiError {
-                    message: e.to_string(),
-                })?
+            let result = self.provider.infer(prompt, None).await;
+            if let Some(permit) = permit {
+                permit.finish(CallOutcome::from_result(&result));
+            }
+            result.map_err(|e| NikaError::ProviderApiError {
+                message: e.to_string(),
+            })?
         };
 
         // Finish streaming
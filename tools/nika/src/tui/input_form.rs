@@ -0,0 +1,402 @@
+//! Input form - values for a workflow's `inputs:` before it runs (v0.7)
+//!
+//! Running a workflow that declares inputs opens a form generated from
+//! the declarations instead of failing on the missing ones:
+//!
+//! - `string`/`number` inputs are text fields
+//! - `enum` and `boolean` inputs cycle through their values with ←/→
+//! - `file` inputs are text fields whose ←/→ pick among the matching files
+//!   under the working directory
+//!
+//! Enter validates every field like `nika run --input` would and starts the
+//! run; fields keep their error until edited. The values used last are
+//! remembered per workflow in `.nika/inputs.json` and prefill the form.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::ast::{InputSpec, InputType, Inputs, Workflow};
+
+use super::app::centered_rect;
+use super::theme::Theme;
+
+/// Last-used input values, per workflow
+pub const INPUTS_MEMORY: &str = ".nika/inputs.json";
+
+/// Most files offered by a `file` field
+const MAX_FILE_CHOICES: usize = 200;
+
+/// What a key did to the form
+#[derive(Debug, PartialEq)]
+pub enum FormKey {
+    Continue,
+    /// Every field is valid: run with these values
+    Submit(BTreeMap<String, String>),
+    Cancel,
+}
+
+/// One generated field
+#[derive(Debug)]
+struct InputField {
+    name: String,
+    spec: InputSpec,
+    value: String,
+    /// Values ←/→ cycle through
+    choices: Vec<String>,
+    error: Option<String>,
+}
+
+impl InputField {
+    /// Enum and boolean fields only take one of their choices
+    fn is_choice_only(&self) -> bool {
+        matches!(self.spec.kind, InputType::Enum | InputType::Boolean)
+    }
+
+    fn cycle(&mut self, forward: bool) {
+        if self.choices.is_empty() {
+            return;
+        }
+        let len = self.choices.len();
+        let next = match self.choices.iter().position(|c| *c == self.value) {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None if forward => 0,
+            None => len - 1,
+        };
+        self.value = self.choices[next].clone();
+        self.error = None;
+    }
+
+    /// Check the value; an empty field falls back to the default
+    fn validate(&mut self) -> bool {
+        self.error = if self.value.is_empty() {
+            self.spec.default.is_none().then(|| "required".to_string())
+        } else {
+            self.spec.parse(&self.value).err()
+        };
+        self.error.is_none()
+    }
+}
+
+/// The form for one workflow
+#[derive(Debug)]
+pub struct InputForm {
+    /// Workflow file to run once submitted
+    pub path: PathBuf,
+    fields: Vec<InputField>,
+    focus: usize,
+}
+
+impl InputForm {
+    /// Form for the workflow at `path`, if it declares inputs
+    ///
+    /// Unreadable or invalid workflows get no form: the run reports them.
+    pub fn open(path: &Path) -> Option<Self> {
+        let yaml = std::fs::read_to_string(path).ok()?;
+        let workflow: Workflow = serde_yaml::from_str(&yaml).ok()?;
+        if workflow.inputs.is_empty() {
+            return None;
+        }
+        let remembered = load_remembered(Path::new(INPUTS_MEMORY), path);
+        Some(Self::new(path, &workflow.inputs, &remembered))
+    }
+
+    /// Fields for `inputs`, prefilled with `remembered` values or defaults
+    pub fn new(path: &Path, inputs: &Inputs, remembered: &BTreeMap<String, String>) -> Self {
+        let fields = inputs
+            .iter()
+            .map(|(name, spec)| {
+                let choices = match spec.kind {
+                    InputType::Enum => spec.values.clone(),
+                    InputType::Boolean => vec!["true".to_string(), "false".to_string()],
+                    InputType::File => file_choices(Path::new("."), spec),
+                    InputType::String | InputType::Number => Vec::new(),
+                };
+                let value = remembered
+                    .get(name)
+                    .cloned()
+                    .or_else(|| spec.default_text())
+                    .unwrap_or_default();
+                InputField {
+                    name: name.to_string(),
+                    spec: spec.clone(),
+                    value,
+                    choices,
+                    error: None,
+                }
+            })
+            .collect();
+        Self {
+            path: path.to_path_buf(),
+            fields,
+            focus: 0,
+        }
+    }
+
+    /// Apply a key
+    pub fn handle_key(&mut self, code: KeyCode) -> FormKey {
+        let count = self.fields.len();
+        let field = &mut self.fields[self.focus];
+        match code {
+            KeyCode::Esc => return FormKey::Cancel,
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Tab | KeyCode::Down => self.focus = (self.focus + 1) % count,
+            KeyCode::BackTab | KeyCode::Up => self.focus = (self.focus + count - 1) % count,
+            KeyCode::Right => field.cycle(true),
+            KeyCode::Left => field.cycle(false),
+            KeyCode::Char(c) if !field.is_choice_only() => {
+                field.value.push(c);
+                field.error = None;
+            }
+            KeyCode::Backspace if !field.is_choice_only() => {
+                field.value.pop();
+                field.error = None;
+            }
+            _ => {}
+        }
+        FormKey::Continue
+    }
+
+    /// Validate every field; focus the first invalid one
+    fn submit(&mut self) -> FormKey {
+        let mut first_invalid = None;
+        for (i, field) in self.fields.iter_mut().enumerate() {
+            if !field.validate() && first_invalid.is_none() {
+                first_invalid = Some(i);
+            }
+        }
+        if let Some(i) = first_invalid {
+            self.focus = i;
+            return FormKey::Continue;
+        }
+        FormKey::Submit(
+            self.fields
+                .iter()
+                .filter(|f| !f.value.is_empty())
+                .map(|f| (f.name.clone(), f.value.clone()))
+                .collect(),
+        )
+    }
+
+    /// Draw the form over `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let overlay = centered_rect(70, 70, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.border_style(true))
+            .title(format!(" ▶ Run {} ", name))
+            .title_bottom(" Tab/↑↓ move  ←/→ choose  Enter run  Esc cancel ");
+
+        let mut lines = Vec::new();
+        for (i, field) in self.fields.iter().enumerate() {
+            let focused = i == self.focus;
+            let marker = if focused { "▸ " } else { "  " };
+            let required = if field.spec.default.is_none() {
+                "*"
+            } else {
+                ""
+            };
+            let label = format!("{}{}{}", marker, field.name, required);
+            let value = if field.is_choice_only() {
+                format!("‹ {} ›", field.value)
+            } else if focused {
+                format!("{}▏", field.value)
+            } else {
+                field.value.clone()
+            };
+            let label_style = if focused {
+                theme.highlight_style().add_modifier(Modifier::BOLD)
+            } else {
+                theme.text_style()
+            };
+            let mut spans = vec![
+                Span::styled(format!("{:<20}", label), label_style),
+                Span::styled(value, theme.text_style()),
+            ];
+            if let Some(hint) = field_hint(field) {
+                spans.push(Span::styled(
+                    format!("   {}", hint),
+                    theme.text_muted_style(),
+                ));
+            }
+            lines.push(Line::from(spans));
+            if let Some(description) = &field.spec.description {
+                lines.push(Line::styled(
+                    format!("    {}", description),
+                    theme.text_secondary_style(),
+                ));
+            }
+            if let Some(error) = &field.error {
+                lines.push(Line::styled(
+                    format!("    ✗ {}", error),
+                    Style::default().fg(theme.status_failed),
+                ));
+            }
+            lines.push(Line::default());
+        }
+
+        frame.render_widget(Clear, overlay);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay);
+    }
+}
+
+/// Type hint shown after a field's value
+fn field_hint(field: &InputField) -> Option<String> {
+    match field.spec.kind {
+        InputType::Number => Some("number".to_string()),
+        InputType::File if field.choices.is_empty() => Some("no matching files".to_string()),
+        InputType::File => Some(format!("←/→ {} files", field.choices.len())),
+        _ => None,
+    }
+}
+
+/// Files under `base` a `file` input accepts, relative to it
+fn file_choices(base: &Path, spec: &InputSpec) -> Vec<String> {
+    let mut files: Vec<String> = ignore::WalkBuilder::new(base)
+        .max_depth(Some(4))
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter(|entry| spec.accepts_extension(entry.path()))
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(base).ok()?;
+            Some(relative.to_string_lossy().into_owned())
+        })
+        .take(MAX_FILE_CHOICES)
+        .collect();
+    files.sort();
+    files
+}
+
+/// Values last used for `workflow`, from the memory file at `memory`
+pub fn load_remembered(memory: &Path, workflow: &Path) -> BTreeMap<String, String> {
+    read_memory(memory)
+        .remove(&workflow.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Remember `values` for `workflow` in the memory file at `memory`
+///
+/// Losing remembered values only costs some typing, so failures are
+/// logged rather than returned.
+pub fn remember(memory: &Path, workflow: &Path, values: &BTreeMap<String, String>) {
+    let mut all = read_memory(memory);
+    all.insert(workflow.to_string_lossy().into_owned(), values.clone());
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = memory.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(memory, serde_json::to_string_pretty(&all)?)
+    };
+    if let Err(e) = write() {
+        tracing::warn!(error = %e, "Could not remember workflow inputs");
+    }
+}
+
+fn read_memory(memory: &Path) -> BTreeMap<String, BTreeMap<String, String>> {
+    std::fs::read_to_string(memory)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(remembered: &[(&str, &str)]) -> InputForm {
+        let inputs: Inputs = serde_yaml::from_str(
+            "topic:\ntone: { type: enum, values: [formal, casual], default: casual }\nwords: { type: number, default: 300 }",
+        )
+        .unwrap();
+        let remembered = remembered
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        InputForm::new(Path::new("post.nika.yaml"), &inputs, &remembered)
+    }
+
+    fn type_text(form: &mut InputForm, text: &str) {
+        for c in text.chars() {
+            form.handle_key(KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn submit_validates_fields() {
+        let mut form = form(&[]);
+        assert_eq!(form.handle_key(KeyCode::Enter), FormKey::Continue);
+        assert_eq!(form.fields[0].error.as_deref(), Some("required"));
+
+        type_text(&mut form, "rust");
+        form.handle_key(KeyCode::Tab);
+        form.handle_key(KeyCode::Right);
+        form.handle_key(KeyCode::Char('x')); // enum fields take no text
+        form.handle_key(KeyCode::Tab);
+        form.handle_key(KeyCode::Backspace);
+        form.handle_key(KeyCode::Backspace);
+        form.handle_key(KeyCode::Backspace);
+        type_text(&mut form, "lots");
+        assert_eq!(form.handle_key(KeyCode::Enter), FormKey::Continue);
+        assert_eq!(form.focus, 2);
+        assert!(form.fields[2]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("not a number"));
+
+        form.handle_key(KeyCode::Backspace);
+        form.handle_key(KeyCode::Backspace);
+        form.handle_key(KeyCode::Backspace);
+        form.handle_key(KeyCode::Backspace);
+        let submitted = form.handle_key(KeyCode::Enter);
+        let expected = BTreeMap::from([
+            ("topic".to_string(), "rust".to_string()),
+            ("tone".to_string(), "formal".to_string()),
+        ]);
+        assert_eq!(submitted, FormKey::Submit(expected));
+    }
+
+    #[test]
+    fn remembered_values_prefill_the_form() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = dir.path().join(".nika/inputs.json");
+        let workflow = Path::new("post.nika.yaml");
+        let values = BTreeMap::from([("topic".to_string(), "sled".to_string())]);
+        remember(&memory, workflow, &values);
+        assert_eq!(load_remembered(&memory, workflow), values);
+        assert!(load_remembered(&memory, Path::new("other.nika.yaml")).is_empty());
+
+        let mut form = form(&[("topic", "sled")]);
+        assert_eq!(form.fields[0].value, "sled");
+        assert_eq!(form.fields[1].value, "casual");
+        assert_eq!(form.handle_key(KeyCode::Esc), FormKey::Cancel);
+    }
+
+    #[test]
+    fn file_fields_offer_matching_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "").unwrap();
+        std::fs::create_dir(dir.path().join("notes")).unwrap();
+        std::fs::write(dir.path().join("notes/c.md"), "").unwrap();
+        let spec = InputSpec {
+            kind: InputType::File,
+            extensions: vec!["md".to_string()],
+            ..InputSpec::default()
+        };
+        assert_eq!(file_choices(dir.path(), &spec), ["a.md", "notes/c.md"]);
+    }
+}
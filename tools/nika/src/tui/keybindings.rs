@@ -108,6 +108,14 @@ pub fn keybindings_for_context(view: TuiView, mode: InputMode) -> Vec<Keybinding
         });
     }
 
+    // Background tasks overlay works in every mode (v0.7)
+    bindings.push(Keybinding {
+        code: KeyCode::F(12),
+        modifiers: KeyModifiers::NONE,
+        description: "Background tasks",
+        category: KeyCategory::Global,
+    });
+
     // Panel navigation
     bindings.push(Keybinding {
         code: KeyCode::Tab,
@@ -386,6 +394,8 @@ mod tests {
         let bindings = keybindings_for_context(TuiView::Chat, InputMode::Insert);
         assert!(bindings.iter().any(|b| b.code == KeyCode::Esc));
         assert!(bindings.iter().any(|b| b.code == KeyCode::Enter));
+        // F12 (background tasks) works while typing (v0.7)
+        assert!(bindings.iter().any(|b| b.code == KeyCode::F(12)));
         // Ctrl+K/T/M also available in Insert mode
         assert!(bindings
             .iter()
@@ -33,7 +33,8 @@
 //! # Panic Recovery (v0.7.0+)
 //!
 //! The TUI installs a panic hook to restore terminal state on crashes.
-//! Crash logs are written to `~/.nika/crash.log`.
+//! Crash logs are written to `~/.nika/crash.log`, and a crash bundle with
+//! the backtrace and last events to `~/.config/nika/crashes/` (v0.7).
 
 #[cfg(feature = "tui")]
 mod app;
@@ -48,6 +49,8 @@ pub mod file_resolve;
 #[cfg(feature = "tui")]
 mod focus;
 #[cfg(feature = "tui")]
+mod input_form;
+#[cfg(feature = "tui")]
 mod keybindings;
 #[cfg(feature = "tui")]
 mod layout;
@@ -60,6 +63,10 @@ mod standalone;
 #[cfg(feature = "tui")]
 mod state;
 #[cfg(feature = "tui")]
+mod stream;
+#[cfg(feature = "tui")]
+mod tasks;
+#[cfg(feature = "tui")]
 mod theme;
 #[cfg(feature = "tui")]
 mod unicode;
@@ -104,7 +111,9 @@ pub use views::{DagTab, MissionTab, NovanetTab, ReasoningTab, TuiView, ViewActio
 /// Install panic hook to restore terminal state on crashes.
 ///
 /// This function saves the original panic hook and wraps it with
-/// terminal restoration logic. Crash logs are written to `~/.nika/crash.log`.
+/// terminal restoration logic. Crash logs are written to `~/.nika/crash.log`,
+/// crash bundles (see [`crate::util::crash`]) next to it, and the user is
+/// offered a pre-filled GitHub issue.
 ///
 /// # Safety
 ///
@@ -118,6 +127,8 @@ fn install_panic_hook() {
 
     use crossterm::{execute, terminal::LeaveAlternateScreen};
 
+    use crate::util::crash;
+
     static HOOK_INSTALLED: Once = Once::new();
 
     HOOK_INSTALLED.call_once(|| {
@@ -160,7 +171,15 @@ fn install_panic_hook() {
                 let _ = writeln!(f, "\nBacktrace:\n{}", backtrace);
             }
 
-            // 3. Print user-friendly message to stderr
+            // 3. Write the crash bundle (v0.7)
+            let bundle = crash::CrashBundle::new(
+                "tui",
+                crash::PanicRecord::capture(panic_info),
+                &crash::watched_events(),
+            );
+            let bundle_path = bundle.write().ok();
+
+            // 4. Print user-friendly message to stderr
             eprintln!(
                 "\n\x1b[31m╔══════════════════════════════════════════════════════════════╗\x1b[0m"
             );
@@ -174,6 +193,9 @@ fn install_panic_hook() {
                 "\x1b[31m║  Crash log: {:49} ║\x1b[0m",
                 crash_log_path.display()
             );
+            if let Some(path) = &bundle_path {
+                eprintln!("\x1b[31m║  Crash bundle: {:46} ║\x1b[0m", path.display());
+            }
             eprintln!(
                 "\x1b[31m║  Please report: https://github.com/SuperNovae-studio/nika    ║\x1b[0m"
             );
@@ -181,8 +203,16 @@ fn install_panic_hook() {
                 "\x1b[31m╚══════════════════════════════════════════════════════════════╝\x1b[0m"
             );
 
-            // 4. Call original hook
+            eprintln!(
+                "\nReport it with a pre-filled issue:\n{}\n",
+                bundle.issue_url()
+            );
+            crash::offer_issue(&bundle.issue_url());
+
+            // 5. Call original hook; the bundle is written, so the runner
+            // doesn't write another one for a task panic
             original_hook(panic_info);
+            crash::take_last_panic();
         }));
     });
 }
@@ -196,9 +226,32 @@ fn install_panic_hook() {
 /// 4. Runs the TUI with real-time event updates
 #[cfg(feature = "tui")]
 pub async fn run_tui(workflow_path: &std::path::Path) -> crate::error::Result<()> {
+    run_tui_workflow(workflow_path, None).await
+}
+
+/// Run a workflow in the TUI under the step-through debugger (v0.7, `nika debug --tui`)
+///
+/// Pauses before every task, or only at `breakpoints` when given.
+#[cfg(feature = "tui")]
+pub async fn run_tui_debug(
+    workflow_path: &std::path::Path,
+    breakpoints: Vec<String>,
+) -> crate::error::Result<()> {
+    run_tui_workflow(workflow_path, Some(breakpoints)).await
+}
+
+#[cfg(feature = "tui")]
+async fn run_tui_workflow(
+    workflow_path: &std::path::Path,
+    debug_breakpoints: Option<Vec<String>>,
+) -> crate::error::Result<()> {
     use crate::ast::Workflow;
+    use crate::config::NikaConfig;
     use crate::event::EventLog;
-    use crate::runtime::Runner;
+    use crate::provider::ConcurrencyLimiter;
+    use crate::runtime::{Debugger, Runner};
+    use crate::store::{HttpCache, StateStore, VectorStore};
+    use std::sync::Arc;
 
     // Install panic hook for terminal recovery
     install_panic_hook();
@@ -224,12 +277,51 @@ pub async fn run_tui(workflow_path: &std::path::Path) -> crate::error::Result<()
 
     // 2. Create EventLog with broadcast channel for TUI
     let (event_log, event_rx) = EventLog::new_with_broadcast();
+    crate::util::crash::watch(&event_log);
 
     // 3. Create Runner with the broadcast-enabled EventLog and quiet mode
-    // quiet() suppresses console output that would interfere with the TUI
-    let runner = Runner::with_event_log(workflow, event_log).quiet();
-
-    // 4. Spawn Runner in background task
+    // quiet() suppresses console output that would interfere with the TUI;
+    // approve: tasks are answered in the TUI (v0.7)
+    let (approval_tx, approval_rx) = tokio::sync::mpsc::channel(4);
+    let gate = app::approval_gate(approval_tx, &workflow);
+    let config = NikaConfig::load()?;
+    // Shared with the App, whose chat takes the interactive lane
+    let limiter = Arc::new(ConcurrencyLimiter::new(&config.concurrency));
+    let runner = Runner::with_event_log(workflow, event_log)
+        .with_store(&config.store)?
+        .with_router(&config.router)
+        .with_concurrency_limiter(Arc::clone(&limiter))
+        .with_web_search(&config.web_search)
+        .with_key_rotation(&config.key_rotation)?
+        .with_size_limits(&config.size_limits)
+        .with_memory(&config.memory)
+        .with_state_store(Arc::new(StateStore::project()))
+        .with_vector_store(Arc::new(VectorStore::project()))
+        .with_http_cache(Arc::new(HttpCache::project()))
+        .quiet()
+        .with_approval_gate(gate);
+
+    // Step-through debugger (v0.7): the App answers paused tasks
+    let (debug_tx, debug_rx) = tokio::sync::mpsc::channel(1);
+    let debugger = debug_breakpoints
+        .map(|breakpoints| Arc::new(Debugger::new(debug_tx).with_breakpoints(breakpoints)));
+    let runner = match &debugger {
+        Some(debugger) => runner.with_debugger(Arc::clone(debugger)),
+        None => runner,
+    };
+
+    // 4. Create the TUI before spawning, so a setup error cannot leak the run
+    // Use run_unified() for the 4-view architecture (Chat/Home/Studio/Monitor)
+    let app = App::new(workflow_path)?
+        .with_broadcast_receiver(event_rx)
+        .with_approval_receiver(approval_rx)
+        .with_concurrency_limiter(limiter);
+    let app = match debugger {
+        Some(debugger) => app.with_debugger(debugger, debug_rx),
+        None => app,
+    };
+
+    // 5. Spawn Runner in background task and run TUI with event receiver
     let runner_handle = tokio::spawn(async move {
         match runner.run().await {
             Ok(output) => {
@@ -240,10 +332,6 @@ pub async fn run_tui(workflow_path: &std::path::Path) -> crate::error::Result<()
             }
         }
     });
-
-    // 5. Create and run TUI with event receiver
-    // Use run_unified() for the 4-view architecture (Chat/Home/Studio/Monitor)
-    let app = App::new(workflow_path)?.with_broadcast_receiver(event_rx);
     let tui_result = app.run_unified().await;
 
     // 6. Abort runner if TUI exits early (user pressed q)
@@ -252,6 +340,77 @@ pub async fn run_tui(workflow_path: &std::path::Path) -> crate::error::Result<()
     tui_result
 }
 
+/// Replay a recorded trace in the TUI Monitor view (v0.7)
+///
+/// Events are re-driven from the NDJSON trace at `speed` times real time
+/// (`0.0` = as fast as possible). No workflow is executed.
+#[cfg(feature = "tui")]
+pub async fn run_tui_replay(
+    trace_path: &std::path::Path,
+    events: Vec<crate::event::Event>,
+    speed: f64,
+) -> crate::error::Result<()> {
+    install_panic_hook();
+
+    let (tx, rx) = tokio::sync::mpsc::channel(256);
+    let replay_handle = tokio::spawn(crate::event::replay_trace(events, speed, tx));
+
+    let app = App::new(trace_path)?.with_event_receiver(rx);
+    let tui_result = app.run_unified().await;
+
+    replay_handle.abort();
+
+    tui_result
+}
+
+/// Follow a run shared with `nika run --share` in the Monitor view (v0.7)
+///
+/// Read-only: events come from the relay at `url`, nothing runs locally.
+#[cfg(feature = "tui")]
+pub async fn run_tui_shared(url: &str) -> crate::error::Result<()> {
+    install_panic_hook();
+
+    // Connect first, so a bad URL fails before the screen switches
+    let subscription = crate::relay::Subscription::connect(url).await?;
+    run_tui_following(url, |tx| subscription.forward(tx)).await
+}
+
+/// Follow a run of a remote daemon in the Monitor view (v0.7)
+///
+/// Read-only: events come from the daemon at `server` (started with
+/// `--http`), reconnecting when the connection drops.
+#[cfg(feature = "tui")]
+pub async fn run_tui_remote(server: &str, run_id: &str) -> crate::error::Result<()> {
+    install_panic_hook();
+
+    let tail = crate::tail::RunTail::connect(server, run_id).await?;
+    let url = tail.url().to_string();
+    run_tui_following(&url, |tx| tail.forward(tx)).await
+}
+
+/// Monitor view over the events `forward` sends, labelled `source`
+#[cfg(feature = "tui")]
+async fn run_tui_following<F>(
+    source: &str,
+    forward: impl FnOnce(tokio::sync::mpsc::Sender<crate::event::Event>) -> F,
+) -> crate::error::Result<()>
+where
+    F: std::future::Future<Output = crate::error::Result<()>> + Send + 'static,
+{
+    let (tx, rx) = tokio::sync::mpsc::channel(256);
+    let follow_handle = tokio::spawn(forward(tx));
+
+    let app = App::new_shared(source).with_event_receiver(rx);
+    let tui_result = app.run_unified().await;
+
+    follow_handle.abort();
+    // A dropped connection is worth reporting over the TUI's result
+    match follow_handle.await {
+        Ok(Err(e)) => Err(e),
+        _ => tui_result,
+    }
+}
+
 /// Run the TUI in standalone mode (file browser + history)
 ///
 /// This function:
@@ -363,6 +522,41 @@ pub async fn run_tui(_workflow_path: &std::path::Path) -> crate::error::Result<(
     })
 }
 
+#[cfg(not(feature = "tui"))]
+pub async fn run_tui_debug(
+    _workflow_path: &std::path::Path,
+    _breakpoints: Vec<String>,
+) -> crate::error::Result<()> {
+    Err(crate::error::NikaError::ValidationError {
+        reason: "TUI feature not enabled. Rebuild with --features tui".to_string(),
+    })
+}
+
+#[cfg(not(feature = "tui"))]
+pub async fn run_tui_replay(
+    _trace_path: &std::path::Path,
+    _events: Vec<crate::event::Event>,
+    _speed: f64,
+) -> crate::error::Result<()> {
+    Err(crate::error::NikaError::ValidationError {
+        reason: "TUI feature not enabled. Rebuild with --features tui".to_string(),
+    })
+}
+
+#[cfg(not(feature = "tui"))]
+pub async fn run_tui_shared(_url: &str) -> crate::error::Result<()> {
+    Err(crate::error::NikaError::ValidationError {
+        reason: "TUI feature not enabled. Rebuild with --features tui".to_string(),
+    })
+}
+
+#[cfg(not(feature = "tui"))]
+pub async fn run_tui_remote(_server: &str, _run_id: &str) -> crate::error::Result<()> {
+    Err(crate::error::NikaError::ValidationError {
+        reason: "TUI feature not enabled. Rebuild with --features tui".to_string(),
+    })
+}
+
 #[cfg(not(feature = "tui"))]
 pub async fn run_tui_standalone() -> crate::error::Result<()> {
     Err(crate::error::NikaError::ValidationError {
@@ -15,9 +15,9 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
-use crate::tui::state::TuiState;
+use crate::tui::state::{ContextPoolUsage, TuiState};
 use crate::tui::theme::{MissionPhase, TaskStatus, Theme};
-use crate::tui::utils::format_number;
+use crate::tui::utils::{format_number, format_number_compact};
 use crate::tui::widgets::{Gauge, LatencySparkline, Timeline};
 
 /// Progress panel (Panel 1: Mission Control)
@@ -350,7 +350,55 @@ impl<'a> ProgressPanel<'a> {
                 sparkline.render(sparkline_area, buf);
             }
         }
+
+        // Provider limits moved by throttling (v0.7)
+        let mut row = 2;
+        if area.height > row && !metrics.concurrency_limits.is_empty() {
+            let limits: Vec<String> = metrics
+                .concurrency_limits
+                .iter()
+                .map(|(provider, limit)| format!("{} {}", provider, limit))
+                .collect();
+            buf.set_stringn(
+                area.x + 2,
+                area.y + 2,
+                format!("In flight ≤ {}", limits.join(" · ")),
+                area.width.saturating_sub(4) as usize,
+                Style::default().fg(Color::Yellow),
+            );
+            row += 1;
+        }
+
+        // Shared token budget (v0.7, context_pool:)
+        if let Some(pool) = metrics.context_pool.filter(|_| area.height > row) {
+            let color = if pool.waiting > 0 || pool.used + pool.reserved >= pool.capacity {
+                Color::Yellow
+            } else {
+                Color::Rgb(139, 92, 246) // violet
+            };
+            buf.set_stringn(
+                area.x + 2,
+                area.y + row,
+                pool_line(&pool),
+                area.width.saturating_sub(4) as usize,
+                Style::default().fg(color),
+            );
+        }
+    }
+}
+
+/// `Pool: 42.0K used + 20.0K held / 200.0K · 1 waiting`
+fn pool_line(pool: &ContextPoolUsage) -> String {
+    let mut line = format!(
+        "Pool: {} used + {} held / {}",
+        format_number_compact(pool.used),
+        format_number_compact(pool.reserved),
+        format_number_compact(pool.capacity)
+    );
+    if pool.waiting > 0 {
+        line.push_str(&format!(" · {} waiting", pool.waiting));
     }
+    line
 }
 
 impl Widget for ProgressPanel<'_> {
@@ -423,6 +471,19 @@ mod tests {
         assert_eq!(format_number(1234567), "1,234,567");
     }
 
+    #[test]
+    fn test_pool_line() {
+        let pool = ContextPoolUsage {
+            capacity: 200_000,
+            used: 42_000,
+            reserved: 20_000,
+            waiting: 0,
+        };
+        assert_eq!(pool_line(&pool), "Pool: 42.0K used + 20.0K held / 200.0K");
+        let pool = ContextPoolUsage { waiting: 2, ..pool };
+        assert!(pool_line(&pool).ends_with(" · 2 waiting"));
+    }
+
     #[test]
     fn test_phase_colors_distinct() {
         let colors: Vec<Color> = vec![
@@ -31,6 +31,8 @@ use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
+use crate::store::{PresetStore, RunPreset, PRESETS_DIR};
+
 /// Panel in standalone mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum StandalonePanel {
@@ -204,6 +206,8 @@ pub struct StandaloneState {
     pub history_index: usize,
     /// Preview content (YAML of selected file)
     pub preview_content: String,
+    /// Saved run presets of the selected file (v0.7)
+    pub presets: Vec<(String, RunPreset)>,
     /// Scroll offset in preview
     pub preview_scroll: usize,
     /// Search query for filtering
@@ -223,6 +227,7 @@ impl StandaloneState {
             history: Vec::new(),
             history_index: 0,
             preview_content: String::new(),
+            presets: Vec::new(),
             preview_scroll: 0,
             search_query: String::new(),
             search_active: false,
@@ -358,8 +363,10 @@ impl StandaloneState {
 
     /// Update preview content based on selected file
     pub fn update_preview(&mut self) {
+        self.presets.clear();
         if let Some(entry) = self.browser_entries.get(self.browser_index) {
             if !entry.is_dir {
+                self.presets = PresetStore::open(self.root.join(PRESETS_DIR)).list(&entry.path);
                 match std::fs::read_to_string(&entry.path) {
                     Ok(content) => {
                         self.preview_content = content;
@@ -3,7 +3,7 @@
 //! Central state for the TUI application.
 //! Updated by events from the runtime, queried by panels for rendering.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -11,7 +11,9 @@ use serde_json::Value;
 
 use crate::config::NikaConfig;
 use crate::event::{ContextSource, EventKind, ExcludedItem};
+use crate::runtime::ApprovalRequest;
 
+use super::tasks::BackgroundTask;
 use super::theme::{MissionPhase, TaskStatus, ThemeMode};
 use super::views::{DagTab, MissionTab, NovanetTab, ReasoningTab};
 use super::widgets::TimelineEntry;
@@ -392,6 +394,23 @@ pub struct TaskState {
     pub model: Option<String>,
     /// Prompt length in chars
     pub prompt_len: Option<usize>,
+    /// Where each use: binding came from (v0.7)
+    pub bindings: Vec<BindingOrigin>,
+}
+
+/// Provenance of a resolved use: binding (v0.7)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingOrigin {
+    /// Alias as referenced in templates
+    pub alias: String,
+    /// Task that produced the value (None for for_each items)
+    pub source_task: Option<String>,
+    /// Binding path (e.g. `weather.summary`)
+    pub path: String,
+    /// ID of the source task's TaskCompleted event
+    pub source_event_id: Option<u64>,
+    /// Value fell back to the `?? default`
+    pub used_default: bool,
 }
 
 impl TaskState {
@@ -410,6 +429,7 @@ impl TaskState {
             provider: None,
             model: None,
             prompt_len: None,
+            bindings: Vec::new(),
         }
     }
 }
@@ -506,6 +526,36 @@ pub enum Breakpoint {
     OnAgentTurn(String, u32),
 }
 
+/// Task paused by the step-through debugger (v0.7, `nika debug --tui`)
+#[derive(Debug, Clone)]
+pub struct DebugPause {
+    pub task_id: String,
+    pub verb: &'static str,
+    /// What `rendered` holds (prompt, command, request...)
+    pub rendered_label: &'static str,
+    /// Resolved inputs, pretty-printed
+    pub inputs: String,
+    pub rendered: String,
+    /// Whether the prompt can be edited
+    pub editable: bool,
+    /// Text being typed, if any
+    pub edit: Option<DebugEdit>,
+}
+
+/// What a debugger text edit replaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugEditKind {
+    Prompt,
+    Output,
+}
+
+/// Text input in the debugger overlay
+#[derive(Debug, Clone)]
+pub struct DebugEdit {
+    pub kind: DebugEditKind,
+    pub buffer: String,
+}
+
 /// Metrics aggregation
 #[derive(Debug, Clone, Default)]
 pub struct Metrics {
@@ -517,6 +567,8 @@ pub struct Metrics {
     pub output_tokens: u32,
     /// Total cache-read tokens (prompt caching)
     pub cache_read_tokens: u32,
+    /// Total cache-write tokens (prompt caching, v0.7)
+    pub cache_write_tokens: u32,
     /// Total cost in USD
     pub cost_usd: f64,
     /// MCP call count by tool
@@ -529,6 +581,22 @@ pub struct Metrics {
     pub provider_calls: usize,
     /// Last model used (for status display)
     pub last_model: Option<String>,
+    /// In-flight limit per provider, once adjusted (v0.7)
+    pub concurrency_limits: BTreeMap<String, usize>,
+    /// Shared token budget, when the workflow has a `context_pool:` (v0.7)
+    pub context_pool: Option<ContextPoolUsage>,
+}
+
+/// Live usage of the workflow's `context_pool:` (v0.7)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContextPoolUsage {
+    pub capacity: u64,
+    /// Charged by finished tasks
+    pub used: u64,
+    /// Held by running tasks
+    pub reserved: u64,
+    /// Tasks waiting for tokens
+    pub waiting: usize,
 }
 
 // ═══════════════════════════════════════════
@@ -1220,6 +1288,12 @@ pub struct TuiState {
     pub paused: bool,
     /// Step mode (advance one step at a time)
     pub step_mode: bool,
+    /// Task paused by the step-through debugger
+    pub debug_pause: Option<DebugPause>,
+    /// Approval waiting for y/n: `approve:` question or agent tool call (v0.7)
+    pub approval: Option<ApprovalRequest>,
+    /// Background tasks overlay (F12), refreshed every frame while open
+    pub background_tasks: Option<Vec<BackgroundTask>>,
 
     // ═══════════════════════════════════════════
     // METRICS
@@ -1430,6 +1504,9 @@ impl TuiState {
             breakpoints: HashSet::new(),
             paused: false,
             step_mode: false,
+            debug_pause: None,
+            approval: None,
+            background_tasks: None,
             metrics: Metrics::default(),
             filter_query: String::new(),
             filter_cursor: 0,
@@ -1464,6 +1541,52 @@ impl TuiState {
                 self.json_cache.clear();
             }
 
+            // Run labels are trace metadata (nika run --matrix)
+            EventKind::RunLabeled { .. } => {}
+
+            // Session pool counters are summary metadata (v0.7)
+            EventKind::SessionPoolReport { .. } => {}
+
+            EventKind::ConcurrencyAdjusted {
+                provider, limit, ..
+            } => {
+                self.metrics
+                    .concurrency_limits
+                    .insert(provider.to_string(), *limit);
+                self.dirty.progress = true;
+            }
+
+            EventKind::ApiKeyQuarantined {
+                key,
+                reason,
+                seconds,
+                ..
+            } => {
+                let until = match seconds {
+                    Some(seconds) => format!("for {}s", seconds),
+                    None => "until restart".to_string(),
+                };
+                self.add_notification(Notification::warning(
+                    format!("⚠ API key {} quarantined ({}) {}", key, reason, until),
+                    timestamp_ms,
+                ));
+                self.dirty.status = true;
+            }
+
+            // Only failed preflight probes need attention (v0.7)
+            EventKind::PreflightChecked {
+                target,
+                error: Some(error),
+                ..
+            } => {
+                self.add_notification(Notification::alert(
+                    format!("✗ Preflight {}: {}", target, error),
+                    timestamp_ms,
+                ));
+                self.dirty.status = true;
+            }
+            EventKind::PreflightChecked { .. } => {}
+
             EventKind::WorkflowCompleted {
                 final_output,
                 total_duration_ms,
@@ -1548,6 +1671,28 @@ impl TuiState {
                 self.invalidate_timeline_cache();
             }
 
+            EventKind::BindingResolved {
+                task_id,
+                alias,
+                source_task,
+                path,
+                source_event_id,
+                used_default,
+                ..
+            } => {
+                if let Some(task) = self.tasks.get_mut(task_id.as_ref()) {
+                    task.bindings.retain(|b| b.alias != alias.as_str());
+                    task.bindings.push(BindingOrigin {
+                        alias: alias.to_string(),
+                        source_task: source_task.as_ref().map(|s| s.to_string()),
+                        path: path.to_string(),
+                        source_event_id: *source_event_id,
+                        used_default: *used_default,
+                    });
+                }
+                self.dirty.dag = true;
+            }
+
             EventKind::TaskStarted {
                 task_id,
                 verb,
@@ -1636,6 +1781,73 @@ impl TuiState {
                 self.invalidate_timeline_cache();
             }
 
+            // Unselected `when:` branch (v0.7): the node keeps its pending
+            // look, but counts toward progress so the run can reach 100%
+            EventKind::TaskSkipped { .. } => {
+                self.workflow.tasks_completed += 1;
+                self.dirty.progress = true;
+                self.dirty.dag = true;
+                self.invalidate_timeline_cache();
+            }
+
+            // Phase timings are read from the trace (`nika trace flame`)
+            EventKind::PhaseCompleted { .. } => {}
+
+            // Sources and artifacts are read from the trace (`nika trace
+            // show`, `nika trace export --format cypher`)
+            EventKind::SourceFetched { .. }
+            | EventKind::HttpCacheLookup { .. }
+            | EventKind::ArtifactWritten { .. } => {}
+
+            // Remote exec output (v0.7) streams into the live output panel
+            EventKind::ExecOutput { host, line, .. } => {
+                self.streaming_buffer
+                    .push_str(&format!("[{}] {}\n", host, line));
+                self.dirty.reasoning = true;
+            }
+
+            // WebSocket messages (v0.7) stream into the live output panel
+            EventKind::WsMessage { index, message, .. } => {
+                self.streaming_buffer
+                    .push_str(&format!("[ws #{}] {}\n", index, message));
+                self.dirty.reasoning = true;
+            }
+
+            EventKind::TaskDebugged { task_id, action } => {
+                self.add_notification(Notification::info(
+                    format!("🐞 '{}': {}", task_id, action.replace('_', " ")),
+                    timestamp_ms,
+                ));
+                self.dirty.status = true;
+            }
+
+            // Approval gates (v0.7): the App holds the pending request and
+            // answers it on y/n; the reducer only surfaces it
+            EventKind::ApprovalRequested { task_id, .. } => {
+                self.add_notification(Notification::alert(
+                    format!(
+                        "✋ '{}' needs approval — press y to approve, n to reject",
+                        task_id
+                    ),
+                    timestamp_ms,
+                ));
+                self.dirty.status = true;
+            }
+            EventKind::ApprovalGranted { task_id, by } => {
+                self.add_notification(Notification::success(
+                    format!("✅ '{}' approved ({})", task_id, by),
+                    timestamp_ms,
+                ));
+                self.dirty.status = true;
+            }
+            EventKind::ApprovalDenied { task_id, by } => {
+                self.add_notification(Notification::warning(
+                    format!("🚫 '{}' rejected ({})", task_id, by),
+                    timestamp_ms,
+                ));
+                self.dirty.status = true;
+            }
+
             // ═══════════════════════════════════════════
             // MCP EVENTS
             // ═══════════════════════════════════════════
@@ -1648,10 +1860,10 @@ impl TuiState {
                 params,
             } => {
                 let call = McpCall {
-                    call_id: call_id.clone(),
+                    call_id: call_id.to_string(),
                     seq: self.mcp_seq,
-                    server: mcp_server.clone(),
-                    tool: tool.clone(),
+                    server: mcp_server.to_string(),
+                    tool: tool.as_ref().map(ToString::to_string),
                     resource: resource.clone(),
                     task_id: task_id.to_string(),
                     completed: false,
@@ -1670,7 +1882,11 @@ impl TuiState {
 
                 // Track in metrics
                 if let Some(ref tool_name) = tool {
-                    let entry = self.metrics.mcp_calls.entry(tool_name.clone()).or_insert(0);
+                    let entry = self
+                        .metrics
+                        .mcp_calls
+                        .entry(tool_name.to_string())
+                        .or_insert(0);
                     *entry += 1;
                 }
                 // TIER 4.1: Mark novanet panel dirty
@@ -1750,6 +1966,87 @@ impl TuiState {
                 self.dirty.novanet = true;
             }
 
+            EventKind::ContextPoolUpdated {
+                task_id,
+                action,
+                tokens,
+                used,
+                reserved,
+                capacity,
+                waiting,
+            } => {
+                self.metrics.context_pool = Some(ContextPoolUsage {
+                    capacity: *capacity,
+                    used: *used,
+                    reserved: *reserved,
+                    waiting: *waiting,
+                });
+                if action == "exhausted" {
+                    self.add_notification(Notification::alert(
+                        format!(
+                            "✗ Context pool can't cover {} tokens for {}",
+                            tokens, task_id
+                        ),
+                        timestamp_ms,
+                    ));
+                    self.dirty.status = true;
+                }
+                self.dirty.progress = true;
+            }
+
+            EventKind::SecurityWarning {
+                task_id,
+                source,
+                rules,
+                action,
+            } => {
+                self.add_notification(Notification::warning(
+                    format!(
+                        "Possible prompt injection in '{}' from {} ({}; {})",
+                        task_id,
+                        source,
+                        rules.join(", "),
+                        action
+                    ),
+                    timestamp_ms,
+                ));
+                self.dirty.notifications = true;
+            }
+
+            EventKind::ModerationChecked {
+                task_id,
+                stage,
+                flagged,
+                action,
+                ..
+            } => {
+                if !flagged.is_empty() {
+                    self.add_notification(Notification::warning(
+                        format!(
+                            "Moderation flagged the {} of '{}' ({}; {})",
+                            stage,
+                            task_id,
+                            flagged.join(", "),
+                            action
+                        ),
+                        timestamp_ms,
+                    ));
+                    self.dirty.notifications = true;
+                }
+            }
+
+            EventKind::ToolBlocked {
+                task_id,
+                tool,
+                rule,
+            } => {
+                self.add_notification(Notification::warning(
+                    format!("🚫 '{}' blocked {} ({})", task_id, tool, rule),
+                    timestamp_ms,
+                ));
+                self.dirty.notifications = true;
+            }
+
             // ═══════════════════════════════════════════
             // BINDING EVENTS
             // ═══════════════════════════════════════════
@@ -1817,6 +2114,10 @@ impl TuiState {
                 self.dirty.reasoning = true;
             }
 
+            // Tool batch timing is for traces; the turn list already shows the calls
+            EventKind::AgentToolBatch { .. } => {}
+            EventKind::AgentTranscript { .. } => {}
+
             EventKind::AgentComplete { turns, .. } => {
                 // Update metrics
                 if let Some(last_turn) = self.agent_turns.last() {
@@ -1858,6 +2159,19 @@ impl TuiState {
             // ═══════════════════════════════════════════
             // PROVIDER EVENTS
             // ═══════════════════════════════════════════
+            EventKind::ModelRouted {
+                task_id,
+                tier,
+                model,
+                reason,
+            } => {
+                self.add_notification(Notification::info(
+                    format!("Routed '{}' to {} ({}, {})", task_id, model, tier, reason),
+                    timestamp_ms,
+                ));
+                self.dirty.notifications = true;
+            }
+
             EventKind::ProviderCalled {
                 task_id,
                 provider,
@@ -1866,14 +2180,14 @@ impl TuiState {
             } => {
                 // Update task's provider info
                 if let Some(task) = self.tasks.get_mut(task_id.as_ref()) {
-                    task.provider = Some(provider.clone());
-                    task.model = Some(model.clone());
+                    task.provider = Some(provider.to_string());
+                    task.model = Some(model.to_string());
                     task.prompt_len = Some(*prompt_len);
                 }
 
                 // Update metrics
                 self.metrics.provider_calls += 1;
-                self.metrics.last_model = Some(model.clone());
+                self.metrics.last_model = Some(model.to_string());
 
                 // TIER 4.1: Mark progress dirty (for provider display)
                 self.dirty.progress = true;
@@ -1883,6 +2197,7 @@ impl TuiState {
                 input_tokens,
                 output_tokens,
                 cache_read_tokens,
+                cache_write_tokens,
                 cost_usd,
                 ttft_ms,
                 ..
@@ -1890,6 +2205,7 @@ impl TuiState {
                 self.metrics.input_tokens += input_tokens;
                 self.metrics.output_tokens += output_tokens;
                 self.metrics.cache_read_tokens += cache_read_tokens;
+                self.metrics.cache_write_tokens += cache_write_tokens;
                 self.metrics.total_tokens += input_tokens + output_tokens;
                 self.metrics.cost_usd += cost_usd;
                 self.metrics
@@ -2283,6 +2599,26 @@ impl TuiState {
         }
     }
 
+    /// Edge previews for resolved bindings (v0.7)
+    ///
+    /// Maps a binding label (`{{use.alias}}`) to where its value came from,
+    /// e.g. `weather.summary @#12` or `weather.summary (default)`.
+    /// Format matches `DagAscii::with_previews`.
+    pub fn binding_previews(&self) -> HashMap<String, String> {
+        self.tasks
+            .values()
+            .flat_map(|task| task.bindings.iter())
+            .map(|b| {
+                let origin = match (b.used_default, b.source_event_id) {
+                    (true, _) => format!("{} (default)", b.path),
+                    (false, Some(id)) => format!("{} @#{}", b.path, id),
+                    (false, None) => b.path.clone(),
+                };
+                (format!("{{{{use.{}}}}}", b.alias), origin)
+            })
+            .collect()
+    }
+
     /// Check if a task has a breakpoint set (TIER 2.3)
     pub fn has_breakpoint(&self, task_id: &str) -> bool {
         self.breakpoints
@@ -2523,6 +2859,7 @@ impl TuiState {
                 task.duration_ms = None;
                 task.error = None;
                 task.output = None;
+                task.bindings.clear();
                 reset_tasks.push(task_id.clone());
             }
         }
@@ -2760,6 +3097,7 @@ mod tests {
                 task_count: 5,
                 generation_id: "gen-123".to_string(),
                 workflow_hash: "abc".to_string(),
+                workflow_name: None,
                 nika_version: TEST_VERSION.to_string(),
             },
             0,
@@ -2818,9 +3156,9 @@ mod tests {
         state.handle_event(
             &EventKind::McpInvoke {
                 task_id: Arc::from("task1"),
-                call_id: "test-call-1".to_string(),
-                mcp_server: "novanet".to_string(),
-                tool: Some("novanet_describe".to_string()),
+                call_id: "test-call-1".into(),
+                mcp_server: "novanet".into(),
+                tool: Some("novanet_describe".into()),
                 resource: None,
                 params: Some(test_params.clone()),
             },
@@ -2840,7 +3178,7 @@ mod tests {
         state.handle_event(
             &EventKind::McpResponse {
                 task_id: Arc::from("task1"),
-                call_id: "test-call-1".to_string(),
+                call_id: "test-call-1".into(),
                 output_len: 1024,
                 duration_ms: 100,
                 cached: false,
@@ -2864,9 +3202,9 @@ mod tests {
         state.handle_event(
             &EventKind::McpInvoke {
                 task_id: Arc::from("task1"),
-                call_id: "error-call-1".to_string(),
-                mcp_server: "novanet".to_string(),
-                tool: Some("novanet_traverse".to_string()),
+                call_id: "error-call-1".into(),
+                mcp_server: "novanet".into(),
+                tool: Some("novanet_traverse".into()),
                 resource: None,
                 params: Some(serde_json::json!({"invalid": "params"})),
             },
@@ -2876,7 +3214,7 @@ mod tests {
         state.handle_event(
             &EventKind::McpResponse {
                 task_id: Arc::from("task1"),
-                call_id: "error-call-1".to_string(),
+                call_id: "error-call-1".into(),
                 output_len: 50,
                 duration_ms: 25,
                 cached: false,
@@ -2902,9 +3240,9 @@ mod tests {
         state.handle_event(
             &EventKind::McpInvoke {
                 task_id: Arc::from("task1"),
-                call_id: "call-fr".to_string(),
-                mcp_server: "novanet".to_string(),
-                tool: Some("novanet_generate".to_string()),
+                call_id: "call-fr".into(),
+                mcp_server: "novanet".into(),
+                tool: Some("novanet_generate".into()),
                 resource: None,
                 params: Some(serde_json::json!({"locale": "fr-FR"})),
             },
@@ -2913,9 +3251,9 @@ mod tests {
         state.handle_event(
             &EventKind::McpInvoke {
                 task_id: Arc::from("task1"),
-                call_id: "call-en".to_string(),
-                mcp_server: "novanet".to_string(),
-                tool: Some("novanet_generate".to_string()),
+                call_id: "call-en".into(),
+                mcp_server: "novanet".into(),
+                tool: Some("novanet_generate".into()),
                 resource: None,
                 params: Some(serde_json::json!({"locale": "en-US"})),
             },
@@ -2930,7 +3268,7 @@ mod tests {
         state.handle_event(
             &EventKind::McpResponse {
                 task_id: Arc::from("task1"),
-                call_id: "call-en".to_string(),
+                call_id: "call-en".into(),
                 output_len: 500,
                 duration_ms: 50,
                 cached: false,
@@ -2949,7 +3287,7 @@ mod tests {
         state.handle_event(
             &EventKind::McpResponse {
                 task_id: Arc::from("task1"),
-                call_id: "call-fr".to_string(),
+                call_id: "call-fr".into(),
                 output_len: 600,
                 duration_ms: 120,
                 cached: false,
@@ -2990,6 +3328,40 @@ mod tests {
         assert!(!state.should_break(&event2));
     }
 
+    #[test]
+    fn test_binding_resolved_records_origin() {
+        let mut state = TuiState::new("test.yaml");
+        state.handle_event(
+            &EventKind::TaskScheduled {
+                task_id: Arc::from("report"),
+                dependencies: vec![Arc::from("weather")],
+            },
+            0,
+        );
+        state.handle_event(
+            &EventKind::BindingResolved {
+                task_id: Arc::from("report"),
+                alias: "forecast".into(),
+                source_task: Some(Arc::from("weather")),
+                path: "weather.summary".into(),
+                source_event_id: Some(7),
+                used_default: false,
+                lazy: false,
+            },
+            10,
+        );
+
+        let task = &state.tasks["report"];
+        assert_eq!(task.bindings.len(), 1);
+        assert_eq!(task.bindings[0].source_task.as_deref(), Some("weather"));
+
+        let previews = state.binding_previews();
+        assert_eq!(
+            previews.get("{{use.forecast}}").map(String::as_str),
+            Some("weather.summary @#7")
+        );
+    }
+
     // ═══════════════════════════════════════════
     // TIMELINE CACHE TESTS
     // ═══════════════════════════════════════════
@@ -3515,6 +3887,7 @@ mod tests {
                 provider: None,
                 model: None,
                 prompt_len: None,
+                bindings: vec![],
             },
         );
         state.tasks.insert(
@@ -3533,6 +3906,7 @@ mod tests {
                 provider: None,
                 model: None,
                 prompt_len: None,
+                bindings: vec![],
             },
         );
 
@@ -3834,6 +4208,7 @@ mod tests {
                 provider: None,
                 model: None,
                 prompt_len: None,
+                bindings: vec![],
             },
         );
         state.tasks.insert(
@@ -3852,6 +4227,7 @@ mod tests {
                 provider: None,
                 model: None,
                 prompt_len: None,
+                bindings: vec![],
             },
         );
 
@@ -4327,6 +4703,7 @@ mod tests {
                 task_count: 5,
                 generation_id: "gen-123".to_string(),
                 workflow_hash: "abc".to_string(),
+                workflow_name: None,
                 nika_version: TEST_VERSION.to_string(),
             },
             0,
@@ -4427,10 +4804,10 @@ mod tests {
         state.handle_event(
             &EventKind::McpInvoke {
                 task_id: "task1".into(),
-                mcp_server: "novanet".to_string(),
-                tool: Some("describe".to_string()),
+                mcp_server: "novanet".into(),
+                tool: Some("describe".into()),
                 resource: None,
-                call_id: "call1".to_string(),
+                call_id: "call1".into(),
                 params: None,
             },
             100,
@@ -4442,7 +4819,7 @@ mod tests {
             &EventKind::McpResponse {
                 task_id: "task1".into(),
                 output_len: 100,
-                call_id: "call1".to_string(),
+                call_id: "call1".into(),
                 duration_ms: 50,
                 cached: false,
                 is_error: false,
@@ -4485,7 +4862,7 @@ mod tests {
             &EventKind::AgentComplete {
                 task_id: "task1".into(),
                 turns: 1,
-                stop_reason: "natural".to_string(),
+                stop_reason: "natural".into(),
             },
             300,
         );
@@ -4643,6 +5020,7 @@ mod tests {
             &EventKind::WorkflowStarted {
                 task_count: 1,
                 workflow_hash: "hash-123".into(),
+                workflow_name: None,
                 generation_id: "gen-123".into(),
                 nika_version: "0.5.1".into(),
             },
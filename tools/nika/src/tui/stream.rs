@@ -0,0 +1,163 @@
+//! Per-frame coalescing of chat stream chunks (v0.7)
+//!
+//! Providers send one [`StreamChunk::Token`] per token into a bounded
+//! channel. Applying them one by one costs a view update per token, and a
+//! fast stream fills the channel faster than the UI drains it, stalling the
+//! producer. [`StreamCoalescer`] drains the channel once per frame and merges
+//! adjacent tokens (and thinking chunks) into a single chunk.
+//!
+//! The per-frame drain budget adapts: it doubles while the channel still
+//! holds chunks after a frame (a burst) and decays back once it keeps up.
+//! Channel capacity stays bounded either way; the producer simply waits.
+
+use tokio::sync::mpsc;
+
+use crate::provider::rig::StreamChunk;
+
+/// Chunks drained per frame when the stream keeps up
+pub const MIN_CHUNKS_PER_FRAME: usize = 256;
+
+/// Upper bound on chunks drained in one frame, however far behind
+pub const MAX_CHUNKS_PER_FRAME: usize = 16 * 1024;
+
+/// Adaptive per-frame drain of a chunk channel
+#[derive(Debug)]
+pub struct StreamCoalescer {
+    budget: usize,
+}
+
+impl Default for StreamCoalescer {
+    fn default() -> Self {
+        Self {
+            budget: MIN_CHUNKS_PER_FRAME,
+        }
+    }
+}
+
+impl StreamCoalescer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drain up to the budget from `rx`, merging adjacent text chunks
+    pub fn drain(&mut self, rx: &mut mpsc::Receiver<StreamChunk>) -> Vec<StreamChunk> {
+        let mut merged = Vec::new();
+        let mut drained = 0;
+        while drained < self.budget {
+            let Ok(chunk) = rx.try_recv() else {
+                break;
+            };
+            drained += 1;
+            push_merged(&mut merged, chunk);
+        }
+
+        self.budget = if drained == self.budget && !rx.is_empty() {
+            (self.budget * 2).min(MAX_CHUNKS_PER_FRAME)
+        } else {
+            (self.budget / 2).max(MIN_CHUNKS_PER_FRAME)
+        };
+        merged
+    }
+}
+
+/// Append `chunk`, folding it into the previous one when both are text
+fn push_merged(merged: &mut Vec<StreamChunk>, chunk: StreamChunk) {
+    match (merged.last_mut(), chunk) {
+        (Some(StreamChunk::Token(text)), StreamChunk::Token(token)) => text.push_str(&token),
+        // ChatView joins thinking chunks with newlines
+        (Some(StreamChunk::Thinking(text)), StreamChunk::Thinking(thinking)) => {
+            text.push('\n');
+            text.push_str(&thinking);
+        }
+        (_, chunk) => merged.push(chunk),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::views::ChatView;
+
+    #[test]
+    fn test_adjacent_tokens_merge_in_order() {
+        let (tx, mut rx) = mpsc::channel(16);
+        for chunk in [
+            StreamChunk::Token("Hel".into()),
+            StreamChunk::Token("lo".into()),
+            StreamChunk::Thinking("a".into()),
+            StreamChunk::Thinking("b".into()),
+            StreamChunk::Token("!".into()),
+            StreamChunk::Done("Hello!".into()),
+        ] {
+            tx.try_send(chunk).unwrap();
+        }
+
+        let chunks = StreamCoalescer::new().drain(&mut rx);
+        assert!(matches!(&chunks[..], [
+            StreamChunk::Token(a),
+            StreamChunk::Thinking(b),
+            StreamChunk::Token(c),
+            StreamChunk::Done(_),
+        ] if a == "Hello" && b == "a\nb" && c == "!"));
+    }
+
+    #[tokio::test]
+    async fn test_10k_token_burst_is_lossless_and_capped() {
+        const TOKENS: usize = 10_000;
+        let (tx, mut rx) = mpsc::channel(256);
+        let producer = tokio::spawn(async move {
+            for i in 0..TOKENS {
+                tx.send(StreamChunk::Token(format!("token-{:05} ", i)))
+                    .await
+                    .unwrap();
+            }
+            tx.send(StreamChunk::Done(String::new())).await.unwrap();
+        });
+
+        let mut view = ChatView::new();
+        view.add_nika_message("Thinking...".to_string(), None);
+        let mut coalescer = StreamCoalescer::new();
+        let (mut frames, mut max_updates, mut done) = (0, 0, false);
+        while !done {
+            let chunks = coalescer.drain(&mut rx);
+            max_updates = max_updates.max(chunks.len());
+            for chunk in chunks {
+                match chunk {
+                    StreamChunk::Token(text) => view.append_to_last_message(&text),
+                    StreamChunk::Done(_) => done = true,
+                    _ => unreachable!(),
+                }
+            }
+            frames += 1;
+            tokio::task::yield_now().await;
+        }
+        producer.await.unwrap();
+
+        // One view update per frame, not one per token
+        assert!(max_updates <= 2, "{} updates in a frame", max_updates);
+        assert!(frames < TOKENS / 10, "took {} frames", frames);
+
+        let expected: String = (0..TOKENS).map(|i| format!("token-{:05} ", i)).collect();
+        let last = view.messages.last().unwrap();
+        assert_eq!(last.text(), expected);
+        assert!(last.content.len() < expected.len());
+        assert!(last.content.ends_with(crate::tui::views::TRUNCATION_NOTICE));
+    }
+
+    #[test]
+    fn test_budget_adapts_to_backlog() {
+        let (tx, mut rx) = mpsc::channel(MAX_CHUNKS_PER_FRAME);
+        for _ in 0..(MIN_CHUNKS_PER_FRAME * 3) {
+            tx.try_send(StreamChunk::Token("x".into())).unwrap();
+        }
+
+        let mut coalescer = StreamCoalescer::new();
+        coalescer.drain(&mut rx);
+        assert_eq!(coalescer.budget, MIN_CHUNKS_PER_FRAME * 2);
+        coalescer.drain(&mut rx);
+        assert!(rx.is_empty());
+        // Caught up: decays back to the minimum
+        coalescer.drain(&mut rx);
+        assert_eq!(coalescer.budget, MIN_CHUNKS_PER_FRAME);
+    }
+}
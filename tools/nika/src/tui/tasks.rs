@@ -0,0 +1,189 @@
+//! Background task group for the TUI (v0.7)
+//!
+//! Every task the TUI spawns (chat requests, MCP connects, workflow runs)
+//! goes through [`TaskGroup`]. Each task carries a label and a [`TaskScope`]:
+//! view-scoped tasks are aborted when the user leaves that view, app-scoped
+//! tasks (workflow runs) only when the TUI exits. Nothing outlives the TUI,
+//! so a quit chat no longer keeps a request streaming tokens.
+//!
+//! Finished tasks are pruned lazily; [`TaskGroup::live`] feeds the
+//! background tasks overlay (`F12`).
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::task::AbortHandle;
+
+use super::views::TuiView;
+
+/// Lifetime of a background task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskScope {
+    /// Aborted when leaving this view
+    View(TuiView),
+    /// Aborted when the TUI exits
+    App,
+}
+
+/// Snapshot of a live task (for the overlay)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundTask {
+    pub id: u64,
+    pub label: String,
+    pub scope: TaskScope,
+    pub elapsed: Duration,
+}
+
+struct Tracked {
+    id: u64,
+    label: String,
+    scope: TaskScope,
+    started: Instant,
+    handle: AbortHandle,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: u64,
+    tasks: Vec<Tracked>,
+}
+
+impl Inner {
+    fn prune(&mut self) {
+        self.tasks.retain(|task| !task.handle.is_finished());
+    }
+
+    /// Abort and drop every task matching `filter`, returning how many were live
+    fn abort_where(&mut self, filter: impl Fn(&Tracked) -> bool) -> usize {
+        self.prune();
+        let mut aborted = 0;
+        self.tasks.retain(|task| {
+            if !filter(task) {
+                return true;
+            }
+            task.handle.abort();
+            aborted += 1;
+            false
+        });
+        aborted
+    }
+}
+
+/// Labelled, scoped set of spawned tasks (cheap to clone, shared state)
+#[derive(Clone, Default)]
+pub struct TaskGroup {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl TaskGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().expect("task group mutex poisoned")
+    }
+
+    /// Spawn `future` on the runtime and track it
+    pub fn spawn<F>(&self, label: impl Into<String>, scope: TaskScope, future: F) -> u64
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(future).abort_handle();
+        let mut inner = self.lock();
+        inner.prune();
+        inner.next_id += 1;
+        let id = inner.next_id;
+        inner.tasks.push(Tracked {
+            id,
+            label: label.into(),
+            scope,
+            started: Instant::now(),
+            handle,
+        });
+        id
+    }
+
+    /// Tasks still running, oldest first
+    pub fn live(&self) -> Vec<BackgroundTask> {
+        let mut inner = self.lock();
+        inner.prune();
+        inner
+            .tasks
+            .iter()
+            .map(|task| BackgroundTask {
+                id: task.id,
+                label: task.label.clone(),
+                scope: task.scope,
+                elapsed: task.started.elapsed(),
+            })
+            .collect()
+    }
+
+    /// Abort tasks scoped to `view`; returns how many were still running
+    pub fn cancel_view(&self, view: TuiView) -> usize {
+        self.lock()
+            .abort_where(|task| task.scope == TaskScope::View(view))
+    }
+
+    /// Abort every task; returns how many were still running
+    pub fn cancel_all(&self) -> usize {
+        self.lock().abort_where(|_| true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending() -> impl Future<Output = ()> + Send + 'static {
+        std::future::pending()
+    }
+
+    #[tokio::test]
+    async fn test_finished_tasks_are_pruned() {
+        let group = TaskGroup::new();
+        group.spawn("quick", TaskScope::App, async {});
+        group.spawn("slow", TaskScope::App, pending());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let live = group.live();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].label, "slow");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_view_only_aborts_that_view() {
+        let group = TaskGroup::new();
+        group.spawn("/infer", TaskScope::View(TuiView::Chat), pending());
+        group.spawn("/agent", TaskScope::View(TuiView::Chat), pending());
+        group.spawn("overlay", TaskScope::View(TuiView::Monitor), pending());
+        group.spawn("workflow", TaskScope::App, pending());
+
+        assert_eq!(group.cancel_view(TuiView::Chat), 2);
+        assert_eq!(group.cancel_view(TuiView::Chat), 0);
+        let labels: Vec<_> = group.live().into_iter().map(|t| t.label).collect();
+        assert_eq!(labels, vec!["overlay", "workflow"]);
+
+        assert_eq!(group.cancel_all(), 2);
+        assert!(group.live().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_aborted_task_stops_running() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let group = TaskGroup::new();
+        let completed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&completed);
+        group.spawn("sleep", TaskScope::View(TuiView::Chat), async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        group.cancel_view(TuiView::Chat);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!completed.load(Ordering::SeqCst));
+    }
+}
@@ -190,33 +190,81 @@ impl ColorMode {
 /// Verb-specific colors for DAG visualization
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VerbColor {
-    Infer,  // Violet #8B5CF6
-    Exec,   // Amber #F59E0B
-    Fetch,  // Cyan #06B6D4
-    Invoke, // Emerald #10B981
-    Agent,  // Rose #F43F5E
+    Infer,      // Violet #8B5CF6
+    Exec,       // Amber #F59E0B
+    Fetch,      // Cyan #06B6D4
+    Invoke,     // Emerald #10B981
+    Agent,      // Rose #F43F5E
+    Reduce,     // Lime #84CC16
+    Approve,    // Orange #F97316
+    Embed,      // Indigo #6366F1
+    Recall,     // Fuchsia #D946EF
+    Retrieve,   // Teal #14B8A6
+    Validate,   // Sky #0EA5E9
+    Transcribe, // Pink #EC4899
+    Import,     // Yellow #EAB308
+    Export,     // Purple #A855F7
+    Rows,       // Blue #3B82F6
+    Script,     // Slate #64748B
+    Chunk,      // Lime #84CC16
+    Dedupe,     // Rose #F43F5E
+    DetectLang, // Sky #0EA5E9
+    Translate,  // Violet #8B5CF6
+    Ws,         // Green #22C55E
 }
 
 impl VerbColor {
     /// Get the RGB color for this verb
     pub fn rgb(&self) -> Color {
         match self {
-            Self::Infer => Color::Rgb(139, 92, 246),  // Violet
-            Self::Exec => Color::Rgb(245, 158, 11),   // Amber
-            Self::Fetch => Color::Rgb(6, 182, 212),   // Cyan
-            Self::Invoke => Color::Rgb(16, 185, 129), // Emerald
-            Self::Agent => Color::Rgb(244, 63, 94),   // Rose
+            Self::Infer => Color::Rgb(139, 92, 246),      // Violet
+            Self::Exec => Color::Rgb(245, 158, 11),       // Amber
+            Self::Fetch => Color::Rgb(6, 182, 212),       // Cyan
+            Self::Invoke => Color::Rgb(16, 185, 129),     // Emerald
+            Self::Agent => Color::Rgb(244, 63, 94),       // Rose
+            Self::Reduce => Color::Rgb(132, 204, 22),     // Lime
+            Self::Approve => Color::Rgb(249, 115, 22),    // Orange
+            Self::Embed => Color::Rgb(99, 102, 241),      // Indigo
+            Self::Recall => Color::Rgb(217, 70, 239),     // Fuchsia
+            Self::Retrieve => Color::Rgb(20, 184, 166),   // Teal
+            Self::Validate => Color::Rgb(14, 165, 233),   // Sky
+            Self::Transcribe => Color::Rgb(236, 72, 153), // Pink
+            Self::Import => Color::Rgb(234, 179, 8),      // Yellow
+            Self::Export => Color::Rgb(168, 85, 247),     // Purple
+            Self::Rows => Color::Rgb(59, 130, 246),       // Blue
+            Self::Script => Color::Rgb(100, 116, 139),    // Slate
+            Self::Chunk => Color::Rgb(132, 204, 22),      // Lime
+            Self::Dedupe => Color::Rgb(244, 63, 94),      // Rose
+            Self::DetectLang => Color::Rgb(14, 165, 233), // Sky
+            Self::Translate => Color::Rgb(139, 92, 246),  // Violet
+            Self::Ws => Color::Rgb(34, 197, 94),          // Green
         }
     }
 
     /// Get glow version (brighter for active/hover states)
     pub fn glow(&self) -> Color {
         match self {
-            Self::Infer => Color::Rgb(167, 139, 250), // Violet-400
-            Self::Exec => Color::Rgb(251, 191, 36),   // Amber-400
-            Self::Fetch => Color::Rgb(34, 211, 238),  // Cyan-400
-            Self::Invoke => Color::Rgb(52, 211, 153), // Emerald-400
-            Self::Agent => Color::Rgb(251, 113, 133), // Rose-400
+            Self::Infer => Color::Rgb(167, 139, 250),      // Violet-400
+            Self::Exec => Color::Rgb(251, 191, 36),        // Amber-400
+            Self::Fetch => Color::Rgb(34, 211, 238),       // Cyan-400
+            Self::Invoke => Color::Rgb(52, 211, 153),      // Emerald-400
+            Self::Agent => Color::Rgb(251, 113, 133),      // Rose-400
+            Self::Reduce => Color::Rgb(163, 230, 53),      // Lime-400
+            Self::Approve => Color::Rgb(251, 146, 60),     // Orange-400
+            Self::Embed => Color::Rgb(129, 140, 248),      // Indigo-400
+            Self::Recall => Color::Rgb(232, 121, 249),     // Fuchsia-400
+            Self::Retrieve => Color::Rgb(45, 212, 191),    // Teal-400
+            Self::Validate => Color::Rgb(56, 189, 248),    // Sky-400
+            Self::Transcribe => Color::Rgb(244, 114, 182), // Pink-400
+            Self::Import => Color::Rgb(250, 204, 21),      // Yellow-400
+            Self::Export => Color::Rgb(192, 132, 252),     // Purple-400
+            Self::Rows => Color::Rgb(96, 165, 250),        // Blue-400
+            Self::Script => Color::Rgb(148, 163, 184),     // Slate-400
+            Self::Chunk => Color::Rgb(163, 230, 53),       // Lime-400
+            Self::Dedupe => Color::Rgb(251, 113, 133),     // Rose-400
+            Self::DetectLang => Color::Rgb(56, 189, 248),  // Sky-400
+            Self::Translate => Color::Rgb(167, 139, 250),  // Violet-400
+            Self::Ws => Color::Rgb(74, 222, 128),          // Green-400
         }
     }
 
@@ -228,28 +276,76 @@ impl VerbColor {
             Self::Fetch => Color::Rgb(4, 127, 148),
             Self::Invoke => Color::Rgb(11, 129, 90),
             Self::Agent => Color::Rgb(170, 44, 66),
+            Self::Reduce => Color::Rgb(92, 143, 15),
+            Self::Approve => Color::Rgb(174, 80, 15),
+            Self::Embed => Color::Rgb(72, 74, 176),
+            Self::Recall => Color::Rgb(157, 50, 172),
+            Self::Retrieve => Color::Rgb(14, 129, 116),
+            Self::Validate => Color::Rgb(10, 116, 163),
+            Self::Transcribe => Color::Rgb(168, 50, 108),
+            Self::Import => Color::Rgb(161, 124, 6),
+            Self::Export => Color::Rgb(118, 58, 174),
+            Self::Rows => Color::Rgb(41, 91, 172),
+            Self::Script => Color::Rgb(70, 81, 97),
+            Self::Chunk => Color::Rgb(92, 143, 15),
+            Self::Dedupe => Color::Rgb(171, 44, 66),
+            Self::DetectLang => Color::Rgb(3, 105, 161),
+            Self::Translate => Color::Rgb(91, 33, 182),
+            Self::Ws => Color::Rgb(21, 128, 61),
         }
     }
 
     /// Get subtle version (very muted for backgrounds)
     pub fn subtle(&self) -> Color {
         match self {
-            Self::Infer => Color::Rgb(55, 48, 83),  // Violet-950/50
-            Self::Exec => Color::Rgb(69, 53, 18),   // Amber-950/50
-            Self::Fetch => Color::Rgb(22, 57, 67),  // Cyan-950/50
-            Self::Invoke => Color::Rgb(20, 61, 47), // Emerald-950/50
-            Self::Agent => Color::Rgb(68, 32, 41),  // Rose-950/50
+            Self::Infer => Color::Rgb(55, 48, 83),      // Violet-950/50
+            Self::Exec => Color::Rgb(69, 53, 18),       // Amber-950/50
+            Self::Fetch => Color::Rgb(22, 57, 67),      // Cyan-950/50
+            Self::Invoke => Color::Rgb(20, 61, 47),     // Emerald-950/50
+            Self::Agent => Color::Rgb(68, 32, 41),      // Rose-950/50
+            Self::Reduce => Color::Rgb(45, 62, 20),     // Lime-950/50
+            Self::Approve => Color::Rgb(67, 37, 20),    // Orange-950/50
+            Self::Embed => Color::Rgb(40, 41, 80),      // Indigo-950/50
+            Self::Recall => Color::Rgb(66, 30, 72),     // Fuchsia-950/50
+            Self::Retrieve => Color::Rgb(19, 60, 56),   // Teal-950/50
+            Self::Validate => Color::Rgb(20, 52, 71),   // Sky-950/50
+            Self::Transcribe => Color::Rgb(70, 30, 50), // Pink-950/50
+            Self::Import => Color::Rgb(66, 56, 16),     // Yellow-950/50
+            Self::Export => Color::Rgb(60, 36, 80),     // Purple-950/50
+            Self::Rows => Color::Rgb(24, 43, 74),       // Blue-950/50
+            Self::Script => Color::Rgb(30, 35, 45),     // Slate-950/50
+            Self::Chunk => Color::Rgb(33, 50, 12),      // Lime-950/50
+            Self::Dedupe => Color::Rgb(70, 20, 30),     // Rose-950/50
+            Self::DetectLang => Color::Rgb(8, 40, 60),  // Sky-950/50
+            Self::Translate => Color::Rgb(40, 20, 70),  // Violet-950/50
+            Self::Ws => Color::Rgb(10, 50, 25),         // Green-950/50
         }
     }
 
     /// Get icon for this verb (matches CLAUDE.md canonical icons)
     pub fn icon(&self) -> &'static str {
         match self {
-            Self::Infer => "⚡",  // LLM generation
-            Self::Exec => "📟",   // Shell command
-            Self::Fetch => "🛰️",  // HTTP request
-            Self::Invoke => "🔌", // MCP tool
-            Self::Agent => "🐔",  // Agentic loop (parent)
+            Self::Infer => "⚡",      // LLM generation
+            Self::Exec => "📟",       // Shell command
+            Self::Fetch => "🛰️",      // HTTP request
+            Self::Invoke => "🔌",     // MCP tool
+            Self::Agent => "🐔",      // Agentic loop (parent)
+            Self::Reduce => "🧮",     // Array aggregation
+            Self::Approve => "✋",    // Human-in-the-loop
+            Self::Embed => "🧬",      // Embeddings
+            Self::Recall => "🔎",     // Vector search
+            Self::Retrieve => "📚",   // Document retrieval
+            Self::Validate => "🛡️",   // Data quality gate
+            Self::Transcribe => "🎙️", // Speech to text
+            Self::Import => "📥",     // Spreadsheet in
+            Self::Export => "📤",     // Spreadsheet out
+            Self::Rows => "🗃️",       // Row batch ops
+            Self::Script => "📜",     // Sandboxed script
+            Self::Chunk => "✂️",      // Text splitting
+            Self::Dedupe => "🧹",     // Near-duplicate removal
+            Self::DetectLang => "🏳️", // Language detection
+            Self::Translate => "🌐",  // Translation
+            Self::Ws => "📶",         // WebSocket stream
         }
     }
 
@@ -266,6 +362,22 @@ impl VerbColor {
             Self::Fetch => "[F]",
             Self::Invoke => "[V]",
             Self::Agent => "[A]",
+            Self::Reduce => "[R]",
+            Self::Approve => "[H]",
+            Self::Embed => "[E]",
+            Self::Recall => "[Q]",
+            Self::Retrieve => "[D]",
+            Self::Validate => "[C]",
+            Self::Transcribe => "[S]",
+            Self::Import => "[L]",
+            Self::Export => "[W]",
+            Self::Rows => "[T]",
+            Self::Script => "[P]",
+            Self::Chunk => "[K]",
+            Self::Dedupe => "[U]",
+            Self::DetectLang => "[G]",
+            Self::Translate => "[N]",
+            Self::Ws => "[B]",
         }
     }
 
@@ -277,6 +389,22 @@ impl VerbColor {
             "fetch" => Self::Fetch,
             "invoke" => Self::Invoke,
             "agent" => Self::Agent,
+            "reduce" => Self::Reduce,
+            "approve" => Self::Approve,
+            "embed" => Self::Embed,
+            "recall" => Self::Recall,
+            "retrieve" => Self::Retrieve,
+            "validate" => Self::Validate,
+            "transcribe" => Self::Transcribe,
+            "import" => Self::Import,
+            "export" => Self::Export,
+            "rows" => Self::Rows,
+            "script" => Self::Script,
+            "chunk" => Self::Chunk,
+            "dedupe" => Self::Dedupe,
+            "detect_lang" => Self::DetectLang,
+            "translate" => Self::Translate,
+            "ws" => Self::Ws,
             _ => Self::Infer, // default
         }
     }
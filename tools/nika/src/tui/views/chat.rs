@@ -75,6 +75,44 @@ pub struct ChatMessage {
     /// Optional agent thinking/reasoning content (v0.5.2+)
     /// Displayed inline when present (collapsible in UI)
     pub thinking: Option<String>,
+    /// Complete text once `content` was capped at [`MAX_MESSAGE_DISPLAY_BYTES`] (v0.7)
+    pub full_text: Option<String>,
+}
+
+/// Longest message text rendered in the chat view (v0.7)
+///
+/// Beyond this, wrapping the message every frame stalls the UI; the view keeps
+/// the head plus [`TRUNCATION_NOTICE`] and the message keeps the full text.
+pub const MAX_MESSAGE_DISPLAY_BYTES: usize = 64 * 1024;
+
+/// Shown in place of the text beyond [`MAX_MESSAGE_DISPLAY_BYTES`]
+pub const TRUNCATION_NOTICE: &str = "…output truncated in view, full text preserved";
+
+impl ChatMessage {
+    /// Complete message text, including any part truncated in the view
+    pub fn text(&self) -> &str {
+        self.full_text.as_deref().unwrap_or(&self.content)
+    }
+
+    /// Append text, capping what is displayed
+    pub fn push_text(&mut self, text: &str) {
+        if let Some(full) = &mut self.full_text {
+            full.push_str(text);
+            return;
+        }
+        self.content.push_str(text);
+        if self.content.len() > MAX_MESSAGE_DISPLAY_BYTES {
+            let full = self.content.clone();
+            let mut cut = MAX_MESSAGE_DISPLAY_BYTES;
+            while !self.content.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            self.content.truncate(cut);
+            self.content.push_str("\n\n");
+            self.content.push_str(TRUNCATION_NOTICE);
+            self.full_text = Some(full);
+        }
+    }
 }
 
 /// Inline execution result in chat
@@ -141,7 +179,7 @@ impl From<&ChatMessage> for SerializableMessage {
     fn from(msg: &ChatMessage) -> Self {
         Self {
             role: (&msg.role).into(),
-            content: msg.content.clone(),
+            content: msg.text().to_string(),
             thinking: msg.thinking.clone(),
         }
     }
@@ -298,6 +336,7 @@ impl ChatView {
                     "Welcome to Nika Agent. Type a message to chat, or use /help for commands."
                         .to_string(),
                 thinking: None,
+                full_text: None,
                 timestamp: Instant::now(),
                 execution: None,
             }],
@@ -423,13 +462,16 @@ impl ChatView {
         // Clear current messages and load from session
         self.messages.clear();
         for msg in session.messages {
-            self.messages.push(ChatMessage {
+            let mut message = ChatMessage {
                 role: msg.role.into(),
-                content: msg.content,
+                content: String::new(),
                 timestamp: Instant::now(), // Use current time since original is lost
                 execution: None,
                 thinking: msg.thinking,
-            });
+                full_text: None,
+            };
+            message.push_text(&msg.content);
+            self.messages.push(message);
         }
 
         // Update model if specified in session
@@ -497,6 +539,7 @@ impl ChatView {
             timestamp: Instant::now(),
             execution: None,
             thinking: None,
+            full_text: None,
         });
     }
 
@@ -631,6 +674,7 @@ impl ChatView {
             timestamp: Instant::now(),
             execution: None,
             thinking: None,
+            full_text: None,
         });
         self.history.push(content);
         self.history_index = None;
@@ -638,13 +682,17 @@ impl ChatView {
 
     /// Add a Nika response
     pub fn add_nika_message(&mut self, content: String, execution: Option<ExecutionResult>) {
-        self.messages.push(ChatMessage {
+        let mut message = ChatMessage {
             role: MessageRole::Nika,
-            content,
+            content: String::new(),
             timestamp: Instant::now(),
             execution,
             thinking: None,
-        });
+            full_text: None,
+        };
+        // Large /exec or /fetch output is capped in view like streams (v0.7)
+        message.push_text(&content);
+        self.messages.push(message);
     }
 
     /// Add a Nika response with thinking content (v0.5.2+)
@@ -660,6 +708,7 @@ impl ChatView {
             timestamp: Instant::now(),
             execution,
             thinking,
+            full_text: None,
         });
     }
 
@@ -671,6 +720,7 @@ impl ChatView {
             timestamp: Instant::now(),
             execution: None,
             thinking: None,
+            full_text: None,
         });
     }
 
@@ -681,12 +731,11 @@ impl ChatView {
     pub fn append_to_last_message(&mut self, token: &str) {
         if let Some(last) = self.messages.last_mut() {
             // If it's "Thinking...", replace it with the first token
-            if last.content == "Thinking..." {
-                last.content = token.to_string();
-            } else {
-                // Append token to existing content
-                last.content.push_str(token);
+            if last.content == "Thinking..." && last.full_text.is_none() {
+                last.content.clear();
             }
+            // Append token to existing content (capped in view, v0.7)
+            last.push_text(token);
         }
     }
 
@@ -2268,6 +2317,7 @@ mod tests {
             timestamp: Instant::now(),
             execution: None,
             thinking: Some("Let me analyze this step by step...".to_string()),
+            full_text: None,
         };
 
         assert!(msg.thinking.is_some());
@@ -2644,6 +2694,29 @@ mod tests {
         assert_eq!(last.thinking, Some("My reasoning...".to_string()));
     }
 
+    #[test]
+    fn test_chat_session_preserves_truncated_text() {
+        use tempfile::tempdir;
+
+        // Multi-byte chars so the cap falls inside one
+        let long = "é".repeat(MAX_MESSAGE_DISPLAY_BYTES);
+        let mut view = ChatView::new();
+        view.add_nika_message(long.clone(), None);
+        let last = view.messages.last().unwrap();
+        assert!(last.content.ends_with(TRUNCATION_NOTICE));
+        assert!(last.content.len() <= MAX_MESSAGE_DISPLAY_BYTES + TRUNCATION_NOTICE.len() + 2);
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("long-session.json");
+        view.save_session(&path).unwrap();
+        let mut view2 = ChatView::new();
+        view2.load_session(&path).unwrap();
+
+        let last = view2.messages.last().unwrap();
+        assert_eq!(last.text(), long);
+        assert!(last.content.ends_with(TRUNCATION_NOTICE));
+    }
+
     #[test]
     fn test_default_session_path() {
         let path = ChatView::default_session_path();
@@ -7,6 +7,8 @@
 //! +-----------------------------------+---------------------------------------------+
 //! | FILES (40%)                       | PREVIEW (60%)                               |
 //! | Tree view of .nika.yaml files     | YAML syntax highlighted                     |
+//! |                                   +---------------------------------------------+
+//! |                                   | PRESETS: saved `nika run` presets (v0.7)    |
 //! +-----------------------------------+---------------------------------------------+
 //! | HISTORY: recent workflow runs (toggleable with [h])                             |
 //! +---------------------------------------------------------------------------------+
@@ -296,6 +298,35 @@ impl HomeView {
         frame.render_widget(paragraph, area);
     }
 
+    /// Render the saved run presets of the selected workflow (v0.7)
+    fn render_presets(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let items: Vec<ListItem> = self
+            .standalone
+            .presets
+            .iter()
+            .map(|(name, preset)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<12} ", name),
+                        Style::default()
+                            .fg(theme.highlight)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(preset.summary(), Style::default().fg(theme.text_muted)),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" PRESETS · nika run <file> --preset NAME ")
+                .border_style(Style::default().fg(theme.border_normal)),
+        );
+
+        frame.render_widget(list, area);
+    }
+
     /// Render the welcome screen (v0.5.2+)
     fn render_welcome(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let welcome_lines = vec![
@@ -505,8 +536,18 @@ impl View for HomeView {
         // Files panel (left 40%)
         self.render_files(frame, main_chunks[0], theme);
 
-        // Preview panel (right 60%)
-        self.render_preview(frame, main_chunks[1], theme);
+        // Preview panel (right 60%), with the workflow's presets below
+        if self.standalone.presets.is_empty() {
+            self.render_preview(frame, main_chunks[1], theme);
+        } else {
+            let preset_height = (self.standalone.presets.len() as u16).min(6) + 2;
+            let preview_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(5), Constraint::Length(preset_height)])
+                .split(main_chunks[1]);
+            self.render_preview(frame, preview_chunks[0], theme);
+            self.render_presets(frame, preview_chunks[1], theme);
+        }
 
         // History bar (bottom)
         self.render_history(frame, chunks[1], theme);
@@ -726,6 +767,43 @@ mod tests {
         assert!(status.contains("0 in history"));
     }
 
+    #[test]
+    fn test_presets_listed_for_selected_workflow() {
+        use crate::store::{PresetStore, RunPreset, PRESETS_DIR};
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let dir = tempfile::tempdir().unwrap();
+        let workflow = dir.path().join("post.nika.yaml");
+        std::fs::write(&workflow, "schema: nika/workflow@0.5\ntasks: []\n").unwrap();
+        let preset = RunPreset {
+            provider: Some("mock".to_string()),
+            ..RunPreset::default()
+        };
+        PresetStore::open(dir.path().join(PRESETS_DIR))
+            .save(&workflow, "nightly", &preset)
+            .unwrap();
+
+        let view = HomeView::new(dir.path().to_path_buf());
+        assert_eq!(view.standalone.presets.len(), 1);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let state = TuiState::new("test.nika.yaml");
+        terminal
+            .draw(|frame| view.render(frame, frame.area(), &state, &Theme::novanet()))
+            .unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(content.contains("PRESETS"));
+        assert!(content.contains("nightly"));
+        assert!(content.contains("provider=mock"));
+    }
+
     // === Welcome Screen Tests (MEDIUM 13) ===
 
     #[test]
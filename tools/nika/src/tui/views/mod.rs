@@ -28,7 +28,7 @@ mod studio;
 mod trait_view;
 // ChatMode exported for future external use (mode indicator in status bar)
 #[allow(unused_imports)]
-pub use chat::{ChatMode, ChatView, MessageRole};
+pub use chat::{ChatMode, ChatView, MessageRole, TRUNCATION_NOTICE};
 pub use home::HomeView;
 pub use studio::{EditorMode, StudioView};
 // Future export: ValidationResult
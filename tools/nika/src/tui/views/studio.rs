@@ -397,7 +397,7 @@ impl Default for StudioView {
 }
 
 impl View for StudioView {
-    fn render(&self, frame: &mut Frame, area: Rect, _state: &TuiState, theme: &Theme) {
+    fn render(&self, frame: &mut Frame, area: Rect, state: &TuiState, theme: &Theme) {
         // Layout: Editor (70%) | Structure (30%) above, Validation bar below
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -413,7 +413,7 @@ impl View for StudioView {
         self.render_editor(frame, main_chunks[0], theme);
 
         // Structure panel
-        self.render_structure(frame, main_chunks[1], theme);
+        self.render_structure(frame, main_chunks[1], state, theme);
 
         // Validation bar
         self.render_validation(frame, chunks[1], theme);
@@ -614,7 +614,7 @@ impl StudioView {
         }
     }
 
-    fn render_structure(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+    fn render_structure(&self, frame: &mut Frame, area: Rect, state: &TuiState, theme: &Theme) {
         // Title shows mode state
         let title = if self.dag_expanded {
             " STRUCTURE [E]xpanded "
@@ -632,11 +632,18 @@ impl StudioView {
 
         // Parse YAML and render with DagAscii
         let content = self.buffer.content();
-        self.render_dag_structure(frame, inner, &content, theme);
+        self.render_dag_structure(frame, inner, &content, state, theme);
     }
 
     /// Render DAG structure using DagAscii widget
-    fn render_dag_structure(&self, frame: &mut Frame, area: Rect, yaml: &str, theme: &Theme) {
+    fn render_dag_structure(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        yaml: &str,
+        state: &TuiState,
+        theme: &Theme,
+    ) {
         // Try to parse as workflow YAML
         let workflow: Result<Workflow, _> = serde_yaml::from_str(yaml);
 
@@ -669,8 +676,11 @@ impl StudioView {
                 };
 
                 // Create and render DagAscii widget
+                // Edge labels from use: wiring, previews from resolved provenance (v0.7)
                 let widget = DagAscii::new(&nodes)
                     .with_dependencies(deps)
+                    .with_bindings(self.extract_use_bindings(&wf))
+                    .with_previews(state.binding_previews())
                     .mode(mode)
                     .scroll(0, self.dag_scroll);
 
@@ -712,6 +722,27 @@ impl StudioView {
         deps
     }
 
+    /// Extract edge binding labels from use: blocks
+    ///
+    /// Returns a map: source_task_id -> [(target_task_id, "{{use.alias}}")]
+    fn extract_use_bindings(&self, wf: &Workflow) -> HashMap<String, Vec<(String, String)>> {
+        let mut bindings: HashMap<String, Vec<(String, String)>> = HashMap::new();
+
+        for task in &wf.tasks {
+            let Some(wiring) = task.use_wiring.as_ref() else {
+                continue;
+            };
+            for (alias, entry) in wiring {
+                bindings
+                    .entry(entry.task_id().to_string())
+                    .or_default()
+                    .push((task.id.clone(), format!("{{{{use.{}}}}}", alias)));
+            }
+        }
+
+        bindings
+    }
+
     /// Get VerbColor from task action
     fn task_verb_color(&self, task: &crate::ast::Task) -> VerbColor {
         use crate::ast::TaskAction;
@@ -721,6 +752,22 @@ impl StudioView {
             TaskAction::Fetch { .. } => VerbColor::Fetch,
             TaskAction::Invoke { .. } => VerbColor::Invoke,
             TaskAction::Agent { .. } => VerbColor::Agent,
+            TaskAction::Reduce { .. } => VerbColor::Reduce,
+            TaskAction::Approve { .. } => VerbColor::Approve,
+            TaskAction::Embed { .. } => VerbColor::Embed,
+            TaskAction::Recall { .. } => VerbColor::Recall,
+            TaskAction::Retrieve { .. } => VerbColor::Retrieve,
+            TaskAction::Validate { .. } => VerbColor::Validate,
+            TaskAction::Transcribe { .. } => VerbColor::Transcribe,
+            TaskAction::Import { .. } => VerbColor::Import,
+            TaskAction::Export { .. } => VerbColor::Export,
+            TaskAction::Rows { .. } => VerbColor::Rows,
+            TaskAction::Script { .. } => VerbColor::Script,
+            TaskAction::Chunk { .. } => VerbColor::Chunk,
+            TaskAction::Dedupe { .. } => VerbColor::Dedupe,
+            TaskAction::DetectLang { .. } => VerbColor::DetectLang,
+            TaskAction::Translate { .. } => VerbColor::Translate,
+            TaskAction::Ws { .. } => VerbColor::Ws,
         }
     }
 
@@ -945,6 +992,29 @@ unknown_field: "should fail""#;
         );
     }
 
+    #[test]
+    fn test_extract_use_bindings() {
+        let view = StudioView::new();
+        let yaml = r#"schema: "nika/workflow@0.5"
+tasks:
+  - id: weather
+    exec: "echo sunny"
+  - id: report
+    use:
+      forecast: weather.summary
+    infer: "Report {{use.forecast}}""#;
+        let wf: Workflow = serde_yaml::from_str(yaml).unwrap();
+
+        let bindings = view.extract_use_bindings(&wf);
+        assert_eq!(
+            bindings.get("weather"),
+            Some(&vec![(
+                "report".to_string(),
+                "{{use.forecast}}".to_string()
+            )])
+        );
+    }
+
     // ═══════════════════════════════════════════════════════════════════════════
     // TextBuffer tests
     // ═══════════════════════════════════════════════════════════════════════════
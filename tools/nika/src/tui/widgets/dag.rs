@@ -22,6 +22,22 @@ pub enum VerbType {
     Fetch,
     Invoke,
     Agent,
+    Reduce,
+    Approve,
+    Embed,
+    Recall,
+    Retrieve,
+    Validate,
+    Transcribe,
+    Import,
+    Export,
+    Rows,
+    Script,
+    Chunk,
+    Dedupe,
+    DetectLang,
+    Translate,
+    Ws,
 }
 
 impl VerbType {
@@ -29,11 +45,27 @@ impl VerbType {
     pub fn icon(&self) -> &'static str {
         match self {
             Self::Unknown => "📋",
-            Self::Infer => "⚡",  // LLM generation
-            Self::Exec => "📟",   // Shell command
-            Self::Fetch => "🛰️",  // HTTP request
-            Self::Invoke => "🔌", // MCP tool
-            Self::Agent => "🐔",  // Agentic loop (parent)
+            Self::Infer => "⚡",      // LLM generation
+            Self::Exec => "📟",       // Shell command
+            Self::Fetch => "🛰️",      // HTTP request
+            Self::Invoke => "🔌",     // MCP tool
+            Self::Agent => "🐔",      // Agentic loop (parent)
+            Self::Reduce => "🧮",     // Array aggregation
+            Self::Approve => "✋",    // Human-in-the-loop
+            Self::Embed => "🧬",      // Embeddings
+            Self::Recall => "🔎",     // Vector search
+            Self::Retrieve => "📚",   // Document retrieval
+            Self::Validate => "🛡️",   // Data quality gate
+            Self::Transcribe => "🎙️", // Speech to text
+            Self::Import => "📥",     // Spreadsheet in
+            Self::Export => "📤",     // Spreadsheet out
+            Self::Rows => "🗃️",       // Row batch ops
+            Self::Script => "📜",     // Sandboxed script
+            Self::Chunk => "✂️",      // Text splitting
+            Self::Dedupe => "🧹",     // Near-duplicate removal
+            Self::DetectLang => "🏳️", // Language detection
+            Self::Translate => "🌐",  // Translation
+            Self::Ws => "📶",         // WebSocket stream
         }
     }
 
@@ -50,6 +82,22 @@ impl VerbType {
             "fetch" => Self::Fetch,
             "invoke" => Self::Invoke,
             "agent" => Self::Agent,
+            "reduce" => Self::Reduce,
+            "approve" => Self::Approve,
+            "embed" => Self::Embed,
+            "recall" => Self::Recall,
+            "retrieve" => Self::Retrieve,
+            "validate" => Self::Validate,
+            "transcribe" => Self::Transcribe,
+            "import" => Self::Import,
+            "export" => Self::Export,
+            "rows" => Self::Rows,
+            "script" => Self::Script,
+            "chunk" => Self::Chunk,
+            "dedupe" => Self::Dedupe,
+            "detect_lang" => Self::DetectLang,
+            "translate" => Self::Translate,
+            "ws" => Self::Ws,
             _ => Self::Unknown,
         }
     }
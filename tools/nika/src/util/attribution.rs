@@ -0,0 +1,160 @@
+//! License and attribution hints in fetched content (v0.7)
+//!
+//! Looks for what a source says about its own license, in order:
+//!
+//! - a `Link: <...>; rel="license"` response header
+//! - HTML `<link rel="license" href>` and `<meta name="license|dcterms.license|dc.rights|copyright">`
+//! - a top-level `license` in JSON (a string, or an object with `spdx_id` / `name`)
+//!
+//! and for an author in `<meta name="author">` or a JSON `author`. These are
+//! declarations, not verified licensing.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde_json::Value;
+
+static LINK_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<([^>]+)>\s*;[^,]*\brel\s*=\s*"?license\b"#).expect("valid regex")
+});
+static TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<(link|meta)\b[^>]*>").expect("valid regex"));
+
+/// What a source declares about its license and author
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attribution {
+    pub license: Option<String>,
+    pub author: Option<String>,
+}
+
+impl Attribution {
+    /// Read hints from a `Link` header and the response body
+    pub fn detect(link_header: Option<&str>, body: &str) -> Self {
+        let mut found = Self {
+            license: link_header
+                .and_then(|h| LINK_HEADER.captures(h))
+                .map(|c| c[1].trim().to_string()),
+            author: None,
+        };
+        let head = body.trim_start();
+        if head.starts_with('{') {
+            if let Ok(json) = serde_json::from_str::<Value>(head) {
+                found.merge(from_json(&json));
+            }
+        } else if head.starts_with('<') {
+            found.merge(from_html(body));
+        }
+        found
+    }
+
+    /// Fill the fields still missing from `other`
+    fn merge(&mut self, other: Self) {
+        self.license = self.license.take().or(other.license);
+        self.author = self.author.take().or(other.author);
+    }
+}
+
+fn from_html(body: &str) -> Attribution {
+    let mut found = Attribution::default();
+    for tag in TAG.captures_iter(body) {
+        let tag_text = &tag[0];
+        let rel = attr(tag_text, "rel").map(|v| v.to_ascii_lowercase());
+        let name = attr(tag_text, "name").map(|v| v.to_ascii_lowercase());
+        match (
+            &tag[1].to_ascii_lowercase()[..],
+            rel.as_deref(),
+            name.as_deref(),
+        ) {
+            ("link", Some(rel), _) if rel.split_whitespace().any(|r| r == "license") => {
+                found.license = found.license.or(attr(tag_text, "href"));
+            }
+            (
+                "meta",
+                _,
+                Some("license" | "dcterms.license" | "dc.rights" | "dcterms.rights" | "copyright"),
+            ) => {
+                found.license = found.license.or(attr(tag_text, "content"));
+            }
+            ("meta", _, Some("author")) => {
+                found.author = found.author.or(attr(tag_text, "content"));
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+fn from_json(json: &Value) -> Attribution {
+    let license = match json.get("license") {
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Object(o)) => ["spdx_id", "name", "url"]
+            .iter()
+            .find_map(|k| o.get(*k).and_then(Value::as_str))
+            .map(str::to_string),
+        _ => None,
+    };
+    let author = match json.get("author") {
+        Some(Value::String(s)) => Some(s.clone()),
+        Some(Value::Object(o)) => o.get("name").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    };
+    Attribution { license, author }
+}
+
+/// Value of `name="..."` (or single-quoted / bare) inside one tag
+fn attr(tag: &str, name: &str) -> Option<String> {
+    let pattern = format!(r#"(?i)\b{}\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#, name);
+    let caps = Regex::new(&pattern).ok()?.captures(tag)?;
+    let value = caps.get(1).or(caps.get(2)).or(caps.get(3))?.as_str().trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_html_and_header() {
+        let html = r#"<html><head>
+<meta name="author" content="Ada Lovelace">
+<link href="https://creativecommons.org/licenses/by/4.0/" rel="license">
+</head></html>"#;
+        let found = Attribution::detect(None, html);
+        assert_eq!(
+            found.license.as_deref(),
+            Some("https://creativecommons.org/licenses/by/4.0/")
+        );
+        assert_eq!(found.author.as_deref(), Some("Ada Lovelace"));
+
+        // The header wins over the body
+        let header = r#"<https://opensource.org/license/mit>; rel="license""#;
+        let found = Attribution::detect(Some(header), html);
+        assert_eq!(
+            found.license.as_deref(),
+            Some("https://opensource.org/license/mit")
+        );
+
+        let meta = "<meta name='dc.rights' content='All rights reserved'>";
+        assert_eq!(
+            Attribution::detect(None, meta).license.as_deref(),
+            Some("All rights reserved")
+        );
+        assert_eq!(
+            Attribution::detect(None, "plain text"),
+            Attribution::default()
+        );
+    }
+
+    #[test]
+    fn test_detect_json() {
+        let repo = r#"{"name": "nika", "license": {"key": "agpl-3.0", "spdx_id": "AGPL-3.0"}}"#;
+        assert_eq!(
+            Attribution::detect(None, repo).license.as_deref(),
+            Some("AGPL-3.0")
+        );
+        let package = r#"{"license": "MIT", "author": {"name": "Jane"}}"#;
+        let found = Attribution::detect(None, package);
+        assert_eq!(found.license.as_deref(), Some("MIT"));
+        assert_eq!(found.author.as_deref(), Some("Jane"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Version stamps of files Nika reads back (v0.7)
+//!
+//! Traces and approval checkpoints outlive the binary that wrote them.
+//! Each is stamped with the Nika version and a schema number, checked on
+//! read:
+//!
+//! - same schema: read as is
+//! - older schema: upgraded through the registered [`MIGRATIONS`], one
+//!   schema at a time
+//! - newer schema, or no migration: `NIKA-098` naming both versions,
+//!   instead of a deserialization error halfway through a replay or resume
+//!
+//! Files written before stamps existed read as schema 1.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{NikaError, Result};
+
+/// Kind of versioned file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    /// `.nika/traces/<generation id>.ndjson` (migrations apply per event)
+    Trace,
+    /// `.nika/approvals/<workflow>.json`
+    ApprovalCheckpoint,
+}
+
+impl FileKind {
+    /// Schema this build writes
+    pub fn current_schema(self) -> u32 {
+        match self {
+            Self::Trace => 2,
+            Self::ApprovalCheckpoint => 2,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::ApprovalCheckpoint => "approval checkpoint",
+        }
+    }
+}
+
+/// Who wrote a file, and in which schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionStamp {
+    pub schema: u32,
+    pub nika_version: String,
+}
+
+impl VersionStamp {
+    /// Stamp of files this build writes
+    pub fn current(kind: FileKind) -> Self {
+        Self {
+            schema: kind.current_schema(),
+            nika_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Stamp of files written before stamps existed
+    pub fn legacy() -> Self {
+        Self {
+            schema: 1,
+            nika_version: "0.7.0 or older".to_string(),
+        }
+    }
+}
+
+/// Upgrade of one kind of file from schema `from` to `from + 1`
+#[derive(Debug)]
+pub struct Migration {
+    pub kind: FileKind,
+    pub from: u32,
+    pub apply: fn(&mut Value) -> std::result::Result<(), String>,
+}
+
+/// Registered migrations, in no particular order
+pub static MIGRATIONS: &[Migration] = &[
+    // v2 only added the header line; events are unchanged
+    Migration {
+        kind: FileKind::Trace,
+        from: 1,
+        apply: |_| Ok(()),
+    },
+    // v1 was the bare entry map; v2 nests it under `entries` next to the stamp
+    Migration {
+        kind: FileKind::ApprovalCheckpoint,
+        from: 1,
+        apply: |value| {
+            *value = json!({ "entries": value.take() });
+            Ok(())
+        },
+    },
+];
+
+/// Check `stamp` of `file`, returning the migrations to apply (none if current)
+pub fn check(kind: FileKind, file: &Path, stamp: &VersionStamp) -> Result<Vec<&'static Migration>> {
+    let current = VersionStamp::current(kind);
+    let incompatible = |reason: String| NikaError::IncompatibleVersion {
+        file: file.display().to_string(),
+        reason,
+    };
+    if stamp.schema > current.schema {
+        return Err(incompatible(format!(
+            "{} schema {} was written by nika {}; nika {} reads up to schema {}",
+            kind.as_str(),
+            stamp.schema,
+            stamp.nika_version,
+            current.nika_version,
+            current.schema
+        )));
+    }
+    (stamp.schema..current.schema)
+        .map(|from| {
+            MIGRATIONS
+                .iter()
+                .find(|m| m.kind == kind && m.from == from)
+                .ok_or_else(|| {
+                    incompatible(format!(
+                        "no migration from {} schema {} (nika {}) to {}",
+                        kind.as_str(),
+                        from,
+                        stamp.nika_version,
+                        from + 1
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Apply `migrations` to `value`
+pub fn migrate(file: &Path, migrations: &[&'static Migration], value: &mut Value) -> Result<()> {
+    for migration in migrations {
+        (migration.apply)(value).map_err(|reason| NikaError::IncompatibleVersion {
+            file: file.display().to_string(),
+            reason: format!(
+                "migrating {} schema {}: {}",
+                migration.kind.as_str(),
+                migration.from,
+                reason
+            ),
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_schema_fails_with_both_versions() {
+        let stamp = VersionStamp {
+            schema: 9,
+            nika_version: "0.9.0".to_string(),
+        };
+        let err = check(FileKind::Trace, Path::new("t.ndjson"), &stamp).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.starts_with("[NIKA-098]"), "{}", msg);
+        assert!(
+            msg.contains("schema 9 was written by nika 0.9.0"),
+            "{}",
+            msg
+        );
+        assert!(msg.contains(env!("CARGO_PKG_VERSION")), "{}", msg);
+    }
+
+    #[test]
+    fn every_older_schema_has_a_migration_chain() {
+        for kind in [FileKind::Trace, FileKind::ApprovalCheckpoint] {
+            let current = VersionStamp::current(kind);
+            assert!(check(kind, Path::new("f"), &current).unwrap().is_empty());
+            let chain = check(kind, Path::new("f"), &VersionStamp::legacy()).unwrap();
+            assert_eq!(chain.len() as u32, current.schema - 1);
+        }
+
+        let mut checkpoint = json!({"review:1": {"decision": "approve"}});
+        let chain = check(
+            FileKind::ApprovalCheckpoint,
+            Path::new("a.json"),
+            &VersionStamp::legacy(),
+        )
+        .unwrap();
+        migrate(Path::new("a.json"), &chain, &mut checkpoint).unwrap();
+        assert_eq!(checkpoint["entries"]["review:1"]["decision"], "approve");
+    }
+}
@@ -0,0 +1,323 @@
+//! Crash bundles (v0.7)
+//!
+//! When Nika panics, in the TUI or in a task the runner catches, a JSON
+//! bundle is written to `~/.config/nika/crashes/`: the panic message,
+//! location and backtrace, the last 200 events of the run (anonymized as
+//! by `nika trace export --anonymize`) and the version, OS and features.
+//!
+//! [`CrashBundle::issue_url`] pre-fills a GitHub issue with the message,
+//! location, versions and the top of the backtrace; events stay in the
+//! bundle, for the user to attach.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::event::anonymize::{environment, Anonymizer};
+use crate::event::{Event, EventLog};
+
+/// Where new issues are opened
+pub const ISSUES_URL: &str = "https://github.com/SuperNovae-studio/nika/issues/new";
+
+/// Events kept in a bundle (the last ones)
+const MAX_EVENTS: usize = 200;
+
+/// Backtrace lines in an issue body
+const MAX_ISSUE_BACKTRACE_LINES: usize = 40;
+
+/// Issue bodies stay under GitHub's URL length limit
+const MAX_ISSUE_BODY_CHARS: usize = 6000;
+
+/// Last panic seen by [`install_hook`]'s hook
+static LAST_PANIC: Mutex<Option<PanicRecord>> = Mutex::new(None);
+
+/// Event log whose events go into TUI crash bundles
+static WATCHED: Mutex<Option<EventLog>> = Mutex::new(None);
+
+/// A panic: message, where, and the backtrace
+#[derive(Debug, Clone, Serialize)]
+pub struct PanicRecord {
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+}
+
+impl PanicRecord {
+    /// Record the panic being handled, with a backtrace
+    pub fn capture(info: &PanicHookInfo<'_>) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        Self {
+            message,
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            thread: std::thread::current().name().map(str::to_string),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        }
+    }
+}
+
+/// Record every panic for [`take_last_panic`], then run the previous hook
+///
+/// Tokio catches panics of spawned tasks; the runner turns the record
+/// into a bundle. Safe to call more than once.
+pub fn install_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let record = PanicRecord::capture(info);
+            if let Ok(mut last) = LAST_PANIC.lock() {
+                *last = Some(record);
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// The last panic recorded by [`install_hook`]'s hook
+pub fn take_last_panic() -> Option<PanicRecord> {
+    LAST_PANIC.lock().ok().and_then(|mut last| last.take())
+}
+
+/// Put the events of `log` in the bundles of later TUI crashes
+pub fn watch(log: &EventLog) {
+    if let Ok(mut watched) = WATCHED.lock() {
+        *watched = Some(log.clone());
+    }
+}
+
+/// Last events of the watched log, if it isn't locked
+pub fn watched_events() -> Vec<Event> {
+    WATCHED
+        .try_lock()
+        .ok()
+        .and_then(|watched| watched.as_ref().and_then(|log| log.try_recent(MAX_EVENTS)))
+        .unwrap_or_default()
+}
+
+/// Everything known about a crash
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashBundle {
+    /// Local time of the crash, RFC 3339
+    pub timestamp: String,
+    /// What crashed: `tui` or `task`
+    pub context: String,
+    pub panic: PanicRecord,
+    /// Version, OS, architecture, features and models of the run
+    pub environment: Value,
+    /// Last events of the run, anonymized
+    pub events: Vec<Value>,
+}
+
+impl CrashBundle {
+    /// Bundle for `panic`, keeping the last 200 of `events`
+    pub fn new(context: &str, panic: PanicRecord, events: &[Event]) -> Self {
+        let events = &events[events.len().saturating_sub(MAX_EVENTS)..];
+        let anonymizer = Anonymizer::random();
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            context: context.to_string(),
+            panic,
+            environment: environment(events),
+            events: events
+                .iter()
+                .map(|e| anonymizer.anonymize_event(e))
+                .collect(),
+        }
+    }
+
+    /// Write to `~/.config/nika/crashes/`, returning the file
+    pub fn write(&self) -> std::io::Result<PathBuf> {
+        let dir = dirs::config_dir()
+            .map(|d| d.join("nika").join("crashes"))
+            .unwrap_or_else(|| std::env::temp_dir().join("nika-crashes"));
+        self.write_to(&dir)
+    }
+
+    /// Write to `dir` as `crash-<time>-<pid>.json`
+    pub fn write_to(&self, dir: &Path) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "crash-{}-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            std::process::id()
+        ));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// New-issue URL with the title and body filled in
+    pub fn issue_url(&self) -> String {
+        let first_line = self.panic.message.lines().next().unwrap_or_default();
+        let title: String = format!("Crash: {}", first_line).chars().take(100).collect();
+
+        let backtrace: Vec<&str> = self
+            .panic
+            .backtrace
+            .lines()
+            .take(MAX_ISSUE_BACKTRACE_LINES)
+            .collect();
+        let env = &self.environment;
+        let mut body = format!(
+            "**Panic** ({})\n```\n{}\n```\nat `{}`\n\n\
+             **Version:** nika {} on {}/{}, features: {}\n\n\
+             **Backtrace** (top)\n```\n{}\n```\n",
+            self.context,
+            self.panic.message,
+            self.panic.location.as_deref().unwrap_or("unknown"),
+            env["nika_version"].as_str().unwrap_or_default(),
+            env["os"].as_str().unwrap_or_default(),
+            env["arch"].as_str().unwrap_or_default(),
+            env["features"]
+                .as_array()
+                .map(|f| f
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "))
+                .unwrap_or_default(),
+            backtrace.join("\n"),
+        );
+        if let Some((cut, _)) = body.char_indices().nth(MAX_ISSUE_BODY_CHARS) {
+            body.truncate(cut);
+            body.push_str("\n…\n```\n");
+        }
+        body.push_str(
+            "\n**Crash bundle:** please attach the `crash-*.json` file \
+             (its events are anonymized).\n\n**What were you doing?**\n",
+        );
+
+        url::Url::parse_with_params(ISSUES_URL, &[("title", title), ("body", body)])
+            .map(String::from)
+            .unwrap_or_else(|_| ISSUES_URL.to_string())
+    }
+}
+
+/// Write a bundle for a caught panic, returning its file and the bundle
+///
+/// Needs the panic recorded by [`install_hook`]'s hook: None without one,
+/// or when the TUI hook already wrote the bundle.
+pub fn report(context: &str, log: &EventLog) -> Option<(PathBuf, CrashBundle)> {
+    let panic = take_last_panic()?;
+    let events = log.try_recent(MAX_EVENTS).unwrap_or_default();
+    let bundle = CrashBundle::new(context, panic, &events);
+    match bundle.write() {
+        Ok(path) => {
+            tracing::error!(path = %path.display(), "Crash bundle written");
+            Some((path, bundle))
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Could not write crash bundle");
+            None
+        }
+    }
+}
+
+/// Ask on the terminal whether to open `url` in a browser
+///
+/// Does nothing unless stdin and stderr are terminals.
+pub fn offer_issue(url: &str) {
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        return;
+    }
+    eprint!("Open a pre-filled GitHub issue in your browser? [y/N] ");
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return;
+    }
+    if matches!(answer.trim(), "y" | "Y" | "yes") {
+        if let Err(e) = open_browser(url) {
+            eprintln!("Could not open a browser ({}). Issue URL:\n{}", e, url);
+        }
+    }
+}
+
+fn open_browser(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command.arg(url).spawn().map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::event::EventKind;
+
+    fn panic_record() -> PanicRecord {
+        PanicRecord {
+            message: "index out of bounds: the len is 3 but the index is 7".to_string(),
+            location: Some("src/runtime/runner.rs:42:9".to_string()),
+            thread: Some("tokio-runtime-worker".to_string()),
+            backtrace: "0: nika::runtime::runner::run\n1: tokio::runtime::task".to_string(),
+        }
+    }
+
+    #[test]
+    fn bundle_keeps_last_events_anonymized() {
+        let log = EventLog::new();
+        for i in 0..250 {
+            log.emit(EventKind::TaskCompleted {
+                task_id: Arc::from(format!("secret-task-{}", i)),
+                output: Arc::new(serde_json::json!("customer list")),
+                duration_ms: 10,
+            });
+        }
+        let bundle = CrashBundle::new("task", panic_record(), &log.events());
+        assert_eq!(bundle.events.len(), 200);
+        assert_eq!(bundle.events[0]["id"], 50);
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("secret-task") && !json.contains("customer"));
+        assert_eq!(bundle.environment["events"], 200);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = bundle.write_to(dir.path()).unwrap();
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["panic"]["location"], "src/runtime/runner.rs:42:9");
+        assert_eq!(written["context"], "task");
+    }
+
+    #[test]
+    fn issue_url_is_prefilled_without_events() {
+        let log = EventLog::new();
+        log.emit(EventKind::TaskFailed {
+            task_id: Arc::from("private-step"),
+            error: "oops".to_string(),
+            duration_ms: 1,
+        });
+        let url = CrashBundle::new("tui", panic_record(), &log.events()).issue_url();
+        assert!(url.starts_with(ISSUES_URL), "{}", url);
+
+        let parsed = url::Url::parse(&url).unwrap();
+        let params: std::collections::HashMap<_, _> = parsed.query_pairs().collect();
+        assert_eq!(
+            params["title"],
+            "Crash: index out of bounds: the len is 3 but the index is 7"
+        );
+        let body = &params["body"];
+        assert!(body.contains("at `src/runtime/runner.rs:42:9`"), "{}", body);
+        assert!(body.contains("nika::runtime::runner::run"));
+        assert!(!body.contains("private-step"));
+    }
+}
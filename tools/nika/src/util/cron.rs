@@ -0,0 +1,266 @@
+//! Cron expressions for `triggers: { cron: ... }` (v0.7)
+//!
+//! Standard 5-field syntax, evaluated in local time:
+//!
+//! ```text
+//! ┌─ minute (0-59)
+//! │ ┌─ hour (0-23)
+//! │ │ ┌─ day of month (1-31)
+//! │ │ │ ┌─ month (1-12 or JAN-DEC)
+//! │ │ │ │ ┌─ day of week (0-7 or SUN-SAT, 0 and 7 are Sunday)
+//! 0 9 * * MON
+//! ```
+//!
+//! Fields accept `*`, values, ranges (`1-5`), lists (`1,15`) and steps
+//! (`*/15`, `0-30/10`). As in classic cron, when both day fields are
+//! restricted a day matches if *either* does. `@hourly`, `@daily`,
+//! `@weekly`, `@monthly` and `@yearly` are accepted as shorthands.
+
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, TimeZone, Timelike};
+
+use crate::error::NikaError;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// How far ahead `next_after` looks before giving up (e.g. `0 0 30 2 *`)
+const SEARCH_DAYS: i64 = 366 * 5;
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expr: String,
+    /// Bit i set = value i allowed
+    minutes: u64,
+    hours: u32,
+    days: u32,
+    months: u16,
+    /// Sunday = 0
+    weekdays: u8,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parse a 5-field expression or an `@` shorthand
+    pub fn parse(expr: &str) -> Result<Self, NikaError> {
+        let expanded = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(
+                expr,
+                format!("expected 5 fields, found {}", fields.len()),
+            ));
+        }
+
+        let field = |idx: usize, min: u32, max: u32, names: &[&str]| {
+            parse_field(fields[idx], min, max, names).map_err(|reason| invalid(expr, reason))
+        };
+        let minutes = field(0, 0, 59, &[])?;
+        let hours = field(1, 0, 23, &[])?;
+        let days = field(2, 1, 31, &[])?;
+        let months = field(3, 1, 12, &MONTHS)?;
+        let mut weekdays = field(4, 0, 7, &WEEKDAYS)?;
+        // 7 is Sunday too
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Ok(Self {
+            expr: expr.trim().to_string(),
+            minutes,
+            hours: hours as u32,
+            days: days as u32,
+            months: months as u16,
+            weekdays: (weekdays & 0x7f) as u8,
+            days_restricted: fields[2] != "*",
+            weekdays_restricted: fields[4] != "*",
+        })
+    }
+
+    /// The expression as written
+    pub fn as_str(&self) -> &str {
+        &self.expr
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+
+    /// First matching time strictly after `after` (seconds truncated)
+    ///
+    /// Times skipped by a DST change are skipped; `None` if nothing matches
+    /// within five years.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = (after.naive_local() + Duration::minutes(1))
+            .with_second(0)?
+            .with_nanosecond(0)?;
+        let start_date = start.date();
+        for offset in 0..SEARCH_DAYS {
+            let date = start_date + Duration::days(offset);
+            if !self.matches_day(date) {
+                continue;
+            }
+            for hour in 0..24 {
+                if self.hours & (1 << hour) == 0 {
+                    continue;
+                }
+                for minute in 0..60 {
+                    if self.minutes & (1 << minute) == 0 {
+                        continue;
+                    }
+                    let naive = date.and_hms_opt(hour, minute, 0)?;
+                    if naive < start {
+                        continue;
+                    }
+                    match tz.from_local_datetime(&naive) {
+                        LocalResult::Single(time) => return Some(time),
+                        LocalResult::Ambiguous(earliest, _) => return Some(earliest),
+                        LocalResult::None => continue,
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn invalid(expr: &str, reason: impl Into<String>) -> NikaError {
+    NikaError::InvalidCron {
+        expr: expr.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Parse one field into a bitmask of allowed values
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be at least 1".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (value(lo, min, names)?, value(hi, min, names)?)
+        } else {
+            let v = value(range, min, names)?;
+            // `5/10` means from 5 to the end, every 10
+            (v, if step > 1 { max } else { v })
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(format!("'{}' is outside {}-{}", part, min, max));
+        }
+        for v in (lo..=hi).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+/// A number or a name (names are offset by `min`: JAN = 1, SUN = 0)
+fn value(token: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    if let Ok(v) = token.parse() {
+        return Ok(v);
+    }
+    names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(token))
+        .map(|idx| idx as u32 + min)
+        .ok_or_else(|| format!("invalid value '{}'", token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        CronSchedule::parse(expr)
+            .unwrap()
+            .next_after(&at(after))
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2026-10-17 is a Saturday
+        assert_eq!(
+            next("0 9 * * MON", "2026-10-17T10:00:00Z"),
+            "2026-10-19T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-10-17T10:07:30Z"),
+            "2026-10-17T10:15:00+00:00"
+        );
+        // Strictly after
+        assert_eq!(
+            next("0 9 * * *", "2026-10-17T09:00:00Z"),
+            "2026-10-18T09:00:00+00:00"
+        );
+        assert_eq!(
+            next("@monthly", "2026-12-15T00:00:00Z"),
+            "2027-01-01T00:00:00+00:00"
+        );
+        // Day-of-month OR day-of-week when both are restricted
+        assert_eq!(
+            next("0 0 1 * SUN", "2026-10-17T12:00:00Z"),
+            "2026-10-18T00:00:00+00:00"
+        );
+        // 7 is Sunday
+        assert_eq!(
+            next("30 8 * * 7", "2026-10-17T12:00:00Z"),
+            "2026-10-18T08:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CronSchedule::parse("0 9 * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("0 9 * * FUNDAY").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        let err = CronSchedule::parse("0 25 * * *").unwrap_err();
+        assert!(err.to_string().contains("0 25 * * *"));
+    }
+
+    #[test]
+    fn test_impossible_schedule_has_no_next() {
+        let cron = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert!(cron.next_after(&at("2026-10-17T00:00:00Z")).is_none());
+    }
+}
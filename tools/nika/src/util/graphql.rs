@@ -0,0 +1,463 @@
+//! GraphQL document syntax check (v0.7)
+//!
+//! Enough of the GraphQL grammar to reject broken queries at `nika check`
+//! time: operations (`query`, `mutation`, `subscription` or a bare
+//! selection set), variable definitions, fields with aliases, arguments and
+//! directives, fragment spreads, inline fragments and fragment definitions.
+//!
+//! Does NOT check the query against a schema, and does not parse type
+//! system definitions (`type`, `schema`, `directive`, ...).
+
+/// A lexical token
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    /// `$name`
+    Variable(String),
+    /// One of `{ } ( ) [ ] : = @ ! ...`
+    Punct(&'static str),
+    /// Int, float, string or block string literal
+    Literal,
+}
+
+/// A token and its 1-based line
+type Spanned = (Token, usize);
+
+/// Check `query` as a GraphQL executable document
+///
+/// Returns the first syntax error with its line number.
+pub fn validate(query: &str) -> Result<(), String> {
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        return Err("query is empty".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    while !parser.at_end() {
+        parser.definition()?;
+    }
+    Ok(())
+}
+
+/// Kind of the first operation in `query` (`query`, `mutation` or `subscription`)
+pub fn operation_kind(query: &str) -> Option<&'static str> {
+    let tokens = tokenize(query).ok()?;
+    match tokens.first().map(|(t, _)| t) {
+        Some(Token::Punct("{")) => Some("query"),
+        Some(Token::Name(name)) => match name.as_str() {
+            "query" => Some("query"),
+            "mutation" => Some("mutation"),
+            "subscription" => Some("subscription"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Spanned>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                line += 1;
+                i += 1;
+            }
+            // Whitespace, commas and the byte order mark are insignificant
+            ' ' | '\t' | '\r' | ',' | '\u{feff}' => i += 1,
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ':' | '=' | '@' | '!' => {
+                let punct = match c {
+                    '{' => "{",
+                    '}' => "}",
+                    '(' => "(",
+                    ')' => ")",
+                    '[' => "[",
+                    ']' => "]",
+                    ':' => ":",
+                    '=' => "=",
+                    '@' => "@",
+                    _ => "!",
+                };
+                tokens.push((Token::Punct(punct), line));
+                i += 1;
+            }
+            '.' => {
+                if chars.get(i + 1) == Some(&'.') && chars.get(i + 2) == Some(&'.') {
+                    tokens.push((Token::Punct("..."), line));
+                    i += 3;
+                } else {
+                    return Err(format!("line {}: unexpected '.'", line));
+                }
+            }
+            '$' => {
+                let start = i + 1;
+                i = scan_name(&chars, start);
+                if i == start {
+                    return Err(format!("line {}: '$' must be followed by a name", line));
+                }
+                let name: String = chars[start..i].iter().collect();
+                tokens.push((Token::Variable(name), line));
+            }
+            '"' => {
+                let start_line = line;
+                if chars.get(i + 1) == Some(&'"') && chars.get(i + 2) == Some(&'"') {
+                    i += 3;
+                    loop {
+                        match chars.get(i) {
+                            None => {
+                                return Err(format!(
+                                    "line {}: unterminated block string",
+                                    start_line
+                                ))
+                            }
+                            Some('\\') if chars[i..].starts_with(&['\\', '"', '"', '"']) => i += 4,
+                            Some('"') if chars[i..].starts_with(&['"', '"', '"']) => {
+                                i += 3;
+                                break;
+                            }
+                            Some('\n') => {
+                                line += 1;
+                                i += 1;
+                            }
+                            Some(_) => i += 1,
+                        }
+                    }
+                } else {
+                    i += 1;
+                    loop {
+                        match chars.get(i) {
+                            None | Some('\n') => {
+                                return Err(format!("line {}: unterminated string", start_line))
+                            }
+                            Some('\\') => i += 2,
+                            Some('"') => {
+                                i += 1;
+                                break;
+                            }
+                            Some(_) => i += 1,
+                        }
+                    }
+                }
+                tokens.push((Token::Literal, start_line));
+            }
+            '-' | '0'..='9' => {
+                let start = i;
+                i += 1;
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || matches!(chars[i], '.' | 'e' | 'E' | '+' | '-'))
+                {
+                    i += 1;
+                }
+                let number: String = chars[start..i].iter().collect();
+                if number.parse::<f64>().is_err() {
+                    return Err(format!("line {}: invalid number '{}'", line, number));
+                }
+                tokens.push((Token::Literal, line));
+            }
+            c if c == '_' || c.is_ascii_alphabetic() => {
+                let start = i;
+                i = scan_name(&chars, i);
+                tokens.push((Token::Name(chars[start..i].iter().collect()), line));
+            }
+            other => return Err(format!("line {}: unexpected character '{}'", line, other)),
+        }
+    }
+    Ok(tokens)
+}
+
+/// End of the name starting at `start`
+fn scan_name(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    if i < chars.len() && (chars[i] == '_' || chars[i].is_ascii_alphabetic()) {
+        i += 1;
+        while i < chars.len() && (chars[i] == '_' || chars[i].is_ascii_alphanumeric()) {
+            i += 1;
+        }
+    }
+    i
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn is(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Token::Punct(p)) if *p == punct)
+    }
+
+    fn is_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(n)) if n == name)
+    }
+
+    fn error(&self, expected: &str) -> String {
+        match self.tokens.get(self.pos) {
+            Some((token, line)) => {
+                let found = match token {
+                    Token::Name(name) => format!("'{}'", name),
+                    Token::Variable(name) => format!("'${}'", name),
+                    Token::Punct(p) => format!("'{}'", p),
+                    Token::Literal => "a literal".to_string(),
+                };
+                format!("line {}: expected {}, found {}", line, expected, found)
+            }
+            None => format!("expected {}, found end of query", expected),
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        if self.is(punct) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", punct)))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Name(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error("a name")),
+        }
+    }
+
+    fn definition(&mut self) -> Result<(), String> {
+        if self.is("{") {
+            return self.selection_set();
+        }
+        let keyword = self.name()?;
+        match keyword.as_str() {
+            "query" | "mutation" | "subscription" => {
+                if matches!(self.peek(), Some(Token::Name(_))) {
+                    self.pos += 1;
+                }
+                if self.is("(") {
+                    self.variable_definitions()?;
+                }
+                self.directives()?;
+                self.selection_set()
+            }
+            "fragment" => {
+                if self.name()? == "on" {
+                    self.pos -= 1;
+                    return Err(self.error("a fragment name"));
+                }
+                if !self.is_name("on") {
+                    return Err(self.error("'on'"));
+                }
+                self.pos += 1;
+                self.name()?;
+                self.directives()?;
+                self.selection_set()
+            }
+            _ => {
+                self.pos -= 1;
+                Err(self.error("'query', 'mutation', 'subscription', 'fragment' or '{'"))
+            }
+        }
+    }
+
+    fn variable_definitions(&mut self) -> Result<(), String> {
+        self.expect("(")?;
+        loop {
+            match self.peek() {
+                Some(Token::Variable(_)) => self.pos += 1,
+                _ => return Err(self.error("a variable")),
+            }
+            self.expect(":")?;
+            self.type_ref()?;
+            if self.is("=") {
+                self.pos += 1;
+                self.value(true)?;
+            }
+            self.directives()?;
+            if self.is(")") {
+                self.pos += 1;
+                return Ok(());
+            }
+        }
+    }
+
+    fn type_ref(&mut self) -> Result<(), String> {
+        if self.is("[") {
+            self.pos += 1;
+            self.type_ref()?;
+            self.expect("]")?;
+        } else {
+            self.name()?;
+        }
+        if self.is("!") {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn directives(&mut self) -> Result<(), String> {
+        while self.is("@") {
+            self.pos += 1;
+            self.name()?;
+            if self.is("(") {
+                self.arguments()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn arguments(&mut self) -> Result<(), String> {
+        self.expect("(")?;
+        loop {
+            self.name()?;
+            self.expect(":")?;
+            self.value(false)?;
+            if self.is(")") {
+                self.pos += 1;
+                return Ok(());
+            }
+        }
+    }
+
+    /// A value; `constant` forbids variables (default values)
+    fn value(&mut self, constant: bool) -> Result<(), String> {
+        match self.peek() {
+            Some(Token::Literal) | Some(Token::Name(_)) => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(Token::Variable(_)) if !constant => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(Token::Punct("[")) => {
+                self.pos += 1;
+                while !self.is("]") {
+                    if self.at_end() {
+                        return Err(self.error("']'"));
+                    }
+                    self.value(constant)?;
+                }
+                self.pos += 1;
+                Ok(())
+            }
+            Some(Token::Punct("{")) => {
+                self.pos += 1;
+                while !self.is("}") {
+                    self.name()?;
+                    self.expect(":")?;
+                    self.value(constant)?;
+                }
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(self.error(if constant {
+                "a constant value"
+            } else {
+                "a value"
+            })),
+        }
+    }
+
+    fn selection_set(&mut self) -> Result<(), String> {
+        self.expect("{")?;
+        if self.is("}") {
+            return Err(self.error("a field"));
+        }
+        while !self.is("}") {
+            if self.at_end() {
+                return Err(self.error("'}'"));
+            }
+            self.selection()?;
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn selection(&mut self) -> Result<(), String> {
+        if self.is("...") {
+            self.pos += 1;
+            if self.is_name("on") {
+                self.pos += 1;
+                self.name()?;
+                self.directives()?;
+                return self.selection_set();
+            }
+            if matches!(self.peek(), Some(Token::Name(_))) {
+                self.pos += 1;
+                return self.directives();
+            }
+            self.directives()?;
+            return self.selection_set();
+        }
+        self.name()?;
+        if self.is(":") {
+            self.pos += 1;
+            self.name()?;
+        }
+        if self.is("(") {
+            self.arguments()?;
+        }
+        self.directives()?;
+        if self.is("{") {
+            self.selection_set()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_documents() {
+        for query in [
+            "{ viewer { login } }",
+            "query Repo($owner: String!, $first: Int = 10) {\n  repository(owner: $owner, name: \"nika\") {\n    issues(first: $first, states: [OPEN]) { nodes { title ...Meta } }\n  }\n}\nfragment Meta on Issue { number createdAt @include(if: true) }",
+            "mutation { addStar(input: {starrableId: \"x\"}) { clientMutationId } }",
+            "query { search(query: \"\"\"multi\nline\"\"\") { ... on Repo { name } } } # trailing",
+        ] {
+            assert!(validate(query).is_ok(), "{}: {:?}", query, validate(query));
+        }
+    }
+
+    #[test]
+    fn syntax_errors_name_the_line() {
+        let missing = validate("query {\n  viewer { login }\n").unwrap_err();
+        assert!(missing.contains("found end of query"), "{}", missing);
+        let empty = validate("{ viewer { } }").unwrap_err();
+        assert_eq!(empty, "line 1: expected a field, found '}'");
+        let arg = validate("{\n  user(id: ) { name }\n}").unwrap_err();
+        assert_eq!(arg, "line 2: expected a value, found ')'");
+        assert!(validate("query($id: ID = $other) { a }").is_err());
+        assert!(validate("{ a \"unterminated }").is_err());
+        assert!(validate("   # only a comment").is_err());
+        assert!(validate("select * from users").is_err());
+    }
+
+    #[test]
+    fn operation_kinds() {
+        assert_eq!(operation_kind("{ a }"), Some("query"));
+        assert_eq!(operation_kind("mutation M { a }"), Some("mutation"));
+        assert_eq!(operation_kind("fragment F on T { a }"), None);
+    }
+}
@@ -1,14 +1,21 @@
-//! JSONPath Parser - minimal subset (v0.1)
+//! JSONPath Parser (v0.7)
 //!
 //! Supports:
-//! - $.a.b.c (dot notation)
-//! - $.a[0].b (array index)
-//! - a.b.c (without $ prefix)
+//! - $.a.b.c (dot notation), a.b.c (without $ prefix)
+//! - $.a[0].b, $.a[-1] (array index, negative counts from the end)
+//! - $['a.b'] (quoted key)
+//! - $.a[*], $.a.* (wildcard)
+//! - $.a[1:3], $.a[::2], $.a[-2:] (slices)
+//! - $.a[?(@.price > 10 && @.tags)] (filters)
+//! - $..name (recursive descent)
 //!
-//! Does NOT support:
-//! - Filters: $.a[?(@.x==1)]
-//! - Wildcards: $.a[*]
-//! - Slices: $.a[0:5]
+//! A path made only of fields and indices selects one value. A wildcard,
+//! slice, filter or recursive descent makes it select every match, returned
+//! as an array (empty when nothing matches).
+//!
+//! Does NOT support: unions ($.a[0,2]), script expressions, functions.
+
+use std::cmp::Ordering;
 
 use serde_json::Value;
 
@@ -17,10 +24,65 @@ use crate::error::NikaError;
 /// A parsed JSONPath segment
 #[derive(Debug, Clone, PartialEq)]
 pub enum Segment {
-    /// Object field access: .field
+    /// Object field access: .field, ['field']
     Field(String),
     /// Array index access: [0]
     Index(usize),
+    /// Array index from the end: [-1] is FromEnd(1)
+    FromEnd(usize),
+    /// Every array item or object value: [*], .*
+    Wildcard,
+    /// Array slice: [start:end:step], negative bounds count from the end
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: i64,
+    },
+    /// Array items (or object values) matching a filter: [?(...)]
+    Filter(Filter),
+    /// Segment applied at every depth: ..name, ..[*]
+    Descendant(Box<Segment>),
+}
+
+/// A filter expression: `[?(@.price > 10 && @.tags)]`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Operand is present and not `null` or `false`: `@.tags`
+    Exists(Operand),
+    Compare(Operand, CmpOp, Operand),
+    Not(Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+/// A side of a filter comparison
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operand {
+    /// Path relative to the current item: `@`, `@.a.b`
+    Current(Vec<Segment>),
+    /// Number, quoted string, `true`, `false` or `null`
+    Literal(Value),
+}
+
+/// Filter comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Segment {
+    /// Whether this segment selects at most one value
+    pub fn is_definite(&self) -> bool {
+        matches!(
+            self,
+            Segment::Field(_) | Segment::Index(_) | Segment::FromEnd(_)
+        )
+    }
 }
 
 /// Parse a JSONPath string into segments
@@ -28,77 +90,48 @@ pub enum Segment {
 /// Examples:
 /// - "$.price.currency" → [Field("price"), Field("currency")]
 /// - "items[0].name" → [Field("items"), Index(0), Field("name")]
+/// - "$..id" → [Descendant(Field("id"))]
 pub fn parse(path: &str) -> Result<Vec<Segment>, NikaError> {
-    // Remove $. prefix if present
-    let path = if let Some(stripped) = path.strip_prefix("$.") {
-        stripped
-    } else if path == "$" {
-        return Ok(vec![]); // Root reference
-    } else {
-        path
-    };
-
-    if path.is_empty() {
-        return Ok(vec![]);
-    }
-
+    let mut parser = Parser { path, pos: 0 };
     let mut segments = Vec::new();
 
-    for part in path.split('.') {
-        if part.is_empty() {
-            return Err(NikaError::JsonPathUnsupported {
-                path: path.to_string(),
-            });
-        }
-
-        // Check for array index: field[0] or just [0]
-        if let Some(bracket_pos) = part.find('[') {
-            // Field before bracket
-            let field = &part[..bracket_pos];
-            if !field.is_empty() {
-                segments.push(Segment::Field(field.to_string()));
-            }
-
-            // Parse index
-            if !part.ends_with(']') {
-                return Err(NikaError::JsonPathUnsupported {
-                    path: path.to_string(),
-                });
-            }
-
-            let index_str = &part[bracket_pos + 1..part.len() - 1];
-            let index: usize = index_str
-                .parse()
-                .map_err(|_| NikaError::JsonPathUnsupported {
-                    path: path.to_string(),
-                })?;
-
-            segments.push(Segment::Index(index));
-        } else if let Ok(index) = part.parse::<usize>() {
-            // Numeric segment treated as array index (e.g., "items.0")
-            segments.push(Segment::Index(index));
+    if !parser.eat("$") && !path.is_empty() {
+        // `task..id` leaves `.id` once the task id is split off
+        let descend = parser.eat(".");
+        let first = parser.member(false)?;
+        segments.push(if descend {
+            Segment::Descendant(Box::new(first))
         } else {
-            segments.push(Segment::Field(part.to_string()));
-        }
+            first
+        });
     }
+    parser.selectors(false, &mut segments)?;
 
+    if parser.pos != path.len() {
+        return Err(parser.err());
+    }
     Ok(segments)
 }
 
 /// Apply JSONPath segments to a JSON value
 ///
-/// Uses references internally, only clones once at the end.
+/// Definite paths return the selected value (`None` when missing); other
+/// paths return an array of all matches. Uses references internally and
+/// only clones the result.
 pub fn apply(value: &Value, segments: &[Segment]) -> Option<Value> {
-    let mut current = value;
+    if segments.iter().all(Segment::is_definite) {
+        return select_one(value, segments).cloned();
+    }
 
+    let mut matches = vec![value];
     for segment in segments {
-        current = match segment {
-            Segment::Field(name) => current.get(name)?,
-            Segment::Index(idx) => current.get(*idx)?,
-        };
+        let mut next = Vec::new();
+        for value in matches {
+            select(value, segment, &mut next);
+        }
+        matches = next;
     }
-
-    Some(current.clone()) // Single clone at the end
+    Some(Value::Array(matches.into_iter().cloned().collect()))
 }
 
 /// Parse and apply JSONPath in one step
@@ -107,20 +140,390 @@ pub fn resolve(value: &Value, path: &str) -> Result<Option<Value>, NikaError> {
     Ok(apply(value, &segments))
 }
 
-/// Validate JSONPath syntax without parsing (v0.1 minimal subset)
+/// Validate JSONPath syntax without applying it
 ///
-/// Supported:
-/// - `$.a.b.c` (dot notation)
-/// - `$.a[0].b` (array index)
-/// - `a.b.c` (without $ prefix)
-///
-/// Not supported: filters, wildcards, slices, unions
+/// Not supported: unions, script expressions, functions
 pub fn validate(path: &str) -> Result<(), NikaError> {
     // Parsing validates syntax - if it parses, it's valid
     parse(path)?;
     Ok(())
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// EVALUATION
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn select_one<'a>(value: &'a Value, segments: &[Segment]) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in segments {
+        current = match segment {
+            Segment::Field(name) => current.get(name)?,
+            Segment::Index(idx) => current.get(*idx)?,
+            Segment::FromEnd(n) => {
+                let items = current.as_array()?;
+                items.get(items.len().checked_sub(*n)?)?
+            }
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+fn select<'a>(value: &'a Value, segment: &Segment, out: &mut Vec<&'a Value>) {
+    match segment {
+        Segment::Field(_) | Segment::Index(_) | Segment::FromEnd(_) => {
+            out.extend(select_one(value, std::slice::from_ref(segment)))
+        }
+        Segment::Wildcard => out.extend(children(value)),
+        Segment::Slice { start, end, step } => {
+            if let Some(items) = value.as_array() {
+                out.extend(
+                    slice_indices(items.len(), *start, *end, *step)
+                        .into_iter()
+                        .map(|i| &items[i]),
+                );
+            }
+        }
+        Segment::Filter(filter) => out.extend(
+            children(value)
+                .into_iter()
+                .filter(|item| filter.matches(item)),
+        ),
+        Segment::Descendant(inner) => {
+            select(value, inner, out);
+            for child in children(value) {
+                select(child, segment, out);
+            }
+        }
+    }
+}
+
+/// Array items or object values
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Indices selected by `[start:end:step]` on an array of `len` items
+fn slice_indices(len: usize, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let len = len as i64;
+    let bound = |i: i64| if i < 0 { i + len } else { i };
+    let mut out = Vec::new();
+    if step > 0 {
+        let mut i = start.map_or(0, bound).clamp(0, len);
+        let end = end.map_or(len, bound).clamp(0, len);
+        while i < end {
+            out.push(i as usize);
+            i += step;
+        }
+    } else {
+        let mut i = start.map_or(len - 1, bound).clamp(-1, len - 1);
+        let end = end.map_or(-1, bound).clamp(-1, len - 1);
+        while i > end {
+            out.push(i as usize);
+            i += step;
+        }
+    }
+    out
+}
+
+impl Filter {
+    /// Whether `item` passes the filter
+    pub fn matches(&self, item: &Value) -> bool {
+        match self {
+            Filter::Exists(operand) => !matches!(
+                operand.eval(item),
+                None | Some(Value::Null | Value::Bool(false))
+            ),
+            Filter::Compare(left, op, right) => {
+                // Items without the compared field never match
+                let (Some(a), Some(b)) = (left.eval(item), right.eval(item)) else {
+                    return false;
+                };
+                let ordering = match (&a, &b) {
+                    (Value::Number(x), Value::Number(y)) => x
+                        .as_f64()
+                        .zip(y.as_f64())
+                        .and_then(|(x, y)| x.partial_cmp(&y)),
+                    (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+                    _ => (a == b).then_some(Ordering::Equal),
+                };
+                match op {
+                    CmpOp::Eq => ordering == Some(Ordering::Equal),
+                    CmpOp::Ne => ordering != Some(Ordering::Equal),
+                    CmpOp::Lt => ordering == Some(Ordering::Less),
+                    CmpOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    CmpOp::Gt => ordering == Some(Ordering::Greater),
+                    CmpOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                }
+            }
+            Filter::Not(inner) => !inner.matches(item),
+            Filter::And(a, b) => a.matches(item) && b.matches(item),
+            Filter::Or(a, b) => a.matches(item) || b.matches(item),
+        }
+    }
+}
+
+impl Operand {
+    fn eval(&self, item: &Value) -> Option<Value> {
+        match self {
+            Operand::Current(segments) => apply(item, segments),
+            Operand::Literal(value) => Some(value.clone()),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// PARSER
+// ═══════════════════════════════════════════════════════════════════════════
+
+struct Parser<'a> {
+    path: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn err(&self) -> NikaError {
+        NikaError::JsonPathUnsupported {
+            path: self.path.to_string(),
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.path[self.pos..]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.rest().starts_with(token);
+        if found {
+            self.pos += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), NikaError> {
+        self.skip_ws();
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.err())
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consume `.name`, `..name` and `[...]` selectors into `segments`
+    fn selectors(&mut self, in_filter: bool, segments: &mut Vec<Segment>) -> Result<(), NikaError> {
+        loop {
+            if self.eat("..") {
+                let inner = self.member(in_filter)?;
+                segments.push(Segment::Descendant(Box::new(inner)));
+            } else if self.eat(".") {
+                segments.push(self.member(in_filter)?);
+            } else if self.eat("[") {
+                segments.push(self.bracket()?);
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// A selector after a dot: `name`, `0`, `*` or `[...]`
+    fn member(&mut self, in_filter: bool) -> Result<Segment, NikaError> {
+        if self.eat("[") {
+            return self.bracket();
+        }
+        if self.eat("*") {
+            return Ok(Segment::Wildcard);
+        }
+        let len = self
+            .rest()
+            .find(|c: char| {
+                matches!(c, '.' | '[')
+                    || in_filter
+                        && (c.is_whitespace()
+                            || matches!(c, ')' | ']' | '=' | '!' | '<' | '>' | '&' | '|'))
+            })
+            .unwrap_or(self.rest().len());
+        let name = &self.rest()[..len];
+        if name.is_empty() {
+            return Err(self.err());
+        }
+        let segment = match name.parse::<usize>() {
+            // Numeric segment treated as array index (e.g., "items.0")
+            Ok(index) => Segment::Index(index),
+            Err(_) => Segment::Field(name.to_string()),
+        };
+        self.pos += len;
+        Ok(segment)
+    }
+
+    /// A bracket selector, after `[`: `*`, `'key'`, `?(...)`, index or slice
+    fn bracket(&mut self) -> Result<Segment, NikaError> {
+        self.skip_ws();
+        let segment = if self.eat("*") {
+            Segment::Wildcard
+        } else if self.eat("?") {
+            self.skip_ws();
+            let parens = self.eat("(");
+            let filter = self.or()?;
+            if parens {
+                self.expect(")")?;
+            }
+            Segment::Filter(filter)
+        } else if self.rest().starts_with(['\'', '"']) {
+            Segment::Field(self.quoted()?)
+        } else {
+            let len = self.rest().find(']').ok_or_else(|| self.err())?;
+            let text = self.rest()[..len].trim().to_string();
+            self.pos += len;
+            self.index_or_slice(&text)?
+        };
+        self.expect("]")?;
+        Ok(segment)
+    }
+
+    fn index_or_slice(&self, text: &str) -> Result<Segment, NikaError> {
+        let int = |s: &str| -> Result<Option<i64>, NikaError> {
+            match s.trim() {
+                "" => Ok(None),
+                s => s.parse().map(Some).map_err(|_| self.err()),
+            }
+        };
+        if !text.contains(':') {
+            return match int(text)? {
+                Some(i) if i >= 0 => Ok(Segment::Index(i as usize)),
+                Some(i) => Ok(Segment::FromEnd(i.unsigned_abs() as usize)),
+                None => Err(self.err()),
+            };
+        }
+        let parts: Vec<&str> = text.split(':').collect();
+        if parts.len() > 3 {
+            return Err(self.err());
+        }
+        let step = match parts.get(2) {
+            Some(s) => int(s)?.unwrap_or(1),
+            None => 1,
+        };
+        if step == 0 {
+            return Err(self.err());
+        }
+        Ok(Segment::Slice {
+            start: int(parts[0])?,
+            end: int(parts[1])?,
+            step,
+        })
+    }
+
+    /// A `'...'` or `"..."` string; backslash escapes the next character
+    fn quoted(&mut self) -> Result<String, NikaError> {
+        let mut chars = self.rest().char_indices();
+        let (_, quote) = chars.next().ok_or_else(|| self.err())?;
+        let mut out = String::new();
+        let mut escaped = false;
+        for (i, c) in chars {
+            if escaped {
+                out.push(c);
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                self.pos += i + c.len_utf8();
+                return Ok(out);
+            } else {
+                out.push(c);
+            }
+        }
+        Err(self.err())
+    }
+
+    fn or(&mut self) -> Result<Filter, NikaError> {
+        let mut left = self.and()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("||") {
+                return Ok(left);
+            }
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> Result<Filter, NikaError> {
+        let mut left = self.unary()?;
+        loop {
+            self.skip_ws();
+            if !self.eat("&&") {
+                return Ok(left);
+            }
+            left = Filter::And(Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Filter, NikaError> {
+        self.skip_ws();
+        if self.eat("!") {
+            return Ok(Filter::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let inner = self.or()?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+
+        let left = self.operand()?;
+        self.skip_ws();
+        let op = [
+            ("==", CmpOp::Eq),
+            ("!=", CmpOp::Ne),
+            ("<=", CmpOp::Le),
+            (">=", CmpOp::Ge),
+            ("<", CmpOp::Lt),
+            (">", CmpOp::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat(token));
+        match op {
+            Some((_, op)) => Ok(Filter::Compare(left, op, self.operand()?)),
+            None => Ok(Filter::Exists(left)),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, NikaError> {
+        self.skip_ws();
+        if self.eat("@") {
+            let mut segments = Vec::new();
+            self.selectors(true, &mut segments)?;
+            return Ok(Operand::Current(segments));
+        }
+        if self.rest().starts_with(['\'', '"']) {
+            return Ok(Operand::Literal(Value::String(self.quoted()?)));
+        }
+        let len = self
+            .rest()
+            .find(|c: char| {
+                c.is_whitespace() || matches!(c, ')' | ']' | '&' | '|' | '=' | '!' | '<' | '>')
+            })
+            .unwrap_or(self.rest().len());
+        let literal = match &self.rest()[..len] {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "null" => Value::Null,
+            text => match serde_json::from_str::<Value>(text) {
+                Ok(number @ Value::Number(_)) => number,
+                _ => return Err(self.err()),
+            },
+        };
+        self.pos += len;
+        Ok(Operand::Literal(literal))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,4 +633,135 @@ mod tests {
         let result = resolve(&value, "items.1").unwrap();
         assert_eq!(result, Some(json!("second")));
     }
+
+    fn store() -> Value {
+        json!({
+            "store": {
+                "books": [
+                    {"title": "Dune", "price": 9.5, "tags": ["scifi"]},
+                    {"title": "Emma", "price": 12, "author": {"name": "Austen"}},
+                    {"title": "Ulysses", "price": 25, "tags": []},
+                    {"title": "It", "price": 12.0, "stock": false}
+                ],
+                "owner": {"name": "Ada"}
+            }
+        })
+    }
+
+    fn titles(path: &str) -> Value {
+        resolve(&store(), path).unwrap().unwrap()
+    }
+
+    #[test]
+    fn parse_new_segments() {
+        assert_eq!(
+            parse("$.a[*]['b.c'][-1][1:3]..d").unwrap(),
+            vec![
+                Segment::Field("a".to_string()),
+                Segment::Wildcard,
+                Segment::Field("b.c".to_string()),
+                Segment::FromEnd(1),
+                Segment::Slice {
+                    start: Some(1),
+                    end: Some(3),
+                    step: 1,
+                },
+                Segment::Descendant(Box::new(Segment::Field("d".to_string()))),
+            ]
+        );
+        assert_eq!(
+            parse("a[?(@.x >= 2)]").unwrap()[1],
+            Segment::Filter(Filter::Compare(
+                Operand::Current(vec![Segment::Field("x".to_string())]),
+                CmpOp::Ge,
+                Operand::Literal(json!(2)),
+            ))
+        );
+        // `task..id` split at the task id leaves `.id`
+        assert_eq!(
+            parse(".id").unwrap(),
+            vec![Segment::Descendant(Box::new(Segment::Field(
+                "id".to_string()
+            )))]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        for path in [
+            "a..",
+            "a.",
+            "a[",
+            "a[0",
+            "a[x]",
+            "a[0,1]",
+            "a[::0]",
+            "a['b]",
+            "a[?(@.x >)]",
+            "a[?(@.x == 1]",
+            "$x",
+        ] {
+            assert!(
+                matches!(parse(path), Err(NikaError::JsonPathUnsupported { .. })),
+                "{path} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn apply_wildcard_and_descent() {
+        assert_eq!(
+            titles("$.store.books[*].title"),
+            json!(["Dune", "Emma", "Ulysses", "It"])
+        );
+        assert_eq!(titles("store.books.*.price"), json!([9.5, 12, 25, 12.0]));
+        assert_eq!(titles("$..name"), json!(["Austen", "Ada"]));
+        assert_eq!(titles("$.store..tags[0]"), json!(["scifi"]));
+        assert_eq!(titles("$.nope[*]"), json!([]));
+    }
+
+    #[test]
+    fn apply_slices_and_negative_index() {
+        assert_eq!(titles("$.store.books[-1].title"), json!("It"));
+        assert_eq!(resolve(&store(), "$.store.books[-9]").unwrap(), None);
+        assert_eq!(
+            titles("$.store.books[1:3].title"),
+            json!(["Emma", "Ulysses"])
+        );
+        assert_eq!(titles("$.store.books[-2:].title"), json!(["Ulysses", "It"]));
+        assert_eq!(
+            titles("$.store.books[::2].title"),
+            json!(["Dune", "Ulysses"])
+        );
+        assert_eq!(
+            titles("$.store.books[::-1].title"),
+            json!(["It", "Ulysses", "Emma", "Dune"])
+        );
+    }
+
+    #[test]
+    fn apply_filters() {
+        assert_eq!(
+            titles("$.store.books[?(@.price > 10)].title"),
+            json!(["Emma", "Ulysses", "It"])
+        );
+        assert_eq!(
+            titles("$.store.books[?(@.price == 12 && @.author)].title"),
+            json!(["Emma"])
+        );
+        assert_eq!(
+            titles("$.store.books[?(@.title == 'Dune' || @.price>=25)].title"),
+            json!(["Dune", "Ulysses"])
+        );
+        assert_eq!(
+            titles("$.store.books[?(!@.tags)].title"),
+            json!(["Emma", "It"])
+        );
+        assert_eq!(titles("$.store.books[?(@.stock)]"), json!([]));
+        assert_eq!(
+            titles("$.store.books[?(@.author.name != \"Austen\")].title"),
+            json!([])
+        );
+        assert_eq!(titles("$.store.books[?(@.price < 0)]"), json!([]));
+    }
 }
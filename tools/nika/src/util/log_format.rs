@@ -0,0 +1,181 @@
+//! Log Format - text or JSON lines for tracing output (v0.7)
+//!
+//! `--log-format json` (or `NIKA_LOG_FORMAT=json`) writes one JSON object
+//! per log line, for Loki, Datadog and other log pipelines. Fields of the
+//! spans an event happened in are flattened into its line, so every line
+//! of a run carries its `run_id`, and every line of a task its `task_id`:
+//!
+//! ```text
+//! {"timestamp":"2026-10-18T09:12:03.114Z","level":"INFO","target":"nika::runtime::runner",
+//!  "run_id":"gen-7f3a...","task_id":"summarize","message":"Task completed"}
+//! ```
+
+use std::fmt;
+
+use chrono::SecondsFormat;
+use serde_json::{Map, Value};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::{JsonFields, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Read when `--log-format` is not given
+pub const LOG_FORMAT_ENV: &str = "NIKA_LOG_FORMAT";
+
+/// Shape of tracing output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines (default)
+    #[default]
+    Text,
+    /// One JSON object per line ([`JsonLines`])
+    Json,
+}
+
+impl LogFormat {
+    /// Parse `text` or `json` (case-insensitive)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// The `--log-format` value, else `NIKA_LOG_FORMAT`, else text
+    ///
+    /// Err holds the unrecognized value.
+    pub fn resolve(flag: Option<&str>) -> Result<Self, String> {
+        let value = match flag {
+            Some(value) => value.to_string(),
+            None => match std::env::var(LOG_FORMAT_ENV) {
+                Ok(value) if !value.trim().is_empty() => value,
+                _ => return Ok(Self::Text),
+            },
+        };
+        Self::parse(&value).ok_or(value)
+    }
+}
+
+/// Event formatter writing one JSON object per line
+///
+/// Span fields are read back as formatted by [`JsonFields`], which must be
+/// the subscriber's field formatter (`.fmt_fields(JsonFields::new())`).
+/// Inner spans win over outer ones, and event fields over span fields.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonLines;
+
+impl<S> FormatEvent<S, JsonFields> for JsonLines
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, JsonFields>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            chrono::Utc::now()
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+                .into(),
+        );
+        line.insert("level".to_string(), meta.level().to_string().into());
+        line.insert("target".to_string(), meta.target().into());
+
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<FormattedFields<JsonFields>>() {
+                    extend_with_object(&mut line, &fields.fields);
+                }
+            }
+        }
+
+        let mut fields = String::new();
+        ctx.format_fields(Writer::new(&mut fields), event)?;
+        extend_with_object(&mut line, &fields);
+
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+/// Merge the JSON object `fields` into `line` (spans without fields are empty)
+fn extend_with_object(line: &mut Map<String, Value>, fields: &str) {
+    if let Ok(Value::Object(map)) = serde_json::from_str(fields) {
+        line.extend(map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer collecting every line into a shared buffer
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_flatten_run_and_task_ids() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .fmt_fields(JsonFields::new())
+            .event_format(JsonLines)
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let run = tracing::info_span!("run", run_id = "gen-1", workflow_tasks = 2);
+            let _run = run.enter();
+            tracing::info!("Starting workflow execution");
+            let task = tracing::info_span!("task", task_id = "summarize");
+            let _task = task.enter();
+            tracing::warn!(attempt = 2, "Retrying");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        assert_eq!(lines[0]["run_id"], "gen-1");
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["message"], "Starting workflow execution");
+        assert!(lines[0].get("task_id").is_none());
+
+        assert_eq!(lines[1]["run_id"], "gen-1");
+        assert_eq!(lines[1]["task_id"], "summarize");
+        assert_eq!(lines[1]["attempt"], 2);
+        assert_eq!(lines[1]["level"], "WARN");
+        assert!(lines[1]["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn test_parses_log_format() {
+        assert_eq!(LogFormat::parse("json"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse(" JSON "), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("text"), Some(LogFormat::Text));
+        assert_eq!(LogFormat::parse("xml"), None);
+        assert_eq!(LogFormat::resolve(Some("json")), Ok(LogFormat::Json));
+        assert_eq!(LogFormat::resolve(Some("yaml")), Err("yaml".to_string()));
+    }
+}
@@ -1,16 +1,42 @@
 //! Utilities Module - shared infrastructure (v0.1)
 //!
 //! Contains helper functions and data structures used across the codebase:
+//! - `attribution`: License and author hints in fetched content (v0.7)
+//! - `compat`: Version stamps and migrations of traces and checkpoints (v0.7)
 //! - `constants`: Centralized timeouts and limits
+//! - `crash`: Crash bundles and pre-filled issue URLs on panics (v0.7)
+//! - `cron`: Cron expressions for scheduled workflows (v0.7)
+//! - `graphql`: GraphQL query syntax check for `fetch: { graphql }` (v0.7)
+//! - `injection`: Prompt-injection heuristics for untrusted content (v0.7)
 //! - `interner`: String interning for recurring task IDs (Arc<str> deduplication)
-//! - `jsonpath`: Minimal JSONPath parser for path resolution
+//! - `jsonpath`: JSONPath parser for path resolution (filters, wildcards, slices)
+//! - `log_format`: Text or JSON-lines tracing output (v0.7)
+//! - `readability`: Main content of HTML pages as Markdown (v0.7, `http` feature)
+//! - `smart_string`: Inline strings for short event labels (v0.7)
+//! - `watch`: File globs for watch-triggered workflows (v0.7)
 
+pub mod attribution;
+pub mod compat;
 pub mod constants;
+pub mod crash;
+pub mod cron;
+pub mod graphql;
+pub mod injection;
 mod interner;
 pub mod jsonpath;
+pub mod log_format;
+#[cfg(feature = "http")]
+pub mod readability;
+pub mod smart_string;
+pub mod watch;
 
 // Re-export public types
+pub use attribution::Attribution;
 pub use constants::{
     CONNECT_TIMEOUT, EXEC_TIMEOUT, FETCH_TIMEOUT, INFER_TIMEOUT, MCP_CALL_TIMEOUT, REDIRECT_LIMIT,
 };
+pub use cron::CronSchedule;
+pub use injection::InjectionGuard;
 pub use interner::{intern, Interner};
+pub use smart_string::SmartString;
+pub use watch::WatchPattern;
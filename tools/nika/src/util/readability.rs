@@ -0,0 +1,522 @@
+//! Readability - main content of an HTML page as Markdown (v0.7)
+//!
+//! A small take on Mozilla's Readability, used by the `read_url` tool:
+//! 1. Pick the content root: `<article>`, `<main>` or `role="main"` when
+//!    present, otherwise the element whose paragraphs score best (text
+//!    length and commas, minus link-heavy blocks).
+//! 2. Drop boilerplate under it: scripts, navigation, headers, footers,
+//!    forms, and elements whose class/id reads like a sidebar, share bar,
+//!    cookie banner or comment thread.
+//! 3. Render what's left as Markdown: headings, paragraphs, lists, links
+//!    (made absolute), emphasis, code blocks, quotes and tables.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use reqwest::Url;
+use rustc_hash::FxHashMap;
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements never rendered
+const SKIP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "iframe", "svg", "canvas", "button", "select", "input", "textarea", "dialog",
+];
+
+/// Elements rendered as their own paragraph
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "figure",
+    "figcaption",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "details",
+    "summary",
+];
+
+/// class/id of boilerplate blocks
+static UNLIKELY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"(?i)banner|breadcrumb|comment|cookie|consent|footer|masthead|menu|modal|",
+        r"newsletter|nav|popup|promo|related|share|sidebar|social|sponsor|subscribe|",
+        r"advert|\bads?\b",
+    ))
+    .expect("valid regex")
+});
+
+/// class/id that keeps a block despite `UNLIKELY`
+static LIKELY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)article|body|content|entry|main|post|story|text").expect("valid regex")
+});
+
+/// Main content of a page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Article {
+    /// `og:title`, else `<title>`, else the first `<h1>`
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// Extract the main content of `html`, resolving links against `base`
+pub fn extract(html: &str, base: Option<&Url>) -> Article {
+    let document = Html::parse_document(html);
+    let root = content_root(&document);
+    let mut renderer = Renderer {
+        base,
+        out: String::new(),
+        lists: Vec::new(),
+    };
+    renderer.children(root);
+    Article {
+        title: title(&document),
+        markdown: tidy(&renderer.out),
+    }
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).expect("valid selector")
+}
+
+fn title(document: &Html) -> Option<String> {
+    let first_text = |css: &str| {
+        document
+            .select(&selector(css))
+            .next()
+            .map(|el| collapse(&el.text().collect::<String>()))
+            .filter(|text| !text.is_empty())
+    };
+    document
+        .select(&selector(r#"meta[property="og:title"]"#))
+        .next()
+        .and_then(|el| el.attr("content"))
+        .map(collapse)
+        .filter(|text| !text.is_empty())
+        .or_else(|| first_text("title"))
+        .or_else(|| first_text("h1"))
+}
+
+/// Element holding the page's main content
+fn content_root(document: &Html) -> ElementRef<'_> {
+    let body = document
+        .select(&selector("body"))
+        .next()
+        .unwrap_or_else(|| document.root_element());
+    if let Some(root) = document
+        .select(&selector(r#"article, main, [role="main"]"#))
+        .max_by_key(|el| text_len(*el))
+        .filter(|el| text_len(*el) >= 200)
+    {
+        return root;
+    }
+
+    // Score paragraph containers: the paragraph's parent gets its points,
+    // the grandparent half of them
+    let mut scores: FxHashMap<_, (ElementRef<'_>, f64)> = FxHashMap::default();
+    for paragraph in document.select(&selector("p, pre, td")) {
+        let text = collapse(&paragraph.text().collect::<String>());
+        if text.chars().count() < 25 {
+            continue;
+        }
+        let points =
+            1.0 + text.matches(',').count() as f64 + (text.chars().count() / 100).min(3) as f64;
+        let parents = paragraph.ancestors().filter_map(ElementRef::wrap).take(2);
+        for (depth, parent) in parents.enumerate() {
+            let share = if depth == 0 { points } else { points / 2.0 };
+            scores.entry(parent.id()).or_insert((parent, 0.0)).1 += share;
+        }
+    }
+    scores
+        .into_values()
+        .map(|(el, score)| (el, score * (1.0 - link_density(el))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(el, _)| el)
+        .unwrap_or(body)
+}
+
+fn text_len(el: ElementRef<'_>) -> usize {
+    el.text().map(|t| t.trim().len()).sum()
+}
+
+/// Share of the text that sits in links
+fn link_density(el: ElementRef<'_>) -> f64 {
+    let total = text_len(el);
+    if total == 0 {
+        return 0.0;
+    }
+    let linked: usize = el.select(&selector("a")).map(text_len).sum();
+    linked as f64 / total as f64
+}
+
+fn is_boilerplate(el: ElementRef<'_>) -> bool {
+    let element = el.value();
+    if SKIP_TAGS.contains(&element.name()) || element.attr("hidden").is_some() {
+        return true;
+    }
+    if matches!(element.attr("aria-hidden"), Some("true")) {
+        return true;
+    }
+    let names = format!(
+        "{} {}",
+        element.attr("class").unwrap_or_default(),
+        element.id().unwrap_or_default()
+    );
+    UNLIKELY.is_match(&names) && !LIKELY.is_match(&names) && element.name() != "body"
+}
+
+struct Renderer<'a> {
+    base: Option<&'a Url>,
+    out: String,
+    /// Open lists: None for `<ul>`, the next number for `<ol>`
+    lists: Vec<Option<usize>>,
+}
+
+impl Renderer<'_> {
+    fn children(&mut self, el: ElementRef<'_>) {
+        for child in el.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, el: ElementRef<'_>) {
+        if is_boilerplate(el) {
+            return;
+        }
+        let name = el.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = self.inline(el);
+                if !text.is_empty() {
+                    let level = name[1..].parse().unwrap_or(1);
+                    self.paragraph_break();
+                    self.out.push_str(&"#".repeat(level));
+                    self.out.push(' ');
+                    self.out.push_str(&text);
+                    self.paragraph_break();
+                }
+            }
+            "br" => self.out.push('\n'),
+            "hr" => {
+                self.paragraph_break();
+                self.out.push_str("---");
+                self.paragraph_break();
+            }
+            "pre" => {
+                let code: String = el.text().collect();
+                self.paragraph_break();
+                self.out.push_str("```\n");
+                self.out.push_str(code.trim_matches('\n'));
+                self.out.push_str("\n```");
+                self.paragraph_break();
+            }
+            "code" | "kbd" | "samp" => {
+                let code = collapse(&el.text().collect::<String>());
+                if !code.is_empty() {
+                    self.push_inline(&format!("`{}`", code));
+                }
+            }
+            "strong" | "b" => self.wrapped(el, "**"),
+            "em" | "i" => self.wrapped(el, "*"),
+            "a" => self.link(el),
+            "img" => {
+                let alt = collapse(el.attr("alt").unwrap_or_default());
+                if let (false, Some(src)) = (alt.is_empty(), el.attr("src")) {
+                    let src = self.absolute(src);
+                    self.push_inline(&format!("![{}]({})", alt, src));
+                }
+            }
+            "ul" | "ol" => {
+                let start = el.attr("start").and_then(|s| s.parse().ok()).unwrap_or(1);
+                self.lists.push((name == "ol").then_some(start));
+                self.line_break();
+                self.children(el);
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.paragraph_break();
+                } else {
+                    self.line_break();
+                }
+            }
+            "li" => {
+                self.line_break();
+                let depth = self.lists.len().max(1);
+                self.out.push_str(&"  ".repeat(depth - 1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.out.push_str(&marker);
+                self.children(el);
+            }
+            "blockquote" => {
+                let mut inner = Renderer {
+                    base: self.base,
+                    out: String::new(),
+                    lists: Vec::new(),
+                };
+                inner.children(el);
+                let quoted = tidy(&inner.out)
+                    .lines()
+                    .map(|line| format!("> {}", line).trim_end().to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if !quoted.is_empty() {
+                    self.paragraph_break();
+                    self.out.push_str(&quoted);
+                    self.paragraph_break();
+                }
+            }
+            "table" => self.table(el),
+            _ if BLOCK_TAGS.contains(&name) => {
+                self.paragraph_break();
+                self.children(el);
+                self.paragraph_break();
+            }
+            _ => self.children(el),
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let collapsed = collapse_keep_edges(text);
+        if collapsed.is_empty() {
+            return;
+        }
+        self.push_inline(&collapsed);
+    }
+
+    /// Append inline content, without leading spaces at a line start
+    fn push_inline(&mut self, text: &str) {
+        let at_line_start = self.out.is_empty() || self.out.ends_with('\n');
+        let at_space = at_line_start || self.out.ends_with(' ');
+        let text = if at_space { text.trim_start() } else { text };
+        self.out.push_str(text);
+    }
+
+    /// Inline rendering of `el` as one line
+    fn inline(&self, el: ElementRef<'_>) -> String {
+        let mut inner = Renderer {
+            base: self.base,
+            out: String::new(),
+            lists: Vec::new(),
+        };
+        inner.children(el);
+        collapse(&inner.out)
+    }
+
+    fn wrapped(&mut self, el: ElementRef<'_>, marker: &str) {
+        let text = self.inline(el);
+        if !text.is_empty() {
+            self.push_inline(&format!(" {}{}{}", marker, text, marker));
+        }
+    }
+
+    fn link(&mut self, el: ElementRef<'_>) {
+        let text = self.inline(el);
+        if text.is_empty() {
+            return;
+        }
+        match el.attr("href").map(str::trim) {
+            Some(href)
+                if !href.is_empty()
+                    && !href.starts_with('#')
+                    && !href.to_ascii_lowercase().starts_with("javascript:") =>
+            {
+                let href = self.absolute(href);
+                self.push_inline(&format!(" [{}]({})", text, href));
+            }
+            _ => self.push_inline(&format!(" {}", text)),
+        }
+    }
+
+    fn table(&mut self, el: ElementRef<'_>) {
+        let rows: Vec<(Vec<String>, bool)> = el
+            .select(&selector("tr"))
+            .map(|row| {
+                let cells = row
+                    .child_elements()
+                    .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                    .collect::<Vec<_>>();
+                let header = cells.iter().all(|cell| cell.value().name() == "th");
+                let cells: Vec<String> = cells
+                    .into_iter()
+                    .map(|cell| self.inline(cell).replace('|', "\\|"))
+                    .collect();
+                (cells, header)
+            })
+            .filter(|(cells, _)| !cells.is_empty())
+            .collect();
+        if rows.is_empty() {
+            return;
+        }
+        self.paragraph_break();
+        for (i, (cells, header)) in rows.iter().enumerate() {
+            self.out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            if i == 0 && *header {
+                let rule = vec!["---"; cells.len()].join(" | ");
+                self.out.push_str(&format!("| {} |\n", rule));
+            }
+        }
+        self.paragraph_break();
+    }
+
+    fn absolute(&self, href: &str) -> String {
+        self.base
+            .and_then(|base| base.join(href).ok())
+            .map(String::from)
+            .unwrap_or_else(|| href.to_string())
+    }
+
+    fn line_break(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn paragraph_break(&mut self) {
+        self.line_break();
+        if !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+}
+
+/// Collapse whitespace runs to single spaces, trimmed
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Collapse whitespace runs, keeping one space where the text had some at
+/// either end (so `a <b>b</b>` keeps its space)
+fn collapse_keep_edges(text: &str) -> String {
+    let inner = collapse(text);
+    if inner.is_empty() {
+        return if text.is_empty() {
+            inner
+        } else {
+            " ".to_string()
+        };
+    }
+    let lead = if text.starts_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    let trail = if text.ends_with(char::is_whitespace) {
+        " "
+    } else {
+        ""
+    };
+    format!("{}{}{}", lead, inner, trail)
+}
+
+/// Trim lines, and keep at most one blank line between blocks
+fn tidy(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut blank = 0;
+    for line in markdown.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank > 0 { "\n\n" } else { "\n" });
+        }
+        out.push_str(line);
+        blank = 0;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r##"<!doctype html>
+<html><head>
+  <title>Fallback title</title>
+  <meta property="og:title" content="Rust 2026 Survey Results">
+  <script>var tracking = 1;</script>
+</head><body>
+  <header><a href="/">Home</a> <a href="/blog">Blog</a></header>
+  <nav><ul><li><a href="/a">Menu A</a></li></ul></nav>
+  <div class="layout">
+    <div class="sidebar-widget"><p>Subscribe to our newsletter, get updates, win prizes, etc.</p></div>
+    <div class="post-body">
+      <h1>Survey <em>results</em></h1>
+      <p>This year, over 10,000 people answered, which is a record, by far.
+         See the <a href="/data.csv">raw data</a> or the
+         <a href="#methods">methods</a>.</p>
+      <ul><li>Async is <strong>popular</strong></li><li>Compile times improved
+        <ol><li>incremental</li><li>parallel front end</li></ol></li></ul>
+      <pre><code>cargo install nika
+nika run flow.yaml</code></pre>
+      <blockquote><p>Rust keeps growing.</p></blockquote>
+      <table><tr><th>Year</th><th>Answers</th></tr><tr><td>2026</td><td>10,400</td></tr></table>
+      <div class="share-buttons"><a href="https://x.com/share">Share</a></div>
+    </div>
+  </div>
+  <footer>Copyright, all rights reserved, 2026, Example Inc.</footer>
+</body></html>"##;
+
+    #[test]
+    fn test_extracts_main_content_as_markdown() {
+        let base = Url::parse("https://blog.example.com/posts/survey").unwrap();
+        let article = extract(PAGE, Some(&base));
+        assert_eq!(article.title.as_deref(), Some("Rust 2026 Survey Results"));
+        assert_eq!(
+            article.markdown,
+            "# Survey *results*
+
+This year, over 10,000 people answered, which is a record, by far. See the [raw data](https://blog.example.com/data.csv) or the methods.
+
+- Async is **popular**
+- Compile times improved
+  1. incremental
+  2. parallel front end
+
+```
+cargo install nika
+nika run flow.yaml
+```
+
+> Rust keeps growing.
+
+| Year | Answers |
+| --- | --- |
+| 2026 | 10,400 |"
+        );
+    }
+
+    #[test]
+    fn test_prefers_article_and_falls_back_to_body() {
+        let html = format!(
+            "<body><div>Teaser</div><article><p>{}</p></article></body>",
+            "Long enough article text. ".repeat(10)
+        );
+        let article = extract(&html, None);
+        assert!(article.markdown.starts_with("Long enough article text."));
+        assert!(!article.markdown.contains("Teaser"));
+
+        let short = extract("<title> Hi </title><body>Just text</body>", None);
+        assert_eq!(short.title.as_deref(), Some("Hi"));
+        assert_eq!(short.markdown, "Just text");
+    }
+}
@@ -0,0 +1,29 @@
+//! Smart Strings - inline storage for short event labels (v0.7)
+//!
+//! [`SmartString`] keeps strings of up to 24 bytes inline (no heap
+//! allocation) and serializes like `String`. Used for the identifiers and
+//! labels events carry over and over: provider and model names, binding
+//! aliases and paths, MCP servers, tools and call IDs, finish reasons.
+//! Replaying a trace deserializes them without allocating.
+//!
+//! Task IDs (in events and DataStore keys) stay `Arc<str>`: they are
+//! interned once ([`intern`](super::intern)) and shared by the store, the
+//! DAG and every event, so cloning them is already free.
+
+pub type SmartString = compact_str::CompactString;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_labels_are_inline_and_serialize_as_strings() {
+        let model = SmartString::from("claude-sonnet-4-20250514");
+        assert!(!model.is_heap_allocated());
+
+        let json = serde_json::to_string(&model).unwrap();
+        assert_eq!(json, "\"claude-sonnet-4-20250514\"");
+        let back: SmartString = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, "claude-sonnet-4-20250514");
+    }
+}
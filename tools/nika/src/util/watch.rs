@@ -0,0 +1,142 @@
+//! File globs for `triggers: { watch: ... }` (v0.7)
+//!
+//! A pattern is relative to the workflow file's directory (absolute
+//! patterns are used as is):
+//!
+//! ```text
+//! ./inbox/*.md          files directly in inbox/
+//! ./inbox/**/*.md       files anywhere below inbox/
+//! ../shared/report.csv  one file
+//! ```
+//!
+//! The leading components without glob characters form the watched root
+//! directory; the rest is matched against paths below it. `*` and `?` stay
+//! within one directory level, `**` crosses levels.
+
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::error::NikaError;
+
+/// A parsed watch pattern, anchored at a base directory
+#[derive(Debug, Clone)]
+pub struct WatchPattern {
+    pattern: String,
+    root: PathBuf,
+    /// Part matched below `root`, with `/` separators
+    rest: String,
+    matcher: GlobMatcher,
+}
+
+impl WatchPattern {
+    /// Parse `pattern`, resolving its literal prefix against `base`
+    pub fn parse(pattern: &str, base: &Path) -> Result<Self, NikaError> {
+        let components: Vec<String> = Path::new(pattern.trim())
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        // Literal directories, always leaving the last component to match
+        let literal = components
+            .iter()
+            .take(components.len().saturating_sub(1))
+            .take_while(|c| !is_glob(c))
+            .count();
+        let rest = components[literal..].join("/");
+        if rest.is_empty() {
+            return Err(invalid(pattern, "pattern is empty"));
+        }
+        if rest.split('/').any(|c| c == "..") {
+            return Err(invalid(
+                pattern,
+                "'..' is only allowed before the first glob",
+            ));
+        }
+
+        let matcher = GlobBuilder::new(&rest)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| invalid(pattern, e.kind().to_string()))?
+            .compile_matcher();
+        let root = components[..literal]
+            .iter()
+            .fold(base.to_path_buf(), |root, c| root.join(c));
+
+        Ok(Self {
+            pattern: pattern.trim().to_string(),
+            root,
+            rest,
+            matcher,
+        })
+    }
+
+    /// The pattern as written
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Directory to watch
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether matches can be deeper than the root's direct children
+    pub fn is_recursive(&self) -> bool {
+        self.rest.contains('/')
+    }
+
+    /// Whether `path` (under the root) matches
+    pub fn matches(&self, path: &Path) -> bool {
+        path.strip_prefix(&self.root)
+            .is_ok_and(|relative| self.matcher.is_match(relative))
+    }
+}
+
+fn is_glob(component: &str) -> bool {
+    component.contains(['*', '?', '[', '{'])
+}
+
+fn invalid(pattern: &str, reason: impl Into<String>) -> NikaError {
+    NikaError::InvalidWatchTrigger {
+        pattern: pattern.to_string(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_and_matching() {
+        let base = Path::new("/work/flows");
+        let inbox = WatchPattern::parse("./inbox/*.md", base).unwrap();
+        assert_eq!(inbox.root(), Path::new("/work/flows/inbox"));
+        assert!(!inbox.is_recursive());
+        assert!(inbox.matches(Path::new("/work/flows/inbox/note.md")));
+        assert!(!inbox.matches(Path::new("/work/flows/inbox/note.txt")));
+        // `*` does not cross directories
+        assert!(!inbox.matches(Path::new("/work/flows/inbox/old/note.md")));
+        assert!(!inbox.matches(Path::new("/elsewhere/inbox/note.md")));
+
+        let deep = WatchPattern::parse("../data/**/*.csv", base).unwrap();
+        assert_eq!(deep.root(), Path::new("/work/flows/../data"));
+        assert!(deep.is_recursive());
+        assert!(deep.matches(Path::new("/work/flows/../data/2026/10/sales.csv")));
+
+        let file = WatchPattern::parse("report.csv", base).unwrap();
+        assert_eq!(file.root(), Path::new("/work/flows"));
+        assert!(file.matches(Path::new("/work/flows/report.csv")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let base = Path::new(".");
+        assert!(WatchPattern::parse("", base).is_err());
+        assert!(WatchPattern::parse("./inbox/[*.md", base).is_err());
+        assert!(WatchPattern::parse("./*/../x.md", base).is_err());
+        let err = WatchPattern::parse("./inbox/{a", base).unwrap_err();
+        assert_eq!(err.code(), "NIKA-008");
+    }
+}
@@ -11,6 +11,8 @@
 //!   ANTHROPIC_API_KEY=... cargo test --test chat_continuation_test -- --ignored
 //!   OPENAI_API_KEY=... cargo test --test chat_continuation_test -- --ignored
 
+#![cfg(feature = "providers")]
+
 use rig::message::Message;
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
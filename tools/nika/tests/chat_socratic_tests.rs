@@ -12,6 +12,8 @@
 //! 5. **UX Consistency** - Is the interface intuitive?
 //! 6. **Edge Cases** - What happens at the boundaries?
 
+#![cfg(feature = "tui")]
+
 use nika::tui::chat_agent::{ChatAgent, ChatRole, StreamingState};
 use nika::tui::command::{Command, ModelProvider, HELP_TEXT};
 use nika::tui::file_resolve::FileResolver;
@@ -6,6 +6,8 @@
 //! These tests verify the agent: verb works correctly in conversational
 //! scenarios typical of chat interfaces.
 
+#![cfg(feature = "providers")]
+
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
@@ -81,7 +83,7 @@ fn test_event_log_conversation_tracking() {
     log.emit(EventKind::AgentComplete {
         task_id: "chat-1".into(),
         turns: 1,
-        stop_reason: "natural_completion".to_string(),
+        stop_reason: "natural_completion".into(),
     });
 
     let events = log.events();
@@ -95,16 +97,16 @@ fn test_event_log_mcp_invoke_sequence() {
     // Simulate MCP tool calling in conversation using correct variants
     log.emit(EventKind::McpInvoke {
         task_id: "chat-1".into(),
-        call_id: "call-123".to_string(),
-        mcp_server: "novanet".to_string(),
-        tool: Some("novanet_describe".to_string()),
+        call_id: "call-123".into(),
+        mcp_server: "novanet".into(),
+        tool: Some("novanet_describe".into()),
         resource: None,
         params: Some(serde_json::json!({"entity": "qr-code"})),
     });
 
     log.emit(EventKind::McpResponse {
         task_id: "chat-1".into(),
-        call_id: "call-123".to_string(),
+        call_id: "call-123".into(),
         output_len: 256,
         duration_ms: 150,
         cached: false,
@@ -383,7 +385,7 @@ fn test_event_kind_task_id_extraction() {
     let event = EventKind::AgentComplete {
         task_id: "complete-task".into(),
         turns: 2,
-        stop_reason: "max_turns".to_string(),
+        stop_reason: "max_turns".into(),
     };
 
     assert_eq!(event.task_id(), Some("complete-task"));
@@ -9,10 +9,12 @@
 //! - Non-2xx HTTP status (MEDIUM)
 //! - Connection refused (LOW)
 
+#![cfg(feature = "http")]
+
 use std::sync::Arc;
 use std::time::Duration;
 
-use nika::ast::{FetchParams, TaskAction};
+use nika::ast::{FetchParams, FetchRetry, TaskAction};
 use nika::error::NikaError;
 use nika::test_utils::{mock_executor, test_context};
 use rustc_hash::FxHashMap;
@@ -30,6 +32,10 @@ fn fetch_params(url: &str) -> FetchParams {
         method: "GET".to_string(),
         headers: FxHashMap::default(),
         body: None,
+        auth: None,
+        retry: None,
+        paginate: None,
+        graphql: None,
     }
 }
 
@@ -173,9 +179,13 @@ async fn test_fetch_non_2xx_status_returns_body_not_error() {
     let (bindings, datastore) = test_context();
 
     let url = start_status_server(500, "Internal Server Error").await;
-    let action = TaskAction::Fetch {
-        fetch: fetch_params(&url),
-    };
+    // The server answers once, so no 5xx retries (v0.7)
+    let mut fetch = fetch_params(&url);
+    fetch.retry = Some(FetchRetry {
+        max: 0,
+        ..FetchRetry::default()
+    });
+    let action = TaskAction::Fetch { fetch };
 
     // Act
     let result = executor
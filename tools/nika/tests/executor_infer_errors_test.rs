@@ -40,6 +40,9 @@ fn infer_params(prompt: &str) -> InferParams {
         prompt: prompt.to_string(),
         model: None,
         provider: None,
+        max_tokens: None,
+        hedge: None,
+        images: Vec::new(),
     }
 }
 
@@ -301,6 +304,9 @@ async fn test_infer_unknown_provider() {
             prompt: "Test prompt".to_string(),
             model: None,
             provider: Some("unknown_provider".to_string()),
+            max_tokens: None,
+            hedge: None,
+            images: Vec::new(),
         },
     };
     let bindings = ResolvedBindings::new();
@@ -629,3 +635,42 @@ fn test_error_codes() {
     let provider_err = NikaError::Provider("test error".to_string());
     assert_eq!(provider_err.code(), "NIKA-030");
 }
+
+// =============================================================================
+// TEST 8: Image Input Errors (v0.7)
+// =============================================================================
+
+/// Image problems fail the task before any provider is called
+#[tokio::test]
+async fn test_infer_images_rejected_before_provider_call() {
+    let executor = create_executor();
+    let task_id: Arc<str> = "test_images".into();
+    let datastore = DataStore::new();
+    let mut bindings = ResolvedBindings::new();
+    bindings.set("chart", json!("./does-not-exist.png"));
+
+    // Templates in images: resolve like the prompt
+    let mut params = infer_params("What does this chart show?");
+    params.images = vec!["{{use.chart}}".to_string()];
+    let action = TaskAction::Infer { infer: params };
+    let err = executor
+        .execute(&task_id, &action, &bindings, &datastore)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "NIKA-036");
+    assert!(err.to_string().contains("./does-not-exist.png"));
+
+    // A provider without vision is refused up front (images need `providers`)
+    #[cfg(feature = "providers")]
+    {
+        let mut params = infer_params("Describe it");
+        params.provider = Some("groq".to_string());
+        params.images = vec!["https://example.com/photo.jpg".to_string()];
+        let action = TaskAction::Infer { infer: params };
+        let err = executor
+            .execute(&task_id, &action, &bindings, &datastore)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, NikaError::VisionUnsupported { ref provider } if provider == "groq"));
+    }
+}
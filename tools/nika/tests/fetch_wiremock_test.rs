@@ -15,16 +15,19 @@
 //! | Delays | `tokio::time::sleep` | `.set_delay()` |
 //! | Multiple requests | Complex state | `.mount()` + `expect()` |
 
+#![cfg(feature = "http")]
+
 use std::sync::Arc;
 
-use nika::ast::{FetchParams, TaskAction};
+use nika::ast::{FetchGraphql, FetchPaginate, FetchParams, FetchRetry, TaskAction};
 use nika::binding::ResolvedBindings;
-use nika::event::EventLog;
+use nika::error::NikaError;
+use nika::event::{EventKind, EventLog};
 use nika::runtime::TaskExecutor;
-use nika::store::DataStore;
+use nika::store::{DataStore, HttpCache};
 use rustc_hash::FxHashMap;
 use serde_json::json;
-use wiremock::matchers::{body_json, header, method, path};
+use wiremock::matchers::{body_json, header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 // =============================================================================
@@ -43,6 +46,10 @@ fn fetch_params(url: &str, http_method: &str, body: Option<String>) -> FetchPara
         method: http_method.to_string(),
         headers: FxHashMap::default(),
         body,
+        auth: None,
+        retry: None,
+        paginate: None,
+        graphql: None,
     }
 }
 
@@ -61,6 +68,10 @@ fn fetch_params_with_headers(
         method: http_method.to_string(),
         headers: h,
         body: None,
+        auth: None,
+        retry: None,
+        paginate: None,
+        graphql: None,
     }
 }
 
@@ -685,3 +696,320 @@ async fn test_fetch_unmatched_path_returns_error() {
     let body = result.unwrap();
     assert!(body.is_empty() || body.contains("404"));
 }
+
+// =============================================================================
+// RETRY AND PAGINATION (v0.7)
+// =============================================================================
+
+#[tokio::test]
+async fn test_fetch_retries_503_with_retry_after() {
+    // Arrange: one 503, then success
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/busy"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/busy"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ready"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let executor = create_test_executor();
+    let task_id: Arc<str> = Arc::from("fetch_retry");
+    let (bindings, datastore) = empty_context();
+
+    let mut fetch = fetch_params(&format!("{}/api/busy", mock_server.uri()), "GET", None);
+    fetch.retry = Some(FetchRetry {
+        max: 1,
+        backoff_ms: 10_000,
+    });
+    let action = TaskAction::Fetch { fetch };
+
+    // Act
+    let started = std::time::Instant::now();
+    let result = executor
+        .execute(&task_id, &action, &bindings, &datastore)
+        .await;
+
+    // Assert: Retry-After: 0 wins over the 10s backoff
+    assert_eq!(result.unwrap(), "ready");
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_fetch_post_not_retried_on_500() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/orders"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("boom"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let executor = create_test_executor();
+    let task_id: Arc<str> = Arc::from("fetch_post_500");
+    let (bindings, datastore) = empty_context();
+
+    let url = format!("{}/api/orders", mock_server.uri());
+    let action = TaskAction::Fetch {
+        fetch: fetch_params(&url, "POST", Some("{}".to_string())),
+    };
+
+    let result = executor
+        .execute(&task_id, &action, &bindings, &datastore)
+        .await;
+
+    assert_eq!(result.unwrap(), "boom");
+}
+
+#[tokio::test]
+async fn test_fetch_paginate_concatenates_pages() {
+    // Arrange: two pages linked by next_url
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/items"))
+        .and(query_param("page", "2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"data": [3], "next_url": null})),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/items"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"data": [1, 2], "next_url": "/api/items?page=2"})),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let executor = create_test_executor();
+    let task_id: Arc<str> = Arc::from("fetch_pages");
+    let (bindings, datastore) = empty_context();
+
+    let mut fetch = fetch_params(&format!("{}/api/items", mock_server.uri()), "GET", None);
+    fetch.paginate = Some(FetchPaginate {
+        next: Some("$.next_url".to_string()),
+        items: Some("$.data".to_string()),
+        limit: 10,
+    });
+    let action = TaskAction::Fetch { fetch };
+
+    // Act
+    let result = executor
+        .execute(&task_id, &action, &bindings, &datastore)
+        .await;
+
+    // Assert
+    let items: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(items, json!([1, 2, 3]));
+}
+
+// =============================================================================
+// GRAPHQL (v0.7)
+// =============================================================================
+
+fn graphql_params(endpoint: &str, query: &str) -> FetchParams {
+    let mut fetch = fetch_params("", "GET", None);
+    fetch.graphql = Some(FetchGraphql {
+        endpoint: endpoint.to_string(),
+        query: query.to_string(),
+        variables: Some(json!({"owner": "supernovae-st"})),
+    });
+    fetch
+}
+
+#[tokio::test]
+async fn test_fetch_graphql_returns_data() {
+    // Arrange
+    let mock_server = MockServer::start().await;
+    let query = "query($owner: String!) { repositoryOwner(login: $owner) { login } }";
+
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .and(header("content-type", "application/json"))
+        .and(body_json(
+            json!({"query": query, "variables": {"owner": "supernovae-st"}}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({"data": {"repositoryOwner": {"login": "supernovae-st"}}})),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let executor = create_test_executor();
+    let task_id: Arc<str> = Arc::from("gql");
+    let (bindings, datastore) = empty_context();
+
+    let endpoint = format!("{}/graphql", mock_server.uri());
+    let action = TaskAction::Fetch {
+        fetch: graphql_params(&endpoint, query),
+    };
+
+    // Act
+    let result = executor
+        .execute(&task_id, &action, &bindings, &datastore)
+        .await;
+
+    // Assert: the output is `data`, not the envelope
+    let data: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+    assert_eq!(data, json!({"repositoryOwner": {"login": "supernovae-st"}}));
+}
+
+#[tokio::test]
+async fn test_fetch_graphql_errors_fail_the_task() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": null,
+            "errors": [{"message": "Field 'stars' doesn't exist on type 'Repository'"}]
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let executor = create_test_executor();
+    let task_id: Arc<str> = Arc::from("gql_errors");
+    let (bindings, datastore) = empty_context();
+
+    let endpoint = format!("{}/graphql", mock_server.uri());
+    let action = TaskAction::Fetch {
+        fetch: graphql_params(&endpoint, "{ repository { stars } }"),
+    };
+
+    let result = executor
+        .execute(&task_id, &action, &bindings, &datastore)
+        .await;
+
+    match result.unwrap_err() {
+        NikaError::GraphqlError { task_id, errors } => {
+            assert_eq!(task_id, "gql_errors");
+            assert_eq!(errors, ["Field 'stars' doesn't exist on type 'Repository'"]);
+        }
+        err => panic!("Expected GraphqlError, got: {err:?}"),
+    }
+}
+
+// =============================================================================
+// HTTP CACHE (v0.7)
+// =============================================================================
+
+/// Cache outcomes emitted so far, with the counters of the last one
+fn cache_lookups(event_log: &EventLog) -> (Vec<String>, (u64, u64)) {
+    let mut outcomes = Vec::new();
+    let mut counts = (0, 0);
+    for event in event_log.events() {
+        if let EventKind::HttpCacheLookup {
+            outcome,
+            hits,
+            misses,
+            ..
+        } = event.kind
+        {
+            outcomes.push(outcome);
+            counts = (hits, misses);
+        }
+    }
+    (outcomes, counts)
+}
+
+#[tokio::test]
+async fn test_fetch_cache_serves_fresh_responses() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/prices"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Cache-Control", "max-age=300")
+                .set_body_string("[1,2,3]"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let event_log = EventLog::new();
+    let executor = TaskExecutor::new("mock", None, None, event_log.clone())
+        .with_http_cache(Arc::new(HttpCache::open(dir.path())));
+    let (bindings, datastore) = empty_context();
+    let action = TaskAction::Fetch {
+        fetch: fetch_params(&format!("{}/prices", mock_server.uri()), "GET", None),
+    };
+
+    for _ in 0..3 {
+        let output = executor
+            .execute(&Arc::from("poll"), &action, &bindings, &datastore)
+            .await
+            .unwrap();
+        assert_eq!(output, "[1,2,3]");
+    }
+
+    let (outcomes, counts) = cache_lookups(&event_log);
+    assert_eq!(outcomes, ["miss", "hit", "hit"]);
+    assert_eq!(counts, (2, 1));
+}
+
+#[tokio::test]
+async fn test_fetch_cache_revalidates_with_etag_and_refresh_bypasses() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/feed"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/feed"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v1\"")
+                .set_body_json(json!({"items": ["a"]})),
+        )
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let (bindings, datastore) = empty_context();
+    let action = TaskAction::Fetch {
+        fetch: fetch_params(&format!("{}/feed", mock_server.uri()), "GET", None),
+    };
+    let run = |cache: HttpCache| {
+        let event_log = EventLog::new();
+        let executor = TaskExecutor::new("mock", None, None, event_log.clone())
+            .with_http_cache(Arc::new(cache));
+        let action = action.clone();
+        let (bindings, datastore) = (bindings.clone(), datastore.clone());
+        async move {
+            let output = executor
+                .execute(&Arc::from("feed"), &action, &bindings, &datastore)
+                .await
+                .unwrap();
+            (output, cache_lookups(&event_log).0)
+        }
+    };
+
+    let (first, outcomes) = run(HttpCache::open(dir.path())).await;
+    assert_eq!(outcomes, ["miss"]);
+    let (second, outcomes) = run(HttpCache::open(dir.path())).await;
+    assert_eq!(outcomes, ["revalidated"]);
+    assert_eq!(first, second);
+
+    // --refresh: no conditional request, the response is stored again
+    let (_, outcomes) = run(HttpCache::open(dir.path()).refreshing(true)).await;
+    assert_eq!(outcomes, ["miss"]);
+}
@@ -16,7 +16,7 @@ fn test_event_kind_mcp_connected_structure() {
 
     // Emit McpConnected event
     event_log.emit(EventKind::McpConnected {
-        server_name: "novanet".to_string(),
+        server_name: "novanet".into(),
     });
 
     // Verify event was recorded
@@ -37,7 +37,7 @@ fn test_event_kind_mcp_error_structure() {
 
     // Emit McpError event
     event_log.emit(EventKind::McpError {
-        server_name: "novanet".to_string(),
+        server_name: "novanet".into(),
         error: "Connection refused".to_string(),
     });
 
@@ -59,7 +59,7 @@ fn test_event_kind_mcp_events_have_timestamps() {
     let event_log = EventLog::new();
 
     event_log.emit(EventKind::McpConnected {
-        server_name: "test".to_string(),
+        server_name: "test".into(),
     });
 
     let events = event_log.events();
@@ -75,10 +75,10 @@ fn test_event_kind_mcp_events_sequential() {
 
     // Emit connection then error
     event_log.emit(EventKind::McpConnected {
-        server_name: "server1".to_string(),
+        server_name: "server1".into(),
     });
     event_log.emit(EventKind::McpError {
-        server_name: "server2".to_string(),
+        server_name: "server2".into(),
         error: "Failed".to_string(),
     });
 
@@ -239,7 +239,7 @@ async fn test_mcp_events_broadcast() {
 
     // Emit MCP connected event
     event_log.emit(EventKind::McpConnected {
-        server_name: "novanet".to_string(),
+        server_name: "novanet".into(),
     });
 
     // Should receive via broadcast
@@ -258,7 +258,7 @@ async fn test_mcp_error_broadcast() {
 
     // Emit MCP error event
     event_log.emit(EventKind::McpError {
-        server_name: "perplexity".to_string(),
+        server_name: "perplexity".into(),
         error: "API key not found".to_string(),
     });
 
@@ -159,9 +159,9 @@ fn test_mcp_invoke_event() {
     // Use correct EventKind::McpInvoke variant
     log.emit(EventKind::McpInvoke {
         task_id: "task-1".into(),
-        call_id: "call-001".to_string(),
-        mcp_server: "novanet".to_string(),
-        tool: Some("novanet_describe".to_string()),
+        call_id: "call-001".into(),
+        mcp_server: "novanet".into(),
+        tool: Some("novanet_describe".into()),
         resource: None,
         params: Some(serde_json::json!({"entity": "qr-code"})),
     });
@@ -187,7 +187,7 @@ fn test_mcp_response_event() {
     // Use correct EventKind::McpResponse variant
     log.emit(EventKind::McpResponse {
         task_id: "task-1".into(),
-        call_id: "call-001".to_string(),
+        call_id: "call-001".into(),
         output_len: 256,
         duration_ms: 150,
         cached: false,
@@ -229,16 +229,16 @@ fn test_mcp_tool_sequence() {
 
         log.emit(EventKind::McpInvoke {
             task_id: "task-1".into(),
-            call_id: call_id.clone(),
-            mcp_server: "novanet".to_string(),
-            tool: Some(tool.to_string()),
+            call_id: call_id.clone().into(),
+            mcp_server: "novanet".into(),
+            tool: Some((*tool).into()),
             resource: None,
             params: Some(serde_json::from_str(params).unwrap()),
         });
 
         log.emit(EventKind::McpResponse {
             task_id: "task-1".into(),
-            call_id,
+            call_id: call_id.into(),
             output_len: 100,
             duration_ms: 100,
             cached: false,
@@ -293,16 +293,16 @@ fn test_introspect_event() {
 
     log.emit(EventKind::McpInvoke {
         task_id: "introspect".into(),
-        call_id: "intro-001".to_string(),
-        mcp_server: "novanet".to_string(),
-        tool: Some("novanet_introspect".to_string()),
+        call_id: "intro-001".into(),
+        mcp_server: "novanet".into(),
+        tool: Some("novanet_introspect".into()),
         resource: None,
         params: Some(serde_json::json!({"query": "nodes"})),
     });
 
     log.emit(EventKind::McpResponse {
         task_id: "introspect".into(),
-        call_id: "intro-001".to_string(),
+        call_id: "intro-001".into(),
         output_len: 500,
         duration_ms: 50,
         cached: false,
@@ -359,16 +359,16 @@ fn test_multi_mcp_events() {
     // NovaNet call
     log.emit(EventKind::McpInvoke {
         task_id: "research".into(),
-        call_id: "novanet-001".to_string(),
-        mcp_server: "novanet".to_string(),
-        tool: Some("novanet_describe".to_string()),
+        call_id: "novanet-001".into(),
+        mcp_server: "novanet".into(),
+        tool: Some("novanet_describe".into()),
         resource: None,
         params: Some(serde_json::json!({"entity": "qr-code"})),
     });
 
     log.emit(EventKind::McpResponse {
         task_id: "research".into(),
-        call_id: "novanet-001".to_string(),
+        call_id: "novanet-001".into(),
         output_len: 500,
         duration_ms: 120,
         cached: false,
@@ -379,16 +379,16 @@ fn test_multi_mcp_events() {
     // Perplexity call
     log.emit(EventKind::McpInvoke {
         task_id: "research".into(),
-        call_id: "perplexity-001".to_string(),
-        mcp_server: "perplexity".to_string(),
-        tool: Some("search".to_string()),
+        call_id: "perplexity-001".into(),
+        mcp_server: "perplexity".into(),
+        tool: Some("search".into()),
         resource: None,
         params: Some(serde_json::json!({"query": "QR code best practices 2025"})),
     });
 
     log.emit(EventKind::McpResponse {
         task_id: "research".into(),
-        call_id: "perplexity-001".to_string(),
+        call_id: "perplexity-001".into(),
         output_len: 1500,
         duration_ms: 800,
         cached: false,
@@ -411,8 +411,8 @@ fn test_multi_mcp_events() {
         })
         .collect();
 
-    assert!(servers.contains(&"novanet".to_string()));
-    assert!(servers.contains(&"perplexity".to_string()));
+    assert!(servers.contains(&"novanet".into()));
+    assert!(servers.contains(&"perplexity".into()));
 }
 
 // ============================================================================
@@ -425,16 +425,16 @@ fn test_mcp_error_event() {
 
     log.emit(EventKind::McpInvoke {
         task_id: "error-test".into(),
-        call_id: "err-001".to_string(),
-        mcp_server: "novanet".to_string(),
-        tool: Some("novanet_describe".to_string()),
+        call_id: "err-001".into(),
+        mcp_server: "novanet".into(),
+        tool: Some("novanet_describe".into()),
         resource: None,
         params: Some(serde_json::json!({"entity": "nonexistent"})),
     });
 
     log.emit(EventKind::McpResponse {
         task_id: "error-test".into(),
-        call_id: "err-001".to_string(),
+        call_id: "err-001".into(),
         output_len: 50,
         duration_ms: 30,
         cached: false,
@@ -457,7 +457,7 @@ fn test_mcp_cached_response() {
 
     log.emit(EventKind::McpResponse {
         task_id: "cached-test".into(),
-        call_id: "cache-001".to_string(),
+        call_id: "cache-001".into(),
         output_len: 256,
         duration_ms: 5, // Very fast because cached
         cached: true,
@@ -482,8 +482,8 @@ fn test_mcp_resource_read() {
     // MCP can also read resources, not just call tools
     log.emit(EventKind::McpInvoke {
         task_id: "resource-test".into(),
-        call_id: "res-001".to_string(),
-        mcp_server: "novanet".to_string(),
+        call_id: "res-001".into(),
+        mcp_server: "novanet".into(),
         tool: None, // No tool - this is a resource read
         resource: Some("entity://qr-code".to_string()),
         params: None,
@@ -509,7 +509,7 @@ tasks:
             ..
         } = &event.kind
         {
-            assert_eq!(tool.as_ref(), Some(&"novanet_describe".to_string()));
+            assert_eq!(tool.as_ref(), Some(&"novanet_describe".into()));
             assert!(params.is_some(), "Params should be captured");
             assert!(!call_id.is_empty(), "call_id should be set");
 
@@ -585,7 +585,7 @@ flows:
         .iter()
         .filter_map(|e| {
             if let EventKind::McpInvoke { call_id, .. } = &e.kind {
-                Some(call_id.clone())
+                Some(call_id.to_string())
             } else {
                 None
             }
@@ -597,7 +597,7 @@ flows:
         .iter()
         .filter_map(|e| {
             if let EventKind::McpResponse { call_id, .. } = &e.kind {
-                Some(call_id.clone())
+                Some(call_id.to_string())
             } else {
                 None
             }
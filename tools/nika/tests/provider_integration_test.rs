@@ -7,6 +7,8 @@
 //! - `cargo nextest run provider_integration -- --ignored`
 //! - Requires ANTHROPIC_API_KEY or OPENAI_API_KEY
 
+#![cfg(feature = "providers")]
+
 use rustc_hash::FxHashMap;
 use std::env;
 
@@ -8,6 +8,8 @@
 //! 4. EventLog (AgentTurn events)
 //! 5. TUI state (display)
 
+#![cfg(feature = "tui")]
+
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
@@ -0,0 +1,109 @@
+//! `nika watch <run-id> --server` against a daemon serving run events (v0.7)
+
+#![cfg(all(unix, feature = "cli"))]
+
+use std::sync::Arc;
+
+use nika::daemon::{bind, Daemon, DaemonClient, Response, RunRequest};
+use nika::event::redact::Redactor;
+use nika::event::{Event, EventKind};
+use nika::tail::RunTail;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+const WORKFLOW: &str = r#"
+schema: "nika/workflow@0.5"
+provider: mock
+tasks:
+  - id: slow
+    exec: "sleep 0.3 && echo token=hunter2-hunter2"
+  - id: after
+    flow: [slow]
+    exec: "echo done"
+"#;
+
+/// Start a daemon on a socket in `dir` with run events on HTTP
+async fn start(dir: &std::path::Path) -> (std::path::PathBuf, String) {
+    let path = dir.join("daemon.sock");
+    let listener = bind(&path).await.unwrap();
+    let http = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let server = format!("http://{}", http.local_addr().unwrap());
+    let daemon = Arc::new(Daemon::new(2));
+    let redactor = Redactor::new(["hunter2-hunter2".to_string()], &[]).unwrap();
+    tokio::spawn(Arc::clone(&daemon).serve_http(http, redactor));
+    tokio::spawn(daemon.serve(listener));
+    (path, server)
+}
+
+/// Every event of run `run_id`, until it ends
+async fn tail_all(server: &str, run_id: &str) -> Vec<Event> {
+    let tail = RunTail::connect(server, run_id).await.unwrap();
+    let (tx, mut rx) = mpsc::channel(64);
+    let forward = tokio::spawn(tail.forward(tx));
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    forward.await.unwrap().unwrap();
+    events
+}
+
+#[tokio::test]
+async fn test_tail_live_run_then_backfill_from_trace() {
+    // Arrange
+    let dir = tempfile::tempdir().unwrap();
+    let (path, server) = start(dir.path()).await;
+    let (id_tx, mut id_rx) = mpsc::unbounded_channel();
+    let client = DaemonClient::connect(&path).await.unwrap();
+    let run = tokio::spawn(client.run(
+        RunRequest {
+            yaml: WORKFLOW.to_string(),
+            provider: None,
+            model: None,
+            phases: false,
+        },
+        move |progress| {
+            if let Response::Started { generation_id } = progress {
+                let _ = id_tx.send(generation_id.clone());
+            }
+        },
+    ));
+    let run_id = id_rx.recv().await.unwrap();
+
+    // Act: follow while the run is in progress
+    let events = tail_all(&server, &run_id).await;
+    let (_, generation_id) = run.await.unwrap().unwrap();
+
+    // Assert
+    assert_eq!(generation_id, run_id);
+    let ids: Vec<u64> = events.iter().map(|e| e.id).collect();
+    assert_eq!(ids, (0..events.len() as u64).collect::<Vec<_>>());
+    assert!(matches!(
+        events.last().unwrap().kind,
+        EventKind::WorkflowCompleted { .. }
+    ));
+    let text = serde_json::to_string(&events).unwrap();
+    assert!(!text.contains("hunter2-hunter2"));
+    assert!(text.contains("[redacted]"));
+
+    // The finished run is served from its trace, after Last-Event-ID
+    let response = reqwest::Client::new()
+        .get(format!("{}/runs/{}/events", server, run_id))
+        .header("Last-Event-ID", "2")
+        .send()
+        .await
+        .unwrap();
+    let body = response.text().await.unwrap();
+    assert!(body.starts_with("id: 3\n"), "{}", body);
+    assert!(body.ends_with("event: end\ndata: {}\n\n"));
+    assert_eq!(tail_all(&server, &run_id).await.len(), events.len());
+}
+
+#[tokio::test]
+async fn test_tail_unknown_run_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, server) = start(dir.path()).await;
+    let err = RunTail::connect(&server, "gen-missing").await.unwrap_err();
+    assert_eq!(err.code(), "NIKA-180");
+    assert!(err.to_string().contains("no such run"), "{}", err);
+}
@@ -3,6 +3,8 @@
 //! These tests define the expected behavior of the new rig-based agent loop.
 //! Following TDD: tests are written FIRST, then implementation.
 
+#![cfg(feature = "providers")]
+
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
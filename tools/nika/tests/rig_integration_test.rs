@@ -7,6 +7,8 @@
 //!
 //! Run with: cargo test --test rig_integration_test -- --ignored
 
+#![cfg(feature = "providers")]
+
 use nika::mcp::{McpClient, McpConfig};
 use nika::provider::rig::{NikaMcpTool, NikaMcpToolDef};
 use rig::tool::ToolDyn;
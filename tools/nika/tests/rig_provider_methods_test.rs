@@ -11,6 +11,8 @@
 //!   GROQ_API_KEY=... cargo test --test rig_provider_methods_test -- --ignored
 //!   DEEPSEEK_API_KEY=... cargo test --test rig_provider_methods_test -- --ignored
 
+#![cfg(feature = "providers")]
+
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
@@ -9,6 +9,8 @@
 //! Note: These tests manipulate environment variables and MUST run serially.
 //! Use `cargo test --test rig_provider_selection_test -- --test-threads=1`
 
+#![cfg(feature = "providers")]
+
 use std::sync::Arc;
 
 use nika::ast::AgentParams;
@@ -482,3 +482,48 @@ flows:
         assert_eq!(workflow.flows.len(), 2);
     }
 }
+
+// =============================================================================
+// TEST 7: Workflow Inputs (v0.7)
+// =============================================================================
+
+mod workflow_inputs {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const YAML: &str = r#"
+schema: nika/workflow@0.5
+provider: mock
+inputs:
+  topic:
+  words: { type: number, default: 300 }
+tasks:
+  - id: echo
+    use:
+      topic: inputs.topic
+      words: inputs.words
+    exec: "echo {{use.topic}}/{{use.words}}"
+"#;
+
+    #[tokio::test]
+    async fn test_inputs_are_bound_with_defaults() {
+        let workflow = parse_workflow(YAML);
+        workflow.validate_schema().unwrap();
+        let given = BTreeMap::from([("topic".to_string(), "rust".to_string())]);
+
+        let output = Runner::new(workflow)
+            .with_inputs(given)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(output.trim(), "rust/300");
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_input_fails() {
+        let err = Runner::new(parse_workflow(YAML)).run().await.unwrap_err();
+        assert_eq!(err.code(), "NIKA-009");
+        assert!(err.to_string().contains("'topic'"), "{}", err);
+    }
+}
@@ -0,0 +1,96 @@
+//! `nika run --share` / `nika watch <url>` through a local relay (v0.7)
+
+#![cfg(feature = "http")]
+
+use std::sync::Arc;
+
+use nika::event::redact::Redactor;
+use nika::event::{Event, EventKind, EventLog};
+use nika::relay::{Relay, ShareSession, Subscription};
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// Start a relay on a free port, returning its base URL
+async fn start_relay() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(Arc::new(Relay::default()).serve(listener));
+    url
+}
+
+/// Every event of the session at `url`, until it ends
+async fn watch_all(url: &str) -> Vec<Event> {
+    let subscription = Subscription::connect(url).await.unwrap();
+    let (tx, mut rx) = mpsc::channel(64);
+    let forward = tokio::spawn(subscription.forward(tx));
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    forward.await.unwrap().unwrap();
+    events
+}
+
+fn resolved(result: &str) -> EventKind {
+    EventKind::TemplateResolved {
+        task_id: Arc::from("fetch"),
+        template: "{{use.token}}".to_string(),
+        result: result.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_shared_run_reaches_viewers_redacted() {
+    // Arrange
+    let relay = start_relay().await;
+    let session = ShareSession::create(&relay).await.unwrap();
+    let url = session.url().to_string();
+    assert!(url.starts_with(&format!("{}/sessions/", relay)));
+
+    let event_log = EventLog::new();
+    event_log.emit(resolved("token=hunter2-hunter2"));
+    let redactor = Redactor::new(["hunter2-hunter2".to_string()], &[]).unwrap();
+    let done = CancellationToken::new();
+    let publisher = session.spawn_publisher(event_log.clone(), redactor, done.clone());
+
+    // A viewer joining mid-run gets the backlog, then live events
+    let live = tokio::spawn({
+        let url = url.clone();
+        async move { watch_all(&url).await }
+    });
+    event_log.emit(EventKind::WorkflowCompleted {
+        final_output: Arc::new(json!("ok")),
+        total_duration_ms: 12,
+    });
+
+    // Act
+    done.cancel();
+    publisher.await.unwrap().unwrap();
+    let events = live.await.unwrap();
+
+    // Assert
+    assert_eq!(events.len(), 2);
+    match &events[0].kind {
+        EventKind::TemplateResolved { result, .. } => assert_eq!(result, "token=[redacted]"),
+        other => panic!("unexpected event: {other:?}"),
+    }
+    assert!(matches!(
+        events[1].kind,
+        EventKind::WorkflowCompleted { .. }
+    ));
+
+    // Ended sessions stay viewable for a while
+    assert_eq!(watch_all(&url).await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_watch_unknown_session_fails() {
+    let relay = start_relay().await;
+    let err = Subscription::connect(&format!("{}/sessions/nope", relay))
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "NIKA-182");
+    assert!(err.to_string().contains("no such session"), "{}", err);
+}
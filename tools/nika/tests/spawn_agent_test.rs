@@ -3,6 +3,8 @@
 //! TDD RED phase: These tests should FAIL initially.
 //! They define the expected behavior for nested agent spawning.
 
+#![cfg(feature = "providers")]
+
 use nika::ast::AgentParams;
 use nika::event::{EventKind, EventLog};
 use serde_json::json;
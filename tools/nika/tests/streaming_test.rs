@@ -9,6 +9,8 @@
 //! - DEEPSEEK_API_KEY for DeepSeek
 //! - OLLAMA_API_BASE_URL for Ollama (local)
 
+#![cfg(feature = "providers")]
+
 use nika::provider::rig::{RigInferError, RigProvider, StreamChunk, StreamResult};
 use tokio::sync::mpsc;
 
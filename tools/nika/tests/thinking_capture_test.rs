@@ -5,6 +5,8 @@
 //!
 //! Run with: cargo test --test thinking_capture_test -- --ignored
 
+#![cfg(feature = "providers")]
+
 use nika::ast::AgentParams;
 use nika::event::{EventKind, EventLog};
 use nika::runtime::RigAgentLoop;
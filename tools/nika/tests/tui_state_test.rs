@@ -26,6 +26,7 @@ fn test_workflow_started_updates_state() {
         task_count: 5,
         generation_id: "gen-123".to_string(),
         workflow_hash: "abc123".to_string(),
+        workflow_name: None,
         nika_version: "0.5.0".to_string(),
     };
 
@@ -47,6 +48,7 @@ fn test_workflow_completed_updates_state() {
             task_count: 2,
             generation_id: "gen-123".to_string(),
             workflow_hash: "abc".to_string(),
+            workflow_name: None,
             nika_version: "0.5.0".to_string(),
         },
         0,
@@ -76,6 +78,7 @@ fn test_workflow_failed_updates_state() {
             task_count: 2,
             generation_id: "gen-123".to_string(),
             workflow_hash: "abc".to_string(),
+            workflow_name: None,
             nika_version: "0.5.0".to_string(),
         },
         0,
@@ -174,6 +177,7 @@ fn test_task_completed_updates_state() {
             task_count: 1,
             generation_id: "gen-1".to_string(),
             workflow_hash: "abc".to_string(),
+            workflow_name: None,
             nika_version: "0.5.0".to_string(),
         },
         0,
@@ -265,6 +269,7 @@ fn test_progress_calculation_partial() {
             task_count: 4,
             generation_id: "gen-1".to_string(),
             workflow_hash: "abc".to_string(),
+            workflow_name: None,
             nika_version: "0.5.0".to_string(),
         },
         0,
@@ -285,6 +290,7 @@ fn test_progress_calculation_complete() {
             task_count: 3,
             generation_id: "gen-1".to_string(),
             workflow_hash: "abc".to_string(),
+            workflow_name: None,
             nika_version: "0.5.0".to_string(),
         },
         0,
@@ -306,9 +312,9 @@ fn test_mcp_invoke_creates_call_record() {
     state.handle_event(
         &EventKind::McpInvoke {
             task_id: Arc::from("step1"),
-            call_id: "call-001".to_string(),
-            mcp_server: "novanet".to_string(),
-            tool: Some("novanet_describe".to_string()),
+            call_id: "call-001".into(),
+            mcp_server: "novanet".into(),
+            tool: Some("novanet_describe".into()),
             resource: None,
             params: Some(json!({"entity": "qr-code"})),
         },
@@ -333,9 +339,9 @@ fn test_mcp_response_updates_call_record() {
     state.handle_event(
         &EventKind::McpInvoke {
             task_id: Arc::from("step1"),
-            call_id: "call-001".to_string(),
-            mcp_server: "novanet".to_string(),
-            tool: Some("novanet_describe".to_string()),
+            call_id: "call-001".into(),
+            mcp_server: "novanet".into(),
+            tool: Some("novanet_describe".into()),
             resource: None,
             params: None,
         },
@@ -346,7 +352,7 @@ fn test_mcp_response_updates_call_record() {
     state.handle_event(
         &EventKind::McpResponse {
             task_id: Arc::from("step1"),
-            call_id: "call-001".to_string(),
+            call_id: "call-001".into(),
             output_len: 1024,
             duration_ms: 250,
             cached: false,
@@ -371,9 +377,9 @@ fn test_mcp_error_response() {
     state.handle_event(
         &EventKind::McpInvoke {
             task_id: Arc::from("step1"),
-            call_id: "call-002".to_string(),
-            mcp_server: "novanet".to_string(),
-            tool: Some("novanet_traverse".to_string()),
+            call_id: "call-002".into(),
+            mcp_server: "novanet".into(),
+            tool: Some("novanet_traverse".into()),
             resource: None,
             params: None,
         },
@@ -383,7 +389,7 @@ fn test_mcp_error_response() {
     state.handle_event(
         &EventKind::McpResponse {
             task_id: Arc::from("step1"),
-            call_id: "call-002".to_string(),
+            call_id: "call-002".into(),
             output_len: 50,
             duration_ms: 100,
             cached: false,
@@ -406,9 +412,9 @@ fn test_multiple_mcp_calls_sequenced() {
         state.handle_event(
             &EventKind::McpInvoke {
                 task_id: Arc::from("step1"),
-                call_id: format!("call-{:03}", i),
-                mcp_server: "novanet".to_string(),
-                tool: Some(format!("tool_{}", i)),
+                call_id: format!("call-{:03}", i).into(),
+                mcp_server: "novanet".into(),
+                tool: Some(format!("tool_{}", i).into()),
                 resource: None,
                 params: None,
             },
@@ -489,6 +495,7 @@ fn test_agent_turn_with_metadata() {
         input_tokens: 500,
         output_tokens: 100,
         cache_read_tokens: 0,
+        cache_write_tokens: 0,
         stop_reason: "end_turn".to_string(),
     };
 
@@ -600,8 +607,9 @@ fn test_provider_responded_updates_metrics() {
             input_tokens: 1000,
             output_tokens: 500,
             cache_read_tokens: 200,
+            cache_write_tokens: 800,
             ttft_ms: Some(150),
-            finish_reason: "end_turn".to_string(),
+            finish_reason: "end_turn".into(),
             cost_usd: 0.015,
         },
         500,
@@ -610,6 +618,8 @@ fn test_provider_responded_updates_metrics() {
     assert_eq!(state.metrics.input_tokens, 1000);
     assert_eq!(state.metrics.output_tokens, 500);
     assert_eq!(state.metrics.total_tokens, 1500);
+    assert_eq!(state.metrics.cache_read_tokens, 200);
+    assert_eq!(state.metrics.cache_write_tokens, 800);
     assert!((state.metrics.cost_usd - 0.015).abs() < 0.0001);
 }
 
@@ -630,6 +640,7 @@ fn test_mission_phase_transitions() {
             task_count: 3,
             generation_id: "gen-1".to_string(),
             workflow_hash: "abc".to_string(),
+            workflow_name: None,
             nika_version: "0.5.0".to_string(),
         },
         0,
@@ -676,9 +687,9 @@ fn test_mission_phase_transitions() {
     state.handle_event(
         &EventKind::McpInvoke {
             task_id: Arc::from("t2"),
-            call_id: "c1".to_string(),
-            mcp_server: "novanet".to_string(),
-            tool: Some("tool".to_string()),
+            call_id: "c1".into(),
+            mcp_server: "novanet".into(),
+            tool: Some("tool".into()),
             resource: None,
             params: None,
         },
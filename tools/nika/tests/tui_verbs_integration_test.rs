@@ -11,6 +11,8 @@
 //!   OPENAI_API_KEY - Required for OpenAI tests
 //!   PERPLEXITY_API_KEY - Optional for MCP tests
 
+#![cfg(feature = "tui")]
+
 use nika::provider::rig::{RigInferError, RigProvider, StreamChunk, StreamResult};
 use nika::tui::chat_agent::ChatAgent;
 use tokio::sync::mpsc;
@@ -285,6 +285,7 @@ tasks:
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
+#[cfg(feature = "http")]
 async fn test_fetch_get_json() {
     let workflow = parse_workflow(
         r#"
@@ -325,6 +326,7 @@ tasks:
 }
 
 #[tokio::test]
+#[cfg(feature = "http")]
 async fn test_fetch_with_headers() {
     let workflow = parse_workflow(
         r#"
@@ -355,6 +357,7 @@ tasks:
 }
 
 #[tokio::test]
+#[cfg(feature = "http")]
 async fn test_fetch_post_with_body() {
     let workflow = parse_workflow(
         r#"
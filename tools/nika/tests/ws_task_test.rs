@@ -0,0 +1,129 @@
+//! `ws:` tasks against a local WebSocket server (v0.7)
+
+#![cfg(feature = "http")]
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use futures::{SinkExt, StreamExt};
+use nika::ast::{TaskAction, WsParams};
+use nika::binding::ResolvedBindings;
+use nika::event::{EventKind, EventLog};
+use nika::runtime::TaskExecutor;
+use nika::store::DataStore;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Serve one connection: wait for a subscribe message, then send `replies`
+async fn start_server(replies: Vec<&'static str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        let Some(Ok(Message::Text(subscribe))) = socket.next().await else {
+            return;
+        };
+        assert!(subscribe.as_str().contains("trades"));
+        for reply in replies {
+            if socket.send(Message::text(reply)).await.is_err() {
+                return;
+            }
+        }
+        // Keep the connection open until the client leaves
+        while let Some(Ok(_)) = socket.next().await {}
+    });
+    url
+}
+
+fn ws_params(url: &str) -> WsParams {
+    WsParams {
+        url: url.to_string(),
+        headers: BTreeMap::new(),
+        send: vec![r#"{"op": "subscribe", "channel": "trades"}"#.to_string()],
+        until: None,
+        max_messages: None,
+        timeout_ms: 2_000,
+    }
+}
+
+async fn run(params: WsParams, event_log: &EventLog) -> Result<String, nika::error::NikaError> {
+    let executor = TaskExecutor::new("mock", None, None, event_log.clone());
+    executor
+        .execute(
+            &Arc::from("ticker"),
+            &TaskAction::Ws { ws: params },
+            &ResolvedBindings::new(),
+            &DataStore::new(),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn test_ws_collects_until_condition() {
+    let url = start_server(vec![
+        r#"{"type": "trade", "price": 10}"#,
+        "heartbeat",
+        r#"{"type": "snapshot_end"}"#,
+        r#"{"type": "trade", "price": 11}"#,
+    ])
+    .await;
+    let event_log = EventLog::new();
+    let params = WsParams {
+        until: Some("type == 'snapshot_end'".to_string()),
+        ..ws_params(&url)
+    };
+
+    let output = run(params, &event_log).await.unwrap();
+
+    let messages: Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(
+        messages,
+        json!([
+            {"type": "trade", "price": 10},
+            "heartbeat",
+            {"type": "snapshot_end"}
+        ])
+    );
+    let live: Vec<(usize, String)> = event_log
+        .events()
+        .into_iter()
+        .filter_map(|e| match e.kind {
+            EventKind::WsMessage { index, message, .. } => Some((index, message)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(live.len(), 3);
+    assert_eq!(live[1], (1, "heartbeat".to_string()));
+}
+
+#[tokio::test]
+async fn test_ws_stops_at_max_messages_or_timeout() {
+    let url = start_server(vec!["a", "b", "c"]).await;
+    let params = WsParams {
+        max_messages: Some(2),
+        ..ws_params(&url)
+    };
+    let output = run(params, &EventLog::new()).await.unwrap();
+    assert_eq!(output, r#"["a","b"]"#);
+
+    // Without a stop condition, the timeout ends collection normally
+    let url = start_server(vec!["a"]).await;
+    let params = WsParams {
+        timeout_ms: 300,
+        ..ws_params(&url)
+    };
+    let output = run(params, &EventLog::new()).await.unwrap();
+    assert_eq!(output, r#"["a"]"#);
+}
+
+#[tokio::test]
+async fn test_ws_connection_refused_is_nika_097() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    drop(listener);
+
+    let err = run(ws_params(&url), &EventLog::new()).await.unwrap_err();
+    assert_eq!(err.code(), "NIKA-097");
+}